@@ -5,4 +5,25 @@ fn main() {
         .file("schema/finch_ipc.capnp")
         .run()
         .expect("capnp schema compilation failed — is `capnp` installed? (brew install capnp / apt install capnproto)");
+
+    // Public gRPC API; protoc is vendored so only capnp must be installed
+    println!("cargo:rerun-if-changed=proto/finch/v1/finch.proto");
+    let protoc =
+        protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc for this platform");
+    std::env::set_var("PROTOC", protoc);
+    tonic_build::configure()
+        .compile_protos(&["proto/finch/v1/finch.proto"], &["proto"])
+        .expect("gRPC proto compilation failed");
+
+    // Provider plugins must be built with the same toolchain (see
+    // providers::plugin)
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=FINCH_RUSTC_VERSION={}", version);
 }
@@ -29,10 +29,14 @@ pub enum AgentEvent {
         duration_s: u64,
         reason: String,
     },
+    /// Older turns of a long task were folded into a summary
+    Summarized { id: String, turns: usize },
     /// Self-reflection / persona update
     Reflect { summary: String },
     /// Agent is sleeping waiting for new tasks
     Idle { sleep_s: u64 },
+    /// Agent is waiting for `[schedule]` to allow it to work
+    QuietHours { until: String },
 }
 
 #[derive(Debug, Serialize)]
@@ -45,6 +49,8 @@ struct LogEntry<'a> {
 /// Writes agent activity to a daily JSONL log file
 pub struct ActivityLogger {
     finch_dir: PathBuf,
+    /// `false` for a logger that drops every event
+    enabled: bool,
 }
 
 impl ActivityLogger {
@@ -54,11 +60,25 @@ impl ActivityLogger {
             .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
         let finch_dir = home.join(".finch");
         std::fs::create_dir_all(&finch_dir).context("Failed to create ~/.finch directory")?;
-        Ok(Self { finch_dir })
+        Ok(Self {
+            finch_dir,
+            enabled: true,
+        })
+    }
+
+    /// A logger that writes nothing (replays don't add to the activity log)
+    pub fn disabled() -> Self {
+        Self {
+            finch_dir: PathBuf::new(),
+            enabled: false,
+        }
     }
 
     /// Log an event
     pub fn log(&self, event: AgentEvent) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
         let date = Local::now().format("%Y-%m-%d").to_string();
         let path = self.finch_dir.join(format!("agent_{}.jsonl", date));
 
@@ -86,7 +106,10 @@ impl ActivityLogger {
     /// Create a logger that writes to an arbitrary directory (for testing)
     #[cfg(test)]
     pub fn with_dir(finch_dir: PathBuf) -> Self {
-        Self { finch_dir }
+        Self {
+            finch_dir,
+            enabled: true,
+        }
     }
 }
 
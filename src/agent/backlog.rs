@@ -1,5 +1,6 @@
 // Agent task backlog — loaded from ~/.finch/tasks.toml
 
+use super::transcript::SummaryPolicy;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -48,6 +49,14 @@ pub struct AgentTask {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
 
+    /// Model turns allowed before the task fails (default 25)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<usize>,
+
+    /// When to summarize older turns of a long task (`[tasks.summarize]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summarize: Option<SummaryPolicy>,
+
     /// Failure reason (set when status == Failed)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub failure_reason: Option<String>,
@@ -67,6 +76,8 @@ struct BacklogFile {
 pub struct TaskBacklog {
     path: PathBuf,
     tasks: Vec<AgentTask>,
+    /// Write status changes back to the file (off for dry runs)
+    persist: bool,
 }
 
 impl TaskBacklog {
@@ -81,7 +92,17 @@ impl TaskBacklog {
         } else {
             Vec::new()
         };
-        Ok(Self { path, tasks })
+        Ok(Self {
+            path,
+            tasks,
+            persist: true,
+        })
+    }
+
+    /// Keep status changes in memory only, leaving the file untouched
+    pub fn without_persistence(mut self) -> Self {
+        self.persist = false;
+        self
     }
 
     /// Return the next pending task (high priority first, then normal, then low)
@@ -155,7 +176,49 @@ impl TaskBacklog {
         &self.tasks
     }
 
+    /// The task with `id`
+    pub fn get(&self, id: &str) -> Option<&AgentTask> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Append a pending task and persist, returning its id: one past the
+    /// highest numeric id, zero-padded like "007"
+    pub fn add(
+        &mut self,
+        description: &str,
+        priority: TaskPriority,
+        repo: Option<String>,
+    ) -> Result<String> {
+        let next = self
+            .tasks
+            .iter()
+            .filter_map(|t| t.id.parse::<u64>().ok())
+            .max()
+            .map_or(1, |n| n + 1);
+        let id = format!("{:03}", next);
+        self.tasks.push(AgentTask {
+            id: id.clone(),
+            description: description.to_string(),
+            repo,
+            status: TaskStatus::Pending,
+            priority,
+            notes: None,
+            max_turns: None,
+            summarize: None,
+            failure_reason: None,
+        });
+        self.save()?;
+        Ok(id)
+    }
+
     fn save(&self) -> Result<()> {
+        if !self.persist {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
         let file = BacklogFile {
             tasks: self.tasks.clone(),
         };
@@ -322,6 +385,19 @@ status = "pending"
         assert!(reloaded.next_pending().is_none());
     }
 
+    #[test]
+    fn test_without_persistence_leaves_file_untouched() {
+        let f = write_toml(SINGLE_TASK);
+        let mut backlog = TaskBacklog::load(f.path().to_path_buf())
+            .unwrap()
+            .without_persistence();
+        backlog.mark_done("001").unwrap();
+        assert!(backlog.next_pending().is_none());
+
+        let reloaded = TaskBacklog::load(f.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.tasks()[0].status, TaskStatus::Pending);
+    }
+
     #[test]
     fn test_mark_done_clears_failure_reason() {
         let toml = r#"
@@ -416,6 +492,41 @@ status = "pending"
         assert!(backlog.tasks().is_empty());
     }
 
+    #[test]
+    fn test_add_numbers_after_existing_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".finch").join("tasks.toml");
+        let mut backlog = TaskBacklog::load(path.clone()).unwrap();
+        assert_eq!(
+            backlog.add("First", TaskPriority::Normal, None).unwrap(),
+            "001"
+        );
+
+        fs::write(
+            &path,
+            "[[tasks]]\nid = \"041\"\ndescription = \"Old\"\nstatus = \"done\"\n\n\
+             [[tasks]]\nid = \"cleanup\"\ndescription = \"Named\"\nstatus = \"pending\"\n",
+        )
+        .unwrap();
+        let mut backlog = TaskBacklog::load(path.clone()).unwrap();
+        let id = backlog
+            .add(
+                "Write the docs",
+                TaskPriority::High,
+                Some("/work/app".into()),
+            )
+            .unwrap();
+        assert_eq!(id, "042");
+
+        let reloaded = TaskBacklog::load(path).unwrap();
+        let task = reloaded.get("042").unwrap();
+        assert_eq!(task.description, "Write the docs");
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert_eq!(task.priority, TaskPriority::High);
+        assert_eq!(task.repo.as_deref(), Some("/work/app"));
+        assert_eq!(reloaded.next_pending().unwrap().id, "042");
+    }
+
     // ── serialisation round-trip ──────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,381 @@
+// Dry-run mode — preview what an agent run would change
+//
+// Read tools run normally.  write / edit / patch are applied to an in-memory
+// overlay instead of the disk, so later edits of the same file build on the
+// earlier ones, and every change is recorded as a diff in the report.  bash
+// only runs when the command is read-only, and git commits are recorded but
+// never made.  Every other tool runs only when it is known not to change
+// anything: read-only built-ins, GET requests, browser actions that only look,
+// and executable plugins declared `category = "read"`.  Anything else —
+// run_code, MCP tools, unknown plugins — is recorded and not run.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::backlog::AgentTask;
+use crate::tools::change_preview::changed_content;
+use crate::tools::exec_plugins;
+use crate::tools::implementations::patch::unified_diff;
+
+/// Commands that only read, allowed to run in a dry run
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "cat", "cd", "diff", "du", "echo", "file", "find", "grep", "head", "ls", "pwd", "rg", "stat",
+    "tail", "tree", "wc", "which",
+];
+
+/// Built-in tools that never change files or outside systems
+const READ_ONLY_TOOLS: &[&str] = &[
+    "doc_lookup",
+    "gui_inspect",
+    "gui_screenshot",
+    "glob",
+    "grep",
+    "hash_compare",
+    "list_recent_memories",
+    "outline",
+    "read",
+    "search_memory",
+    "TodoRead",
+    "web_fetch",
+    "web_search",
+];
+
+/// HTTP methods the http tool may send in a dry run
+const READ_ONLY_METHODS: &[&str] = &["GET", "HEAD", "OPTIONS"];
+
+/// Browser actions that only look at the page
+const READ_ONLY_BROWSER_ACTIONS: &[&str] = &["navigate", "extract_text", "screenshot"];
+
+/// git subcommands that only read
+const READ_ONLY_GIT: &[&str] = &[
+    "blame",
+    "diff",
+    "log",
+    "ls-files",
+    "rev-parse",
+    "show",
+    "status",
+];
+
+/// Simulated file changes and the Markdown report built from them
+pub struct DryRun {
+    /// Simulated file contents, keyed by the path the tool was given
+    overlay: HashMap<PathBuf, String>,
+    report: String,
+    /// Simulated changes in the current task
+    task_changes: usize,
+}
+
+impl Default for DryRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        Self {
+            overlay: HashMap::new(),
+            report: format!(
+                "# finch agent dry run — {}\n\nNothing below was written to disk or committed.\n",
+                Local::now().format("%Y-%m-%d %H:%M:%S")
+            ),
+            task_changes: 0,
+        }
+    }
+
+    /// Start the report section for `task`
+    pub fn begin_task(&mut self, task: &AgentTask) {
+        self.task_changes = 0;
+        self.report
+            .push_str(&format!("\n## Task {}: {}\n", task.id, task.description));
+        if let Some(repo) = &task.repo {
+            self.report.push_str(&format!("\nRepository: `{}`\n", repo));
+        }
+    }
+
+    /// Number of simulated changes recorded for the current task
+    pub fn task_changes(&self) -> usize {
+        self.task_changes
+    }
+
+    /// Simulate `tool` unless it is known to be read-only.  `None` means
+    /// the tool is safe to run for real.
+    pub fn intercept(&mut self, tool: &str, input: &Value) -> Option<Result<String>> {
+        match tool {
+            "write" | "edit" | "patch" => Some(self.simulate_file_change(tool, input)),
+            "bash" => {
+                let command = input["command"].as_str().unwrap_or("");
+                if is_read_only_command(command) {
+                    return None;
+                }
+                self.task_changes += 1;
+                self.report.push_str(&format!(
+                    "\n### bash (not run)\n\n```sh\n{}\n```\n",
+                    command
+                ));
+                Some(Ok(format!(
+                    "[dry run] Not executed, the command may modify files: {}\n",
+                    command
+                )))
+            }
+            name if is_read_only_tool(name, input) => None,
+            name => {
+                self.task_changes += 1;
+                self.report.push_str(&format!(
+                    "\n### {} (not run)\n\n```json\n{}\n```\n",
+                    name, input
+                ));
+                Some(Ok(format!(
+                    "[dry run] Not executed, {} may change things\n",
+                    name
+                )))
+            }
+        }
+    }
+
+    /// Record the commit the agent would have made
+    pub fn record_commit(&mut self, repo: &str, message: &str) {
+        self.report.push_str(&format!(
+            "\n### git commit in `{}` (not made)\n\n```\n{}\n```\n",
+            repo, message
+        ));
+    }
+
+    /// Record how the task ended
+    pub fn end_task(&mut self, outcome: &str) {
+        self.report
+            .push_str(&format!("\n**Outcome:** {}\n", outcome));
+    }
+
+    pub fn report(&self) -> &str {
+        &self.report
+    }
+
+    /// Write the report to ~/.finch/agent_dry_run_<timestamp>.md
+    pub fn save_report(&self) -> Result<PathBuf> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+        let path = home.join(".finch").join(format!(
+            "agent_dry_run_{}.md",
+            Local::now().format("%Y-%m-%d_%H%M%S")
+        ));
+        fs::write(&path, &self.report)
+            .with_context(|| format!("Failed to write dry-run report: {}", path.display()))?;
+        Ok(path)
+    }
+
+    fn simulate_file_change(&mut self, tool: &str, input: &Value) -> Result<String> {
+        let file_path = input["file_path"]
+            .as_str()
+            .context("Missing file_path parameter")?;
+        let path = PathBuf::from(file_path);
+        let before = match self.overlay.get(&path) {
+            Some(content) => Some(content.clone()),
+            None => fs::read_to_string(&path).ok(),
+        };
+
+        let after = changed_content(tool, input, before.as_deref())?;
+
+        let diff = unified_diff(file_path, before.as_deref().unwrap_or(""), &after);
+        self.task_changes += 1;
+        self.report.push_str(&format!(
+            "\n### {} `{}`\n\n```diff\n{}```\n",
+            tool, file_path, diff
+        ));
+        self.overlay.insert(path, after);
+
+        Ok(format!(
+            "[dry run] Change to {} recorded, not written to disk. Reads still show the \
+             original file; continue as if the change had been made.\n{}",
+            file_path, diff
+        ))
+    }
+}
+
+/// Whether a call to `tool` with `input` only reads.  Tools not listed here
+/// — including MCP tools, whose effects finch cannot know — count as writes.
+fn is_read_only_tool(tool: &str, input: &Value) -> bool {
+    match tool {
+        "http" => {
+            let method = input["method"].as_str().unwrap_or("GET");
+            READ_ONLY_METHODS.contains(&method.to_ascii_uppercase().as_str())
+        }
+        "browser" => input["action"]
+            .as_str()
+            .is_some_and(|action| READ_ONLY_BROWSER_ACTIONS.contains(&action)),
+        name => READ_ONLY_TOOLS.contains(&name) || exec_plugins::is_read_only(name),
+    }
+}
+
+/// Whether `command` only reads: every part of a pipeline or command list
+/// is a known read-only program, with no output redirection or substitution
+pub fn is_read_only_command(command: &str) -> bool {
+    if command.trim().is_empty()
+        || command.contains('>')
+        || command.contains('`')
+        || command.contains("$(")
+    {
+        return false;
+    }
+    command
+        .split(['|', ';', '&', '\n'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .all(|part| {
+            let mut words = part.split_whitespace();
+            match words.next() {
+                Some("git") => words.next().is_some_and(|sub| READ_ONLY_GIT.contains(&sub)),
+                Some("find") => !part.contains("-delete") && !part.contains("-exec"),
+                Some(program) => READ_ONLY_COMMANDS.contains(&program),
+                None => true,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::backlog::{TaskPriority, TaskStatus};
+    use serde_json::json;
+
+    fn task() -> AgentTask {
+        AgentTask {
+            id: "001".into(),
+            description: "Rename greeting".into(),
+            repo: None,
+            status: TaskStatus::Pending,
+            priority: TaskPriority::Normal,
+            notes: None,
+            max_turns: None,
+            summarize: None,
+            failure_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_edits_are_simulated_and_chained() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "fn main() {\n    hello();\n}\n").unwrap();
+        let path = file.to_str().unwrap();
+
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+        let edit =
+            |old: &str, new: &str| json!({"file_path": path, "old_string": old, "new_string": new});
+        let result = dry_run.intercept("edit", &edit("hello", "greet")).unwrap();
+        assert!(result.unwrap().contains("+    greet();"));
+        // Second edit sees the first one
+        assert!(dry_run
+            .intercept("edit", &edit("greet()", "greet(\"x\")"))
+            .unwrap()
+            .is_ok());
+        assert!(dry_run
+            .intercept("edit", &edit("hello", "hi"))
+            .unwrap()
+            .is_err());
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "fn main() {\n    hello();\n}\n"
+        );
+        assert_eq!(dry_run.task_changes(), 2);
+        assert!(dry_run
+            .report()
+            .contains("-    greet();\n+    greet(\"x\");"));
+    }
+
+    #[test]
+    fn test_new_file_and_bash() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("new.txt");
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+
+        let write = json!({"file_path": file.to_str().unwrap(), "content": "a\nb\n"});
+        assert!(dry_run.intercept("write", &write).unwrap().is_ok());
+        assert!(!file.exists());
+        assert!(dry_run.report().contains("@@ -0,0 +1,2 @@\n+a\n+b\n"));
+
+        assert!(dry_run.intercept("read", &json!({})).is_none());
+        assert!(dry_run
+            .intercept("bash", &json!({"command": "git status && ls src | wc -l"}))
+            .is_none());
+        let rm = dry_run.intercept("bash", &json!({"command": "rm -rf target"}));
+        assert!(rm.unwrap().unwrap().starts_with("[dry run]"));
+        assert!(dry_run.report().contains("rm -rf target"));
+    }
+
+    #[test]
+    fn test_http_runs_only_reads() {
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+        let url = "https://example.com/api";
+        assert!(dry_run.intercept("http", &json!({"url": url})).is_none());
+        assert!(dry_run
+            .intercept("http", &json!({"url": url, "method": "head"}))
+            .is_none());
+        let post = dry_run.intercept("http", &json!({"url": url, "method": "post"}));
+        assert!(post.unwrap().unwrap().starts_with("[dry run]"));
+        assert!(dry_run
+            .intercept("http", &json!({"url": url, "method": "DELETE"}))
+            .is_some());
+        assert_eq!(dry_run.task_changes(), 2);
+    }
+
+    #[test]
+    fn test_browser_click_is_simulated() {
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+        let navigate = json!({"action": "navigate", "url": "https://example.com"});
+        assert!(dry_run.intercept("browser", &navigate).is_none());
+        assert!(dry_run
+            .intercept("browser", &json!({"action": "extract_text"}))
+            .is_none());
+        let click = json!({"action": "click", "selector": "#submit"});
+        assert!(dry_run.intercept("browser", &click).is_some());
+        assert!(dry_run.intercept("browser", &json!({})).is_some());
+        assert!(dry_run.report().contains("### browser (not run)"));
+    }
+
+    #[test]
+    fn test_run_code_is_simulated() {
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+        let input = json!({"language": "python", "code": "open('x', 'w').write('y')"});
+        assert!(dry_run.intercept("run_code", &input).is_some());
+        assert!(dry_run.intercept("save_and_exec", &input).is_some());
+        assert_eq!(dry_run.task_changes(), 2);
+    }
+
+    #[test]
+    fn test_mcp_and_unknown_tools_are_simulated() {
+        let mut dry_run = DryRun::new();
+        dry_run.begin_task(&task());
+        let input = json!({"issue": 42, "state": "closed"});
+        let mcp = dry_run.intercept("mcp_github_update_issue", &input);
+        assert!(mcp.unwrap().unwrap().contains("mcp_github_update_issue"));
+        assert!(dry_run.report().contains("\"state\":\"closed\""));
+        assert!(dry_run.intercept("no_such_plugin", &json!({})).is_some());
+        assert!(dry_run
+            .intercept("grep", &json!({"pattern": "x"}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_is_read_only_command() {
+        assert!(is_read_only_command("grep -rn foo src | head -5"));
+        assert!(is_read_only_command("cd repo && git log --oneline -3"));
+        assert!(!is_read_only_command("cat a > b"));
+        assert!(!is_read_only_command("git commit -m wip"));
+        assert!(!is_read_only_command("ls; cargo fmt"));
+        assert!(!is_read_only_command("find . -name '*.tmp' -delete"));
+        assert!(!is_read_only_command("echo $(rm x)"));
+        assert!(!is_read_only_command(""));
+    }
+}
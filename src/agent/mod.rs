@@ -2,14 +2,19 @@
 //
 // Usage:
 //   finch agent [--persona <name|path>] [--tasks <path>] [--reflect-every <n>] [--once]
+//               [--dry-run] [--no-record]
+//   finch agent replay <recording>
 
 pub mod activity_log;
 pub mod backlog;
+pub mod dry_run;
+pub mod recording;
 pub mod reflection;
+pub mod transcript;
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::claude::types::{ContentBlock, Message, MessageRequest};
@@ -17,15 +22,25 @@ use crate::claude::ClaudeClient;
 use crate::config::constants::DEFAULT_CLAUDE_MODEL;
 use crate::config::{persona::Persona, Config};
 use crate::generators::claude::CODING_SYSTEM_PROMPT;
+use crate::scheduling::BackgroundJob;
 use crate::tools::implementations::{
-    BashTool, EditTool, GlobTool, GrepTool, PatchTool, ReadTool, WebFetchTool, WriteTool,
+    BashTool, BrowserTool, EditTool, GlobTool, GrepTool, HttpTool, OutlineTool, PatchTool,
+    ReadTool, RunCodeTool, WebFetchTool, WebSearchTool, WriteTool,
 };
 use crate::tools::types::ToolDefinition;
-use crate::tools::{PermissionManager, PermissionRule, ToolExecutor, ToolRegistry};
+use crate::tools::{
+    PermissionManager, PermissionRule, RenameCheck, ToolExecutor, ToolRegistry, ToolResultBudget,
+};
 
 use activity_log::{ActivityLogger, AgentEvent};
 use backlog::{AgentTask, TaskBacklog};
+use dry_run::DryRun;
+use recording::{
+    Recorder, Recording, RecordingProvider, Replay, ReplayProvider, RunInfo, TaskOutcome,
+    TaskReplay,
+};
 use reflection::ReflectionEngine;
+use transcript::{Transcript, DEFAULT_MAX_TURNS};
 
 /// Configuration for the agent loop
 pub struct AgentConfig {
@@ -37,6 +52,10 @@ pub struct AgentConfig {
     pub reflect_every: usize,
     /// Stop after completing one task (useful for testing)
     pub once: bool,
+    /// Simulate file changes and commits, writing a report instead
+    pub dry_run: bool,
+    /// Record the run to ~/.finch/agent_runs for `finch agent replay`
+    pub record: bool,
 }
 
 impl AgentConfig {
@@ -63,16 +82,74 @@ impl AgentConfig {
 pub struct AgentLoop {
     config: Config,
     agent_config: AgentConfig,
+    /// Set in `--dry-run` mode
+    dry_run: Option<Mutex<DryRun>>,
+    /// Set while the run is being recorded
+    recorder: Option<Arc<Recorder>>,
+    /// Set when replaying a recording
+    replay: Option<Arc<Replay>>,
 }
 
 impl AgentLoop {
     pub fn new(config: Config, agent_config: AgentConfig) -> Self {
+        let dry_run = agent_config.dry_run.then(|| Mutex::new(DryRun::new()));
         Self {
             config,
             agent_config,
+            dry_run,
+            recorder: None,
+            replay: None,
         }
     }
 
+    /// Drive the tasks of `recording` through the loop again, answering
+    /// model requests and tool calls from the recording.  Nothing is
+    /// executed, sent or committed.
+    pub async fn replay(recording: Recording) -> Result<Vec<TaskReplay>> {
+        let run = recording.run.clone();
+        let tasks: Vec<AgentTask> = recording.tasks.iter().map(|t| t.task.clone()).collect();
+        let replay = Arc::new(Replay::new(recording));
+        let client =
+            ClaudeClient::with_provider(Box::new(ReplayProvider::new(Arc::clone(&replay))));
+        let config = Config::new(Vec::new());
+        // Only needed by run_task's signature: tool calls are answered by the replay
+        let (executor, _) = build_tool_executor(&config).await?;
+        let agent = Self {
+            config,
+            agent_config: AgentConfig {
+                persona_spec: run.persona.name().to_string(),
+                tasks_path: PathBuf::new(),
+                reflect_every: 1,
+                once: false,
+                dry_run: false,
+                record: false,
+            },
+            dry_run: None,
+            recorder: None,
+            replay: Some(Arc::clone(&replay)),
+        };
+        let logger = ActivityLogger::disabled();
+
+        let mut results = Vec::new();
+        for (index, task) in tasks.iter().enumerate() {
+            println!("[Task {}] {}", task.id, task.description);
+            replay.begin_task(index);
+            let result = agent
+                .run_task(
+                    task,
+                    &run.persona,
+                    &client,
+                    run.model.clone(),
+                    executor.clone(),
+                    run.tools.clone(),
+                    &logger,
+                )
+                .await;
+            results.push(replay.end_task(TaskOutcome::of(&result)));
+        }
+        Ok(results)
+    }
+
     /// Run the agent loop (returns when `--once` is set or Ctrl-C received)
     pub async fn run(&mut self) -> Result<()> {
         // Load persona
@@ -93,6 +170,10 @@ impl AgentLoop {
         // Load backlog
         let mut backlog = TaskBacklog::load(self.agent_config.tasks_path.clone())
             .context("Failed to load task backlog")?;
+        if self.dry_run.is_some() {
+            // Task statuses stay in memory so the real run starts from the same backlog
+            backlog = backlog.without_persistence();
+        }
 
         let pending_count = backlog
             .tasks()
@@ -106,7 +187,7 @@ impl AgentLoop {
         // Set up activity logger and tool executor
         let logger = ActivityLogger::new()?;
         let (executor, tool_defs) = build_tool_executor(&self.config).await?;
-        let client = create_client(&self.config)?;
+        let mut client = create_client(&self.config)?;
 
         // Set up reflection engine
         let model = self
@@ -116,10 +197,49 @@ impl AgentLoop {
             .unwrap_or_else(|| DEFAULT_CLAUDE_MODEL.to_string());
         let reflector = ReflectionEngine::new(create_client(&self.config)?, model.clone());
 
+        // Record model traffic and tool results for `finch agent replay`
+        if self.agent_config.record {
+            let run = RunInfo::new(&persona, &model, &tool_defs, self.dry_run.is_some());
+            match recording::recordings_dir()
+                .context("Could not determine home directory")
+                .and_then(|dir| Recorder::start(&dir, run))
+            {
+                Ok(recorder) => {
+                    println!("Recording: {}", recorder.path().display());
+                    let recorder = Arc::new(recorder);
+                    client = ClaudeClient::with_provider(Box::new(RecordingProvider::new(
+                        Arc::clone(client.provider()),
+                        Arc::clone(&recorder),
+                    )));
+                    self.recorder = Some(recorder);
+                }
+                Err(e) => tracing::warn!("Not recording this run: {:#}", e),
+            }
+        }
+
+        // Background work runs at a lower CPU priority (tools inherit it)
+        let schedule = &self.config.schedule;
+        if let Err(e) = crate::scheduling::priority::lower_current_process(schedule.nice) {
+            tracing::warn!(
+                "Could not lower agent priority to nice {}: {}",
+                schedule.nice,
+                e
+            );
+        }
+
         let mut completed_count: usize = 0;
         let mut completed_descs: Vec<String> = Vec::new();
 
         loop {
+            // Stay idle outside the hours [schedule] allows (dry runs only preview)
+            if self.dry_run.is_none() && !schedule.allows_now(BackgroundJob::Agent) {
+                let until = schedule.describe_wait(BackgroundJob::Agent);
+                println!("Quiet hours: waiting {}...", until);
+                let _ = logger.log(AgentEvent::QuietHours { until });
+                schedule.wait_until_allowed(BackgroundJob::Agent).await;
+                backlog.reload().context("Failed to reload task backlog")?;
+            }
+
             // Try to get the next pending task
             let task_id = {
                 match backlog.next_pending() {
@@ -129,6 +249,10 @@ impl AgentLoop {
                             println!("No pending tasks. Exiting (--once).");
                             break;
                         }
+                        if self.dry_run.is_some() {
+                            println!("No more pending tasks. Exiting (--dry-run).");
+                            break;
+                        }
                         println!("No pending tasks. Sleeping 60s...");
                         let _ = logger.log(AgentEvent::Idle { sleep_s: 60 });
                         tokio::time::sleep(Duration::from_secs(60)).await;
@@ -152,6 +276,12 @@ impl AgentLoop {
                 desc: task.description.clone(),
             });
             backlog.mark_running(&task.id)?;
+            if let Some(dry_run) = &self.dry_run {
+                dry_run.lock().unwrap().begin_task(&task);
+            }
+            if let Some(recorder) = &self.recorder {
+                recorder.begin_task(&task);
+            }
 
             let start = Instant::now();
             let result = self
@@ -167,6 +297,16 @@ impl AgentLoop {
                 .await;
 
             let duration_s = start.elapsed().as_secs();
+            if let Some(recorder) = &self.recorder {
+                recorder.end_task(TaskOutcome::of(&result));
+            }
+            if let Some(dry_run) = &self.dry_run {
+                let outcome = match &result {
+                    Ok(()) => format!("done ({}s)", duration_s),
+                    Err(e) => format!("failed: {:#}", e),
+                };
+                dry_run.lock().unwrap().end_task(&outcome);
+            }
 
             match result {
                 Ok(()) => {
@@ -179,8 +319,10 @@ impl AgentLoop {
                     completed_count += 1;
                     completed_descs.push(task.description.clone());
 
-                    // Trigger reflection every N tasks
-                    if completed_count.is_multiple_of(self.agent_config.reflect_every) {
+                    // Trigger reflection every N tasks (not in dry runs: it rewrites the persona)
+                    if self.dry_run.is_none()
+                        && completed_count.is_multiple_of(self.agent_config.reflect_every)
+                    {
                         println!("Running self-reflection after {} tasks...", completed_count);
                         match reflector
                             .reflect(&persona, persona_path.as_deref(), &completed_descs)
@@ -220,6 +362,11 @@ impl AgentLoop {
             tokio::time::sleep(Duration::from_secs(2)).await;
         }
 
+        if let Some(dry_run) = &self.dry_run {
+            let path = dry_run.lock().unwrap().save_report()?;
+            println!("Dry-run report: {}", path.display());
+        }
+
         Ok(())
     }
 
@@ -234,6 +381,13 @@ impl AgentLoop {
         tool_defs: Vec<ToolDefinition>,
         logger: &ActivityLogger,
     ) -> Result<()> {
+        // The file tools may also work in the task's repository
+        let path_sandbox = crate::tools::path_sandbox::active();
+        executor.lock().await.set_path_sandbox(match &task.repo {
+            Some(repo) => path_sandbox.with_root(Path::new(repo)),
+            None => path_sandbox.clone(),
+        });
+
         // Build system prompt: coding base + persona + task context
         let repo_cwd = task.repo.as_deref().unwrap_or(".");
         let mut system = format!(
@@ -255,15 +409,32 @@ impl AgentLoop {
             user_msg.push_str(&format!("\n\nRepository: {}", repo));
         }
 
-        let mut messages = vec![Message::user(&user_msg)];
+        let max_turns = task.max_turns.unwrap_or(DEFAULT_MAX_TURNS);
+        let policy = task.summarize.unwrap_or_default();
+        let mut transcript = Transcript::new(user_msg);
 
-        const MAX_TURNS: usize = 25;
+        for _ in 0..max_turns {
+            // Long tasks carry a summary of their older turns instead
+            match transcript.condense(client, &model, &policy).await {
+                Ok(true) => {
+                    println!(
+                        "[Task {}] Summarized the first {} turns",
+                        task.id,
+                        transcript.folded()
+                    );
+                    let _ = logger.log(AgentEvent::Summarized {
+                        id: task.id.clone(),
+                        turns: transcript.folded(),
+                    });
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Keeping the full transcript: {:#}", e),
+            }
 
-        for _ in 0..MAX_TURNS {
             let request = MessageRequest {
                 model: model.clone(),
                 max_tokens: crate::config::constants::DEFAULT_MAX_TOKENS,
-                messages: messages.clone(),
+                messages: transcript.messages(),
                 system: Some(system.clone()),
                 tools: Some(tool_defs.clone()),
             };
@@ -280,9 +451,15 @@ impl AgentLoop {
                     println!("{}", text);
                 }
 
-                // Auto-commit if git changes exist in the task repo
-                if let Some(repo_path) = &task.repo {
-                    if let Err(e) = self.maybe_commit(repo_path, task, persona, logger).await {
+                // Auto-commit if git changes exist in the task repo (not when replaying)
+                if let Some(repo_path) = task.repo.as_ref().filter(|_| self.replay.is_none()) {
+                    if let Some(dry_run) = &self.dry_run {
+                        let mut dry_run = dry_run.lock().unwrap();
+                        if dry_run.task_changes() > 0 {
+                            dry_run.record_commit(repo_path, &commit_message(task, persona));
+                        }
+                    } else if let Err(e) = self.maybe_commit(repo_path, task, persona, logger).await
+                    {
                         tracing::warn!("Auto-commit failed: {}", e);
                     }
                 }
@@ -290,10 +467,13 @@ impl AgentLoop {
             }
 
             // Execute tool calls
-            messages.push(response.to_message());
+            let assistant = response.to_message();
             let tool_uses = response.tool_uses();
-            let mut result_blocks = Vec::new();
 
+            // Replayed and simulated calls are answered right away; the rest
+            // run as one batch, independent calls side by side
+            let mut exec_results = Vec::with_capacity(tool_uses.len());
+            let mut live_calls = Vec::new();
             for tu in &tool_uses {
                 // Log tool use
                 let cmd_preview = tu
@@ -309,46 +489,60 @@ impl AgentLoop {
                     cmd: cmd_preview,
                 });
 
-                let tool_use = crate::tools::types::ToolUse {
-                    id: tu.id.clone(),
-                    name: tu.name.clone(),
-                    input: tu.input.clone(),
-                };
-
-                let exec_result = {
-                    let guard = executor.lock().await;
-                    guard
-                        .execute_tool::<fn() -> anyhow::Result<()>>(
-                            &tool_use, None, // conversation
-                            None, // save_models_fn
-                            None, // batch_trainer
-                            None, // local_generator
-                            None, // tokenizer
-                            None, // repl_mode
-                            None, // plan_content
-                            None, // live_output
-                            None, // stack
-                        )
-                        .await
-                };
+                let simulated = self
+                    .dry_run
+                    .as_ref()
+                    .and_then(|d| d.lock().unwrap().intercept(&tu.name, &tu.input));
+                if let Some(replay) = &self.replay {
+                    exec_results.push(Some(replay.tool_result(tu)));
+                } else if let Some(simulated) = simulated {
+                    exec_results.push(Some(simulated.map(|content| {
+                        crate::tools::types::ToolResult::success(tu.id.clone(), content)
+                    })));
+                } else {
+                    exec_results.push(None);
+                    live_calls.push(tu.clone());
+                }
+            }
+            if !live_calls.is_empty() {
+                let runner = executor.lock().await.runner();
+                let mut live_results = runner
+                    .execute_batch(
+                        &live_calls,
+                        std::time::Duration::from_secs(
+                            crate::config::constants::BATCH_TOOL_TIMEOUT_SECS,
+                        ),
+                    )
+                    .await
+                    .into_iter();
+                for slot in exec_results.iter_mut().filter(|slot| slot.is_none()) {
+                    *slot = live_results.next();
+                }
+            }
 
+            let mut result_blocks = Vec::new();
+            for (tool_use, exec_result) in tool_uses.iter().zip(exec_results) {
+                let exec_result = exec_result.expect("every tool call has a result");
                 let (content, is_error) = match exec_result {
                     Ok(result) => (result.content, result.is_error),
                     Err(e) => (format!("Error: {e}"), true),
                 };
+                if let Some(recorder) = &self.recorder {
+                    recorder.tool_result(tool_use, &content, is_error);
+                }
                 result_blocks.push(ContentBlock::tool_result(
-                    tu.id.clone(),
+                    tool_use.id.clone(),
                     content,
                     if is_error { Some(true) } else { None },
                 ));
             }
 
-            messages.push(Message::with_content("user", result_blocks));
+            transcript.push(assistant, Message::with_content("user", result_blocks));
         }
 
         anyhow::bail!(
             "Reached max tool turns ({}) without completing task",
-            MAX_TURNS
+            max_turns
         )
     }
 
@@ -382,13 +576,7 @@ impl AgentLoop {
             anyhow::bail!("git add failed: {}", String::from_utf8_lossy(&add.stderr));
         }
 
-        // Build commit message
-        let commit_msg = format!(
-            "agent: {}\n\nTask ID: {}\nAgent: {}",
-            truncate(&task.description, 72),
-            task.id,
-            persona.name()
-        );
+        let commit_msg = commit_message(task, persona);
 
         // Determine git identity
         let git_name = persona
@@ -479,17 +667,26 @@ impl AgentLoop {
 
 /// Build the tool executor for agent mode (auto-approve all tools)
 async fn build_tool_executor(
-    _config: &Config,
+    config: &Config,
 ) -> Result<(Arc<tokio::sync::Mutex<ToolExecutor>>, Vec<ToolDefinition>)> {
     let mut registry = ToolRegistry::new();
     registry.register(Box::new(ReadTool));
     registry.register(Box::new(GlobTool));
     registry.register(Box::new(GrepTool));
+    registry.register(Box::new(OutlineTool));
     registry.register(Box::new(WebFetchTool::new()));
-    registry.register(Box::new(BashTool));
+    registry.register(Box::new(WebSearchTool::new()));
+    registry.register(Box::new(BashTool::new()));
+    registry.register(Box::new(RunCodeTool::new()));
     registry.register(Box::new(EditTool));
     registry.register(Box::new(PatchTool));
     registry.register(Box::new(WriteTool));
+    if crate::tools::implementations::browser::enabled() {
+        registry.register(Box::new(BrowserTool::new()));
+    }
+    if crate::tools::implementations::http::enabled() {
+        registry.register(Box::new(HttpTool::new()));
+    }
 
     // In agent mode, auto-approve everything by default
     // (controlled by features.auto_approve_tools, but agent mode always runs headless)
@@ -500,13 +697,30 @@ async fn build_tool_executor(
         .unwrap_or_else(|| PathBuf::from(".finch/tool_patterns.json"));
 
     let executor = ToolExecutor::new(registry, permissions, patterns_path)
-        .context("Failed to create tool executor")?;
+        .context("Failed to create tool executor")?
+        .with_result_budget(ToolResultBudget::from_features(&config.features))
+        .with_rename_check(RenameCheck::new(config.features.rename_check))
+        .with_max_parallel_tools(config.features.max_parallel_tools)
+        .with_tool_timeouts(&config.features.tool_timeouts)
+        .with_mcp(config)
+        .await
+        .with_plugins();
     let executor = Arc::new(tokio::sync::Mutex::new(executor));
 
     let tool_defs = executor.lock().await.list_all_tools().await;
     Ok((executor, tool_defs))
 }
 
+/// Commit message for the changes made by `task`
+fn commit_message(task: &AgentTask, persona: &Persona) -> String {
+    format!(
+        "agent: {}\n\nTask ID: {}\nAgent: {}",
+        truncate(&task.description, 72),
+        task.id,
+        persona.name()
+    )
+}
+
 fn create_client(config: &Config) -> Result<ClaudeClient> {
     let provider = crate::providers::create_provider(&config.teachers)?;
     Ok(ClaudeClient::with_provider(provider))
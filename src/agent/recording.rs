@@ -0,0 +1,821 @@
+// Record and replay of agent runs
+//
+// Every `finch agent` run is recorded to ~/.finch/agent_runs/<timestamp>.jsonl,
+// one event per line: the run (persona, model, tool definitions), then for
+// each task its start, every model request with its response or error, every
+// tool result, and the outcome.  Lines are written as they happen, so a run
+// that dies overnight still leaves a recording up to the failure.  A request
+// only stores the messages added since the previous request.
+//
+// `finch agent replay <recording>` drives the recorded tasks through the
+// agent loop again, with the model and the tools answered from the recording,
+// and reports where the loop's requests, tool calls or outcomes differ from
+// what was recorded.  Nothing is executed, sent or committed, so a replay is
+// deterministic: use it for postmortems, and to check that a change to the
+// loop still drives recorded runs the same way.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::Receiver;
+
+use super::backlog::AgentTask;
+use crate::claude::types::Message;
+use crate::config::persona::Persona;
+use crate::providers::{LlmProvider, ProviderRequest, ProviderResponse, StreamChunk};
+use crate::tools::types::{ToolDefinition, ToolResult, ToolUse};
+
+/// Format version written to the `run` line
+const RECORDING_VERSION: u32 = 1;
+
+/// Recordings kept in ~/.finch/agent_runs; older ones are deleted
+const KEEP_RECORDINGS: usize = 50;
+
+/// Where recordings are written
+pub fn recordings_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".finch").join("agent_runs"))
+}
+
+/// The first line of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub version: u32,
+    pub finch_version: String,
+    /// RFC 3339
+    pub started_at: String,
+    pub persona: Persona,
+    pub model: String,
+    pub tools: Vec<ToolDefinition>,
+    /// Recorded with `--dry-run`: tool results are the simulated ones
+    pub dry_run: bool,
+}
+
+impl RunInfo {
+    pub fn new(persona: &Persona, model: &str, tools: &[ToolDefinition], dry_run: bool) -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            finch_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            persona: persona.clone(),
+            model: model.to_string(),
+            tools: tools.to_vec(),
+            dry_run,
+        }
+    }
+}
+
+/// One model request and what came back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTurn {
+    /// Leading messages shared with the previous request (0 once loaded:
+    /// `messages` then holds the whole conversation)
+    pub messages_from: usize,
+    pub messages: Vec<Message>,
+    pub model: String,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ProviderResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedToolResult {
+    pub id: String,
+    pub name: String,
+    pub input: Value,
+    pub content: String,
+    pub is_error: bool,
+}
+
+/// How a task ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TaskOutcome {
+    Done,
+    Failed { reason: String },
+}
+
+impl TaskOutcome {
+    pub fn of(result: &Result<()>) -> Self {
+        match result {
+            Ok(()) => Self::Done,
+            Err(e) => Self::Failed {
+                reason: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for TaskOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Done => write!(f, "done"),
+            Self::Failed { reason } => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// A line of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    Run(RunInfo),
+    TaskStart { task: AgentTask },
+    Turn(RecordedTurn),
+    ToolResult(RecordedToolResult),
+    TaskEnd { outcome: TaskOutcome },
+}
+
+/// Appends events to a recording.  Failing to write only disables the
+/// recording; the run goes on.
+pub struct Recorder {
+    path: PathBuf,
+    state: Mutex<RecorderState>,
+}
+
+struct RecorderState {
+    file: Option<File>,
+    /// Messages of the previous request, as JSON
+    sent: Vec<Value>,
+}
+
+impl Recorder {
+    /// Start a new recording in `dir`, deleting the oldest recordings there
+    /// beyond the last `KEEP_RECORDINGS`
+    pub fn start(dir: &Path, run: RunInfo) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        prune(dir, KEEP_RECORDINGS.saturating_sub(1));
+
+        let stamp = chrono::Local::now().format("%Y-%m-%d_%H%M%S");
+        let mut path = dir.join(format!("{}.jsonl", stamp));
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = dir.join(format!("{}_{}.jsonl", stamp, n));
+        }
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let recorder = Self {
+            path,
+            state: Mutex::new(RecorderState {
+                file: Some(file),
+                sent: Vec::new(),
+            }),
+        };
+        recorder.write(
+            &mut recorder.state.lock().unwrap(),
+            &RecordedEvent::Run(run),
+        );
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn begin_task(&self, task: &AgentTask) {
+        let event = RecordedEvent::TaskStart { task: task.clone() };
+        self.write(&mut self.state.lock().unwrap(), &event);
+    }
+
+    pub fn end_task(&self, outcome: TaskOutcome) {
+        let event = RecordedEvent::TaskEnd { outcome };
+        self.write(&mut self.state.lock().unwrap(), &event);
+    }
+
+    pub fn tool_result(&self, tool_use: &ToolUse, content: &str, is_error: bool) {
+        let event = RecordedEvent::ToolResult(RecordedToolResult {
+            id: tool_use.id.clone(),
+            name: tool_use.name.clone(),
+            input: tool_use.input.clone(),
+            content: content.to_string(),
+            is_error,
+        });
+        self.write(&mut self.state.lock().unwrap(), &event);
+    }
+
+    fn turn(&self, request: &ProviderRequest, result: &Result<ProviderResponse>) {
+        let mut state = self.state.lock().unwrap();
+        let messages: Vec<Value> = request
+            .messages
+            .iter()
+            .map(|m| serde_json::to_value(m).unwrap_or_default())
+            .collect();
+        let shared = state
+            .sent
+            .iter()
+            .zip(&messages)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let event = RecordedEvent::Turn(RecordedTurn {
+            messages_from: shared,
+            messages: request.messages[shared..].to_vec(),
+            model: request.model.clone(),
+            max_tokens: request.max_tokens,
+            system: request.system.clone(),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        });
+        state.sent = messages;
+        self.write(&mut state, &event);
+    }
+
+    fn write(&self, state: &mut RecorderState, event: &RecordedEvent) {
+        let Some(file) = state.file.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_string(event)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(writeln!(file, "{}", line)?));
+        if let Err(e) = written {
+            tracing::warn!(
+                "Agent run recording stopped, could not write {}: {}",
+                self.path.display(),
+                e
+            );
+            state.file = None;
+        }
+    }
+}
+
+/// Delete all but the newest `keep` recordings in `dir`
+fn prune(dir: &Path, keep: usize) {
+    let mut recordings: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    recordings.retain(|p| p.extension().is_some_and(|ext| ext == "jsonl"));
+    // Timestamped names sort oldest first
+    recordings.sort();
+    let excess = recordings.len().saturating_sub(keep);
+    for path in &recordings[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Records every request sent through `inner` and its response.  Streams
+/// pass through unrecorded; the agent loop doesn't stream.
+pub struct RecordingProvider {
+    inner: Arc<dyn LlmProvider>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>, recorder: Arc<Recorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RecordingProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        let result = self.inner.send_message(request).await;
+        self.recorder.turn(request, &result);
+        result
+    }
+
+    async fn send_message_stream(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<Receiver<Result<StreamChunk>>> {
+        self.inner.send_message_stream(request).await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.inner.context_limit_tokens()
+    }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        self.inner.cancel_generation().await
+    }
+}
+
+/// A task as recorded
+#[derive(Debug, Clone)]
+pub struct RecordedTask {
+    pub task: AgentTask,
+    pub turns: Vec<RecordedTurn>,
+    pub tool_results: Vec<RecordedToolResult>,
+    /// `None` when the run stopped during the task
+    pub outcome: Option<TaskOutcome>,
+}
+
+/// A loaded recording
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub run: RunInfo,
+    pub tasks: Vec<RecordedTask>,
+}
+
+impl Recording {
+    /// Load a recording.  A cut-off last line (the run was killed while
+    /// writing it) is ignored.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid recording {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        let mut run = None;
+        let mut tasks: Vec<RecordedTask> = Vec::new();
+        // The conversation of the previous request, to rebuild each request
+        let mut sent: Vec<Message> = Vec::new();
+
+        for (i, line) in lines.iter().enumerate() {
+            let event: RecordedEvent = match serde_json::from_str(line) {
+                Ok(event) => event,
+                Err(_) if i + 1 == lines.len() && i > 0 => break,
+                Err(e) => bail!("line {}: {}", i + 1, e),
+            };
+            if let RecordedEvent::Run(info) = event {
+                if info.version > RECORDING_VERSION {
+                    bail!(
+                        "recorded by finch {} in format {}, newer than this finch reads",
+                        info.finch_version,
+                        info.version
+                    );
+                }
+                run = Some(info);
+                continue;
+            }
+            if run.is_none() {
+                bail!("line {}: the recording does not start with a run", i + 1);
+            }
+            if let RecordedEvent::TaskStart { task } = event {
+                tasks.push(RecordedTask {
+                    task,
+                    turns: Vec::new(),
+                    tool_results: Vec::new(),
+                    outcome: None,
+                });
+                continue;
+            }
+            let Some(task) = tasks.last_mut() else {
+                bail!("line {}: event outside a task", i + 1);
+            };
+            match event {
+                RecordedEvent::Turn(mut turn) => {
+                    if turn.messages_from > sent.len() {
+                        bail!("line {}: refers to messages never recorded", i + 1);
+                    }
+                    sent.truncate(turn.messages_from);
+                    sent.append(&mut turn.messages);
+                    turn.messages = sent.clone();
+                    turn.messages_from = 0;
+                    task.turns.push(turn);
+                }
+                RecordedEvent::ToolResult(result) => task.tool_results.push(result),
+                RecordedEvent::TaskEnd { outcome } => task.outcome = Some(outcome),
+                RecordedEvent::Run(_) | RecordedEvent::TaskStart { .. } => unreachable!(),
+            }
+        }
+
+        let run = run.context("the recording is empty")?;
+        Ok(Self { run, tasks })
+    }
+}
+
+/// Serves a recording to a replayed run and notes where the run departs
+/// from it
+pub struct Replay {
+    recording: Recording,
+    state: Mutex<ReplayState>,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    task: usize,
+    turn: usize,
+    divergences: Vec<String>,
+}
+
+/// The replay of one task
+#[derive(Debug, Clone)]
+pub struct TaskReplay {
+    pub id: String,
+    pub description: String,
+    pub recorded: Option<TaskOutcome>,
+    pub replayed: TaskOutcome,
+    /// Where the replayed task departed from the recording
+    pub divergences: Vec<String>,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            state: Mutex::new(ReplayState::default()),
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Serve the recorded task at `index` from now on
+    pub fn begin_task(&self, index: usize) {
+        *self.state.lock().unwrap() = ReplayState {
+            task: index,
+            ..Default::default()
+        };
+    }
+
+    /// Finish the current task, which ended with `outcome`
+    pub fn end_task(&self, outcome: TaskOutcome) -> TaskReplay {
+        let mut state = self.state.lock().unwrap();
+        let recorded = &self.recording.tasks[state.task];
+        let unused = recorded.turns.len().saturating_sub(state.turn);
+        if unused > 0 {
+            state.divergences.push(format!(
+                "the recorded run made {} more model request(s)",
+                unused
+            ));
+        }
+        if recorded.outcome.as_ref().is_some_and(|o| *o != outcome) {
+            state
+                .divergences
+                .push("the task ended differently from the recording".to_string());
+        }
+        TaskReplay {
+            id: recorded.task.id.clone(),
+            description: recorded.task.description.clone(),
+            recorded: recorded.outcome.clone(),
+            replayed: outcome,
+            divergences: std::mem::take(&mut state.divergences),
+        }
+    }
+
+    /// The recorded result of `tool_use`
+    pub fn tool_result(&self, tool_use: &ToolUse) -> Result<ToolResult> {
+        let mut state = self.state.lock().unwrap();
+        let recorded = self.recording.tasks[state.task]
+            .tool_results
+            .iter()
+            .find(|r| r.id == tool_use.id);
+        let Some(recorded) = recorded else {
+            state.divergences.push(format!(
+                "{} call {} was not made in the recorded run",
+                tool_use.name, tool_use.id
+            ));
+            bail!(
+                "Replay: no recorded result for {} call {}",
+                tool_use.name,
+                tool_use.id
+            );
+        };
+        if recorded.name != tool_use.name || recorded.input != tool_use.input {
+            state.divergences.push(format!(
+                "{} call {} differs from the recorded {} call",
+                tool_use.name, tool_use.id, recorded.name
+            ));
+        }
+        Ok(ToolResult {
+            tool_use_id: tool_use.id.clone(),
+            content: recorded.content.clone(),
+            is_error: recorded.is_error,
+        })
+    }
+
+    /// The recorded answer to the next model request of the current task
+    fn next_turn(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        let mut state = self.state.lock().unwrap();
+        let task = &self.recording.tasks[state.task];
+        let n = state.turn;
+        state.turn += 1;
+        let Some(turn) = task.turns.get(n) else {
+            state.divergences.push(format!(
+                "model request {} was not made in the recorded run",
+                n + 1
+            ));
+            bail!(
+                "Replay: the recording has no model request {} for task {}",
+                n + 1,
+                task.task.id
+            );
+        };
+        if let Some(difference) = request_difference(turn, request, &self.recording.run.tools) {
+            state
+                .divergences
+                .push(format!("model request {}: {}", n + 1, difference));
+        }
+        match (&turn.response, &turn.error) {
+            (Some(response), _) => Ok(response.clone()),
+            (None, Some(error)) => Err(anyhow::anyhow!("{}", error)),
+            (None, None) => bail!("Replay: model request {} has no recorded answer", n + 1),
+        }
+    }
+}
+
+/// How `request` differs from the recorded one, if it does
+fn request_difference(
+    recorded: &RecordedTurn,
+    request: &ProviderRequest,
+    recorded_tools: &[ToolDefinition],
+) -> Option<String> {
+    if request.model != recorded.model {
+        return Some(format!(
+            "model is {}, recorded {}",
+            request.model, recorded.model
+        ));
+    }
+    if request.max_tokens != recorded.max_tokens {
+        return Some(format!(
+            "max_tokens is {}, recorded {}",
+            request.max_tokens, recorded.max_tokens
+        ));
+    }
+    if request.system != recorded.system {
+        return Some("the system prompt differs".to_string());
+    }
+    let tools = request.tools.as_deref().unwrap_or_default();
+    if serde_json::to_value(tools).ok() != serde_json::to_value(recorded_tools).ok() {
+        return Some("the tool definitions differ".to_string());
+    }
+    let first_difference = request
+        .messages
+        .iter()
+        .zip(&recorded.messages)
+        .position(|(a, b)| serde_json::to_value(a).ok() != serde_json::to_value(b).ok());
+    match first_difference {
+        Some(i) => Some(format!("message {} differs", i + 1)),
+        None if request.messages.len() != recorded.messages.len() => Some(format!(
+            "{} messages, recorded {}",
+            request.messages.len(),
+            recorded.messages.len()
+        )),
+        None => None,
+    }
+}
+
+/// Answers model requests from a `Replay`
+pub struct ReplayProvider {
+    replay: Arc<Replay>,
+}
+
+impl ReplayProvider {
+    pub fn new(replay: Arc<Replay>) -> Self {
+        Self { replay }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        self.replay.next_turn(request)
+    }
+
+    async fn send_message_stream(
+        &self,
+        _request: &ProviderRequest,
+    ) -> Result<Receiver<Result<StreamChunk>>> {
+        bail!("Replayed runs don't stream")
+    }
+
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn default_model(&self) -> &str {
+        &self.replay.recording.run.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::types::ContentBlock;
+
+    /// Answers with a tool call first, then with text
+    struct Scripted;
+
+    #[async_trait]
+    impl LlmProvider for Scripted {
+        async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+            let content = if request.messages.len() == 1 {
+                vec![ContentBlock::ToolUse {
+                    id: "call_1".to_string(),
+                    name: "read".to_string(),
+                    input: serde_json::json!({ "file_path": "notes.txt" }),
+                }]
+            } else {
+                vec![ContentBlock::text("All done")]
+            };
+            Ok(ProviderResponse {
+                id: "msg".to_string(),
+                model: request.model.clone(),
+                content,
+                stop_reason: Some("end_turn".to_string()),
+                role: "assistant".to_string(),
+                provider: "scripted".to_string(),
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<Receiver<Result<StreamChunk>>> {
+            bail!("no streaming")
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn default_model(&self) -> &str {
+            "scripted-model"
+        }
+    }
+
+    fn task(id: &str) -> AgentTask {
+        toml::from_str(&format!(
+            "id = \"{}\"\ndescription = \"Summarise notes\"\nstatus = \"pending\"",
+            id
+        ))
+        .unwrap()
+    }
+
+    fn request(messages: Vec<Message>) -> ProviderRequest {
+        ProviderRequest::new(messages)
+            .with_model("scripted-model")
+            .with_system("Be brief")
+    }
+
+    /// One task: a read call, then a final answer
+    async fn run_task(provider: &dyn LlmProvider, tools: &dyn Fn(&ToolUse) -> ToolResult) {
+        let mut messages = vec![Message::user("Task: Summarise notes")];
+        loop {
+            let response = provider
+                .send_message(&request(messages.clone()))
+                .await
+                .unwrap();
+            if !response.has_tool_uses() {
+                return;
+            }
+            messages.push(Message::with_content("assistant", response.content.clone()));
+            let mut results = Vec::new();
+            for tool_use in response.tool_uses() {
+                let result = tools(&tool_use);
+                results.push(ContentBlock::tool_result(
+                    result.tool_use_id,
+                    result.content,
+                    None,
+                ));
+            }
+            messages.push(Message::with_content("user", results));
+        }
+    }
+
+    async fn record_run(dir: &Path) -> PathBuf {
+        let persona = Persona::load_builtin("autonomous").unwrap();
+        let recorder = Arc::new(
+            Recorder::start(dir, RunInfo::new(&persona, "scripted-model", &[], false)).unwrap(),
+        );
+        let provider = RecordingProvider::new(Arc::new(Scripted), Arc::clone(&recorder));
+        for id in ["001", "002"] {
+            recorder.begin_task(&task(id));
+            run_task(&provider, &|tool_use| {
+                let result = ToolResult::success(tool_use.id.clone(), "buy milk".to_string());
+                recorder.tool_result(tool_use, &result.content, false);
+                result
+            })
+            .await;
+            recorder.end_task(TaskOutcome::Done);
+        }
+        recorder.path().to_path_buf()
+    }
+
+    #[tokio::test]
+    async fn test_recording_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = record_run(dir.path()).await;
+        let text = std::fs::read_to_string(&path).unwrap();
+        // run, then per task: start, two turns, a tool result, end
+        assert_eq!(text.lines().count(), 1 + 2 * 5);
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.run.model, "scripted-model");
+        assert_eq!(recording.tasks.len(), 2);
+        let first = &recording.tasks[0];
+        assert_eq!(first.task.id, "001");
+        assert_eq!(first.outcome, Some(TaskOutcome::Done));
+        assert_eq!(first.tool_results[0].content, "buy milk");
+        // The second request is rebuilt from the messages it shares with the first
+        assert_eq!(first.turns[0].messages.len(), 1);
+        assert_eq!(first.turns[1].messages.len(), 3);
+        assert_eq!(first.turns[1].system.as_deref(), Some("Be brief"));
+        assert_eq!(recording.tasks[1].turns[1].messages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_faithful_replay_has_no_divergences() {
+        let dir = tempfile::tempdir().unwrap();
+        let replay = Arc::new(Replay::new(
+            Recording::load(&record_run(dir.path()).await).unwrap(),
+        ));
+        let provider = ReplayProvider::new(Arc::clone(&replay));
+        for index in 0..2 {
+            replay.begin_task(index);
+            run_task(&provider, &|tool_use| replay.tool_result(tool_use).unwrap()).await;
+            let result = replay.end_task(TaskOutcome::Done);
+            assert!(result.divergences.is_empty(), "{:?}", result.divergences);
+            assert_eq!(result.recorded, Some(TaskOutcome::Done));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_reports_divergences() {
+        let dir = tempfile::tempdir().unwrap();
+        let replay = Arc::new(Replay::new(
+            Recording::load(&record_run(dir.path()).await).unwrap(),
+        ));
+        let provider = ReplayProvider::new(Arc::clone(&replay));
+
+        replay.begin_task(0);
+        let changed = ProviderRequest::new(vec![Message::user("Task: something else")])
+            .with_model("scripted-model")
+            .with_system("Be brief");
+        let response = provider.send_message(&changed).await.unwrap();
+        // The recorded answer is served regardless
+        assert!(response.has_tool_uses());
+        let other_call = ToolUse {
+            id: "call_9".to_string(),
+            name: "bash".to_string(),
+            input: serde_json::json!({ "command": "ls" }),
+        };
+        assert!(replay.tool_result(&other_call).is_err());
+        let result = replay.end_task(TaskOutcome::Failed {
+            reason: "gave up".to_string(),
+        });
+        assert_eq!(
+            result.divergences,
+            vec![
+                "model request 1: message 1 differs",
+                "bash call call_9 was not made in the recorded run",
+                "the recorded run made 1 more model request(s)",
+                "the task ended differently from the recording",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_truncated_last_line_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = record_run(dir.path()).await;
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str("{\"event\":\"tool_res");
+        let recording = Recording::parse(&text).unwrap();
+        assert_eq!(recording.tasks.len(), 2);
+        assert!(
+            Recording::parse("{\"event\":\"task_end\",\"outcome\":{\"status\":\"done\"}}\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_old_recordings_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        for day in 1..=5 {
+            std::fs::write(
+                dir.path().join(format!("2026-01-0{}_000000.jsonl", day)),
+                "",
+            )
+            .unwrap();
+        }
+        prune(dir.path(), 2);
+        let mut left: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec!["2026-01-04_000000.jsonl", "2026-01-05_000000.jsonl"]
+        );
+    }
+}
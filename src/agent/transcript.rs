@@ -0,0 +1,410 @@
+// Task transcripts with rolling summaries
+//
+// A task's transcript is the task message followed by one turn per model
+// reply: the assistant message with its tool calls, then the user message
+// with their results.  Every request resends all of it, so a task that needs
+// many steps used to run into the context limit (or MAX_TURNS) just before
+// finishing.  Now, once more than `after_turns` turns are kept in full, all
+// but the newest `keep_recent` are folded into a summary the model writes,
+// which rides along in the first message after the task itself.
+//
+// Summaries lose code, so the latest `read` of each file that the kept turns
+// still mention is carried along verbatim until no kept turn mentions it.
+
+use crate::claude::types::{ContentBlock, Message, MessageRequest};
+use crate::claude::ClaudeClient;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Turn limit for a task that doesn't set `max_turns`
+pub const DEFAULT_MAX_TURNS: usize = 25;
+
+/// Longest file content carried across a summary
+const MAX_SNIPPET_CHARS: usize = 4_000;
+
+/// Files carried across a summary; the most recently read win
+const MAX_SNIPPETS: usize = 6;
+
+/// How much of each tool input and result the summarizer sees
+const MAX_INPUT_CHARS: usize = 300;
+const MAX_RESULT_CHARS: usize = 1_500;
+
+const SUMMARY_MAX_TOKENS: u32 = 1024;
+
+/// When to fold a task's older turns into a summary, from
+/// `[tasks.summarize]` in tasks.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryPolicy {
+    /// Turns kept in full before the older ones are summarized (0 = never)
+    #[serde(default = "default_after_turns")]
+    pub after_turns: usize,
+    /// Newest turns left as they are when summarizing
+    #[serde(default = "default_keep_recent")]
+    pub keep_recent: usize,
+}
+
+fn default_after_turns() -> usize {
+    12
+}
+
+fn default_keep_recent() -> usize {
+    4
+}
+
+impl Default for SummaryPolicy {
+    fn default() -> Self {
+        Self {
+            after_turns: default_after_turns(),
+            keep_recent: default_keep_recent(),
+        }
+    }
+}
+
+/// A file's content as it was last read, kept across summaries
+#[derive(Debug, Clone, PartialEq)]
+struct Snippet {
+    path: String,
+    content: String,
+}
+
+/// One model reply and the results of its tool calls
+struct Turn {
+    assistant: Message,
+    results: Message,
+}
+
+pub struct Transcript {
+    task: String,
+    summary: Option<String>,
+    snippets: Vec<Snippet>,
+    turns: Vec<Turn>,
+    /// Turns folded into `summary` so far
+    folded: usize,
+}
+
+impl Transcript {
+    /// A transcript starting with the task message
+    pub fn new(task: impl Into<String>) -> Self {
+        Self {
+            task: task.into(),
+            summary: None,
+            snippets: Vec::new(),
+            turns: Vec::new(),
+            folded: 0,
+        }
+    }
+
+    /// Add a turn: the assistant's tool calls and the user message with
+    /// their results
+    pub fn push(&mut self, assistant: Message, results: Message) {
+        self.turns.push(Turn { assistant, results });
+    }
+
+    /// Turns folded into the summary so far
+    pub fn folded(&self) -> usize {
+        self.folded
+    }
+
+    /// The messages to send: the task (with the summary and carried files,
+    /// once there are any), then the turns kept in full
+    pub fn messages(&self) -> Vec<Message> {
+        let mut first = self.task.clone();
+        if let Some(summary) = &self.summary {
+            first.push_str(&format!(
+                "\n\n## Progress so far (summary of the first {} turns)\n\n{}",
+                self.folded, summary
+            ));
+        }
+        for snippet in &self.snippets {
+            first.push_str(&format!(
+                "\n\n## {} (as read earlier)\n\n```\n{}\n```",
+                snippet.path, snippet.content
+            ));
+        }
+        let mut messages = vec![Message::user(first)];
+        for turn in &self.turns {
+            messages.push(turn.assistant.clone());
+            messages.push(turn.results.clone());
+        }
+        messages
+    }
+
+    /// Whether `policy` calls for a summary now
+    pub fn due(&self, policy: &SummaryPolicy) -> bool {
+        policy.after_turns > 0 && self.turns.len() > policy.after_turns.max(policy.keep_recent)
+    }
+
+    /// Have the model summarize the older turns and fold them, when
+    /// `policy` says it's time.  Returns whether it folded.
+    pub async fn condense(
+        &mut self,
+        client: &ClaudeClient,
+        model: &str,
+        policy: &SummaryPolicy,
+    ) -> Result<bool> {
+        if !self.due(policy) {
+            return Ok(false);
+        }
+        let request = MessageRequest {
+            model: model.to_string(),
+            max_tokens: SUMMARY_MAX_TOKENS,
+            messages: vec![Message::user(self.summary_prompt(policy.keep_recent))],
+            system: None,
+            tools: None,
+        };
+        let response = client
+            .send_message(&request)
+            .await
+            .context("Summarizing the transcript failed")?;
+        let summary = response.text();
+        if summary.trim().is_empty() {
+            bail!("The transcript summary came back empty");
+        }
+        self.fold(summary.trim().to_string(), policy.keep_recent);
+        Ok(true)
+    }
+
+    /// Asks for a summary of the turns `fold` would remove, folding in the
+    /// previous summary
+    fn summary_prompt(&self, keep_recent: usize) -> String {
+        let count = self.turns.len().saturating_sub(keep_recent);
+        let mut prompt = format!(
+            "Condense part of the transcript of an autonomous coding task so the work can \
+             continue without it.\n\n# Task\n\n{}\n\n",
+            self.task
+        );
+        if let Some(summary) = &self.summary {
+            prompt.push_str(&format!("# Earlier summary\n\n{}\n\n", summary));
+        }
+        prompt.push_str("# Turns to condense\n\n");
+        for (i, turn) in self.turns[..count].iter().enumerate() {
+            prompt.push_str(&format!("Turn {}:\n", self.folded + i + 1));
+            prompt.push_str(&describe_turn(turn));
+            prompt.push('\n');
+        }
+        prompt.push_str(
+            "Write a progress summary of under 300 words, covering the earlier summary too: \
+             what has been done (files changed, commands run and how they went), facts that \
+             are still needed (file paths, function names, exact error messages), and what \
+             remains. Reply with the summary only.",
+        );
+        prompt
+    }
+
+    /// Replace all but the newest `keep_recent` turns with `summary`
+    fn fold(&mut self, summary: String, keep_recent: usize) {
+        let count = self.turns.len().saturating_sub(keep_recent);
+        let folded: Vec<Turn> = self.turns.drain(..count).collect();
+
+        // The latest read of each file, newest last
+        let mut snippets = std::mem::take(&mut self.snippets);
+        for turn in &folded {
+            for (path, content) in reads(turn) {
+                snippets.retain(|s| s.path != path);
+                snippets.push(Snippet {
+                    content: truncate(&content, MAX_SNIPPET_CHARS),
+                    path,
+                });
+            }
+        }
+        // Only files the task or the kept turns still refer to
+        let kept: String = self.turns.iter().map(turn_text).collect();
+        snippets.retain(|s| self.task.contains(&s.path) || kept.contains(&s.path));
+        let excess = snippets.len().saturating_sub(MAX_SNIPPETS);
+        snippets.drain(..excess);
+
+        self.snippets = snippets;
+        self.summary = Some(summary);
+        self.folded += count;
+    }
+}
+
+/// Files a turn read successfully, with what it read
+fn reads(turn: &Turn) -> Vec<(String, String)> {
+    let paths: HashMap<&str, &str> = turn
+        .assistant
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, name, input } if name == "read" => {
+                input["file_path"].as_str().map(|path| (id.as_str(), path))
+            }
+            _ => None,
+        })
+        .collect();
+    turn.results
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } if *is_error != Some(true) => paths
+                .get(tool_use_id.as_str())
+                .map(|path| (path.to_string(), content.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Everything a turn says, for finding the files it mentions
+fn turn_text(turn: &Turn) -> String {
+    let mut text = String::new();
+    for block in turn.assistant.content.iter().chain(&turn.results.content) {
+        match block {
+            ContentBlock::Text { text: t } => text.push_str(t),
+            ContentBlock::ToolUse { input, .. } => text.push_str(&input.to_string()),
+            ContentBlock::ToolResult { content, .. } => text.push_str(content),
+            _ => {}
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// A turn for the summarizer: what the model said, each call and its result
+fn describe_turn(turn: &Turn) -> String {
+    let mut out = String::new();
+    for block in &turn.assistant.content {
+        match block {
+            ContentBlock::Text { text } if !text.trim().is_empty() => {
+                out.push_str(&format!("{}\n", text.trim()));
+            }
+            ContentBlock::ToolUse { name, input, .. } => {
+                out.push_str(&format!(
+                    "→ {} {}\n",
+                    name,
+                    truncate(&input.to_string(), MAX_INPUT_CHARS)
+                ));
+            }
+            _ => {}
+        }
+    }
+    for block in &turn.results.content {
+        if let ContentBlock::ToolResult {
+            content, is_error, ..
+        } = block
+        {
+            let marker = if *is_error == Some(true) {
+                "✗"
+            } else {
+                "←"
+            };
+            out.push_str(&format!(
+                "{} {}\n",
+                marker,
+                truncate(content, MAX_RESULT_CHARS)
+            ));
+        }
+    }
+    out
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A turn calling `tool` with `input`, answered with `result`
+    fn turn(id: &str, tool: &str, input: serde_json::Value, result: &str) -> (Message, Message) {
+        (
+            Message::with_content(
+                "assistant",
+                vec![ContentBlock::ToolUse {
+                    id: id.to_string(),
+                    name: tool.to_string(),
+                    input,
+                }],
+            ),
+            Message::with_content(
+                "user",
+                vec![ContentBlock::tool_result(
+                    id.to_string(),
+                    result.to_string(),
+                    None,
+                )],
+            ),
+        )
+    }
+
+    fn transcript(turns: usize) -> Transcript {
+        let mut transcript = Transcript::new("Task: rename greet to welcome");
+        let (a, r) = turn(
+            "t0",
+            "read",
+            json!({"file_path": "src/lib.rs"}),
+            "fn greet() {}",
+        );
+        transcript.push(a, r);
+        let (a, r) = turn(
+            "t1",
+            "read",
+            json!({"file_path": "src/old.rs"}),
+            "// unused",
+        );
+        transcript.push(a, r);
+        for i in 2..turns {
+            let (a, r) = turn(
+                &format!("t{}", i),
+                "bash",
+                json!({"command": "cargo check"}),
+                "ok",
+            );
+            transcript.push(a, r);
+        }
+        transcript
+    }
+
+    #[test]
+    fn test_due_after_policy_turns() {
+        let policy = SummaryPolicy {
+            after_turns: 5,
+            keep_recent: 2,
+        };
+        assert!(!transcript(5).due(&policy));
+        assert!(transcript(6).due(&policy));
+        assert!(!transcript(50).due(&SummaryPolicy {
+            after_turns: 0,
+            keep_recent: 2
+        }));
+    }
+
+    #[test]
+    fn test_fold_keeps_recent_turns_and_referenced_reads() {
+        let mut transcript = transcript(6);
+        // A kept turn edits src/lib.rs, so its earlier read is still needed
+        let (a, r) = turn(
+            "t6",
+            "edit",
+            json!({"file_path": "src/lib.rs", "old_string": "greet", "new_string": "welcome"}),
+            "Edited src/lib.rs",
+        );
+        transcript.push(a, r);
+
+        let prompt = transcript.summary_prompt(2);
+        assert!(prompt.contains("Turn 1:\n→ read {\"file_path\":\"src/lib.rs\"}\n← fn greet() {}"));
+        assert!(!prompt.contains("Turn 6:"));
+
+        transcript.fold("Read both files; checks pass.".to_string(), 2);
+        assert_eq!(transcript.folded(), 5);
+
+        let messages = transcript.messages();
+        // The task message, then two kept turns
+        assert_eq!(messages.len(), 5);
+        let first = messages[0].text();
+        assert!(first.starts_with("Task: rename greet to welcome"));
+        assert!(first.contains("summary of the first 5 turns)\n\nRead both files; checks pass."));
+        assert!(first.contains("## src/lib.rs (as read earlier)\n\n```\nfn greet() {}\n```"));
+        assert!(!first.contains("src/old.rs"));
+        assert_eq!(messages[1].role, "assistant");
+    }
+}
@@ -1,21 +1,21 @@
 // AskUserBrainTool — lets the background brain ask the user a clarifying question
 // while they are still composing their query.
 
+use super::{NO_ANSWER, QUESTION_TIMEOUT};
 use crate::cli::repl_event::events::ReplEvent;
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolDefinition, ToolInputSchema};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 
 /// Tool that lets the brain ask the user a short clarifying question mid-typing.
 ///
-/// The question is displayed as a dialog in the TUI; the user's answer is
-/// returned as a string.  If the user doesn't answer within 30 s (or the brain
-/// session is cancelled) the tool returns `"[no answer]"` so the brain can
-/// continue gracefully.
+/// The question waits in the REPL's brain inbox until the user opens it; the
+/// user's answer is returned as a string.  If the user doesn't answer within
+/// [`QUESTION_TIMEOUT`] (or the brain session is cancelled) the tool returns
+/// [`NO_ANSWER`] so the brain can continue gracefully.
 pub struct AskUserBrainTool {
     event_tx: mpsc::UnboundedSender<ReplEvent>,
 }
@@ -35,7 +35,8 @@ impl Tool for AskUserBrainTool {
     fn description(&self) -> &str {
         "Ask the user a short clarifying question about their in-progress query. \
          Use this to disambiguate scope, preferred language, or key constraints \
-         before they hit Enter. Provide 2-4 short option strings when helpful."
+         before they hit Enter. Provide 2-4 short option strings when helpful. \
+         The user may not answer; on \"[no answer]\" continue with your best judgement."
     }
 
     fn input_schema(&self) -> ToolInputSchema {
@@ -73,7 +74,7 @@ impl Tool for AskUserBrainTool {
 
         let (response_tx, response_rx) = oneshot::channel();
 
-        // Send the question to the event loop — it will show a dialog.
+        // Send the question to the event loop — it queues it in the inbox.
         // If the channel is closed (event loop stopped), return no-answer.
         if self
             .event_tx
@@ -84,13 +85,13 @@ impl Tool for AskUserBrainTool {
             })
             .is_err()
         {
-            return Ok("[no answer]".to_string());
+            return Ok(NO_ANSWER.to_string());
         }
 
-        // Wait up to 30 s for the user to respond.
-        match tokio::time::timeout(Duration::from_secs(30), response_rx).await {
+        // Dropping the receiver on timeout tells the inbox to expire the question.
+        match tokio::time::timeout(QUESTION_TIMEOUT, response_rx).await {
             Ok(Ok(answer)) => Ok(answer),
-            _ => Ok("[no answer]".to_string()),
+            _ => Ok(NO_ANSWER.to_string()),
         }
     }
 
@@ -125,7 +126,7 @@ mod tests {
         //
         // We override the timeout to 0 ms by testing the channel-closed branch:
         // drop the receiver immediately so the send fails and we get "[no answer]"
-        // without waiting the full 30 s.
+        // without waiting out QUESTION_TIMEOUT.
         let (tx, _rx) = mpsc::unbounded_channel::<ReplEvent>();
         // Drop the receiver so the send fails immediately.
         drop(_rx);
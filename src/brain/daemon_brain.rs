@@ -16,19 +16,26 @@ use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::claude::types::{ContentBlock, Message};
+use crate::memory::MemorySystem;
 use crate::providers::{LlmProvider, ProviderRequest};
 use crate::server::brain_registry::{BrainRegistry, BrainState, PlanResponse};
+use crate::server::{WebhookEvent, Webhooks};
 use crate::tools::implementations::glob::GlobTool;
 use crate::tools::implementations::grep::GrepTool;
+use crate::tools::implementations::memory_tools::{
+    CreateMemoryTool, ListRecentTool, SearchMemoryTool,
+};
+use crate::tools::implementations::outline::OutlineTool;
 use crate::tools::implementations::read::ReadTool;
+use crate::tools::implementations::web_search::WebSearchTool;
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolDefinition, ToolInputSchema, ToolUse};
 
 /// Maximum turns the daemon brain may run (more than REPL brain — research is thorough).
 const DAEMON_BRAIN_MAX_TURNS: usize = 12;
 
-/// System prompt for the daemon brain.
-fn daemon_brain_system_prompt(task: &str, cwd: &str) -> String {
+/// System prompt for the daemon brain, listing the tools it was given.
+fn daemon_brain_system_prompt(task: &str, cwd: &str, tools: &[&str]) -> String {
     format!(
         "You are a background research agent running in the Finch daemon.\n\
          Task: {task}\n\n\
@@ -36,11 +43,12 @@ fn daemon_brain_system_prompt(task: &str, cwd: &str) -> String {
          information from the user, call ask_user_question. When you have a complete \
          plan ready, call present_plan — the user will approve, request changes, or \
          reject.\n\n\
-         Available tools: read, glob, grep, ask_user_question, present_plan.\n\
+         Available tools: {tools}.\n\
          Max turns: {max_turns}. Summarise findings in your plan.\n\
          Working directory: {cwd}",
         task = task,
         cwd = cwd,
+        tools = tools.join(", "),
         max_turns = DAEMON_BRAIN_MAX_TURNS,
     )
 }
@@ -48,22 +56,47 @@ fn daemon_brain_system_prompt(task: &str, cwd: &str) -> String {
 /// Run the daemon brain loop as a tokio task.
 ///
 /// Called by the spawn handler in `handlers.rs` after inserting the entry.
+/// With `memory` (a workspace's own namespace) the brain also gets the
+/// memory tools.
 pub async fn run_daemon_brain_loop(
     id: Uuid,
     task: String,
     registry: Arc<BrainRegistry>,
     provider: Arc<dyn LlmProvider>,
     cwd: String,
+    webhooks: Arc<Webhooks>,
+    memory: Option<Arc<MemorySystem>>,
 ) {
     info!("Daemon brain {} starting: {}", id, task);
-    match run_loop(id, &task, Arc::clone(&registry), provider.as_ref(), &cwd).await {
+    let result = run_loop(
+        id,
+        &task,
+        Arc::clone(&registry),
+        provider.as_ref(),
+        &cwd,
+        memory,
+    )
+    .await;
+    let event = match result {
         Ok(Some(summary)) => {
             info!("Daemon brain {} finished with summary ({} chars)", id, summary.len());
-            registry.set_completed_with_summary(id, summary).await;
+            registry
+                .set_completed_with_summary(id, summary.clone())
+                .await;
+            WebhookEvent::AgentTaskDone {
+                id: id.to_string(),
+                task,
+                summary: Some(summary),
+            }
         }
         Ok(None) => {
             info!("Daemon brain {} finished (plan path)", id);
             registry.set_dead(id).await;
+            WebhookEvent::AgentTaskDone {
+                id: id.to_string(),
+                task,
+                summary: None,
+            }
         }
         Err(e) => {
             warn!("Daemon brain {} error: {}", id, e);
@@ -71,8 +104,14 @@ pub async fn run_daemon_brain_loop(
                 .append_log(id, format!("[Error] {}", e))
                 .await;
             registry.set_dead(id).await;
+            WebhookEvent::AgentTaskFailed {
+                id: id.to_string(),
+                task,
+                error: e.to_string(),
+            }
         }
-    }
+    };
+    webhooks.emit(event);
 }
 
 async fn run_loop(
@@ -81,14 +120,15 @@ async fn run_loop(
     registry: Arc<BrainRegistry>,
     provider: &dyn LlmProvider,
     cwd: &str,
+    memory: Option<Arc<MemorySystem>>,
 ) -> Result<Option<String>> {
-    let system = daemon_brain_system_prompt(task, cwd);
-
-    // Build tool set: read/glob/grep + ask_user_question + present_plan
-    let tools: Vec<Box<dyn Tool>> = vec![
+    // Build tool set: read/glob/grep/outline/web_search + ask_user_question + present_plan
+    let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(ReadTool),
         Box::new(GlobTool),
         Box::new(GrepTool),
+        Box::new(OutlineTool),
+        Box::new(WebSearchTool::new()),
         Box::new(DaemonAskUserTool {
             brain_id: id,
             registry: Arc::clone(&registry),
@@ -98,7 +138,14 @@ async fn run_loop(
             registry: Arc::clone(&registry),
         }),
     ];
+    if let Some(memory) = memory {
+        tools.push(Box::new(SearchMemoryTool::new(Arc::clone(&memory))));
+        tools.push(Box::new(CreateMemoryTool::new(Arc::clone(&memory))));
+        tools.push(Box::new(ListRecentTool::new(memory)));
+    }
     let tool_defs: Vec<ToolDefinition> = tools.iter().map(|t| t.definition()).collect();
+    let tool_names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+    let system = daemon_brain_system_prompt(task, cwd, &tool_names);
 
     let mut messages: Vec<Message> = vec![Message::user(task)];
 
@@ -203,7 +250,7 @@ async fn execute_daemon_brain_tool(
         poset: None,
     };
 
-    match tool.execute(tool_use.input.clone(), &context).await {
+    match tool.execute_call(tool_use, &context).await {
         Ok(s) => (s, false),
         Err(e) => (format!("Error: {}", e), true),
     }
@@ -266,10 +313,16 @@ impl Tool for DaemonAskUserTool {
             .set_waiting_for_input(self.brain_id, question.clone(), options, tx)
             .await;
 
-        // Block until the REPL posts an answer (or brain is cancelled).
-        match rx.await {
-            Ok(answer) => Ok(answer),
-            Err(_) => anyhow::bail!("Brain was cancelled while waiting for answer"),
+        // Block until the REPL posts an answer (or brain is cancelled), and
+        // carry on without one if nobody answers in time.
+        match tokio::time::timeout(super::QUESTION_TIMEOUT, rx).await {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(_)) => anyhow::bail!("Brain was cancelled while waiting for answer"),
+            Err(_) => {
+                info!("Brain {} question went unanswered", self.brain_id);
+                self.registry.expire_question(self.brain_id).await;
+                Ok(super::NO_ANSWER.to_string())
+            }
         }
     }
 }
@@ -369,9 +422,14 @@ mod tests {
 
     #[test]
     fn test_daemon_brain_system_prompt_includes_task_and_cwd() {
-        let prompt = daemon_brain_system_prompt("investigate cargo slowness", "/Users/test");
+        let prompt = daemon_brain_system_prompt(
+            "investigate cargo slowness",
+            "/Users/test",
+            &["read", "search_memory"],
+        );
         assert!(prompt.contains("investigate cargo slowness"));
         assert!(prompt.contains("/Users/test"));
+        assert!(prompt.contains("Available tools: read, search_memory."));
     }
 
     #[tokio::test]
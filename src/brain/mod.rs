@@ -5,7 +5,8 @@
 // clarifying question — so that by the time they hit Enter the brain already
 // has pre-gathered context ready to be injected into the real query.
 //
-// Tool set: read, glob, grep, ask_user_question (no bash, no web_fetch).
+// Tool set: read, glob, grep, outline, web_search, ask_user_question (no bash, no
+// web_fetch).
 // Max turns: 6 (3-4 tool calls + summary reply).
 
 mod action;
@@ -20,7 +21,9 @@ use crate::memory::MemorySystem;
 use crate::providers::{LlmProvider, ProviderRequest};
 use crate::tools::implementations::glob::GlobTool;
 use crate::tools::implementations::grep::GrepTool;
+use crate::tools::implementations::outline::OutlineTool;
 use crate::tools::implementations::read::ReadTool;
+use crate::tools::implementations::web_search::WebSearchTool;
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolDefinition, ToolUse};
 use anyhow::Result;
@@ -34,6 +37,14 @@ use uuid::Uuid;
 /// Maximum turns the brain may run.  8 allows 4-5 tool calls (inc. action round-trips) + summary.
 const BRAIN_MAX_TURNS: usize = 8;
 
+/// How long a brain waits for the user to answer a question before carrying
+/// on without one.  Questions wait in the REPL's inbox rather than
+/// interrupting, so the user may take a while to get to them.
+pub const QUESTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// What `ask_user_question` returns when the question goes unanswered
+pub const NO_ANSWER: &str = "[no answer]";
+
 /// A running background brain session.
 ///
 /// Drop or call [`cancel`] to stop the brain immediately.
@@ -95,12 +106,11 @@ impl BrainSession {
                                 let summary_clone = summary.clone();
                                 tokio::spawn(async move {
                                     let daemon_addr = crate::config::constants::DEFAULT_HTTP_ADDR;
-                                    let _ = reqwest::Client::new()
-                                        .post(format!("http://{daemon_addr}/v1/brains/shared/shared"))
-                                        .json(&serde_json::json!({ "context": summary_clone }))
-                                        .timeout(std::time::Duration::from_millis(400))
-                                        .send()
-                                        .await;
+                                    let timeout = std::time::Duration::from_millis(400);
+                                    if let Ok(daemon) = crate::client::HttpTransport::new(daemon_addr, timeout) {
+                                        let body = serde_json::json!({ "context": summary_clone });
+                                        let _ = daemon.post_json("/v1/brains/shared/shared", &body, None).await;
+                                    }
                                 });
                                 *brain_context.write().await = Some(summary);
                             }
@@ -152,7 +162,9 @@ fn brain_system_prompt(cwd: &str) -> String {
          If you discover something that needs attention (failing tests, missing deps, \
          broken builds), you may propose a command via the run_command tool — the user \
          will be asked to approve before it runs.\n\
-         Available tools: read, glob, grep, ask_user_question, run_command.\n\
+         Use web_search only for facts the codebase can't tell you, such as the \
+         latest version of a dependency.\n\
+         Available tools: read, glob, grep, outline, web_search, ask_user_question, run_command.\n\
          Stop after 3-6 tool calls. Summarise your findings concisely (200-400 words), \
          including any command output if a command was approved and run.\n\
          Working directory: {cwd}",
@@ -174,6 +186,8 @@ async fn run_brain_loop(
         Box::new(ReadTool),
         Box::new(GlobTool),
         Box::new(GrepTool),
+        Box::new(OutlineTool),
+        Box::new(WebSearchTool::new()),
         Box::new(AskUserBrainTool::new(event_tx.clone())),
         Box::new(BrainActionTool::new(event_tx)),
     ];
@@ -274,7 +288,7 @@ async fn execute_brain_tool(tools: &[Box<dyn Tool>], tool_use: &ToolUse) -> Resu
         poset: None,
     };
 
-    tool.execute(tool_use.input.clone(), &context).await
+    tool.execute_call(tool_use, &context).await
 }
 
 // ---------------------------------------------------------------------------
@@ -287,12 +301,13 @@ mod tests {
 
     #[test]
     fn test_brain_tools_are_read_only() {
-        // The brain has exactly read, glob, grep, ask_user_question — no bash.
+        // The brain has exactly read, glob, grep, web_search, ask_user_question — no bash.
         let (tx, _rx) = mpsc::unbounded_channel::<ReplEvent>();
         let tools: Vec<Box<dyn Tool>> = vec![
             Box::new(ReadTool),
             Box::new(GlobTool),
             Box::new(GrepTool),
+            Box::new(WebSearchTool::new()),
             Box::new(AskUserBrainTool::new(tx)),
         ];
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
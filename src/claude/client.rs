@@ -36,8 +36,13 @@ impl ClaudeClient {
         self.provider.name()
     }
 
+    /// The provider requests are sent to
+    pub fn provider(&self) -> &std::sync::Arc<dyn LlmProvider> {
+        &self.provider
+    }
+
     /// Convert MessageRequest to ProviderRequest
-    fn to_provider_request(&self, request: &MessageRequest) -> ProviderRequest {
+    pub fn to_provider_request(&self, request: &MessageRequest) -> ProviderRequest {
         let mut provider_req = ProviderRequest::new(request.messages.clone())
             .with_model(request.model.clone())
             .with_max_tokens(request.max_tokens);
@@ -1,9 +1,17 @@
 // Retry logic with exponential backoff
+//
+// Rate-limited attempts (`RateLimited` errors) wait until exactly the
+// provider's reset instead, as long as that is within
+// `RATE_LIMIT_MAX_WAIT_SECS` and the caller hasn't opted out with
+// `rate_limit::without_waiting`.
 
 use anyhow::Result;
 use std::time::Duration;
 use tokio::time::sleep;
 
+use crate::config::constants::RATE_LIMIT_MAX_WAIT_SECS;
+use crate::providers::rate_limit::{self, RateLimited};
+
 const MAX_RETRIES: u32 = 3;
 const BASE_DELAY_MS: u64 = 1000;
 
@@ -19,6 +27,20 @@ where
         match f().await {
             Ok(result) => return Ok(result),
             Err(e) => {
+                if let Some(limited) = e.downcast_ref::<RateLimited>() {
+                    let wait = limited.retry_after;
+                    if attempt == MAX_RETRIES - 1
+                        || !rate_limit::waiting_allowed()
+                        || wait > Duration::from_secs(RATE_LIMIT_MAX_WAIT_SECS)
+                    {
+                        return Err(e);
+                    }
+                    let provider = limited.provider.clone();
+                    rate_limit::wait_for_reset(&provider, wait, rate_limit::wait_status()).await;
+                    last_error = Some(e);
+                    continue;
+                }
+
                 last_error = Some(e);
 
                 if attempt < MAX_RETRIES - 1 {
@@ -121,6 +143,59 @@ mod tests {
         );
     }
 
+    fn rate_limited(secs: u64) -> anyhow::Error {
+        RateLimited {
+            provider: "retry-test".to_string(),
+            retry_after: Duration::from_secs(secs),
+            message: "API error 429 Too Many Requests".to_string(),
+        }
+        .into()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_waits_for_reset() {
+        let call_count = Arc::new(AtomicU32::new(0));
+        let cc = Arc::clone(&call_count);
+        let start = tokio::time::Instant::now();
+
+        let result = with_retry(|| {
+            let cc = Arc::clone(&cc);
+            async move {
+                if cc.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(rate_limited(30))
+                } else {
+                    Ok(1)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 1);
+        // Exactly the reset, not the 1s exponential backoff
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_fails_fast_when_waiting_is_off_or_too_long() {
+        let call_count = Arc::new(AtomicU32::new(0));
+        let attempt = |secs| {
+            let cc = Arc::clone(&call_count);
+            move || {
+                let cc = Arc::clone(&cc);
+                async move {
+                    cc.fetch_add(1, Ordering::SeqCst);
+                    Err::<(), _>(rate_limited(secs))
+                }
+            }
+        };
+
+        let result = rate_limit::without_waiting(with_retry(attempt(5))).await;
+        assert!(result.unwrap_err().is::<RateLimited>());
+        let result = with_retry(attempt(RATE_LIMIT_MAX_WAIT_SECS + 1)).await;
+        assert!(result.is_err());
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn test_first_try_success_returns_ok_not_err() {
         // Verify the happy path produces Ok, not Err
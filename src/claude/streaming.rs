@@ -22,6 +22,8 @@ pub struct ContentBlock {
     pub id: Option<String>,
     #[serde(default)]
     pub name: Option<String>,
+    #[serde(default)]
+    pub data: Option<String>, // For redacted_thinking
 }
 
 /// Delta within a streaming event
@@ -32,6 +34,10 @@ pub struct StreamDelta {
     pub text: Option<String>,
     #[serde(default)]
     pub partial_json: Option<String>, // For input_json_delta
+    #[serde(default)]
+    pub thinking: Option<String>, // For thinking_delta
+    #[serde(default)]
+    pub signature: Option<String>, // For signature_delta
 }
 
 impl StreamEvent {
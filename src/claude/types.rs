@@ -8,7 +8,7 @@ pub use crate::tools::types::ToolDefinition;
 
 use crate::config::constants::{DEFAULT_CLAUDE_MODEL, DEFAULT_MAX_TOKENS};
 
-/// Content block - supports text, image, tool_use, and tool_result
+/// Content block - supports text, image, tool_use, tool_result and thinking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ContentBlock {
@@ -33,6 +33,20 @@ pub enum ContentBlock {
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
     },
+
+    /// A reasoning model's chain-of-thought.  Never part of the text; kept
+    /// only so Claude gets it back with the tool calls it led to.
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        /// Claude's proof that it wrote the thinking; empty from other providers
+        #[serde(default)]
+        signature: String,
+    },
+
+    /// Thinking Claude returned encrypted
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
 }
 
 /// Source for an image content block
@@ -55,6 +69,14 @@ impl ContentBlock {
         matches!(self, ContentBlock::ToolUse { .. })
     }
 
+    /// Check if this is a (possibly redacted) thinking block
+    pub fn is_thinking(&self) -> bool {
+        matches!(
+            self,
+            ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. }
+        )
+    }
+
     /// Extract text from text block
     pub fn as_text(&self) -> Option<&str> {
         match self {
@@ -0,0 +1,397 @@
+// Activity dashboard — `finch tui --attach ADDR`
+//
+// A read-only view of what a daemon (usually a worker node elsewhere on the
+// LAN) is doing, fed by its `GET /v1/events` stream: requests as they are
+// answered, how they were routed, local generation throughput, and training
+// and other daemon events.  The connection is retried every couple of seconds,
+// so the dashboard survives the daemon restarting.
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::client::{DaemonClient, DaemonConfig};
+use crate::server::{Activity, RequestActivity};
+
+/// Feed lines kept for display
+const FEED_LINES: usize = 500;
+/// Generations averaged for the throughput figure
+const THROUGHPUT_WINDOW: usize = 20;
+/// Wait between attempts to (re)connect
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// What the watcher task reports to the dashboard
+enum Update {
+    Connected,
+    Disconnected(String),
+    Activity(Activity),
+}
+
+/// Totals and recent history built from the activity feed
+#[derive(Debug, Default)]
+struct Dashboard {
+    /// `None` until the first connection attempt finishes
+    connected: Option<bool>,
+    /// Why the last connection attempt or stream failed
+    problem: Option<String>,
+    requests: u64,
+    worker_requests: u64,
+    errors: u64,
+    /// Requests per routing decision
+    decisions: BTreeMap<String, u64>,
+    /// Most recent output tokens/s, newest last
+    throughput: VecDeque<f64>,
+    training_batches: u64,
+    feed: VecDeque<Line<'static>>,
+}
+
+impl Dashboard {
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Connected => {
+                self.connected = Some(true);
+                self.problem = None;
+            }
+            Update::Disconnected(problem) => {
+                self.connected = Some(false);
+                self.problem = Some(problem);
+            }
+            Update::Activity(Activity::Request(request)) => self.record_request(request),
+            Update::Activity(Activity::Event {
+                timestamp,
+                name,
+                text,
+            }) => {
+                if name == "training.batch_complete" {
+                    self.training_batches += 1;
+                }
+                self.push_line(Line::from(vec![
+                    Span::styled(
+                        timestamp.format("%H:%M:%S ").to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{} ", name), Style::default().fg(Color::Magenta)),
+                    Span::raw(text),
+                ]));
+            }
+        }
+    }
+
+    fn record_request(&mut self, request: RequestActivity) {
+        self.requests += 1;
+        if request.worker {
+            self.worker_requests += 1;
+        }
+        if request.status >= 400 {
+            self.errors += 1;
+        }
+        if let Some(decision) = &request.decision {
+            *self.decisions.entry(decision.clone()).or_default() += 1;
+        }
+        let tokens_per_sec = request.tokens_per_sec();
+        if let Some(rate) = tokens_per_sec {
+            if self.throughput.len() == THROUGHPUT_WINDOW {
+                self.throughput.pop_front();
+            }
+            self.throughput.push_back(rate);
+        }
+
+        let status_color = if request.status >= 400 {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        let mut spans = vec![
+            Span::styled(
+                request.timestamp.format("%H:%M:%S ").to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("{} ", request.status),
+                Style::default().fg(status_color),
+            ),
+            Span::raw(format!("{} {} ", request.method, request.route)),
+            Span::styled(
+                format!("{}ms", request.latency_ms),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if let Some(decision) = &request.decision {
+            spans.push(Span::styled(
+                format!(" {}", decision),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if let Some(provider) = request
+            .provider
+            .filter(|p| Some(p) != request.decision.as_ref())
+        {
+            spans.push(Span::raw(format!(" via {}", provider)));
+        }
+        if let Some(tokens) = request.output_tokens {
+            spans.push(Span::raw(format!(" {} tok", tokens)));
+        }
+        if let Some(rate) = tokens_per_sec {
+            spans.push(Span::raw(format!(" ({:.1} tok/s)", rate)));
+        }
+        if request.worker {
+            spans.push(Span::styled(
+                " [worker]",
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        self.push_line(Line::from(spans));
+    }
+
+    fn push_line(&mut self, line: Line<'static>) {
+        if self.feed.len() == FEED_LINES {
+            self.feed.pop_front();
+        }
+        self.feed.push_back(line);
+    }
+
+    /// Mean output tokens/s over the recent generations
+    fn average_throughput(&self) -> Option<f64> {
+        (!self.throughput.is_empty())
+            .then(|| self.throughput.iter().sum::<f64>() / self.throughput.len() as f64)
+    }
+
+    fn render(&self, frame: &mut Frame, address: &str) {
+        let [header, stats, feed, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(6),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let (state, color) = match self.connected {
+            None => ("connecting…".to_string(), Color::Yellow),
+            Some(true) => ("connected".to_string(), Color::Green),
+            Some(false) => (
+                format!(
+                    "reconnecting ({})",
+                    self.problem.as_deref().unwrap_or("disconnected")
+                ),
+                Color::Red,
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("finch ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{} — ", address)),
+                Span::styled(state, Style::default().fg(color)),
+            ])),
+            header,
+        );
+
+        let routing = if self.decisions.is_empty() {
+            "—".to_string()
+        } else {
+            self.decisions
+                .iter()
+                .map(|(decision, count)| format!("{} {}", decision, count))
+                .collect::<Vec<_>>()
+                .join(" · ")
+        };
+        let throughput = match (self.average_throughput(), self.throughput.back()) {
+            (Some(average), Some(last)) => format!(
+                "{:.1} tok/s (last {:.1}, {} generations)",
+                average,
+                last,
+                self.throughput.len()
+            ),
+            _ => "—".to_string(),
+        };
+        let stats_text = vec![
+            Line::from(format!(
+                "Requests    {} ({} over the worker listener, {} errors)",
+                self.requests, self.worker_requests, self.errors
+            )),
+            Line::from(format!("Routing     {}", routing)),
+            Line::from(format!("Throughput  {}", throughput)),
+            Line::from(format!(
+                "Training    {} batches queued",
+                self.training_batches
+            )),
+        ];
+        frame.render_widget(
+            Paragraph::new(stats_text)
+                .block(Block::default().borders(Borders::ALL).title(" Totals ")),
+            stats,
+        );
+
+        // Newest at the bottom, like a log
+        let rows = feed.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = self
+            .feed
+            .iter()
+            .skip(self.feed.len().saturating_sub(rows))
+            .cloned()
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Activity ")),
+            feed,
+        );
+
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "read-only · q to quit",
+                Style::default().fg(Color::DarkGray),
+            )),
+            footer,
+        );
+    }
+}
+
+/// Show the activity of the daemon at `address` until the user quits
+pub async fn run(address: String) -> Result<()> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let watcher = tokio::spawn(watch(address.clone(), tx));
+    let ui = tokio::task::spawn_blocking(move || show(&address, rx)).await;
+    watcher.abort();
+    ui?
+}
+
+/// Follow the daemon's feed, reconnecting whenever it drops, until the
+/// dashboard goes away
+async fn watch(address: String, tx: mpsc::UnboundedSender<Update>) {
+    loop {
+        let config = DaemonConfig {
+            bind_address: address.clone(),
+            auto_spawn: false,
+            ..DaemonConfig::default()
+        };
+        let problem = match DaemonClient::connect(config).await {
+            Ok(client) => {
+                if tx.send(Update::Connected).is_err() {
+                    return;
+                }
+                let feed = tx.clone();
+                match client
+                    .watch_activity(move |activity| {
+                        let _ = feed.send(Update::Activity(activity));
+                    })
+                    .await
+                {
+                    Ok(()) => "the daemon closed the stream".to_string(),
+                    Err(e) => format!("{:#}", e),
+                }
+            }
+            Err(e) => format!("{:#}", e),
+        };
+        if tx.send(Update::Disconnected(problem)).is_err() {
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Draw the dashboard until q, Esc or Ctrl+C
+fn show(address: &str, mut rx: mpsc::UnboundedReceiver<Update>) -> Result<()> {
+    crossterm::terminal::enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
+    let backend = ratatui::backend::CrosstermBackend::new(stdout);
+    let mut terminal = ratatui::Terminal::new(backend)?;
+
+    let mut dashboard = Dashboard::default();
+    let result = (|| -> Result<()> {
+        loop {
+            while let Ok(update) = rx.try_recv() {
+                dashboard.apply(update);
+            }
+            terminal.draw(|frame| dashboard.render(frame, address))?;
+
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    let ctrl_c = key.modifiers.contains(KeyModifiers::CONTROL)
+                        && key.code == KeyCode::Char('c');
+                    if key.kind == KeyEventKind::Press
+                        && (ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc))
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+
+    // Restore the terminal whatever happened
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
+    terminal.show_cursor()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn request(status: u16, decision: Option<&str>, output_tokens: Option<u32>) -> Activity {
+        Activity::Request(RequestActivity {
+            timestamp: Utc::now(),
+            method: "POST".to_string(),
+            route: "/v1/chat/completions".to_string(),
+            status,
+            latency_ms: 1000,
+            worker: decision == Some("local"),
+            provider: decision.map(str::to_string),
+            decision: decision.map(str::to_string),
+            input_tokens: None,
+            output_tokens,
+        })
+    }
+
+    #[test]
+    fn test_dashboard_totals() {
+        let mut dashboard = Dashboard::default();
+        dashboard.apply(Update::Connected);
+        dashboard.apply(Update::Activity(request(200, Some("local"), Some(40))));
+        dashboard.apply(Update::Activity(request(200, Some("local"), Some(20))));
+        dashboard.apply(Update::Activity(request(200, Some("forward"), None)));
+        dashboard.apply(Update::Activity(request(503, None, None)));
+        dashboard.apply(Update::Activity(Activity::Event {
+            timestamp: Utc::now(),
+            name: "training.batch_complete".to_string(),
+            text: "finch queued a LoRA training batch of 8 examples".to_string(),
+        }));
+
+        assert_eq!(dashboard.requests, 4);
+        assert_eq!(dashboard.worker_requests, 2);
+        assert_eq!(dashboard.errors, 1);
+        assert_eq!(dashboard.decisions.get("local"), Some(&2));
+        assert_eq!(dashboard.decisions.get("forward"), Some(&1));
+        assert_eq!(dashboard.average_throughput(), Some(30.0));
+        assert_eq!(dashboard.training_batches, 1);
+        assert_eq!(dashboard.feed.len(), 5);
+
+        dashboard.apply(Update::Disconnected("connection refused".to_string()));
+        assert_eq!(dashboard.connected, Some(false));
+        assert_eq!(dashboard.requests, 4, "totals survive a reconnect");
+    }
+
+    #[test]
+    fn test_dashboard_keeps_recent_history() {
+        let mut dashboard = Dashboard::default();
+        for _ in 0..FEED_LINES + 10 {
+            dashboard.apply(Update::Activity(request(200, Some("local"), Some(10))));
+        }
+        assert_eq!(dashboard.feed.len(), FEED_LINES);
+        assert_eq!(dashboard.throughput.len(), THROUGHPUT_WINDOW);
+    }
+}
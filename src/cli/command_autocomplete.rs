@@ -98,6 +98,36 @@ impl CommandRegistry {
                     description: "Clear history but keep a summary in context. Optional: /compact [instruction...]",
                     category: CommandCategory::Basic,
                 },
+                CommandSpec {
+                    name: "/context",
+                    params: None,
+                    description: "Show what fills the context window",
+                    category: CommandCategory::Basic,
+                },
+                CommandSpec {
+                    name: "/undo",
+                    params: None,
+                    description: "Revert the last file change made by a tool",
+                    category: CommandCategory::Basic,
+                },
+                CommandSpec {
+                    name: "/rollback",
+                    params: Some("[n]"),
+                    description: "Revert the last n tool file changes (no n: list them)",
+                    category: CommandCategory::Basic,
+                },
+                CommandSpec {
+                    name: "/paste",
+                    params: Some("[question]"),
+                    description: "Push the clipboard text onto the stack, or ask about it now",
+                    category: CommandCategory::Basic,
+                },
+                CommandSpec {
+                    name: "/promote",
+                    params: Some("[ids]"),
+                    description: "Move todos to the agent backlog for `finch agent`",
+                    category: CommandCategory::Basic,
+                },
                 CommandSpec {
                     name: "/debug",
                     params: None,
@@ -136,6 +166,12 @@ impl CommandRegistry {
                     description: "Switch to a specific teacher (e.g., /model grok)",
                     category: CommandCategory::Model,
                 },
+                CommandSpec {
+                    name: "/model reload",
+                    params: Some("[size] [repo]"),
+                    description: "Reload the daemon's local model, e.g. at another size",
+                    category: CommandCategory::Model,
+                },
                 CommandSpec {
                     name: "/teacher",
                     params: None,
@@ -156,6 +192,30 @@ impl CommandRegistry {
                     description: "Show memory usage (system and process)",
                     category: CommandCategory::Memory,
                 },
+                CommandSpec {
+                    name: "/memory stats",
+                    params: None,
+                    description: "Show what finch remembers",
+                    category: CommandCategory::Memory,
+                },
+                CommandSpec {
+                    name: "/memory search",
+                    params: Some("<query>"),
+                    description: "Show the memories a query recalls, with their ids",
+                    category: CommandCategory::Memory,
+                },
+                CommandSpec {
+                    name: "/memory pin",
+                    params: Some("<id>"),
+                    description: "Mark a memory Critical so it is never forgotten",
+                    category: CommandCategory::Memory,
+                },
+                CommandSpec {
+                    name: "/memory forget",
+                    params: Some("<id>"),
+                    description: "Stop recalling a memory and remove it",
+                    category: CommandCategory::Memory,
+                },
 
                 // MCP Plugin Commands
                 CommandSpec {
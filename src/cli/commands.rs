@@ -17,11 +17,21 @@ pub enum Command {
     Help,
     Quit,
     Metrics,
-    Memory,
+    Memory,               // /memory — process and system memory usage
+    MemoryStats,          // /memory stats — what the memory system holds
+    MemorySearch(String), // /memory search <query> [filters]
+    MemoryPin(String),    // /memory pin <id>
+    MemoryForget(String), // /memory forget <id>
     Debug,
     Training,
     Clear,
     Compact(Option<String>), // Clear with summary (optional instruction)
+    Context,                 // Show what fills the context window
+    Undo,                    // Revert the last file change made by a tool
+    Rollback(Option<usize>), // Revert the last N tool file changes (None lists them)
+    Incognito,               // Toggle incognito: no memory, logs or training
+    Promote(Vec<String>),    // Move todos (all open ones when empty) to the agent backlog
+    Paste(Option<String>),   // Clipboard text onto the stack, or asked about with a question
     PatternsList,
     PatternsRemove(String),
     PatternsClear,
@@ -48,6 +58,8 @@ pub enum Command {
     ModelList,           // /provider list
     ModelSwitch(String), // /provider <name>  e.g. /provider grok
     ModelShow,           // /provider  (show current active provider)
+    // /model reload [size] [repo]: load the daemon's local model again
+    ModelReload(crate::server::ModelReloadRequest),
     // Service discovery (Phase 3)
     Discover,  // Discover Finch daemons on local network
     Machines,  // List known peer machines (from LAN discovery)
@@ -99,10 +111,17 @@ impl Command {
             "/quit" | "/exit" => return Some(Command::Quit),
             "/metrics" => return Some(Command::Metrics),
             "/memory" => return Some(Command::Memory),
+            "/memory stats" => return Some(Command::MemoryStats),
             "/debug" => return Some(Command::Debug),
             "/training" => return Some(Command::Training),
             "/clear" | "/reset" => return Some(Command::Clear),
             "/compact" => return Some(Command::Compact(None)),
+            "/context" => return Some(Command::Context),
+            "/undo" => return Some(Command::Undo),
+            "/incognito" => return Some(Command::Incognito),
+            "/rollback" => return Some(Command::Rollback(None)),
+            "/promote" => return Some(Command::Promote(Vec::new())),
+            "/paste" => return Some(Command::Paste(None)),
             // Feedback commands (simple form)
             "/critical" => return Some(Command::FeedbackCritical(None)),
             "/medium" => return Some(Command::FeedbackMedium(None)),
@@ -301,6 +320,14 @@ impl Command {
             }
         }
 
+        // Handle /model reload [size] [repo] (before /model <name> takes it)
+        if let Some(rest) = trimmed
+            .strip_prefix("/model reload")
+            .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            return Some(Command::ModelReload(parse_model_reload(rest)));
+        }
+
         // Handle /provider <name> (canonical), /model <name>, /teacher <name> (aliases)
         if let Some(rest) = trimmed
             .strip_prefix("/provider ")
@@ -386,6 +413,45 @@ impl Command {
             }));
         }
 
+        // Handle /rollback <n>
+        if let Some(rest) = trimmed.strip_prefix("/rollback ") {
+            if let Ok(n) = rest.trim().parse::<usize>() {
+                if n > 0 {
+                    return Some(Command::Rollback(Some(n)));
+                }
+            }
+        }
+
+        // Handle /memory search|pin|forget <arg>
+        if let Some(rest) = trimmed.strip_prefix("/memory ") {
+            let (sub, arg) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+            let arg = arg.trim().to_string();
+            if !arg.is_empty() {
+                match sub {
+                    "search" => return Some(Command::MemorySearch(arg)),
+                    "pin" => return Some(Command::MemoryPin(arg)),
+                    "forget" => return Some(Command::MemoryForget(arg)),
+                    _ => {}
+                }
+            }
+        }
+
+        // Handle /paste <question>
+        if let Some(rest) = trimmed.strip_prefix("/paste ") {
+            let question = rest.trim();
+            if !question.is_empty() {
+                return Some(Command::Paste(Some(question.to_string())));
+            }
+        }
+
+        // Handle /promote <todo id>...
+        if let Some(rest) = trimmed.strip_prefix("/promote ") {
+            let ids: Vec<String> = rest.split_whitespace().map(String::from).collect();
+            if !ids.is_empty() {
+                return Some(Command::Promote(ids));
+            }
+        }
+
         // Handle /local command with query
         if let Some(rest) = trimmed.strip_prefix("/local ") {
             let query = rest.trim();
@@ -495,8 +561,36 @@ pub fn handle_command(
         Command::Local { .. } => Ok(CommandOutput::Status(
             "Local command should be handled in REPL.".to_string(),
         )),
+        // Context command is handled directly in REPL
+        Command::Context => Ok(CommandOutput::Status(
+            "Context command should be handled in REPL.".to_string(),
+        )),
+        // Model reload is handled directly in REPL
+        Command::ModelReload(_) => Ok(CommandOutput::Status(
+            "Model reload should be handled in REPL.".to_string(),
+        )),
+        // Undo commands are handled directly in REPL
+        Command::Undo | Command::Rollback(_) => Ok(CommandOutput::Status(
+            "Undo commands should be handled in REPL.".to_string(),
+        )),
+        // Incognito is handled directly in REPL
+        Command::Incognito => Ok(CommandOutput::Status(
+            "Incognito command should be handled in REPL.".to_string(),
+        )),
+        // Promote needs the session todo list, held by the REPL
+        Command::Promote(_) => Ok(CommandOutput::Status(
+            "Promote command should be handled in REPL.".to_string(),
+        )),
+        // Paste feeds the REPL's stack or query
+        Command::Paste(_) => Ok(CommandOutput::Status(
+            "Paste command should be handled in REPL.".to_string(),
+        )),
         // Memory command is handled directly in REPL
-        Command::Memory => Ok(CommandOutput::Status(
+        Command::Memory
+        | Command::MemoryStats
+        | Command::MemorySearch(_)
+        | Command::MemoryPin(_)
+        | Command::MemoryForget(_) => Ok(CommandOutput::Status(
             "Memory command should be handled in REPL.".to_string(),
         )),
         // MCP commands are handled directly in REPL
@@ -565,6 +659,25 @@ pub fn handle_command(
 }
 
 /// Parse "W3" or "3" into a node id (usize).
+/// Arguments of `/model reload`: a size (small, medium, large, xlarge) and
+/// a repository, in any order.  "default" is the family's default repository.
+fn parse_model_reload(args: &str) -> crate::server::ModelReloadRequest {
+    use crate::models::ModelSize;
+
+    let mut request = crate::server::ModelReloadRequest::default();
+    for arg in args.split_whitespace() {
+        match arg.to_ascii_lowercase().as_str() {
+            "small" => request.model_size = Some(ModelSize::Small),
+            "medium" => request.model_size = Some(ModelSize::Medium),
+            "large" => request.model_size = Some(ModelSize::Large),
+            "xlarge" => request.model_size = Some(ModelSize::XLarge),
+            "default" => request.model_repo = Some(String::new()),
+            _ => request.model_repo = Some(arg.to_string()),
+        }
+    }
+    request
+}
+
 fn parse_word_id(s: &str) -> Option<usize> {
     let s = s.trim();
     let digits = s.strip_prefix('W').or_else(|| s.strip_prefix('w')).unwrap_or(s);
@@ -580,9 +693,20 @@ pub fn format_help() -> String {
          \x1b[36m  /quit\x1b[0m              Exit the REPL (also: Ctrl+D)\n\
          \x1b[36m  /clear\x1b[0m             Clear conversation history and free up context\n\
          \x1b[36m  /compact [note]\x1b[0m    Clear history but keep a summary in context\n\
+         \x1b[36m  /context\x1b[0m           Show what fills the context window\n\
+         \x1b[36m  /undo\x1b[0m              Revert the last file change made by a tool\n\
+         \x1b[36m  /rollback [n]\x1b[0m      Revert the last n tool file changes (no n: list them)\n\
+         \x1b[36m  /incognito\x1b[0m         Stop (or resume) memory, logs and training for this session\n\
+         \x1b[36m  /promote [ids]\x1b[0m     Move todos to the agent backlog (tasks.toml) for `finch agent`\n\
+         \x1b[36m  /paste [question]\x1b[0m  Push the clipboard text onto the stack, or ask about it now\n\
          \x1b[36m  /debug\x1b[0m             Toggle debug output\n\
          \x1b[36m  /metrics\x1b[0m           Display usage statistics\n\
          \x1b[36m  /memory\x1b[0m            Show memory usage (system and process)\n\
+         \x1b[36m  /memory stats\x1b[0m      Show what finch remembers: counts, store, recent items\n\
+         \x1b[36m  /memory search <q>\x1b[0m Show the memories a query recalls, with their ids\n\
+         \x1b[90m                       filter with session: role: project: after: before: importance:\x1b[0m\n\
+         \x1b[36m  /memory pin <id>\x1b[0m   Mark a memory Critical: ranked first, never forgotten\n\
+         \x1b[36m  /memory forget <id>\x1b[0m Stop recalling a memory and remove it\n\
          \x1b[36m  /training\x1b[0m          Show detailed training statistics\n\n\
          \x1b[1;33m🤖 Provider Commands:\x1b[0m\n\
          \x1b[36m  /provider\x1b[0m          Show current active provider\n\
@@ -591,6 +715,8 @@ pub fn format_help() -> String {
          \x1b[0m                     Example: /provider grok\n\
          \x1b[36m  /local <query>\x1b[0m     Query local ONNX model directly (bypass routing)\n\
          \x1b[0m                     Example: /local What is 2+2?\n\
+         \x1b[36m  /model reload [size] [repo]\x1b[0m Reload the daemon's local model\n\
+         \x1b[0m                     Example: /model reload large\n\
          \x1b[0m\n\
          \x1b[90m  Aliases: /model and /teacher also work (kept for compatibility)\x1b[0m\n\
          \x1b[90m  Switch between Claude, Grok, GPT-4, local ONNX, etc.\x1b[0m\n\
@@ -649,6 +775,7 @@ pub fn format_help() -> String {
          \x1b[36m  Ctrl+B\x1b[0m             Mark last response as \x1b[31mbad\x1b[0m (10x training weight)\n\
          \x1b[36m  Ctrl+Z\x1b[0m             Undo last Forth definition (/undefine)\n\
          \x1b[36m  Ctrl+P\x1b[0m             Pop top word off vocabulary stack (/pop)\n\
+         \x1b[36m  ?\x1b[0m                  Answer a pending brain question (empty prompt)\n\
          \x1b[36m  Tab\x1b[0m                Complete /command (accepts ghost text)\n\
          \x1b[36m  Shift+Tab\x1b[0m          Toggle plan mode on/off\n\
          \x1b[36m  Shift+Enter\x1b[0m        Multi-line input (insert newline)\n\
@@ -841,6 +968,55 @@ pub fn format_training(
     Ok(output)
 }
 
+/// Matches listed by /memory search
+pub const MEMORY_SEARCH_RESULTS: usize = 10;
+
+pub fn format_memory_stats(stats: &crate::memory::MemoryStats) -> String {
+    format!(
+        "Memory System\n\
+         =============\n\n\
+         Conversations stored: {}\n\
+         Memories: {}\n\
+         Facts: {}\n\
+         Stored in: {}\n\n\
+         Use /memory search <query> to see what a prompt would recall.",
+        stats.conversation_count, stats.tree_node_count, stats.fact_count, stats.store
+    )
+}
+
+/// /memory search results, with the ids /memory pin and /memory forget take
+pub fn format_memory_hits(query: &str, hits: &[crate::memory::MemoryHit]) -> String {
+    if hits.is_empty() {
+        return format!("No memories match \"{}\".", query);
+    }
+    let mut output = format!("Memories recalled for \"{}\":\n", query);
+    for hit in hits {
+        let importance = crate::memory::MemoryImportance::from_u8(hit.importance);
+        let text: String = hit.text.chars().take(120).collect();
+        output.push_str(&format!(
+            "  [{}] {:.2} {:?}  {}\n",
+            hit.id,
+            hit.score,
+            importance,
+            text.replace('\n', " ")
+        ));
+    }
+    output.push_str("Pin with /memory pin <id>, drop with /memory forget <id>");
+    output
+}
+
+/// Result of /memory pin (`pin`) or /memory forget on memory `id`
+pub fn format_memory_edit(id: &str, pin: bool, found: bool) -> String {
+    match (found, pin) {
+        (false, _) => format!("No memory with id {} (see /memory search)", id),
+        (true, true) => format!(
+            "📌 Pinned memory {}: it ranks first and is never forgotten",
+            id
+        ),
+        (true, false) => format!("Forgot memory {}", id),
+    }
+}
+
 // Pattern management command handlers are now in Repl (Phase 3 implementation)
 // The command handlers above return a placeholder message since the actual
 // handling is done directly in the REPL loop to avoid borrowing issues
@@ -987,6 +1163,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_incognito() {
+        assert!(matches!(
+            Command::parse("/incognito"),
+            Some(Command::Incognito)
+        ));
+    }
+
+    #[test]
+    fn test_parse_context() {
+        assert!(matches!(Command::parse("/context"), Some(Command::Context)));
+        assert!(matches!(
+            Command::parse("  /context  "),
+            Some(Command::Context)
+        ));
+    }
+
     #[test]
     fn test_parse_invalid_patterns_command() {
         // Invalid subcommands should return None
@@ -995,6 +1188,110 @@ mod tests {
         assert!(matches!(Command::parse("/patterns rm"), None)); // Missing ID
     }
 
+    #[test]
+    fn test_parse_model_reload() {
+        use crate::models::ModelSize;
+
+        let Some(Command::ModelReload(request)) = Command::parse("/model reload") else {
+            panic!("expected /model reload");
+        };
+        assert_eq!(request, Default::default());
+        let Some(Command::ModelReload(request)) =
+            Command::parse("/model reload Large onnx-community/Qwen2.5-7B")
+        else {
+            panic!("expected /model reload with arguments");
+        };
+        assert_eq!(request.model_size, Some(ModelSize::Large));
+        assert_eq!(
+            request.model_repo.as_deref(),
+            Some("onnx-community/Qwen2.5-7B")
+        );
+        let Some(Command::ModelReload(request)) = Command::parse("/model reload default") else {
+            panic!("expected /model reload default");
+        };
+        assert_eq!(request.model_repo.as_deref(), Some(""));
+        // Still a provider switch
+        assert!(matches!(
+            Command::parse("/model reloaded"),
+            Some(Command::ModelSwitch(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_undo_and_rollback() {
+        assert!(matches!(Command::parse("/undo"), Some(Command::Undo)));
+        assert!(matches!(
+            Command::parse("/rollback"),
+            Some(Command::Rollback(None))
+        ));
+        assert!(matches!(
+            Command::parse("/rollback 3"),
+            Some(Command::Rollback(Some(3)))
+        ));
+        assert!(Command::parse("/rollback 0").is_none());
+        assert!(Command::parse("/rollback all").is_none());
+    }
+
+    #[test]
+    fn test_parse_paste() {
+        assert!(matches!(
+            Command::parse("/paste"),
+            Some(Command::Paste(None))
+        ));
+        assert!(matches!(
+            Command::parse("/paste why does this fail"),
+            Some(Command::Paste(Some(q))) if q == "why does this fail"
+        ));
+    }
+
+    #[test]
+    fn test_parse_memory() {
+        assert!(matches!(Command::parse("/memory"), Some(Command::Memory)));
+        assert!(matches!(
+            Command::parse("/memory stats"),
+            Some(Command::MemoryStats)
+        ));
+        assert!(matches!(
+            Command::parse("/memory search staging database"),
+            Some(Command::MemorySearch(q)) if q == "staging database"
+        ));
+        assert!(matches!(
+            Command::parse("/memory pin 12"),
+            Some(Command::MemoryPin(id)) if id == "12"
+        ));
+        assert!(matches!(
+            Command::parse("/memory forget 7"),
+            Some(Command::MemoryForget(id)) if id == "7"
+        ));
+        assert!(Command::parse("/memory forget").is_none());
+        assert!(Command::parse("/memory wipe 3").is_none());
+    }
+
+    #[test]
+    fn test_format_memory_hits() {
+        let hits = [crate::memory::MemoryHit {
+            id: "12".to_string(),
+            text: "staging listens on\nport 5433".to_string(),
+            importance: 3,
+            score: 0.91,
+        }];
+        let output = format_memory_hits("staging port", &hits);
+        assert!(output.contains("[12] 0.91 Critical  staging listens on port 5433"));
+        assert!(format_memory_hits("nothing", &[]).starts_with("No memories match"));
+    }
+
+    #[test]
+    fn test_parse_promote() {
+        assert!(matches!(
+            Command::parse("/promote"),
+            Some(Command::Promote(ids)) if ids.is_empty()
+        ));
+        assert!(matches!(
+            Command::parse("/promote 2 5"),
+            Some(Command::Promote(ids)) if ids == ["2", "5"]
+        ));
+    }
+
     // MCP Command Tests
 
     #[test]
@@ -0,0 +1,298 @@
+// Context window breakdown for /context
+//
+// Splits the context sent with the latest request into the system prompt,
+// tool definitions, recalled memories, conversation history and the tool
+// results of the current turn, so users can see why they are near the limit
+// and what /compact would give back.  Sizes use the same 4-characters-per-
+// token estimate as the status bar.
+
+use crate::claude::{ContentBlock, Message};
+use crate::tools::types::ToolDefinition;
+
+/// Characters per token used for every estimate
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Rough token cost of one image block (the base64 payload is not text)
+const IMAGE_TOKENS: usize = 1_600;
+
+/// Upper bound on the summary /compact keeps in place of the history
+const COMPACT_SUMMARY_TOKENS: usize = 1_024;
+
+/// Width in cells of the stacked bar
+const BAR_WIDTH: usize = 50;
+
+/// Width in cells of each per-category bar
+const ROW_WIDTH: usize = 20;
+
+/// Estimated token usage of one request, by category
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextBreakdown {
+    pub system_prompt: usize,
+    pub tools: usize,
+    pub memories: usize,
+    pub history: usize,
+    /// Tool results returned since the user's latest message
+    pub tool_results: usize,
+    /// Size of the context window
+    pub limit: usize,
+}
+
+impl ContextBreakdown {
+    /// Measure a request about to be sent.
+    ///
+    /// `memory_chars` is the length of the recalled-memory block injected
+    /// into `messages`; it is counted under memories rather than history.
+    pub fn measure(
+        system_prompt_chars: usize,
+        tools: &[ToolDefinition],
+        memory_chars: usize,
+        messages: &[Message],
+        limit: usize,
+    ) -> Self {
+        let tool_chars = serde_json::to_string(tools).map(|s| s.len()).unwrap_or(0);
+
+        // The current turn starts at the last user message with text in it;
+        // the tool results after that are the ones the model is reading now
+        let turn_start = messages
+            .iter()
+            .rposition(|m| m.role == "user" && m.content.iter().any(|b| b.is_text()))
+            .unwrap_or(0);
+        let mut message_chars = 0;
+        let mut result_chars = 0;
+        for (i, message) in messages.iter().enumerate() {
+            for block in &message.content {
+                let chars = block_chars(block);
+                match block {
+                    ContentBlock::ToolResult { .. } if i >= turn_start => result_chars += chars,
+                    _ => message_chars += chars,
+                }
+            }
+        }
+
+        Self {
+            system_prompt: system_prompt_chars / CHARS_PER_TOKEN,
+            tools: tool_chars / CHARS_PER_TOKEN,
+            memories: memory_chars / CHARS_PER_TOKEN,
+            history: message_chars.saturating_sub(memory_chars) / CHARS_PER_TOKEN,
+            tool_results: result_chars / CHARS_PER_TOKEN,
+            limit,
+        }
+    }
+
+    /// Total tokens in use
+    pub fn total(&self) -> usize {
+        self.system_prompt + self.tools + self.memories + self.history + self.tool_results
+    }
+
+    /// Tokens /compact would free: history and tool results are replaced by
+    /// a summary, everything else is sent again on the next turn
+    pub fn reclaimable(&self) -> usize {
+        (self.history + self.tool_results).saturating_sub(COMPACT_SUMMARY_TOKENS)
+    }
+
+    /// Render as a stacked bar over the whole window followed by one row per
+    /// category
+    pub fn render(&self) -> String {
+        let categories = [
+            ("System prompt", self.system_prompt, "\x1b[35m"),
+            ("Tools", self.tools, "\x1b[34m"),
+            ("Memories", self.memories, "\x1b[36m"),
+            ("History", self.history, "\x1b[32m"),
+            ("Tool results", self.tool_results, "\x1b[33m"),
+        ];
+        let total = self.total();
+        let window = self.limit.max(total).max(1);
+
+        let mut bar = String::new();
+        let mut used_cells = 0;
+        for (_, tokens, color) in &categories {
+            let cells = cells(*tokens, window, BAR_WIDTH).min(BAR_WIDTH - used_cells);
+            if cells > 0 {
+                bar.push_str(&format!("{}{}\x1b[0m", color, "█".repeat(cells)));
+                used_cells += cells;
+            }
+        }
+        bar.push_str(&format!(
+            "\x1b[90m{}\x1b[0m",
+            "░".repeat(BAR_WIDTH - used_cells)
+        ));
+
+        let mut out = format!(
+            "\x1b[1mContext window\x1b[0m  {} / {} tokens ({})\n{}\n\n",
+            format_tokens(total),
+            format_tokens(self.limit),
+            format_percent(total, self.limit),
+            bar
+        );
+        for (label, tokens, color) in &categories {
+            out.push_str(&format!(
+                "  {}■\x1b[0m {:<14} {:>7}  {:>6}  {}{}\x1b[0m\n",
+                color,
+                label,
+                format_tokens(*tokens),
+                format_percent(*tokens, self.limit),
+                color,
+                "▇".repeat(cells(*tokens, total.max(1), ROW_WIDTH))
+            ));
+        }
+        out.push_str(&format!(
+            "  \x1b[90m□\x1b[0m {:<14} {:>7}  {:>6}\n\n",
+            "Free",
+            format_tokens(self.limit.saturating_sub(total)),
+            format_percent(self.limit.saturating_sub(total), self.limit)
+        ));
+        out.push_str(&format!(
+            "/compact would reclaim about {} tokens",
+            format_tokens(self.reclaimable())
+        ));
+        out
+    }
+}
+
+/// Characters a content block adds to the request
+fn block_chars(block: &ContentBlock) -> usize {
+    match block {
+        ContentBlock::Text { text } => text.len(),
+        ContentBlock::Image { .. } => IMAGE_TOKENS * CHARS_PER_TOKEN,
+        ContentBlock::ToolUse { name, input, .. } => name.len() + input.to_string().len(),
+        ContentBlock::ToolResult { content, .. } => content.len(),
+        ContentBlock::Thinking { thinking, .. } => thinking.len(),
+        ContentBlock::RedactedThinking { data } => data.len(),
+    }
+}
+
+/// Cells out of `width` for `part` of `whole`, with any non-zero part visible
+fn cells(part: usize, whole: usize, width: usize) -> usize {
+    if part == 0 {
+        return 0;
+    }
+    ((part * width + whole / 2) / whole).clamp(1, width)
+}
+
+fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+fn format_percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", part as f64 * 100.0 / whole as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(role: &str, text: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: vec![ContentBlock::text(text)],
+        }
+    }
+
+    fn tool_result(content: &str) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::tool_result(
+                "t1".to_string(),
+                content.to_string(),
+                None,
+            )],
+        }
+    }
+
+    #[test]
+    fn test_measure_splits_categories() {
+        let memories = "m".repeat(400);
+        let messages = vec![
+            text("user", &"a".repeat(800)),
+            tool_result(&"r".repeat(4_000)),
+            text("assistant", &"b".repeat(400)),
+            // Latest user message carries the injected memory block
+            text("user", &format!("{}{}", memories, "c".repeat(400))),
+            tool_result(&"s".repeat(2_000)),
+        ];
+
+        let breakdown = ContextBreakdown::measure(1_200, &[], memories.len(), &messages, 10_000);
+
+        assert_eq!(breakdown.system_prompt, 300);
+        assert_eq!(breakdown.memories, 100);
+        // Earlier tool results count as history, only the last turn's are separate
+        assert_eq!(breakdown.history, (800 + 4_000 + 400 + 400) / 4);
+        assert_eq!(breakdown.tool_results, 500);
+        assert_eq!(breakdown.total(), 300 + 100 + 1_400 + 500 + breakdown.tools);
+    }
+
+    #[test]
+    fn test_reclaimable_keeps_a_summary() {
+        let breakdown = ContextBreakdown {
+            system_prompt: 2_000,
+            tools: 3_000,
+            memories: 500,
+            history: 40_000,
+            tool_results: 5_000,
+            limit: 150_000,
+        };
+        assert_eq!(breakdown.reclaimable(), 45_000 - COMPACT_SUMMARY_TOKENS);
+
+        let small = ContextBreakdown {
+            history: 200,
+            ..breakdown
+        };
+        assert_eq!(small.reclaimable(), 4_176);
+        let empty = ContextBreakdown {
+            history: 0,
+            tool_results: 0,
+            ..small
+        };
+        assert_eq!(empty.reclaimable(), 0);
+    }
+
+    #[test]
+    fn test_render_shows_every_category() {
+        let breakdown = ContextBreakdown {
+            system_prompt: 2_000,
+            tools: 3_000,
+            memories: 0,
+            history: 40_000,
+            tool_results: 5_000,
+            limit: 150_000,
+        };
+        let out = breakdown.render();
+        assert!(out.contains("50.0k / 150.0k tokens (33.3%)"));
+        for label in [
+            "System prompt",
+            "Tools",
+            "Memories",
+            "History",
+            "Tool results",
+            "Free",
+        ] {
+            assert!(out.contains(label), "missing {}", label);
+        }
+        assert!(out.contains("/compact would reclaim about 44.0k tokens"));
+        // The stacked bar is always BAR_WIDTH cells
+        let bar = out.lines().nth(1).unwrap();
+        assert_eq!(
+            bar.matches('█').count() + bar.matches('░').count(),
+            BAR_WIDTH
+        );
+    }
+
+    #[test]
+    fn test_render_over_limit_fills_bar() {
+        let breakdown = ContextBreakdown {
+            history: 200_000,
+            limit: 150_000,
+            ..Default::default()
+        };
+        let bar_line = breakdown.render().lines().nth(1).unwrap().to_string();
+        assert_eq!(bar_line.matches('█').count(), BAR_WIDTH);
+        assert_eq!(bar_line.matches('░').count(), 0);
+    }
+}
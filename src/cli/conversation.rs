@@ -1,6 +1,7 @@
 // Conversation history manager for multi-turn interactions
 
 use crate::claude::{ContentBlock, Message};
+use crate::generators::Attribution;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -10,6 +11,10 @@ use std::path::Path;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationHistory {
     messages: Vec<Message>,
+    /// Generator of each message, parallel to `messages` (`None` for user
+    /// input, tool results and anything recorded before attribution)
+    #[serde(default)]
+    attribution: Vec<Option<Attribution>>,
     #[serde(skip)]
     max_messages: usize,
     #[serde(skip)]
@@ -18,6 +23,9 @@ pub struct ConversationHistory {
     compaction_threshold_percent: f32, // Trigger compaction at this % of max tokens (e.g., 0.8 = 80%)
     #[serde(skip)]
     auto_compact_enabled: bool, // Whether auto-compaction is enabled
+    /// Messages before this index are already summarized in an episode memory
+    #[serde(skip)]
+    episode_start: usize,
 }
 
 impl ConversationHistory {
@@ -25,10 +33,12 @@ impl ConversationHistory {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
+            attribution: Vec::new(),
             max_messages: 500, // ~250 turns — plenty for a full coding session
             max_tokens_estimate: 600_000, // ~150k tokens * 4 chars/token (Claude: 200k context)
             compaction_threshold_percent: 0.9, // Compact at 90% of max
             auto_compact_enabled: true, // Auto-compaction enabled by default
+            episode_start: 0,
         }
     }
 
@@ -36,20 +46,21 @@ impl ConversationHistory {
     pub fn with_limits(max_messages: usize, max_tokens_estimate: usize) -> Self {
         Self {
             messages: Vec::new(),
+            attribution: Vec::new(),
             max_messages,
             max_tokens_estimate,
             compaction_threshold_percent: 0.8,
             auto_compact_enabled: true,
+            episode_start: 0,
         }
     }
 
     /// Add a user message to the conversation
     pub fn add_user_message(&mut self, content: String) {
-        self.messages.push(Message {
+        self.add_message(Message {
             role: "user".to_string(),
             content: vec![ContentBlock::Text { text: content }],
         });
-        self.trim_if_needed();
     }
 
     /// Add a user message with optional image attachments.
@@ -61,25 +72,30 @@ impl ConversationHistory {
             .collect();
         blocks.push(ContentBlock::Text { text });
 
-        self.messages.push(Message {
+        self.add_message(Message {
             role: "user".to_string(),
             content: blocks,
         });
-        self.trim_if_needed();
     }
 
     /// Add an assistant message to the conversation
     pub fn add_assistant_message(&mut self, content: String) {
-        self.messages.push(Message {
-            role: "assistant".to_string(),
-            content: vec![ContentBlock::Text { text: content }],
-        });
-        self.trim_if_needed();
+        self.add_message(Message::assistant(content));
     }
 
     /// Add a complete message to the conversation
     pub fn add_message(&mut self, message: Message) {
+        self.push(message, None);
+    }
+
+    /// Add a generated message along with the generator that produced it
+    pub fn add_attributed_message(&mut self, message: Message, attribution: Attribution) {
+        self.push(message, Some(attribution));
+    }
+
+    fn push(&mut self, message: Message, attribution: Option<Attribution>) {
         self.messages.push(message);
+        self.attribution.push(attribution);
         self.trim_if_needed();
     }
 
@@ -88,9 +104,21 @@ impl ConversationHistory {
         self.messages.clone()
     }
 
+    /// Generator of each message, in the same order as `get_messages()`
+    pub fn attributions(&self) -> &[Option<Attribution>] {
+        &self.attribution
+    }
+
+    /// Generator of the most recent attributed message
+    pub fn last_attribution(&self) -> Option<&Attribution> {
+        self.attribution.iter().rev().flatten().next()
+    }
+
     /// Clear conversation history (start fresh)
     pub fn clear(&mut self) {
         self.messages.clear();
+        self.attribution.clear();
+        self.episode_start = 0;
     }
 
     /// Check if conversation has any messages
@@ -114,9 +142,37 @@ impl ConversationHistory {
         self.messages.clone()
     }
 
-    /// Restore conversation from a snapshot
+    /// Restore conversation from a snapshot.  Snapshots carry no
+    /// attribution, so every restored message is unattributed.
     pub fn restore_snapshot(&mut self, snapshot: Vec<Message>) {
-        self.messages = snapshot;
+        self.restore_attributed(snapshot, Vec::new());
+    }
+
+    /// Restore messages along with their attribution (padded or cut to
+    /// match, e.g. for sessions saved before attribution existed)
+    pub fn restore_attributed(
+        &mut self,
+        messages: Vec<Message>,
+        mut attribution: Vec<Option<Attribution>>,
+    ) {
+        attribution.resize(messages.len(), None);
+        self.messages = messages;
+        self.attribution = attribution;
+        self.episode_start = 0;
+    }
+
+    /// Index of the first message not yet summarized in an episode memory
+    pub fn episode_start(&self) -> usize {
+        self.episode_start.min(self.messages.len())
+    }
+
+    /// The messages before `end` not yet summarized in an episode memory,
+    /// which from now on count as summarized
+    pub fn take_episode(&mut self, end: usize) -> Vec<Message> {
+        let end = end.min(self.messages.len());
+        let start = self.episode_start.min(end);
+        self.episode_start = end;
+        self.messages[start..end].to_vec()
     }
 
     /// Trim old messages if context exceeds limits
@@ -125,6 +181,8 @@ impl ConversationHistory {
         if self.messages.len() > self.max_messages {
             let remove_count = self.messages.len() - self.max_messages;
             self.messages.drain(0..remove_count);
+            self.attribution.drain(0..remove_count);
+            self.episode_start = self.episode_start.saturating_sub(remove_count);
         }
 
         // Estimate token count (rough: 1 token ≈ 4 characters)
@@ -139,6 +197,8 @@ impl ConversationHistory {
                     > self.max_tokens_estimate
             {
                 self.messages.remove(0);
+                self.attribution.remove(0);
+                self.episode_start = self.episode_start.saturating_sub(1);
             }
         }
     }
@@ -149,6 +209,11 @@ impl ConversationHistory {
         total_chars / 4 // Rough estimate: 1 token ≈ 4 characters
     }
 
+    /// Get the context window size in tokens
+    pub fn token_limit(&self) -> usize {
+        self.max_tokens_estimate / 4
+    }
+
     /// Get percentage of context window used (0.0 to 1.0)
     pub fn context_usage_percent(&self) -> f32 {
         let current_tokens = self.estimated_tokens() as f32;
@@ -225,6 +290,9 @@ impl ConversationHistory {
 
         let mut history: ConversationHistory =
             serde_json::from_str(&json).context("Failed to parse conversation JSON")?;
+        let messages = std::mem::take(&mut history.messages);
+        let attribution = std::mem::take(&mut history.attribution);
+        history.restore_attributed(messages, attribution);
 
         // Restore default config values (these are skipped during serialization)
         history.max_messages = 500;
@@ -427,8 +495,12 @@ impl<'a> ConversationCompactor<'a> {
         // Add recent messages
         compacted_messages.extend(to_keep.iter().cloned());
 
+        // The kept messages keep their attribution; the summary has none
+        let mut attribution = vec![None];
+        attribution.extend_from_slice(&history.attributions()[split_point..]);
+
         // Replace conversation history with compacted version
-        history.restore_snapshot(compacted_messages);
+        history.restore_attributed(compacted_messages, attribution);
 
         tracing::info!(
             "Conversation compacted: {} → {} messages (saved ~{} tokens)",
@@ -516,6 +588,29 @@ mod tests {
         assert_eq!(messages[1].text_content(), "Assistant 1");
     }
 
+    #[test]
+    fn test_take_episode_follows_trimming() {
+        let mut conv = ConversationHistory::with_limits(4, 100_000);
+        conv.add_user_message("User 0".to_string());
+        conv.add_assistant_message("Assistant 0".to_string());
+        conv.add_user_message("User 1".to_string());
+
+        let episode = conv.take_episode(2);
+        assert_eq!(episode.len(), 2);
+        assert_eq!(conv.episode_start(), 2);
+
+        // Two trimmed messages were summarized, so the episode start moves back
+        conv.add_assistant_message("Assistant 1".to_string());
+        conv.add_user_message("User 2".to_string());
+        conv.add_assistant_message("Assistant 2".to_string());
+        assert_eq!(conv.episode_start(), 0);
+
+        let rest = conv.take_episode(usize::MAX);
+        assert_eq!(rest.len(), 4);
+        assert_eq!(rest[0].text_content(), "User 1");
+        assert!(conv.take_episode(usize::MAX).is_empty());
+    }
+
     #[test]
     fn test_token_estimation() {
         let mut conv = ConversationHistory::new();
@@ -563,4 +658,44 @@ mod tests {
         // Clean up
         let _ = std::fs::remove_file(temp_path);
     }
+
+    #[test]
+    fn test_attribution_follows_messages() {
+        let mut conv = ConversationHistory::with_limits(3, 100_000);
+        conv.add_user_message("First".to_string());
+        conv.add_attributed_message(
+            Message::assistant("From the teacher"),
+            Attribution::teacher("claude", "claude-sonnet-4-6"),
+        );
+        conv.add_user_message("Second".to_string());
+        conv.add_attributed_message(
+            Message::assistant("From the local model"),
+            Attribution::local("Qwen2.5-3B"),
+        );
+
+        // Trimming drops the first message and its (missing) attribution
+        assert_eq!(conv.message_count(), 3);
+        let providers: Vec<_> = conv
+            .attributions()
+            .iter()
+            .map(|a| a.as_ref().map(|a| a.provider.as_str()))
+            .collect();
+        assert_eq!(providers, [Some("claude"), None, Some("local")]);
+        assert_eq!(conv.last_attribution().unwrap().model, "Qwen2.5-3B");
+
+        let temp_path = "/tmp/test_conv_finch_attribution.json";
+        conv.save(temp_path).unwrap();
+        let loaded = ConversationHistory::load(temp_path).unwrap();
+        let _ = std::fs::remove_file(temp_path);
+        assert_eq!(loaded.attributions(), conv.attributions());
+
+        // Conversations saved before attribution load as unattributed
+        let mut json: serde_json::Value = serde_json::to_value(&conv).unwrap();
+        json.as_object_mut().unwrap().remove("attribution");
+        std::fs::write(temp_path, json.to_string()).unwrap();
+        let loaded = ConversationHistory::load(temp_path).unwrap();
+        let _ = std::fs::remove_file(temp_path);
+        assert_eq!(loaded.attributions(), [None, None, None]);
+        assert!(loaded.last_attribution().is_none());
+    }
 }
@@ -101,6 +101,7 @@ pub fn format_messages_for_summary(messages: &[Message]) -> String {
             let parts: Vec<String> = msg
                 .content
                 .iter()
+                .filter(|block| !block.is_thinking())
                 .map(|block| match block {
                     ContentBlock::Text { text } => text.clone(),
                     ContentBlock::ToolUse { name, .. } => {
@@ -110,6 +111,9 @@ pub fn format_messages_for_summary(messages: &[Message]) -> String {
                         format!("[Tool result for: {tool_use_id}]")
                     }
                     ContentBlock::Image { .. } => "[image]".to_string(),
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {
+                        String::new()
+                    }
                 })
                 .collect();
             format!("{role}: {}", parts.join(" "))
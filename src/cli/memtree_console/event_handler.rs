@@ -48,7 +48,9 @@ impl EventHandler {
                 self.handle_query_complete(console, *query_id, response)?;
             }
 
-            ReplEvent::QueryFailed { query_id, error } => {
+            ReplEvent::QueryFailed {
+                query_id, error, ..
+            } => {
                 self.handle_query_failed(console, *query_id, error)?;
             }
 
@@ -71,6 +73,7 @@ impl EventHandler {
                 input_tokens,
                 output_tokens,
                 latency_ms,
+                ..
             } => {
                 self.handle_stats_update(
                     console,
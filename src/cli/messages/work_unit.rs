@@ -1,9 +1,13 @@
 // WorkUnit - Unified message type for one AI generation turn
 //
 // A WorkUnit covers the full lifecycle of one AI response:
-//   1. Streaming phase  → animated "✦ Channeling… (Xs · thinking)" header
+//   1. Streaming phase  → animated "✦ Channeling… (Xs · thinking)" header, with
+//                         a dimmed "✻ Thinking…" line while a reasoning model
+//                         thinks
 //   2. Tool call phase  → sub-rows with "⎿ bash(cmd)…" / "⎿ bash(cmd) N lines"
-//   3. Complete phase   → "⏺ response text" with collapsed sub-rows
+//   3. Complete phase   → "⏺ response text" with a dimmed "↳ [mem-12] recalled
+//                         from …" footnote per memory the answer cites, and
+//                         collapsed sub-rows
 //
 // WorkUnit replaces the combination of StreamingResponseMessage + OperationMessage.
 // It lives in the shadow buffer, rendered by the blit cycle (~100ms tick).
@@ -104,6 +108,11 @@ struct WorkUnitInner {
     token_count: usize,
     /// True while in the "thinking" phase (before tokens arrive)
     thinking: bool,
+    /// Hidden chain-of-thought streamed by a reasoning model; display only
+    thinking_text: String,
+    /// Footnotes for recalled memories the response cites, e.g.
+    /// "[mem-12] recalled from 2026-10-02, session swift-otter"
+    citations: Vec<String>,
     /// Sub-rows for tool calls
     rows: Vec<WorkRow>,
     /// Overall status of this unit
@@ -141,6 +150,8 @@ impl WorkUnit {
                 response_text: String::new(),
                 token_count: 0,
                 thinking: false,
+                thinking_text: String::new(),
+                citations: Vec::new(),
                 rows: Vec::new(),
                 status: MessageStatus::InProgress,
                 elapsed_at_finish: None,
@@ -167,6 +178,15 @@ impl WorkUnit {
             .thinking = thinking;
     }
 
+    /// Append reasoning-model thinking, shown as a collapsed, dimmed block.
+    pub fn append_thinking(&self, text: &str) {
+        self.inner
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .thinking_text
+            .push_str(text);
+    }
+
     /// Set the final response text (call after streaming ends).
     pub fn set_response(&self, text: impl Into<String>) {
         self.inner
@@ -175,6 +195,15 @@ impl WorkUnit {
             .response_text = text.into();
     }
 
+    /// Set the footnotes for recalled memories the response cites; shown
+    /// one dimmed line each beneath the response once it is complete.
+    pub fn set_citations(&self, citations: Vec<String>) {
+        self.inner
+            .write()
+            .unwrap_or_else(|p| p.into_inner())
+            .citations = citations;
+    }
+
     /// Append a chunk to the response text (for partial updates).
     pub fn append_response(&self, text: &str) {
         self.inner
@@ -298,6 +327,11 @@ impl Message for WorkUnit {
                     CYAN, icon, RESET, self.verb, stats, RESET
                 );
 
+                if !inner.thinking_text.is_empty() {
+                    out.push('\n');
+                    out.push_str(&format_thinking(&inner.thinking_text, true));
+                }
+
                 for row in &inner.rows {
                     out.push('\n');
                     out.push_str(&format_row(row));
@@ -323,12 +357,27 @@ impl Message for WorkUnit {
                     String::new()
                 };
 
+                // Collapsed thinking goes above the answer it led to
+                let mut out = String::new();
+                if !inner.thinking_text.is_empty() {
+                    out.push_str(&format_thinking(&inner.thinking_text, false));
+                    out.push('\n');
+                }
+
                 // Show final response (bare bullet if no text)
-                let mut out = if inner.response_text.is_empty() {
-                    format!("{}⏺{}{}", CYAN, RESET, timing)
+                if inner.response_text.is_empty() {
+                    out.push_str(&format!("{}⏺{}{}", CYAN, RESET, timing));
                 } else {
-                    format!("{}⏺{} {}{}", CYAN, RESET, inner.response_text, timing)
-                };
+                    out.push_str(&format!(
+                        "{}⏺{} {}{}",
+                        CYAN, RESET, inner.response_text, timing
+                    ));
+                }
+
+                // Where the memories the answer cites were recalled from
+                for citation in &inner.citations {
+                    out.push_str(&format!("\n  {}↳ {}{}", GRAY_DIM, citation, RESET));
+                }
 
                 // Collapsed sub-rows: show what tools ran (label + summary + body lines)
                 for row in &inner.rows {
@@ -458,6 +507,24 @@ fn fmt_elapsed(secs: u64) -> String {
     }
 }
 
+/// One dimmed line standing in for the model's thinking.  While it streams,
+/// the latest line of thought is shown after the token count.
+fn format_thinking(thinking: &str, live: bool) -> String {
+    let tokens = fmt_tokens(crate::providers::reasoning::estimate_tokens(thinking) as usize);
+    if !live {
+        return format!("{}✻ Thought for {} tokens{}", GRAY_DIM, tokens, RESET);
+    }
+    let latest = thinking
+        .lines()
+        .rfind(|l| !l.trim().is_empty())
+        .unwrap_or("");
+    let latest: String = latest.trim().chars().take(80).collect();
+    format!(
+        "  {}✻ Thinking… ({} tokens) {}{}",
+        GRAY_DIM, tokens, latest, RESET
+    )
+}
+
 fn fmt_tokens(n: usize) -> String {
     if n >= 1000 {
         format!("{:.1}k", n as f64 / 1000.0)
@@ -548,6 +615,39 @@ mod tests {
         assert!(!wu.inner.read().unwrap().thinking);
     }
 
+    #[test]
+    fn test_thinking_is_shown_collapsed_but_not_content() {
+        let wu = WorkUnit::new("X");
+        wu.append_thinking("First, read the file.\nThen check the ");
+        wu.append_thinking("tests.");
+        assert!(wu
+            .format(&colors())
+            .contains("✻ Thinking… (11 tokens) Then check the tests."));
+
+        wu.set_response("Done.");
+        wu.set_complete();
+        let out = wu.format(&colors());
+        assert!(out.contains("✻ Thought for 11 tokens"));
+        assert!(!out.contains("read the file"));
+        // Thinking never becomes part of the response
+        assert_eq!(wu.content(), "Done.");
+    }
+
+    #[test]
+    fn test_citations_are_footnotes_once_complete() {
+        let wu = WorkUnit::new("X");
+        wu.set_response("Deploy through staging [mem-12].");
+        wu.set_citations(vec![
+            "[mem-12] recalled from 2026-10-02, session swift-otter".to_string(),
+        ]);
+        assert!(!wu.format(&colors()).contains("↳"));
+
+        wu.set_complete();
+        let out = wu.format(&colors());
+        assert!(out.contains("↳ [mem-12] recalled from 2026-10-02, session swift-otter"));
+        assert_eq!(wu.content(), "Deploy through staging [mem-12].");
+    }
+
     // ── Response text ────────────────────────────────────────────────────────
 
     #[test]
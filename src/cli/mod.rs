@@ -1,8 +1,10 @@
 // CLI module
 // Public interface for command-line interface
 
+pub mod activity_dashboard; // finch tui --attach: live view of a daemon's activity
 pub mod command_autocomplete;
 mod commands;
+pub mod context_breakdown; // /context: what fills the context window
 mod conversation;
 pub mod conversation_compactor; // Infinite context: summarise dropped messages
 pub mod global_output; // Phase 3.5: Global output system with macros
@@ -13,6 +15,8 @@ pub mod menu;
 pub mod messages; // Trait-based polymorphic message system
 pub mod output_layer; // Phase 3.5: Tracing integration
 mod output_manager;
+pub mod prompt_lint; // Catch likely-unproductive prompts before sending
+pub mod provider_recovery; // Retry / switch / local / copy after a teacher failure
 mod repl;
 pub mod repl_event; // Phase 2-3: Event loop infrastructure
 pub mod setup_wizard; // First-run setup wizard (API keys + device selection)
@@ -0,0 +1,333 @@
+// Prompt lint — catch prompts unlikely to get a useful first answer
+//
+// Before a query is sent, a cheap local pass looks for three common slips:
+// a prompt that points at a diff that isn't there, a question about an error
+// that leaves out the error, and a file path that doesn't exist.  A finding
+// can come with a fix-up (attach `git diff HEAD`, use the path that does
+// exist) that the REPL offers on a single key, next to sending the prompt
+// as is or editing it.  The local model gains most, since it can't go looking
+// for what's missing.
+//
+// These are heuristics, so nothing is ever blocked.
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+/// Largest `git diff` attached by the fix-up
+const MAX_ATTACHED_DIFF_BYTES: usize = 64 * 1024;
+
+/// Files looked at when searching for the path a prompt meant
+const MAX_FILES: usize = 20_000;
+
+/// Directories never searched
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Extensions that make a word look like a file name
+const FILE_EXTENSIONS: &[&str] = &[
+    "c", "cc", "cfg", "cpp", "cs", "css", "go", "h", "hpp", "html", "ini", "java", "js", "json",
+    "jsx", "kt", "lock", "lua", "md", "php", "py", "rb", "rs", "scss", "sh", "sql", "swift",
+    "toml", "ts", "tsx", "txt", "vue", "xml", "yaml", "yml", "zig",
+];
+
+/// Something likely to make the prompt less productive
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    pub message: String,
+    pub fix: Option<FixUp>,
+}
+
+/// A rewritten prompt that addresses an issue
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixUp {
+    /// Shown as the choice, e.g. "Attach `git diff HEAD` (2 files)"
+    pub label: String,
+    pub prompt: String,
+}
+
+/// Issues found in `prompt`, with paths resolved against `root`
+pub fn lint(prompt: &str, root: &Path) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    issues.extend(check_missing_diff(prompt, root));
+    issues.extend(check_missing_error(prompt));
+    issues.extend(check_missing_files(prompt, root));
+    issues
+}
+
+/// "Review this diff" with no hunk in sight
+fn check_missing_diff(prompt: &str, root: &Path) -> Option<LintIssue> {
+    static POINTS_AT_DIFF: OnceLock<Regex> = OnceLock::new();
+    let re = POINTS_AT_DIFF.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(this|these|following|attached|here'?s|here is)\s+(diff|patch|changes)\b|\b(diff|patch)\s+(below|above|attached)\b",
+        )
+        .expect("static regex")
+    });
+    if !re.is_match(prompt) || prompt.lines().any(|l| l.trim_start().starts_with("@@")) {
+        return None;
+    }
+    Some(LintIssue {
+        message: "The prompt refers to a diff, but none is included".to_string(),
+        fix: attach_git_diff(prompt, root),
+    })
+}
+
+/// Append the uncommitted changes under `root`, when there are any
+fn attach_git_diff(prompt: &str, root: &Path) -> Option<FixUp> {
+    let output = Command::new("git")
+        .args(["diff", "--no-color", "--no-ext-diff", "HEAD"])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success()
+        || output.stdout.is_empty()
+        || output.stdout.len() > MAX_ATTACHED_DIFF_BYTES
+    {
+        return None;
+    }
+    let diff = String::from_utf8_lossy(&output.stdout);
+    let files = diff
+        .lines()
+        .filter(|l| l.starts_with("diff --git "))
+        .count();
+    Some(FixUp {
+        label: format!(
+            "Attach `git diff HEAD` ({} file{})",
+            files,
+            if files == 1 { "" } else { "s" }
+        ),
+        prompt: format!("{}\n\n```diff\n{}```", prompt.trim_end(), diff),
+    })
+}
+
+/// "It fails with an error" and nothing that reads like the error
+fn check_missing_error(prompt: &str) -> Option<LintIssue> {
+    static MENTIONS_ERROR: OnceLock<Regex> = OnceLock::new();
+    static ERROR_TEXT: OnceLock<Regex> = OnceLock::new();
+    let mentions = MENTIONS_ERROR.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(get|gets|getting|got|throws?|gives?|giving|keeps?|it|this|still)\b[^.?!\n]{0,40}\b(an? )?(error|exception|panic|crash|fail|fails|failed|failing|failure)\b|\b(doesn'?t|won'?t|does not|will not|can'?t) (compile|build|run|start|work)\b",
+        )
+        .expect("static regex")
+    });
+    let error_text = ERROR_TEXT.get_or_init(|| {
+        Regex::new(
+            r"(?m)(error(\[E\d+\])?:|Error:|Exception|panicked at|Traceback|FAILED|^\s+at |:\d+:\d+|exit (code|status):? \d+|```)",
+        )
+        .expect("static regex")
+    });
+    if !mentions.is_match(prompt) || error_text.is_match(prompt) {
+        return None;
+    }
+    Some(LintIssue {
+        message: "The prompt mentions an error but doesn't include the error message".to_string(),
+        fix: None,
+    })
+}
+
+/// Paths that don't exist, with the closest one that does when there is one
+fn check_missing_files(prompt: &str, root: &Path) -> Vec<LintIssue> {
+    static CREATES: OnceLock<Regex> = OnceLock::new();
+    let creates = CREATES
+        .get_or_init(|| {
+            Regex::new(r"(?i)\b(create|new|add|write|generate|scaffold|make)\b")
+                .expect("static regex")
+        })
+        .is_match(prompt);
+
+    let mut files: Option<Vec<PathBuf>> = None;
+    let mut issues = Vec::new();
+    let mut seen = Vec::new();
+    for path in prompt.split_whitespace().filter_map(mentioned_path) {
+        if seen.contains(&path) || root.join(&path).exists() {
+            continue;
+        }
+        seen.push(path.clone());
+        let files = files.get_or_insert_with(|| workspace_files(root));
+        let closest = closest_path(&path, files);
+        // A prompt about new files names paths that don't exist yet
+        if creates && closest.is_none() {
+            continue;
+        }
+        issues.push(LintIssue {
+            message: format!("{} doesn't exist", path),
+            fix: closest.map(|closest| FixUp {
+                label: format!("Use {} instead of {}", closest, path),
+                prompt: replace_word(prompt, &path, &closest),
+            }),
+        });
+    }
+    issues
+}
+
+/// The file path `word` names, if it looks like one: a relative or absolute
+/// path whose last component has a known extension, stripped of quotes,
+/// punctuation and a `:line` suffix
+fn mentioned_path(word: &str) -> Option<String> {
+    if word.contains("://") || word.starts_with('~') || word.starts_with('-') {
+        return None;
+    }
+    let word = word.trim_matches(|c: char| "`'\"()[]{}<>,;!?".contains(c));
+    let word = word.trim_end_matches(['.', ':']);
+    // src/main.rs:42 or src/main.rs:42:7
+    let word = word
+        .split_once(':')
+        .filter(|(_, line)| line.split(':').all(|n| n.parse::<u32>().is_ok()))
+        .map_or(word, |(path, _)| path);
+    let (_, extension) = word.rsplit_once('.')?;
+    if !FILE_EXTENSIONS.contains(&extension) || word.starts_with('.') && !word.contains('/') {
+        return None;
+    }
+    Some(word.to_string())
+}
+
+/// Files under `root`, relative to it
+fn workspace_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || !(name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref()))
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .take(MAX_FILES)
+        .filter_map(|entry| entry.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect()
+}
+
+/// The workspace file `path` most likely meant: same name elsewhere, or a
+/// name a typo or two away.  `None` unless one file is closest.
+fn closest_path(path: &str, files: &[PathBuf]) -> Option<String> {
+    let name = Path::new(path).file_name()?.to_string_lossy().to_string();
+    let mut best: Option<(usize, String)> = None;
+    let mut tied = false;
+    for file in files {
+        let Some(file_name) = file.file_name().map(|n| n.to_string_lossy()) else {
+            continue;
+        };
+        let name_distance = edit_distance(&name, &file_name);
+        if name_distance > 2 || (name_distance > 0 && name.len() < 5) {
+            continue;
+        }
+        let candidate = file.to_string_lossy().to_string();
+        let distance = edit_distance(path, &candidate);
+        match &best {
+            Some((best_distance, _)) if distance > *best_distance => {}
+            Some((best_distance, _)) if distance == *best_distance => tied = true,
+            _ => {
+                best = Some((distance, candidate));
+                tied = false;
+            }
+        }
+    }
+    best.filter(|_| !tied).map(|(_, path)| path)
+}
+
+/// Levenshtein distance in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// `prompt` with each whitespace-separated mention of `from` replaced
+fn replace_word(prompt: &str, from: &str, to: &str) -> String {
+    let re = Regex::new(&format!(r"(^|[\s`'\x22(]){}", regex::escape(from))).expect("escaped path");
+    re.replace_all(prompt, |captures: &regex::Captures| {
+        format!("{}{}", &captures[1], to)
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/cli")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("src/cli/repl.rs"), "\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_productive_prompts_pass() {
+        let dir = workspace();
+        for prompt in [
+            "Why does `src/main.rs` print nothing?",
+            "Explain how error handling works in Rust",
+            "Create src/cli/status.rs with a status bar widget",
+            "cargo build fails:\nerror[E0425]: cannot find value `x` in this scope",
+            "Review this diff:\n@@ -1 +1 @@\n-a\n+b",
+        ] {
+            assert_eq!(lint(prompt, dir.path()), vec![], "{}", prompt);
+        }
+    }
+
+    #[test]
+    fn test_missing_diff_and_error() {
+        let dir = workspace();
+        let issues = lint("Can you review this diff?", dir.path());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("diff"));
+        assert_eq!(issues[0].fix, None, "not a git repository");
+
+        let issues = lint("I keep getting an error when I run the tests", dir.path());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("error message"));
+        assert_eq!(lint("the build doesn't compile, help", dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_missing_file_suggests_the_closest() {
+        let dir = workspace();
+        let issues = lint(
+            "What does src/mian.rs do? Compare with repl.rs.",
+            dir.path(),
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "src/mian.rs doesn't exist");
+        let fix = issues[0].fix.as_ref().unwrap();
+        assert_eq!(
+            fix.prompt,
+            "What does src/main.rs do? Compare with repl.rs."
+        );
+        // Same name in another directory
+        let fix = issues[1].fix.as_ref().unwrap();
+        assert_eq!(fix.label, "Use src/cli/repl.rs instead of repl.rs");
+
+        let issues = lint("Look at `src/cli/missing_module.rs:12`", dir.path());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].fix, None);
+    }
+
+    #[test]
+    fn test_mentioned_path() {
+        assert_eq!(
+            mentioned_path("(src/lib.rs:10:4),"),
+            Some("src/lib.rs".to_string())
+        );
+        assert_eq!(
+            mentioned_path("Cargo.toml."),
+            Some("Cargo.toml".to_string())
+        );
+        assert_eq!(mentioned_path("https://example.com/a.html"), None);
+        assert_eq!(mentioned_path("and/or"), None);
+        assert_eq!(mentioned_path("e.g."), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
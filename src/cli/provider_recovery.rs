@@ -0,0 +1,263 @@
+// Provider recovery — what to do when a teacher call fails
+//
+// A failed teacher call used to end the turn on a raw error line.  Now the
+// REPL (and `finch query` on a terminal) shows the error in a dialog that
+// offers a way on:
+//
+//   * retry the same request
+//   * switch to another configured cloud provider and retry there
+//   * fall back to the local model, when one is loaded
+//   * copy the failed request, redacted, for a bug report
+//
+// The conversation is kept as it was, so nothing before the failure (tool
+// calls included) runs again.  Esc leaves the error as it is.
+
+use crate::claude::types::Message;
+use crate::cli::tui::{Dialog, DialogOption, DialogResult, DialogType};
+use crate::config::ProviderEntry;
+use crate::providers::create_provider_from_entry;
+use crate::tools::types::ToolDefinition;
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Longest error shown in the dialog body
+const MAX_ERROR_CHARS: usize = 600;
+
+/// A way on after a failed teacher call
+#[derive(Debug, Clone)]
+pub enum Recovery {
+    /// Send the same request again
+    Retry,
+    /// Make this provider the teacher and send the request there
+    Switch(ProviderEntry),
+    /// Answer with the local model
+    Local,
+    /// Copy the failed request for debugging
+    CopyRequest,
+}
+
+/// The recovery dialog and the action behind each of its options
+pub struct RecoveryMenu {
+    pub dialog: Dialog,
+    actions: Vec<Recovery>,
+}
+
+impl RecoveryMenu {
+    /// Options after `failed` (a provider name) failed with `error`
+    pub fn new(
+        failed: &str,
+        error: &str,
+        alternatives: Vec<ProviderEntry>,
+        local_available: bool,
+    ) -> Self {
+        let mut options = vec![DialogOption::with_description(
+            "Retry",
+            "Send the same request again",
+        )];
+        let mut actions = vec![Recovery::Retry];
+        for entry in alternatives {
+            options.push(DialogOption::with_description(
+                format!("Switch to {}", entry.display_name()),
+                "Use it as the teacher from now on and retry there",
+            ));
+            actions.push(Recovery::Switch(entry));
+        }
+        if local_available {
+            options.push(DialogOption::with_description(
+                "Use the local model",
+                "Answer this request locally",
+            ));
+            actions.push(Recovery::Local);
+        }
+        options.push(DialogOption::with_description(
+            "Copy the request",
+            "Copy the failed request (secrets redacted) for debugging",
+        ));
+        actions.push(Recovery::CopyRequest);
+
+        let mut body: String = error.chars().take(MAX_ERROR_CHARS).collect();
+        if error.chars().count() > MAX_ERROR_CHARS {
+            body.push('…');
+        }
+        let dialog = Dialog::select(format!("{} failed", failed), options)
+            .with_body(body)
+            .with_help("Esc to leave the error as it is");
+        Self { dialog, actions }
+    }
+
+    /// The action the user picked, `None` when they dismissed the dialog
+    pub fn action(&self, result: &DialogResult) -> Option<&Recovery> {
+        match result {
+            DialogResult::Selected(i) => self.actions.get(*i),
+            _ => None,
+        }
+    }
+}
+
+/// Configured cloud providers that can take over from `failed`
+pub fn alternatives(providers: &[ProviderEntry], failed: &str) -> Vec<ProviderEntry> {
+    providers
+        .iter()
+        .filter(|entry| !entry.is_local())
+        .filter(|entry| {
+            !entry.provider_type().eq_ignore_ascii_case(failed)
+                && !entry.display_name().eq_ignore_ascii_case(failed)
+        })
+        .filter(|entry| create_provider_from_entry(entry).is_ok())
+        .cloned()
+        .collect()
+}
+
+/// The failed request as pretty JSON, with secrets redacted
+pub fn debug_report(
+    provider: &str,
+    error: &str,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+) -> String {
+    let mut report = serde_json::json!({
+        "finch_version": env!("CARGO_PKG_VERSION"),
+        "provider": provider,
+        "error": error,
+        "messages": messages,
+        "tools": tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+    });
+    crate::redact::active().redact_json(&mut report);
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+/// Save `report` under `~/.finch/debug/` and put it on the clipboard when
+/// there is one.  Returns a line saying where it went.
+pub fn copy_report(report: &str) -> Result<String> {
+    let dir = dirs::home_dir()
+        .context("Cannot determine home directory")?
+        .join(".finch")
+        .join("debug");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path: PathBuf = dir.join(format!(
+        "failed-request-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, report).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let copied = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(report))
+        .is_ok();
+    Ok(if copied {
+        format!(
+            "✓ Copied the failed request to the clipboard (also saved to {})",
+            path.display()
+        )
+    } else {
+        format!("✓ Saved the failed request to {}", path.display())
+    })
+}
+
+/// Show a select `dialog` as a numbered list on stderr and read the choice
+/// from stdin, for `finch query`, which runs without the TUI.  An empty
+/// line, or anything that isn't an option number, dismisses it.
+pub fn prompt_on_terminal(dialog: &Dialog) -> Result<DialogResult> {
+    let DialogType::Select { options, .. } = &dialog.dialog_type else {
+        return Ok(DialogResult::Cancelled);
+    };
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "\n{}", dialog.title)?;
+    if let Some(body) = &dialog.body {
+        for line in body.lines() {
+            writeln!(stderr, "  {}", line)?;
+        }
+    }
+    for (i, option) in options.iter().enumerate() {
+        match &option.description {
+            Some(description) => {
+                writeln!(stderr, "  {}. {} — {}", i + 1, option.label, description)?
+            }
+            None => writeln!(stderr, "  {}. {}", i + 1, option.label)?,
+        }
+    }
+    write!(stderr, "Choose 1-{} (Enter to give up): ", options.len())?;
+    stderr.flush()?;
+
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(match line.trim().parse::<usize>() {
+        Ok(n) if (1..=options.len()).contains(&n) => DialogResult::Selected(n - 1),
+        _ => DialogResult::Cancelled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(toml: &str) -> ProviderEntry {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_menu_maps_options_to_actions() {
+        let grok = entry("type = \"grok\"\napi_key = \"xai-test\"");
+        let menu = RecoveryMenu::new("claude", "HTTP 529: overloaded", vec![grok], true);
+
+        let DialogType::Select { options, .. } = &menu.dialog.dialog_type else {
+            panic!("expected a select dialog");
+        };
+        let labels: Vec<&str> = options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Retry",
+                "Switch to Grok",
+                "Use the local model",
+                "Copy the request"
+            ]
+        );
+        assert_eq!(menu.dialog.title, "claude failed");
+        assert!(matches!(
+            menu.action(&DialogResult::Selected(1)),
+            Some(Recovery::Switch(e)) if e.provider_type() == "grok"
+        ));
+        assert!(matches!(
+            menu.action(&DialogResult::Selected(3)),
+            Some(Recovery::CopyRequest)
+        ));
+        assert!(menu.action(&DialogResult::Cancelled).is_none());
+
+        // No local model loaded: the option isn't offered
+        let menu = RecoveryMenu::new("claude", "timeout", vec![], false);
+        assert!(matches!(
+            menu.action(&DialogResult::Selected(1)),
+            Some(Recovery::CopyRequest)
+        ));
+    }
+
+    #[test]
+    fn test_alternatives_skip_the_failed_and_local_providers() {
+        let providers = vec![
+            entry("type = \"claude\"\napi_key = \"sk-ant-test\""),
+            entry("type = \"openai\"\napi_key = \"sk-test\""),
+            entry("type = \"local\""),
+        ];
+        let names: Vec<&str> = alternatives(&providers, "claude")
+            .iter()
+            .map(|e| e.provider_type())
+            .collect();
+        assert_eq!(names, ["openai"]);
+    }
+
+    #[test]
+    fn test_debug_report_lists_messages_and_tools() {
+        let report = debug_report(
+            "claude",
+            "HTTP 500",
+            &[Message::user("why does the build fail?")],
+            &[],
+        );
+        let value: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(value["provider"], "claude");
+        assert_eq!(value["error"], "HTTP 500");
+        assert_eq!(value["messages"].as_array().unwrap().len(), 1);
+        assert!(value["tools"].as_array().unwrap().is_empty());
+    }
+}
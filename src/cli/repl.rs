@@ -28,15 +28,18 @@ use crate::providers::{TeacherContextConfig, TeacherSession};
 use crate::router::{ForwardReason, RouteDecision, Router};
 use crate::tools::executor::{generate_tool_signature, ApprovalSource, ToolSignature};
 use crate::tools::implementations::{
-    AnsibleTool, AskUserQuestionTool, BashTool, EditTool, EnterPlanModeTool, GlobTool, GrepTool,
-    HashCompareTool, PatchTool, PresentPlanTool, ReadTool, RestartTool, SaveAndExecTool,
-    WebFetchTool, WriteTool,
+    AnsibleTool, AskUserQuestionTool, BashTool, BrowserTool, ClipboardTool, EditTool,
+    EnterPlanModeTool, GlobTool, GrepTool, HashCompareTool, HttpTool, OutlineTool, PatchTool,
+    PresentPlanTool, ReadTool, RestartTool, RunCodeTool, SaveAndExecTool, TaskTool, WebFetchTool,
+    WebSearchTool, WriteTool,
 };
 #[cfg(target_os = "macos")]
-use crate::tools::implementations::{GuiClickTool, GuiInspectTool, GuiTypeTool};
+use crate::tools::implementations::{GuiClickTool, GuiInspectTool, GuiScreenshotTool, GuiTypeTool};
 use crate::tools::patterns::ToolPattern;
 use crate::tools::types::{ToolDefinition, ToolUse};
-use crate::tools::{PermissionManager, PermissionRule, ToolExecutor, ToolRegistry};
+use crate::tools::{
+    PermissionManager, PermissionRule, RenameCheck, ToolExecutor, ToolRegistry, ToolResultBudget,
+};
 use crate::training::batch_trainer::BatchTrainer;
 
 use super::commands::{handle_command, Command, CommandOutput};
@@ -74,7 +77,7 @@ fn terminal_width() -> usize {
 pub enum ReplMode {
     /// Normal mode - all tools require confirmation
     Normal,
-    /// Planning mode - only inspection tools allowed (read, glob, grep, web_fetch)
+    /// Planning mode - only inspection tools allowed (read, glob, grep, outline, web_fetch)
     Planning {
         task: String,
         plan_path: PathBuf,
@@ -174,6 +177,9 @@ pub struct Repl {
     // Enable the background brain agent (from config.features.brain_enabled)
     brain_enabled: bool,
 
+    // Check queries before sending them (from config.features.prompt_lint)
+    prompt_lint: bool,
+
     // Enable mDNS peer auto-discovery at startup
     auto_discover: bool,
 }
@@ -255,7 +261,12 @@ impl Repl {
 
         // Phase 4: Initialize memory system (before tool registry so we can register memory tools)
         let memory_system = if config.memory.enabled {
-            match crate::memory::MemorySystem::new(config.memory.clone()) {
+            // New memories are filed under the directory finch runs in
+            let memory_config = crate::memory::MemoryConfig {
+                project: std::env::current_dir().ok(),
+                ..config.memory.clone()
+            };
+            match crate::memory::MemorySystem::new(memory_config) {
                 Ok(system) => {
                     if is_interactive && !daemon_mode {
                         output_status!("✓ Memory system enabled");
@@ -272,18 +283,66 @@ impl Repl {
             None
         };
 
+        // Archive policy: move old conversations, memories and session files
+        // to cold storage in the background so startup isn't delayed
+        if config.archive.after_months > 0 {
+            let cutoff = crate::memory::cutoff_months_ago(config.archive.after_months);
+            let memory = memory_system.clone();
+            let archive = crate::memory::Archive::new(config.memory.archive_dir.clone());
+            let sessions_dir = config.server.sessions_dir.clone();
+            tokio::spawn(async move {
+                if let Some(memory) = memory {
+                    if let Err(e) = memory.archive_older_than(cutoff).await {
+                        tracing::warn!("Memory archive failed: {}", e);
+                    }
+                }
+                if let Some(dir) = sessions_dir {
+                    if let Err(e) = archive.archive_sessions_dir(&dir, cutoff) {
+                        tracing::warn!("Session archive failed: {}", e);
+                    }
+                }
+            });
+        }
+
+        // Decay policy: forget old, unimportant memories now and then, and
+        // merge near-duplicates
+        if let Some(memory) = &memory_system {
+            if config.memory.decay.enabled() {
+                tokio::spawn(crate::memory::decay::run_periodically(
+                    memory.clone(),
+                    config.memory.decay.clone(),
+                ));
+            }
+            if config.memory.consolidate.enabled {
+                tokio::spawn(crate::memory::consolidate::run_periodically(
+                    memory.clone(),
+                    config.memory.consolidate.clone(),
+                    config.schedule.clone(),
+                ));
+            }
+        }
+
         // Initialize tool execution system
         let mut tool_registry = ToolRegistry::new();
         tool_registry.register(Box::new(ReadTool));
         tool_registry.register(Box::new(GlobTool));
         tool_registry.register(Box::new(GrepTool));
+        tool_registry.register(Box::new(OutlineTool));
         tool_registry.register(Box::new(WebFetchTool::new()));
-        tool_registry.register(Box::new(BashTool));
+        tool_registry.register(Box::new(WebSearchTool::new()));
+        tool_registry.register(Box::new(BashTool::new()));
+        tool_registry.register(Box::new(RunCodeTool::new()));
         tool_registry.register(Box::new(EditTool));
         tool_registry.register(Box::new(PatchTool));
         tool_registry.register(Box::new(WriteTool));
         tool_registry.register(Box::new(HashCompareTool));
         tool_registry.register(Box::new(AnsibleTool));
+        if crate::tools::implementations::browser::enabled() {
+            tool_registry.register(Box::new(BrowserTool::new()));
+        }
+        if crate::tools::implementations::http::enabled() {
+            tool_registry.register(Box::new(HttpTool::new()));
+        }
 
         // Self-improvement tools
         let session_state_file = dirs::home_dir()
@@ -299,6 +358,10 @@ impl Repl {
 
         // User interaction tools
         tool_registry.register(Box::new(AskUserQuestionTool));
+        if is_interactive {
+            // The clipboard of the user at this terminal
+            tool_registry.register(Box::new(ClipboardTool));
+        }
 
         // Phase 1: Initialize LLM registry (before ToolExecutor creation)
         let llm_registry = if config.teachers.len() > 1 {
@@ -344,6 +407,7 @@ impl Repl {
                 tool_registry.register(Box::new(GuiClickTool));
                 tool_registry.register(Box::new(GuiTypeTool));
                 tool_registry.register(Box::new(GuiInspectTool));
+                tool_registry.register(Box::new(GuiScreenshotTool));
             }
         }
 
@@ -360,16 +424,35 @@ impl Repl {
             }
         }
 
-        // Session task list (TodoWrite / TodoRead)
+        // Dependency docs indexed by `finch ask-docs`
+        if crate::memory::DocsIndex::exists() {
+            match crate::memory::DocsIndex::open() {
+                Ok(index) => {
+                    use crate::tools::implementations::DocLookupTool;
+                    tool_registry.register(Box::new(DocLookupTool::new(Arc::new(index))));
+                    if is_interactive && !daemon_mode {
+                        output_status!("✓ Docs lookup registered (doc_lookup)");
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to open docs index: {}", e),
+            }
+        }
+
+        // Session task list (TodoWrite / TodoRead / TodoPromote)
         let todo_list = Arc::new(tokio::sync::RwLock::new(
             crate::tools::todo::TodoList::default(),
         ));
         {
             use crate::tools::implementations::{
-                StackClearTool, StackPushTool, StackRunTool, TodoReadTool, TodoWriteTool,
+                StackClearTool, StackPushTool, StackRunTool, TodoPromoteTool, TodoReadTool,
+                TodoWriteTool,
             };
             tool_registry.register(Box::new(TodoWriteTool::new(Arc::clone(&todo_list))));
             tool_registry.register(Box::new(TodoReadTool::new(Arc::clone(&todo_list))));
+            tool_registry.register(Box::new(TodoPromoteTool::new(
+                Arc::clone(&todo_list),
+                crate::agent::AgentConfig::resolve_tasks_path(None),
+            )));
             // Co-Forth VM — stack Arc is wired in later via with_stack()
             // NOTE: StackPopTool intentionally omitted — only the user can pop (undo).
             tool_registry.register(Box::new(StackPushTool));
@@ -377,6 +460,13 @@ impl Repl {
             tool_registry.register(Box::new(StackClearTool));
         }
 
+        // Sub-agents (spawn_task): a fresh teacher provider, like the brain's,
+        // so delegated work never shares context with the main conversation
+        match crate::providers::create_provider(&config.teachers) {
+            Ok(provider) => tool_registry.register(Box::new(TaskTool::new(Arc::from(provider)))),
+            Err(e) => tracing::warn!("spawn_task disabled: could not create provider: {}", e),
+        }
+
         // Create permission manager
         // Use config.features.auto_approve_tools to determine default rule
         let default_rule = if config.features.auto_approve_tools {
@@ -401,8 +491,10 @@ impl Repl {
                 fallback_registry.register(Box::new(ReadTool));
                 fallback_registry.register(Box::new(GlobTool));
                 fallback_registry.register(Box::new(GrepTool));
+                fallback_registry.register(Box::new(OutlineTool));
                 fallback_registry.register(Box::new(WebFetchTool::new()));
-                fallback_registry.register(Box::new(BashTool));
+                fallback_registry.register(Box::new(WebSearchTool::new()));
+                fallback_registry.register(Box::new(BashTool::new()));
                 fallback_registry.register(Box::new(RestartTool::new(session_state_file.clone())));
                 fallback_registry
                     .register(Box::new(SaveAndExecTool::new(session_state_file.clone())));
@@ -420,7 +512,14 @@ impl Repl {
             });
 
         // Add MCP support if configured (graceful - always returns even on error)
-        let executor = executor.with_mcp(&config).await;
+        let executor = executor
+            .with_mcp(&config)
+            .await
+            .with_plugins()
+            .with_result_budget(ToolResultBudget::from_features(&config.features))
+            .with_rename_check(RenameCheck::new(config.features.rename_check))
+            .with_max_parallel_tools(config.features.max_parallel_tools)
+            .with_tool_timeouts(&config.features.tool_timeouts);
 
         let tool_executor = Arc::new(tokio::sync::Mutex::new(executor));
 
@@ -439,6 +538,7 @@ impl Repl {
         let enable_summarization = config.features.enable_summarization;
         let auto_compact_enabled = config.features.auto_compact_enabled;
         let brain_enabled = config.features.brain_enabled;
+        let prompt_lint = config.features.prompt_lint;
         let auto_discover = config.client.auto_discover;
 
         // Generate tool definitions from registry (includes built-in + MCP tools)
@@ -642,6 +742,7 @@ impl Repl {
             enable_summarization,
             auto_compact_enabled,
             brain_enabled,
+            prompt_lint,
             auto_discover,
         }
     }
@@ -1072,7 +1173,7 @@ impl Repl {
                         format!(
                             "Tool '{}' is not allowed in planning mode.\n\
                              Reason: This tool can modify system state.\n\
-                             Available tools: read, glob, grep, web_fetch, present_plan, ask_user_question\n\
+                             Available tools: read, glob, grep, outline, web_fetch, present_plan, ask_user_question\n\
                              Type /approve to execute your plan with all tools enabled.",
                             tool_use.name
                         ),
@@ -1098,19 +1199,22 @@ impl Repl {
                     } else {
                         // Auto-approve read-only tools and user interaction tools when in plan mode
                         let is_plan_mode = matches!(self.mode, ReplMode::Planning { .. });
-                        let is_readonly_tool = matches!(
-                            tool_name,
-                            "read"
-                                | "Read"
-                                | "glob"
-                                | "Glob"
-                                | "grep"
-                                | "Grep"
-                                | "web_fetch"
-                                | "WebFetch"
-                                | "AskUserQuestion"
-                                | "ask_user_question"
-                        );
+                        let is_readonly_tool = crate::tools::exec_plugins::is_read_only(tool_name)
+                            || matches!(
+                                tool_name,
+                                "read"
+                                    | "Read"
+                                    | "glob"
+                                    | "Glob"
+                                    | "grep"
+                                    | "Grep"
+                                    | "outline"
+                                    | "web_fetch"
+                                    | "WebFetch"
+                                    | "web_search"
+                                    | "AskUserQuestion"
+                                    | "ask_user_question"
+                            );
 
                         is_plan_mode && is_readonly_tool
                     }
@@ -1461,8 +1565,12 @@ impl Repl {
                 Ok(crate::generators::StreamChunk::ContentBlockComplete(_block)) => {
                     // Tool block completed - ignore for now (event loop handles tools)
                 }
-                Ok(crate::generators::StreamChunk::Usage { .. }) => {
-                    // Usage metadata (input token count) — not used in this path
+                Ok(crate::generators::StreamChunk::Usage { .. })
+                | Ok(crate::generators::StreamChunk::ReasoningTokens(_)) => {
+                    // Usage metadata — not used in this path
+                }
+                Ok(crate::generators::StreamChunk::ThinkingDelta(_)) => {
+                    // Hidden chain-of-thought is only shown by the event loop TUI
                 }
                 Err(e) => {
                     return Err(e);
@@ -1588,6 +1696,7 @@ impl Repl {
                 None
             },
             self.auto_discover,
+            self.prompt_lint,
         );
 
         // Run the event loop
@@ -1872,10 +1981,32 @@ impl Repl {
                         continue;
                     }
                     // Phase 4: Memory system
-                    Command::Memory => {
+                    Command::Memory | Command::MemoryStats => {
                         self.handle_memory_stats().await?;
                         continue;
                     }
+                    Command::MemorySearch(query) => {
+                        self.handle_memory_search(&query).await?;
+                        continue;
+                    }
+                    Command::MemoryPin(id) => {
+                        self.handle_memory_edit(&id, true).await?;
+                        continue;
+                    }
+                    Command::MemoryForget(id) => {
+                        self.handle_memory_edit(&id, false).await?;
+                        continue;
+                    }
+                    Command::Incognito => {
+                        let on = !crate::incognito::is_active();
+                        crate::incognito::set(on);
+                        self.output_status(if on {
+                            "🕶 Incognito: nothing is added to memory, logs or training data"
+                        } else {
+                            "Incognito off"
+                        });
+                        continue;
+                    }
                     _ => {
                         let output = handle_command(
                             command,
@@ -2155,17 +2286,20 @@ impl Repl {
             }
             ReplMode::Planning { .. } => {
                 // Inspection tools + plan completion tools allowed
-                matches!(
-                    tool_name,
-                    "read"
-                        | "glob"
-                        | "grep"
-                        | "web_fetch"
-                        | "present_plan"
-                        | "PresentPlan"
-                        | "ask_user_question"
-                        | "AskUserQuestion"
-                )
+                crate::tools::exec_plugins::is_read_only(tool_name)
+                    || matches!(
+                        tool_name,
+                        "read"
+                            | "glob"
+                            | "grep"
+                            | "outline"
+                            | "web_fetch"
+                            | "web_search"
+                            | "present_plan"
+                            | "PresentPlan"
+                            | "ask_user_question"
+                            | "AskUserQuestion"
+                    )
             }
         }
     }
@@ -2191,7 +2325,7 @@ impl Repl {
                     self.output_status(format!("  Description: {}", desc));
                 }
             }
-            "read" => {
+            "read" | "outline" => {
                 if let Some(path) = tool_use.input["file_path"].as_str() {
                     self.output_status(format!("  File: {}", path));
                 }
@@ -2204,6 +2338,29 @@ impl Repl {
                     self.output_status(format!("  Prompt: {}", prompt));
                 }
             }
+            "web_search" => {
+                if let Some(query) = tool_use.input["query"].as_str() {
+                    self.output_status(format!("  Query: {}", query));
+                }
+            }
+            "http" => {
+                let method = tool_use.input["method"].as_str().unwrap_or("GET");
+                if let Some(url) = tool_use.input["url"].as_str() {
+                    self.output_status(format!("  {} {}", method.to_ascii_uppercase(), url));
+                }
+                if let Some(profile) = tool_use.input["auth"].as_str() {
+                    self.output_status(format!("  Auth profile: {}", profile));
+                }
+            }
+            "spawn_task" => {
+                let subagent_type = tool_use.input["subagent_type"]
+                    .as_str()
+                    .unwrap_or("general");
+                self.output_status(format!("  Subagent: {}", subagent_type));
+                if let Some(task) = tool_use.input["task"].as_str() {
+                    self.output_status(format!("  Task: {}", task));
+                }
+            }
             "grep" => {
                 if let Some(pattern) = tool_use.input["pattern"].as_str() {
                     self.output_status(format!("  Pattern: {}", pattern));
@@ -2692,8 +2849,26 @@ impl Repl {
                     persona.to_system_message()
                 };
 
-                // Augment system prompt with semantically relevant memories from previous sessions
+                // Augment system prompt with remembered facts and semantically relevant
+                // memories from previous sessions
                 let system_prompt = if let Some(ref memory) = self.memory_system {
+                    let base_system = match memory
+                        .matching_facts(query, crate::memory::facts::MAX_FACTS_INJECTED)
+                        .await
+                    {
+                        Ok(facts) if !facts.is_empty() => {
+                            let fact_block = facts
+                                .iter()
+                                .map(|f| format!("• {}", f.statement))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            format!(
+                                "{}\n\n## Known facts and preferences\n{}",
+                                base_system, fact_block
+                            )
+                        }
+                        _ => base_system,
+                    };
                     match memory.query(query, Some(5)).await {
                         Ok(memories) if !memories.is_empty() => {
                             let memory_block = memories
@@ -3128,14 +3303,16 @@ impl Repl {
             "teacher".to_string()
         };
         let tools_used = vec![]; // TODO: Track tools used during execution
-        if let Err(e) = self
-            .conversation_logger
-            .lock()
-            .await
-            .log_interaction(query, &claude_response, &model_name, &tools_used)
-            .await
-        {
-            tracing::warn!("Failed to log conversation: {}", e);
+        if !crate::incognito::is_active() {
+            if let Err(e) = self
+                .conversation_logger
+                .lock()
+                .await
+                .log_interaction(query, &claude_response, &model_name, &tools_used)
+                .await
+            {
+                tracing::warn!("Failed to log conversation: {}", e);
+            }
         }
 
         let metric = RequestMetric::new(
@@ -3179,7 +3356,11 @@ impl Repl {
             .add_assistant_message(claude_response.clone());
 
         // Phase 4: Store conversation in memory automatically
-        if let Some(ref memory) = self.memory_system {
+        if let Some(memory) = self
+            .memory_system
+            .as_ref()
+            .filter(|_| !crate::incognito::is_active())
+        {
             if let Err(e) = memory
                 .insert_conversation("user", query, Some(&model_name), None)
                 .await
@@ -3204,6 +3385,11 @@ impl Repl {
 
     /// Handle feedback commands - add weighted training example
     async fn handle_feedback(&mut self, weight: f64, note: Option<String>) -> Result<()> {
+        if crate::incognito::is_active() {
+            self.output_status("🕶 Incognito: feedback isn't recorded.");
+            return Ok(());
+        }
+
         // Check if we have a last query/response to provide feedback on
         let query = match &self.last_query {
             Some(q) => q.clone(),
@@ -3705,6 +3891,7 @@ impl Repl {
                 stats.conversation_count
             ));
             self.output_status(format!("MemTree nodes: {}", stats.tree_node_count));
+            self.output_status(format!("Stored in: {}", stats.store));
             self.output_status("");
 
             if stats.conversation_count > 0 {
@@ -3725,6 +3912,7 @@ impl Repl {
 
             self.output_status("\nMemory queries are automatically included in your prompts");
             self.output_status("for better context across sessions.");
+            self.output_status("Use /memory search <query> to see what a prompt would recall.");
         } else {
             self.output_status("Memory system is disabled.");
             self.output_status("\nTo enable memory:");
@@ -3736,6 +3924,36 @@ impl Repl {
         Ok(())
     }
 
+    /// Handle /memory search <query>: the memories recall would consider,
+    /// with the ids /memory pin and /memory forget take
+    async fn handle_memory_search(&self, query: &str) -> Result<()> {
+        let Some(memory) = &self.memory_system else {
+            self.output_status("Memory system is disabled.");
+            return Ok(());
+        };
+        let (text, filter) = crate::memory::MemoryFilter::parse(query)?;
+        let hits = memory
+            .search_where(&text, crate::cli::commands::MEMORY_SEARCH_RESULTS, &filter)
+            .await?;
+        self.output_status(crate::cli::commands::format_memory_hits(query, &hits));
+        Ok(())
+    }
+
+    /// Handle /memory pin <id> (`pin`) and /memory forget <id>
+    async fn handle_memory_edit(&self, id: &str, pin: bool) -> Result<()> {
+        let Some(memory) = &self.memory_system else {
+            self.output_status("Memory system is disabled.");
+            return Ok(());
+        };
+        let found = if pin {
+            memory.pin(id).await?
+        } else {
+            memory.forget(id).await?
+        };
+        self.output_status(crate::cli::commands::format_memory_edit(id, pin, found));
+        Ok(())
+    }
+
     /// Handle /compact command - clear history but keep a summary
     async fn handle_compact_command(&mut self, instruction: Option<String>) -> Result<()> {
         // Get current conversation history
@@ -3910,7 +4128,7 @@ impl Repl {
         self.output_status(format!("📁 Plan will be saved to: {}", plan_path.display()));
         self.output_status("");
         self.output_status(format!("{}", "Available tools:".green()));
-        self.output_status("  read, glob, grep, web_fetch");
+        self.output_status("  read, glob, grep, outline, web_fetch");
         self.output_status(format!("{}", "Blocked tools:".red()));
         self.output_status("  bash, save_and_exec");
         self.output_status("");
@@ -3923,7 +4141,7 @@ impl Repl {
         // Add mode change notification to conversation
         self.conversation.write().await.add_user_message(format!(
             "[System: Entered planning mode for task: {}]\n\
-             Available tools: read, glob, grep, web_fetch, present_plan, ask_user_question\n\
+             Available tools: read, glob, grep, outline, web_fetch, present_plan, ask_user_question\n\
              Blocked tools: bash, save_and_exec\n\
              Please explore the codebase and generate a detailed plan.",
             task
@@ -15,7 +15,8 @@
 // impl EventLoop (in-process brain):
 //   cancel_active_brain     — cancel + optionally discard gathered context
 //   handle_typing_started   — debounce-spawns brain on partial input
-//   handle_brain_question   — shows dialog for in-process brain question
+//   handle_brain_question   — queues an in-process brain question in the inbox
+//   service_brain_inbox     — render tick: expires questions, opens one on `?`
 //   handle_brain_proposed_action — shows Yes/No dialog for brain action
 //
 // impl EventLoop (daemon brain):
@@ -24,7 +25,7 @@
 //   handle_brain_cancel     — /brain cancel <id>: cancels a daemon brain
 //   poll_daemon_brains      — 500ms poll: detects state transitions
 //   update_brain_status_bar — updates status bar brain count
-//   show_daemon_brain_question — shows question dialog from daemon brain
+//   show_daemon_brain_question — shows question dialog from daemon brain (from the inbox)
 //   show_daemon_brain_plan  — shows plan approval dialog from daemon brain
 
 // ── In-process brain handlers ─────────────────────────────────────────────────
@@ -59,29 +60,93 @@ impl EventLoop {
         tui.set_typing_words(words);
     }
 
-    /// Handle a `BrainQuestion` event: show a dialog and store the response channel.
+    /// Handle a `BrainQuestion` event: queue the question in the inbox.
     ///
-    /// If the user is currently busy (active query in flight), defer the question
-    /// until they become idle — the brain waits rather than interrupting.
+    /// The question never interrupts the user — the status line says it is
+    /// waiting and `?` opens it.  The brain gives up after QUESTION_TIMEOUT.
     async fn handle_brain_question(
         &mut self,
         question: String,
         options: Vec<String>,
         response_tx: tokio::sync::oneshot::Sender<String>,
     ) -> Result<()> {
-        tracing::debug!("[EVENT_LOOP] Brain question: {}", question);
-
-        // If a query is in flight, defer the question — don't interrupt the user.
-        let is_busy = self.active_query_id.read().await.is_some();
-        if is_busy {
-            tracing::debug!("[EVENT_LOOP] User busy — deferring brain question");
-            // Replace any older deferred question (drop it, sending no answer).
-            let _ = self.deferred_brain_question.take();
-            self.deferred_brain_question = Some((question, options, response_tx));
+        use super::brain_inbox::{InboxQuestion, QuestionSource};
+
+        tracing::debug!("[EVENT_LOOP] Brain question queued: {}", question);
+        self.brain_inbox.push(InboxQuestion {
+            question,
+            options,
+            source: QuestionSource::Local(response_tx),
+        });
+        self.refresh_brain_inbox().await;
+        Ok(())
+    }
+
+    /// Show the inbox count in the status line and arm the `?` shortcut.
+    async fn refresh_brain_inbox(&self) {
+        use crate::cli::status_bar::StatusLineType;
+
+        let line = StatusLineType::Custom("brain_inbox".to_string());
+        match self.brain_inbox.status() {
+            Some(text) => self.status_bar.update_line(line, text),
+            None => self.status_bar.remove_line(&line),
+        }
+        self.tui_renderer.lock().await.brain_questions_pending = self.brain_inbox.len();
+    }
+
+    /// Called on every render tick: drop questions no brain is waiting on
+    /// any more, and open the oldest one if the user pressed `?`.
+    async fn service_brain_inbox(&mut self) -> Result<()> {
+        use super::brain_inbox::QuestionSource;
+
+        let (open_requested, dialog_open) = {
+            let mut tui = self.tui_renderer.lock().await;
+            let requested = std::mem::take(&mut tui.pending_inbox_open);
+            (requested, tui.active_dialog.is_some())
+        };
+        if self.brain_inbox.is_empty() {
             return Ok(());
         }
 
-        self.show_brain_question_dialog(question, options, response_tx).await
+        let known = &self.known_brain_states;
+        let expired = self.brain_inbox.expire(|id| {
+            matches!(
+                known.get(&id),
+                Some(crate::server::BrainState::WaitingForInput)
+            )
+        });
+        for q in &expired {
+            self.output_manager.write_info(format!(
+                "🧠 {} stopped waiting for an answer to: {}",
+                q.brain_name(),
+                q.question
+            ));
+        }
+        let mut changed = !expired.is_empty();
+
+        if open_requested && !dialog_open {
+            if let Some(q) = self.brain_inbox.take_oldest() {
+                changed = true;
+                match q.source {
+                    QuestionSource::Local(response_tx) => {
+                        self.show_brain_question_dialog(q.question, q.options, response_tx)
+                            .await?;
+                    }
+                    QuestionSource::Daemon { id, name } => {
+                        let view = crate::server::brain_registry::PendingQuestionView {
+                            question: q.question,
+                            options: q.options,
+                        };
+                        self.show_daemon_brain_question(id, &name, view).await?;
+                    }
+                }
+            }
+        }
+
+        if changed {
+            self.refresh_brain_inbox().await;
+        }
+        Ok(())
     }
 
     /// Actually show the brain question dialog in TUI and store the response channel.
@@ -121,16 +186,6 @@ impl EventLoop {
         Ok(())
     }
 
-    /// Show any deferred brain question if the user is now idle.
-    /// Called when a query completes so the brain can ask its question.
-    pub(super) async fn maybe_show_deferred_brain_question(&mut self) -> Result<()> {
-        if let Some((question, options, response_tx)) = self.deferred_brain_question.take() {
-            tracing::debug!("[EVENT_LOOP] Showing deferred brain question now that user is idle");
-            self.show_brain_question_dialog(question, options, response_tx).await?;
-        }
-        Ok(())
-    }
-
     /// Handle a `BrainProposedAction` event: show a Yes/No approval dialog.
     ///
     /// The response channel is stored and resolved by the render tick after the
@@ -381,10 +436,19 @@ impl EventLoop {
             }
         }
 
-        // Phase 3: show dialogs / inject summaries (needs &mut self, no ipc_client borrow held)
+        // Phase 3: queue questions, show plans / inject summaries (needs &mut self,
+        // no ipc_client borrow held)
         for d in details {
             if let Some(q) = d.question {
-                self.show_daemon_brain_question(d.id, &d.name, q).await?;
+                self.brain_inbox.push(super::brain_inbox::InboxQuestion {
+                    question: q.question,
+                    options: q.options,
+                    source: super::brain_inbox::QuestionSource::Daemon {
+                        id: d.id,
+                        name: d.name,
+                    },
+                });
+                self.refresh_brain_inbox().await;
             } else if let Some(p) = d.plan {
                 self.show_daemon_brain_plan(d.id, &d.name, p).await?;
             } else if let Some(summary) = d.final_summary {
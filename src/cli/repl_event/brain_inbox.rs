@@ -0,0 +1,173 @@
+//! Brain inbox — questions from brains, queued until the user wants them.
+//!
+//! Brains ask clarifying questions whenever they hit an ambiguity, which is
+//! usually while the user is typing.  Instead of a dialog popping up over the
+//! prompt, the event loop queues the question here and the status line shows
+//! "🧠 1 question pending — press ? to answer".  `?` on an empty prompt opens
+//! the oldest question.
+//!
+//! Questions expire on their own.  An in-process brain stops waiting after
+//! [`crate::brain::QUESTION_TIMEOUT`], which closes its response channel, and
+//! a daemon brain leaves `WaitingForInput` when its wait runs out.  Either way
+//! the brain carries on without an answer and [`BrainInbox::expire`] drops
+//! the question.
+
+use std::collections::VecDeque;
+
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Who is waiting for the answer
+#[derive(Debug)]
+pub enum QuestionSource {
+    /// The in-process brain, answered on this channel
+    Local(oneshot::Sender<String>),
+    /// A daemon brain, answered with `POST /v1/brains/:id/answer`
+    Daemon { id: Uuid, name: String },
+}
+
+/// A queued brain question
+#[derive(Debug)]
+pub struct InboxQuestion {
+    pub question: String,
+    pub options: Vec<String>,
+    pub source: QuestionSource,
+}
+
+impl InboxQuestion {
+    /// Short name of the asking brain, for messages
+    pub fn brain_name(&self) -> &str {
+        match &self.source {
+            QuestionSource::Local(_) => "brain",
+            QuestionSource::Daemon { name, .. } => name,
+        }
+    }
+}
+
+/// Unanswered brain questions, oldest first
+#[derive(Debug, Default)]
+pub struct BrainInbox {
+    questions: VecDeque<InboxQuestion>,
+}
+
+impl BrainInbox {
+    /// Queue a question.  A daemon brain has one question at a time, so a
+    /// newer one from the same brain replaces the old.
+    pub fn push(&mut self, question: InboxQuestion) {
+        if let QuestionSource::Daemon { id, .. } = &question.source {
+            let id = *id;
+            self.questions.retain(
+                |q| !matches!(&q.source, QuestionSource::Daemon { id: other, .. } if *other == id),
+            );
+        }
+        self.questions.push_back(question);
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    /// Remove and return the questions nobody is waiting on any more: local
+    /// ones whose brain timed out or was cancelled, and daemon ones whose
+    /// brain `still_waiting` says no longer waits.
+    pub fn expire(&mut self, still_waiting: impl Fn(Uuid) -> bool) -> Vec<InboxQuestion> {
+        let (live, expired): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.questions)
+            .into_iter()
+            .partition(|q| match &q.source {
+                QuestionSource::Local(tx) => !tx.is_closed(),
+                QuestionSource::Daemon { id, .. } => still_waiting(*id),
+            });
+        self.questions = live;
+        expired.into()
+    }
+
+    /// Take the oldest question to show the user
+    pub fn take_oldest(&mut self) -> Option<InboxQuestion> {
+        self.questions.pop_front()
+    }
+
+    /// Status line text, `None` when the inbox is empty
+    pub fn status(&self) -> Option<String> {
+        match self.questions.len() {
+            0 => None,
+            1 => Some("🧠 1 question pending — press ? to answer".to_string()),
+            n => Some(format!("🧠 {} questions pending — press ? to answer", n)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(question: &str) -> (InboxQuestion, oneshot::Receiver<String>) {
+        let (tx, rx) = oneshot::channel();
+        let q = InboxQuestion {
+            question: question.to_string(),
+            options: Vec::new(),
+            source: QuestionSource::Local(tx),
+        };
+        (q, rx)
+    }
+
+    fn daemon(id: Uuid, question: &str) -> InboxQuestion {
+        InboxQuestion {
+            question: question.to_string(),
+            options: vec!["yes".to_string(), "no".to_string()],
+            source: QuestionSource::Daemon {
+                id,
+                name: "refactor".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_questions_queue_oldest_first() {
+        let mut inbox = BrainInbox::default();
+        assert_eq!(inbox.status(), None);
+
+        let (first, _rx1) = local("Which crate?");
+        let (second, _rx2) = local("Keep the old API?");
+        inbox.push(first);
+        inbox.push(second);
+        assert_eq!(
+            inbox.status().unwrap(),
+            "🧠 2 questions pending — press ? to answer"
+        );
+        assert_eq!(inbox.take_oldest().unwrap().question, "Which crate?");
+        assert_eq!(inbox.len(), 1);
+
+        // A daemon brain's newer question replaces its older one
+        let id = Uuid::new_v4();
+        inbox.push(daemon(id, "Tests too?"));
+        inbox.push(daemon(id, "Docs too?"));
+        assert_eq!(inbox.len(), 2);
+        assert_eq!(inbox.take_oldest().unwrap().question, "Keep the old API?");
+        assert_eq!(inbox.take_oldest().unwrap().brain_name(), "refactor");
+        assert!(inbox.is_empty());
+    }
+
+    #[test]
+    fn test_unanswered_questions_expire() {
+        let mut inbox = BrainInbox::default();
+        let (waiting, _rx) = local("Which crate?");
+        let (gave_up, rx) = local("Which module?");
+        inbox.push(waiting);
+        inbox.push(gave_up);
+        // The brain stopped waiting for this one
+        drop(rx);
+        let still = Uuid::new_v4();
+        let moved_on = Uuid::new_v4();
+        inbox.push(daemon(still, "Tests too?"));
+        inbox.push(daemon(moved_on, "Docs too?"));
+
+        let expired = inbox.expire(|id| id == still);
+        let expired: Vec<_> = expired.iter().map(|q| q.question.as_str()).collect();
+        assert_eq!(expired, ["Which module?", "Docs too?"]);
+        assert_eq!(inbox.len(), 2);
+    }
+}
@@ -31,12 +31,11 @@ use crate::cli::tui::{spawn_input_task, TuiRenderer};
 use crate::feedback::{FeedbackEntry, FeedbackLogger, FeedbackRating};
 use crate::generators::Generator;
 use crate::local::LocalGenerator;
-use crate::memory::NeuralEmbeddingEngine;
 use crate::models::bootstrap::GeneratorState;
 use crate::models::tokenizer::TextTokenizer;
 use crate::router::Router;
 use crate::tools::executor::ToolExecutor;
-use crate::tools::types::ToolDefinition;
+use crate::tools::types::{ToolCancelled, ToolDefinition};
 
 use super::events::ReplEvent;
 use super::query_processor::{
@@ -116,6 +115,11 @@ pub struct EventLoop {
     /// Tool results collected per query (query_id -> Vec<(tool_id, result)>)
     tool_results: ToolResultsMap,
 
+    /// Cancelled queries whose tool calls are still winding down
+    /// (query_id -> tools_pending), so their results can be recorded
+    /// without asking the model to continue
+    cancelled_tools: std::collections::HashMap<Uuid, usize>,
+
     /// Currently active query ID (for cancellation)
     active_query_id: Arc<RwLock<Option<Uuid>>>,
 
@@ -149,9 +153,12 @@ pub struct EventLoop {
     /// Feedback logger — writes rated responses to ~/.finch/feedback.jsonl
     feedback_logger: Option<FeedbackLogger>,
 
-    /// Metrics logger — reads from ~/.finch/metrics/ for /metrics command
+    /// Metrics logger — records LLM calls/failures and serves /metrics
     metrics_logger: Option<crate::metrics::MetricsLogger>,
 
+    /// Alert rules from `[[alerts]]` in config (`None` when none are configured)
+    alert_engine: Option<crate::metrics::AlertEngine>,
+
     /// Memory system for semantic recall across sessions
     memory_system: Option<Arc<crate::memory::MemorySystem>>,
 
@@ -188,6 +195,10 @@ pub struct EventLoop {
     /// From config.client.auto_discover.
     auto_discover: bool,
 
+    /// Whether to check queries for likely slips before sending them.
+    /// From config.features.prompt_lint.
+    prompt_lint: bool,
+
     /// Provider used by the brain (background context-gathering agent).
     /// `None` when the brain is disabled (config flag) or no cloud provider is available.
     brain_provider: Option<Arc<dyn crate::providers::LlmProvider>>,
@@ -233,9 +244,8 @@ pub struct EventLoop {
     pending_daemon_brain_plan: bool,
     pending_daemon_brain_plan_id: Option<Uuid>,
 
-    /// Deferred brain question: held when a BrainQuestion arrives while the user
-    /// is busy (active query in flight).  Shown when the user becomes idle.
-    deferred_brain_question: Option<(String, Vec<String>, tokio::sync::oneshot::Sender<String>)>,
+    /// Brain questions waiting for the user to press `?` (in-process and daemon).
+    brain_inbox: super::brain_inbox::BrainInbox,
 
     /// Co-Forth shared stack: items pushed by the user (text) or by the AI (Push tool).
     /// Arc<Mutex> so the tool executor can write to it during generation.
@@ -253,6 +263,10 @@ pub struct EventLoop {
     /// Stack state is cleared between evals; only the dictionary persists.
     forth_vm: crate::coforth::Forth,
 
+    /// Peer tokens from `forth_vm.peer_meta` (addr → token), refreshed on
+    /// every render tick so the vocabulary poll can authenticate to peers
+    peer_tokens: Arc<std::sync::RwLock<std::collections::HashMap<String, String>>>,
+
     /// Undo history for Forth definitions.
     /// Each entry is a snapshot taken just before an eval (or /define).
     /// `/undefine` (or Ctrl+Z in Forth context) pops and restores.
@@ -264,6 +278,9 @@ pub struct EventLoop {
     /// Word names auto-compiled from the vocabulary library (not user-authored).
     /// Excluded from the vocab section sent to the AI so the prompt stays small.
     auto_compiled_word_names: std::collections::HashSet<String>,
+
+    /// Context composition of the most recent request (shown by /context).
+    context_breakdown: Option<crate::cli::context_breakdown::ContextBreakdown>,
 }
 
 /// View mode for the REPL
@@ -467,6 +484,30 @@ fn looks_like_natural_language(s: &str) -> bool {
     trimmed.chars().next().map(|c| c.is_uppercase()).unwrap_or(false)
 }
 
+/// Status bar line for a model reload in progress
+fn reload_status_line(event: &crate::server::ReloadEvent) -> Option<String> {
+    let crate::server::ReloadEvent::Progress {
+        stage,
+        model_name,
+        progress,
+    } = event
+    else {
+        return None;
+    };
+    let model = model_name.as_deref().unwrap_or("local model");
+    Some(match (stage.as_str(), progress.download_percent) {
+        ("downloading", Some(percent)) => format!(
+            "⏳ Downloading {}: {:.0}% ({}/{} files)",
+            model,
+            percent,
+            progress.files_done,
+            progress.files_done + progress.files_remaining
+        ),
+        ("initializing", _) => "⏳ Unloading the local model…".to_string(),
+        _ => format!("⏳ Loading {}…", model),
+    })
+}
+
 fn extract_channel_forth(msg: &str) -> Option<String> {
     if !msg.starts_with('[') { return None; }
     let close = msg.find(']')?;
@@ -522,6 +563,7 @@ impl EventLoop {
         auto_compact_enabled: bool,
         brain_provider: Option<Arc<dyn crate::providers::LlmProvider>>,
         auto_discover: bool,
+        prompt_lint: bool,
     ) -> Self {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
@@ -590,6 +632,7 @@ impl EventLoop {
             streaming_enabled,
             tool_coordinator,
             tool_results: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            cancelled_tools: std::collections::HashMap::new(),
             active_query_id: Arc::new(RwLock::new(None)),
             pending_approvals: Arc::new(RwLock::new(std::collections::HashMap::new())),
             ipc_client,
@@ -603,6 +646,7 @@ impl EventLoop {
             metrics_logger: dirs::home_dir()
                 .map(|h| h.join(".finch").join("metrics"))
                 .and_then(|p| crate::metrics::MetricsLogger::new(p).ok()),
+            alert_engine: None, // built from config in run()
             memory_system,
             session_label,
             cwd: String::new(), // populated at the start of run()
@@ -613,12 +657,13 @@ impl EventLoop {
             enable_summarization,
             auto_compact_enabled,
             auto_discover,
+            prompt_lint,
             brain_provider,
             brain_context: Arc::new(RwLock::new(None)),
             active_brain: Arc::new(RwLock::new(None)),
             pending_brain_question_tx: None,
             pending_brain_question_options: Vec::new(),
-            deferred_brain_question: None,
+            brain_inbox: Default::default(),
             pending_brain_action_tx: None,
             pending_brain_action_command: None,
             known_brain_states: std::collections::HashMap::new(),
@@ -635,9 +680,11 @@ impl EventLoop {
             poset,
             plan_word: None,
             forth_vm: crate::coforth::Library::precompiled_vm(),
+            peer_tokens: Arc::default(),
             forth_undo: Vec::new(),
             push_rx: crate::server::handlers::PUSH_INBOX.subscribe(),
             auto_compiled_word_names: std::collections::HashSet::new(),
+            context_breakdown: None,
         }
     }
 
@@ -702,6 +749,16 @@ impl EventLoop {
             }
         }
 
+        // Metric alerts: evaluate once at startup so an already-firing rule
+        // (e.g. today's spend) shows its banner immediately.
+        if let Ok(cfg) = crate::config::load_config() {
+            let engine = crate::metrics::AlertEngine::new(&cfg.alerts, Some(cfg.metrics_dir));
+            if !engine.is_empty() {
+                self.alert_engine = Some(engine);
+                self.check_alerts();
+            }
+        }
+
         // Apply auto-compact setting to the conversation history
         if !self.auto_compact_enabled {
             self.conversation.write().await.set_auto_compact(false);
@@ -715,10 +772,13 @@ impl EventLoop {
         // Initialize plan mode indicator (starts in Normal mode)
         self.update_plan_mode_indicator(&crate::cli::repl::ReplMode::Normal);
 
+        // A project listed under `[incognito] projects` starts incognito
+        self.update_incognito_indicator();
+
         // Set initial memory context in status bar
         if let Some(ref mem) = self.memory_system {
             if let Ok(stats) = mem.stats().await {
-                let engine = if NeuralEmbeddingEngine::find_in_cache().is_some() {
+                let engine = if mem.embedding_model().is_some() {
                     "neural"
                 } else {
                     "tfidf"
@@ -728,6 +788,25 @@ impl EventLoop {
                     format!("🧠 {}  ·  {} memories", engine, stats.conversation_count),
                 );
             }
+
+            // Memories embedded by another model are re-embedded in the
+            // background, with progress on the status bar
+            let line = crate::cli::status_bar::StatusLineType::Custom("reembed".to_string());
+            let (status_bar, progress_line) = (self.status_bar.clone(), line.clone());
+            let task = mem.spawn_reembedding(move |done, total| {
+                status_bar.update_line(
+                    progress_line.clone(),
+                    format!("🧠 re-embedding memories  ·  {}/{}", done, total),
+                )
+            });
+            let status_bar = self.status_bar.clone();
+            tokio::spawn(async move {
+                match task.await {
+                    Ok(Err(e)) => status_bar
+                        .update_line(line, format!("⚠ re-embedding memories failed: {}", e)),
+                    _ => status_bar.remove_line(&line),
+                }
+            });
         }
 
         // Set initial terminal window/tab title (no topic yet on fresh start)
@@ -933,6 +1012,9 @@ s" it is ours"      s" -1 is ours"     argue
         // Brain poll interval (500ms) - polls daemon for active brain state transitions
         let mut brain_poll_interval = tokio::time::interval(Duration::from_millis(500));
 
+        // Backlog poll interval (5 seconds) - checks off promoted todos the agent finished
+        let mut backlog_poll_interval = tokio::time::interval(Duration::from_secs(5));
+
         // Flag to control the loop
         let mut should_exit = false;
 
@@ -994,6 +1076,7 @@ s" it is ours"      s" -1 is ours"     argue
                         ReplEvent::PosetComplete { result: Err(_) } => "PosetComplete(err)",
                         ReplEvent::PeersDiscovered(_) => "PeersDiscovered",
                         ReplEvent::VocabSync(_) => "VocabSync",
+                        ReplEvent::ContextMeasured(_) => "ContextMeasured",
                     };
                     tracing::debug!("[EVENT_LOOP] Received event: {}", event_name);
                     tracing::debug!("Received event: {:?}", event);
@@ -1008,6 +1091,9 @@ s" it is ours"      s" -1 is ours"     argue
 
                 // Periodic rendering
                 _ = render_interval.tick() => {
+                    if let Ok(mut shared) = self.peer_tokens.write() {
+                        *shared = self.forth_peer_tokens();
+                    }
                     // Slowly rotate the poset 3D view (0.008 rad/tick ≈ 1 full turn per ~12s)
                     self.poset.lock().await.rotate(0.008, 0.0);
 
@@ -1021,6 +1107,9 @@ s" it is ours"      s" -1 is ours"     argue
                         }
                     }
 
+                    // Expire unanswered brain questions; open the next one on `?`
+                    self.service_brain_inbox().await?;
+
                     // Check for pending dialog result (tool approval OR brain question)
                     {
                         let mut tui = self.tui_renderer.lock().await;
@@ -1182,9 +1271,16 @@ s" it is ours"      s" -1 is ours"     argue
                         tracing::debug!("Brain poll error (non-fatal): {}", e);
                     }
                 }
+
+                // Backlog poll (5s) — promoted todos finished by `finch agent`
+                _ = backlog_poll_interval.tick() => {
+                    self.sync_promoted_todos().await;
+                }
             }
         }
 
+        self.remember_session_episode().await;
+
         // Normal exit — shut down TUI and restore terminal before returning.
         {
             let mut tui = self.tui_renderer.lock().await;
@@ -1204,6 +1300,7 @@ s" it is ours"      s" -1 is ours"     argue
             if let Some(command) = Command::parse(&input) {
                 match command {
                     Command::Quit => {
+                        self.remember_session_episode().await;
                         // Restore terminal before exiting — disable raw mode, show cursor.
                         // Use try_lock to avoid deadlock if the TUI lock is held elsewhere.
                         if let Ok(mut tui) = self.tui_renderer.try_lock() {
@@ -1295,6 +1392,57 @@ s" it is ours"      s" -1 is ours"     argue
                         self.output_manager.write_info(info.format_with_warning());
                         self.render_tui().await?;
                     }
+                    Command::MemoryStats => {
+                        self.handle_memory_stats_command().await;
+                        self.render_tui().await?;
+                    }
+                    Command::MemorySearch(query) => {
+                        self.handle_memory_search_command(&query).await;
+                        self.render_tui().await?;
+                    }
+                    Command::MemoryPin(id) => {
+                        self.handle_memory_edit_command(&id, true).await;
+                        self.render_tui().await?;
+                    }
+                    Command::MemoryForget(id) => {
+                        self.handle_memory_edit_command(&id, false).await;
+                        self.render_tui().await?;
+                    }
+                    Command::Context => {
+                        self.handle_context_command().await;
+                        self.render_tui().await?;
+                    }
+                    Command::Incognito => {
+                        self.handle_incognito_command();
+                        self.render_tui().await?;
+                    }
+                    Command::Undo => {
+                        self.handle_rollback_command(Some(1)).await;
+                        self.render_tui().await?;
+                    }
+                    Command::Paste(question) => {
+                        self.handle_paste_command(question).await?;
+                    }
+                    Command::Promote(ids) => {
+                        let tasks_path = crate::agent::AgentConfig::resolve_tasks_path(None);
+                        match crate::tools::implementations::todo_tools::promote_todos(
+                            &self.todo_list,
+                            &ids,
+                            &tasks_path,
+                        )
+                        .await
+                        {
+                            Ok(summary) => self.output_manager.write_info(summary),
+                            Err(e) => self
+                                .output_manager
+                                .write_error(format!("Promote failed: {:#}", e)),
+                        }
+                        self.render_tui().await?;
+                    }
+                    Command::Rollback(n) => {
+                        self.handle_rollback_command(n).await;
+                        self.render_tui().await?;
+                    }
                     Command::Local { query } => {
                         // Handle /local command - query local model directly (bypass routing)
                         self.handle_local_query(query).await?;
@@ -1355,7 +1503,7 @@ s" it is ours"      s" -1 is ours"     argue
                                     self.output_manager.write_info(
                                         "📋 Entered plan mode.\n\
                                          You can explore the codebase using read-only tools:\n\
-                                         - Read files, glob, grep, web_fetch are allowed\n\
+                                         - Read files, glob, grep, outline, web_fetch are allowed\n\
                                          - Write, edit, bash are restricted\n\
                                          Use /plan to exit plan mode.",
                                     );
@@ -1403,6 +1551,10 @@ s" it is ours"      s" -1 is ours"     argue
                         self.handle_feedback_command(1.0, FeedbackRating::Good, note)
                             .await?;
                     }
+                    Command::ModelReload(request) => {
+                        self.handle_model_reload(request);
+                        self.render_tui().await?;
+                    }
                     Command::ModelShow => {
                         let name = self.cloud_gen.read().await.name().to_string();
                         self.output_manager
@@ -1594,7 +1746,9 @@ s" it is ours"      s" -1 is ours"     argue
                         self.handle_stack_override(word, definition).await?;
                     }
                     Command::Ask(query) => {
-                        self.execute_query(query).await?;
+                        if let Some(query) = self.lint_prompt(query).await? {
+                            self.execute_query(query).await?;
+                        }
                     }
                     Command::ForthEval(code) => {
                         self.handle_forth_eval(code).await?;
@@ -1688,7 +1842,10 @@ s" it is ours"      s" -1 is ours"     argue
         if let Some(query) = input.trim().strip_prefix("?? ").or_else(|| input.trim().strip_prefix("??")) {
             let query = query.trim().to_string();
             if !query.is_empty() {
-                self.output_manager.write_user(input.clone());
+                let Some(query) = self.lint_prompt(query).await? else {
+                    return Ok(());
+                };
+                self.output_manager.write_user(format!("?? {}", query));
                 return self.execute_query(query).await;
             }
         }
@@ -1776,21 +1933,17 @@ s" it is ours"      s" -1 is ours"     argue
         let enriched = if chat_only {
             enriched
         } else {
+            // Through HttpTransport so the daemon token goes with the request
             let daemon_addr = crate::config::constants::DEFAULT_HTTP_ADDR;
-            let shared_ctx: Option<String> = reqwest::Client::new()
-                .get(format!("http://{daemon_addr}/v1/brains/shared/shared"))
-                .timeout(std::time::Duration::from_millis(300))
-                .send()
-                .await
-                .ok()
-                .and_then(|r| {
-                    tokio::task::block_in_place(|| {
-                        tokio::runtime::Handle::current()
-                            .block_on(r.json::<serde_json::Value>())
-                    }).ok()
-                })
-                .and_then(|v| v["context"].as_str().map(|s| s.to_owned()))
-                .filter(|s| !s.trim().is_empty());
+            let shared_ctx: Option<String> = async {
+                let timeout = Duration::from_millis(300);
+                let daemon = crate::client::HttpTransport::new(daemon_addr, timeout).ok()?;
+                let response = daemon.get("/v1/brains/shared/shared", None).await.ok()?;
+                let v: serde_json::Value = response.json().await.ok()?;
+                v["context"].as_str().map(|s| s.to_owned())
+            }
+            .await
+            .filter(|s| !s.trim().is_empty());
             match shared_ctx {
                 Some(ctx) => format!("{enriched}\n\n---\n[Shared brain context:\n{ctx}]"),
                 None => enriched,
@@ -1858,7 +2011,21 @@ s" it is ours"      s" -1 is ours"     argue
         rating: FeedbackRating,
         note: Option<String>,
     ) -> Result<()> {
-        let messages = self.conversation.read().await.get_messages();
+        if crate::incognito::is_active() {
+            self.output_manager.write_info(
+                "🕶 Incognito: ratings aren't recorded. /incognito to turn it off first.",
+            );
+            self.render_tui().await?;
+            return Ok(());
+        }
+
+        let (messages, attribution) = {
+            let conversation = self.conversation.read().await;
+            (
+                conversation.get_messages(),
+                conversation.last_attribution().cloned(),
+            )
+        };
         let (last_query, last_response) = find_last_exchange(&messages);
 
         if last_response.is_empty() {
@@ -1880,6 +2047,9 @@ s" it is ours"      s" -1 is ours"     argue
         if let Some(ref n) = note {
             entry = entry.with_note(n.clone());
         }
+        if let Some(attribution) = attribution {
+            entry = entry.with_attribution(attribution);
+        }
 
         if let Some(ref logger) = self.feedback_logger {
             match logger.log(&entry) {
@@ -1964,9 +2134,6 @@ s" it is ours"      s" -1 is ours"     argue
 
     /// Handle `/provider <name>` — switch the active cloud generator.
     async fn handle_provider_switch(&mut self, name: String) -> Result<()> {
-        use crate::generators::claude::ClaudeGenerator;
-        use crate::providers::create_provider_from_entry;
-
         let target = self
             .available_providers
             .iter()
@@ -1989,21 +2156,95 @@ s" it is ours"      s" -1 is ours"     argue
                         .to_string(),
                 );
             }
-            Some(entry) => match create_provider_from_entry(&entry) {
-                Err(e) => {
+            Some(entry) => {
+                if let Err(e) = self.use_cloud_provider(&entry).await {
                     self.output_manager
                         .write_info(format!("⚠️  Failed to create provider '{}': {}", name, e));
                 }
-                Ok(provider) => {
-                    let client = crate::claude::ClaudeClient::with_provider(provider);
-                    let new_gen: Arc<dyn Generator> =
-                        Arc::new(ClaudeGenerator::new(Arc::new(client)));
-                    *self.cloud_gen.write().await = new_gen;
+            }
+        }
+        self.render_tui().await
+    }
+
+    /// Make `entry` the active cloud generator
+    async fn use_cloud_provider(&self, entry: &crate::config::ProviderEntry) -> Result<()> {
+        use crate::generators::claude::ClaudeGenerator;
+        use crate::providers::create_provider_from_entry;
+
+        let provider = create_provider_from_entry(entry)?;
+        let client = crate::claude::ClaudeClient::with_provider(provider);
+        let new_gen: Arc<dyn Generator> = Arc::new(ClaudeGenerator::new(Arc::new(client)));
+        *self.cloud_gen.write().await = new_gen;
+        self.output_manager
+            .write_info(format!("✓ Switched to provider: {}", entry.provider_type()));
+        Ok(())
+    }
+
+    /// After the teacher failed on `query_id`, offer to retry, switch
+    /// provider, fall back to the local model or copy the request (see
+    /// `provider_recovery`).  The conversation still ends where the failed
+    /// request did, so carrying on is a continuation of the same query.
+    async fn recover_from_provider_failure(
+        &mut self,
+        query_id: Uuid,
+        provider: &str,
+        error: &str,
+    ) -> Result<()> {
+        use crate::cli::provider_recovery::{self, Recovery, RecoveryMenu};
+
+        let alternatives = provider_recovery::alternatives(&self.available_providers, provider);
+        let local_ready = self.generator_state.read().await.is_ready();
+        let menu = RecoveryMenu::new(provider, error, alternatives, local_ready);
+
+        loop {
+            let chosen = {
+                self.tui_renderer
+                    .lock()
+                    .await
+                    .show_dialog(menu.dialog.clone())?
+            };
+            match menu.action(&chosen) {
+                Some(Recovery::Retry) => break,
+                Some(Recovery::Switch(entry)) => {
+                    if let Err(e) = self.use_cloud_provider(entry).await {
+                        self.output_manager.write_error(format!(
+                            "Failed to create provider '{}': {}",
+                            entry.display_name(),
+                            e
+                        ));
+                        self.render_tui().await?;
+                        continue;
+                    }
+                    break;
+                }
+                Some(Recovery::Local) => {
                     self.output_manager
-                        .write_info(format!("✓ Switched to provider: {}", entry.provider_type()));
+                        .write_info("Answering with the local model");
+                    self.spawn_query_task_with(query_id, String::new(), Arc::clone(&self.qwen_gen))
+                        .await;
+                    return self.render_tui().await;
                 }
-            },
+                Some(Recovery::CopyRequest) => {
+                    let messages = self.conversation.read().await.get_messages();
+                    let report = provider_recovery::debug_report(
+                        provider,
+                        error,
+                        &messages,
+                        &self.tool_definitions,
+                    );
+                    match provider_recovery::copy_report(&report) {
+                        Ok(line) => self.output_manager.write_info(line),
+                        Err(e) => self
+                            .output_manager
+                            .write_error(format!("Failed to save the request: {:#}", e)),
+                    }
+                    self.render_tui().await?;
+                }
+                None => return Ok(()),
+            }
         }
+
+        self.spawn_query_task(query_id, String::new()).await;
         self.render_tui().await
     }
 
@@ -2089,22 +2330,355 @@ s" it is ours"      s" -1 is ours"     argue
         Ok(())
     }
 
+    /// Handle /context command - show what fills the context window
+    ///
+    /// Uses the measurement taken for the last request; before the first one,
+    /// measures the conversation as it stands (no recalled memories yet).
+    async fn handle_context_command(&self) {
+        let breakdown = match &self.context_breakdown {
+            Some(breakdown) => breakdown.clone(),
+            None => {
+                let conversation = self.conversation.read().await;
+                crate::cli::context_breakdown::ContextBreakdown::measure(
+                    self.cloud_gen.read().await.system_prompt_chars(),
+                    &self.tool_definitions,
+                    0,
+                    &conversation.get_messages(),
+                    conversation.token_limit(),
+                )
+            }
+        };
+        self.output_manager.write_info(breakdown.render());
+    }
+
+    /// Handle /memory stats - what the memory system holds
+    async fn handle_memory_stats_command(&self) {
+        let Some(memory) = &self.memory_system else {
+            self.output_manager.write_info("Memory system is disabled.");
+            return;
+        };
+        match memory.stats().await {
+            Ok(stats) => self
+                .output_manager
+                .write_info(crate::cli::commands::format_memory_stats(&stats)),
+            Err(e) => self
+                .output_manager
+                .write_error(format!("Failed to read memory stats: {:#}", e)),
+        }
+    }
+
+    /// Handle /memory search <query> - the memories recall would consider
+    async fn handle_memory_search_command(&self, query: &str) {
+        use crate::cli::commands::{format_memory_hits, MEMORY_SEARCH_RESULTS};
+        let Some(memory) = &self.memory_system else {
+            self.output_manager.write_info("Memory system is disabled.");
+            return;
+        };
+        let (text, filter) = match crate::memory::MemoryFilter::parse(query) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.output_manager.write_error(format!("{:#}", e));
+                return;
+            }
+        };
+        match memory
+            .search_where(&text, MEMORY_SEARCH_RESULTS, &filter)
+            .await
+        {
+            Ok(hits) => self
+                .output_manager
+                .write_info(format_memory_hits(query, &hits)),
+            Err(e) => self
+                .output_manager
+                .write_error(format!("Memory search failed: {:#}", e)),
+        }
+    }
+
+    /// Handle /memory pin <id> (`pin`) and /memory forget <id>
+    async fn handle_memory_edit_command(&self, id: &str, pin: bool) {
+        let Some(memory) = &self.memory_system else {
+            self.output_manager.write_info("Memory system is disabled.");
+            return;
+        };
+        let result = if pin {
+            memory.pin(id).await
+        } else {
+            memory.forget(id).await
+        };
+        match result {
+            Ok(found) => self
+                .output_manager
+                .write_info(crate::cli::commands::format_memory_edit(id, pin, found)),
+            Err(e) => self
+                .output_manager
+                .write_error(format!("Memory update failed: {:#}", e)),
+        }
+    }
+
+    /// Handle /incognito - toggle whether this session is remembered, logged
+    /// and trained on
+    fn handle_incognito_command(&self) {
+        let on = !crate::incognito::is_active();
+        crate::incognito::set(on);
+        self.update_incognito_indicator();
+        self.output_manager.write_info(if on {
+            "🕶 Incognito: from now on nothing is added to memory, the conversation log, \
+             the prompt history or training data. /incognito again to resume."
+        } else {
+            "Incognito off: memory, logs and training resume from the next message."
+        });
+    }
+
+    /// Show or clear the incognito status line
+    fn update_incognito_indicator(&self) {
+        use crate::cli::status_bar::StatusLineType;
+        if crate::incognito::is_active() {
+            self.status_bar
+                .update_line(StatusLineType::Incognito, crate::incognito::STATUS);
+        } else {
+            self.status_bar.remove_line(&StatusLineType::Incognito);
+        }
+    }
+
+    /// Handle /paste [question] - bring the clipboard text into the
+    /// conversation: onto the stack for the next /run, or asked about
+    /// straight away when there is a question
+    async fn handle_paste_command(&mut self, question: Option<String>) -> Result<()> {
+        use crate::tools::implementations::clipboard;
+        let text = match tokio::task::spawn_blocking(clipboard::read_text).await {
+            Ok(Ok(text)) if !text.trim().is_empty() => text,
+            Ok(Ok(_)) => {
+                self.output_manager.write_info("The clipboard is empty.");
+                return self.render_tui().await;
+            }
+            Ok(Err(e)) => {
+                self.output_manager.write_error(format!("{:#}", e));
+                return self.render_tui().await;
+            }
+            Err(e) => {
+                self.output_manager
+                    .write_error(format!("Failed to read the clipboard: {}", e));
+                return self.render_tui().await;
+            }
+        };
+        let frame = format!("[clipboard]\n{}", text.trim_end());
+
+        if let Some(question) = question {
+            let query = format!("{}\n\n{}", frame, question);
+            if let Some(query) = self.lint_prompt(query).await? {
+                self.execute_query(query).await?;
+            }
+            return Ok(());
+        }
+
+        let lines = text.trim_end().lines().count();
+        self.stack.lock().await.push(frame);
+        self.output_manager.write_info(format!(
+            "📋 Pushed {} line{} from the clipboard onto the stack (/run to send)",
+            lines,
+            if lines == 1 { "" } else { "s" }
+        ));
+        self.render_tui().await
+    }
+
+    /// Remember the part of the session no episode covers yet, before finch
+    /// exits.  Waits at most `EPISODE_SUMMARY_TIMEOUT_SECS`; short sessions
+    /// and incognito ones leave no episode.
+    async fn remember_session_episode(&self) {
+        use crate::memory::episodes::MIN_EPISODE_MESSAGES;
+
+        let Some(memory) = self
+            .memory_system
+            .as_ref()
+            .filter(|m| m.records_episodes() && !crate::incognito::is_active())
+        else {
+            return;
+        };
+        let messages = {
+            let mut history = self.conversation.write().await;
+            if history.message_count() < history.episode_start() + MIN_EPISODE_MESSAGES {
+                return;
+            }
+            history.take_episode(usize::MAX)
+        };
+
+        self.output_manager
+            .write_info("Saving a summary of this session to memory…");
+        let _ = self.render_tui().await;
+        let summarizer = self.cloud_gen.read().await.clone();
+        let timeout = Duration::from_secs(crate::config::constants::EPISODE_SUMMARY_TIMEOUT_SECS);
+        let summary = super::query_processor::remember_episode(
+            memory,
+            summarizer.as_ref(),
+            &messages,
+            &self.session_label,
+        );
+        match tokio::time::timeout(timeout, summary).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::warn!("Session episode summary failed: {}", e),
+            Err(_) => tracing::warn!("Session episode summary timed out"),
+        }
+    }
+
+    /// Check off promoted todos whose backlog task `finch agent` has finished
+    async fn sync_promoted_todos(&self) {
+        if !self.todo_list.read().await.has_promoted() {
+            return;
+        }
+        let tasks_path = crate::agent::AgentConfig::resolve_tasks_path(None);
+        let backlog = match crate::agent::backlog::TaskBacklog::load(tasks_path) {
+            Ok(backlog) => backlog,
+            Err(e) => {
+                tracing::debug!("Backlog poll error (non-fatal): {:#}", e);
+                return;
+            }
+        };
+        let finished = self.todo_list.write().await.sync_from_backlog(&backlog);
+        for content in finished {
+            self.output_manager
+                .write_info(format!("✓ Agent finished: {}", content));
+        }
+    }
+
+    /// Handle /undo and /rollback [n] - revert the newest `n` file changes
+    /// made by tools, or list them when `n` is `None`
+    async fn handle_rollback_command(&self, n: Option<usize>) {
+        let journal = Arc::clone(
+            self.tool_coordinator
+                .tool_executor()
+                .lock()
+                .await
+                .edit_journal(),
+        );
+
+        let Some(n) = n else {
+            let entries = journal.entries();
+            if entries.is_empty() {
+                self.output_manager
+                    .write_info("No file changes to undo this session.");
+                return;
+            }
+            let mut listing = String::from("File changes made by tools, newest first:\n");
+            for (i, entry) in entries.iter().rev().enumerate() {
+                listing.push_str(&format!("  {:>3}. {}\n", i + 1, entry.summary()));
+            }
+            listing.push_str("/rollback <n> reverts the newest n; /undo the newest one.");
+            self.output_manager.write_info(listing);
+            return;
+        };
+
+        let rollback = journal.rollback(n);
+        for entry in &rollback.reverted {
+            self.output_manager
+                .write_info(format!("↩ Reverted {}", entry.summary()));
+        }
+        if let Some(reason) = rollback.stopped {
+            if rollback.reverted.is_empty() {
+                self.output_manager.write_error(reason);
+            } else {
+                self.output_manager.write_info(format!(
+                    "Stopped after {}: {}",
+                    rollback.reverted.len(),
+                    reason
+                ));
+            }
+        }
+    }
+
+    /// Handle /model reload - have the daemon load its local model again,
+    /// with progress in the status bar while the REPL stays usable
+    fn handle_model_reload(&self, request: crate::server::ModelReloadRequest) {
+        use crate::cli::status_bar::StatusLineType;
+        use crate::client::DaemonClient;
+
+        let output = Arc::clone(&self.output_manager);
+        let status_bar = Arc::clone(&self.status_bar);
+        output.write_info("Reloading the local model; local queries go to the cloud meanwhile.");
+        tokio::spawn(async move {
+            let client_config = crate::config::load_config()
+                .map(|config| config.client)
+                .unwrap_or_default();
+            let reloaded = async {
+                let client =
+                    DaemonClient::connect(DaemonClient::config_from_settings(&client_config))
+                        .await?;
+                client
+                    .reload_model(&request, |event| {
+                        if let Some(line) = reload_status_line(event) {
+                            status_bar.update_line(StatusLineType::DownloadProgress, line);
+                        }
+                    })
+                    .await
+            }
+            .await;
+            status_bar.remove_line(&StatusLineType::DownloadProgress);
+            match reloaded {
+                Ok(model_name) => output.write_info(format!("✓ {} loaded", model_name)),
+                Err(e) => output.write_error(format!("{:#}", e)),
+            }
+        });
+    }
+
+    /// Check a query for likely slips before it is sent (see `prompt_lint`).
+    /// Returns the query to send, possibly fixed up, or `None` when the user
+    /// chose to edit it, in which case it is back in the input box.
+    async fn lint_prompt(&self, query: String) -> Result<Option<String>> {
+        use crate::cli::tui::{Dialog, DialogOption, DialogResult};
+
+        if !self.prompt_lint {
+            return Ok(Some(query));
+        }
+        let issues = {
+            let query = query.clone();
+            let root = std::env::current_dir().unwrap_or_default();
+            tokio::task::spawn_blocking(move || crate::cli::prompt_lint::lint(&query, &root))
+                .await
+                .unwrap_or_default()
+        };
+        if issues.is_empty() {
+            return Ok(Some(query));
+        }
+
+        let body = issues
+            .iter()
+            .map(|issue| format!("⚠ {}", issue.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fixes: Vec<_> = issues.into_iter().filter_map(|i| i.fix).collect();
+        let mut options = vec![DialogOption::new("Send anyway")];
+        options.extend(fixes.iter().map(|fix| DialogOption::new(&fix.label)));
+        options.push(DialogOption::new("Edit the prompt"));
+
+        let dialog =
+            Dialog::select("This prompt may not get a useful answer", options).with_body(body);
+        let chosen = { self.tui_renderer.lock().await.show_dialog(dialog)? };
+        match chosen {
+            DialogResult::Selected(0) => Ok(Some(query)),
+            DialogResult::Selected(i) if i <= fixes.len() => Ok(Some(fixes[i - 1].prompt.clone())),
+            _ => {
+                let mut tui = self.tui_renderer.lock().await;
+                tui.input_textarea =
+                    TuiRenderer::create_clean_textarea_with_text(&format!("?? {}", query));
+                Ok(None)
+            }
+        }
+    }
+
     /// Handle /mcp refresh command - refresh tools from all servers
     async fn handle_mcp_refresh(&mut self) -> Result<()> {
         let tool_executor = self.tool_coordinator.tool_executor();
-        let executor_guard = tool_executor.lock().await;
+        let mut executor_guard = tool_executor.lock().await;
 
-        if let Some(mcp_client) = executor_guard.mcp_client() {
+        if executor_guard.mcp_client().is_some() {
             self.output_manager.write_info("Refreshing MCP tools...");
             self.render_tui().await?;
 
-            match mcp_client.refresh_all_tools().await {
-                Ok(()) => {
-                    let tools = mcp_client.list_tools().await;
-                    self.output_manager.write_info(format!(
-                        "✓ Refreshed MCP tools ({} tools available)",
-                        tools.len()
-                    ));
+            match executor_guard.refresh_mcp_tools().await {
+                Ok(count) => {
+                    // Later queries offer the re-discovered tools to the model
+                    self.tool_definitions = Arc::new(executor_guard.list_all_tools().await);
+                    self.output_manager
+                        .write_info(format!("✓ Refreshed MCP tools ({} tools available)", count));
                 }
                 Err(e) => {
                     self.output_manager
@@ -2132,6 +2706,19 @@ s" it is ours"      s" -1 is ours"     argue
 
     /// Spawn a background task to process a query
     async fn spawn_query_task(&self, query_id: Uuid, query: String) {
+        let claude_gen = self.cloud_gen.read().await.clone();
+        self.spawn_query_task_with(query_id, query, claude_gen)
+            .await;
+    }
+
+    /// Like `spawn_query_task`, with `claude_gen` answering whatever the
+    /// router doesn't keep local
+    async fn spawn_query_task_with(
+        &self,
+        query_id: Uuid,
+        query: String,
+        claude_gen: Arc<dyn Generator>,
+    ) {
         // ── Reset execution graph for a real new query (not a tool continuation) ──
         // Tool-continuation calls pass empty `query`; those extend the same graph.
         if !query.is_empty() {
@@ -2141,7 +2728,6 @@ s" it is ours"      s" -1 is ours"     argue
         }
 
         let event_tx = self.event_tx.clone();
-        let claude_gen = self.cloud_gen.read().await.clone();
         let qwen_gen = Arc::clone(&self.qwen_gen);
         let router = Arc::clone(&self.router);
         let generator_state = Arc::clone(&self.generator_state);
@@ -2299,7 +2885,11 @@ s" it is ours"      s" -1 is ours"     argue
                 self.output_manager.write_response(&response);
             }
 
-            ReplEvent::QueryFailed { query_id, error } => {
+            ReplEvent::QueryFailed {
+                query_id,
+                error,
+                provider,
+            } => {
                 // DON'T remove streaming message here - fallback providers need it!
                 // The message will be removed on StreamingComplete or stays for final error display
 
@@ -2313,6 +2903,8 @@ s" it is ours"      s" -1 is ours"     argue
                     )
                     .await;
 
+                self.record_metric(crate::metrics::RequestMetric::failure(&error, 0));
+
                 // Display error
                 self.output_manager
                     .write_error(format!("Query failed: {}", error));
@@ -2322,6 +2914,14 @@ s" it is ours"      s" -1 is ours"     argue
                     tracing::warn!("Failed to render TUI after query error: {}", e);
                 }
 
+                // A teacher failure on the live query: offer a way on
+                if let Some(provider) = provider {
+                    if *self.active_query_id.read().await == Some(query_id) {
+                        self.recover_from_provider_failure(query_id, &provider, &error)
+                            .await?;
+                    }
+                }
+
                 // DON'T clear active query - fallback might still be running
                 // It will be cleared on StreamingComplete or final failure
             }
@@ -2350,6 +2950,7 @@ s" it is ours"      s" -1 is ours"     argue
             ReplEvent::StreamingComplete {
                 query_id,
                 full_response,
+                attribution,
             } => {
                 tracing::debug!(
                     "[EVENT_LOOP] Handling StreamingComplete event"
@@ -2374,10 +2975,10 @@ s" it is ours"      s" -1 is ours"     argue
                         "[EVENT_LOOP] No tools, adding assistant message to conversation"
                     );
                     // Add complete response to conversation (only if not executing tools)
-                    self.conversation
-                        .write()
-                        .await
-                        .add_assistant_message(full_response.clone());
+                    self.conversation.write().await.add_attributed_message(
+                        crate::claude::Message::assistant(full_response.clone()),
+                        attribution,
+                    );
                     tracing::debug!("[EVENT_LOOP] Added assistant message to conversation");
 
                     // Update query state
@@ -2408,9 +3009,6 @@ s" it is ours"      s" -1 is ours"     argue
                         *active = None;
                     }
                 }
-                // Now that the user is idle, show any brain question that was deferred.
-                self.maybe_show_deferred_brain_question().await.ok();
-
                 // Record final response + save execution graph
                 if !is_executing_tools {
                     let preview = full_response
@@ -2436,6 +3034,7 @@ s" it is ours"      s" -1 is ours"     argue
                 model,
                 input_tokens,
                 output_tokens,
+                reasoning_tokens,
                 latency_ms,
             } => {
                 // Record LLM invocation in execution graph
@@ -2446,6 +3045,15 @@ s" it is ours"      s" -1 is ours"     argue
                         output_tokens,
                     },
                 );
+                self.record_metric(
+                    crate::metrics::RequestMetric::llm_call(
+                        &model,
+                        input_tokens,
+                        output_tokens,
+                        latency_ms.unwrap_or(0),
+                    )
+                    .with_reasoning_tokens(reasoning_tokens),
+                );
                 // Update status bar with live stats
                 self.status_bar
                     .update_live_stats(model, input_tokens, output_tokens, latency_ms);
@@ -2463,7 +3071,22 @@ s" it is ours"      s" -1 is ours"     argue
                 if let Some(qid) = query_id {
                     // Fire the per-query cancellation token so handle_present_plan
                     // (and any other token-aware loops) can detect the cancel immediately.
-                    self.query_states.cancel_query(qid).await;
+                    // Running tools see the same token and stop; remember how
+                    // many results are owed so they still reach the conversation.
+                    if let Some(QueryState::ExecutingTools { tools_pending, .. }) =
+                        self.query_states.cancel_query(qid).await
+                    {
+                        self.cancelled_tools.insert(qid, tools_pending);
+                    }
+
+                    // Dropping the request stops a cloud provider, but a daemon
+                    // keeps generating (and spending tokens) until told to stop
+                    let cloud_gen = self.cloud_gen.read().await.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = cloud_gen.cancel_generation().await {
+                            tracing::warn!("Failed to cancel generation: {}", e);
+                        }
+                    });
 
                     // Clear active query
                     *self.active_query_id.write().await = None;
@@ -2585,6 +3208,9 @@ s" it is ours"      s" -1 is ours"     argue
                     self.render_tui().await?;
                 }
             }
+            ReplEvent::ContextMeasured(breakdown) => {
+                self.context_breakdown = Some(breakdown);
+            }
         }
 
         Ok(())
@@ -2682,6 +3308,46 @@ s" it is ours"      s" -1 is ours"     argue
         );
     }
 
+    /// Append a metric to the store and re-evaluate alert rules.
+    fn record_metric(&mut self, metric: crate::metrics::RequestMetric) {
+        if let Some(ref logger) = self.metrics_logger {
+            if let Err(e) = logger.log(&metric) {
+                tracing::warn!("Failed to log metric: {}", e);
+            }
+        }
+        if let Some(engine) = self.alert_engine.as_mut() {
+            engine.record(&metric);
+        }
+        self.check_alerts();
+    }
+
+    /// Evaluate alert rules, notify on newly fired alerts and refresh the
+    /// status-bar banner.
+    fn check_alerts(&mut self) {
+        use crate::cli::status_bar::StatusLineType;
+        use crate::metrics::AlertEvent;
+
+        let Some(engine) = self.alert_engine.as_mut() else {
+            return;
+        };
+        match engine.check() {
+            Ok(events) => {
+                for event in &events {
+                    if let AlertEvent::Fired { name, message } = event {
+                        self.output_manager
+                            .write_error(format!("Alert '{}': {}", name, message));
+                    }
+                    engine.notify(event);
+                }
+            }
+            Err(e) => tracing::warn!("Alert check failed: {}", e),
+        }
+        match engine.banner() {
+            Some(banner) => self.status_bar.update_line(StatusLineType::Alert, banner),
+            None => self.status_bar.remove_line(&StatusLineType::Alert),
+        }
+    }
+
     /// Handle a tool result
     async fn handle_tool_result(
         &mut self,
@@ -2706,6 +3372,9 @@ s" it is ours"      s" -1 is ours"     argue
                 let (summary, body) = tool_result_to_display(&tool_name, content);
                 work_unit.complete_row_with_body(row_idx, summary, body);
             }
+            Err(e) if e.downcast_ref::<ToolCancelled>().is_some() => {
+                work_unit.fail_row(row_idx, "cancelled".to_string());
+            }
             Err(e) => {
                 // Truncate very long error messages for the row display
                 let err_str = e.to_string();
@@ -2755,17 +3424,16 @@ s" it is ours"      s" -1 is ours"     argue
             .push((tool_id, result));
 
         // Check if all tools for this query have completed
+        let results_count = self
+            .tool_results
+            .read()
+            .await
+            .get(&query_id)
+            .map(|v| v.len())
+            .unwrap_or(0);
         let metadata = self.query_states.get_metadata(query_id).await;
-        if let Some(meta) = metadata {
-            if let QueryState::ExecutingTools { tools_pending, .. } = meta.state {
-                let results_count = self
-                    .tool_results
-                    .read()
-                    .await
-                    .get(&query_id)
-                    .map(|v| v.len())
-                    .unwrap_or(0);
-
+        match metadata.map(|meta| meta.state) {
+            Some(QueryState::ExecutingTools { tools_pending, .. }) => {
                 if results_count >= tools_pending {
                     // All tools completed — mark the WorkUnit complete so the
                     // animation stops and the final content is shown.
@@ -2774,11 +3442,38 @@ s" it is ours"      s" -1 is ours"     argue
                     self.finalize_tool_execution(query_id).await?;
                 }
             }
+            Some(QueryState::Cancelled) => {
+                let pending = self.cancelled_tools.get(&query_id).copied();
+                if pending.is_some_and(|pending| results_count >= pending) {
+                    work_unit.set_complete();
+                    self.close_cancelled_tools(query_id).await;
+                }
+            }
+            _ => {}
         }
 
         Ok(())
     }
 
+    /// Record the results of a cancelled query's tool calls.
+    ///
+    /// The assistant message with the tool_use blocks is already in the
+    /// conversation, and the API rejects a history where those go unanswered,
+    /// so the results are added — but the model is not re-invoked.
+    async fn close_cancelled_tools(&mut self, query_id: Uuid) {
+        self.cancelled_tools.remove(&query_id);
+        let results = self
+            .tool_results
+            .write()
+            .await
+            .remove(&query_id)
+            .unwrap_or_default();
+        self.conversation
+            .write()
+            .await
+            .add_message(tool_result_message(results));
+    }
+
     /// Finalize tool execution (all tools complete, re-invoke Claude)
     async fn finalize_tool_execution(&mut self, query_id: Uuid) -> Result<()> {
         // Get all tool results for this query
@@ -2830,37 +3525,11 @@ s" it is ours"      s" -1 is ours"     argue
         }
 
         // ── Normal path: build ToolResult message and continue ────────────────
-        // Create a user message with proper ToolResult content blocks
-        let mut content_blocks = Vec::new();
-        for (tool_id, result) in results {
-            match result {
-                Ok(content) => {
-                    content_blocks.push(ContentBlock::ToolResult {
-                        tool_use_id: tool_id,
-                        content,
-                        is_error: None,
-                    });
-                }
-                Err(e) => {
-                    content_blocks.push(ContentBlock::ToolResult {
-                        tool_use_id: tool_id,
-                        content: e.to_string(),
-                        is_error: Some(true),
-                    });
-                }
-            }
-        }
-
         // Add tool results to conversation as a proper message
-        let tool_result_message = crate::claude::Message {
-            role: "user".to_string(),
-            content: content_blocks,
-        };
-
         self.conversation
             .write()
             .await
-            .add_message(tool_result_message);
+            .add_message(tool_result_message(results));
 
         // Spawn new query task to continue the conversation
         // This will send another request to Claude with the tool results
@@ -3189,6 +3858,13 @@ s" it is ours"      s" -1 is ours"     argue
         self.render_tui().await
     }
 
+    /// Auth tokens of the Forth VM's peers (addr → token)
+    fn forth_peer_tokens(&self) -> std::collections::HashMap<String, String> {
+        self.forth_vm.peer_meta.iter()
+            .filter_map(|(a, m)| m.token.as_ref().map(|t| (a.clone(), t.clone())))
+            .collect()
+    }
+
     async fn handle_push_message(&mut self, msg: String) -> Result<()> {
         use crossterm::style::Stylize;
         let peers = self.forth_vm.peers.clone();
@@ -3203,6 +3879,7 @@ s" it is ours"      s" -1 is ours"     argue
             &peers,
             &msg,
             from.as_deref(),
+            &self.forth_peer_tokens(),
         ).await;
         self.render_tui().await
     }
@@ -3606,6 +4283,7 @@ s" it is ours"      s" -1 is ours"     argue
         tokio::spawn(async move {
             let _ = reqwest::Client::new()
                 .post(&url)
+                .header(crate::peer_token::HEADER, crate::peer_token::TOKEN.as_str())
                 .json(&body)
                 .timeout(std::time::Duration::from_millis(300))
                 .send()
@@ -3623,21 +4301,19 @@ s" it is ours"      s" -1 is ours"     argue
     /// Tries the running daemon first (canonical shared store), falls back to file.
     async fn load_user_words(&mut self) {
         let daemon_addr = crate::config::constants::DEFAULT_HTTP_ADDR;
-        let url = format!("http://{daemon_addr}/v1/forth/vocab");
-        let daemon_source = reqwest::Client::new()
-            .get(&url)
-            .timeout(std::time::Duration::from_millis(400))
-            .send()
-            .await
-            .ok()
-            .and_then(|r| {
-                // Use spawn_blocking to safely parse JSON — works on both
-                // current-thread and multi-thread runtimes (block_in_place panics
-                // on current-thread).
-                futures::executor::block_on(r.json::<serde_json::Value>()).ok()
-            })
-            .and_then(|v| v["source"].as_str().map(|s| s.to_owned()))
-            .filter(|s: &String| !s.is_empty());
+        let daemon_source = async {
+            let response = reqwest::Client::new()
+                .get(format!("http://{daemon_addr}/v1/forth/vocab"))
+                .header(crate::peer_token::HEADER, crate::peer_token::TOKEN.as_str())
+                .timeout(Duration::from_millis(400))
+                .send()
+                .await
+                .ok()?;
+            let v: serde_json::Value = response.json().await.ok()?;
+            v["source"].as_str().map(|s| s.to_owned())
+        }
+        .await
+        .filter(|s: &String| !s.is_empty());
 
         let source = daemon_source.or_else(|| {
             let path = dirs::home_dir().map(|mut p| { p.push(".finch"); p.push("user_words.forth"); p })?;
@@ -3674,6 +4350,7 @@ s" it is ours"      s" -1 is ours"     argue
     /// a VocabSync event is sent to the event loop so the new words are compiled in.
     fn spawn_vocab_poll(&self) {
         let tx = self.event_tx.clone();
+        let peer_tokens = Arc::clone(&self.peer_tokens);
         tokio::spawn(async move {
             let daemon_addr = crate::config::constants::DEFAULT_HTTP_ADDR;
             let client = match reqwest::Client::builder()
@@ -3693,24 +4370,33 @@ s" it is ours"      s" -1 is ours"     argue
                 // Build list of vocab URLs to poll:
                 // 1. Local daemon (same-machine terminals)
                 // 2. Remote peers from the daemon's registry (machines sent to other people)
-                let mut urls: Vec<String> = vec![
+                // Each with the peer token it expects: ours for the local
+                // daemon, the one discovered for each remote peer.
+                let mut urls: Vec<(String, Option<String>)> = vec![(
                     format!("http://{daemon_addr}/v1/forth/vocab"),
-                ];
+                    Some(crate::peer_token::TOKEN.clone()),
+                )];
                 if let Ok(resp) = client.get(format!("http://{daemon_addr}/v1/registry/peers")).send().await {
                     if let Ok(peers) = resp.json::<Vec<serde_json::Value>>().await {
                         for peer in &peers {
                             if let Some(addr) = peer["addr"].as_str() {
                                 // Don't double-poll the local daemon.
                                 if addr != daemon_addr {
-                                    urls.push(format!("http://{addr}/v1/forth/vocab"));
+                                    let token =
+                                        peer_tokens.read().ok().and_then(|t| t.get(addr).cloned());
+                                    urls.push((format!("http://{addr}/v1/forth/vocab"), token));
                                 }
                             }
                         }
                     }
                 }
 
-                for url in &urls {
-                    if let Ok(resp) = client.get(url).send().await {
+                for (url, token) in &urls {
+                    let mut request = client.get(url);
+                    if let Some(token) = token {
+                        request = request.header(crate::peer_token::HEADER, token.as_str());
+                    }
+                    if let Ok(resp) = request.send().await {
                         if let Ok(val) = resp.json::<serde_json::Value>().await {
                             let version = val["version"].as_u64().unwrap_or(0);
                             let last = last_versions.entry(url.clone()).or_insert(0);
@@ -4762,14 +5448,17 @@ s" it is ours"      s" -1 is ours"     argue
             if !approved { return Ok(()); }
 
             use crate::tools::implementations::{
-                BashTool, GlobTool, GrepTool, ReadTool, WebFetchTool, WriteTool, EditTool,
+                BashTool, GlobTool, GrepTool, OutlineTool, ReadTool, WebFetchTool, WebSearchTool,
+                WriteTool, EditTool,
             };
             let mut reg = crate::tools::ToolRegistry::new();
             reg.register(Box::new(ReadTool));
             reg.register(Box::new(GlobTool));
             reg.register(Box::new(GrepTool));
-            reg.register(Box::new(BashTool));
+            reg.register(Box::new(OutlineTool));
+            reg.register(Box::new(BashTool::new()));
             reg.register(Box::new(WebFetchTool::new()));
+            reg.register(Box::new(WebSearchTool::new()));
             reg.register(Box::new(WriteTool));
             reg.register(Box::new(EditTool));
             let registry = Some(Arc::new(reg));
@@ -4896,12 +5585,24 @@ s" it is ours"      s" -1 is ours"     argue
         let tool_name = &tool_use.name;
         let summary = tool_approval_summary(&tool_use);
 
-        let options = vec![
+        let mut options = vec![
             DialogOption::new("1. Yes"),
             DialogOption::new(format!("2. Yes, and don't ask again for: {}:*", tool_name)),
             DialogOption::new("3. No"),
         ];
 
+        // File changes show their diff, whichever option is focused
+        let diff = crate::tools::change_preview::preview(&tool_use).map(|diff| match diff {
+            Ok(diff) => crate::tools::change_preview::colorize(&diff),
+            Err(e) => format!("⚠ This change would fail: {:#}", e),
+        });
+        if let Some(diff) = diff {
+            options = options
+                .into_iter()
+                .map(|option| option.with_markdown(diff.clone()))
+                .collect();
+        }
+
         let dialog = Dialog::select(format!("{}\n{}", tool_name, summary), options);
 
         // Set dialog in TUI (non-blocking - will be handled by async_input task)
@@ -5008,7 +5709,7 @@ s" it is ours"      s" -1 is ours"     argue
         self.output_manager
             .write_info(format!("{}", "Available tools:".green()));
         self.output_manager
-            .write_info("  read, glob, grep, web_fetch");
+            .write_info("  read, glob, grep, outline, web_fetch");
         self.output_manager
             .write_info(format!("{}", "Blocked tools:".red()));
         self.output_manager.write_info("  bash, save_and_exec");
@@ -5023,7 +5724,7 @@ s" it is ours"      s" -1 is ours"     argue
         // Add mode change notification to conversation
         self.conversation.write().await.add_user_message(format!(
             "[System: Entered planning mode for task: {}]\n\
-             Available tools: read, glob, grep, web_fetch, present_plan, ask_user_question\n\
+             Available tools: read, glob, grep, outline, web_fetch, present_plan, ask_user_question\n\
              Blocked tools: bash, save_and_exec\n\
              Please explore the codebase and generate a detailed plan.",
             task
@@ -5246,6 +5947,46 @@ include!("brain_handler.rs");
 
 // handle_present_plan, handle_ask_user_question, is_tool_allowed_in_mode moved to plan_handler.rs
 
+/// Build the user message that answers a batch of tool calls, one
+/// `ToolResult` content block per call (errors flagged with `is_error`).
+/// Images a tool attached to its output follow the results.
+pub(crate) fn tool_result_message(
+    results: Vec<(String, Result<String>)>,
+) -> crate::claude::Message {
+    let mut images = Vec::new();
+    let mut content: Vec<ContentBlock> = results
+        .into_iter()
+        .map(|(tool_use_id, result)| match result {
+            Ok(content) => {
+                images.extend(crate::tools::types::image_attachments(&content));
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error: None,
+                }
+            }
+            Err(e) => ContentBlock::ToolResult {
+                tool_use_id,
+                content: e.to_string(),
+                is_error: Some(true),
+            },
+        })
+        .collect();
+    for (media_type, path) in images {
+        match std::fs::read(&path) {
+            Ok(bytes) => content.push(ContentBlock::image(
+                media_type,
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes),
+            )),
+            Err(e) => tracing::warn!("Can't attach {}: {}", path.display(), e),
+        }
+    }
+    crate::claude::Message {
+        role: "user".to_string(),
+        content,
+    }
+}
+
 /// Find the most recent (query, response) pair from conversation history.
 ///
 /// Scans messages in reverse: finds the latest non-empty assistant message,
@@ -5355,6 +6096,13 @@ pub(crate) fn tool_approval_summary(tool_use: &crate::tools::types::ToolUse) ->
                 "Read file".to_string()
             }
         }
+        "outline" => {
+            if let Some(path) = tool_use.input.get("file_path").and_then(|v| v.as_str()) {
+                format!("File: {}", path)
+            } else {
+                "Outline file".to_string()
+            }
+        }
         "grep" | "Grep" => {
             if let Some(pattern) = tool_use.input.get("pattern").and_then(|v| v.as_str()) {
                 format!(
@@ -5437,6 +6185,66 @@ mod tests {
     // Pulsing animation frames used in status-bar tests.
     const THROB_FRAMES: &[&str] = &["✦", "✳", "✼", "✳"];
 
+    #[test]
+    fn test_tool_result_message_answers_every_call() {
+        let message = tool_result_message(vec![
+            ("t1".to_string(), Ok("done".to_string())),
+            (
+                "t2".to_string(),
+                Err(ToolCancelled {
+                    tool_name: "bash".to_string(),
+                }
+                .into()),
+            ),
+        ]);
+
+        assert_eq!(message.role, "user");
+        assert!(matches!(
+            &message.content[0],
+            ContentBlock::ToolResult { tool_use_id, is_error: None, .. } if tool_use_id == "t1"
+        ));
+        match &message.content[1] {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } => {
+                assert_eq!(tool_use_id, "t2");
+                assert_eq!(*is_error, Some(true));
+                assert!(content.starts_with("Cancelled by the user"));
+            }
+            other => panic!("expected a tool result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_tool_result_message_attaches_images() {
+        use crate::tools::types::{attachment_dir, image_attachment_line};
+
+        std::fs::create_dir_all(attachment_dir()).unwrap();
+        let image = attachment_dir().join(format!("test-{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&image, b"png").unwrap();
+
+        let message = tool_result_message(vec![(
+            "t1".to_string(),
+            Ok(format!("Captured\n{}", image_attachment_line(&image))),
+        )]);
+        std::fs::remove_file(&image).unwrap();
+
+        assert_eq!(message.content.len(), 2);
+        assert!(matches!(
+            message.content[0],
+            ContentBlock::ToolResult { .. }
+        ));
+        match &message.content[1] {
+            ContentBlock::Image { source } => {
+                assert_eq!(source.media_type, "image/png");
+                assert_eq!(source.data, "cG5n");
+            }
+            other => panic!("expected an image, got {:?}", other),
+        }
+    }
+
     // --- streaming status bar format ---
 
     #[test]
@@ -8,6 +8,7 @@
 //! * **Brain** — `BrainQuestion`, `BrainProposedAction`.
 //! * **Daemon** — `DaemonBrainQuestion`, `DaemonBrainProposedAction`.
 
+use crate::generators::Attribution;
 use crate::tools::executor::ToolSignature;
 use crate::tools::patterns::ToolPattern;
 use crate::tools::types::ToolUse;
@@ -36,8 +37,13 @@ pub enum ReplEvent {
     /// A query completed successfully with a response
     QueryComplete { query_id: Uuid, response: String },
 
-    /// A query failed with an error
-    QueryFailed { query_id: Uuid, error: String },
+    /// A query failed with an error; `provider` names the teacher that
+    /// failed (`None` when the local model did)
+    QueryFailed {
+        query_id: Uuid,
+        error: String,
+        provider: Option<String>,
+    },
 
     /// A tool execution completed
     ToolResult {
@@ -60,6 +66,8 @@ pub enum ReplEvent {
     StreamingComplete {
         query_id: Uuid,
         full_response: String,
+        /// Generator that produced the response
+        attribution: Attribution,
     },
 
     /// Query statistics update (for status bar)
@@ -67,6 +75,8 @@ pub enum ReplEvent {
         model: String,
         input_tokens: Option<u32>,
         output_tokens: Option<u32>,
+        /// Hidden reasoning tokens, counted apart from output
+        reasoning_tokens: Option<u32>,
         latency_ms: Option<u64>,
     },
 
@@ -109,6 +119,9 @@ pub enum ReplEvent {
     /// Daemon vocabulary changed — another terminal defined new words.
     /// Payload is the full vocab source from `GET /v1/forth/vocab`.
     VocabSync(String),
+
+    /// Context composition of the request just sent, for `/context`.
+    ContextMeasured(crate::cli::context_breakdown::ContextBreakdown),
 }
 
 #[cfg(test)]
@@ -157,6 +170,7 @@ mod tests {
         let event = ReplEvent::QueryFailed {
             query_id: id,
             error: "network timeout".to_string(),
+            provider: Some("claude".to_string()),
         };
         match event {
             ReplEvent::QueryFailed { error, .. } => assert_eq!(error, "network timeout"),
@@ -181,6 +195,7 @@ mod tests {
         let event = ReplEvent::StreamingComplete {
             query_id: id,
             full_response: "complete response".to_string(),
+            attribution: Attribution::local("Qwen2.5-3B"),
         };
         match event {
             ReplEvent::StreamingComplete { full_response, .. } => {
@@ -196,6 +211,7 @@ mod tests {
             model: "claude-sonnet-4-6".to_string(),
             input_tokens: Some(100),
             output_tokens: Some(250),
+            reasoning_tokens: Some(40),
             latency_ms: Some(1500),
         };
         match event {
@@ -203,11 +219,13 @@ mod tests {
                 model,
                 input_tokens,
                 output_tokens,
+                reasoning_tokens,
                 latency_ms,
             } => {
                 assert_eq!(model, "claude-sonnet-4-6");
                 assert_eq!(input_tokens, Some(100));
                 assert_eq!(output_tokens, Some(250));
+                assert_eq!(reasoning_tokens, Some(40));
                 assert_eq!(latency_ms, Some(1500));
             }
             _ => panic!("Wrong variant"),
@@ -220,6 +238,7 @@ mod tests {
             model: "local".to_string(),
             input_tokens: None,
             output_tokens: None,
+            reasoning_tokens: None,
             latency_ms: None,
         };
         match event {
@@ -8,6 +8,7 @@
 //! |-------------------|----------------|
 //! | [`event_loop`]    | Main `EventLoop` struct; orchestrates everything |
 //! | [`events`]        | `ReplEvent` enum — the message bus between tasks |
+//! | [`brain_inbox`]   | Brain questions queued until the user opens them with `?` |
 //! | [`plan_handler`]  | Tool handlers for `PresentPlan` / `AskUserQuestion` / mode gates |
 //! | [`query_state`]   | Per-query metadata and state machine |
 //! | [`tool_display`]  | Display/formatting helpers for tool output in the TUI |
@@ -27,6 +28,7 @@
 //! `ReplEvent::ToolResult` message.  The event loop collects all results for
 //! a query and sends the next LLM turn once every pending tool has resolved.
 
+pub mod brain_inbox;
 pub mod event_loop;
 pub mod events;
 pub mod plan_handler;
@@ -40,21 +40,24 @@ pub(crate) fn is_tool_allowed_in_mode(tool_name: &str, mode: &ReplMode) -> bool
             // Inspection tools, bash (read-only by convention, confirmed normally),
             // plan completion tools, and plan-mode meta-tools are all allowed.
             // Write/Edit remain blocked to enforce read-only exploration during planning.
-            matches!(
-                tool_name,
-                "read"
-                    | "glob"
-                    | "grep"
-                    | "web_fetch"
-                    | "bash"
-                    | "Bash"
-                    | "present_plan"
-                    | "PresentPlan"
-                    | "ask_user_question"
-                    | "AskUserQuestion"
-                    | "EnterPlanMode"
-                    | "ExitPlanMode"
-            )
+            crate::tools::exec_plugins::is_read_only(tool_name)
+                || matches!(
+                    tool_name,
+                    "read"
+                        | "glob"
+                        | "grep"
+                        | "outline"
+                        | "web_fetch"
+                        | "web_search"
+                        | "bash"
+                        | "Bash"
+                        | "present_plan"
+                        | "PresentPlan"
+                        | "ask_user_question"
+                        | "AskUserQuestion"
+                        | "EnterPlanMode"
+                        | "ExitPlanMode"
+                )
         }
     }
 }
@@ -402,7 +405,7 @@ mod tests {
     #[test]
     fn test_plan_mode_allows_read_only_tools() {
         let mode = planning_mode();
-        for tool in &["read", "glob", "grep", "web_fetch"] {
+        for tool in &["read", "glob", "grep", "web_fetch", "web_search"] {
             assert!(
                 is_tool_allowed_in_mode(tool, &mode),
                 "{} must be allowed in planning mode",
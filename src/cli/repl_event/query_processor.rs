@@ -9,6 +9,7 @@ use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 
 use crate::claude::ContentBlock;
+use crate::cli::context_breakdown::ContextBreakdown;
 use crate::cli::conversation::ConversationHistory;
 use crate::cli::output_manager::OutputManager;
 use crate::cli::repl::ReplMode;
@@ -107,6 +108,79 @@ pub(super) async fn refresh_context_strip(
     }
 }
 
+/// Ask the local model for the durable facts in one exchange and remember
+/// them (see `memory::facts`).  Runs in the background, so it never delays
+/// the response; a failure only loses this turn's facts.
+fn spawn_fact_extraction(
+    memory: Arc<crate::memory::MemorySystem>,
+    local: Arc<dyn Generator>,
+    user: String,
+    assistant: String,
+    session_label: String,
+) {
+    use crate::memory::facts;
+    tokio::spawn(async move {
+        let prompt = facts::extraction_prompt(&user, &assistant);
+        let response = match local
+            .generate(vec![crate::claude::Message::user(prompt)], None)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::debug!("Fact extraction failed: {}", e);
+                return;
+            }
+        };
+        let Some(found) = facts::parse_facts(&response.text).filter(|f| !f.is_empty()) else {
+            return;
+        };
+        match memory.remember_facts(&found, Some(&session_label)).await {
+            Ok(n) => tracing::debug!("Remembered {} facts", n),
+            Err(e) => tracing::warn!("Failed to store extracted facts: {}", e),
+        }
+    });
+}
+
+/// Summarize `messages` into an episode and remember it (see
+/// `memory::episodes`).  `false` when the summarizer's answer held no
+/// episode.
+pub(super) async fn remember_episode(
+    memory: &crate::memory::MemorySystem,
+    summarizer: &dyn Generator,
+    messages: &[crate::claude::Message],
+    session_label: &str,
+) -> anyhow::Result<bool> {
+    use crate::memory::episodes;
+    let transcript = crate::cli::conversation_compactor::format_messages_for_summary(messages);
+    let prompt = episodes::summary_prompt(&transcript);
+    let response = summarizer
+        .generate(vec![crate::claude::Message::user(prompt)], None)
+        .await?;
+    let Some(episode) = episodes::parse_episode(&response.text) else {
+        return Ok(false);
+    };
+    memory
+        .remember_episode(&episode, Some(session_label))
+        .await?;
+    Ok(true)
+}
+
+/// `remember_episode` in the background, for messages that slid out of the
+/// context window; a failure only loses this episode
+fn spawn_episode_summary(
+    memory: Arc<crate::memory::MemorySystem>,
+    summarizer: Arc<dyn Generator>,
+    messages: Vec<crate::claude::Message>,
+    session_label: String,
+) {
+    tokio::spawn(async move {
+        match remember_episode(&memory, summarizer.as_ref(), &messages, &session_label).await {
+            Ok(stored) => tracing::debug!("Episode summary stored: {}", stored),
+            Err(e) => tracing::warn!("Episode summary failed: {}", e),
+        }
+    });
+}
+
 /// Dispatch a batch of tool uses for one query turn.
 ///
 /// Called from both the streaming and non-streaming response paths — they used
@@ -145,6 +219,13 @@ pub(super) async fn dispatch_tool_uses(
     use super::tool_display::format_tool_label;
     use tokio_util::sync::CancellationToken;
 
+    // Fired by Ctrl+C; stops the interactive handlers and running tools alike
+    let cancel = query_states
+        .get_metadata(query_id)
+        .await
+        .map(|m| m.cancellation_token)
+        .unwrap_or_else(CancellationToken::new);
+
     let current_mode = mode.read().await;
     for tool_use in tool_uses {
         // Loop detection: a second identical (tool, input) call for this query means
@@ -186,7 +267,7 @@ pub(super) async fn dispatch_tool_uses(
             let error_msg = format!(
                 "Tool '{}' is not allowed in planning mode.\n\
                  Reason: This tool can modify system state.\n\
-                 Available tools: read, glob, grep, web_fetch, present_plan, ask_user_question\n\
+                 Available tools: read, glob, grep, outline, web_fetch, present_plan, ask_user_question\n\
                  Type /approve to execute your plan with all tools enabled.",
                 tool_use.name
             );
@@ -220,11 +301,7 @@ pub(super) async fn dispatch_tool_uses(
             Arc::clone(tui_renderer),
             Arc::clone(mode),
             Arc::clone(output_manager),
-            query_states
-                .get_metadata(query_id)
-                .await
-                .map(|m| m.cancellation_token)
-                .unwrap_or_else(CancellationToken::new),
+            cancel.clone(),
             Arc::clone(work_unit),
         )
         .await
@@ -241,6 +318,7 @@ pub(super) async fn dispatch_tool_uses(
                 tool_use,
                 Arc::clone(work_unit),
                 row_idx,
+                cancel.clone(),
             );
         }
     }
@@ -337,8 +415,29 @@ pub(crate) async fn process_query_with_tools(
 
     // Get conversation context, optionally injecting relevant memories
     let mut memory_recall_count: usize = 0;
+    let mut memory_chars: usize = 0;
+    let mut recalled = Vec::new();
     let messages = {
         let all_msgs = conversation.read().await.get_messages();
+        // Once a window's worth of messages has slid out since the last
+        // episode, remember what that stretch of the session was about
+        let episodes = memory_system
+            .as_ref()
+            .filter(|m| m.records_episodes() && !crate::incognito::is_active());
+        if let Some(mem) = episodes.filter(|_| max_verbatim > 0) {
+            let drop_end = all_msgs.len().saturating_sub(max_verbatim);
+            let mut history = conversation.write().await;
+            if drop_end >= history.episode_start() + max_verbatim {
+                let stretch = history.take_episode(drop_end);
+                drop(history);
+                spawn_episode_summary(
+                    Arc::clone(mem),
+                    Arc::clone(&summary_gen),
+                    stretch,
+                    session_label.clone(),
+                );
+            }
+        }
         // When summarization is enabled and messages have been dropped by the
         // sliding window, summarise them and inject as a prefix so the LLM
         // retains awareness of earlier turns.
@@ -355,30 +454,50 @@ pub(crate) async fn process_query_with_tools(
                 apply_sliding_window(all_msgs, max_verbatim)
             };
         if let Some(ref mem) = memory_system {
-            if let Ok(memories) = mem.query(&query, Some(recall_k)).await {
-                if !memories.is_empty() {
-                    memory_recall_count = memories.len();
-                    let mem_block = memories.join("\n\n---\n\n");
-                    // Inject into the last user message so the LLM sees the recalled context
-                    if let Some(last_user) = msgs.iter_mut().rev().find(|m| m.role == "user") {
-                        if let Some(ContentBlock::Text { ref mut text }) =
-                            last_user.content.first_mut()
-                        {
-                            *text = format!(
-                                "[Relevant memories from past sessions:\n\n{}]\n\n{}",
-                                mem_block, text
-                            );
-                        }
+            // Extracted facts go first: they are exact where recall is fuzzy
+            let facts = mem
+                .matching_facts(&query, crate::memory::facts::MAX_FACTS_INJECTED)
+                .await
+                .unwrap_or_default();
+            recalled = mem.recall(&query, Some(recall_k)).await.unwrap_or_default();
+            // Files and names this session already mentioned (see memory::working)
+            let working = mem.working_block(&session_label);
+            if !recalled.is_empty() || !facts.is_empty() || working.is_some() {
+                memory_recall_count = recalled.len() + facts.len();
+                let mut mem_block = working.unwrap_or_default();
+                if !facts.is_empty() {
+                    mem_block.push_str(&crate::memory::facts::format_block(&facts));
+                }
+                if !recalled.is_empty() {
+                    // Tagged so the answer can cite them (see memory::citations)
+                    mem_block.push_str(&crate::memory::citations::format_block(&recalled));
+                }
+                // Inject into the last user message so the LLM sees the recalled context
+                if let Some(last_user) = msgs.iter_mut().rev().find(|m| m.role == "user") {
+                    if let Some(ContentBlock::Text { ref mut text }) = last_user.content.first_mut()
+                    {
+                        memory_chars = mem_block.len();
+                        *text = format!("{}{}", mem_block, text);
                     }
-                    status_bar.update_line(
-                        crate::cli::status_bar::StatusLineType::MemoryContext,
-                        format!("🧠 recalled {}  ·  querying…", memory_recall_count),
-                    );
                 }
+                status_bar.update_line(
+                    crate::cli::status_bar::StatusLineType::MemoryContext,
+                    format!("🧠 recalled {}  ·  querying…", memory_recall_count),
+                );
             }
         }
         msgs
     };
+    // Named in QueryFailed so the event loop can offer another provider
+    let teacher = (!Arc::ptr_eq(&generator, &qwen_gen)).then(|| generator.name().to_string());
+    let token_limit = conversation.read().await.token_limit();
+    let _ = event_tx.send(ReplEvent::ContextMeasured(ContextBreakdown::measure(
+        generator.system_prompt_chars(),
+        &tool_definitions,
+        memory_chars,
+        &messages,
+        token_limit,
+    )));
     let caps = generator.capabilities();
 
     // Try streaming first if supported
@@ -415,6 +534,10 @@ pub(crate) async fn process_query_with_tools(
                 // Process stream (handles tools via StreamChunk::ContentBlockComplete)
                 let mut blocks = Vec::new();
                 let mut text = String::new();
+                // Thinking is shown but never joins `text`, so it stays out of
+                // history, memory and training data
+                let mut thinking_tokens: Option<u32> = None;
+                let mut reported_reasoning_tokens: Option<u32> = None;
 
                 while let Some(result) = rx.recv().await {
                     match result {
@@ -428,6 +551,16 @@ pub(crate) async fn process_query_with_tools(
                             // WorkUnit accumulates tokens for its own animated display
                             work_unit.add_tokens(&delta);
                         }
+                        Ok(StreamChunk::ThinkingDelta(delta)) => {
+                            *thinking_tokens.get_or_insert(0) +=
+                                crate::providers::reasoning::estimate_tokens(&delta);
+                            if crate::providers::reasoning::show_thinking() {
+                                work_unit.append_thinking(&delta);
+                            }
+                        }
+                        Ok(StreamChunk::ReasoningTokens(n)) => {
+                            reported_reasoning_tokens = Some(n);
+                        }
                         Ok(StreamChunk::ContentBlockComplete(block)) => {
                             tracing::debug!("Received ContentBlockComplete: {:?}", block);
                             blocks.push(block);
@@ -438,6 +571,7 @@ pub(crate) async fn process_query_with_tools(
                             let _ = event_tx.send(ReplEvent::QueryFailed {
                                 query_id,
                                 error: format!("{}", e),
+                                provider: teacher.clone(),
                             });
                             return;
                         }
@@ -455,6 +589,8 @@ pub(crate) async fn process_query_with_tools(
                 // If no tools, set_complete() is called below.
                 if !text.is_empty() {
                     work_unit.set_response(&text);
+                    work_unit.set_citations(citation_footnotes(&text, &recalled));
+                    record_recall(&memory_system, &recalled, &text).await;
                 }
 
                 // Send stats update
@@ -462,6 +598,7 @@ pub(crate) async fn process_query_with_tools(
                     model: generator.name().to_string(),
                     input_tokens: input_token_count,
                     output_tokens: Some(token_count as u32),
+                    reasoning_tokens: reported_reasoning_tokens.or(thinking_tokens),
                     latency_ms: Some(stream_start.elapsed().as_millis() as u64),
                 });
 
@@ -482,6 +619,7 @@ pub(crate) async fn process_query_with_tools(
                     .collect();
 
                 tracing::debug!("[EVENT_LOOP] Found {} tool uses", tool_uses.len());
+                let attribution = generator.attribution().await;
 
                 if !tool_uses.is_empty() {
                     tracing::debug!("[EVENT_LOOP] Tools detected, updating query state");
@@ -506,7 +644,10 @@ pub(crate) async fn process_query_with_tools(
                         content: blocks.clone(),
                     };
                     tracing::debug!("[EVENT_LOOP] Acquiring conversation write lock...");
-                    conversation.write().await.add_message(assistant_message);
+                    conversation
+                        .write()
+                        .await
+                        .add_attributed_message(assistant_message, attribution);
                     tracing::debug!(
                         "[EVENT_LOOP] Assistant message added, spawning tool executions"
                     );
@@ -543,30 +684,33 @@ pub(crate) async fn process_query_with_tools(
                 tracing::debug!(
                     "[EVENT_LOOP] No tools found, adding assistant message to conversation"
                 );
-                conversation
-                    .write()
-                    .await
-                    .add_assistant_message(text.clone());
+                conversation.write().await.add_attributed_message(
+                    crate::claude::Message::assistant(text.clone()),
+                    attribution.clone(),
+                );
 
-                // Store to memory (fire-and-forget; never blocks the response path)
-                if let Some(ref mem) = memory_system {
+                // Store to memory (fire-and-forget; never blocks the response path),
+                // unless the session is incognito
+                if let Some(mem) = memory_system
+                    .as_ref()
+                    .filter(|_| !crate::incognito::is_active())
+                {
                     let model_name = generator.name().to_string();
                     let _ = mem
-                        .insert_conversation(
-                            "user",
-                            &query,
-                            Some(&model_name),
-                            Some(&session_label),
-                        )
+                        .insert_session_turn("user", &query, Some(&model_name), &session_label)
                         .await;
                     let _ = mem
-                        .insert_conversation(
-                            "assistant",
-                            &text,
-                            Some(&model_name),
-                            Some(&session_label),
-                        )
+                        .insert_session_turn("assistant", &text, Some(&model_name), &session_label)
                         .await;
+                    if mem.extracts_facts() && generator_state.read().await.is_ready() {
+                        spawn_fact_extraction(
+                            Arc::clone(mem),
+                            Arc::clone(&qwen_gen),
+                            query.clone(),
+                            text.clone(),
+                            session_label.clone(),
+                        );
+                    }
                     if let Ok(stats) = mem.stats().await {
                         status_bar.update_line(
                             crate::cli::status_bar::StatusLineType::MemoryContext,
@@ -610,6 +754,7 @@ pub(crate) async fn process_query_with_tools(
                 let _ = event_tx.send(ReplEvent::StreamingComplete {
                     query_id,
                     full_response: text.clone(),
+                    attribution,
                 });
 
                 tracing::debug!("[EVENT_LOOP] Query complete, returning");
@@ -634,13 +779,23 @@ pub(crate) async fn process_query_with_tools(
             // Set response text on the WorkUnit
             if !response.text.is_empty() {
                 work_unit.set_response(&response.text);
+                work_unit.set_citations(citation_footnotes(&response.text, &recalled));
+                record_recall(&memory_system, &recalled, &response.text).await;
             }
+            let attribution = generator
+                .attribution()
+                .await
+                .with_model(&response.metadata.model);
 
             // Send stats update
             let _ = event_tx.send(ReplEvent::StatsUpdate {
                 model: response.metadata.model.clone(),
                 input_tokens: response.metadata.input_tokens,
                 output_tokens: response.metadata.output_tokens,
+                reasoning_tokens: Some(crate::providers::reasoning::thinking_tokens(
+                    &response.content_blocks,
+                ))
+                .filter(|&n| n > 0),
                 latency_ms: response.metadata.latency_ms,
             });
 
@@ -648,6 +803,7 @@ pub(crate) async fn process_query_with_tools(
             let _ = event_tx.send(ReplEvent::StreamingComplete {
                 query_id,
                 full_response: response.text.clone(),
+                attribution: attribution.clone(),
             });
 
             // Convert GenToolUse to ToolUse
@@ -679,7 +835,10 @@ pub(crate) async fn process_query_with_tools(
                     role: "assistant".to_string(),
                     content: response.content_blocks.clone(),
                 };
-                conversation.write().await.add_message(assistant_message);
+                conversation
+                    .write()
+                    .await
+                    .add_attributed_message(assistant_message, attribution);
 
                 // Dispatch tools (loop detection, mode gating, inline handlers, spawn)
                 dispatch_tool_uses(
@@ -709,25 +868,32 @@ pub(crate) async fn process_query_with_tools(
             work_unit.set_complete();
             tracing::debug!("Query complete (no tools), non-streaming finished");
 
-            // Store to memory (fire-and-forget)
-            if let Some(ref mem) = memory_system {
+            // Store to memory (fire-and-forget), unless the session is incognito
+            if let Some(mem) = memory_system
+                .as_ref()
+                .filter(|_| !crate::incognito::is_active())
+            {
                 let model_name = response.metadata.model.clone();
                 let _ = mem
-                    .insert_conversation(
-                        "user",
-                        &query,
-                        Some(&model_name),
-                        Some(&session_label),
-                    )
+                    .insert_session_turn("user", &query, Some(&model_name), &session_label)
                     .await;
                 let _ = mem
-                    .insert_conversation(
+                    .insert_session_turn(
                         "assistant",
                         &response.text,
                         Some(&model_name),
-                        Some(&session_label),
+                        &session_label,
                     )
                     .await;
+                if mem.extracts_facts() && generator_state.read().await.is_ready() {
+                    spawn_fact_extraction(
+                        Arc::clone(mem),
+                        Arc::clone(&qwen_gen),
+                        query.clone(),
+                        response.text.clone(),
+                        session_label.clone(),
+                    );
+                }
                 if let Ok(stats) = mem.stats().await {
                     status_bar.update_line(
                         crate::cli::status_bar::StatusLineType::MemoryContext,
@@ -745,11 +911,38 @@ pub(crate) async fn process_query_with_tools(
             let _ = event_tx.send(ReplEvent::QueryFailed {
                 query_id,
                 error: format!("{}", e),
+                provider: teacher,
             });
         }
     }
 }
 
+/// "[mem-12] recalled from …" for each recalled memory `answer` cites
+fn citation_footnotes(answer: &str, recalled: &[crate::memory::RecalledMemory]) -> Vec<String> {
+    crate::memory::citations::cited(answer, recalled)
+        .into_iter()
+        .map(|memory| format!("{} {}", memory.tag(), memory.provenance()))
+        .collect()
+}
+
+/// Count which recalled memories `answer` cited, for `finch memory stats
+/// --verbose`, unless the session is incognito
+async fn record_recall(
+    memory_system: &Option<Arc<crate::memory::MemorySystem>>,
+    recalled: &[crate::memory::RecalledMemory],
+    answer: &str,
+) {
+    let Some(mem) = memory_system
+        .as_ref()
+        .filter(|_| !crate::incognito::is_active())
+    else {
+        return;
+    };
+    if let Err(e) = mem.record_recall(recalled, answer).await {
+        tracing::debug!("Failed to record memory recall: {}", e);
+    }
+}
+
 /// Apply a sliding window to the message list, keeping only the last `max` messages
 /// verbatim. If `max` is 0 or the list is shorter than `max`, returns all messages.
 ///
@@ -102,11 +102,17 @@ impl QueryStateManager {
     }
 
     /// Cancel a query
-    pub async fn cancel_query(&self, query_id: Uuid) {
-        if let Some(metadata) = self.states.read().await.get(&query_id) {
-            metadata.cancellation_token.cancel();
-        }
-        self.update_state(query_id, QueryState::Cancelled).await;
+    ///
+    /// Returns the state the query was in, so the caller can tell whether
+    /// it still has tool calls outstanding.
+    pub async fn cancel_query(&self, query_id: Uuid) -> Option<QueryState> {
+        let mut states = self.states.write().await;
+        let metadata = states.get_mut(&query_id)?;
+        metadata.cancellation_token.cancel();
+        Some(std::mem::replace(
+            &mut metadata.state,
+            QueryState::Cancelled,
+        ))
     }
 
     /// Remove a completed/failed/cancelled query (cleanup)
@@ -268,6 +274,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_cancel_query_returns_previous_state() {
+        let manager = QueryStateManager::new();
+        let id = manager.create_query(vec![]).await;
+        manager
+            .update_state(
+                id,
+                QueryState::ExecutingTools {
+                    tools_pending: 2,
+                    tools_completed: 0,
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            manager.cancel_query(id).await,
+            Some(QueryState::ExecutingTools {
+                tools_pending: 2,
+                ..
+            })
+        ));
+        assert!(manager.cancel_query(Uuid::new_v4()).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_remove_query_cleans_up_state() {
         let manager = QueryStateManager::new();
@@ -53,7 +53,7 @@ fn extract_key_param(tool_name: &str, input: &Value) -> String {
             let cmd = input["command"].as_str().unwrap_or("");
             cmd.trim().to_string()
         }
-        "read" => {
+        "read" | "outline" => {
             let path = input["file_path"].as_str().unwrap_or("");
             shorten_path(path)
         }
@@ -77,9 +77,46 @@ fn extract_key_param(tool_name: &str, input: &Value) -> String {
                 .trim_start_matches("http://")
                 .to_string()
         }
+        "websearch" | "web_search" => truncate(input["query"].as_str().unwrap_or(""), 40),
+        "http" => format!(
+            "{} {}",
+            input["method"]
+                .as_str()
+                .unwrap_or("GET")
+                .to_ascii_uppercase(),
+            truncate(
+                input["url"]
+                    .as_str()
+                    .unwrap_or("")
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://"),
+                40
+            )
+        ),
+        "browser" => {
+            let action = input["action"].as_str().unwrap_or("");
+            match input["url"].as_str().or(input["selector"].as_str()) {
+                Some(target) => format!(
+                    "{} {}",
+                    action,
+                    truncate(
+                        target
+                            .trim_start_matches("https://")
+                            .trim_start_matches("http://"),
+                        40
+                    )
+                ),
+                None => action.to_string(),
+            }
+        }
         "write" => shorten_path(input["file_path"].as_str().unwrap_or("")),
         "edit" => shorten_path(input["file_path"].as_str().unwrap_or("")),
         "task" => input["description"].as_str().unwrap_or("").to_string(),
+        "spawn_task" => format!(
+            "{}: {}",
+            input["subagent_type"].as_str().unwrap_or("general"),
+            truncate(input["task"].as_str().unwrap_or(""), 40)
+        ),
         "presentplan" | "present_plan" => {
             // Show the plan title (first # heading) rather than raw markdown content
             let plan = input["plan"].as_str().unwrap_or("");
@@ -170,6 +207,7 @@ pub(crate) const MAX_TOOL_BODY_LINES: usize = 20;
 ///   Write → "Created foo.rs (N lines)"
 ///   Glob  → "N files" + first 8 paths
 ///   Grep  → "N matches" + first 8 match lines
+///   Outline → "N symbols" + first 8 symbols
 ///   Bash  → semantic summary line + remaining lines as body
 pub(crate) fn tool_result_to_display(tool_name: &str, content: &str) -> (String, Vec<String>) {
     let trimmed = content.trim();
@@ -205,6 +243,31 @@ pub(crate) fn tool_result_to_display(tool_name: &str, content: &str) -> (String,
 
         "write" => (compact_tool_summary(content), Vec::new()),
 
+        "outline" => {
+            let mut lines = trimmed.lines();
+            let first = lines.next().unwrap_or("");
+            if first.starts_with("No functions") {
+                return (first.to_string(), Vec::new());
+            }
+            let symbols: Vec<&str> = lines
+                .filter(|l| !l.is_empty() && !l.starts_with("..."))
+                .collect();
+            let count = symbols.len();
+            let summary = if count == 1 {
+                "1 symbol".to_string()
+            } else {
+                format!("{} symbols", count)
+            };
+            let mut body: Vec<String> = symbols.iter().take(8).map(|l| l.to_string()).collect();
+            if count > 8 {
+                body.push(format!(
+                    "\x1b[90m… +{} more (ctrl+o to expand)\x1b[0m",
+                    count - 8
+                ));
+            }
+            (summary, body)
+        }
+
         "glob" => {
             let lines: Vec<&str> = trimmed.lines().collect();
             let count = lines.len();
@@ -830,6 +893,18 @@ mod tests {
         assert!(body.is_empty());
     }
 
+    #[test]
+    fn test_tool_result_outline_counts_symbols() {
+        let content = "src/lib.rs (40 lines, 3 symbols)\n\n1-5    pub struct Cache\n7-40   impl Cache\n8-12     pub fn get(&self)";
+        let (summary, body) = tool_result_to_display("outline", content);
+        assert_eq!(summary, "3 symbols");
+        assert_eq!(body.len(), 3);
+        let (summary, body) =
+            tool_result_to_display("outline", "No functions or types found in a.rs (2 lines).");
+        assert!(summary.starts_with("No functions"));
+        assert!(body.is_empty());
+    }
+
     #[test]
     fn test_tool_result_glob_counts_files() {
         let content = "src/main.rs\nsrc/lib.rs\nsrc/foo.rs";
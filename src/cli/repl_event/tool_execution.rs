@@ -6,21 +6,34 @@
 //! 1. Checks whether the tool needs user approval (via `ToolExecutor::is_approved`).
 //! 2. If needed, sends a `ReplEvent::ToolApprovalNeeded` and waits on a oneshot
 //!    channel — only *this* task blocks; other tool tasks proceed independently.
-//! 3. Executes the tool (with a 30-second timeout) and sends the result back as
-//!    `ReplEvent::ToolResult`.
+//! 3. Executes the tool (with a 30-second timeout, or the tool's own from
+//!    `features.tool_timeouts`) and sends the result back as
+//!    `ReplEvent::ToolResult`.  The executor lock is only held to take a
+//!    `ToolRunner`; its gate lets read-only tools run side by side, up to
+//!    `features.max_parallel_tools`, while tools that change something run alone.
+//!
+//! Cancelling the query (Ctrl+C) drops a running call, which stops its
+//! process (bash closes its shell), and answers it with `ToolCancelled`.
+//!
+//! Every call, including denied ones, is written to the tool audit log
+//! (`crate::tools::audit`) with how it was approved.
 
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use super::events::ConfirmationResult;
 use crate::cli::conversation::ConversationHistory;
 use crate::cli::messages::WorkUnit;
 use crate::cli::ReplMode;
+use crate::config::constants::REPL_TOOL_TIMEOUT_SECS;
 use crate::local::LocalGenerator;
 use crate::models::tokenizer::TextTokenizer;
+use crate::tools::audit::{self, Approval, AuditRecord, Outcome};
 use crate::tools::executor::{generate_tool_signature, ToolExecutor};
-use crate::tools::types::ToolUse;
+use crate::tools::types::{ToolCancelled, ToolUse};
 
 use super::events::ReplEvent;
 
@@ -106,13 +119,14 @@ impl ToolExecutionCoordinator {
     ///
     /// `work_unit` + `row_idx` are used to stream live bash output lines into the
     /// WorkUnit row while the command runs, creating the scrolling preview in the
-    /// live area.
+    /// live area.  Cancelling `cancel` stops the call wherever it is.
     pub fn spawn_tool_execution(
         &self,
         query_id: Uuid,
         tool_use: ToolUse,
         work_unit: Arc<WorkUnit>,
         row_idx: usize,
+        cancel: CancellationToken,
     ) {
         let event_tx = self.event_tx.clone();
         let tool_executor = Arc::clone(&self.tool_executor);
@@ -158,19 +172,22 @@ impl ToolExecutionCoordinator {
                     let current_mode = repl_mode.read().await;
                     let is_plan_mode =
                         matches!(*current_mode, crate::cli::ReplMode::Planning { .. });
-                    let is_readonly_tool = matches!(
-                        tool_name,
-                        "read"
-                            | "Read"
-                            | "glob"
-                            | "Glob"
-                            | "grep"
-                            | "Grep"
-                            | "web_fetch"
-                            | "WebFetch"
-                            | "AskUserQuestion"
-                            | "ask_user_question"
-                    );
+                    let is_readonly_tool = crate::tools::exec_plugins::is_read_only(tool_name)
+                        || matches!(
+                            tool_name,
+                            "read"
+                                | "Read"
+                                | "glob"
+                                | "Glob"
+                                | "grep"
+                                | "Grep"
+                                | "outline"
+                                | "web_fetch"
+                                | "WebFetch"
+                                | "web_search"
+                                | "AskUserQuestion"
+                                | "ask_user_question"
+                        );
 
                     is_plan_mode && is_readonly_tool
                 }
@@ -181,6 +198,12 @@ impl ToolExecutionCoordinator {
                     approval_source,
                     crate::tools::executor::ApprovalSource::NotApproved
                 );
+            // For the audit log; becomes `User` once the dialog approves it
+            let mut approval = if is_auto_approved {
+                Approval::Automatic
+            } else {
+                Approval::from(&approval_source)
+            };
 
             if needs_approval {
                 // Request approval from user (non-blocking for other queries)
@@ -200,8 +223,17 @@ impl ToolExecutionCoordinator {
                 }
 
                 // Wait for approval response (blocks only THIS task)
-                match response_rx.await {
+                let response = tokio::select! {
+                    _ = cancel.cancelled() => {
+                        audit::record(AuditRecord::new(&tool_use, Approval::Unanswered, Outcome::Stopped));
+                        send_cancelled(&event_tx, query_id, &tool_use);
+                        return;
+                    }
+                    response = response_rx => response,
+                };
+                match response {
                     Ok(confirmation) => {
+                        approval = Approval::User;
                         // Process approval result
                         match confirmation {
                             ConfirmationResult::ApproveOnce => {
@@ -239,6 +271,11 @@ impl ToolExecutionCoordinator {
                             }
                             ConfirmationResult::Deny => {
                                 // Tool denied, send error result
+                                audit::record(AuditRecord::new(
+                                    &tool_use,
+                                    Approval::Denied,
+                                    Outcome::Denied,
+                                ));
                                 let _ = event_tx.send(ReplEvent::ToolResult {
                                     query_id,
                                     tool_id: tool_use.id.clone(),
@@ -250,6 +287,11 @@ impl ToolExecutionCoordinator {
                     }
                     Err(_) => {
                         // Approval channel closed (user cancelled?)
+                        audit::record(AuditRecord::new(
+                            &tool_use,
+                            Approval::Unanswered,
+                            Outcome::Stopped,
+                        ));
                         let _ = event_tx.send(ReplEvent::ToolResult {
                             query_id,
                             tool_id: tool_use.id.clone(),
@@ -264,16 +306,22 @@ impl ToolExecutionCoordinator {
             let conversation_snapshot = conversation.read().await.clone();
 
             // Wire the poset into the executor so tool calls auto-record trace nodes.
-            tool_executor.lock().await.poset = poset.clone();
+            // The call itself runs on a runner without holding the executor lock;
+            // the gate lets read-only tools overlap and runs the others alone.
+            let runner = {
+                let mut executor = tool_executor.lock().await;
+                executor.poset = poset.clone();
+                executor.runner().with_approval(approval)
+            };
 
             // Execute with timeout to prevent system freezing (especially for CPU-heavy operations)
-            let timeout_duration = std::time::Duration::from_secs(30);
-            let result = tokio::time::timeout(
-                timeout_duration,
-                tool_executor
-                    .lock()
-                    .await
-                    .execute_tool::<fn() -> anyhow::Result<()>>(
+            let timeout_duration =
+                runner.timeout_for(&tool_use.name, Duration::from_secs(REPL_TOOL_TIMEOUT_SECS));
+            let run = async {
+                let _permit = runner.gate().enter(&tool_use.name).await;
+                tokio::time::timeout(
+                    timeout_duration,
+                    runner.execute_tool::<fn() -> anyhow::Result<()>>(
                         &tool_use,
                         Some(&conversation_snapshot),
                         None, // save_fn (not needed in event loop)
@@ -285,8 +333,19 @@ impl ToolExecutionCoordinator {
                         Some(Arc::clone(&live_output)),
                         stack.clone(), // Co-Forth shared stack
                     ),
-            )
-            .await;
+                )
+                .await
+            };
+            // Ctrl+C drops the call, and with it whatever process it runs
+            let result = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => {
+                    tracing::info!("[tool_exec] Tool {} cancelled by user", tool_use.name);
+                    send_cancelled(&event_tx, query_id, &tool_use);
+                    return;
+                }
+                result = run => result,
+            };
 
             // Send result back to event loop
             match result {
@@ -304,7 +363,12 @@ impl ToolExecutionCoordinator {
                     // their own stack interaction.
                     let skip_stack = matches!(
                         tool_use.name.as_str(),
-                        "Push" | "TodoWrite" | "TodoRead" | "EnterPlanMode" | "enter_plan_mode"
+                        "Push"
+                            | "TodoWrite"
+                            | "TodoRead"
+                            | "TodoPromote"
+                            | "EnterPlanMode"
+                            | "enter_plan_mode"
                     );
                     if !skip_stack {
                         if let Some(ref s) = stack {
@@ -353,3 +417,15 @@ impl ToolExecutionCoordinator {
         });
     }
 }
+
+/// Answer `tool_use` with `ToolCancelled` so the query still gets a result for it
+fn send_cancelled(event_tx: &mpsc::UnboundedSender<ReplEvent>, query_id: Uuid, tool_use: &ToolUse) {
+    let _ = event_tx.send(ReplEvent::ToolResult {
+        query_id,
+        tool_id: tool_use.id.clone(),
+        result: Err(ToolCancelled {
+            tool_name: tool_use.name.clone(),
+        }
+        .into()),
+    });
+}
@@ -667,6 +667,12 @@ pub fn apply_and_save(result: &SetupResult) -> Result<()> {
         enable_summarization: new_config.features.enable_summarization,
         auto_compact_enabled: new_config.features.auto_compact_enabled,
         brain_enabled: new_config.features.brain_enabled,
+        tool_result_max_tokens: new_config.features.tool_result_max_tokens,
+        tool_result_max_tokens_by_tool: new_config.features.tool_result_max_tokens_by_tool.clone(),
+        rename_check: new_config.features.rename_check,
+        max_parallel_tools: new_config.features.max_parallel_tools,
+        tool_timeouts: new_config.features.tool_timeouts.clone(),
+        prompt_lint: new_config.features.prompt_lint,
     };
     if result.daemon_only_mode {
         new_config.server.mode = "daemon-only".to_string();
@@ -876,6 +882,8 @@ fn handle_models_input(state: &mut WizardState, key: crossterm::event::KeyEvent)
                 let mut v = vec![InferenceProvider::Onnx];
                 #[cfg(feature = "candle")]
                 v.push(InferenceProvider::Candle);
+                #[cfg(feature = "gguf")]
+                v.push(InferenceProvider::Gguf);
                 v
             };
             // When Candle is selected, only Qwen 2.5 is currently supported
@@ -917,6 +925,8 @@ fn handle_models_input(state: &mut WizardState, key: crossterm::event::KeyEvent)
                 let mut v = vec![ExecutionTarget::Auto];
                 #[cfg(target_os = "macos")]
                 v.push(ExecutionTarget::CoreML);
+                #[cfg(target_os = "macos")]
+                v.push(ExecutionTarget::Metal);
                 v.push(ExecutionTarget::Cpu);
                 #[cfg(feature = "cuda")]
                 v.push(ExecutionTarget::Cuda);
@@ -2834,6 +2844,8 @@ fn render_configure_local_overlay(
         InferenceProvider::Onnx => "ONNX Runtime",
         #[cfg(feature = "candle")]
         InferenceProvider::Candle => "Candle",
+        #[cfg(feature = "gguf")]
+        InferenceProvider::Gguf => "llama.cpp (GGUF)",
     };
     // When Candle is selected, only Qwen 2.5 is supported — annotate the display
     let mut family_name = family.name().to_string();
@@ -13,6 +13,12 @@ use std::sync::{Arc, RwLock};
 /// Types of status lines
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum StatusLineType {
+    /// Firing metric alerts ("⚠ daily-spend: spend $5.12 > $5.00"), shown first
+    Alert,
+    /// Countdown to a provider's rate-limit reset ("⏳ claude rate limited — retrying in 12s")
+    RateLimit,
+    /// Shown while the session is incognito (see `crate::incognito`)
+    Incognito,
     /// Session label shown permanently (e.g. "◆ swift-falcon · ~/repos/finch")
     SessionLabel,
     /// Memory context: engine type + recall info ("🧠 neural · 142 memories · recalled 3")
@@ -85,10 +91,32 @@ impl StatusBar {
     pub fn get_lines(&self) -> Vec<StatusLine> {
         let lines = self.lines.read().unwrap();
 
-        // Order: SessionLabel, MemoryContext, LiveStats, TrainingStats, DownloadProgress,
+        // Order: Alert, RateLimit, Incognito, SessionLabel, MemoryContext, LiveStats, TrainingStats, DownloadProgress,
         //        OperationStatus, then Custom
         let mut result = Vec::new();
 
+        // Firing alerts are a warning banner: always on top
+        if let Some(content) = lines.get(&StatusLineType::Alert) {
+            result.push(StatusLine {
+                line_type: StatusLineType::Alert,
+                content: content.clone(),
+            });
+        }
+
+        if let Some(content) = lines.get(&StatusLineType::RateLimit) {
+            result.push(StatusLine {
+                line_type: StatusLineType::RateLimit,
+                content: content.clone(),
+            });
+        }
+
+        if let Some(content) = lines.get(&StatusLineType::Incognito) {
+            result.push(StatusLine {
+                line_type: StatusLineType::Incognito,
+                content: content.clone(),
+            });
+        }
+
         // Add in preferred order
         if let Some(content) = lines.get(&StatusLineType::SessionLabel) {
             result.push(StatusLine {
@@ -285,6 +313,23 @@ impl StatusBar {
         self.remove_line(&StatusLineType::OperationStatus);
     }
 
+    /// Show the countdown to `provider`'s rate-limit reset
+    pub fn update_rate_limit(&self, provider: &str, remaining: std::time::Duration) {
+        self.update_line(
+            StatusLineType::RateLimit,
+            format!(
+                "⏳ {} rate limited — retrying in {}s",
+                provider,
+                crate::providers::rate_limit::ceil_secs(remaining)
+            ),
+        );
+    }
+
+    /// Clear the rate-limit countdown (shorthand)
+    pub fn clear_rate_limit(&self) {
+        self.remove_line(&StatusLineType::RateLimit);
+    }
+
     /// Update live query statistics
     pub fn update_live_stats(
         &self,
@@ -419,6 +464,31 @@ mod tests {
         assert_eq!(lines[1].line_type, StatusLineType::MemoryContext);
     }
 
+    #[test]
+    fn test_alert_banner_is_first() {
+        let status = StatusBar::new();
+        status.update_line(StatusLineType::SessionLabel, "Session");
+        status.update_line(StatusLineType::Alert, "⚠ budget: spend $5.12 > $5.00");
+
+        let lines = status.get_lines();
+        assert_eq!(lines[0].line_type, StatusLineType::Alert);
+        assert_eq!(lines[1].line_type, StatusLineType::SessionLabel);
+    }
+
+    #[test]
+    fn test_rate_limit_countdown() {
+        let status = StatusBar::new();
+        status.update_line(StatusLineType::SessionLabel, "Session");
+        status.update_rate_limit("claude", std::time::Duration::from_millis(11_200));
+
+        let lines = status.get_lines();
+        assert_eq!(lines[0].line_type, StatusLineType::RateLimit);
+        assert_eq!(lines[0].content, "⏳ claude rate limited — retrying in 12s");
+
+        status.clear_rate_limit();
+        assert_eq!(status.get_lines().len(), 1);
+    }
+
     #[test]
     fn test_live_stats_not_rendered() {
         // LiveStats is suppressed — pushing it should not cause it to appear in get_lines()
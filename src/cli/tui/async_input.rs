@@ -183,7 +183,7 @@ pub fn spawn_input_task(
                                     let input = tui.input_textarea.lines().join("\n");
                                     if !input.trim().is_empty() {
                                         // Add to command history
-                                        tui.command_history.push(input.clone());
+                                        tui.push_history(input.clone());
                                         tui.history_index = None;
                                         tui.history_draft = None; // Clear any saved draft
 
@@ -372,6 +372,16 @@ pub fn spawn_input_task(
                                         }
                                         Ok(None)
                                     }
+                                    (
+                                        KeyCode::Char('?'),
+                                        KeyModifiers::NONE | KeyModifiers::SHIFT,
+                                    ) if tui.brain_questions_pending > 0
+                                        && tui.input_textarea.lines().join("").is_empty() =>
+                                    {
+                                        // ?: Open the oldest pending brain question
+                                        tui.pending_inbox_open = true;
+                                        Ok(None)
+                                    }
                                     (KeyCode::Tab, KeyModifiers::NONE) => {
                                         // Tab: Accept ghost text suggestion if available
                                         if let Some(ghost) = tui.ghost_text.take() {
@@ -0,0 +1,377 @@
+// Terminal output targets — the real stdout or a headless capture screen
+//
+// TuiRenderer draws by writing crossterm escape sequences straight to the
+// terminal.  `CaptureTerminal` stands in for stdout in tests: it interprets the
+// subset of VT sequences the renderer emits (cursor movement, clears, CR/LF
+// with scrolling, deferred auto-wrap) into a fixed-size character grid, so
+// live-area layout, dialogs, wrapping, and erase/redraw can be asserted as
+// plain-text snapshots without a TTY.  Colours and attributes are discarded.
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use super::shadow_buffer::char_display_width;
+
+/// Where TuiRenderer writes its output.  Cheap to clone, like `io::stdout()`.
+#[derive(Clone)]
+pub enum TermOutput {
+    /// The process's real terminal
+    Stdout,
+    /// In-memory screen used by headless renderers
+    Capture(CaptureTerminal),
+}
+
+impl TermOutput {
+    /// Terminal size as `(columns, rows)`
+    pub fn size(&self) -> (u16, u16) {
+        match self {
+            Self::Stdout => crossterm::terminal::size().unwrap_or((80, 24)),
+            Self::Capture(term) => term.size(),
+        }
+    }
+
+    pub fn is_stdout(&self) -> bool {
+        matches!(self, Self::Stdout)
+    }
+}
+
+impl Write for TermOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout => io::stdout().write(buf),
+            Self::Capture(term) => term.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout => io::stdout().flush(),
+            Self::Capture(term) => term.flush(),
+        }
+    }
+}
+
+/// Headless terminal that records what a real one would display.
+///
+/// Clones share the same screen, so a test can keep one handle while the
+/// renderer writes through another.
+#[derive(Clone)]
+pub struct CaptureTerminal {
+    screen: Arc<Mutex<Screen>>,
+}
+
+struct Screen {
+    width: usize,
+    height: usize,
+    /// `rows[y][x]`; `None` marks the right half of a double-width character
+    rows: Vec<Vec<Option<char>>>,
+    /// Rows scrolled off the top, oldest first
+    scrollback: Vec<String>,
+    col: usize,
+    row: usize,
+    /// Set after printing into the last column; the next printable character
+    /// wraps first (xterm's deferred wrap).
+    wrap_pending: bool,
+    saved: (usize, usize),
+    /// Bytes of an incomplete escape sequence or UTF-8 character
+    pending: Vec<u8>,
+}
+
+impl CaptureTerminal {
+    pub fn new(width: u16, height: u16) -> Self {
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+        Self {
+            screen: Arc::new(Mutex::new(Screen {
+                width,
+                height,
+                rows: vec![vec![Some(' '); width]; height],
+                scrollback: Vec::new(),
+                col: 0,
+                row: 0,
+                wrap_pending: false,
+                saved: (0, 0),
+                pending: Vec::new(),
+            })),
+        }
+    }
+
+    /// Size as `(columns, rows)`
+    pub fn size(&self) -> (u16, u16) {
+        let screen = self.lock();
+        (screen.width as u16, screen.height as u16)
+    }
+
+    /// Cursor position as `(column, row)`, 0-based
+    pub fn cursor(&self) -> (u16, u16) {
+        let screen = self.lock();
+        (screen.col.min(screen.width - 1) as u16, screen.row as u16)
+    }
+
+    /// Visible screen contents: one line per row with trailing spaces removed,
+    /// and trailing blank rows dropped.
+    pub fn snapshot(&self) -> String {
+        let screen = self.lock();
+        let mut lines: Vec<String> = screen.rows.iter().map(|r| render_row(r)).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+
+    /// Rows that have scrolled off the top of the screen, oldest first
+    pub fn scrollback(&self) -> Vec<String> {
+        self.lock().scrollback.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Screen> {
+        // A panicking test thread must not hide the screen from its assertions
+        self.screen.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Write for CaptureTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut screen = self.lock();
+        screen.pending.extend_from_slice(buf);
+        screen.process();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn render_row(row: &[Option<char>]) -> String {
+    let line: String = row.iter().flatten().collect();
+    line.trim_end().to_string()
+}
+
+impl Screen {
+    /// Consume as many complete characters / sequences from `pending` as
+    /// possible.  crossterm writes a single command in several `write` calls,
+    /// so partial input is kept for the next call.
+    fn process(&mut self) {
+        let input = std::mem::take(&mut self.pending);
+        let mut i = 0;
+        while i < input.len() {
+            let consumed = match input[i] {
+                0x1b => self.escape(&input[i..]),
+                b'\r' => {
+                    self.col = 0;
+                    self.wrap_pending = false;
+                    Some(1)
+                }
+                b'\n' => {
+                    self.line_feed();
+                    Some(1)
+                }
+                0x08 => {
+                    self.col = self.col.min(self.width - 1).saturating_sub(1);
+                    self.wrap_pending = false;
+                    Some(1)
+                }
+                b if b < 0x20 || b == 0x7f => Some(1),
+                _ => self.text(&input[i..]),
+            };
+            match consumed {
+                Some(n) => i += n,
+                None => {
+                    self.pending = input[i..].to_vec();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Decode and print one UTF-8 character; `None` if it is incomplete.
+    fn text(&mut self, bytes: &[u8]) -> Option<usize> {
+        let len = match bytes[0] {
+            b if b < 0x80 => 1,
+            b if b >= 0xf0 => 4,
+            b if b >= 0xe0 => 3,
+            _ => 2,
+        };
+        if bytes.len() < len {
+            return None;
+        }
+        match std::str::from_utf8(&bytes[..len]) {
+            Ok(s) => s.chars().for_each(|c| self.print(c)),
+            Err(_) => self.print('\u{fffd}'),
+        }
+        Some(len)
+    }
+
+    fn print(&mut self, c: char) {
+        let w = char_display_width(c).min(self.width);
+        if self.wrap_pending || self.col + w > self.width {
+            self.col = 0;
+            self.line_feed();
+        }
+        self.rows[self.row][self.col] = Some(c);
+        if w == 2 {
+            self.rows[self.row][self.col + 1] = None;
+        }
+        self.col += w;
+        if self.col >= self.width {
+            self.col = self.width - 1;
+            self.wrap_pending = true;
+        }
+    }
+
+    fn line_feed(&mut self) {
+        self.wrap_pending = false;
+        if self.row + 1 < self.height {
+            self.row += 1;
+        } else {
+            let top = self.rows.remove(0);
+            self.scrollback.push(render_row(&top));
+            self.rows.push(vec![Some(' '); self.width]);
+        }
+    }
+
+    /// Handle an escape sequence starting at `bytes[0] == ESC`.
+    /// Returns the sequence length, or `None` if it is incomplete.
+    fn escape(&mut self, bytes: &[u8]) -> Option<usize> {
+        match *bytes.get(1)? {
+            b'[' => {
+                let end = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b))? + 2;
+                self.csi(&bytes[2..end], bytes[end]);
+                Some(end + 1)
+            }
+            b'7' => {
+                self.saved = (self.col, self.row);
+                Some(2)
+            }
+            b'8' => {
+                (self.col, self.row) = self.saved;
+                self.wrap_pending = false;
+                Some(2)
+            }
+            _ => Some(2),
+        }
+    }
+
+    fn csi(&mut self, params: &[u8], action: u8) {
+        // Private modes (`?2026h` synchronized update, `?25h` show cursor, …)
+        // do not affect the screen contents.
+        if params.first() == Some(&b'?') {
+            return;
+        }
+        let args: Vec<usize> = std::str::from_utf8(params)
+            .unwrap_or("")
+            .split(';')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let arg = |i: usize| args.get(i).copied().unwrap_or(0);
+        let count = arg(0).max(1);
+        let col = self.col.min(self.width - 1);
+
+        match action {
+            b'A' => self.row = self.row.saturating_sub(count),
+            b'B' => self.row = (self.row + count).min(self.height - 1),
+            b'C' => self.col = (col + count).min(self.width - 1),
+            b'D' => self.col = col.saturating_sub(count),
+            b'G' => self.col = (count - 1).min(self.width - 1),
+            b'H' | b'f' => {
+                self.row = (arg(0).max(1) - 1).min(self.height - 1);
+                self.col = (arg(1).max(1) - 1).min(self.width - 1);
+            }
+            b'J' => match arg(0) {
+                0 => {
+                    self.clear_line(self.row, col, self.width);
+                    for row in self.row + 1..self.height {
+                        self.clear_line(row, 0, self.width);
+                    }
+                }
+                1 => {
+                    for row in 0..self.row {
+                        self.clear_line(row, 0, self.width);
+                    }
+                    self.clear_line(self.row, 0, col + 1);
+                }
+                _ => {
+                    for row in 0..self.height {
+                        self.clear_line(row, 0, self.width);
+                    }
+                }
+            },
+            b'K' => match arg(0) {
+                0 => self.clear_line(self.row, col, self.width),
+                1 => self.clear_line(self.row, 0, col + 1),
+                _ => self.clear_line(self.row, 0, self.width),
+            },
+            b's' => self.saved = (self.col, self.row),
+            b'u' => (self.col, self.row) = self.saved,
+            // SGR (`m`) and anything else: no effect on the text grid
+            _ => return,
+        }
+        self.wrap_pending = false;
+    }
+
+    fn clear_line(&mut self, row: usize, from: usize, to: usize) {
+        for cell in &mut self.rows[row][from..to.min(self.width)] {
+            *cell = Some(' ');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::{cursor, execute, style::Print, terminal};
+
+    #[test]
+    fn test_prints_and_strips_colours() {
+        let mut term = CaptureTerminal::new(20, 4);
+        write!(term, "\x1b[36m❯\x1b[0m hello\r\nworld").unwrap();
+        assert_eq!(term.snapshot(), "❯ hello\nworld");
+        assert_eq!(term.cursor(), (5, 1));
+    }
+
+    #[test]
+    fn test_deferred_wrap_and_scrolling() {
+        let mut term = CaptureTerminal::new(4, 2);
+        // Exactly filling a row does not wrap until the next printable char
+        write!(term, "abcd").unwrap();
+        assert_eq!(term.cursor(), (3, 0));
+        write!(term, "\r\nefghij").unwrap();
+        assert_eq!(term.snapshot(), "efgh\nij");
+        assert_eq!(term.scrollback(), vec!["abcd".to_string()]);
+    }
+
+    #[test]
+    fn test_crossterm_cursor_commands() {
+        let mut term = CaptureTerminal::new(10, 4);
+        execute!(term, Print("one\r\ntwo\r\nthree")).unwrap();
+        execute!(
+            term,
+            cursor::MoveUp(1),
+            cursor::MoveToColumn(0),
+            terminal::Clear(terminal::ClearType::FromCursorDown)
+        )
+        .unwrap();
+        assert_eq!(term.snapshot(), "one");
+        execute!(
+            term,
+            cursor::SavePosition,
+            cursor::MoveTo(5, 0),
+            Print("X"),
+            cursor::RestorePosition,
+            Print("2")
+        )
+        .unwrap();
+        assert_eq!(term.snapshot(), "one  X\n2");
+    }
+
+    #[test]
+    fn test_split_writes_and_wide_chars() {
+        let mut term = CaptureTerminal::new(5, 2);
+        let bytes = "\x1b[1mé中\x1b[0m".as_bytes();
+        for b in bytes {
+            term.write_all(std::slice::from_ref(b)).unwrap();
+        }
+        assert_eq!(term.snapshot(), "é中");
+        assert_eq!(term.cursor(), (3, 0));
+    }
+}
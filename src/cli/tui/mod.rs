@@ -14,6 +14,10 @@
 //                  The setup wizard uses ratatui in an alternate screen so it
 //                  gets the whole terminal and restores it cleanly.
 //
+// Output:         all drawing goes through a `TermOutput` — stdout normally,
+//                  or a `CaptureTerminal` (capture.rs) for headless snapshot
+//                  tests.
+//
 // Note: shadow_buffer.rs is retained — it provides ColorScheme re-exports and
 //       may be used for flicker-free live-area diffing in a future pass.
 
@@ -39,6 +43,7 @@ use crate::cli::messages::{MessageId, MessageRef, MessageStatus};
 // Sub-modules
 mod async_input;
 mod autocomplete_widget;
+mod capture;
 mod dialog;
 mod dialog_widget;
 mod input_widget; // kept, used by wizard helpers
@@ -50,6 +55,7 @@ mod tabbed_dialog_widget; // kept for wizard helpers
 
 pub use async_input::{spawn_input_task, InputEvent};
 pub use autocomplete_widget::AutocompleteState;
+pub use capture::{CaptureTerminal, TermOutput};
 pub use dialog::{Dialog, DialogOption, DialogResult, DialogType};
 pub use dialog_widget::DialogWidget;
 pub use shadow_buffer::visible_length;
@@ -63,6 +69,32 @@ pub use crate::config::ColorScheme;
 const RESET: &str = "\x1b[0m";
 const CYAN: &str = "\x1b[36m";
 const DIM_GRAY: &str = "\x1b[90m";
+const GREEN: &str = "\x1b[32m";
+
+/// The first `width` display columns of `line`, keeping its color codes
+fn truncate_visible(line: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            for ch in chars.by_ref() {
+                out.push(ch);
+                if ch.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        used += shadow_buffer::char_display_width(c);
+        if used > width {
+            break;
+        }
+        out.push(c);
+    }
+    out
+}
 
 // ─── CWD helper ───────────────────────────────────────────────────────────────
 
@@ -395,9 +427,14 @@ pub struct TuiRenderer {
     status_bar: Arc<StatusBar>,
     colors: ColorScheme,
 
+    // Where drawing goes: the real terminal, or a capture screen when headless.
+    output: TermOutput,
+
     // Input — tui-textarea manages multi-line state; we render it manually.
     pub(crate) input_textarea: TextArea<'static>,
     pub(crate) command_history: Vec<String>,
+    /// Indices of history entries typed while incognito; never saved
+    incognito_history: HashSet<usize>,
     pub(crate) history_index: Option<usize>,
     pub(crate) history_draft: Option<String>,
 
@@ -426,6 +463,10 @@ pub struct TuiRenderer {
     pub pending_feedback: Option<crate::feedback::FeedbackRating>,
     pub pending_cancellation: bool,
     pub pending_dialog_result: Option<DialogResult>,
+    /// Brain questions in the inbox; `?` on an empty prompt opens one
+    pub brain_questions_pending: usize,
+    /// Set by `?`; the event loop opens the oldest brain question
+    pub pending_inbox_open: bool,
 
     // Autocomplete / suggestions
     pub(crate) ghost_text: Option<String>,
@@ -495,18 +536,55 @@ impl TuiRenderer {
 
         execute!(io::stdout(), cursor::Show)?;
 
+        let command_history = Self::load_history();
+        Ok(Self::with_output(
+            output_manager,
+            status_bar,
+            colors,
+            TermOutput::Stdout,
+            command_history,
+        ))
+    }
+
+    /// Build a renderer that draws into `terminal` instead of stdout.
+    ///
+    /// Raw mode is never touched and command history is neither loaded nor
+    /// saved, so this is safe in tests and CI: render, then assert on
+    /// `terminal.snapshot()`.
+    pub fn headless(
+        output_manager: Arc<OutputManager>,
+        status_bar: Arc<StatusBar>,
+        colors: ColorScheme,
+        terminal: CaptureTerminal,
+    ) -> Self {
+        Self::with_output(
+            output_manager,
+            status_bar,
+            colors,
+            TermOutput::Capture(terminal),
+            Vec::new(),
+        )
+    }
+
+    fn with_output(
+        output_manager: Arc<OutputManager>,
+        status_bar: Arc<StatusBar>,
+        colors: ColorScheme,
+        output: TermOutput,
+        command_history: Vec<String>,
+    ) -> Self {
         // Suppress OutputManager's own stdout writes — we own the terminal.
         output_manager.disable_stdout();
 
-        let command_history = Self::load_history();
-
-        Ok(TuiRenderer {
+        TuiRenderer {
             output_manager,
             status_bar,
             colors,
+            output,
 
             input_textarea: Self::create_clean_textarea(),
             command_history,
+            incognito_history: HashSet::new(),
             history_index: None,
             history_draft: None,
 
@@ -523,6 +601,8 @@ impl TuiRenderer {
             pending_feedback: None,
             pending_cancellation: false,
             pending_dialog_result: None,
+            brain_questions_pending: 0,
+            pending_inbox_open: false,
 
             ghost_text: None,
             suggestions: crate::cli::suggestions::SuggestionManager::new(),
@@ -545,7 +625,7 @@ impl TuiRenderer {
             session_label: String::new(),
             typing_words: Vec::new(),
             pre_typing_mode: PosetPanelMode::Forth,
-        })
+        }
     }
 
     /// Attach the session task list so the live area can display it.
@@ -627,8 +707,8 @@ impl TuiRenderer {
 impl TuiRenderer {
     /// Print a multi-line string to the terminal scrollback.
     /// In raw mode every `\n` needs an accompanying `\r`.
-    fn raw_println(text: &str) -> Result<()> {
-        let mut stdout = io::stdout();
+    fn raw_println(&self, text: &str) -> Result<()> {
+        let mut stdout = self.output.clone();
         for line in text.split('\n') {
             let line = line.trim_end_matches('\r');
             execute!(stdout, Print(line), Print("\r\n"))?;
@@ -636,8 +716,8 @@ impl TuiRenderer {
         Ok(())
     }
 
-    fn raw_blank_line() -> Result<()> {
-        execute!(io::stdout(), Print("\r\n")).map_err(anyhow::Error::from)
+    fn raw_blank_line(&self) -> Result<()> {
+        execute!(self.output.clone(), Print("\r\n")).map_err(anyhow::Error::from)
     }
 }
 
@@ -654,7 +734,7 @@ impl TuiRenderer {
         if self.active_rows == 0 && self.cursor_row_from_top == 0 {
             return Ok(()); // Nothing to erase
         }
-        let mut stdout = io::stdout();
+        let mut stdout = self.output.clone();
         execute!(stdout, cursor::MoveToColumn(0))?;
         if self.cursor_row_from_top > 0 {
             execute!(stdout, cursor::MoveUp(self.cursor_row_from_top as u16))?;
@@ -667,7 +747,8 @@ impl TuiRenderer {
 
     /// Draw the live area from scratch and track `active_rows`.
     pub fn draw_live_area(&mut self) -> Result<()> {
-        let mut stdout = io::stdout();
+        let mut stdout = self.output.clone();
+        let (term_cols, term_rows) = stdout.size();
         execute!(stdout, BeginSynchronizedUpdate)?;
 
         let mut rows: usize = 0;
@@ -675,7 +756,7 @@ impl TuiRenderer {
         // ── 1. Active WorkUnit ────────────────────────────────────────────────
         // Cap to the last third of the terminal height so streaming responses
         // don't grow the live area upward and shoot content off-screen.
-        let term_h = crossterm::terminal::size().unwrap_or((80, 24)).1 as usize;
+        let term_h = term_rows as usize;
         let max_live_lines = (term_h / 3).max(5);
         let live_msg = self.find_live_message();
         if let Some(msg) = &live_msg {
@@ -689,17 +770,19 @@ impl TuiRenderer {
             }
         }
 
-        // ── 1b. Session task list (active items only) ─────────────────────────
+        // ── 1b. Session task list (active items, then promoted todos the
+        //        agent finished) ──────────────────────────────────────────────
         if let Some(ref todo_arc) = self.todo_list {
             if let Ok(todo) = todo_arc.try_read() {
-                let active = todo.active_items();
-                if !active.is_empty() {
-                    let term_w = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
-                    for item in &active {
+                let mut shown = todo.active_items();
+                shown.extend(todo.checked_off_items());
+                if !shown.is_empty() {
+                    let term_w = term_cols as usize;
+                    for item in &shown {
                         let (symbol, color) = match item.status {
                             crate::tools::todo::TodoStatus::InProgress => ("●", CYAN),
                             crate::tools::todo::TodoStatus::Pending => ("○", DIM_GRAY),
-                            crate::tools::todo::TodoStatus::Completed => unreachable!(),
+                            crate::tools::todo::TodoStatus::Completed => ("✓", GREEN),
                         };
                         let priority_tag = match item.priority {
                             crate::tools::todo::TodoPriority::High => " [!]",
@@ -729,7 +812,7 @@ impl TuiRenderer {
 
         // ── 2. Separator: "──  ~/repos/finch ──────── jade-river ──" ──────────
         // CWD is left-anchored; session name is right-anchored.
-        let term_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
+        let term_width = term_cols as usize;
         let cwd_label = tilde_cwd();
         let prefix = "── ";
         let prefix_vis = 3_usize;
@@ -755,7 +838,8 @@ impl TuiRenderer {
         // ── 3. Dialog or input ────────────────────────────────────────────────
         let cursor_row_from_top;
         if let Some(dialog) = &self.active_dialog {
-            let dialog_rows = Self::draw_dialog_inline_static(&mut stdout, dialog)?;
+            let dialog_rows =
+                Self::draw_dialog_inline_static(&mut stdout, dialog, (term_cols, term_rows))?;
             rows += dialog_rows;
             // Dialog drawing ends each line with \r\n, so the cursor is one row
             // PAST the last drawn row (at row `rows`, 0-indexed from the start of
@@ -960,10 +1044,10 @@ impl TuiRenderer {
         if !to_commit.is_empty() {
             self.erase_live_area()?;
             for msg in &to_commit {
-                Self::raw_println(&msg.format(&self.colors))?;
+                self.raw_println(&msg.format(&self.colors))?;
                 // Blank line after every committed message so the output area
                 // stays readable (issue #15 — remove clutter between work items).
-                Self::raw_blank_line()?;
+                self.raw_blank_line()?;
             }
             self.draw_live_area()?;
         } else if self.last_render.elapsed() >= self.render_interval {
@@ -1004,7 +1088,7 @@ impl TuiRenderer {
 
         const PANEL_H: usize = 9; // rows (includes mode-hint header)
         const PANEL_W: usize = 44; // visible columns
-        let (term_cols, _term_rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let (term_cols, _term_rows) = self.output.size();
         let start_col = (term_cols as usize).saturating_sub(PANEL_W) as u16;
 
         // Build lines for the chosen view.
@@ -1042,7 +1126,7 @@ impl TuiRenderer {
             reset  = RESET,
         );
 
-        let mut stdout = io::stdout();
+        let mut stdout = self.output.clone();
         execute!(stdout, cursor::SavePosition)?;
 
         // Header row
@@ -1130,8 +1214,9 @@ impl TuiRenderer {
         //   Line 4:   ▝▜██████▛▘  <cwd>        (lower body + cwd)
         //   Line 5:      ╥  ╥                  (legs)
         //   Line 6:     ╱    ╲                 (perch)
+        let mut stdout = self.output.clone();
         execute!(
-            io::stdout(),
+            stdout,
             // Line 1 — head top
             Print("      "),
             SetForegroundColor(Color::DarkYellow),
@@ -1191,11 +1276,11 @@ impl TuiRenderer {
             use crossterm::style::{Color, ResetColor, SetForegroundColor, Print};
             // Print each proof line.
             for line in proof_output.lines() {
-                execute!(io::stdout(), Print(line), Print("\r\n"))?;
+                execute!(stdout, Print(line), Print("\r\n"))?;
             }
             // Summary line.
             execute!(
-                io::stdout(),
+                stdout,
                 Print("  "),
                 SetForegroundColor(if passed == total { Color::Green } else { Color::Red }),
                 Print(format!("{}/{} ✓", passed, total)),
@@ -1205,19 +1290,18 @@ impl TuiRenderer {
         }
 
         // Suggestion line — one compact row of things to try.
-        print_suggestions()?;
+        print_suggestions(&mut stdout)?;
 
         self.draw_live_area()
     }
 }
 
 /// Print a one-line hint row showing things worth trying.
-fn print_suggestions() -> std::io::Result<()> {
+fn print_suggestions(stdout: &mut impl Write) -> std::io::Result<()> {
     use crossterm::{
         style::{Color, ResetColor, SetForegroundColor, SetAttribute, Attribute, Print},
         execute,
     };
-    use std::io;
 
     // Pairs of (word, hint-colour).  The word is shown dim/grey; separator is dark-grey.
     let items: &[(&str, Color)] = &[
@@ -1229,14 +1313,14 @@ fn print_suggestions() -> std::io::Result<()> {
         ("slowest",      Color::Cyan),
     ];
 
-    execute!(io::stdout(), Print("  "), SetForegroundColor(Color::DarkGrey), Print("try:  "), ResetColor)?;
+    execute!(stdout, Print("  "), SetForegroundColor(Color::DarkGrey), Print("try:  "), ResetColor)?;
     for (i, (word, colour)) in items.iter().enumerate() {
-        execute!(io::stdout(), SetForegroundColor(*colour), SetAttribute(Attribute::Dim), Print(word), SetAttribute(Attribute::Reset), ResetColor)?;
+        execute!(stdout, SetForegroundColor(*colour), SetAttribute(Attribute::Dim), Print(word), SetAttribute(Attribute::Reset), ResetColor)?;
         if i + 1 < items.len() {
-            execute!(io::stdout(), SetForegroundColor(Color::DarkGrey), Print("  ·  "), ResetColor)?;
+            execute!(stdout, SetForegroundColor(Color::DarkGrey), Print("  ·  "), ResetColor)?;
         }
     }
-    execute!(io::stdout(), Print("\r\n"))?;
+    execute!(stdout, Print("\r\n"))?;
     Ok(())
 }
 
@@ -1252,13 +1336,22 @@ impl TuiRenderer {
         // Reset terminal state: show cursor, reset colours, move to a clean line.
         // The `\r\n` ensures the shell prompt lands on its own fresh line rather
         // than overwriting content from the erased live area.
-        let _ = execute!(io::stdout(), cursor::Show, ResetColor);
-        print!("\r\n");
+        let mut stdout = self.output.clone();
+        let _ = execute!(stdout, cursor::Show, ResetColor, Print("\r\n"));
         // Flush pending output BEFORE leaving raw mode — otherwise some terminals
         // silently discard buffered bytes after the mode switch.
-        let _ = io::stdout().flush();
-        let _ = disable_raw_mode();
-        Self::save_history(&self.command_history);
+        let _ = stdout.flush();
+        if self.output.is_stdout() {
+            let _ = disable_raw_mode();
+            let kept: Vec<String> = self
+                .command_history
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !self.incognito_history.contains(i))
+                .map(|(_, line)| line.clone())
+                .collect();
+            Self::save_history(&kept);
+        }
         self.output_manager.enable_stdout();
         Ok(())
     }
@@ -1289,7 +1382,7 @@ impl Drop for TuiRenderer {
         // Safety net: restore terminal if shutdown() was never explicitly called.
         // shutdown() sets is_active = false before doing anything, so this is
         // idempotent — if shutdown() already ran, this is a no-op.
-        if self.is_active {
+        if self.is_active && self.output.is_stdout() {
             let _ = disable_raw_mode();
             let _ = execute!(io::stdout(), cursor::Show, ResetColor);
             let _ = io::stdout().flush();
@@ -1324,7 +1417,7 @@ impl TuiRenderer {
                             if input.trim().is_empty() {
                                 continue;
                             }
-                            self.command_history.push(input.clone());
+                            self.push_history(input.clone());
                             self.history_index = None;
                             self.input_textarea = Self::create_clean_textarea();
                             self.render()?;
@@ -1372,7 +1465,7 @@ impl TuiRenderer {
 
     pub fn handle_resize(&mut self, _w: u16, _h: u16) -> Result<()> {
         // Clear the entire screen on resize to prevent ghosts from old layout
-        execute!(io::stdout(), Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        execute!(self.output.clone(), Clear(ClearType::All), cursor::MoveTo(0, 0))?;
         self.active_rows = 0;
         Ok(())
     }
@@ -1436,7 +1529,7 @@ pub(crate) fn format_custom_input_content(input: &str, cursor: usize) -> String
 ///
 /// Returns the number of terminal rows consumed (always 1).
 fn render_other_row_inline(
-    stdout: &mut io::Stdout,
+    stdout: &mut impl Write,
     inner: usize,
     is_on_other: bool,
     dialog: &Dialog,
@@ -1480,8 +1573,12 @@ fn render_other_row_inline(
 impl TuiRenderer {
     /// Draw a `Dialog` inline using crossterm box-drawing characters.
     /// Returns the number of terminal rows consumed.
-    fn draw_dialog_inline_static(stdout: &mut io::Stdout, dialog: &Dialog) -> Result<usize> {
-        let term_width = crossterm::terminal::size().unwrap_or((80, 24)).0 as usize;
+    fn draw_dialog_inline_static(
+        stdout: &mut impl Write,
+        dialog: &Dialog,
+        (term_cols, term_rows): (u16, u16),
+    ) -> Result<usize> {
+        let term_width = term_cols as usize;
         let box_width = term_width.min(72);
         let inner = box_width.saturating_sub(6); // │ + 2 spaces on each side + │ = 6
 
@@ -1518,7 +1615,7 @@ impl TuiRenderer {
 
         // Body text (optional, shown above the options divider)
         if let Some(ref body) = dialog.body {
-            let term_h = crossterm::terminal::size().unwrap_or((80, 24)).1 as usize;
+            let term_h = term_rows as usize;
             // Reserve ~12 rows for title, help, both dividers, options, and bottom border.
             let max_body_rows = term_h.saturating_sub(12).clamp(3, 15);
 
@@ -1647,7 +1744,7 @@ impl TuiRenderer {
                         no_style,
                         RESET,
                         "",
-                        w = inner.saturating_sub(12)
+                        w = inner.saturating_sub(10) // "Yes   No  " = 10 chars
                     ))
                 )?;
                 rows += 1;
@@ -1694,7 +1791,7 @@ impl TuiRenderer {
         };
 
         if let Some(md) = focused_markdown {
-            let term_height = crossterm::terminal::size().unwrap_or((80, 24)).1 as usize;
+            let term_height = term_rows as usize;
             let max_preview_lines = 10.min(term_height / 3).max(1);
 
             // Strip leading/trailing blank lines and collect non-empty content
@@ -1724,12 +1821,13 @@ impl TuiRenderer {
                 // Truncate to inner width using visible_length to handle ANSI codes
                 let vlen = shadow_buffer::visible_length(line);
                 let display = if vlen <= inner {
-                    format!("│  {:<w$}  │\r\n", line, w = inner)
+                    format!("│  {}{}{}  │\r\n", line, RESET, " ".repeat(inner - vlen))
                 } else {
-                    // Truncate by chars (ANSI codes make byte slicing unsafe)
-                    let truncated_line: String =
-                        line.chars().take(inner.saturating_sub(1)).collect();
-                    format!("│  {}…  │\r\n", truncated_line)
+                    let truncated_line = truncate_visible(line, inner.saturating_sub(1));
+                    // A wide character at the cut leaves one column over
+                    let pad =
+                        inner.saturating_sub(1 + shadow_buffer::visible_length(&truncated_line));
+                    format!("│  {}{}…{}  │\r\n", truncated_line, RESET, " ".repeat(pad))
                 };
                 execute!(stdout, Print(display))?;
                 rows += 1;
@@ -1801,7 +1899,7 @@ impl TuiRenderer {
             } else {
                 "  ↑↓ nav · Enter select · Esc cancel"
             };
-            let hint_vis = hint.len();
+            let hint_vis = hint.chars().count(); // not len(): ↑↓ and · are multi-byte
             execute!(
                 stdout,
                 Print(format!(
@@ -1993,6 +2091,15 @@ impl TuiRenderer {
 // ─── History persistence ──────────────────────────────────────────────────────
 
 impl TuiRenderer {
+    /// Add `input` to the command history; it is only saved on exit if the
+    /// session wasn't incognito when it was typed
+    pub(crate) fn push_history(&mut self, input: String) {
+        if crate::incognito::is_active() {
+            self.incognito_history.insert(self.command_history.len());
+        }
+        self.command_history.push(input);
+    }
+
     fn history_path() -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|h| h.join(".finch").join("history"))
     }
@@ -2068,6 +2175,20 @@ mod tests {
     use super::*;
     use crate::cli::command_autocomplete::CommandRegistry;
 
+    #[test]
+    fn truncate_visible_keeps_color_codes() {
+        let line = "\x1b[32m+let wide = \"中文\";\x1b[0m";
+        let cut = truncate_visible(line, 15);
+        assert_eq!(cut, "\x1b[32m+let wide = \"中");
+        assert_eq!(shadow_buffer::visible_length(&cut), 15);
+        // A wide character is never split
+        assert_eq!(
+            shadow_buffer::visible_length(&truncate_visible(line, 14)),
+            13
+        );
+        assert_eq!(truncate_visible("abc", 10), "abc");
+    }
+
     // ── count_status_lines ────────────────────────────────────────────────────
 
     #[test]
@@ -2745,4 +2866,39 @@ mod tests {
             "correct and buggy must differ for single-row case"
         );
     }
+
+    // ── headless rendering ────────────────────────────────────────────────────
+
+    fn headless(width: u16, height: u16) -> (TuiRenderer, CaptureTerminal) {
+        let term = CaptureTerminal::new(width, height);
+        let renderer = TuiRenderer::headless(
+            Arc::new(OutputManager::new(ColorScheme::default())),
+            Arc::new(StatusBar::new()),
+            ColorScheme::default(),
+            term.clone(),
+        );
+        (renderer, term)
+    }
+
+    #[test]
+    fn headless_wrapped_input_parks_cursor_after_text() {
+        let (mut renderer, term) = headless(80, 10);
+        renderer.input_textarea = TuiRenderer::create_clean_textarea_with_text(&"a".repeat(100));
+        renderer.render().unwrap();
+
+        // "❯ " + 100 chars = 102 columns → 78 on the first row, 22 on the second
+        let snapshot = term.snapshot();
+        let lines: Vec<&str> = snapshot.lines().collect();
+        assert_eq!(lines.len(), 5, "{}", snapshot);
+        assert_eq!(lines[1], format!("❯ {}", "a".repeat(78)));
+        assert_eq!(lines[2], "a".repeat(22));
+        assert_eq!(lines[3], "─".repeat(80));
+        assert_eq!(term.cursor(), (22, 2));
+        assert_eq!(renderer.cursor_row_from_top, 2);
+
+        // Erase + redraw must land on exactly the same rows
+        renderer.render().unwrap();
+        assert_eq!(term.snapshot(), snapshot);
+        assert!(term.scrollback().is_empty());
+    }
 }
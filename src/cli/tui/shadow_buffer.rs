@@ -262,7 +262,7 @@ impl ShadowBuffer {
 /// This covers the Unicode ranges that crossterm / terminal emulators treat as
 /// double-width without pulling in an extra crate.
 #[inline]
-fn char_display_width(c: char) -> usize {
+pub(crate) fn char_display_width(c: char) -> usize {
     match c as u32 {
         // Hangul Jamo
         0x1100..=0x115F |
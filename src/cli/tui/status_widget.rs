@@ -30,6 +30,22 @@ impl<'a> StatusWidget<'a> {
     /// Get the style for a status line based on its type
     fn get_line_style(&self, line_type: &StatusLineType) -> Style {
         match line_type {
+            StatusLineType::Alert => {
+                // Alert banner: loud, so a firing rule can't be missed
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            }
+            StatusLineType::RateLimit => {
+                // Rate-limit countdown: a warning, but quieter than alerts
+                Style::default().fg(Color::Yellow)
+            }
+            StatusLineType::Incognito => {
+                // Incognito: stands out for as long as it lasts
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD)
+            }
             StatusLineType::SessionLabel => {
                 // Session label: bold, prominent
                 Style::default()
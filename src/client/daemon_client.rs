@@ -4,15 +4,16 @@
 // Automatically spawns daemon if not running.
 
 use anyhow::{Context, Result};
-use reqwest::Client;
 use std::time::Duration;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
+use super::transport::HttpTransport;
 use crate::claude::{ContentBlock, Message};
 use crate::daemon::ensure_daemon_running;
 use crate::server::openai_types::{
     ChatCompletionRequest, ChatCompletionResponse, ChatMessage, FunctionDefinition, Tool,
 };
+use crate::server::Capabilities;
 use crate::tools::executor::ToolExecutor;
 use crate::tools::types::{ToolDefinition, ToolUse};
 
@@ -50,39 +51,42 @@ impl DaemonConfig {
 
 /// HTTP client for communicating with Shammah daemon
 pub struct DaemonClient {
-    base_url: String,
-    client: Client,
+    transport: HttpTransport,
     config: DaemonConfig,
+    /// Sent with every chat request so the daemon can cancel it by id
+    session_id: String,
+    /// What the daemon announced in `GET /health` at connect time
+    capabilities: Capabilities,
 }
 
 impl DaemonClient {
     /// Create a new daemon client and ensure daemon is running
     pub async fn connect(config: DaemonConfig) -> Result<Self> {
-        let base_url = format!("http://{}", config.bind_address);
+        // `host:port` or `unix:PATH` (HTTP over a Unix domain socket)
+        let transport = HttpTransport::new(
+            &config.bind_address,
+            Duration::from_secs(config.timeout_seconds),
+        )?;
 
         // Ensure daemon is running (auto-spawn if enabled)
         if config.auto_spawn {
             ensure_daemon_running(Some(&config.bind_address))
                 .await
                 .context("Failed to ensure daemon is running")?;
-        } else {
-            // Just check if daemon is reachable
-            Self::check_health(&base_url).await?;
         }
+        let capabilities = Self::handshake(&transport).await?;
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .pool_idle_timeout(Duration::from_secs(90))
-            .pool_max_idle_per_host(0) // Disable connection pooling
-            .build()
-            .context("Failed to build HTTP client")?;
-
-        info!(base_url = %base_url, "Connected to daemon");
+        info!(
+            endpoint = %transport.endpoint(),
+            protocol = capabilities.protocol_version,
+            "Connected to daemon"
+        );
 
         Ok(Self {
-            base_url,
-            client,
+            transport,
             config,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            capabilities,
         })
     }
 
@@ -136,31 +140,31 @@ impl DaemonClient {
             stop: None,
             tools: None,
             local_only: None,
+            cache: None,
+            session_id: Some(self.session_id.clone()),
+            incognito: crate::incognito::is_active().then_some(true),
+            // Finch clients recall and remember through their own memory
+            memory: Some(false),
         };
 
         // Send to daemon
-        let url = format!("{}/v1/chat/completions", self.base_url);
-        debug!(url = %url, "Sending chat completion request");
+        let path = "/v1/chat/completions";
+        debug!(path = %path, "Sending chat completion request");
 
         let response: ChatCompletionResponse = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
+            .transport
+            .post_json(path, &request, None)
             .await
             .map_err(|e| {
                 // Log detailed error info
                 error!("HTTP request failed: {}", e);
-                if e.is_timeout() {
-                    error!("  → Error type: TIMEOUT");
-                } else if e.is_connect() {
-                    error!("  → Error type: CONNECTION");
-                } else if e.is_request() {
-                    error!("  → Error type: REQUEST");
-                } else if e.is_body() {
-                    error!("  → Error type: BODY");
-                } else {
-                    error!("  → Error type: OTHER");
+                // Socket transport errors are plain I/O errors, not reqwest's
+                match e.downcast_ref::<reqwest::Error>() {
+                    Some(e) if e.is_timeout() => error!("  → Error type: TIMEOUT"),
+                    Some(e) if e.is_connect() => error!("  → Error type: CONNECTION"),
+                    Some(e) if e.is_request() => error!("  → Error type: REQUEST"),
+                    Some(e) if e.is_body() => error!("  → Error type: BODY"),
+                    _ => error!("  → Error type: OTHER"),
                 }
                 anyhow::anyhow!("Failed to send request to daemon: {}", e)
             })?
@@ -209,6 +213,27 @@ impl DaemonClient {
                 text: initial_query.to_string(),
             }],
         }];
+        self.continue_with_tools(&mut messages, &tools, tool_executor, false)
+            .await
+    }
+
+    /// The tool execution loop of `query_with_tools`, carrying on from
+    /// `messages`.  Each turn is appended as it completes, so after an error
+    /// the conversation can be resumed where it stopped, on the local model
+    /// alone when `local_only` is set.
+    pub async fn continue_with_tools(
+        &self,
+        messages: &mut Vec<Message>,
+        tools: &[ToolDefinition],
+        tool_executor: &ToolExecutor,
+        local_only: bool,
+    ) -> Result<String> {
+        if !self.capabilities.is_legacy() && self.capabilities.tool_passthrough == 0 {
+            anyhow::bail!(
+                "The daemon (finch {}) doesn't accept client-side tools",
+                self.capabilities.version
+            );
+        }
 
         const MAX_TURNS: usize = 30;
         let mut turn = 0;
@@ -220,8 +245,8 @@ impl DaemonClient {
             turn += 1;
 
             // Convert to OpenAI format
-            let openai_messages = Self::convert_to_openai_messages(&messages);
-            let openai_tools = Self::convert_to_openai_tools(&tools);
+            let openai_messages = Self::convert_to_openai_messages(messages);
+            let openai_tools = Self::convert_to_openai_tools(tools);
 
             // Send request
             let request = ChatCompletionRequest {
@@ -234,19 +259,27 @@ impl DaemonClient {
                 n: None,
                 stream: false,
                 stop: None,
-                local_only: None,
+                local_only: local_only.then_some(true),
+                cache: None,
+                session_id: Some(self.session_id.clone()),
+                incognito: crate::incognito::is_active().then_some(true),
+                memory: Some(false),
             };
 
-            let url = format!("{}/v1/chat/completions", self.base_url);
-            debug!(url = %url, turn, "Sending chat completion request with tools");
+            let path = "/v1/chat/completions";
+            debug!(path = %path, turn, "Sending chat completion request with tools");
 
-            let response: ChatCompletionResponse = self
-                .client
-                .post(&url)
-                .json(&request)
-                .send()
+            let response = self
+                .transport
+                .post_json(path, &request, None)
                 .await
-                .context("Failed to send request to daemon")?
+                .context("Failed to send request to daemon")?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Daemon error ({}): {}", status, body);
+            }
+            let response: ChatCompletionResponse = response
                 .json()
                 .await
                 .context("Failed to parse response from daemon")?;
@@ -276,31 +309,33 @@ impl DaemonClient {
                     content: tool_use_blocks.clone(),
                 });
 
-                // Execute tools locally
-                let mut tool_result_blocks = Vec::new();
-                for block in tool_use_blocks {
-                    if let ContentBlock::ToolUse { id, name, input } = block {
-                        info!("Executing tool locally: {} ({})", name, id);
-
-                        // Execute tool
-                        let tool_use = ToolUse {
-                            id: id.clone(),
-                            name,
-                            input,
-                        };
-
-                        let result = tool_executor
-                            .execute_tool::<fn() -> Result<()>>(
-                                &tool_use, None, None, None, None, None, None, None, None, None,
-                            )
-                            .await?;
+                // Execute tools locally, independent calls side by side
+                let tool_uses: Vec<ToolUse> = tool_use_blocks
+                    .into_iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolUse { id, name, input } => {
+                            info!("Executing tool locally: {} ({})", name, id);
+                            Some(ToolUse { id, name, input })
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let results = tool_executor
+                    .runner()
+                    .execute_batch(
+                        &tool_uses,
+                        Duration::from_secs(crate::config::constants::BATCH_TOOL_TIMEOUT_SECS),
+                    )
+                    .await;
 
-                        tool_result_blocks.push(ContentBlock::ToolResult {
-                            tool_use_id: id,
-                            content: result.content,
-                            is_error: Some(result.is_error),
-                        });
-                    }
+                let mut tool_result_blocks = Vec::new();
+                for (tool_use, result) in tool_uses.into_iter().zip(results) {
+                    let result = result?;
+                    tool_result_blocks.push(ContentBlock::ToolResult {
+                        tool_use_id: tool_use.id,
+                        content: result.content,
+                        is_error: Some(result.is_error),
+                    });
                 }
 
                 // Add tool results to conversation
@@ -393,7 +428,9 @@ impl DaemonClient {
                         // Mixed ToolResult + text: encode as text (edge case)
                         text_parts.push(format!("[Tool Result for {}]: {}", tool_use_id, content));
                     }
-                    ContentBlock::Image { .. } => {}
+                    ContentBlock::Image { .. }
+                    | ContentBlock::Thinking { .. }
+                    | ContentBlock::RedactedThinking { .. } => {}
                 }
             }
 
@@ -431,12 +468,10 @@ impl DaemonClient {
 
     /// Check daemon health
     pub async fn check_health_status(&self) -> Result<serde_json::Value> {
-        let url = format!("{}/health", self.base_url);
+        let path = "/health";
         let response = self
-            .client
-            .get(&url)
-            .timeout(Duration::from_secs(30)) // Increased from 5 to 30 seconds
-            .send()
+            .transport
+            .get(path, Some(Duration::from_secs(30))) // Increased from 5 to 30 seconds
             .await
             .context("Failed to check daemon health")?
             .json()
@@ -446,17 +481,11 @@ impl DaemonClient {
         Ok(response)
     }
 
-    /// Internal health check (used during connection)
-    async fn check_health(base_url: &str) -> Result<()> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30)) // Increased from 5 to 30 seconds
-            .build()
-            .context("Failed to build HTTP client")?;
-
-        let url = format!("{}/health", base_url);
-        let response = client
-            .get(&url)
-            .send()
+    /// Check the daemon is healthy and read its capabilities (used during
+    /// connection).  Fails if the daemon's protocol is incompatible.
+    async fn handshake(transport: &HttpTransport) -> Result<Capabilities> {
+        let response = transport
+            .get("/health", Some(Duration::from_secs(30))) // Increased from 5 to 30 seconds
             .await
             .context("Daemon is not reachable")?;
 
@@ -464,12 +493,39 @@ impl DaemonClient {
             anyhow::bail!("Daemon health check failed: {}", response.status());
         }
 
-        Ok(())
+        let health: serde_json::Value = response.json().await.unwrap_or_default();
+        let capabilities = parse_capabilities(&health);
+        if let Some(reason) = capabilities.incompatibility() {
+            anyhow::bail!("Can't use the daemon: {}", reason);
+        }
+        if capabilities.is_legacy() {
+            warn!("The daemon predates capability announcements; newer features may fail");
+        }
+        Ok(capabilities)
     }
 
-    /// Get base URL
+    /// What the daemon announced at connect time
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Fail with a clear message if the daemon doesn't serve `endpoint`
+    /// (usually because it was started by an older finch)
+    fn require(&self, endpoint: &str, feature: &str) -> Result<()> {
+        if self.capabilities.supports(endpoint) {
+            return Ok(());
+        }
+        anyhow::bail!(
+            "The daemon (finch {}) doesn't support {}; restart it with \
+             `finch daemon-stop` and `finch daemon-start`",
+            self.capabilities.version,
+            feature
+        )
+    }
+
+    /// Get base URL (`http://localhost` for Unix socket endpoints)
     pub fn base_url(&self) -> &str {
-        &self.base_url
+        self.transport.base_url()
     }
 
     /// Get configuration
@@ -477,6 +533,134 @@ impl DaemonClient {
         &self.config
     }
 
+    /// Session id this client's chat requests are tagged with
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Stop the response the daemon is generating for `session_id`, e.g.
+    /// when the user presses Ctrl+C, so it stops spending provider tokens.
+    ///
+    /// Returns `false` if nothing was being generated for the session.
+    pub async fn cancel_generation(&self, session_id: &str) -> Result<bool> {
+        self.require(
+            "/v1/sessions/:id/active-generation",
+            "cancelling generations",
+        )?;
+        let path = format!("/v1/sessions/{}/active-generation", session_id);
+        let response = self
+            .transport
+            .delete(&path, None)
+            .await
+            .context("Failed to cancel generation")?;
+        match response.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Cancel failed: {} {}", status, error_body);
+            }
+        }
+    }
+
+    /// Ask the daemon to also serve LAN worker traffic on `bind`, from the
+    /// models it already has loaded.  Returns the address it listens on.
+    pub async fn take_worker_role(&self, bind: &str) -> Result<String> {
+        self.require("/v1/roles/worker", "the worker role")?;
+        let response = self
+            .transport
+            .post_json(
+                "/v1/roles/worker",
+                &serde_json::json!({ "bind": bind }),
+                None,
+            )
+            .await
+            .context("Failed to ask the daemon to take the worker role")?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            let message = body["error"]["message"].as_str().unwrap_or("no details");
+            anyhow::bail!("Daemon refused the worker role: {}", message);
+        }
+        Ok(body["worker"].as_str().unwrap_or(bind).to_string())
+    }
+
+    /// Stop the daemon serving worker traffic.  `false` if it wasn't.
+    pub async fn drop_worker_role(&self) -> Result<bool> {
+        self.require("/v1/roles/worker", "the worker role")?;
+        let response = self
+            .transport
+            .delete("/v1/roles/worker", None)
+            .await
+            .context("Failed to ask the daemon to drop the worker role")?;
+        match response.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Dropping the worker role failed: {} {}", status, error_body);
+            }
+        }
+    }
+
+    /// Unload a local model and load it again with `request`'s changes
+    /// (`POST /v1/models/reload`).
+    ///
+    /// Calls `on_progress` as the load goes on and returns the name of the
+    /// model now loaded.  Fails if the daemon refuses or the load fails.
+    pub async fn reload_model<F>(
+        &self,
+        request: &crate::server::ModelReloadRequest,
+        mut on_progress: F,
+    ) -> Result<String>
+    where
+        F: FnMut(&crate::server::ReloadEvent) + Send,
+    {
+        use crate::server::ReloadEvent;
+        use futures::StreamExt;
+
+        self.require("/v1/models/reload", "model reloads")?;
+        // A first load of a large model can download for a long time
+        let response = self
+            .transport
+            .post_json(
+                "/v1/models/reload",
+                request,
+                Some(Duration::from_secs(24 * 60 * 60)),
+            )
+            .await
+            .context("Failed to ask the daemon to reload the model")?;
+        if !response.status().is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let message = body["error"]["message"].as_str().unwrap_or("no details");
+            anyhow::bail!("Daemon refused to reload the model: {}", message);
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buffer.extend_from_slice(&chunk.context("Failed to read reload progress")?);
+
+            while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let frame: Vec<u8> = buffer.drain(..pos + 2).collect();
+                for line in String::from_utf8_lossy(&frame).lines() {
+                    let Some(data) = line.strip_prefix("data:") else {
+                        continue;
+                    };
+                    match serde_json::from_str::<ReloadEvent>(data.trim_start()) {
+                        Ok(ReloadEvent::Ready { model_name }) => return Ok(model_name),
+                        Ok(ReloadEvent::Failed { error }) => {
+                            anyhow::bail!("Model reload failed: {}", error)
+                        }
+                        Ok(event) => on_progress(&event),
+                        Err(e) => debug!("Skipping unparseable reload event: {}", e),
+                    }
+                }
+            }
+        }
+        anyhow::bail!("The daemon closed the connection before the model was loaded")
+    }
+
     /// Query local model directly, bypassing routing
     ///
     /// This sends a request with local_only=true to bypass crisis detection
@@ -502,16 +686,18 @@ impl DaemonClient {
             stop: None,
             tools: None,
             local_only: Some(true), // KEY: Bypass routing
+            cache: None,
+            session_id: Some(self.session_id.clone()),
+            incognito: crate::incognito::is_active().then_some(true),
+            memory: Some(false),
         };
 
-        let url = format!("{}/v1/chat/completions", self.base_url);
-        debug!(url = %url, "Sending local-only query");
+        let path = "/v1/chat/completions";
+        debug!(path = %path, "Sending local-only query");
 
         let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
+            .transport
+            .post_json(path, &request, None)
             .await
             .context("Failed to send request to daemon")?;
 
@@ -642,17 +828,18 @@ impl DaemonClient {
             stop: None,
             tools: None,
             local_only: Some(true), // Bypass routing
+            cache: None,
+            session_id: Some(self.session_id.clone()),
+            incognito: crate::incognito::is_active().then_some(true),
+            memory: Some(false),
         };
 
-        let url = format!("{}/v1/chat/completions", self.base_url);
-        debug!(url = %url, "Sending streaming local-only query with callback");
+        let path = "/v1/chat/completions";
+        debug!(path = %path, "Sending streaming local-only query with callback");
 
         let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .timeout(Duration::from_secs(300)) // 5 minute timeout for streaming
-            .send()
+            .transport
+            .post_json(path, &request, Some(Duration::from_secs(300))) // 5 minute timeout for streaming
             .await
             .context("Failed to send streaming request to daemon")?;
 
@@ -786,17 +973,18 @@ impl DaemonClient {
             stop: None,
             tools: None,
             local_only: Some(true), // Bypass routing
+            cache: None,
+            session_id: Some(self.session_id.clone()),
+            incognito: crate::incognito::is_active().then_some(true),
+            memory: Some(false),
         };
 
-        let url = format!("{}/v1/chat/completions", self.base_url);
-        debug!(url = %url, "Sending streaming local-only query");
+        let path = "/v1/chat/completions";
+        debug!(path = %path, "Sending streaming local-only query");
 
         let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .timeout(Duration::from_secs(300)) // 5 minute timeout for streaming (per chunk, not total)
-            .send()
+            .transport
+            .post_json(path, &request, Some(Duration::from_secs(300))) // 5 minute timeout for streaming (per chunk, not total)
             .await
             .context("Failed to send streaming request to daemon")?;
 
@@ -905,13 +1093,11 @@ impl DaemonClient {
         task: &str,
         _name: Option<&str>,
     ) -> Result<crate::server::brain_registry::BrainSummary> {
-        let url = format!("{}/v1/brains", self.base_url);
+        let path = "/v1/brains";
         let body = serde_json::json!({ "task": task });
         let response: crate::server::brain_registry::BrainSummary = self
-            .client
-            .post(&url)
-            .json(&body)
-            .send()
+            .transport
+            .post_json(path, &body, None)
             .await
             .context("Failed to spawn brain")?
             .json()
@@ -922,11 +1108,26 @@ impl DaemonClient {
 
     /// List active brain sessions.
     pub async fn list_brains(&self) -> Result<Vec<crate::server::brain_registry::BrainSummary>> {
-        let url = format!("{}/v1/brains", self.base_url);
+        let path = "/v1/brains";
+        let response: Vec<crate::server::brain_registry::BrainSummary> = self
+            .transport
+            .get(path, None)
+            .await
+            .context("Failed to list brains")?
+            .json()
+            .await
+            .context("Failed to parse brain list")?;
+        Ok(response)
+    }
+
+    /// List all brain sessions, including finished ones.
+    pub async fn list_all_brains(
+        &self,
+    ) -> Result<Vec<crate::server::brain_registry::BrainSummary>> {
+        let path = "/v1/brains?all=true";
         let response: Vec<crate::server::brain_registry::BrainSummary> = self
-            .client
-            .get(&url)
-            .send()
+            .transport
+            .get(path, None)
             .await
             .context("Failed to list brains")?
             .json()
@@ -935,16 +1136,104 @@ impl DaemonClient {
         Ok(response)
     }
 
+    /// Follow a brain session's progress.
+    ///
+    /// Calls `on_event` for every event from `GET /v1/brains/:id/events`,
+    /// starting with a `Snapshot`, and returns once the brain is dead or
+    /// cancelled.
+    pub async fn watch_brain<F>(&self, id: uuid::Uuid, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(crate::server::brain_registry::BrainEvent) + Send,
+    {
+        use futures::StreamExt;
+
+        let path = format!("/v1/brains/{}/events", id);
+        // Brains may wait on the user for a long time; don't cut the stream short
+        let response = self
+            .transport
+            .get(&path, Some(Duration::from_secs(24 * 60 * 60)))
+            .await
+            .context("Failed to subscribe to brain events")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to watch brain {}: {} {}", id, status, error_body);
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buffer.extend_from_slice(&chunk.context("Failed to read brain event stream")?);
+
+            // SSE frames end with a blank line; keep-alive frames carry no data
+            while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let frame: Vec<u8> = buffer.drain(..pos + 2).collect();
+                for line in String::from_utf8_lossy(&frame).lines() {
+                    if let Some(data) = line.strip_prefix("data:") {
+                        match serde_json::from_str(data.trim_start()) {
+                            Ok(event) => on_event(event),
+                            Err(e) => debug!("Skipping unparseable brain event: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Follow the daemon's activity feed.
+    ///
+    /// Calls `on_activity` for every request answered and daemon event
+    /// emitted from now on (`GET /v1/events`), until the daemon closes the
+    /// stream.
+    pub async fn watch_activity<F>(&self, mut on_activity: F) -> Result<()>
+    where
+        F: FnMut(crate::server::Activity) + Send,
+    {
+        use futures::StreamExt;
+
+        self.require("/v1/events", "the activity feed")?;
+        // The feed runs until someone stops watching
+        let response = self
+            .transport
+            .get("/v1/events", Some(Duration::from_secs(24 * 60 * 60)))
+            .await
+            .context("Failed to subscribe to daemon activity")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to watch daemon activity: {} {}", status, error_body);
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buffer.extend_from_slice(&chunk.context("Failed to read activity stream")?);
+
+            while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let frame: Vec<u8> = buffer.drain(..pos + 2).collect();
+                for line in String::from_utf8_lossy(&frame).lines() {
+                    if let Some(data) = line.strip_prefix("data:") {
+                        match serde_json::from_str(data.trim_start()) {
+                            Ok(activity) => on_activity(activity),
+                            Err(e) => debug!("Skipping unparseable activity: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Get full detail for a brain session.
     pub async fn get_brain(
         &self,
         id: uuid::Uuid,
     ) -> Result<crate::server::brain_registry::BrainDetail> {
-        let url = format!("{}/v1/brains/{}", self.base_url, id);
+        let path = format!("/v1/brains/{}", id);
         let response: crate::server::brain_registry::BrainDetail = self
-            .client
-            .get(&url)
-            .send()
+            .transport
+            .get(&path, None)
             .await
             .context("Failed to get brain")?
             .json()
@@ -955,12 +1244,10 @@ impl DaemonClient {
 
     /// Answer a pending question in a brain session.
     pub async fn answer_brain_question(&self, id: uuid::Uuid, answer: &str) -> Result<()> {
-        let url = format!("{}/v1/brains/{}/answer", self.base_url, id);
+        let path = format!("/v1/brains/{}/answer", id);
         let body = serde_json::json!({ "answer": answer });
-        self.client
-            .post(&url)
-            .json(&body)
-            .send()
+        self.transport
+            .post_json(&path, &body, None)
             .await
             .context("Failed to answer brain question")?;
         Ok(())
@@ -973,15 +1260,13 @@ impl DaemonClient {
         action: &str,
         feedback: Option<&str>,
     ) -> Result<()> {
-        let url = format!("{}/v1/brains/{}/plan", self.base_url, id);
+        let path = format!("/v1/brains/{}/plan", id);
         let mut body = serde_json::json!({ "action": action });
         if let Some(fb) = feedback {
             body["feedback"] = serde_json::Value::String(fb.to_string());
         }
-        self.client
-            .post(&url)
-            .json(&body)
-            .send()
+        self.transport
+            .post_json(&path, &body, None)
             .await
             .context("Failed to respond to brain plan")?;
         Ok(())
@@ -989,15 +1274,53 @@ impl DaemonClient {
 
     /// Cancel a brain session.
     pub async fn cancel_brain(&self, id: uuid::Uuid) -> Result<()> {
-        let url = format!("{}/v1/brains/{}", self.base_url, id);
-        self.client
-            .delete(&url)
-            .send()
+        let path = format!("/v1/brains/{}", id);
+        self.transport
+            .delete(&path, None)
             .await
             .context("Failed to cancel brain")?;
         Ok(())
     }
 
+    /// Fetch the `n`th most recent provider exchange of a session (1 is the
+    /// latest), re-sent to `provider` and/or `model` when either is given.
+    pub async fn replay_session_request(
+        &self,
+        session_id: &str,
+        n: usize,
+        provider: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<crate::server::handlers::ReplayResponse> {
+        self.require("/admin/sessions/:id/replay/:n", "request replay")?;
+        let mut url = reqwest::Url::parse("http://daemon")?
+            .join(&format!("/admin/sessions/{}/replay/{}", session_id, n))?;
+        if let Some(provider) = provider {
+            url.query_pairs_mut().append_pair("provider", provider);
+        }
+        if let Some(model) = model {
+            url.query_pairs_mut().append_pair("model", model);
+        }
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+
+        let response = self
+            .transport
+            .get(&path, None)
+            .await
+            .context("Failed to fetch captured request")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Replay failed: {} {}", status, error_body);
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse replay response")
+    }
+
     /// Query local model only with automatic crash recovery
     ///
     /// Same as query_local_only but with automatic daemon restart on connection failure.
@@ -1053,6 +1376,15 @@ impl DaemonClient {
     }
 }
 
+/// The capabilities in a health response; a daemon that predates them gets
+/// the legacy default
+fn parse_capabilities(health: &serde_json::Value) -> Capabilities {
+    health
+        .get("capabilities")
+        .and_then(|c| serde_json::from_value(c.clone()).ok())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1064,4 +1396,20 @@ mod tests {
         assert!(config.auto_spawn);
         assert_eq!(config.timeout_seconds, 120);
     }
+
+    #[test]
+    fn test_parse_capabilities() {
+        let health = serde_json::json!({
+            "status": "healthy",
+            "capabilities": {"protocol_version": 1, "endpoints": ["/health", "/v1/events"]}
+        });
+        let capabilities = parse_capabilities(&health);
+        assert!(capabilities.supports("/v1/events"));
+        assert!(!capabilities.supports("/v1/models/reload"));
+
+        // A daemon from before capabilities were announced
+        let legacy = parse_capabilities(&serde_json::json!({"status": "healthy"}));
+        assert!(legacy.is_legacy());
+        assert!(legacy.supports("/v1/models/reload"));
+    }
 }
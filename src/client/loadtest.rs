@@ -0,0 +1,518 @@
+// Load generator for the daemon (`finch loadtest`)
+//
+// Runs N synthetic sessions side by side against `/v1/chat/completions`.
+// Every session works through `requests` conversations. A conversation opens
+// with a filler prompt of `prompt_chars` characters, then adds `tool_depth`
+// made-up tool rounds, sending the whole conversation again after each round.
+// The daemon therefore sees the growing context of a real tool loop, however
+// the model answers. While the run lasts, `/health` is polled for the
+// daemon's resident memory, so leaks under concurrency show up as growth.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::transport::HttpTransport;
+use crate::server::openai_types::{
+    ChatCompletionRequest, ChatMessage, FunctionCall, FunctionDefinition, Tool, ToolCall,
+};
+
+/// How often `/health` is sampled for the daemon's memory during a run
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Name of the made-up tool the synthetic conversations call
+const SYNTHETIC_TOOL: &str = "lookup_record";
+
+const FILLER_WORDS: &[&str] = &[
+    "daemon",
+    "latency",
+    "worker",
+    "session",
+    "throughput",
+    "queue",
+    "model",
+    "token",
+    "request",
+    "cache",
+    "memory",
+    "budget",
+    "provider",
+    "stream",
+    "context",
+    "answer",
+];
+
+/// Shape of the synthetic load
+#[derive(Debug, Clone)]
+pub struct LoadTestOptions {
+    /// Sessions running at the same time
+    pub sessions: usize,
+    /// Conversations each session runs, one after another
+    pub requests: usize,
+    /// Length of the opening prompt, in characters
+    pub prompt_chars: usize,
+    /// Tool rounds per conversation; each one is another request
+    pub tool_depth: usize,
+    /// Cap on generated tokens per request
+    pub max_tokens: u32,
+    /// Keep requests on the local model instead of routing to a teacher
+    pub local_only: bool,
+}
+
+impl LoadTestOptions {
+    /// Requests the run will send in total
+    pub fn total_requests(&self) -> usize {
+        self.sessions * self.requests * (self.tool_depth + 1)
+    }
+}
+
+/// Latency percentiles over the successful requests, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+    pub mean_ms: u64,
+}
+
+impl LatencySummary {
+    fn from_samples(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let percentile = |p: f64| {
+            let idx = ((samples.len() as f64 * p).ceil() as usize).saturating_sub(1);
+            samples[idx.min(samples.len() - 1)]
+        };
+        Self {
+            p50_ms: percentile(0.50),
+            p95_ms: percentile(0.95),
+            p99_ms: percentile(0.99),
+            max_ms: samples[samples.len() - 1],
+            mean_ms: samples.iter().sum::<u64>() / samples.len() as u64,
+        }
+    }
+}
+
+/// Daemon resident memory over the run, from `/health`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemoryGrowth {
+    pub start_mb: u64,
+    pub peak_mb: u64,
+    pub end_mb: u64,
+}
+
+impl MemoryGrowth {
+    /// Memory still held after the run, relative to the start
+    pub fn growth_mb(&self) -> i64 {
+        self.end_mb as i64 - self.start_mb as i64
+    }
+}
+
+/// Outcome of a load test
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadTestReport {
+    pub sessions: usize,
+    pub requests: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Failures by kind, e.g. "HTTP 503" or "timeout"
+    pub errors: BTreeMap<String, usize>,
+    pub elapsed_secs: f64,
+    /// Successful requests per second
+    pub throughput: f64,
+    pub latency: LatencySummary,
+    /// `None` when the daemon's `/health` doesn't report memory
+    pub memory: Option<MemoryGrowth>,
+}
+
+impl LoadTestReport {
+    /// Share of requests that failed (0.0-1.0)
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.failed as f64 / self.requests as f64
+    }
+
+    /// Plain-text report for the terminal
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Requests   : {} ({} ok, {} failed, {:.1}% errors)\n",
+            self.requests,
+            self.succeeded,
+            self.failed,
+            self.error_rate() * 100.0
+        );
+        for (kind, count) in &self.errors {
+            out.push_str(&format!("             {} × {}\n", count, kind));
+        }
+        out.push_str(&format!(
+            "Duration   : {:.1}s with {} sessions\n",
+            self.elapsed_secs, self.sessions
+        ));
+        out.push_str(&format!("Throughput : {:.2} req/s\n", self.throughput));
+        let l = &self.latency;
+        out.push_str(&format!(
+            "Latency    : p50 {}ms  p95 {}ms  p99 {}ms  max {}ms  mean {}ms\n",
+            l.p50_ms, l.p95_ms, l.p99_ms, l.max_ms, l.mean_ms
+        ));
+        match &self.memory {
+            Some(m) => out.push_str(&format!(
+                "Memory     : {} MB → {} MB (peak {} MB, {:+} MB)\n",
+                m.start_mb,
+                m.end_mb,
+                m.peak_mb,
+                m.growth_mb()
+            )),
+            None => out.push_str("Memory     : not reported by the daemon\n"),
+        }
+        out
+    }
+}
+
+/// Result of one request
+struct Sample {
+    latency_ms: u64,
+    error: Option<String>,
+}
+
+/// Run the load test described by `options` against the daemon behind
+/// `transport`.  `on_progress` is called with the requests finished so far.
+pub async fn run(
+    transport: HttpTransport,
+    options: LoadTestOptions,
+    on_progress: impl Fn(usize) + Send + Sync + 'static,
+) -> Result<LoadTestReport> {
+    anyhow::ensure!(options.sessions > 0, "need at least one session");
+    anyhow::ensure!(
+        options.requests > 0,
+        "need at least one request per session"
+    );
+
+    let start_mb = process_mb(&transport)
+        .await
+        .context("Daemon is not reachable")?;
+    let peak_mb = Arc::new(Mutex::new(start_mb));
+    let sampler = start_mb.map(|_| {
+        let transport = transport.clone();
+        let peak_mb = peak_mb.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MEMORY_SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Ok(Some(mb)) = process_mb(&transport).await {
+                    let mut peak = peak_mb.lock().await;
+                    *peak = (*peak).max(Some(mb));
+                }
+            }
+        })
+    });
+
+    let samples = Arc::new(Mutex::new(Vec::with_capacity(options.total_requests())));
+    let on_progress = Arc::new(on_progress);
+    let started = Instant::now();
+    let mut sessions = Vec::with_capacity(options.sessions);
+    for index in 0..options.sessions {
+        let transport = transport.clone();
+        let options = options.clone();
+        let samples = samples.clone();
+        let on_progress = on_progress.clone();
+        sessions.push(tokio::spawn(async move {
+            run_session(index, &transport, &options, &samples, &*on_progress).await
+        }));
+    }
+    for session in sessions {
+        session.await.context("Load test session panicked")?;
+    }
+    let elapsed = started.elapsed();
+
+    if let Some(sampler) = sampler {
+        sampler.abort();
+    }
+    let end_mb = process_mb(&transport).await.ok().flatten();
+    let peak_mb = *peak_mb.lock().await;
+    let memory = match (start_mb, end_mb) {
+        (Some(start_mb), Some(end_mb)) => Some(MemoryGrowth {
+            start_mb,
+            peak_mb: peak_mb.unwrap_or(start_mb).max(end_mb),
+            end_mb,
+        }),
+        _ => None,
+    };
+
+    let samples = std::mem::take(&mut *samples.lock().await);
+    Ok(summarize(&options, samples, elapsed, memory))
+}
+
+fn summarize(
+    options: &LoadTestOptions,
+    samples: Vec<Sample>,
+    elapsed: Duration,
+    memory: Option<MemoryGrowth>,
+) -> LoadTestReport {
+    let mut errors = BTreeMap::new();
+    let mut latencies = Vec::with_capacity(samples.len());
+    for sample in &samples {
+        match &sample.error {
+            Some(kind) => *errors.entry(kind.clone()).or_insert(0) += 1,
+            None => latencies.push(sample.latency_ms),
+        }
+    }
+    let succeeded = latencies.len();
+    let elapsed_secs = elapsed.as_secs_f64();
+    LoadTestReport {
+        sessions: options.sessions,
+        requests: samples.len(),
+        succeeded,
+        failed: samples.len() - succeeded,
+        errors,
+        elapsed_secs,
+        throughput: if elapsed_secs > 0.0 {
+            succeeded as f64 / elapsed_secs
+        } else {
+            0.0
+        },
+        latency: LatencySummary::from_samples(latencies),
+        memory,
+    }
+}
+
+/// One synthetic session: `requests` conversations of `tool_depth + 1`
+/// requests each, all under the same session id.
+async fn run_session(
+    index: usize,
+    transport: &HttpTransport,
+    options: &LoadTestOptions,
+    samples: &Mutex<Vec<Sample>>,
+    on_progress: &(dyn Fn(usize) + Send + Sync),
+) {
+    let session_id = format!("loadtest-{}-{}", index, uuid::Uuid::new_v4());
+    for conversation in 0..options.requests {
+        let mut messages = vec![ChatMessage {
+            role: "user".to_string(),
+            content: Some(synthetic_prompt(
+                &format!("{} {}", session_id, conversation),
+                options.prompt_chars,
+            )),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        }];
+        for round in 0..=options.tool_depth {
+            let sample = send(transport, options, &session_id, &messages).await;
+            let failed = sample.error.is_some();
+            {
+                let mut samples = samples.lock().await;
+                samples.push(sample);
+                on_progress(samples.len());
+            }
+            // A conversation whose request failed can't meaningfully go on
+            if failed || round == options.tool_depth {
+                break;
+            }
+            messages.extend(synthetic_tool_round(round));
+        }
+    }
+}
+
+async fn send(
+    transport: &HttpTransport,
+    options: &LoadTestOptions,
+    session_id: &str,
+    messages: &[ChatMessage],
+) -> Sample {
+    let request = ChatCompletionRequest {
+        model: "qwen-local".to_string(),
+        messages: messages.to_vec(),
+        max_tokens: Some(options.max_tokens),
+        temperature: None,
+        top_p: None,
+        n: None,
+        stream: false,
+        stop: None,
+        tools: (options.tool_depth > 0).then(|| vec![synthetic_tool()]),
+        local_only: options.local_only.then_some(true),
+        // Identical prompts would otherwise be answered from the cache
+        cache: Some(false),
+        session_id: Some(session_id.to_string()),
+        // Keep synthetic traffic out of memory, logs and training data
+        incognito: Some(true),
+        memory: Some(false),
+    };
+
+    let started = Instant::now();
+    let error = match transport
+        .post_json("/v1/chat/completions", &request, None)
+        .await
+    {
+        Ok(response) if response.status().is_success() => {
+            // Count a request as done only once its whole body has arrived
+            response.bytes().await.err().map(|_| "body".to_string())
+        }
+        Ok(response) => Some(format!("HTTP {}", response.status().as_u16())),
+        Err(e) => Some(classify_error(&e)),
+    };
+    Sample {
+        latency_ms: started.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+fn classify_error(e: &anyhow::Error) -> String {
+    match e.downcast_ref::<reqwest::Error>() {
+        Some(e) if e.is_timeout() => "timeout".to_string(),
+        Some(e) if e.is_connect() => "connect".to_string(),
+        _ => "transport".to_string(),
+    }
+}
+
+/// Daemon resident memory from `/health`; `None` if it isn't reported
+async fn process_mb(transport: &HttpTransport) -> Result<Option<u64>> {
+    let health: serde_json::Value = transport
+        .get("/health", Some(Duration::from_secs(5)))
+        .await?
+        .json()
+        .await?;
+    Ok(health
+        .pointer("/memory/process_mb")
+        .and_then(|v| v.as_u64()))
+}
+
+/// Filler prompt of about `chars` characters, made unique by `seed`
+fn synthetic_prompt(seed: &str, chars: usize) -> String {
+    let mut prompt = format!("[{}] Summarize the following notes in one sentence:", seed);
+    let mut i = seed.len();
+    while prompt.len() < chars {
+        prompt.push(' ');
+        prompt.push_str(FILLER_WORDS[i % FILLER_WORDS.len()]);
+        i = i.wrapping_mul(31).wrapping_add(7);
+    }
+    prompt
+}
+
+fn synthetic_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: FunctionDefinition {
+            name: SYNTHETIC_TOOL.to_string(),
+            description: Some("Look up a record by id".to_string()),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" } },
+                "required": ["id"],
+            }),
+        },
+    }
+}
+
+/// An assistant tool call followed by its result, as a client running the
+/// tool loop would append them
+fn synthetic_tool_round(round: usize) -> [ChatMessage; 2] {
+    let call_id = format!("call_loadtest_{}", round);
+    [
+        ChatMessage {
+            role: "assistant".to_string(),
+            content: None,
+            tool_calls: Some(vec![ToolCall {
+                id: call_id.clone(),
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name: SYNTHETIC_TOOL.to_string(),
+                    arguments: format!("{{\"id\":{}}}", round),
+                },
+            }]),
+            tool_call_id: None,
+            name: None,
+        },
+        ChatMessage {
+            role: "tool".to_string(),
+            content: Some(format!(
+                "{{\"id\":{},\"status\":\"active\",\"owner\":\"ops\",\"notes\":\"{}\"}}",
+                round,
+                synthetic_prompt(&call_id, 200)
+            )),
+            tool_calls: None,
+            tool_call_id: Some(call_id),
+            name: None,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let latency = LatencySummary::from_samples((1..=100).rev().collect());
+        assert_eq!(latency.p50_ms, 50);
+        assert_eq!(latency.p95_ms, 95);
+        assert_eq!(latency.p99_ms, 99);
+        assert_eq!(latency.max_ms, 100);
+        assert_eq!(latency.mean_ms, 50);
+        assert_eq!(
+            LatencySummary::from_samples(vec![]),
+            LatencySummary::default()
+        );
+    }
+
+    #[test]
+    fn test_summarize_counts_errors_and_throughput() {
+        let options = LoadTestOptions {
+            sessions: 2,
+            requests: 1,
+            prompt_chars: 100,
+            tool_depth: 1,
+            max_tokens: 16,
+            local_only: true,
+        };
+        let sample = |latency_ms, error: Option<&str>| Sample {
+            latency_ms,
+            error: error.map(str::to_string),
+        };
+        let samples = vec![
+            sample(100, None),
+            sample(300, None),
+            sample(50, Some("HTTP 503")),
+            sample(5000, Some("timeout")),
+        ];
+        let report = summarize(&options, samples, Duration::from_secs(2), None);
+        assert_eq!(report.requests, 4);
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.errors.get("HTTP 503"), Some(&1));
+        assert_eq!(report.error_rate(), 0.5);
+        assert_eq!(report.throughput, 1.0);
+        assert_eq!(report.latency.max_ms, 300);
+        assert!(report.to_text().contains("not reported"));
+    }
+
+    #[test]
+    fn test_synthetic_conversation_shape() {
+        let prompt = synthetic_prompt("s 0", 500);
+        assert!(prompt.len() >= 500 && prompt.len() < 520);
+        assert_ne!(prompt, synthetic_prompt("s 1", 500));
+
+        let [call, result] = synthetic_tool_round(3);
+        let tool_call = &call.tool_calls.as_ref().unwrap()[0];
+        assert_eq!(tool_call.function.name, SYNTHETIC_TOOL);
+        assert_eq!(result.role, "tool");
+        assert_eq!(result.tool_call_id.as_deref(), Some(tool_call.id.as_str()));
+    }
+
+    #[test]
+    fn test_memory_growth() {
+        let memory = MemoryGrowth {
+            start_mb: 900,
+            peak_mb: 1400,
+            end_mb: 950,
+        };
+        assert_eq!(memory.growth_mb(), 50);
+    }
+}
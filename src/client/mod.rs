@@ -4,5 +4,8 @@
 // Handles auto-spawn, health checks, and message passing.
 
 mod daemon_client;
+pub mod loadtest;
+pub mod transport;
 
 pub use daemon_client::{DaemonClient, DaemonConfig};
+pub use transport::{DaemonResponse, HttpTransport};
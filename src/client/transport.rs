@@ -0,0 +1,322 @@
+// HTTP transport to the daemon
+//
+// TCP endpoints go through reqwest.  reqwest cannot dial Unix domain sockets,
+// so `unix:` endpoints speak HTTP/1.1 over a `UnixStream` with hyper directly,
+// one connection per request (the TCP client disables pooling too).
+//
+// When `FINCH_API_KEY` is set it is sent as a bearer token, for daemons that
+// serve several workspaces.  Otherwise daemons on this machine get the
+// per-install token from ~/.finch/daemon.token.
+
+use anyhow::{Context, Result};
+use futures::stream::{BoxStream, StreamExt};
+use hyper::body::Bytes;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::daemon::DaemonEndpoint;
+
+/// Sends requests to the daemon over TCP or a Unix domain socket
+#[derive(Clone)]
+pub struct HttpTransport {
+    endpoint: DaemonEndpoint,
+    /// `http://host:port` for TCP; a placeholder origin for sockets
+    base_url: String,
+    timeout: Duration,
+    client: reqwest::Client,
+    /// Workspace key from `FINCH_API_KEY`, or the per-install token
+    api_key: Option<String>,
+}
+
+/// Response from the daemon, independent of the transport it came over
+pub struct DaemonResponse {
+    status: StatusCode,
+    body: ResponseBody,
+}
+
+enum ResponseBody {
+    Tcp(reqwest::Response),
+    #[cfg(unix)]
+    Unix(hyper::body::Incoming),
+}
+
+impl HttpTransport {
+    /// Build a transport for `addr` (`host:port` or `unix:PATH`).
+    ///
+    /// `timeout` bounds each request; for sockets it covers connecting and
+    /// receiving response headers, so long SSE bodies are not cut off.
+    pub fn new(addr: &str, timeout: Duration) -> Result<Self> {
+        let endpoint = DaemonEndpoint::parse(addr)?;
+        let base_url = match &endpoint {
+            DaemonEndpoint::Tcp(addr) => format!("http://{}", addr),
+            DaemonEndpoint::Unix(_) => "http://localhost".to_string(),
+        };
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .pool_max_idle_per_host(0) // Disable connection pooling
+            .build()
+            .context("Failed to build HTTP client")?;
+        let api_key = std::env::var("FINCH_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .or_else(|| endpoint.is_local().then(local_token).flatten());
+        Ok(Self {
+            endpoint,
+            base_url,
+            timeout,
+            client,
+            api_key,
+        })
+    }
+
+    /// Endpoint this transport talks to
+    pub fn endpoint(&self) -> &DaemonEndpoint {
+        &self.endpoint
+    }
+
+    /// Origin used to build request URLs (`http://host:port`)
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// `GET path`, optionally overriding the default timeout
+    pub async fn get(&self, path: &str, timeout: Option<Duration>) -> Result<DaemonResponse> {
+        self.send(hyper::Method::GET, path, None, timeout).await
+    }
+
+    /// `POST path` with a JSON body, optionally overriding the default timeout
+    pub async fn post_json<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+        timeout: Option<Duration>,
+    ) -> Result<DaemonResponse> {
+        let body = serde_json::to_vec(body).context("Failed to serialize request body")?;
+        self.send(hyper::Method::POST, path, Some(body), timeout)
+            .await
+    }
+
+    /// `DELETE path`, optionally overriding the default timeout
+    pub async fn delete(&self, path: &str, timeout: Option<Duration>) -> Result<DaemonResponse> {
+        self.send(hyper::Method::DELETE, path, None, timeout).await
+    }
+
+    async fn send(
+        &self,
+        method: hyper::Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+        timeout: Option<Duration>,
+    ) -> Result<DaemonResponse> {
+        let timeout = timeout.unwrap_or(self.timeout);
+        match &self.endpoint {
+            DaemonEndpoint::Tcp(_) => {
+                let url = format!("{}{}", self.base_url, path);
+                let method = reqwest::Method::from_bytes(method.as_str().as_bytes())?;
+                let mut request = self.client.request(method, &url).timeout(timeout);
+                if let Some(key) = &self.api_key {
+                    request = request.bearer_auth(key);
+                }
+                if let Some(body) = body {
+                    request = request
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(body);
+                }
+                let response = request.send().await?;
+                Ok(DaemonResponse {
+                    status: response.status(),
+                    body: ResponseBody::Tcp(response),
+                })
+            }
+            #[cfg(unix)]
+            DaemonEndpoint::Unix(socket) => {
+                let api_key = self.api_key.as_deref();
+                tokio::time::timeout(timeout, unix::send(socket, method, path, body, api_key))
+                    .await
+                    .with_context(|| {
+                        format!("Request to {} timed out after {:?}", self.endpoint, timeout)
+                    })?
+            }
+            #[cfg(not(unix))]
+            DaemonEndpoint::Unix(socket) => {
+                anyhow::bail!(
+                    "Unix domain sockets are not supported: {}",
+                    socket.display()
+                )
+            }
+        }
+    }
+}
+
+/// The per-install token, created if this client runs before the daemon
+fn local_token() -> Option<String> {
+    let path = crate::daemon::token::default_path()?;
+    match crate::daemon::token::load_or_create(&path) {
+        Ok(token) => Some(token),
+        Err(e) => {
+            tracing::debug!("No daemon token: {:#}", e);
+            None
+        }
+    }
+}
+
+impl DaemonResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Read the whole body
+    pub async fn bytes(self) -> Result<Bytes> {
+        match self.body {
+            ResponseBody::Tcp(response) => Ok(response.bytes().await?),
+            #[cfg(unix)]
+            ResponseBody::Unix(incoming) => {
+                use http_body_util::BodyExt;
+                Ok(incoming.collect().await?.to_bytes())
+            }
+        }
+    }
+
+    pub async fn text(self) -> Result<String> {
+        let bytes = self.bytes().await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        let bytes = self.bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Stream the body as it arrives (for SSE responses)
+    pub fn bytes_stream(self) -> BoxStream<'static, Result<Bytes>> {
+        match self.body {
+            ResponseBody::Tcp(response) => response
+                .bytes_stream()
+                .map(|chunk| chunk.map_err(anyhow::Error::from))
+                .boxed(),
+            #[cfg(unix)]
+            ResponseBody::Unix(incoming) => http_body_util::BodyStream::new(incoming)
+                .filter_map(|frame| async move {
+                    match frame {
+                        Ok(frame) => frame.into_data().ok().map(Ok),
+                        Err(e) => Some(Err(anyhow::Error::from(e))),
+                    }
+                })
+                .boxed(),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{DaemonResponse, ResponseBody};
+    use anyhow::Result;
+    use http_body_util::Full;
+    use hyper::body::Bytes;
+    use hyper_util::rt::TokioIo;
+    use std::path::Path;
+    use tokio::net::UnixStream;
+
+    pub(super) async fn send(
+        socket: &Path,
+        method: hyper::Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+        api_key: Option<&str>,
+    ) -> Result<DaemonResponse> {
+        // Keep the OS error in the message: callers match "connection refused"
+        // to decide whether to restart the daemon.
+        let stream = UnixStream::connect(socket)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to {}: {}", socket.display(), e))?;
+        let (mut sender, connection) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("Daemon socket connection closed with error: {}", e);
+            }
+        });
+
+        let mut request = hyper::Request::builder()
+            .method(method)
+            .uri(path)
+            .header(hyper::header::HOST, "localhost");
+        if let Some(key) = api_key {
+            request = request.header(hyper::header::AUTHORIZATION, format!("Bearer {}", key));
+        }
+        if body.is_some() {
+            request = request.header(hyper::header::CONTENT_TYPE, "application/json");
+        }
+        let request = request.body(Full::new(Bytes::from(body.unwrap_or_default())))?;
+
+        let response = sender.send_request(request).await?;
+        Ok(DaemonResponse {
+            status: reqwest::StatusCode::from_u16(response.status().as_u16())?,
+            body: ResponseBody::Unix(response.into_body()),
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+
+    /// Serve a tiny router on a temp socket and talk to it through the transport.
+    #[tokio::test]
+    async fn test_unix_socket_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route(
+                "/echo",
+                post(|Json(v): Json<serde_json::Value>| async move { Json(v) }),
+            );
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let service = hyper_util::service::TowerToHyperService::new(app.clone());
+                tokio::spawn(async move {
+                    let _ = hyper_util::server::conn::auto::Builder::new(
+                        hyper_util::rt::TokioExecutor::new(),
+                    )
+                    .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                    .await;
+                });
+            }
+        });
+
+        let addr = format!("unix:{}", socket.display());
+        let transport = HttpTransport::new(&addr, Duration::from_secs(5)).unwrap();
+
+        let health = transport.get("/health", None).await.unwrap();
+        assert_eq!(health.status(), StatusCode::OK);
+        assert_eq!(health.text().await.unwrap(), "ok");
+
+        let echoed: serde_json::Value = transport
+            .post_json("/echo", &serde_json::json!({"hello": "socket"}), None)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(echoed["hello"], "socket");
+    }
+
+    #[tokio::test]
+    async fn test_missing_socket_is_connection_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = format!("unix:{}", dir.path().join("nope.sock").display());
+        let transport = HttpTransport::new(&addr, Duration::from_secs(1)).unwrap();
+        let Err(err) = transport.get("/health", None).await else {
+            panic!("request to a missing socket should fail");
+        };
+        assert!(format!("{:#}", err).contains("Failed to connect"));
+    }
+}
@@ -6349,7 +6349,7 @@ fn run_push_one(addr: &str, text: &str, from: Option<&str>, token: Option<&str>)
             Some(f) => serde_json::json!({ "text": text, "from": f }),
             None    => serde_json::json!({ "text": text }),
         };
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()
             .unwrap_or_default();
@@ -6362,13 +6362,14 @@ fn run_push_one(addr: &str, text: &str, from: Option<&str>, token: Option<&str>)
     futures::executor::block_on(fut);
 }
 
-fn run_push_all(peers: &[String], text: &str, from: Option<&str>, _tokens: &std::collections::HashMap<String, String>) {
+fn run_push_all(peers: &[String], text: &str, from: Option<&str>, tokens: &std::collections::HashMap<String, String>) {
     if peers.is_empty() { return; }
-    let peers = peers.to_vec();
-    let text  = text.to_string();
-    let from  = from.map(|s| s.to_string());
+    let peers  = peers.to_vec();
+    let text   = text.to_string();
+    let from   = from.map(|s| s.to_string());
+    let tokens = tokens.clone();
     let fut = async move {
-        crate::coforth::scatter::scatter_push(&peers, &text, from.as_deref()).await;
+        crate::coforth::scatter::scatter_push(&peers, &text, from.as_deref(), &tokens).await;
     };
     futures::executor::block_on(fut);
 }
@@ -6538,7 +6539,7 @@ fn run_registry_join(registry: &str, entry: crate::registry::PeerEntry) -> anyho
         format!("http://{registry}/v1/registry/join")
     };
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         client.post(&url).json(&entry).send().await?;
@@ -6555,7 +6556,7 @@ fn run_registry_leave(registry: &str, addr: &str) -> anyhow::Result<()> {
     };
     let body = serde_json::json!({ "addr": addr });
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         client.post(&url).json(&body).send().await?;
@@ -6579,7 +6580,7 @@ fn run_registry_peers(
     if let Some(t) = tag    { url.push(sep); url.push_str(&format!("tag={t}"));    sep = '&'; }
     if let Some(r) = region { url.push(sep); url.push_str(&format!("region={r}")); }
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         let peers = client.get(&url).send().await?.json().await?;
@@ -6598,7 +6599,7 @@ fn run_registry_ledger(
         format!("http://{registry}/v1/registry/ledger/{addr}")
     };
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         let entry = client.get(&base).send().await?.json().await?;
@@ -6616,7 +6617,7 @@ fn run_registry_all_ledgers(
         format!("http://{registry}/v1/registry/ledgers")
     };
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         let entries: Vec<(String, crate::registry::LedgerEntry)> =
@@ -6645,7 +6646,7 @@ fn run_peer_settle(peer_addr: &str, my_addr: Option<&str>) -> anyhow::Result<(u6
     let body = serde_json::json!({ "creditor": creditor, "amount_ms": 0u64 });
     let _ = body; // replaced below after ledger fetch
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(10))
             .build()?;
         // Fetch how much the peer thinks we owe them.
@@ -6683,7 +6684,7 @@ fn run_registry_debit(
     };
     let body = serde_json::json!({ "addr": peer_addr, "compute_ms": compute_ms });
     let fut = async move {
-        let client = reqwest::Client::builder()
+        let client = crate::http::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()?;
         client.post(&base).json(&body).send().await?;
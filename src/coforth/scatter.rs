@@ -97,13 +97,20 @@ pub async fn scatter_exec(
 
 /// Send a plain-text message to every peer via POST /v1/forth/push.
 /// The peer displays it in their TUI without running any Forth.
-pub async fn scatter_push(peers: &[String], text: &str, from: Option<&str>) {
+/// `peer_tokens`: optional per-peer auth tokens (addr → token).
+pub async fn scatter_push(
+    peers: &[String],
+    text: &str,
+    from: Option<&str>,
+    peer_tokens: &std::collections::HashMap<String, String>,
+) {
     let tasks: Vec<_> = peers
         .iter()
         .map(|peer| {
             let peer = peer.clone();
             let text = text.to_string();
             let from = from.map(|s| s.to_string());
+            let token = peer_tokens.get(&peer).cloned();
             async move {
                 let url = if peer.starts_with("http://") || peer.starts_with("https://") {
                     format!("{peer}/v1/forth/push")
@@ -114,11 +121,15 @@ pub async fn scatter_push(peers: &[String], text: &str, from: Option<&str>) {
                     Some(f) => serde_json::json!({ "text": text, "from": f }),
                     None    => serde_json::json!({ "text": text }),
                 };
-                let client = reqwest::Client::builder()
+                let client = crate::http::builder()
                     .timeout(std::time::Duration::from_secs(10))
                     .build()
                     .unwrap_or_default();
-                let _ = client.post(&url).json(&body).send().await;
+                let mut req = client.post(&url);
+                if let Some(t) = &token {
+                    req = req.header(crate::peer_token::HEADER, t.as_str());
+                }
+                let _ = req.json(&body).send().await;
             }
         })
         .collect();
@@ -132,7 +143,7 @@ async fn exec_on_peer(addr: &str, cmd: &str, token: Option<&str>) -> anyhow::Res
         format!("http://{addr}/v1/exec")
     };
 
-    let client = reqwest::Client::builder()
+    let client = crate::http::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -214,7 +225,7 @@ async fn define_on_peer(addr: &str, source: &str, token: Option<&str>) -> anyhow
         format!("http://{addr}/v1/forth/define")
     };
 
-    let client = reqwest::Client::builder()
+    let client = crate::http::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()?;
 
@@ -244,7 +255,7 @@ async fn eval_on_peer(addr: &str, code: &str, caller: Option<&str>, token: Optio
         format!("http://{addr}/v1/forth/eval")
     };
 
-    let client = reqwest::Client::builder()
+    let client = crate::http::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
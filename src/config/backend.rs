@@ -6,9 +6,11 @@ use std::path::PathBuf;
 
 /// Execution target for inference (hardware where code runs)
 ///
-/// All targets use ONNX Runtime as the inference provider.
-/// The target determines which ONNX Runtime execution provider is used:
+/// With ONNX Runtime as the inference provider, the target determines
+/// which ONNX Runtime execution provider is used:
 /// - CoreML: Uses Apple Neural Engine (ANE) via CoreML execution provider
+/// - Metal: Runs MLX on the Apple GPU where the family has an MLX model,
+///   otherwise the CoreML execution provider
 /// - CPU: Uses CPU execution provider (universal fallback)
 /// - CUDA: Uses CUDA execution provider for NVIDIA GPUs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +21,11 @@ pub enum ExecutionTarget {
     #[serde(rename = "coreml")]
     CoreML,
 
+    /// Apple GPU via MLX (macOS only, needs the mlx-lm Python package)
+    #[cfg(target_os = "macos")]
+    #[serde(rename = "metal")]
+    Metal,
+
     /// NVIDIA CUDA GPU (Windows/Linux, fast)
     #[cfg(feature = "cuda")]
     #[serde(rename = "cuda")]
@@ -43,6 +50,8 @@ impl ExecutionTarget {
         match self {
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML => "CoreML (ANE)",
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal => "Metal (MLX)",
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda => "CUDA (GPU)",
             ExecutionTarget::Cpu => "CPU",
@@ -57,6 +66,8 @@ impl ExecutionTarget {
             ExecutionTarget::CoreML => {
                 "Apple Neural Engine (CoreML) - Fastest on Mac, best battery life"
             }
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal => "Apple GPU (MLX) - Fastest generation on Apple Silicon",
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda => "NVIDIA GPU (CUDA) - Very fast on supported hardware",
             ExecutionTarget::Cpu => "CPU (Universal Fallback) - Slower than specialized hardware",
@@ -72,6 +83,8 @@ impl ExecutionTarget {
         match self {
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML => true, // Assume CoreML available on all macOS
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal => cfg!(target_arch = "aarch64"), // MLX needs Apple Silicon
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda => true, // Assume CUDA available if compiled with feature
             ExecutionTarget::Cpu => true,  // Always available
@@ -88,6 +101,9 @@ impl ExecutionTarget {
             if ExecutionTarget::CoreML.is_available() {
                 targets.push(ExecutionTarget::CoreML);
             }
+            if ExecutionTarget::Metal.is_available() {
+                targets.push(ExecutionTarget::Metal);
+            }
         }
 
         #[cfg(feature = "cuda")]
@@ -135,7 +151,7 @@ pub struct BackendConfig {
     #[serde(default = "default_backend_enabled")]
     pub enabled: bool,
 
-    /// Inference provider (ONNX Runtime or Candle)
+    /// Inference provider (ONNX Runtime, Candle or llama.cpp GGUF)
     #[serde(default = "default_inference_provider")]
     pub inference_provider: crate::models::unified_loader::InferenceProvider,
 
@@ -198,7 +214,7 @@ fn default_fallback_chain() -> Vec<ExecutionTarget> {
     return vec![ExecutionTarget::Cpu];
 }
 
-/// Custom deserializer for fallback_chain that filters out deprecated/invalid entries (like "metal")
+/// Custom deserializer for fallback_chain that filters out invalid entries (like "metal" off macOS)
 fn deserialize_fallback_chain<'de, D>(deserializer: D) -> Result<Vec<ExecutionTarget>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -222,8 +238,8 @@ where
             }
             "auto" => targets.push(ExecutionTarget::Auto),
             "metal" => {
-                // Silently skip deprecated "metal" variant
-                tracing::warn!("Skipping deprecated 'metal' execution target in config");
+                #[cfg(target_os = "macos")]
+                targets.push(ExecutionTarget::Metal);
             }
             other => {
                 tracing::warn!(
@@ -28,5 +28,134 @@ pub const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:8000";
 /// Default bind address for the network worker (all interfaces).
 pub const DEFAULT_WORKER_ADDR: &str = "0.0.0.0:8000";
 
+/// Default bind address for `finch proxy` (next to the daemon's 11435).
+pub const DEFAULT_PROXY_ADDR: &str = "127.0.0.1:11436";
+
+/// Default time the daemon waits on shutdown for in-flight requests and
+/// agent turns to finish before exiting anyway.
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// Size at which the daemon's access log (`~/.finch/access.jsonl`) is rotated.
+pub const ACCESS_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Number of rotated access logs kept (`access.jsonl.1` … `.N`).
+pub const ACCESS_LOG_KEEP: usize = 5;
+
+/// Default lifetime of a daemon response-cache entry (0 disables the cache).
+pub const DEFAULT_RESPONSE_CACHE_TTL_SECS: u64 = 300;
+
+/// Maximum number of responses the daemon keeps cached.
+pub const RESPONSE_CACHE_MAX_ENTRIES: usize = 1024;
+
+/// Provider exchanges the daemon keeps per session for debugging and replay
+/// (`GET /admin/sessions/{id}/replay/{n}`).
+pub const REPLAY_LOG_PER_SESSION: usize = 20;
+
+/// Default time a page fetched by web_fetch is served from the cache
+/// (0 disables the cache).
+pub const DEFAULT_WEB_CACHE_TTL_SECS: u64 = 3600;
+
+/// Default minimum gap between web_fetch requests to the same host.
+pub const DEFAULT_WEB_MIN_INTERVAL_MS: u64 = 1000;
+
+/// Default number of results returned by web_search.
+pub const DEFAULT_WEB_SEARCH_MAX_RESULTS: usize = 5;
+
+/// Default time a web_search query is answered from the cache
+/// (0 disables the cache).
+pub const DEFAULT_WEB_SEARCH_CACHE_TTL_SECS: u64 = 3600;
+
+/// Default minimum gap between requests to the web_search backend.
+pub const DEFAULT_WEB_SEARCH_MIN_INTERVAL_MS: u64 = 1000;
+
+/// Default wall-clock limit for a run_code snippet.
+pub const DEFAULT_RUN_CODE_TIMEOUT_SECS: u64 = 10;
+
+/// Default memory limit for a run_code snippet.
+pub const DEFAULT_RUN_CODE_MEMORY_MB: u64 = 512;
+
+/// Default limit for a single browser tool action, including page loads.
+pub const DEFAULT_BROWSER_TIMEOUT_SECS: u64 = 30;
+
+/// Default limit for a single http tool request.
+pub const DEFAULT_HTTP_TOOL_TIMEOUT_SECS: u64 = 30;
+
+/// Default limit for a single call of an executable tool plugin.
+pub const DEFAULT_EXEC_PLUGIN_TIMEOUT_SECS: u64 = 60;
+
+/// Default seconds between uploads of the daemon's metrics to remote storage.
+pub const DEFAULT_METRICS_UPLOAD_SECS: u64 = 300;
+
+/// Default hours between the daemon's background checks for a new release.
+pub const DEFAULT_UPDATE_CHECK_HOURS: u64 = 24;
+
+/// Default hours between compaction runs of the memory decay policy.
+pub const DEFAULT_MEMORY_DECAY_INTERVAL_HOURS: u64 = 24;
+
+/// Default hours between runs of the memory consolidation job.
+pub const DEFAULT_MEMORY_CONSOLIDATE_INTERVAL_HOURS: u64 = 24;
+
+/// Default cosine similarity at which memories count as near-duplicates.
+pub const DEFAULT_MEMORY_CONSOLIDATE_SIMILARITY: f32 = 0.95;
+
+/// Seconds finch waits on exit for the session's episode summary.
+pub const EPISODE_SUMMARY_TIMEOUT_SECS: u64 = 20;
+
+/// Default CPU niceness for background training and `finch agent`.
+pub const DEFAULT_BACKGROUND_NICE: i32 = 10;
+
+/// GitHub repository `finch update` installs releases from.
+pub const RELEASES_REPO: &str = "darwin-finch/finch";
+
+/// Default number of delivery attempts per webhook event before it is
+/// written to the dead-letter log.
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+
+/// Consecutive failures after which a provider's circuit opens.
+pub const PROVIDER_CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open provider circuit rejects requests before a trial call.
+pub const PROVIDER_CIRCUIT_COOLDOWN_SECS: u64 = 60;
+
+/// Wait assumed for a 429 that carries no Retry-After or reset header.
+pub const RATE_LIMIT_DEFAULT_WAIT_SECS: u64 = 5;
+
+/// Longest rate-limit reset a request waits for before giving up on the
+/// provider.
+pub const RATE_LIMIT_MAX_WAIT_SECS: u64 = 120;
+
+/// Default token cap for a single tool result; larger results are cut from
+/// the middle before they reach the model.
+pub const DEFAULT_TOOL_RESULT_MAX_TOKENS: usize = 10_000;
+
+/// Default number of read-only tool calls from one response that run at once
+/// (1 runs every call on its own).
+pub const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+
+/// Limit for one tool call in a `finch query` or `finch agent` batch; longer
+/// than bash's own 10-minute command limit, so bash reports its timeouts itself.
+pub const BATCH_TOOL_TIMEOUT_SECS: u64 = 900;
+
+/// Limit for one tool call in the REPL, unless `features.tool_timeouts` sets
+/// one for the tool
+pub const REPL_TOOL_TIMEOUT_SECS: u64 = 30;
+
+/// Characters per chunk when `finch summarize` map-reduces a large input.
+pub const SUMMARIZE_CHUNK_CHARS: usize = 12_000;
+
+/// Largest file or page `finch summarize` will read.
+pub const SUMMARIZE_MAX_BYTES: usize = 8 * 1024 * 1024;
+
+/// Rounds of combining chunk summaries before `finch summarize` truncates
+/// what is left to one request.
+pub const SUMMARIZE_MAX_ROUNDS: usize = 3;
+
+/// Characters of a single file's patch `finch explain-diff` sends in one
+/// request; larger patches are described in parts first.
+pub const EXPLAIN_DIFF_CHUNK_CHARS: usize = 12_000;
+
+/// Largest diff `finch explain-diff` will read.
+pub const EXPLAIN_DIFF_MAX_BYTES: usize = 8 * 1024 * 1024;
+
 /// Default Claude model used when no model is specified in config.
 pub const DEFAULT_CLAUDE_MODEL: &str = "claude-sonnet-4-6";
@@ -3,6 +3,7 @@
 
 use anyhow::{bail, Context, Result};
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use super::provider::ProviderEntry;
 use super::settings::Config;
@@ -90,6 +91,93 @@ fn try_load_from_finch_config() -> Result<Option<Config>> {
         huggingface_token: Option<String>,
         #[serde(default)]
         license: super::settings::LicenseConfig,
+        #[serde(default)]
+        alerts: Vec<super::settings::AlertConfig>,
+        #[serde(default)]
+        archive: super::settings::ArchiveConfig,
+        #[serde(default)]
+        http: super::settings::HttpConfig,
+        #[serde(default)]
+        update: super::settings::UpdateConfig,
+        #[serde(default)]
+        web_fetch: super::settings::WebFetchConfig,
+        #[serde(default)]
+        web_search: super::settings::WebSearchConfig,
+        #[serde(default)]
+        run_code: super::settings::RunCodeConfig,
+        #[serde(default)]
+        browser: super::settings::BrowserConfig,
+        #[serde(default)]
+        http_tool: super::settings::HttpToolConfig,
+        #[serde(default)]
+        storage: super::settings::StorageConfig,
+        #[serde(default)]
+        schedule: crate::scheduling::ScheduleConfig,
+        #[serde(default)]
+        reasoning: crate::providers::ReasoningConfig,
+        #[serde(default)]
+        redaction: crate::redact::RedactionConfig,
+        #[serde(default)]
+        audit: crate::tools::audit::AuditConfig,
+        #[serde(default)]
+        incognito: crate::incognito::IncognitoConfig,
+        #[serde(default)]
+        self_eval: crate::server::SelfEvalConfig,
+        #[serde(default)]
+        model_integrity: crate::models::IntegrityConfig,
+        #[serde(default)]
+        wasm_plugins: crate::tools::wasm_plugins::WasmPluginsConfig,
+        #[serde(default)]
+        exec_plugins: crate::tools::exec_plugins::ExecPluginsConfig,
+        #[serde(default)]
+        memory: MemorySection,
+        #[serde(default)]
+        server: ServerSection,
+    }
+
+    /// Only `[memory.store]`, `[memory.rerank]`, `[memory.decay]`,
+    /// `[memory.consolidate]`, `embedding_model`, `extract_facts`,
+    /// `episodes` and `working_memory` are read from the file
+    #[derive(serde::Deserialize, Default)]
+    struct MemorySection {
+        #[serde(default)]
+        store: crate::memory::MemoryStoreConfig,
+        #[serde(default)]
+        rerank: crate::memory::RerankConfig,
+        #[serde(default)]
+        decay: crate::memory::DecayConfig,
+        #[serde(default)]
+        consolidate: crate::memory::ConsolidateConfig,
+        #[serde(default)]
+        embedding_model: Option<crate::memory::EmbeddingModel>,
+        #[serde(default)]
+        extract_facts: Option<bool>,
+        #[serde(default)]
+        episodes: Option<bool>,
+        #[serde(default)]
+        working_memory: Option<bool>,
+    }
+
+    /// Only these `[server]` settings are read from the file; other daemon
+    /// settings come from CLI flags.
+    #[derive(serde::Deserialize, Default)]
+    struct ServerSection {
+        #[serde(default)]
+        webhooks: super::settings::WebhooksConfig,
+        #[serde(default)]
+        cors: super::settings::CorsConfig,
+        #[serde(default)]
+        web_ui: bool,
+        #[serde(default)]
+        grpc_address: Option<String>,
+        #[serde(default)]
+        speculative_local: Option<bool>,
+        #[serde(default)]
+        workspaces: Vec<super::settings::WorkspaceConfig>,
+        #[serde(default)]
+        memory: bool,
+        #[serde(default)]
+        local_token: Option<bool>,
     }
 
     fn default_tui_enabled() -> bool {
@@ -155,16 +243,109 @@ fn try_load_from_finch_config() -> Result<Option<Config>> {
 
     // Apply license config (default = Noncommercial when section is absent)
     config.license = toml_config.license;
+    config.alerts = toml_config.alerts;
+    config.archive = toml_config.archive;
+    config.http = toml_config.http;
+    config.update = toml_config.update;
+    config.web_fetch = toml_config.web_fetch;
+    config.web_search = toml_config.web_search;
+    config.run_code = toml_config.run_code;
+    config.browser = toml_config.browser;
+    config.http_tool = toml_config.http_tool;
+    config.storage = toml_config.storage;
+    config.schedule = toml_config.schedule;
+    config.reasoning = toml_config.reasoning;
+    config.redaction = toml_config.redaction;
+    config.audit = toml_config.audit;
+    config.incognito = toml_config.incognito;
+    config.self_eval = toml_config.self_eval;
+    config.model_integrity = toml_config.model_integrity;
+    config.wasm_plugins = toml_config.wasm_plugins;
+    config.exec_plugins = toml_config.exec_plugins;
+    config.memory.store = toml_config.memory.store;
+    config.memory.rerank = toml_config.memory.rerank;
+    config.memory.decay = toml_config.memory.decay;
+    config.memory.consolidate = toml_config.memory.consolidate;
+    if let Some(embedding_model) = toml_config.memory.embedding_model {
+        config.memory.embedding_model = embedding_model;
+    }
+    if let Some(extract_facts) = toml_config.memory.extract_facts {
+        config.memory.extract_facts = extract_facts;
+    }
+    if let Some(episodes) = toml_config.memory.episodes {
+        config.memory.episodes = episodes;
+    }
+    if let Some(working_memory) = toml_config.memory.working_memory {
+        config.memory.working_memory = working_memory;
+    }
+    config.server.webhooks = toml_config.server.webhooks;
+    config.server.cors = toml_config.server.cors;
+    config.server.web_ui = toml_config.server.web_ui;
+    config.server.grpc_address = toml_config.server.grpc_address;
+    if let Some(speculative) = toml_config.server.speculative_local {
+        config.server.speculative_local = speculative;
+    }
+    config.server.workspaces = toml_config.server.workspaces;
+    config.server.memory = toml_config.server.memory;
+    if let Some(local_token) = toml_config.server.local_token {
+        config.server.local_token = local_token;
+    }
 
     // Validate configuration
     config
         .validate()
         .context("Configuration validation failed")?;
 
+    // Every HTTP client built from here on uses the configured proxy and CA bundle
+    crate::http::init(&config.http);
+    crate::web::init(&config.web_fetch);
+    crate::web::search::init(&config.web_search);
+    crate::tools::implementations::run_code::init(&config.run_code);
+    crate::tools::implementations::browser::init(&config.browser);
+    crate::tools::implementations::http::init(&config.http_tool);
+    crate::providers::reasoning::init(&config.reasoning);
+    crate::redact::init(
+        &config.redaction,
+        config
+            .providers
+            .iter()
+            .filter_map(|p| p.api_key())
+            .map(str::to_string)
+            .chain(config.huggingface_token.clone())
+            .collect(),
+    );
+    crate::tools::audit::init(&config.audit);
+    crate::incognito::init(&config.incognito);
+    crate::models::integrity::init(&config.model_integrity);
+    crate::tools::wasm_plugins::init(&config.wasm_plugins);
+    crate::tools::exec_plugins::init(&config.exec_plugins);
+
     Ok(Some(config))
 }
 
+/// `path` with a leading `~` replaced by the home directory, for paths
+/// written in the config file.  `~user` forms are left alone.
+pub fn expand_home(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) if rest.as_os_str().is_empty() => home,
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Config loading tests rely on filesystem state; see integration tests.
+    use super::*;
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(expand_home("/etc/hosts"), PathBuf::from("/etc/hosts"));
+        assert_eq!(expand_home("~other/notes"), PathBuf::from("~other/notes"));
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(expand_home("~"), home);
+            assert_eq!(expand_home("~/notes"), home.join("notes"));
+        }
+    }
 }
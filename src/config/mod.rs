@@ -15,9 +15,13 @@ pub use backend::{BackendConfig, ExecutionTarget};
 pub use colors::{
     ColorScheme, ColorSpec, ColorTheme, DialogColors, MessageColors, StatusColors, UiColors,
 };
-pub use loader::load_config;
+pub use loader::{expand_home, load_config};
 pub use persona::Persona;
 pub use provider::ProviderEntry;
 pub use settings::{
-    ClientConfig, Config, FeaturesConfig, LicenseConfig, LicenseType, ServerConfig, TeacherEntry,
+    AlertConfig, ArchiveConfig, BrowserConfig, ClientConfig, Config, CorsConfig, FeaturesConfig,
+    HttpAuth, HttpAuthProfile, HttpConfig, HttpToolConfig, LicenseConfig, LicenseType,
+    RunCodeConfig, SearchBackend, ServerConfig, StorageConfig, StorageKind, TeacherEntry,
+    ToolProfile, UpdateChannel, UpdateConfig, WebFetchConfig, WebSearchConfig, WebhooksConfig,
+    WorkspaceConfig,
 };
@@ -126,6 +126,26 @@ pub enum ProviderEntry {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         name: Option<String>,
     },
+    /// Provider registered by a plugin (see `providers::plugin`).
+    ///
+    /// ```toml
+    /// [[providers]]
+    /// type = "plugin"
+    /// plugin = "acme"
+    /// api_key = "..."                      # optional, passed to the plugin
+    /// base_url = "https://llm.corp.example" # optional
+    /// ```
+    Plugin {
+        plugin: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
     Local {
         #[serde(default = "default_inference_provider")]
         inference_provider: InferenceProvider,
@@ -158,6 +178,7 @@ impl ProviderEntry {
             Self::Groq { name, .. } => name.as_deref().unwrap_or("Groq"),
             Self::Ollama { name, .. } => name.as_deref().unwrap_or("Ollama"),
             Self::RemoteDaemon { name, .. } => name.as_deref().unwrap_or("Remote Daemon"),
+            Self::Plugin { name, plugin, .. } => name.as_deref().unwrap_or(plugin),
             Self::Local { name, .. } => name.as_deref().unwrap_or("Local"),
         }
     }
@@ -173,6 +194,7 @@ impl ProviderEntry {
             Self::Groq { .. } => "groq",
             Self::Ollama { .. } => "ollama",
             Self::RemoteDaemon { .. } => "remote_daemon",
+            Self::Plugin { .. } => "plugin",
             Self::Local { .. } => "local",
         }
     }
@@ -182,7 +204,8 @@ impl ProviderEntry {
         matches!(self, Self::Local { .. })
     }
 
-    /// API key for cloud variants; `None` for Local, Ollama, and RemoteDaemon.
+    /// API key for cloud variants and plugins that have one; `None` for
+    /// Local, Ollama, and RemoteDaemon.
     pub fn api_key(&self) -> Option<&str> {
         match self {
             Self::Claude { api_key, .. } => Some(api_key.as_str()),
@@ -191,10 +214,37 @@ impl ProviderEntry {
             Self::Gemini { api_key, .. } => Some(api_key.as_str()),
             Self::Mistral { api_key, .. } => Some(api_key.as_str()),
             Self::Groq { api_key, .. } => Some(api_key.as_str()),
+            Self::Plugin { api_key, .. } => api_key.as_deref(),
             Self::Ollama { .. } | Self::RemoteDaemon { .. } | Self::Local { .. } => None,
         }
     }
 
+    /// Model id a `Local` entry is served under by the daemon (`/v1/models`).
+    ///
+    /// Uses the entry's `name` when set, otherwise family + size, e.g.
+    /// `"qwen2.5-7b"`. Returns `None` for non-local variants.
+    pub fn local_model_id(&self) -> Option<String> {
+        if let Self::Local {
+            model_family,
+            model_size,
+            name,
+            ..
+        } = self
+        {
+            let id = match name {
+                Some(n) if !n.trim().is_empty() => n.trim().to_string(),
+                _ => format!(
+                    "{}-{}",
+                    model_family.name(),
+                    model_size.to_size_string(*model_family)
+                ),
+            };
+            Some(id.to_lowercase().replace(' ', ""))
+        } else {
+            None
+        }
+    }
+
     /// Optional model override (cloud providers only).
     pub fn model(&self) -> Option<&str> {
         match self {
@@ -205,6 +255,7 @@ impl ProviderEntry {
             Self::Mistral { model, .. } => model.as_deref(),
             Self::Groq { model, .. } => model.as_deref(),
             Self::Ollama { model, .. } => Some(model.as_str()),
+            Self::Plugin { model, .. } => model.as_deref(),
             Self::RemoteDaemon { .. } | Self::Local { .. } => None,
         }
     }
@@ -256,6 +307,23 @@ mod tests {
         assert_eq!(entry, decoded);
     }
 
+    #[test]
+    fn test_plugin_entry() {
+        let entry: ProviderEntry = toml::from_str(
+            r#"
+            type = "plugin"
+            plugin = "acme"
+            model = "acme-large"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(entry.provider_type(), "plugin");
+        assert_eq!(entry.display_name(), "acme");
+        assert_eq!(entry.model(), Some("acme-large"));
+        assert_eq!(entry.api_key(), None);
+        assert!(!entry.is_local());
+    }
+
     #[test]
     fn test_display_name_fallback() {
         let entry = ProviderEntry::Claude {
@@ -315,6 +383,33 @@ mod tests {
         assert!(local.api_key().is_none());
     }
 
+    #[test]
+    fn test_local_model_id() {
+        let mut entry = ProviderEntry::Local {
+            inference_provider: InferenceProvider::Onnx,
+            execution_target: ExecutionTarget::Auto,
+            model_family: ModelFamily::Qwen2,
+            model_size: ModelSize::Large,
+            model_repo: None,
+            model_path: None,
+            enabled: true,
+            name: None,
+        };
+        assert_eq!(entry.local_model_id().as_deref(), Some("qwen2.5-7b"));
+
+        if let ProviderEntry::Local { name, .. } = &mut entry {
+            *name = Some("Big Qwen".to_string());
+        }
+        assert_eq!(entry.local_model_id().as_deref(), Some("bigqwen"));
+
+        let cloud = ProviderEntry::Grok {
+            api_key: "k".to_string(),
+            model: None,
+            name: None,
+        };
+        assert!(cloud.local_model_id().is_none());
+    }
+
     #[test]
     fn test_provider_type_tags() {
         assert_eq!(
@@ -4,7 +4,7 @@ use super::backend::BackendConfig;
 use super::colors::ColorScheme;
 use super::provider::ProviderEntry;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// Feature flags configuration
@@ -62,6 +62,38 @@ pub struct FeaturesConfig {
     #[serde(default = "default_true")]
     pub brain_enabled: bool,
 
+    /// Token cap for one tool result. Larger results keep their first and last
+    /// lines and the model is told which range was left out. 0 = unlimited.
+    #[serde(default = "default_tool_result_max_tokens")]
+    pub tool_result_max_tokens: usize,
+
+    /// Per-tool overrides of `tool_result_max_tokens`, e.g. `{ grep = 4000 }`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_result_max_tokens_by_tool: HashMap<String, usize>,
+
+    /// Hold an `edit` that renames a definition while references to the old
+    /// name remain elsewhere, and show the model the list first.
+    /// Default: true.
+    #[serde(default = "default_true")]
+    pub rename_check: bool,
+
+    /// Read-only tool calls (read, glob, grep, web lookups) from one response
+    /// that run at once; calls that change something always run alone.
+    /// Default: 4. Set to 1 to run every call in turn.
+    #[serde(default = "default_max_parallel_tools")]
+    pub max_parallel_tools: usize,
+
+    /// Per-tool time limits in seconds, e.g. `{ bash = 600 }`, replacing the
+    /// default (30 seconds in the REPL, 15 minutes in `finch query`/`agent`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_timeouts: HashMap<String, u64>,
+
+    /// Check a prompt for likely slips (a diff or error message it mentions
+    /// but doesn't include, a file that doesn't exist) before sending it, and
+    /// offer a fix-up. Default: true.
+    #[serde(default = "default_true")]
+    pub prompt_lint: bool,
+
     /// Enable GUI automation tools (macOS only)
     #[cfg(target_os = "macos")]
     #[serde(default)]
@@ -80,6 +112,12 @@ impl Default for FeaturesConfig {
             enable_summarization: false,
             auto_compact_enabled: false,
             brain_enabled: true,
+            tool_result_max_tokens: default_tool_result_max_tokens(),
+            tool_result_max_tokens_by_tool: HashMap::new(),
+            rename_check: true,
+            max_parallel_tools: default_max_parallel_tools(),
+            tool_timeouts: HashMap::new(),
+            prompt_lint: true,
             #[cfg(target_os = "macos")]
             gui_automation: false,
         }
@@ -90,6 +128,14 @@ fn default_true() -> bool {
     true
 }
 
+fn default_tool_result_max_tokens() -> usize {
+    super::constants::DEFAULT_TOOL_RESULT_MAX_TOKENS
+}
+
+fn default_max_parallel_tools() -> usize {
+    super::constants::DEFAULT_MAX_PARALLEL_TOOLS
+}
+
 fn default_context_lines() -> usize {
     5
 }
@@ -160,6 +206,63 @@ pub struct Config {
 
     /// License configuration (Noncommercial by default; Commercial with a valid key)
     pub license: LicenseConfig,
+
+    /// Alert rules evaluated over the metrics store
+    pub alerts: Vec<AlertConfig>,
+
+    /// Cold-storage archive policy for old memory and sessions
+    pub archive: ArchiveConfig,
+
+    /// Proxy and TLS settings shared by every outbound HTTP client
+    pub http: HttpConfig,
+
+    /// Self-update channel and background checks
+    pub update: UpdateConfig,
+
+    /// Caching and politeness for the web_fetch tool
+    pub web_fetch: WebFetchConfig,
+
+    /// Search backend for the web_search tool
+    pub web_search: WebSearchConfig,
+
+    /// Interpreters and limits for the run_code tool
+    pub run_code: RunCodeConfig,
+
+    /// Headless Chromium for the browser tool
+    pub browser: BrowserConfig,
+
+    /// Allowed hosts and auth profiles for the http tool
+    pub http_tool: HttpToolConfig,
+
+    /// Local or S3-compatible storage for daemon sessions and metrics
+    pub storage: StorageConfig,
+
+    /// Quiet hours and CPU priority for background work
+    pub schedule: crate::scheduling::ScheduleConfig,
+
+    /// Thinking budget / effort for reasoning models
+    pub reasoning: crate::providers::ReasoningConfig,
+
+    /// Secret redaction in tool results sent to providers and in training logs
+    pub redaction: crate::redact::RedactionConfig,
+
+    /// Signed log of every tool call (~/.finch/audit/tools.jsonl)
+    pub audit: crate::tools::audit::AuditConfig,
+
+    /// Projects whose sessions start incognito
+    pub incognito: crate::incognito::IncognitoConfig,
+
+    /// Idle-time replays of teacher answers through the local model
+    pub self_eval: crate::server::SelfEvalConfig,
+
+    /// Checksum manifests and publisher keys for downloaded models
+    pub model_integrity: crate::models::IntegrityConfig,
+
+    /// Limits and grants for WebAssembly tool plugins
+    pub wasm_plugins: crate::tools::wasm_plugins::WasmPluginsConfig,
+
+    /// Executables declared as tools
+    pub exec_plugins: crate::tools::exec_plugins::ExecPluginsConfig,
 }
 
 /// Server configuration for daemon mode
@@ -177,6 +280,40 @@ pub struct ServerConfig {
     pub auth_enabled: bool,
     /// Valid API keys for authentication
     pub api_keys: Vec<String>,
+    /// Maximum requests waiting for the local generator before 503s are returned
+    pub max_queue_depth: usize,
+    /// Generations each local model runs at once (one loaded copy per session)
+    pub parallel_sessions: usize,
+    /// Where daemon sessions are persisted across restarts (`None` = don't persist)
+    pub sessions_dir: Option<PathBuf>,
+    /// Seconds shutdown waits for in-flight requests and agent turns
+    pub drain_timeout_secs: u64,
+    /// JSONL access log, one line per HTTP request (`None` = disabled)
+    pub access_log: Option<PathBuf>,
+    /// Seconds a cached response is reused (0 = response cache disabled)
+    pub response_cache_ttl_secs: u64,
+    /// Also reuse responses to similar questions above this cosine similarity
+    pub response_cache_similarity: Option<f32>,
+    /// Event webhooks from `[server.webhooks]`
+    pub webhooks: WebhooksConfig,
+    /// Browser access from `[server.cors]`
+    pub cors: CorsConfig,
+    /// Serve the built-in chat page at `/ui`
+    pub web_ui: bool,
+    /// Also serve the gRPC API on this address (`None` = HTTP only)
+    pub grpc_address: Option<String>,
+    /// Draft locally while routing decides (discarded if the query is forwarded)
+    pub speculative_local: bool,
+    /// Tenants from `[[server.workspaces]]` (empty = single-tenant, no auth)
+    pub workspaces: Vec<WorkspaceConfig>,
+    /// Per-workspace memory and usage live in `<dir>/<name>/`
+    pub workspaces_dir: Option<PathBuf>,
+    /// Recall and remember for API sessions (`/v1/memory`, chat completions)
+    pub memory: bool,
+    /// The daemon's own memory lives in `<dir>/memory.db`; workspaces keep theirs
+    pub memory_dir: Option<PathBuf>,
+    /// Require the owner's token from ~/.finch/daemon.token (or a workspace key)
+    pub local_token: bool,
     /// Operating mode: "full" (daemon + REPL) or "daemon-only" (no REPL)
     pub mode: String,
     /// Enable mDNS/Bonjour advertisement for service discovery
@@ -187,6 +324,107 @@ pub struct ServerConfig {
     pub service_description: String,
 }
 
+/// Event webhooks from `[server.webhooks]` in ~/.finch/config.toml
+///
+/// Every URL receives a signed JSON POST per daemon event.  The body has a
+/// Slack-style `text` field, so Slack incoming webhooks (and Discord's
+/// `/slack` webhook endpoint) can be used directly.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhooksConfig {
+    /// Endpoints that receive every event (empty = webhooks disabled)
+    #[serde(default)]
+    pub urls: Vec<String>,
+    /// Only send these event names, e.g. `["agent.task_failed"]` (empty = all)
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Shared secret for the `X-Finch-Signature` HMAC-SHA256 header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Delivery attempts before an event is dead-lettered
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// JSONL file for undeliverable events (default: ~/.finch/webhooks-dead.jsonl)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<PathBuf>,
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    crate::config::constants::DEFAULT_WEBHOOK_MAX_ATTEMPTS
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            events: Vec::new(),
+            secret: None,
+            max_attempts: default_webhook_max_attempts(),
+            dead_letter: None,
+        }
+    }
+}
+
+impl WebhooksConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+}
+
+/// CORS policy from `[server.cors]` in ~/.finch/config.toml
+///
+/// Browsers only let a page on another origin call the daemon when that
+/// origin is listed here.  No origins (the default) means no CORS headers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CorsConfig {
+    /// Origins allowed to call the daemon, e.g. `http://localhost:3000`;
+    /// `"*"` allows any origin
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Let browsers send cookies and `Authorization` headers cross-origin.
+    /// Ignored with `"*"`, which browsers refuse to combine with credentials.
+    #[serde(default)]
+    pub allow_credentials: bool,
+}
+
+/// A tenant of a shared daemon, from `[[server.workspaces]]` in ~/.finch/config.toml
+///
+/// Every API key belongs to one workspace.  Once any workspace is configured
+/// the daemon refuses requests without a known key, and a workspace only
+/// sees its own sessions and brains.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceConfig {
+    /// Unique name; also the workspace's directory under `workspaces_dir`
+    pub name: String,
+    /// Keys clients send as `Authorization: Bearer <key>` or `x-api-key`
+    pub api_keys: Vec<String>,
+    /// Tools daemon brains started from this workspace may use
+    #[serde(default)]
+    pub tools: ToolProfile,
+    /// Cloud providers this workspace may use, by name; the first is its
+    /// default (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    /// Tokens per UTC day, local and cloud together (`None` = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_tokens: Option<u64>,
+    /// Estimated spend per calendar month in USD (`None` = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_budget_usd: Option<f64>,
+}
+
+/// Which tools a workspace's daemon brains get
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolProfile {
+    /// No daemon brains
+    None,
+    /// read, glob and grep
+    #[default]
+    ReadOnly,
+    /// Read-only tools plus the workspace's own memory
+    Memory,
+}
+
 /// Client configuration for connecting to daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
@@ -213,6 +451,23 @@ impl Default for ServerConfig {
             session_timeout_minutes: 30,
             auth_enabled: false,
             api_keys: vec![],
+            max_queue_depth: 32,
+            parallel_sessions: 1,
+            sessions_dir: dirs::home_dir().map(|h| h.join(".finch").join("sessions")),
+            drain_timeout_secs: crate::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS,
+            access_log: dirs::home_dir().map(|h| h.join(".finch").join("access.jsonl")),
+            response_cache_ttl_secs: crate::config::constants::DEFAULT_RESPONSE_CACHE_TTL_SECS,
+            response_cache_similarity: None, // Exact matches only
+            webhooks: WebhooksConfig::default(),
+            cors: CorsConfig::default(),
+            web_ui: false,
+            grpc_address: None,
+            speculative_local: true,
+            workspaces: Vec::new(),
+            workspaces_dir: dirs::home_dir().map(|h| h.join(".finch").join("workspaces")),
+            memory: false,
+            memory_dir: dirs::home_dir().map(|h| h.join(".finch").join("daemon")),
+            local_token: true,
             mode: "full".to_string(), // "full" (daemon + REPL) or "daemon-only"
             advertise: false,         // Disabled by default
             service_name: String::new(), // Empty = auto-generate from hostname
@@ -270,10 +525,502 @@ pub struct LicenseConfig {
     pub notice_suppress_until: Option<String>,
 }
 
+/// A user-defined alert rule from `[[alerts]]` in ~/.finch/config.toml
+///
+/// `rule` is parsed by `metrics::AlertRule::parse`, e.g.
+/// `"spend > 5 over 24h"` or `"error_rate > 20% over 10m"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    /// Short identifier shown in notifications and the status bar
+    pub name: String,
+    /// Rule expression: `<metric> <op> <threshold> [over <window>]`
+    pub rule: String,
+    /// Optional webhook URL; receives a JSON POST when the alert fires
+    #[serde(default)]
+    pub webhook: Option<String>,
+    /// Show a desktop notification when the alert fires (default: true)
+    #[serde(default = "default_true")]
+    pub desktop: bool,
+}
+
+/// Archive policy from `[archive]` in ~/.finch/config.toml
+///
+/// Conversations, memory leaves and daemon session files older than
+/// `after_months` are moved to compressed files in `~/.finch/archive/` at
+/// REPL startup.  They stay searchable with `finch archive search`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ArchiveConfig {
+    /// Archive records older than this many months (0 = never archive)
+    #[serde(default)]
+    pub after_months: u32,
+}
+
+/// Outbound HTTP settings from `[http]` in ~/.finch/config.toml
+///
+/// Applied to every HTTP client finch builds (providers, web_fetch, the Lotus
+/// Network client, ...) so they all work behind a TLS-intercepting corporate
+/// proxy.  Unset fields fall back to the environment: `HTTPS_PROXY` /
+/// `HTTP_PROXY` / `NO_PROXY`, `SSL_CERT_FILE`, then the OS proxy settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct HttpConfig {
+    /// Proxy URL for all outbound requests, e.g. `http://proxy.corp:8080`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy, e.g. `localhost,.corp.example`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// PEM file with extra root certificates to trust (the proxy's CA)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl HttpConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Release channel followed by `finch update`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Tagged releases only
+    #[default]
+    Stable,
+    /// Pre-releases as well (`v0.8.0-nightly.20261018`)
+    Nightly,
+}
+
+impl UpdateChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Nightly => "nightly",
+        }
+    }
+}
+
+impl std::str::FromStr for UpdateChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "nightly" => Ok(Self::Nightly),
+            other => Err(format!(
+                "unknown update channel '{}' (expected stable or nightly)",
+                other
+            )),
+        }
+    }
+}
+
+/// Self-update settings from `[update]` in ~/.finch/config.toml
+///
+/// Releases are only installed when their signature checks out against the
+/// release key built into the binary, or `public_key` when set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateConfig {
+    /// Release channel: "stable" or "nightly"
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    /// Hours between the daemon's background update checks (0 = never check)
+    #[serde(default = "default_update_check_hours")]
+    pub check_interval_hours: u64,
+    /// Let the daemon install updates it finds and restart itself
+    #[serde(default)]
+    pub auto_install: bool,
+    /// Base64 Ed25519 key that release signatures must verify against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+fn default_update_check_hours() -> u64 {
+    crate::config::constants::DEFAULT_UPDATE_CHECK_HOURS
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            check_interval_hours: default_update_check_hours(),
+            auto_install: false,
+            public_key: None,
+        }
+    }
+}
+
+impl UpdateConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// web_fetch settings from `[web_fetch]` in ~/.finch/config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebFetchConfig {
+    /// Seconds a fetched page is served from the cache (0 disables the cache)
+    #[serde(default = "default_web_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// Minimum milliseconds between requests to the same host; a longer
+    /// robots.txt Crawl-delay takes precedence
+    #[serde(default = "default_web_min_interval_ms")]
+    pub min_interval_ms: u64,
+    /// Refuse pages the site's robots.txt disallows
+    #[serde(default = "default_true")]
+    pub respect_robots: bool,
+}
+
+fn default_web_cache_ttl_secs() -> u64 {
+    crate::config::constants::DEFAULT_WEB_CACHE_TTL_SECS
+}
+
+fn default_web_min_interval_ms() -> u64 {
+    crate::config::constants::DEFAULT_WEB_MIN_INTERVAL_MS
+}
+
+impl Default for WebFetchConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: default_web_cache_ttl_secs(),
+            min_interval_ms: default_web_min_interval_ms(),
+            respect_robots: true,
+        }
+    }
+}
+
+impl WebFetchConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Search engine queried by the web_search tool
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackend {
+    /// DuckDuckGo's HTML results page; needs no key
+    #[default]
+    DuckDuckGo,
+    /// Brave Search API (`api_key` or `BRAVE_API_KEY`)
+    Brave,
+    /// A SearXNG instance with the JSON format enabled (`searxng_url`)
+    Searxng,
+}
+
+impl SearchBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "duckduckgo",
+            Self::Brave => "brave",
+            Self::Searxng => "searxng",
+        }
+    }
+}
+
+/// web_search settings from `[web_search]` in ~/.finch/config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebSearchConfig {
+    #[serde(default)]
+    pub backend: SearchBackend,
+    /// Brave Search API key (falls back to `BRAVE_API_KEY`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Base URL of the SearXNG instance, e.g. `http://localhost:8888`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub searxng_url: Option<String>,
+    /// Results returned per search
+    #[serde(default = "default_web_search_max_results")]
+    pub max_results: usize,
+    /// Seconds a search is answered from the cache (0 disables the cache)
+    #[serde(default = "default_web_search_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// Minimum milliseconds between requests to the search backend
+    #[serde(default = "default_web_search_min_interval_ms")]
+    pub min_interval_ms: u64,
+}
+
+fn default_web_search_max_results() -> usize {
+    crate::config::constants::DEFAULT_WEB_SEARCH_MAX_RESULTS
+}
+
+fn default_web_search_cache_ttl_secs() -> u64 {
+    crate::config::constants::DEFAULT_WEB_SEARCH_CACHE_TTL_SECS
+}
+
+fn default_web_search_min_interval_ms() -> u64 {
+    crate::config::constants::DEFAULT_WEB_SEARCH_MIN_INTERVAL_MS
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: SearchBackend::default(),
+            api_key: None,
+            searxng_url: None,
+            max_results: default_web_search_max_results(),
+            cache_ttl_secs: default_web_search_cache_ttl_secs(),
+            min_interval_ms: default_web_search_min_interval_ms(),
+        }
+    }
+}
+
+impl WebSearchConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// run_code settings from `[run_code]` in ~/.finch/config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunCodeConfig {
+    /// Python interpreter (name on PATH or full path)
+    #[serde(default = "default_python")]
+    pub python: String,
+    /// Node.js interpreter for JavaScript
+    #[serde(default = "default_node")]
+    pub node: String,
+    /// Wall-clock limit per snippet
+    #[serde(default = "default_run_code_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Memory limit per snippet
+    #[serde(default = "default_run_code_memory_mb")]
+    pub memory_mb: u64,
+    /// Let snippets use the network (isolated by default)
+    #[serde(default)]
+    pub allow_network: bool,
+}
+
+fn default_python() -> String {
+    "python3".to_string()
+}
+
+fn default_node() -> String {
+    "node".to_string()
+}
+
+fn default_run_code_timeout_secs() -> u64 {
+    crate::config::constants::DEFAULT_RUN_CODE_TIMEOUT_SECS
+}
+
+fn default_run_code_memory_mb() -> u64 {
+    crate::config::constants::DEFAULT_RUN_CODE_MEMORY_MB
+}
+
+impl Default for RunCodeConfig {
+    fn default() -> Self {
+        Self {
+            python: default_python(),
+            node: default_node(),
+            timeout_secs: default_run_code_timeout_secs(),
+            memory_mb: default_run_code_memory_mb(),
+            allow_network: false,
+        }
+    }
+}
+
+impl RunCodeConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// browser tool settings from `[browser]` in ~/.finch/config.toml
+///
+/// The browser can click through sites as the user's machine, so it is its
+/// own permission category: off unless `enabled`, and approved per site.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BrowserConfig {
+    /// Offer the browser tool at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Chrome or Chromium binary (found on PATH when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chrome_path: Option<PathBuf>,
+    /// Limit for each browser action, including page loads
+    #[serde(default = "default_browser_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_browser_timeout_secs() -> u64 {
+    crate::config::constants::DEFAULT_BROWSER_TIMEOUT_SECS
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chrome_path: None,
+            timeout_secs: default_browser_timeout_secs(),
+        }
+    }
+}
+
+impl BrowserConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// http tool settings from `[http_tool]` in ~/.finch/config.toml
+///
+/// The tool can call any API the user can reach, so it only talks to the
+/// hosts listed here and isn't offered while the list is empty.  Credentials
+/// live in named profiles that requests refer to, so the model never sees or
+/// types a secret.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HttpToolConfig {
+    /// Hosts requests may go to; `*.example.com` covers its subdomains
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// Limit for each request
+    #[serde(default = "default_http_tool_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Credentials by name, from `[http_tool.profiles.<name>]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, HttpAuthProfile>,
+}
+
+fn default_http_tool_timeout_secs() -> u64 {
+    crate::config::constants::DEFAULT_HTTP_TOOL_TIMEOUT_SECS
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            timeout_secs: default_http_tool_timeout_secs(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl HttpToolConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Credentials an http tool request can name with `auth`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HttpAuthProfile {
+    /// Hosts the credentials may be sent to (same patterns as `allowed_hosts`)
+    pub hosts: Vec<String>,
+    #[serde(flatten)]
+    pub auth: HttpAuth,
+}
+
+/// How a profile authenticates.  Each secret is given inline or read from
+/// the environment variable named by its `*_env` field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HttpAuth {
+    /// `Authorization: Bearer <token>`
+    Bearer {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token_env: Option<String>,
+    },
+    /// `Authorization: Basic` with a username and password
+    Basic {
+        username: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password_env: Option<String>,
+    },
+    /// Any other header, e.g. `X-Api-Key`
+    Header {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value_env: Option<String>,
+    },
+}
+
+/// Where the daemon keeps sessions and metrics
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    /// `server.sessions_dir` and the local metrics directory
+    #[default]
+    Local,
+    /// An S3-compatible bucket (AWS S3, MinIO, Cloudflare R2, ...)
+    S3,
+}
+
+/// Storage settings from `[storage]` in ~/.finch/config.toml
+///
+/// With `backend = "s3"` the daemon keeps sessions in the bucket instead of
+/// `sessions_dir` and uploads its metrics day files there, so a fleet of
+/// workers shares one place for transcripts and usage data.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub backend: StorageKind,
+    /// Bucket name (required for `s3`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Service URL, e.g. `http://minio:9000` (default: AWS S3 in `region`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Region used to sign requests
+    #[serde(default = "default_storage_region")]
+    pub region: String,
+    /// Key prefix for everything finch stores in the bucket
+    #[serde(default = "default_storage_prefix")]
+    pub prefix: String,
+    /// Access key (falls back to `AWS_ACCESS_KEY_ID`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key_id: Option<String>,
+    /// Secret key (falls back to `AWS_SECRET_ACCESS_KEY`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<String>,
+    /// Seconds between metrics uploads (also uploaded on shutdown)
+    #[serde(default = "default_metrics_upload_secs")]
+    pub metrics_upload_secs: u64,
+}
+
+fn default_storage_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_storage_prefix() -> String {
+    "finch".to_string()
+}
+
+fn default_metrics_upload_secs() -> u64 {
+    crate::config::constants::DEFAULT_METRICS_UPLOAD_SECS
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageKind::default(),
+            bucket: None,
+            endpoint: None,
+            region: default_storage_region(),
+            prefix: default_storage_prefix(),
+            access_key_id: None,
+            secret_access_key: None,
+            metrics_upload_secs: default_metrics_upload_secs(),
+        }
+    }
+}
+
+impl StorageConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// A single teacher entry with provider and settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeacherEntry {
-    /// Provider name: "claude", "openai", "grok", "gemini", "mistral", "groq"
+    /// Provider name: "claude", "openai", "grok", "gemini", "mistral", "groq",
+    /// or "plugin:<kind>" for a plugin provider
     pub provider: String,
 
     /// API key for this provider
@@ -371,6 +1118,19 @@ impl ProviderEntry {
                 base_url: None,
                 name: name.clone(),
             }),
+            Self::Plugin {
+                plugin,
+                api_key,
+                model,
+                base_url,
+                name,
+            } => Some(TeacherEntry {
+                provider: format!("plugin:{}", plugin),
+                api_key: api_key.clone().unwrap_or_default(),
+                model: model.clone(),
+                base_url: base_url.clone(),
+                name: name.clone(),
+            }),
             Self::Ollama { .. } | Self::RemoteDaemon { .. } | Self::Local { .. } => None,
         }
     }
@@ -411,6 +1171,13 @@ impl ProviderEntry {
                 model: entry.model.clone(),
                 name: entry.name.clone(),
             },
+            provider if provider.starts_with("plugin:") => Self::Plugin {
+                plugin: entry.provider["plugin:".len()..].to_string(),
+                api_key: Some(entry.api_key.clone()).filter(|k| !k.is_empty()),
+                model: entry.model.clone(),
+                base_url: entry.base_url.clone(),
+                name: entry.name.clone(),
+            },
             _ => {
                 // Unknown provider — treat as Claude (safest fallback)
                 Self::Claude {
@@ -581,6 +1348,39 @@ impl Config {
             anyhow::bail!("session_timeout_minutes must be greater than 0");
         }
 
+        let mut workspace_names = std::collections::HashSet::new();
+        let mut workspace_keys = std::collections::HashSet::new();
+        for workspace in &self.server.workspaces {
+            let name = workspace.name.as_str();
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                anyhow::bail!(errors::wrap_error_with_suggestion(
+                    format!("Invalid workspace name: '{}'", name),
+                    "Workspace names may only contain letters, digits, '-' and '_'"
+                ));
+            }
+            if !workspace_names.insert(name) {
+                anyhow::bail!("Workspace '{}' is defined twice", name);
+            }
+            if workspace.api_keys.is_empty() {
+                anyhow::bail!("Workspace '{}' has no api_keys", name);
+            }
+            for key in &workspace.api_keys {
+                if key.trim().is_empty() {
+                    anyhow::bail!("Workspace '{}' has an empty API key", name);
+                }
+                if !workspace_keys.insert(key.as_str()) {
+                    anyhow::bail!(
+                        "Workspace '{}' reuses an API key; every key must belong to one workspace",
+                        name
+                    );
+                }
+            }
+        }
+
         if self.client.timeout_seconds == 0 {
             anyhow::bail!("timeout_seconds must be greater than 0");
         }
@@ -596,6 +1396,15 @@ impl Config {
             ));
         }
 
+        self.schedule.validate()?;
+        self.self_eval.validate()?;
+        self.model_integrity.validate()?;
+        self.exec_plugins.validate()?;
+        self.memory.store.validate()?;
+        self.memory.rerank.validate()?;
+        self.memory.decay.validate()?;
+        self.memory.consolidate.validate()?;
+
         // Validate paths exist if specified
         if let Some(ref path) = self.constitution_path {
             if !path.exists() {
@@ -670,6 +1479,25 @@ impl Config {
             mcp_servers: HashMap::new(),
             memory: crate::memory::MemoryConfig::default(),
             license: LicenseConfig::default(),
+            alerts: Vec::new(),
+            archive: ArchiveConfig::default(),
+            http: HttpConfig::default(),
+            update: UpdateConfig::default(),
+            web_fetch: WebFetchConfig::default(),
+            web_search: WebSearchConfig::default(),
+            run_code: RunCodeConfig::default(),
+            browser: BrowserConfig::default(),
+            http_tool: HttpToolConfig::default(),
+            storage: StorageConfig::default(),
+            schedule: crate::scheduling::ScheduleConfig::default(),
+            reasoning: crate::providers::ReasoningConfig::default(),
+            redaction: crate::redact::RedactionConfig::default(),
+            audit: crate::tools::audit::AuditConfig::default(),
+            incognito: crate::incognito::IncognitoConfig::default(),
+            self_eval: crate::server::SelfEvalConfig::default(),
+            model_integrity: crate::models::IntegrityConfig::default(),
+            wasm_plugins: crate::tools::wasm_plugins::WasmPluginsConfig::default(),
+            exec_plugins: crate::tools::exec_plugins::ExecPluginsConfig::default(),
         }
     }
 
@@ -736,6 +1564,47 @@ impl Config {
             colors: Some(self.colors.clone()),
             features: Some(self.features.clone()),
             license: self.license.clone(),
+            alerts: self.alerts.clone(),
+            archive: self.archive.clone(),
+            http: self.http.clone(),
+            update: self.update.clone(),
+            web_fetch: self.web_fetch.clone(),
+            web_search: self.web_search.clone(),
+            run_code: self.run_code.clone(),
+            browser: self.browser.clone(),
+            http_tool: self.http_tool.clone(),
+            storage: self.storage.clone(),
+            schedule: self.schedule.clone(),
+            reasoning: self.reasoning.clone(),
+            redaction: self.redaction.clone(),
+            audit: self.audit.clone(),
+            incognito: self.incognito.clone(),
+            self_eval: self.self_eval.clone(),
+            model_integrity: self.model_integrity.clone(),
+            wasm_plugins: self.wasm_plugins.clone(),
+            exec_plugins: self.exec_plugins.clone(),
+            memory: TomlMemorySection {
+                store: self.memory.store.clone(),
+                rerank: self.memory.rerank.clone(),
+                embedding_model: (self.memory.embedding_model
+                    != crate::memory::EmbeddingModel::default())
+                .then_some(self.memory.embedding_model),
+                decay: self.memory.decay.clone(),
+                consolidate: self.memory.consolidate.clone(),
+                extract_facts: (!self.memory.extract_facts).then_some(false),
+                episodes: (!self.memory.episodes).then_some(false),
+                working_memory: (!self.memory.working_memory).then_some(false),
+            },
+            server: TomlServerSection {
+                webhooks: self.server.webhooks.clone(),
+                cors: self.server.cors.clone(),
+                web_ui: self.server.web_ui,
+                grpc_address: self.server.grpc_address.clone(),
+                speculative_local: (!self.server.speculative_local).then_some(false),
+                workspaces: self.server.workspaces.clone(),
+                memory: self.server.memory,
+                local_token: (!self.server.local_token).then_some(false),
+            },
         };
 
         let toml_string = toml::to_string_pretty(&toml_config)?;
@@ -765,6 +1634,156 @@ struct TomlConfig {
     features: Option<FeaturesConfig>,
     #[serde(default)]
     license: LicenseConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    alerts: Vec<AlertConfig>,
+    #[serde(default)]
+    archive: ArchiveConfig,
+    #[serde(default, skip_serializing_if = "HttpConfig::is_default")]
+    http: HttpConfig,
+    #[serde(default, skip_serializing_if = "UpdateConfig::is_default")]
+    update: UpdateConfig,
+    #[serde(default, skip_serializing_if = "WebFetchConfig::is_default")]
+    web_fetch: WebFetchConfig,
+    #[serde(default, skip_serializing_if = "WebSearchConfig::is_default")]
+    web_search: WebSearchConfig,
+    #[serde(default, skip_serializing_if = "RunCodeConfig::is_default")]
+    run_code: RunCodeConfig,
+    #[serde(default, skip_serializing_if = "BrowserConfig::is_default")]
+    browser: BrowserConfig,
+    #[serde(default, skip_serializing_if = "HttpToolConfig::is_default")]
+    http_tool: HttpToolConfig,
+    #[serde(default, skip_serializing_if = "StorageConfig::is_default")]
+    storage: StorageConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::scheduling::ScheduleConfig::is_default"
+    )]
+    schedule: crate::scheduling::ScheduleConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::providers::ReasoningConfig::is_default"
+    )]
+    reasoning: crate::providers::ReasoningConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::redact::RedactionConfig::is_default"
+    )]
+    redaction: crate::redact::RedactionConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tools::audit::AuditConfig::is_default"
+    )]
+    audit: crate::tools::audit::AuditConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::incognito::IncognitoConfig::is_default"
+    )]
+    incognito: crate::incognito::IncognitoConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::server::SelfEvalConfig::is_default"
+    )]
+    self_eval: crate::server::SelfEvalConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::models::IntegrityConfig::is_default"
+    )]
+    model_integrity: crate::models::IntegrityConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tools::wasm_plugins::WasmPluginsConfig::is_default"
+    )]
+    wasm_plugins: crate::tools::wasm_plugins::WasmPluginsConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::tools::exec_plugins::ExecPluginsConfig::is_default"
+    )]
+    exec_plugins: crate::tools::exec_plugins::ExecPluginsConfig,
+    #[serde(default, skip_serializing_if = "TomlMemorySection::is_default")]
+    memory: TomlMemorySection,
+    #[serde(default, skip_serializing_if = "TomlServerSection::is_default")]
+    server: TomlServerSection,
+}
+
+/// The persisted part of `[server]`; the rest comes from CLI flags.
+#[derive(Serialize, Deserialize, Default)]
+struct TomlServerSection {
+    #[serde(default)]
+    webhooks: WebhooksConfig,
+    #[serde(default)]
+    cors: CorsConfig,
+    #[serde(default)]
+    web_ui: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grpc_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    speculative_local: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    workspaces: Vec<WorkspaceConfig>,
+    #[serde(default)]
+    memory: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    local_token: Option<bool>,
+}
+
+/// The `[memory]` settings read from the file; the rest of `MemoryConfig`
+/// has fixed defaults
+#[derive(Serialize, Deserialize, Default)]
+struct TomlMemorySection {
+    #[serde(
+        default,
+        skip_serializing_if = "crate::memory::MemoryStoreConfig::is_default"
+    )]
+    store: crate::memory::MemoryStoreConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::memory::RerankConfig::is_default"
+    )]
+    rerank: crate::memory::RerankConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    embedding_model: Option<crate::memory::EmbeddingModel>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::memory::DecayConfig::is_default"
+    )]
+    decay: crate::memory::DecayConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::memory::ConsolidateConfig::is_default"
+    )]
+    consolidate: crate::memory::ConsolidateConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extract_facts: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    episodes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    working_memory: Option<bool>,
+}
+
+impl TomlMemorySection {
+    fn is_default(&self) -> bool {
+        self.store.is_default()
+            && self.rerank.is_default()
+            && self.embedding_model.is_none()
+            && self.decay.is_default()
+            && self.consolidate.is_default()
+            && self.extract_facts.is_none()
+            && self.episodes.is_none()
+            && self.working_memory.is_none()
+    }
+}
+
+impl TomlServerSection {
+    fn is_default(&self) -> bool {
+        self.webhooks == WebhooksConfig::default()
+            && self.cors == CorsConfig::default()
+            && !self.web_ui
+            && self.grpc_address.is_none()
+            && self.speculative_local.is_none()
+            && self.workspaces.is_empty()
+            && !self.memory
+            && self.local_token.is_none()
+    }
 }
 
 #[cfg(test)]
@@ -838,6 +1857,21 @@ mod tests {
         assert!(config.active_teacher().is_some());
     }
 
+    #[test]
+    fn test_plugin_provider_teacher_roundtrip() {
+        use crate::config::ProviderEntry;
+        let entry = ProviderEntry::Plugin {
+            plugin: "acme".to_string(),
+            api_key: None,
+            model: Some("acme-large".to_string()),
+            base_url: Some("https://llm.corp.example".to_string()),
+            name: None,
+        };
+        let teacher = entry.to_teacher_entry().unwrap();
+        assert_eq!(teacher.provider, "plugin:acme");
+        assert_eq!(ProviderEntry::from_teacher_entry(&teacher), entry);
+    }
+
     #[test]
     fn test_with_providers_derives_backend_from_local() {
         use crate::config::ExecutionTarget;
@@ -887,4 +1921,34 @@ mod tests {
         assert_eq!(config.cloud_providers().len(), 1);
         assert_eq!(config.local_providers().len(), 1);
     }
+
+    #[test]
+    fn test_workspace_config_parses_and_validates() {
+        let toml = r#"
+            name = "alice"
+            api_keys = ["key-a"]
+            tools = "memory"
+            providers = ["claude"]
+            daily_tokens = 50000
+        "#;
+        let workspace: WorkspaceConfig = toml::from_str(toml).unwrap();
+        assert_eq!(workspace.tools, ToolProfile::Memory);
+        assert_eq!(workspace.daily_tokens, Some(50000));
+        assert_eq!(workspace.monthly_budget_usd, None);
+
+        let mut config = Config::new(vec![]);
+        config.server.workspaces = vec![workspace.clone()];
+        assert!(config.validate().is_ok());
+
+        // A key may only identify one workspace
+        let mut bob = workspace.clone();
+        bob.name = "bob".to_string();
+        config.server.workspaces = vec![workspace.clone(), bob.clone()];
+        assert!(config.validate().is_err());
+
+        bob.api_keys = vec!["key-b".to_string()];
+        bob.name = "../bob".to_string();
+        config.server.workspaces = vec![workspace, bob];
+        assert!(config.validate().is_err());
+    }
 }
@@ -10,6 +10,7 @@
 // README.md is loaded last as general project overview context.
 // When multiple files exist in the same directory, all are loaded in order.
 
+use crate::policy::Policy;
 use std::path::Path;
 use tracing::{debug, info};
 
@@ -24,8 +25,15 @@ const CONTEXT_FILENAMES: &[&str] = &["CLAUDE.md", "FINCH.md", "CONTEXT.md", "REA
 /// 3. Each `CLAUDE.md` / `FINCH.md` / `CONTEXT.md` / `README.md` found walking
 ///    from root down to `cwd` (outermost first, in filename order within same dir)
 ///
+/// Project files covered by a data-residency path rule are skipped, since
+/// the system prompt is sent to every provider (see `crate::policy`).
+///
 /// Returns `None` if no files were found or all were empty.
 pub fn collect_claude_md_context(cwd: &Path) -> Option<String> {
+    collect_context(cwd, crate::policy::active())
+}
+
+fn collect_context(cwd: &Path, policy: &Policy) -> Option<String> {
     let mut sections: Vec<String> = Vec::new();
 
     // 1. User-level: ~/.claude/CLAUDE.md  (Claude Code convention)
@@ -55,6 +63,16 @@ pub fn collect_claude_md_context(cwd: &Path) -> Option<String> {
     for dir in &ancestor_dirs {
         for &filename in CONTEXT_FILENAMES {
             let path = dir.join(filename);
+            if let Some(rule) = policy.restricting_rule(&path) {
+                if path.exists() {
+                    info!(
+                        "Skipped {} (restricted by policy rule '{}')",
+                        path.display(),
+                        rule.name
+                    );
+                }
+                continue;
+            }
             if let Some(content) = read_non_empty(&path) {
                 info!("Loaded project {}: {}", filename, path.display());
                 sections.push(content);
@@ -226,4 +244,23 @@ mod tests {
         assert!(outer_pos < inner_pos, "outer should appear before inner");
         assert!(text.contains("---"));
     }
+
+    #[test]
+    fn skips_files_restricted_by_policy() {
+        let root = TempDir::new().unwrap();
+        let payments = root.path().join("src").join("payments");
+        fs::create_dir_all(&payments).unwrap();
+        write(root.path(), "FINCH.md", "project instructions");
+        write(&payments, "FINCH.md", "settlement internals");
+
+        let policy = Policy::from_toml_str(
+            "[[rule]]\nname = \"payments\"\npaths = [\"src/payments/**\"]\nallow_providers = [\"local\"]",
+            Some(root.path().to_path_buf()),
+        )
+        .unwrap();
+
+        let text = collect_context(&payments, &policy).unwrap();
+        assert!(text.contains("project instructions"));
+        assert!(!text.contains("settlement internals"));
+    }
 }
@@ -4,4 +4,6 @@
 // context that should be prepended to every conversation's system prompt.
 
 pub mod claude_md;
+pub mod outline;
 pub use claude_md::collect_claude_md_context;
+pub use outline::Outline;
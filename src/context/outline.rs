@@ -0,0 +1,403 @@
+// Code outlines — the symbol skeleton of a source file
+//
+// Parses a file with tree-sitter and lists its functions, types, impls,
+// classes and methods with their line ranges, nested the way they are in the
+// file.  Each symbol is shown by its header (the declaration up to its body),
+// so the model can see signatures without reading the file.
+//
+// Supported: Rust, Python, JavaScript, TypeScript (and TSX) and Go.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Longest header shown for a symbol
+const MAX_HEADER_CHARS: usize = 160;
+
+/// Languages an outline can be made for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+/// How to find symbols in one language's syntax tree
+struct Grammar {
+    /// Nodes that are symbols
+    symbols: &'static [&'static str],
+    /// Symbols whose members are symbols too (impls, classes, …)
+    containers: &'static [&'static str],
+    /// Other nodes symbols can sit in (the file itself, declaration lists, …)
+    transparent: &'static [&'static str],
+}
+
+const RUST: Grammar = Grammar {
+    symbols: &[
+        "function_item",
+        "function_signature_item",
+        "struct_item",
+        "enum_item",
+        "union_item",
+        "trait_item",
+        "impl_item",
+        "mod_item",
+        "type_item",
+        "const_item",
+        "static_item",
+        "macro_definition",
+    ],
+    containers: &["impl_item", "trait_item", "mod_item"],
+    transparent: &["source_file", "declaration_list"],
+};
+
+const PYTHON: Grammar = Grammar {
+    symbols: &["function_definition", "class_definition"],
+    containers: &["class_definition"],
+    transparent: &["module", "block", "decorated_definition"],
+};
+
+const JAVASCRIPT: Grammar = Grammar {
+    symbols: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "class_declaration",
+        "abstract_class_declaration",
+        "method_definition",
+        "interface_declaration",
+        "type_alias_declaration",
+        "enum_declaration",
+        // Only when it holds a function or class, see `is_symbol`
+        "variable_declarator",
+    ],
+    containers: &["class_declaration", "abstract_class_declaration"],
+    transparent: &[
+        "program",
+        "class_body",
+        "export_statement",
+        "lexical_declaration",
+        "variable_declaration",
+    ],
+};
+
+const GO: Grammar = Grammar {
+    symbols: &["function_declaration", "method_declaration", "type_spec"],
+    containers: &[],
+    transparent: &["source_file", "type_declaration"],
+};
+
+impl Language {
+    /// The language of `path`, by extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Self::JavaScript,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "go" => Self::Go,
+            _ => return None,
+        })
+    }
+
+    fn parser_language(&self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    fn grammar(&self) -> &'static Grammar {
+        match self {
+            Self::Rust => &RUST,
+            Self::Python => &PYTHON,
+            Self::JavaScript | Self::TypeScript | Self::Tsx => &JAVASCRIPT,
+            Self::Go => &GO,
+        }
+    }
+}
+
+/// One symbol of an outline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// Syntax node kind, e.g. `function_item` or `class_definition`
+    pub kind: &'static str,
+    /// The declaration up to its body, on one line
+    pub header: String,
+    /// First and last line, 1-based
+    pub start_line: usize,
+    pub end_line: usize,
+    /// How many containing symbols it sits in (0 = top level)
+    pub depth: usize,
+}
+
+/// The symbols of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outline {
+    /// Lines in the file
+    pub lines: usize,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Outline {
+    /// Outline of `source`, written in `language`
+    pub fn parse(source: &str, language: Language) -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language.parser_language())
+            .context("Failed to load the tree-sitter grammar")?;
+        let tree = parser
+            .parse(source, None)
+            .context("tree-sitter could not parse the file")?;
+        let mut symbols = Vec::new();
+        collect(
+            tree.root_node(),
+            source,
+            language.grammar(),
+            0,
+            &mut symbols,
+        );
+        Ok(Self {
+            lines: source.lines().count(),
+            symbols,
+        })
+    }
+
+    /// Outline of the file at `path`, its language picked by extension
+    pub fn of_file(path: &Path) -> Result<Self> {
+        let Some(language) = Language::from_path(path) else {
+            bail!(
+                "No outline for {}: supported languages are Rust, Python, \
+                 JavaScript, TypeScript and Go",
+                path.display()
+            );
+        };
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&source, language)
+    }
+
+    /// One line per symbol: its line range, then its header indented by depth
+    pub fn render(&self) -> String {
+        let width = self.lines.max(1).to_string().len();
+        self.symbols
+            .iter()
+            .map(|symbol| {
+                let range = format!("{}-{}", symbol.start_line, symbol.end_line);
+                format!(
+                    "{:<w$}  {}{}",
+                    range,
+                    "  ".repeat(symbol.depth),
+                    symbol.header,
+                    w = width * 2 + 1
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn collect(node: Node, source: &str, grammar: &Grammar, depth: usize, out: &mut Vec<Symbol>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let kind = child.kind();
+        if is_symbol(child, grammar) {
+            out.push(Symbol {
+                kind,
+                header: header(child, source),
+                start_line: child.start_position().row + 1,
+                end_line: child.end_position().row + 1,
+                depth,
+            });
+            if grammar.containers.contains(&kind) {
+                collect(child, source, grammar, depth + 1, out);
+            }
+        } else if grammar.transparent.contains(&kind) {
+            collect(child, source, grammar, depth, out);
+        }
+    }
+}
+
+fn is_symbol(node: Node, grammar: &Grammar) -> bool {
+    let kind = node.kind();
+    if kind == "variable_declarator" {
+        // `const handler = async () => { … }`, not every constant
+        return node.child_by_field_name("value").is_some_and(|value| {
+            matches!(
+                value.kind(),
+                "arrow_function" | "function" | "function_expression" | "class"
+            )
+        });
+    }
+    grammar.symbols.contains(&kind)
+}
+
+/// The declaration up to its body (its first line when it has none),
+/// whitespace collapsed
+fn header(node: Node, source: &str) -> String {
+    let body = match node.kind() {
+        "variable_declarator" => node
+            .child_by_field_name("value")
+            .and_then(|value| value.child_by_field_name("body")),
+        _ => node.child_by_field_name("body"),
+    };
+    let text = match body {
+        Some(body) => &source[node.start_byte()..body.start_byte()],
+        None => source[node.start_byte()..node.end_byte()]
+            .lines()
+            .next()
+            .unwrap_or(""),
+    };
+    let mut header = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    // What opens the body: `{`, Python's `:`, an arrow function's `=>`
+    while let Some(rest) = ["{", ":", "=>"]
+        .iter()
+        .find_map(|opener| header.strip_suffix(opener))
+    {
+        header = rest.trim_end().to_string();
+    }
+    if node.kind() == "type_spec" {
+        header.insert_str(0, "type ");
+    }
+    if header.chars().count() > MAX_HEADER_CHARS {
+        header = header.chars().take(MAX_HEADER_CHARS).collect::<String>() + "…";
+    }
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(outline: &Outline) -> Vec<(usize, &str)> {
+        outline
+            .symbols
+            .iter()
+            .map(|s| (s.depth, s.header.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_outline() {
+        let source = r#"
+use std::fmt;
+
+/// A point
+pub struct Point {
+    x: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>)
+        -> fmt::Result {
+        let inner = |x: i32| x + 1;
+        write!(f, "{}", inner(self.x))
+    }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+const ORIGIN: Point = Point { x: 0 };
+"#;
+        let outline = Outline::parse(source, Language::Rust).unwrap();
+        assert_eq!(
+            headers(&outline),
+            [
+                (0, "pub struct Point"),
+                (0, "impl fmt::Display for Point"),
+                (
+                    1,
+                    "fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result"
+                ),
+                (0, "pub trait Shape"),
+                (1, "fn area(&self) -> f64;"),
+                (0, "const ORIGIN: Point = Point { x: 0 };"),
+            ]
+        );
+        let point = &outline.symbols[0];
+        assert_eq!((point.start_line, point.end_line), (5, 7));
+        assert!(outline
+            .render()
+            .contains("9-15   impl fmt::Display for Point"));
+    }
+
+    #[test]
+    fn test_python_outline() {
+        let source = "\
+import os
+
+class Store(Base):
+    @property
+    def path(self) -> str:
+        return self._path
+
+def load(name, *, strict=False):
+    def helper():
+        pass
+    return helper
+";
+        let outline = Outline::parse(source, Language::Python).unwrap();
+        assert_eq!(
+            headers(&outline),
+            [
+                (0, "class Store(Base)"),
+                (1, "def path(self) -> str"),
+                (0, "def load(name, *, strict=False)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_outline() {
+        let source = "\
+export interface Options { verbose: boolean }
+export class Client {
+  constructor(private url: string) {}
+  async get(path: string): Promise<string> { return fetch(path); }
+}
+export const handler = async (req: Request) => {
+  return new Response();
+};
+const LIMIT = 10;
+";
+        let outline = Outline::parse(source, Language::TypeScript).unwrap();
+        assert_eq!(
+            headers(&outline),
+            [
+                (0, "interface Options"),
+                (0, "class Client"),
+                (1, "constructor(private url: string)"),
+                (1, "async get(path: string): Promise<string>"),
+                (0, "handler = async (req: Request)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_languages_by_extension() {
+        assert_eq!(
+            Language::from_path(Path::new("src/main.rs")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("app/View.TSX")),
+            Some(Language::Tsx)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("cmd/main.go")),
+            Some(Language::Go)
+        );
+        assert_eq!(Language::from_path(Path::new("README.md")), None);
+        assert!(Outline::of_file(Path::new("README.md")).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+// Small cryptographic helpers shared across subsystems
+//
+// Webhook deliveries and S3 request signing both need HMAC-SHA256.  Model
+// manifests, release downloads, shared adapters and tool audit logs are all
+// checked against detached Ed25519 signatures: base64 for files published
+// alongside releases, unpadded base64url for what finch signs with its own
+// device key.  None of them should reach into another's module.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+/// Raw HMAC-SHA256 of `body` under `secret` (RFC 2104)
+pub fn hmac_sha256(secret: &[u8], body: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut key = [0u8; BLOCK];
+    if secret.len() > BLOCK {
+        key[..32].copy_from_slice(&Sha256::digest(secret));
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(key.map(|b| b ^ 0x36));
+    inner.update(body);
+    let mut outer = Sha256::new();
+    outer.update(key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Ed25519 public key from base64; `what` names the key in errors
+pub fn parse_public_key(key: &str, what: &str) -> Result<VerifyingKey> {
+    public_key(decode(&STANDARD, "base64", key, what)?, what)
+}
+
+/// Ed25519 public key from unpadded base64url, as device keys are written
+pub fn parse_public_key_url(key: &str, what: &str) -> Result<VerifyingKey> {
+    public_key(decode(&URL_SAFE_NO_PAD, "base64url", key, what)?, what)
+}
+
+/// Check a detached base64 Ed25519 signature over `data`; `what` names the
+/// signature in errors
+pub fn verify_signature(
+    key: &VerifyingKey,
+    data: &[u8],
+    signature: &[u8],
+    what: &str,
+) -> Result<()> {
+    let signature = String::from_utf8_lossy(signature);
+    let signature = decode(&STANDARD, "base64", &signature, what)?;
+    check(key, data, signature, what)
+}
+
+/// Check a detached unpadded base64url Ed25519 signature over `data`
+pub fn verify_signature_url(
+    key: &VerifyingKey,
+    data: &[u8],
+    signature: &str,
+    what: &str,
+) -> Result<()> {
+    let signature = decode(&URL_SAFE_NO_PAD, "base64url", signature, what)?;
+    check(key, data, signature, what)
+}
+
+/// `N` bytes from `text` in `encoding`
+fn decode<const N: usize>(
+    engine: &impl Engine,
+    encoding: &str,
+    text: &str,
+    what: &str,
+) -> Result<[u8; N]> {
+    let bytes = engine
+        .decode(text.trim())
+        .with_context(|| format!("{} is not valid {}", what, encoding))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("{} must be {} bytes", what, N))
+}
+
+fn public_key(bytes: [u8; 32], what: &str) -> Result<VerifyingKey> {
+    VerifyingKey::from_bytes(&bytes).with_context(|| format!("{} is invalid", what))
+}
+
+fn check(key: &VerifyingKey, data: &[u8], signature: [u8; 64], what: &str) -> Result<()> {
+    key.verify(data, &Signature::from_bytes(&signature))
+        .map_err(|_| anyhow::anyhow!("{} does not match the signed data", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_hmac_sha256_long_key_is_hashed() {
+        // RFC 4231 test case 6: a 131-byte key
+        let key = [0xaa; 131];
+        assert_eq!(
+            hex(&hmac_sha256(
+                &key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_signature_must_match_data() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let encoded = STANDARD.encode(signing_key.verifying_key().as_bytes());
+        let key = parse_public_key(&encoded, "Key").unwrap();
+        let data = b"release tarball".to_vec();
+        let signature = STANDARD.encode(signing_key.sign(&data).to_bytes());
+
+        verify_signature(&key, &data, format!("{}\n", signature).as_bytes(), "Sig").unwrap();
+
+        let mut tampered = data.clone();
+        tampered[0] ^= 1;
+        let err = verify_signature(&key, &tampered, signature.as_bytes(), "Sig").unwrap_err();
+        assert_eq!(err.to_string(), "Sig does not match the signed data");
+
+        let other = SigningKey::from_bytes(&[8u8; 32]).verifying_key();
+        assert!(verify_signature(&other, &data, signature.as_bytes(), "Sig").is_err());
+        assert!(verify_signature(&key, &data, b"not base64!", "Sig").is_err());
+        assert!(parse_public_key("AAAA", "Key").is_err());
+    }
+
+    #[test]
+    fn test_url_safe_keys_and_signatures() {
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let encoded = URL_SAFE_NO_PAD.encode(signing_key.verifying_key().as_bytes());
+        let key = parse_public_key_url(&encoded, "Key").unwrap();
+        let signature = URL_SAFE_NO_PAD.encode(signing_key.sign(b"entry").to_bytes());
+
+        verify_signature_url(&key, b"entry", &signature, "Sig").unwrap();
+        let err = verify_signature_url(&key, b"other", &signature, "Sig").unwrap_err();
+        assert_eq!(err.to_string(), "Sig does not match the signed data");
+
+        let short = URL_SAFE_NO_PAD.encode([0u8; 63]);
+        let err = verify_signature_url(&key, b"entry", &short, "Sig").unwrap_err();
+        assert_eq!(err.to_string(), "Sig must be 64 bytes");
+        let err = parse_public_key_url("a+b/", "Key").unwrap_err();
+        assert_eq!(err.to_string(), "Key is not valid base64url");
+    }
+}
@@ -0,0 +1,114 @@
+// Daemon endpoint addressing
+//
+// The daemon listens either on TCP (`127.0.0.1:11435`) or, for local-only
+// setups, on a Unix domain socket (`unix:~/.finch/daemon.sock`).  A socket
+// avoids port conflicts and is protected by filesystem permissions (0600)
+// instead of API keys.
+
+use anyhow::{bail, Result};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Prefix marking a Unix domain socket address
+pub const UNIX_PREFIX: &str = "unix:";
+
+/// Where the daemon listens / where clients connect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaemonEndpoint {
+    /// `host:port`
+    Tcp(String),
+    /// Path to a Unix domain socket (`~` already expanded)
+    Unix(PathBuf),
+}
+
+impl DaemonEndpoint {
+    /// Parse a bind/daemon address.  `unix:PATH` selects a socket; anything
+    /// else is treated as a TCP `host:port`.
+    pub fn parse(addr: &str) -> Result<Self> {
+        let Some(path) = addr.strip_prefix(UNIX_PREFIX) else {
+            return Ok(Self::Tcp(addr.to_string()));
+        };
+        if !cfg!(unix) {
+            bail!(
+                "Unix domain sockets are not supported on this platform: {}",
+                addr
+            );
+        }
+        if path.is_empty() {
+            bail!("Missing socket path in '{}'", addr);
+        }
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => match dirs::home_dir() {
+                Some(home) => home.join(rest),
+                None => bail!("Cannot expand '~' in socket path: {}", addr),
+            },
+            None => PathBuf::from(path),
+        };
+        Ok(Self::Unix(path))
+    }
+
+    /// Whether the daemon runs on this machine (a socket or a loopback
+    /// address), so it may be sent the per-install token
+    pub fn is_local(&self) -> bool {
+        let addr = match self {
+            Self::Unix(_) => return true,
+            Self::Tcp(addr) => addr,
+        };
+        let host = match addr.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => host,
+            _ => addr.as_str(),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified())
+    }
+}
+
+impl fmt::Display for DaemonEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            Self::Unix(path) => write!(f, "{}{}", UNIX_PREFIX, path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tcp_address() {
+        let endpoint = DaemonEndpoint::parse("127.0.0.1:11435").unwrap();
+        assert_eq!(endpoint, DaemonEndpoint::Tcp("127.0.0.1:11435".to_string()));
+    }
+
+    #[test]
+    fn test_is_local() {
+        for addr in [
+            "127.0.0.1:11435",
+            "localhost:8000",
+            "[::1]:11435",
+            "0.0.0.0:11435",
+        ] {
+            assert!(DaemonEndpoint::parse(addr).unwrap().is_local(), "{}", addr);
+        }
+        for addr in ["192.168.1.20:11435", "gpu-box.local:11435"] {
+            assert!(!DaemonEndpoint::parse(addr).unwrap().is_local(), "{}", addr);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_address_expands_home() {
+        let endpoint = DaemonEndpoint::parse("unix:~/.finch/daemon.sock").unwrap();
+        let expected = dirs::home_dir().unwrap().join(".finch/daemon.sock");
+        assert_eq!(endpoint, DaemonEndpoint::Unix(expected));
+
+        let endpoint = DaemonEndpoint::parse("unix:/tmp/finch.sock").unwrap();
+        assert_eq!(endpoint.to_string(), "unix:/tmp/finch.sock");
+        assert!(DaemonEndpoint::parse("unix:").is_err());
+    }
+}
@@ -8,6 +8,10 @@ use std::fs;
 use std::path::PathBuf;
 use tracing::{info, warn};
 
+/// Extra time `stop_daemon` allows beyond the drain timeout for flushing the
+/// training queue and persisting sessions.
+pub const STOP_MARGIN_SECS: u64 = 15;
+
 /// Manages daemon lifecycle (PID file, shutdown)
 pub struct DaemonLifecycle {
     pid_file: PathBuf,
@@ -82,17 +86,55 @@ impl DaemonLifecycle {
         &self.pid_file
     }
 
+    /// Ask the daemon to restart into the installed binary
+    ///
+    /// Sends SIGHUP: the daemon drains and persists its sessions as on
+    /// shutdown, then re-executes itself with the same arguments (and PID).
+    /// Returns the daemon's PID.
+    pub fn restart_daemon(&self) -> Result<u32> {
+        let pid = self.read_pid().context("Daemon is not running")?;
+
+        #[cfg(target_family = "unix")]
+        {
+            use nix::sys::signal::{kill, Signal};
+            use nix::unistd::Pid;
+
+            kill(Pid::from_raw(pid as i32), Signal::SIGHUP)
+                .context("Failed to send SIGHUP to daemon")?;
+            info!(pid = pid, "Asked daemon to restart");
+            Ok(pid)
+        }
+
+        #[cfg(target_family = "windows")]
+        {
+            anyhow::bail!(
+                "Restart the daemon (PID {}) with `finch daemon-stop` and `finch daemon-start`",
+                pid
+            )
+        }
+    }
+
     /// Stop the daemon gracefully
     ///
+    /// Waits long enough for a daemon using the default drain timeout to
+    /// finish in-flight work; see [`Self::stop_daemon_with_grace`].
+    pub fn stop_daemon(&self) -> Result<()> {
+        self.stop_daemon_with_grace(std::time::Duration::from_secs(
+            crate::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS + STOP_MARGIN_SECS,
+        ))
+    }
+
+    /// Stop the daemon, allowing it up to `grace` to drain
+    ///
     /// Attempts graceful shutdown:
-    /// 1. Send SIGTERM
-    /// 2. Wait up to 5 seconds for process to exit
+    /// 1. Send SIGTERM (the daemon stops accepting work and drains)
+    /// 2. Wait up to `grace` for process to exit
     /// 3. If still running, send SIGKILL
     /// 4. Remove PID file
     ///
     /// Returns Ok if daemon stopped successfully or wasn't running.
     /// Returns Err if failed to stop process.
-    pub fn stop_daemon(&self) -> Result<()> {
+    pub fn stop_daemon_with_grace(&self, grace: std::time::Duration) -> Result<()> {
         // Check if daemon is running
         if !self.pid_file.exists() {
             info!("Daemon not running (PID file does not exist)");
@@ -129,11 +171,10 @@ impl DaemonLifecycle {
             kill(Pid::from_raw(pid as i32), Signal::SIGTERM)
                 .context("Failed to send SIGTERM to daemon")?;
 
-            // Wait up to 5 seconds for graceful shutdown
+            // Wait for the daemon to drain and exit
             let start = Instant::now();
-            let timeout = Duration::from_secs(5);
 
-            while start.elapsed() < timeout {
+            while start.elapsed() < grace {
                 if !process_exists(pid) {
                     info!(pid = pid, "Daemon stopped gracefully");
                     self.cleanup()?;
@@ -166,6 +207,9 @@ impl DaemonLifecycle {
         {
             use std::process::Command as ProcessCommand;
 
+            // taskkill /F terminates immediately; there is no drain on Windows
+            let _ = grace;
+
             // Use taskkill on Windows
             let output = ProcessCommand::new("taskkill")
                 .args(&["/PID", &pid.to_string(), "/F"])
@@ -3,8 +3,12 @@
 // This module provides daemon lifecycle management, auto-spawn capabilities,
 // and utilities for running Shammah as a persistent background service.
 
+pub mod endpoint;
 pub mod lifecycle;
 pub mod spawn;
+pub mod token;
+pub mod update;
 
+pub use endpoint::DaemonEndpoint;
 pub use lifecycle::DaemonLifecycle;
 pub use spawn::{ensure_daemon_running, spawn_daemon};
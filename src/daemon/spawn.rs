@@ -9,6 +9,7 @@ use std::time::Duration;
 use tracing::{debug, info, warn};
 
 use super::lifecycle::DaemonLifecycle;
+use crate::client::HttpTransport;
 use crate::errors;
 
 use crate::config::constants::DEFAULT_DAEMON_ADDR as DEFAULT_BIND;
@@ -24,10 +25,9 @@ use crate::config::constants::DEFAULT_DAEMON_ADDR as DEFAULT_BIND;
 /// Returns Ok(()) if daemon is ready, error otherwise.
 pub async fn ensure_daemon_running(bind_address: Option<&str>) -> Result<()> {
     let bind = bind_address.unwrap_or(DEFAULT_BIND);
-    let base_url = format!("http://{}", bind);
 
     // Quick health check first
-    if health_check_succeeds(&base_url).await {
+    if health_check_succeeds(bind).await {
         debug!("Daemon already running and healthy");
         return Ok(());
     }
@@ -40,7 +40,7 @@ pub async fn ensure_daemon_running(bind_address: Option<&str>) -> Result<()> {
         info!("Daemon process exists, waiting for health check...");
         tokio::time::sleep(Duration::from_secs(2)).await;
 
-        if health_check_succeeds(&base_url).await {
+        if health_check_succeeds(bind).await {
             info!("Daemon now healthy");
             return Ok(());
         }
@@ -67,7 +67,7 @@ pub async fn ensure_daemon_running(bind_address: Option<&str>) -> Result<()> {
     for attempt in 0..20 {
         tokio::time::sleep(Duration::from_millis(500)).await;
 
-        if health_check_succeeds(&base_url).await {
+        if health_check_succeeds(bind).await {
             info!("Daemon started successfully");
             return Ok(());
         }
@@ -170,26 +170,27 @@ pub fn spawn_daemon(bind_address: &str) -> Result<()> {
     Ok(())
 }
 
-/// Check if daemon health endpoint responds
-async fn health_check_succeeds(base_url: &str) -> bool {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(500))
-        .build()
-        .expect("Failed to build HTTP client");
-
-    let url = format!("{}/health", base_url);
+/// Check if daemon health endpoint responds at `bind` (`host:port` or `unix:PATH`)
+async fn health_check_succeeds(bind: &str) -> bool {
+    let transport = match HttpTransport::new(bind, Duration::from_millis(500)) {
+        Ok(transport) => transport,
+        Err(e) => {
+            debug!(bind, error = %e, "Invalid daemon address");
+            return false;
+        }
+    };
 
-    match client.get(&url).send().await {
+    match transport.get("/health", None).await {
         Ok(response) if response.status().is_success() => {
-            debug!(url = %url, "Health check succeeded");
+            debug!(bind, "Health check succeeded");
             true
         }
         Ok(response) => {
-            debug!(url = %url, status = %response.status(), "Health check failed");
+            debug!(bind, status = %response.status(), "Health check failed");
             false
         }
         Err(e) => {
-            debug!(url = %url, error = %e, "Health check request failed");
+            debug!(bind, error = %e, "Health check request failed");
             false
         }
     }
@@ -202,7 +203,7 @@ mod tests {
     #[tokio::test]
     async fn test_health_check_fails_for_invalid_url() {
         // Non-existent server should fail health check
-        let result = health_check_succeeds("http://127.0.0.1:99999").await;
+        let result = health_check_succeeds("127.0.0.1:99999").await;
         assert!(!result);
     }
 }
@@ -0,0 +1,136 @@
+// Per-install daemon token
+//
+// A bind-localhost daemon is reachable by every user on the machine.  The
+// daemon and its clients share a random token in ~/.finch/daemon.token, which
+// only the owning user can read (0600), and clients send it as a bearer key.
+// Whichever side starts first creates the file.
+
+use anyhow::{bail, Context, Result};
+use rand::RngCore;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Random bytes in a token (hex-encoded on disk)
+const TOKEN_BYTES: usize = 32;
+
+/// Default token location: `~/.finch/daemon.token`
+pub fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".finch").join("daemon.token"))
+}
+
+/// Read the token at `path`, creating it (mode 0600) if it doesn't exist
+pub fn load_or_create(path: &Path) -> Result<String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    match create(path) {
+        Ok(token) => return Ok(token),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to create {}", path.display()));
+        }
+    }
+
+    restrict_permissions(path)?;
+    // Another process may have created the file and not written it yet
+    for _ in 0..10 {
+        let token = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    bail!("Daemon token file is empty: {}", path.display())
+}
+
+/// Compare a presented key with the token without leaking where they differ
+pub fn matches(token: &str, candidate: &str) -> bool {
+    token.len() == candidate.len()
+        && token
+            .bytes()
+            .zip(candidate.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn create(path: &Path) -> std::io::Result<String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    file.write_all(token.as_bytes())?;
+    file.sync_all()?;
+    Ok(token)
+}
+
+/// Tighten a token file other users can read (e.g. restored from a backup)
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)
+        .with_context(|| format!("Failed to stat {}", path.display()))?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        tracing::warn!(
+            "{} was readable by other users; restricting it to 0600",
+            path.display()
+        );
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_created_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("daemon.token");
+
+        let token = load_or_create(&path).unwrap();
+        assert_eq!(token.len(), TOKEN_BYTES * 2);
+        assert_eq!(load_or_create(&path).unwrap(), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+            assert_eq!(load_or_create(&path).unwrap(), token);
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("abc123", "abc123"));
+        assert!(!matches("abc123", "abc124"));
+        assert!(!matches("abc123", "abc12"));
+        assert!(!matches("abc123", ""));
+    }
+}
@@ -0,0 +1,608 @@
+// Self-update — signed GitHub releases, atomic install, daemon restart
+//
+// `finch update` and the daemon's background check ask the GitHub releases
+// API for the newest release on the configured channel, then download this
+// platform's tarball together with its detached Ed25519 signature.  Nothing
+// touches the installed binary until the signature verifies against the
+// release key.  The new binary is written next to the old one and renamed
+// over it, so the executable on disk is always complete; the old one is kept
+// as `finch.previous`.
+//
+// A running daemon is then sent SIGHUP.  It drains and persists its sessions
+// exactly as on shutdown, then re-executes itself with the same arguments,
+// and the new binary restores the sessions on start.
+
+use anyhow::{bail, Context, Result};
+use ed25519_dalek::VerifyingKey;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::client::HttpTransport;
+use crate::config::constants::RELEASES_REPO;
+use crate::config::{UpdateChannel, UpdateConfig};
+use crate::crypto;
+use crate::errors;
+
+/// Release signing key baked in by the release workflow (base64, 32 bytes)
+pub const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("FINCH_RELEASE_PUBLIC_KEY");
+
+/// Name of the release asset built for this platform, without `.tar.gz`
+pub fn platform_asset() -> Option<&'static str> {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("finch-macos-arm64")
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some("finch-linux-x86_64")
+    } else {
+        None
+    }
+}
+
+/// A release version (`v0.7.28`, `v0.8.0-nightly.20261018`), ordered by
+/// semver precedence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<String>,
+}
+
+impl Version {
+    /// Parse a tag or version string; a leading `v` and build metadata are ignored
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let s = s.split('+').next().unwrap_or(s);
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (s, Vec::new()),
+        };
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let version = Self {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+            pre,
+        };
+        if parts.next().is_some() || version.pre.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        Some(version)
+    }
+
+    /// Version of this binary
+    pub fn current() -> Self {
+        Self::parse(env!("CARGO_PKG_VERSION")).expect("CARGO_PKG_VERSION is semver")
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // A release outranks its pre-releases
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_pre(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Dot-separated identifiers: numbers numerically and below words, which
+/// compare as text; a shorter prefix comes first
+fn compare_pre(a: &[String], b: &[String]) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.is_prerelease() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// An installable release for this platform
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: Version,
+    pub tag: String,
+    /// Release page
+    pub html_url: String,
+    /// `<asset>.tar.gz`
+    pub tarball_url: String,
+    /// `<asset>.tar.gz.sig`: base64 Ed25519 signature of the tarball
+    pub signature_url: String,
+}
+
+impl Release {
+    fn from_github(release: GithubRelease, asset: &str) -> Option<Self> {
+        let tarball = format!("{}.tar.gz", asset);
+        let signature = format!("{}.sig", tarball);
+        let url_of = |name: &str| {
+            release
+                .assets
+                .iter()
+                .find(|a| a.name == name)
+                .map(|a| a.browser_download_url.clone())
+        };
+        Some(Self {
+            version: Version::parse(&release.tag_name)?,
+            tarball_url: url_of(&tarball)?,
+            // Unsigned releases can't be installed
+            signature_url: url_of(&signature)?,
+            tag: release.tag_name,
+            html_url: release.html_url,
+        })
+    }
+}
+
+/// Newest signed release for `asset` on `channel`
+fn pick_release(
+    releases: Vec<GithubRelease>,
+    channel: UpdateChannel,
+    asset: &str,
+) -> Option<Release> {
+    releases
+        .into_iter()
+        .filter(|r| !r.draft && (channel == UpdateChannel::Nightly || !r.prerelease))
+        .filter_map(|r| Release::from_github(r, asset))
+        .filter(|r| channel == UpdateChannel::Nightly || !r.version.is_prerelease())
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// Finds, verifies and installs releases
+pub struct Updater {
+    client: reqwest::Client,
+    channel: UpdateChannel,
+    public_key: Option<VerifyingKey>,
+}
+
+impl Updater {
+    /// The key in `config` takes precedence over the built-in release key
+    pub fn new(config: &UpdateConfig) -> Result<Self> {
+        let public_key = config
+            .public_key
+            .as_deref()
+            .or(RELEASE_PUBLIC_KEY)
+            .map(|key| crypto::parse_public_key(key, "Release public key"))
+            .transpose()?;
+        let client = crate::http::builder()
+            .user_agent(concat!("finch/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(600))
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            client,
+            channel: config.channel,
+            public_key,
+        })
+    }
+
+    pub fn channel(&self) -> UpdateChannel {
+        self.channel
+    }
+
+    /// Newest installable release on the channel, whatever its version
+    pub async fn latest(&self) -> Result<Option<Release>> {
+        let Some(asset) = platform_asset() else {
+            bail!(errors::wrap_error_with_suggestion(
+                format!(
+                    "No release builds for this platform ({}-{})",
+                    std::env::consts::OS,
+                    std::env::consts::ARCH
+                ),
+                "Build from source instead: cargo install --git https://github.com/darwin-finch/finch"
+            ));
+        };
+        let url = format!(
+            "https://api.github.com/repos/{}/releases?per_page=30",
+            RELEASES_REPO
+        );
+        let response = self
+            .client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to reach GitHub releases")?;
+        if !response.status().is_success() {
+            bail!("GitHub releases returned {}", response.status());
+        }
+        let releases: Vec<GithubRelease> = response
+            .json()
+            .await
+            .context("Failed to parse GitHub releases")?;
+        Ok(pick_release(releases, self.channel, asset))
+    }
+
+    /// The newest release if it is newer than this binary
+    pub async fn check(&self) -> Result<Option<Release>> {
+        let current = Version::current();
+        Ok(self.latest().await?.filter(|r| r.version > current))
+    }
+
+    /// Download a release and return its verified `finch` binary
+    pub async fn download(&self, release: &Release) -> Result<Vec<u8>> {
+        let Some(key) = &self.public_key else {
+            bail!(errors::wrap_error_with_suggestion(
+                "This build has no release signing key, so downloads can't be verified",
+                "Set `public_key` under [update] in ~/.finch/config.toml to the published release key"
+            ));
+        };
+        let tarball = self.fetch(&release.tarball_url).await?;
+        let signature = self.fetch(&release.signature_url).await?;
+        crypto::verify_signature(key, &tarball, &signature, "Release signature")
+            .with_context(|| format!("Refusing to install {}", release.tag))?;
+        extract_binary(&tarball)
+    }
+
+    /// Download, verify and install a release over the running executable.
+    /// Returns the path that was replaced.
+    pub async fn install(&self, release: &Release) -> Result<PathBuf> {
+        let binary = self.download(release).await?;
+        let exe = installed_exe()?;
+        install_binary(&binary, &exe)?;
+        Ok(exe)
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", url))?;
+        if !response.status().is_success() {
+            bail!("Download of {} returned {}", url, response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// The `finch` executable inside a release tarball
+fn extract_binary(tarball: &[u8]) -> Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+    for entry in archive.entries().context("Release tarball is corrupt")? {
+        let mut entry = entry.context("Release tarball is corrupt")?;
+        let is_finch = entry.header().entry_type().is_file()
+            && entry.path()?.file_name() == Some("finch".as_ref());
+        if is_finch {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary)?;
+            if binary.is_empty() {
+                bail!("Release tarball contains an empty finch binary");
+            }
+            return Ok(binary);
+        }
+    }
+    bail!("Release tarball does not contain a finch binary")
+}
+
+/// Path of the running executable, with symlinks resolved
+pub fn installed_exe() -> Result<PathBuf> {
+    let exe = std::env::current_exe().context("Failed to determine current executable path")?;
+    exe.canonicalize()
+        .with_context(|| format!("Failed to resolve {}", exe.display()))
+}
+
+/// Atomically replace `exe` with `binary`, keeping the old one as
+/// `<exe>.previous`
+pub fn install_binary(binary: &[u8], exe: &Path) -> Result<()> {
+    let dir = exe.parent().context("Executable has no parent directory")?;
+    let name = exe
+        .file_name()
+        .context("Executable has no file name")?
+        .to_string_lossy();
+    let staged = dir.join(format!(".{}.update", name));
+    let previous = dir.join(format!("{}.previous", name));
+
+    let cannot_write = |e: std::io::Error| {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            anyhow::anyhow!(errors::wrap_error_with_suggestion(
+                format!("Cannot write to {}", dir.display()),
+                "Run `sudo finch update`, or install finch somewhere you own (e.g. ~/.local/bin)"
+            ))
+        } else {
+            anyhow::Error::new(e).context(format!("Failed to write {}", staged.display()))
+        }
+    };
+
+    {
+        use std::io::Write;
+        let mut file = std::fs::File::create(&staged).map_err(cannot_write)?;
+        file.write_all(binary).map_err(cannot_write)?;
+        file.sync_all().map_err(cannot_write)?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    if let Err(e) = std::fs::copy(exe, &previous) {
+        warn!(error = %e, "Could not keep the previous binary");
+    }
+    if let Err(e) = std::fs::rename(&staged, exe) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e).with_context(|| format!("Failed to replace {}", exe.display()));
+    }
+    info!(path = %exe.display(), "Installed new finch binary");
+    Ok(())
+}
+
+/// Replace this process with `exe`, keeping its arguments — the daemon's
+/// restart after SIGHUP.  Only returns on failure.
+#[cfg(unix)]
+pub fn reexec(exe: &Path) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    let error = std::process::Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .exec();
+    Err(error).with_context(|| format!("Failed to restart into {}", exe.display()))
+}
+
+/// Start `exe` again with this process's arguments; the caller then exits
+#[cfg(not(unix))]
+pub fn reexec(exe: &Path) -> Result<()> {
+    std::process::Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .spawn()
+        .with_context(|| format!("Failed to restart into {}", exe.display()))?;
+    Ok(())
+}
+
+/// Wait until the daemon at `bind` reports `version` on `/health`
+pub async fn wait_for_daemon_version(
+    bind: &str,
+    version: &Version,
+    timeout: Duration,
+) -> Result<()> {
+    let transport = HttpTransport::new(bind, Duration::from_secs(2))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(response) = transport.get("/health", None).await {
+            if response.status().is_success() {
+                if let Ok(health) = response.json::<serde_json::Value>().await {
+                    let running = health["version"].as_str().and_then(Version::parse);
+                    if running.as_ref() == Some(version) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        if Instant::now() >= deadline {
+            bail!(errors::wrap_error_with_suggestion(
+                format!("Daemon did not come back on finch {} in time", version),
+                "Check the daemon log: tail -f ~/.finch/daemon.log"
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Check for a newer release every `check_interval_hours` from inside the
+/// daemon.  With `auto_install` the release is installed and the daemon
+/// restarts itself; otherwise it is logged once.
+pub fn spawn_background_check(config: UpdateConfig) -> Option<JoinHandle<()>> {
+    if config.check_interval_hours == 0 {
+        return None;
+    }
+    let updater = match Updater::new(&config) {
+        Ok(updater) => updater,
+        Err(e) => {
+            warn!(error = %e, "Update checks disabled");
+            return None;
+        }
+    };
+    let period = Duration::from_secs(config.check_interval_hours * 3600);
+
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        let mut announced: Option<Version> = None;
+        loop {
+            interval.tick().await;
+            let release = match updater.check().await {
+                Ok(Some(release)) => release,
+                Ok(None) => {
+                    debug!(channel = updater.channel().as_str(), "finch is up to date");
+                    continue;
+                }
+                Err(e) => {
+                    debug!(error = %e, "Update check failed");
+                    continue;
+                }
+            };
+
+            if !config.auto_install {
+                if announced.as_ref() != Some(&release.version) {
+                    info!(
+                        version = %release.version,
+                        "finch {} is available; run `finch update` to install it",
+                        release.version
+                    );
+                    announced = Some(release.version);
+                }
+                continue;
+            }
+
+            match updater.install(&release).await {
+                Ok(_) => {
+                    info!(version = %release.version, "Update installed; restarting daemon");
+                    request_restart();
+                    return;
+                }
+                Err(e) => warn!(version = %release.version, error = %e, "Automatic update failed"),
+            }
+        }
+    }))
+}
+
+/// Ask this daemon to restart itself (the same SIGHUP `finch update` sends)
+fn request_restart() {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+        if let Err(e) = kill(Pid::this(), Signal::SIGHUP) {
+            warn!(error = %e, "Restart the daemon to run the new version");
+        }
+    }
+    #[cfg(not(unix))]
+    warn!("Restart the daemon to run the new version");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    fn release(tag: &str, prerelease: bool, assets: &[&str]) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag.to_string(),
+            draft: false,
+            prerelease,
+            html_url: format!("https://github.com/{}/releases/tag/{}", RELEASES_REPO, tag),
+            assets: assets
+                .iter()
+                .map(|name| GithubAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{}/{}", tag, name),
+                })
+                .collect(),
+        }
+    }
+
+    fn tarball(name: &str, contents: &[u8]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_version_precedence() {
+        assert!(v("v0.7.28") > v("0.7.27"));
+        assert!(v("0.10.0") > v("0.9.9"));
+        assert!(v("0.8.0") > v("0.8.0-nightly.20261018"));
+        assert!(v("0.8.0-nightly.20261019") > v("0.8.0-nightly.20261018"));
+        assert!(v("0.8.0-nightly.2") < v("0.8.0-nightly.10"));
+        assert!(v("0.8.0-alpha") < v("0.8.0-alpha.1"));
+        assert_eq!(v("v1.2.3+build.5"), v("1.2.3"));
+        assert_eq!(v("0.8.0-nightly.1").to_string(), "0.8.0-nightly.1");
+        assert!(Version::parse("nightly").is_none());
+        assert!(Version::parse("1.2").is_none());
+        assert!(Version::parse("1.2.3.4").is_none());
+    }
+
+    #[test]
+    fn test_pick_release_by_channel() {
+        let asset = "finch-linux-x86_64";
+        let signed = ["finch-linux-x86_64.tar.gz", "finch-linux-x86_64.tar.gz.sig"];
+        let releases = || {
+            vec![
+                release("v0.7.28", false, &signed),
+                release("v0.8.0-nightly.20261018", true, &signed),
+                // Unsigned and other-platform releases are never picked
+                release("v0.9.0", false, &["finch-linux-x86_64.tar.gz"]),
+                release("v0.9.1", false, &["finch-macos-arm64.tar.gz"]),
+            ]
+        };
+
+        let stable = pick_release(releases(), UpdateChannel::Stable, asset).unwrap();
+        assert_eq!(stable.tag, "v0.7.28");
+        assert!(stable.signature_url.ends_with(".tar.gz.sig"));
+
+        let nightly = pick_release(releases(), UpdateChannel::Nightly, asset).unwrap();
+        assert_eq!(nightly.version, v("0.8.0-nightly.20261018"));
+
+        let mut draft = release("v1.0.0", false, &signed);
+        draft.draft = true;
+        assert!(pick_release(vec![draft], UpdateChannel::Nightly, asset).is_none());
+    }
+
+    #[test]
+    fn test_extract_binary() {
+        assert_eq!(extract_binary(&tarball("finch", b"ELF")).unwrap(), b"ELF");
+        assert!(extract_binary(&tarball("README.md", b"hi")).is_err());
+        assert!(extract_binary(b"not a tarball").is_err());
+    }
+
+    #[test]
+    fn test_install_replaces_binary_and_keeps_previous() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("finch");
+        std::fs::write(&exe, b"old").unwrap();
+
+        install_binary(b"new", &exe).unwrap();
+
+        assert_eq!(std::fs::read(&exe).unwrap(), b"new");
+        assert_eq!(
+            std::fs::read(dir.path().join("finch.previous")).unwrap(),
+            b"old"
+        );
+        assert!(!dir.path().join(".finch.update").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&exe).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
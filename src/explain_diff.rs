@@ -0,0 +1,847 @@
+// Reviews of arbitrary diffs (`finch explain-diff`)
+//
+// The patch comes from `git diff <range>` or from stdin and is split into one
+// section per file.  Each file gets one routed query asking for its intent, a
+// risk level and any concerns; a file whose patch is too large for a single
+// request is map-reduced first, hunk by hunk, the same way `finch summarize`
+// handles large inputs.  A final routed query reads the per-file notes and
+// writes the overall summary.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::io::Read;
+
+use crate::config::constants::{EXPLAIN_DIFF_CHUNK_CHARS, EXPLAIN_DIFF_MAX_BYTES};
+use crate::summarize::{map_chunk, SummaryBackend};
+
+/// Lock files and other generated output: listed in the review, never sent
+const GENERATED_FILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "Gemfile.lock",
+    "go.sum",
+];
+
+/// A unified diff and where it came from
+#[derive(Debug, Clone)]
+pub struct Patch {
+    /// The revision range, or "stdin"
+    pub label: String,
+    pub text: String,
+}
+
+impl Patch {
+    /// Run `git diff <range>` in the current directory
+    pub fn from_git(range: &str) -> Result<Self> {
+        let output = std::process::Command::new("git")
+            .args(["diff", "--no-color", "--no-ext-diff", "-M", range])
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            bail!(
+                "git diff {} failed: {}",
+                range,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        if output.stdout.len() > EXPLAIN_DIFF_MAX_BYTES {
+            bail!(
+                "git diff {} is larger than {} MB",
+                range,
+                EXPLAIN_DIFF_MAX_BYTES / (1024 * 1024)
+            );
+        }
+        Self::new(range, String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Read a patch (git or plain `diff -u` format) from `reader`
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader
+            .take(EXPLAIN_DIFF_MAX_BYTES as u64 + 1)
+            .read_to_end(&mut bytes)
+            .context("Failed to read patch")?;
+        if bytes.len() > EXPLAIN_DIFF_MAX_BYTES {
+            bail!(
+                "Patch is larger than {} MB",
+                EXPLAIN_DIFF_MAX_BYTES / (1024 * 1024)
+            );
+        }
+        Self::new("stdin", String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn new(label: &str, text: String) -> Result<Self> {
+        if text.trim().is_empty() {
+            bail!("No changes in {}", label);
+        }
+        Ok(Self {
+            label: label.to_string(),
+            text,
+        })
+    }
+}
+
+/// How a file was changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    #[default]
+    Modified,
+    Renamed,
+}
+
+impl FileStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Deleted => "deleted",
+            Self::Modified => "modified",
+            Self::Renamed => "renamed",
+        }
+    }
+}
+
+/// One file's section of a patch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilePatch {
+    pub path: String,
+    /// The path before a rename
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    /// The section itself, headers included
+    pub text: String,
+}
+
+impl FilePatch {
+    fn change(&self) -> String {
+        describe_change(
+            self.status,
+            self.old_path.as_deref(),
+            self.additions,
+            self.deletions,
+        )
+    }
+}
+
+/// "modified, +3 -1" or "renamed from old.rs, +0 -0"
+fn describe_change(
+    status: FileStatus,
+    old_path: Option<&str>,
+    additions: usize,
+    deletions: usize,
+) -> String {
+    match old_path {
+        Some(old) => format!("renamed from {}, +{} -{}", old, additions, deletions),
+        None => format!("{}, +{} -{}", status.as_str(), additions, deletions),
+    }
+}
+
+/// Split a unified diff into per-file sections.  Understands `git diff`
+/// output (renames, new and deleted files, binary files) and plain
+/// `diff -u` output.
+pub fn parse_patch(text: &str) -> Vec<FilePatch> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut files: Vec<FilePatch> = Vec::new();
+    // Whether the current section started with a `diff --git` header, and
+    // whether a hunk has begun in it
+    let mut git_header = false;
+    let mut in_hunk = false;
+
+    for (i, line) in lines.iter().enumerate() {
+        let plain_header = line.starts_with("--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| next.starts_with("+++ "))
+            && (files.is_empty() || (!git_header && in_hunk));
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FilePatch {
+                path: rest.rsplit_once(" b/").map_or(rest, |(_, b)| b).to_string(),
+                ..FilePatch::default()
+            });
+            git_header = true;
+            in_hunk = false;
+        } else if plain_header {
+            files.push(FilePatch::default());
+            git_header = false;
+            in_hunk = false;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        file.text.push_str(line);
+        file.text.push('\n');
+
+        if in_hunk {
+            if line.starts_with('+') {
+                file.additions += 1;
+            } else if line.starts_with('-') {
+                file.deletions += 1;
+            }
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if let Some(old) = line.strip_prefix("rename from ") {
+            file.old_path = Some(old.to_string());
+            file.status = FileStatus::Renamed;
+        } else if let Some(new) = line.strip_prefix("rename to ") {
+            file.path = new.to_string();
+        } else if line.starts_with("Binary files ") || line.starts_with("GIT binary patch") {
+            file.binary = true;
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            match header_path(old) {
+                Some(old) if file.path.is_empty() => file.path = old,
+                Some(_) => {}
+                None => file.status = FileStatus::Added,
+            }
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            match header_path(new) {
+                Some(new) => file.path = new,
+                None => file.status = FileStatus::Deleted,
+            }
+        }
+    }
+    files
+}
+
+/// The path in a `---`/`+++` header: `a/`/`b/` prefix and timestamp dropped,
+/// `None` for /dev/null
+fn header_path(header: &str) -> Option<String> {
+    let path = header.split('\t').next().unwrap_or(header).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+fn is_generated(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    GENERATED_FILES.contains(&name)
+}
+
+/// Split a file's patch into pieces of at most `max_chars`, breaking between
+/// hunks where possible (and between lines inside an oversized hunk)
+fn chunk_patch(text: &str, max_chars: usize) -> Vec<String> {
+    let mut hunks: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("@@") || hunks.is_empty() {
+            hunks.push(String::new());
+        }
+        let hunk = hunks.last_mut().expect("pushed above");
+        hunk.push_str(line);
+        hunk.push('\n');
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for hunk in hunks {
+        if !current.is_empty() && current.len() + hunk.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if hunk.len() <= max_chars {
+            current.push_str(&hunk);
+            continue;
+        }
+        for line in hunk.lines() {
+            if !current.is_empty() && current.len() + line.len() + 1 > max_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// How risky a change looks to the reviewer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    Low,
+    Medium,
+    High,
+}
+
+impl Risk {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// The first word of `text` as a risk level, if it is one
+    fn parse(text: &str) -> Option<Self> {
+        let word = text
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .find(|w| !w.is_empty())?;
+        match word.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" | "moderate" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// The review of one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReview {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub additions: usize,
+    pub deletions: usize,
+    /// What the change does (or why the file wasn't reviewed)
+    pub intent: String,
+    /// `None` for files that weren't sent, or when the reply had no risk
+    pub risk: Option<Risk>,
+    pub concerns: Vec<String>,
+}
+
+/// A finished review (the `--json` output)
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReview {
+    pub source: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub summary: String,
+    pub risk: Option<Risk>,
+    pub files: Vec<FileReview>,
+}
+
+impl DiffReview {
+    /// Markdown for the terminal or a pipe
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Review of {}\n\n{} file{} changed, +{} -{}, overall risk: {}\n\n{}\n",
+            self.source,
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+            self.additions,
+            self.deletions,
+            risk_label(self.risk),
+            self.summary.trim()
+        );
+
+        out.push_str("\n| File | Change | Risk |\n|---|---|---|\n");
+        for file in &self.files {
+            out.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                file.path,
+                file_change(file),
+                risk_label(file.risk)
+            ));
+        }
+
+        for file in &self.files {
+            out.push_str(&format!(
+                "\n## `{}`\n\n{}, risk: {}\n\n{}\n",
+                file.path,
+                file_change(file),
+                risk_label(file.risk),
+                file.intent.trim()
+            ));
+            if !file.concerns.is_empty() {
+                out.push_str("\nConcerns:\n");
+                for concern in &file.concerns {
+                    out.push_str(&format!("- {}\n", concern));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn risk_label(risk: Option<Risk>) -> &'static str {
+    risk.map_or("n/a", Risk::as_str)
+}
+
+fn file_change(file: &FileReview) -> String {
+    describe_change(
+        file.status,
+        file.old_path.as_deref(),
+        file.additions,
+        file.deletions,
+    )
+}
+
+/// The fields of a reply in the `LEAD: ... RISK: ... CONCERNS: - ...` format
+#[derive(Debug, Default, PartialEq)]
+struct Reply {
+    text: String,
+    risk: Option<Risk>,
+    concerns: Vec<String>,
+}
+
+/// Read a reply in the format the prompts ask for.  Models don't always
+/// follow it: markdown emphasis is ignored, and a reply with no `lead:` line
+/// is taken whole as the text.
+fn parse_reply(reply: &str, lead: &str) -> Reply {
+    enum Section {
+        None,
+        Text,
+        Concerns,
+    }
+    let mut parsed = Reply::default();
+    let mut section = Section::None;
+    let mut found_lead = false;
+
+    for raw in reply.lines() {
+        let line = raw.trim().replace("**", "");
+        let lower = line.to_ascii_lowercase();
+        if let Some(rest) = lower.strip_prefix(lead).and_then(|r| r.strip_prefix(':')) {
+            let start = line.len() - rest.len();
+            parsed.text = line[start..].trim().to_string();
+            section = Section::Text;
+            found_lead = true;
+        } else if let Some(rest) = lower.strip_prefix("risk:") {
+            parsed.risk = Risk::parse(rest);
+            section = Section::None;
+        } else if let Some(rest) = lower.strip_prefix("concerns:") {
+            section = Section::Concerns;
+            let start = line.len() - rest.len();
+            push_concern(&mut parsed.concerns, &line[start..]);
+        } else {
+            match section {
+                Section::Text if !line.is_empty() => {
+                    parsed.text.push(' ');
+                    parsed.text.push_str(&line);
+                }
+                Section::Concerns => push_concern(&mut parsed.concerns, &line),
+                _ => {}
+            }
+        }
+    }
+    if !found_lead {
+        parsed.text = reply.trim().to_string();
+    }
+    parsed.text = parsed.text.trim().to_string();
+    parsed
+}
+
+fn push_concern(concerns: &mut Vec<String>, line: &str) {
+    let concern = line
+        .trim()
+        .trim_start_matches(['-', '*', '•'])
+        .trim()
+        .trim_end_matches('.');
+    if concern.is_empty() || concern.eq_ignore_ascii_case("none") {
+        return;
+    }
+    concerns.push(format!("{}.", concern));
+}
+
+/// Review every file in `patch`, then the change as a whole, reporting
+/// progress through `progress`
+pub async fn explain_diff(
+    backend: &dyn SummaryBackend,
+    patch: &Patch,
+    mut progress: impl FnMut(&str),
+) -> Result<DiffReview> {
+    let files = parse_patch(&patch.text);
+    if files.is_empty() {
+        bail!("{} does not look like a unified diff", patch.label);
+    }
+    let file_list = files
+        .iter()
+        .map(|f| format!("{} ({})", f.path, f.change()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut reviews = Vec::with_capacity(files.len());
+    for (i, file) in files.iter().enumerate() {
+        let skipped = if file.binary {
+            Some("Binary file; not reviewed.")
+        } else if is_generated(&file.path) {
+            Some("Generated file; not reviewed.")
+        } else if file.additions + file.deletions == 0 {
+            Some("No content changes (mode change or pure rename).")
+        } else {
+            None
+        };
+        let reply = match skipped {
+            Some(reason) => Reply {
+                text: reason.to_string(),
+                ..Reply::default()
+            },
+            None => {
+                progress(&format!(
+                    "Reviewing {} ({}/{})",
+                    file.path,
+                    i + 1,
+                    files.len()
+                ));
+                review_file(backend, patch, file, &file_list, &mut progress).await?
+            }
+        };
+        reviews.push(FileReview {
+            path: file.path.clone(),
+            old_path: file.old_path.clone(),
+            status: file.status,
+            additions: file.additions,
+            deletions: file.deletions,
+            intent: reply.text,
+            risk: reply.risk,
+            concerns: reply.concerns,
+        });
+    }
+
+    let additions = reviews.iter().map(|r| r.additions).sum();
+    let deletions = reviews.iter().map(|r| r.deletions).sum();
+    let notes = reviews
+        .iter()
+        .map(|r| {
+            let mut note = format!(
+                "{} ({}, risk: {})\n{}",
+                r.path,
+                file_change(r),
+                risk_label(r.risk),
+                r.intent
+            );
+            for concern in &r.concerns {
+                note.push_str(&format!("\n- {}", concern));
+            }
+            note
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    progress("Writing summary");
+    let prompt = format!(
+        "Below are per-file review notes for a diff ({}, {} files, +{} -{}).\n\
+         Reply in exactly this format:\n\
+         SUMMARY: <one short paragraph on what the change as a whole does and why>\n\
+         RISK: <low, medium or high, for the change as a whole>\n\n{}",
+        patch.label,
+        reviews.len(),
+        additions,
+        deletions,
+        notes
+    );
+    let reply = parse_reply(&backend.routed(&prompt).await?, "summary");
+    let risk = reply
+        .risk
+        .or_else(|| reviews.iter().filter_map(|r| r.risk).max());
+
+    Ok(DiffReview {
+        source: patch.label.clone(),
+        additions,
+        deletions,
+        summary: reply.text,
+        risk,
+        files: reviews,
+    })
+}
+
+/// Ask for one file's intent, risk and concerns; a patch too large for one
+/// request is described hunk group by hunk group first
+async fn review_file(
+    backend: &dyn SummaryBackend,
+    patch: &Patch,
+    file: &FilePatch,
+    file_list: &str,
+    progress: &mut impl FnMut(&str),
+) -> Result<Reply> {
+    let chunks = chunk_patch(&file.text, EXPLAIN_DIFF_CHUNK_CHARS);
+    let body = if chunks.len() <= 1 {
+        format!("```diff\n{}```", file.text)
+    } else {
+        let mut notes = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            progress(&format!("  {} part {}/{}", file.path, i + 1, chunks.len()));
+            let prompt = format!(
+                "This is part {} of {} of a diff to {}. Describe what these hunks \
+                 change in a short paragraph, and point out anything risky (removed \
+                 checks, changed error handling, concurrency, security, public API).\
+                 \n\n```diff\n{}```",
+                i + 1,
+                chunks.len(),
+                file.path,
+                chunk
+            );
+            let (note, _) = map_chunk(backend, &prompt).await?;
+            notes.push(note.trim().to_string());
+        }
+        format!(
+            "The diff is too large to show; here are notes on its consecutive parts:\n\n{}",
+            notes.join("\n\n")
+        )
+    };
+
+    let prompt = format!(
+        "Review this change to {} ({}), part of {} which touches: {}.\n\
+         Reply in exactly this format:\n\
+         INTENT: <one or two sentences on what the change does and why>\n\
+         RISK: <low, medium or high>\n\
+         CONCERNS:\n\
+         - <a specific problem a reviewer should check, or \"none\">\n\n{}",
+        file.path,
+        file.change(),
+        patch.label,
+        file_list,
+        body
+    );
+    Ok(parse_reply(&backend.routed(&prompt).await?, "intent"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    const GIT_PATCH: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ pub mod a;
+-pub mod b;
++pub mod c;
++pub mod d;
+diff --git a/NOTES.md b/NOTES.md
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/NOTES.md
+@@ -0,0 +1 @@
++--- not a header
+diff --git a/old.rs b/new.rs
+similarity index 90%
+rename from old.rs
+rename to new.rs
+diff --git a/logo.png b/logo.png
+index 4444444..5555555 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/Cargo.lock b/Cargo.lock
+index 6666666..7777777 100644
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1 +1 @@
+-version = 3
++version = 4
+";
+
+    /// Answers per-file prompts with `file_reply` and the summary prompt
+    /// with `summary_reply`, recording every prompt
+    struct FakeBackend {
+        file_reply: &'static str,
+        summary_reply: &'static str,
+        prompts: Mutex<Vec<(&'static str, String)>>,
+    }
+
+    impl FakeBackend {
+        fn new(file_reply: &'static str, summary_reply: &'static str) -> Self {
+            Self {
+                file_reply,
+                summary_reply,
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SummaryBackend for FakeBackend {
+        async fn local(&self, prompt: &str) -> Result<String> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(("local", prompt.to_string()));
+            Ok("part note".to_string())
+        }
+
+        async fn routed(&self, prompt: &str) -> Result<String> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(("routed", prompt.to_string()));
+            Ok(if prompt.contains("SUMMARY:") {
+                self.summary_reply
+            } else {
+                self.file_reply
+            }
+            .to_string())
+        }
+    }
+
+    fn patch(text: &str) -> Patch {
+        Patch {
+            label: "main..HEAD".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_git_patch() {
+        let files = parse_patch(GIT_PATCH);
+        assert_eq!(files.len(), 5);
+
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+
+        assert_eq!(files[1].path, "NOTES.md");
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!((files[1].additions, files[1].deletions), (1, 0));
+
+        assert_eq!(files[2].path, "new.rs");
+        assert_eq!(files[2].old_path.as_deref(), Some("old.rs"));
+        assert_eq!(files[2].status, FileStatus::Renamed);
+
+        assert!(files[3].binary);
+        assert_eq!(files[4].path, "Cargo.lock");
+        assert!(files[0].text.starts_with("diff --git a/src/lib.rs"));
+        assert!(files[0].text.ends_with("+pub mod d;\n"));
+    }
+
+    #[test]
+    fn test_parse_plain_unified_diff() {
+        let text = "\
+--- a.txt\t2026-01-01 00:00:00
++++ a.txt\t2026-01-02 00:00:00
+@@ -1 +1 @@
+-one
++two
+--- b.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+        let files = parse_patch(text);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.txt");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        assert_eq!(files[1].path, "b.txt");
+        assert_eq!(files[1].status, FileStatus::Deleted);
+        assert!(parse_patch("just some text\n").is_empty());
+    }
+
+    #[test]
+    fn test_chunk_patch_breaks_between_hunks() {
+        let hunk = format!("@@ -1 +1 @@\n{}\n", "+x".repeat(40));
+        let text = format!("--- a/f\n+++ b/f\n{}", hunk.repeat(3));
+        let chunks = chunk_patch(&text, hunk.len() + 20);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].starts_with("@@"));
+        assert_eq!(chunks.concat(), text);
+
+        // A single oversized hunk is split between lines
+        let big = format!("@@ -1 +1 @@\n{}", "+line\n".repeat(50));
+        let chunks = chunk_patch(&big, 60);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 60));
+        assert_eq!(chunks.concat(), big);
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = "**INTENT:** Swaps module b for c\nand adds d.\n\
+                     **RISK:** Medium.\nCONCERNS:\n- b may still be used elsewhere.\n* Check d";
+        assert_eq!(
+            parse_reply(reply, "intent"),
+            Reply {
+                text: "Swaps module b for c and adds d.".to_string(),
+                risk: Some(Risk::Medium),
+                concerns: vec![
+                    "b may still be used elsewhere.".to_string(),
+                    "Check d.".to_string()
+                ],
+            }
+        );
+
+        let none = parse_reply("INTENT: Docs.\nRISK: low\nCONCERNS: none", "intent");
+        assert_eq!(none.risk, Some(Risk::Low));
+        assert!(none.concerns.is_empty());
+
+        // Off-format replies are kept whole
+        let loose = parse_reply("It renames a function.", "intent");
+        assert_eq!(loose.text, "It renames a function.");
+        assert_eq!(loose.risk, None);
+    }
+
+    #[tokio::test]
+    async fn test_explain_diff_reviews_text_files_only() {
+        let backend = FakeBackend::new(
+            "INTENT: Changes things.\nRISK: high\nCONCERNS:\n- Watch out",
+            "SUMMARY: Reorganizes modules.\nRISK: medium",
+        );
+        let review = explain_diff(&backend, &patch(GIT_PATCH), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(review.files.len(), 5);
+        assert_eq!(review.summary, "Reorganizes modules.");
+        assert_eq!(review.risk, Some(Risk::Medium));
+        assert_eq!((review.additions, review.deletions), (4, 2));
+        assert_eq!(review.files[0].risk, Some(Risk::High));
+        assert_eq!(review.files[0].concerns, vec!["Watch out."]);
+        // Rename without edits, binary and lock file are listed but not sent
+        for skipped in &review.files[2..] {
+            assert_eq!(skipped.risk, None);
+        }
+        assert!(review.files[3].intent.starts_with("Binary"));
+        assert!(review.files[4].intent.starts_with("Generated"));
+
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 3);
+        assert!(prompts[0].1.contains("+pub mod c;"));
+        assert!(prompts[0].1.contains("NOTES.md (added, +1 -0)"));
+        assert!(prompts[2]
+            .1
+            .contains("src/lib.rs (modified, +2 -1, risk: high)"));
+
+        let markdown = review.to_markdown();
+        assert!(markdown.starts_with("# Review of main..HEAD\n\n5 files changed, +4 -2"));
+        assert!(markdown.contains("| `new.rs` | renamed from old.rs, +0 -0 | n/a |"));
+        assert!(markdown.contains("\nConcerns:\n- Watch out.\n"));
+    }
+
+    #[tokio::test]
+    async fn test_large_file_is_described_in_parts() {
+        let hunk = format!("@@ -1 +1 @@\n{}\n", "+x".repeat(1000));
+        let text = format!(
+            "--- a/big.rs\n+++ b/big.rs\n{}",
+            hunk.repeat(EXPLAIN_DIFF_CHUNK_CHARS * 3 / hunk.len())
+        );
+        // No RISK line in the summary: the worst file risk stands in
+        let backend = FakeBackend::new("INTENT: Big change.\nRISK: low", "Just prose.");
+        let review = explain_diff(&backend, &patch(&text), |_| {}).await.unwrap();
+
+        assert_eq!(review.summary, "Just prose.");
+        assert_eq!(review.risk, Some(Risk::Low));
+        let prompts = backend.prompts.lock().unwrap();
+        let locals = prompts.iter().filter(|(kind, _)| *kind == "local").count();
+        assert!(locals >= 3);
+        let file_prompt = &prompts[locals].1;
+        assert!(file_prompt.contains("part note"));
+        assert!(!file_prompt.contains("+xx"));
+    }
+
+    #[test]
+    fn test_patch_from_reader_rejects_empty_input() {
+        assert!(Patch::from_reader("  \n".as_bytes()).is_err());
+        let patch = Patch::from_reader(GIT_PATCH.as_bytes()).unwrap();
+        assert_eq!(patch.label, "stdin");
+    }
+}
@@ -3,6 +3,7 @@
 // Users can rate responses to collect training data for LoRA fine-tuning.
 // Feedback is logged to ~/.finch/feedback.jsonl
 
+use crate::generators::Attribution;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, OpenOptions};
@@ -53,6 +54,10 @@ pub struct FeedbackEntry {
     /// Optional note from user
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    /// Generator of the rated response (absent in entries logged before
+    /// attribution)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<Attribution>,
 }
 
 impl FeedbackEntry {
@@ -68,6 +73,7 @@ impl FeedbackEntry {
             weight: rating.training_weight(),
             rating,
             note: None,
+            attribution: None,
         }
     }
 
@@ -76,6 +82,12 @@ impl FeedbackEntry {
         self.note = Some(note);
         self
     }
+
+    /// Record which generator produced the rated response
+    pub fn with_attribution(mut self, attribution: Attribution) -> Self {
+        self.attribution = Some(attribution);
+        self
+    }
 }
 
 /// Feedback logger - writes feedback to JSONL file
@@ -107,7 +119,9 @@ impl FeedbackLogger {
                 format!("Failed to open feedback log: {}", self.file_path.display())
             })?;
 
-        let json = serde_json::to_string(entry).context("Failed to serialize feedback entry")?;
+        let json = crate::redact::active()
+            .to_json_line(entry)
+            .context("Failed to serialize feedback entry")?;
 
         writeln!(file, "{}", json).context("Failed to write feedback entry")?;
 
@@ -191,4 +205,23 @@ mod tests {
 
         assert_eq!(entry.note, Some("Wrong algorithm".to_string()));
     }
+
+    #[test]
+    fn test_feedback_entry_attribution() {
+        let entry = FeedbackEntry::new(
+            "Test".to_string(),
+            "Response".to_string(),
+            FeedbackRating::Good,
+        );
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(!json.contains("attribution"));
+        // Entries logged before attribution still parse
+        let parsed: FeedbackEntry = serde_json::from_str(&json).unwrap();
+        assert!(parsed.attribution.is_none());
+
+        let entry = entry.with_attribution(Attribution::local("Qwen2.5-3B"));
+        let json = serde_json::to_string(&entry).unwrap();
+        let parsed: FeedbackEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.attribution, entry.attribution);
+    }
 }
@@ -7,10 +7,12 @@ use tokio::sync::mpsc;
 
 use crate::claude::{ClaudeClient, ContentBlock, Message, MessageRequest};
 use crate::context::collect_claude_md_context;
+use crate::providers::LlmProvider;
 use crate::tools::types::ToolDefinition;
 
 use super::{
-    Generator, GeneratorCapabilities, GeneratorResponse, ResponseMetadata, StreamChunk, ToolUse,
+    Attribution, Generator, GeneratorCapabilities, GeneratorResponse, ResponseMetadata,
+    StreamChunk, ToolUse,
 };
 
 pub const CODING_SYSTEM_PROMPT: &str = "You are Finch, an expert software engineering \
@@ -32,7 +34,7 @@ use edit instead.
 
 ## Approach
 
-Before editing: glob/grep to find the file, read the relevant section, understand the context.
+Before editing: glob/grep to find the file, outline it if it is large, read the relevant section, understand the context.
 Make the minimum change needed — don't touch code outside the task.
 After structural changes: run the build or tests to verify (cargo build, cargo test, npm test…).
 If tests fail: read the error carefully and diagnose the root cause before retrying.
@@ -164,4 +166,17 @@ impl Generator for ClaudeGenerator {
     fn name(&self) -> &str {
         self.client.provider_name()
     }
+
+    fn system_prompt_chars(&self) -> usize {
+        self.system_prompt().len()
+    }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        self.client.provider().cancel_generation().await
+    }
+
+    async fn attribution(&self) -> Attribution {
+        let provider = self.client.provider();
+        Attribution::teacher(provider.name(), provider.default_model())
+    }
 }
@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::mpsc;
 
@@ -34,6 +35,77 @@ pub trait Generator: Send + Sync {
 
     /// Get generator name for logging
     fn name(&self) -> &str;
+
+    /// Length in characters of the system prompt sent with each request
+    fn system_prompt_chars(&self) -> usize {
+        0
+    }
+
+    /// Tell the backend to stop a response that was abandoned (e.g. on
+    /// Ctrl+C) so it stops spending tokens
+    async fn cancel_generation(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Who messages from this generator are attributed to.  Streaming
+    /// responses don't report their model, so this names the default one.
+    async fn attribution(&self) -> Attribution {
+        Attribution::teacher(self.name(), self.name())
+    }
+}
+
+/// How far a message's generator is trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trust {
+    /// A cloud teacher model
+    Teacher,
+    /// The on-device model (possibly with a trained adapter)
+    Local,
+}
+
+/// Which generator produced a message, kept with it so transcripts mixing
+/// local and teacher output stay auditable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attribution {
+    pub trust: Trust,
+    /// "local" or the teacher provider's name ("claude", "openai", ...)
+    pub provider: String,
+    pub model: String,
+    /// LoRA adapter (name and version) applied to the local model.  Not
+    /// set yet: the local generator doesn't load trained adapters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<String>,
+}
+
+impl Attribution {
+    /// A message generated by the on-device model
+    pub fn local(model: impl Into<String>) -> Self {
+        Self {
+            trust: Trust::Local,
+            provider: "local".to_string(),
+            model: model.into(),
+            adapter: None,
+        }
+    }
+
+    /// A message generated by a teacher provider
+    pub fn teacher(provider: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            trust: Trust::Teacher,
+            provider: provider.into(),
+            model: model.into(),
+            adapter: None,
+        }
+    }
+
+    /// The same generator, with the model a response reported using
+    pub fn with_model(mut self, model: &str) -> Self {
+        if !model.is_empty() {
+            self.model = model.to_string();
+        }
+        self
+    }
 }
 
 /// Generator capabilities (what features are supported)
@@ -82,6 +154,12 @@ pub enum StreamChunk {
     Usage {
         input_tokens: u32,
     },
+    /// Incremental hidden chain-of-thought from a reasoning model.  Shown
+    /// collapsed in the TUI; never part of the response text.
+    ThinkingDelta(String),
+    /// Reasoning tokens a model reported using, for models whose thinking
+    /// isn't streamed as text
+    ReasoningTokens(u32),
 }
 
 /// Tool use request from generator
@@ -141,6 +219,24 @@ mod tests {
         assert!(matches!(block, ContentBlock::ToolUse { .. }));
     }
 
+    // --- Attribution ---
+
+    #[test]
+    fn test_attribution_serializes_trust_label() {
+        let teacher = Attribution::teacher("openai", "").with_model("gpt-4o");
+        assert_eq!(
+            serde_json::to_value(&teacher).unwrap(),
+            serde_json::json!({"trust": "teacher", "provider": "openai", "model": "gpt-4o"})
+        );
+
+        let mut local = Attribution::local("Qwen2.5-3B").with_model("");
+        local.adapter = Some("desk v3".to_string());
+        let json = serde_json::to_value(&local).unwrap();
+        assert_eq!(json["trust"], "local");
+        assert_eq!(json["model"], "Qwen2.5-3B");
+        assert_eq!(serde_json::from_value::<Attribution>(json).unwrap(), local);
+    }
+
     // --- GeneratorCapabilities ---
 
     #[test]
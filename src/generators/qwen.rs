@@ -14,6 +14,7 @@ use crate::tools::types::ToolUse as ToolsToolUse;
 use crate::tools::types::{ToolDefinition, ToolResult}; // Import with alias to avoid confusion
 
 use super::{
+    Attribution,
     Generator,
     GeneratorCapabilities,
     GeneratorResponse,
@@ -85,6 +86,10 @@ impl Generator for QwenGenerator {
     fn name(&self) -> &str {
         "Local"
     }
+
+    async fn attribution(&self) -> Attribution {
+        Attribution::local(self.local_generator.read().await.model_name())
+    }
 }
 
 impl QwenGenerator {
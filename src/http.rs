@@ -0,0 +1,378 @@
+// Shared HTTP client setup — proxies and custom CA bundles
+//
+// Corporate networks often route HTTPS through a TLS-intercepting proxy that
+// re-signs traffic with its own root CA.  Every outbound client is built from
+// `builder()`, which applies the proxy and extra root certificates resolved
+// from `[http]` (falling back to the environment and OS settings), so
+// providers, web_fetch, the Lotus Network client, Co-Forth peers and the
+// HuggingFace model downloader (`models::hub`) all behave the same way
+// behind it.
+//
+// `finch doctor --network` prints what was resolved and probes the endpoints
+// finch talks to, naming the likely fix when one fails.
+
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::HttpConfig;
+
+/// Proxy variables, most specific first
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+];
+
+/// Never send loopback traffic (the local daemon, Ollama) through a proxy
+const LOOPBACK_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+/// Where the proxy in use came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxySource {
+    /// `[http] proxy`
+    Config,
+    /// `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY`
+    Environment,
+    /// OS network settings (macOS `scutil --proxy`)
+    System,
+}
+
+/// Proxy and TLS settings resolved from `[http]`, the environment and the OS
+#[derive(Debug, Clone)]
+pub struct HttpSettings {
+    pub proxy: Option<(String, ProxySource)>,
+    /// Hosts that bypass the proxy (always includes loopback)
+    pub no_proxy: String,
+    pub ca_bundle: Option<PathBuf>,
+    /// Root certificates loaded from `ca_bundle`
+    pub certificates: Vec<Certificate>,
+    /// Why `ca_bundle` couldn't be used
+    pub ca_error: Option<String>,
+}
+
+static SETTINGS: OnceLock<HttpSettings> = OnceLock::new();
+
+/// Apply `[http]` to every client built afterwards.  Called once when the
+/// config is loaded; later calls are ignored.
+pub fn init(config: &HttpConfig) {
+    if SETTINGS.get().is_some() {
+        return;
+    }
+    let settings = HttpSettings::resolve(config);
+    if let Some(error) = &settings.ca_error {
+        tracing::warn!("{}", error);
+    }
+    if let Some((proxy, source)) = &settings.proxy {
+        tracing::info!(proxy = %proxy, ?source, "Using HTTP proxy");
+    }
+    let _ = SETTINGS.set(settings);
+}
+
+/// Settings in force for this process (from the environment alone if
+/// `init()` hasn't been called)
+pub fn settings() -> &'static HttpSettings {
+    SETTINGS.get_or_init(|| HttpSettings::resolve(&HttpConfig::default()))
+}
+
+/// A client builder with the proxy and extra root certificates applied
+pub fn builder() -> ClientBuilder {
+    settings().apply(reqwest::Client::builder())
+}
+
+impl HttpSettings {
+    pub fn resolve(config: &HttpConfig) -> Self {
+        Self::resolve_with(config, |name| std::env::var(name).ok(), system_proxy)
+    }
+
+    fn resolve_with(
+        config: &HttpConfig,
+        env: impl Fn(&str) -> Option<String>,
+        system: impl FnOnce() -> Option<String>,
+    ) -> Self {
+        let env = |name: &str| env(name).filter(|v| !v.trim().is_empty());
+
+        let proxy = config
+            .proxy
+            .clone()
+            .map(|p| (p, ProxySource::Config))
+            .or_else(|| {
+                PROXY_ENV_VARS
+                    .iter()
+                    .find_map(|var| env(var))
+                    .map(|p| (p, ProxySource::Environment))
+            })
+            .or_else(|| system().map(|p| (p, ProxySource::System)));
+
+        let no_proxy = match config
+            .no_proxy
+            .clone()
+            .or_else(|| env("NO_PROXY"))
+            .or_else(|| env("no_proxy"))
+        {
+            Some(hosts) => format!("{},{}", hosts.trim_end_matches(','), LOOPBACK_HOSTS),
+            None => LOOPBACK_HOSTS.to_string(),
+        };
+
+        let ca_bundle = config
+            .ca_bundle
+            .clone()
+            .or_else(|| env("SSL_CERT_FILE").map(PathBuf::from));
+        let (certificates, ca_error) = match &ca_bundle {
+            Some(path) => match load_certificates(path) {
+                Ok(certs) => (certs, None),
+                Err(e) => (Vec::new(), Some(e)),
+            },
+            None => (Vec::new(), None),
+        };
+
+        Self {
+            proxy,
+            no_proxy,
+            ca_bundle,
+            certificates,
+            ca_error,
+        }
+    }
+
+    fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        for cert in &self.certificates {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        // reqwest reads the proxy environment variables itself
+        if let Some((url, source)) = &self.proxy {
+            if *source != ProxySource::Environment {
+                match Proxy::all(url.as_str()) {
+                    Ok(proxy) => {
+                        builder =
+                            builder.proxy(proxy.no_proxy(NoProxy::from_string(&self.no_proxy)))
+                    }
+                    Err(e) => tracing::warn!(proxy = %url, "Ignoring invalid proxy URL: {}", e),
+                }
+            }
+        }
+        builder
+    }
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, String> {
+    let pem = std::fs::read(path)
+        .map_err(|e| format!("Can't read CA bundle {}: {}", path.display(), e))?;
+    let certs = Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!(
+            "CA bundle {} contains no PEM certificates",
+            path.display()
+        ));
+    }
+    Ok(certs)
+}
+
+/// The HTTPS proxy from the OS network settings, if one is enabled
+fn system_proxy() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("scutil")
+            .arg("--proxy")
+            .output()
+            .ok()?;
+        parse_scutil_proxy(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        None
+    }
+}
+
+/// Parse the HTTPS proxy out of `scutil --proxy` output
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_scutil_proxy(output: &str) -> Option<String> {
+    let value = |key: &str| {
+        output.lines().find_map(|line| {
+            let (k, v) = line.split_once(':')?;
+            (k.trim() == key).then(|| v.trim().to_string())
+        })
+    };
+    if value("HTTPSEnable")? != "1" {
+        return None;
+    }
+    let host = value("HTTPSProxy")?;
+    match value("HTTPSPort") {
+        Some(port) => Some(format!("http://{}:{}", host, port)),
+        None => Some(format!("http://{}", host)),
+    }
+}
+
+/// Outcome of probing one endpoint
+#[derive(Debug)]
+pub enum Probe {
+    /// Reached the server (any HTTP status counts)
+    Reachable(u16),
+    /// Failed, with an explanation and likely fix
+    Failed(String),
+}
+
+/// GET `url` with the shared client settings and explain any failure
+pub async fn probe(url: &str) -> Probe {
+    let client = match builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => return Probe::Failed(format!("could not build HTTP client: {}", e)),
+    };
+    match client.get(url).send().await {
+        Ok(response) => Probe::Reachable(response.status().as_u16()),
+        Err(e) => Probe::Failed(explain_error(&e, settings().proxy.is_some())),
+    }
+}
+
+fn explain_error(error: &reqwest::Error, proxied: bool) -> String {
+    let mut chain = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    explain(&chain, error.is_timeout(), error.is_connect(), proxied)
+}
+
+fn explain(chain: &str, timeout: bool, connect: bool, proxied: bool) -> String {
+    let lower = chain.to_lowercase();
+    if lower.contains("certificate") || lower.contains("unknownissuer") {
+        format!(
+            "TLS certificate not trusted ({}). A proxy is probably re-signing HTTPS \
+             traffic: set `[http] ca_bundle` to its root CA (PEM).",
+            chain
+        )
+    } else if timeout {
+        format!(
+            "timed out ({}). Check `[http] proxy` / HTTPS_PROXY and firewall rules.",
+            chain
+        )
+    } else if connect && proxied {
+        format!(
+            "could not connect through the proxy ({}). Check `[http] proxy` and `no_proxy`.",
+            chain
+        )
+    } else if connect {
+        format!(
+            "could not connect ({}). If this network needs a proxy, set `[http] proxy` \
+             or HTTPS_PROXY.",
+            chain
+        )
+    } else {
+        chain.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(config: &HttpConfig, env: &[(&str, &str)], system: Option<&str>) -> HttpSettings {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        HttpSettings::resolve_with(
+            config,
+            |name| env.get(name).cloned(),
+            || system.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_proxy_precedence() {
+        let env = [
+            ("HTTP_PROXY", "http://env-http:3128"),
+            ("HTTPS_PROXY", "http://env:3128"),
+        ];
+        let config = HttpConfig {
+            proxy: Some("http://config:8080".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve(&config, &env, Some("http://sys:1")).proxy,
+            Some(("http://config:8080".into(), ProxySource::Config))
+        );
+        assert_eq!(
+            resolve(&HttpConfig::default(), &env, Some("http://sys:1")).proxy,
+            Some(("http://env:3128".into(), ProxySource::Environment))
+        );
+        assert_eq!(
+            resolve(&HttpConfig::default(), &[], Some("http://sys:1")).proxy,
+            Some(("http://sys:1".into(), ProxySource::System))
+        );
+        assert_eq!(resolve(&HttpConfig::default(), &[], None).proxy, None);
+    }
+
+    #[test]
+    fn test_no_proxy_always_includes_loopback() {
+        let settings = resolve(
+            &HttpConfig::default(),
+            &[("NO_PROXY", ".corp.example,")],
+            None,
+        );
+        assert_eq!(settings.no_proxy, ".corp.example,localhost,127.0.0.1,::1");
+        assert_eq!(
+            resolve(&HttpConfig::default(), &[], None).no_proxy,
+            LOOPBACK_HOSTS
+        );
+    }
+
+    #[test]
+    fn test_unusable_ca_bundle_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.pem");
+        let settings = resolve(
+            &HttpConfig::default(),
+            &[("SSL_CERT_FILE", missing.to_str().unwrap())],
+            None,
+        );
+        assert_eq!(settings.ca_bundle.as_deref(), Some(missing.as_path()));
+        assert!(settings.ca_error.unwrap().contains("Can't read CA bundle"));
+
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "not a certificate\n").unwrap();
+        let config = HttpConfig {
+            ca_bundle: Some(empty),
+            ..Default::default()
+        };
+        assert!(resolve(&config, &[], None)
+            .ca_error
+            .unwrap()
+            .contains("no PEM certificates"));
+    }
+
+    #[test]
+    fn test_parse_scutil_proxy() {
+        let enabled = "<dictionary> {\n  HTTPEnable : 0\n  HTTPSEnable : 1\n  \
+                       HTTPSPort : 8080\n  HTTPSProxy : proxy.corp.example\n}\n";
+        assert_eq!(
+            parse_scutil_proxy(enabled).as_deref(),
+            Some("http://proxy.corp.example:8080")
+        );
+        assert_eq!(
+            parse_scutil_proxy("<dictionary> {\n  HTTPSEnable : 0\n}\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_explain_names_the_fix() {
+        let tls = explain(
+            "error sending request: invalid peer certificate: UnknownIssuer",
+            false,
+            true,
+            true,
+        );
+        assert!(tls.contains("ca_bundle"));
+        assert!(explain("error trying to connect", false, true, false).contains("HTTPS_PROXY"));
+        assert!(explain("error trying to connect", false, true, true).contains("through the proxy"));
+    }
+}
@@ -0,0 +1,93 @@
+// Incognito sessions — nothing remembered, logged or trained on
+//
+// Handling credentials or a sensitive incident, a session should leave no
+// trace in finch's own records.  `/incognito` toggles it for the rest of the
+// session, and a session started inside a project listed under
+// `[incognito] projects` starts incognito.  While it is on:
+//
+//   * nothing new is indexed into memory (recall of older memories still
+//     works)
+//   * nothing is written to the conversation log or the prompt history
+//   * no training examples are collected: ratings are refused, and chat
+//     requests ask the daemon (`"incognito": true`) not to keep the exchange
+//
+// The status line shows it for as long as it lasts.
+//
+//   [incognito]
+//   projects = ["~/work/incidents"]
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::expand_home;
+
+/// Status line text while incognito
+pub const STATUS: &str = "🕶 incognito — no memory, logs or training";
+
+/// `[incognito]` in config.toml
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IncognitoConfig {
+    /// Projects whose sessions start incognito (`~/` is expanded); a
+    /// session started in one of them or below starts incognito
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<PathBuf>,
+}
+
+impl IncognitoConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a session started in `dir` starts incognito
+    pub fn covers(&self, dir: &Path) -> bool {
+        self.projects
+            .iter()
+            .map(expand_home)
+            .any(|project| dir.starts_with(project))
+    }
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Apply `[incognito]`: start incognito when finch runs in a listed project.
+/// Called once when the config is loaded.
+pub fn init(config: &IncognitoConfig) {
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    if config.covers(&cwd) {
+        tracing::info!(dir = %cwd.display(), "Project is incognito");
+        set(true);
+    }
+}
+
+/// Whether this session is incognito
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Turn incognito on or off for the rest of the session
+pub fn set(on: bool) {
+    ACTIVE.store(on, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listed_projects_and_their_subdirectories_are_covered() {
+        let config = IncognitoConfig {
+            projects: vec![PathBuf::from("/work/incidents"), PathBuf::from("~/vault")],
+        };
+        assert!(config.covers(Path::new("/work/incidents")));
+        assert!(config.covers(Path::new("/work/incidents/2026-10-db-leak")));
+        assert!(!config.covers(Path::new("/work/incidents-archive")));
+        assert!(!config.covers(Path::new("/work")));
+        if let Some(home) = dirs::home_dir() {
+            assert!(config.covers(&home.join("vault").join("keys")));
+        }
+        assert!(!IncognitoConfig::default().covers(Path::new("/work/incidents")));
+    }
+}
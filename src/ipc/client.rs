@@ -291,6 +291,12 @@ impl stream_receiver::Server for StreamReceiverImpl {
                     .map(StreamChunk::TextDelta)
                     .map_err(|e| anyhow::anyhow!("{}", e))
             }
+            Ok(Which::ThinkingDelta(t)) => {
+                t.and_then(|s| s.to_str().map(|s| s.to_string()).map_err(|e| capnp::Error::failed(e.to_string())))
+                    .map(StreamChunk::ThinkingDelta)
+                    .map_err(|e| anyhow::anyhow!("{}", e))
+            }
+            Ok(Which::ReasoningTokens(n)) => Ok(StreamChunk::ReasoningTokens(n)),
             Ok(Which::ToolUseComplete(tu)) => tu
                 .and_then(|tu| {
                     let id = tu.get_id()?.to_str().map_err(|e| capnp::Error::failed(e.to_string()))?.to_string();
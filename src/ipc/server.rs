@@ -15,7 +15,7 @@ use uuid::Uuid;
 use crate::ipc::schema::finch_ipc_capnp::{
     self, BrainState as CapnpBrainState, finch_daemon,
 };
-use crate::server::{AgentServer, PlanResponse};
+use crate::server::{AgentServer, InFlightGuard, PlanResponse};
 
 // ---------------------------------------------------------------------------
 // Server implementation struct
@@ -32,6 +32,26 @@ impl FinchDaemonImpl {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Helper: admission control while the daemon drains for shutdown
+// ---------------------------------------------------------------------------
+
+/// Admit a unit of work and count it as in flight.  While draining, only
+/// work that finishes a turn already under way (`continues_turn`) is admitted.
+fn admit(server: &AgentServer, continues_turn: bool) -> Result<InFlightGuard, capnp::Error> {
+    server
+        .drain()
+        .admit(continues_turn)
+        .map_err(|e| capnp::Error::overloaded(e.to_string()))?;
+    Ok(server.drain().track())
+}
+
+/// True when the conversation ends with tool results, i.e. the client is
+/// continuing a tool loop rather than starting a new turn.
+fn continues_turn(messages: &[crate::claude::Message]) -> bool {
+    messages.last().is_some_and(|m| m.has_tool_results())
+}
+
 // ---------------------------------------------------------------------------
 // Helper: read a capnp Message list into internal Message vec
 // ---------------------------------------------------------------------------
@@ -150,8 +170,10 @@ impl finch_daemon::Server for FinchDaemonImpl {
         let messages = pry!(read_messages(pry!(p.get_messages())));
         let tools = pry!(read_tools(pry!(p.get_tools())));
         let server = Arc::clone(&self.server);
+        let in_flight = pry!(admit(&server, continues_turn(&messages)));
 
         Promise::from_future(async move {
+            let _in_flight = in_flight;
             let provider = server
                 .primary_provider()
                 .ok_or_else(|| capnp::Error::failed("no provider configured".into()))?;
@@ -192,8 +214,10 @@ impl finch_daemon::Server for FinchDaemonImpl {
         let tools = pry!(read_tools(pry!(p.get_tools())));
         let receiver = pry!(p.get_receiver());
         let server = Arc::clone(&self.server);
+        let in_flight = pry!(admit(&server, continues_turn(&messages)));
 
         Promise::from_future(async move {
+            let _in_flight = in_flight;
             let provider = server
                 .primary_provider()
                 .ok_or_else(|| capnp::Error::failed("no provider configured".into()))?;
@@ -241,6 +265,16 @@ impl finch_daemon::Server for FinchDaemonImpl {
                         upd.set_output_tokens(0);
                         r.send().promise.await?;
                     }
+                    Ok(StreamChunk::ThinkingDelta(delta)) => {
+                        let mut r = receiver.on_chunk_request();
+                        r.get().init_chunk().set_thinking_delta(delta.as_str());
+                        r.send().promise.await?;
+                    }
+                    Ok(StreamChunk::ReasoningTokens(n)) => {
+                        let mut r = receiver.on_chunk_request();
+                        r.get().init_chunk().set_reasoning_tokens(n);
+                        r.send().promise.await?;
+                    }
                     Ok(StreamChunk::ContentBlockComplete(block)) => {
                         if let crate::claude::ContentBlock::ToolUse { id, name, input } = block {
                             let mut r = receiver.on_chunk_request();
@@ -281,6 +315,7 @@ impl finch_daemon::Server for FinchDaemonImpl {
         let task = pry!(p.get_task_description()).to_str().unwrap_or("").to_string();
         let provider_name = pry!(p.get_provider()).to_str().unwrap_or("").to_string();
         let server = Arc::clone(&self.server);
+        let in_flight = pry!(admit(&server, false));
 
         Promise::from_future(async move {
             let id = Uuid::new_v4();
@@ -300,9 +335,23 @@ impl finch_daemon::Server for FinchDaemonImpl {
             let registry_clone = Arc::clone(&registry);
             let task_clone = task.clone();
             let cwd_clone = cwd.clone();
-            tokio::spawn(async move {
-                run_daemon_brain_loop(id, task_clone, registry_clone, provider, cwd_clone).await;
+            let webhooks = Arc::clone(server.webhooks());
+            let schedule = Arc::clone(server.schedule());
+            let handle = tokio::spawn(async move {
+                registry_clone.wait_for_schedule(id, &schedule).await;
+                run_daemon_brain_loop(
+                    id,
+                    task_clone,
+                    registry_clone,
+                    provider,
+                    cwd_clone,
+                    webhooks,
+                    None,
+                )
+                .await;
+                drop(in_flight);
             });
+            registry.set_task_handle(id, handle.abort_handle()).await;
 
             results.get().set_id(id.to_string().as_str());
             Ok(())
@@ -23,11 +23,15 @@ pub mod cli;
 pub mod client; // HTTP client for daemon communication (Phase 8)
 pub mod config;
 pub mod context; // Project context (CLAUDE.md / FINCH.md auto-loading)
+pub mod crypto; // HMAC-SHA256 and Ed25519 signature checks shared across subsystems
 pub mod daemon; // Daemon lifecycle and auto-spawn (Phase 8)
 pub mod errors; // User-friendly error messages
+pub mod explain_diff; // `finch explain-diff`: per-file intent and risk review of a diff
 pub mod feedback; // Response feedback system for LoRA training
 pub mod generators; // Unified generator interface
 pub mod graph; // Execution graph — causal trace of query turns
+pub mod http; // Shared HTTP client setup: proxy and custom CA bundle
+pub mod incognito; // Sessions that leave no memory, logs or training data
 pub mod ipc;        // Cap'n Proto IPC layer (CLI ↔ daemon over Unix socket)
 pub mod license;
 pub mod llms; // Generic LLM abstraction (Phase 1)
@@ -41,13 +45,18 @@ pub mod network; // Lotus Network device registration and membership
 pub mod node; // Node identity and work statistics (distributed worker)
 pub mod planning; // IMPCPD iterative plan refinement loop
 pub mod coforth;  // Co-Forth English library — every word as a Forth word
+pub mod policy;   // Data-residency policy: which content may go to which provider
 pub mod poset;    // Co-Forth poset VM — partially-ordered task graph with 3D renderer
 pub mod providers; // Multi-provider LLM support
 pub mod peer_token; // Peer authentication token for daemon endpoints
+pub mod redact; // Secret redaction for tool results sent to providers and for training logs
 pub mod registry; // Peer registry — machines check in, you query who's alive
 pub mod router;
 pub mod scheduling; // Autonomous task scheduling (Phase 5)
 pub mod server; // HTTP daemon mode (Phase 1)
 pub mod service; // Service discovery (Phase 3)
+pub mod storage; // Local or S3 storage for daemon sessions and metrics
+pub mod summarize; // `finch summarize`: map-reduce summaries of files and URLs
 pub mod tools; // Tool execution system
 pub mod training; // Batch training and checkpoints (Phase 2) // Offline Ed25519 commercial license key validation
+pub mod web; // web_fetch backend: page cache, robots.txt, per-host pacing, HTML to markdown
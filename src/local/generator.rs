@@ -293,26 +293,14 @@ impl TemplateGenerator {
             .try_write()
             .map_err(|_| anyhow::anyhow!("Generator model is locked"))?;
 
-        // Get ONNX model backend
-        use crate::models::loaders::onnx::LoadedOnnxModel;
-        use crate::models::TextGeneration;
-
-        let onnx_model = gen
-            .backend_mut()
-            .as_any_mut()
-            .downcast_mut::<LoadedOnnxModel>()
-            .ok_or_else(|| anyhow::anyhow!("Backend is not an ONNX model"))?;
+        // Any backend streams through the trait (ONNX, Candle, GGUF)
+        let backend = gen.backend_mut();
 
         // Tokenize input
-        let encoding = onnx_model
-            .tokenizer()
-            .encode(formatted_prompt.as_str(), true)
-            .map_err(|e| anyhow::anyhow!("Failed to encode prompt: {}", e))?;
-
-        let input_ids = encoding.get_ids().to_vec();
+        let input_ids = backend.tokenize(&formatted_prompt)?;
 
         // Generate with streaming callback (filter special tokens)
-        let output_ids = onnx_model.generate_stream(
+        let output_ids = backend.generate_stream(
             &input_ids,
             100, // max 100 new tokens
             Box::new(move |token_id, token_text| {
@@ -335,10 +323,7 @@ impl TemplateGenerator {
         )?;
 
         // Decode full output
-        let raw_response = onnx_model
-            .tokenizer()
-            .decode(&output_ids, true)
-            .map_err(|e| anyhow::anyhow!("Failed to decode output: {}", e))?;
+        let raw_response = backend.decode_tokens(&output_ids)?;
 
         // Clean output using model adapter
         let clean_response = self.model_adapter.clean_output(&raw_response);
@@ -398,7 +383,8 @@ impl TemplateGenerator {
         Ok(clean_response)
     }
 
-    /// Learn from a Claude response
+    /// Learn from a Claude response (nothing is learned while the session is
+    /// incognito)
     pub fn learn_from_claude(
         &mut self,
         query: &str,
@@ -406,6 +392,9 @@ impl TemplateGenerator {
         quality_score: f64,
         batch_trainer: Option<&Arc<RwLock<BatchTrainer>>>,
     ) {
+        if crate::incognito::is_active() {
+            return;
+        }
         let (pattern, _) = self.pattern_classifier.classify(query);
 
         let learned = LearnedResponse {
@@ -11,7 +11,7 @@ pub use patterns::{PatternClassifier, QueryPattern};
 
 use crate::claude::Message;
 use crate::generators::GeneratorResponse;
-use crate::models::adapters::LocalModelAdapter;
+use crate::models::adapters::{AdapterRegistry, LocalModelAdapter};
 use crate::models::GeneratorModel;
 use crate::tools::types::ToolDefinition;
 use crate::training::batch_trainer::BatchTrainer;
@@ -38,25 +38,31 @@ impl LocalGenerator {
     pub fn with_models(neural_generator: Option<Arc<RwLock<GeneratorModel>>>) -> Self {
         let pattern_classifier = PatternClassifier::new();
 
-        // Extract actual model name from GeneratorModel if available
-        let model_name = if let Some(ref gen) = neural_generator {
+        // Extract actual model name (and detected family) from GeneratorModel if available
+        let (model_name, family) = if let Some(ref gen) = neural_generator {
             // Try non-blocking read to get model name (avoid deadlock in async context)
             match gen.try_read() {
-                Ok(g) => g.name().to_string(),
+                Ok(g) => (g.name().to_string(), g.model_family()),
                 Err(_) => {
                     // Lock contention - try to get from config
-                    Self::extract_model_name_from_config()
+                    (Self::extract_model_name_from_config(), None)
                 }
             }
         } else {
             // Model not loaded yet - get from config
-            Self::extract_model_name_from_config()
+            (Self::extract_model_name_from_config(), None)
         };
 
+        // A family detected from the model's tokenizer files wins over the
+        // name, which says nothing for custom `model_repo` exports
+        let adapter_name = family
+            .map(|f| AdapterRegistry::from_family(f).family_name().to_string())
+            .unwrap_or_else(|| model_name.clone());
+
         let response_generator = TemplateGenerator::with_models(
             pattern_classifier.clone(),
             neural_generator,
-            &model_name,
+            &adapter_name,
         );
 
         Self {
@@ -158,8 +158,12 @@ impl ConversationLogger {
             .open(&self.log_path)
             .context("Failed to open log file")?;
 
+        // Entries may be trained on, so secrets never reach the file
+        let redactor = crate::redact::active();
         for entry in &self.buffer {
-            let json = serde_json::to_string(entry).context("Failed to serialize log entry")?;
+            let json = redactor
+                .to_json_line(entry)
+                .context("Failed to serialize log entry")?;
             writeln!(file, "{}", json).context("Failed to write log entry")?;
         }
 
@@ -4,7 +4,7 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::io::{self, IsTerminal, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use finch::claude::ClaudeClient;
@@ -58,19 +58,41 @@ enum Command {
     Setup,
     /// Run HTTP daemon server
     Daemon {
-        /// Bind address (default: 127.0.0.1:8000)
+        /// Bind address (default: 127.0.0.1:8000), or `unix:PATH` for a Unix socket
         // constant: crate::config::constants::DEFAULT_HTTP_ADDR
         #[arg(long, default_value = "127.0.0.1:8000")]
         bind: String,
+        /// Seconds to wait on shutdown for in-flight requests and agent turns
+        // constant: crate::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS
+        #[arg(long, value_name = "SECS", default_value_t = 30)]
+        drain_timeout: u64,
+        /// Seconds to reuse a cached response to a repeated request (0 disables the cache)
+        // constant: crate::config::constants::DEFAULT_RESPONSE_CACHE_TTL_SECS
+        #[arg(long, value_name = "SECS", default_value_t = 300)]
+        cache_ttl: u64,
+        /// Also reuse answers to similar questions at or above this cosine similarity (0.0-1.0)
+        #[arg(long, value_name = "THRESHOLD")]
+        cache_similarity: Option<f32>,
+        /// Generations each local model runs at once; every session loads its own model copy
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        parallel_sessions: usize,
+        /// Also serve the gRPC API on this address (e.g. 127.0.0.1:50051)
+        #[arg(long, value_name = "ADDR")]
+        grpc: Option<String>,
     },
     /// Start the daemon in background
     DaemonStart {
-        /// Bind address (default: 127.0.0.1:11435)
+        /// Bind address (default: 127.0.0.1:11435), or `unix:PATH` for a Unix socket
         #[arg(long, default_value = "127.0.0.1:11435")]
         bind: String,
     },
     /// Stop the running daemon
-    DaemonStop,
+    DaemonStop {
+        /// Seconds to wait for the daemon to drain before killing it
+        /// (default: drain timeout + 15)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
     /// Show daemon status
     DaemonStatus,
     /// Training commands
@@ -83,6 +105,43 @@ enum Command {
         /// Query text
         query: String,
     },
+    /// Summarize a file or web page (markdown, or JSON with --json)
+    Summarize {
+        /// File path or http(s) URL
+        target: String,
+        /// Summary length: short or detailed
+        #[arg(long, default_value = "short")]
+        length: finch::summarize::SummaryLength,
+        /// Print JSON instead of markdown
+        #[arg(long)]
+        json: bool,
+    },
+    /// Review a diff: intent, risk and concerns per file, then an overall summary
+    ///
+    /// With no range, reviews a patch piped on stdin, or the uncommitted
+    /// changes (`git diff HEAD`) when nothing is piped.
+    ExplainDiff {
+        /// Git revision range (e.g. main..HEAD), or `-` to read a patch from stdin
+        range: Option<String>,
+        /// Print JSON instead of markdown
+        #[arg(long)]
+        json: bool,
+    },
+    /// Serve only the OpenAI/Anthropic-compatible API in front of [[providers]]
+    ///
+    /// No local model, sessions or tools: requests go down the provider
+    /// fallback chain, with rate-limit handling and cost tracking.
+    Proxy {
+        /// Bind address (default: 127.0.0.1:11436), or `unix:PATH` for a Unix socket
+        // constant: crate::config::constants::DEFAULT_PROXY_ADDR
+        #[arg(long, default_value = "127.0.0.1:11436")]
+        bind: String,
+    },
+    /// Model Context Protocol: serve finch's tools to MCP clients
+    Mcp {
+        #[command(subcommand)]
+        mcp_command: McpCommand,
+    },
     /// Run as a network worker node (accepts queries from other machines)
     ///
     /// Binds to 0.0.0.0 by default so other machines on the network can
@@ -97,7 +156,19 @@ enum Command {
         info: bool,
     },
     /// Show this node's identity and capabilities
-    NodeInfo,
+    NodeInfo {
+        /// Measure tokens/sec of the local model on each inference path
+        /// (MLX and ONNX Runtime with CoreML on a Mac) before showing them
+        #[arg(long)]
+        bench: bool,
+    },
+    /// Terminal dashboards
+    Tui {
+        /// Watch the live activity of the daemon at ADDR (e.g. a worker at
+        /// 192.168.1.20:8000): requests, routing, throughput, training
+        #[arg(long, value_name = "ADDR")]
+        attach: String,
+    },
     /// Lotus Network device registration and account linking
     Network {
         #[command(subcommand)]
@@ -119,7 +190,11 @@ enum Command {
         library_command: LibraryCommand,
     },
     /// Run as an autonomous agent, working through a task backlog
+    #[command(args_conflicts_with_subcommands = true)]
     Agent {
+        #[command(subcommand)]
+        agent_command: Option<AgentCommand>,
+
         /// Persona name (builtin or ~/.finch/personas/<name>.toml) or path to .toml
         #[arg(long, default_value = "autonomous")]
         persona: String,
@@ -135,6 +210,232 @@ enum Command {
         /// Complete one task then exit (for testing)
         #[arg(long)]
         once: bool,
+
+        /// Preview the run: file changes and commits are simulated and
+        /// written to a report instead of the repository
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Don't record the run to ~/.finch/agent_runs
+        #[arg(long)]
+        no_record: bool,
+    },
+    /// Index docs for the project's dependencies (Cargo.toml / package.json)
+    /// so the model can look up real APIs with the doc_lookup tool
+    AskDocs {
+        /// Project directory (default: current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Re-fetch docs even if the same version is already indexed
+        #[arg(long)]
+        refresh: bool,
+
+        /// Maximum docs.rs pages to index per crate
+        #[arg(long, default_value = "20")]
+        max_pages: usize,
+    },
+    /// Benchmark providers and the local model
+    Bench {
+        #[command(subcommand)]
+        bench_command: BenchCommand,
+    },
+    /// Show the provider benchmark leaderboard
+    Stats,
+    /// Move old memory and sessions to cold storage, or search the archive
+    Archive {
+        #[command(subcommand)]
+        archive_command: ArchiveCommand,
+    },
+    /// Export or import conversations and memories as portable JSONL
+    Memory {
+        #[command(subcommand)]
+        memory_command: MemoryCommand,
+    },
+    /// Check the configuration for problems
+    Doctor {
+        /// Also probe provider and model-download endpoints through the
+        /// configured proxy and CA bundle
+        #[arg(long)]
+        network: bool,
+    },
+    /// Show or verify the signed log of tool calls
+    Audit {
+        #[command(subcommand)]
+        audit_command: AuditCommand,
+    },
+    /// Inspect what the daemon sent to providers
+    Debug {
+        #[command(subcommand)]
+        debug_command: DebugCommand,
+    },
+    /// Fire concurrent synthetic sessions at a daemon and report throughput,
+    /// latency percentiles, error rates and memory growth
+    Loadtest {
+        /// Daemon address (`host:port` or `unix:PATH`; default: `[client] daemon_address`)
+        #[arg(long)]
+        target: Option<String>,
+        /// Sessions running at the same time
+        #[arg(long, default_value_t = 8)]
+        sessions: usize,
+        /// Conversations each session runs, one after another
+        #[arg(long, default_value_t = 5)]
+        requests: usize,
+        /// Length of each opening prompt, in characters
+        #[arg(long, value_name = "CHARS", default_value_t = 1000)]
+        prompt_size: usize,
+        /// Tool rounds per conversation; each round is another request
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        tool_depth: usize,
+        /// Cap on generated tokens per request
+        #[arg(long, value_name = "N", default_value_t = 64)]
+        max_tokens: u32,
+        /// Seconds before a request counts as timed out
+        #[arg(long, value_name = "SECS", default_value_t = 300)]
+        timeout: u64,
+        /// Let the daemon route requests to teacher providers (costs API credits)
+        #[arg(long)]
+        allow_teacher: bool,
+        /// Print JSON instead of the text report
+        #[arg(long)]
+        json: bool,
+    },
+    /// Install the newest signed release and restart the daemon
+    Update {
+        /// Only report whether an update is available
+        #[arg(long)]
+        check: bool,
+        /// Release channel instead of `[update] channel`: stable or nightly
+        #[arg(long)]
+        channel: Option<finch::config::UpdateChannel>,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum DebugCommand {
+    /// Show a captured provider request of a daemon session, optionally
+    /// re-sending it to another provider or model for comparison
+    Replay {
+        /// Session ID
+        session: String,
+        /// Which captured request, counting back from the latest (1)
+        #[arg(default_value = "1")]
+        n: usize,
+        /// Re-send the request to this provider
+        #[arg(long)]
+        provider: Option<String>,
+        /// Re-send the request with this model
+        #[arg(long)]
+        model: Option<String>,
+        /// Print the raw JSON instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum AuditCommand {
+    /// Show the most recent tool calls
+    Show {
+        /// Number of entries to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Check every entry's signature and that none were changed, removed or
+    /// reordered
+    Verify {
+        /// Public key to check against (default: this device's key)
+        #[arg(long)]
+        key: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum McpCommand {
+    /// Serve read, grep, glob, bash and memory search as MCP tools over
+    /// stdio, for Claude Desktop and other MCP clients
+    Serve {
+        /// Directory the tools work in (default: current directory)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Don't expose the bash tool
+        #[arg(long)]
+        no_bash: bool,
+        /// Don't expose memory search
+        #[arg(long)]
+        no_memory: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum ArchiveCommand {
+    /// Archive conversations, memories and session files older than N months
+    Run {
+        /// Age cutoff in months (default: `[archive] after_months` from config)
+        #[arg(long)]
+        older_than_months: Option<u32>,
+    },
+    /// Search archived records (slow: scans every archive file)
+    Search {
+        /// Text to search for
+        query: String,
+        /// Maximum number of results
+        #[arg(long, default_value = "10")]
+        limit: usize,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum MemoryCommand {
+    /// Write conversations and memories to a JSONL file (stdout by default)
+    Export {
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Leave out embeddings; import computes them again
+        #[arg(long)]
+        no_embeddings: bool,
+    },
+    /// Merge a JSONL export into this machine's memory
+    Import {
+        /// Export file to read (`-` for stdin)
+        path: PathBuf,
+    },
+    /// Merge near-duplicate memories now and report how many were merged
+    Consolidate {
+        /// Cosine similarity at which memories count as duplicates
+        /// (default: `[memory.consolidate] similarity`)
+        #[arg(long)]
+        similarity: Option<f32>,
+    },
+    /// Show how much is stored and where
+    Stats {
+        /// Also show memories by importance, growth per month, the most
+        /// recalled memories, how often answers cite recalled memories, and
+        /// the database size
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// Embed stored memories again with `[memory] embedding_model`,
+    /// downloading the model first if needed
+    Reembed {
+        /// Re-embed even if the memories were embedded with this model
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum BenchCommand {
+    /// Run the built-in prompt suite against every configured provider and the
+    /// local model, and update the leaderboard shown by `finch stats`
+    Providers {
+        /// Provider that scores the answers (default: first configured provider)
+        #[arg(long)]
+        judge: Option<String>,
+        /// Skip the local model (don't start or contact the daemon)
+        #[arg(long)]
+        no_local: bool,
     },
 }
 
@@ -149,6 +450,37 @@ enum NetworkCommand {
         /// Invite code from your Lotus account settings
         invite_code: String,
     },
+    /// Share LoRA adapters with this account's other devices
+    Adapter {
+        #[command(subcommand)]
+        adapter_command: AdapterCommand,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum AdapterCommand {
+    /// Sign and publish this device's trained adapter to the account
+    Publish {
+        /// Adapter name (default: this device's name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Adapter weights (default: ~/.finch/adapters/latest.safetensors)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// List adapters published to the account and whether they fit this device
+    List,
+    /// Download, verify and install an adapter from another device
+    Pull {
+        /// Adapter name (default: the newest compatible adapter from another device)
+        name: Option<String>,
+        /// Version to pull (default: the newest compatible one)
+        #[arg(long)]
+        version: Option<u32>,
+        /// Install even if the adapter's base model revision differs from this device's
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -223,6 +555,16 @@ enum LibraryCommand {
     },
 }
 
+#[derive(Parser, Debug)]
+enum AgentCommand {
+    /// Re-run a recorded agent run with the model and tools answered from the
+    /// recording, and report where the agent loop now behaves differently
+    Replay {
+        /// Recording to replay (a .jsonl file in ~/.finch/agent_runs)
+        recording: PathBuf,
+    },
+}
+
 #[derive(Parser, Debug)]
 enum LicenseCommand {
     /// Show license status (default when no subcommand is given)
@@ -320,14 +662,30 @@ async fn main() -> Result<()> {
         Some(Command::Setup) => {
             return run_setup().await;
         }
-        Some(Command::Daemon { bind }) => {
-            return run_daemon(bind).await;
+        Some(Command::Daemon {
+            bind,
+            drain_timeout,
+            cache_ttl,
+            cache_similarity,
+            parallel_sessions,
+            grpc,
+        }) => {
+            return run_daemon(
+                bind,
+                drain_timeout,
+                cache_ttl,
+                cache_similarity,
+                parallel_sessions,
+                grpc,
+                None,
+            )
+            .await;
         }
         Some(Command::DaemonStart { bind }) => {
             return run_daemon_start(bind).await;
         }
-        Some(Command::DaemonStop) => {
-            return run_daemon_stop();
+        Some(Command::DaemonStop { timeout }) => {
+            return run_daemon_stop(timeout);
         }
         Some(Command::DaemonStatus) => {
             return run_daemon_status().await;
@@ -338,11 +696,30 @@ async fn main() -> Result<()> {
         Some(Command::Query { query }) => {
             return run_query(&query).await;
         }
+        Some(Command::Summarize {
+            target,
+            length,
+            json,
+        }) => {
+            return run_summarize(&target, length, json).await;
+        }
+        Some(Command::ExplainDiff { range, json }) => {
+            return run_explain_diff(range.as_deref(), json).await;
+        }
+        Some(Command::Proxy { bind }) => {
+            return run_proxy(bind).await;
+        }
+        Some(Command::Mcp { mcp_command }) => {
+            return run_mcp_command(mcp_command).await;
+        }
         Some(Command::Worker { bind, info }) => {
             return run_worker(bind, info).await;
         }
-        Some(Command::NodeInfo) => {
-            return run_node_info().await;
+        Some(Command::NodeInfo { bench }) => {
+            return run_node_info(bench).await;
+        }
+        Some(Command::Tui { attach }) => {
+            return finch::cli::activity_dashboard::run(attach).await;
         }
         Some(Command::Network { network_command }) => {
             return run_network_command(network_command).await;
@@ -357,12 +734,73 @@ async fn main() -> Result<()> {
             return run_library_command(library_command).await;
         }
         Some(Command::Agent {
+            agent_command: Some(AgentCommand::Replay { recording }),
+            ..
+        }) => {
+            return run_agent_replay(&recording).await;
+        }
+        Some(Command::Agent {
+            agent_command: None,
             persona,
             tasks,
             reflect_every,
             once,
+            dry_run,
+            no_record,
+        }) => {
+            return run_agent(persona, tasks, reflect_every, once, dry_run, !no_record).await;
+        }
+        Some(Command::AskDocs {
+            path,
+            refresh,
+            max_pages,
+        }) => {
+            return run_ask_docs(path, refresh, max_pages).await;
+        }
+        Some(Command::Bench { bench_command }) => {
+            return run_bench_command(bench_command).await;
+        }
+        Some(Command::Stats) => {
+            return run_stats();
+        }
+        Some(Command::Archive { archive_command }) => {
+            return run_archive_command(archive_command).await;
+        }
+        Some(Command::Memory { memory_command }) => {
+            return run_memory_command(memory_command).await;
+        }
+        Some(Command::Doctor { network }) => {
+            return run_doctor(network).await;
+        }
+        Some(Command::Audit { audit_command }) => {
+            return run_audit_command(audit_command);
+        }
+        Some(Command::Debug { debug_command }) => {
+            return run_debug_command(debug_command).await;
+        }
+        Some(Command::Loadtest {
+            target,
+            sessions,
+            requests,
+            prompt_size,
+            tool_depth,
+            max_tokens,
+            timeout,
+            allow_teacher,
+            json,
         }) => {
-            return run_agent(persona, tasks, reflect_every, once).await;
+            let options = finch::client::loadtest::LoadTestOptions {
+                sessions,
+                requests,
+                prompt_chars: prompt_size,
+                tool_depth,
+                max_tokens,
+                local_only: !allow_teacher,
+            };
+            return run_loadtest(target, options, timeout, json).await;
+        }
+        Some(Command::Update { check, channel }) => {
+            return run_update(check, channel).await;
         }
         None => {
             // Fall through to REPL mode (check for piped input first)
@@ -400,6 +838,15 @@ async fn main() -> Result<()> {
     set_global_output(output_manager.clone());
     set_global_status(status_bar.clone());
 
+    // Count rate-limit waits down in the status bar
+    if io::stdout().is_terminal() {
+        let status = status_bar.clone();
+        finch::providers::rate_limit::set_wait_status(move |provider, left| match left {
+            Some(left) => status.update_rate_limit(provider, left),
+            None => status.clear_rate_limit(),
+        });
+    }
+
     // Check if debug logging is enabled in config (before init_tracing)
     // This allows the debug_logging feature flag to control log verbosity
     if let Ok(temp_config) = load_config() {
@@ -493,6 +940,15 @@ async fn main() -> Result<()> {
                             enable_summarization: new_config.features.enable_summarization,
                             auto_compact_enabled: new_config.features.auto_compact_enabled,
                             brain_enabled: new_config.features.brain_enabled,
+                            tool_result_max_tokens: new_config.features.tool_result_max_tokens,
+                            tool_result_max_tokens_by_tool: new_config
+                                .features
+                                .tool_result_max_tokens_by_tool
+                                .clone(),
+                            rename_check: new_config.features.rename_check,
+                            max_parallel_tools: new_config.features.max_parallel_tools,
+                            tool_timeouts: new_config.features.tool_timeouts.clone(),
+                            prompt_lint: new_config.features.prompt_lint,
                         };
                         if daemon_only_mode {
                             new_config.server.mode = "daemon-only".to_string();
@@ -762,7 +1218,7 @@ async fn run_daemon_start(bind_address: String) -> Result<()> {
 }
 
 /// Stop the running daemon
-fn run_daemon_stop() -> Result<()> {
+fn run_daemon_stop(timeout_secs: Option<u64>) -> Result<()> {
     use finch::daemon::DaemonLifecycle;
 
     let lifecycle = DaemonLifecycle::new()?;
@@ -777,8 +1233,11 @@ fn run_daemon_stop() -> Result<()> {
     let pid = lifecycle.read_pid()?;
     println!("Stopping daemon (PID: {})...", pid);
 
-    // Stop daemon
-    lifecycle.stop_daemon()?;
+    // Stop daemon (it drains in-flight work first, so this can take a while)
+    match timeout_secs {
+        Some(secs) => lifecycle.stop_daemon_with_grace(std::time::Duration::from_secs(secs))?,
+        None => lifecycle.stop_daemon()?,
+    }
 
     println!("✓ Daemon stopped successfully");
     Ok(())
@@ -819,12 +1278,47 @@ async fn run_daemon_status() -> Result<()> {
         anyhow::bail!("Daemon returned error status: {}", response.status());
     }
 
-    // Parse JSON response
+    // Parse JSON response (daemons older than this CLI leave out the
+    // readiness details)
     #[derive(serde::Deserialize)]
     struct HealthStatus {
         status: String,
         uptime_seconds: u64,
         active_sessions: usize,
+        #[serde(default)]
+        models: Vec<ModelHealth>,
+        #[serde(default)]
+        providers: Vec<ProviderHealth>,
+        memory: Option<MemoryHealth>,
+        training_queue_depth: Option<usize>,
+    }
+    #[derive(serde::Deserialize)]
+    struct ModelHealth {
+        id: String,
+        state: String,
+        error: Option<String>,
+        #[serde(default)]
+        files_remaining: usize,
+        download_percent: Option<f32>,
+        current_file: Option<String>,
+        #[serde(default)]
+        tokenizer_loaded: bool,
+        #[serde(default)]
+        warmup_complete: bool,
+    }
+    #[derive(serde::Deserialize)]
+    struct ProviderHealth {
+        name: String,
+        status: String,
+        consecutive_failures: u32,
+        retry_after_secs: Option<u64>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MemoryHealth {
+        total_mb: u64,
+        available_mb: u64,
+        process_mb: u64,
+        pressure: String,
     }
 
     let health: HealthStatus = response
@@ -843,6 +1337,67 @@ async fn run_daemon_status() -> Result<()> {
         "  Bind Address:    {}",
         finch::config::constants::DEFAULT_DAEMON_ADDR
     );
+    if let Some(memory) = &health.memory {
+        println!(
+            "  Memory:          {} MB free of {} MB ({}), daemon {} MB",
+            memory.available_mb, memory.total_mb, memory.pressure, memory.process_mb
+        );
+    }
+    if let Some(depth) = health.training_queue_depth {
+        println!("  Training Queue:  {} examples", depth);
+    }
+
+    if !health.models.is_empty() {
+        println!();
+        println!("  Local models:");
+    }
+    for model in &health.models {
+        let detail = match model.state.as_str() {
+            "downloading" => {
+                let mut detail = format!(
+                    "{:.0}%, {} files left",
+                    model.download_percent.unwrap_or(0.0),
+                    model.files_remaining
+                );
+                if let Some(file) = &model.current_file {
+                    detail.push_str(&format!(", fetching {}", file));
+                }
+                detail
+            }
+            "loading" if model.tokenizer_loaded => "tokenizer loaded".to_string(),
+            "ready" if model.warmup_complete => "warmed up".to_string(),
+            "ready" => "not warmed up".to_string(),
+            "failed" => model.error.clone().unwrap_or_default(),
+            _ => String::new(),
+        };
+        if detail.is_empty() {
+            println!("    {:<20} {}", model.id, model.state);
+        } else {
+            println!("    {:<20} {} ({})", model.id, model.state, detail);
+        }
+    }
+
+    if !health.providers.is_empty() {
+        println!();
+        println!("  Providers:");
+    }
+    for provider in &health.providers {
+        match (provider.consecutive_failures, provider.retry_after_secs) {
+            (0, Some(secs)) => println!(
+                "    {:<20} {} (resets in {}s)",
+                provider.name, provider.status, secs
+            ),
+            (0, None) => println!("    {:<20} {}", provider.name, provider.status),
+            (failures, Some(secs)) => println!(
+                "    {:<20} {} ({} failures, retrying in {}s)",
+                provider.name, provider.status, failures, secs
+            ),
+            (failures, None) => println!(
+                "    {:<20} {} ({} failures)",
+                provider.name, provider.status, failures
+            ),
+        }
+    }
     println!();
 
     Ok(())
@@ -964,7 +1519,63 @@ async fn run_train_setup() -> Result<()> {
     Ok(())
 }
 
-async fn run_daemon(bind_address: String) -> Result<()> {
+/// Advertise this node over mDNS (`_finch._tcp.local.`) at the port of
+/// `address`.  `None` if advertising failed; the daemon carries on without.
+fn advertise_service(config: &Config, address: &str) -> Option<finch::service::ServiceDiscovery> {
+    use finch::service::{ServiceConfig, ServiceDiscovery};
+
+    let service_config = ServiceConfig {
+        name: config.server.service_name.clone(),
+        description: config.server.service_description.clone(),
+        model: format!("{:?}", config.backend.model_size), // e.g., "Small", "Medium", "Large"
+        capabilities: vec![
+            "code".to_string(),
+            "general".to_string(),
+            "tool-use".to_string(),
+        ],
+    };
+
+    match ServiceDiscovery::new(service_config) {
+        Ok(discovery) => {
+            let port = address
+                .split(':')
+                .next_back()
+                .and_then(|p| p.parse::<u16>().ok())
+                .unwrap_or(finch::config::constants::DEFAULT_DAEMON_PORT);
+
+            match discovery.advertise(port) {
+                Ok(_) => {
+                    tracing::info!("✓ mDNS advertisement enabled");
+                    Some(discovery)
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to advertise service: {}. Continuing without mDNS.",
+                        e
+                    );
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to create service discovery: {}. Continuing without mDNS.",
+                e
+            );
+            None
+        }
+    }
+}
+
+async fn run_daemon(
+    bind_address: String,
+    drain_timeout_secs: u64,
+    cache_ttl_secs: u64,
+    cache_similarity: Option<f32>,
+    parallel_sessions: usize,
+    grpc_address: Option<String>,
+    worker_address: Option<String>,
+) -> Result<()> {
     use finch::daemon::DaemonLifecycle;
     use finch::local::LocalGenerator;
     use finch::models::{BootstrapLoader, GeneratorState, TrainingCoordinator};
@@ -1023,6 +1634,10 @@ async fn run_daemon(bind_address: String) -> Result<()> {
 
     tracing::info!("Starting Shammah in daemon mode");
 
+    // Resolved now, so a restart after `finch update` swaps the file runs the
+    // new binary rather than the unlinked old one
+    let exe = finch::daemon::update::installed_exe();
+
     // Initialize daemon lifecycle (PID file management)
     let lifecycle = DaemonLifecycle::new()?;
 
@@ -1040,6 +1655,13 @@ async fn run_daemon(bind_address: String) -> Result<()> {
     let mut config = load_config()?;
     config.server.enabled = true;
     config.server.bind_address = bind_address.clone();
+    config.server.drain_timeout_secs = drain_timeout_secs;
+    config.server.response_cache_ttl_secs = cache_ttl_secs;
+    config.server.response_cache_similarity = cache_similarity;
+    config.server.parallel_sessions = parallel_sessions.max(1);
+    if grpc_address.is_some() {
+        config.server.grpc_address = grpc_address;
+    }
 
     // Load or create threshold router
     let models_dir = dirs::home_dir()
@@ -1157,6 +1779,31 @@ async fn run_daemon(bind_address: String) -> Result<()> {
         session_timeout_minutes: config.server.session_timeout_minutes,
         auth_enabled: config.server.auth_enabled,
         api_keys: config.server.api_keys.clone(),
+        max_queue_depth: config.server.max_queue_depth,
+        parallel_sessions: config.server.parallel_sessions,
+        sessions_dir: config.server.sessions_dir.clone(),
+        drain_timeout_secs: config.server.drain_timeout_secs,
+        access_log: config.server.access_log.clone(),
+        response_cache_ttl_secs: config.server.response_cache_ttl_secs,
+        response_cache_similarity: config.server.response_cache_similarity,
+        webhooks: config.server.webhooks.clone(),
+        cors: config.server.cors.clone(),
+        web_ui: config.server.web_ui,
+        grpc_address: config.server.grpc_address.clone(),
+        speculative_local: config.server.speculative_local,
+        workspaces: config.server.workspaces.clone(),
+        workspaces_dir: config.server.workspaces_dir.clone(),
+        memory_dir: config
+            .server
+            .memory
+            .then(|| config.server.memory_dir.clone())
+            .flatten(),
+        local_token: config
+            .server
+            .local_token
+            .then(finch::daemon::token::default_path)
+            .flatten(),
+        worker_address: worker_address.clone(),
     };
 
     // Build the multi-provider pool from [[providers]] config (cloud providers only).
@@ -1167,7 +1814,7 @@ async fn run_daemon(bind_address: String) -> Result<()> {
     };
 
     // Create and start agent server (with LocalGenerator support)
-    let server = AgentServer::new(
+    let mut server = AgentServer::new(
         config.clone(),
         server_config.clone(),
         claude_client,
@@ -1180,56 +1827,62 @@ async fn run_daemon(bind_address: String) -> Result<()> {
         providers,
     )?;
 
-    // Set up mDNS service advertisement if enabled
-    let service_discovery = if config.server.advertise {
-        use finch::service::{ServiceConfig, ServiceDiscovery};
-
-        let service_config = ServiceConfig {
-            name: config.server.service_name.clone(),
-            description: config.server.service_description.clone(),
-            model: format!("{:?}", config.backend.model_size), // e.g., "Small", "Medium", "Large"
-            capabilities: vec![
-                "code".to_string(),
-                "general".to_string(),
-                "tool-use".to_string(),
-            ],
-        };
+    // Serve any further `type = "local"` providers as additional models,
+    // selectable through the OpenAI `model` field (see GET /v1/models).
+    if config.backend.enabled {
+        for entry in config.local_providers().into_iter().skip(1) {
+            let (Some(backend), Some(id)) = (entry.to_backend_config(), entry.local_model_id())
+            else {
+                continue;
+            };
+            if !backend.enabled {
+                continue;
+            }
 
-        match ServiceDiscovery::new(service_config) {
-            Ok(discovery) => {
-                // Extract port from bind_address
-                let port = config
-                    .server
-                    .bind_address
-                    .split(':')
-                    .next_back()
-                    .and_then(|p| p.parse::<u16>().ok())
-                    .unwrap_or(finch::config::constants::DEFAULT_DAEMON_PORT);
-
-                match discovery.advertise(port) {
-                    Ok(_) => {
-                        tracing::info!("✓ mDNS advertisement enabled");
-                        Some(discovery)
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to advertise service: {}. Continuing without mDNS.",
-                            e
-                        );
-                        None
-                    }
+            let slot =
+                finch::server::LocalModelSlot::new(id.clone(), server_config.max_queue_depth)
+                    .with_parallel_sessions(server_config.parallel_sessions);
+            let loader = Arc::clone(&slot.bootstrap_loader);
+            let state = Arc::clone(&slot.generator_state);
+            tokio::spawn(async move {
+                if let Err(e) = loader
+                    .load_generator_async(
+                        backend.inference_provider,
+                        backend.model_family,
+                        backend.model_size,
+                        backend.execution_target,
+                        backend.model_repo,
+                    )
+                    .await
+                {
+                    tracing::warn!(model = %id, error = %e, "Additional local model failed to load");
+                    *state.write().await = GeneratorState::Failed {
+                        error: format!("{}", e),
+                    };
                 }
-            }
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to create service discovery: {}. Continuing without mDNS.",
-                    e
-                );
-                None
+            });
+
+            if let Err(e) = server.add_local_model(slot) {
+                tracing::warn!(error = %e, "Skipping local model");
             }
         }
-    } else {
-        None
+    }
+
+    // Set up mDNS service advertisement if enabled (TCP only — a Unix
+    // socket is not reachable from other machines).  With the worker role,
+    // peers are pointed at the worker address.
+    let unix_socket = match finch::daemon::DaemonEndpoint::parse(&config.server.bind_address)? {
+        finch::daemon::DaemonEndpoint::Unix(path) => Some(path),
+        finch::daemon::DaemonEndpoint::Tcp(_) => None,
+    };
+    let advertised = match &worker_address {
+        Some(address) => Some(address.as_str()),
+        None if unix_socket.is_none() => Some(config.server.bind_address.as_str()),
+        None => None,
+    };
+    let service_discovery = match advertised {
+        Some(address) if config.server.advertise => advertise_service(&config, address),
+        _ => None,
     };
 
     // Set up graceful shutdown handling
@@ -1254,11 +1907,50 @@ async fn run_daemon(bind_address: String) -> Result<()> {
         }
     });
 
+    // Look for new releases in the background ([update] in config.toml)
+    let _update_check = finch::daemon::update::spawn_background_check(config.update.clone());
+
+    // `finch update` sends SIGHUP once a new binary is installed: shut down
+    // like SIGTERM, then re-execute with the same arguments
+    let hangup = async {
+        #[cfg(unix)]
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(mut sighup) => {
+                sighup.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+        #[cfg(not(unix))]
+        std::future::pending::<()>().await;
+    };
+    let mut restart = false;
+
+    // `finch daemon-stop` sends SIGTERM; without a handler the process would
+    // die before sessions are persisted.
+    let terminate = async {
+        #[cfg(unix)]
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+        #[cfg(not(unix))]
+        std::future::pending::<()>().await;
+    };
+
     // Wait for shutdown signal (Ctrl+C or SIGTERM)
     tokio::select! {
         _ = tokio::signal::ctrl_c() => {
             tracing::info!("Received SIGINT, shutting down gracefully");
         }
+        _ = terminate => {
+            tracing::info!("Received SIGTERM, shutting down gracefully");
+        }
+        _ = hangup => {
+            tracing::info!("Received SIGHUP, restarting");
+            restart = true;
+        }
         result = server_handle => {
             match result {
                 Ok(Ok(())) => {
@@ -1281,6 +1973,14 @@ async fn run_daemon(bind_address: String) -> Result<()> {
         }
     }
 
+    // Stop accepting new work, let in-flight turns finish, then flush the
+    // training queue and save live sessions so clients can resume them
+    server
+        .drain_and_flush(std::time::Duration::from_secs(
+            server.config().drain_timeout_secs,
+        ))
+        .await;
+
     // Stop mDNS advertisement if enabled
     if let Some(discovery) = service_discovery {
         if let Err(e) = discovery.stop() {
@@ -1288,33 +1988,57 @@ async fn run_daemon(bind_address: String) -> Result<()> {
         }
     }
 
+    if let Some(path) = unix_socket {
+        let _ = std::fs::remove_file(path);
+    }
+
     // Cleanup PID file on exit
     lifecycle.cleanup()?;
     tracing::info!("Daemon shutdown complete");
 
+    if restart {
+        // Sessions were persisted above; the new process restores them
+        finch::daemon::update::reexec(&exe?)?;
+    }
+
     Ok(())
 }
 
 /// Build the standard tool registry + executor used for non-interactive query mode.
 /// Auto-approves all tools (no interactive prompting in non-interactive mode).
-async fn build_query_tool_executor() -> Result<(
+async fn build_query_tool_executor(
+    features: &finch::config::FeaturesConfig,
+) -> Result<(
     Arc<tokio::sync::Mutex<finch::tools::ToolExecutor>>,
     Vec<finch::tools::types::ToolDefinition>,
 )> {
     use finch::tools::implementations::{
-        BashTool, EditTool, GlobTool, GrepTool, PatchTool, ReadTool, WebFetchTool, WriteTool,
+        BashTool, BrowserTool, EditTool, GlobTool, GrepTool, HttpTool, OutlineTool, PatchTool,
+        ReadTool, RunCodeTool, WebFetchTool, WebSearchTool, WriteTool,
+    };
+    use finch::tools::{
+        PermissionManager, PermissionRule, RenameCheck, ToolExecutor, ToolRegistry,
+        ToolResultBudget,
     };
-    use finch::tools::{PermissionManager, PermissionRule, ToolExecutor, ToolRegistry};
 
     let mut registry = ToolRegistry::new();
     registry.register(Box::new(ReadTool));
     registry.register(Box::new(GlobTool));
     registry.register(Box::new(GrepTool));
+    registry.register(Box::new(OutlineTool));
     registry.register(Box::new(WebFetchTool::new()));
-    registry.register(Box::new(BashTool));
+    registry.register(Box::new(WebSearchTool::new()));
+    registry.register(Box::new(BashTool::new()));
+    registry.register(Box::new(RunCodeTool::new()));
     registry.register(Box::new(EditTool));
     registry.register(Box::new(PatchTool));
     registry.register(Box::new(WriteTool));
+    if finch::tools::implementations::browser::enabled() {
+        registry.register(Box::new(BrowserTool::new()));
+    }
+    if finch::tools::implementations::http::enabled() {
+        registry.register(Box::new(HttpTool::new()));
+    }
 
     // Auto-approve everything in non-interactive mode
     let permissions = PermissionManager::new().with_default_rule(PermissionRule::Allow);
@@ -1323,7 +2047,12 @@ async fn build_query_tool_executor() -> Result<(
         .unwrap_or_else(|| PathBuf::from(".finch/tool_patterns.json"));
 
     let executor = ToolExecutor::new(registry, permissions, patterns_path)
-        .context("Failed to create tool executor")?;
+        .context("Failed to create tool executor")?
+        .with_result_budget(ToolResultBudget::from_features(features))
+        .with_rename_check(RenameCheck::new(features.rename_check))
+        .with_max_parallel_tools(features.max_parallel_tools)
+        .with_tool_timeouts(&features.tool_timeouts)
+        .with_plugins();
     let executor = Arc::new(tokio::sync::Mutex::new(executor));
 
     let tool_definitions = executor.lock().await.list_all_tools().await;
@@ -1333,6 +2062,7 @@ async fn build_query_tool_executor() -> Result<(
 
 /// Run a single query with full tool support (agentic mode)
 async fn run_query(query: &str) -> Result<()> {
+    use finch::cli::provider_recovery::{self, Recovery, RecoveryMenu};
     use finch::client::DaemonClient;
     use finch::daemon::ensure_daemon_running;
 
@@ -1340,92 +2070,191 @@ async fn run_query(query: &str) -> Result<()> {
     let config = load_config()?;
 
     // Build tool executor (same tools as the REPL)
-    let (executor, tool_definitions) = build_query_tool_executor().await?;
+    let (executor, tool_definitions) = build_query_tool_executor(&config.features).await?;
 
     // Ensure daemon is running (auto-spawn if needed)
-    if let Err(e) = ensure_daemon_running(Some(&config.client.daemon_address)).await {
-        eprintln!("⚠️  Daemon failed to start: {}", e);
-        eprintln!("   Using teacher API directly (no local model)");
-        return run_query_teacher_only(query, &config, executor, tool_definitions).await;
-    }
-
-    // Create daemon client and run full tool loop
-    let daemon_config = finch::client::DaemonConfig::from_client_config(&config.client);
-    let client = DaemonClient::connect(daemon_config).await?;
-
-    let guard = executor.lock().await;
-    let response = client
-        .query_with_tools(query, tool_definitions, &guard)
-        .await?;
-    println!("{}", response);
-
-    Ok(())
-}
-
-/// Run query using teacher API only (fallback when daemon fails), with tool support
-async fn run_query_teacher_only(
-    query: &str,
-    config: &Config,
-    executor: Arc<tokio::sync::Mutex<finch::tools::ToolExecutor>>,
-    tool_definitions: Vec<finch::tools::types::ToolDefinition>,
-) -> Result<()> {
-    use finch::claude::{ContentBlock, Message, MessageRequest};
-
-    eprintln!("⚠️  Running in teacher-only mode (no local model)");
-
-    let claude_client = create_claude_client_with_provider(config)?;
-    let model = config
-        .active_teacher()
-        .and_then(|t| t.model.clone())
-        .unwrap_or_else(|| finch::config::constants::DEFAULT_CLAUDE_MODEL.to_string());
-
-    let mut messages = vec![Message::user(query)];
-
-    const MAX_TURNS: usize = 25;
-    for _ in 0..MAX_TURNS {
-        let request = MessageRequest {
-            model: model.clone(),
-            max_tokens: finch::config::constants::DEFAULT_MAX_TOKENS,
-            messages: messages.clone(),
-            system: Some(finch::generators::claude::CODING_SYSTEM_PROMPT.to_string()),
-            tools: Some(tool_definitions.clone()),
-        };
-
-        let response = claude_client.send_message(&request).await?;
-
-        // If no tool use, print the final text and stop
-        if !response.has_tool_uses() {
-            println!("{}", response.text());
-            return Ok(());
+    let daemon = match ensure_daemon_running(Some(&config.client.daemon_address)).await {
+        Ok(()) => {
+            let daemon_config = finch::client::DaemonConfig::from_client_config(&config.client);
+            Some(DaemonClient::connect(daemon_config).await?)
+        }
+        Err(e) => {
+            eprintln!("⚠️  Daemon failed to start: {}", e);
+            eprintln!("   Using teacher API directly (no local model)");
+            None
+        }
+    };
+    let mut backend = match &daemon {
+        Some(client) => QueryBackend::Daemon {
+            client,
+            local_only: false,
+        },
+        None => {
+            eprintln!("⚠️  Running in teacher-only mode (no local model)");
+            QueryBackend::Teacher {
+                client: create_claude_client_with_provider(&config)?,
+                model: config
+                    .active_teacher()
+                    .and_then(|t| t.model.clone())
+                    .unwrap_or_else(|| finch::config::constants::DEFAULT_CLAUDE_MODEL.to_string()),
+            }
         }
+    };
 
-        // Execute tool calls and collect results
+    // Failures are offered a way on only when someone is there to choose
+    let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+    let mut messages = vec![finch::claude::Message::user(query)];
+    loop {
+        let answer = match &backend {
+            QueryBackend::Daemon { client, local_only } => {
+                let guard = executor.lock().await;
+                client
+                    .continue_with_tools(&mut messages, &tool_definitions, &guard, *local_only)
+                    .await
+                    .map(Some)
+            }
+            QueryBackend::Teacher { client, model } => {
+                teacher_tool_loop(&mut messages, client, model, &executor, &tool_definitions).await
+            }
+        };
+        let error = match answer {
+            Ok(Some(response)) => {
+                println!("{}", response);
+                return Ok(());
+            }
+            Ok(None) => {
+                eprintln!("⚠️  Reached max tool turns without a final answer");
+                return Ok(());
+            }
+            Err(e) if interactive => e,
+            Err(e) => return Err(e),
+        };
+
+        let failed = backend.name();
+        let error_text = format!("{:#}", error);
+        let local_available = daemon.is_some()
+            && !matches!(
+                backend,
+                QueryBackend::Daemon {
+                    local_only: true,
+                    ..
+                }
+            );
+        let menu = RecoveryMenu::new(
+            &failed,
+            &error_text,
+            provider_recovery::alternatives(&config.providers, &failed),
+            local_available,
+        );
+        loop {
+            match menu.action(&provider_recovery::prompt_on_terminal(&menu.dialog)?) {
+                Some(Recovery::Retry) => break,
+                Some(Recovery::Switch(entry)) => {
+                    let provider = finch::providers::create_provider_from_entry(entry)?;
+                    let model = entry
+                        .model()
+                        .unwrap_or(provider.default_model())
+                        .to_string();
+                    backend = QueryBackend::Teacher {
+                        client: ClaudeClient::with_provider(provider),
+                        model,
+                    };
+                    break;
+                }
+                Some(Recovery::Local) => {
+                    if let Some(client) = &daemon {
+                        backend = QueryBackend::Daemon {
+                            client,
+                            local_only: true,
+                        };
+                    }
+                    break;
+                }
+                Some(Recovery::CopyRequest) => {
+                    let report = provider_recovery::debug_report(
+                        &failed,
+                        &error_text,
+                        &messages,
+                        &tool_definitions,
+                    );
+                    eprintln!("{}", provider_recovery::copy_report(&report)?);
+                }
+                None => return Err(error),
+            }
+        }
+    }
+}
+
+/// Who answers `finch query`
+enum QueryBackend<'a> {
+    /// The daemon, routing as usual or keeping to the local model
+    Daemon {
+        client: &'a finch::client::DaemonClient,
+        local_only: bool,
+    },
+    /// A teacher called directly (the daemon didn't start, or the user
+    /// switched provider after a failure)
+    Teacher { client: ClaudeClient, model: String },
+}
+
+impl QueryBackend<'_> {
+    /// What a failure is attributed to in the recovery dialog
+    fn name(&self) -> String {
+        match self {
+            Self::Daemon {
+                local_only: true, ..
+            } => "The local model".to_string(),
+            Self::Daemon { .. } => "The daemon".to_string(),
+            Self::Teacher { client, .. } => client.provider_name().to_string(),
+        }
+    }
+}
+
+/// The teacher tool loop, carrying on from `messages` (each turn is appended
+/// as it completes, so a failed call can be resumed).  `None` when it ran out
+/// of turns without a final answer.
+async fn teacher_tool_loop(
+    messages: &mut Vec<finch::claude::Message>,
+    claude_client: &ClaudeClient,
+    model: &str,
+    executor: &Arc<tokio::sync::Mutex<finch::tools::ToolExecutor>>,
+    tool_definitions: &[finch::tools::types::ToolDefinition],
+) -> Result<Option<String>> {
+    use finch::claude::{ContentBlock, Message, MessageRequest};
+
+    const MAX_TURNS: usize = 25;
+    for _ in 0..MAX_TURNS {
+        let request = MessageRequest {
+            model: model.to_string(),
+            max_tokens: finch::config::constants::DEFAULT_MAX_TOKENS,
+            messages: messages.clone(),
+            system: Some(finch::generators::claude::CODING_SYSTEM_PROMPT.to_string()),
+            tools: Some(tool_definitions.to_vec()),
+        };
+
+        let response = claude_client.send_message(&request).await?;
+
+        // If no tool use, the text is the final answer
+        if !response.has_tool_uses() {
+            return Ok(Some(response.text()));
+        }
+
+        // Execute tool calls and collect results
         messages.push(response.to_message());
 
         let tool_uses = response.tool_uses();
+        // Independent calls run side by side; the lock is only held to take
+        // the runner
+        let runner = executor.lock().await.runner();
+        let exec_results = runner
+            .execute_batch(
+                &tool_uses,
+                std::time::Duration::from_secs(finch::config::constants::BATCH_TOOL_TIMEOUT_SECS),
+            )
+            .await;
+
         let mut result_blocks = Vec::new();
-        for tu in &tool_uses {
-            let tool_use = finch::tools::types::ToolUse {
-                id: tu.id.clone(),
-                name: tu.name.clone(),
-                input: tu.input.clone(),
-            };
-            let exec_result = {
-                let guard = executor.lock().await;
-                guard
-                    .execute_tool::<fn() -> anyhow::Result<()>>(
-                        &tool_use, None, // conversation
-                        None, // save_models_fn
-                        None, // batch_trainer
-                        None, // local_generator
-                        None, // tokenizer
-                        None, // repl_mode
-                        None, // plan_content
-                        None, // live_output
-                        None, // stack
-                    )
-                    .await
-            };
+        for (tu, exec_result) in tool_uses.iter().zip(exec_results) {
             let (content, is_error) = match exec_result {
                 Ok(result) => (result.content, result.is_error),
                 Err(e) => (format!("Error: {e}"), true),
@@ -1440,10 +2269,226 @@ async fn run_query_teacher_only(
         messages.push(Message::with_content("user", result_blocks));
     }
 
-    eprintln!("⚠️  Reached max tool turns without a final answer");
+    Ok(None)
+}
+
+/// Summarize a file or URL through the daemon, or the teacher when the
+/// daemon can't start
+async fn run_summarize(
+    target: &str,
+    length: finch::summarize::SummaryLength,
+    json: bool,
+) -> Result<()> {
+    use finch::summarize::{summarize, Source};
+
+    let config = load_config()?;
+    let source = Source::load(target).await?;
+    let backend = connect_summary_backend(&config).await?;
+
+    let summary = summarize(backend.as_ref(), &source, length, |step| {
+        eprintln!("{}", step)
+    })
+    .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        print!("{}", summary.to_markdown());
+    }
     Ok(())
 }
 
+/// Review a revision range, a piped patch or the uncommitted changes
+async fn run_explain_diff(range: Option<&str>, json: bool) -> Result<()> {
+    use finch::explain_diff::{explain_diff, Patch};
+
+    let patch = match range {
+        Some("-") => Patch::from_reader(io::stdin().lock())?,
+        Some(range) => Patch::from_git(range)?,
+        None if !io::stdin().is_terminal() => Patch::from_reader(io::stdin().lock())?,
+        None => Patch::from_git("HEAD")?,
+    };
+    let config = load_config()?;
+    let backend = connect_summary_backend(&config).await?;
+
+    let review = explain_diff(backend.as_ref(), &patch, |step| eprintln!("{}", step)).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&review)?);
+    } else {
+        print!("{}", review.to_markdown());
+    }
+    Ok(())
+}
+
+/// The daemon (local model plus router), or the teacher directly when the
+/// daemon won't start
+async fn connect_summary_backend(
+    config: &Config,
+) -> Result<Box<dyn finch::summarize::SummaryBackend>> {
+    use finch::client::DaemonClient;
+    use finch::daemon::ensure_daemon_running;
+
+    match ensure_daemon_running(Some(&config.client.daemon_address)).await {
+        Ok(()) => {
+            let daemon_config = finch::client::DaemonConfig::from_client_config(&config.client);
+            Ok(Box::new(DaemonClient::connect(daemon_config).await?))
+        }
+        Err(e) => {
+            eprintln!("⚠️  Daemon failed to start: {}", e);
+            eprintln!("   Using teacher API directly (no local model)");
+            Ok(Box::new(TeacherSummaryBackend::new(config)?))
+        }
+    }
+}
+
+/// Run `finch mcp` subcommands
+async fn run_mcp_command(cmd: McpCommand) -> Result<()> {
+    use finch::memory::{MemoryConfig, MemorySystem};
+    use finch::tools::mcp::{default_registry, McpServer};
+    use std::sync::Arc;
+
+    match cmd {
+        McpCommand::Serve {
+            dir,
+            no_bash,
+            no_memory,
+        } => {
+            // stdout carries the protocol: logs go to stderr
+            let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(env_filter)
+                .with_writer(std::io::stderr)
+                .try_init();
+
+            if let Some(dir) = dir {
+                std::env::set_current_dir(&dir)
+                    .with_context(|| format!("Cannot change to {}", dir.display()))?;
+            }
+
+            // Memory search needs no provider, so a missing config only
+            // means the default memory location
+            let memory_config = load_config()
+                .map(|config| config.memory)
+                .unwrap_or_else(|_| MemoryConfig::default());
+            let memory = if no_memory || !memory_config.enabled {
+                None
+            } else {
+                match MemorySystem::new(memory_config) {
+                    Ok(memory) => Some(Arc::new(memory)),
+                    Err(e) => {
+                        tracing::warn!("[mcp serve] memory unavailable: {:#}", e);
+                        None
+                    }
+                }
+            };
+
+            let server = McpServer::new(default_registry(memory, !no_bash));
+            tracing::info!(
+                "[mcp serve] serving {} on stdio",
+                server.tool_names().join(", ")
+            );
+            server.serve_stdio().await
+        }
+    }
+}
+
+/// Run `finch proxy`: the chat APIs in front of the provider fallback chain
+async fn run_proxy(bind_address: String) -> Result<()> {
+    use finch::providers::create_providers_from_entries;
+    use finch::server::{ProxyConfig, ProxyServer};
+    use std::sync::Arc;
+
+    let config = load_config()?;
+    let providers = create_providers_from_entries(&config.providers)
+        .context("finch proxy needs at least one cloud provider in [[providers]]")?;
+
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(env_filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+
+    let names: Vec<_> = providers.iter().map(|p| p.name().to_string()).collect();
+    let proxy_config = ProxyConfig {
+        bind_address: bind_address.clone(),
+        local_token: config
+            .server
+            .local_token
+            .then(finch::daemon::token::default_path)
+            .flatten(),
+        cors: config.server.cors.clone(),
+    };
+    let proxy = Arc::new(ProxyServer::new(proxy_config, providers)?);
+
+    eprintln!("finch proxy on {}", bind_address);
+    eprintln!("  Providers: {}", names.join(" → "));
+    if config.server.local_token {
+        eprintln!("  Clients must send the token in ~/.finch/daemon.token");
+    }
+
+    let terminate = async {
+        #[cfg(unix)]
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+        #[cfg(not(unix))]
+        std::future::pending::<()>().await;
+    };
+
+    tokio::select! {
+        result = Arc::clone(&proxy).serve() => result?,
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+
+    let usage = proxy.usage();
+    eprintln!(
+        "\nProxied {} requests: {} input / {} output tokens, ~${:.4}",
+        usage.total.requests,
+        usage.total.input_tokens,
+        usage.total.output_tokens,
+        usage.total.cost_usd
+    );
+    Ok(())
+}
+
+/// Summary backend that sends everything to the active teacher
+struct TeacherSummaryBackend {
+    client: ClaudeClient,
+    model: String,
+}
+
+impl TeacherSummaryBackend {
+    fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: create_claude_client_with_provider(config)?,
+            model: config
+                .active_teacher()
+                .and_then(|t| t.model.clone())
+                .unwrap_or_else(|| finch::config::constants::DEFAULT_CLAUDE_MODEL.to_string()),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl finch::summarize::SummaryBackend for TeacherSummaryBackend {
+    async fn local(&self, _prompt: &str) -> Result<String> {
+        anyhow::bail!("No local model without the daemon")
+    }
+
+    async fn routed(&self, prompt: &str) -> Result<String> {
+        let mut request = finch::claude::MessageRequest::new(prompt);
+        request.model = self.model.clone();
+        Ok(self.client.send_message(&request).await?.text())
+    }
+}
+
 /// Run interactive setup wizard
 async fn run_setup() -> Result<()> {
     use finch::cli::show_setup_wizard;
@@ -1470,6 +2515,12 @@ async fn run_setup() -> Result<()> {
         enable_summarization: config.features.enable_summarization,
         auto_compact_enabled: config.features.auto_compact_enabled,
         brain_enabled: config.features.brain_enabled,
+        tool_result_max_tokens: config.features.tool_result_max_tokens,
+        tool_result_max_tokens_by_tool: config.features.tool_result_max_tokens_by_tool.clone(),
+        rename_check: config.features.rename_check,
+        max_parallel_tools: config.features.max_parallel_tools,
+        tool_timeouts: config.features.tool_timeouts.clone(),
+        prompt_lint: config.features.prompt_lint,
     };
     #[allow(deprecated)]
     {
@@ -1487,11 +2538,52 @@ async fn run_setup() -> Result<()> {
 }
 
 /// Show this node's identity and capabilities
-async fn run_node_info() -> Result<()> {
+/// Load-test a running daemon with synthetic sessions
+async fn run_loadtest(
+    target: Option<String>,
+    options: finch::client::loadtest::LoadTestOptions,
+    timeout: u64,
+    json: bool,
+) -> Result<()> {
+    use finch::client::HttpTransport;
+
+    let target = match target {
+        Some(target) => target,
+        None => load_config()?.client.daemon_address,
+    };
+    let transport = HttpTransport::new(&target, std::time::Duration::from_secs(timeout))?;
+
+    let total = options.total_requests();
+    eprintln!(
+        "Load-testing {}: {} sessions × {} conversations × {} requests ({} in all)",
+        target,
+        options.sessions,
+        options.requests,
+        options.tool_depth + 1,
+        total
+    );
+    let report = finch::client::loadtest::run(transport, options, move |done| {
+        eprint!("\r  {}/{} requests", done, total)
+    })
+    .await?;
+    eprintln!();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+async fn run_node_info(bench: bool) -> Result<()> {
     use finch::node::NodeInfo;
 
     let config = load_config().unwrap_or_else(|_| Config::new(vec![]));
     let has_teacher = config.active_teacher().is_some();
+    if bench {
+        run_generation_benchmarks(&config).await?;
+    }
     let info = NodeInfo::load(has_teacher)?;
 
     println!("╔══════════════════════════════════════╗");
@@ -1507,6 +2599,13 @@ async fn run_node_info() -> Result<()> {
     } else {
         println!("  Model    : cloud-only (teacher API)");
     }
+    if let Some(provenance) = &info.model_provenance {
+        println!("  Download : {}", provenance.summary());
+    }
+    for (i, benchmark) in info.benchmarks.iter().enumerate() {
+        let label = if i == 0 { "Speed    :" } else { "          " };
+        println!("  {} {}", label, benchmark.summary());
+    }
     println!(
         "  Teacher  : {}",
         if info.capabilities.has_teacher_api {
@@ -1524,6 +2623,554 @@ async fn run_node_info() -> Result<()> {
     Ok(())
 }
 
+/// Time the configured local model on each inference path and record the
+/// results for `NodeInfo`
+async fn run_generation_benchmarks(config: &Config) -> Result<()> {
+    use finch::models::benchmark::{self, BenchmarkStore};
+    use finch::models::ModelLoadConfig;
+
+    if !config.backend.enabled {
+        anyhow::bail!("No local model configured (backend disabled); nothing to benchmark");
+    }
+    let load_config = ModelLoadConfig {
+        provider: config.backend.inference_provider,
+        family: config.backend.model_family,
+        size: config.backend.model_size,
+        target: config.backend.effective_target(),
+        repo_override: config.backend.model_repo.clone(),
+    };
+    let store = BenchmarkStore::open_default().context("Could not determine home directory")?;
+
+    // Loading and generating block, and may download models
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        for path in benchmark::paths(&load_config) {
+            println!("⏱  Benchmarking {}...", path.label);
+            let result = path
+                .load()
+                .and_then(|mut model| benchmark::run(&path.label, model.as_mut()));
+            match result {
+                Ok(result) => {
+                    println!("   {}", result.summary());
+                    store.record(&result)?;
+                }
+                Err(e) => eprintln!("⚠️  {} skipped: {:#}", path.label, e),
+            }
+        }
+        println!();
+        Ok(())
+    })
+    .await?
+}
+
+// ── finch bench / stats ───────────────────────────────────────────────────────
+
+async fn run_bench_command(cmd: BenchCommand) -> Result<()> {
+    use finch::metrics::{run_bench, BenchTarget, Leaderboard, LocalBenchTarget, BENCH_SUITE};
+    use finch::providers::create_providers_from_entries;
+
+    let BenchCommand::Providers { judge, no_local } = cmd;
+    let config = load_config()?;
+    let providers = create_providers_from_entries(&config.providers)?;
+    let judge = match &judge {
+        Some(name) => providers
+            .iter()
+            .find(|p| p.name().eq_ignore_ascii_case(name))
+            .with_context(|| format!("Judge provider '{}' is not configured", name))?,
+        None => &providers[0],
+    };
+
+    let mut targets: Vec<Box<dyn BenchTarget>> = Vec::new();
+    if !no_local {
+        let daemon_config = finch::client::DaemonConfig::from_client_config(&config.client);
+        match finch::client::DaemonClient::connect(daemon_config).await {
+            Ok(client) => targets.push(Box::new(LocalBenchTarget::new(client))),
+            Err(e) => eprintln!("⚠️  Skipping local model (daemon unavailable: {})", e),
+        }
+    }
+
+    println!(
+        "Benchmarking {} target(s) on {} prompts, judged by {}/{}",
+        providers.len() + targets.len(),
+        BENCH_SUITE.len(),
+        judge.name(),
+        judge.default_model()
+    );
+
+    let mut leaderboard = Leaderboard::load()?;
+    let all_targets = providers
+        .iter()
+        .map(|p| p as &dyn BenchTarget)
+        .chain(targets.iter().map(|t| t.as_ref()));
+    for target in all_targets {
+        println!("  → {}/{}", target.provider(), target.model());
+        let entry = run_bench(target, judge.as_ref(), BENCH_SUITE).await;
+        if entry.failed > 0 {
+            println!(
+                "    {} of {} prompts failed",
+                entry.failed,
+                BENCH_SUITE.len()
+            );
+        }
+        leaderboard.record(entry);
+    }
+    leaderboard.save()?;
+
+    println!();
+    print!("{}", leaderboard.render_table());
+    Ok(())
+}
+
+fn run_stats() -> Result<()> {
+    use finch::metrics::Leaderboard;
+
+    let leaderboard = Leaderboard::load()?;
+    if leaderboard.entries.is_empty() {
+        println!("No benchmark results yet. Run `finch bench providers` first.");
+        return Ok(());
+    }
+    println!(
+        "Provider leaderboard ({})",
+        Leaderboard::default_path()?.display()
+    );
+    println!();
+    print!("{}", leaderboard.render_table());
+    Ok(())
+}
+
+// ── finch doctor ──────────────────────────────────────────────────────────────
+
+async fn run_doctor(network: bool) -> Result<()> {
+    let config = match load_config() {
+        Ok(config) => {
+            println!("✓ Config loaded ({} providers)", config.providers.len());
+            config
+        }
+        Err(e) => {
+            println!("✗ Config: {:#}", e);
+            return Ok(());
+        }
+    };
+    if !network {
+        println!("\nRun `finch doctor --network` to check connectivity.");
+        return Ok(());
+    }
+    run_network_doctor(&config).await;
+    Ok(())
+}
+
+/// Check for, install and switch the daemon to a new release
+async fn run_update(check: bool, channel: Option<finch::config::UpdateChannel>) -> Result<()> {
+    use finch::daemon::lifecycle::STOP_MARGIN_SECS;
+    use finch::daemon::update::{self, Updater, Version};
+    use finch::daemon::DaemonLifecycle;
+
+    let config = load_config()?;
+    let mut update_config = config.update.clone();
+    if let Some(channel) = channel {
+        update_config.channel = channel;
+    }
+    let updater = Updater::new(&update_config)?;
+    let current = Version::current();
+
+    println!(
+        "Checking for updates ({} channel)...",
+        update_config.channel.as_str()
+    );
+    let Some(release) = updater.check().await? else {
+        println!("✓ finch {} is up to date", current);
+        return Ok(());
+    };
+    println!("Update available: {} → {}", current, release.version);
+    println!("  {}", release.html_url);
+    if check {
+        println!("\nRun `finch update` to install it.");
+        return Ok(());
+    }
+
+    println!("Downloading and verifying {}...", release.tag);
+    let exe = updater.install(&release).await?;
+    println!("✓ Installed finch {} at {}", release.version, exe.display());
+
+    let lifecycle = DaemonLifecycle::new()?;
+    if lifecycle.is_running() {
+        println!("Restarting daemon (sessions are kept)...");
+        let pid = lifecycle.restart_daemon()?;
+        // The daemon drains in-flight work before it restarts
+        let timeout = std::time::Duration::from_secs(
+            finch::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS + STOP_MARGIN_SECS + 30,
+        );
+        update::wait_for_daemon_version(&config.client.daemon_address, &release.version, timeout)
+            .await?;
+        println!(
+            "✓ Daemon (PID: {}) is running finch {}",
+            pid, release.version
+        );
+    }
+    Ok(())
+}
+
+/// Default API endpoint for a cloud provider type
+fn provider_endpoint(provider_type: &str) -> Option<&'static str> {
+    match provider_type {
+        "claude" => Some("https://api.anthropic.com"),
+        "openai" => Some("https://api.openai.com"),
+        "grok" => Some("https://api.x.ai"),
+        "gemini" => Some("https://generativelanguage.googleapis.com"),
+        "mistral" => Some("https://api.mistral.ai"),
+        "groq" => Some("https://api.groq.com"),
+        _ => None,
+    }
+}
+
+async fn run_network_doctor(config: &finch::config::Config) {
+    use finch::http::{self, Probe, ProxySource};
+
+    let settings = http::settings();
+    println!("\nNetwork");
+    match &settings.proxy {
+        Some((proxy, source)) => {
+            let source = match source {
+                ProxySource::Config => "[http] proxy",
+                ProxySource::Environment => "environment",
+                ProxySource::System => "system settings",
+            };
+            println!("  Proxy:     {} (from {})", proxy, source);
+            println!("  No proxy:  {}", settings.no_proxy);
+        }
+        None => println!("  Proxy:     none"),
+    }
+    match (&settings.ca_bundle, &settings.ca_error) {
+        (_, Some(error)) => println!("  CA bundle: ✗ {}", error),
+        (Some(path), None) => println!(
+            "  CA bundle: {} ({} certificates)",
+            path.display(),
+            settings.certificates.len()
+        ),
+        (None, None) => println!("  CA bundle: none (built-in roots only)"),
+    }
+
+    let mut endpoints: Vec<(String, String)> = Vec::new();
+    for entry in &config.providers {
+        if let Some(url) = provider_endpoint(entry.provider_type()) {
+            if !endpoints.iter().any(|(_, u)| u == url) {
+                endpoints.push((entry.display_name().to_string(), url.to_string()));
+            }
+        }
+    }
+    let hf_endpoint =
+        std::env::var("HF_ENDPOINT").unwrap_or_else(|_| "https://huggingface.co".to_string());
+    endpoints.push(("Model downloads".to_string(), hf_endpoint));
+
+    println!();
+    let mut failures = 0;
+    for (name, url) in &endpoints {
+        match http::probe(url).await {
+            Probe::Reachable(status) => println!("  ✓ {} — {} (HTTP {})", name, url, status),
+            Probe::Failed(reason) => {
+                failures += 1;
+                println!("  ✗ {} — {}\n      {}", name, url, reason);
+            }
+        }
+    }
+
+    if settings.ca_bundle.is_some() {
+        println!(
+            "\nNote: model downloads use their own TLS roots and ignore the CA bundle. If they \
+             fail, download models on another network or point HF_ENDPOINT at an internal mirror."
+        );
+    }
+    if failures == 0 {
+        println!("\n✓ All endpoints reachable");
+    }
+}
+
+// ── finch debug ───────────────────────────────────────────────────────────────
+
+async fn run_debug_command(cmd: DebugCommand) -> Result<()> {
+    match cmd {
+        DebugCommand::Replay {
+            session,
+            n,
+            provider,
+            model,
+            json,
+        } => {
+            let config = load_config()?;
+            // Captures live in the running daemon; a freshly spawned one has none
+            let mut daemon_config = finch::client::DaemonConfig::from_client_config(&config.client);
+            daemon_config.auto_spawn = false;
+            let client = finch::client::DaemonClient::connect(daemon_config).await?;
+            let replay = client
+                .replay_session_request(&session, n, provider.as_deref(), model.as_deref())
+                .await?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&replay)?);
+                return Ok(());
+            }
+            print_exchange("Original", &replay.original);
+            if let Some(exchange) = &replay.replay {
+                println!();
+                print_exchange("Replay", exchange);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_exchange(label: &str, exchange: &finch::server::Exchange) {
+    println!(
+        "── {} · {} / {} · {} ms · {}",
+        label,
+        exchange.provider,
+        exchange.request.model,
+        exchange.latency_ms,
+        exchange.captured_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    if let Some(last) = exchange.request.messages.last() {
+        println!(
+            "{} messages; last {}: {}",
+            exchange.request.messages.len(),
+            last.role,
+            last.text()
+        );
+    }
+    match (&exchange.response, &exchange.error) {
+        (Some(response), _) => println!("\n{}", response.text()),
+        (None, Some(error)) => println!("\nError: {}", error),
+        (None, None) => {}
+    }
+}
+
+// ── finch audit ───────────────────────────────────────────────────────────────
+
+fn run_audit_command(cmd: AuditCommand) -> Result<()> {
+    use finch::network::DeviceKey;
+    use finch::tools::audit::{self, AuditLog};
+
+    let path = AuditLog::default_path()?;
+    match cmd {
+        AuditCommand::Show { limit } => {
+            let entries = audit::read_entries(&path)?;
+            if entries.is_empty() {
+                println!("No tool calls recorded in {}", path.display());
+            }
+            let skip = entries.len().saturating_sub(limit);
+            for entry in &entries[skip..] {
+                let detail = if entry.paths.is_empty() {
+                    entry.input.clone()
+                } else {
+                    entry.paths.join(", ")
+                };
+                println!(
+                    "{:>5} {} {} {} {:?}/{:?} {}ms  {}",
+                    entry.seq,
+                    entry.time,
+                    entry.user,
+                    entry.tool,
+                    entry.approval,
+                    entry.outcome,
+                    entry.duration_ms,
+                    detail
+                );
+            }
+        }
+        AuditCommand::Verify { key } => {
+            let key = match key {
+                Some(key) => key,
+                None => DeviceKey::load_or_create()?.public_key(),
+            };
+            let verification = audit::verify(&path, &key)?;
+            for problem in &verification.problems {
+                println!("✗ {}", problem);
+            }
+            if !verification.is_intact() {
+                anyhow::bail!(
+                    "{} of {} entries in {} failed verification",
+                    verification.problems.len(),
+                    verification.entries,
+                    path.display()
+                );
+            }
+            println!(
+                "✓ {} entries in {} are intact (key {})",
+                verification.entries,
+                path.display(),
+                key
+            );
+        }
+    }
+    Ok(())
+}
+
+// ── finch archive ─────────────────────────────────────────────────────────────
+
+async fn run_archive_command(cmd: ArchiveCommand) -> Result<()> {
+    use finch::memory::{cutoff_months_ago, Archive, MemorySystem};
+
+    let config = load_config()?;
+    match cmd {
+        ArchiveCommand::Run { older_than_months } => {
+            let months = older_than_months.unwrap_or(config.archive.after_months);
+            if months == 0 {
+                anyhow::bail!(
+                    "No archive age set. Pass --older-than-months or set \
+                     [archive] after_months in ~/.finch/config.toml"
+                );
+            }
+            let cutoff = cutoff_months_ago(months);
+            let memory = MemorySystem::new(config.memory.clone())?;
+            let mut report = memory.archive_older_than(cutoff).await?;
+            if let Some(dir) = &config.server.sessions_dir {
+                report.sessions = Archive::new(config.memory.archive_dir.clone())
+                    .archive_sessions_dir(dir, cutoff)?;
+            }
+            if report.total() == 0 {
+                println!("Nothing older than {} months to archive.", months);
+            } else {
+                println!(
+                    "Archived {} conversations, {} memories and {} sessions to {}",
+                    report.conversations,
+                    report.memories,
+                    report.sessions,
+                    config.memory.archive_dir.display()
+                );
+            }
+        }
+        ArchiveCommand::Search { query, limit } => {
+            let hits = Archive::new(config.memory.archive_dir.clone()).search(&query, limit)?;
+            if hits.is_empty() {
+                println!("No archived records match.");
+            }
+            for hit in hits {
+                let text = hit.record.text();
+                let preview: String = text.chars().take(200).collect();
+                println!(
+                    "[{:.2}] {} {} — {}",
+                    hit.score,
+                    hit.record.timestamp().format("%Y-%m-%d"),
+                    hit.record.kind(),
+                    preview.replace('\n', " ")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// ── finch memory ──────────────────────────────────────────────────────────────
+
+async fn run_memory_command(cmd: MemoryCommand) -> Result<()> {
+    use finch::memory::MemorySystem;
+    use std::io::{BufReader, Write};
+
+    let config = load_config()?;
+    let memory = match cmd {
+        // Download the configured model rather than re-embed with TF-IDF
+        MemoryCommand::Reembed { .. } => MemorySystem::new_async(config.memory.clone()).await?,
+        _ => MemorySystem::new(config.memory.clone())?,
+    };
+    if !config.memory.store.is_default()
+        && !matches!(
+            cmd,
+            MemoryCommand::Consolidate { .. }
+                | MemoryCommand::Stats { .. }
+                | MemoryCommand::Reembed { .. }
+        )
+    {
+        eprintln!(
+            "Note: memories in the configured [memory.store] are not exported; \
+             only conversations and the local MemTree are. Import adds to the store."
+        );
+    }
+    match cmd {
+        MemoryCommand::Export {
+            output,
+            no_embeddings,
+        } => {
+            let (report, target) = match &output {
+                Some(path) => {
+                    let mut file = io::BufWriter::new(
+                        std::fs::File::create(path)
+                            .with_context(|| format!("Failed to create {}", path.display()))?,
+                    );
+                    let report = memory.export(&mut file, !no_embeddings).await?;
+                    file.flush()?;
+                    (report, path.display().to_string())
+                }
+                None => {
+                    let mut stdout = io::stdout().lock();
+                    (
+                        memory.export(&mut stdout, !no_embeddings).await?,
+                        "stdout".to_string(),
+                    )
+                }
+            };
+            eprintln!(
+                "Exported {} conversations, {} memories and {} facts to {}",
+                report.conversations, report.memories, report.facts, target
+            );
+        }
+        MemoryCommand::Import { path } => {
+            let report = if path.as_os_str() == "-" {
+                memory.import(io::stdin().lock()).await?
+            } else {
+                let file = std::fs::File::open(&path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                memory.import(BufReader::new(file)).await?
+            };
+            println!(
+                "Imported {} conversations, {} memories and {} facts \
+                 ({} already present, {} re-embedded)",
+                report.conversations,
+                report.memories,
+                report.facts,
+                report.skipped,
+                report.reembedded
+            );
+        }
+        MemoryCommand::Consolidate { similarity } => {
+            let similarity = similarity.unwrap_or(config.memory.consolidate.similarity);
+            finch::memory::consolidate::validate_similarity(similarity)?;
+            let report = memory.consolidate(similarity).await?;
+            println!(
+                "Merged {} near-duplicate memories into {} ({} scanned, similarity {})",
+                report.merged, report.groups, report.scanned, similarity
+            );
+        }
+        MemoryCommand::Stats { verbose } => {
+            let stats = memory.stats().await?;
+            println!("Conversations: {}", stats.conversation_count);
+            println!("Memories:      {} ({})", stats.tree_node_count, stats.store);
+            println!("Facts:         {}", stats.fact_count);
+            if verbose {
+                println!();
+                print!("{}", memory.usage().await?.render());
+            }
+        }
+        MemoryCommand::Reembed { force } => {
+            let model = config.memory.embedding_model.id();
+            if config.memory.use_neural_embeddings && memory.embedding_model().is_none() {
+                anyhow::bail!(
+                    "Could not load embedding model {}; nothing re-embedded",
+                    model
+                );
+            }
+            let model = memory.embedding_model().map_or("TF-IDF", |m| m.id());
+            if !force && !memory.needs_reembedding().await? {
+                println!("Memories are already embedded with {}", model);
+                return Ok(());
+            }
+            let count = memory
+                .reembed(|done, total| eprint!("\rRe-embedding memories: {}/{}", done, total))
+                .await?;
+            eprintln!();
+            println!("Re-embedded {} memories with {}", count, model);
+        }
+    }
+    Ok(())
+}
+
 // ── finch coforth ─────────────────────────────────────────────────────────────
 
 fn run_coforth_command(cmd: CoforthCommand) -> Result<()> {
@@ -1565,6 +3212,52 @@ fn run_coforth_command(cmd: CoforthCommand) -> Result<()> {
 
 // ── finch library ─────────────────────────────────────────────────────────────
 
+async fn run_ask_docs(path: Option<PathBuf>, refresh: bool, max_pages: usize) -> Result<()> {
+    use finch::memory::docs::{discover_dependencies, IndexOutcome};
+    use finch::memory::DocsIndex;
+
+    let dir = match path {
+        Some(p) => p,
+        None => std::env::current_dir()?,
+    };
+    let deps = discover_dependencies(&dir)?;
+    if deps.is_empty() {
+        println!(
+            "No dependencies found (looked for Cargo.toml and package.json in {})",
+            dir.display()
+        );
+        return Ok(());
+    }
+
+    let index = DocsIndex::open()?;
+    println!(
+        "Indexing docs for {} dependencies into {}",
+        deps.len(),
+        DocsIndex::db_path().display()
+    );
+
+    let mut failed = 0;
+    for dep in &deps {
+        match index.index(dep, max_pages, refresh).await {
+            Ok(IndexOutcome::Indexed { pages, chunks }) => {
+                println!("  ✓ {} ({} pages, {} chunks)", dep.tag(), pages, chunks)
+            }
+            Ok(IndexOutcome::Skipped) => println!("  · {} (already indexed)", dep.tag()),
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}: {}", dep.tag(), e);
+            }
+        }
+    }
+
+    println!(
+        "Done. {} indexed or up to date, {} failed. The doc_lookup tool is available in new sessions.",
+        deps.len() - failed,
+        failed
+    );
+    Ok(())
+}
+
 async fn run_library_command(cmd: LibraryCommand) -> Result<()> {
     use finch::coforth::generator::{self, BuildOptions, CATEGORIES};
     use finch::coforth::Library;
@@ -1921,6 +3614,222 @@ async fn run_network_command(cmd: NetworkCommand) -> Result<()> {
                 }
             }
         }
+
+        NetworkCommand::Adapter { adapter_command } => {
+            run_adapter_command(adapter_command, &identity, &membership).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_adapter_command(
+    cmd: AdapterCommand,
+    identity: &finch::node::identity::NodeIdentity,
+    membership: &finch::network::DeviceMembership,
+) -> Result<()> {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use finch::network::adapters::{self, AdapterManifest, MANIFEST_FORMAT};
+    use finch::network::{
+        AdapterPayload, AdapterStore, DeviceKey, LotusClient, SignedAdapter, TrustedDevices,
+    };
+    use finch::training::LoRATrainingConfig;
+
+    let (Some(account_id), Some(device_token)) = (
+        membership.status.account_id(),
+        membership.status.device_token(),
+    ) else {
+        anyhow::bail!(
+            "Adapter sharing needs a Lotus account. Link this device with \
+             `finch network join <invite-code>` first."
+        );
+    };
+    let client = LotusClient::new(&membership.lotus_url)?;
+    let store = AdapterStore::default_dir()?;
+    // Adapters are trained against the training base model, so that's what
+    // published adapters record and pulled ones must match
+    let training = LoRATrainingConfig::default();
+    let base_revision = adapters::base_model_revision(&training.base_model);
+
+    // Manifests that verify and belong to this account; anything else is skipped
+    let mut published: Vec<(SignedAdapter, AdapterManifest)> = Vec::new();
+    for adapter in client.list_adapters(device_token).await? {
+        match adapter.verify() {
+            Ok(manifest) if manifest.account_id == account_id => {
+                published.push((adapter, manifest))
+            }
+            Ok(manifest) => eprintln!(
+                "⚠  Skipping {} v{}: published to another account",
+                manifest.name, manifest.version
+            ),
+            Err(e) => eprintln!("⚠  Skipping unverifiable adapter: {}", e),
+        }
+    }
+
+    match cmd {
+        AdapterCommand::Publish { name, path } => {
+            let path = path.unwrap_or_else(|| store.latest_trained());
+            let data = std::fs::read(&path).with_context(|| {
+                format!(
+                    "No adapter at {} — train one first, or pass --path",
+                    path.display()
+                )
+            })?;
+            let name = name.unwrap_or_else(|| identity.name.clone());
+            let version = published
+                .iter()
+                .filter(|(_, m)| m.name == name)
+                .map(|(_, m)| m.version)
+                .max()
+                .unwrap_or(0)
+                + 1;
+
+            let key = DeviceKey::load_or_create()?;
+            let manifest = AdapterManifest {
+                format: MANIFEST_FORMAT,
+                name,
+                version,
+                base_model: training.base_model.clone(),
+                base_revision,
+                rank: training.rank,
+                alpha: training.alpha,
+                sha256: adapters::sha256_hex(&data),
+                size_bytes: data.len() as u64,
+                account_id: account_id.to_string(),
+                device_id: identity.id,
+                device_name: identity.name.clone(),
+                public_key: key.public_key(),
+                created_at: chrono::Utc::now(),
+            };
+            let payload = AdapterPayload {
+                adapter: SignedAdapter::sign(&manifest, &key)?,
+                data: STANDARD.encode(&data),
+            };
+            client.publish_adapter(device_token, &payload).await?;
+            println!(
+                "✓ Published {} v{} ({} KB, base {})",
+                manifest.name,
+                manifest.version,
+                data.len() / 1024,
+                manifest.base_model
+            );
+        }
+
+        AdapterCommand::List => {
+            if published.is_empty() {
+                println!("No adapters published to this account yet.");
+                println!("  On the device that trains: finch network adapter publish");
+                return Ok(());
+            }
+            published.sort_by_key(|(_, m)| std::cmp::Reverse(m.created_at));
+            for (_, m) in &published {
+                let origin = if m.device_id == identity.id {
+                    "this device".to_string()
+                } else {
+                    m.device_name.clone()
+                };
+                let fit = match m.check_compatible(&training.base_model, base_revision.as_deref()) {
+                    Ok(()) => "✓ compatible".to_string(),
+                    Err(e) => format!("✗ {}", e),
+                };
+                let installed = if store.installed_path(&m.name, m.version).exists() {
+                    " · installed"
+                } else {
+                    ""
+                };
+                println!(
+                    "  {} v{}  from {}  {}  {} KB  {}{}",
+                    m.name,
+                    m.version,
+                    origin,
+                    m.created_at.format("%Y-%m-%d %H:%M"),
+                    m.size_bytes / 1024,
+                    fit,
+                    installed
+                );
+            }
+        }
+
+        AdapterCommand::Pull {
+            name,
+            version,
+            force,
+        } => {
+            let candidates: Vec<&AdapterManifest> = published
+                .iter()
+                .map(|(_, m)| m)
+                .filter(|m| match &name {
+                    Some(name) => m.name == *name,
+                    None => m.device_id != identity.id,
+                })
+                .filter(|m| version.is_none_or(|v| m.version == v))
+                .collect();
+            if candidates.is_empty() {
+                anyhow::bail!("No matching adapter published to this account");
+            }
+
+            // Newest adapter that fits; with --force a revision mismatch is
+            // tolerated, but never a different base model
+            let fits = |m: &AdapterManifest| {
+                if force {
+                    m.check_compatible(&training.base_model, None)
+                } else {
+                    m.check_compatible(&training.base_model, base_revision.as_deref())
+                }
+            };
+            let Some(chosen) = candidates
+                .iter()
+                .filter(|m| fits(m).is_ok())
+                .max_by_key(|m| m.created_at)
+            else {
+                for m in &candidates {
+                    if let Err(e) = fits(m) {
+                        println!("  ✗ {} v{}: {}", m.name, m.version, e);
+                    }
+                }
+                anyhow::bail!("No compatible adapter to pull");
+            };
+
+            let installed = store.installed_path(&chosen.name, chosen.version);
+            if installed.exists() {
+                println!(
+                    "{} v{} is already installed at {}",
+                    chosen.name,
+                    chosen.version,
+                    installed.display()
+                );
+                return Ok(());
+            }
+
+            let trusted_path = store.trusted_devices_path();
+            let mut trusted = TrustedDevices::load(&trusted_path)?;
+            trusted.check(chosen)?;
+
+            let payload = client
+                .download_adapter(device_token, &chosen.name, chosen.version)
+                .await?;
+            if payload.adapter.verify()? != **chosen {
+                anyhow::bail!(
+                    "Downloaded manifest for {} v{} differs from the listed one",
+                    chosen.name,
+                    chosen.version
+                );
+            }
+            let data = STANDARD
+                .decode(payload.data.as_bytes())
+                .context("Adapter data is not valid base64")?;
+            let path = store.install(&payload.adapter, chosen, &data)?;
+            trusted.save(&trusted_path)?;
+
+            println!(
+                "✓ Installed {} v{} from {} at {}",
+                chosen.name,
+                chosen.version,
+                chosen.device_name,
+                path.display()
+            );
+        }
     }
 
     Ok(())
@@ -1953,12 +3862,84 @@ async fn run_worker(bind_address: String, info_only: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Start the daemon on the specified address (usually 0.0.0.0)
+    // One model per machine: with a daemon already up, it takes on the
+    // worker role instead of a second process loading the model again
+    let lifecycle = finch::daemon::DaemonLifecycle::new()?;
+    if lifecycle.is_running() {
+        let pid = lifecycle.read_pid()?;
+        return attach_worker(&config, pid, &bind_address).await;
+    }
+
+    // Otherwise this process is the daemon: local sessions on the usual
+    // address, LAN traffic on the worker one
+    let local_address = config.client.daemon_address.clone();
+    let worker_address = (bind_address != local_address).then_some(bind_address);
     println!("  Starting worker daemon...");
+    println!("  Local sessions use it too (at {})", local_address);
     println!("  Workers on your LAN can find this node via mDNS (_finch._tcp.local.)");
     println!("  Press Ctrl+C to stop.\n");
 
-    run_daemon(bind_address).await
+    run_daemon(
+        local_address,
+        finch::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS,
+        finch::config::constants::DEFAULT_RESPONSE_CACHE_TTL_SECS,
+        None,
+        config.server.parallel_sessions,
+        None,
+        worker_address,
+    )
+    .await
+}
+
+/// Have the running daemon (`pid`) serve worker traffic on `bind_address`
+/// until Ctrl+C, or until the daemon itself stops
+async fn attach_worker(config: &Config, pid: u32, bind_address: &str) -> Result<()> {
+    use finch::client::{DaemonClient, DaemonConfig};
+
+    let client = DaemonClient::connect(DaemonConfig {
+        auto_spawn: false,
+        ..DaemonConfig::from_client_config(&config.client)
+    })
+    .await?;
+    let address = client.take_worker_role(bind_address).await?;
+    println!("  Daemon already running (PID {}); sharing its model", pid);
+    println!(
+        "  Worker traffic on {} waits behind local sessions",
+        address
+    );
+    let discovery = config
+        .server
+        .advertise
+        .then(|| advertise_service(config, &address))
+        .flatten();
+    if discovery.is_some() {
+        println!("  Workers on your LAN can find this node via mDNS (_finch._tcp.local.)");
+    }
+    println!("  Press Ctrl+C to stop.\n");
+
+    let lifecycle = finch::daemon::DaemonLifecycle::new()?;
+    let mut check = tokio::time::interval(std::time::Duration::from_secs(5));
+    let daemon_stopped = loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break false,
+            _ = check.tick() => {
+                if !lifecycle.is_running() {
+                    break true;
+                }
+            }
+        }
+    };
+
+    if let Some(discovery) = discovery {
+        let _ = discovery.stop();
+    }
+    if daemon_stopped {
+        println!("  Daemon stopped; no longer serving worker traffic");
+    } else {
+        client.drop_worker_role().await?;
+        println!("  Stopped serving worker traffic; the daemon keeps running");
+    }
+    Ok(())
 }
 
 /// Handle `finch license` subcommands
@@ -2033,6 +4014,8 @@ async fn run_agent(
     tasks: Option<PathBuf>,
     reflect_every: usize,
     once: bool,
+    dry_run: bool,
+    record: bool,
 ) -> Result<()> {
     use finch::agent::{AgentConfig, AgentLoop};
 
@@ -2070,6 +4053,9 @@ async fn run_agent(
     if once {
         println!("  Mode: --once (exit after first task)");
     }
+    if dry_run {
+        println!("  Mode: --dry-run (nothing is written or committed)");
+    }
     println!();
 
     let agent_config = AgentConfig {
@@ -2077,8 +4063,57 @@ async fn run_agent(
         tasks_path,
         reflect_every: reflect_every.max(1), // At least 1 to avoid div-by-zero
         once,
+        dry_run,
+        record,
     };
 
     let mut agent = AgentLoop::new(config, agent_config);
     agent.run().await
 }
+
+/// Replay a recorded agent run (`finch agent replay`)
+async fn run_agent_replay(path: &Path) -> Result<()> {
+    use finch::agent::recording::Recording;
+    use finch::agent::AgentLoop;
+
+    let recording = Recording::load(path)?;
+    let run = &recording.run;
+    println!("Replaying {}", path.display());
+    println!(
+        "  Recorded {} by finch {}{}",
+        run.started_at,
+        run.finch_version,
+        if run.dry_run { " (--dry-run)" } else { "" }
+    );
+    println!("  Persona: {}, model: {}", run.persona.name(), run.model);
+    println!();
+
+    let results = AgentLoop::replay(recording).await?;
+    println!();
+    let mut diverged = 0;
+    for task in &results {
+        let recorded = task
+            .recorded
+            .as_ref()
+            .map_or_else(|| "unfinished".to_string(), |o| o.to_string());
+        println!(
+            "[Task {}] recorded: {}; replayed: {}",
+            task.id, recorded, task.replayed
+        );
+        for divergence in &task.divergences {
+            println!("  differs: {}", divergence);
+        }
+        if !task.divergences.is_empty() {
+            diverged += 1;
+        }
+    }
+    if diverged > 0 {
+        anyhow::bail!(
+            "{} of {} replayed task(s) departed from the recording",
+            diverged,
+            results.len()
+        );
+    }
+    println!("Replay matches the recording ({} task(s))", results.len());
+    Ok(())
+}
@@ -0,0 +1,338 @@
+// Cold-storage archive for old memory and sessions
+//
+// Long-lived installs accumulate years of conversation rows and MemTree
+// leaves, and every query pays for them.  The archive policy moves anything
+// older than a cutoff out of the hot SQLite DB (and stale files out of the
+// daemon's sessions directory) into gzip-compressed JSONL files under
+// `~/.finch/archive/`.  Archived records are not recalled automatically, but
+// `Archive::search` scans them on demand: slower, but nothing is lost.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use super::embeddings::{cosine_similarity, EmbeddingEngine, TfIdfEmbedding};
+
+/// One archived item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveRecord {
+    /// A row of the `conversations` table
+    Conversation {
+        id: String,
+        session_id: Option<String>,
+        role: String,
+        content: String,
+        model: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+    /// A MemTree leaf
+    Memory {
+        text: String,
+        importance: u8,
+        created_at: DateTime<Utc>,
+    },
+    /// A persisted daemon session file, stored verbatim
+    Session {
+        id: String,
+        modified: DateTime<Utc>,
+        data: serde_json::Value,
+    },
+}
+
+impl ArchiveRecord {
+    /// Searchable text of the record
+    pub fn text(&self) -> String {
+        match self {
+            Self::Conversation { content, .. } => content.clone(),
+            Self::Memory { text, .. } => text.clone(),
+            Self::Session { data, .. } => {
+                let mut parts = Vec::new();
+                collect_strings(data, &mut parts);
+                parts.join("\n")
+            }
+        }
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Self::Conversation { timestamp, .. } => *timestamp,
+            Self::Memory { created_at, .. } => *created_at,
+            Self::Session { modified, .. } => *modified,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Conversation { .. } => "conversation",
+            Self::Memory { .. } => "memory",
+            Self::Session { .. } => "session",
+        }
+    }
+}
+
+fn collect_strings<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// What one archive run moved
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArchiveReport {
+    pub conversations: usize,
+    pub memories: usize,
+    pub sessions: usize,
+    /// File the records were written to (`None` if nothing was old enough)
+    pub file: Option<PathBuf>,
+}
+
+impl ArchiveReport {
+    pub fn total(&self) -> usize {
+        self.conversations + self.memories + self.sessions
+    }
+}
+
+/// A search result from the archive
+#[derive(Debug, Clone)]
+pub struct ArchiveHit {
+    pub record: ArchiveRecord,
+    pub score: f32,
+}
+
+/// Directory of compressed archive files
+pub struct Archive {
+    dir: PathBuf,
+}
+
+impl Archive {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `records` to a new `archive-<timestamp>.jsonl.gz` file.
+    ///
+    /// The file is complete on disk before this returns, so callers may
+    /// delete the originals afterwards.
+    pub fn write(&self, records: &[ArchiveRecord]) -> Result<Option<PathBuf>> {
+        if records.is_empty() {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create archive dir: {}", self.dir.display()))?;
+
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+        let mut path = self.dir.join(format!("archive-{}.jsonl.gz", stamp));
+        let mut n = 1;
+        while path.exists() {
+            path = self.dir.join(format!("archive-{}-{}.jsonl.gz", stamp, n));
+            n += 1;
+        }
+
+        let tmp = path.with_extension("gz.tmp");
+        let file = std::fs::File::create(&tmp)
+            .with_context(|| format!("Failed to create {}", tmp.display()))?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        for record in records {
+            serde_json::to_writer(&mut encoder, record)?;
+            encoder.write_all(b"\n")?;
+        }
+        encoder.finish()?.sync_all()?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Some(path))
+    }
+
+    /// Archive files, oldest first
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.dir)?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.to_string_lossy().ends_with(".jsonl.gz"))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Read every record of one archive file
+    pub fn read(path: &Path) -> Result<Vec<ArchiveRecord>> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut records = Vec::new();
+        for line in BufReader::new(GzDecoder::new(file)).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Corrupt record in archive {}", path.display()))?,
+            );
+        }
+        Ok(records)
+    }
+
+    /// Scan every archive file for the `limit` records most similar to
+    /// `query`.  Decompresses everything, so expect it to be slow on big
+    /// archives; unreadable files are skipped with a warning.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<ArchiveHit>> {
+        let engine = TfIdfEmbedding::new();
+        let query_embedding = engine.embed(query)?;
+
+        let mut hits = Vec::new();
+        for path in self.files()? {
+            let records = match Self::read(&path) {
+                Ok(records) => records,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable archive {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            for record in records {
+                let score = cosine_similarity(&query_embedding, &engine.embed(&record.text())?);
+                if score > 0.0 {
+                    hits.push(ArchiveHit { record, score });
+                }
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Archive session files in `sessions_dir` last modified before `cutoff`,
+    /// removing them once the archive file is written.  Returns the number
+    /// of sessions moved.
+    pub fn archive_sessions_dir(
+        &self,
+        sessions_dir: &Path,
+        cutoff: DateTime<Utc>,
+    ) -> Result<usize> {
+        if !sessions_dir.exists() {
+            return Ok(0);
+        }
+        let mut records = Vec::new();
+        let mut paths = Vec::new();
+        for path in std::fs::read_dir(sessions_dir)?.flatten().map(|e| e.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(modified) = path.metadata().and_then(|m| m.modified()) else {
+                continue;
+            };
+            let modified = DateTime::<Utc>::from(modified);
+            if modified >= cutoff {
+                continue;
+            }
+            let data = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+            let Some(data) = data else {
+                tracing::warn!("Not archiving unreadable session file {}", path.display());
+                continue;
+            };
+            let id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            records.push(ArchiveRecord::Session { id, modified, data });
+            paths.push(path);
+        }
+
+        self.write(&records)?;
+        for path in &paths {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove archived {}", path.display()))?;
+        }
+        Ok(paths.len())
+    }
+}
+
+/// Cutoff for "older than `months` months" measured from now
+pub fn cutoff_months_ago(months: u32) -> DateTime<Utc> {
+    Utc::now()
+        .checked_sub_months(chrono::Months::new(months))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(text: &str) -> ArchiveRecord {
+        ArchiveRecord::Memory {
+            text: text.to_string(),
+            importance: 1,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_write_read_and_search_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = Archive::new(dir.path().join("archive"));
+        assert!(archive.write(&[]).unwrap().is_none());
+
+        let records = vec![
+            memory("the deploy script needs AWS_PROFILE=prod"),
+            memory("user prefers tabs over spaces"),
+        ];
+        let first = archive.write(&records).unwrap().unwrap();
+        let second = archive
+            .write(&[memory("postgres runs on port 5433")])
+            .unwrap()
+            .unwrap();
+        assert_ne!(first, second);
+        assert_eq!(Archive::read(&first).unwrap(), records);
+
+        let hits = archive.search("which port does postgres use", 1).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].record.text().contains("5433"));
+    }
+
+    #[test]
+    fn test_archive_sessions_dir_moves_only_old_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = dir.path().join("sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let path = sessions.join("abc.json");
+        std::fs::write(
+            &path,
+            r#"{"id":"abc","messages":[{"content":"hello there"}]}"#,
+        )
+        .unwrap();
+
+        let archive = Archive::new(dir.path().join("archive"));
+        // Everything is newer than a cutoff in the past
+        assert_eq!(
+            archive
+                .archive_sessions_dir(&sessions, cutoff_months_ago(1))
+                .unwrap(),
+            0
+        );
+        assert!(path.exists());
+
+        let future = Utc::now() + chrono::Duration::days(1);
+        assert_eq!(archive.archive_sessions_dir(&sessions, future).unwrap(), 1);
+        assert!(!path.exists());
+
+        let records = Archive::read(&archive.files().unwrap()[0]).unwrap();
+        assert_eq!(records[0].kind(), "session");
+        assert!(records[0].text().contains("hello there"));
+    }
+}
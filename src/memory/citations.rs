@@ -0,0 +1,140 @@
+// Citations of recalled memories
+//
+// Recalled memories are injected into the prompt tagged `[mem-<id>]`, with
+// the id `/memory pin` and `/memory forget` take, and the model is asked to
+// cite the tag when its answer relies on a memory.  The REPL shows where
+// each cited memory came from under the answer, so it is clear why finch
+// "knows" something.
+//
+// Where a memory came from is kept in the `memory_sources` table, keyed by
+// its text: the session that produced it and when.  Memories written before
+// the table existed, imported or merged ones have no source and are shown
+// without one.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// A memory brought back by recall, with where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecalledMemory {
+    /// Store id, as taken by `/memory pin` and `/memory forget`
+    pub id: String,
+    pub text: String,
+    pub session_id: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl RecalledMemory {
+    /// The tag the memory is injected and cited with
+    pub fn tag(&self) -> String {
+        format!("[mem-{}]", self.id)
+    }
+
+    /// "recalled from 2026-10-02, session swift-otter", leaving out what
+    /// isn't known
+    pub fn provenance(&self) -> String {
+        match (&self.created_at, &self.session_id) {
+            (Some(at), Some(session)) => format!(
+                "recalled from {}, session {}",
+                at.format("%Y-%m-%d"),
+                session
+            ),
+            (Some(at), None) => format!("recalled from {}", at.format("%Y-%m-%d")),
+            (None, Some(session)) => format!("recalled from session {}", session),
+            (None, None) => "recalled from memory".to_string(),
+        }
+    }
+}
+
+/// Record where the memory with `text` came from; the first source wins
+pub(crate) fn record_source(
+    conn: &Connection,
+    text: &str,
+    session_id: Option<&str>,
+    role: &str,
+    project: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO memory_sources (text, session_id, created_at, role, project)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![text, session_id, Utc::now().timestamp(), role, project],
+    )?;
+    Ok(())
+}
+
+/// Where the memory with `text` came from: its session and creation time
+pub(crate) fn source(
+    conn: &Connection,
+    text: &str,
+) -> Result<(Option<String>, Option<DateTime<Utc>>)> {
+    let row: Option<(Option<String>, i64)> = conn
+        .query_row(
+            "SELECT session_id, created_at FROM memory_sources WHERE text = ?1",
+            [text],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(match row {
+        Some((session_id, at)) => (session_id, DateTime::from_timestamp(at, 0)),
+        None => (None, None),
+    })
+}
+
+/// The memories as a tagged block for the start of the user's message
+pub fn format_block(memories: &[RecalledMemory]) -> String {
+    let entries: Vec<String> = memories
+        .iter()
+        .map(|memory| format!("{} {}", memory.tag(), memory.text))
+        .collect();
+    format!(
+        "[Relevant memories from past sessions. When your answer relies on one, \
+         cite its tag, e.g. {}:\n\n{}]\n\n",
+        memories.first().map(|m| m.tag()).unwrap_or_default(),
+        entries.join("\n\n---\n\n")
+    )
+}
+
+/// The memories `answer` cites, in the order they are first cited
+pub fn cited<'a>(answer: &str, memories: &'a [RecalledMemory]) -> Vec<&'a RecalledMemory> {
+    let mut cited: Vec<(usize, &RecalledMemory)> = memories
+        .iter()
+        .filter_map(|memory| answer.find(&memory.tag()).map(|at| (at, memory)))
+        .collect();
+    cited.sort_by_key(|(at, _)| *at);
+    cited.into_iter().map(|(_, memory)| memory).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recalled(id: &str, session_id: Option<&str>) -> RecalledMemory {
+        RecalledMemory {
+            id: id.to_string(),
+            text: format!("memory {}", id),
+            session_id: session_id.map(str::to_string),
+            created_at: DateTime::from_timestamp(1_790_000_000, 0),
+        }
+    }
+
+    #[test]
+    fn test_cited_in_citation_order() {
+        let memories = [
+            recalled("3", Some("swift-otter")),
+            recalled("12", None),
+            recalled("1", None),
+        ];
+        let answer = "Deploy via staging [mem-12], as decided before [mem-3] [mem-12].";
+        let cited = cited(answer, &memories);
+        let ids: Vec<&str> = cited.iter().map(|m| m.id.as_str()).collect();
+        // [mem-1] is not cited: "[mem-12]" doesn't contain the tag "[mem-1]"
+        assert_eq!(ids, ["12", "3"]);
+
+        assert_eq!(
+            cited[1].provenance(),
+            "recalled from 2026-09-21, session swift-otter"
+        );
+        assert_eq!(cited[0].provenance(), "recalled from 2026-09-21");
+    }
+}
@@ -0,0 +1,127 @@
+// Cohere rerank API, for `[memory.rerank] backend = "cohere"`
+//
+// The query and the candidate texts go to `POST /v2/rerank`; the answer
+// scores each candidate by its index.  Used instead of the local
+// cross-encoder when there is no room for the model or a stronger reranker
+// is worth the round trip.  Memories often hold tool output, so the texts
+// are redacted like any other request leaving the machine.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::reranker::Reranker;
+
+const RERANK_URL: &str = "https://api.cohere.com/v2/rerank";
+
+/// Model used when `[memory.rerank]` doesn't name one
+pub const DEFAULT_MODEL: &str = "rerank-v3.5";
+
+pub struct CohereReranker {
+    client: reqwest::Client,
+    model: String,
+    api_key: String,
+}
+
+impl CohereReranker {
+    /// `api_key` falls back to `COHERE_API_KEY`; an error when neither is set
+    pub fn new(model: &str, api_key: Option<String>) -> Result<Self> {
+        let api_key = api_key
+            .or_else(|| std::env::var("COHERE_API_KEY").ok())
+            .filter(|key| !key.is_empty())
+            .context("memory.rerank: Cohere needs api_key or COHERE_API_KEY")?;
+        Ok(Self {
+            client: crate::http::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            model: model.to_string(),
+            api_key,
+        })
+    }
+}
+
+#[async_trait]
+impl Reranker for CohereReranker {
+    async fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let response = self
+            .client
+            .post(RERANK_URL)
+            .bearer_auth(&self.api_key)
+            .json(&request_body(&self.model, query, passages))
+            .send()
+            .await
+            .context("Cohere rerank request failed")?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let error = body["message"].as_str().unwrap_or("no details");
+            bail!("Cohere rerank returned {}: {}", status, error);
+        }
+        scores(&body, passages.len())
+    }
+}
+
+/// The rerank request, with secrets redacted from the query and passages
+fn request_body(model: &str, query: &str, passages: &[&str]) -> Value {
+    let redactor = crate::redact::active();
+    let documents: Vec<String> = passages.iter().map(|p| redactor.redact(p)).collect();
+    json!({ "model": model, "query": redactor.redact(query), "documents": documents })
+}
+
+/// One score per passage from a rerank response; passages it leaves out
+/// score lowest
+fn scores(body: &Value, count: usize) -> Result<Vec<f32>> {
+    let results = body["results"]
+        .as_array()
+        .context("Cohere rerank response has no results")?;
+    let mut scores = vec![f32::MIN; count];
+    for result in results {
+        let index = result["index"]
+            .as_u64()
+            .context("Rerank result without index")?;
+        let score = result["relevance_score"]
+            .as_f64()
+            .context("Rerank result without relevance_score")?;
+        if let Some(slot) = scores.get_mut(index as usize) {
+            *slot = score as f32;
+        }
+    }
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_follow_result_indexes() {
+        let body = json!({
+            "id": "abc",
+            "results": [
+                { "index": 2, "relevance_score": 0.9 },
+                { "index": 0, "relevance_score": 0.25 }
+            ]
+        });
+        assert_eq!(scores(&body, 3).unwrap(), vec![0.25, f32::MIN, 0.9]);
+        assert!(scores(&json!({ "message": "invalid api token" }), 3).is_err());
+    }
+
+    #[test]
+    fn test_request_is_redacted() {
+        let body = request_body(
+            DEFAULT_MODEL,
+            "deploy key?",
+            &[
+                "pushed with ghp_abcdefghijklmnopqrstuvwx12",
+                "no secrets here",
+            ],
+        );
+        assert_eq!(body["documents"][0], "pushed with [REDACTED]");
+        assert_eq!(body["documents"][1], "no secrets here");
+        assert_eq!(body["query"], "deploy key?");
+    }
+}
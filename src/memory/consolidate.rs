@@ -0,0 +1,175 @@
+// Near-duplicate consolidation
+//
+// The same preference or fact gets stated again and again across sessions,
+// and each restatement becomes its own leaf.  Recall then fills its few slots
+// with copies of one memory.  With `[memory.consolidate] enabled = true`, a
+// background job groups leaves whose embeddings are at least `similarity`
+// alike and merges each group into its newest member:
+//
+//   [memory.consolidate]
+//   enabled = true
+//   similarity = 0.95
+//   interval_hours = 24
+//
+// The kept memory takes the group's highest importance.  In the MemTree,
+// where a restatement is usually filed under the memory it repeats, such
+// leaves are merged into that parent instead; the kept node takes the
+// group's average embedding and its ancestors' aggregates are refreshed.
+// Flat remote stores just drop the rest of the group.  The job is
+// `memory` work for `[schedule]` quiet hours.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::embeddings::cosine_similarity;
+use super::{MemorySystem, StoredMemory};
+use crate::scheduling::{BackgroundJob, ScheduleConfig};
+
+/// `[memory.consolidate]` in config.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidateConfig {
+    /// Merge near-duplicates in the background while finch is running
+    #[serde(default)]
+    pub enabled: bool,
+    /// Cosine similarity at which two memories count as duplicates
+    #[serde(default = "default_similarity")]
+    pub similarity: f32,
+    /// Hours between consolidation runs
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_similarity() -> f32 {
+    crate::config::constants::DEFAULT_MEMORY_CONSOLIDATE_SIMILARITY
+}
+
+fn default_interval_hours() -> u64 {
+    crate::config::constants::DEFAULT_MEMORY_CONSOLIDATE_INTERVAL_HOURS
+}
+
+impl Default for ConsolidateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            similarity: default_similarity(),
+            interval_hours: default_interval_hours(),
+        }
+    }
+}
+
+impl ConsolidateConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_similarity(self.similarity)?;
+        if self.interval_hours == 0 {
+            bail!("memory.consolidate: interval_hours must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// Reject similarities that would merge unrelated memories or nothing at all
+pub fn validate_similarity(similarity: f32) -> Result<()> {
+    if !(0.5..=1.0).contains(&similarity) {
+        bail!(
+            "memory.consolidate: similarity must be between 0.5 and 1.0 (got {})",
+            similarity
+        );
+    }
+    Ok(())
+}
+
+/// What a consolidation run merged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsolidationReport {
+    /// Memories compared
+    pub scanned: usize,
+    /// Groups of near-duplicates found
+    pub groups: usize,
+    /// Memories merged into another and removed
+    pub merged: usize,
+}
+
+/// Groups of near-duplicates among `memories`, as indexes, each with at least
+/// two members and its newest member first.  A memory joins the first group
+/// whose newest member it is at least `similarity` alike; hidden (Discard)
+/// memories are left out.
+pub fn groups(memories: &[StoredMemory], similarity: f32) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..memories.len())
+        .filter(|&i| memories[i].importance > 0)
+        .collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(memories[i].created_at));
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in order {
+        let embedding = &memories[i].embedding;
+        match groups
+            .iter_mut()
+            .find(|g| cosine_similarity(&memories[g[0]].embedding, embedding) >= similarity)
+        {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Consolidate `memory` every `config.interval_hours` once `[schedule]`
+/// allows `memory` work, for as long as the task runs.  Failures are logged
+/// and retried at the next interval.
+pub async fn run_periodically(
+    memory: Arc<MemorySystem>,
+    config: ConsolidateConfig,
+    schedule: ScheduleConfig,
+) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(config.interval_hours.max(1) * 60 * 60));
+    loop {
+        interval.tick().await;
+        schedule.wait_until_allowed(BackgroundJob::Memory).await;
+        match memory.consolidate(config.similarity).await {
+            Ok(report) if report.merged > 0 => tracing::info!(
+                "Merged {} near-duplicate memories into {} ({} scanned)",
+                report.merged,
+                report.groups,
+                report.scanned
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Memory consolidation failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups_keep_the_newest_first() {
+        let memory = |id: &str, embedding: Vec<f32>, importance, created_at| StoredMemory {
+            id: id.to_string(),
+            text: id.to_string(),
+            embedding,
+            importance,
+            created_at,
+        };
+        let memories = vec![
+            memory("old", vec![1.0, 0.0], 1, 10),
+            memory("unrelated", vec![0.0, 1.0], 1, 20),
+            memory("new", vec![0.99, 0.1], 1, 30),
+            memory("hidden", vec![1.0, 0.0], 0, 40),
+        ];
+        assert_eq!(groups(&memories, 0.95), vec![vec![2, 0]]);
+        assert!(groups(&memories, 0.999).is_empty());
+
+        assert!(ConsolidateConfig::default().validate().is_ok());
+        assert!(validate_similarity(0.3).is_err());
+        assert!(validate_similarity(1.5).is_err());
+    }
+}
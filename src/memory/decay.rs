@@ -0,0 +1,130 @@
+// Forgetting policy for old, unimportant memories
+//
+// Recall gets noisier as years of small talk pile up next to the decisions
+// that matter.  With `[memory.decay] horizon_days` set, a compaction job
+// forgets memories that are old for their importance:
+//
+//   Normal    after horizon_days
+//   High      after 2 × horizon_days
+//   Critical  never
+//
+// In the MemTree a forgotten leaf is folded into its parent first (its first
+// line is appended to the parent's text, whose embedding already includes
+// it), so the gist stays recallable while the tree and the database stay
+// bounded.  Flat remote stores have no parents and simply drop the memory.
+// Conversation rows are left to the archive policy.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::MemorySystem;
+
+/// `[memory.decay]` in config.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecayConfig {
+    /// Forget Normal memories older than this many days (0 = never forget)
+    #[serde(default)]
+    pub horizon_days: u32,
+    /// Hours between compaction runs while finch is running
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_interval_hours() -> u64 {
+    crate::config::constants::DEFAULT_MEMORY_DECAY_INTERVAL_HOURS
+}
+
+impl Default for DecayConfig {
+    fn default() -> Self {
+        Self {
+            horizon_days: 0,
+            interval_hours: default_interval_hours(),
+        }
+    }
+}
+
+impl DecayConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_hours == 0 {
+            bail!("memory.decay: interval_hours must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// Whether old memories are forgotten at all
+    pub fn enabled(&self) -> bool {
+        self.horizon_days > 0
+    }
+
+    /// The horizon in seconds
+    pub fn horizon_secs(&self) -> i64 {
+        i64::from(self.horizon_days) * 24 * 60 * 60
+    }
+}
+
+/// Whether a memory of `importance` that is `age_secs` old is past the
+/// `horizon_secs` of a Normal memory, scaled by its importance
+pub fn forgets(importance: u8, age_secs: i64, horizon_secs: i64) -> bool {
+    match importance {
+        0 => true,
+        1 | 2 => age_secs > horizon_secs * i64::from(importance),
+        _ => false,
+    }
+}
+
+/// What a compaction run forgot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecayReport {
+    /// Memories removed from the store
+    pub forgotten: usize,
+    /// Of those, the ones whose gist was kept in their parent
+    pub folded: usize,
+}
+
+/// Compact `memory` now and then every `config.interval_hours`, for as long
+/// as the task runs.  Failures are logged and retried at the next interval.
+pub async fn run_periodically(memory: Arc<MemorySystem>, config: DecayConfig) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(config.interval_hours.max(1) * 60 * 60));
+    loop {
+        interval.tick().await;
+        match memory.decay(config.horizon_secs()).await {
+            Ok(report) if report.forgotten > 0 => tracing::info!(
+                "Forgot {} old memories ({} folded into their parents)",
+                report.forgotten,
+                report.folded
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Memory compaction failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_importance_scales_the_horizon() {
+        let day = 24 * 60 * 60;
+        let horizon = DecayConfig {
+            horizon_days: 30,
+            ..Default::default()
+        }
+        .horizon_secs();
+        assert_eq!(horizon, 30 * day);
+
+        assert!(!forgets(1, 29 * day, horizon));
+        assert!(forgets(1, 31 * day, horizon));
+        assert!(!forgets(2, 31 * day, horizon));
+        assert!(forgets(2, 61 * day, horizon));
+        assert!(!forgets(3, 10_000 * day, horizon));
+        assert!(forgets(0, 0, horizon));
+    }
+}
@@ -0,0 +1,554 @@
+// Dependency documentation index ("ask-docs")
+//
+// Reads the Cargo.toml / package.json of a project, fetches documentation for
+// every dependency (docs.rs pages for crates, the registry README for npm
+// packages) and stores it in a separate MemTree database (~/.finch/docs.db).
+// The `doc_lookup` tool queries it so the model can check real APIs for the
+// exact library versions the project uses instead of guessing.
+
+use super::{MemoryConfig, MemorySystem};
+use anyhow::{Context, Result};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Target size of a stored chunk, in characters.
+const CHUNK_CHARS: usize = 1200;
+
+/// Package ecosystem a dependency comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// Rust crate (docs.rs)
+    Crate,
+    /// npm package (registry README)
+    Npm,
+}
+
+impl Ecosystem {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Crate => "crate",
+            Self::Npm => "npm",
+        }
+    }
+}
+
+/// A dependency declared in a project manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    /// Requested version with range operators stripped (`None` = latest)
+    pub version: Option<String>,
+    pub ecosystem: Ecosystem,
+}
+
+impl Dependency {
+    /// `name@version` label prefixed to every stored chunk.
+    pub fn tag(&self) -> String {
+        format!(
+            "{}@{}",
+            self.name,
+            self.version.as_deref().unwrap_or("latest")
+        )
+    }
+
+    fn metadata_key(&self) -> String {
+        format!("docs:{}:{}", self.ecosystem.as_str(), self.name)
+    }
+}
+
+/// Result of indexing one dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexOutcome {
+    /// Docs were fetched and stored
+    Indexed { pages: usize, chunks: usize },
+    /// Same version already indexed (use `refresh` to force)
+    Skipped,
+}
+
+/// Strip semver range operators; `None` for wildcards and complex ranges.
+fn normalize_version(raw: &str) -> Option<String> {
+    let v = raw
+        .trim()
+        .trim_start_matches(['^', '~', '=', '>', '<', 'v', ' ']);
+    if v.is_empty() || v.contains(['*', 'x', ',', '|', ' ']) || v == "latest" {
+        return None;
+    }
+    Some(v.to_string())
+}
+
+/// Parse `[dependencies]`, `[workspace.dependencies]` and
+/// `[target.*.dependencies]` from a Cargo.toml.  Path-only dependencies are
+/// skipped — they are local crates with nothing on docs.rs.
+pub fn parse_cargo_toml(src: &str) -> Result<Vec<Dependency>> {
+    let doc: toml::Value = toml::from_str(src).context("Invalid Cargo.toml")?;
+
+    let mut tables = Vec::new();
+    tables.extend(doc.get("dependencies"));
+    tables.extend(doc.get("workspace").and_then(|w| w.get("dependencies")));
+    if let Some(targets) = doc.get("target").and_then(|t| t.as_table()) {
+        tables.extend(targets.values().filter_map(|t| t.get("dependencies")));
+    }
+
+    let mut deps = Vec::new();
+    for table in tables.iter().filter_map(|t| t.as_table()) {
+        for (key, spec) in table {
+            let (name, version) = match spec {
+                toml::Value::String(v) => (key.clone(), normalize_version(v)),
+                toml::Value::Table(t) => {
+                    let version = t.get("version").and_then(|v| v.as_str());
+                    if version.is_none() && (t.contains_key("path") || t.contains_key("git")) {
+                        continue;
+                    }
+                    let name = t
+                        .get("package")
+                        .and_then(|p| p.as_str())
+                        .unwrap_or(key)
+                        .to_string();
+                    (name, version.and_then(normalize_version))
+                }
+                _ => continue,
+            };
+            deps.push(Dependency {
+                name,
+                version,
+                ecosystem: Ecosystem::Crate,
+            });
+        }
+    }
+    Ok(deps)
+}
+
+/// Parse `dependencies` and `devDependencies` from a package.json.  Local and
+/// git specifiers (`file:`, `link:`, `workspace:`, URLs) are skipped.
+pub fn parse_package_json(src: &str) -> Result<Vec<Dependency>> {
+    let doc: serde_json::Value = serde_json::from_str(src).context("Invalid package.json")?;
+
+    let mut deps = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        let Some(map) = doc.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (name, spec) in map {
+            let spec = spec.as_str().unwrap_or("");
+            if spec.contains(':') || spec.contains('/') {
+                continue;
+            }
+            deps.push(Dependency {
+                name: name.clone(),
+                version: normalize_version(spec),
+                ecosystem: Ecosystem::Npm,
+            });
+        }
+    }
+    Ok(deps)
+}
+
+/// Collect dependencies from every manifest found in `dir`, de-duplicated.
+pub fn discover_dependencies(dir: &Path) -> Result<Vec<Dependency>> {
+    let mut deps = Vec::new();
+
+    let cargo = dir.join("Cargo.toml");
+    if cargo.exists() {
+        let src = std::fs::read_to_string(&cargo)
+            .with_context(|| format!("Failed to read {}", cargo.display()))?;
+        deps.extend(parse_cargo_toml(&src)?);
+    }
+
+    let package = dir.join("package.json");
+    if package.exists() {
+        let src = std::fs::read_to_string(&package)
+            .with_context(|| format!("Failed to read {}", package.display()))?;
+        deps.extend(parse_package_json(&src)?);
+    }
+
+    let mut seen = HashSet::new();
+    deps.retain(|d| seen.insert((d.ecosystem, d.name.clone())));
+    Ok(deps)
+}
+
+/// Split text into chunks of at most ~`max_chars`, breaking on line
+/// boundaries where possible.  Blank runs are collapsed.
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if !current.is_empty() && current.len() + line.len() + 1 > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if line.len() > max_chars {
+            // A single very long line: hard-split on char boundaries.
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Visible text of a rustdoc page's main content, one block per line.
+fn extract_rustdoc_text(html: &scraper::Html) -> String {
+    let main = scraper::Selector::parse("#main-content").expect("static selector");
+    let body = scraper::Selector::parse("body").expect("static selector");
+    let root = html
+        .select(&main)
+        .next()
+        .or_else(|| html.select(&body).next());
+    let Some(root) = root else {
+        return String::new();
+    };
+    let raw: String = root.text().collect();
+    raw.lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Links from a rustdoc page's main content that stay inside `root`.
+fn rustdoc_links(html: &scraper::Html, page: &reqwest::Url, root: &str) -> Vec<reqwest::Url> {
+    let anchors = scraper::Selector::parse("#main-content a[href]").expect("static selector");
+    html.select(&anchors)
+        .filter_map(|a| a.value().attr("href"))
+        .filter(|href| !href.starts_with('#'))
+        .filter_map(|href| page.join(href).ok())
+        .map(|mut url| {
+            url.set_fragment(None);
+            url
+        })
+        .filter(|url| url.as_str().starts_with(root) && url.path().ends_with(".html"))
+        .filter(|url| !url.path().ends_with("/all.html"))
+        .collect()
+}
+
+/// Dependency documentation store backed by its own `MemorySystem`.
+pub struct DocsIndex {
+    memory: Arc<MemorySystem>,
+    client: reqwest::Client,
+}
+
+impl DocsIndex {
+    /// Location of the docs database.
+    pub fn db_path() -> PathBuf {
+        MemoryConfig::docs().db_path
+    }
+
+    /// Whether any docs have been indexed on this machine.
+    pub fn exists() -> bool {
+        Self::db_path().exists()
+    }
+
+    /// Open (or create) the docs database.
+    pub fn open() -> Result<Self> {
+        Ok(Self::with_memory(Arc::new(MemorySystem::new(
+            MemoryConfig::docs(),
+        )?)))
+    }
+
+    /// Wrap an existing memory system (used by tests).
+    pub fn with_memory(memory: Arc<MemorySystem>) -> Self {
+        Self {
+            memory,
+            client: crate::http::builder()
+                .timeout(std::time::Duration::from_secs(20))
+                .user_agent(concat!("finch/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .expect("reqwest client"),
+        }
+    }
+
+    /// Fetch and store docs for `dep`, following up to `max_pages` pages.
+    pub async fn index(
+        &self,
+        dep: &Dependency,
+        max_pages: usize,
+        refresh: bool,
+    ) -> Result<IndexOutcome> {
+        let key = dep.metadata_key();
+        let tag = dep.tag();
+        if !refresh && self.memory.get_metadata(&key).await?.as_deref() == Some(tag.as_str()) {
+            return Ok(IndexOutcome::Skipped);
+        }
+
+        let pages = match dep.ecosystem {
+            Ecosystem::Crate => self.fetch_crate_pages(dep, max_pages).await?,
+            Ecosystem::Npm => self.fetch_npm_readme(dep).await?,
+        };
+
+        let mut chunks = Vec::new();
+        for (title, text) in &pages {
+            chunks.extend(
+                chunk_text(text, CHUNK_CHARS)
+                    .into_iter()
+                    .map(|c| format!("[docs:{} {}]\n{}", tag, title, c)),
+            );
+        }
+        let stored = self.replace(dep, &chunks).await?;
+
+        Ok(IndexOutcome::Indexed {
+            pages: pages.len(),
+            chunks: stored,
+        })
+    }
+
+    /// Store `chunks` as the docs of `dep`, dropping those of any version
+    /// indexed before so lookups don't mix versions.
+    async fn replace(&self, dep: &Dependency, chunks: &[String]) -> Result<usize> {
+        let tag = dep.tag();
+        self.memory
+            .forget_documents(&format!("{}@", dep.name))
+            .await?;
+        let stored = self.memory.insert_documents(&tag, chunks).await?;
+        self.memory.set_metadata(&dep.metadata_key(), &tag).await?;
+        Ok(stored)
+    }
+
+    /// Semantic search over indexed docs, optionally restricted to one library.
+    pub async fn lookup(
+        &self,
+        query: &str,
+        library: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let Some(library) = library else {
+            return self.memory.query(query, Some(limit)).await;
+        };
+        // Over-fetch, then keep only chunks tagged with the requested library.
+        let prefix = format!("[docs:{}@", library.to_lowercase());
+        let results = self.memory.query(query, Some(limit * 8)).await?;
+        Ok(results
+            .into_iter()
+            .filter(|r| r.to_lowercase().starts_with(&prefix))
+            .take(limit)
+            .collect())
+    }
+
+    /// Crawl docs.rs starting at the crate root, breadth-first.
+    async fn fetch_crate_pages(
+        &self,
+        dep: &Dependency,
+        max_pages: usize,
+    ) -> Result<Vec<(String, String)>> {
+        let version = dep.version.as_deref().unwrap_or("latest");
+        let lib = dep.name.replace('-', "_");
+        let start = format!(
+            "https://docs.rs/{}/{}/{}/index.html",
+            dep.name, version, lib
+        );
+
+        let mut pages = Vec::new();
+        let mut queue = VecDeque::from([reqwest::Url::parse(&start)?]);
+        let mut seen = HashSet::new();
+        let mut root: Option<String> = None;
+
+        while let Some(url) = queue.pop_front() {
+            if pages.len() >= max_pages {
+                break;
+            }
+            if !seen.insert(url.to_string()) {
+                continue;
+            }
+            let response = match self.client.get(url.clone()).send().await {
+                Ok(r) if r.status().is_success() => r,
+                // The crate root must load; later pages are best-effort.
+                Ok(r) if root.is_none() => {
+                    anyhow::bail!("docs.rs returned {} for {}", r.status(), dep.tag())
+                }
+                Err(e) if root.is_none() => {
+                    return Err(e).with_context(|| format!("Failed to fetch {}", start))
+                }
+                _ => continue,
+            };
+            // docs.rs redirects "latest"/partial versions; crawl under the
+            // resolved crate root.
+            let final_url = response.url().clone();
+            let root_prefix = root
+                .get_or_insert_with(|| {
+                    let s = final_url.as_str();
+                    s[..=s.rfind('/').unwrap_or(s.len() - 1)].to_string()
+                })
+                .clone();
+
+            let body = response.text().await?;
+            let (title, text, links) = {
+                let html = scraper::Html::parse_document(&body);
+                let title_sel = scraper::Selector::parse("title").expect("static selector");
+                let title = html
+                    .select(&title_sel)
+                    .next()
+                    .map(|t| t.text().collect::<String>())
+                    .unwrap_or_default();
+                let title = title.split(" - ").next().unwrap_or("").trim().to_string();
+                (
+                    title,
+                    extract_rustdoc_text(&html),
+                    rustdoc_links(&html, &final_url, &root_prefix),
+                )
+            };
+            if !text.is_empty() {
+                pages.push((title, text));
+            }
+            queue.extend(links);
+        }
+        Ok(pages)
+    }
+
+    /// README from the npm registry (markdown, stored as-is).
+    async fn fetch_npm_readme(&self, dep: &Dependency) -> Result<Vec<(String, String)>> {
+        let url = format!("https://registry.npmjs.org/{}", dep.name);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "npm registry returned {} for {}",
+                response.status(),
+                dep.name
+            );
+        }
+        let doc: serde_json::Value = response.json().await?;
+        let readme = doc["readme"].as_str().unwrap_or("").to_string();
+        if readme.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![("README".to_string(), readme)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_cargo_toml() {
+        let deps = parse_cargo_toml(
+            r#"
+            [package]
+            name = "demo"
+
+            [dependencies]
+            serde = { version = "^1.0", features = ["derive"] }
+            tokio = "1.35"
+            local = { path = "../local" }
+            http02 = { package = "http", version = "0.2" }
+
+            [target.'cfg(unix)'.dependencies]
+            nix = "0.29"
+            "#,
+        )
+        .unwrap();
+
+        let find = |n: &str| deps.iter().find(|d| d.name == n);
+        assert_eq!(find("serde").unwrap().version.as_deref(), Some("1.0"));
+        assert_eq!(find("tokio").unwrap().tag(), "tokio@1.35");
+        assert_eq!(find("http").unwrap().version.as_deref(), Some("0.2"));
+        assert!(find("nix").is_some());
+        assert!(find("local").is_none(), "path deps have no docs.rs page");
+    }
+
+    #[test]
+    fn test_parse_package_json() {
+        let deps = parse_package_json(
+            r#"{
+                "dependencies": { "express": "^4.18.2", "mine": "file:../mine" },
+                "devDependencies": { "vitest": "*" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(deps.len(), 2);
+        assert_eq!(deps[0].tag(), "express@4.18.2");
+        assert_eq!(deps[1].tag(), "vitest@latest");
+    }
+
+    #[test]
+    fn test_chunk_text_respects_limit() {
+        let text = (0..50)
+            .map(|i| format!("line number {}", i))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let chunks = chunk_text(&text, 100);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 100));
+        assert!(chunks[0].starts_with("line number 0"));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_filters_by_library() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = Arc::new(MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?);
+        memory
+            .insert_documents(
+                "serde@1.0",
+                &["[docs:serde@1.0 Serialize]\nA data structure that can be serialized".into()],
+            )
+            .await?;
+        memory
+            .insert_documents(
+                "tokio@1.35",
+                &["[docs:tokio@1.35 spawn]\nSpawns a new asynchronous task".into()],
+            )
+            .await?;
+
+        let index = DocsIndex::with_memory(memory);
+        let hits = index.lookup("spawn a task", Some("tokio"), 3).await?;
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].starts_with("[docs:tokio@1.35"));
+
+        let none = index.lookup("spawn a task", Some("axum"), 3).await?;
+        assert!(none.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reindex_replaces_older_version() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = Arc::new(MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?);
+        let index = DocsIndex::with_memory(memory);
+        let dep = |version: &str| Dependency {
+            name: "tokio".into(),
+            version: Some(version.into()),
+            ecosystem: Ecosystem::Crate,
+        };
+
+        index
+            .replace(
+                &dep("1.35"),
+                &["[docs:tokio@1.35 spawn]\nSpawns a new asynchronous task".into()],
+            )
+            .await?;
+        index
+            .replace(
+                &dep("1.40"),
+                &["[docs:tokio@1.40 spawn]\nSpawns a new asynchronous task".into()],
+            )
+            .await?;
+
+        let hits = index.lookup("spawn a task", Some("tokio"), 3).await?;
+        assert_eq!(hits.len(), 1, "{:?}", hits);
+        assert!(hits[0].starts_with("[docs:tokio@1.40"));
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+// Episode summaries of whole stretches of a session
+//
+// Per-turn memories are snippets: recall can bring back "use the staging
+// bucket" without what the session was trying to do or where it got to.
+// When older messages slide out of the context window, and again when the
+// session ends, the messages not yet covered are summarized into an episode
+// (its goal, the decisions made, the files and other artifacts touched, and
+// the questions left open) and stored as one High-importance memory.
+//
+// Episodes are on by default; `[memory] episodes = false` turns them off.
+// Incognito sessions never write them.
+
+use serde::Deserialize;
+
+/// Fewest messages worth an episode at the end of a session
+pub const MIN_EPISODE_MESSAGES: usize = 4;
+
+/// Characters of the transcript shown to the summarizer; the end is kept
+/// when it is longer, since that is where the session got to
+const TRANSCRIPT_CHARS: usize = 24_000;
+
+/// Entries kept per list
+const MAX_ITEMS: usize = 8;
+
+/// What one stretch of a session was about
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Episode {
+    pub goal: String,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    /// Files, commands, branches and the like that were touched
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+    #[serde(default)]
+    pub open_questions: Vec<String>,
+}
+
+impl Episode {
+    /// The episode as one memory: a line per field, lists joined with "; "
+    pub fn to_memory_text(&self) -> String {
+        let mut text = format!("Session episode: {}", self.goal);
+        for (label, items) in [
+            ("Decisions", &self.decisions),
+            ("Artifacts", &self.artifacts),
+            ("Open questions", &self.open_questions),
+        ] {
+            if !items.is_empty() {
+                text.push_str(&format!("\n{}: {}", label, items.join("; ")));
+            }
+        }
+        text
+    }
+}
+
+/// Prompt asking for the episode in `transcript` (see
+/// `format_messages_for_summary` in the CLI)
+pub fn summary_prompt(transcript: &str) -> String {
+    let count = transcript.chars().count();
+    let transcript: String = transcript
+        .chars()
+        .skip(count.saturating_sub(TRANSCRIPT_CHARS))
+        .collect();
+    format!(
+        "Summarize this stretch of a coding session for a memory that later sessions can \
+         recall. Answer with a JSON object only:\n\
+         {{\"goal\": \"what the user was trying to do, one sentence\", \
+         \"decisions\": [\"choices that were settled\"], \
+         \"artifacts\": [\"files, commands or branches that were created or changed\"], \
+         \"open_questions\": [\"what was left unresolved\"]}}\n\
+         Use short phrases and leave a list empty when there is nothing for it.\n\n\
+         Transcript:\n{}",
+        transcript
+    )
+}
+
+/// The episode in the summarizer's answer.  Text around the JSON object
+/// (code fences, a preamble) is ignored; `None` when there is no object or
+/// its goal is empty.
+pub fn parse_episode(response: &str) -> Option<Episode> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    let mut episode: Episode = serde_json::from_str(&response[start..=end]).ok()?;
+    episode.goal = episode.goal.trim().to_string();
+    if episode.goal.is_empty() {
+        return None;
+    }
+    for items in [
+        &mut episode.decisions,
+        &mut episode.artifacts,
+        &mut episode.open_questions,
+    ] {
+        items.retain(|item| !item.trim().is_empty());
+        items.truncate(MAX_ITEMS);
+    }
+    Some(episode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_episode() {
+        let response = "Here is the summary:\n```json\n{\
+            \"goal\": \" Move deploys to fly.io \",\
+            \"decisions\": [\"use the staging app first\", \"\"],\
+            \"artifacts\": [\"fly.toml\", \"deploy.sh\"]\
+        }\n```";
+        let episode = parse_episode(response).unwrap();
+        assert_eq!(episode.goal, "Move deploys to fly.io");
+        assert_eq!(episode.decisions, ["use the staging app first"]);
+        assert!(episode.open_questions.is_empty());
+        assert_eq!(
+            episode.to_memory_text(),
+            "Session episode: Move deploys to fly.io\n\
+             Decisions: use the staging app first\n\
+             Artifacts: fly.toml; deploy.sh"
+        );
+
+        assert_eq!(parse_episode("{\"goal\": \"\"}"), None);
+        assert_eq!(parse_episode("Nothing happened."), None);
+    }
+}
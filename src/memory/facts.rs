@@ -0,0 +1,306 @@
+// Structured facts extracted from conversations
+//
+// MemTree recall is fuzzy: it finds text that looks like the query, which
+// misses "the user prefers anyhow" when the query is "add error handling to
+// the parser".  After each assistant turn the local model is asked for the
+// durable facts, preferences and decisions in the exchange.  They are kept
+// in the `facts` table under a short entity key (`error_handling`,
+// `deploy_target`), so a newer fact about the same subject replaces the older
+// one instead of both being recalled.  Facts whose key or text shares terms
+// with the query are injected ahead of the MemTree recall.
+
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Facts kept from one exchange
+pub const MAX_FACTS_PER_TURN: usize = 5;
+
+/// Facts injected ahead of recall
+pub const MAX_FACTS_INJECTED: usize = 5;
+
+/// Longest fact statement kept
+const MAX_FACT_CHARS: usize = 200;
+
+/// Longest entity key kept
+const MAX_ENTITY_CHARS: usize = 48;
+
+/// Characters of each side of the exchange shown to the extractor
+const EXCERPT_CHARS: usize = 4000;
+
+/// Words too common to tie a query to a fact
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "can", "her", "was", "one", "our",
+    "out", "use", "uses", "used", "using", "with", "this", "that", "from", "have", "what", "when",
+    "which", "will", "would", "should", "could", "does", "into", "there", "their", "them", "then",
+    "than", "about", "user", "users", "prefers", "prefer", "make", "how", "why", "who", "add",
+];
+
+/// What a fact records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FactKind {
+    /// How the user likes things done ("prefers anyhow over thiserror")
+    Preference,
+    /// Something settled ("deploys go to fly.io")
+    Decision,
+    /// Anything else durable about the user's projects or environment
+    Fact,
+}
+
+impl FactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Preference => "preference",
+            Self::Decision => "decision",
+            Self::Fact => "fact",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "preference" => Self::Preference,
+            "decision" => Self::Decision,
+            _ => Self::Fact,
+        }
+    }
+}
+
+/// A fact to remember
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewFact {
+    /// Normalized key (see `entity_key`); one fact is kept per key
+    pub entity: String,
+    pub kind: FactKind,
+    pub statement: String,
+}
+
+/// A remembered fact
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fact {
+    pub entity: String,
+    pub kind: FactKind,
+    pub statement: String,
+    pub session_id: Option<String>,
+    /// Unix seconds of the last time the fact was stated
+    pub updated_at: i64,
+}
+
+/// `raw` as a lowercase snake_case key, or `None` if nothing is left
+pub fn entity_key(raw: &str) -> Option<String> {
+    let mut key = String::new();
+    for c in raw.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            key.push(c);
+        } else if !key.is_empty() && !key.ends_with('_') {
+            key.push('_');
+        }
+    }
+    let key: String = key
+        .trim_end_matches('_')
+        .chars()
+        .take(MAX_ENTITY_CHARS)
+        .collect();
+    let key = key.trim_end_matches('_').to_string();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Prompt asking the local model for the durable facts in one exchange
+pub fn extraction_prompt(user: &str, assistant: &str) -> String {
+    let excerpt = |text: &str| text.chars().take(EXCERPT_CHARS).collect::<String>();
+    format!(
+        "Extract the durable facts from this exchange: the user's preferences, decisions \
+         that were made, and facts about their projects or environment that will still \
+         matter in a later session. Skip details of this one task and anything uncertain.\n\n\
+         Answer with a JSON array only, or [] if there is nothing. Each item is \
+         {{\"entity\": \"short_snake_case_subject\", \"kind\": \"preference\" | \"decision\" | \
+         \"fact\", \"fact\": \"one short sentence\"}}. Use the same entity for the same \
+         subject, such as \"error_handling\" or \"deploy_target\".\n\n\
+         User: {}\n\nAssistant: {}",
+        excerpt(user),
+        excerpt(assistant)
+    )
+}
+
+/// Facts in the extractor's answer.  Text around the JSON array (code
+/// fences, a preamble) is ignored, as are malformed items; `None` when
+/// there is no array at all.
+pub fn parse_facts(response: &str) -> Option<Vec<NewFact>> {
+    #[derive(Deserialize)]
+    struct RawFact {
+        entity: String,
+        #[serde(default)]
+        kind: String,
+        fact: String,
+    }
+
+    let start = response.find('[')?;
+    let end = response.rfind(']')?;
+    if end < start {
+        return None;
+    }
+    let items: Vec<serde_json::Value> = serde_json::from_str(&response[start..=end]).ok()?;
+    let mut seen = HashSet::new();
+    Some(
+        items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value::<RawFact>(item).ok())
+            .filter_map(|raw| {
+                let statement = raw.fact.trim();
+                if statement.is_empty() || statement.chars().count() > MAX_FACT_CHARS {
+                    return None;
+                }
+                Some(NewFact {
+                    entity: entity_key(&raw.entity)?,
+                    kind: FactKind::parse(&raw.kind),
+                    statement: statement.to_string(),
+                })
+            })
+            .filter(|fact| seen.insert(fact.entity.clone()))
+            .take(MAX_FACTS_PER_TURN)
+            .collect(),
+    )
+}
+
+/// Store `facts`, replacing older facts with the same entity key
+pub fn upsert(conn: &Connection, facts: &[NewFact], session_id: Option<&str>) -> Result<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    for fact in facts {
+        tx.execute(
+            "INSERT OR REPLACE INTO facts (entity, kind, statement, session_id, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                fact.entity,
+                fact.kind.as_str(),
+                fact.statement,
+                session_id,
+                now
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(facts.len())
+}
+
+/// Every stored fact, newest first
+pub fn all(conn: &Connection) -> Result<Vec<Fact>> {
+    let mut stmt = conn.prepare(
+        "SELECT entity, kind, statement, session_id, updated_at
+         FROM facts ORDER BY updated_at DESC, entity ASC",
+    )?;
+    let facts = stmt
+        .query_map([], |row| {
+            Ok(Fact {
+                entity: row.get(0)?,
+                kind: FactKind::parse(&row.get::<_, String>(1)?),
+                statement: row.get(2)?,
+                session_id: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(facts)
+}
+
+/// Significant words of `text`, with a plural `s` dropped
+fn terms(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 3 && !STOPWORDS.contains(w))
+        .map(|w| {
+            w.strip_suffix('s')
+                .filter(|stem| stem.len() >= 3)
+                .unwrap_or(w)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Up to `limit` of `facts` sharing terms with `query`, the most shared
+/// first and the newest among equals (`facts` come newest first)
+pub fn matching(facts: Vec<Fact>, query: &str, limit: usize) -> Vec<Fact> {
+    let query = terms(query);
+    let mut scored: Vec<(usize, Fact)> = facts
+        .into_iter()
+        .map(|fact| {
+            let text = format!("{} {}", fact.entity.replace('_', " "), fact.statement);
+            (terms(&text).intersection(&query).count(), fact)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, fact)| fact)
+        .collect()
+}
+
+/// The facts as a block to put ahead of recalled memories
+pub fn format_block(facts: &[Fact]) -> String {
+    let lines: Vec<String> = facts
+        .iter()
+        .map(|fact| format!("- {} ({})", fact.statement, fact.kind.as_str()))
+        .collect();
+    format!("[Known facts and preferences:\n{}]\n\n", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_facts() {
+        let response = "Here you go:\n```json\n[\
+            {\"entity\": \"Error handling\", \"kind\": \"preference\", \"fact\": \"User prefers anyhow\"},\
+            {\"entity\": \"deploy target\", \"kind\": \"decision\", \"fact\": \"Deploy target is fly.io\"},\
+            {\"entity\": \"error-handling\", \"kind\": \"fact\", \"fact\": \"duplicate key\"},\
+            {\"entity\": \"!!!\", \"fact\": \"no key\"},\
+            {\"kind\": \"fact\"}\
+        ]\n```";
+        let facts = parse_facts(response).unwrap();
+        assert_eq!(
+            facts,
+            vec![
+                NewFact {
+                    entity: "error_handling".to_string(),
+                    kind: FactKind::Preference,
+                    statement: "User prefers anyhow".to_string(),
+                },
+                NewFact {
+                    entity: "deploy_target".to_string(),
+                    kind: FactKind::Decision,
+                    statement: "Deploy target is fly.io".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parse_facts("[]"), Some(Vec::new()));
+        assert_eq!(parse_facts("Nothing durable here."), None);
+    }
+
+    #[test]
+    fn test_matching_prefers_shared_terms_then_recency() {
+        let fact = |entity: &str, statement: &str, updated_at| Fact {
+            entity: entity.to_string(),
+            kind: FactKind::Fact,
+            statement: statement.to_string(),
+            session_id: None,
+            updated_at,
+        };
+        let facts = vec![
+            fact("error_handling", "User prefers anyhow for errors", 30),
+            fact("deploy_target", "Deploys go to fly.io", 20),
+            fact("staging_deploy", "Staging deploys need the VPN", 10),
+        ];
+
+        let hits = matching(facts.clone(), "how do we deploy to staging?", 5);
+        let keys: Vec<_> = hits.iter().map(|f| f.entity.as_str()).collect();
+        assert_eq!(keys, ["staging_deploy", "deploy_target"]);
+
+        let hits = matching(facts.clone(), "add error handling to the parser", 5);
+        assert_eq!(hits[0].entity, "error_handling");
+        assert!(matching(facts, "what is the weather", 5).is_empty());
+    }
+}
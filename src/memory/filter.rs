@@ -0,0 +1,168 @@
+// Filters for memory queries
+//
+// Recall normally considers every memory.  `MemorySystem::query_where` and
+// `search_where` can narrow it by where a memory came from, when, and how
+// important it is.  `/memory search` takes them as `key:value` words
+// alongside the query:
+//
+//   /memory search staging deploy session:swift-otter role:user
+//   /memory search postgres port project:finch after:2026-09-01 importance:high
+//
+// and `POST /v1/memory/query` as JSON fields of the same names.
+//
+// Session, role and project are answered in SQL from the `memory_sources`
+// table, date and importance by the store, all before any memory is scored
+// against the query.  Memories without a source (see `citations`) only
+// match filters on date and importance.
+
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, NaiveTime};
+use rusqlite::{types::Value, Connection};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use super::quality::MemoryImportance;
+use super::store::SearchFilter;
+
+/// What a memory query may match; every field left `None` matches anything
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct MemoryFilter {
+    pub session_id: Option<String>,
+    /// "user", "assistant", "system" or "episode"
+    pub role: Option<String>,
+    /// The directory the REPL ran in, or its last component
+    pub project: Option<String>,
+    /// Created on or after this day (UTC)
+    pub after: Option<NaiveDate>,
+    /// Created before this day (UTC)
+    pub before: Option<NaiveDate>,
+    /// Lowest importance tier that matches: 1 Normal, 2 High, 3 Critical
+    pub min_importance: Option<u8>,
+}
+
+impl MemoryFilter {
+    /// Split `/memory search` arguments into the query and the filter given
+    /// by its `session:`, `role:`, `project:`, `after:`, `before:` and
+    /// `importance:` words
+    pub fn parse(args: &str) -> Result<(String, Self)> {
+        let mut filter = Self::default();
+        let mut query = Vec::new();
+        for word in args.split_whitespace() {
+            let Some((key, value)) = word.split_once(':').filter(|(_, v)| !v.is_empty()) else {
+                query.push(word);
+                continue;
+            };
+            match key {
+                "session" => filter.session_id = Some(value.to_string()),
+                "role" => filter.role = Some(value.to_string()),
+                "project" => filter.project = Some(value.to_string()),
+                "after" => filter.after = Some(parse_day(value)?),
+                "before" => filter.before = Some(parse_day(value)?),
+                "importance" => filter.min_importance = Some(parse_importance(value)?),
+                _ => query.push(word),
+            }
+        }
+        Ok((query.join(" "), filter))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the filter needs the `memory_sources` table
+    fn by_source(&self) -> bool {
+        self.session_id.is_some() || self.role.is_some() || self.project.is_some()
+    }
+
+    /// The store-side filter, with the texts the source filters allow
+    /// looked up in `conn`
+    pub(crate) fn resolve(&self, conn: &Connection) -> Result<SearchFilter> {
+        let unix = |day: NaiveDate| day.and_time(NaiveTime::MIN).and_utc().timestamp();
+        let texts = if self.by_source() {
+            Some(self.source_texts(conn)?)
+        } else {
+            None
+        };
+        Ok(SearchFilter {
+            min_importance: self.min_importance,
+            since: self.after.map(unix),
+            until: self.before.map(unix),
+            texts,
+        })
+    }
+
+    /// Texts of memories whose source matches the session, role and project
+    fn source_texts(&self, conn: &Connection) -> Result<HashSet<String>> {
+        let mut sql = "SELECT text FROM memory_sources WHERE 1 = 1".to_string();
+        let mut params: Vec<Value> = Vec::new();
+        if let Some(session_id) = &self.session_id {
+            params.push(Value::Text(session_id.clone()));
+            sql.push_str(&format!(" AND session_id = ?{}", params.len()));
+        }
+        if let Some(role) = &self.role {
+            params.push(Value::Text(role.clone()));
+            sql.push_str(&format!(" AND role = ?{}", params.len()));
+        }
+        if let Some(project) = &self.project {
+            params.push(Value::Text(project.trim_end_matches('/').to_string()));
+            sql.push_str(&format!(
+                " AND (project = ?{0} OR project LIKE '%/' || ?{0})",
+                params.len()
+            ));
+        }
+        let mut stmt = conn.prepare(&sql)?;
+        let texts = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+            .collect::<Result<HashSet<String>, _>>()?;
+        Ok(texts)
+    }
+}
+
+fn parse_day(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Expected a date like 2026-09-01, got '{}'", value))
+}
+
+/// A tier by number (1–3) or name
+fn parse_importance(value: &str) -> Result<u8> {
+    let importance = match value.to_ascii_lowercase().as_str() {
+        "normal" => MemoryImportance::Normal,
+        "high" => MemoryImportance::High,
+        "critical" => MemoryImportance::Critical,
+        number => match number.parse::<u8>() {
+            Ok(n @ 1..=3) => MemoryImportance::from_u8(n),
+            _ => bail!(
+                "Expected importance normal, high or critical (1-3), got '{}'",
+                value
+            ),
+        },
+    };
+    Ok(importance.as_u8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_splits_filters_from_the_query() {
+        let (query, filter) = MemoryFilter::parse(
+            "postgres port project:finch after:2026-09-01 importance:high note:x",
+        )
+        .unwrap();
+        assert_eq!(query, "postgres port note:x");
+        assert_eq!(
+            filter,
+            MemoryFilter {
+                project: Some("finch".to_string()),
+                after: NaiveDate::from_ymd_opt(2026, 9, 1),
+                min_importance: Some(2),
+                ..Default::default()
+            }
+        );
+
+        assert!(MemoryFilter::parse("deploys after:yesterday").is_err());
+        assert!(MemoryFilter::parse("deploys importance:9").is_err());
+        assert!(MemoryFilter::parse("deploys").unwrap().1.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+// LanceDB memory store (feature `lancedb`)
+//
+// One row per memory: `id`, `text`, `importance`, `created_at` and a
+// fixed-size `vector` column, searched by cosine distance.  The table is
+// opened (or created) on first use, so a store on an object-store URI
+// doesn't delay startup.
+
+use anyhow::{bail, Context, Result};
+use arrow_array::types::Float32Type;
+use arrow_array::{
+    Array, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, RecordBatchIterator,
+    StringArray, UInt8Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use lancedb::DistanceType;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use super::store::{
+    importance_boost, MemoryHit, MemoryStore, NewMemory, SearchFilter, StoredMemory,
+};
+
+/// Matches fetched per requested result, so importance weighting can
+/// reorder them the way MemTree does
+const SEARCH_OVERFETCH: usize = 3;
+
+pub struct LanceStore {
+    uri: String,
+    table_name: String,
+    dim: usize,
+    table: OnceCell<lancedb::Table>,
+}
+
+impl LanceStore {
+    pub fn new(uri: &str, table: &str, dim: usize) -> Self {
+        let uri = match (uri.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest).display().to_string(),
+            _ => uri.to_string(),
+        };
+        Self {
+            uri,
+            table_name: table.to_string(),
+            dim,
+            table: OnceCell::new(),
+        }
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("text", DataType::Utf8, false),
+            Field::new("importance", DataType::UInt8, false),
+            Field::new("created_at", DataType::Int64, false),
+            Field::new(
+                "vector",
+                DataType::FixedSizeList(
+                    Arc::new(Field::new("item", DataType::Float32, true)),
+                    self.dim as i32,
+                ),
+                false,
+            ),
+        ]))
+    }
+
+    /// The table, opened or created on first use
+    async fn table(&self) -> Result<&lancedb::Table> {
+        self.table
+            .get_or_try_init(|| async {
+                let db = lancedb::connect(&self.uri)
+                    .execute()
+                    .await
+                    .with_context(|| format!("Failed to open LanceDB at {}", self.uri))?;
+                let names = db.table_names().execute().await?;
+                if !names.contains(&self.table_name) {
+                    tracing::info!("Creating LanceDB table {}", self.location());
+                    return Ok(db
+                        .create_empty_table(&self.table_name, self.schema())
+                        .execute()
+                        .await?);
+                }
+                let table = db.open_table(&self.table_name).execute().await?;
+                let schema = table.schema().await?;
+                if let Ok(field) = schema.field_with_name("vector") {
+                    if let DataType::FixedSizeList(_, size) = field.data_type() {
+                        if *size as usize != self.dim {
+                            bail!(
+                                "LanceDB table {} holds {}-dimension vectors, but the \
+                                 embedding engine makes {}; use another table for this engine",
+                                self.table_name,
+                                size,
+                                self.dim
+                            );
+                        }
+                    }
+                }
+                Ok(table)
+            })
+            .await
+    }
+
+    /// Rows matching the SQL `filter` (all rows for `None`)
+    async fn rows(&self, filter: Option<String>) -> Result<Vec<StoredMemory>> {
+        let mut query = self.table().await?.query();
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        let batches: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+        Ok(batches.iter().flat_map(memories).collect())
+    }
+}
+
+/// `id` as a SQL string literal
+fn quote(id: &str) -> String {
+    format!("'{}'", id.replace('\'', "''"))
+}
+
+/// SQL predicate for `filter`; Discard-tier memories never match
+fn predicate(filter: &SearchFilter) -> String {
+    let mut clauses = vec!["importance > 0".to_string()];
+    if let Some(min) = filter.min_importance {
+        clauses.push(format!("importance >= {}", min));
+    }
+    if let Some(since) = filter.since {
+        clauses.push(format!("created_at >= {}", since));
+    }
+    if let Some(until) = filter.until {
+        clauses.push(format!("created_at < {}", until));
+    }
+    if let Some(texts) = &filter.texts {
+        let mut texts: Vec<String> = texts.iter().map(|text| quote(text)).collect();
+        texts.sort();
+        // An empty IN list is not valid SQL
+        texts.push("NULL".to_string());
+        clauses.push(format!("text IN ({})", texts.join(", ")));
+    }
+    clauses.join(" AND ")
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Option<&'a T> {
+    batch.column_by_name(name)?.as_any().downcast_ref::<T>()
+}
+
+/// Memories in a batch with the table's columns; `vector` may be absent
+fn memories(batch: &RecordBatch) -> Vec<StoredMemory> {
+    let (Some(ids), Some(texts), Some(importance), Some(created_at)) = (
+        column::<StringArray>(batch, "id"),
+        column::<StringArray>(batch, "text"),
+        column::<UInt8Array>(batch, "importance"),
+        column::<Int64Array>(batch, "created_at"),
+    ) else {
+        return Vec::new();
+    };
+    let vectors = column::<FixedSizeListArray>(batch, "vector");
+    (0..batch.num_rows())
+        .map(|i| StoredMemory {
+            id: ids.value(i).to_string(),
+            text: texts.value(i).to_string(),
+            embedding: vectors
+                .and_then(|v| {
+                    v.value(i)
+                        .as_any()
+                        .downcast_ref::<Float32Array>()
+                        .map(|f| f.values().to_vec())
+                })
+                .unwrap_or_default(),
+            importance: importance.value(i),
+            created_at: created_at.value(i),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MemoryStore for LanceStore {
+    async fn insert(&self, memories: Vec<NewMemory>) -> Result<()> {
+        if memories.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp();
+        let ids: Vec<String> = memories
+            .iter()
+            .map(|_| uuid::Uuid::new_v4().to_string())
+            .collect();
+        let schema = self.schema();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(ids)),
+                Arc::new(StringArray::from_iter_values(
+                    memories.iter().map(|m| m.text.as_str()),
+                )),
+                Arc::new(UInt8Array::from_iter_values(
+                    memories.iter().map(|m| m.importance),
+                )),
+                Arc::new(Int64Array::from_iter_values(
+                    memories.iter().map(|m| m.created_at.unwrap_or(now)),
+                )),
+                Arc::new(
+                    FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+                        memories
+                            .iter()
+                            .map(|m| Some(m.embedding.iter().copied().map(Some))),
+                        self.dim as i32,
+                    ),
+                ),
+            ],
+        )?;
+        self.table()
+            .await?
+            .add(RecordBatchIterator::new(vec![Ok(batch)], schema))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    async fn search_where(
+        &self,
+        embedding: &[f32],
+        k: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<MemoryHit>> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let batches: Vec<RecordBatch> = self
+            .table()
+            .await?
+            .query()
+            .nearest_to(embedding.to_vec())?
+            .distance_type(DistanceType::Cosine)
+            .only_if(predicate(filter))
+            .limit(k.saturating_mul(SEARCH_OVERFETCH))
+            .execute()
+            .await?
+            .try_collect()
+            .await?;
+        let mut scored: Vec<MemoryHit> = Vec::new();
+        for batch in &batches {
+            let Some(distances) = column::<Float32Array>(batch, "_distance") else {
+                continue;
+            };
+            for (i, memory) in memories(batch).into_iter().enumerate() {
+                let similarity = 1.0 - distances.value(i);
+                scored.push(MemoryHit {
+                    score: similarity * importance_boost(memory.importance),
+                    id: memory.id,
+                    text: memory.text,
+                    importance: memory.importance,
+                });
+            }
+        }
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        scored.truncate(k);
+        Ok(scored)
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.table().await?.count_rows(None).await?)
+    }
+
+    async fn recent(&self, limit: usize) -> Result<Vec<StoredMemory>> {
+        // LanceDB queries can't sort, so the newest are picked here
+        let mut rows = self.rows(None).await?;
+        rows.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+        rows.truncate(limit);
+        Ok(rows)
+    }
+
+    async fn older_than(&self, cutoff: i64) -> Result<Vec<StoredMemory>> {
+        self.rows(Some(format!("created_at < {}", cutoff))).await
+    }
+
+    async fn remove(&self, ids: &[String]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let list = ids
+            .iter()
+            .map(|id| quote(id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.table()
+            .await?
+            .delete(&format!("id IN ({})", list))
+            .await?;
+        Ok(ids.len())
+    }
+
+    async fn set_importance(&self, id: &str, importance: u8) -> Result<bool> {
+        let filter = format!("id = {}", quote(id));
+        let table = self.table().await?;
+        if table.count_rows(Some(filter.clone())).await? == 0 {
+            return Ok(false);
+        }
+        table
+            .update()
+            .only_if(filter)
+            .column("importance", importance.to_string())
+            .execute()
+            .await?;
+        Ok(true)
+    }
+
+    fn location(&self) -> String {
+        format!("{}/{}", self.uri.trim_end_matches('/'), self.table_name)
+    }
+}
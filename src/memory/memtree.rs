@@ -10,6 +10,7 @@
 // - Aggregated parent summaries
 
 use super::embeddings::{average_embeddings, cosine_similarity};
+use super::store::importance_boost;
 use anyhow::Result;
 use std::collections::HashMap;
 
@@ -19,6 +20,12 @@ pub type NodeId = u64;
 /// Threshold for semantic similarity (0.0 to 1.0)
 const SIMILARITY_THRESHOLD: f32 = 0.7;
 
+/// Longest text a parent grows to by absorbing folded children
+const MAX_FOLDED_TEXT_CHARS: usize = 2000;
+
+/// Longest gist of a folded child kept in its parent's text
+const FOLD_GIST_CHARS: usize = 160;
+
 /// A node in the MemTree
 #[derive(Debug, Clone)]
 pub struct TreeNode {
@@ -90,7 +97,18 @@ impl MemTree {
     /// It is stored on the node and used to boost retrieval scores.
     pub fn insert(&mut self, text: String, embedding: Vec<f32>, importance: u8) -> Result<NodeId> {
         let created_at = chrono::Utc::now().timestamp();
+        self.insert_at(text, embedding, importance, created_at)
+    }
 
+    /// `insert` with an explicit creation time (unix seconds), for memories
+    /// restored from an export
+    pub fn insert_at(
+        &mut self,
+        text: String,
+        embedding: Vec<f32>,
+        importance: u8,
+        created_at: i64,
+    ) -> Result<NodeId> {
         // Start traversal at root
         let mut current = self.root;
 
@@ -212,27 +230,217 @@ impl MemTree {
     /// Normal memory at 0.85 — important things surface even when slightly less
     /// semantically close to the query.
     pub fn retrieve(&self, query_embedding: &[f32], top_k: usize) -> Vec<(NodeId, String, f32)> {
+        self.retrieve_where(query_embedding, top_k, |_| true)
+    }
+
+    /// Like `retrieve`, among the nodes `keep` accepts only
+    pub fn retrieve_where(
+        &self,
+        query_embedding: &[f32],
+        top_k: usize,
+        keep: impl Fn(&TreeNode) -> bool,
+    ) -> Vec<(NodeId, String, f32)> {
         let mut results: Vec<_> = self
             .nodes
             .values()
-            .filter(|node| node.id != self.root && node.importance > 0)
+            .filter(|node| node.id != self.root && node.importance > 0 && keep(node))
             .map(|node| {
                 let similarity = cosine_similarity(query_embedding, &node.embedding);
-                let boost = match node.importance {
-                    3 => 1.4_f32,
-                    2 => 1.2_f32,
-                    _ => 1.0_f32,
-                };
+                let boost = importance_boost(node.importance);
                 (node.id, node.text.clone(), similarity * boost)
             })
             .collect();
 
-        // Sort by weighted score descending
-        results.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort by weighted score descending; ties go to the older node, so
+        // equal scores rank the same way on every call
+        results.sort_by(|a, b| {
+            b.2.partial_cmp(&a.2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
 
         results.into_iter().take(top_k).collect()
     }
 
+    /// Leaves created before `cutoff` (unix seconds), excluding the root
+    pub fn leaves_older_than(&self, cutoff: i64) -> Vec<NodeId> {
+        self.nodes
+            .values()
+            .filter(|n| n.id != self.root && n.children.is_empty() && n.created_at < cutoff)
+            .map(|n| n.id)
+            .collect()
+    }
+
+    /// Remove a leaf and refresh its ancestors' aggregated embeddings.
+    ///
+    /// Returns `None` (and removes nothing) for the root, inner nodes and
+    /// unknown ids.
+    pub fn remove_leaf(&mut self, id: NodeId) -> Result<Option<TreeNode>> {
+        match self.nodes.get(&id) {
+            Some(node) if id != self.root && node.children.is_empty() => {}
+            _ => return Ok(None),
+        }
+        let node = self
+            .nodes
+            .remove(&id)
+            .ok_or_else(|| anyhow::anyhow!("memtree: node {} vanished during removal", id))?;
+        if let Some(parent) = node.parent.and_then(|p| self.nodes.get_mut(&p)) {
+            parent.children.retain(|&c| c != id);
+        }
+        // A parent left without children keeps its last aggregate; refresh
+        // from the nearest ancestor that still has some
+        let mut ancestor = node.parent;
+        while let Some(ancestor_id) = ancestor {
+            let Some(a) = self.nodes.get(&ancestor_id) else {
+                break;
+            };
+            if !a.children.is_empty() {
+                self.update_parent_aggregation(ancestor_id)?;
+                break;
+            }
+            ancestor = a.parent;
+        }
+        Ok(Some(node))
+    }
+
+    /// Forget a leaf, keeping its gist: the first line of its text is
+    /// appended to its parent's (unless the parent is the root or already
+    /// holds `MAX_FOLDED_TEXT_CHARS`), then the leaf is removed.
+    ///
+    /// Returns the removed leaf and whether its gist was kept, or `None`
+    /// (and changes nothing) for the root, inner nodes and unknown ids.
+    pub fn fold_into_parent(&mut self, id: NodeId) -> Result<Option<(TreeNode, bool)>> {
+        let (gist, parent_id) = match self.nodes.get(&id) {
+            Some(node) if id != self.root && node.children.is_empty() => {
+                let line = node.text.lines().next().unwrap_or_default().trim();
+                let gist = if line.chars().count() > FOLD_GIST_CHARS {
+                    let cut: String = line.chars().take(FOLD_GIST_CHARS - 1).collect();
+                    format!("{}…", cut)
+                } else {
+                    line.to_string()
+                };
+                (gist, node.parent)
+            }
+            _ => return Ok(None),
+        };
+        let mut kept = false;
+        if let Some(parent) = parent_id
+            .filter(|&p| p != self.root)
+            .and_then(|p| self.nodes.get_mut(&p))
+        {
+            let len = parent.text.chars().count() + gist.chars().count() + 3;
+            if !gist.is_empty() && len <= MAX_FOLDED_TEXT_CHARS {
+                parent.text.push_str("\n- ");
+                parent.text.push_str(&gist);
+                kept = true;
+            }
+        }
+        Ok(self.remove_leaf(id)?.map(|node| (node, kept)))
+    }
+
+    /// Merge the near-duplicate leaves `others` into the node `into` (a
+    /// leaf, or the parent they repeat), which keeps its text and takes the
+    /// group's highest importance and newest creation time.  The others are
+    /// removed.  If `into` is a leaf afterwards it takes the group's average
+    /// embedding; otherwise it keeps aggregating its remaining children.
+    /// Ancestors' aggregated embeddings are refreshed.
+    ///
+    /// Returns the removed ids; ids that are not leaves are skipped, and
+    /// nothing changes when `into` is the root or unknown.
+    pub fn merge_leaves(&mut self, into: NodeId, others: &[NodeId]) -> Result<Vec<NodeId>> {
+        if into == self.root || !self.nodes.contains_key(&into) {
+            return Ok(Vec::new());
+        }
+        let mut merged: Vec<NodeId> = Vec::new();
+        for &id in others {
+            let is_leaf =
+                id != self.root && self.nodes.get(&id).is_some_and(|n| n.children.is_empty());
+            if id != into && is_leaf && !merged.contains(&id) {
+                merged.push(id);
+            }
+        }
+        if merged.is_empty() {
+            return Ok(merged);
+        }
+
+        let group: Vec<&TreeNode> = std::iter::once(into)
+            .chain(merged.iter().copied())
+            .filter_map(|id| self.nodes.get(&id))
+            .collect();
+        let embeddings: Vec<&Vec<f32>> = group.iter().map(|n| &n.embedding).collect();
+        let embedding = average_embeddings(&embeddings);
+        let importance = group.iter().map(|n| n.importance).max().unwrap_or(1);
+        let created_at = group.iter().map(|n| n.created_at).max().unwrap_or_default();
+
+        for &id in &merged {
+            self.remove_leaf(id)?;
+        }
+
+        let node = self
+            .nodes
+            .get_mut(&into)
+            .ok_or_else(|| anyhow::anyhow!("memtree: node {} not found during merge", into))?;
+        if node.children.is_empty() {
+            node.embedding = embedding;
+        }
+        node.importance = importance;
+        node.created_at = created_at;
+        if let Some(parent) = node.parent {
+            self.update_parent_aggregation(parent)?;
+        }
+        Ok(merged)
+    }
+
+    /// Change a node's importance tier; false for the root or an unknown id
+    pub fn set_importance(&mut self, id: NodeId, importance: u8) -> bool {
+        match self.nodes.get_mut(&id) {
+            Some(node) if id != self.root => {
+                node.importance = importance;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Give the leaves in `embeddings` their new embedding after the
+    /// embedding model changed, then recompute every inner node's aggregate
+    /// from its children, deepest first.  The root becomes a zero vector of
+    /// `dim` dimensions.  The tree keeps its shape; leaves not in
+    /// `embeddings` (inserted with the new model) keep theirs.
+    pub fn reembed(&mut self, mut embeddings: HashMap<NodeId, Vec<f32>>, dim: usize) {
+        for (id, node) in self.nodes.iter_mut() {
+            if node.children.is_empty() {
+                if let Some(embedding) = embeddings.remove(id) {
+                    node.embedding = embedding;
+                }
+            }
+        }
+        let mut inner: Vec<(usize, NodeId)> = self
+            .nodes
+            .values()
+            .filter(|n| n.id != self.root && !n.children.is_empty())
+            .map(|n| (n.level, n.id))
+            .collect();
+        inner.sort_unstable_by_key(|&(level, _)| std::cmp::Reverse(level));
+        for (_, id) in inner {
+            let aggregated = {
+                let children: Vec<&Vec<f32>> = self.nodes[&id]
+                    .children
+                    .iter()
+                    .filter_map(|child| self.nodes.get(child))
+                    .map(|child| &child.embedding)
+                    .collect();
+                average_embeddings(&children)
+            };
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.embedding = aggregated;
+            }
+        }
+        if let Some(root) = self.nodes.get_mut(&self.root) {
+            root.embedding = vec![0.0; dim];
+        }
+    }
+
     /// Get node by ID
     pub fn get_node(&self, id: NodeId) -> Option<&TreeNode> {
         self.nodes.get(&id)
@@ -387,6 +595,105 @@ mod tests {
         assert_eq!(tree.size(), texts.len());
     }
 
+    #[test]
+    fn test_remove_leaf_only_removes_old_leaves() {
+        let mut tree = MemTree::new();
+        let engine = TfIdfEmbedding::new();
+        let mut insert = |text: &str| {
+            tree.insert(text.to_string(), engine.embed(text).unwrap(), 1)
+                .unwrap()
+        };
+        let parent = insert("rust error handling");
+        // A node without children always takes the next insert as its child
+        let child = insert("rust error handling with anyhow");
+        assert_eq!(tree.get_node(child).unwrap().parent, Some(parent));
+
+        // Nothing is older than a cutoff in the past; everything is older than one in the future
+        assert!(tree.leaves_older_than(0).is_empty());
+        assert_eq!(tree.leaves_older_than(i64::MAX), vec![child]);
+
+        // Inner nodes and the root stay
+        assert!(tree.remove_leaf(parent).unwrap().is_none());
+        assert!(tree.remove_leaf(0).unwrap().is_none());
+
+        assert!(tree.remove_leaf(child).unwrap().is_some());
+        assert!(tree.get_node(parent).unwrap().children.is_empty());
+        assert_eq!(tree.size(), 1);
+    }
+
+    #[test]
+    fn test_fold_into_parent_keeps_gist() {
+        let mut tree = MemTree::new();
+        let engine = TfIdfEmbedding::new();
+        let mut insert = |text: &str| {
+            tree.insert(text.to_string(), engine.embed(text).unwrap(), 1)
+                .unwrap()
+        };
+        let parent = insert("deploys go through the staging cluster");
+        let child = insert("staging deploys need VPN access\nsecond line is dropped");
+
+        assert!(tree.fold_into_parent(parent).unwrap().is_none());
+        let (node, kept) = tree.fold_into_parent(child).unwrap().unwrap();
+        assert_eq!(node.id, child);
+        assert!(kept);
+        assert_eq!(
+            tree.get_node(parent).unwrap().text,
+            "deploys go through the staging cluster\n- staging deploys need VPN access"
+        );
+
+        // Under the root there is no parent to keep the gist
+        let (_, kept) = tree.fold_into_parent(parent).unwrap().unwrap();
+        assert!(!kept);
+        assert_eq!(tree.size(), 0);
+    }
+
+    #[test]
+    fn test_merge_leaves_keeps_one_node() {
+        let mut tree = MemTree::new_with_dim(4);
+        let parent = tree
+            .insert("parent".into(), vec![1.0, 0.0, 0.0, 0.0], 1)
+            .unwrap();
+        let first = tree
+            .insert("first".into(), vec![0.0, 1.0, 0.0, 0.0], 2)
+            .unwrap();
+        let other = tree
+            .insert("other".into(), vec![0.0, 0.0, 1.0, 0.0], 1)
+            .unwrap();
+        // Too far from the parent's aggregate to descend, so a sibling
+        let again = tree
+            .insert("again".into(), vec![0.6, 0.8, 0.0, 0.0], 1)
+            .unwrap();
+        for id in [first, other, again] {
+            assert_eq!(tree.get_node(id).unwrap().parent, Some(parent));
+        }
+
+        // Inner nodes and the root are never merged away
+        assert!(tree.merge_leaves(first, &[parent, 0]).unwrap().is_empty());
+        assert!(tree.merge_leaves(0, &[first]).unwrap().is_empty());
+        assert_eq!(
+            tree.merge_leaves(again, &[first, first, parent]).unwrap(),
+            vec![first]
+        );
+
+        let node = tree.get_node(again).unwrap();
+        assert_eq!(node.text, "again");
+        assert_eq!(node.importance, 2);
+        assert!(cosine_similarity(&node.embedding, &[0.0, 1.0, 0.0, 0.0]) > 0.9);
+        let aggregate = average_embeddings(&[&node.embedding, &vec![0.0, 0.0, 1.0, 0.0]]);
+        assert_eq!(tree.get_node(parent).unwrap().embedding, aggregate);
+        assert_eq!(tree.get_node(parent).unwrap().children, vec![other, again]);
+
+        // Into the parent they repeat, which is a leaf afterwards
+        assert_eq!(
+            tree.merge_leaves(parent, &[other, again]).unwrap(),
+            vec![other, again]
+        );
+        let node = tree.get_node(parent).unwrap();
+        assert!(node.children.is_empty());
+        assert_eq!(node.importance, 2);
+        assert_eq!(tree.size(), 1);
+    }
+
     // ── Importance ───────────────────────────────────────────────────────────
 
     #[test]
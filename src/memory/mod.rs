@@ -5,23 +5,67 @@
 // - SQLite with WAL mode for concurrency
 // - O(log N) insertion for real-time updates
 // - Cross-session context recall
-
+// - Pluggable recall index: MemTree by default, or Qdrant / LanceDB
+
+pub mod archive;
+pub mod citations;
+mod cohere;
+pub mod consolidate;
+pub mod decay;
+pub mod docs;
 mod embeddings;
+pub mod episodes;
+pub mod facts;
+pub mod filter;
+#[cfg(feature = "lancedb")]
+mod lance;
 mod memtree;
 pub mod neural_embedding;
+mod qdrant;
 pub mod quality;
-
+pub mod reranker;
+pub mod store;
+pub mod transfer;
+pub mod usage;
+pub mod working;
+
+pub use archive::{cutoff_months_ago, Archive, ArchiveHit, ArchiveRecord, ArchiveReport};
+pub use citations::RecalledMemory;
+pub use consolidate::{ConsolidateConfig, ConsolidationReport};
+pub use decay::{DecayConfig, DecayReport};
+pub use docs::{Dependency, DocsIndex, Ecosystem};
 pub use embeddings::{average_embeddings, cosine_similarity, EmbeddingEngine, TfIdfEmbedding};
+pub use episodes::Episode;
+pub use facts::{Fact, FactKind, NewFact};
+pub use filter::MemoryFilter;
 pub use memtree::{MemTree, NodeId, TreeNode};
-pub use neural_embedding::NeuralEmbeddingEngine;
+pub use neural_embedding::{EmbeddingModel, NeuralEmbeddingEngine};
 pub use quality::{MemoryClassifier, MemoryImportance};
+pub use reranker::{CrossEncoderReranker, RerankConfig, Reranker};
+pub use store::{
+    LocalStore, MemoryHit, MemoryStore, MemoryStoreConfig, NewMemory, SearchFilter, StoredMemory,
+};
+pub use transfer::{TransferRecord, TransferReport};
+pub use usage::MemoryUsage;
+pub use working::WorkingMemory;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Newest memories considered by `conversation_summary`
+const SUMMARY_MEMORIES: usize = 1000;
+
+/// Metadata key naming the model the stored memories were embedded with
+const EMBEDDING_MODEL_KEY: &str = "embedding_model";
+
+/// Memories embedded between progress reports while re-embedding
+const REEMBED_PROGRESS_EVERY: usize = 50;
+
 /// Configuration for memory system
 #[derive(Debug, Clone)]
 pub struct MemoryConfig {
@@ -36,8 +80,35 @@ pub struct MemoryConfig {
     /// Use neural ONNX embeddings when the model is cached (default: true).
     /// Falls back to TF-IDF if the model is not yet downloaded.
     pub use_neural_embeddings: bool,
+    /// Which neural model, from `[memory] embedding_model` (default:
+    /// all-MiniLM-L6-v2).  Changing it re-embeds stored memories.
+    pub embedding_model: EmbeddingModel,
     /// Directory where the embedding model is cached / downloaded.
     pub embedding_cache_dir: PathBuf,
+    /// Directory for compressed archives of old conversations and leaves
+    pub archive_dir: PathBuf,
+    /// What rescores the top embedding matches, from `[memory.rerank]`
+    /// (default: the local cross-encoder, when it is cached)
+    pub rerank: RerankConfig,
+    /// Where recalled memories live, from `[memory.store]` (default: local)
+    pub store: MemoryStoreConfig,
+    /// Forgetting policy, from `[memory.decay]` (default: never forget)
+    pub decay: DecayConfig,
+    /// Near-duplicate merging, from `[memory.consolidate]` (default: off)
+    pub consolidate: ConsolidateConfig,
+    /// Ask the local model for durable facts after each assistant turn,
+    /// from `[memory] extract_facts` (default: true)
+    pub extract_facts: bool,
+    /// Summarize sessions into episode memories as context slides out and
+    /// when they end, from `[memory] episodes` (default: true)
+    pub episodes: bool,
+    /// Keep the files and names a REPL session mentions in working memory
+    /// and send only durable turns to the long-term store, from `[memory]
+    /// working_memory` (default: true)
+    pub working_memory: bool,
+    /// Project directory new memories are filed under, for `project:`
+    /// filters; the REPL sets it to the directory it runs in
+    pub project: Option<PathBuf>,
 }
 
 impl Default for MemoryConfig {
@@ -50,16 +121,55 @@ impl Default for MemoryConfig {
             max_context_items: 5,
             checkpoint_interval_secs: 300, // 5 minutes
             use_neural_embeddings: true,
+            embedding_model: EmbeddingModel::default(),
             embedding_cache_dir: home.join(".finch").join("embeddings"),
+            archive_dir: home.join(".finch").join("archive"),
+            rerank: RerankConfig::Local,
+            store: MemoryStoreConfig::Local,
+            decay: DecayConfig::default(),
+            consolidate: ConsolidateConfig::default(),
+            extract_facts: true,
+            episodes: true,
+            working_memory: true,
+            project: None,
+        }
+    }
+}
+
+impl MemoryConfig {
+    /// Config for the dependency-docs namespace (`~/.finch/docs.db`).
+    ///
+    /// Docs live in their own database so indexed library docs never crowd
+    /// conversation recall, and vice versa.
+    pub fn docs() -> Self {
+        let default = Self::default();
+        let db_path = default.db_path.with_file_name("docs.db");
+        Self { db_path, ..default }
+    }
+
+    /// Config for a daemon workspace's own namespace (`<dir>/memory.db`),
+    /// so tenants of a shared daemon never recall each other's memories.
+    pub fn workspace(dir: &std::path::Path) -> Self {
+        let db_path = dir.join("memory.db");
+        Self {
+            db_path,
+            ..Self::default()
         }
     }
 }
 
-/// Memory system with MemTree and SQLite storage
+/// Memory system: conversation history in SQLite, recall through a
+/// `MemoryStore`
 pub struct MemorySystem {
     db: Arc<Mutex<Connection>>,
-    tree: Arc<Mutex<MemTree>>,
+    store: Arc<dyn MemoryStore>,
     embedding_engine: Arc<dyn EmbeddingEngine>,
+    /// The neural model behind `embedding_engine`; `None` for TF-IDF
+    embedding_model: Option<EmbeddingModel>,
+    /// Cross-encoder over the embedding matches; `None` if not cached
+    reranker: Option<Arc<dyn Reranker>>,
+    /// Working memory of each live session, by session id
+    working: std::sync::Mutex<HashMap<String, WorkingMemory>>,
     config: MemoryConfig,
 }
 
@@ -124,54 +234,74 @@ impl MemorySystem {
             [],
         );
 
+        // Migration C: memory_sources gained role and project for filtering.
+        // Silently ignored if the columns already exist.
+        for column in ["role", "project"] {
+            let _ = conn.execute(
+                &format!("ALTER TABLE memory_sources ADD COLUMN {} TEXT", column),
+                [],
+            );
+        }
+
         tracing::info!("Memory system initialized: {}", config.db_path.display());
 
         // Select embedding engine: try neural if enabled and cached, else TF-IDF.
-        let embedding_engine: Arc<dyn EmbeddingEngine> = if config.use_neural_embeddings {
-            match NeuralEmbeddingEngine::find_in_cache()
-                .and_then(|dir| NeuralEmbeddingEngine::load(&dir).ok())
-            {
-                Some(neural) => {
-                    tracing::info!("Using neural ONNX embeddings (all-MiniLM-L6-v2)");
-                    Arc::new(neural)
-                }
-                None => {
-                    tracing::warn!(
-                        "Neural embedding model not in cache — using TF-IDF fallback. \
-                         Run `finch memory download` or call MemorySystem::new_async() \
-                         to download."
-                    );
-                    Arc::new(TfIdfEmbedding::new())
-                }
+        let model = config.embedding_model;
+        let neural = if config.use_neural_embeddings {
+            let neural = NeuralEmbeddingEngine::find_in_cache(model)
+                .and_then(|dir| NeuralEmbeddingEngine::load(&dir, model).ok());
+            if neural.is_none() {
+                tracing::warn!(
+                    "Neural embedding model {} not in cache — using TF-IDF fallback. \
+                     Run `finch memory reembed` or call MemorySystem::new_async() \
+                     to download.",
+                    model.id()
+                );
             }
+            neural
         } else {
-            Arc::new(TfIdfEmbedding::new())
+            None
+        };
+        let embedding_model = neural.as_ref().map(|neural| neural.model());
+        let embedding_engine: Arc<dyn EmbeddingEngine> = match neural {
+            Some(neural) => {
+                tracing::info!("Using neural ONNX embeddings ({})", model.id());
+                Arc::new(neural)
+            }
+            None => Arc::new(TfIdfEmbedding::new()),
         };
 
-        // Parameterize MemTree dimension to match the chosen engine.
+        let reranker = config.rerank.load();
+
+        // Parameterize the store's dimension to match the chosen engine.
         let dim = embedding_engine.dimension();
-        let mut tree = MemTree::new_with_dim(dim);
 
-        // Load MemTree from persisted tree_nodes table.
-        // Falls back gracefully to empty tree if table is empty or data is missing.
-        {
-            let node_count: i64 = conn
-                .query_row("SELECT COUNT(*) FROM tree_nodes", [], |row| row.get(0))
-                .unwrap_or(0);
-            if node_count > 0 {
-                if let Err(e) = Self::load_tree_from_db_conn(&conn, &mut tree) {
-                    tracing::warn!("Failed to load MemTree from DB (will start fresh): {}", e);
-                    tree = MemTree::new_with_dim(dim);
-                } else {
-                    tracing::info!("Loaded MemTree with {} nodes from disk", tree.size());
-                }
-            }
+        // The MemTree is loaded from the persisted tree_nodes table; a remote
+        // store leaves that table alone.
+        let tree = match config.store {
+            MemoryStoreConfig::Local => Some(LocalStore::load_tree(&conn, dim)),
+            _ => None,
+        };
+        let db = Arc::new(Mutex::new(conn));
+        let store: Arc<dyn MemoryStore> = match tree {
+            Some(tree) => Arc::new(LocalStore::new(
+                db.clone(),
+                tree,
+                config.db_path.display().to_string(),
+            )),
+            None => store::remote(&config.store, dim)?,
+        };
+        if !config.store.is_default() {
+            tracing::info!("Memory recall uses {}", store.location());
         }
 
         Ok(Self {
-            db: Arc::new(Mutex::new(conn)),
-            tree: Arc::new(Mutex::new(tree)),
+            db,
+            store,
             embedding_engine,
+            embedding_model,
+            reranker,
+            working: std::sync::Mutex::new(HashMap::new()),
             config,
         })
     }
@@ -183,14 +313,25 @@ impl MemorySystem {
     /// falling back to TF-IDF.
     pub async fn new_async(config: MemoryConfig) -> Result<Self> {
         if config.use_neural_embeddings {
-            match NeuralEmbeddingEngine::ensure_downloaded().await {
+            match NeuralEmbeddingEngine::ensure_downloaded(config.embedding_model).await {
                 Ok(_) => tracing::info!("Neural embedding model ready"),
                 Err(e) => tracing::warn!("Could not download neural model: {} — using TF-IDF", e),
             }
         }
+        if config.rerank == RerankConfig::Local {
+            if let Err(e) = CrossEncoderReranker::ensure_downloaded().await {
+                tracing::warn!("Could not download reranker: {} — results not reranked", e);
+            }
+        }
         Self::new(config)
     }
 
+    /// Rerank query results with `reranker` instead of the cached model.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
     /// Insert a conversation turn into memory
     pub async fn insert_conversation(
         &self,
@@ -198,6 +339,83 @@ impl MemorySystem {
         content: &str,
         model: Option<&str>,
         session_id: Option<&str>,
+    ) -> Result<()> {
+        self.insert_history(role, content, model, session_id)
+            .await?;
+
+        // Quality filter: classify and extract key content before indexing.
+        // Low-signal content (acks, greetings) is kept out of the store but still
+        // written to the conversations table above for raw history.
+        let classifier = MemoryClassifier::new();
+        if let Some((key_content, importance)) = classifier.process(role, content) {
+            let embedding = self.embedding_engine.embed(&key_content)?;
+            citations::record_source(
+                &*self.db.lock().await,
+                &key_content,
+                session_id,
+                role,
+                self.project().as_deref(),
+            )?;
+            self.store
+                .insert(vec![NewMemory {
+                    text: key_content,
+                    embedding,
+                    importance: importance.as_u8(),
+                    created_at: None,
+                }])
+                .await?;
+        }
+
+        tracing::debug!("Inserted conversation into memory: {} chars", content.len());
+
+        Ok(())
+    }
+
+    /// Insert a turn of the live session `session_id`.  Its files and names
+    /// go to the session's working memory (see `working`) and it is kept in
+    /// the conversation history, but only durable turns
+    /// (`MemoryClassifier::is_durable`) reach the long-term store.  With
+    /// `[memory] working_memory = false` this is `insert_conversation`.
+    pub async fn insert_session_turn(
+        &self,
+        role: &str,
+        content: &str,
+        model: Option<&str>,
+        session_id: &str,
+    ) -> Result<()> {
+        if !self.config.working_memory {
+            return self
+                .insert_conversation(role, content, model, Some(session_id))
+                .await;
+        }
+        if let Ok(mut working) = self.working.lock() {
+            working
+                .entry(session_id.to_string())
+                .or_default()
+                .observe(content);
+        }
+        if MemoryClassifier::new().is_durable(role, content) {
+            self.insert_conversation(role, content, model, Some(session_id))
+                .await
+        } else {
+            self.insert_history(role, content, model, Some(session_id))
+                .await
+        }
+    }
+
+    /// The files and names session `session_id` mentioned, as a block for
+    /// the start of the user's message; `None` before it mentioned any
+    pub fn working_block(&self, session_id: &str) -> Option<String> {
+        self.working.lock().ok()?.get(session_id)?.format_block()
+    }
+
+    /// Write a turn to the conversations table only
+    async fn insert_history(
+        &self,
+        role: &str,
+        content: &str,
+        model: Option<&str>,
+        session_id: Option<&str>,
     ) -> Result<()> {
         let timestamp = chrono::Utc::now()
             .timestamp_nanos_opt()
@@ -223,43 +441,448 @@ impl MemorySystem {
             )?;
         }
 
-        // Quality filter: classify and extract key content before indexing.
-        // Low-signal content (acks, greetings) is skipped in MemTree but still
-        // written to the conversations table above for raw history.
-        let classifier = MemoryClassifier::new();
-        if let Some((key_content, importance)) = classifier.process(role, content) {
-            let embedding = self.embedding_engine.embed(&key_content)?;
-            {
-                let mut tree = self.tree.lock().await;
-                tree.insert(key_content, embedding, importance.as_u8())?;
+        Ok(())
+    }
+
+    /// Insert pre-chunked reference text (e.g. dependency docs) in one batch.
+    ///
+    /// Unlike `insert_conversation`, chunks bypass the quality classifier —
+    /// documentation is never "noise" — and the tree is persisted once at the
+    /// end instead of after every chunk.  `source` is recorded in the
+    /// conversations table's `model` column so rows can be traced back.
+    pub async fn insert_documents(&self, source: &str, chunks: &[String]) -> Result<usize> {
+        let now = chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .ok_or_else(|| anyhow::anyhow!("Timestamp out of range"))?;
+
+        {
+            let conn = self.db.lock().await;
+            let tx = conn.unchecked_transaction()?;
+            for (i, chunk) in chunks.iter().enumerate() {
+                let timestamp = now + i as i64;
+                tx.execute(
+                    "INSERT INTO conversations (id, timestamp, role, content, tokens, model, session_id, created_at)
+                     VALUES (?1, ?2, 'docs', ?3, NULL, ?4, NULL, ?5)",
+                    params![
+                        uuid::Uuid::new_v4().to_string(),
+                        timestamp,
+                        chunk,
+                        source,
+                        timestamp
+                    ],
+                )?;
             }
-            // Persist all nodes (root + ancestors + new leaf) so the DB stays
-            // consistent across process restarts and FK constraints are satisfied.
-            self.save_all_nodes_to_db().await?;
+            tx.commit()?;
         }
 
-        tracing::debug!("Inserted conversation into memory: {} chars", content.len());
+        let memories = chunks
+            .iter()
+            .map(|chunk| {
+                Ok(NewMemory {
+                    text: chunk.clone(),
+                    embedding: self.embedding_engine.embed(chunk)?,
+                    importance: MemoryImportance::Normal.as_u8(),
+                    created_at: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.store.insert(memories).await?;
+
+        tracing::debug!("Inserted {} document chunks from {}", chunks.len(), source);
+
+        Ok(chunks.len())
+    }
 
+    /// Forget the chunks `insert_documents` stored under every source that
+    /// starts with `source_prefix`, returning how many there were
+    pub async fn forget_documents(&self, source_prefix: &str) -> Result<usize> {
+        let texts: Vec<String> = {
+            let conn = self.db.lock().await;
+            let mut stmt = conn.prepare(
+                "SELECT content FROM conversations
+                 WHERE role = 'docs' AND substr(model, 1, length(?1)) = ?1",
+            )?;
+            let texts = stmt
+                .query_map(params![source_prefix], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            texts
+        };
+        if texts.is_empty() {
+            return Ok(0);
+        }
+
+        // Every chunk is wanted, not the best matches, so any query will do
+        let filter = SearchFilter {
+            texts: Some(texts.iter().cloned().collect()),
+            ..Default::default()
+        };
+        let embedding = self.embedding_engine.embed(source_prefix)?;
+        for hit in self
+            .store
+            .search_where(&embedding, texts.len(), &filter)
+            .await?
+        {
+            self.forget(&hit.id).await?;
+        }
+
+        self.db.lock().await.execute(
+            "DELETE FROM conversations
+             WHERE role = 'docs' AND substr(model, 1, length(?1)) = ?1",
+            params![source_prefix],
+        )?;
+        tracing::debug!(
+            "Forgot {} document chunks from {}*",
+            texts.len(),
+            source_prefix
+        );
+
+        Ok(texts.len())
+    }
+
+    /// Read a value from the metadata table.
+    pub async fn get_metadata(&self, key: &str) -> Result<Option<String>> {
+        use rusqlite::OptionalExtension;
+        let conn = self.db.lock().await;
+        let value = conn
+            .query_row("SELECT value FROM metadata WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(value)
+    }
+
+    /// Write a value to the metadata table (insert or replace).
+    pub async fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.db.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value, updated_at) VALUES (?1, ?2, ?3)",
+            params![key, value, chrono::Utc::now().timestamp()],
+        )?;
         Ok(())
     }
 
+    /// The neural model memories are embedded with; `None` for TF-IDF
+    pub fn embedding_model(&self) -> Option<EmbeddingModel> {
+        self.embedding_model
+    }
+
+    /// Name of the engine memories are embedded with, as recorded in the
+    /// metadata table
+    fn embedding_id(&self) -> &'static str {
+        self.embedding_model.map_or("tfidf", EmbeddingModel::id)
+    }
+
+    /// Whether stored memories were embedded by another model than the one
+    /// in use, so recall can't compare them until `reembed` runs.
+    ///
+    /// Never while falling back to TF-IDF because the configured model isn't
+    /// downloaded: the memories would only need re-embedding again once it
+    /// is.  Stores from before the model was recorded are judged by their
+    /// dimension.
+    pub async fn needs_reembedding(&self) -> Result<bool> {
+        if self.config.use_neural_embeddings && self.embedding_model.is_none() {
+            return Ok(false);
+        }
+        let current = self.embedding_id();
+        let Some(sample) = self.store.recent(1).await?.pop() else {
+            self.set_metadata(EMBEDDING_MODEL_KEY, current).await?;
+            return Ok(false);
+        };
+        match self.get_metadata(EMBEDDING_MODEL_KEY).await? {
+            Some(model) => Ok(model != current),
+            None if sample.embedding.len() == self.embedding_engine.dimension() => {
+                self.set_metadata(EMBEDDING_MODEL_KEY, current).await?;
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Embed every stored memory again with the current model, calling
+    /// `progress(done, total)` as it goes, and record the model.  Returns
+    /// how many memories were re-embedded.
+    ///
+    /// Memories inserted meanwhile are already embedded with the new model.
+    /// A remote store holding embeddings of another dimension is an error:
+    /// it needs a fresh collection.
+    pub async fn reembed(&self, progress: impl Fn(usize, usize) + Send + Sync) -> Result<usize> {
+        let memories = self.store.older_than(i64::MAX).await?;
+        let dim = self.embedding_engine.dimension();
+        if let Some(memory) = memories.first() {
+            if !self.config.store.is_default() && memory.embedding.len() != dim {
+                anyhow::bail!(
+                    "{} holds {}-dimension embeddings but {} makes {}; \
+                     point [memory.store] at a fresh collection",
+                    self.store.location(),
+                    memory.embedding.len(),
+                    self.embedding_id(),
+                    dim
+                );
+            }
+        }
+
+        let total = memories.len();
+        let mut embeddings = Vec::with_capacity(total);
+        for (done, memory) in memories.into_iter().enumerate() {
+            embeddings.push((memory.id, self.embedding_engine.embed(&memory.text)?));
+            if (done + 1) % REEMBED_PROGRESS_EVERY == 0 {
+                progress(done + 1, total);
+                // Embedding is CPU-bound; let queries and inserts through
+                tokio::task::yield_now().await;
+            }
+        }
+        self.store.reembed(embeddings, dim).await?;
+        self.set_metadata(EMBEDDING_MODEL_KEY, self.embedding_id())
+            .await?;
+        progress(total, total);
+
+        tracing::info!(
+            "Re-embedded {} memories with {}",
+            total,
+            self.embedding_id()
+        );
+        Ok(total)
+    }
+
+    /// Re-embed in the background if the embedding model changed (see
+    /// `needs_reembedding`), reporting to `progress` as `reembed` does.
+    /// The task yields how many memories were re-embedded (0 when none
+    /// needed it); failures are also logged.
+    pub fn spawn_reembedding(
+        self: &Arc<Self>,
+        progress: impl Fn(usize, usize) + Send + Sync + 'static,
+    ) -> tokio::task::JoinHandle<Result<usize>> {
+        let memory = self.clone();
+        tokio::spawn(async move {
+            let result = match memory.needs_reembedding().await {
+                Ok(true) => memory.reembed(progress).await,
+                Ok(false) => Ok(0),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                tracing::warn!("Re-embedding memories failed: {:#}", e);
+            }
+            result
+        })
+    }
+
     /// Query memory for relevant context
     pub async fn query(&self, query_text: &str, top_k: Option<usize>) -> Result<Vec<String>> {
+        self.query_where(query_text, top_k, &MemoryFilter::default())
+            .await
+    }
+
+    /// Like `query`, among the memories `filter` matches only
+    pub async fn query_where(
+        &self,
+        query_text: &str,
+        top_k: Option<usize>,
+        filter: &MemoryFilter,
+    ) -> Result<Vec<String>> {
+        let recalled = self.recall_where(query_text, top_k, filter).await?;
+        Ok(recalled.into_iter().map(|memory| memory.text).collect())
+    }
+
+    /// Like `query`, with each memory's id and where it came from, so an
+    /// answer can cite it (see `citations`)
+    pub async fn recall(
+        &self,
+        query_text: &str,
+        top_k: Option<usize>,
+    ) -> Result<Vec<RecalledMemory>> {
+        self.recall_where(query_text, top_k, &MemoryFilter::default())
+            .await
+    }
+
+    /// Like `recall`, among the memories `filter` matches only
+    pub async fn recall_where(
+        &self,
+        query_text: &str,
+        top_k: Option<usize>,
+        filter: &MemoryFilter,
+    ) -> Result<Vec<RecalledMemory>> {
         let k = top_k.unwrap_or(self.config.max_context_items);
+        let Some(filter) = self.search_filter(filter).await? else {
+            return Ok(Vec::new());
+        };
 
         // Generate query embedding
         let query_embedding = self.embedding_engine.embed(query_text)?;
 
-        // Retrieve from MemTree
-        let tree = self.tree.lock().await;
-        let results = tree.retrieve(&query_embedding, k);
+        // Retrieve from the store, over-fetching when the reranker will choose
+        let candidates = match self.reranker {
+            Some(_) => k.saturating_mul(reranker::RERANK_FACTOR),
+            None => k,
+        };
+        let mut hits = self
+            .store
+            .search_where(&query_embedding, candidates, &filter)
+            .await?;
+        if let Some(reranker) = &self.reranker {
+            let texts = hits.iter().map(|hit| hit.text.clone()).collect();
+            let ranked = reranker::rerank(reranker.as_ref(), query_text, texts, k).await;
+            hits = ranked
+                .iter()
+                .filter_map(|text| {
+                    let at = hits.iter().position(|hit| &hit.text == text)?;
+                    Some(hits.swap_remove(at))
+                })
+                .collect();
+        }
+        hits.truncate(k);
+
+        tracing::debug!("Memory query returned {} results", hits.len());
+
+        let conn = self.db.lock().await;
+        hits.into_iter()
+            .map(|hit| {
+                let (session_id, created_at) = citations::source(&conn, &hit.text)?;
+                Ok(RecalledMemory {
+                    id: hit.id,
+                    text: hit.text,
+                    session_id,
+                    created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether facts should be extracted after assistant turns
+    pub fn extracts_facts(&self) -> bool {
+        self.config.extract_facts
+    }
+
+    /// Whether sessions should be summarized into episodes
+    pub fn records_episodes(&self) -> bool {
+        self.config.episodes
+    }
+
+    /// Store a session episode as one High-importance memory, bypassing the
+    /// quality classifier.  The text is also kept in the conversations table
+    /// with the role `episode`.
+    pub async fn remember_episode(
+        &self,
+        episode: &Episode,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let text = episode.to_memory_text();
+        let timestamp = chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .ok_or_else(|| anyhow::anyhow!("Timestamp out of range"))?;
+        {
+            let conn = self.db.lock().await;
+            conn.execute(
+                "INSERT INTO conversations (id, timestamp, role, content, tokens, model, session_id, created_at)
+                 VALUES (?1, ?2, 'episode', ?3, NULL, NULL, ?4, ?5)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    timestamp,
+                    &text,
+                    session_id,
+                    timestamp
+                ],
+            )?;
+            citations::record_source(
+                &conn,
+                &text,
+                session_id,
+                "episode",
+                self.project().as_deref(),
+            )?;
+        }
+
+        let embedding = self.embedding_engine.embed(&text)?;
+        self.store
+            .insert(vec![NewMemory {
+                text,
+                embedding,
+                importance: MemoryImportance::High.as_u8(),
+                created_at: None,
+            }])
+            .await?;
+        Ok(())
+    }
+
+    /// Store extracted facts, replacing older ones with the same entity key
+    pub async fn remember_facts(
+        &self,
+        facts: &[NewFact],
+        session_id: Option<&str>,
+    ) -> Result<usize> {
+        let conn = self.db.lock().await;
+        facts::upsert(&conn, facts, session_id)
+    }
+
+    /// Stored facts relevant to `query`, at most `limit` (see `facts::matching`)
+    pub async fn matching_facts(&self, query: &str, limit: usize) -> Result<Vec<Fact>> {
+        let all = {
+            let conn = self.db.lock().await;
+            facts::all(&conn)?
+        };
+        Ok(facts::matching(all, query, limit))
+    }
+
+    /// The `k` best matches for `query` with their ids and scores, before
+    /// reranking: what recall considers, for `/memory search`
+    pub async fn search(&self, query: &str, k: usize) -> Result<Vec<MemoryHit>> {
+        self.search_where(query, k, &MemoryFilter::default()).await
+    }
+
+    /// Like `search`, among the memories `filter` matches only
+    pub async fn search_where(
+        &self,
+        query: &str,
+        k: usize,
+        filter: &MemoryFilter,
+    ) -> Result<Vec<MemoryHit>> {
+        let Some(filter) = self.search_filter(filter).await? else {
+            return Ok(Vec::new());
+        };
+        let embedding = self.embedding_engine.embed(query)?;
+        self.store.search_where(&embedding, k, &filter).await
+    }
+
+    /// `filter` for the store, its source filters resolved in SQL; `None`
+    /// when no memory's source matches
+    async fn search_filter(&self, filter: &MemoryFilter) -> Result<Option<SearchFilter>> {
+        let filter = filter.resolve(&*self.db.lock().await)?;
+        Ok(filter
+            .texts
+            .as_ref()
+            .is_none_or(|texts| !texts.is_empty())
+            .then_some(filter))
+    }
 
-        // Extract texts
-        let texts: Vec<String> = results.into_iter().map(|(_, text, _)| text).collect();
+    /// The project directory new memories are filed under
+    fn project(&self) -> Option<String> {
+        self.config
+            .project
+            .as_ref()
+            .map(|dir| dir.display().to_string())
+    }
 
-        tracing::debug!("Memory query returned {} results", texts.len());
+    /// Mark memory `id` Critical, so it ranks first and is never forgotten;
+    /// false if there is no such memory
+    pub async fn pin(&self, id: &str) -> Result<bool> {
+        self.store
+            .set_importance(id, MemoryImportance::Critical.as_u8())
+            .await
+    }
 
-        Ok(texts)
+    /// Forget memory `id`; false if there is no such memory.
+    ///
+    /// It is hidden from recall first: a MemTree node with children can't be
+    /// removed, so it stays in the tree as a Discard-tier node.
+    pub async fn forget(&self, id: &str) -> Result<bool> {
+        if !self
+            .store
+            .set_importance(id, MemoryImportance::Discard.as_u8())
+            .await?
+        {
+            return Ok(false);
+        }
+        self.store.remove(&[id.to_string()]).await?;
+        Ok(true)
     }
 
     /// Get recent conversations (for context window)
@@ -280,172 +903,159 @@ impl MemorySystem {
 
     /// Get memory statistics
     pub async fn stats(&self) -> Result<MemoryStats> {
-        let conn = self.db.lock().await;
-
-        let conversation_count: i64 =
-            conn.query_row("SELECT COUNT(*) FROM conversations", [], |row| row.get(0))?;
-
-        let tree = self.tree.lock().await;
-        let tree_size = tree.size();
+        let (conversation_count, fact_count): (i64, i64) = {
+            let conn = self.db.lock().await;
+            (
+                conn.query_row("SELECT COUNT(*) FROM conversations", [], |row| row.get(0))?,
+                conn.query_row("SELECT COUNT(*) FROM facts", [], |row| row.get(0))?,
+            )
+        };
 
         Ok(MemoryStats {
             conversation_count: conversation_count as usize,
-            tree_node_count: tree_size,
+            tree_node_count: self.store.count().await?,
+            fact_count: fact_count as usize,
+            store: self.store.location(),
         })
     }
 
-    /// Persist all MemTree nodes to the tree_nodes table in a single transaction.
-    ///
-    /// Nodes are written sorted by node_id (root first) so that the self-referential
-    /// FK constraint `parent_id → node_id` is satisfied for each INSERT.
-    ///
-    /// This replaces the old `save_node_to_db(leaf_id)` approach which only persisted
-    /// the newly inserted leaf.  That missed two things:
-    ///   1. The root node (id=0) was never written, causing FK violations because
-    ///      libsqlite3-sys bundles SQLite compiled with SQLITE_DEFAULT_FOREIGN_KEYS=1.
-    ///   2. Parent embeddings updated by `update_parent_aggregation` were never
-    ///      persisted, so embeddings went stale across process restarts.
-    async fn save_all_nodes_to_db(&self) -> Result<()> {
-        let mut nodes: Vec<TreeNode> = {
-            let tree = self.tree.lock().await;
-            tree.all_nodes().values().cloned().collect()
-        };
-
-        // Sort by node_id ascending so root (id=0) is written before its children.
-        // SQLite enforces the self-referential FK immediately (IMMEDIATE mode),
-        // so parent rows must exist before child rows within the transaction.
-        nodes.sort_by_key(|n| n.id);
-
+    /// Importance, growth, recall and citation counts and database size, for
+    /// `finch memory stats --verbose`
+    pub async fn usage(&self) -> Result<MemoryUsage> {
+        let memories = self.store.older_than(i64::MAX).await?;
         let conn = self.db.lock().await;
-        let tx = conn.unchecked_transaction()?;
-        for node in &nodes {
-            let embedding_bytes: Vec<u8> = node
-                .embedding
-                .iter()
-                .flat_map(|f| f.to_le_bytes())
-                .collect();
-            tx.execute(
-                "INSERT OR REPLACE INTO tree_nodes
-                 (node_id, parent_id, text, embedding, level, created_at, importance)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                params![
-                    node.id as i64,
-                    node.parent.map(|p| p as i64),
-                    &node.text,
-                    &embedding_bytes,
-                    node.level as i64,
-                    node.created_at,
-                    node.importance as i64,
-                ],
-            )?;
-        }
-        tx.commit()?;
-        Ok(())
+        MemoryUsage::collect(&conn, &memories, &self.config.db_path, Utc::now())
     }
 
-    /// Reconstruct MemTree from the tree_nodes table at startup.
-    fn load_tree_from_db_conn(conn: &Connection, tree: &mut MemTree) -> Result<()> {
-        struct Row {
-            node_id: u64,
-            parent_id: Option<u64>,
-            text: String,
-            embedding: Vec<f32>,
-            level: usize,
-            created_at: i64,
-            importance: u8,
+    /// Count the memories recalled for a prompt, and which of them its
+    /// `answer` cited (see `usage`)
+    pub async fn record_recall(&self, recalled: &[RecalledMemory], answer: &str) -> Result<()> {
+        if recalled.is_empty() {
+            return Ok(());
         }
+        usage::record(&*self.db.lock().await, recalled, answer)
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT node_id, parent_id, text, embedding, level, created_at, importance
-             FROM tree_nodes ORDER BY node_id ASC",
-        )?;
+    /// Move conversation rows and stored memories older than `cutoff` into a
+    /// compressed archive file under `config.archive_dir`.
+    ///
+    /// The archive file is written before anything is deleted, so a crash
+    /// mid-run can at worst leave records in both places.
+    pub async fn archive_older_than(&self, cutoff: DateTime<Utc>) -> Result<ArchiveReport> {
+        let cutoff_nanos = cutoff.timestamp_nanos_opt().unwrap_or(i64::MIN);
 
-        let rows: Vec<Row> = stmt
-            .query_map([], |row| {
-                let node_id: i64 = row.get(0)?;
-                let parent_id: Option<i64> = row.get(1)?;
-                let text: String = row.get(2)?;
-                let embedding_bytes: Vec<u8> = row.get(3)?;
-                let level: i64 = row.get(4)?;
-                let created_at: i64 = row.get(5)?;
-                let importance: i64 = row.get(6).unwrap_or(1);
-                Ok((
-                    node_id,
-                    parent_id,
-                    text,
-                    embedding_bytes,
-                    level,
-                    created_at,
-                    importance,
-                ))
-            })?
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .map(
-                |(node_id, parent_id, text, embedding_bytes, level, created_at, importance)| Row {
-                    node_id: node_id as u64,
-                    parent_id: parent_id.map(|p| p as u64),
-                    text,
-                    embedding: embedding_bytes
-                        .chunks_exact(4)
-                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
-                        .collect(),
-                    level: level as usize,
-                    created_at,
-                    importance: importance.clamp(0, 3) as u8,
-                },
-            )
+        let mut records: Vec<ArchiveRecord> = {
+            let conn = self.db.lock().await;
+            let mut stmt = conn.prepare(
+                "SELECT id, session_id, role, content, model, timestamp FROM conversations
+                 WHERE timestamp < ?1
+                 ORDER BY timestamp",
+            )?;
+            let rows = stmt.query_map([cutoff_nanos], |row| {
+                Ok(ArchiveRecord::Conversation {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    role: row.get(2)?,
+                    content: row.get(3)?,
+                    model: row.get(4)?,
+                    timestamp: DateTime::from_timestamp_nanos(row.get(5)?),
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let conversation_ids: Vec<String> = records
+            .iter()
+            .filter_map(|r| match r {
+                ArchiveRecord::Conversation { id, .. } => Some(id.clone()),
+                _ => None,
+            })
             .collect();
 
-        if rows.is_empty() {
-            return Ok(());
-        }
+        let memories = self.store.older_than(cutoff.timestamp()).await?;
+        records.extend(memories.iter().map(|memory| ArchiveRecord::Memory {
+            text: memory.text.clone(),
+            importance: memory.importance,
+            created_at: DateTime::from_timestamp(memory.created_at, 0).unwrap_or_default(),
+        }));
 
-        let nodes = tree.all_nodes_mut();
-        let mut max_id: u64 = 0;
-
-        // First pass: insert all nodes
-        for row in &rows {
-            max_id = max_id.max(row.node_id);
-            nodes.insert(
-                row.node_id,
-                TreeNode {
-                    id: row.node_id,
-                    parent: row.parent_id,
-                    children: Vec::new(),
-                    text: row.text.clone(),
-                    embedding: row.embedding.clone(),
-                    level: row.level,
-                    created_at: row.created_at,
-                    importance: row.importance,
-                },
-            );
+        let file = Archive::new(self.config.archive_dir.clone()).write(&records)?;
+        if file.is_none() {
+            return Ok(ArchiveReport::default());
         }
 
-        // Second pass: rebuild children lists
-        for row in &rows {
-            if let Some(parent_id) = row.parent_id {
-                if let Some(parent) = nodes.get_mut(&parent_id) {
-                    if !parent.children.contains(&row.node_id) {
-                        parent.children.push(row.node_id);
-                    }
-                }
+        // A memory the store keeps anyway (a MemTree leaf that gained a
+        // child) stays recallable, and harmlessly archived too
+        let ids: Vec<String> = memories.into_iter().map(|memory| memory.id).collect();
+        let removed = self.store.remove(&ids).await?;
+        {
+            let conn = self.db.lock().await;
+            let tx = conn.unchecked_transaction()?;
+            for id in &conversation_ids {
+                tx.execute("DELETE FROM conversations WHERE id = ?1", [id])?;
             }
+            tx.commit()?;
         }
 
-        // Advance next_id past all loaded IDs
-        tree.set_next_id(max_id + 1);
+        tracing::info!(
+            "Archived {} conversations and {} memories to {}",
+            conversation_ids.len(),
+            removed,
+            self.config.archive_dir.display()
+        );
+        Ok(ArchiveReport {
+            conversations: conversation_ids.len(),
+            memories: removed,
+            sessions: 0,
+            file,
+        })
+    }
 
-        Ok(())
+    /// Forget memories older than `horizon_secs` for their importance,
+    /// folding them into their parents where the store can (see `decay`)
+    pub async fn decay(&self, horizon_secs: i64) -> Result<DecayReport> {
+        self.store.decay(horizon_secs, Utc::now().timestamp()).await
+    }
+
+    /// Merge memories at least `similarity` alike into one (see `consolidate`)
+    pub async fn consolidate(&self, similarity: f32) -> Result<ConsolidationReport> {
+        self.store
+            .consolidate(similarity, self.embedding_engine.as_ref())
+            .await
+    }
+
+    /// Write all conversations and MemTree nodes to `out` as JSON lines,
+    /// with or without their embeddings (see `transfer`)
+    pub async fn export(
+        &self,
+        out: &mut dyn std::io::Write,
+        embeddings: bool,
+    ) -> Result<TransferReport> {
+        let conn = self.db.lock().await;
+        transfer::export(&conn, out, embeddings)
+    }
+
+    /// Merge an export into this memory, skipping what is already here
+    pub async fn import(&self, input: impl std::io::BufRead) -> Result<TransferReport> {
+        let records = transfer::read_records(input)?;
+        transfer::import(self, records).await
+    }
+
+    /// Search archived records.  Much slower than `query`: every archive
+    /// file is decompressed and scanned.
+    pub async fn search_archive(&self, query: &str, limit: usize) -> Result<Vec<ArchiveHit>> {
+        let archive = Archive::new(self.config.archive_dir.clone());
+        let query = query.to_string();
+        tokio::task::spawn_blocking(move || archive.search(&query, limit)).await?
     }
 
     /// Derive a short topic summary without any LLM call.
     ///
-    /// Uses centroid queries against the MemTree:
+    /// Uses centroid queries against the store's newest memories (at most
+    /// `SUMMARY_MEMORIES` of them):
     /// - `overall` → representative turn for the whole session
     /// - `current` → representative turn among the 5 most recent turns
     ///
-    /// Returns `depth` context-summary lines by querying the MemTree at
+    /// Returns `depth` context-summary lines by querying the store at
     /// increasingly fine-grained time windows (broadest → most recent).
     ///
     /// - `depth` = 0   → empty result
@@ -461,22 +1071,11 @@ impl MemorySystem {
             return Ok(ConversationSummaryLines::default());
         }
 
-        let tree = self.tree.lock().await;
-        let nodes = tree.all_nodes();
-
-        // Collect leaf embeddings and texts (exclude root id=0)
-        let mut leaves: Vec<(i64, &Vec<f32>, &str)> = nodes
-            .values()
-            .filter(|n| n.id != 0 && n.children.is_empty())
-            .map(|n| (n.created_at, &n.embedding, n.text.as_str()))
-            .collect();
-
+        // Most-recent first for window slicing
+        let leaves = self.store.recent(SUMMARY_MEMORIES).await?;
         if leaves.is_empty() {
             return Ok(ConversationSummaryLines::default());
         }
-
-        // Sort most-recent first for window slicing
-        leaves.sort_by(|a, b| b.0.cmp(&a.0));
         let num_leaves = leaves.len();
 
         // Compute the window sizes for the requested depth
@@ -485,17 +1084,17 @@ impl MemorySystem {
         // The last window is always the "now" slot. Pin it to the most-recent
         // leaf's actual text so it is guaranteed to show something fresh and
         // distinct, even when all centroid queries converge on the same node.
-        let now_text = truncate_str(leaves[0].2, 70);
+        let now_text = truncate_str(&leaves[0].text, 70);
 
         let mut lines: Vec<String> = Vec::new();
         let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
 
         // Centroid queries for all windows except the last ("now") slot.
         for window in windows.iter().take(windows.len().saturating_sub(1)) {
-            let slice: Vec<&Vec<f32>> = leaves.iter().take(*window).map(|(_, e, _)| *e).collect();
+            let slice: Vec<&Vec<f32>> = leaves.iter().take(*window).map(|m| &m.embedding).collect();
             let centroid = average_embeddings(&slice);
-            if let Some((_, text, _)) = tree.retrieve(&centroid, 1).into_iter().next() {
-                let s = truncate_str(&text, 70);
+            if let Some(hit) = self.store.search(&centroid, 1).await?.into_iter().next() {
+                let s = truncate_str(&hit.text, 70);
                 if !s.trim().is_empty() && s != now_text && seen.insert(s.clone()) {
                     lines.push(s);
                 }
@@ -511,7 +1110,7 @@ impl MemorySystem {
     }
 }
 
-/// Summary of conversation topics derived from MemTree centroid queries.
+/// Summary of conversation topics derived from memory centroid queries.
 #[derive(Debug, Clone, Default)]
 pub struct ConversationSummaryLines {
     /// Context lines ordered from broadest (overall session) to most recent.
@@ -567,6 +1166,10 @@ fn truncate_str(s: &str, max_chars: usize) -> String {
 pub struct MemoryStats {
     pub conversation_count: usize,
     pub tree_node_count: usize,
+    /// Rows of the `facts` table
+    pub fact_count: usize,
+    /// Where memories are kept (see `MemoryStore::location`)
+    pub store: String,
 }
 
 #[cfg(test)]
@@ -651,6 +1254,268 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_remember_episode() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            ..Default::default()
+        })?;
+
+        let episode = Episode {
+            goal: "Move deploys to fly.io".to_string(),
+            decisions: vec!["staging app first".to_string()],
+            artifacts: vec!["fly.toml".to_string()],
+            open_questions: Vec::new(),
+        };
+        memory.remember_episode(&episode, Some("s1")).await?;
+
+        let hits = memory.search("deploys fly.io", 1).await?;
+        assert_eq!(hits[0].text, episode.to_memory_text());
+        assert_eq!(hits[0].importance, MemoryImportance::High.as_u8());
+        assert_eq!(memory.stats().await?.conversation_count, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_is_reranked() -> Result<()> {
+        /// Prefers whatever mentions asyncio, whatever the query
+        struct LikesAsyncio;
+        #[async_trait::async_trait]
+        impl Reranker for LikesAsyncio {
+            async fn score(&self, _query: &str, passages: &[&str]) -> Result<Vec<f32>> {
+                Ok(passages
+                    .iter()
+                    .map(|p| p.contains("asyncio") as u8 as f32)
+                    .collect())
+            }
+        }
+
+        let temp = NamedTempFile::new()?;
+        let config = MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        };
+        let memory = MemorySystem::new(config)?.with_reranker(Arc::new(LikesAsyncio));
+        memory
+            .insert_conversation("user", "How do I use Rust lifetimes?", Some("local"), None)
+            .await?;
+        memory
+            .insert_conversation("user", "What is Python asyncio?", Some("local"), None)
+            .await?;
+
+        // The reranker picks from all candidates, not just the embedding top-1
+        let results = memory.query("Rust lifetimes", Some(1)).await?;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].contains("asyncio"));
+
+        // A huge k over-fetches everything rather than overflowing
+        let results = memory.query("Rust lifetimes", Some(usize::MAX)).await?;
+        assert_eq!(results.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recall_knows_where_memories_came_from() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            rerank: RerankConfig::Off,
+            ..Default::default()
+        })?;
+        memory
+            .insert_conversation(
+                "user",
+                "We deploy through the staging bucket first",
+                None,
+                Some("swift-otter"),
+            )
+            .await?;
+
+        let recalled = memory.recall("staging bucket deploy", Some(1)).await?;
+        let hits = memory.search("staging bucket deploy", 1).await?;
+        assert_eq!(recalled[0].id, hits[0].id);
+        assert_eq!(recalled[0].session_id.as_deref(), Some("swift-otter"));
+        assert!(recalled[0].created_at.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_recalls_and_citations() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            rerank: RerankConfig::Off,
+            ..Default::default()
+        })?;
+        memory
+            .insert_conversation(
+                "user",
+                "We deploy through the staging bucket first",
+                None,
+                None,
+            )
+            .await?;
+
+        let recalled = memory.recall("staging bucket deploy", Some(1)).await?;
+        let tag = recalled[0].tag();
+        memory.record_recall(&recalled, "Staging first.").await?;
+        memory
+            .record_recall(&recalled, &format!("Staging first {}.", tag))
+            .await?;
+
+        let usage = memory.usage().await?;
+        assert_eq!(usage.by_importance.iter().sum::<usize>(), 1);
+        assert_eq!(usage.growth.last().map(|(_, n)| *n), Some(1));
+        assert_eq!((usage.injected, usage.cited), (2, 1));
+        assert_eq!(usage.hit_rate(), Some(0.5));
+        assert_eq!(usage.top_recalled[0].text, recalled[0].text);
+        assert!(usage.db_bytes > 0);
+        assert!(usage.render().contains("Hit rate: 50%"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_turns_keep_requests_in_working_memory() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            rerank: RerankConfig::Off,
+            ..Default::default()
+        })?;
+        assert!(memory.working_block("s1").is_none());
+
+        memory
+            .insert_session_turn(
+                "user",
+                "Why does LocalStore drop nodes in src/memory/store.rs?",
+                None,
+                "s1",
+            )
+            .await?;
+        let stored = |usage: MemoryUsage| usage.by_importance.iter().sum::<usize>();
+        assert_eq!(stored(memory.usage().await?), 0);
+        assert_eq!(memory.stats().await?.conversation_count, 1);
+        let block = memory.working_block("s1").unwrap();
+        assert!(block.contains("src/memory/store.rs") && block.contains("LocalStore"));
+        assert!(memory.working_block("s2").is_none());
+
+        memory
+            .insert_session_turn(
+                "assistant",
+                "LocalStore only persists dirty nodes when the tree is saved",
+                None,
+                "s1",
+            )
+            .await?;
+        assert_eq!(stored(memory.usage().await?), 1);
+        assert_eq!(memory.stats().await?.conversation_count, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_where_filters_before_scoring() -> Result<()> {
+        /// Texts of the memories matching `filter` words
+        async fn matching(memory: &MemorySystem, filter: &str) -> Result<Vec<String>> {
+            let (_, filter) = MemoryFilter::parse(filter)?;
+            let hits = memory
+                .search_where("staging database port", 5, &filter)
+                .await?;
+            Ok(hits.into_iter().map(|hit| hit.text).collect())
+        }
+
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            rerank: RerankConfig::Off,
+            project: Some(PathBuf::from("/home/ada/src/finch")),
+            ..Default::default()
+        })?;
+        memory
+            .insert_conversation(
+                "user",
+                "The staging database listens on port 5433",
+                None,
+                Some("s1"),
+            )
+            .await?;
+        memory
+            .insert_conversation(
+                "assistant",
+                "Staging database port is 5433 per docker-compose.yml",
+                None,
+                Some("s2"),
+            )
+            .await?;
+
+        let hits = matching(&memory, "session:s2 project:finch").await?;
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].contains("docker-compose.yml"));
+        assert!(matching(&memory, "role:user").await?[0].contains("listens on"));
+        for filter in ["project:other", "session:s3", "before:2000-01-01"] {
+            assert!(matching(&memory, filter).await?.is_empty(), "{}", filter);
+        }
+        assert_eq!(matching(&memory, "").await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reembed_after_model_change() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let config = MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            rerank: RerankConfig::Off,
+            ..Default::default()
+        };
+        {
+            let memory = MemorySystem::new(config.clone())?;
+            assert!(!memory.needs_reembedding().await?);
+            memory
+                .insert_conversation(
+                    "user",
+                    "The staging database listens on port 5433",
+                    None,
+                    None,
+                )
+                .await?;
+            assert!(!memory.needs_reembedding().await?);
+        }
+
+        // As if written by a 384-dimension model, before models were recorded
+        {
+            let conn = Connection::open(temp.path())?;
+            conn.execute("DELETE FROM metadata WHERE key = ?1", [EMBEDDING_MODEL_KEY])?;
+            conn.execute("UPDATE tree_nodes SET embedding = ?1", [vec![0u8; 384 * 4]])?;
+        }
+        let memory = MemorySystem::new(config)?;
+        assert!(memory.needs_reembedding().await?);
+        let score = |hits: Vec<MemoryHit>| hits.first().map_or(0.0, |hit| hit.score);
+        assert_eq!(score(memory.search("staging database port", 1).await?), 0.0);
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let count = memory
+            .reembed(|done, total| reports.lock().unwrap().push((done, total)))
+            .await?;
+        assert_eq!(count, 1);
+        assert_eq!(reports.into_inner().unwrap(), [(1, 1)]);
+        assert!(!memory.needs_reembedding().await?);
+        assert!(score(memory.search("staging database port", 1).await?) > 0.5);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_conversation_summary_empty() -> Result<()> {
         let temp = NamedTempFile::new()?;
@@ -782,6 +1647,272 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_archive_moves_old_records_out_of_hot_db() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let archive_dir = tempfile::tempdir()?;
+        let config = MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            archive_dir: archive_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let memory = MemorySystem::new(config.clone())?;
+        memory
+            .insert_conversation(
+                "user",
+                "We decided the staging database runs on port 5433.",
+                Some("test"),
+                None,
+            )
+            .await?;
+
+        // Nothing is older than a month
+        let report = memory.archive_older_than(cutoff_months_ago(1)).await?;
+        assert_eq!(report.total(), 0);
+        assert!(report.file.is_none());
+
+        let future = Utc::now() + chrono::Duration::days(1);
+        let report = memory.archive_older_than(future).await?;
+        assert_eq!(report.conversations, 1);
+        assert_eq!(report.memories, 1);
+        assert!(report.file.is_some());
+
+        let stats = memory.stats().await?;
+        assert_eq!(stats.conversation_count, 0);
+        assert_eq!(stats.tree_node_count, 0);
+
+        let hits = memory.search_archive("staging database port", 5).await?;
+        assert!(hits.iter().any(|h| h.record.text().contains("5433")));
+
+        // The removal was persisted, not just applied in memory
+        drop(memory);
+        let reopened = MemorySystem::new(config)?;
+        assert_eq!(reopened.stats().await?.tree_node_count, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decay_folds_old_memories_into_their_parents() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let config = MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        };
+        let memory = MemorySystem::new(config.clone())?;
+        memory
+            .insert_documents(
+                "notes",
+                &[
+                    "deploys go through the staging cluster".to_string(),
+                    "staging deploys need VPN access".to_string(),
+                ],
+            )
+            .await?;
+
+        let day = 24 * 60 * 60;
+        let now = Utc::now().timestamp();
+        let report = memory.store.decay(30 * day, now + 29 * day).await?;
+        assert_eq!(report, DecayReport::default());
+
+        let report = memory.store.decay(30 * day, now + 31 * day).await?;
+        assert_eq!(
+            report,
+            DecayReport {
+                forgotten: 1,
+                folded: 1
+            }
+        );
+
+        // The gist survives a restart, in the parent
+        drop(memory);
+        let memory = MemorySystem::new(config)?;
+        assert_eq!(memory.stats().await?.tree_node_count, 1);
+        let hits = memory.query("VPN access", Some(1)).await?;
+        assert!(
+            hits[0].contains("- staging deploys need VPN access"),
+            "{:?}",
+            hits
+        );
+
+        // The parent is a leaf now and goes on the next run
+        let report = memory.store.decay(30 * day, now + 31 * day).await?;
+        assert_eq!(report.forgotten, 1);
+        assert_eq!(memory.stats().await?.tree_node_count, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pin_and_forget() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?;
+        memory
+            .insert_documents(
+                "notes",
+                &[
+                    // Later memories are filed under the first, which takes
+                    // their average embedding; this one keeps the two below
+                    // apart as leaves with their own
+                    "weekly infrastructure sync notes".to_string(),
+                    "the staging database listens on port 5433".to_string(),
+                    "production deploys happen on Tuesdays".to_string(),
+                ],
+            )
+            .await?;
+
+        let hits = memory.search("staging database port", 2).await?;
+        assert_eq!(hits[0].text, "the staging database listens on port 5433");
+        assert_eq!(hits[0].importance, MemoryImportance::Normal.as_u8());
+
+        let deploys = memory.search("production deploys", 1).await?.remove(0);
+        assert_eq!(deploys.text, "production deploys happen on Tuesdays");
+        assert!(memory.pin(&deploys.id).await?);
+        let hits = memory.search("production deploys", 1).await?;
+        assert_eq!(hits[0].importance, MemoryImportance::Critical.as_u8());
+
+        let staging = memory.search("staging database port", 1).await?.remove(0);
+        assert!(memory.forget(&staging.id).await?);
+        let texts: Vec<String> = memory
+            .search("staging database port", 2)
+            .await?
+            .into_iter()
+            .map(|hit| hit.text)
+            .collect();
+        assert!(!texts.contains(&staging.text), "{:?}", texts);
+
+        assert!(!memory.pin("9999").await?);
+        assert!(!memory.forget("not-an-id").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_merges_restatements() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?;
+        memory
+            .insert_documents(
+                "notes",
+                &[
+                    "the staging database listens on port 5433".to_string(),
+                    "The staging database listens on port 5433.".to_string(),
+                    "production deploys happen on Tuesdays".to_string(),
+                ],
+            )
+            .await?;
+        assert_eq!(memory.store.count().await?, 3);
+
+        let report = memory.consolidate(0.95).await?;
+        assert_eq!((report.groups, report.merged), (1, 1));
+        assert_eq!(memory.store.count().await?, 2);
+        let texts: Vec<String> = memory
+            .search("staging database port", 3)
+            .await?
+            .into_iter()
+            .map(|hit| hit.text)
+            .collect();
+        assert_eq!(
+            texts.iter().filter(|t| t.contains("staging")).count(),
+            1,
+            "{:?}",
+            texts
+        );
+
+        // Nothing left to merge, and the merge survives a restart
+        assert_eq!(memory.consolidate(0.95).await?.merged, 0);
+        drop(memory);
+        let memory = MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?;
+        assert_eq!(memory.store.count().await?, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() -> Result<()> {
+        let config = |path: &std::path::Path| MemoryConfig {
+            db_path: path.to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        };
+        let source_db = NamedTempFile::new()?;
+        let source = MemorySystem::new(config(source_db.path()))?;
+        source
+            .insert_conversation(
+                "user",
+                "Remember that deploys go through the staging cluster first",
+                None,
+                Some("s1"),
+            )
+            .await?;
+        source
+            .insert_documents("notes", &["staging deploys need VPN access".to_string()])
+            .await?;
+        source
+            .remember_facts(
+                &[NewFact {
+                    entity: "deploy_target".to_string(),
+                    kind: FactKind::Decision,
+                    statement: "Deploys go to fly.io".to_string(),
+                }],
+                Some("s1"),
+            )
+            .await?;
+        let before = source.stats().await?;
+
+        for embeddings in [true, false] {
+            let mut export = Vec::new();
+            let exported = source.export(&mut export, embeddings).await?;
+            assert_eq!(exported.conversations, before.conversation_count);
+            assert_eq!(exported.memories, before.tree_node_count);
+            assert_eq!(exported.facts, 1);
+
+            let target_db = NamedTempFile::new()?;
+            let target = MemorySystem::new(config(target_db.path()))?;
+            let imported = target.import(export.as_slice()).await?;
+            assert_eq!(imported.conversations, exported.conversations);
+            assert_eq!(imported.memories, exported.memories);
+            assert_eq!(imported.facts, 1);
+            let reembedded = if embeddings { 0 } else { exported.memories };
+            assert_eq!(imported.reembedded, reembedded);
+            let after = target.stats().await?;
+            assert_eq!(after.conversation_count, before.conversation_count);
+            assert_eq!(after.tree_node_count, before.tree_node_count);
+            assert_eq!(
+                target.matching_facts("where do we deploy", 5).await?[0].statement,
+                "Deploys go to fly.io"
+            );
+            assert_eq!(
+                target.query("VPN access", Some(2)).await?,
+                source.query("VPN access", Some(2)).await?
+            );
+
+            // A second import adds nothing
+            let again = target.import(export.as_slice()).await?;
+            assert_eq!(again.conversations + again.memories + again.facts, 0);
+            assert_eq!(
+                again.skipped,
+                exported.conversations + exported.memories + exported.facts
+            );
+        }
+
+        Ok(())
+    }
+
     /// Regression: old production DBs had `id AUTOINCREMENT` as the tree_nodes PK
     /// instead of `node_id INTEGER PRIMARY KEY`.  MemorySystem::new() must detect
     /// this and drop/recreate the table so inserts don't fail with
@@ -1,74 +1,172 @@
 // Neural ONNX Embedding Engine
 //
-// Implements EmbeddingEngine using a sentence transformer (all-MiniLM-L6-v2)
-// running via ONNX Runtime for 384-dimensional semantic embeddings.
+// Implements EmbeddingEngine using a sentence transformer running via ONNX
+// Runtime.  `[memory] embedding_model` picks which one:
 //
-// Model: sentence-transformers/all-MiniLM-L6-v2 (Apache 2.0 license)
-// ONNX conversion by Xenova/HuggingFace (also Apache 2.0)
+//   all-minilm-l6-v2       384 dims, mean pooling (default)
+//   bge-small-en-v1.5      384 dims, CLS pooling
+//   nomic-embed-text-v1.5  768 dims, mean pooling, task prefix
 //
-// Distribution: downloaded from HuggingFace (Xenova/all-MiniLM-L6-v2-ONNX)
-// ~23MB quantized ONNX model; cached in standard HF cache after first download.
+// Models: sentence-transformers/all-MiniLM-L6-v2, BAAI/bge-small-en-v1.5 and
+// nomic-ai/nomic-embed-text-v1.5 (Apache 2.0); ONNX conversions by
+// Xenova/HuggingFace and Nomic.
+//
+// Distribution: downloaded from HuggingFace; quantized ONNX models of
+// ~23-140MB, cached in standard HF cache after first download.
+//
+// Embeddings from different models don't compare, so switching models means
+// re-embedding what is stored (see `MemorySystem::reembed`).
+//
+// Builds without the `onnx` feature get a placeholder engine that is never
+// cached, so memory falls back to TF-IDF.
 
 use super::embeddings::EmbeddingEngine;
-use anyhow::{anyhow, bail, Context, Result};
+#[cfg(feature = "onnx")]
+use anyhow::{anyhow, Context};
+use anyhow::{bail, Result};
+#[cfg(feature = "onnx")]
 use ndarray::Array2;
+#[cfg(feature = "onnx")]
 use ort::{
     memory::MemoryInfo,
     session::{builder::GraphOptimizationLevel, Session},
     value::Value,
 };
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+#[cfg(feature = "onnx")]
 use std::sync::Mutex;
+#[cfg(feature = "onnx")]
 use tokenizers::Tokenizer;
+#[cfg(feature = "onnx")]
 use tracing::{debug, info};
 
 /// Maximum sequence length for the embedding model.
-/// all-MiniLM-L6-v2 supports up to 512 tokens; we truncate at 256 for efficiency.
+/// All supported models take at least 512 tokens; we truncate at 256 for efficiency.
+#[cfg(feature = "onnx")]
 const MAX_SEQ_LEN: usize = 256;
 
-/// Output embedding dimension for all-MiniLM-L6-v2.
-const EMBEDDING_DIM: usize = 384;
+/// Sentence transformer used for neural embeddings, from `[memory] embedding_model`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmbeddingModel {
+    #[default]
+    #[serde(rename = "all-minilm-l6-v2", alias = "minilm")]
+    AllMiniLmL6V2,
+    #[serde(rename = "bge-small-en-v1.5", alias = "bge-small")]
+    BgeSmallEnV15,
+    #[serde(rename = "nomic-embed-text-v1.5", alias = "nomic-embed")]
+    NomicEmbedTextV15,
+}
+
+/// How token states become one sentence embedding
+#[cfg(feature = "onnx")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pooling {
+    /// Average over the attended tokens
+    Mean,
+    /// The first ([CLS]) token's state
+    Cls,
+}
+
+impl EmbeddingModel {
+    /// Name in config and in the metadata table
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::AllMiniLmL6V2 => "all-minilm-l6-v2",
+            Self::BgeSmallEnV15 => "bge-small-en-v1.5",
+            Self::NomicEmbedTextV15 => "nomic-embed-text-v1.5",
+        }
+    }
+
+    /// Output embedding dimension
+    pub fn dimension(self) -> usize {
+        match self {
+            Self::AllMiniLmL6V2 | Self::BgeSmallEnV15 => 384,
+            Self::NomicEmbedTextV15 => 768,
+        }
+    }
+
+    /// HuggingFace repository the model downloads from
+    #[cfg(feature = "onnx")]
+    fn repo(self) -> &'static str {
+        match self {
+            Self::AllMiniLmL6V2 => "Xenova/all-MiniLM-L6-v2-ONNX",
+            Self::BgeSmallEnV15 => "Xenova/bge-small-en-v1.5",
+            Self::NomicEmbedTextV15 => "nomic-ai/nomic-embed-text-v1.5",
+        }
+    }
+
+    /// ONNX files to look for, relative to the repository root, preferred first
+    #[cfg(feature = "onnx")]
+    fn model_files(self) -> [&'static str; 2] {
+        match self {
+            Self::AllMiniLmL6V2 => ["model_quantized.onnx", "model.onnx"],
+            Self::BgeSmallEnV15 | Self::NomicEmbedTextV15 => {
+                ["onnx/model_quantized.onnx", "onnx/model.onnx"]
+            }
+        }
+    }
+
+    #[cfg(feature = "onnx")]
+    fn pooling(self) -> Pooling {
+        match self {
+            Self::BgeSmallEnV15 => Pooling::Cls,
+            Self::AllMiniLmL6V2 | Self::NomicEmbedTextV15 => Pooling::Mean,
+        }
+    }
+
+    /// Task prefix nomic-embed expects.  Memories and queries get the same
+    /// one so they compare symmetrically.
+    #[cfg(feature = "onnx")]
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::NomicEmbedTextV15 => "search_query: ",
+            Self::AllMiniLmL6V2 | Self::BgeSmallEnV15 => "",
+        }
+    }
+}
 
 /// ONNX sentence transformer embedding engine.
 ///
-/// Produces 384-dimensional L2-normalized embeddings via mean pooling over the
-/// model's last_hidden_state output. Semantically much richer than the TF-IDF
-/// fallback — two phrases with the same meaning score near 1.0 even if they
-/// share no words.
+/// Produces L2-normalized embeddings pooled from the model's
+/// last_hidden_state output (see `EmbeddingModel`). Semantically much richer
+/// than the TF-IDF fallback — two phrases with the same meaning score near
+/// 1.0 even if they share no words.
 ///
 /// The ONNX session is wrapped in a `Mutex` because `run_binding` requires
 /// `&mut Session` while `EmbeddingEngine::embed` takes `&self`.
+#[cfg(feature = "onnx")]
 pub struct NeuralEmbeddingEngine {
     session: Mutex<Session>,
     tokenizer: Tokenizer,
     /// Whether the ONNX model expects a token_type_ids input.
     has_token_type_ids: bool,
+    model: EmbeddingModel,
 }
 
+#[cfg(feature = "onnx")]
 impl NeuralEmbeddingEngine {
     /// Load a pre-downloaded embedding model from a directory.
     ///
     /// `model_dir` must contain:
-    /// - `model_quantized.onnx` (preferred) or `model.onnx`
+    /// - the model's ONNX file, quantized preferred (see `EmbeddingModel::model_files`)
     /// - `tokenizer.json`
-    pub fn load(model_dir: &Path) -> Result<Self> {
+    pub fn load(model_dir: &Path, model: EmbeddingModel) -> Result<Self> {
         info!("Loading neural embedding model from: {:?}", model_dir);
 
         // Find model file
-        let model_path = {
-            let quantized = model_dir.join("model_quantized.onnx");
-            let regular = model_dir.join("model.onnx");
-            if quantized.exists() {
-                quantized
-            } else if regular.exists() {
-                regular
-            } else {
-                bail!(
-                    "Embedding model not found in {:?}. Expected model_quantized.onnx or model.onnx",
-                    model_dir
-                );
-            }
-        };
+        let model_path = model
+            .model_files()
+            .iter()
+            .map(|file| model_dir.join(file))
+            .find(|path| path.exists())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Embedding model not found in {:?}. Expected {}",
+                    model_dir,
+                    model.model_files().join(" or ")
+                )
+            })?;
 
         // Load tokenizer
         let tokenizer_path = model_dir.join("tokenizer.json");
@@ -96,49 +194,55 @@ impl NeuralEmbeddingEngine {
             .any(|i: &ort::value::Outlet| i.name() == "token_type_ids");
 
         info!(
-            "Neural embedding model loaded: dim={}, token_type_ids={}",
-            EMBEDDING_DIM, has_token_type_ids
+            "Neural embedding model loaded: {} dim={}, token_type_ids={}",
+            model.id(),
+            model.dimension(),
+            has_token_type_ids
         );
 
         Ok(Self {
             session: Mutex::new(session),
             tokenizer,
             has_token_type_ids,
+            model,
         })
     }
 
+    /// Which model this engine runs
+    pub fn model(&self) -> EmbeddingModel {
+        self.model
+    }
+
     /// Download the embedding model from HuggingFace if not already cached.
     ///
     /// Returns the local directory containing model and tokenizer files.
     /// This is a blocking operation; wrap in `spawn_blocking` for async contexts.
-    pub fn download_sync() -> Result<PathBuf> {
-        use hf_hub::{api::sync::Api, Repo, RepoType};
+    pub fn download_sync(model: EmbeddingModel) -> Result<PathBuf> {
+        use crate::models::hub::HubRepo;
 
-        info!("Downloading neural embedding model (all-MiniLM-L6-v2)...");
+        info!("Downloading neural embedding model ({})...", model.id());
 
-        let api = Api::new().context("Failed to create HuggingFace Hub API")?;
-        let repo = api.repo(Repo::new(
-            "Xenova/all-MiniLM-L6-v2-ONNX".to_string(),
-            RepoType::Model,
-        ));
+        let repo = HubRepo::model(model.repo())?;
 
         // Download model (tries quantized first, then regular)
-        let model_path = repo
-            .get("model_quantized.onnx")
-            .or_else(|_| repo.get("model.onnx"))
-            .context(
-                "Failed to download embedding model \
-                 (tried model_quantized.onnx and model.onnx)",
-            )?;
-
-        // Download tokenizer
-        let _tokenizer_path = repo
+        let [quantized, regular] = model.model_files();
+        repo.get(quantized)
+            .or_else(|_| repo.get(regular))
+            .with_context(|| {
+                format!(
+                    "Failed to download embedding model (tried {} and {})",
+                    quantized, regular
+                )
+            })?;
+
+        // Download tokenizer; it sits at the repository root
+        let tokenizer_path = repo
             .get("tokenizer.json")
             .context("Failed to download tokenizer.json")?;
 
-        let dir = model_path
+        let dir = tokenizer_path
             .parent()
-            .ok_or_else(|| anyhow!("Model path has no parent directory"))?
+            .ok_or_else(|| anyhow!("Tokenizer path has no parent directory"))?
             .to_path_buf();
 
         info!("Neural embedding model downloaded to: {:?}", dir);
@@ -146,26 +250,22 @@ impl NeuralEmbeddingEngine {
     }
 
     /// Async version: download model using a blocking thread pool.
-    pub async fn ensure_downloaded() -> Result<PathBuf> {
-        tokio::task::spawn_blocking(Self::download_sync)
+    pub async fn ensure_downloaded(model: EmbeddingModel) -> Result<PathBuf> {
+        tokio::task::spawn_blocking(move || Self::download_sync(model))
             .await
-            .context("Embedding model download task panicked")??;
-
-        // Re-run synchronously to get the path (spawn_blocking result already dropped)
-        // Actually, re-run is cheap since files are already cached after the above
-        Self::download_sync()
+            .context("Embedding model download task panicked")?
     }
 
     /// Try to find the model in the HuggingFace cache without downloading.
     ///
     /// Returns `None` if the model is not yet cached (i.e., first run).
-    pub fn find_in_cache() -> Option<PathBuf> {
+    pub fn find_in_cache(model: EmbeddingModel) -> Option<PathBuf> {
         // HF hub caches models under: ~/.cache/huggingface/hub/
         let cache_base = dirs::home_dir()?
             .join(".cache")
             .join("huggingface")
             .join("hub");
-        let repo_dir = cache_base.join("models--Xenova--all-MiniLM-L6-v2-ONNX");
+        let repo_dir = cache_base.join(format!("models--{}", model.repo().replace('/', "--")));
 
         if !repo_dir.exists() {
             debug!("Embedding model not in cache: {:?}", repo_dir);
@@ -183,8 +283,10 @@ impl NeuralEmbeddingEngine {
         for entry in entries.flatten() {
             let snapshot = entry.path();
             if snapshot.is_dir() {
-                let has_model = snapshot.join("model_quantized.onnx").exists()
-                    || snapshot.join("model.onnx").exists();
+                let has_model = model
+                    .model_files()
+                    .iter()
+                    .any(|file| snapshot.join(file).exists());
                 let has_tokenizer = snapshot.join("tokenizer.json").exists();
                 if has_model && has_tokenizer {
                     debug!("Found embedding model in cache: {:?}", snapshot);
@@ -198,6 +300,7 @@ impl NeuralEmbeddingEngine {
 
     /// Encode text into input_ids and attention_mask, truncated at MAX_SEQ_LEN.
     fn tokenize(&self, text: &str) -> Result<(Vec<i64>, Vec<i64>)> {
+        let text = format!("{}{}", self.model.prefix(), text);
         let encoding = self
             .tokenizer
             .encode(text, true)
@@ -221,13 +324,14 @@ impl NeuralEmbeddingEngine {
     }
 }
 
+#[cfg(feature = "onnx")]
 impl EmbeddingEngine for NeuralEmbeddingEngine {
     fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
         let (input_ids, attention_mask) = self.tokenize(text)?;
         let seq_len = input_ids.len();
 
-        if seq_len == 0 {
-            return Ok(vec![0.0; EMBEDDING_DIM]);
+        if text.is_empty() || seq_len == 0 {
+            return Ok(vec![0.0; self.dimension()]);
         }
 
         // Build input tensors [1, seq_len]
@@ -298,7 +402,7 @@ impl EmbeddingEngine for NeuralEmbeddingEngine {
             .get("last_hidden_state")
             .ok_or_else(|| anyhow!("Missing last_hidden_state in model outputs"))?;
 
-        // Shape: [1, seq_len, EMBEDDING_DIM]
+        // Shape: [1, seq_len, dimension]
         let (shape, data) = lhs
             .try_extract_tensor::<f32>()
             .context("Failed to extract last_hidden_state tensor")?;
@@ -312,26 +416,32 @@ impl EmbeddingEngine for NeuralEmbeddingEngine {
         let hidden_dim = shape[2] as usize;
         let actual_seq = shape[1] as usize;
 
-        // Mean pool over sequence dimension, weighted by attention_mask
         let mut pooled = vec![0.0f32; hidden_dim];
-        let mut count = 0.0f32;
-        for (i, &mask) in attention_mask
-            .iter()
-            .enumerate()
-            .take(actual_seq.min(attention_mask.len()))
-        {
-            if mask == 1 {
-                count += 1.0;
-                let offset = i * hidden_dim;
-                for j in 0..hidden_dim {
-                    pooled[j] += data[offset + j];
+        match self.model.pooling() {
+            // Mean pool over sequence dimension, weighted by attention_mask
+            Pooling::Mean => {
+                let mut count = 0.0f32;
+                for (i, &mask) in attention_mask
+                    .iter()
+                    .enumerate()
+                    .take(actual_seq.min(attention_mask.len()))
+                {
+                    if mask == 1 {
+                        count += 1.0;
+                        let offset = i * hidden_dim;
+                        for j in 0..hidden_dim {
+                            pooled[j] += data[offset + j];
+                        }
+                    }
+                }
+                if count > 0.0 {
+                    for v in &mut pooled {
+                        *v /= count;
+                    }
                 }
             }
-        }
-        if count > 0.0 {
-            for v in &mut pooled {
-                *v /= count;
-            }
+            // The [CLS] token leads the sequence
+            Pooling::Cls => pooled.copy_from_slice(&data[..hidden_dim]),
         }
 
         // L2 normalize
@@ -346,7 +456,52 @@ impl EmbeddingEngine for NeuralEmbeddingEngine {
     }
 
     fn dimension(&self) -> usize {
-        EMBEDDING_DIM
+        self.model.dimension()
+    }
+}
+
+#[cfg(not(feature = "onnx"))]
+const WITHOUT_ONNX: &str =
+    "Neural embeddings need ONNX Runtime; rebuild finch with --features onnx";
+
+/// Placeholder when the onnx feature is disabled
+#[cfg(not(feature = "onnx"))]
+pub struct NeuralEmbeddingEngine {
+    model: EmbeddingModel,
+}
+
+#[cfg(not(feature = "onnx"))]
+impl NeuralEmbeddingEngine {
+    pub fn load(_model_dir: &Path, _model: EmbeddingModel) -> Result<Self> {
+        bail!(WITHOUT_ONNX)
+    }
+
+    pub fn model(&self) -> EmbeddingModel {
+        self.model
+    }
+
+    pub fn download_sync(_model: EmbeddingModel) -> Result<PathBuf> {
+        bail!(WITHOUT_ONNX)
+    }
+
+    pub async fn ensure_downloaded(model: EmbeddingModel) -> Result<PathBuf> {
+        Self::download_sync(model)
+    }
+
+    /// Never cached, so `MemorySystem::new` picks TF-IDF
+    pub fn find_in_cache(_model: EmbeddingModel) -> Option<PathBuf> {
+        None
+    }
+}
+
+#[cfg(not(feature = "onnx"))]
+impl EmbeddingEngine for NeuralEmbeddingEngine {
+    fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        bail!(WITHOUT_ONNX)
+    }
+
+    fn dimension(&self) -> usize {
+        self.model.dimension()
     }
 }
 
@@ -356,9 +511,29 @@ mod tests {
 
     #[test]
     fn test_neural_embedding_dim_constant() {
-        assert_eq!(EMBEDDING_DIM, 384);
+        assert_eq!(EmbeddingModel::default().dimension(), 384);
+    }
+
+    #[test]
+    fn test_embedding_model_names() {
+        #[derive(Deserialize)]
+        struct Config {
+            embedding_model: EmbeddingModel,
+        }
+        let model = |name: &str| {
+            toml::from_str::<Config>(&format!("embedding_model = \"{}\"", name))
+                .map(|config| config.embedding_model)
+        };
+        assert_eq!(model("bge-small").unwrap(), EmbeddingModel::BgeSmallEnV15);
+        let nomic = model("nomic-embed-text-v1.5").unwrap();
+        assert_eq!(
+            (nomic.id(), nomic.dimension()),
+            ("nomic-embed-text-v1.5", 768)
+        );
+        assert!(model("word2vec").is_err());
     }
 
+    #[cfg(feature = "onnx")]
     #[test]
     fn test_max_seq_len_constant() {
         assert_eq!(MAX_SEQ_LEN, 256);
@@ -369,7 +544,7 @@ mod tests {
     fn test_find_in_cache_returns_none_when_absent() {
         // This test is expected to return None in CI (no HF cache pre-seeded).
         // It should never panic.
-        let _result = NeuralEmbeddingEngine::find_in_cache();
+        let _result = NeuralEmbeddingEngine::find_in_cache(EmbeddingModel::default());
         // Just checking it doesn't panic
     }
 
@@ -378,8 +553,9 @@ mod tests {
     #[test]
     #[ignore]
     fn test_neural_embed_dimensions() {
-        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache() {
-            let engine = NeuralEmbeddingEngine::load(&model_dir).expect("Should load from cache");
+        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache(EmbeddingModel::default()) {
+            let engine = NeuralEmbeddingEngine::load(&model_dir, EmbeddingModel::default())
+                .expect("Should load from cache");
             assert_eq!(engine.dimension(), 384);
 
             let emb = engine.embed("Hello world").unwrap();
@@ -398,8 +574,9 @@ mod tests {
     #[test]
     #[ignore]
     fn test_neural_embed_semantic_similarity() {
-        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache() {
-            let engine = NeuralEmbeddingEngine::load(&model_dir).unwrap();
+        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache(EmbeddingModel::default()) {
+            let engine =
+                NeuralEmbeddingEngine::load(&model_dir, EmbeddingModel::default()).unwrap();
 
             let e1 = engine.embed("Rust programming language").unwrap();
             let e2 = engine.embed("Rust systems programming").unwrap();
@@ -420,8 +597,9 @@ mod tests {
     #[test]
     #[ignore]
     fn test_neural_embed_empty_text() {
-        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache() {
-            let engine = NeuralEmbeddingEngine::load(&model_dir).unwrap();
+        if let Some(model_dir) = NeuralEmbeddingEngine::find_in_cache(EmbeddingModel::default()) {
+            let engine =
+                NeuralEmbeddingEngine::load(&model_dir, EmbeddingModel::default()).unwrap();
             let emb = engine.embed("").unwrap();
             assert_eq!(emb.len(), 384);
             // Empty text → zero vector (no tokens to pool)
@@ -0,0 +1,530 @@
+// Qdrant memory store, over the REST API
+//
+// Each memory is a point with a random UUID and the payload
+// `{text, importance, created_at}`.  The collection is created with cosine
+// distance and an integer index on `created_at` (needed to list the newest
+// memories) the first time it is used.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+use super::store::{
+    importance_boost, MemoryHit, MemoryStore, NewMemory, SearchFilter, StoredMemory,
+};
+
+/// Matches fetched per requested result, so importance weighting can
+/// reorder them the way MemTree does
+const SEARCH_OVERFETCH: usize = 3;
+
+/// Points per scroll request
+const SCROLL_PAGE_SIZE: usize = 256;
+
+pub struct QdrantStore {
+    client: reqwest::Client,
+    /// Server URL without a trailing `/`
+    url: String,
+    collection: String,
+    api_key: Option<String>,
+    dim: usize,
+    ready: OnceCell<()>,
+}
+
+impl QdrantStore {
+    pub fn new(url: &str, collection: &str, api_key: Option<String>, dim: usize) -> Result<Self> {
+        reqwest::Url::parse(url).with_context(|| format!("Invalid Qdrant url: {}", url))?;
+        Ok(Self {
+            client: crate::http::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            url: url.trim_end_matches('/').to_string(),
+            collection: collection.to_string(),
+            api_key,
+            dim,
+            ready: OnceCell::new(),
+        })
+    }
+
+    /// Send a request to `path` under the collection, returning `result`
+    /// from the response.  `Ok(None)` for 404.
+    async fn send(&self, method: Method, path: &str, body: Option<Value>) -> Result<Option<Value>> {
+        let url = format!("{}/collections/{}{}", self.url, self.collection, path);
+        let mut request = self.client.request(method, &url);
+        if let Some(key) = &self.api_key {
+            request = request.header("api-key", key);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Qdrant request failed: {}", url))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let error = body["status"]["error"].as_str().unwrap_or("no details");
+            bail!("Qdrant returned {} for {}: {}", status, url, error);
+        }
+        Ok(Some(body["result"].clone()))
+    }
+
+    /// Like `send`, after making sure the collection exists; a missing
+    /// collection is an error here
+    async fn call(&self, method: Method, path: &str, body: Value) -> Result<Value> {
+        self.ready
+            .get_or_try_init(|| self.ensure_collection())
+            .await?;
+        self.send(method, path, Some(body))
+            .await?
+            .with_context(|| format!("Qdrant collection {} disappeared", self.collection))
+    }
+
+    /// Create the collection if needed, and check its vector size
+    async fn ensure_collection(&self) -> Result<()> {
+        if let Some(info) = self.send(Method::GET, "", None).await? {
+            let size = info["config"]["params"]["vectors"]["size"].as_u64();
+            if size.is_some_and(|size| size as usize != self.dim) {
+                bail!(
+                    "Qdrant collection {} holds {}-dimension vectors, but the embedding \
+                     engine makes {}; use another collection for this engine",
+                    self.collection,
+                    size.unwrap_or_default(),
+                    self.dim
+                );
+            }
+            return Ok(());
+        }
+        self.send(
+            Method::PUT,
+            "",
+            Some(json!({ "vectors": { "size": self.dim, "distance": "Cosine" } })),
+        )
+        .await?
+        .context("Failed to create the Qdrant collection")?;
+        self.send(
+            Method::PUT,
+            "/index?wait=true",
+            Some(json!({ "field_name": "created_at", "field_schema": "integer" })),
+        )
+        .await?;
+        tracing::info!("Created Qdrant collection {}", self.location());
+        Ok(())
+    }
+
+    /// Every point matching `filter`, following scroll pages
+    async fn scroll_all(&self, filter: Value) -> Result<Vec<StoredMemory>> {
+        let mut memories = Vec::new();
+        let mut offset = Value::Null;
+        loop {
+            let page = self
+                .call(
+                    Method::POST,
+                    "/points/scroll",
+                    json!({
+                        "filter": filter,
+                        "limit": SCROLL_PAGE_SIZE,
+                        "offset": offset,
+                        "with_payload": true,
+                        "with_vector": true,
+                    }),
+                )
+                .await?;
+            memories.extend(points(&page["points"]));
+            offset = page["next_page_offset"].clone();
+            if offset.is_null() {
+                return Ok(memories);
+            }
+        }
+    }
+}
+
+/// Qdrant filter for `filter`; Discard-tier memories never match
+fn search_filter(filter: &SearchFilter) -> Value {
+    let mut must = Vec::new();
+    if let Some(min) = filter.min_importance {
+        must.push(json!({ "key": "importance", "range": { "gte": min } }));
+    }
+    if filter.since.is_some() || filter.until.is_some() {
+        must.push(json!({
+            "key": "created_at",
+            "range": { "gte": filter.since, "lt": filter.until },
+        }));
+    }
+    if let Some(texts) = &filter.texts {
+        let mut texts: Vec<&String> = texts.iter().collect();
+        texts.sort();
+        must.push(json!({ "key": "text", "match": { "any": texts } }));
+    }
+    json!({
+        "must": must,
+        "must_not": [{ "key": "importance", "match": { "value": 0 } }],
+    })
+}
+
+/// A point id as a string: UUIDs as they are, integers in decimal
+fn point_id(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+/// Memories in a JSON array of points; points without text are skipped
+fn points(points: &Value) -> Vec<StoredMemory> {
+    points
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|point| {
+            let payload = &point["payload"];
+            Some(StoredMemory {
+                id: point_id(&point["id"]),
+                text: payload["text"].as_str()?.to_string(),
+                embedding: point["vector"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_f64().map(|v| v as f32))
+                    .collect(),
+                importance: payload["importance"].as_u64().unwrap_or(1).min(3) as u8,
+                created_at: payload["created_at"].as_i64().unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MemoryStore for QdrantStore {
+    async fn insert(&self, memories: Vec<NewMemory>) -> Result<()> {
+        if memories.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp();
+        let points: Vec<Value> = memories
+            .into_iter()
+            .map(|memory| {
+                json!({
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "vector": memory.embedding,
+                    "payload": {
+                        "text": memory.text,
+                        "importance": memory.importance,
+                        "created_at": memory.created_at.unwrap_or(now),
+                    },
+                })
+            })
+            .collect();
+        self.call(
+            Method::PUT,
+            "/points?wait=true",
+            json!({ "points": points }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn search_where(
+        &self,
+        embedding: &[f32],
+        k: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<MemoryHit>> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let hits = self
+            .call(
+                Method::POST,
+                "/points/search",
+                json!({
+                    "vector": embedding,
+                    "limit": k.saturating_mul(SEARCH_OVERFETCH),
+                    "with_payload": true,
+                    "filter": search_filter(filter),
+                }),
+            )
+            .await?;
+        let mut scored: Vec<MemoryHit> = hits
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|hit| {
+                let payload = &hit["payload"];
+                let importance = payload["importance"].as_u64().unwrap_or(1).min(3) as u8;
+                Some(MemoryHit {
+                    id: point_id(&hit["id"]),
+                    text: payload["text"].as_str()?.to_string(),
+                    importance,
+                    score: hit["score"].as_f64()? as f32 * importance_boost(importance),
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        scored.truncate(k);
+        Ok(scored)
+    }
+
+    async fn count(&self) -> Result<usize> {
+        let result = self
+            .call(Method::POST, "/points/count", json!({ "exact": true }))
+            .await?;
+        Ok(result["count"].as_u64().unwrap_or(0) as usize)
+    }
+
+    async fn recent(&self, limit: usize) -> Result<Vec<StoredMemory>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let page = self
+            .call(
+                Method::POST,
+                "/points/scroll",
+                json!({
+                    "limit": limit,
+                    "order_by": { "key": "created_at", "direction": "desc" },
+                    "with_payload": true,
+                    "with_vector": true,
+                }),
+            )
+            .await?;
+        Ok(points(&page["points"]))
+    }
+
+    async fn older_than(&self, cutoff: i64) -> Result<Vec<StoredMemory>> {
+        self.scroll_all(json!({
+            "must": [{ "key": "created_at", "range": { "lt": cutoff } }]
+        }))
+        .await
+    }
+
+    async fn remove(&self, ids: &[String]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        self.call(
+            Method::POST,
+            "/points/delete?wait=true",
+            json!({ "points": ids }),
+        )
+        .await?;
+        Ok(ids.len())
+    }
+
+    async fn set_importance(&self, id: &str, importance: u8) -> Result<bool> {
+        // Qdrant point ids are UUIDs or unsigned integers; anything else
+        // would be a 400 rather than a missing point
+        if uuid::Uuid::parse_str(id).is_err() && id.parse::<u64>().is_err() {
+            return Ok(false);
+        }
+        let id = id.parse::<u64>().map_or_else(|_| json!(id), |n| json!(n));
+        self.ready
+            .get_or_try_init(|| self.ensure_collection())
+            .await?;
+        let updated = self
+            .send(
+                Method::POST,
+                "/points/payload?wait=true",
+                Some(json!({ "payload": { "importance": importance }, "points": [id] })),
+            )
+            .await?;
+        Ok(updated.is_some())
+    }
+
+    fn location(&self) -> String {
+        format!("{}/collections/{}", self.url, self.collection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::{get, post, put};
+    use axum::{Json, Router};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Collection {
+        size: Option<u64>,
+        points: Vec<Value>,
+    }
+
+    type Shared = Arc<Mutex<Collection>>;
+
+    fn ok(result: Value) -> (StatusCode, Json<Value>) {
+        (
+            StatusCode::OK,
+            Json(json!({ "result": result, "status": "ok" })),
+        )
+    }
+
+    fn dot(a: &[Value], b: &[Value]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| x.as_f64().unwrap() * y.as_f64().unwrap())
+            .sum()
+    }
+
+    /// Minimal in-memory Qdrant: one collection, exact search by dot
+    /// product, unfiltered count.  Requests without the API key are refused.
+    async fn fake_qdrant() -> (String, Shared) {
+        async fn collection(
+            State(state): State<Shared>,
+            Path(_name): Path<String>,
+            method: axum::http::Method,
+            headers: HeaderMap,
+            body: Option<Json<Value>>,
+        ) -> (StatusCode, Json<Value>) {
+            if headers.get("api-key").is_none() {
+                return (StatusCode::FORBIDDEN, Json(Value::Null));
+            }
+            let mut state = state.lock().unwrap();
+            match (method.as_str(), state.size) {
+                ("GET", None) => (StatusCode::NOT_FOUND, Json(Value::Null)),
+                ("GET", Some(size)) => {
+                    ok(json!({ "config": { "params": { "vectors": { "size": size } } } }))
+                }
+                _ => {
+                    state.size = body.and_then(|b| b["vectors"]["size"].as_u64());
+                    ok(json!(true))
+                }
+            }
+        }
+        async fn upsert(
+            State(state): State<Shared>,
+            Json(body): Json<Value>,
+        ) -> impl axum::response::IntoResponse {
+            let points = body["points"].as_array().cloned().unwrap_or_default();
+            state.lock().unwrap().points.extend(points);
+            ok(json!({ "status": "completed" }))
+        }
+        async fn search(
+            State(state): State<Shared>,
+            Json(body): Json<Value>,
+        ) -> impl axum::response::IntoResponse {
+            let query = body["vector"].as_array().cloned().unwrap_or_default();
+            let mut hits: Vec<Value> = state
+                .lock()
+                .unwrap()
+                .points
+                .iter()
+                .filter(|p| p["payload"]["importance"] != json!(0))
+                .map(|p| {
+                    let score = dot(&query, p["vector"].as_array().unwrap());
+                    json!({ "id": p["id"], "score": score, "payload": p["payload"] })
+                })
+                .collect();
+            hits.sort_by(|a, b| {
+                b["score"]
+                    .as_f64()
+                    .partial_cmp(&a["score"].as_f64())
+                    .unwrap()
+            });
+            hits.truncate(body["limit"].as_u64().unwrap() as usize);
+            ok(json!(hits))
+        }
+        async fn set_payload(
+            State(state): State<Shared>,
+            Json(body): Json<Value>,
+        ) -> (StatusCode, Json<Value>) {
+            let ids = body["points"].as_array().cloned().unwrap_or_default();
+            let mut state = state.lock().unwrap();
+            let mut found = false;
+            for point in state.points.iter_mut().filter(|p| ids.contains(&p["id"])) {
+                for (key, value) in body["payload"].as_object().into_iter().flatten() {
+                    point["payload"][key] = value.clone();
+                }
+                found = true;
+            }
+            if found {
+                ok(json!({ "status": "completed" }))
+            } else {
+                (StatusCode::NOT_FOUND, Json(Value::Null))
+            }
+        }
+        async fn count(State(state): State<Shared>) -> impl axum::response::IntoResponse {
+            ok(json!({ "count": state.lock().unwrap().points.len() }))
+        }
+
+        let state = Shared::default();
+        let app = Router::new()
+            .route("/collections/:name", get(collection).put(collection))
+            .route(
+                "/collections/:name/index",
+                put(|| async { ok(json!(true)) }),
+            )
+            .route("/collections/:name/points", put(upsert))
+            .route("/collections/:name/points/search", post(search))
+            .route("/collections/:name/points/count", post(count))
+            .route("/collections/:name/points/payload", post(set_payload))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, state)
+    }
+
+    fn memory(text: &str, embedding: [f32; 2], importance: u8) -> NewMemory {
+        NewMemory {
+            text: text.to_string(),
+            embedding: embedding.to_vec(),
+            importance,
+            created_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_search() {
+        let (url, state) = fake_qdrant().await;
+        let store = QdrantStore::new(&url, "finch_memory", Some("key".to_string()), 2).unwrap();
+
+        assert_eq!(store.count().await.unwrap(), 0);
+        assert_eq!(state.lock().unwrap().size, Some(2));
+
+        store
+            .insert(vec![
+                memory("staging runs on port 5433", [1.0, 0.0], 1),
+                memory("always use anyhow", [0.0, 1.0], 1),
+                memory("ok thanks", [0.9, 0.1], 0),
+                memory("never force-push main", [0.6, 0.8], 3),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(store.count().await.unwrap(), 4);
+
+        // Critical memories are weighted up; Discard-tier ones never match
+        let texts = |hits: &[MemoryHit]| hits.iter().map(|h| h.text.clone()).collect::<Vec<_>>();
+        let hits = store.search(&[1.0, 0.0], 2).await.unwrap();
+        assert_eq!(
+            texts(&hits),
+            ["staging runs on port 5433", "never force-push main"]
+        );
+        let pinned = &hits[0].id;
+        let hits = store.search(&[0.6, 0.8], 1).await.unwrap();
+        assert_eq!(texts(&hits), ["never force-push main"]);
+
+        // Pinning makes a memory Critical; unknown ids are reported
+        assert!(store.set_importance(pinned, 3).await.unwrap());
+        assert_eq!(store.search(&[1.0, 0.0], 1).await.unwrap()[0].importance, 3);
+        let missing = uuid::Uuid::new_v4().to_string();
+        assert!(!store.set_importance(&missing, 3).await.unwrap());
+        assert!(!store.set_importance("not-an-id", 3).await.unwrap());
+
+        // A collection made for another embedding engine is refused
+        let other = QdrantStore::new(&url, "finch_memory", Some("key".to_string()), 384).unwrap();
+        let err = other.count().await.unwrap_err();
+        assert!(err.to_string().contains("2-dimension"), "{}", err);
+    }
+}
@@ -61,6 +61,30 @@ impl MemoryImportance {
     }
 }
 
+/// High: file references and code structure
+const HIGH_REFERENCES: &[&str] = &[
+    "src/", "~/", ".rs ", ".rs\"", ".toml", "cargo", "impl ", "fn ", "pub ", "struct ", "enum ",
+    "trait ", "mod ", "#[", "::",
+];
+
+/// High: factual explanations and preferences
+const HIGH_KNOWLEDGE: &[&str] = &[
+    "the reason",
+    "because ",
+    "works by",
+    "is defined in",
+    "lives in",
+    "is located",
+    "is stored",
+    "the pattern",
+    "the approach",
+    "we use ",
+    "we're using",
+    "i prefer",
+    "i like to",
+    "prefer to",
+];
+
 /// Classifies and pre-processes a conversation turn for MemTree storage.
 pub struct MemoryClassifier;
 
@@ -99,6 +123,25 @@ impl MemoryClassifier {
         Some((extracted, importance))
     }
 
+    /// Whether a turn belongs in long-term memory rather than only the
+    /// session's working memory (see `working`).
+    ///
+    /// Answers and explicit memories always do.  User turns do when they
+    /// decide, instruct or correct (Critical), or explain or state a
+    /// preference; other user turns are requests about the task at hand,
+    /// High at most for the files and code they mention.
+    pub fn is_durable(&self, role: &str, content: &str) -> bool {
+        if role != "user" {
+            return true;
+        }
+        let lower = content.to_lowercase();
+        match self.classify(content.trim()) {
+            MemoryImportance::Critical => true,
+            MemoryImportance::High => HIGH_KNOWLEDGE.iter().any(|p| lower.contains(p)),
+            _ => false,
+        }
+    }
+
     // ── Private helpers ──────────────────────────────────────────────────────
 
     fn is_noise(&self, content: &str) -> bool {
@@ -189,39 +232,11 @@ impl MemoryClassifier {
             return MemoryImportance::Critical;
         }
 
-        // High: file references, code structure, factual explanations, preferences
-        const HIGH: &[&str] = &[
-            "src/",
-            "~/",
-            ".rs ",
-            ".rs\"",
-            ".toml",
-            "cargo",
-            "impl ",
-            "fn ",
-            "pub ",
-            "struct ",
-            "enum ",
-            "trait ",
-            "mod ",
-            "#[",
-            "::",
-            "the reason",
-            "because ",
-            "works by",
-            "is defined in",
-            "lives in",
-            "is located",
-            "is stored",
-            "the pattern",
-            "the approach",
-            "we use ",
-            "we're using",
-            "i prefer",
-            "i like to",
-            "prefer to",
-        ];
-        if HIGH.iter().any(|p| lower.contains(p)) {
+        if HIGH_REFERENCES
+            .iter()
+            .chain(HIGH_KNOWLEDGE)
+            .any(|p| lower.contains(p))
+        {
             return MemoryImportance::High;
         }
 
@@ -442,6 +457,17 @@ mod tests {
 
     // ── MemoryImportance helpers ─────────────────────────────────────────────
 
+    #[test]
+    fn test_only_durable_user_turns_are_long_term() {
+        let c = classifier();
+        assert!(!c.is_durable("user", "Can you look at src/memory/store.rs for me?"));
+        assert!(!c.is_durable("user", "How do I run the integration tests?"));
+        assert!(c.is_durable("user", "We decided to keep the MemTree local."));
+        assert!(c.is_durable("user", "I prefer small commits with short subjects."));
+        assert!(c.is_durable("assistant", "The tests run with cargo test --workspace."));
+        assert!(c.is_durable("system", "Deploys go through staging."));
+    }
+
     #[test]
     fn test_importance_round_trip_u8() {
         for imp in [
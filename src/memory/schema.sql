@@ -27,6 +27,35 @@ CREATE TABLE IF NOT EXISTS tree_nodes (
     FOREIGN KEY (parent_id) REFERENCES tree_nodes(node_id)
 );
 
+-- Durable facts extracted from conversations (see memory/facts.rs).
+-- One row per entity key: a newer fact about the same subject replaces it.
+CREATE TABLE IF NOT EXISTS facts (
+    entity TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,  -- 'preference', 'decision' or 'fact'
+    statement TEXT NOT NULL,
+    session_id TEXT,
+    updated_at INTEGER NOT NULL
+);
+
+-- Where recalled memories came from (see memory/citations.rs), keyed by
+-- the memory's text so every store backend shares it.  Memory filters
+-- (memory/filter.rs) select on session_id, role and project.
+CREATE TABLE IF NOT EXISTS memory_sources (
+    text TEXT PRIMARY KEY,
+    session_id TEXT,
+    created_at INTEGER NOT NULL,
+    role TEXT,
+    project TEXT  -- directory the REPL ran in
+);
+
+-- How often each memory was injected into a prompt, and cited by the answer
+CREATE TABLE IF NOT EXISTS memory_recalls (
+    text TEXT PRIMARY KEY,
+    recalled INTEGER NOT NULL DEFAULT 0,
+    cited INTEGER NOT NULL DEFAULT 0,
+    last_recalled_at INTEGER NOT NULL
+);
+
 -- Metadata for tracking system state
 CREATE TABLE IF NOT EXISTS metadata (
     key TEXT PRIMARY KEY,
@@ -0,0 +1,774 @@
+// Pluggable vector storage for memory
+//
+// Conversation rows and metadata always stay in the SQLite database; what a
+// `MemoryStore` holds is the recall index, the embedded memories that
+// `MemorySystem::query` searches.  The default keeps them in the MemTree,
+// persisted to the `tree_nodes` table of the same database.  Heavy users can
+// point finch at an existing vector database instead:
+//
+//   [memory.store]
+//   backend = "qdrant"              # or "lancedb" (feature `lancedb`)
+//   url = "http://localhost:6333"
+//   collection = "finch_memory"
+//
+// Remote stores are created on first use with the embedding engine's
+// dimension; switching to an engine of another dimension (TF-IDF ↔ neural,
+// or a 768-dimension model) needs a fresh collection.  Between models of the
+// same dimension the stored memories are re-embedded in place.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::consolidate::{self, ConsolidationReport};
+use super::decay::{self, DecayReport};
+use super::embeddings::{cosine_similarity, EmbeddingEngine};
+use super::memtree::{MemTree, NodeId, TreeNode};
+
+/// `[memory.store]` in config.toml: where recalled memories live
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum MemoryStoreConfig {
+    /// MemTree in the local SQLite database
+    #[default]
+    Local,
+    /// A Qdrant server, over its REST API
+    Qdrant {
+        /// Server URL, e.g. `http://localhost:6333`
+        url: String,
+        #[serde(default = "default_collection")]
+        collection: String,
+        /// API key (falls back to `QDRANT_API_KEY`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+    },
+    /// A LanceDB database: a local directory or an object-store URI
+    Lancedb {
+        /// e.g. `~/.finch/lancedb` or `s3://bucket/finch`
+        uri: String,
+        #[serde(default = "default_collection")]
+        table: String,
+    },
+}
+
+fn default_collection() -> String {
+    "finch_memory".to_string()
+}
+
+impl MemoryStoreConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::Local
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Local => {}
+            Self::Qdrant {
+                url, collection, ..
+            } => {
+                let parsed = reqwest::Url::parse(url)
+                    .with_context(|| format!("memory.store: invalid Qdrant url '{}'", url))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    bail!("memory.store: Qdrant url must be http or https");
+                }
+                validate_name("collection", collection)?;
+            }
+            Self::Lancedb { uri, table } => {
+                if uri.is_empty() {
+                    bail!("memory.store: uri is empty");
+                }
+                validate_name("table", table)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_name(field: &str, name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        bail!(
+            "memory.store: '{}' is not a valid {} name (use a-z, 0-9, _ and -)",
+            name,
+            field
+        );
+    }
+    Ok(())
+}
+
+/// A memory to add
+#[derive(Debug, Clone)]
+pub struct NewMemory {
+    pub text: String,
+    pub embedding: Vec<f32>,
+    /// Tier from `MemoryClassifier` (0–3)
+    pub importance: u8,
+    /// Unix seconds; `None` stamps it with the current time
+    pub created_at: Option<i64>,
+}
+
+/// A memory read back from a store
+#[derive(Debug, Clone)]
+pub struct StoredMemory {
+    /// Store-specific id, as taken by `remove`
+    pub id: String,
+    pub text: String,
+    pub embedding: Vec<f32>,
+    pub importance: u8,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+/// What a store search may match, from a `MemoryFilter`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilter {
+    pub min_importance: Option<u8>,
+    /// Created at or after (unix seconds)
+    pub since: Option<i64>,
+    /// Created before (unix seconds)
+    pub until: Option<i64>,
+    /// Only memories with one of these texts
+    pub texts: Option<HashSet<String>>,
+}
+
+impl SearchFilter {
+    pub fn matches(&self, text: &str, importance: u8, created_at: i64) -> bool {
+        self.min_importance.is_none_or(|min| importance >= min)
+            && self.since.is_none_or(|since| created_at >= since)
+            && self.until.is_none_or(|until| created_at < until)
+            && self.texts.as_ref().is_none_or(|texts| texts.contains(text))
+    }
+}
+
+/// A search match
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryHit {
+    /// Store-specific id, as taken by `remove` and `set_importance`
+    pub id: String,
+    pub text: String,
+    pub importance: u8,
+    /// Similarity weighted by importance
+    pub score: f32,
+}
+
+/// Recall index behind `MemorySystem`
+#[async_trait]
+pub trait MemoryStore: Send + Sync {
+    /// Add memories, stamped with the current time unless they carry one
+    async fn insert(&self, memories: Vec<NewMemory>) -> Result<()>;
+
+    /// The `k` best matches for `embedding`, best first.  Scores are
+    /// weighted by importance and Discard-tier memories never match.
+    async fn search(&self, embedding: &[f32], k: usize) -> Result<Vec<MemoryHit>> {
+        self.search_where(embedding, k, &SearchFilter::default())
+            .await
+    }
+
+    /// Like `search`, among the memories `filter` matches only
+    async fn search_where(
+        &self,
+        embedding: &[f32],
+        k: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<MemoryHit>>;
+
+    /// Number of stored memories
+    async fn count(&self) -> Result<usize>;
+
+    /// Up to `limit` memories, newest first
+    async fn recent(&self, limit: usize) -> Result<Vec<StoredMemory>>;
+
+    /// Memories created before `cutoff` (unix seconds), for archiving
+    async fn older_than(&self, cutoff: i64) -> Result<Vec<StoredMemory>>;
+
+    /// Remove memories by id, returning how many were removed
+    async fn remove(&self, ids: &[String]) -> Result<usize>;
+
+    /// Change a memory's importance tier; false if there is no memory `id`
+    async fn set_importance(&self, id: &str, importance: u8) -> Result<bool>;
+
+    /// Forget memories that are old for their importance (see
+    /// `decay::forgets`), as of `now` (unix seconds).  Stores without a
+    /// hierarchy just remove them.
+    async fn decay(&self, horizon_secs: i64, now: i64) -> Result<DecayReport> {
+        let ids: Vec<String> = self
+            .older_than(now.saturating_sub(horizon_secs))
+            .await?
+            .into_iter()
+            .filter(|m| decay::forgets(m.importance, now - m.created_at, horizon_secs))
+            .map(|m| m.id)
+            .collect();
+        Ok(DecayReport {
+            forgotten: self.remove(&ids).await?,
+            folded: 0,
+        })
+    }
+
+    /// Merge groups of memories at least `similarity` alike (see
+    /// `consolidate::groups`).  Stores without a hierarchy keep the newest
+    /// of each group at the group's highest importance and remove the rest.
+    async fn consolidate(
+        &self,
+        similarity: f32,
+        _engine: &dyn EmbeddingEngine,
+    ) -> Result<ConsolidationReport> {
+        let memories = self.older_than(i64::MAX).await?;
+        let mut report = ConsolidationReport {
+            scanned: memories.len(),
+            ..Default::default()
+        };
+        for group in consolidate::groups(&memories, similarity) {
+            let keep = &memories[group[0]];
+            let importance = group.iter().map(|&i| memories[i].importance).max();
+            if let Some(importance) = importance.filter(|&i| i > keep.importance) {
+                self.set_importance(&keep.id, importance).await?;
+            }
+            let ids: Vec<String> = group[1..].iter().map(|&i| memories[i].id.clone()).collect();
+            report.groups += 1;
+            report.merged += self.remove(&ids).await?;
+        }
+        Ok(report)
+    }
+
+    /// Replace memories' embeddings, keyed by id, after the embedding model
+    /// changed.  Stores without a hierarchy insert the memories again with
+    /// their new embedding and then remove the old ones.
+    async fn reembed(&self, embeddings: Vec<(String, Vec<f32>)>, _dim: usize) -> Result<()> {
+        let mut embeddings: HashMap<String, Vec<f32>> = embeddings.into_iter().collect();
+        let mut ids = Vec::new();
+        let mut memories = Vec::new();
+        for memory in self.older_than(i64::MAX).await? {
+            if let Some(embedding) = embeddings.remove(&memory.id) {
+                ids.push(memory.id);
+                memories.push(NewMemory {
+                    text: memory.text,
+                    embedding,
+                    importance: memory.importance,
+                    created_at: Some(memory.created_at),
+                });
+            }
+        }
+        self.insert(memories).await?;
+        self.remove(&ids).await?;
+        Ok(())
+    }
+
+    /// Where memories go, for logs (a database path or a collection URL)
+    fn location(&self) -> String;
+}
+
+/// Multiplier applied to a match's similarity, by importance tier
+pub(crate) fn importance_boost(importance: u8) -> f32 {
+    match importance {
+        0 => 0.0,
+        3 => 1.4,
+        2 => 1.2,
+        _ => 1.0,
+    }
+}
+
+/// The configured remote store for embeddings of `dim` dimensions
+pub fn remote(config: &MemoryStoreConfig, dim: usize) -> Result<Arc<dyn MemoryStore>> {
+    match config {
+        MemoryStoreConfig::Local => bail!("The local memory store is not remote"),
+        MemoryStoreConfig::Qdrant {
+            url,
+            collection,
+            api_key,
+        } => {
+            let api_key = api_key
+                .clone()
+                .or_else(|| std::env::var("QDRANT_API_KEY").ok());
+            Ok(Arc::new(super::qdrant::QdrantStore::new(
+                url, collection, api_key, dim,
+            )?))
+        }
+        #[cfg(feature = "lancedb")]
+        MemoryStoreConfig::Lancedb { uri, table } => {
+            Ok(Arc::new(super::lance::LanceStore::new(uri, table, dim)))
+        }
+        #[cfg(not(feature = "lancedb"))]
+        MemoryStoreConfig::Lancedb { .. } => {
+            bail!("memory.store: finch was built without the lancedb feature")
+        }
+    }
+}
+
+/// MemTree persisted to the `tree_nodes` table
+pub struct LocalStore {
+    db: Arc<Mutex<Connection>>,
+    tree: Mutex<MemTree>,
+    location: String,
+}
+
+impl LocalStore {
+    /// Store over `db`, starting from `tree` (see `load_tree`)
+    pub fn new(db: Arc<Mutex<Connection>>, tree: MemTree, location: String) -> Self {
+        Self {
+            db,
+            tree: Mutex::new(tree),
+            location,
+        }
+    }
+
+    /// The MemTree persisted in `conn`, or an empty one of `dim` dimensions
+    /// if there is none or it can't be read
+    pub fn load_tree(conn: &Connection, dim: usize) -> MemTree {
+        let mut tree = MemTree::new_with_dim(dim);
+        let node_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM tree_nodes", [], |row| row.get(0))
+            .unwrap_or(0);
+        if node_count > 0 {
+            if let Err(e) = load_tree_from_db_conn(conn, &mut tree) {
+                tracing::warn!("Failed to load MemTree from DB (will start fresh): {}", e);
+                tree = MemTree::new_with_dim(dim);
+            } else {
+                tracing::info!("Loaded MemTree with {} nodes from disk", tree.size());
+            }
+        }
+        tree
+    }
+
+    /// Persist all MemTree nodes to the tree_nodes table in a single transaction.
+    ///
+    /// Nodes are written sorted by node_id (root first) so that the self-referential
+    /// FK constraint `parent_id → node_id` is satisfied for each INSERT.
+    ///
+    /// This replaces the old `save_node_to_db(leaf_id)` approach which only persisted
+    /// the newly inserted leaf.  That missed two things:
+    ///   1. The root node (id=0) was never written, causing FK violations because
+    ///      libsqlite3-sys bundles SQLite compiled with SQLITE_DEFAULT_FOREIGN_KEYS=1.
+    ///   2. Parent embeddings updated by `update_parent_aggregation` were never
+    ///      persisted, so embeddings went stale across process restarts.
+    async fn save_all_nodes_to_db(&self) -> Result<()> {
+        let mut nodes: Vec<TreeNode> = {
+            let tree = self.tree.lock().await;
+            tree.all_nodes().values().cloned().collect()
+        };
+
+        // Sort by node_id ascending so root (id=0) is written before its children.
+        // SQLite enforces the self-referential FK immediately (IMMEDIATE mode),
+        // so parent rows must exist before child rows within the transaction.
+        nodes.sort_by_key(|n| n.id);
+
+        let conn = self.db.lock().await;
+        let tx = conn.unchecked_transaction()?;
+        for node in &nodes {
+            let embedding_bytes: Vec<u8> = node
+                .embedding
+                .iter()
+                .flat_map(|f| f.to_le_bytes())
+                .collect();
+            tx.execute(
+                "INSERT OR REPLACE INTO tree_nodes
+                 (node_id, parent_id, text, embedding, level, created_at, importance)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    node.id as i64,
+                    node.parent.map(|p| p as i64),
+                    &node.text,
+                    &embedding_bytes,
+                    node.level as i64,
+                    node.created_at,
+                    node.importance as i64,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete removed nodes' rows, then persist the rest: ancestors of
+    /// removed leaves have fresh aggregate embeddings (and maybe text)
+    async fn delete_nodes(&self, removed: &[NodeId]) -> Result<()> {
+        {
+            let conn = self.db.lock().await;
+            let tx = conn.unchecked_transaction()?;
+            for &id in removed {
+                tx.execute("DELETE FROM tree_nodes WHERE node_id = ?1", [id as i64])?;
+            }
+            tx.commit()?;
+        }
+        self.save_all_nodes_to_db().await
+    }
+
+    /// Leaves matching `keep`, newest first
+    async fn leaves(&self, keep: impl Fn(&TreeNode) -> bool) -> Vec<StoredMemory> {
+        let tree = self.tree.lock().await;
+        let mut leaves: Vec<StoredMemory> = tree
+            .all_nodes()
+            .values()
+            .filter(|n| n.id != 0 && n.children.is_empty() && keep(n))
+            .map(|n| StoredMemory {
+                id: n.id.to_string(),
+                text: n.text.clone(),
+                embedding: n.embedding.clone(),
+                importance: n.importance,
+                created_at: n.created_at,
+            })
+            .collect();
+        leaves.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+        leaves
+    }
+}
+
+#[async_trait]
+impl MemoryStore for LocalStore {
+    async fn insert(&self, memories: Vec<NewMemory>) -> Result<()> {
+        {
+            let mut tree = self.tree.lock().await;
+            for memory in memories {
+                match memory.created_at {
+                    Some(created_at) => tree.insert_at(
+                        memory.text,
+                        memory.embedding,
+                        memory.importance,
+                        created_at,
+                    )?,
+                    None => tree.insert(memory.text, memory.embedding, memory.importance)?,
+                };
+            }
+        }
+        // Persist all nodes (root + ancestors + new leaves) so the DB stays
+        // consistent across process restarts and FK constraints are satisfied.
+        self.save_all_nodes_to_db().await
+    }
+
+    async fn search_where(
+        &self,
+        embedding: &[f32],
+        k: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<MemoryHit>> {
+        let tree = self.tree.lock().await;
+        Ok(tree
+            .retrieve_where(embedding, k, |node| {
+                filter.matches(&node.text, node.importance, node.created_at)
+            })
+            .into_iter()
+            .map(|(id, text, score)| MemoryHit {
+                id: id.to_string(),
+                text,
+                importance: tree.get_node(id).map_or(1, |n| n.importance),
+                score,
+            })
+            .collect())
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.tree.lock().await.size())
+    }
+
+    async fn recent(&self, limit: usize) -> Result<Vec<StoredMemory>> {
+        let mut leaves = self.leaves(|_| true).await;
+        leaves.truncate(limit);
+        Ok(leaves)
+    }
+
+    async fn older_than(&self, cutoff: i64) -> Result<Vec<StoredMemory>> {
+        Ok(self.leaves(|n| n.created_at < cutoff).await)
+    }
+
+    async fn remove(&self, ids: &[String]) -> Result<usize> {
+        // A leaf that gained a child since it was collected is an inner node
+        // now; it stays in the tree
+        let removed: Vec<NodeId> = {
+            let mut tree = self.tree.lock().await;
+            let mut removed = Vec::new();
+            for id in ids.iter().filter_map(|id| id.parse::<NodeId>().ok()) {
+                if tree.remove_leaf(id)?.is_some() {
+                    removed.push(id);
+                }
+            }
+            removed
+        };
+        self.delete_nodes(&removed).await?;
+        Ok(removed.len())
+    }
+
+    async fn set_importance(&self, id: &str, importance: u8) -> Result<bool> {
+        let Ok(id) = id.parse::<NodeId>() else {
+            return Ok(false);
+        };
+        if !self.tree.lock().await.set_importance(id, importance) {
+            return Ok(false);
+        }
+        self.db.lock().await.execute(
+            "UPDATE tree_nodes SET importance = ?1 WHERE node_id = ?2",
+            params![importance as i64, id as i64],
+        )?;
+        Ok(true)
+    }
+
+    async fn decay(&self, horizon_secs: i64, now: i64) -> Result<DecayReport> {
+        let mut report = DecayReport::default();
+        let removed: Vec<NodeId> = {
+            let mut tree = self.tree.lock().await;
+            let mut candidates: Vec<(i64, NodeId)> = tree
+                .all_nodes()
+                .values()
+                .filter(|n| n.id != 0 && n.children.is_empty())
+                .filter(|n| decay::forgets(n.importance, now - n.created_at, horizon_secs))
+                .map(|n| (n.created_at, n.id))
+                .collect();
+            // Oldest first.  A parent left without children waits for the
+            // next run, so its own age decides whether it goes too.
+            candidates.sort_unstable();
+            let mut removed = Vec::new();
+            for (_, id) in candidates {
+                if let Some((_, kept)) = tree.fold_into_parent(id)? {
+                    report.forgotten += 1;
+                    report.folded += kept as usize;
+                    removed.push(id);
+                }
+            }
+            removed
+        };
+        if !removed.is_empty() {
+            self.delete_nodes(&removed).await?;
+        }
+        Ok(report)
+    }
+
+    async fn consolidate(
+        &self,
+        similarity: f32,
+        engine: &dyn EmbeddingEngine,
+    ) -> Result<ConsolidationReport> {
+        let leaves = self.leaves(|_| true).await;
+        let mut report = ConsolidationReport {
+            scanned: leaves.len(),
+            ..Default::default()
+        };
+        let removed: Vec<NodeId> = {
+            let mut tree = self.tree.lock().await;
+            let mut removed = Vec::new();
+            for group in consolidate::groups(&leaves, similarity) {
+                let mut ids = group
+                    .iter()
+                    .filter_map(|&i| leaves[i].id.parse::<NodeId>().ok());
+                let Some(into) = ids.next() else {
+                    continue;
+                };
+                let merged = tree.merge_leaves(into, &ids.collect::<Vec<_>>())?;
+                if !merged.is_empty() {
+                    report.groups += 1;
+                    report.merged += merged.len();
+                    removed.extend(merged);
+                }
+            }
+
+            // A restatement is filed under the memory it repeats, so leaves
+            // are also merged into a parent whose own text they duplicate.
+            // A parent's embedding is its children's average, so its text is
+            // embedded again.  Merging can turn the parent into a leaf that
+            // repeats its own parent, hence the loop.
+            let mut parent_embeddings: HashMap<NodeId, Vec<f32>> = HashMap::new();
+            loop {
+                let pairs: Vec<(NodeId, NodeId)> = tree
+                    .all_nodes()
+                    .values()
+                    .filter(|n| n.id != 0 && n.children.is_empty() && n.importance > 0)
+                    .filter_map(|n| Some((n.id, n.parent.filter(|&p| p != 0)?)))
+                    .collect();
+                let mut by_parent: BTreeMap<NodeId, Vec<NodeId>> = BTreeMap::new();
+                for (leaf, parent) in pairs {
+                    let (Some(leaf_node), Some(parent_node)) =
+                        (tree.get_node(leaf), tree.get_node(parent))
+                    else {
+                        continue;
+                    };
+                    // A forgotten parent stays forgotten
+                    if parent_node.importance == 0 {
+                        continue;
+                    }
+                    let parent_embedding = match parent_embeddings.entry(parent) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => entry.insert(engine.embed(&parent_node.text)?),
+                    };
+                    if cosine_similarity(&leaf_node.embedding, parent_embedding) >= similarity {
+                        by_parent.entry(parent).or_default().push(leaf);
+                    }
+                }
+                let mut changed = false;
+                for (parent, leaves) in by_parent {
+                    let merged = tree.merge_leaves(parent, &leaves)?;
+                    if !merged.is_empty() {
+                        changed = true;
+                        report.groups += 1;
+                        report.merged += merged.len();
+                        removed.extend(merged);
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+            removed
+        };
+        if !removed.is_empty() {
+            self.delete_nodes(&removed).await?;
+        }
+        Ok(report)
+    }
+
+    async fn reembed(&self, embeddings: Vec<(String, Vec<f32>)>, dim: usize) -> Result<()> {
+        let embeddings = embeddings
+            .into_iter()
+            .filter_map(|(id, embedding)| Some((id.parse().ok()?, embedding)))
+            .collect();
+        self.tree.lock().await.reembed(embeddings, dim);
+        self.save_all_nodes_to_db().await
+    }
+
+    fn location(&self) -> String {
+        self.location.clone()
+    }
+}
+
+/// Reconstruct MemTree from the tree_nodes table at startup.
+fn load_tree_from_db_conn(conn: &Connection, tree: &mut MemTree) -> Result<()> {
+    struct Row {
+        node_id: u64,
+        parent_id: Option<u64>,
+        text: String,
+        embedding: Vec<f32>,
+        level: usize,
+        created_at: i64,
+        importance: u8,
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id, parent_id, text, embedding, level, created_at, importance
+         FROM tree_nodes ORDER BY node_id ASC",
+    )?;
+
+    let rows: Vec<Row> = stmt
+        .query_map([], |row| {
+            let node_id: i64 = row.get(0)?;
+            let parent_id: Option<i64> = row.get(1)?;
+            let text: String = row.get(2)?;
+            let embedding_bytes: Vec<u8> = row.get(3)?;
+            let level: i64 = row.get(4)?;
+            let created_at: i64 = row.get(5)?;
+            let importance: i64 = row.get(6).unwrap_or(1);
+            Ok((
+                node_id,
+                parent_id,
+                text,
+                embedding_bytes,
+                level,
+                created_at,
+                importance,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(
+            |(node_id, parent_id, text, embedding_bytes, level, created_at, importance)| Row {
+                node_id: node_id as u64,
+                parent_id: parent_id.map(|p| p as u64),
+                text,
+                embedding: embedding_bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+                level: level as usize,
+                created_at,
+                importance: importance.clamp(0, 3) as u8,
+            },
+        )
+        .collect();
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let nodes = tree.all_nodes_mut();
+    let mut max_id: u64 = 0;
+
+    // First pass: insert all nodes
+    for row in &rows {
+        max_id = max_id.max(row.node_id);
+        nodes.insert(
+            row.node_id,
+            TreeNode {
+                id: row.node_id,
+                parent: row.parent_id,
+                children: Vec::new(),
+                text: row.text.clone(),
+                embedding: row.embedding.clone(),
+                level: row.level,
+                created_at: row.created_at,
+                importance: row.importance,
+            },
+        );
+    }
+
+    // Second pass: rebuild children lists
+    for row in &rows {
+        if let Some(parent_id) = row.parent_id {
+            if let Some(parent) = nodes.get_mut(&parent_id) {
+                if !parent.children.contains(&row.node_id) {
+                    parent.children.push(row.node_id);
+                }
+            }
+        }
+    }
+
+    // Advance next_id past all loaded IDs
+    tree.set_next_id(max_id + 1);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_config() {
+        #[derive(Deserialize)]
+        struct Memory {
+            store: MemoryStoreConfig,
+        }
+        let parse = |toml: &str| toml::from_str::<Memory>(toml).map(|m| m.store);
+
+        let store = parse(
+            r#"
+            [store]
+            backend = "qdrant"
+            url = "http://localhost:6333"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            store,
+            MemoryStoreConfig::Qdrant {
+                url: "http://localhost:6333".to_string(),
+                collection: "finch_memory".to_string(),
+                api_key: None,
+            }
+        );
+        store.validate().unwrap();
+
+        let store = parse("[store]\nbackend = \"lancedb\"\nuri = \"/data/lance\"").unwrap();
+        store.validate().unwrap();
+
+        assert!(parse("[store]\nbackend = \"pinecone\"").is_err());
+        let bad = parse("[store]\nbackend = \"qdrant\"\nurl = \"localhost:6333\"").unwrap();
+        assert!(bad.validate().is_err());
+        let bad =
+            parse("[store]\nbackend = \"qdrant\"\nurl = \"http://q:6333\"\ncollection = \"a b\"")
+                .unwrap();
+        assert!(bad.validate().is_err());
+    }
+}
@@ -0,0 +1,322 @@
+// Portable export and import of the memory database
+//
+// `finch memory export` writes the `conversations`, `tree_nodes` and `facts`
+// tables as JSON lines, one record per row, so a memory.db can move to another
+// machine or into a backup without copying SQLite files around.  Embeddings
+// are optional: they make an export several times larger, and they only help
+// when the importing side runs the same embedding engine.  Import keeps an
+// exported embedding whose dimension matches the current engine and
+// re-embeds the text otherwise.
+//
+// Import merges instead of replacing.  Conversations keep their ids, and rows
+// already present are skipped; a fact replaces one with the same entity key
+// only if it is newer.  Memories are inserted again in their original
+// order with their importance and age, so the MemTree rebuilds its own
+// structure (or the configured remote store receives them); a memory whose
+// text is already recalled verbatim is skipped.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+use super::{FactKind, MemorySystem, NewMemory};
+
+/// Nearest memories checked for an identical text before importing one.  A
+/// stored copy scores 1.0 against its own embedding, so only near-duplicates
+/// with an importance boost can push it further down.
+const DUPLICATE_CHECK_K: usize = 10;
+
+/// One exported row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransferRecord {
+    /// A row of the `conversations` table
+    Conversation {
+        id: String,
+        /// Unix nanoseconds, as stored
+        timestamp: i64,
+        role: String,
+        content: String,
+        tokens: Option<i64>,
+        model: Option<String>,
+        session_id: Option<String>,
+        created_at: i64,
+    },
+    /// A row of the `tree_nodes` table, other than the root
+    Memory {
+        node_id: u64,
+        parent_id: Option<u64>,
+        level: usize,
+        text: String,
+        importance: u8,
+        /// Unix seconds
+        created_at: i64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        embedding: Option<Vec<f32>>,
+    },
+    /// A row of the `facts` table
+    Fact {
+        entity: String,
+        fact_kind: FactKind,
+        statement: String,
+        session_id: Option<String>,
+        /// Unix seconds
+        updated_at: i64,
+    },
+}
+
+/// What an export or import moved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferReport {
+    pub conversations: usize,
+    pub memories: usize,
+    pub facts: usize,
+    /// Import only: rows and memories that were already present
+    pub skipped: usize,
+    /// Import only: memories embedded again by the current engine
+    pub reembedded: usize,
+}
+
+/// Write every conversation and memory in `conn` to `out` as JSON lines
+pub fn export(conn: &Connection, out: &mut dyn Write, embeddings: bool) -> Result<TransferReport> {
+    let mut report = TransferReport::default();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, role, content, tokens, model, session_id, created_at
+         FROM conversations ORDER BY timestamp ASC",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        write_record(
+            out,
+            &TransferRecord::Conversation {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                role: row.get(2)?,
+                content: row.get(3)?,
+                tokens: row.get(4)?,
+                model: row.get(5)?,
+                session_id: row.get(6)?,
+                created_at: row.get(7)?,
+            },
+        )?;
+        report.conversations += 1;
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id, parent_id, level, text, importance, created_at, embedding
+         FROM tree_nodes WHERE node_id != 0 ORDER BY node_id ASC",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let embedding = if embeddings {
+            let bytes: Vec<u8> = row.get(6)?;
+            Some(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        write_record(
+            out,
+            &TransferRecord::Memory {
+                node_id: row.get::<_, i64>(0)? as u64,
+                parent_id: row.get::<_, Option<i64>>(1)?.map(|p| p as u64),
+                level: row.get::<_, i64>(2)? as usize,
+                text: row.get(3)?,
+                importance: row.get::<_, i64>(4)?.clamp(0, 3) as u8,
+                created_at: row.get(5)?,
+                embedding,
+            },
+        )?;
+        report.memories += 1;
+    }
+
+    for fact in super::facts::all(conn)? {
+        write_record(
+            out,
+            &TransferRecord::Fact {
+                entity: fact.entity,
+                fact_kind: fact.kind,
+                statement: fact.statement,
+                session_id: fact.session_id,
+                updated_at: fact.updated_at,
+            },
+        )?;
+        report.facts += 1;
+    }
+
+    out.flush()?;
+    Ok(report)
+}
+
+fn write_record(out: &mut dyn Write, record: &TransferRecord) -> Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Parse an export, skipping blank lines
+pub fn read_records(input: impl BufRead) -> Result<Vec<TransferRecord>> {
+    let mut records = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("Line {} is not a memory export record", i + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Merge `records` into `memory`
+pub async fn import(memory: &MemorySystem, records: Vec<TransferRecord>) -> Result<TransferReport> {
+    let mut report = TransferReport::default();
+    let mut memories = Vec::new();
+
+    {
+        let conn = memory.db.lock().await;
+        let tx = conn.unchecked_transaction()?;
+        for record in records {
+            match record {
+                TransferRecord::Conversation {
+                    id,
+                    timestamp,
+                    role,
+                    content,
+                    tokens,
+                    model,
+                    session_id,
+                    created_at,
+                } => {
+                    let inserted = tx.execute(
+                        "INSERT OR IGNORE INTO conversations
+                         (id, timestamp, role, content, tokens, model, session_id, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            id, timestamp, role, content, tokens, model, session_id, created_at
+                        ],
+                    )?;
+                    if inserted > 0 {
+                        report.conversations += 1;
+                    } else {
+                        report.skipped += 1;
+                    }
+                }
+                TransferRecord::Memory {
+                    text,
+                    importance,
+                    created_at,
+                    embedding,
+                    ..
+                } => memories.push((text, importance, created_at, embedding)),
+                TransferRecord::Fact {
+                    entity,
+                    fact_kind,
+                    statement,
+                    session_id,
+                    updated_at,
+                } => {
+                    let changed = tx.execute(
+                        "INSERT INTO facts (entity, kind, statement, session_id, updated_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5)
+                         ON CONFLICT(entity) DO UPDATE SET
+                             kind = excluded.kind,
+                             statement = excluded.statement,
+                             session_id = excluded.session_id,
+                             updated_at = excluded.updated_at
+                         WHERE excluded.updated_at > facts.updated_at",
+                        params![
+                            entity,
+                            fact_kind.as_str(),
+                            statement,
+                            session_id,
+                            updated_at
+                        ],
+                    )?;
+                    if changed > 0 {
+                        report.facts += 1;
+                    } else {
+                        report.skipped += 1;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+    }
+
+    let dim = memory.embedding_engine.dimension();
+    let mut seen = HashSet::new();
+    let mut new = Vec::new();
+    for (text, importance, created_at, embedding) in memories {
+        let embedding = match embedding {
+            Some(embedding) if embedding.len() == dim => embedding,
+            _ => {
+                report.reembedded += 1;
+                memory.embedding_engine.embed(&text)?
+            }
+        };
+        let present = memory.store.search(&embedding, DUPLICATE_CHECK_K).await?;
+        if present.iter().any(|hit| hit.text == text) || !seen.insert(text.clone()) {
+            report.skipped += 1;
+            continue;
+        }
+        new.push(NewMemory {
+            text,
+            embedding,
+            importance,
+            created_at: Some(created_at),
+        });
+    }
+    report.memories = new.len();
+    memory.store.insert(new).await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_round_trip_as_json_lines() {
+        let records = vec![
+            TransferRecord::Conversation {
+                id: "c1".to_string(),
+                timestamp: 1_700_000_000_000_000_000,
+                role: "user".to_string(),
+                content: "how do I deploy?".to_string(),
+                tokens: None,
+                model: None,
+                session_id: Some("s1".to_string()),
+                created_at: 1_700_000_000_000_000_000,
+            },
+            TransferRecord::Memory {
+                node_id: 1,
+                parent_id: Some(0),
+                level: 1,
+                text: "deploys go through staging".to_string(),
+                importance: 2,
+                created_at: 1_700_000_000,
+                embedding: None,
+            },
+        ];
+        let mut out = Vec::new();
+        for record in &records {
+            write_record(&mut out, record).unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        assert!(!text.contains("embedding"));
+
+        let parsed = read_records(format!("{}\n", text).as_bytes()).unwrap();
+        assert_eq!(parsed, records);
+        assert!(read_records("{\"kind\":\"memory\"}".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+// Memory usage, for `finch memory stats --verbose`
+//
+// Whether memory is helping shows in how it is used: which memories recall
+// keeps bringing back, and whether answers cite the memories injected into
+// their prompt (see `citations`).  Each answered REPL prompt adds to the
+// `memory_recalls` table, keyed by memory text like `memory_sources`; counts
+// by importance and growth come from the store itself.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Utc};
+use rusqlite::{params, Connection};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::citations::{self, RecalledMemory};
+use super::store::StoredMemory;
+
+/// Months of growth shown, this one included
+pub const GROWTH_MONTHS: usize = 6;
+
+/// Most recalled memories shown
+pub const TOP_RECALLED: usize = 5;
+
+/// Longest memory text shown in the top-recalled list
+const PREVIEW_CHARS: usize = 60;
+
+/// Count the memories injected for one prompt, and which of them `answer` cited
+pub(crate) fn record(conn: &Connection, recalled: &[RecalledMemory], answer: &str) -> Result<()> {
+    let cited = citations::cited(answer, recalled);
+    let now = Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    for memory in recalled {
+        let was_cited = cited.iter().any(|c| c.id == memory.id);
+        tx.execute(
+            "INSERT INTO memory_recalls (text, recalled, cited, last_recalled_at)
+             VALUES (?1, 1, ?2, ?3)
+             ON CONFLICT(text) DO UPDATE SET
+                 recalled = recalled + 1,
+                 cited = cited + excluded.cited,
+                 last_recalled_at = excluded.last_recalled_at",
+            params![memory.text, was_cited as i64, now],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// A memory by how often it was recalled
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopRecall {
+    pub text: String,
+    /// Prompts it was injected into
+    pub recalled: u64,
+    /// Answers that cited it
+    pub cited: u64,
+}
+
+/// How memory is used, as shown by `finch memory stats --verbose`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryUsage {
+    /// Memories per importance tier, Discard (0) to Critical (3)
+    pub by_importance: [usize; 4],
+    /// Memories added per month ("2026-09"), the last `GROWTH_MONTHS`
+    /// months, oldest first
+    pub growth: Vec<(String, usize)>,
+    pub top_recalled: Vec<TopRecall>,
+    /// Memories injected into prompts, counting repeats
+    pub injected: u64,
+    /// Of those, how many the answer cited
+    pub cited: u64,
+    /// Database size on disk, write-ahead log included
+    pub db_bytes: u64,
+}
+
+impl MemoryUsage {
+    /// Usage of `memories` as of `now`, with the recall counts from `conn`
+    /// and the size of the database at `db_path`
+    pub(crate) fn collect(
+        conn: &Connection,
+        memories: &[StoredMemory],
+        db_path: &Path,
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let mut by_importance = [0; 4];
+        for memory in memories {
+            by_importance[memory.importance.min(3) as usize] += 1;
+        }
+
+        let (injected, cited): (i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(recalled), 0), COALESCE(SUM(cited), 0) FROM memory_recalls",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT text, recalled, cited FROM memory_recalls
+             ORDER BY recalled DESC, last_recalled_at DESC
+             LIMIT ?1",
+        )?;
+        let top_recalled = stmt
+            .query_map([TOP_RECALLED as i64], |row| {
+                Ok(TopRecall {
+                    text: row.get(0)?,
+                    recalled: row.get::<_, i64>(1)? as u64,
+                    cited: row.get::<_, i64>(2)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The WAL holds writes not yet checkpointed into the main file
+        let mut wal = db_path.as_os_str().to_owned();
+        wal.push("-wal");
+        let db_bytes = [db_path, Path::new(&wal)]
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum();
+
+        Ok(Self {
+            by_importance,
+            growth: growth(memories, now),
+            top_recalled,
+            injected: injected as u64,
+            cited: cited as u64,
+            db_bytes,
+        })
+    }
+
+    /// Share of injected memories the answer cited; `None` before any recall
+    pub fn hit_rate(&self) -> Option<f64> {
+        (self.injected > 0).then(|| self.cited as f64 / self.injected as f64)
+    }
+
+    /// The dashboard as plain text
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let total: usize = self.by_importance.iter().sum();
+        let _ = writeln!(out, "By importance ({} memories):", total);
+        for (name, count) in ["Critical", "High", "Normal", "Discard"]
+            .iter()
+            .zip(self.by_importance.iter().rev())
+        {
+            let _ = writeln!(out, "  {:<9}{:>6}", name, count);
+        }
+
+        let _ = writeln!(out, "\nAdded per month:");
+        let most = self.growth.iter().map(|(_, n)| *n).max().unwrap_or(0);
+        for (month, count) in &self.growth {
+            let bar = "█".repeat((count * 30).div_ceil(most.max(1)));
+            let _ = writeln!(out, "  {}  {:>5}  {}", month, count, bar);
+        }
+
+        let _ = writeln!(out, "\nMost recalled:");
+        if self.top_recalled.is_empty() {
+            let _ = writeln!(out, "  (nothing recalled yet)");
+        }
+        for top in &self.top_recalled {
+            let _ = writeln!(
+                out,
+                "  {:>4}×  cited {:>3}  {}",
+                top.recalled,
+                top.cited,
+                preview(&top.text)
+            );
+        }
+
+        let _ = writeln!(out);
+        match self.hit_rate() {
+            Some(rate) => {
+                let _ = writeln!(
+                    out,
+                    "Hit rate: {:.0}% of injected memories were cited ({} of {})",
+                    rate * 100.0,
+                    self.cited,
+                    self.injected
+                );
+            }
+            None => {
+                let _ = writeln!(out, "Hit rate: no memories injected yet");
+            }
+        }
+        let _ = writeln!(out, "Database: {}", human_bytes(self.db_bytes));
+        out
+    }
+}
+
+/// Memories added in each of the last `GROWTH_MONTHS` months
+fn growth(memories: &[StoredMemory], now: DateTime<Utc>) -> Vec<(String, usize)> {
+    let month_index = |at: DateTime<Utc>| at.year() as i64 * 12 + at.month0() as i64;
+    let current = month_index(now);
+    let first = current - GROWTH_MONTHS as i64 + 1;
+    let mut counts = vec![0; GROWTH_MONTHS];
+    for memory in memories {
+        let Some(at) = DateTime::from_timestamp(memory.created_at, 0) else {
+            continue;
+        };
+        let month = month_index(at);
+        if (first..=current).contains(&month) {
+            counts[(month - first) as usize] += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let month = first + i as i64;
+            (format!("{:04}-{:02}", month / 12, month % 12 + 1), count)
+        })
+        .collect()
+}
+
+/// First line of `text`, cut at `PREVIEW_CHARS`
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > PREVIEW_CHARS {
+        let cut: String = line.chars().take(PREVIEW_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MB", b as f64 / (1u64 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KB", b as f64 / (1u64 << 10) as f64),
+        b => format!("{} B", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_counts_the_last_months() {
+        let at = |date: &str| StoredMemory {
+            id: String::new(),
+            text: String::new(),
+            embedding: Vec::new(),
+            importance: 1,
+            created_at: DateTime::parse_from_rfc3339(date).unwrap().timestamp(),
+        };
+        let memories = [
+            at("2026-10-02T09:00:00Z"),
+            at("2026-10-17T09:00:00Z"),
+            at("2026-06-30T23:00:00Z"),
+            at("2026-05-31T23:00:00Z"),
+        ];
+        let now = DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            growth(&memories, now),
+            [
+                ("2026-05".to_string(), 1),
+                ("2026-06".to_string(), 1),
+                ("2026-07".to_string(), 0),
+                ("2026-08".to_string(), 0),
+                ("2026-09".to_string(), 0),
+                ("2026-10".to_string(), 2),
+            ]
+        );
+        assert_eq!(preview("short\nsecond line"), "short");
+        assert_eq!(human_bytes(3 << 20), "3.0 MB");
+    }
+}
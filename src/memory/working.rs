@@ -0,0 +1,226 @@
+// Working memory: what the current session is about
+//
+// Long-term recall is for what outlives a session.  Most user turns are
+// requests about the task at hand ("look at src/memory/store.rs", "why does
+// `load_tree` return an empty tree?"); filing each of them in the MemTree
+// only churns it.  Instead the files and names a session mentions are kept
+// here, in process, and injected into every prompt of that session, and only
+// durable turns (see `MemoryClassifier::is_durable`) reach the long-term
+// store.  Working memory is gone when the process exits.
+
+use std::collections::HashMap;
+
+/// Files and names remembered per session; the least recent go first
+const MAX_ITEMS: usize = 200;
+
+/// Files and names injected into a prompt
+pub const MAX_INJECTED: usize = 12;
+
+/// Longest name kept
+const MAX_NAME_CHARS: usize = 60;
+
+/// Extensions that make a word a file name even without a directory
+const FILE_EXTENSIONS: &[&str] = &[
+    "rs", "toml", "md", "py", "ts", "tsx", "js", "json", "yaml", "yml", "sql", "go", "c", "h",
+    "cpp", "java", "kt", "swift", "sh", "lock", "txt", "html", "css",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    File,
+    Name,
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    kind: Kind,
+    mentions: u32,
+    /// Turn it was last mentioned in
+    last_seen: u64,
+}
+
+/// Files and names mentioned in one session
+#[derive(Debug, Default)]
+pub struct WorkingMemory {
+    items: HashMap<String, Item>,
+    turns: u64,
+}
+
+impl WorkingMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note the files and names mentioned in a turn
+    pub fn observe(&mut self, text: &str) {
+        self.turns += 1;
+        for (mention, kind) in mentions(text) {
+            let item = self.items.entry(mention).or_insert(Item {
+                kind,
+                mentions: 0,
+                last_seen: 0,
+            });
+            item.mentions += 1;
+            item.last_seen = self.turns;
+        }
+        while self.items.len() > MAX_ITEMS {
+            let Some(oldest) = self
+                .items
+                .iter()
+                .min_by_key(|(_, item)| (item.last_seen, item.mentions))
+                .map(|(mention, _)| mention.clone())
+            else {
+                break;
+            };
+            self.items.remove(&oldest);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The most recent `MAX_INJECTED` files and names as a block for the
+    /// start of the user's message; `None` before anything was mentioned
+    pub fn format_block(&self) -> Option<String> {
+        let mut items: Vec<(&String, &Item)> = self.items.iter().collect();
+        items.sort_by(|a, b| {
+            (b.1.last_seen, b.1.mentions)
+                .cmp(&(a.1.last_seen, a.1.mentions))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        items.truncate(MAX_INJECTED);
+        let list = |kind: Kind| -> Vec<&str> {
+            items
+                .iter()
+                .filter(|(_, item)| item.kind == kind)
+                .map(|(mention, _)| mention.as_str())
+                .collect()
+        };
+        let (files, names) = (list(Kind::File), list(Kind::Name));
+        let mut lines = Vec::new();
+        if !files.is_empty() {
+            lines.push(format!("Files: {}", files.join(", ")));
+        }
+        if !names.is_empty() {
+            lines.push(format!("Names: {}", names.join(", ")));
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "[Working memory — mentioned earlier in this session:\n{}]\n\n",
+            lines.join("\n")
+        ))
+    }
+}
+
+/// File paths, `code spans`, CamelCase names and `a::b` paths in `text`
+fn mentions(text: &str) -> Vec<(String, Kind)> {
+    let mut found = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        // Code blocks are too noisy to mine for names
+        if in_fence {
+            continue;
+        }
+        for (i, span) in line.split('`').enumerate() {
+            // Odd pieces were between backticks
+            if i % 2 == 1 {
+                if let Some(mention) = classify(span.trim(), true) {
+                    found.push(mention);
+                }
+                continue;
+            }
+            for word in span.split_whitespace() {
+                let word = word.trim_matches(|c: char| {
+                    !(c.is_alphanumeric() || matches!(c, '_' | '/' | '.' | '~' | ':'))
+                });
+                let word = word.trim_end_matches(['.', ':']);
+                if let Some(mention) = classify(word, false) {
+                    found.push(mention);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// What `word` is worth remembering as, if anything.  A `quoted` code span
+/// is a name whatever its shape.
+fn classify(word: &str, quoted: bool) -> Option<(String, Kind)> {
+    if word.is_empty()
+        || word.chars().count() > MAX_NAME_CHARS
+        || word.contains(char::is_whitespace)
+    {
+        return None;
+    }
+    if word.contains("://") {
+        return None;
+    }
+    if is_file(word) {
+        return Some((word.to_string(), Kind::File));
+    }
+    let identifier = |s: &str| {
+        s.chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    let camel_case = identifier(word)
+        && word.chars().next().is_some_and(char::is_uppercase)
+        && word.chars().skip(1).any(char::is_lowercase)
+        && word.chars().skip(1).any(char::is_uppercase);
+    let path = word.split("::").count() > 1 && word.split("::").all(identifier);
+    let code = word.trim_end_matches("()");
+    if camel_case || path || (quoted && (identifier(code) || code.contains("::"))) {
+        return Some((code.to_string(), Kind::Name));
+    }
+    None
+}
+
+/// A name with a known extension, a path from `/`, `./` or `~/`, or a
+/// relative one of three parts or more ("and/or" is not a file)
+fn is_file(word: &str) -> bool {
+    let has_extension = word
+        .rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && FILE_EXTENSIONS.contains(&ext));
+    let parts = word.split('/').filter(|part| !part.is_empty()).count();
+    let rooted = ["/", "./", "../", "~/"]
+        .iter()
+        .any(|root| word.starts_with(root));
+    let is_path = word.contains('/') && (parts >= 3 || (rooted && parts >= 1));
+    has_extension || (is_path && word.chars().any(char::is_alphabetic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remembers_files_and_names_most_recent_first() {
+        let mut working = WorkingMemory::new();
+        assert!(working.format_block().is_none());
+
+        working.observe(
+            "Why does `load_tree` return an empty MemTree? See src/memory/store.rs, \
+             line 300. It works on https://example.com/a/b. Ok.",
+        );
+        working.observe(
+            "MemorySystem::new calls LocalStore::load_tree:\n\
+             ```rust\nlet IgnoredName = 1;\n```\n\
+             Cargo.toml pins rusqlite.",
+        );
+
+        let block = working.format_block().unwrap();
+        assert!(block.contains(
+            "Files: Cargo.toml, src/memory/store.rs\n\
+             Names: LocalStore::load_tree, MemorySystem::new, MemTree, load_tree]"
+        ));
+        assert!(!block.contains("IgnoredName") && !block.contains("example.com"));
+    }
+}
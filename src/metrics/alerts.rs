@@ -0,0 +1,546 @@
+// Alerting rules over the metrics store
+//
+// Rules come from `[[alerts]]` in ~/.finch/config.toml:
+//
+//   [[alerts]]
+//   name = "daily-spend"
+//   rule = "spend > 5 over 24h"
+//   webhook = "https://hooks.example.com/finch"
+//
+// The engine re-evaluates every rule whenever it is checked and reports
+// transitions only: an alert fires once when its condition becomes true and
+// resolves once when it stops being true.  It keeps the metrics in the longest
+// window in memory, adding those the caller records, and re-reads the JSONL
+// store at most once a minute to pick up other processes' requests.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::logger::MetricsLogger;
+use super::types::RequestMetric;
+use crate::config::AlertConfig;
+
+/// Window used when a rule has no `over <window>` clause.
+const DEFAULT_WINDOW_SECS: i64 = 24 * 3600;
+
+/// Rate metrics need this many requests in the window before they can fire,
+/// so one failed call out of one doesn't page anyone.
+const MIN_RATE_SAMPLES: usize = 5;
+
+/// How often `check` re-reads the metrics store; in between it counts the
+/// metrics passed to `record`.
+const RELOAD_INTERVAL_SECS: i64 = 60;
+
+/// Quantity a rule measures over its window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// Estimated USD spent
+    Spend,
+    /// Fraction of requests that failed (0.0–1.0)
+    ErrorRate,
+    /// Mean response time in ms
+    LatencyAvg,
+    /// 95th-percentile response time in ms
+    LatencyP95,
+    /// Number of requests
+    Requests,
+}
+
+impl AlertMetric {
+    fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "spend" | "cost" => Self::Spend,
+            "error_rate" | "errors" => Self::ErrorRate,
+            "latency" | "latency_avg" => Self::LatencyAvg,
+            "latency_p95" | "p95_latency" => Self::LatencyP95,
+            "requests" => Self::Requests,
+            other => bail!(
+                "unknown alert metric '{}' (expected spend, error_rate, latency, latency_p95 or requests)",
+                other
+            ),
+        })
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            Self::Spend => format!("${:.2}", value),
+            Self::ErrorRate => format!("{:.0}%", value * 100.0),
+            Self::LatencyAvg | Self::LatencyP95 => format!("{:.0}ms", value),
+            Self::Requests => format!("{:.0}", value),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Spend => "spend",
+            Self::ErrorRate => "error rate",
+            Self::LatencyAvg => "avg latency",
+            Self::LatencyP95 => "p95 latency",
+            Self::Requests => "requests",
+        }
+    }
+}
+
+/// Comparison operator of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparison {
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Ge => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Le => value <= threshold,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+        }
+    }
+}
+
+/// A parsed rule expression: `[daily|hourly] <metric> <op> <threshold> [over <window>]`.
+///
+/// Thresholds accept a leading `$` (spend) or trailing `%` (error rate);
+/// windows are `<n>s`, `<n>m`, `<n>h` or `<n>d`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub window: Duration,
+}
+
+impl AlertRule {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let re = regex::Regex::new(
+            r"^(?:(daily|hourly)\s+)?([a-z_0-9]+)\s*(>=|<=|>|<)\s*\$?([0-9]*\.?[0-9]+)\s*(%)?(?:\s+over\s+([0-9]+)\s*([smhd]))?$",
+        )
+        .expect("static regex");
+        let expr = expr.trim().to_lowercase();
+        let caps = re.captures(&expr).with_context(|| {
+            format!(
+                "invalid alert rule '{}' (expected e.g. \"spend > 5 over 24h\")",
+                expr
+            )
+        })?;
+
+        let metric = AlertMetric::parse(&caps[2])?;
+        let comparison = match &caps[3] {
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            "<" => Comparison::Lt,
+            _ => Comparison::Le,
+        };
+        let mut threshold: f64 = caps[4].parse()?;
+        if caps.get(5).is_some() {
+            threshold /= 100.0;
+        }
+
+        let window = match (caps.get(1).map(|m| m.as_str()), caps.get(6), caps.get(7)) {
+            (_, Some(n), Some(unit)) => {
+                let n: i64 = n.as_str().parse()?;
+                match unit.as_str() {
+                    "s" => Duration::seconds(n),
+                    "m" => Duration::minutes(n),
+                    "h" => Duration::hours(n),
+                    _ => Duration::days(n),
+                }
+            }
+            (Some("hourly"), _, _) => Duration::hours(1),
+            _ => Duration::seconds(DEFAULT_WINDOW_SECS),
+        };
+        if window <= Duration::zero() {
+            bail!("alert window must be positive in '{}'", expr);
+        }
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+            window,
+        })
+    }
+
+    /// Current value of the metric over the window ending at `now`, or `None`
+    /// when there is not enough data to judge.
+    pub fn evaluate(&self, metrics: &[RequestMetric], now: DateTime<Utc>) -> Option<f64> {
+        let since = now - self.window;
+        let in_window: Vec<&RequestMetric> =
+            metrics.iter().filter(|m| m.timestamp >= since).collect();
+
+        match self.metric {
+            AlertMetric::Spend => Some(in_window.iter().filter_map(|m| m.cost_usd).sum()),
+            AlertMetric::Requests => Some(in_window.len() as f64),
+            AlertMetric::ErrorRate => {
+                if in_window.len() < MIN_RATE_SAMPLES {
+                    return None;
+                }
+                let errors = in_window.iter().filter(|m| m.error.is_some()).count();
+                Some(errors as f64 / in_window.len() as f64)
+            }
+            AlertMetric::LatencyAvg | AlertMetric::LatencyP95 => {
+                let mut times: Vec<u64> = in_window
+                    .iter()
+                    .filter(|m| m.error.is_none())
+                    .map(|m| m.response_time_ms)
+                    .collect();
+                if times.is_empty() {
+                    return None;
+                }
+                if self.metric == AlertMetric::LatencyAvg {
+                    return Some(times.iter().sum::<u64>() as f64 / times.len() as f64);
+                }
+                times.sort_unstable();
+                let idx = ((times.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+                Some(times[idx.min(times.len() - 1)] as f64)
+            }
+        }
+    }
+
+    /// Human-readable description of a firing value, e.g. "spend $5.12 > $5.00".
+    pub fn describe(&self, value: f64) -> String {
+        format!(
+            "{} {} {} {}",
+            self.metric.label(),
+            self.metric.format(value),
+            self.comparison.as_str(),
+            self.metric.format(self.threshold)
+        )
+    }
+}
+
+/// A state change reported by `AlertEngine::check`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    Fired { name: String, message: String },
+    Resolved { name: String },
+}
+
+struct ActiveRule {
+    config: AlertConfig,
+    rule: AlertRule,
+}
+
+/// Evaluates configured rules and dispatches notifications.
+pub struct AlertEngine {
+    rules: Vec<ActiveRule>,
+    /// Currently firing alerts: name → message
+    firing: BTreeMap<String, String>,
+    logger: Option<MetricsLogger>,
+    /// Metrics in the longest window: the store as last read, plus those
+    /// recorded since
+    recent: Vec<RequestMetric>,
+    loaded_at: Option<DateTime<Utc>>,
+    client: reqwest::Client,
+}
+
+impl AlertEngine {
+    /// Build from config.  Rules that fail to parse are logged and skipped so
+    /// one typo doesn't disable every alert.
+    pub fn new(configs: &[AlertConfig], metrics_dir: Option<PathBuf>) -> Self {
+        let rules = configs
+            .iter()
+            .filter_map(|config| match AlertRule::parse(&config.rule) {
+                Ok(rule) => Some(ActiveRule {
+                    config: config.clone(),
+                    rule,
+                }),
+                Err(e) => {
+                    tracing::warn!("Ignoring alert '{}': {}", config.name, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            rules,
+            firing: BTreeMap::new(),
+            logger: metrics_dir.and_then(|dir| MetricsLogger::new(dir).ok()),
+            recent: Vec::new(),
+            loaded_at: None,
+            client: crate::http::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Count a metric the caller has just logged to the store.
+    pub fn record(&mut self, metric: &RequestMetric) {
+        // Before the first read the store has it already
+        if self.loaded_at.is_some() {
+            self.recent.push(metric.clone());
+        }
+    }
+
+    /// Evaluate every rule, re-reading the metrics store when the last read
+    /// is more than `RELOAD_INTERVAL_SECS` old.
+    pub fn check(&mut self) -> Result<Vec<AlertEvent>> {
+        let Some(logger) = &self.logger else {
+            return Ok(Vec::new());
+        };
+        let Some(longest) = self.rules.iter().map(|r| r.rule.window).max() else {
+            return Ok(Vec::new());
+        };
+        let now = Utc::now();
+        let since = now - longest;
+        let stale = self
+            .loaded_at
+            .is_none_or(|at| now - at >= Duration::seconds(RELOAD_INTERVAL_SECS));
+        if stale {
+            self.recent = logger.read_since(since)?;
+            self.loaded_at = Some(now);
+        } else {
+            self.recent.retain(|m| m.timestamp >= since);
+        }
+
+        let metrics = std::mem::take(&mut self.recent);
+        let events = self.check_metrics(&metrics, now);
+        self.recent = metrics;
+        Ok(events)
+    }
+
+    /// Evaluate every rule against `metrics` and return state transitions.
+    pub fn check_metrics(
+        &mut self,
+        metrics: &[RequestMetric],
+        now: DateTime<Utc>,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for active in &self.rules {
+            let name = &active.config.name;
+            let value = active.rule.evaluate(metrics, now);
+            match value.filter(|v| active.rule.comparison.holds(*v, active.rule.threshold)) {
+                Some(v) => {
+                    let message = active.rule.describe(v);
+                    let newly_fired = !self.firing.contains_key(name);
+                    self.firing.insert(name.clone(), message.clone());
+                    if newly_fired {
+                        events.push(AlertEvent::Fired {
+                            name: name.clone(),
+                            message,
+                        });
+                    }
+                }
+                None => {
+                    if self.firing.remove(name).is_some() {
+                        events.push(AlertEvent::Resolved { name: name.clone() });
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    /// Status-bar banner for the currently firing alerts, if any.
+    pub fn banner(&self) -> Option<String> {
+        if self.firing.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "⚠ {}",
+            self.firing
+                .iter()
+                .map(|(name, message)| format!("{}: {}", name, message))
+                .collect::<Vec<_>>()
+                .join("  ·  ")
+        ))
+    }
+
+    /// Send the webhook / desktop notification for a newly fired alert.
+    /// Delivery is best-effort and never blocks the caller.
+    pub fn notify(&self, event: &AlertEvent) {
+        let AlertEvent::Fired { name, message } = event else {
+            return;
+        };
+        let Some(active) = self.rules.iter().find(|r| &r.config.name == name) else {
+            return;
+        };
+
+        if let Some(url) = active.config.webhook.clone() {
+            let payload = serde_json::json!({
+                "alert": name,
+                "rule": active.config.rule,
+                "message": message,
+                "fired_at": Utc::now().to_rfc3339(),
+            });
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.post(&url).json(&payload).send().await {
+                    tracing::warn!("Alert webhook to {} failed: {}", url, e);
+                }
+            });
+        }
+
+        if active.config.desktop {
+            desktop_notification(&format!("finch alert: {}", name), message);
+        }
+    }
+}
+
+/// Fire-and-forget desktop notification via the platform's CLI notifier.
+fn desktop_notification(title: &str, body: &str) {
+    #[cfg(target_os = "macos")]
+    let result = {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification \"{}\" with title \"{}\"",
+                escape(body),
+                escape(title)
+            ))
+            .spawn()
+    };
+    #[cfg(not(target_os = "macos"))]
+    let result = std::process::Command::new("notify-send")
+        .arg(title)
+        .arg(body)
+        .spawn();
+
+    if let Err(e) = result {
+        tracing::debug!("Desktop notification unavailable: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, rule: &str) -> AlertConfig {
+        AlertConfig {
+            name: name.to_string(),
+            rule: rule.to_string(),
+            webhook: None,
+            desktop: false,
+        }
+    }
+
+    fn call(cost: f64, ms: u64, age_mins: i64, now: DateTime<Utc>) -> RequestMetric {
+        let mut m = RequestMetric::llm_call("claude-sonnet-4-6", None, None, ms);
+        m.cost_usd = Some(cost);
+        m.timestamp = now - Duration::minutes(age_mins);
+        m
+    }
+
+    fn failure(age_mins: i64, now: DateTime<Utc>) -> RequestMetric {
+        let mut m = RequestMetric::failure("HTTP 529", 0);
+        m.timestamp = now - Duration::minutes(age_mins);
+        m
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let r = AlertRule::parse("daily spend > $5").unwrap();
+        assert_eq!(r.metric, AlertMetric::Spend);
+        assert_eq!(r.threshold, 5.0);
+        assert_eq!(r.window, Duration::hours(24));
+
+        let r = AlertRule::parse("error_rate > 20% over 10m").unwrap();
+        assert_eq!(r.metric, AlertMetric::ErrorRate);
+        assert!((r.threshold - 0.2).abs() < 1e-9);
+        assert_eq!(r.window, Duration::minutes(10));
+
+        let r = AlertRule::parse("latency_p95 >= 3000 over 1h").unwrap();
+        assert_eq!(r.comparison, Comparison::Ge);
+
+        assert!(AlertRule::parse("vibes > 3").is_err());
+        assert!(AlertRule::parse("spend is high").is_err());
+    }
+
+    #[test]
+    fn test_spend_respects_window() {
+        let now = Utc::now();
+        let rule = AlertRule::parse("spend > 1 over 1h").unwrap();
+        let metrics = vec![call(0.8, 100, 10, now), call(5.0, 100, 120, now)];
+        assert_eq!(rule.evaluate(&metrics, now), Some(0.8));
+    }
+
+    #[test]
+    fn test_error_rate_needs_min_samples() {
+        let now = Utc::now();
+        let rule = AlertRule::parse("error_rate > 20% over 10m").unwrap();
+        assert_eq!(rule.evaluate(&[failure(1, now)], now), None);
+
+        let mut metrics: Vec<_> = (0..4).map(|i| call(0.0, 100, i, now)).collect();
+        metrics.push(failure(1, now));
+        metrics.push(failure(2, now));
+        let rate = rule.evaluate(&metrics, now).unwrap();
+        assert!((rate - 2.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_engine_reports_transitions_once() {
+        let now = Utc::now();
+        let mut engine = AlertEngine::new(&[config("budget", "spend > 1 over 1h")], None);
+
+        let over = vec![call(0.7, 100, 5, now), call(0.7, 100, 6, now)];
+        let events = engine.check_metrics(&over, now);
+        assert!(matches!(&events[..], [AlertEvent::Fired { name, .. }] if name == "budget"));
+        assert!(engine
+            .banner()
+            .unwrap()
+            .contains("budget: spend $1.40 > $1.00"));
+
+        // Still firing: no duplicate event
+        assert!(engine.check_metrics(&over, now).is_empty());
+
+        // Calls age out of the window → resolved
+        let later = now + Duration::hours(2);
+        assert_eq!(
+            engine.check_metrics(&over, later),
+            vec![AlertEvent::Resolved {
+                name: "budget".to_string()
+            }]
+        );
+        assert!(engine.banner().is_none());
+    }
+
+    #[test]
+    fn test_check_counts_recorded_metrics_between_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let logger = MetricsLogger::new(dir.path().to_path_buf()).unwrap();
+        let mut engine = AlertEngine::new(
+            &[config("budget", "spend > 1 over 1h")],
+            Some(dir.path().to_path_buf()),
+        );
+        let now = Utc::now();
+
+        logger.log(&call(0.7, 100, 0, now)).unwrap();
+        assert!(engine.check().unwrap().is_empty());
+
+        // Logged by someone else: not seen until the next read
+        logger.log(&call(0.7, 100, 0, now)).unwrap();
+        assert!(engine.check().unwrap().is_empty());
+
+        let mine = call(0.7, 100, 0, now);
+        logger.log(&mine).unwrap();
+        engine.record(&mine);
+        let events = engine.check().unwrap();
+        assert!(matches!(&events[..], [AlertEvent::Fired { name, .. }] if name == "budget"));
+    }
+
+    #[test]
+    fn test_invalid_rule_is_skipped() {
+        let engine = AlertEngine::new(&[config("typo", "spend >> 5")], None);
+        assert!(engine.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+// Provider benchmark — `finch bench providers`
+//
+// Runs a fixed prompt suite against each target (cloud providers and the
+// daemon's local model), timing every answer and estimating its cost, then asks
+// a judge provider to score each answer against the prompt's rubric.  The
+// suite is compiled in so that results from different days and machines are
+// comparable.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::time::Instant;
+
+use super::leaderboard::LeaderboardEntry;
+use super::pricing::estimate_cost_usd;
+use crate::claude::Message;
+use crate::client::DaemonClient;
+use crate::providers::{LlmProvider, ProviderRequest};
+
+/// One prompt of the benchmark suite
+#[derive(Debug, Clone, Copy)]
+pub struct BenchPrompt {
+    pub id: &'static str,
+    pub prompt: &'static str,
+    /// What the judge should look for
+    pub rubric: &'static str,
+}
+
+/// The fixed suite: short coding-assistant tasks of varied kinds
+pub const BENCH_SUITE: &[BenchPrompt] = &[
+    BenchPrompt {
+        id: "rust-fn",
+        prompt: "Write a Rust function `fn is_palindrome(s: &str) -> bool` that ignores case \
+                 and non-alphanumeric characters. Reply with only the code.",
+        rubric: "Compiles, handles Unicode-safe iteration, ignores case and punctuation, \
+                 no unnecessary allocation beyond what is needed.",
+    },
+    BenchPrompt {
+        id: "bug-spot",
+        prompt: "What is wrong with this Python?\n\ndef add_item(item, items=[]):\n    \
+                 items.append(item)\n    return items",
+        rubric: "Identifies the shared mutable default argument and shows the `None` default fix.",
+    },
+    BenchPrompt {
+        id: "reasoning",
+        prompt: "A job runs every 90 minutes starting at 00:00. How many times does it run \
+                 between 00:00 and 23:59 inclusive? Explain briefly.",
+        rubric: "Answers 16 with correct reasoning (0:00, 1:30, ... 22:30).",
+    },
+    BenchPrompt {
+        id: "shell",
+        prompt: "Give a one-line shell command that prints the 5 largest files under the \
+                 current directory, recursively, with human-readable sizes.",
+        rubric: "Command works as written on Linux (e.g. find/du + sort -h + head), \
+                 recursive, shows sizes, limits to 5.",
+    },
+    BenchPrompt {
+        id: "explain",
+        prompt: "In two or three sentences, explain the difference between a mutex and a \
+                 read-write lock and when to prefer each.",
+        rubric: "Accurate distinction (exclusive vs shared readers), sensible guidance, \
+                 respects the length limit.",
+    },
+    BenchPrompt {
+        id: "sql",
+        prompt: "Write a SQL query returning each customer's id and their most recent order \
+                 date from a table orders(customer_id, ordered_at).",
+        rubric: "Correct GROUP BY customer_id with MAX(ordered_at) or an equivalent window query.",
+    },
+];
+
+/// Something that can answer benchmark prompts
+#[async_trait]
+pub trait BenchTarget: Send + Sync {
+    /// Provider name for the leaderboard ("local" for the local model)
+    fn provider(&self) -> &str;
+    /// Model name, also used for pricing
+    fn model(&self) -> &str;
+    async fn answer(&self, prompt: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl BenchTarget for Box<dyn LlmProvider> {
+    fn provider(&self) -> &str {
+        self.name()
+    }
+
+    fn model(&self) -> &str {
+        self.default_model()
+    }
+
+    async fn answer(&self, prompt: &str) -> Result<String> {
+        let request = ProviderRequest::new(vec![Message::user(prompt)]);
+        Ok(self.send_message(&request).await?.text())
+    }
+}
+
+/// The daemon's local model, bypassing routing
+pub struct LocalBenchTarget {
+    client: DaemonClient,
+}
+
+impl LocalBenchTarget {
+    pub fn new(client: DaemonClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl BenchTarget for LocalBenchTarget {
+    fn provider(&self) -> &str {
+        "local"
+    }
+
+    fn model(&self) -> &str {
+        "local"
+    }
+
+    async fn answer(&self, prompt: &str) -> Result<String> {
+        self.client.query_local_only(prompt).await
+    }
+}
+
+/// Run `suite` against `target`, scoring answers with `judge`.
+///
+/// Failed prompts are counted, not fatal; an answer the judge can't score
+/// still counts toward latency and cost.
+pub async fn run_bench(
+    target: &dyn BenchTarget,
+    judge: &dyn LlmProvider,
+    suite: &[BenchPrompt],
+) -> LeaderboardEntry {
+    let mut answered = 0;
+    let mut failed = 0;
+    let mut latency_ms = 0u64;
+    let mut cost_usd = Some(0.0);
+    let mut scores = Vec::new();
+
+    for item in suite {
+        let start = Instant::now();
+        let answer = match target.answer(item.prompt).await {
+            Ok(answer) if !answer.trim().is_empty() => answer,
+            Ok(_) => {
+                tracing::warn!(prompt = item.id, target = target.provider(), "Empty answer");
+                failed += 1;
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    prompt = item.id,
+                    target = target.provider(),
+                    "Bench prompt failed: {}",
+                    e
+                );
+                failed += 1;
+                continue;
+            }
+        };
+        latency_ms += start.elapsed().as_millis() as u64;
+        answered += 1;
+
+        // Providers don't report usage here; ~4 chars per token like elsewhere
+        let cost = estimate_cost_usd(
+            target.model(),
+            (item.prompt.len() / 4) as u32,
+            (answer.len() / 4) as u32,
+        );
+        cost_usd = cost_usd.zip(cost).map(|(total, c)| total + c);
+
+        match judge_answer(judge, item.prompt, item.rubric, &answer).await {
+            Ok(score) => scores.push(score),
+            Err(e) => tracing::warn!(prompt = item.id, "Judge could not score answer: {}", e),
+        }
+    }
+
+    let per_answer = answered.max(1) as f64;
+    LeaderboardEntry {
+        provider: target.provider().to_string(),
+        model: target.model().to_string(),
+        answered,
+        failed,
+        avg_latency_ms: latency_ms / answered.max(1) as u64,
+        avg_cost_usd: cost_usd.filter(|_| answered > 0).map(|c| c / per_answer),
+        quality: (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+        judge: format!("{}/{}", judge.name(), judge.default_model()),
+        run_at: Utc::now(),
+    }
+}
+
+/// Ask `judge` for a 0–10 score of `answer` to `task`, graded by `rubric`
+pub async fn judge_answer(
+    judge: &dyn LlmProvider,
+    task: &str,
+    rubric: &str,
+    answer: &str,
+) -> Result<f64> {
+    let prompt = format!(
+        "You are grading an AI assistant's answer.\n\n\
+         Task:\n{}\n\nGrading rubric:\n{}\n\nAnswer:\n{}\n\n\
+         Score the answer from 0 (useless or wrong) to 10 (fully correct and well put). \
+         Reply with only the number.",
+        task, rubric, answer
+    );
+    let request = ProviderRequest::new(vec![Message::user(prompt)])
+        .with_max_tokens(16)
+        .with_temperature(0.0);
+    let reply = judge.send_message(&request).await?.text();
+    parse_score(&reply).ok_or_else(|| anyhow::anyhow!("Unparseable judge reply: {:?}", reply))
+}
+
+/// First number in the judge's reply, clamped to 0–10
+fn parse_score(reply: &str) -> Option<f64> {
+    let start = reply.find(|c: char| c.is_ascii_digit())?;
+    let number: String = reply[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number
+        .trim_end_matches('.')
+        .parse::<f64>()
+        .ok()
+        .map(|s| s.clamp(0.0, 10.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score("8"), Some(8.0));
+        assert_eq!(parse_score("Score: 7.5/10"), Some(7.5));
+        assert_eq!(parse_score("9."), Some(9.0));
+        assert_eq!(parse_score("42"), Some(10.0));
+        assert_eq!(parse_score("no idea"), None);
+    }
+
+    #[test]
+    fn test_suite_ids_are_unique() {
+        let mut ids: Vec<_> = BENCH_SUITE.iter().map(|p| p.id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), BENCH_SUITE.len());
+    }
+}
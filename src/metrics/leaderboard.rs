@@ -0,0 +1,179 @@
+// Provider/model leaderboard built by `finch bench providers`
+//
+// One row per (provider, model) with the latest benchmark run's latency,
+// estimated cost and judge-scored quality.  Stored in
+// `~/.finch/leaderboard.json`; re-running the benchmark replaces a row rather
+// than averaging it with stale results from an older model version.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Benchmark result for one provider/model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub provider: String,
+    pub model: String,
+    /// Prompts answered successfully
+    pub answered: usize,
+    /// Prompts that errored or timed out
+    pub failed: usize,
+    /// Mean wall-clock time per answered prompt
+    pub avg_latency_ms: u64,
+    /// Mean estimated cost per answered prompt (`None` = model not priced)
+    pub avg_cost_usd: Option<f64>,
+    /// Mean judge score, 0–10 (`None` = no answer could be scored)
+    pub quality: Option<f64>,
+    /// Provider/model that scored the answers
+    pub judge: String,
+    pub run_at: DateTime<Utc>,
+}
+
+impl LeaderboardEntry {
+    fn is_same_target(&self, other: &LeaderboardEntry) -> bool {
+        self.provider == other.provider && self.model == other.model
+    }
+}
+
+/// All benchmark results on this machine
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    /// Default location: `~/.finch/leaderboard.json`
+    pub fn default_path() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        Ok(home.join(".finch").join("leaderboard.json"))
+    }
+
+    /// Load the leaderboard from the default location (empty if never run)
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Save to the default location
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize leaderboard")?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add a run, replacing any earlier result for the same provider/model
+    pub fn record(&mut self, entry: LeaderboardEntry) {
+        self.entries.retain(|e| !e.is_same_target(&entry));
+        self.entries.push(entry);
+    }
+
+    /// Entries best-first: highest quality, then cheapest, then fastest
+    pub fn ranked(&self) -> Vec<&LeaderboardEntry> {
+        let mut ranked: Vec<&LeaderboardEntry> = self.entries.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.quality
+                .unwrap_or(-1.0)
+                .total_cmp(&a.quality.unwrap_or(-1.0))
+                .then(cost_key(a).total_cmp(&cost_key(b)))
+                .then(a.avg_latency_ms.cmp(&b.avg_latency_ms))
+        });
+        ranked
+    }
+
+    /// Fixed-width table for the terminal
+    pub fn render_table(&self) -> String {
+        let mut out = format!(
+            "{:<4} {:<12} {:<28} {:>7} {:>10} {:>12} {:>6}\n",
+            "#", "PROVIDER", "MODEL", "QUALITY", "LATENCY", "COST/PROMPT", "FAILS"
+        );
+        for (rank, e) in self.ranked().iter().enumerate() {
+            let quality = e
+                .quality
+                .map(|q| format!("{:.1}", q))
+                .unwrap_or_else(|| "-".to_string());
+            let cost = e
+                .avg_cost_usd
+                .map(|c| format!("${:.5}", c))
+                .unwrap_or_else(|| "?".to_string());
+            out.push_str(&format!(
+                "{:<4} {:<12} {:<28} {:>7} {:>8}ms {:>12} {:>6}\n",
+                rank + 1,
+                e.provider,
+                e.model,
+                quality,
+                e.avg_latency_ms,
+                cost,
+                e.failed
+            ));
+        }
+        out
+    }
+}
+
+/// Sort key for cost; unpriced models sort last
+fn cost_key(entry: &LeaderboardEntry) -> f64 {
+    entry.avg_cost_usd.unwrap_or(f64::INFINITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(provider: &str, quality: f64, cost: Option<f64>) -> LeaderboardEntry {
+        LeaderboardEntry {
+            provider: provider.to_string(),
+            model: format!("{}-model", provider),
+            answered: 6,
+            failed: 0,
+            avg_latency_ms: 1000,
+            avg_cost_usd: cost,
+            quality: Some(quality),
+            judge: "claude/claude-sonnet-4-6".to_string(),
+            run_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_record_replaces_same_model_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboard.json");
+
+        let mut board = Leaderboard::load_from(&path).unwrap();
+        board.record(entry("claude", 7.0, Some(0.01)));
+        board.record(entry("claude", 8.5, Some(0.01)));
+        board.record(entry("local", 5.0, Some(0.0)));
+        board.save_to(&path).unwrap();
+
+        let board = Leaderboard::load_from(&path).unwrap();
+        assert_eq!(board.entries.len(), 2);
+        assert_eq!(board.ranked()[0].quality, Some(8.5));
+    }
+
+    #[test]
+    fn test_ranked_breaks_quality_ties_on_cost() {
+        let mut board = Leaderboard::default();
+        board.record(entry("claude", 8.0, Some(0.02)));
+        board.record(entry("openai", 8.0, Some(0.001)));
+        board.record(entry("mystery", 8.0, None));
+        board.record(entry("local", 4.0, Some(0.0)));
+
+        let order: Vec<&str> = board.ranked().iter().map(|e| e.provider.as_str()).collect();
+        // Unpriced models sort after priced ones of equal quality
+        assert_eq!(order, ["openai", "claude", "mystery", "local"]);
+    }
+}
@@ -1,16 +1,27 @@
 // Metrics logger
+//
+// Metrics are appended to local `<date>.jsonl` day files.  With remote
+// storage configured, the daemon also copies the day files that changed to
+// the store now and then, so a fleet's usage data ends up in one place.
 
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use super::types::RequestMetric;
+use crate::storage::StorageBackend;
 
 pub struct MetricsLogger {
     metrics_dir: PathBuf,
+    /// Store day files are uploaded to, and this node's folder in it
+    remote: Option<(Arc<dyn StorageBackend>, String)>,
+    /// Size of each day file when it was last uploaded
+    uploaded: Mutex<HashMap<String, u64>>,
 }
 
 impl MetricsLogger {
@@ -23,7 +34,51 @@ impl MetricsLogger {
             )
         })?;
 
-        Ok(Self { metrics_dir })
+        Ok(Self {
+            metrics_dir,
+            remote: None,
+            uploaded: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Upload day files to `storage` under `<node>/` (see `upload`)
+    pub fn with_remote(mut self, storage: Arc<dyn StorageBackend>, node: &str) -> Self {
+        self.remote = Some((storage, node.to_string()));
+        self
+    }
+
+    /// Copy day files that grew since their last upload to the remote store
+    /// as `<node>/<date>.jsonl`, returning how many were copied.
+    ///
+    /// Files are append-only, so the size tells whether one changed.  After a
+    /// restart every day file is copied once more.  A no-op without a store.
+    pub async fn upload(&self) -> Result<usize> {
+        let Some((storage, node)) = &self.remote else {
+            return Ok(0);
+        };
+        let mut files: Vec<_> = fs::read_dir(&self.metrics_dir)
+            .with_context(|| format!("Failed to read {}", self.metrics_dir.display()))?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let size = entry.metadata().ok()?.len();
+                name.ends_with(".jsonl").then_some((name, size))
+            })
+            .collect();
+        files.sort();
+
+        let mut count = 0;
+        for (name, size) in files {
+            if self.uploaded.lock().unwrap().get(&name) == Some(&size) {
+                continue;
+            }
+            let bytes = tokio::fs::read(self.metrics_dir.join(&name)).await?;
+            let size = bytes.len() as u64;
+            storage.put(&format!("{}/{}", node, name), bytes).await?;
+            self.uploaded.lock().unwrap().insert(name, size);
+            count += 1;
+        }
+        Ok(count)
     }
 
     /// Log a request metric to today's JSONL file
@@ -72,10 +127,32 @@ impl MetricsLogger {
         Ok(metrics)
     }
 
+    /// Read all metrics recorded at or after `since` (spans day files).
+    pub fn read_since(&self, since: DateTime<Utc>) -> Result<Vec<RequestMetric>> {
+        let mut metrics = Vec::new();
+        let mut day = since.date_naive();
+        let today = Utc::now().date_naive();
+        while day <= today {
+            metrics.extend(
+                self.read_metrics(&day.format("%Y-%m-%d").to_string())?
+                    .into_iter()
+                    .filter(|m| m.timestamp >= since),
+            );
+            day = day.succ_opt().unwrap_or(today + chrono::Days::new(1));
+        }
+        Ok(metrics)
+    }
+
     /// Get summary statistics for today
     pub fn get_today_summary(&self) -> Result<MetricsSummary> {
         let today = Utc::now().format("%Y-%m-%d").to_string();
-        let metrics = self.read_metrics(&today)?;
+        // Per-call usage/error records feed alerting; the summary covers
+        // routing decisions only.
+        let metrics: Vec<RequestMetric> = self
+            .read_metrics(&today)?
+            .into_iter()
+            .filter(|m| m.routing_decision != "llm_call" && m.routing_decision != "error")
+            .collect();
 
         let total = metrics.len();
         let local_count = metrics
@@ -153,4 +230,26 @@ mod tests {
         assert_ne!(hash1, hash3);
         assert_eq!(hash1.len(), 64); // SHA256 produces 64 hex chars
     }
+
+    #[tokio::test]
+    async fn test_upload_copies_changed_day_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(crate::storage::LocalStorage::new(dir.path().join("remote")).unwrap());
+        let logger = MetricsLogger::new(dir.path().join("metrics"))
+            .unwrap()
+            .with_remote(store.clone(), "worker-1");
+        fs::write(dir.path().join("metrics/2026-10-17.jsonl"), "{}\n").unwrap();
+        fs::write(dir.path().join("metrics/2026-10-18.jsonl"), "{}\n").unwrap();
+
+        assert_eq!(logger.upload().await.unwrap(), 2);
+        // Nothing changed, nothing to send
+        assert_eq!(logger.upload().await.unwrap(), 0);
+
+        fs::write(dir.path().join("metrics/2026-10-18.jsonl"), "{}\n{}\n").unwrap();
+        assert_eq!(logger.upload().await.unwrap(), 1);
+        assert_eq!(
+            store.get("worker-1/2026-10-18.jsonl").await.unwrap(),
+            Some(b"{}\n{}\n".to_vec())
+        );
+    }
 }
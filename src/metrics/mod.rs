@@ -1,11 +1,18 @@
 // Metrics module
 // Public interface for logging and tracking metrics
 
+mod alerts;
+mod bench;
+mod leaderboard;
 mod logger;
+pub mod pricing;
 mod similarity;
 mod trends;
 mod types;
 
+pub use alerts::{AlertEngine, AlertEvent, AlertMetric, AlertRule, Comparison};
+pub use bench::{judge_answer, run_bench, BenchPrompt, BenchTarget, LocalBenchTarget, BENCH_SUITE};
+pub use leaderboard::{Leaderboard, LeaderboardEntry};
 pub use logger::MetricsLogger;
 pub use similarity::semantic_similarity;
 pub use trends::{TrainingTrends, Trend};
@@ -0,0 +1,67 @@
+// Approximate per-token pricing for spend tracking
+//
+// Prices are list prices in USD per million tokens, matched by model-name
+// prefix (most specific first).  They are estimates for budgeting alerts, not
+// billing — unknown models return `None` rather than a guess.
+
+/// (model prefix, input $/Mtok, output $/Mtok)
+const PRICES: &[(&str, f64, f64)] = &[
+    // Anthropic
+    ("claude-opus", 15.0, 75.0),
+    ("claude-sonnet", 3.0, 15.0),
+    ("claude-3-5-sonnet", 3.0, 15.0),
+    ("claude-3-7-sonnet", 3.0, 15.0),
+    ("claude-haiku", 0.80, 4.0),
+    ("claude-3-5-haiku", 0.80, 4.0),
+    // OpenAI
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.0),
+    ("gpt-4", 30.0, 60.0),
+    // Google
+    ("gemini-2.0-flash", 0.10, 0.40),
+    ("gemini-1.5-flash", 0.075, 0.30),
+    ("gemini-1.5-pro", 1.25, 5.0),
+    ("gemini-pro", 0.50, 1.50),
+    // xAI
+    ("grok-code-fast", 0.20, 1.50),
+    ("grok-2", 2.0, 10.0),
+    // Mistral / Groq
+    ("mistral-large", 2.0, 6.0),
+    ("llama-3.1-70b", 0.59, 0.79),
+];
+
+/// Model names served on-device; always free.
+const LOCAL_MARKERS: &[&str] = &["local", "qwen", "onnx"];
+
+/// Estimated cost of one call, or `None` if the model is not in the table.
+pub fn estimate_cost_usd(model: &str, input_tokens: u32, output_tokens: u32) -> Option<f64> {
+    let model = model.to_lowercase();
+    if LOCAL_MARKERS.iter().any(|m| model.contains(m)) {
+        return Some(0.0);
+    }
+    PRICES
+        .iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|(_, input, output)| {
+            (input_tokens as f64 * input + output_tokens as f64 * output) / 1_000_000.0
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        // gpt-4o-mini must not be priced as gpt-4o or gpt-4
+        let cost = estimate_cost_usd("gpt-4o-mini", 1_000_000, 0).unwrap();
+        assert!((cost - 0.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_local_is_free_and_unknown_is_none() {
+        assert_eq!(estimate_cost_usd("qwen-local", 5000, 5000), Some(0.0));
+        assert_eq!(estimate_cost_usd("some-new-model", 5000, 5000), None);
+    }
+}
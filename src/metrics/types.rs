@@ -33,6 +33,22 @@ pub struct RequestMetric {
     /// Router confidence scores
     pub router_confidence: Option<f64>,
     pub validator_confidence: Option<f64>,
+    /// Model that served the call (LLM-call and error metrics)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u32>,
+    /// Hidden reasoning tokens, not part of `output_tokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<u32>,
+    /// Estimated cost in USD (see `metrics::pricing`); `None` if unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Error message if the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 impl RequestMetric {
@@ -59,8 +75,71 @@ impl RequestMetric {
             comparison,
             router_confidence,
             validator_confidence,
+            model: None,
+            input_tokens: None,
+            output_tokens: None,
+            reasoning_tokens: None,
+            cost_usd: None,
+            error: None,
         }
     }
+
+    /// One completed LLM call, with token usage and estimated cost.
+    pub fn llm_call(
+        model: &str,
+        input_tokens: Option<u32>,
+        output_tokens: Option<u32>,
+        response_time_ms: u64,
+    ) -> Self {
+        let mut metric = Self::new(
+            String::new(),
+            "llm_call".to_string(),
+            None,
+            None,
+            None,
+            response_time_ms,
+            ResponseComparison::default(),
+            None,
+            None,
+        );
+        metric.cost_usd = super::pricing::estimate_cost_usd(
+            model,
+            input_tokens.unwrap_or(0),
+            output_tokens.unwrap_or(0),
+        );
+        metric.model = Some(model.to_string());
+        metric.input_tokens = input_tokens;
+        metric.output_tokens = output_tokens;
+        metric
+    }
+
+    /// Add the reasoning tokens the call spent, billed at the output price.
+    pub fn with_reasoning_tokens(mut self, reasoning_tokens: Option<u32>) -> Self {
+        let (Some(tokens), Some(model)) = (reasoning_tokens, self.model.as_deref()) else {
+            return self;
+        };
+        let cost = super::pricing::estimate_cost_usd(model, 0, tokens);
+        self.cost_usd = self.cost_usd.zip(cost).map(|(total, cost)| total + cost);
+        self.reasoning_tokens = Some(tokens);
+        self
+    }
+
+    /// A failed request.
+    pub fn failure(error: impl Into<String>, response_time_ms: u64) -> Self {
+        let mut metric = Self::new(
+            String::new(),
+            "error".to_string(),
+            None,
+            None,
+            None,
+            response_time_ms,
+            ResponseComparison::default(),
+            None,
+            None,
+        );
+        metric.error = Some(error.into());
+        metric
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +242,35 @@ mod tests {
         assert_eq!(decoded.response_time_ms, metric.response_time_ms);
     }
 
+    #[test]
+    fn test_llm_call_records_usage_and_cost() {
+        let metric = RequestMetric::llm_call("claude-sonnet-4-6", Some(1_000_000), Some(0), 900);
+        assert_eq!(metric.routing_decision, "llm_call");
+        assert_eq!(metric.model.as_deref(), Some("claude-sonnet-4-6"));
+        assert!((metric.cost_usd.unwrap() - 3.0).abs() < 1e-9);
+        assert!(metric.error.is_none());
+    }
+
+    #[test]
+    fn test_reasoning_tokens_add_to_cost() {
+        let metric = RequestMetric::llm_call("claude-sonnet-4-6", Some(1_000_000), Some(0), 900)
+            .with_reasoning_tokens(Some(1_000_000));
+        assert_eq!(metric.reasoning_tokens, Some(1_000_000));
+        assert_eq!(metric.output_tokens, Some(0));
+        assert!((metric.cost_usd.unwrap() - 18.0).abs() < 1e-9);
+    }
+
+    /// Lines written before usage/cost fields existed must still parse.
+    #[test]
+    fn test_request_metric_reads_legacy_json() {
+        let json = r#"{"timestamp":"2026-01-01T00:00:00Z","query_hash":"h","routing_decision":"forward",
+            "pattern_id":null,"confidence":null,"forward_reason":null,"response_time_ms":5,
+            "router_confidence":null,"validator_confidence":null}"#;
+        let metric: RequestMetric = serde_json::from_str(json).unwrap();
+        assert!(metric.cost_usd.is_none());
+        assert!(metric.error.is_none());
+    }
+
     #[test]
     fn test_response_comparison_serde_roundtrip() {
         let c = ResponseComparison {
@@ -0,0 +1,276 @@
+// Local generation benchmarks - tokens/sec per inference path
+//
+// `finch node-info --bench` times the configured model on each local path the
+// machine offers: on a Mac, MLX on the GPU against ONNX Runtime with CoreML.
+// Results are kept in ~/.finch/models/benchmarks.json and reported in
+// NodeInfo, so `finch node-info` and /v1/node/info show the last measurement.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::generator_new::TextGeneration;
+#[cfg(target_os = "macos")]
+use super::loaders::mlx;
+use super::loaders::mlx::MlxLoader;
+use super::unified_loader::{InferenceProvider, ModelLoadConfig, UnifiedModelLoader};
+#[cfg(target_os = "macos")]
+use crate::config::ExecutionTarget;
+
+/// Prompt every path generates from
+const BENCH_PROMPT: &str = "Explain how a hash map resolves collisions, with an example.";
+
+/// Tokens generated per measurement
+const BENCH_TOKENS: usize = 128;
+
+/// Tokens generated first, outside the measurement, so kernel compilation
+/// and cache warm-up don't count
+const WARMUP_TOKENS: usize = 8;
+
+/// One measured inference path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationBenchmark {
+    /// Inference path, e.g. "MLX (Metal)"
+    pub backend: String,
+    /// Model the path ran
+    pub model: String,
+    /// Tokens generated (fewer than asked when the model stopped early)
+    pub tokens: usize,
+    pub seconds: f64,
+    pub measured_at: DateTime<Utc>,
+}
+
+impl GenerationBenchmark {
+    pub fn tokens_per_sec(&self) -> f64 {
+        if self.seconds > 0.0 {
+            self.tokens as f64 / self.seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// One-line description for status display
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {:.1} tok/s ({})",
+            self.backend,
+            self.tokens_per_sec(),
+            self.model
+        )
+    }
+}
+
+/// Time `generator` on the benchmark prompt
+pub fn run(backend: &str, generator: &mut dyn TextGeneration) -> Result<GenerationBenchmark> {
+    let input_ids = generator.tokenize(BENCH_PROMPT)?;
+    generator
+        .generate(&input_ids, WARMUP_TOKENS)
+        .context("Warm-up generation failed")?;
+
+    let started = Instant::now();
+    let output_ids = generator.generate(&input_ids, BENCH_TOKENS)?;
+    let seconds = started.elapsed().as_secs_f64();
+
+    Ok(GenerationBenchmark {
+        backend: backend.to_string(),
+        model: generator.name().to_string(),
+        tokens: output_ids.len().saturating_sub(input_ids.len()),
+        seconds,
+        measured_at: Utc::now(),
+    })
+}
+
+/// A way of running the configured model
+pub struct BenchPath {
+    pub label: String,
+    /// Set for MLX, which loads outside `UnifiedModelLoader` so a missing
+    /// mlx-lm fails the path rather than falling back to ONNX Runtime
+    mlx_repo: Option<&'static str>,
+    config: ModelLoadConfig,
+}
+
+impl BenchPath {
+    fn new(config: ModelLoadConfig) -> Self {
+        Self {
+            label: format!("{} on {}", config.provider.name(), config.target.name()),
+            mlx_repo: None,
+            config,
+        }
+    }
+
+    /// Load the model for this path (blocking, may download)
+    pub fn load(&self) -> Result<Box<dyn TextGeneration>> {
+        match self.mlx_repo {
+            Some(repo) => MlxLoader::new().load(repo),
+            None => UnifiedModelLoader::new()?.load(self.config.clone()),
+        }
+    }
+}
+
+/// The paths to compare for `config`: MLX and ONNX Runtime with CoreML on a
+/// Mac, plus the configured path when it is neither
+pub fn paths(config: &ModelLoadConfig) -> Vec<BenchPath> {
+    let mut paths = Vec::new();
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(repo) = mlx::repository(config.family, config.size) {
+            paths.push(BenchPath {
+                label: "MLX (Metal)".to_string(),
+                mlx_repo: Some(repo),
+                config: config.clone(),
+            });
+        }
+        paths.push(BenchPath::new(ModelLoadConfig {
+            provider: InferenceProvider::Onnx,
+            target: ExecutionTarget::CoreML,
+            ..config.clone()
+        }));
+    }
+
+    let covered = cfg!(target_os = "macos") && config.provider == InferenceProvider::Onnx;
+    if !covered {
+        paths.push(BenchPath::new(config.clone()));
+    }
+    paths
+}
+
+/// Latest benchmark of each path (~/.finch/models/benchmarks.json)
+pub struct BenchmarkStore {
+    path: PathBuf,
+}
+
+impl BenchmarkStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The store in ~/.finch/models
+    pub fn open_default() -> Option<Self> {
+        let dir = dirs::home_dir()?.join(".finch").join("models");
+        Some(Self::new(dir.join("benchmarks.json")))
+    }
+
+    fn load(&self) -> BTreeMap<String, GenerationBenchmark> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Every path's latest result, fastest first
+    pub fn all(&self) -> Vec<GenerationBenchmark> {
+        let mut all: Vec<_> = self.load().into_values().collect();
+        all.sort_by(|a, b| b.tokens_per_sec().total_cmp(&a.tokens_per_sec()));
+        all
+    }
+
+    /// Replaces the path's previous result
+    pub fn record(&self, benchmark: &GenerationBenchmark) -> Result<()> {
+        let mut all = self.load();
+        all.insert(benchmark.backend.clone(), benchmark.clone());
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&all)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExecutionTarget;
+    use crate::models::unified_loader::{ModelFamily, ModelSize};
+
+    /// Emits `BENCH_TOKENS / 2` tokens, then stops
+    struct ShortModel;
+
+    impl TextGeneration for ShortModel {
+        fn generate(&mut self, input_ids: &[u32], max_new_tokens: usize) -> Result<Vec<u32>> {
+            let mut output = input_ids.to_vec();
+            output.resize(input_ids.len() + max_new_tokens.min(BENCH_TOKENS / 2), 7);
+            Ok(output)
+        }
+
+        fn tokenize(&self, text: &str) -> Result<Vec<u32>> {
+            Ok(text.split_whitespace().map(|_| 1).collect())
+        }
+
+        fn decode_tokens(&self, _tokens: &[u32]) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn name(&self) -> &str {
+            "short"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+    }
+
+    fn benchmark(backend: &str, tokens: usize, seconds: f64) -> GenerationBenchmark {
+        GenerationBenchmark {
+            backend: backend.to_string(),
+            model: "Qwen2.5-3B-Instruct".to_string(),
+            tokens,
+            seconds,
+            measured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_run_counts_only_generated_tokens() {
+        let result = run("fake", &mut ShortModel).unwrap();
+        assert_eq!(result.tokens, BENCH_TOKENS / 2);
+        assert_eq!(result.model, "short");
+        assert_eq!(benchmark("idle", 10, 0.0).tokens_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_store_keeps_latest_per_path_fastest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BenchmarkStore::new(dir.path().join("models").join("benchmarks.json"));
+        assert!(store.all().is_empty());
+
+        store
+            .record(&benchmark("ONNX Runtime on CPU", 64, 4.0))
+            .unwrap();
+        store.record(&benchmark("MLX (Metal)", 128, 4.0)).unwrap();
+        store.record(&benchmark("MLX (Metal)", 128, 2.0)).unwrap();
+
+        let all = store.all();
+        assert_eq!(all.len(), 2);
+        assert_eq!(
+            all[0].summary(),
+            "MLX (Metal): 64.0 tok/s (Qwen2.5-3B-Instruct)"
+        );
+        assert_eq!(all[1].tokens_per_sec(), 16.0);
+    }
+
+    #[test]
+    fn test_paths_cover_the_configured_backend() {
+        let config = ModelLoadConfig {
+            provider: InferenceProvider::Onnx,
+            family: ModelFamily::Qwen2,
+            size: ModelSize::Medium,
+            target: ExecutionTarget::Cpu,
+            repo_override: None,
+        };
+        let labels: Vec<String> = paths(&config).into_iter().map(|p| p.label).collect();
+        if cfg!(target_os = "macos") {
+            assert_eq!(labels, ["MLX (Metal)", "ONNX Runtime on CoreML (ANE)"]);
+        } else {
+            assert_eq!(labels, ["ONNX Runtime on CPU"]);
+        }
+    }
+}
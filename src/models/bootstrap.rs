@@ -3,10 +3,11 @@
 
 use anyhow::{anyhow, Context, Result};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
 use super::generator_new::GeneratorModel;
+use super::load_progress::{self, LoadProgress};
 use super::unified_loader::{ModelFamily, ModelLoadConfig, ModelSize};
 use super::GeneratorConfig;
 use crate::cli::OutputManager;
@@ -85,12 +86,21 @@ impl GeneratorState {
 pub struct BootstrapLoader {
     state: Arc<RwLock<GeneratorState>>,
     output: Option<Arc<OutputManager>>,
+    /// Download / tokenizer / warm-up detail for the current load
+    progress: Arc<LoadProgress>,
+    /// What the current (or last) load was asked for
+    config: Mutex<Option<ModelLoadConfig>>,
 }
 
 impl BootstrapLoader {
     /// Create new bootstrap loader with shared state
     pub fn new(state: Arc<RwLock<GeneratorState>>, output: Option<Arc<OutputManager>>) -> Self {
-        Self { state, output }
+        Self {
+            state,
+            output,
+            progress: Arc::new(LoadProgress::default()),
+            config: Mutex::new(None),
+        }
     }
 
     /// Get reference to the generator state
@@ -98,6 +108,16 @@ impl BootstrapLoader {
         &self.state
     }
 
+    /// Fine-grained progress of the current (or last) load
+    pub fn progress(&self) -> &Arc<LoadProgress> {
+        &self.progress
+    }
+
+    /// Model the current (or last) load was for; `None` before the first
+    pub fn load_config(&self) -> Option<ModelLoadConfig> {
+        self.config.lock().unwrap().clone()
+    }
+
     /// Check if HuggingFace token exists and is valid
     fn check_hf_token() -> Result<()> {
         let token_path = dirs::cache_dir()
@@ -149,6 +169,7 @@ impl BootstrapLoader {
     ) -> Result<()> {
         // Step 1: Initializing
         *self.state.write().await = GeneratorState::Initializing;
+        self.progress.reset();
 
         let model_name = format!(
             "{} {} ({:?})",
@@ -170,6 +191,7 @@ impl BootstrapLoader {
             target: execution_target,
             repo_override: model_repo.clone(),
         };
+        *self.config.lock().unwrap() = Some(load_config.clone());
 
         // Step 4: Load using UnifiedModelLoader (handles download + loading)
         *self.state.write().await = GeneratorState::Loading {
@@ -192,15 +214,26 @@ impl BootstrapLoader {
         // Load in blocking task (model loading + potential download is CPU/IO intensive)
         let model_name_clone = model_name.clone();
         let output_clone = self.output.clone();
+        let progress = Arc::clone(&self.progress);
 
         let generator = tokio::task::spawn_blocking(move || {
-            if let Some(output) = &output_clone {
-                output.write_progress(format!("  └─ Initializing {}...", model_name_clone));
-            }
+            load_progress::scope(Arc::clone(&progress), || {
+                if let Some(output) = &output_clone {
+                    output.write_progress(format!("  └─ Initializing {}...", model_name_clone));
+                }
 
-            // GeneratorModel::new() handles download + loading internally
-            let config = GeneratorConfig::Pretrained(load_config);
-            GeneratorModel::new(config)
+                // GeneratorModel::new() handles download + loading internally
+                let config = GeneratorConfig::Pretrained(load_config);
+                let mut generator = GeneratorModel::new(config)?;
+
+                // Run one tiny generation so the first real query doesn't
+                // pay for kernel compilation and allocator warm-up
+                match generator.generate_text("Hello", 1) {
+                    Ok(_) => progress.set_warmup_complete(),
+                    Err(e) => tracing::warn!("Model warm-up generation failed: {}", e),
+                }
+                Ok::<_, anyhow::Error>(generator)
+            })
         })
         .await??;
 
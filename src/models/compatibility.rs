@@ -4,6 +4,10 @@
 // with required files (config.json, tokenizer.json, .onnx model files)
 //
 // Last audit: 2026-02-17
+//
+// GGUF repositories (the `gguf` feature) hold .gguf files only; the file for
+// the configured quantization is picked from the repo's listing at download
+// time. They were not part of the audit above.
 
 use super::unified_loader::{InferenceProvider, ModelFamily, ModelSize};
 use crate::config::ExecutionTarget;
@@ -35,6 +39,10 @@ pub struct ModelCompatibility {
     #[cfg(feature = "candle")]
     pub candle_size_repos: Option<fn(ModelSize) -> Option<&'static str>>,
 
+    /// GGUF repositories for llama.cpp, by size (one file per quantization)
+    #[cfg(feature = "gguf")]
+    pub gguf_size_repos: Option<fn(ModelSize) -> Option<&'static str>>,
+
     /// Notes about this model family
     pub notes: &'static str,
 }
@@ -77,6 +85,12 @@ impl ModelCompatibility {
                     None
                 }
             }
+
+            #[cfg(feature = "gguf")]
+            InferenceProvider::Gguf => self
+                .gguf_size_repos
+                .and_then(|size_repos| size_repos(size))
+                .map(str::to_string),
         }
     }
 }
@@ -99,6 +113,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -125,6 +141,13 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Large => Some("Qwen/Qwen2.5-7B-Instruct"),
             ModelSize::XLarge => Some("Qwen/Qwen2.5-14B-Instruct"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small => Some("bartowski/Qwen2.5-1.5B-Instruct-GGUF"),
+            ModelSize::Medium => Some("bartowski/Qwen2.5-3B-Instruct-GGUF"),
+            ModelSize::Large => Some("bartowski/Qwen2.5-7B-Instruct-GGUF"),
+            ModelSize::XLarge => Some("bartowski/Qwen2.5-14B-Instruct-GGUF"),
+        }),
         notes:
             "Best overall quality. ONNX uses Coder variant for 3B. Candle uses original Qwen repos.",
     },
@@ -136,6 +159,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -162,6 +187,13 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Large => Some("meta-llama/Llama-3.1-8B-Instruct"), // 3.1 for 8B
             ModelSize::XLarge => Some("meta-llama/Llama-3.1-70B-Instruct"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small => Some("bartowski/Llama-3.2-1B-Instruct-GGUF"),
+            ModelSize::Medium => Some("bartowski/Llama-3.2-3B-Instruct-GGUF"),
+            ModelSize::Large => Some("bartowski/Meta-Llama-3.1-8B-Instruct-GGUF"),
+            ModelSize::XLarge => Some("bartowski/Meta-Llama-3.1-70B-Instruct-GGUF"),
+        }),
         notes: "ONNX: Only 1B/3B available. Candle: Full range including 8B and 70B.",
     },
     // Gemma - Google's model
@@ -172,6 +204,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -197,6 +231,12 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Large => Some("google/gemma-2-9b-it"),
             ModelSize::XLarge => Some("google/gemma-2-27b-it"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small | ModelSize::Medium => Some("bartowski/gemma-2-2b-it-GGUF"),
+            ModelSize::Large => Some("bartowski/gemma-2-9b-it-GGUF"),
+            ModelSize::XLarge => Some("bartowski/gemma-2-27b-it-GGUF"),
+        }),
         notes: "ONNX: Community repos (270M/2B/7B). Candle: Official Google repos (2B/9B/27B).",
     },
     // Mistral - Efficient 7B model
@@ -207,6 +247,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -226,6 +268,13 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Small | ModelSize::Medium => Some("mistralai/Mistral-7B-Instruct-v0.3"),
             ModelSize::Large | ModelSize::XLarge => Some("mistralai/Mixtral-8x22B-Instruct-v0.1"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small | ModelSize::Medium => Some("bartowski/Mistral-7B-Instruct-v0.3-GGUF"),
+            ModelSize::Large | ModelSize::XLarge => {
+                Some("bartowski/Mistral-Small-Instruct-2409-GGUF")
+            }
+        }),
         notes: "ONNX: Only 7B (community). Candle: 7B and 22B (official Mistral).",
     },
     // Phi - Microsoft's compact model
@@ -236,6 +285,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -260,6 +311,11 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Medium => Some("microsoft/Phi-3.5-mini-instruct"),
             ModelSize::Large | ModelSize::XLarge => Some("microsoft/Phi-4-14b-instruct"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small | ModelSize::Medium => Some("bartowski/Phi-3.5-mini-instruct-GGUF"),
+            ModelSize::Large | ModelSize::XLarge => Some("bartowski/phi-4-GGUF"),
+        }),
         notes: "Official Microsoft repositories for both ONNX and Candle. Phi-4 recommended.",
     },
     // DeepSeek - Specialized for coding
@@ -270,6 +326,8 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
         supported_targets: &[
             #[cfg(target_os = "macos")]
             ExecutionTarget::CoreML,
+            #[cfg(target_os = "macos")]
+            ExecutionTarget::Metal,
             ExecutionTarget::Cpu,
             #[cfg(feature = "cuda")]
             ExecutionTarget::Cuda,
@@ -291,6 +349,13 @@ static COMPATIBILITY_MATRIX: &[ModelCompatibility] = &[
             ModelSize::Large => Some("deepseek-ai/DeepSeek-Coder-V2-Lite-Instruct"), // 16B
             ModelSize::XLarge => Some("deepseek-ai/DeepSeek-Coder-33B-Instruct"),
         }),
+        #[cfg(feature = "gguf")]
+        gguf_size_repos: Some(|size| match size {
+            ModelSize::Small => Some("TheBloke/deepseek-coder-1.3b-instruct-GGUF"),
+            ModelSize::Medium => Some("TheBloke/deepseek-coder-6.7B-instruct-GGUF"),
+            ModelSize::Large => Some("bartowski/DeepSeek-Coder-V2-Lite-Instruct-GGUF"),
+            ModelSize::XLarge => Some("TheBloke/deepseek-coder-33B-instruct-GGUF"),
+        }),
         notes: "ONNX: Only 1.5B (R1 Distill). Candle: Full Coder range (1.3B-33B).",
     },
 ];
@@ -326,6 +391,8 @@ pub fn get_compatible_families_for_provider(
                 InferenceProvider::Candle => {
                     c.candle_size_repos.is_some() || !c.candle_repo_template.is_empty()
                 }
+                #[cfg(feature = "gguf")]
+                InferenceProvider::Gguf => c.gguf_size_repos.is_some(),
             }
         })
         .map(|c| c.family)
@@ -560,6 +627,23 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "gguf")]
+    fn test_all_repos_return_something_gguf() {
+        // Every family should return a GGUF repository for every size
+        for compat in COMPATIBILITY_MATRIX {
+            for size in compat.sizes {
+                let repo = get_repository(InferenceProvider::Gguf, compat.family, *size);
+                assert!(
+                    repo.is_some_and(|r| r.ends_with("-GGUF")),
+                    "{:?} should have a GGUF repository for {:?}",
+                    compat.family,
+                    size
+                );
+            }
+        }
+    }
+
     #[test]
     #[cfg(feature = "candle")]
     fn test_all_repos_return_something_candle() {
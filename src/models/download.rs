@@ -2,10 +2,12 @@
 // Uses HuggingFace Hub for download management and caching
 
 use anyhow::{anyhow, Context, Result};
-use hf_hub::{api::sync::Api, Repo, RepoType};
 use std::path::PathBuf;
 use std::sync::mpsc;
 
+use super::hub::HubRepo;
+use super::integrity::{self, ModelVerifier, ProvenanceStore};
+use super::load_progress;
 use super::model_selector::QwenSize;
 
 /// Download progress events sent via channel
@@ -29,6 +31,33 @@ pub enum DownloadProgress {
     Error { model_id: String, error: String },
 }
 
+/// Fetch `file` from `repo`, counting it toward the model load in progress
+fn fetch(repo: &HubRepo, file: &str) -> Result<PathBuf> {
+    load_progress::report(|p| p.start_file(file));
+    let result = repo.get(file);
+    load_progress::report(|p| match result {
+        Ok(_) => p.file_done(),
+        Err(_) => p.file_skipped(),
+    });
+    result
+}
+
+/// Verify downloaded files and record the model's provenance
+fn verify_files(repo_id: &str, model_dir: &std::path::Path, files: &[PathBuf]) -> Result<()> {
+    tracing::info!("Verifying checksums for {}...", repo_id);
+    let store = ProvenanceStore::open_default();
+    let previous = store.as_ref().and_then(|s| s.get(repo_id));
+    let provenance =
+        ModelVerifier::from_settings()?.verify(repo_id, model_dir, files, previous.as_ref())?;
+    tracing::info!("{}", provenance.summary());
+    if let Some(store) = store {
+        if let Err(e) = store.record(&provenance) {
+            tracing::warn!("Failed to record model provenance: {}", e);
+        }
+    }
+    Ok(())
+}
+
 /// Model downloader with HuggingFace Hub integration
 pub struct ModelDownloader {
     cache_dir: Option<PathBuf>,
@@ -86,11 +115,8 @@ impl ModelDownloader {
         })
         .ok();
 
-        // Create API instance (cache dir controlled by HF_HOME env var if set)
-        let api = Api::new()?;
-
-        // Get repository reference
-        let repo = api.repo(Repo::new(repo_id.to_string(), RepoType::Model));
+        // Get repository reference (cache dir controlled by HF_HOME env var if set)
+        let repo = HubRepo::model(repo_id)?;
 
         tracing::info!("Downloading {} to cache...", repo_id);
 
@@ -101,13 +127,15 @@ impl ModelDownloader {
             ("config.json", true),            // Required
             ("tokenizer.json", true),         // Required
             ("tokenizer_config.json", false), // Optional
+            ("generation_config.json", false), // Optional - stop tokens
             ("meta.yaml", false),             // Optional - CoreML component mapping
         ];
 
         let mut required_failed = Vec::new();
 
+        load_progress::report(|p| p.expect_files(config_files.len()));
         for (file, required) in &config_files {
-            match repo.get(file) {
+            match fetch(&repo, file) {
                 Ok(path) => {
                     tracing::info!("Downloaded {} to {:?}", file, path);
                     downloaded_files.push(path);
@@ -134,6 +162,13 @@ impl ModelDownloader {
             ));
         }
 
+        // Checksum manifest and its signature, when the repo publishes them
+        for file in [integrity::MANIFEST_FILE, integrity::SIGNATURE_FILE] {
+            if repo.get(file).is_ok() {
+                tracing::debug!("Downloaded {}", file);
+            }
+        }
+
         // Try to download model weights
         // Check if this is a CoreML model (anemll repos have .mlmodelc directories)
         let coreml_model_dirs = vec![
@@ -166,9 +201,10 @@ impl ModelDownloader {
                     tracing::info!("✓ Found CoreML bundle: {}", dir_name);
 
                     // Download all files in this bundle
+                    load_progress::report(|p| p.expect_files(mlmodelc_files.len()));
                     for file in &mlmodelc_files {
                         let full_path = format!("{}/{}", dir_name, file);
-                        match repo.get(&full_path) {
+                        match fetch(&repo, &full_path) {
                             Ok(path) => {
                                 tracing::debug!("  ✓ {}", file);
                                 downloaded_files.push(path);
@@ -201,8 +237,9 @@ impl ModelDownloader {
                 "onnx/model.onnx_data", // Optional: external data for large models
             ];
 
+            load_progress::report(|p| p.expect_files(onnx_files.len()));
             for file in &onnx_files {
-                match repo.get(file) {
+                match fetch(&repo, file) {
                     Ok(path) => {
                         tracing::info!("Downloaded {}", file);
                         downloaded_files.push(path);
@@ -221,7 +258,8 @@ impl ModelDownloader {
 
         if !found_coreml && !found_onnx {
             // Not CoreML or ONNX, try standard safetensors files
-            match repo.get("model.safetensors") {
+            load_progress::report(|p| p.expect_files(1));
+            match fetch(&repo, "model.safetensors") {
                 Ok(path) => {
                     tracing::info!("Downloaded single model file");
                     downloaded_files.push(path);
@@ -250,6 +288,12 @@ impl ModelDownloader {
                                     );
                                     downloaded_files.push(path);
                                     found_this_shard = true;
+                                    load_progress::report(|p| {
+                                        if shard_idx == 1 {
+                                            p.expect_files(total);
+                                        }
+                                        p.file_done();
+                                    });
 
                                     // If we found shard N of N, we're done
                                     if shard_idx == total {
@@ -295,6 +339,12 @@ impl ModelDownloader {
             ));
         };
 
+        // Check every file against the model's manifest before anything loads it
+        if let Err(e) = verify_files(repo_id, &cache_path, &downloaded_files) {
+            progress_msg.set_failed();
+            return Err(e);
+        }
+
         // Mark progress as complete
         progress_msg.update_progress(100);
         progress_msg.set_complete();
@@ -308,6 +358,54 @@ impl ModelDownloader {
         Ok((cache_path, rx))
     }
 
+    /// Download the `.gguf` file(s) for `quantization` from a GGUF repository
+    ///
+    /// GGUF repos hold every quantization side by side (tens of GB), so this
+    /// fetches only the one chosen by `gguf::select_files`.  Returns the path
+    /// of the model file, or of the first shard of a split one.
+    #[cfg(feature = "gguf")]
+    pub fn download_gguf(&self, repo_id: &str, quantization: &str) -> Result<PathBuf> {
+        use super::loaders::gguf;
+
+        let repo = HubRepo::model(repo_id)?;
+
+        let listing: Vec<String> = repo
+            .info()
+            .with_context(|| format!("Failed to list files of {}", repo_id))?
+            .siblings
+            .into_iter()
+            .map(|s| s.rfilename)
+            .collect();
+        let files = gguf::select_files(&listing, quantization);
+        if files.is_empty() {
+            return Err(anyhow!("No .gguf files found in {}", repo_id));
+        }
+        tracing::info!("Downloading {} from {}", files.join(", "), repo_id);
+
+        // Checksum manifest and its signature, when the repo publishes them
+        for file in [integrity::MANIFEST_FILE, integrity::SIGNATURE_FILE] {
+            if repo.get(file).is_ok() {
+                tracing::debug!("Downloaded {}", file);
+            }
+        }
+
+        let mut downloaded_files = Vec::new();
+        load_progress::report(|p| p.expect_files(files.len()));
+        for file in &files {
+            let path =
+                fetch(&repo, file).with_context(|| format!("Failed to download {}", file))?;
+            downloaded_files.push(path);
+        }
+
+        let model_file = downloaded_files[0].clone();
+        let model_dir = model_file
+            .parent()
+            .context("Failed to get cache directory")?;
+        verify_files(repo_id, model_dir, &downloaded_files)?;
+
+        Ok(model_file)
+    }
+
     /// Download Qwen model with progress tracking (convenience wrapper)
     ///
     /// Returns path to cached model directory containing safetensors and tokenizer files.
@@ -331,14 +429,11 @@ impl ModelDownloader {
             old_val
         });
 
-        let api = match Api::new() {
-            Ok(api) => api,
+        let repo = match HubRepo::model(model_size.model_id()) {
+            Ok(repo) => repo,
             Err(_) => return false,
         };
 
-        let model_id = model_size.model_id();
-        let repo = api.repo(Repo::new(model_id.to_string(), RepoType::Model));
-
         // Check if required files exist in cache
         let result = repo.get("config.json").is_ok() && repo.get("tokenizer.json").is_ok();
 
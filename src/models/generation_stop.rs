@@ -0,0 +1,53 @@
+// Stopping a local generation between tokens
+//
+// Generation runs inside a backend's decode loop on a blocking thread, out of
+// reach of the request that started it.  `scope()` gives that thread a stop
+// flag, and the ONNX, Candle and GGUF loops check `requested()` before each
+// token, ending the generation early once the flag is set.  The MLX backend
+// streams from a worker process and always finishes its generation.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `stop` as the flag this thread's generations check
+pub fn scope<T>(stop: Arc<AtomicBool>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<AtomicBool>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|c| c.borrow_mut().replace(stop)));
+    f()
+}
+
+/// Whether the generation running on this thread should stop
+pub fn requested() -> bool {
+    CURRENT.with(|c| {
+        c.borrow()
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_applies_only_inside_scope() {
+        let stop = Arc::new(AtomicBool::new(true));
+        assert!(!requested());
+        assert!(scope(Arc::clone(&stop), requested));
+        assert!(!requested(), "restored after the scope");
+
+        stop.store(false, Ordering::Relaxed);
+        assert!(!scope(stop, requested));
+    }
+}
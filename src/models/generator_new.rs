@@ -4,6 +4,7 @@
 use anyhow::Result;
 use std::path::Path;
 
+use super::adapters::ModelFamily;
 use super::common::{GeneratorConfig, Saveable};
 use super::unified_loader::UnifiedModelLoader;
 
@@ -36,6 +37,13 @@ pub trait TextGeneration: Send + Sync {
     /// Get model name/description
     fn name(&self) -> &str;
 
+    /// Chat-template family detected from the model's own files, if known.
+    ///
+    /// Used to pick the prompt adapter when the model name doesn't say.
+    fn model_family(&self) -> Option<ModelFamily> {
+        None
+    }
+
     /// Downcast to Any for accessing concrete type methods
     fn as_any(&self) -> &dyn std::any::Any;
 
@@ -109,6 +117,11 @@ impl GeneratorModel {
         self.backend.name()
     }
 
+    /// Chat-template family detected by the backend, if any
+    pub fn model_family(&self) -> Option<ModelFamily> {
+        self.backend.model_family()
+    }
+
     /// Get mutable reference to backend (for accessing ONNX model directly)
     pub fn backend_mut(&mut self) -> &mut dyn TextGeneration {
         self.backend.as_mut()
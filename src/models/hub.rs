@@ -0,0 +1,266 @@
+// HuggingFace Hub downloads through the shared HTTP client
+//
+// hf-hub's downloaders build their own HTTP agents, which only see a proxy
+// set in the environment and never `[http] ca_bundle`, so model downloads
+// failed behind TLS-intercepting proxies that everything else got through.
+// Files are fetched here with `crate::http::builder()` instead and stored in
+// hf-hub's cache layout (`blobs/<etag>`, `snapshots/<commit>/<file>`,
+// `refs/main`), so `hf_hub::Cache` lookups elsewhere keep finding them.
+
+use anyhow::{bail, Context, Result};
+use hf_hub::{Cache, Repo};
+use reqwest::{header, redirect, Client, RequestBuilder, Response};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+const DEFAULT_ENDPOINT: &str = "https://huggingface.co";
+
+/// Branch files are downloaded from
+const REVISION: &str = "main";
+
+/// Relative redirects followed while resolving a file (renamed repositories)
+const MAX_REDIRECTS: usize = 5;
+
+/// Files in a repository, from the Hub's model API
+#[derive(Debug, Deserialize)]
+pub struct RepoInfo {
+    pub siblings: Vec<Sibling>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Sibling {
+    pub rfilename: String,
+}
+
+/// A model repository on the Hub, cached where hf-hub caches it
+pub struct HubRepo {
+    repo_id: String,
+    repo: Repo,
+    cache: Cache,
+    endpoint: String,
+    token: Option<String>,
+    client: Client,
+    /// Same settings, but stops at the first redirect so the Hub's commit
+    /// and etag headers can be read before the CDN takes over
+    metadata_client: Client,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl HubRepo {
+    /// `repo_id` on the Hub (`HF_ENDPOINT`), cached under `HF_HOME`
+    pub fn model(repo_id: &str) -> Result<Self> {
+        let endpoint = std::env::var("HF_ENDPOINT")
+            .ok()
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        Self::new(repo_id, Cache::from_env(), endpoint)
+    }
+
+    fn new(repo_id: &str, cache: Cache, endpoint: String) -> Result<Self> {
+        let client = crate::http::builder()
+            .build()
+            .context("Failed to build HuggingFace Hub client")?;
+        let metadata_client = crate::http::builder()
+            .redirect(redirect::Policy::none())
+            .build()
+            .context("Failed to build HuggingFace Hub client")?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            repo_id: repo_id.to_string(),
+            repo: Repo::model(repo_id.to_string()),
+            token: cache.token(),
+            cache,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            client,
+            metadata_client,
+            runtime,
+        })
+    }
+
+    /// Path of `filename` in the cache, downloading it first if it isn't
+    /// there.  Blocking; call it from a blocking thread.
+    pub fn get(&self, filename: &str) -> Result<PathBuf> {
+        if let Some(path) = self.cache.repo(self.repo.clone()).get(filename) {
+            return Ok(path);
+        }
+        self.runtime
+            .block_on(self.download(filename))
+            .with_context(|| format!("Failed to download {} from {}", filename, self.repo_id))
+    }
+
+    /// The repository's file listing.  Blocking.
+    pub fn info(&self) -> Result<RepoInfo> {
+        let url = format!(
+            "{}/api/models/{}/revision/{}",
+            self.endpoint, self.repo_id, REVISION
+        );
+        self.runtime.block_on(async {
+            let response = self.authorized(self.client.get(&url)).send().await?;
+            Ok(checked(response)?.json().await?)
+        })
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            // reqwest drops the header when a redirect leaves the Hub
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn download(&self, filename: &str) -> Result<PathBuf> {
+        let url = format!(
+            "{}/{}/resolve/{}/{}",
+            self.endpoint, self.repo_id, REVISION, filename
+        );
+        let (commit, etag) = self.metadata(&url).await?;
+
+        let repo_dir = self.cache.path().join(self.repo.folder_name());
+        let blob = repo_dir.join("blobs").join(&etag);
+        if !blob.exists() {
+            let blobs = blob.parent().context("Blob path has no parent")?;
+            fs::create_dir_all(blobs)
+                .with_context(|| format!("Failed to create {}", blobs.display()))?;
+            let partial = blob.with_extension("part");
+            let mut response = checked(self.authorized(self.client.get(&url)).send().await?)?;
+            let mut file = tokio::fs::File::create(&partial)
+                .await
+                .with_context(|| format!("Failed to create {}", partial.display()))?;
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            tokio::fs::rename(&partial, &blob).await?;
+        }
+
+        let pointer = repo_dir.join("snapshots").join(&commit).join(filename);
+        if let Some(dir) = pointer.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        link(&blob, &pointer)?;
+        self.cache.repo(self.repo.clone()).create_ref(&commit)?;
+        Ok(pointer)
+    }
+
+    /// Commit and etag of the file at `url`
+    async fn metadata(&self, url: &str) -> Result<(String, String)> {
+        let mut url = reqwest::Url::parse(url)?;
+        let mut response = self.probe(&url).await?;
+        for _ in 0..MAX_REDIRECTS {
+            if !response.status().is_redirection() {
+                break;
+            }
+            // Absolute redirects go to the CDN; the headers needed are on
+            // this response already
+            match response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|l| l.to_str().ok())
+            {
+                Some(location) if location.starts_with('/') => {
+                    url = url.join(location)?;
+                    response = self.probe(&url).await?;
+                }
+                _ => break,
+            }
+        }
+        if !response.status().is_redirection() {
+            response = checked(response)?;
+        }
+
+        let headers = response.headers();
+        let value = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let etag = value("x-linked-etag")
+            .or_else(|| value(header::ETAG.as_str()))
+            .context("Hub response has no etag")?
+            .replace('"', "");
+        let commit = value("x-repo-commit")
+            .context("Hub response has no x-repo-commit")?
+            .to_string();
+        Ok((commit, etag))
+    }
+
+    async fn probe(&self, url: &reqwest::Url) -> Result<Response> {
+        Ok(self
+            .authorized(self.metadata_client.get(url.clone()))
+            .header(header::RANGE, "bytes=0-0")
+            .send()
+            .await?)
+    }
+}
+
+fn checked(response: Response) -> Result<Response> {
+    let status = response.status();
+    if !status.is_success() {
+        bail!("{} returned {}", response.url(), status);
+    }
+    Ok(response)
+}
+
+/// Point the snapshot entry at its blob, as hf-hub does
+fn link(blob: &Path, pointer: &Path) -> Result<()> {
+    if pointer.exists() {
+        return Ok(());
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(blob, pointer)?;
+    #[cfg(not(unix))]
+    fs::copy(blob, pointer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path as UrlPath, http::StatusCode, response::IntoResponse, Router};
+
+    async fn resolve(UrlPath(file): UrlPath<String>) -> impl IntoResponse {
+        match file.as_str() {
+            "config.json" => (
+                StatusCode::OK,
+                [("x-repo-commit", "abc123"), ("etag", "\"e1\"")],
+                "{\"hidden_size\": 8}",
+            )
+                .into_response(),
+            _ => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    fn serve() -> String {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async move {
+                let app =
+                    Router::new().route("/me/tiny/resolve/main/*file", axum::routing::get(resolve));
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        format!("http://{}", rx.recv().unwrap())
+    }
+
+    #[test]
+    fn test_downloads_into_hf_hub_cache_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = HubRepo::new("me/tiny", Cache::new(dir.path().to_path_buf()), serve()).unwrap();
+
+        let path = repo.get("config.json").unwrap();
+        assert!(path.ends_with("models--me--tiny/snapshots/abc123/config.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"hidden_size\": 8}");
+
+        // hf-hub itself finds the file now
+        let cached = Cache::new(dir.path().to_path_buf())
+            .model("me/tiny".to_string())
+            .get("config.json");
+        assert_eq!(cached, Some(path));
+
+        let missing = repo.get("model.onnx").unwrap_err();
+        assert!(format!("{:#}", missing).contains("404"), "{:#}", missing);
+    }
+}
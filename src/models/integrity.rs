@@ -0,0 +1,707 @@
+// Model integrity — checksum manifests, publisher signatures, quarantine
+//
+// Every file the downloader fetches (ONNX weights, tokenizer, configs) is
+// hashed and checked against a SHA256SUMS manifest before anything loads it.
+// The manifest comes from, in order:
+//
+//   1. a pinned copy at ~/.finch/manifests/<owner>/<name>/SHA256SUMS
+//   2. a SHA256SUMS file published in the HuggingFace repo itself
+//
+// A repo manifest can carry a detached Ed25519 signature (SHA256SUMS.sig,
+// base64, the same format as release signatures).  When `[model_integrity]`
+// has a key for the repo or its owner, the signature is required:
+//
+//   [model_integrity]
+//   require_manifest = false
+//
+//   [model_integrity.publisher_keys]
+//   "onnx-community" = "base64..."
+//
+// A file whose hash doesn't match is moved to ~/.finch/quarantine/ (out of
+// the HuggingFace cache, so the next load downloads it again) and the load
+// fails.  What was verified, and against what, is recorded per repo in
+// ~/.finch/models/provenance.json and reported in NodeInfo.  A file is
+// hashed again whenever its size or modification time changes.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::crypto;
+
+/// Checksum manifest in `sha256sum` format
+pub const MANIFEST_FILE: &str = "SHA256SUMS";
+
+/// Detached base64 Ed25519 signature over the manifest
+pub const SIGNATURE_FILE: &str = "SHA256SUMS.sig";
+
+static SETTINGS: OnceLock<IntegrityConfig> = OnceLock::new();
+
+/// Apply `[model_integrity]`.  Called once when the config is loaded; later
+/// calls are ignored.
+pub fn init(config: &IntegrityConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// `[model_integrity]` in config.toml
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityConfig {
+    /// Refuse to load models without a manifest covering every file
+    #[serde(default)]
+    pub require_manifest: bool,
+    /// Base64 Ed25519 keys by repo ("owner/name") or owner; a repo with a
+    /// key only loads with a manifest signed by it
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub publisher_keys: BTreeMap<String, String>,
+}
+
+impl IntegrityConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (publisher, key) in &self.publisher_keys {
+            crypto::parse_public_key(key, "Publisher key")
+                .with_context(|| format!("[model_integrity] key for {}", publisher))?;
+        }
+        Ok(())
+    }
+
+    /// The key that must sign `repo_id`'s manifest, with the name it's
+    /// configured under.  A key for the full repo wins over its owner's.
+    fn publisher_key(&self, repo_id: &str) -> Result<Option<(String, VerifyingKey)>> {
+        let owner = repo_id.split('/').next().unwrap_or(repo_id);
+        for name in [repo_id, owner] {
+            if let Some(key) = self.publisher_keys.get(name) {
+                let key = crypto::parse_public_key(key, "Publisher key")?;
+                return Ok(Some((name.to_string(), key)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Expected hashes by path relative to the repo root
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Parse `sha256sum` output: `<hex>  <path>` (or `<hex> *<path>`) per
+    /// line; blank lines and `#` comments are skipped.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (hash, path) = line.split_once(char::is_whitespace).with_context(|| {
+                format!(
+                    "{} line {}: expected `<sha256>  <path>`",
+                    MANIFEST_FILE,
+                    n + 1
+                )
+            })?;
+            let path = path.trim_start();
+            let path = path.strip_prefix('*').unwrap_or(path);
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!(
+                    "{} line {}: `{}` is not a SHA-256 hash",
+                    MANIFEST_FILE,
+                    n + 1,
+                    hash
+                );
+            }
+            files.insert(
+                path.trim_start_matches("./").to_string(),
+                hash.to_ascii_lowercase(),
+            );
+        }
+        if files.is_empty() {
+            bail!("{} lists no files", MANIFEST_FILE);
+        }
+        Ok(Self { files })
+    }
+}
+
+/// Where the manifest a model was checked against came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestSource {
+    /// ~/.finch/manifests/<owner>/<name>/SHA256SUMS
+    Pinned,
+    /// SHA256SUMS in the HuggingFace repo
+    Repository,
+    /// No manifest; files were hashed but not checked
+    None,
+}
+
+/// A model file as it was when last hashed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub sha256: String,
+    pub size: u64,
+    /// Modification time, seconds since the epoch
+    pub modified: u64,
+}
+
+/// What was verified about a downloaded model, and against what
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelProvenance {
+    /// HuggingFace repo ID
+    pub repo: String,
+    /// Commit the cached snapshot was downloaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    pub manifest: ManifestSource,
+    /// Publisher key (as named in `publisher_keys`) that signed the manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_by: Option<String>,
+    pub files: BTreeMap<String, FileRecord>,
+    /// Files the manifest doesn't cover
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlisted: Vec<String>,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl ModelProvenance {
+    /// Every file matched a manifest
+    pub fn verified(&self) -> bool {
+        self.manifest != ManifestSource::None && self.unlisted.is_empty()
+    }
+
+    /// One-line description for status display
+    pub fn summary(&self) -> String {
+        let status = match (self.manifest, &self.signed_by) {
+            (ManifestSource::None, _) => "unverified (no manifest)".to_string(),
+            (_, _) if !self.unlisted.is_empty() => {
+                format!(
+                    "partly verified ({} files not in manifest)",
+                    self.unlisted.len()
+                )
+            }
+            (ManifestSource::Pinned, _) => "verified against pinned manifest".to_string(),
+            (ManifestSource::Repository, Some(publisher)) => {
+                format!("verified, signed by {}", publisher)
+            }
+            (ManifestSource::Repository, None) => {
+                "verified against repo manifest (unsigned)".to_string()
+            }
+        };
+        match &self.revision {
+            Some(revision) => format!(
+                "{}@{} — {}",
+                self.repo,
+                revision.chars().take(12).collect::<String>(),
+                status
+            ),
+            None => format!("{} — {}", self.repo, status),
+        }
+    }
+}
+
+/// Provenance of each downloaded model (~/.finch/models/provenance.json)
+pub struct ProvenanceStore {
+    path: PathBuf,
+}
+
+impl ProvenanceStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The store in ~/.finch/models
+    pub fn open_default() -> Option<Self> {
+        let dir = dirs::home_dir()?.join(".finch").join("models");
+        Some(Self::new(dir.join("provenance.json")))
+    }
+
+    fn load(&self) -> BTreeMap<String, ModelProvenance> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, repo: &str) -> Option<ModelProvenance> {
+        self.load().remove(repo)
+    }
+
+    /// The most recently verified model
+    pub fn latest(&self) -> Option<ModelProvenance> {
+        self.load()
+            .into_values()
+            .max_by_key(|provenance| provenance.verified_at)
+    }
+
+    pub fn record(&self, provenance: &ModelProvenance) -> Result<()> {
+        let mut all = self.load();
+        all.insert(provenance.repo.clone(), provenance.clone());
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&all)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Checks downloaded model files before they are loaded
+pub struct ModelVerifier {
+    config: IntegrityConfig,
+    /// Root of the pinned manifests
+    pinned_dir: PathBuf,
+    /// Where mismatched files are moved
+    quarantine_dir: PathBuf,
+}
+
+impl ModelVerifier {
+    pub fn new(config: IntegrityConfig, pinned_dir: PathBuf, quarantine_dir: PathBuf) -> Self {
+        Self {
+            config,
+            pinned_dir,
+            quarantine_dir,
+        }
+    }
+
+    /// Verifier for `[model_integrity]`, pinning under ~/.finch/manifests
+    /// and quarantining under ~/.finch/quarantine
+    pub fn from_settings() -> Result<Self> {
+        let finch_dir = dirs::home_dir()
+            .context("Cannot determine home directory")?
+            .join(".finch");
+        Ok(Self::new(
+            SETTINGS.get().cloned().unwrap_or_default(),
+            finch_dir.join("manifests"),
+            finch_dir.join("quarantine"),
+        ))
+    }
+
+    /// Check `files`, downloaded from `repo_id` into `model_dir`, against
+    /// the model's manifest.  `previous` is the last provenance recorded for
+    /// the repo; its hashes are reused for files that haven't changed since.
+    ///
+    /// Mismatched files are quarantined and the call fails.  So does a repo
+    /// with a publisher key whose manifest isn't signed by it, and, with
+    /// `require_manifest`, a model the manifest doesn't fully cover.
+    pub fn verify(
+        &self,
+        repo_id: &str,
+        model_dir: &Path,
+        files: &[PathBuf],
+        previous: Option<&ModelProvenance>,
+    ) -> Result<ModelProvenance> {
+        let (manifest, source, signed_by) = self.manifest(repo_id, model_dir)?;
+
+        let mut records = BTreeMap::new();
+        let mut unlisted = Vec::new();
+        let mut mismatched = Vec::new();
+        for file in files {
+            let name = relative_name(model_dir, file);
+            if name == MANIFEST_FILE || name == SIGNATURE_FILE {
+                continue;
+            }
+            let record = hash_file(file, previous.and_then(|p| p.files.get(&name)))?;
+            match manifest.as_ref().map(|m| m.files.get(&name)) {
+                Some(Some(expected)) if *expected != record.sha256 => {
+                    mismatched.push((name, file, expected.clone(), record.sha256))
+                }
+                Some(Some(_)) => {
+                    records.insert(name, record);
+                }
+                _ => {
+                    unlisted.push(name.clone());
+                    records.insert(name, record);
+                }
+            }
+        }
+
+        if !mismatched.is_empty() {
+            let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+            let dest = self
+                .quarantine_dir
+                .join(repo_id.replace('/', "__"))
+                .join(stamp);
+            let mut lines = Vec::new();
+            for (name, file, expected, actual) in mismatched {
+                quarantine(file, &dest.join(&name))?;
+                tracing::error!(
+                    "{}: {} has SHA-256 {} but the manifest expects {}",
+                    repo_id,
+                    name,
+                    actual,
+                    expected
+                );
+                lines.push(format!(
+                    "  {} (expected {}, got {})",
+                    name, expected, actual
+                ));
+            }
+            bail!(
+                "Checksum mismatch in {}:\n{}\n\
+                 The files were moved to {} and will be downloaded again on the next load.",
+                repo_id,
+                lines.join("\n"),
+                dest.display()
+            );
+        }
+
+        if self.config.require_manifest {
+            if manifest.is_none() {
+                bail!(
+                    "{} has no {} and [model_integrity] require_manifest is set.\n\
+                     Pin one at {}",
+                    repo_id,
+                    MANIFEST_FILE,
+                    self.pinned_path(repo_id).display()
+                );
+            }
+            if !unlisted.is_empty() {
+                bail!(
+                    "The {} manifest for {} doesn't list: {}",
+                    MANIFEST_FILE,
+                    repo_id,
+                    unlisted.join(", ")
+                );
+            }
+        }
+        if manifest.is_none() {
+            tracing::warn!(
+                "{} has no {}; its files were hashed but not verified",
+                repo_id,
+                MANIFEST_FILE
+            );
+        } else if !unlisted.is_empty() {
+            tracing::warn!(
+                "{}: not in {}: {}",
+                repo_id,
+                MANIFEST_FILE,
+                unlisted.join(", ")
+            );
+        }
+
+        Ok(ModelProvenance {
+            repo: repo_id.to_string(),
+            revision: snapshot_revision(model_dir),
+            manifest: source,
+            signed_by,
+            files: records,
+            unlisted,
+            verified_at: Utc::now(),
+        })
+    }
+
+    fn pinned_path(&self, repo_id: &str) -> PathBuf {
+        self.pinned_dir.join(repo_id).join(MANIFEST_FILE)
+    }
+
+    /// The manifest to check against, where it came from and who signed it
+    fn manifest(
+        &self,
+        repo_id: &str,
+        model_dir: &Path,
+    ) -> Result<(Option<Manifest>, ManifestSource, Option<String>)> {
+        let pinned = self.pinned_path(repo_id);
+        if pinned.exists() {
+            let manifest = Manifest::parse(&read_text(&pinned)?)
+                .with_context(|| format!("Invalid pinned manifest {}", pinned.display()))?;
+            return Ok((Some(manifest), ManifestSource::Pinned, None));
+        }
+
+        let publisher = self.config.publisher_key(repo_id)?;
+        let path = model_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            if let Some((name, _)) = publisher {
+                bail!(
+                    "{} publishes no {}, but [model_integrity] expects it signed by the \"{}\" key",
+                    repo_id,
+                    MANIFEST_FILE,
+                    name
+                );
+            }
+            return Ok((None, ManifestSource::None, None));
+        }
+
+        let text = read_text(&path)?;
+        let signed_by = match publisher {
+            Some((name, key)) => {
+                let signature =
+                    std::fs::read(model_dir.join(SIGNATURE_FILE)).with_context(|| {
+                        format!(
+                        "{} has no {}, but [model_integrity] expects it signed by the \"{}\" key",
+                        repo_id, SIGNATURE_FILE, name
+                    )
+                    })?;
+                crypto::verify_signature(&key, text.as_bytes(), &signature, SIGNATURE_FILE)
+                    .with_context(|| format!("Refusing to load {}", repo_id))?;
+                Some(name)
+            }
+            None => None,
+        };
+        let manifest = Manifest::parse(&text)
+            .with_context(|| format!("Invalid {} in {}", MANIFEST_FILE, repo_id))?;
+        Ok((Some(manifest), ManifestSource::Repository, signed_by))
+    }
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// `file`'s path under `model_dir`, with `/` separators
+fn relative_name(model_dir: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(model_dir).unwrap_or(file);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The commit hash of a HuggingFace cache snapshot (`.../snapshots/<sha>`)
+fn snapshot_revision(model_dir: &Path) -> Option<String> {
+    let parent = model_dir.parent()?.file_name()?;
+    (parent == "snapshots")
+        .then(|| model_dir.file_name())
+        .flatten()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Hash `file`, or reuse `previous` when its size and modification time
+/// are unchanged
+fn hash_file(file: &Path, previous: Option<&FileRecord>) -> Result<FileRecord> {
+    let metadata =
+        std::fs::metadata(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    if let Some(previous) = previous {
+        if previous.size == metadata.len() && previous.modified == modified {
+            return Ok(previous.clone());
+        }
+    }
+
+    let mut reader =
+        std::fs::File::open(file).with_context(|| format!("Failed to open {}", file.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let n = reader
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(FileRecord {
+        sha256: hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+        size: metadata.len(),
+        modified,
+    })
+}
+
+/// Move `file` to `dest`.  HuggingFace cache entries are symlinks into
+/// `blobs/`, so the blob itself moves and the dangling link is removed.
+fn quarantine(file: &Path, dest: &Path) -> Result<()> {
+    let target = std::fs::canonicalize(file)
+        .with_context(|| format!("Failed to resolve {}", file.display()))?;
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    if std::fs::rename(&target, dest).is_err() {
+        // Different filesystem: copy, then remove the original
+        std::fs::copy(&target, dest)
+            .with_context(|| format!("Failed to quarantine {}", file.display()))?;
+        std::fs::remove_file(&target)
+            .with_context(|| format!("Failed to remove {}", target.display()))?;
+    }
+    if file.symlink_metadata().is_ok() {
+        std::fs::remove_file(file)
+            .with_context(|| format!("Failed to remove {}", file.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const REPO: &str = "onnx-community/Tiny";
+
+    fn sha(data: &[u8]) -> String {
+        Sha256::digest(data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// A snapshot with a tokenizer and weights, plus the verifier's dirs
+    fn snapshot(root: &Path) -> (PathBuf, Vec<PathBuf>) {
+        let dir = root.join("snapshots").join("abc123");
+        std::fs::create_dir_all(dir.join("onnx")).unwrap();
+        std::fs::write(dir.join("tokenizer.json"), b"{}").unwrap();
+        std::fs::write(dir.join("onnx/model.onnx"), b"weights").unwrap();
+        let files = vec![dir.join("tokenizer.json"), dir.join("onnx/model.onnx")];
+        (dir, files)
+    }
+
+    fn verifier(root: &Path, config: IntegrityConfig) -> ModelVerifier {
+        ModelVerifier::new(config, root.join("manifests"), root.join("quarantine"))
+    }
+
+    fn manifest_text() -> String {
+        format!(
+            "{}  tokenizer.json\n{} *onnx/model.onnx\n",
+            sha(b"{}"),
+            sha(b"weights")
+        )
+    }
+
+    #[test]
+    fn test_manifest_parse() {
+        let manifest = Manifest::parse(&format!("# sums\n\n{}", manifest_text())).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files["onnx/model.onnx"], sha(b"weights"));
+
+        assert!(Manifest::parse("deadbeef  model.onnx").is_err());
+        assert!(Manifest::parse("# nothing\n").is_err());
+    }
+
+    #[test]
+    fn test_matching_files_are_verified() {
+        let root = tempfile::tempdir().unwrap();
+        let (dir, files) = snapshot(root.path());
+        std::fs::write(dir.join(MANIFEST_FILE), manifest_text()).unwrap();
+
+        let provenance = verifier(root.path(), IntegrityConfig::default())
+            .verify(REPO, &dir, &files, None)
+            .unwrap();
+        assert!(provenance.verified());
+        assert_eq!(provenance.manifest, ManifestSource::Repository);
+        assert_eq!(provenance.revision.as_deref(), Some("abc123"));
+        assert_eq!(provenance.files["tokenizer.json"].sha256, sha(b"{}"));
+
+        // No manifest: hashed and recorded, refused only when required
+        std::fs::remove_file(dir.join(MANIFEST_FILE)).unwrap();
+        let provenance = verifier(root.path(), IntegrityConfig::default())
+            .verify(REPO, &dir, &files, None)
+            .unwrap();
+        assert!(!provenance.verified());
+        assert_eq!(provenance.unlisted.len(), 2);
+        let strict = IntegrityConfig {
+            require_manifest: true,
+            ..Default::default()
+        };
+        assert!(verifier(root.path(), strict)
+            .verify(REPO, &dir, &files, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_mismatched_file_is_quarantined() {
+        let root = tempfile::tempdir().unwrap();
+        let (dir, files) = snapshot(root.path());
+        std::fs::write(dir.join(MANIFEST_FILE), manifest_text()).unwrap();
+        std::fs::write(dir.join("onnx/model.onnx"), b"tampered").unwrap();
+
+        let err = verifier(root.path(), IntegrityConfig::default())
+            .verify(REPO, &dir, &files, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("onnx/model.onnx"));
+        assert!(!dir.join("onnx/model.onnx").exists());
+        assert!(dir.join("tokenizer.json").exists());
+
+        let quarantined: Vec<_> =
+            std::fs::read_dir(root.path().join("quarantine").join("onnx-community__Tiny"))
+                .unwrap()
+                .collect();
+        assert_eq!(quarantined.len(), 1);
+        let moved = quarantined[0]
+            .as_ref()
+            .unwrap()
+            .path()
+            .join("onnx/model.onnx");
+        assert_eq!(std::fs::read(moved).unwrap(), b"tampered");
+    }
+
+    #[test]
+    fn test_publisher_key_requires_signed_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let (dir, files) = snapshot(root.path());
+        let text = manifest_text();
+        std::fs::write(dir.join(MANIFEST_FILE), &text).unwrap();
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let config = IntegrityConfig {
+            publisher_keys: BTreeMap::from([(
+                "onnx-community".to_string(),
+                STANDARD.encode(signing_key.verifying_key().as_bytes()),
+            )]),
+            ..Default::default()
+        };
+        config.validate().unwrap();
+
+        // Unsigned
+        assert!(verifier(root.path(), config.clone())
+            .verify(REPO, &dir, &files, None)
+            .is_err());
+
+        // Signed by someone else
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        std::fs::write(
+            dir.join(SIGNATURE_FILE),
+            STANDARD.encode(other.sign(text.as_bytes()).to_bytes()),
+        )
+        .unwrap();
+        assert!(verifier(root.path(), config.clone())
+            .verify(REPO, &dir, &files, None)
+            .is_err());
+
+        std::fs::write(
+            dir.join(SIGNATURE_FILE),
+            STANDARD.encode(signing_key.sign(text.as_bytes()).to_bytes()),
+        )
+        .unwrap();
+        let provenance = verifier(root.path(), config)
+            .verify(REPO, &dir, &files, None)
+            .unwrap();
+        assert_eq!(provenance.signed_by.as_deref(), Some("onnx-community"));
+        assert!(provenance.summary().contains("signed by onnx-community"));
+    }
+
+    #[test]
+    fn test_provenance_store_latest() {
+        let root = tempfile::tempdir().unwrap();
+        let (dir, files) = snapshot(root.path());
+        let store = ProvenanceStore::new(root.path().join("provenance.json"));
+        assert!(store.latest().is_none());
+
+        let provenance = verifier(root.path(), IntegrityConfig::default())
+            .verify(REPO, &dir, &files, None)
+            .unwrap();
+        store.record(&provenance).unwrap();
+        assert_eq!(store.get(REPO), Some(provenance.clone()));
+        assert_eq!(store.latest().unwrap().repo, REPO);
+    }
+}
@@ -0,0 +1,173 @@
+// Fine-grained model load progress
+//
+// `GeneratorState` only says which stage a load is in.  `LoadProgress` adds
+// the detail `/health` reports while it runs: files downloaded and remaining,
+// whether the tokenizer is loaded and whether the warm-up generation is done.
+//
+// The download and loaders run synchronously on one blocking thread, several
+// layers below `BootstrapLoader`.  Rather than thread a handle through every
+// loader, the bootstrap installs the progress for that thread with `scope()`
+// and the loaders report into it with `report()`.  Reports made outside a
+// scope are dropped.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Progress of one model load, shared between the loading thread and readers
+#[derive(Debug, Default)]
+pub struct LoadProgress {
+    files_total: AtomicUsize,
+    files_done: AtomicUsize,
+    current_file: Mutex<Option<String>>,
+    tokenizer_loaded: AtomicBool,
+    warmup_complete: AtomicBool,
+}
+
+/// Point-in-time copy of a `LoadProgress`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadProgressSnapshot {
+    /// Model files fetched (or found in the cache) so far
+    pub files_done: usize,
+    /// Model files still to fetch
+    pub files_remaining: usize,
+    /// `files_done` as a percentage of the files known so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_percent: Option<f32>,
+    /// File being fetched right now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_file: Option<String>,
+    pub tokenizer_loaded: bool,
+    pub warmup_complete: bool,
+}
+
+impl LoadProgress {
+    /// Forget a previous load's progress before starting another
+    pub fn reset(&self) {
+        self.files_total.store(0, Ordering::Relaxed);
+        self.files_done.store(0, Ordering::Relaxed);
+        *self.current_file.lock().unwrap() = None;
+        self.tokenizer_loaded.store(false, Ordering::Relaxed);
+        self.warmup_complete.store(false, Ordering::Relaxed);
+    }
+
+    /// `count` more files are going to be fetched
+    pub fn expect_files(&self, count: usize) {
+        self.files_total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Fetching `file` has started
+    pub fn start_file(&self, file: &str) {
+        *self.current_file.lock().unwrap() = Some(file.to_string());
+    }
+
+    /// The current file is on disk
+    pub fn file_done(&self) {
+        self.files_done.fetch_add(1, Ordering::Relaxed);
+        *self.current_file.lock().unwrap() = None;
+    }
+
+    /// An expected file turned out not to exist (optional files)
+    pub fn file_skipped(&self) {
+        let _ = self
+            .files_total
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        *self.current_file.lock().unwrap() = None;
+    }
+
+    pub fn set_tokenizer_loaded(&self) {
+        self.tokenizer_loaded.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set_warmup_complete(&self) {
+        self.warmup_complete.store(true, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> LoadProgressSnapshot {
+        let total = self.files_total.load(Ordering::Relaxed);
+        let done = self.files_done.load(Ordering::Relaxed).min(total);
+        LoadProgressSnapshot {
+            files_done: done,
+            files_remaining: total - done,
+            download_percent: (total > 0).then(|| done as f32 * 100.0 / total as f32),
+            current_file: self.current_file.lock().unwrap().clone(),
+            tokenizer_loaded: self.tokenizer_loaded.load(Ordering::Relaxed),
+            warmup_complete: self.warmup_complete.load(Ordering::Relaxed),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<LoadProgress>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `progress` receiving this thread's `report()` calls
+pub fn scope<T>(progress: Arc<LoadProgress>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<LoadProgress>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|c| c.borrow_mut().replace(progress)));
+    f()
+}
+
+/// Update the load running on this thread, if any
+pub fn report(update: impl FnOnce(&LoadProgress)) {
+    CURRENT.with(|c| {
+        if let Some(progress) = c.borrow().as_ref() {
+            update(progress);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_counts_files() {
+        let progress = LoadProgress::default();
+        assert_eq!(progress.snapshot().download_percent, None);
+
+        progress.expect_files(4);
+        progress.start_file("config.json");
+        progress.file_done();
+        progress.start_file("meta.yaml");
+        progress.file_skipped();
+        progress.start_file("onnx/model.onnx");
+
+        let snap = progress.snapshot();
+        assert_eq!(snap.files_done, 1);
+        assert_eq!(snap.files_remaining, 2);
+        assert_eq!(snap.download_percent, Some(100.0 / 3.0));
+        assert_eq!(snap.current_file.as_deref(), Some("onnx/model.onnx"));
+
+        progress.reset();
+        assert_eq!(progress.snapshot(), LoadProgressSnapshot::default());
+    }
+
+    #[test]
+    fn test_reports_only_reach_the_scoped_progress() {
+        let progress = Arc::new(LoadProgress::default());
+        report(|p| p.set_tokenizer_loaded()); // no scope: dropped
+
+        scope(Arc::clone(&progress), || {
+            report(|p| p.expect_files(1));
+            report(|p| p.file_done());
+            // Other threads have their own (empty) scope
+            std::thread::spawn(|| report(|p| p.set_warmup_complete()))
+                .join()
+                .unwrap();
+        });
+        report(|p| p.set_tokenizer_loaded()); // scope ended
+
+        let snap = progress.snapshot();
+        assert_eq!(snap.files_done, 1);
+        assert!(!snap.tokenizer_loaded);
+        assert!(!snap.warmup_complete);
+    }
+}
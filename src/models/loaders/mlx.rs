@@ -0,0 +1,349 @@
+// MLX Model Loader - Apple GPU inference through mlx-lm
+//
+// MLX runs transformers on the Apple Silicon GPU out of unified memory, and
+// generates several times faster than ONNX Runtime's CoreML provider, which
+// leaves most decoder ops on the CPU.  There is no maintained Rust binding, so
+// the model runs in a Python worker (mlx_worker.py, embedded in the binary)
+// that finch drives over JSON lines on its stdin and stdout.
+//
+// `execution_target = "metal"` selects it for the families mlx-community
+// publishes 4-bit exports of (see `repository`).  Those repositories were not
+// part of the compatibility matrix audit.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use super::super::adapters::ModelFamily as AdapterFamily;
+use super::super::generator_new::{TextGeneration, TokenCallback};
+use super::super::unified_loader::{ModelFamily, ModelSize};
+
+/// The worker, passed to `python3 -c`
+const WORKER_SCRIPT: &str = include_str!("mlx_worker.py");
+
+/// The mlx-community model for a family and size, if MLX can run it
+pub fn repository(family: ModelFamily, size: ModelSize) -> Option<&'static str> {
+    match (family, size) {
+        (ModelFamily::Qwen2, ModelSize::Small) => Some("mlx-community/Qwen2.5-1.5B-Instruct-4bit"),
+        (ModelFamily::Qwen2, ModelSize::Medium) => Some("mlx-community/Qwen2.5-3B-Instruct-4bit"),
+        (ModelFamily::Qwen2, ModelSize::Large) => Some("mlx-community/Qwen2.5-7B-Instruct-4bit"),
+        (ModelFamily::Qwen2, ModelSize::XLarge) => Some("mlx-community/Qwen2.5-14B-Instruct-4bit"),
+        (ModelFamily::Llama3, ModelSize::Small) => Some("mlx-community/Llama-3.2-1B-Instruct-4bit"),
+        (ModelFamily::Llama3, ModelSize::Medium) => {
+            Some("mlx-community/Llama-3.2-3B-Instruct-4bit")
+        }
+        (ModelFamily::Llama3, ModelSize::Large) => {
+            Some("mlx-community/Meta-Llama-3.1-8B-Instruct-4bit")
+        }
+        (ModelFamily::Gemma2, ModelSize::Small | ModelSize::Medium) => {
+            Some("mlx-community/gemma-2-2b-it-4bit")
+        }
+        (ModelFamily::Gemma2, ModelSize::Large) => Some("mlx-community/gemma-2-9b-it-4bit"),
+        (ModelFamily::Gemma2, ModelSize::XLarge) => Some("mlx-community/gemma-2-27b-it-4bit"),
+        (ModelFamily::Mistral, ModelSize::Small | ModelSize::Medium) => {
+            Some("mlx-community/Mistral-7B-Instruct-v0.3-4bit")
+        }
+        (ModelFamily::Phi, ModelSize::Small | ModelSize::Medium) => {
+            Some("mlx-community/Phi-3.5-mini-instruct-4bit")
+        }
+        _ => None,
+    }
+}
+
+/// One reply line from the worker
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Reply {
+    ready: bool,
+    tokens: Option<Vec<u32>>,
+    text: Option<String>,
+    token: Option<u32>,
+    done: bool,
+    error: Option<String>,
+}
+
+/// The worker process and its pipes
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Worker {
+    /// Start `command` and wait until it has loaded the model
+    fn spawn(mut command: Command) -> Result<Self> {
+        // stderr carries mlx-lm's download progress bars; failures come back
+        // as error replies
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start python3 for MLX")?;
+        let stdin = child.stdin.take().context("MLX worker has no stdin")?;
+        let stdout = child.stdout.take().context("MLX worker has no stdout")?;
+        let mut worker = Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        };
+
+        if !worker.receive()?.ready {
+            bail!("MLX worker did not report ready");
+        }
+        Ok(worker)
+    }
+
+    fn send(&mut self, request: &serde_json::Value) -> Result<()> {
+        writeln!(self.stdin, "{}", request)
+            .and_then(|_| self.stdin.flush())
+            .context("MLX worker stopped accepting requests")
+    }
+
+    fn receive(&mut self) -> Result<Reply> {
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            bail!("MLX worker exited");
+        }
+        let reply: Reply = serde_json::from_str(&line)
+            .with_context(|| format!("Unexpected MLX worker output: {}", line.trim()))?;
+        match reply.error {
+            Some(error) => bail!("MLX: {}", error),
+            None => Ok(reply),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// MLX model loader
+pub struct MlxLoader;
+
+impl Default for MlxLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MlxLoader {
+    /// Create new MLX loader
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Load `repo` in a new worker.  mlx-lm downloads it into the
+    /// HuggingFace cache on first use.
+    pub fn load(&self, repo: &str) -> Result<Box<dyn TextGeneration>> {
+        tracing::info!("Loading {} with MLX", repo);
+
+        let mut command = Command::new("python3");
+        command.arg("-c").arg(WORKER_SCRIPT).arg(repo);
+        let worker =
+            Worker::spawn(command).with_context(|| format!("Failed to load {} with MLX", repo))?;
+        crate::models::load_progress::report(|p| p.set_tokenizer_loaded());
+
+        Ok(Box::new(LoadedMlxModel {
+            worker: Mutex::new(worker),
+            repo: repo.to_string(),
+        }))
+    }
+}
+
+/// Loaded MLX model implementing the TextGeneration trait
+pub struct LoadedMlxModel {
+    /// Tokenizing takes `&self` but writes to the worker
+    worker: Mutex<Worker>,
+    repo: String,
+}
+
+impl LoadedMlxModel {
+    /// Get model name (the repository without its owner)
+    pub fn model_name(&self) -> &str {
+        self.repo.rsplit('/').next().unwrap_or(&self.repo)
+    }
+
+    /// Send one request and wait for its reply
+    fn request(&self, request: serde_json::Value) -> Result<Reply> {
+        let mut worker = self
+            .worker
+            .lock()
+            .map_err(|_| anyhow!("MLX worker lock poisoned"))?;
+        worker.send(&request)?;
+        worker.receive()
+    }
+
+    /// Generate with an optional per-token callback, sampling as the ONNX
+    /// loader does: temperature 0.7, top-p 0.9, repetition penalty 1.15
+    fn generate_with_callback(
+        &mut self,
+        input_ids: &[u32],
+        max_new_tokens: usize,
+        mut token_callback: Option<TokenCallback>,
+    ) -> Result<Vec<u32>> {
+        let mut output_ids = input_ids.to_vec();
+        if input_ids.is_empty() || max_new_tokens == 0 {
+            return Ok(output_ids);
+        }
+
+        let worker = self
+            .worker
+            .get_mut()
+            .map_err(|_| anyhow!("MLX worker lock poisoned"))?;
+        worker.send(&serde_json::json!({
+            "op": "generate",
+            "tokens": input_ids,
+            "max_tokens": max_new_tokens,
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "repetition_penalty": 1.15,
+        }))?;
+
+        // One reply per token, EOS excluded, then `done`
+        loop {
+            let reply = worker.receive()?;
+            if reply.done {
+                break;
+            }
+            let Some(token) = reply.token else { continue };
+            output_ids.push(token);
+
+            if let Some(ref mut callback) = token_callback {
+                // Empty while a character spans several tokens
+                match reply.text.as_deref() {
+                    Some(text) if !text.is_empty() => callback(token, text),
+                    _ => {}
+                }
+            }
+        }
+
+        tracing::info!(
+            "Generated {} new tokens",
+            output_ids.len() - input_ids.len()
+        );
+        Ok(output_ids)
+    }
+}
+
+impl TextGeneration for LoadedMlxModel {
+    fn generate(&mut self, input_ids: &[u32], max_new_tokens: usize) -> Result<Vec<u32>> {
+        self.generate_with_callback(input_ids, max_new_tokens, None)
+    }
+
+    fn generate_stream(
+        &mut self,
+        input_ids: &[u32],
+        max_new_tokens: usize,
+        token_callback: TokenCallback,
+    ) -> Result<Vec<u32>> {
+        self.generate_with_callback(input_ids, max_new_tokens, Some(token_callback))
+    }
+
+    fn tokenize(&self, text: &str) -> Result<Vec<u32>> {
+        self.request(serde_json::json!({ "op": "tokenize", "text": text }))?
+            .tokens
+            .context("MLX worker sent no tokens")
+    }
+
+    fn decode_tokens(&self, tokens: &[u32]) -> Result<String> {
+        self.request(serde_json::json!({ "op": "decode", "tokens": tokens }))?
+            .text
+            .context("MLX worker sent no text")
+    }
+
+    fn name(&self) -> &str {
+        self.model_name()
+    }
+
+    fn model_family(&self) -> Option<AdapterFamily> {
+        AdapterFamily::from_name(&self.repo)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers like mlx_worker.py, with a model that counts up from the
+    /// prompt's last token and stops at 5
+    const FAKE_WORKER: &str = r#"
+import json, sys
+print(json.dumps({"ready": True}), flush=True)
+for line in sys.stdin:
+    request = json.loads(line)
+    if request["op"] == "tokenize":
+        print(json.dumps({"tokens": [len(w) for w in request["text"].split()]}), flush=True)
+    elif request["op"] == "generate":
+        token = request["tokens"][-1]
+        for _ in range(request["max_tokens"]):
+            token += 1
+            if token == 5:
+                break
+            print(json.dumps({"token": token, "text": str(token)}), flush=True)
+        print(json.dumps({"done": True}), flush=True)
+    else:
+        print(json.dumps({"error": "no decoder"}), flush=True)
+"#;
+
+    fn fake_model() -> Option<LoadedMlxModel> {
+        let mut command = Command::new("python3");
+        command.arg("-c").arg(FAKE_WORKER);
+        // Skip where there is no python3
+        let worker = Worker::spawn(command).ok()?;
+        Some(LoadedMlxModel {
+            worker: Mutex::new(worker),
+            repo: "mlx-community/Qwen2.5-1.5B-Instruct-4bit".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_mlx_worker_protocol() {
+        let Some(mut model) = fake_model() else {
+            return;
+        };
+        assert_eq!(model.tokenize("a bb ccc").unwrap(), [1, 2, 3]);
+
+        let streamed = std::sync::Arc::new(Mutex::new(String::new()));
+        let sink = streamed.clone();
+        let output = model
+            .generate_stream(
+                &[1, 2],
+                10,
+                Box::new(move |_, text| sink.lock().unwrap().push_str(text)),
+            )
+            .unwrap();
+        assert_eq!(output, [1, 2, 3, 4]);
+        assert_eq!(*streamed.lock().unwrap(), "34");
+
+        // Worker errors surface, and the worker keeps serving
+        let error = model.decode_tokens(&[3]).unwrap_err().to_string();
+        assert!(error.contains("no decoder"), "got: {}", error);
+        assert_eq!(model.generate(&[0], 2).unwrap(), [0, 1, 2]);
+        assert_eq!(model.name(), "Qwen2.5-1.5B-Instruct-4bit");
+        assert_eq!(model.model_family(), Some(AdapterFamily::Qwen));
+    }
+
+    #[test]
+    fn test_mlx_repository_covers_the_exported_families() {
+        assert_eq!(
+            repository(ModelFamily::Qwen2, ModelSize::Medium),
+            Some("mlx-community/Qwen2.5-3B-Instruct-4bit")
+        );
+        // DeepSeek Coder has no MLX export; Metal runs it on ONNX Runtime
+        assert_eq!(repository(ModelFamily::DeepSeek, ModelSize::Small), None);
+    }
+}
@@ -0,0 +1,72 @@
+"""
+MLX generation worker for finch (see mlx.rs)
+
+Loads one mlx-lm model and answers JSON-line requests on stdin, one JSON
+reply per line on stdout:
+
+    {"op": "tokenize", "text": "..."}            -> {"tokens": [...]}
+    {"op": "decode", "tokens": [...]}            -> {"text": "..."}
+    {"op": "generate", "tokens": [...], ...}     -> {"token": 9707, "text": "Hi"} ...
+                                                    then {"done": true}
+
+Any failure is reported as {"error": "..."}.  Usage:
+
+    python3 -c <this file> <repo>
+"""
+
+import json
+import sys
+
+
+def reply(message):
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+
+def main():
+    try:
+        from mlx_lm import load, stream_generate
+        from mlx_lm.sample_utils import make_logits_processors, make_sampler
+    except ImportError as e:
+        reply({"error": f"mlx-lm is not installed ({e}); run: pip install mlx-lm"})
+        return
+
+    try:
+        model, tokenizer = load(sys.argv[1])
+    except Exception as e:
+        reply({"error": f"Failed to load {sys.argv[1]}: {e}"})
+        return
+    reply({"ready": True})
+
+    for line in sys.stdin:
+        try:
+            request = json.loads(line)
+            op = request["op"]
+            if op == "tokenize":
+                reply({"tokens": tokenizer.encode(request["text"])})
+            elif op == "decode":
+                reply({"text": tokenizer.decode(request["tokens"], skip_special_tokens=True)})
+            elif op == "generate":
+                sampler = make_sampler(temp=request["temperature"], top_p=request["top_p"])
+                processors = make_logits_processors(
+                    repetition_penalty=request["repetition_penalty"]
+                )
+                # Stops at the tokenizer's EOS tokens without yielding them
+                for response in stream_generate(
+                    model,
+                    tokenizer,
+                    prompt=request["tokens"],
+                    max_tokens=request["max_tokens"],
+                    sampler=sampler,
+                    logits_processors=processors,
+                ):
+                    reply({"token": response.token, "text": response.text})
+                reply({"done": True})
+            else:
+                reply({"error": f"Unknown op {op!r}"})
+        except Exception as e:
+            reply({"error": str(e)})
+
+
+if __name__ == "__main__":
+    main()
@@ -1,6 +1,13 @@
-// Model loaders: ONNX Runtime (default) and Candle (optional)
+// Model loaders: ONNX Runtime (default), Candle and llama.cpp GGUF (optional),
+// and MLX on Apple Silicon (through a Python worker)
+#[cfg(feature = "onnx")]
 pub mod onnx;
 pub mod onnx_config;
 
 #[cfg(feature = "candle")]
 pub mod candle;
+
+#[cfg(feature = "gguf")]
+pub mod gguf;
+
+pub mod mlx;
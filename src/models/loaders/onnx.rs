@@ -17,9 +17,13 @@ use std::sync::mpsc;
 use tokenizers::Tokenizer;
 use tracing::{debug, info, warn};
 
-use super::onnx_config::{ExecutionProvider as ConfigExecutionProvider, ModelSize, OnnxLoadConfig};
+use super::onnx_config::{
+    ExecutionProvider as ConfigExecutionProvider, KvCacheShape, ModelSize, OnnxLoadConfig,
+};
+use crate::models::adapters::ModelFamily;
 use crate::models::download::{DownloadProgress, ModelDownloader};
 use crate::models::generator_new::TextGeneration;
+use crate::models::tokenizer::TokenizerProfile;
 
 /// ONNX model loader - downloads and loads models from HuggingFace
 #[allow(dead_code)]
@@ -163,8 +167,15 @@ impl OnnxLoader {
             );
         };
 
-        // Step 3: Load tokenizer
+        // Step 3: Load tokenizer and work out the model's chat format and
+        // cache shape from the repo's own config files
         let tokenizer = self.load_tokenizer(&model_dir)?;
+        let profile = TokenizerProfile::detect(&model_dir, |t| tokenizer.token_to_id(t));
+        info!(
+            "Tokenizer profile: family={:?}, eos={:?}",
+            profile.family, profile.eos_token_ids
+        );
+        let kv_shape = Self::load_kv_cache_shape(&model_dir);
 
         // Step 4: Create ONNX Runtime session
         let session = self.create_session(&model_path, config)?;
@@ -174,6 +185,8 @@ impl OnnxLoader {
         Ok(LoadedOnnxModel {
             session,
             tokenizer,
+            profile,
+            kv_shape,
             model_name: config.model_name.clone(),
             model_size: config.size,
             model_path,
@@ -219,14 +232,36 @@ impl OnnxLoader {
         })?;
 
         debug!("Tokenizer loaded successfully");
+        crate::models::load_progress::report(|p| p.set_tokenizer_loaded());
         Ok(tokenizer)
     }
+
+    /// Read layer / head dimensions from `config.json`, falling back to
+    /// Qwen2.5-1.5B's when the file is missing or doesn't describe them.
+    fn load_kv_cache_shape(model_dir: &Path) -> KvCacheShape {
+        let shape = std::fs::read_to_string(model_dir.join("config.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .and_then(|config| KvCacheShape::from_config_json(&config));
+        match shape {
+            Some(shape) => {
+                debug!("KV cache shape from config.json: {:?}", shape);
+                shape
+            }
+            None => {
+                warn!("config.json has no usable architecture fields, assuming Qwen2.5-1.5B");
+                KvCacheShape::QWEN2_5_1_5B
+            }
+        }
+    }
 }
 
 /// Loaded ONNX model with tokenizer
 pub struct LoadedOnnxModel {
     session: Session,
     tokenizer: Tokenizer,
+    profile: TokenizerProfile,
+    kv_shape: KvCacheShape,
     model_name: String,
     model_size: ModelSize,
     model_path: PathBuf,
@@ -307,12 +342,14 @@ impl LoadedOnnxModel {
         );
 
         let mut output_ids = input_ids.to_vec();
-        let eos_token_id = self.get_eos_token_id();
+        let eos_token_ids = self.eos_token_ids();
 
         // Model architecture (from config.json)
-        const NUM_LAYERS: usize = 28;
-        const NUM_KV_HEADS: usize = 2;
-        const HEAD_DIM: usize = 128; // hidden_size / num_attention_heads = 1536 / 12
+        let KvCacheShape {
+            num_layers,
+            num_kv_heads,
+            head_dim,
+        } = self.kv_shape;
 
         // Initialize empty KV cache for first step
         let mut past_key_values: Vec<(DynValue, DynValue)> = Vec::new();
@@ -320,6 +357,10 @@ impl LoadedOnnxModel {
 
         // Generation loop
         for step in 0..max_new_tokens {
+            if crate::models::generation_stop::requested() {
+                debug!("Generation stopped after {} steps", step);
+                break;
+            }
             debug!("Generation step {}/{}", step + 1, max_new_tokens);
 
             // 1. Prepare input tensor - only the new token(s) after first step
@@ -334,9 +375,9 @@ impl LoadedOnnxModel {
                 input_for_step,
                 &past_key_values,
                 past_seq_len,
-                NUM_LAYERS,
-                NUM_KV_HEADS,
-                HEAD_DIM,
+                num_layers,
+                num_kv_heads,
+                head_dim,
             )?;
 
             // Update sequence length for next iteration
@@ -357,7 +398,7 @@ impl LoadedOnnxModel {
             debug!("Generated token: {}", next_token);
 
             // 4. Check for EOS
-            if next_token == eos_token_id {
+            if eos_token_ids.contains(&next_token) {
                 info!("EOS token generated, stopping");
                 break;
             }
@@ -685,19 +726,27 @@ impl LoadedOnnxModel {
         }
     }
 
-    /// Get EOS token ID from tokenizer
-    fn get_eos_token_id(&self) -> u32 {
-        // Try to get from tokenizer's special tokens
-        // For Qwen models, EOS is typically 151643
-        // Fallback to common value if not available
-        let vocab = self.tokenizer.get_vocab(true);
+    /// Chat-template family detected from the repo's tokenizer files
+    pub fn tokenizer_profile(&self) -> &TokenizerProfile {
+        &self.profile
+    }
+
+    /// Token IDs that end generation
+    fn eos_token_ids(&self) -> Vec<u32> {
+        if !self.profile.eos_token_ids.is_empty() {
+            return self.profile.eos_token_ids.clone();
+        }
 
-        vocab
+        // No config declared one: try common end-of-text tokens, falling
+        // back to Qwen's EOS (151643)
+        let vocab = self.tokenizer.get_vocab(true);
+        let eos = vocab
             .get("<|endoftext|>")
             .or_else(|| vocab.get("<|im_end|>"))
             .or_else(|| vocab.get("</s>"))
             .copied()
-            .unwrap_or(151643)
+            .unwrap_or(151643);
+        vec![eos]
     }
 }
 
@@ -734,6 +783,10 @@ impl TextGeneration for LoadedOnnxModel {
         &self.model_name
     }
 
+    fn model_family(&self) -> Option<ModelFamily> {
+        self.profile.family
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -103,6 +103,50 @@ impl OnnxLoadConfig {
     }
 }
 
+/// KV-cache dimensions of a decoder model, read from its `config.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KvCacheShape {
+    pub num_layers: usize,
+    pub num_kv_heads: usize,
+    pub head_dim: usize,
+}
+
+impl KvCacheShape {
+    /// Qwen2.5-1.5B, used when `config.json` is missing or incomplete.
+    pub const QWEN2_5_1_5B: Self = Self {
+        num_layers: 28,
+        num_kv_heads: 2,
+        head_dim: 128, // hidden_size / num_attention_heads = 1536 / 12
+    };
+
+    /// Read the cache shape from a HuggingFace `config.json`.
+    ///
+    /// Models without grouped-query attention omit `num_key_value_heads`
+    /// (it equals `num_attention_heads`); most omit `head_dim`.
+    pub fn from_config_json(config: &serde_json::Value) -> Option<Self> {
+        let field = |name: &str| {
+            config
+                .get(name)
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+        };
+
+        let num_layers = field("num_hidden_layers")?;
+        let num_heads = field("num_attention_heads")?;
+        let num_kv_heads = field("num_key_value_heads").unwrap_or(num_heads);
+        let head_dim = match field("head_dim") {
+            Some(dim) => dim,
+            None => field("hidden_size")? / num_heads,
+        };
+
+        Some(Self {
+            num_layers,
+            num_kv_heads,
+            head_dim,
+        })
+    }
+}
+
 /// Execution provider options for ONNX Runtime
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExecutionProvider {
@@ -173,6 +217,38 @@ mod tests {
         assert_eq!(ModelSize::from_ram(64), ModelSize::XLarge); // 64GB machine
     }
 
+    #[test]
+    fn test_kv_cache_shape_from_config_json() {
+        // Llama-3.2-1B: grouped-query attention, head_dim derived
+        let llama = serde_json::json!({
+            "num_hidden_layers": 16,
+            "num_attention_heads": 32,
+            "num_key_value_heads": 8,
+            "hidden_size": 2048
+        });
+        assert_eq!(
+            KvCacheShape::from_config_json(&llama),
+            Some(KvCacheShape {
+                num_layers: 16,
+                num_kv_heads: 8,
+                head_dim: 64
+            })
+        );
+
+        // Phi-2: no GQA, so kv heads = attention heads
+        let phi = serde_json::json!({
+            "num_hidden_layers": 32,
+            "num_attention_heads": 32,
+            "hidden_size": 2560
+        });
+        assert_eq!(
+            KvCacheShape::from_config_json(&phi).unwrap().num_kv_heads,
+            32
+        );
+
+        assert_eq!(KvCacheShape::from_config_json(&serde_json::json!({})), None);
+    }
+
     #[test]
     fn test_huggingface_repo() {
         let config = OnnxLoadConfig::with_size(ModelSize::Medium, PathBuf::from("/tmp/cache"));
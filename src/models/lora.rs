@@ -476,8 +476,11 @@ impl TrainingCoordinator {
         let count = buffer.len();
 
         use std::io::Write;
+        let redactor = crate::redact::active();
         for example in buffer.examples() {
-            let json = serde_json::to_string(example).context("Failed to serialize example")?;
+            let json = redactor
+                .to_json_line(example)
+                .context("Failed to serialize example")?;
             writeln!(file, "{}", json).context("Failed to write example to queue")?;
         }
 
@@ -2,12 +2,17 @@
 // All models support online learning (update after each forward to Claude)
 
 pub mod adapters; // Local model adapters (chat templates, token IDs)
+pub mod benchmark; // Tokens/sec of each local inference path (finch node-info --bench)
 pub mod bootstrap; // Progressive bootstrap for instant startup
 pub mod common;
 pub mod compatibility; // Model compatibility matrix (which models work with which targets)
 pub mod download;
+pub mod generation_stop; // Ending a local generation early, between tokens
 pub mod generator_new; // New unified generator (ONNX-based)
+pub mod hub; // HuggingFace Hub downloads through the shared HTTP client
+pub mod integrity; // SHA256SUMS manifests, publisher signatures, quarantine
 pub mod learning;
+pub mod load_progress; // Download / tokenizer / warm-up progress for /health
 pub mod loaders; // ONNX model loader
 pub mod lora; // LoRA fine-tuning configuration (Python training, Phase 5)
 pub mod manager;
@@ -16,7 +21,7 @@ pub mod persistence;
 pub mod sampling; // Context-aware sampling system
 pub mod threshold_router;
 pub mod threshold_validator;
-pub mod tokenizer; // Phase 4: Stub for compatibility
+pub mod tokenizer; // Chat-format detection from tokenizer files (+ Phase 4 stub)
 pub mod tool_parser; // Phase 6: Parse tool calls from model output (XML)
 pub mod tool_prompt; // Phase 6: Format tool definitions for model prompts
 pub mod unified_loader; // Generic loader for ONNX models
@@ -24,6 +29,7 @@ pub mod unified_loader; // Generic loader for ONNX models
 pub use adapters::{
     AdapterRegistry, GenerationConfig as AdapterGenerationConfig, LocalModelAdapter,
 };
+pub use benchmark::{BenchmarkStore, GenerationBenchmark};
 pub use bootstrap::{BootstrapLoader, DownloadProgressSnapshot, GeneratorState};
 #[allow(deprecated)]
 pub use common::{
@@ -36,7 +42,9 @@ pub use compatibility::{
 };
 pub use download::{DownloadProgress, ModelDownloader};
 pub use generator_new::{GeneratorModel, TextGeneration, TokenCallback};
+pub use integrity::{IntegrityConfig, ModelProvenance, ModelVerifier, ProvenanceStore};
 pub use learning::{LearningModel, ModelExpectation, ModelPrediction, ModelStats, PredictionData};
+pub use load_progress::{LoadProgress, LoadProgressSnapshot};
 pub use lora::{
     ExampleBuffer, LoRAConfig, LoRATrainer, LoRATrainingAdapter, TrainingCoordinator,
     TrainingStats, WeightedExample,
@@ -50,7 +58,7 @@ pub use threshold_router::{
     QueryCategory as ThresholdQueryCategory, ThresholdRouter, ThresholdRouterStats,
 };
 pub use threshold_validator::{QualitySignal, ThresholdValidator, ValidatorStats};
-pub use tokenizer::TextTokenizer; // Phase 4: Stub for compatibility
+pub use tokenizer::{TextTokenizer, TokenizerProfile}; // TextTokenizer: Phase 4 stub
 pub use tool_parser::ToolCallParser; // Phase 6: Parse tool calls from model output
 pub use tool_prompt::ToolPromptFormatter; // Phase 6: Format tool definitions for prompts
 pub use unified_loader::{ModelFamily, ModelLoadConfig, ModelSize, UnifiedModelLoader};
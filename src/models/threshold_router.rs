@@ -190,6 +190,20 @@ impl ThresholdRouter {
         self.update_threshold();
     }
 
+    /// Learn from an offline replay of `query` through the local model
+    /// (`[self_eval]`).  Only the category's success rate moves: the query
+    /// wasn't routed, so the forward rate and the adaptive threshold stay.
+    pub fn calibrate(&mut self, query: &str, was_successful: bool) {
+        let category = Self::categorize_query(query);
+        let stats = self.category_stats.entry(category).or_default();
+        stats.local_attempts += 1;
+        if was_successful {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
+        }
+    }
+
     /// Deprecated: Use learn_local_attempt() or learn_forwarded() instead
     /// This method is kept for backward compatibility but logs a warning
     #[deprecated(
@@ -520,7 +534,7 @@ impl<'de> Deserialize<'de> for ThresholdRouter {
 }
 
 /// Statistics snapshot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ThresholdRouterStats {
     pub total_queries: usize,
     pub total_local_attempts: usize,
@@ -612,4 +626,21 @@ mod tests {
         // With 90% forward rate (way above 5% target), threshold should decrease
         assert!(router.confidence_threshold < initial_threshold);
     }
+
+    #[test]
+    fn test_calibration_reopens_a_forwarded_category() {
+        let mut router = ThresholdRouter::new();
+        router.learn_local_attempt("explain lifetimes", false);
+        router.learn_local_attempt("explain traits", false);
+        assert!(!router.should_try_local("explain closures"));
+
+        // Replays show the local model now handles explanations
+        for _ in 0..8 {
+            router.calibrate("explain borrowing", true);
+        }
+        assert!(router.should_try_local("explain closures"));
+        // Replays aren't routed queries
+        assert_eq!(router.total_queries, 2);
+        assert_eq!(router.total_local_attempts, 2);
+    }
 }
@@ -1,9 +1,194 @@
-// Tokenizer module stub (Phase 4: Candle-based implementation removed)
+// Tokenizer detection for local models
 //
-// The original tokenizer module used Candle's tensor operations.
-// For ONNX-based models, use the tokenizers crate directly with LoadedOnnxModel.
+// ONNX exports ship their own `tokenizer.json`, which LoadedOnnxModel loads
+// directly with the tokenizers crate.  What the tokenizer file doesn't say is
+// which chat template the model was trained on or which tokens end a turn, so
+// `TokenizerProfile::detect()` works that out from the repo's
+// `tokenizer_config.json`, `config.json` and `generation_config.json`.  That
+// lets `model_repo` point at any HuggingFace ONNX export (Llama, Mistral, Phi,
+// …) instead of assuming Qwen's template and token IDs.
+//
+// `TextTokenizer` is the Phase 4 stub kept for compatibility (the Candle-based
+// implementation was removed).
 
 use anyhow::Result;
+use serde_json::Value;
+use std::path::Path;
+
+use super::adapters::ModelFamily;
+
+/// What a model's tokenizer files say about its chat format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenizerProfile {
+    /// Chat-template family, if it could be recognised
+    pub family: Option<ModelFamily>,
+    /// Tokens that end generation (Llama 3 has several)
+    pub eos_token_ids: Vec<u32>,
+    pub bos_token_id: Option<u32>,
+}
+
+impl TokenizerProfile {
+    /// Inspect the config files next to `tokenizer.json` in `model_dir`.
+    ///
+    /// `token_to_id` resolves special-token strings against the loaded
+    /// tokenizer's vocabulary.  Missing or unreadable files are skipped.
+    pub fn detect(model_dir: &Path, token_to_id: impl Fn(&str) -> Option<u32>) -> Self {
+        let read = |name: &str| -> Option<Value> {
+            let text = std::fs::read_to_string(model_dir.join(name)).ok()?;
+            serde_json::from_str(&text)
+                .map_err(|e| tracing::warn!("Ignoring unparseable {}: {}", name, e))
+                .ok()
+        };
+        Self::from_files(
+            read("tokenizer_config.json").as_ref(),
+            read("config.json").as_ref(),
+            read("generation_config.json").as_ref(),
+            token_to_id,
+        )
+    }
+
+    /// Build a profile from already-parsed config files.
+    pub fn from_files(
+        tokenizer_config: Option<&Value>,
+        model_config: Option<&Value>,
+        generation_config: Option<&Value>,
+        token_to_id: impl Fn(&str) -> Option<u32>,
+    ) -> Self {
+        // The chat template is the most direct evidence of the prompt format;
+        // model_type can mislead (DeepSeek-Coder reports "llama").
+        let family = tokenizer_config
+            .and_then(|c| c.get("chat_template"))
+            .and_then(chat_template_text)
+            .and_then(family_from_chat_template)
+            .or_else(|| {
+                model_config
+                    .and_then(|c| c.get("model_type"))
+                    .and_then(Value::as_str)
+                    .and_then(family_from_model_type)
+            })
+            .or_else(|| family_from_vocab(&token_to_id));
+
+        // generation_config lists every stop token; tokenizer_config names the
+        // primary one; config.json is the last resort.
+        let mut eos_token_ids = generation_config
+            .and_then(|c| c.get("eos_token_id"))
+            .map(token_ids)
+            .unwrap_or_default();
+        if let Some(id) = tokenizer_config
+            .and_then(|c| c.get("eos_token"))
+            .and_then(special_token_text)
+            .and_then(&token_to_id)
+        {
+            if !eos_token_ids.contains(&id) {
+                eos_token_ids.push(id);
+            }
+        }
+        if eos_token_ids.is_empty() {
+            eos_token_ids = model_config
+                .and_then(|c| c.get("eos_token_id"))
+                .map(token_ids)
+                .unwrap_or_default();
+        }
+
+        let bos_token_id = tokenizer_config
+            .and_then(|c| c.get("bos_token"))
+            .and_then(special_token_text)
+            .and_then(&token_to_id)
+            .or_else(|| {
+                model_config
+                    .and_then(|c| c.get("bos_token_id"))
+                    .and_then(Value::as_u64)
+                    .map(|id| id as u32)
+            });
+
+        Self {
+            family,
+            eos_token_ids,
+            bos_token_id,
+        }
+    }
+}
+
+/// `chat_template` is either a string or a list of named templates.
+fn chat_template_text(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Array(templates) => templates
+            .iter()
+            .find(|t| t.get("name").and_then(Value::as_str) == Some("default"))
+            .or_else(|| templates.first())
+            .and_then(|t| t.get("template"))
+            .and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Special tokens are a plain string or an `AddedToken` object.
+fn special_token_text(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Object(_) => value.get("content").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Token ID fields hold a single ID or a list of them.
+fn token_ids(value: &Value) -> Vec<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|id| vec![id as u32]).unwrap_or_default(),
+        Value::Array(ids) => ids
+            .iter()
+            .filter_map(Value::as_u64)
+            .map(|id| id as u32)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn family_from_chat_template(template: &str) -> Option<ModelFamily> {
+    // DeepSeek before ChatML: R1-Distill-Qwen templates contain both
+    if template.contains("<｜Assistant｜>") || template.contains("### Instruction") {
+        Some(ModelFamily::DeepSeek)
+    } else if template.contains("<|im_start|>") {
+        Some(ModelFamily::Qwen)
+    } else if template.contains("<|start_header_id|>") {
+        Some(ModelFamily::Llama)
+    } else if template.contains("<|assistant|>") && template.contains("<|end|>") {
+        Some(ModelFamily::Phi)
+    } else if template.contains("<start_of_turn>") {
+        Some(ModelFamily::Gemma)
+    } else if template.contains("[INST]") {
+        Some(ModelFamily::Mistral)
+    } else {
+        None
+    }
+}
+
+fn family_from_model_type(model_type: &str) -> Option<ModelFamily> {
+    match model_type {
+        t if t.starts_with("qwen") => Some(ModelFamily::Qwen),
+        t if t.starts_with("deepseek") => Some(ModelFamily::DeepSeek),
+        t if t.starts_with("phi") => Some(ModelFamily::Phi),
+        t if t.starts_with("gemma") => Some(ModelFamily::Gemma),
+        "mistral" | "mixtral" => Some(ModelFamily::Mistral),
+        "llama" => Some(ModelFamily::Llama),
+        _ => None,
+    }
+}
+
+/// Fall back to the template markers present in the vocabulary.
+fn family_from_vocab(token_to_id: &impl Fn(&str) -> Option<u32>) -> Option<ModelFamily> {
+    [
+        ("<|im_start|>", ModelFamily::Qwen),
+        ("<|start_header_id|>", ModelFamily::Llama),
+        ("<|assistant|>", ModelFamily::Phi),
+        ("<start_of_turn>", ModelFamily::Gemma),
+        ("[INST]", ModelFamily::Mistral),
+    ]
+    .into_iter()
+    .find(|(token, _)| token_to_id(token).is_some())
+    .map(|(_, family)| family)
+}
 
 /// Text tokenizer (stub for compatibility)
 ///
@@ -43,3 +228,90 @@ impl TextTokenizer {
         anyhow::bail!("TextTokenizer removed in Phase 4 (Candle-based)")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn vocab(tokens: &[(&str, u32)]) -> impl Fn(&str) -> Option<u32> {
+        let map: HashMap<String, u32> = tokens.iter().map(|(t, id)| (t.to_string(), *id)).collect();
+        move |t| map.get(t).copied()
+    }
+
+    #[test]
+    fn test_llama3_uses_all_generation_stop_tokens() {
+        let tokenizer_config = json!({
+            "bos_token": "<|begin_of_text|>",
+            "eos_token": "<|eot_id|>",
+            "chat_template": "{{ '<|start_header_id|>' + message['role'] + '<|end_header_id|>' }}"
+        });
+        let generation_config = json!({ "eos_token_id": [128001, 128008, 128009] });
+        let profile = TokenizerProfile::from_files(
+            Some(&tokenizer_config),
+            Some(&json!({ "model_type": "llama" })),
+            Some(&generation_config),
+            vocab(&[("<|begin_of_text|>", 128000), ("<|eot_id|>", 128009)]),
+        );
+
+        assert_eq!(profile.family, Some(ModelFamily::Llama));
+        assert_eq!(profile.eos_token_ids, vec![128001, 128008, 128009]);
+        assert_eq!(profile.bos_token_id, Some(128000));
+    }
+
+    #[test]
+    fn test_chat_template_beats_model_type() {
+        // DeepSeek-R1-Distill-Qwen: model_type says qwen2, template says DeepSeek
+        let tokenizer_config = json!({
+            "eos_token": { "content": "<｜end▁of▁sentence｜>", "special": true },
+            "chat_template": "{{'<｜User｜>' + content + '<｜Assistant｜>'}}<|im_start|>"
+        });
+        let profile = TokenizerProfile::from_files(
+            Some(&tokenizer_config),
+            Some(&json!({ "model_type": "qwen2", "eos_token_id": 151643 })),
+            None,
+            vocab(&[("<｜end▁of▁sentence｜>", 151643)]),
+        );
+
+        assert_eq!(profile.family, Some(ModelFamily::DeepSeek));
+        assert_eq!(profile.eos_token_ids, vec![151643]);
+    }
+
+    #[test]
+    fn test_falls_back_to_model_type_then_vocab() {
+        let mistral = TokenizerProfile::from_files(
+            None,
+            Some(&json!({ "model_type": "mistral", "eos_token_id": 2, "bos_token_id": 1 })),
+            None,
+            vocab(&[]),
+        );
+        assert_eq!(mistral.family, Some(ModelFamily::Mistral));
+        assert_eq!(mistral.eos_token_ids, vec![2]);
+        assert_eq!(mistral.bos_token_id, Some(1));
+
+        let phi = TokenizerProfile::from_files(
+            None,
+            None,
+            None,
+            vocab(&[("<|assistant|>", 32001), ("<|end|>", 32007)]),
+        );
+        assert_eq!(phi.family, Some(ModelFamily::Phi));
+        assert!(phi.eos_token_ids.is_empty());
+    }
+
+    #[test]
+    fn test_detect_reads_repo_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("tokenizer_config.json"),
+            r#"{"eos_token": "<|end|>", "chat_template": "<|user|>{{m}}<|end|><|assistant|>"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("config.json"), "not json").unwrap();
+
+        let profile = TokenizerProfile::detect(dir.path(), vocab(&[("<|end|>", 32007)]));
+        assert_eq!(profile.family, Some(ModelFamily::Phi));
+        assert_eq!(profile.eos_token_ids, vec![32007]);
+    }
+}
@@ -7,7 +7,9 @@ use std::path::PathBuf;
 
 use super::download::ModelDownloader;
 use super::generator_new::TextGeneration;
+#[cfg(feature = "onnx")]
 use super::loaders::onnx::{LoadedOnnxModel, OnnxLoader};
+#[cfg(feature = "onnx")]
 use super::loaders::onnx_config::{ModelSize as OnnxModelSize, OnnxLoadConfig};
 use super::model_selector::QwenSize;
 use crate::config::ExecutionTarget;
@@ -23,6 +25,10 @@ pub enum InferenceProvider {
     #[cfg(feature = "candle")]
     #[serde(rename = "candle")]
     Candle,
+    /// llama.cpp over GGUF files (quantized, Metal/CUDA offload)
+    #[cfg(feature = "gguf")]
+    #[serde(rename = "gguf", alias = "llama.cpp")]
+    Gguf,
 }
 
 impl InferenceProvider {
@@ -32,6 +38,8 @@ impl InferenceProvider {
             Self::Onnx => "ONNX Runtime",
             #[cfg(feature = "candle")]
             Self::Candle => "Candle",
+            #[cfg(feature = "gguf")]
+            Self::Gguf => "llama.cpp (GGUF)",
         }
     }
 
@@ -41,6 +49,8 @@ impl InferenceProvider {
             Self::Onnx => "ONNX Runtime (Recommended) - Cross-platform, optimized",
             #[cfg(feature = "candle")]
             Self::Candle => "Candle - Native Rust implementation, good for development",
+            #[cfg(feature = "gguf")]
+            Self::Gguf => "llama.cpp (GGUF) - Quantized models, widest model availability",
         }
     }
 }
@@ -48,7 +58,7 @@ impl InferenceProvider {
 /// Configuration for loading any model on any execution target with any provider
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelLoadConfig {
-    /// Which inference provider to use (ONNX Runtime or Candle)
+    /// Which inference provider to use (ONNX Runtime, Candle or llama.cpp)
     #[serde(default)]
     pub provider: InferenceProvider,
     /// Which model architecture to use
@@ -286,7 +296,7 @@ impl UnifiedModelLoader {
         })
     }
 
-    /// Load model with configuration (supports both ONNX and Candle providers)
+    /// Load model with configuration (ONNX, Candle and GGUF providers)
     pub fn load(&self, config: ModelLoadConfig) -> Result<Box<dyn TextGeneration>> {
         tracing::info!(
             "Loading model: {:?} {:?} ({:?}) on {:?}",
@@ -296,7 +306,18 @@ impl UnifiedModelLoader {
             config.target
         );
 
+        // `metal` runs the families MLX has models for with MLX.  The rest,
+        // and machines without mlx-lm, use ONNX Runtime's CoreML provider.
+        #[cfg(target_os = "macos")]
+        if let Some(repo) = Self::mlx_repository(&config) {
+            match super::loaders::mlx::MlxLoader::new().load(repo) {
+                Ok(model) => return Ok(model),
+                Err(e) => tracing::warn!("{:#}; falling back to ONNX Runtime with CoreML", e),
+            }
+        }
+
         match config.provider {
+            #[cfg(feature = "onnx")]
             InferenceProvider::Onnx => {
                 // Convert unified ModelLoadConfig to OnnxLoadConfig
                 let onnx_config = self.to_onnx_config(&config)?;
@@ -313,14 +334,81 @@ impl UnifiedModelLoader {
                 Ok(Box::new(model))
             }
 
+            // Builds without ONNX Runtime (e.g. a static Candle-only binary)
+            #[cfg(not(feature = "onnx"))]
+            InferenceProvider::Onnx => anyhow::bail!(
+                "finch was built without ONNX Runtime (the `onnx` feature); \
+                 set inference_provider = \"candle\" or rebuild with --features onnx"
+            ),
+
             #[cfg(feature = "candle")]
             InferenceProvider::Candle => {
                 // Load via Candle
                 self.load_candle(&config)
             }
+
+            #[cfg(feature = "gguf")]
+            InferenceProvider::Gguf => self.load_gguf(&config),
         }
     }
 
+    /// The MLX model `config` selects: ONNX Runtime on the `metal` target, for
+    /// a family MLX has a model for.  A `repo_override` names an ONNX export,
+    /// so it keeps the ONNX path.
+    #[cfg(target_os = "macos")]
+    pub fn mlx_repository(config: &ModelLoadConfig) -> Option<&'static str> {
+        if config.provider != InferenceProvider::Onnx
+            || config.target != ExecutionTarget::Metal
+            || config.repo_override.is_some()
+        {
+            return None;
+        }
+        super::loaders::mlx::repository(config.family, config.size)
+    }
+
+    /// Load model using llama.cpp over a GGUF file
+    ///
+    /// GGUF repositories hold one file per quantization, so only the
+    /// `DEFAULT_QUANTIZATION` one is fetched rather than the whole repo.
+    #[cfg(feature = "gguf")]
+    fn load_gguf(&self, config: &ModelLoadConfig) -> Result<Box<dyn TextGeneration>> {
+        use super::loaders::gguf::{self, GgufLoader};
+
+        let repo_id = self.resolve_repository(config)?;
+
+        // Check the cached snapshot for the quantization before asking the Hub
+        let cached: Vec<String> = std::fs::read_dir(self.cache.get_cache_path(&repo_id))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let files = gguf::select_files(&cached, gguf::DEFAULT_QUANTIZATION);
+        let model_file = match files.first() {
+            // llama.cpp finds the other shards of a split model next to the first
+            Some(first) if gguf::is_complete(&files) => {
+                tracing::debug!("GGUF model found in cache");
+                self.cache.get_cache_path(&repo_id).join(first)
+            }
+            _ => {
+                tracing::info!("GGUF model not cached, downloading from HuggingFace...");
+                self.downloader
+                    .download_gguf(&repo_id, gguf::DEFAULT_QUANTIZATION)
+                    .with_context(|| format!("Failed to download GGUF model from {}", repo_id))?
+            }
+        };
+
+        let model = GgufLoader::new()
+            .load(&model_file, config.target)
+            .context("Failed to load GGUF model")?;
+
+        tracing::info!("Successfully loaded GGUF model");
+
+        Ok(model)
+    }
+
     /// Load model using Candle provider
     #[cfg(feature = "candle")]
     fn load_candle(&self, config: &ModelLoadConfig) -> Result<Box<dyn TextGeneration>> {
@@ -367,6 +455,7 @@ impl UnifiedModelLoader {
     }
 
     /// Convert ModelLoadConfig to OnnxLoadConfig (Phase 5 helper)
+    #[cfg(feature = "onnx")]
     fn to_onnx_config(&self, config: &ModelLoadConfig) -> Result<OnnxLoadConfig> {
         // Get cache directory
         let cache_dir = dirs::home_dir()
@@ -395,8 +484,9 @@ impl UnifiedModelLoader {
         use super::loaders::onnx_config::ExecutionProvider;
 
         let execution_providers = match config.target {
+            // Metal reaches ONNX Runtime only for families MLX can't run
             #[cfg(target_os = "macos")]
-            ExecutionTarget::CoreML => {
+            ExecutionTarget::CoreML | ExecutionTarget::Metal => {
                 Some(vec![ExecutionProvider::CoreML, ExecutionProvider::CPU])
             }
             #[cfg(feature = "cuda")]
@@ -617,6 +707,7 @@ impl UnifiedModelLoader {
     ///
     /// This will replace the Candle-based loaders in Phase 4.
     /// For now, it coexists with the old loaders for testing.
+    #[cfg(feature = "onnx")]
     pub fn load_onnx(&self, ram_gb: Option<usize>) -> Result<LoadedOnnxModel> {
         tracing::info!("Loading model via ONNX Runtime (Phase 3)");
 
@@ -719,6 +810,34 @@ mod tests {
         assert_eq!(repo, "onnx-community/Qwen2.5-Coder-3B-Instruct");
     }
 
+    #[test]
+    #[cfg(target_os = "macos")]
+    fn test_metal_selects_mlx_for_exported_families() {
+        let config = ModelLoadConfig {
+            provider: InferenceProvider::Onnx,
+            family: ModelFamily::Qwen2,
+            size: ModelSize::Medium,
+            target: ExecutionTarget::Metal,
+            repo_override: None,
+        };
+        assert_eq!(
+            UnifiedModelLoader::mlx_repository(&config),
+            Some("mlx-community/Qwen2.5-3B-Instruct-4bit")
+        );
+
+        // No MLX export, or another target: ONNX Runtime as before
+        let deepseek = ModelLoadConfig {
+            family: ModelFamily::DeepSeek,
+            ..config.clone()
+        };
+        assert_eq!(UnifiedModelLoader::mlx_repository(&deepseek), None);
+        let coreml = ModelLoadConfig {
+            target: ExecutionTarget::CoreML,
+            ..config
+        };
+        assert_eq!(UnifiedModelLoader::mlx_repository(&coreml), None);
+    }
+
     #[test]
     fn test_repo_override() {
         let loader = UnifiedModelLoader::new().unwrap();
@@ -0,0 +1,494 @@
+// Adapter sharing — LoRA adapters published to and pulled from a Lotus account.
+//
+// A device that trains an adapter (say a desktop overnight) publishes it to its
+// account; the account's other devices pull it the next morning.  Every
+// published adapter carries a manifest — name, version, the base model and
+// revision it was trained against, SHA-256 of the weights, the publishing
+// device — signed with that device's Ed25519 key.  A pulled adapter is only
+// installed when:
+//
+//   - the signature verifies over the manifest bytes exactly as published,
+//   - the manifest names this device's account,
+//   - the publishing device's key matches the one first seen for it
+//     (pinned in ~/.finch/adapters/trusted_devices.json),
+//   - the weights hash to the manifest's SHA-256, and
+//   - the base model (and, when both sides know it, the revision) matches
+//     the local one — an adapter trained on other weights is meaningless.
+//
+// The device signing key lives in ~/.finch/device.key and never leaves the
+// machine; the Lotus server only relays manifests and weights.
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::crypto;
+
+/// Manifest format written by this version of finch.
+pub const MANIFEST_FORMAT: u32 = 1;
+
+/// What a published adapter is, where it came from and what it fits.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdapterManifest {
+    /// Manifest format (see [`MANIFEST_FORMAT`]).
+    pub format: u32,
+    /// Adapter name (defaults to the publishing device's name).
+    pub name: String,
+    /// Version within `name`, increasing with every publish.
+    pub version: u32,
+    /// Base model repository the adapter was trained against.
+    pub base_model: String,
+    /// Commit of the base model, when the publisher had it cached.
+    pub base_revision: Option<String>,
+    /// LoRA rank.
+    pub rank: usize,
+    /// LoRA alpha.
+    pub alpha: f64,
+    /// Hex SHA-256 of the adapter weights.
+    pub sha256: String,
+    /// Size of the adapter weights in bytes.
+    pub size_bytes: u64,
+    /// Lotus account the adapter was published to.
+    pub account_id: String,
+    /// Publishing device.
+    pub device_id: Uuid,
+    /// Publishing device's name.
+    pub device_name: String,
+    /// Publishing device's Ed25519 public key (base64url).
+    pub public_key: String,
+    /// When the adapter was published.
+    pub created_at: DateTime<Utc>,
+}
+
+impl AdapterManifest {
+    /// `Err` with the reason when the adapter doesn't fit the local base model.
+    ///
+    /// Revisions are only compared when both sides know theirs.
+    pub fn check_compatible(&self, base_model: &str, base_revision: Option<&str>) -> Result<()> {
+        if !self.base_model.eq_ignore_ascii_case(base_model) {
+            bail!(
+                "trained against {}, but this device uses {}",
+                self.base_model,
+                base_model
+            );
+        }
+        if let (Some(theirs), Some(ours)) = (self.base_revision.as_deref(), base_revision) {
+            if theirs != ours {
+                bail!(
+                    "trained against {} revision {}, but this device has revision {}",
+                    self.base_model,
+                    short_revision(theirs),
+                    short_revision(ours)
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn short_revision(revision: &str) -> &str {
+    &revision[..revision.len().min(12)]
+}
+
+/// A manifest exactly as signed, plus its signature.
+///
+/// The manifest is kept as the JSON text that was signed so that fields added
+/// by newer versions don't break verification on older ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedAdapter {
+    /// Manifest JSON
+    pub manifest: String,
+    /// Ed25519 signature over the manifest bytes (base64url)
+    pub signature: String,
+}
+
+impl SignedAdapter {
+    /// Sign `manifest` with this device's key.
+    pub fn sign(manifest: &AdapterManifest, key: &DeviceKey) -> Result<Self> {
+        let manifest = serde_json::to_string(manifest).context("Failed to serialize manifest")?;
+        let signature = key.signing_key.sign(manifest.as_bytes());
+        Ok(Self {
+            manifest,
+            signature: URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        })
+    }
+
+    /// Parse the manifest and check it was signed by the key it names.
+    ///
+    /// Whether that key belongs to a device worth trusting is up to the caller
+    /// (see [`TrustedDevices`]).
+    pub fn verify(&self) -> Result<AdapterManifest> {
+        let manifest: AdapterManifest =
+            serde_json::from_str(&self.manifest).context("Adapter manifest is malformed")?;
+
+        let key = crypto::parse_public_key_url(&manifest.public_key, "Adapter public key")?;
+        crypto::verify_signature_url(
+            &key,
+            self.manifest.as_bytes(),
+            &self.signature,
+            "Adapter signature",
+        )?;
+
+        Ok(manifest)
+    }
+}
+
+/// This device's Ed25519 signing key, persisted to `~/.finch/device.key`.
+pub struct DeviceKey {
+    signing_key: SigningKey,
+}
+
+impl DeviceKey {
+    /// Load the device key, generating one on first use.
+    pub fn load_or_create() -> Result<Self> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        Self::load_or_create_at(&home.join(".finch").join("device.key"))
+    }
+
+    /// Load the key stored at `path`, generating it when missing.
+    pub fn load_or_create_at(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read device key from {}", path.display()))?;
+            let bytes = URL_SAFE_NO_PAD
+                .decode(raw.trim())
+                .context("Device key is not valid base64url")?;
+            let bytes: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Device key must be 32 bytes"))?;
+            return Ok(Self {
+                signing_key: SigningKey::from_bytes(&bytes),
+            });
+        }
+
+        let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Created owner-only, so the key is never readable by other users
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("Failed to create device key {}", path.display()))?;
+        file.write_all(URL_SAFE_NO_PAD.encode(signing_key.to_bytes()).as_bytes())
+            .and_then(|()| file.sync_all())
+            .with_context(|| format!("Failed to write device key to {}", path.display()))?;
+        tracing::info!(path = %path.display(), "Generated device signing key");
+        Ok(Self { signing_key })
+    }
+
+    /// Public key (base64url), as recorded in manifests.
+    pub fn public_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// Ed25519 signature over `data` (base64url).
+    pub fn sign(&self, data: &[u8]) -> String {
+        URL_SAFE_NO_PAD.encode(self.signing_key.sign(data).to_bytes())
+    }
+}
+
+/// Device keys seen so far, keyed by device ID (trust on first use).
+///
+/// Persisted to `trusted_devices.json` in the adapter directory.  Once a
+/// device's key is pinned, adapters signed by any other key for that device
+/// are refused.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrustedDevices {
+    devices: HashMap<Uuid, String>,
+}
+
+impl TrustedDevices {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize trusted devices")?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Accept `manifest`'s key for its device, pinning it if the device is new.
+    pub fn check(&mut self, manifest: &AdapterManifest) -> Result<()> {
+        match self.devices.get(&manifest.device_id) {
+            Some(pinned) if *pinned != manifest.public_key => bail!(
+                "Device {} ({}) signed with a different key than before. If it was \
+                 reinstalled, remove it from trusted_devices.json to accept the new key.",
+                manifest.device_name,
+                manifest.device_id
+            ),
+            Some(_) => Ok(()),
+            None => {
+                self.devices
+                    .insert(manifest.device_id, manifest.public_key.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Local adapter directory (`~/.finch/adapters`).
+pub struct AdapterStore {
+    root: PathBuf,
+}
+
+impl AdapterStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// The default store under `~/.finch/adapters`.
+    pub fn default_dir() -> Result<Self> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        Ok(Self::new(home.join(".finch").join("adapters")))
+    }
+
+    /// Adapter written by local training.
+    pub fn latest_trained(&self) -> PathBuf {
+        self.root.join("latest.safetensors")
+    }
+
+    pub fn trusted_devices_path(&self) -> PathBuf {
+        self.root.join("trusted_devices.json")
+    }
+
+    /// Where a pulled adapter's weights are installed.
+    pub fn installed_path(&self, name: &str, version: u32) -> PathBuf {
+        self.root
+            .join("shared")
+            .join(sanitize_name(name))
+            .join(format!("v{}.safetensors", version))
+    }
+
+    /// Write pulled weights and their manifest, after checking the hash.
+    pub fn install(
+        &self,
+        adapter: &SignedAdapter,
+        manifest: &AdapterManifest,
+        data: &[u8],
+    ) -> Result<PathBuf> {
+        if data.len() as u64 != manifest.size_bytes || sha256_hex(data) != manifest.sha256 {
+            bail!(
+                "Adapter {} v{} does not match its manifest (corrupt or tampered download)",
+                manifest.name,
+                manifest.version
+            );
+        }
+        let path = self.installed_path(&manifest.name, manifest.version);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, data)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        let signed =
+            serde_json::to_string_pretty(adapter).context("Failed to serialize manifest")?;
+        std::fs::write(path.with_extension("json"), signed)
+            .with_context(|| format!("Failed to write manifest next to {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Keep adapter names usable as directory names.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+/// Hex SHA-256 of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Commit of `repo` in the local Hugging Face cache, if it was downloaded.
+pub fn base_model_revision(repo: &str) -> Option<String> {
+    let path = hf_hub::Cache::from_env()
+        .path()
+        .join(hf_hub::Repo::model(repo.to_string()).folder_name())
+        .join("refs")
+        .join("main");
+    let revision = std::fs::read_to_string(path).ok()?;
+    let revision = revision.trim();
+    (!revision.is_empty()).then(|| revision.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(key: &DeviceKey, data: &[u8]) -> AdapterManifest {
+        AdapterManifest {
+            format: MANIFEST_FORMAT,
+            name: "desktop".to_string(),
+            version: 3,
+            base_model: "Qwen/Qwen2.5-1.5B-Instruct".to_string(),
+            base_revision: Some("abc123".to_string()),
+            rank: 16,
+            alpha: 32.0,
+            sha256: sha256_hex(data),
+            size_bytes: data.len() as u64,
+            account_id: "acc-1".to_string(),
+            device_id: Uuid::new_v4(),
+            device_name: "desktop".to_string(),
+            public_key: key.public_key(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn key(dir: &Path) -> DeviceKey {
+        DeviceKey::load_or_create_at(&dir.join("device.key")).unwrap()
+    }
+
+    #[test]
+    fn test_device_key_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = key(dir.path());
+        let second = key(dir.path());
+        assert_eq!(first.public_key(), second.public_key());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_device_key_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        key(dir.path());
+        let mode = std::fs::metadata(dir.path().join("device.key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key(dir.path());
+        let manifest = manifest(&key, b"weights");
+
+        let signed = SignedAdapter::sign(&manifest, &key).unwrap();
+        assert_eq!(signed.verify().unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key(dir.path());
+        let mut signed = SignedAdapter::sign(&manifest(&key, b"weights"), &key).unwrap();
+        signed.manifest = signed.manifest.replace("\"version\":3", "\"version\":4");
+
+        let err = signed.verify().unwrap_err().to_string();
+        assert!(err.contains("signature"), "{}", err);
+    }
+
+    #[test]
+    fn test_verify_rejects_key_swap() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key(dir.path());
+        let other = DeviceKey::load_or_create_at(&dir.path().join("other.key")).unwrap();
+        // Signed by `other` but claiming to be from `key`
+        let signed = SignedAdapter::sign(&manifest(&key, b"weights"), &other).unwrap();
+        assert!(signed.verify().is_err());
+    }
+
+    #[test]
+    fn test_compatibility() {
+        let dir = tempfile::tempdir().unwrap();
+        let m = manifest(&key(dir.path()), b"weights");
+
+        assert!(m
+            .check_compatible("qwen/qwen2.5-1.5b-instruct", Some("abc123"))
+            .is_ok());
+        // Unknown local revision: base model match is enough
+        assert!(m
+            .check_compatible("Qwen/Qwen2.5-1.5B-Instruct", None)
+            .is_ok());
+        assert!(m
+            .check_compatible("Qwen/Qwen2.5-3B-Instruct", Some("abc123"))
+            .is_err());
+        let err = m
+            .check_compatible("Qwen/Qwen2.5-1.5B-Instruct", Some("def456"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("revision"), "{}", err);
+    }
+
+    #[test]
+    fn test_trusted_devices_pin_first_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key(dir.path());
+        let other = DeviceKey::load_or_create_at(&dir.path().join("other.key")).unwrap();
+        let m = manifest(&key, b"weights");
+        let path = dir.path().join("trusted_devices.json");
+
+        let mut trusted = TrustedDevices::load(&path).unwrap();
+        trusted.check(&m).unwrap();
+        trusted.save(&path).unwrap();
+
+        let mut trusted = TrustedDevices::load(&path).unwrap();
+        assert!(trusted.check(&m).is_ok());
+        let rekeyed = AdapterManifest {
+            public_key: other.public_key(),
+            ..m
+        };
+        assert!(trusted.check(&rekeyed).is_err());
+    }
+
+    #[test]
+    fn test_install_checks_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = key(dir.path());
+        let store = AdapterStore::new(dir.path().join("adapters"));
+        let m = manifest(&key, b"weights");
+        let signed = SignedAdapter::sign(&m, &key).unwrap();
+
+        assert!(store.install(&signed, &m, b"tampered").is_err());
+        let path = store.install(&signed, &m, b"weights").unwrap();
+        assert_eq!(path, store.installed_path("desktop", 3));
+        assert_eq!(std::fs::read(&path).unwrap(), b"weights");
+        assert!(path.with_extension("json").exists());
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("Alice's MacBook"), "Alice_s_MacBook");
+        assert_eq!(sanitize_name("../etc"), "_etc");
+    }
+}
@@ -18,12 +18,29 @@
 //   GET /v1/devices/me
 //     Auth: Bearer <device_token>
 //     Response: { device_id, account_id?, account_name?, registered_at }
+//
+//   POST /v1/adapters
+//     Auth: Bearer <device_token> (account members only)
+//     Body: { manifest, signature, data }   (data = base64 safetensors)
+//
+//   GET /v1/adapters
+//     Auth: Bearer <device_token>
+//     Response: [{ manifest, signature }]   (every adapter in the account)
+//
+//   GET /v1/adapters/{name}/{version}
+//     Auth: Bearer <device_token>
+//     Response: { manifest, signature, data }
 
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::adapters::SignedAdapter;
+
+/// Adapter uploads and downloads can be tens of megabytes.
+const ADAPTER_TRANSFER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
 /// Client for the Lotus Network API.
 pub struct LotusClient {
     base_url: String,
@@ -68,9 +85,18 @@ pub struct DeviceInfo {
     pub registered_at: Option<String>,
 }
 
+/// A signed adapter with its weights, as uploaded and downloaded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdapterPayload {
+    #[serde(flatten)]
+    pub adapter: SignedAdapter,
+    /// Adapter weights (base64).
+    pub data: String,
+}
+
 impl LotusClient {
     pub fn new(base_url: impl Into<String>) -> Result<Self> {
-        let http = Client::builder()
+        let http = crate::http::builder()
             .timeout(std::time::Duration::from_secs(30))
             .build()
             .context("Failed to build HTTP client")?;
@@ -158,6 +184,85 @@ impl LotusClient {
             .await
             .context("Failed to parse device info response")
     }
+
+    /// Publish a signed adapter to this device's account.
+    pub async fn publish_adapter(
+        &self,
+        device_token: &str,
+        payload: &AdapterPayload,
+    ) -> Result<()> {
+        let url = format!("{}/v1/adapters", self.base_url);
+        let resp = self
+            .http
+            .post(&url)
+            .bearer_auth(device_token)
+            .timeout(ADAPTER_TRANSFER_TIMEOUT)
+            .json(payload)
+            .send()
+            .await
+            .context("Failed to reach Lotus Network")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Lotus API error {}: {}", status, body);
+        }
+        Ok(())
+    }
+
+    /// List the adapters published to this device's account (manifests only).
+    pub async fn list_adapters(&self, device_token: &str) -> Result<Vec<SignedAdapter>> {
+        let url = format!("{}/v1/adapters", self.base_url);
+        let resp = self
+            .http
+            .get(&url)
+            .bearer_auth(device_token)
+            .send()
+            .await
+            .context("Failed to reach Lotus Network")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Lotus API error {}: {}", status, body);
+        }
+
+        resp.json::<Vec<SignedAdapter>>()
+            .await
+            .context("Failed to parse adapter list")
+    }
+
+    /// Download one adapter version with its weights.
+    pub async fn download_adapter(
+        &self,
+        device_token: &str,
+        name: &str,
+        version: u32,
+    ) -> Result<AdapterPayload> {
+        let mut url = reqwest::Url::parse(&self.base_url).context("Invalid Lotus URL")?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid Lotus URL: {}", self.base_url))?
+            .pop_if_empty()
+            .extend(["v1", "adapters", name, &version.to_string()]);
+        let resp = self
+            .http
+            .get(url)
+            .bearer_auth(device_token)
+            .timeout(ADAPTER_TRANSFER_TIMEOUT)
+            .send()
+            .await
+            .context("Failed to reach Lotus Network")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Lotus API error {}: {}", status, body);
+        }
+
+        resp.json::<AdapterPayload>()
+            .await
+            .context("Failed to parse adapter download")
+    }
 }
 
 #[cfg(test)]
@@ -206,6 +311,21 @@ mod tests {
         assert_eq!(info.account_name.as_deref(), Some("Alice"));
     }
 
+    #[test]
+    fn test_adapter_payload_is_flat() {
+        let payload = AdapterPayload {
+            adapter: SignedAdapter {
+                manifest: "{}".to_string(),
+                signature: "sig".to_string(),
+            },
+            data: "d2VpZ2h0cw==".to_string(),
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["manifest"], "{}");
+        assert_eq!(json["signature"], "sig");
+        assert_eq!(json["data"], "d2VpZ2h0cw==");
+    }
+
     #[test]
     fn test_device_info_deserializes_minimal() {
         let json = r#"{
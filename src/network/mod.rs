@@ -7,9 +7,13 @@
 //
 // The device UUID is deterministic (UUID v5) so reinstalling finch on
 // the same machine gives the same device ID.
+//
+// Devices on the same account can share LoRA adapters (see `adapters`).
 
+pub mod adapters;
 pub mod client;
 pub mod membership;
 
-pub use client::LotusClient;
+pub use adapters::{AdapterManifest, AdapterStore, DeviceKey, SignedAdapter, TrustedDevices};
+pub use client::{AdapterPayload, LotusClient};
 pub use membership::{DeviceMembership, MembershipStatus};
@@ -14,6 +14,8 @@ pub mod stats;
 pub use identity::NodeIdentity;
 pub use stats::{WorkStats, WorkTracker};
 
+use crate::models::benchmark::{BenchmarkStore, GenerationBenchmark};
+use crate::models::integrity::{ModelProvenance, ProvenanceStore};
 use crate::models::model_selector::{ModelSelection, ModelSelector};
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +25,14 @@ use serde::{Deserialize, Serialize};
 pub struct NodeInfo {
     pub identity: NodeIdentity,
     pub capabilities: NodeCapabilities,
+    /// Where the most recently loaded local model came from and how it was
+    /// verified (None until a model has been downloaded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_provenance: Option<ModelProvenance>,
+    /// Generation speed of each local inference path, fastest first, from
+    /// the last `finch node-info --bench`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub benchmarks: Vec<GenerationBenchmark>,
 }
 
 /// What this node can do
@@ -67,6 +77,10 @@ impl NodeInfo {
         Ok(Self {
             identity: NodeIdentity::load_or_create()?,
             capabilities: NodeCapabilities::detect(has_teacher_api),
+            model_provenance: ProvenanceStore::open_default().and_then(|store| store.latest()),
+            benchmarks: BenchmarkStore::open_default()
+                .map(|store| store.all())
+                .unwrap_or_default(),
         })
     }
 
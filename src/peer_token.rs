@@ -4,7 +4,7 @@
 /// and reused across restarts.  Broadcast in the mDNS TXT record so that
 /// auto-discovered machines receive it automatically.
 ///
-/// Required on the dangerous endpoints: /v1/exec, /v1/forth/eval, /v1/forth/define.
+/// Required on /v1/exec and every /v1/forth/ endpoint (eval, define, vocab, push).
 /// The header name is `X-Finch-Token`.
 pub const HEADER: &str = "x-finch-token";
 
@@ -0,0 +1,538 @@
+// Data-residency policy — which content may be sent to which provider
+//
+// Regulated teams declare rules in a TOML policy file:
+//
+//   [[rule]]
+//   name = "payments-stay-local"
+//   paths = ["src/payments/**"]
+//   allow_providers = ["local"]
+//
+//   [[rule]]
+//   name = "no-code-to-openai"
+//   content = "code"
+//   deny_providers = ["openai"]
+//
+// Rules are read from `~/.finch/policy.toml` and `<project>/.finch/policy.toml`
+// (both apply; rules only ever add restrictions).  They are enforced in two
+// places: `providers::policy` refuses to send a violating request to a cloud
+// provider, and context assembly leaves out project files covered by a path
+// rule.  Every blocked request is logged and appended to the audit log
+// `~/.finch/policy_violations.jsonl`.
+
+use anyhow::{bail, Context, Result};
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::claude::ContentBlock;
+use crate::providers::ProviderRequest;
+
+/// Provider name that stands for the on-device model
+pub const LOCAL_PROVIDER: &str = "local";
+
+/// Policy file name, looked up in `~/.finch/` and `<project>/.finch/`
+pub const POLICY_FILE: &str = "policy.toml";
+
+/// Audit log of blocked requests, in `~/.finch/`
+const AUDIT_LOG_FILE: &str = "policy_violations.jsonl";
+
+/// Tools whose results carry file contents
+const READ_TOOLS: &[&str] = &["read", "grep", "outline"];
+
+/// Tools whose inputs carry file contents
+const WRITE_TOOLS: &[&str] = &["edit", "write", "patch"];
+
+/// Tool input fields that name a file or directory
+const PATH_FIELDS: &[&str] = &["file_path", "path"];
+
+/// Kind of content a rule is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    /// Every request
+    Any,
+    /// Requests carrying source code (fenced code or file tool traffic)
+    Code,
+}
+
+/// One rule as written in the policy file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    content: Option<ContentKind>,
+    #[serde(default)]
+    allow_providers: Vec<String>,
+    #[serde(default)]
+    deny_providers: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default)]
+    rule: Vec<RuleSpec>,
+}
+
+/// A compiled policy rule.
+///
+/// The rule applies to a request when every condition it sets holds (a path
+/// in `paths` is referenced, the request carries `content`).  An applicable
+/// rule only lets the request through to providers in `allow_providers`
+/// (when non-empty) and never to those in `deny_providers`.
+#[derive(Debug, Clone)]
+pub struct PolicyRule {
+    pub name: String,
+    paths: Vec<Pattern>,
+    content: ContentKind,
+    allow_providers: Vec<String>,
+    deny_providers: Vec<String>,
+}
+
+impl PolicyRule {
+    fn compile(spec: RuleSpec) -> Result<Self> {
+        if spec.allow_providers.is_empty() && spec.deny_providers.is_empty() {
+            bail!(
+                "policy rule '{}' needs allow_providers or deny_providers",
+                spec.name
+            );
+        }
+        let paths = spec
+            .paths
+            .iter()
+            .map(|p| {
+                Pattern::new(p.trim_start_matches("./")).with_context(|| {
+                    format!("policy rule '{}': invalid path glob {:?}", spec.name, p)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let lower = |v: Vec<String>| v.into_iter().map(|s| s.to_lowercase()).collect();
+        Ok(Self {
+            name: spec.name,
+            paths,
+            content: spec.content.unwrap_or(ContentKind::Any),
+            allow_providers: lower(spec.allow_providers),
+            deny_providers: lower(spec.deny_providers),
+        })
+    }
+
+    fn permits(&self, provider: &str) -> bool {
+        let provider = provider.to_lowercase();
+        (self.allow_providers.is_empty() || self.allow_providers.contains(&provider))
+            && !self.deny_providers.contains(&provider)
+    }
+
+    /// True if some cloud provider is refused by this rule
+    fn restricts_cloud(&self) -> bool {
+        // An allow-list refuses every provider it doesn't name
+        !self.allow_providers.is_empty() || self.deny_providers.iter().any(|p| p != LOCAL_PROVIDER)
+    }
+}
+
+/// A request refused by the policy
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("blocked by policy rule '{rule}': {reason} may not be sent to provider '{provider}'")]
+pub struct PolicyViolation {
+    pub rule: String,
+    pub provider: String,
+    /// What triggered the rule, e.g. `src/payments/ledger.rs` or `code`
+    pub reason: String,
+}
+
+/// The set of rules in force
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    rules: Vec<PolicyRule>,
+    /// Project root that relative rule paths are resolved against
+    root: Option<PathBuf>,
+    /// Where blocked requests are recorded (`None` = tracing only)
+    audit_log: Option<PathBuf>,
+}
+
+/// The process-wide policy, loaded on first use from the user and project
+/// policy files.  An unreadable or invalid file fails closed: nothing is sent
+/// to cloud providers until it is fixed.
+static ACTIVE: LazyLock<Policy> = LazyLock::new(|| {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    match Policy::load(&cwd) {
+        Ok(policy) => {
+            if !policy.is_empty() {
+                tracing::info!(rules = policy.rules.len(), "Data-residency policy loaded");
+            }
+            policy
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to load policy, blocking all cloud providers: {:#}",
+                e
+            );
+            Policy::local_only("invalid-policy-file")
+        }
+    }
+});
+
+/// The policy in force for this process
+pub fn active() -> &'static Policy {
+    &ACTIVE
+}
+
+impl Policy {
+    /// Load `~/.finch/policy.toml` and `<project_root>/.finch/policy.toml`.
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let home_finch = dirs::home_dir().map(|h| h.join(".finch"));
+        let mut files: Vec<PathBuf> = home_finch.iter().map(|d| d.join(POLICY_FILE)).collect();
+        let project_file = project_root.join(".finch").join(POLICY_FILE);
+        if !files.contains(&project_file) {
+            files.push(project_file);
+        }
+
+        let mut policy = Self {
+            root: Some(project_root.to_path_buf()),
+            audit_log: home_finch.map(|d| d.join(AUDIT_LOG_FILE)),
+            ..Self::default()
+        };
+        for file in files.iter().filter(|f| f.exists()) {
+            let text = std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let parsed = Self::parse_rules(&text)
+                .with_context(|| format!("Invalid policy file {}", file.display()))?;
+            policy.rules.extend(parsed);
+        }
+        Ok(policy)
+    }
+
+    /// Build a policy from TOML text; relative paths resolve against `root`.
+    pub fn from_toml_str(text: &str, root: Option<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            rules: Self::parse_rules(text)?,
+            root,
+            audit_log: None,
+        })
+    }
+
+    fn parse_rules(text: &str) -> Result<Vec<PolicyRule>> {
+        let file: PolicyFile = toml::from_str(text)?;
+        file.rule.into_iter().map(PolicyRule::compile).collect()
+    }
+
+    /// A policy that keeps everything on the local model
+    fn local_only(rule_name: &str) -> Self {
+        Self {
+            rules: vec![PolicyRule {
+                name: rule_name.to_string(),
+                paths: Vec::new(),
+                content: ContentKind::Any,
+                allow_providers: vec![LOCAL_PROVIDER.to_string()],
+                deny_providers: Vec::new(),
+            }],
+            root: None,
+            audit_log: dirs::home_dir().map(|h| h.join(".finch").join(AUDIT_LOG_FILE)),
+        }
+    }
+
+    /// Record blocked requests in `path` as JSON lines
+    pub fn with_audit_log(mut self, path: PathBuf) -> Self {
+        self.audit_log = Some(path);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rules(&self) -> &[PolicyRule] {
+        &self.rules
+    }
+
+    /// Check whether `request` may be sent to `provider`.
+    pub fn check(&self, provider: &str, request: &ProviderRequest) -> Result<(), PolicyViolation> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+        let facts = RequestFacts::gather(request);
+        for rule in self.rules.iter().filter(|r| !r.permits(provider)) {
+            let matched_path = if rule.paths.is_empty() {
+                None
+            } else {
+                match facts.paths.iter().find(|p| self.path_matches(rule, p)) {
+                    Some(path) => Some(path.clone()),
+                    None => continue,
+                }
+            };
+            if rule.content == ContentKind::Code && !facts.has_code {
+                continue;
+            }
+            let reason = match (matched_path, rule.content) {
+                (Some(path), _) => path,
+                (None, ContentKind::Code) => "code".to_string(),
+                (None, ContentKind::Any) => "any request".to_string(),
+            };
+            return Err(PolicyViolation {
+                rule: rule.name.clone(),
+                provider: provider.to_string(),
+                reason,
+            });
+        }
+        Ok(())
+    }
+
+    /// The first path rule covering `path` that restricts cloud providers.
+    ///
+    /// Context assembly uses this to keep such files out of the system
+    /// prompt, which is sent to whichever provider answers.
+    pub fn restricting_rule(&self, path: &Path) -> Option<&PolicyRule> {
+        let path = path.to_string_lossy();
+        self.rules
+            .iter()
+            .filter(|r| !r.paths.is_empty() && r.restricts_cloud())
+            .find(|r| self.path_matches(r, &path))
+    }
+
+    /// Log a blocked request and append it to the audit log
+    pub fn record_violation(&self, violation: &PolicyViolation) {
+        tracing::warn!(
+            rule = %violation.rule,
+            provider = %violation.provider,
+            reason = %violation.reason,
+            "Policy violation blocked"
+        );
+        let Some(path) = &self.audit_log else {
+            return;
+        };
+        let entry = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "rule": violation.rule,
+            "provider": violation.provider,
+            "reason": violation.reason,
+        });
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+            })
+            .and_then(|mut f| writeln!(f, "{}", entry));
+        if let Err(e) = written {
+            tracing::warn!("Failed to write policy audit log {}: {}", path.display(), e);
+        }
+    }
+
+    fn path_matches(&self, rule: &PolicyRule, path: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::default()
+        };
+        let path = path.trim_start_matches("./");
+        // Absolute paths inside the project are matched relative to its root
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| Path::new(path).strip_prefix(root).ok())
+            .map(|p| p.to_string_lossy().into_owned());
+        rule.paths.iter().any(|pattern| {
+            pattern.matches_with(path, options)
+                || relative
+                    .as_deref()
+                    .is_some_and(|rel| pattern.matches_with(rel, options))
+        })
+    }
+}
+
+/// What a request carries, as far as the policy is concerned
+struct RequestFacts {
+    /// Files and directories referenced by tool calls and tool results
+    paths: Vec<String>,
+    /// Whether any source code is included
+    has_code: bool,
+}
+
+impl RequestFacts {
+    fn gather(request: &ProviderRequest) -> Self {
+        let mut tool_names: HashMap<&str, &str> = HashMap::new();
+        let mut paths = Vec::new();
+        let mut has_code = false;
+
+        let blocks = request.messages.iter().flat_map(|m| m.content.iter());
+        for block in blocks.clone() {
+            if let ContentBlock::ToolUse { id, name, input } = block {
+                tool_names.insert(id, name);
+                has_code |= WRITE_TOOLS.contains(&name.as_str());
+                for field in PATH_FIELDS {
+                    if let Some(p) = input.get(field).and_then(|v| v.as_str()) {
+                        paths.push(p.to_string());
+                    }
+                }
+            }
+        }
+        for block in blocks {
+            match block {
+                ContentBlock::Text { text } => has_code |= text.contains("```"),
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    ..
+                } => {
+                    let tool = tool_names.get(tool_use_id.as_str()).copied();
+                    has_code |=
+                        content.contains("```") || tool.is_some_and(|t| READ_TOOLS.contains(&t));
+                    // grep / glob output lists `path:line: …` or bare paths
+                    paths.extend(content.split_whitespace().filter_map(path_token));
+                }
+                _ => {}
+            }
+        }
+        if let Some(system) = &request.system {
+            has_code |= system.contains("```");
+        }
+        Self { paths, has_code }
+    }
+}
+
+/// Extract a file path from a whitespace-separated token of tool output
+fn path_token(token: &str) -> Option<String> {
+    let token = token.split(':').next()?;
+    let token = token.trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | ',' | '(' | ')'));
+    (token.contains('/') && !token.contains("://")).then(|| token.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::Message;
+
+    const POLICY: &str = r#"
+        [[rule]]
+        name = "payments-stay-local"
+        paths = ["src/payments/**"]
+        allow_providers = ["local"]
+
+        [[rule]]
+        name = "no-code-to-openai"
+        content = "code"
+        deny_providers = ["openai"]
+    "#;
+
+    fn policy() -> Policy {
+        Policy::from_toml_str(POLICY, Some(PathBuf::from("/work/shop"))).unwrap()
+    }
+
+    fn request(messages: Vec<Message>) -> ProviderRequest {
+        ProviderRequest::new(messages)
+    }
+
+    fn read_exchange(path: &str, output: &str) -> Vec<Message> {
+        vec![
+            Message::user("look at this"),
+            Message {
+                role: "assistant".to_string(),
+                content: vec![ContentBlock::ToolUse {
+                    id: "t1".to_string(),
+                    name: "read".to_string(),
+                    input: serde_json::json!({ "file_path": path }),
+                }],
+            },
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    content: output.to_string(),
+                    is_error: None,
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_plain_question_is_allowed_everywhere() {
+        let req = request(vec![Message::user("what is a monad?")]);
+        assert!(policy().check("openai", &req).is_ok());
+        assert!(policy().check("claude", &req).is_ok());
+    }
+
+    #[test]
+    fn test_protected_path_only_goes_local() {
+        let req = request(read_exchange("src/payments/ledger.rs", "fn settle() {}"));
+        let err = policy().check("claude", &req).unwrap_err();
+        assert_eq!(err.rule, "payments-stay-local");
+        assert_eq!(err.reason, "src/payments/ledger.rs");
+        assert!(policy().check("local", &req).is_ok());
+    }
+
+    #[test]
+    fn test_absolute_paths_resolve_against_project_root() {
+        let req = request(read_exchange("/work/shop/src/payments/ledger.rs", "x"));
+        assert!(policy().check("claude", &req).is_err());
+        let elsewhere = request(read_exchange("/other/src/payments/ledger.rs", "x"));
+        assert!(policy().check("claude", &elsewhere).is_ok());
+    }
+
+    #[test]
+    fn test_paths_in_grep_output_are_protected() {
+        let mut messages = read_exchange("src", "src/payments/ledger.rs:12: let fee = 3;");
+        if let ContentBlock::ToolUse { name, .. } = &mut messages[1].content[0] {
+            *name = "grep".to_string();
+        }
+        assert!(policy().check("claude", &request(messages)).is_err());
+    }
+
+    #[test]
+    fn test_code_rule_blocks_only_the_denied_provider() {
+        let req = request(read_exchange("src/main.rs", "fn main() {}"));
+        let err = policy().check("OpenAI", &req).unwrap_err();
+        assert_eq!(err.rule, "no-code-to-openai");
+        assert_eq!(err.reason, "code");
+        assert!(policy().check("claude", &req).is_ok());
+
+        let fenced = request(vec![Message::user("why?\n```rust\nfn f() {}\n```")]);
+        assert!(policy().check("openai", &fenced).is_err());
+    }
+
+    #[test]
+    fn test_rule_without_providers_is_rejected() {
+        let err =
+            Policy::from_toml_str("[[rule]]\nname = \"x\"\npaths = [\"a/**\"]", None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("allow_providers or deny_providers"));
+    }
+
+    #[test]
+    fn test_restricting_rule_for_context_files() {
+        let policy = policy();
+        assert!(policy
+            .restricting_rule(Path::new("/work/shop/src/payments/FINCH.md"))
+            .is_some());
+        assert!(policy
+            .restricting_rule(Path::new("/work/shop/FINCH.md"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_violations_are_appended_to_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("violations.jsonl");
+        let policy = policy().with_audit_log(log.clone());
+        let req = request(read_exchange("src/payments/a.rs", "x"));
+        let violation = policy.check("gemini", &req).unwrap_err();
+        policy.record_violation(&violation);
+        policy.record_violation(&violation);
+
+        let text = std::fs::read_to_string(&log).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["rule"], "payments-stay-local");
+        assert_eq!(lines[0]["provider"], "gemini");
+    }
+}
@@ -215,7 +215,12 @@ async fn exec_node(
                         stack: stack.clone(),
                         poset: Some(Arc::clone(&poset)),
                     };
-                    match tool.execute(tu.input.clone(), &ctx_obj).await {
+                    let call = crate::tools::types::ToolUse {
+                        id: tu.id.clone(),
+                        name: tu.name.clone(),
+                        input: tu.input.clone(),
+                    };
+                    match tool.execute_call(&call, &ctx_obj).await {
                         Ok(output) => output,
                         Err(e) => format!("Tool error: {e}"),
                     }
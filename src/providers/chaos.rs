@@ -0,0 +1,430 @@
+// Fault injection for provider testing ("chaos mode")
+//
+// `FaultInjectingProvider` wraps any `LlmProvider` and, at configurable rates,
+// replaces its behaviour with the failures real APIs produce: timeouts, 429
+// rate limits, streams that die half-way, and unparseable response bodies.
+// It exists to exercise the FallbackChain, retry and TUI error paths — it is
+// never enabled unless `FINCH_CHAOS` is set.
+//
+//   FINCH_CHAOS="rate_limit=0.2,timeout=0.05,truncate=0.1,malformed=0.05,seed=42"
+
+use anyhow::{bail, Context, Result};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{LlmProvider, ProviderRequest, ProviderResponse, RateLimited, StreamChunk};
+use crate::claude::ContentBlock;
+
+/// Environment variable holding the chaos spec.
+pub const CHAOS_ENV_VAR: &str = "FINCH_CHAOS";
+
+/// Kind of fault injected into a single call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Hang for `ChaosConfig::timeout`, then fail as a request timeout.
+    Timeout,
+    /// Fail immediately with an HTTP 429.
+    RateLimit,
+    /// Cut the response short (streams end with a connection error).
+    Truncate,
+    /// Fail with a JSON parse error, as if the body were garbage.
+    Malformed,
+}
+
+/// Per-call fault probabilities.  Rates are independent slices of a single
+/// roll, so their sum must not exceed 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    pub timeout_rate: f64,
+    pub rate_limit_rate: f64,
+    pub truncate_rate: f64,
+    pub malformed_rate: f64,
+    /// How long an injected timeout hangs before failing
+    pub timeout: Duration,
+    /// RNG seed for reproducible runs (`None` = random)
+    pub seed: Option<u64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            timeout_rate: 0.0,
+            rate_limit_rate: 0.0,
+            truncate_rate: 0.0,
+            malformed_rate: 0.0,
+            timeout: Duration::from_secs(30),
+            seed: None,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Parse a spec such as `"rate_limit=0.2,timeout=0.1,timeout_ms=500,seed=7"`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut config = Self::default();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("Invalid chaos setting '{}': expected key=value", pair))?;
+            let value = value.trim();
+            let rate = || -> Result<f64> {
+                let rate: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid rate for '{}': {}", key, value))?;
+                if !(0.0..=1.0).contains(&rate) {
+                    bail!("Rate for '{}' must be between 0 and 1, got {}", key, rate);
+                }
+                Ok(rate)
+            };
+            match key.trim() {
+                "timeout" => config.timeout_rate = rate()?,
+                "rate_limit" | "429" => config.rate_limit_rate = rate()?,
+                "truncate" => config.truncate_rate = rate()?,
+                "malformed" => config.malformed_rate = rate()?,
+                "timeout_ms" => {
+                    config.timeout =
+                        Duration::from_millis(value.parse().context("Invalid timeout_ms")?)
+                }
+                "seed" => config.seed = Some(value.parse().context("Invalid seed")?),
+                other => bail!("Unknown chaos setting '{}'", other),
+            }
+        }
+        if config.total_rate() > 1.0 + f64::EPSILON {
+            bail!(
+                "Chaos fault rates sum to {:.2}; they must not exceed 1.0",
+                config.total_rate()
+            );
+        }
+        Ok(config)
+    }
+
+    /// Read `FINCH_CHAOS`.  `Ok(None)` when unset or empty.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var(CHAOS_ENV_VAR) {
+            Ok(spec) if !spec.trim().is_empty() => Self::parse(&spec).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn total_rate(&self) -> f64 {
+        self.timeout_rate + self.rate_limit_rate + self.truncate_rate + self.malformed_rate
+    }
+
+    /// Map a uniform roll in [0, 1) onto a fault.
+    fn pick(&self, roll: f64) -> Option<Fault> {
+        let slices = [
+            (Fault::Timeout, self.timeout_rate),
+            (Fault::RateLimit, self.rate_limit_rate),
+            (Fault::Truncate, self.truncate_rate),
+            (Fault::Malformed, self.malformed_rate),
+        ];
+        let mut upper = 0.0;
+        for (fault, rate) in slices {
+            upper += rate;
+            if roll < upper {
+                return Some(fault);
+            }
+        }
+        None
+    }
+}
+
+/// Provider wrapper that injects faults according to a `ChaosConfig`.
+pub struct FaultInjectingProvider {
+    inner: Box<dyn LlmProvider>,
+    config: ChaosConfig,
+    rng: Mutex<SmallRng>,
+    calls: AtomicU64,
+    injected: AtomicU64,
+}
+
+impl FaultInjectingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, config: ChaosConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        Self {
+            inner,
+            config,
+            rng: Mutex::new(rng),
+            calls: AtomicU64::new(0),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Total calls seen by the wrapper
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Calls that had a fault injected
+    pub fn injected_faults(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    fn roll(&self) -> Option<Fault> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        let roll: f64 = self.rng.lock().unwrap().gen();
+        let fault = self.config.pick(roll);
+        if let Some(fault) = fault {
+            self.injected.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                provider = self.inner.name(),
+                ?fault,
+                "Chaos: injecting fault"
+            );
+        }
+        fault
+    }
+
+    /// Error for a fault that fails the whole call (timeouts wait first).
+    async fn failure(&self, fault: Fault) -> anyhow::Error {
+        let name = self.inner.name();
+        match fault {
+            Fault::Timeout => {
+                tokio::time::sleep(self.config.timeout).await;
+                anyhow::anyhow!(
+                    "{} request timed out after {:?} (chaos)",
+                    name,
+                    self.config.timeout
+                )
+            }
+            // Not recorded as a real throttle, so a FallbackChain still tries
+            // this provider on the next call
+            Fault::RateLimit => RateLimited {
+                provider: name.to_string(),
+                retry_after: Duration::from_secs(1),
+                message: format!(
+                    "{} API error 429 Too Many Requests — You've hit a rate limit; \
+                     wait a moment before retrying: rate limited (chaos)",
+                    name
+                ),
+            }
+            .into(),
+            Fault::Malformed | Fault::Truncate => anyhow::Error::new(malformed_json_error())
+                .context(format!("Failed to parse {} response (chaos)", name)),
+        }
+    }
+}
+
+/// A genuine serde error for a body cut off mid-object.
+fn malformed_json_error() -> serde_json::Error {
+    serde_json::from_str::<serde_json::Value>(r#"{"id":"msg_01","content":[{"ty"#)
+        .expect_err("truncated JSON never parses")
+}
+
+/// Wrap each provider in a `FaultInjectingProvider` when `FINCH_CHAOS` is set.
+/// An invalid spec is logged and ignored rather than breaking startup.
+pub fn wrap_from_env(providers: Vec<Box<dyn LlmProvider>>) -> Vec<Box<dyn LlmProvider>> {
+    let config = match ChaosConfig::from_env() {
+        Ok(Some(config)) => config,
+        Ok(None) => return providers,
+        Err(e) => {
+            tracing::warn!("Ignoring {}: {}", CHAOS_ENV_VAR, e);
+            return providers;
+        }
+    };
+    tracing::warn!(
+        ?config,
+        "Chaos mode enabled: provider faults will be injected"
+    );
+    providers
+        .into_iter()
+        .map(|p| Box::new(FaultInjectingProvider::new(p, config.clone())) as Box<dyn LlmProvider>)
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for FaultInjectingProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        let fault = self.roll();
+        if let Some(f @ (Fault::Timeout | Fault::RateLimit | Fault::Malformed)) = fault {
+            return Err(self.failure(f).await);
+        }
+        let mut response = self.inner.send_message(request).await?;
+        if fault == Some(Fault::Truncate) {
+            // Keep the first half of the text, drop everything after it
+            response
+                .content
+                .retain(|b| matches!(b, ContentBlock::Text { .. }));
+            for block in &mut response.content {
+                if let ContentBlock::Text { text } = block {
+                    let cut = text.char_indices().nth(text.chars().count() / 2);
+                    text.truncate(cut.map_or(0, |(i, _)| i));
+                }
+            }
+            response.stop_reason = None;
+        }
+        Ok(response)
+    }
+
+    async fn send_message_stream(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        let fault = match self.roll() {
+            None => return self.inner.send_message_stream(request).await,
+            Some(f @ (Fault::Timeout | Fault::RateLimit)) => return Err(self.failure(f).await),
+            Some(f) => f,
+        };
+
+        // Mid-stream faults: forward the first chunk, then fail the stream
+        let mut inner_rx = self.inner.send_message_stream(request).await?;
+        let (tx, rx) = mpsc::channel(32);
+        let name = self.inner.name().to_string();
+        tokio::spawn(async move {
+            if let Some(first) = inner_rx.recv().await {
+                if tx.send(first).await.is_err() {
+                    return;
+                }
+            }
+            let error = if fault == Fault::Truncate {
+                anyhow::anyhow!("{} stream closed unexpectedly mid-response (chaos)", name)
+            } else {
+                anyhow::Error::new(malformed_json_error())
+                    .context(format!("Failed to parse {} stream event (chaos)", name))
+            };
+            let _ = tx.send(Err(error)).await;
+        });
+        Ok(rx)
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.inner.context_limit_tokens()
+    }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        self.inner.cancel_generation().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::retry::with_retry;
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for EchoProvider {
+        async fn send_message(&self, _request: &ProviderRequest) -> Result<ProviderResponse> {
+            Ok(ProviderResponse {
+                id: "echo".to_string(),
+                model: "echo-model".to_string(),
+                content: vec![ContentBlock::Text {
+                    text: "abcdef".to_string(),
+                }],
+                stop_reason: Some("end_turn".to_string()),
+                role: "assistant".to_string(),
+                provider: "echo".to_string(),
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+            let (tx, rx) = mpsc::channel(4);
+            for word in ["one ", "two ", "three"] {
+                tx.send(Ok(StreamChunk::TextDelta(word.to_string())))
+                    .await?;
+            }
+            Ok(rx)
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn default_model(&self) -> &str {
+            "echo-model"
+        }
+    }
+
+    fn chaos(spec: &str) -> FaultInjectingProvider {
+        FaultInjectingProvider::new(Box::new(EchoProvider), ChaosConfig::parse(spec).unwrap())
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let config = ChaosConfig::parse("429=0.25, timeout=0.1,timeout_ms=50,seed=7").unwrap();
+        assert_eq!(config.rate_limit_rate, 0.25);
+        assert_eq!(config.timeout_rate, 0.1);
+        assert_eq!(config.timeout, Duration::from_millis(50));
+        assert_eq!(config.seed, Some(7));
+
+        assert!(ChaosConfig::parse("timeout=1.5").is_err());
+        assert!(ChaosConfig::parse("timeout=0.6,malformed=0.6").is_err());
+        assert!(ChaosConfig::parse("explode=0.1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zero_rates_pass_through() {
+        let provider = chaos("");
+        let request = ProviderRequest::new(vec![]);
+        let response = provider.send_message(&request).await.unwrap();
+        assert_eq!(response.id, "echo");
+        assert_eq!(provider.injected_faults(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_truncate_cuts_response() {
+        let provider = chaos("truncate=1");
+        let request = ProviderRequest::new(vec![]);
+        let response = provider.send_message(&request).await.unwrap();
+        assert!(matches!(&response.content[0], ContentBlock::Text { text } if text == "abc"));
+        assert!(response.stop_reason.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_gives_up_on_persistent_rate_limit() {
+        let provider = chaos("rate_limit=1");
+        let request = ProviderRequest::new(vec![]);
+        let result = with_retry(|| provider.send_message(&request)).await;
+        assert!(result.unwrap_err().to_string().contains("429"));
+        assert_eq!(provider.calls(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_recovers_from_intermittent_faults() {
+        let provider = chaos("rate_limit=0.5,seed=3");
+        let request = ProviderRequest::new(vec![]);
+        let result = with_retry(|| provider.send_message(&request)).await;
+        // Succeeds exactly when some attempt escaped injection
+        assert_eq!(
+            result.is_ok(),
+            provider.injected_faults() < provider.calls()
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_faults() {
+        let config = ChaosConfig::parse("timeout=0.3,rate_limit=0.3,seed=11").unwrap();
+        let a = FaultInjectingProvider::new(Box::new(EchoProvider), config.clone());
+        let b = FaultInjectingProvider::new(Box::new(EchoProvider), config);
+        let faults_a: Vec<_> = (0..20).map(|_| a.roll()).collect();
+        let faults_b: Vec<_> = (0..20).map(|_| b.roll()).collect();
+        assert_eq!(faults_a, faults_b);
+    }
+}
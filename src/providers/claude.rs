@@ -8,11 +8,13 @@ use std::collections::HashMap;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+use super::rate_limit::response_error;
+use super::reasoning::{self, Reasoning};
 use super::types::{ProviderRequest, ProviderResponse, StreamChunk};
 use super::LlmProvider;
 use crate::claude::retry::with_retry;
 use crate::claude::streaming::StreamEvent;
-use crate::claude::types::{ContentBlock, MessageRequest};
+use crate::claude::types::{ContentBlock, Message, MessageRequest};
 use crate::config::constants::DEFAULT_CLAUDE_MODEL;
 
 const CLAUDE_API_URL: &str = "https://api.anthropic.com/v1/messages";
@@ -52,6 +54,49 @@ struct BlockBuilder {
     id: Option<String>,
     name: Option<String>,
     accumulated: String,
+    signature: String,
+}
+
+/// Extended thinking budget for a request to `model`, or `None` when it
+/// shouldn't think.
+///
+/// Claude only accepts thinking while continuing a tool call if the
+/// assistant message that made the call opened with its thinking, so a turn
+/// begun without thinking is finished without it.
+fn thinking_budget(reasoning: Option<Reasoning>, model: &str, messages: &[Message]) -> Option<u32> {
+    let reasoning = reasoning?;
+    if !reasoning::claude_supports_thinking(model) {
+        return None;
+    }
+    let continues_tool_call = messages.last().is_some_and(|m| {
+        m.role == "user"
+            && m.content
+                .iter()
+                .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+    });
+    if continues_tool_call {
+        let opened_with_thinking = messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "assistant")
+            .and_then(|m| m.content.first())
+            .is_some_and(|block| sendable(block, true) && block.is_thinking());
+        if !opened_with_thinking {
+            return None;
+        }
+    }
+    Some(reasoning.budget_tokens())
+}
+
+/// Whether a history block can be sent back to Claude.  Thinking goes back
+/// only while thinking is on, and only if Claude signed it (not thinking
+/// another provider produced).
+fn sendable(block: &ContentBlock, thinking: bool) -> bool {
+    match block {
+        ContentBlock::Thinking { signature, .. } => thinking && !signature.is_empty(),
+        ContentBlock::RedactedThinking { .. } => thinking,
+        _ => true,
+    }
 }
 
 /// Claude API provider
@@ -67,7 +112,7 @@ pub struct ClaudeProvider {
 impl ClaudeProvider {
     /// Create a new Claude provider
     pub fn new(api_key: String) -> Result<Self> {
-        let client = Client::builder()
+        let client = crate::http::builder()
             .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
             .build()
             .context("Failed to create HTTP client")?;
@@ -85,28 +130,59 @@ impl ClaudeProvider {
         self
     }
 
-    /// Convert ProviderRequest to Claude's MessageRequest format
-    fn to_message_request(&self, request: &ProviderRequest) -> MessageRequest {
+    /// Convert ProviderRequest to Claude's MessageRequest format, along with
+    /// the extended thinking budget if Claude should think
+    fn to_message_request(&self, request: &ProviderRequest) -> (MessageRequest, Option<u32>) {
         let model = if request.model.is_empty() {
             self.default_model.clone()
         } else {
             request.model.clone()
         };
 
-        MessageRequest {
+        let budget = thinking_budget(reasoning::configured(), &model, &request.messages);
+        let messages = request
+            .messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m
+                    .content
+                    .iter()
+                    .filter(|block| sendable(block, budget.is_some()))
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+
+        let msg_request = MessageRequest {
             model,
-            max_tokens: request.max_tokens,
-            messages: request.messages.clone(),
+            // The thinking budget comes out of max_tokens, so add it on top
+            max_tokens: request.max_tokens.saturating_add(budget.unwrap_or(0)),
+            messages,
             system: request.system.clone(),
             tools: request.tools.clone(),
+        };
+        (msg_request, budget)
+    }
+
+    /// JSON body for the Messages API
+    fn request_body(&self, request: &ProviderRequest) -> Result<serde_json::Value> {
+        let (msg_request, budget) = self.to_message_request(request);
+        let mut request_json = serde_json::to_value(&msg_request)?;
+        if let Some(budget_tokens) = budget {
+            request_json["thinking"] = serde_json::json!({
+                "type": "enabled",
+                "budget_tokens": budget_tokens,
+            });
         }
+        Ok(request_json)
     }
 
     /// Send a single message request (no retry)
     async fn send_message_once(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
-        let msg_request = self.to_message_request(request);
+        let request_json = self.request_body(request)?;
 
-        tracing::debug!("Sending request to Claude API: {:?}", msg_request);
+        tracing::debug!("Sending request to Claude API: {}", request_json);
 
         let response = self
             .client
@@ -114,7 +190,7 @@ impl ClaudeProvider {
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", ANTHROPIC_VERSION)
             .header("content-type", "application/json")
-            .json(&msg_request)
+            .json(&request_json)
             .send()
             .await
             .context("Failed to send request to Claude API")?;
@@ -122,8 +198,7 @@ impl ClaudeProvider {
         let status = response.status();
 
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}", friendly_api_error(status, &error_body));
+            return Err(response_error("claude", response, friendly_api_error).await);
         }
 
         let message_response: crate::claude::types::MessageResponse = response
@@ -151,10 +226,8 @@ impl ClaudeProvider {
     ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
         let (tx, rx) = mpsc::channel(100);
 
-        let msg_request = self.to_message_request(request);
-
         // Convert to JSON and add stream: true
-        let mut request_json = serde_json::to_value(&msg_request)?;
+        let mut request_json = self.request_body(request)?;
         request_json["stream"] = serde_json::json!(true);
 
         tracing::debug!("Sending streaming request to Claude API");
@@ -172,8 +245,7 @@ impl ClaudeProvider {
 
         let status = response.status();
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}", friendly_api_error(status, &error_body));
+            return Err(response_error("claude", response, friendly_api_error).await);
         }
 
         // Spawn task to parse SSE stream with block tracking
@@ -248,7 +320,9 @@ impl ClaudeProvider {
                                                         block_type: cb.block_type,
                                                         id: cb.id,
                                                         name: cb.name,
-                                                        accumulated: String::new(),
+                                                        // Redacted thinking arrives whole
+                                                        accumulated: cb.data.unwrap_or_default(),
+                                                        signature: String::new(),
                                                     },
                                                 );
                                                 tracing::debug!(
@@ -292,6 +366,33 @@ impl ClaudeProvider {
                                                                 builder.accumulated.push_str(&json);
                                                             }
                                                         }
+                                                        "thinking_delta" => {
+                                                            if let Some(thinking) = delta.thinking {
+                                                                builder
+                                                                    .accumulated
+                                                                    .push_str(&thinking);
+                                                                if tx
+                                                                    .send(Ok(
+                                                                        StreamChunk::ThinkingDelta(
+                                                                            thinking,
+                                                                        ),
+                                                                    ))
+                                                                    .await
+                                                                    .is_err()
+                                                                {
+                                                                    done = true;
+                                                                    break;
+                                                                }
+                                                            }
+                                                        }
+                                                        "signature_delta" => {
+                                                            if let Some(signature) = delta.signature
+                                                            {
+                                                                builder
+                                                                    .signature
+                                                                    .push_str(&signature);
+                                                            }
+                                                        }
                                                         _ => {}
                                                     }
                                                 }
@@ -316,6 +417,15 @@ impl ClaudeProvider {
                                                             input,
                                                         }
                                                     }
+                                                    "thinking" => ContentBlock::Thinking {
+                                                        thinking: builder.accumulated,
+                                                        signature: builder.signature,
+                                                    },
+                                                    "redacted_thinking" => {
+                                                        ContentBlock::RedactedThinking {
+                                                            data: builder.accumulated,
+                                                        }
+                                                    }
                                                     _ => continue,
                                                 };
 
@@ -409,4 +519,62 @@ mod tests {
         let provider = ClaudeProvider::new("test-key".to_string()).unwrap();
         assert_eq!(provider.name(), "claude");
     }
+
+    #[test]
+    fn test_thinking_budget() {
+        let medium = Some(Reasoning::Effort(reasoning::ReasoningEffort::Medium));
+        let ask = vec![Message::user("hi")];
+        assert_eq!(
+            thinking_budget(medium, "claude-sonnet-4-6", &ask),
+            Some(8_192)
+        );
+        assert_eq!(thinking_budget(None, "claude-sonnet-4-6", &ask), None);
+        assert_eq!(
+            thinking_budget(medium, "claude-3-5-haiku-latest", &ask),
+            None
+        );
+
+        // A tool call made without thinking is finished without it
+        let call = ContentBlock::ToolUse {
+            id: "t1".to_string(),
+            name: "read".to_string(),
+            input: serde_json::json!({}),
+        };
+        let result = Message {
+            role: "user".to_string(),
+            content: vec![ContentBlock::ToolResult {
+                tool_use_id: "t1".to_string(),
+                content: "ok".to_string(),
+                is_error: None,
+            }],
+        };
+        let without = vec![
+            ask[0].clone(),
+            Message {
+                role: "assistant".to_string(),
+                content: vec![call.clone()],
+            },
+            result.clone(),
+        ];
+        assert_eq!(thinking_budget(medium, "claude-sonnet-4-6", &without), None);
+
+        let with = vec![
+            ask[0].clone(),
+            Message {
+                role: "assistant".to_string(),
+                content: vec![
+                    ContentBlock::Thinking {
+                        thinking: "need the file".to_string(),
+                        signature: "sig".to_string(),
+                    },
+                    call,
+                ],
+            },
+            result,
+        ];
+        assert_eq!(
+            thinking_budget(medium, "claude-sonnet-4-6", &with),
+            Some(8_192)
+        );
+    }
 }
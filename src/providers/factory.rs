@@ -7,6 +7,7 @@ use anyhow::{bail, Context, Result};
 use super::claude::ClaudeProvider;
 use super::gemini::GeminiProvider;
 use super::openai::OpenAIProvider;
+use super::plugin::{create_plugin_provider, PluginProviderConfig};
 use super::LlmProvider;
 use crate::config::{ProviderEntry, TeacherEntry};
 
@@ -17,8 +18,16 @@ use crate::config::{ProviderEntry, TeacherEntry};
 /// Create a cloud `LlmProvider` from a unified `ProviderEntry`.
 ///
 /// Returns an error for `Local` variants — those use a different code path
-/// (`create_local_generator`).
+/// (`create_local_generator`).  The provider enforces the data-residency
+/// policy, if one is configured (see `crate::policy`), and redacts secrets in
+/// tool results (see `crate::redact`).
 pub fn create_provider_from_entry(entry: &ProviderEntry) -> Result<Box<dyn LlmProvider>> {
+    build_provider_from_entry(entry)
+        .map(super::redaction::protect)
+        .map(super::policy::enforce)
+}
+
+fn build_provider_from_entry(entry: &ProviderEntry) -> Result<Box<dyn LlmProvider>> {
     match entry {
         ProviderEntry::Claude { api_key, model, .. } => {
             let mut provider = ClaudeProvider::new(api_key.clone())?;
@@ -76,6 +85,21 @@ pub fn create_provider_from_entry(entry: &ProviderEntry) -> Result<Box<dyn LlmPr
             Ok(Box::new(OpenAIProvider::new_remote_daemon(address.clone())?))
         }
 
+        ProviderEntry::Plugin {
+            plugin,
+            api_key,
+            model,
+            base_url,
+            ..
+        } => create_plugin_provider(
+            plugin,
+            &PluginProviderConfig {
+                api_key: api_key.clone(),
+                model: model.clone(),
+                base_url: base_url.clone(),
+            },
+        ),
+
         ProviderEntry::Local { .. } => {
             bail!("Local providers use a local generator — call create_local_generator() instead")
         }
@@ -84,6 +108,9 @@ pub fn create_provider_from_entry(entry: &ProviderEntry) -> Result<Box<dyn LlmPr
 
 /// Create providers from a slice of unified `ProviderEntry` values.
 /// Only cloud entries are included; `Local` variants are silently skipped.
+///
+/// When `FINCH_CHAOS` is set each provider is wrapped in a
+/// `FaultInjectingProvider` (see `providers::chaos`).
 pub fn create_providers_from_entries(
     entries: &[ProviderEntry],
 ) -> Result<Vec<Box<dyn LlmProvider>>> {
@@ -91,14 +118,15 @@ pub fn create_providers_from_entries(
     if cloud.is_empty() {
         bail!("No cloud provider entries configured");
     }
-    cloud
+    let providers = cloud
         .iter()
         .enumerate()
         .map(|(idx, entry)| {
             create_provider_from_entry(entry)
                 .with_context(|| format!("Failed to create provider #{}", idx + 1))
         })
-        .collect()
+        .collect::<Result<Vec<_>>>()?;
+    Ok(super::chaos::wrap_from_env(providers))
 }
 
 /// Return a single `LlmProvider` from a slice of unified entries.
@@ -136,8 +164,16 @@ pub fn create_providers(teachers: &[TeacherEntry]) -> Result<Vec<Box<dyn LlmProv
         .collect()
 }
 
-/// Create a single provider from a `TeacherEntry`.
+/// Create a single provider from a `TeacherEntry`, enforcing the
+/// data-residency policy if one is configured and redacting secrets in tool
+/// results.
 pub fn create_provider_from_teacher(entry: &TeacherEntry) -> Result<Box<dyn LlmProvider>> {
+    build_provider_from_teacher(entry)
+        .map(super::redaction::protect)
+        .map(super::policy::enforce)
+}
+
+fn build_provider_from_teacher(entry: &TeacherEntry) -> Result<Box<dyn LlmProvider>> {
     match entry.provider.to_lowercase().as_str() {
         "claude" => {
             let mut provider = ClaudeProvider::new(entry.api_key.clone())?;
@@ -187,6 +223,15 @@ pub fn create_provider_from_teacher(entry: &TeacherEntry) -> Result<Box<dyn LlmP
             Ok(Box::new(provider))
         }
 
+        provider if provider.starts_with("plugin:") => create_plugin_provider(
+            &entry.provider["plugin:".len()..],
+            &PluginProviderConfig {
+                api_key: Some(entry.api_key.clone()).filter(|k| !k.is_empty()),
+                model: entry.model.clone(),
+                base_url: entry.base_url.clone(),
+            },
+        ),
+
         _ => bail!("Unknown provider: {}", entry.provider),
     }
 }
@@ -447,4 +492,32 @@ mod tests {
         let result = create_providers_from_entries(&entries);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_plugin_provider_entry_and_teacher() {
+        crate::providers::register_provider("test-factory-gateway", |config| {
+            let mut provider = ClaudeProvider::new(config.api_key.clone().unwrap_or_default())?;
+            if let Some(m) = &config.model {
+                provider = provider.with_model(m.clone());
+            }
+            Ok(Box::new(provider) as Box<dyn LlmProvider>)
+        });
+
+        let p = pentry(ProviderEntry::Plugin {
+            plugin: "test-factory-gateway".to_string(),
+            api_key: Some("key".to_string()),
+            model: Some("gateway-model".to_string()),
+            base_url: None,
+            name: None,
+        });
+        let provider = create_provider_from_entry(&p).unwrap();
+        assert_eq!(provider.default_model(), "gateway-model");
+
+        let teacher = p.to_teacher_entry().unwrap();
+        let provider = create_provider_from_teacher(&teacher).unwrap();
+        assert_eq!(provider.default_model(), "gateway-model");
+
+        let result = create_provider_from_teacher(&entry("plugin:not-installed", "key"));
+        assert!(result.is_err());
+    }
 }
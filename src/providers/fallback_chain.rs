@@ -1,12 +1,18 @@
 // Fallback chain for automatic provider retry
 //
-// Tries providers in priority order until one succeeds
+// Tries providers in priority order until one succeeds.  Providers whose
+// rate-limit window hasn't reset are skipped, and a 429 moves straight on to
+// the next provider; only when every provider is throttled does the chain
+// wait, for whichever window resets first.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
+use super::rate_limit::{self, RateLimited};
 use super::{LlmProvider, ProviderRequest, ProviderResponse, StreamChunk};
+use crate::config::constants::RATE_LIMIT_MAX_WAIT_SECS;
 
 /// A chain of providers to try in order
 pub struct FallbackChain {
@@ -36,6 +42,36 @@ impl FallbackChain {
         self.providers.first().map(|p| p.as_ref())
     }
 
+    /// Providers in priority order
+    pub fn providers(&self) -> impl Iterator<Item = &dyn LlmProvider> {
+        self.providers.iter().map(|p| p.as_ref())
+    }
+
+    /// The provider called `name` (case-insensitive)
+    pub fn provider(&self, name: &str) -> Option<&dyn LlmProvider> {
+        self.providers()
+            .find(|p| p.name().eq_ignore_ascii_case(name))
+    }
+
+    /// When every provider is rate limited, wait for the window that resets
+    /// first (if that is within `RATE_LIMIT_MAX_WAIT_SECS`) and return its
+    /// provider
+    async fn wait_for_first_reset(&self) -> Option<&dyn LlmProvider> {
+        let mut first: Option<(&dyn LlmProvider, Duration)> = None;
+        for provider in self.providers.iter() {
+            let wait = rate_limit::remaining(provider.name())?;
+            if first.is_none_or(|(_, w)| wait < w) {
+                first = Some((provider.as_ref(), wait));
+            }
+        }
+        let (provider, wait) = first?;
+        if wait > Duration::from_secs(RATE_LIMIT_MAX_WAIT_SECS) {
+            return None;
+        }
+        rate_limit::wait_for_reset(provider.name(), wait, rate_limit::wait_status()).await;
+        Some(provider)
+    }
+
     /// Try sending message with automatic fallback
     pub async fn send_message_with_fallback(
         &self,
@@ -51,30 +87,13 @@ impl FallbackChain {
                 self.providers.len()
             );
 
-            // Create a modified request with this provider's model ID,
-            // sanitizing orphaned tool_use blocks from previous failed providers
-            let mut provider_request = ProviderRequest {
-                model: provider.default_model().to_string(),
-                messages: request.messages.clone(),
-                max_tokens: request.max_tokens,
-                tools: request.tools.clone(),
-                temperature: request.temperature,
-                stream: request.stream,
-                system: request.system.clone(),
-            };
-            let dropped =
-                provider_request.truncate_to_context_limit(provider.context_limit_tokens());
-            if dropped > 0 {
-                tracing::debug!(
-                    provider = provider.name(),
-                    dropped_messages = dropped,
-                    context_limit = provider.context_limit_tokens(),
-                    "Truncated conversation history to fit provider context window"
-                );
+            if let Some(throttled) = skip_if_throttled(provider.as_ref()) {
+                last_error = Some(throttled);
+                continue;
             }
-            provider_request.sanitize_messages();
+            let provider_request = request_for(provider.as_ref(), request);
 
-            match provider.send_message(&provider_request).await {
+            match rate_limit::without_waiting(provider.send_message(&provider_request)).await {
                 Ok(response) => {
                     if idx > 0 {
                         tracing::info!(
@@ -101,6 +120,14 @@ impl FallbackChain {
             }
         }
 
+        if let Some(provider) = self.wait_for_first_reset().await {
+            let provider_request = request_for(provider, request);
+            return provider
+                .send_message(&provider_request)
+                .await
+                .context("All fallback providers were rate limited");
+        }
+
         Err(last_error
             .unwrap_or_else(|| anyhow::anyhow!("No providers available"))
             .context("All fallback providers failed"))
@@ -115,6 +142,17 @@ impl FallbackChain {
         &self,
         request: &ProviderRequest,
     ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        self.send_message_stream_with_provider(request)
+            .await
+            .map(|(_, receiver)| receiver)
+    }
+
+    /// Like `send_message_stream_with_fallback`, also returning the provider
+    /// that accepted the stream
+    pub async fn send_message_stream_with_provider(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<(&dyn LlmProvider, mpsc::Receiver<Result<StreamChunk>>)> {
         let mut last_error = None;
 
         for (idx, provider) in self.providers.iter().enumerate() {
@@ -125,30 +163,14 @@ impl FallbackChain {
                 self.providers.len()
             );
 
-            // Create a modified request with this provider's model ID,
-            // sanitizing orphaned tool_use blocks from previous failed providers
-            let mut provider_request = ProviderRequest {
-                model: provider.default_model().to_string(),
-                messages: request.messages.clone(),
-                max_tokens: request.max_tokens,
-                tools: request.tools.clone(),
-                temperature: request.temperature,
-                stream: request.stream,
-                system: request.system.clone(),
-            };
-            let dropped =
-                provider_request.truncate_to_context_limit(provider.context_limit_tokens());
-            if dropped > 0 {
-                tracing::debug!(
-                    provider = provider.name(),
-                    dropped_messages = dropped,
-                    context_limit = provider.context_limit_tokens(),
-                    "Truncated conversation history to fit provider context window"
-                );
+            if let Some(throttled) = skip_if_throttled(provider.as_ref()) {
+                last_error = Some(throttled);
+                continue;
             }
-            provider_request.sanitize_messages();
+            let provider_request = request_for(provider.as_ref(), request);
 
-            match provider.send_message_stream(&provider_request).await {
+            match rate_limit::without_waiting(provider.send_message_stream(&provider_request)).await
+            {
                 Ok(receiver) => {
                     if idx > 0 {
                         tracing::info!(
@@ -160,7 +182,7 @@ impl FallbackChain {
                         tracing::debug!("Primary provider {} streaming succeeded", provider.name());
                     }
                     // Return provider's receiver DIRECTLY (no wrapper, no race condition)
-                    return Ok(receiver);
+                    return Ok((provider.as_ref(), receiver));
                 }
                 Err(e) => {
                     tracing::warn!(
@@ -176,12 +198,66 @@ impl FallbackChain {
             }
         }
 
+        if let Some(provider) = self.wait_for_first_reset().await {
+            let provider_request = request_for(provider, request);
+            return provider
+                .send_message_stream(&provider_request)
+                .await
+                .map(|receiver| (provider, receiver))
+                .context("All fallback providers were rate limited");
+        }
+
         Err(last_error
             .unwrap_or_else(|| anyhow::anyhow!("No providers available for streaming"))
             .context("All fallback providers failed for streaming"))
     }
 }
 
+/// `request` adapted to `provider`: its model ID, history truncated to its
+/// context window and orphaned tool_use blocks from previous failed providers
+/// removed
+fn request_for(provider: &dyn LlmProvider, request: &ProviderRequest) -> ProviderRequest {
+    let mut provider_request = ProviderRequest {
+        model: provider.default_model().to_string(),
+        messages: request.messages.clone(),
+        max_tokens: request.max_tokens,
+        tools: request.tools.clone(),
+        temperature: request.temperature,
+        stream: request.stream,
+        system: request.system.clone(),
+    };
+    let dropped = provider_request.truncate_to_context_limit(provider.context_limit_tokens());
+    if dropped > 0 {
+        tracing::debug!(
+            provider = provider.name(),
+            dropped_messages = dropped,
+            context_limit = provider.context_limit_tokens(),
+            "Truncated conversation history to fit provider context window"
+        );
+    }
+    provider_request.sanitize_messages();
+    provider_request
+}
+
+/// The error to report for `provider` when its rate-limit window hasn't
+/// reset, so the chain doesn't send it another request
+fn skip_if_throttled(provider: &dyn LlmProvider) -> Option<anyhow::Error> {
+    let retry_after = rate_limit::remaining(provider.name())?;
+    tracing::info!(
+        "Skipping rate-limited provider {} (resets in {}s)",
+        provider.name(),
+        rate_limit::ceil_secs(retry_after)
+    );
+    Some(
+        RateLimited {
+            provider: provider.name().to_string(),
+            retry_after,
+            message: format!("{} is rate limited", provider.name()),
+        }
+        .into(),
+    )
+}
+
 // Implement LlmProvider trait for FallbackChain
 #[async_trait::async_trait]
 impl LlmProvider for FallbackChain {
@@ -219,6 +295,14 @@ impl LlmProvider for FallbackChain {
             .map(|p| p.supports_tools())
             .unwrap_or(false)
     }
+
+    /// Any provider in the chain may be the one still answering
+    async fn cancel_generation(&self) -> Result<()> {
+        for provider in self.providers.iter() {
+            provider.cancel_generation().await?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -294,6 +378,58 @@ mod tests {
         }
     }
 
+    /// Answers every request with a 429 that resets in 30s
+    struct ThrottledProvider {
+        name: &'static str,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl ThrottledProvider {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                calls: Default::default(),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        fn throttle(&self) -> anyhow::Error {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            rate_limit::record(self.name, Duration::from_secs(30));
+            RateLimited {
+                provider: self.name.to_string(),
+                retry_after: Duration::from_secs(30),
+                message: "API error 429 Too Many Requests".to_string(),
+            }
+            .into()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for Arc<ThrottledProvider> {
+        async fn send_message(&self, _request: &ProviderRequest) -> Result<ProviderResponse> {
+            Err(self.throttle())
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+            Err(self.throttle())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            "test-model"
+        }
+    }
+
     #[tokio::test]
     async fn test_primary_provider_succeeds() {
         let providers: Vec<Box<dyn LlmProvider>> = vec![
@@ -382,5 +518,49 @@ mod tests {
 
         let result = chain.send_message_stream_with_fallback(&request).await;
         assert!(result.is_ok());
+
+        let (provider, _) = chain
+            .send_message_stream_with_provider(&request)
+            .await
+            .unwrap();
+        assert_eq!(provider.name(), "fallback");
+        assert_eq!(chain.provider("FALLBACK").unwrap().name(), "fallback");
+        assert!(chain.provider("missing").is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_provider_is_skipped_until_reset() {
+        let primary = Arc::new(ThrottledProvider::new("chain-throttled-primary"));
+        let providers: Vec<Box<dyn LlmProvider>> = vec![
+            Box::new(Arc::clone(&primary)),
+            Box::new(MockProvider::new("fallback", false)),
+        ];
+        let chain = FallbackChain::new(providers);
+        let request = ProviderRequest::new(vec![]);
+        let start = tokio::time::Instant::now();
+
+        // The 429 moves straight on to the fallback...
+        let response = chain.send_message_with_fallback(&request).await.unwrap();
+        assert_eq!(response.provider, "fallback");
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // ...and the throttled provider isn't asked again before its reset
+        let response = chain.send_message_with_fallback(&request).await.unwrap();
+        assert_eq!(response.provider, "fallback");
+        assert_eq!(primary.calls(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_all_rate_limited_waits_for_first_reset() {
+        let only = Arc::new(ThrottledProvider::new("chain-throttled-only"));
+        let chain = FallbackChain::new(vec![Box::new(Arc::clone(&only))]);
+        let request = ProviderRequest::new(vec![]);
+        let start = tokio::time::Instant::now();
+
+        let result = chain.send_message_with_fallback(&request).await;
+        assert!(result.is_err());
+        // One attempt, then one more once the 30s window had reset
+        assert_eq!(only.calls(), 2);
+        assert!(start.elapsed() >= Duration::from_secs(29));
     }
 }
@@ -12,6 +12,7 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use super::rate_limit::response_error;
 use super::types::{ProviderRequest, ProviderResponse, StreamChunk};
 use super::LlmProvider;
 use crate::claude::retry::with_retry;
@@ -33,7 +34,7 @@ pub struct GeminiProvider {
 impl GeminiProvider {
     /// Create a new Gemini provider
     pub fn new(api_key: String) -> Result<Self> {
-        let client = Client::builder()
+        let client = crate::http::builder()
             .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
             .build()
             .context("Failed to create HTTP client")?;
@@ -75,6 +76,7 @@ impl GeminiProvider {
                 let parts: Vec<GeminiPart> = msg
                     .content
                     .iter()
+                    .filter(|block| !block.is_thinking())
                     .map(|block| match block {
                         ContentBlock::Text { text } => GeminiPart::Text { text: text.clone() },
                         ContentBlock::ToolUse { id: _, name, input } => GeminiPart::FunctionCall {
@@ -99,6 +101,9 @@ impl GeminiProvider {
                         ContentBlock::Image { .. } => GeminiPart::Text {
                             text: "[image content]".to_string(),
                         },
+                        ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {
+                            unreachable!("thinking is filtered out above")
+                        }
                     })
                     .collect();
 
@@ -223,12 +228,13 @@ impl GeminiProvider {
         let status = response.status();
 
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Gemini API request failed\n\nStatus: {}\nBody: {}",
-                status,
-                error_body
-            );
+            return Err(response_error("gemini", response, |status, body| {
+                format!(
+                    "Gemini API request failed\n\nStatus: {}\nBody: {}",
+                    status, body
+                )
+            })
+            .await);
         }
 
         let gemini_response: GeminiResponse = response
@@ -269,12 +275,13 @@ impl GeminiProvider {
 
         let status = response.status();
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Gemini API streaming request failed\n\nStatus: {}\nBody: {}",
-                status,
-                error_body
-            );
+            return Err(response_error("gemini", response, |status, body| {
+                format!(
+                    "Gemini API streaming request failed\n\nStatus: {}\nBody: {}",
+                    status, body
+                )
+            })
+            .await);
         }
 
         // Spawn task to parse streaming response
@@ -21,6 +21,24 @@ pub mod factory;
 // Fallback chain (not used in student-teacher architecture)
 pub mod fallback_chain;
 
+// Fault injection for testing failure paths (enabled via FINCH_CHAOS)
+pub mod chaos;
+
+// Rate-limit headers, reset tracking and countdowns
+pub mod rate_limit;
+
+// Data-residency policy enforcement (rules from policy.toml)
+pub mod policy;
+
+// Secret redaction in tool results sent to cloud providers ([redaction])
+pub mod redaction;
+
+// Third-party providers: in-process registration and ~/.finch/providers.d
+pub mod plugin;
+
+// Thinking budgets and effort levels for reasoning models ([reasoning])
+pub mod reasoning;
+
 // Teacher session management with context optimization
 pub mod teacher_session;
 
@@ -33,7 +51,13 @@ pub use factory::{
     create_provider, create_provider_from_entries, create_provider_from_entry,
     create_provider_from_teacher, create_providers, create_providers_from_entries,
 };
+pub use chaos::{ChaosConfig, FaultInjectingProvider};
 pub use fallback_chain::FallbackChain;
+pub use plugin::{register_provider, PluginProviderConfig};
+pub use policy::PolicyEnforcingProvider;
+pub use rate_limit::RateLimited;
+pub use reasoning::{Reasoning, ReasoningConfig, ReasoningEffort};
+pub use redaction::RedactingProvider;
 pub use teacher_session::{
     ConversationState, OptimizationStats, TeacherContextConfig, TeacherSession,
 };
@@ -82,6 +106,15 @@ pub trait LlmProvider: Send + Sync {
     fn context_limit_tokens(&self) -> usize {
         128_000 // Conservative default; providers override as needed
     }
+
+    /// Stop whatever the provider's backend is still generating for us.
+    ///
+    /// Dropping a request already closes the connection, which is all most
+    /// providers need; a finch daemon also has to be told to stop its own
+    /// upstream call.
+    async fn cancel_generation(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Helper to convert provider response to format compatible with existing code
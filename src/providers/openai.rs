@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
+use super::rate_limit::response_error;
+use super::reasoning::{self, Reasoning, ReasoningEffort};
 use super::types::{ProviderRequest, ProviderResponse, StreamChunk};
 use super::LlmProvider;
 use crate::claude::retry::with_retry;
@@ -108,6 +110,8 @@ pub struct OpenAIProvider {
     base_url: String,
     default_model: String,
     provider_name: String,
+    /// Session id sent to a finch daemon so it can cancel our requests
+    session_id: Option<String>,
 }
 
 impl OpenAIProvider {
@@ -167,14 +171,17 @@ impl OpenAIProvider {
 
     /// Create a provider that talks to a remote finch daemon's OpenAI-compatible endpoint.
     ///
-    /// The daemon exposes `/v1/chat/completions` at `address`.
+    /// The daemon exposes `/v1/chat/completions` at `address`.  Requests are
+    /// tagged with a session id so `cancel_generation` can stop them there.
     pub fn new_remote_daemon(address: String) -> Result<Self> {
-        Self::new(
+        let mut provider = Self::new(
             String::new(), // no API key for the local/remote daemon
             address,
             "default".to_string(),
             "remote_daemon".to_string(),
-        )
+        )?;
+        provider.session_id = Some(uuid::Uuid::new_v4().to_string());
+        Ok(provider)
     }
 
     /// Set custom model for this provider
@@ -190,7 +197,7 @@ impl OpenAIProvider {
         default_model: String,
         provider_name: String,
     ) -> Result<Self> {
-        let client = Client::builder()
+        let client = crate::http::builder()
             .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
             .build()
             .context("Failed to create HTTP client")?;
@@ -201,6 +208,7 @@ impl OpenAIProvider {
             base_url,
             default_model,
             provider_name,
+            session_id: None,
         })
     }
 
@@ -292,7 +300,9 @@ impl OpenAIProvider {
                             ContentBlock::Image { .. } => {
                                 text_parts.push("[image]");
                             }
-                            ContentBlock::ToolUse { .. } => {}
+                            ContentBlock::ToolUse { .. }
+                            | ContentBlock::Thinking { .. }
+                            | ContentBlock::RedactedThinking { .. } => {}
                         }
                     }
 
@@ -352,14 +362,33 @@ impl OpenAIProvider {
                 .collect()
         });
 
-        OpenAIRequest {
+        let mut openai_request = OpenAIRequest {
             model,
             messages,
             max_tokens: Some(request.max_tokens),
+            max_completion_tokens: None,
             temperature: request.temperature,
+            reasoning_effort: None,
             tools,
             stream: request.stream,
+            stream_options: None,
+            session_id: self.session_id.clone(),
+        };
+
+        // Reasoning models reject max_tokens and temperature, and their
+        // completion limit covers the hidden reasoning too
+        if reasoning::openai_is_reasoning_model(&openai_request.model) {
+            let configured = reasoning::configured();
+            let effort = configured
+                .map(Reasoning::effort)
+                .unwrap_or(ReasoningEffort::Medium);
+            openai_request.max_tokens = None;
+            openai_request.max_completion_tokens =
+                Some(request.max_tokens.saturating_add(effort.budget_tokens()));
+            openai_request.temperature = None;
+            openai_request.reasoning_effort = configured.map(|r| r.effort().as_str());
         }
+        openai_request
     }
 
     /// Convert OpenAI response to ProviderResponse
@@ -373,6 +402,16 @@ impl OpenAIProvider {
         // Convert message content to ContentBlock
         let mut content = Vec::new();
 
+        // Kept for display only; unsigned, so never sent back to Claude
+        if let Some(thinking) = choice.message.reasoning_content {
+            if !thinking.is_empty() {
+                content.push(ContentBlock::Thinking {
+                    thinking,
+                    signature: String::new(),
+                });
+            }
+        }
+
         if let Some(text) = choice.message.content {
             if !text.is_empty() {
                 content.push(ContentBlock::Text { text });
@@ -424,8 +463,7 @@ impl OpenAIProvider {
         let status = response.status();
 
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}", friendly_api_error(status, &error_body));
+            return Err(response_error(&self.provider_name, response, friendly_api_error).await);
         }
 
         let openai_response: OpenAIResponse = response
@@ -447,6 +485,10 @@ impl OpenAIProvider {
 
         let mut openai_request = self.to_openai_request(request);
         openai_request.stream = true;
+        if openai_request.max_completion_tokens.is_some() {
+            // The final chunk then reports how many reasoning tokens were used
+            openai_request.stream_options = Some(serde_json::json!({ "include_usage": true }));
+        }
 
         let url = format!("{}/v1/chat/completions", self.base_url);
 
@@ -464,8 +506,7 @@ impl OpenAIProvider {
 
         let status = response.status();
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            anyhow::bail!("{}", friendly_api_error(status, &error_body));
+            return Err(response_error(&self.provider_name, response, friendly_api_error).await);
         }
 
         // Spawn task to parse SSE stream
@@ -546,7 +587,33 @@ impl OpenAIProvider {
                                 if let Ok(stream_chunk) =
                                     serde_json::from_str::<OpenAIStreamChunk>(json_str)
                                 {
+                                    let reasoning_tokens = stream_chunk
+                                        .usage
+                                        .and_then(|u| u.completion_tokens_details)
+                                        .and_then(|d| d.reasoning_tokens);
+                                    if let Some(n) = reasoning_tokens {
+                                        let _ = tx.send(Ok(StreamChunk::ReasoningTokens(n))).await;
+                                    }
+
                                     if let Some(choice) = stream_chunk.choices.into_iter().next() {
+                                        // DeepSeek, Ollama and xAI stream their reasoning
+                                        // as `reasoning_content`, OpenRouter as `reasoning`
+                                        if let Some(thinking) = choice
+                                            .delta
+                                            .reasoning_content
+                                            .or(choice.delta.reasoning)
+                                            .filter(|t| !t.is_empty())
+                                        {
+                                            if tx
+                                                .send(Ok(StreamChunk::ThinkingDelta(thinking)))
+                                                .await
+                                                .is_err()
+                                            {
+                                                done = true;
+                                                break;
+                                            }
+                                        }
+
                                         if let Some(content) = choice.delta.content {
                                             accumulated_text.push_str(&content);
                                             // Send delta immediately
@@ -626,6 +693,28 @@ impl LlmProvider for OpenAIProvider {
             _ => 120_000,
         }
     }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        let Some(session_id) = &self.session_id else {
+            return Ok(());
+        };
+        let url = format!(
+            "{}/v1/sessions/{}/active-generation",
+            self.base_url, session_id
+        );
+        let response = self
+            .client
+            .delete(&url)
+            .send()
+            .await
+            .context("Failed to cancel generation on the daemon")?;
+        // 404: the daemon had already finished
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            return Err(response_error(&self.provider_name, response, friendly_api_error).await);
+        }
+        Ok(())
+    }
 }
 
 // OpenAI API types
@@ -636,12 +725,22 @@ struct OpenAIRequest {
     messages: Vec<OpenAIMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    /// Replaces `max_tokens` for reasoning models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<OpenAITool>>,
     #[serde(skip_serializing_if = "is_false")]
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
+    /// Finch daemon extension; other servers ignore unknown fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -723,6 +822,8 @@ struct OpenAIResponseMessage {
     role: String,
     content: Option<String>,
     tool_calls: Option<Vec<OpenAIToolCall>>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -747,6 +848,21 @@ struct OpenAIToolFunction {
 struct OpenAIStreamChunk {
     id: String,
     choices: Vec<OpenAIStreamChoice>,
+    /// Only on the final chunk, when `stream_options.include_usage` is set
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    completion_tokens_details: Option<OpenAICompletionTokensDetails>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OpenAICompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -763,6 +879,10 @@ struct OpenAIDelta {
     role: Option<String>,
     content: Option<String>,
     tool_calls: Option<Vec<OpenAIToolCallDelta>>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    reasoning: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -832,6 +952,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_openai_request_reasoning_model() {
+        let provider = OpenAIProvider::new_openai("key".to_string()).unwrap();
+        use crate::claude::types::Message;
+        use crate::providers::types::ProviderRequest;
+        let req = ProviderRequest::new(vec![Message::user("hello")])
+            .with_model("o3-mini")
+            .with_max_tokens(1000)
+            .with_temperature(0.5);
+        let openai_req = provider.to_openai_request(&req);
+        assert_eq!(openai_req.max_tokens, None);
+        assert_eq!(openai_req.max_completion_tokens, Some(1000 + 8_192));
+        assert_eq!(openai_req.temperature, None);
+
+        let req = req.with_model("gpt-4o");
+        let openai_req = provider.to_openai_request(&req);
+        assert_eq!(openai_req.max_tokens, Some(1000));
+        assert_eq!(openai_req.temperature, Some(0.5));
+    }
+
+    #[test]
+    fn test_parse_reasoning_stream_chunks() {
+        let chunk: OpenAIStreamChunk = serde_json::from_str(
+            r#"{"id":"c1","choices":[{"index":0,"delta":{"reasoning_content":"hmm"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            chunk.choices[0].delta.reasoning_content.as_deref(),
+            Some("hmm")
+        );
+
+        let usage: OpenAIStreamChunk = serde_json::from_str(
+            r#"{"id":"c1","choices":[],"usage":{"completion_tokens":90,"completion_tokens_details":{"reasoning_tokens":64}}}"#,
+        )
+        .unwrap();
+        let reasoning_tokens = usage
+            .usage
+            .and_then(|u| u.completion_tokens_details)
+            .and_then(|d| d.reasoning_tokens);
+        assert_eq!(reasoning_tokens, Some(64));
+    }
+
     #[test]
     fn test_to_openai_request_no_system_prompt() {
         let provider = OpenAIProvider::new_openai("key".to_string()).unwrap();
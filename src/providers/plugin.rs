@@ -0,0 +1,582 @@
+// Third-party providers
+//
+// Providers finch doesn't ship (internal gateways, niche APIs) can be added
+// without patching the factory:
+// - programs embedding finch call `register_provider("acme", ...)`
+// - shared libraries in ~/.finch/providers.d (feature `provider-plugins`) are
+//   `cdylib` crates built against finch that export a registration function
+//   with `export_provider_plugin!`
+//
+// Either way the provider is configured like a built-in one:
+//
+//   [[providers]]
+//   type = "plugin"
+//   plugin = "acme"
+//   api_key = "..."
+//   model = "acme-large"
+//
+// A plugin library carries its own copy of finch's dependencies, tokio
+// included, so its futures can't be polled on the host's runtime.  Each
+// library runs its providers on a runtime of its own and hands results back
+// through callbacks (`PluginBackend`), so only plain data crosses the
+// boundary.  Data layouts still have to agree: libraries must be built with
+// the same finch version and Rust toolchain, which loading checks.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once, OnceLock, RwLock};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{LlmProvider, ProviderRequest, ProviderResponse, StreamChunk};
+
+/// finch version plugin libraries must be built against
+pub const FINCH_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Rust toolchain plugin libraries must be built with (set by build.rs)
+pub const RUSTC_VERSION: &str = env!("FINCH_RUSTC_VERSION");
+
+/// Symbol of the `PluginDeclaration` a plugin library exports
+#[cfg(feature = "provider-plugins")]
+const DECLARATION_SYMBOL: &[u8] = b"finch_provider_plugin\0";
+
+/// Settings of a `type = "plugin"` provider entry, passed to the plugin
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PluginProviderConfig {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+}
+
+/// Builds a registered provider from its config entry
+pub type ProviderConstructor =
+    Arc<dyn Fn(&PluginProviderConfig) -> Result<Box<dyn LlmProvider>> + Send + Sync>;
+
+fn registry() -> &'static RwLock<HashMap<String, ProviderConstructor>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, ProviderConstructor>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Make provider kind `kind` available to `type = "plugin"` entries.  A
+/// later registration of the same kind replaces the earlier one.
+pub fn register_provider<F>(kind: &str, constructor: F)
+where
+    F: Fn(&PluginProviderConfig) -> Result<Box<dyn LlmProvider>> + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap()
+        .insert(kind.to_string(), Arc::new(constructor));
+}
+
+/// Registered provider kinds, sorted, including those of installed plugins
+pub fn registered_kinds() -> Vec<String> {
+    load_installed_plugins();
+    let mut kinds: Vec<String> = registry().read().unwrap().keys().cloned().collect();
+    kinds.sort();
+    kinds
+}
+
+/// Create a provider of registered kind `kind`
+pub fn create_plugin_provider(
+    kind: &str,
+    config: &PluginProviderConfig,
+) -> Result<Box<dyn LlmProvider>> {
+    load_installed_plugins();
+    let constructor = registry().read().unwrap().get(kind).cloned();
+    let Some(constructor) = constructor else {
+        bail!(
+            "Unknown plugin provider '{}' (no plugin in {} registers it)",
+            kind,
+            plugins_dir().map_or_else(
+                || "~/.finch/providers.d".into(),
+                |d| d.display().to_string()
+            )
+        );
+    };
+    constructor(config).with_context(|| format!("Plugin provider '{}' failed to start", kind))
+}
+
+// ---------------------------------------------------------------------------
+// Library boundary
+// ---------------------------------------------------------------------------
+
+/// A provider inside a plugin library.  Calls return at once; results come
+/// back through the callbacks, which run on the library's runtime.
+pub trait PluginBackend: Send + Sync {
+    fn name(&self) -> &str;
+    fn default_model(&self) -> &str;
+    fn supports_streaming(&self) -> bool;
+    fn supports_tools(&self) -> bool;
+    fn context_limit_tokens(&self) -> usize;
+
+    /// Send `request`; `done` is called once with the outcome
+    fn send(
+        &self,
+        request: ProviderRequest,
+        done: Box<dyn FnOnce(Result<ProviderResponse>) + Send>,
+    );
+
+    /// Stream `request` into `sink`, which returns false once nobody is
+    /// listening.  Dropping `sink` ends the stream.
+    fn stream(
+        &self,
+        request: ProviderRequest,
+        sink: Box<dyn FnMut(Result<StreamChunk>) -> bool + Send>,
+    );
+}
+
+/// The runtime plugin providers run on.  Each plugin library has its own
+/// copy of this function and so its own runtime.
+fn plugin_runtime() -> tokio::runtime::Handle {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .thread_name("finch-plugin")
+                .enable_all()
+                .build()
+                .expect("plugin runtime")
+        })
+        .handle()
+        .clone()
+}
+
+/// Runs an `LlmProvider` on the plugin runtime
+struct Hosted<P> {
+    provider: Arc<P>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl<P: LlmProvider + 'static> PluginBackend for Hosted<P> {
+    fn name(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.provider.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.provider.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.provider.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.provider.context_limit_tokens()
+    }
+
+    fn send(
+        &self,
+        request: ProviderRequest,
+        done: Box<dyn FnOnce(Result<ProviderResponse>) + Send>,
+    ) {
+        let provider = Arc::clone(&self.provider);
+        self.runtime.spawn(async move {
+            done(provider.send_message(&request).await);
+        });
+    }
+
+    fn stream(
+        &self,
+        request: ProviderRequest,
+        mut sink: Box<dyn FnMut(Result<StreamChunk>) -> bool + Send>,
+    ) {
+        let provider = Arc::clone(&self.provider);
+        self.runtime.spawn(async move {
+            match provider.send_message_stream(&request).await {
+                Ok(mut chunks) => {
+                    while let Some(chunk) = chunks.recv().await {
+                        if !sink(chunk) {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    sink(Err(e));
+                }
+            }
+        });
+    }
+}
+
+/// A plugin library's provider as an `LlmProvider`
+pub struct PluginProvider {
+    backend: Box<dyn PluginBackend>,
+}
+
+impl PluginProvider {
+    pub fn new(backend: Box<dyn PluginBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for PluginProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        let (tx, rx) = oneshot::channel();
+        self.backend.send(
+            request.clone(),
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        rx.await
+            .with_context(|| format!("{} dropped the request", self.backend.name()))?
+    }
+
+    async fn send_message_stream(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        // The sink runs on the plugin's runtime, where only a non-blocking
+        // send is safe; a task of ours moves chunks to the bounded channel
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel();
+        self.backend.stream(
+            request.clone(),
+            Box::new(move |chunk| sink_tx.send(chunk).is_ok()),
+        );
+        let (tx, rx) = mpsc::channel(32);
+        tokio::spawn(async move {
+            while let Some(chunk) = sink_rx.recv().await {
+                if tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    fn name(&self) -> &str {
+        self.backend.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.backend.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.backend.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.backend.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.backend.context_limit_tokens()
+    }
+}
+
+/// Builds a plugin library's provider from its config entry
+pub type BackendConstructor =
+    Box<dyn Fn(&PluginProviderConfig) -> Result<Box<dyn PluginBackend>> + Send + Sync>;
+
+/// Collects the providers a plugin library registers
+#[derive(Default)]
+pub struct ProviderRegistrar {
+    providers: Vec<(String, BackendConstructor)>,
+}
+
+impl ProviderRegistrar {
+    /// Register provider kind `kind`, built by `constructor`
+    pub fn register<P, F>(&mut self, kind: &str, constructor: F)
+    where
+        P: LlmProvider + 'static,
+        F: Fn(&PluginProviderConfig) -> Result<P> + Send + Sync + 'static,
+    {
+        self.providers.push((
+            kind.to_string(),
+            Box::new(move |config| {
+                let runtime = plugin_runtime();
+                // Constructors may build clients that expect a runtime
+                let _guard = runtime.enter();
+                let provider = Arc::new(constructor(config)?);
+                Ok(Box::new(Hosted { provider, runtime }) as Box<dyn PluginBackend>)
+            }),
+        ));
+    }
+
+    /// The registered kinds and their constructors
+    pub fn into_providers(self) -> Vec<(String, BackendConstructor)> {
+        self.providers
+    }
+}
+
+/// What a plugin library exports; see `export_provider_plugin!`
+pub struct PluginDeclaration {
+    pub finch_version: &'static str,
+    pub rustc_version: &'static str,
+    pub register: fn(&mut ProviderRegistrar),
+}
+
+/// Export a plugin library's registration function:
+///
+/// ```ignore
+/// fn register(registrar: &mut finch::providers::plugin::ProviderRegistrar) {
+///     registrar.register("acme", |config| AcmeProvider::new(config));
+/// }
+///
+/// finch::export_provider_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! export_provider_plugin {
+    ($register:path) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static finch_provider_plugin: $crate::providers::plugin::PluginDeclaration =
+            $crate::providers::plugin::PluginDeclaration {
+                finch_version: $crate::providers::plugin::FINCH_VERSION,
+                rustc_version: $crate::providers::plugin::RUSTC_VERSION,
+                register: $register,
+            };
+    };
+}
+
+// ---------------------------------------------------------------------------
+// Discovery
+// ---------------------------------------------------------------------------
+
+/// Where plugin libraries are installed
+pub fn plugins_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".finch").join("providers.d"))
+}
+
+/// Load the libraries in ~/.finch/providers.d, once per process
+fn load_installed_plugins() {
+    static LOADED: Once = Once::new();
+    LOADED.call_once(|| {
+        if let Some(dir) = plugins_dir() {
+            load_plugins_from(&dir);
+        }
+    });
+}
+
+/// Load every plugin library in `dir` and return the provider kinds they
+/// registered.  Libraries that fail to load are logged and skipped.
+#[cfg(feature = "provider-plugins")]
+pub fn load_plugins_from(dir: &Path) -> Vec<String> {
+    let mut kinds = Vec::new();
+    for path in library_files(dir) {
+        match load_library(&path) {
+            Ok(registered) => {
+                tracing::info!(
+                    "[providers] loaded {}: {}",
+                    path.display(),
+                    registered.join(", ")
+                );
+                kinds.extend(registered);
+            }
+            Err(e) => tracing::warn!("[providers] skipping {}: {:#}", path.display(), e),
+        }
+    }
+    kinds
+}
+
+/// Without plugin support, installed libraries are only reported
+#[cfg(not(feature = "provider-plugins"))]
+pub fn load_plugins_from(dir: &Path) -> Vec<String> {
+    let libraries = library_files(dir);
+    if !libraries.is_empty() {
+        tracing::warn!(
+            "[providers] ignoring {} plugin(s) in {}: finch was built without the provider-plugins feature",
+            libraries.len(),
+            dir.display()
+        );
+    }
+    Vec::new()
+}
+
+/// Shared libraries in `dir`, sorted by name
+fn library_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path| {
+        path.is_file()
+            && path.extension().and_then(|e| e.to_str()) == Some(std::env::consts::DLL_EXTENSION)
+    });
+    files.sort();
+    files
+}
+
+#[cfg(feature = "provider-plugins")]
+fn load_library(path: &Path) -> Result<Vec<String>> {
+    // SAFETY: loading runs the library's initialisers; libraries in
+    // providers.d are trusted like the finch binary itself
+    let library = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    // SAFETY: the symbol is the static `export_provider_plugin!` defines
+    let declaration = unsafe {
+        let symbol = library
+            .get::<*const PluginDeclaration>(DECLARATION_SYMBOL)
+            .context("not a finch provider plugin (no export_provider_plugin!)")?;
+        &**symbol
+    };
+    if declaration.finch_version != FINCH_VERSION || declaration.rustc_version != RUSTC_VERSION {
+        bail!(
+            "built for finch {} with {}, but this is finch {} built with {}; rebuild the plugin",
+            declaration.finch_version,
+            declaration.rustc_version,
+            FINCH_VERSION,
+            RUSTC_VERSION
+        );
+    }
+
+    let mut registrar = ProviderRegistrar::default();
+    (declaration.register)(&mut registrar);
+    // The providers' code lives in the library: keep it loaded for good
+    std::mem::forget(library);
+
+    let mut kinds = Vec::new();
+    for (kind, constructor) in registrar.into_providers() {
+        register_provider(&kind, move |config| {
+            Ok(Box::new(PluginProvider::new(constructor(config)?)) as Box<dyn LlmProvider>)
+        });
+        kinds.push(kind);
+    }
+    Ok(kinds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::ContentBlock;
+
+    /// Replies with its configured model; streams the words of "one two"
+    struct ModelEcho {
+        model: String,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ModelEcho {
+        async fn send_message(&self, _request: &ProviderRequest) -> Result<ProviderResponse> {
+            // Plugin providers run inside the plugin runtime
+            let worker = std::thread::current().name().map(str::to_string);
+            Ok(ProviderResponse {
+                id: "1".to_string(),
+                model: self.model.clone(),
+                content: vec![ContentBlock::Text {
+                    text: worker.unwrap_or_default(),
+                }],
+                stop_reason: Some("end_turn".to_string()),
+                role: "assistant".to_string(),
+                provider: "model-echo".to_string(),
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+            let (tx, rx) = mpsc::channel(1);
+            tokio::spawn(async move {
+                for word in ["one ", "two"] {
+                    let _ = tx.send(Ok(StreamChunk::TextDelta(word.to_string()))).await;
+                }
+            });
+            Ok(rx)
+        }
+
+        fn name(&self) -> &str {
+            "model-echo"
+        }
+
+        fn default_model(&self) -> &str {
+            &self.model
+        }
+    }
+
+    fn model_echo(config: &PluginProviderConfig) -> Result<ModelEcho> {
+        Ok(ModelEcho {
+            model: config.model.clone().context("model is required")?,
+        })
+    }
+
+    fn config(model: Option<&str>) -> PluginProviderConfig {
+        PluginProviderConfig {
+            model: model.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_register_and_create() {
+        register_provider("test-in-process", |config| {
+            Ok(Box::new(model_echo(config)?) as Box<dyn LlmProvider>)
+        });
+        assert!(registered_kinds().contains(&"test-in-process".to_string()));
+
+        let provider = create_plugin_provider("test-in-process", &config(Some("m1"))).unwrap();
+        assert_eq!(provider.default_model(), "m1");
+
+        let err = create_plugin_provider("test-in-process", &config(None))
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", err).contains("model is required"));
+        let err = create_plugin_provider("test-missing", &config(None))
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("Unknown plugin provider 'test-missing'"));
+    }
+
+    /// The path a plugin library's provider takes, without the library
+    fn bridged(model: &str) -> PluginProvider {
+        let mut registrar = ProviderRegistrar::default();
+        registrar.register("bridged", model_echo);
+        let (kind, constructor) = registrar.into_providers().pop().unwrap();
+        assert_eq!(kind, "bridged");
+        PluginProvider::new(constructor(&config(Some(model))).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_bridged_send_runs_on_plugin_runtime() {
+        let provider = bridged("m2");
+        assert_eq!(provider.name(), "model-echo");
+        let response = provider
+            .send_message(&ProviderRequest::new(vec![]))
+            .await
+            .unwrap();
+        assert_eq!(response.model, "m2");
+        assert_eq!(response.text(), "finch-plugin");
+    }
+
+    #[tokio::test]
+    async fn test_bridged_stream() {
+        let provider = bridged("m3");
+        let mut chunks = provider
+            .send_message_stream(&ProviderRequest::new(vec![]))
+            .await
+            .unwrap();
+        let mut text = String::new();
+        while let Some(chunk) = chunks.recv().await {
+            if let StreamChunk::TextDelta(delta) = chunk.unwrap() {
+                text.push_str(&delta);
+            }
+        }
+        assert_eq!(text, "one two");
+    }
+
+    #[test]
+    fn test_library_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = format!("libacme.{}", std::env::consts::DLL_EXTENSION);
+        std::fs::write(dir.path().join(&library), b"").unwrap();
+        std::fs::write(dir.path().join("README.md"), b"").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let files = library_files(dir.path());
+        assert_eq!(files, vec![dir.path().join(library)]);
+        // An empty file is no plugin: skipped, not fatal
+        assert!(load_plugins_from(dir.path()).is_empty());
+        assert!(library_files(&dir.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+// Policy enforcement for cloud providers
+//
+// `PolicyEnforcingProvider` wraps an `LlmProvider` and checks every request
+// against the data-residency policy (see `crate::policy`) before it leaves the
+// machine.  A violating request fails with `PolicyViolation` instead of being
+// sent; inside a `FallbackChain` the next provider is tried, so a request
+// barred from one provider can still be answered by one the policy allows.
+
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use super::{LlmProvider, ProviderRequest, ProviderResponse, StreamChunk};
+use crate::policy::Policy;
+
+/// Checks requests against a policy before handing them to the inner provider.
+pub struct PolicyEnforcingProvider {
+    inner: Box<dyn LlmProvider>,
+    policy: Arc<Policy>,
+}
+
+impl PolicyEnforcingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, policy: Arc<Policy>) -> Self {
+        Self { inner, policy }
+    }
+
+    fn check(&self, request: &ProviderRequest) -> Result<()> {
+        self.policy
+            .check(self.inner.name(), request)
+            .map_err(|violation| {
+                self.policy.record_violation(&violation);
+                violation.into()
+            })
+    }
+}
+
+/// Wrap `provider` in the process-wide policy, if it has any rules.
+pub fn enforce(provider: Box<dyn LlmProvider>) -> Box<dyn LlmProvider> {
+    let policy = crate::policy::active();
+    if policy.is_empty() {
+        return provider;
+    }
+    Box::new(PolicyEnforcingProvider::new(
+        provider,
+        Arc::new(policy.clone()),
+    ))
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for PolicyEnforcingProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        self.check(request)?;
+        self.inner.send_message(request).await
+    }
+
+    async fn send_message_stream(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        self.check(request)?;
+        self.inner.send_message_stream(request).await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.inner.context_limit_tokens()
+    }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        self.inner.cancel_generation().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::{ContentBlock, Message};
+    use crate::policy::PolicyViolation;
+    use crate::providers::FallbackChain;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct NamedProvider {
+        name: &'static str,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for NamedProvider {
+        async fn send_message(&self, _request: &ProviderRequest) -> Result<ProviderResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ProviderResponse {
+                id: "r".to_string(),
+                model: "m".to_string(),
+                content: vec![ContentBlock::Text {
+                    text: self.name.to_string(),
+                }],
+                stop_reason: Some("end_turn".to_string()),
+                role: "assistant".to_string(),
+                provider: self.name.to_string(),
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let (_tx, rx) = mpsc::channel(1);
+            Ok(rx)
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            "m"
+        }
+    }
+
+    fn enforced(
+        name: &'static str,
+        policy: &Arc<Policy>,
+    ) -> (Box<dyn LlmProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let inner = Box::new(NamedProvider {
+            name,
+            calls: Arc::clone(&calls),
+        });
+        (
+            Box::new(PolicyEnforcingProvider::new(inner, Arc::clone(policy))),
+            calls,
+        )
+    }
+
+    fn no_code_to_openai() -> Arc<Policy> {
+        let toml =
+            "[[rule]]\nname = \"no-code\"\ncontent = \"code\"\ndeny_providers = [\"openai\"]";
+        Arc::new(Policy::from_toml_str(toml, None).unwrap())
+    }
+
+    fn code_request() -> ProviderRequest {
+        ProviderRequest::new(vec![Message::user("fix:\n```\nfn f() {}\n```")])
+    }
+
+    #[tokio::test]
+    async fn test_violation_is_blocked_before_sending() {
+        let policy = no_code_to_openai();
+        let (provider, calls) = enforced("openai", &policy);
+
+        let err = provider.send_message(&code_request()).await.unwrap_err();
+        assert!(err.downcast_ref::<PolicyViolation>().is_some());
+        assert!(provider.send_message_stream(&code_request()).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Requests the policy doesn't cover go through
+        let plain = ProviderRequest::new(vec![Message::user("hello")]);
+        provider.send_message(&plain).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_routes_around_blocked_provider() {
+        let policy = no_code_to_openai();
+        let (openai, openai_calls) = enforced("openai", &policy);
+        let (claude, claude_calls) = enforced("claude", &policy);
+        let chain = FallbackChain::new(vec![openai, claude]);
+
+        let response = chain
+            .send_message_with_fallback(&code_request())
+            .await
+            .unwrap();
+        assert_eq!(response.provider, "claude");
+        assert_eq!(openai_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(claude_calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,389 @@
+// Provider rate limits
+//
+// A throttled provider says when it will take requests again: `Retry-After`
+// (seconds or an HTTP date), `retry-after-ms`, Anthropic's
+// `anthropic-ratelimit-*-reset` timestamps, OpenAI's `x-ratelimit-reset-*`
+// durations or, for Gemini, a `RetryInfo.retryDelay` in the error body.
+//
+// Providers turn such a response into a `RateLimited` error and note the
+// reset time in a process-wide table.  From there:
+// - `with_retry` sleeps until exactly the reset (counting down through the
+//   `WaitStatus` the front end installs) instead of backing off blindly;
+// - `FallbackChain` skips providers whose window hasn't reset and runs the
+//   rest under `without_waiting`, so a 429 moves on to the next provider
+//   rather than waiting on (or hammering) the throttled one;
+// - the daemon holds the provider's circuit open until the reset.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::constants::RATE_LIMIT_DEFAULT_WAIT_SECS;
+
+/// A provider refused a request until its rate-limit window resets
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message} (rate limit resets in {}s)", ceil_secs(*.retry_after))]
+pub struct RateLimited {
+    pub provider: String,
+    /// Time until the window resets, as of when the response arrived
+    pub retry_after: Duration,
+    /// The provider's error, already made readable
+    pub message: String,
+}
+
+impl RateLimited {
+    /// `Some` when `status`/`headers`/`body` describe a rate limit.  The reset
+    /// is recorded so `remaining()` reports it to other callers.
+    pub fn from_response(
+        provider: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &str,
+        message: impl Into<String>,
+    ) -> Option<Self> {
+        let retry_after = retry_after(status, headers, body, Utc::now())?;
+        record(provider, retry_after);
+        tracing::warn!(
+            provider,
+            reset_in_secs = ceil_secs(retry_after),
+            "Provider rate limit hit"
+        );
+        Some(Self {
+            provider: provider.to_string(),
+            retry_after,
+            message: message.into(),
+        })
+    }
+}
+
+/// Error for a failed `response`: `RateLimited` when the provider is
+/// throttling, otherwise `describe(status, body)`
+pub async fn response_error(
+    provider: &str,
+    response: reqwest::Response,
+    describe: impl FnOnce(StatusCode, &str) -> String,
+) -> anyhow::Error {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+    let message = describe(status, &body);
+    match RateLimited::from_response(provider, status, &headers, &body, message.clone()) {
+        Some(limited) => limited.into(),
+        None => anyhow::anyhow!(message),
+    }
+}
+
+/// Whole seconds, rounded up so a countdown never shows 0 before the reset
+pub fn ceil_secs(d: Duration) -> u64 {
+    d.as_secs() + u64::from(d.subsec_nanos() > 0)
+}
+
+/// How long until a throttled provider takes requests again.
+///
+/// 429 responses are always treated as rate limits (falling back to
+/// `RATE_LIMIT_DEFAULT_WAIT_SECS` when they carry no hint); other statuses
+/// (503, Anthropic's 529 "overloaded") only when they send `Retry-After`.
+pub fn retry_after(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let explicit = header_retry_after(headers, now);
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return explicit;
+    }
+    explicit
+        .or_else(|| anthropic_reset(headers, now))
+        .or_else(|| openai_reset(headers))
+        .or_else(|| gemini_retry_delay(body))
+        .or(Some(Duration::from_secs(RATE_LIMIT_DEFAULT_WAIT_SECS)))
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+}
+
+/// `retry-after-ms`, then `Retry-After` as seconds or an HTTP date
+fn header_retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    if let Some(ms) = header_str(headers, "retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return Duration::try_from_secs_f64(ms / 1000.0).ok();
+    }
+    let value = header_str(headers, "retry-after")?;
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(until(at.with_timezone(&Utc), now))
+}
+
+/// Latest `anthropic-ratelimit-*-reset` among the exhausted limits (or among
+/// all of them when none reports 0 remaining)
+fn anthropic_reset(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let limits = ["requests", "tokens", "input-tokens", "output-tokens"];
+    latest_reset(limits.iter().filter_map(|limit| {
+        let reset = header_str(headers, &format!("anthropic-ratelimit-{limit}-reset"))?;
+        let reset = DateTime::parse_from_rfc3339(reset).ok()?;
+        let remaining = header_str(headers, &format!("anthropic-ratelimit-{limit}-remaining"));
+        Some((
+            remaining == Some("0"),
+            until(reset.with_timezone(&Utc), now),
+        ))
+    }))
+}
+
+/// Latest `x-ratelimit-reset-{requests,tokens}` among the exhausted limits
+fn openai_reset(headers: &HeaderMap) -> Option<Duration> {
+    latest_reset(["requests", "tokens"].iter().filter_map(|limit| {
+        let reset = parse_go_duration(header_str(headers, &format!("x-ratelimit-reset-{limit}"))?)?;
+        let remaining = header_str(headers, &format!("x-ratelimit-remaining-{limit}"));
+        Some((remaining == Some("0"), reset))
+    }))
+}
+
+fn latest_reset(resets: impl Iterator<Item = (bool, Duration)>) -> Option<Duration> {
+    let resets: Vec<_> = resets.collect();
+    let exhausted = resets.iter().filter(|(e, _)| *e).map(|(_, d)| *d).max();
+    exhausted.or_else(|| resets.iter().map(|(_, d)| *d).max())
+}
+
+/// Gemini's `error.details[].retryDelay` ("30s")
+fn gemini_retry_delay(body: &str) -> Option<Duration> {
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    body.pointer("/error/details")?
+        .as_array()?
+        .iter()
+        .find_map(|d| d.get("retryDelay")?.as_str())
+        .and_then(parse_go_duration)
+}
+
+/// Durations like "20ms", "1.5s" or "6m0s"
+fn parse_go_duration(s: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..split].parse().ok()?;
+        rest = &rest[split..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        total += value * scale;
+        rest = &rest[unit_len..];
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+fn until(at: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (at - now).to_std().unwrap_or_default()
+}
+
+fn resets() -> &'static Mutex<HashMap<String, Instant>> {
+    static RESETS: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+    RESETS.get_or_init(Default::default)
+}
+
+/// Note that `provider` is throttled for `retry_after`
+pub fn record(provider: &str, retry_after: Duration) {
+    let until = Instant::now() + retry_after;
+    let mut resets = resets().lock().unwrap();
+    let entry = resets.entry(provider.to_string()).or_insert(until);
+    *entry = (*entry).max(until);
+}
+
+/// Time left until `provider`'s rate-limit window resets, if it is throttled
+pub fn remaining(provider: &str) -> Option<Duration> {
+    let mut resets = resets().lock().unwrap();
+    let left = resets
+        .get(provider)?
+        .checked_duration_since(Instant::now())
+        .filter(|d| !d.is_zero());
+    if left.is_none() {
+        resets.remove(provider);
+    }
+    left
+}
+
+tokio::task_local! {
+    static NO_WAIT: ();
+}
+
+/// Run `f` with `with_retry` returning rate-limit errors immediately instead
+/// of waiting for the reset (the caller has somewhere else to go)
+pub async fn without_waiting<F: Future>(f: F) -> F::Output {
+    NO_WAIT.scope((), f).await
+}
+
+/// Whether rate-limited calls made here should wait for the reset
+pub fn waiting_allowed() -> bool {
+    NO_WAIT.try_with(|_| ()).is_err()
+}
+
+/// Shows a rate-limit wait: called with the provider and the time left about
+/// once a second, then with `None` when the wait is over
+pub type WaitStatus = dyn Fn(&str, Option<Duration>) + Send + Sync;
+
+static WAIT_STATUS: OnceLock<Box<WaitStatus>> = OnceLock::new();
+
+/// Show rate-limit waits through `status` (the REPL's status bar).  Set once
+/// at startup; later calls are ignored.
+pub fn set_wait_status(status: impl Fn(&str, Option<Duration>) + Send + Sync + 'static) {
+    let _ = WAIT_STATUS.set(Box::new(status));
+}
+
+/// The status set with `set_wait_status`, or one that shows nothing
+pub fn wait_status() -> &'static WaitStatus {
+    match WAIT_STATUS.get() {
+        Some(status) => status.as_ref(),
+        None => &|_, _| {},
+    }
+}
+
+/// Sleep until `provider`'s window resets, counting down through `status`
+pub async fn wait_for_reset(
+    provider: &str,
+    wait: Duration,
+    status: impl Fn(&str, Option<Duration>),
+) {
+    tracing::info!(provider, "Rate limited; retrying in {}s", ceil_secs(wait));
+    let deadline = tokio::time::Instant::now() + wait;
+    loop {
+        let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        status(provider, Some(left));
+        tokio::time::sleep(left.min(Duration::from_secs(1))).await;
+    }
+    status(provider, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn wait(pairs: &[(&'static str, &str)], body: &str) -> Option<Duration> {
+        retry_after(StatusCode::TOO_MANY_REQUESTS, &headers(pairs), body, now())
+    }
+
+    #[test]
+    fn test_retry_after_seconds_and_dates() {
+        assert_eq!(
+            wait(&[("retry-after", "12")], ""),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(
+            wait(&[("retry-after", "Sun, 01 Mar 2026 12:00:30 GMT")], ""),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            wait(&[("retry-after-ms", "1500"), ("retry-after", "9")], ""),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn test_provider_reset_headers() {
+        // Anthropic: the exhausted limit decides, not the later one
+        let anthropic = [
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", "2026-03-01T12:00:20Z"),
+            ("anthropic-ratelimit-tokens-remaining", "5000"),
+            ("anthropic-ratelimit-tokens-reset", "2026-03-01T12:01:00Z"),
+        ];
+        assert_eq!(wait(&anthropic, ""), Some(Duration::from_secs(20)));
+
+        let openai = [
+            ("x-ratelimit-remaining-tokens", "0"),
+            ("x-ratelimit-reset-tokens", "6m0.5s"),
+            ("x-ratelimit-reset-requests", "120ms"),
+        ];
+        assert_eq!(wait(&openai, ""), Some(Duration::from_millis(360_500)));
+
+        let gemini = r#"{"error":{"code":429,"details":[
+            {"@type":"type.googleapis.com/google.rpc.QuotaFailure"},
+            {"@type":"type.googleapis.com/google.rpc.RetryInfo","retryDelay":"34s"}]}}"#;
+        assert_eq!(wait(&[], gemini), Some(Duration::from_secs(34)));
+    }
+
+    #[test]
+    fn test_only_429_defaults_without_hints() {
+        assert_eq!(
+            wait(&[], "slow down"),
+            Some(Duration::from_secs(RATE_LIMIT_DEFAULT_WAIT_SECS))
+        );
+        let unavailable = StatusCode::SERVICE_UNAVAILABLE;
+        assert_eq!(retry_after(unavailable, &HeaderMap::new(), "", now()), None);
+        assert_eq!(
+            retry_after(unavailable, &headers(&[("retry-after", "3")]), "", now()),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(parse_go_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_remaining_tracks_latest_reset() {
+        assert_eq!(remaining("rate-limit-test"), None);
+        record("rate-limit-test", Duration::from_secs(60));
+        record("rate-limit-test", Duration::from_secs(5));
+        assert!(remaining("rate-limit-test").is_some_and(|d| d > Duration::from_secs(55)));
+        record("rate-limit-expired", Duration::ZERO);
+        assert_eq!(remaining("rate-limit-expired"), None);
+    }
+
+    #[tokio::test]
+    async fn test_without_waiting_is_scoped() {
+        assert!(waiting_allowed());
+        assert!(!without_waiting(async { waiting_allowed() }).await);
+        assert!(waiting_allowed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_counts_down_through_status() {
+        let seen = Mutex::new(Vec::new());
+        let status = |provider: &str, left: Option<Duration>| {
+            seen.lock()
+                .unwrap()
+                .push((provider.to_string(), left.map(ceil_secs)));
+        };
+        wait_for_reset("grok", Duration::from_millis(2500), status).await;
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.first(), Some(&("grok".to_string(), Some(3))));
+        assert_eq!(seen.last(), Some(&("grok".to_string(), None)));
+        assert_eq!(seen.len(), 4);
+    }
+}
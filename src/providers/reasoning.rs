@@ -0,0 +1,223 @@
+// Reasoning ("thinking") models
+//
+// `[reasoning]` in ~/.finch/config.toml turns on hidden chain-of-thought for
+// the models that have it:
+//
+//   [reasoning]
+//   effort = "medium"        # low | medium | high
+//   budget_tokens = 8000     # Claude extended thinking; wins over effort
+//   show_thinking = true     # collapsed, dimmed block in the TUI
+//
+// Claude takes a thinking token budget and OpenAI's o-series an effort level;
+// each is derived from the other when only one is set.  Models without
+// reasoning are sent nothing extra.
+//
+// Thinking is streamed as `StreamChunk::ThinkingDelta` and kept in history
+// as `ContentBlock::Thinking` only because Claude needs it back during tool
+// use.  It is never part of a response's text, so it stays out of memory and
+// training data, and its tokens are accounted separately.
+
+use crate::claude::types::ContentBlock;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Smallest thinking budget Claude accepts
+pub const MIN_CLAUDE_BUDGET_TOKENS: u32 = 1024;
+
+/// How hard a model should think
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// Claude thinking budget standing in for this effort
+    pub fn budget_tokens(self) -> u32 {
+        match self {
+            Self::Low => 2_048,
+            Self::Medium => 8_192,
+            Self::High => 24_576,
+        }
+    }
+
+    /// Effort standing in for a Claude thinking budget
+    pub fn from_budget(budget_tokens: u32) -> Self {
+        if budget_tokens < Self::Medium.budget_tokens() {
+            Self::Low
+        } else if budget_tokens < Self::High.budget_tokens() {
+            Self::Medium
+        } else {
+            Self::High
+        }
+    }
+}
+
+/// Thinking asked for by a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reasoning {
+    Effort(ReasoningEffort),
+    BudgetTokens(u32),
+}
+
+impl Reasoning {
+    pub fn effort(self) -> ReasoningEffort {
+        match self {
+            Self::Effort(effort) => effort,
+            Self::BudgetTokens(budget) => ReasoningEffort::from_budget(budget),
+        }
+    }
+
+    /// Thinking budget for Claude, raised to its minimum
+    pub fn budget_tokens(self) -> u32 {
+        let budget = match self {
+            Self::Effort(effort) => effort.budget_tokens(),
+            Self::BudgetTokens(budget) => budget,
+        };
+        budget.max(MIN_CLAUDE_BUDGET_TOKENS)
+    }
+}
+
+/// Settings from `[reasoning]` in ~/.finch/config.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    /// Effort for models that take one (OpenAI o-series and gpt-5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
+    /// Thinking budget for Claude; wins over `effort`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u32>,
+    /// Stream thinking into the TUI as a collapsed block
+    #[serde(default = "default_show_thinking")]
+    pub show_thinking: bool,
+}
+
+fn default_show_thinking() -> bool {
+    true
+}
+
+impl Default for ReasoningConfig {
+    fn default() -> Self {
+        Self {
+            effort: None,
+            budget_tokens: None,
+            show_thinking: default_show_thinking(),
+        }
+    }
+}
+
+impl ReasoningConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Thinking to ask for, or `None` when reasoning is off
+    pub fn reasoning(&self) -> Option<Reasoning> {
+        match (self.budget_tokens, self.effort) {
+            (Some(budget), _) => Some(Reasoning::BudgetTokens(budget)),
+            (None, Some(effort)) => Some(Reasoning::Effort(effort)),
+            (None, None) => None,
+        }
+    }
+}
+
+static SETTINGS: OnceLock<ReasoningConfig> = OnceLock::new();
+
+/// Apply `[reasoning]`.  Called once when the config is loaded; later calls
+/// are ignored.
+pub fn init(config: &ReasoningConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+fn settings() -> &'static ReasoningConfig {
+    SETTINGS.get_or_init(ReasoningConfig::default)
+}
+
+/// Configured thinking, or `None` when reasoning is off
+pub fn configured() -> Option<Reasoning> {
+    settings().reasoning()
+}
+
+/// Whether thinking is shown in the TUI
+pub fn show_thinking() -> bool {
+    settings().show_thinking
+}
+
+/// Claude models with extended thinking: everything after the 3.5 family
+pub fn claude_supports_thinking(model: &str) -> bool {
+    !model.starts_with("claude-3-") || model.starts_with("claude-3-7")
+}
+
+/// OpenAI-style models that reason before answering.  They take
+/// `reasoning_effort` and `max_completion_tokens`, and no `temperature`.
+pub fn openai_is_reasoning_model(model: &str) -> bool {
+    const PREFIXES: &[&str] = &["o1", "o3", "o4", "gpt-5"];
+    PREFIXES.iter().any(|prefix| model.starts_with(prefix))
+}
+
+/// Rough token count of thinking text, for models that don't report it
+pub fn estimate_tokens(thinking: &str) -> u32 {
+    (thinking.len() as u32).div_ceil(4)
+}
+
+/// Rough token count of the thinking in `blocks`
+pub fn thinking_tokens(blocks: &[ContentBlock]) -> u32 {
+    blocks
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Thinking { thinking, .. } => estimate_tokens(thinking),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effort_and_budget_convert_both_ways() {
+        assert_eq!(Reasoning::Effort(ReasoningEffort::Low).budget_tokens(), 2_048);
+        assert_eq!(
+            Reasoning::BudgetTokens(10_000).effort(),
+            ReasoningEffort::Medium
+        );
+        assert_eq!(Reasoning::BudgetTokens(64_000).effort(), ReasoningEffort::High);
+        // Claude rejects budgets under 1024
+        assert_eq!(Reasoning::BudgetTokens(100).budget_tokens(), 1024);
+    }
+
+    #[test]
+    fn test_config_budget_wins_over_effort() {
+        let config: ReasoningConfig =
+            toml::from_str("effort = \"high\"\nbudget_tokens = 4000").unwrap();
+        assert_eq!(config.reasoning(), Some(Reasoning::BudgetTokens(4000)));
+        assert!(config.show_thinking);
+
+        let config: ReasoningConfig = toml::from_str("").unwrap();
+        assert!(config.is_default());
+        assert_eq!(config.reasoning(), None);
+    }
+
+    #[test]
+    fn test_model_support() {
+        assert!(claude_supports_thinking("claude-sonnet-4-6"));
+        assert!(claude_supports_thinking("claude-3-7-sonnet-latest"));
+        assert!(!claude_supports_thinking("claude-3-5-haiku-latest"));
+
+        assert!(openai_is_reasoning_model("o3-mini"));
+        assert!(openai_is_reasoning_model("gpt-5"));
+        assert!(!openai_is_reasoning_model("gpt-4o"));
+    }
+}
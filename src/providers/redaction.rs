@@ -0,0 +1,137 @@
+// Secret redaction for cloud providers
+//
+// `RedactingProvider` wraps an `LlmProvider` and redacts the tool results in
+// every request (see `crate::redact`) before it leaves the machine.  The
+// conversation itself keeps the originals, so the local model and the
+// terminal still see them; only the copy sent to the provider is changed.
+
+use anyhow::Result;
+use tokio::sync::mpsc;
+
+use super::{LlmProvider, ProviderRequest, ProviderResponse, StreamChunk};
+use crate::claude::ContentBlock;
+use crate::redact::Redactor;
+
+/// Redacts tool results before handing requests to the inner provider.
+pub struct RedactingProvider {
+    inner: Box<dyn LlmProvider>,
+    redactor: &'static Redactor,
+}
+
+impl RedactingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, redactor: &'static Redactor) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+/// Wrap `provider` in the process-wide redactor, unless redaction is off.
+pub fn protect(provider: Box<dyn LlmProvider>) -> Box<dyn LlmProvider> {
+    let redactor = crate::redact::active();
+    if !redactor.is_enabled() {
+        return provider;
+    }
+    Box::new(RedactingProvider::new(provider, redactor))
+}
+
+/// `request` with its tool results redacted, or `None` when there was
+/// nothing to redact
+pub fn redact_request(redactor: &Redactor, request: &ProviderRequest) -> Option<ProviderRequest> {
+    let mut redacted: Option<ProviderRequest> = None;
+    for (m, message) in request.messages.iter().enumerate() {
+        for (b, block) in message.content.iter().enumerate() {
+            let ContentBlock::ToolResult { content, .. } = block else {
+                continue;
+            };
+            let clean = redactor.redact(content);
+            if clean == *content {
+                continue;
+            }
+            let copy = redacted.get_or_insert_with(|| request.clone());
+            if let ContentBlock::ToolResult { content, .. } = &mut copy.messages[m].content[b] {
+                *content = clean;
+            }
+        }
+    }
+    if redacted.is_some() {
+        tracing::info!("Redacted secrets in tool results before sending");
+    }
+    redacted
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for RedactingProvider {
+    async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+        match redact_request(self.redactor, request) {
+            Some(redacted) => self.inner.send_message(&redacted).await,
+            None => self.inner.send_message(request).await,
+        }
+    }
+
+    async fn send_message_stream(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        match redact_request(self.redactor, request) {
+            Some(redacted) => self.inner.send_message_stream(&redacted).await,
+            None => self.inner.send_message_stream(request).await,
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn context_limit_tokens(&self) -> usize {
+        self.inner.context_limit_tokens()
+    }
+
+    async fn cancel_generation(&self) -> Result<()> {
+        self.inner.cancel_generation().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::claude::Message;
+
+    #[test]
+    fn test_only_tool_results_are_redacted() {
+        let redactor = Redactor::new(Vec::new());
+        let env = "HOME=/home/me\nANTHROPIC_API_KEY=sk-ant-REDACTED\n";
+        let request = ProviderRequest::new(vec![
+            Message::user("what's in my env?"),
+            Message {
+                role: "user".to_string(),
+                content: vec![ContentBlock::tool_result(
+                    "toolu_1".to_string(),
+                    env.to_string(),
+                    None,
+                )],
+            },
+        ]);
+
+        let redacted = redact_request(&redactor, &request).unwrap();
+        assert!(matches!(
+            &redacted.messages[1].content[0],
+            ContentBlock::ToolResult { content, .. }
+                if content == "HOME=/home/me\nANTHROPIC_API_KEY=[REDACTED]\n"
+        ));
+        assert_eq!(redacted.messages[0].text(), "what's in my env?");
+
+        let clean = ProviderRequest::new(vec![Message::user("sk-ant-REDACTED")]);
+        assert!(redact_request(&redactor, &clean).is_none());
+    }
+}
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// This wraps the existing Message format and adds provider-agnostic options.
 /// Each provider implementation will transform this into their specific API format.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderRequest {
     /// Conversation messages (using Claude's Message format as the common denominator)
     pub messages: Vec<Message>,
@@ -271,6 +271,8 @@ fn estimate_message_tokens(msg: &Message) -> usize {
             ContentBlock::ToolUse { name, input, .. } => name.len() + input.to_string().len(),
             ContentBlock::ToolResult { content, .. } => content.len(),
             ContentBlock::Image { source } => source.data.len().min(4_000) + 20,
+            ContentBlock::Thinking { thinking, .. } => thinking.len(),
+            ContentBlock::RedactedThinking { data } => data.len(),
         })
         .sum();
     (content_chars / 3).max(1) + 4 // +4 overhead per message
@@ -1,5 +1,6 @@
 // Routing decision logic
 
+use super::policy::{RouteOutcome, RoutingPolicy};
 use crate::models::{ThresholdRouter, ThresholdRouterStats};
 use anyhow::Result;
 use std::path::Path;
@@ -28,36 +29,30 @@ pub enum RouteDecision {
     Forward { reason: ForwardReason },
 }
 
+/// Routes queries between the local model and cloud providers.
+///
+/// The decision itself is delegated to a [`RoutingPolicy`]; `Router::new`
+/// uses the built-in [`ThresholdRouter`].
 #[derive(Clone)]
 pub struct Router {
-    threshold_router: ThresholdRouter,
+    policy: Box<dyn RoutingPolicy>,
 }
 
 impl Router {
     pub fn new(threshold_router: ThresholdRouter) -> Self {
-        Self { threshold_router }
+        Self::with_policy(threshold_router)
     }
 
-    /// Make a routing decision for a query
-    pub fn route(&self, query: &str) -> RouteDecision {
-        // Layer 1: Data-driven routing - use threshold model
-        if self.threshold_router.should_try_local(query) {
-            let stats = self.threshold_router.stats();
-            tracing::info!(
-                "Routing decision: LOCAL (threshold confidence: {:.2})",
-                stats.confidence_threshold
-            );
-            return RouteDecision::Local {
-                pattern_id: "threshold_based".to_string(),
-                confidence: stats.confidence_threshold,
-            };
+    /// Route with a custom policy instead of the threshold router
+    pub fn with_policy(policy: impl RoutingPolicy + 'static) -> Self {
+        Self {
+            policy: Box::new(policy),
         }
+    }
 
-        // Layer 2: Default fallback - forward when uncertain
-        tracing::info!("Routing decision: FORWARD (threshold too low)");
-        RouteDecision::Forward {
-            reason: ForwardReason::NoMatch,
-        }
+    /// Make a routing decision for a query
+    pub fn route(&self, query: &str) -> RouteDecision {
+        self.policy.decide(query)
     }
 
     /// Make routing decision with generator state check (progressive bootstrap support)
@@ -83,13 +78,22 @@ impl Router {
 
     /// Learn from a local generation attempt
     pub fn learn_local_attempt(&mut self, query: &str, was_successful: bool) {
-        self.threshold_router
-            .learn_local_attempt(query, was_successful);
+        self.policy.record_outcome(
+            query,
+            RouteOutcome::Local {
+                success: was_successful,
+            },
+        );
     }
 
     /// Learn from a forwarded query
     pub fn learn_forwarded(&mut self, query: &str) {
-        self.threshold_router.learn_forwarded(query);
+        self.policy.record_outcome(query, RouteOutcome::Forwarded);
+    }
+
+    /// Learn from a replay of `query` through the local model
+    pub fn record_evaluation(&mut self, query: &str, was_successful: bool) {
+        self.policy.record_evaluation(query, was_successful);
     }
 
     /// Deprecated: Use learn_local_attempt() or learn_forwarded() instead
@@ -97,19 +101,18 @@ impl Router {
         since = "0.2.0",
         note = "Use learn_local_attempt() or learn_forwarded() instead"
     )]
-    #[allow(deprecated)]
     pub fn learn(&mut self, query: &str, was_successful: bool) {
-        self.threshold_router.learn(query, was_successful);
+        self.learn_local_attempt(query, was_successful);
     }
 
-    /// Get threshold router statistics
+    /// Get routing policy statistics
     pub fn stats(&self) -> ThresholdRouterStats {
-        self.threshold_router.stats()
+        self.policy.stats()
     }
 
-    /// Save threshold router state to disk
+    /// Save routing policy state to disk (no-op for stateless policies)
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        self.threshold_router.save(path)
+        self.policy.save(path.as_ref())
     }
 
     /// Load threshold router state from disk
@@ -263,6 +266,83 @@ mod tests {
         assert!(!debug.is_empty());
     }
 
+    /// Compliance-style policy: anything that looks like file contents stays
+    /// on the local model; everything else is forwarded.
+    #[derive(Clone, Default)]
+    struct KeepFilesLocal {
+        outcomes: Vec<RouteOutcome>,
+    }
+
+    impl RoutingPolicy for KeepFilesLocal {
+        fn decide(&self, query: &str) -> RouteDecision {
+            if query.contains("```") {
+                RouteDecision::Local {
+                    pattern_id: "file_contents".to_string(),
+                    confidence: 1.0,
+                }
+            } else {
+                RouteDecision::Forward {
+                    reason: ForwardReason::NoMatch,
+                }
+            }
+        }
+
+        fn record_outcome(&mut self, _query: &str, outcome: RouteOutcome) {
+            self.outcomes.push(outcome);
+        }
+
+        fn stats(&self) -> ThresholdRouterStats {
+            ThresholdRouterStats {
+                total_queries: self.outcomes.len(),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_policy_decides() {
+        let router = Router::with_policy(KeepFilesLocal::default());
+        assert!(matches!(
+            router.route("summarise this:\n```\nfn main() {}\n```"),
+            RouteDecision::Local { .. }
+        ));
+        assert!(matches!(
+            router.route("what is Rust?"),
+            RouteDecision::Forward { .. }
+        ));
+        // The readiness check still applies before the policy is consulted
+        assert!(matches!(
+            router.route_with_generator_check("```secret```", false),
+            RouteDecision::Forward {
+                reason: ForwardReason::ModelNotReady
+            }
+        ));
+    }
+
+    #[test]
+    fn test_custom_policy_records_outcomes_and_clones() {
+        let mut router = Router::with_policy(KeepFilesLocal::default());
+        router.learn_local_attempt("a", true);
+        router.learn_forwarded("b");
+        assert_eq!(router.stats().total_queries, 2);
+
+        // Clones are independent snapshots
+        let snapshot = router.clone();
+        router.learn_forwarded("c");
+        assert_eq!(snapshot.stats().total_queries, 2);
+        assert_eq!(router.stats().total_queries, 3);
+    }
+
+    #[test]
+    fn test_stateless_policy_save_is_noop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("router.json");
+        Router::with_policy(KeepFilesLocal::default())
+            .save(&path)
+            .unwrap();
+        assert!(!path.exists());
+    }
+
     #[test]
     fn test_route_decision_clone() {
         let reason = ForwardReason::ModelNotReady;
@@ -2,5 +2,7 @@
 // Public interface for routing decisions
 
 mod decision;
+mod policy;
 
 pub use decision::{ForwardReason, RouteDecision, Router};
+pub use policy::{ClonePolicy, RouteOutcome, RoutingPolicy};
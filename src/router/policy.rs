@@ -0,0 +1,107 @@
+// Routing policy — the pluggable part of the router
+//
+// `Router` handles the plumbing every policy needs (generator-readiness
+// check, logging, persistence hooks) and asks a `RoutingPolicy` for the actual
+// local-vs-forward decision.  Applications embedding finch as a library can
+// supply their own policy with `Router::with_policy()`, e.g. one that never
+// forwards queries containing file contents; `ThresholdRouter` is the default.
+
+use super::RouteDecision;
+use crate::models::{ThresholdRouter, ThresholdRouterStats};
+use anyhow::Result;
+use std::path::Path;
+
+/// What happened to a query after the router decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteOutcome {
+    /// The local model handled the query; `success` is the quality verdict
+    Local { success: bool },
+    /// The query was forwarded to a cloud provider
+    Forwarded,
+}
+
+/// A routing policy: decides where each query goes and learns from outcomes.
+///
+/// Implementations must be `Clone` (the router is snapshotted for the event
+/// loop); `clone_policy` is provided automatically for any `Clone` type.
+pub trait RoutingPolicy: ClonePolicy + Send + Sync {
+    /// Decide whether to try the local model or forward the query
+    fn decide(&self, query: &str) -> RouteDecision;
+
+    /// Feed back the outcome of a query previously passed to `decide()`
+    fn record_outcome(&mut self, query: &str, outcome: RouteOutcome);
+
+    /// Feed back an offline evaluation: the local model replayed `query`
+    /// and its answer was (or wasn't) good enough.  Policies that don't
+    /// calibrate from evaluations keep the default no-op.
+    fn record_evaluation(&mut self, _query: &str, _success: bool) {}
+
+    /// Statistics shown by `/metrics` and the REPL status line.  Policies that
+    /// don't track something leave it at its default.
+    fn stats(&self) -> ThresholdRouterStats;
+
+    /// Persist learned state.  Stateless policies keep the default no-op.
+    fn save(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Object-safe cloning for boxed policies
+pub trait ClonePolicy {
+    fn clone_policy(&self) -> Box<dyn RoutingPolicy>;
+}
+
+impl<T: RoutingPolicy + Clone + 'static> ClonePolicy for T {
+    fn clone_policy(&self) -> Box<dyn RoutingPolicy> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn RoutingPolicy> {
+    fn clone(&self) -> Self {
+        self.clone_policy()
+    }
+}
+
+/// The built-in policy: try local unless the query's category has a success
+/// rate below the adaptive confidence threshold.
+impl RoutingPolicy for ThresholdRouter {
+    fn decide(&self, query: &str) -> RouteDecision {
+        if self.should_try_local(query) {
+            let confidence = self.stats().confidence_threshold;
+            tracing::info!(
+                "Routing decision: LOCAL (threshold confidence: {:.2})",
+                confidence
+            );
+            return RouteDecision::Local {
+                pattern_id: "threshold_based".to_string(),
+                confidence,
+            };
+        }
+
+        // Forward when uncertain
+        tracing::info!("Routing decision: FORWARD (threshold too low)");
+        RouteDecision::Forward {
+            reason: super::ForwardReason::NoMatch,
+        }
+    }
+
+    fn record_outcome(&mut self, query: &str, outcome: RouteOutcome) {
+        match outcome {
+            RouteOutcome::Local { success } => self.learn_local_attempt(query, success),
+            RouteOutcome::Forwarded => self.learn_forwarded(query),
+        }
+    }
+
+    fn record_evaluation(&mut self, query: &str, success: bool) {
+        self.calibrate(query, success);
+    }
+
+    fn stats(&self) -> ThresholdRouterStats {
+        ThresholdRouter::stats(self)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        ThresholdRouter::save(self, path)
+    }
+}
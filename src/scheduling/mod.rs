@@ -1,9 +1,13 @@
 // Autonomous task scheduling system
 //
-// Enables the AI to schedule its own tasks and resume work without human intervention
+// Enables the AI to schedule its own tasks and resume work without human intervention,
+// and keeps background work out of the user's way (quiet hours, CPU priority)
 
+pub mod priority;
 pub mod queue;
+pub mod quiet_hours;
 pub mod scheduler;
 
 pub use queue::{ScheduledTask, TaskQueue, TaskStatus};
+pub use quiet_hours::{BackgroundJob, ScheduleConfig, ScheduleRule, TimeWindow};
 pub use scheduler::TaskScheduler;
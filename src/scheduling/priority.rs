@@ -0,0 +1,79 @@
+// CPU priority for background work
+//
+// Background jobs run at the `[schedule] nice` level so the scheduler favours
+// whatever the user is doing in the foreground.  Priorities can only be
+// lowered without privileges, so a level below the current one is an error.
+
+use std::io;
+
+/// Run `cmd`'s process at niceness `nice` (0 leaves it unchanged)
+pub fn lower_child(cmd: &mut tokio::process::Command, nice: i32) {
+    #[cfg(unix)]
+    if nice > 0 {
+        // SAFETY: setpriority is async-signal-safe, and the closure touches
+        // nothing but its copied argument
+        unsafe {
+            cmd.pre_exec(move || set_nice(0, nice));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (cmd, nice);
+}
+
+/// Lower the priority of every thread of this process (and so of the
+/// processes it starts) to niceness `nice`
+pub fn lower_current_process(nice: i32) -> io::Result<()> {
+    if nice <= 0 {
+        return Ok(());
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // Linux keeps a priority per thread: renice each one.  Threads and
+        // processes started later inherit it.
+        for entry in std::fs::read_dir("/proc/self/task")? {
+            if let Some(tid) = entry?.file_name().to_str().and_then(|s| s.parse().ok()) {
+                set_nice(tid, nice)?;
+            }
+        }
+        Ok(())
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        set_nice(0, nice)
+    }
+    #[cfg(not(unix))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "process priority is only supported on Unix",
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn set_nice(who: libc::id_t, nice: i32) -> io::Result<()> {
+    // SAFETY: plain syscall on an id we own
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, who, nice) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lower_child_runs_niced() {
+        // `nice` with no arguments prints the niceness it runs at
+        let mut cmd = tokio::process::Command::new("nice");
+        lower_child(&mut cmd, 5);
+        let output = cmd.output().await.unwrap();
+        let nice: i32 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap();
+        assert!(nice >= 5, "child ran at nice {}", nice);
+    }
+}
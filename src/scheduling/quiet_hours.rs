@@ -0,0 +1,407 @@
+// Quiet hours for background work
+//
+// `[schedule]` in ~/.finch/config.toml keeps finch's autonomous work (LoRA
+// training, daemon brains, `finch agent`, the local model's self-evaluation
+// and memory consolidation) out of the hours the machine belongs to the user:
+//
+//   [schedule]
+//   nice = 10
+//
+//   [[schedule.rules]]
+//   jobs = ["training", "brain"]
+//   quiet = "09:00-18:00"
+//   days = ["mon", "tue", "wed", "thu", "fri"]
+//
+//   [[schedule.rules]]
+//   jobs = ["agent", "memory"]
+//   only = "23:00-06:00"
+//
+// Times are local.  A job may run when none of its `quiet` windows is open
+// and, if it has `only` rules, one of those windows is.  Jobs that become due
+// during quiet hours wait rather than being dropped.
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest single sleep while waiting, so clock changes are noticed
+const MAX_WAIT: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// How far ahead `next_allowed` looks before giving up
+const LOOKAHEAD_DAYS: i64 = 8;
+
+/// Background work that `[schedule]` rules apply to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundJob {
+    /// LoRA fine-tuning started by the daemon's training worker
+    Training,
+    /// Daemon brain sessions (`POST /v1/brains`)
+    Brain,
+    /// The `finch agent` task loop
+    Agent,
+    /// The daemon replaying teacher answers through the local model
+    /// (`[self_eval]`)
+    Eval,
+    /// Merging near-duplicate memories (`[memory.consolidate]`)
+    Memory,
+}
+
+impl BackgroundJob {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Training => "training",
+            Self::Brain => "brain",
+            Self::Agent => "agent",
+            Self::Eval => "eval",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+impl fmt::Display for BackgroundJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A daily time range such as "09:00-18:00"
+///
+/// A range whose end is before its start wraps past midnight ("23:00-06:00");
+/// one whose end equals its start covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl TimeWindow {
+    /// Whether the window is open at `at`, counting only windows that start
+    /// on one of `days` (every day when empty)
+    fn is_open(&self, days: &[Weekday], at: NaiveDateTime) -> bool {
+        let on = |date: NaiveDate| days.is_empty() || days.contains(&date.weekday());
+        let time = at.time();
+        if self.start < self.end {
+            on(at.date()) && self.start <= time && time < self.end
+        } else if self.start == self.end {
+            on(at.date())
+        } else {
+            // Wraps past midnight: the early-morning part belongs to the
+            // window that started the day before
+            (time >= self.start && on(at.date()))
+                || (time < self.end && at.date().pred_opt().is_some_and(on))
+        }
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("invalid time window '{}' (expected HH:MM-HH:MM)", s))?;
+        let parse = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .map_err(|_| format!("invalid time '{}' in window '{}' (expected HH:MM)", t, s))
+        };
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+impl TryFrom<String> for TimeWindow {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeWindow> for String {
+    fn from(window: TimeWindow) -> Self {
+        window.to_string()
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start.hour(),
+            self.start.minute(),
+            self.end.hour(),
+            self.end.minute()
+        )
+    }
+}
+
+/// One `[[schedule.rules]]` entry: a `quiet` or `only` window for some jobs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleRule {
+    /// Jobs the rule applies to
+    pub jobs: Vec<BackgroundJob>,
+    /// The jobs may not run while this window is open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet: Option<TimeWindow>,
+    /// The jobs may only run while this window (or another `only` window) is open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only: Option<TimeWindow>,
+    /// Days the window starts on, e.g. `["mon", "fri"]` (empty = every day)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+}
+
+/// Background work settings from `[schedule]` in ~/.finch/config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleConfig {
+    /// CPU niceness for background jobs (0 = normal priority, 19 = lowest)
+    #[serde(default = "default_nice")]
+    pub nice: i32,
+    /// Quiet hours and allowed hours per job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ScheduleRule>,
+}
+
+fn default_nice() -> i32 {
+    crate::config::constants::DEFAULT_BACKGROUND_NICE
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            nice: default_nice(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(0..=19).contains(&self.nice) {
+            bail!(
+                "[schedule] nice must be between 0 and 19 (got {})",
+                self.nice
+            );
+        }
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.jobs.is_empty() {
+                bail!("[[schedule.rules]] entry {} has no jobs", idx + 1);
+            }
+            if rule.quiet.is_some() == rule.only.is_some() {
+                bail!(
+                    "[[schedule.rules]] entry {} needs exactly one of `quiet` or `only`",
+                    idx + 1
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `job` may run at local time `at`
+    pub fn allows(&self, job: BackgroundJob, at: NaiveDateTime) -> bool {
+        let rules = self.rules.iter().filter(|r| r.jobs.contains(&job));
+        let mut has_only = false;
+        let mut in_only = false;
+        for rule in rules {
+            if let Some(quiet) = &rule.quiet {
+                if quiet.is_open(&rule.days, at) {
+                    return false;
+                }
+            }
+            if let Some(only) = &rule.only {
+                has_only = true;
+                in_only |= only.is_open(&rule.days, at);
+            }
+        }
+        !has_only || in_only
+    }
+
+    /// Whether `job` may run now
+    pub fn allows_now(&self, job: BackgroundJob) -> bool {
+        self.allows(job, Local::now().naive_local())
+    }
+
+    /// The first minute from `from` on when `job` may run (`from` itself if
+    /// it may run now), or `None` if the rules never allow it
+    pub fn next_allowed(&self, job: BackgroundJob, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.allows(job, from) {
+            return Some(from);
+        }
+        let mut at = from.with_second(0)?.with_nanosecond(0)?;
+        let limit = from + Duration::days(LOOKAHEAD_DAYS);
+        while at < limit {
+            at += Duration::minutes(1);
+            if self.allows(job, at) {
+                return Some(at);
+            }
+        }
+        None
+    }
+
+    /// Wait until `job` may run
+    pub async fn wait_until_allowed(&self, job: BackgroundJob) {
+        loop {
+            let now = Local::now().naive_local();
+            let wait = match self.next_allowed(job, now) {
+                Some(at) if at <= now => return,
+                Some(at) => (at - now).to_std().unwrap_or(MAX_WAIT).min(MAX_WAIT),
+                None => MAX_WAIT,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// "until 18:00" / "until Mon 09:00" for messages about waiting `job`
+    pub fn describe_wait(&self, job: BackgroundJob) -> String {
+        let now = Local::now().naive_local();
+        match self.next_allowed(job, now) {
+            Some(at) if at.date() == now.date() => format!("until {}", at.format("%H:%M")),
+            Some(at) => format!("until {}", at.format("%a %H:%M")),
+            None => "until [schedule] allows it".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn schedule(toml: &str) -> ScheduleConfig {
+        let config: ScheduleConfig = toml::from_str(toml).unwrap();
+        config.validate().unwrap();
+        config
+    }
+
+    // 2026-10-16 is a Friday
+    const FRI: &str = "2026-10-16";
+    const SAT: &str = "2026-10-17";
+
+    #[test]
+    fn test_quiet_window_blocks_only_its_jobs() {
+        let config = schedule(
+            r#"
+            [[rules]]
+            jobs = ["training", "brain"]
+            quiet = "09:00-18:00"
+            "#,
+        );
+        assert!(!config.allows(BackgroundJob::Training, at(FRI, "09:00")));
+        assert!(!config.allows(BackgroundJob::Brain, at(FRI, "17:59")));
+        assert!(config.allows(BackgroundJob::Training, at(FRI, "18:00")));
+        assert!(config.allows(BackgroundJob::Training, at(FRI, "08:59")));
+        assert!(config.allows(BackgroundJob::Agent, at(FRI, "12:00")));
+    }
+
+    #[test]
+    fn test_only_window_wraps_past_midnight() {
+        let config = schedule(
+            r#"
+            [[rules]]
+            jobs = ["agent"]
+            only = "23:00-06:00"
+            "#,
+        );
+        assert!(config.allows(BackgroundJob::Agent, at(FRI, "23:30")));
+        assert!(config.allows(BackgroundJob::Agent, at(SAT, "05:59")));
+        assert!(!config.allows(BackgroundJob::Agent, at(SAT, "06:00")));
+        assert!(!config.allows(BackgroundJob::Agent, at(FRI, "22:59")));
+        assert!(config.allows(BackgroundJob::Training, at(FRI, "12:00")));
+    }
+
+    #[test]
+    fn test_days_apply_to_the_day_a_window_starts() {
+        let config = schedule(
+            r#"
+            [[rules]]
+            jobs = ["training"]
+            quiet = "22:00-02:00"
+            days = ["fri"]
+            "#,
+        );
+        assert!(!config.allows(BackgroundJob::Training, at(FRI, "23:00")));
+        // Friday's window runs into Saturday morning
+        assert!(!config.allows(BackgroundJob::Training, at(SAT, "01:00")));
+        // Saturday's own window doesn't exist
+        assert!(config.allows(BackgroundJob::Training, at(SAT, "23:00")));
+    }
+
+    #[test]
+    fn test_next_allowed() {
+        let config = schedule(
+            r#"
+            [[rules]]
+            jobs = ["brain"]
+            quiet = "09:00-18:00"
+            "#,
+        );
+        let now = at(FRI, "10:15");
+        assert_eq!(
+            config.next_allowed(BackgroundJob::Brain, now),
+            Some(at(FRI, "18:00"))
+        );
+        assert_eq!(config.next_allowed(BackgroundJob::Agent, now), Some(now));
+
+        let never = schedule(
+            r#"
+            [[rules]]
+            jobs = ["brain"]
+            quiet = "00:00-00:00"
+            "#,
+        );
+        assert_eq!(never.next_allowed(BackgroundJob::Brain, now), None);
+    }
+
+    #[test]
+    fn test_defaults_and_validation() {
+        let config = ScheduleConfig::default();
+        assert!(config.is_default());
+        assert!(config.allows(BackgroundJob::Agent, at(FRI, "12:00")));
+
+        let both: ScheduleConfig = toml::from_str(
+            r#"
+            [[rules]]
+            jobs = ["agent"]
+            quiet = "09:00-18:00"
+            only = "23:00-06:00"
+            "#,
+        )
+        .unwrap();
+        assert!(both.validate().is_err());
+
+        assert!(toml::from_str::<ScheduleConfig>(
+            r#"
+            [[rules]]
+            jobs = ["agent"]
+            quiet = "9am-6pm"
+            "#,
+        )
+        .is_err());
+
+        let niced = ScheduleConfig {
+            nice: 25,
+            ..Default::default()
+        };
+        assert!(niced.validate().is_err());
+    }
+
+    #[test]
+    fn test_time_window_round_trips() {
+        let window: TimeWindow = "23:00-06:30".parse().unwrap();
+        assert_eq!(window.to_string(), "23:00-06:30");
+    }
+}
@@ -0,0 +1,278 @@
+// Access log — one JSON line per HTTP request
+//
+// `daemon.log` is a tracing stream meant for humans and mixes request handling
+// with model-loading and training chatter.  The access log is for analysis: a
+// `record_access` middleware writes one line per request to
+// `~/.finch/access.jsonl` with the route, status and latency, and handlers add
+// what only they know (session, provider, local-vs-cloud decision, tokens) by
+// attaching an `AccessDetails` extension to their response.  The file is
+// rotated by size: `access.jsonl` → `access.jsonl.1` → … → `access.jsonl.N`.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Per-request facts only the handler knows.
+///
+/// Insert into the response extensions; fields left `None` are omitted.
+#[derive(Debug, Clone, Default)]
+pub struct AccessDetails {
+    pub session_id: Option<String>,
+    /// Provider that produced the answer ("local" for the local model)
+    pub provider: Option<String>,
+    /// Routing outcome: "local", "forward", or a "*fallback" variant
+    pub decision: Option<String>,
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+}
+
+/// One line of the access log
+#[derive(Debug, Serialize)]
+struct AccessEntry<'a> {
+    timestamp: String,
+    method: &'a str,
+    route: &'a str,
+    status: u16,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decision: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_tokens: Option<u32>,
+}
+
+/// Size-rotated JSONL writer
+pub struct AccessLog {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Mutex<Option<File>>,
+}
+
+impl AccessLog {
+    /// Log to `path`, rotating once it exceeds `max_bytes` and keeping `keep`
+    /// rotated files.
+    pub fn new(path: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            keep,
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one entry.  Failures are logged, never returned: a full disk
+    /// must not fail the request being logged.
+    fn write(&self, entry: &AccessEntry<'_>) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize access log entry: {}", e);
+                return;
+            }
+        };
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = self.append(&mut file, &line) {
+            tracing::warn!("Failed to write access log {}: {}", self.path.display(), e);
+            // Reopen on the next request
+            *file = None;
+        }
+    }
+
+    fn append(&self, file: &mut Option<File>, line: &str) -> std::io::Result<()> {
+        if let Some(f) = file.as_ref() {
+            if f.metadata()?.len() >= self.max_bytes {
+                *file = None;
+                self.rotate()?;
+            }
+        }
+        if file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let f = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            // A previous run may have left a full file behind
+            if f.metadata()?.len() >= self.max_bytes {
+                drop(f);
+                self.rotate()?;
+                return self.append(file, line);
+            }
+            *file = Some(f);
+        }
+        writeln!(file.as_mut().unwrap(), "{}", line)
+    }
+
+    /// Shift `access.jsonl.{i}` to `.{i+1}`, dropping the oldest.
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.keep == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(self.rotated(self.keep));
+        for i in (1..self.keep).rev() {
+            let from = self.rotated(i);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated(i + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+}
+
+/// Middleware: write an access log line for every request.
+///
+/// Latency is measured to the response head; for streamed completions that is
+/// time-to-first-byte.
+pub async fn record_access(
+    State(log): State<Arc<AccessLog>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    // The route template ("/v1/session/:id"), not the raw path, so entries
+    // group cleanly and session ids in URLs don't leak into the route field
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let response = next.run(request).await;
+
+    let details = response
+        .extensions()
+        .get::<AccessDetails>()
+        .cloned()
+        .unwrap_or_default();
+    log.write(&AccessEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        method: &method,
+        route: &route,
+        status: response.status().as_u16(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        session_id: details.session_id.as_deref(),
+        provider: details.provider.as_deref(),
+        decision: details.decision.as_deref(),
+        input_tokens: details.input_tokens,
+        output_tokens: details.output_tokens,
+    });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn entry(route: &str) -> AccessEntry<'_> {
+        AccessEntry {
+            timestamp: "t".to_string(),
+            method: "POST",
+            route,
+            status: 200,
+            latency_ms: 1,
+            session_id: None,
+            provider: None,
+            decision: None,
+            input_tokens: None,
+            output_tokens: None,
+        }
+    }
+
+    fn lines(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_n_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("access.jsonl");
+        let log = AccessLog::new(path.clone(), 1, 2);
+
+        for route in ["/a", "/b", "/c", "/d"] {
+            log.write(&entry(route));
+        }
+
+        // Every write fills the 1-byte budget, so each entry ends up alone
+        assert_eq!(lines(&path)[0]["route"], "/d");
+        assert_eq!(lines(&log.rotated(1))[0]["route"], "/c");
+        assert_eq!(lines(&log.rotated(2))[0]["route"], "/b");
+        assert!(!log.rotated(3).exists());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_merges_handler_details() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = Arc::new(AccessLog::new(dir.path().join("access.jsonl"), 1 << 20, 1));
+
+        let app = Router::new()
+            .route(
+                "/v1/session/:id",
+                get(|| async {
+                    let mut response = StatusCode::ACCEPTED.into_response();
+                    response.extensions_mut().insert(AccessDetails {
+                        session_id: Some("s1".to_string()),
+                        provider: Some("claude".to_string()),
+                        decision: Some("forward".to_string()),
+                        input_tokens: Some(12),
+                        output_tokens: Some(34),
+                    });
+                    response
+                }),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::clone(&log),
+                record_access,
+            ));
+
+        for uri in ["/v1/session/abc", "/health"] {
+            let request = Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let entries = lines(log.path());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["route"], "/v1/session/:id");
+        assert_eq!(entries[0]["status"], 202);
+        assert_eq!(entries[0]["session_id"], "s1");
+        assert_eq!(entries[0]["decision"], "forward");
+        assert_eq!(entries[0]["output_tokens"], 34);
+        assert_eq!(entries[1]["route"], "/health");
+        assert!(entries[1].get("provider").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+// Activity feed — a live view of what the daemon is doing
+//
+// `GET /v1/events` streams one SSE event per thing that happens on the node:
+// every answered HTTP request (route, status, latency, routing decision,
+// tokens, whether it came in over the worker listener) and every daemon event
+// that is also offered to webhooks (training batches, agent tasks, provider
+// circuits).  `finch tui --attach` renders the feed as a dashboard.
+//
+// Nothing is kept for late subscribers, and with nobody watching publishing
+// is a no-op.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::broadcast;
+
+use super::{AccessDetails, WebhookEvent, WorkerTraffic};
+
+/// Route of the feed itself, which is left out of it
+pub const EVENTS_ROUTE: &str = "/v1/events";
+
+/// Activity a slow subscriber may fall behind by before it misses some
+const FEED_CAPACITY: usize = 256;
+
+/// One item of the activity feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Activity {
+    /// An HTTP request was answered
+    Request(RequestActivity),
+    /// A daemon event (see `WebhookEvent`)
+    Event {
+        timestamp: DateTime<Utc>,
+        /// Event name, e.g. "training.batch_complete"
+        name: String,
+        /// One-line summary
+        text: String,
+    },
+}
+
+/// An answered HTTP request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestActivity {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    /// Route template, e.g. "/v1/chat/completions"
+    pub route: String,
+    pub status: u16,
+    /// Time to the response head (time-to-first-byte for streams)
+    pub latency_ms: u64,
+    /// Arrived over the worker listener
+    #[serde(default)]
+    pub worker: bool,
+    /// Provider that produced the answer ("local" for the local model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Routing outcome: "local", "forward", "cache" or a "*fallback" variant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u32>,
+}
+
+impl RequestActivity {
+    /// Output tokens per second over the whole request, when known
+    pub fn tokens_per_sec(&self) -> Option<f64> {
+        let tokens = self.output_tokens.filter(|&t| t > 0)?;
+        (self.latency_ms > 0).then(|| tokens as f64 * 1000.0 / self.latency_ms as f64)
+    }
+}
+
+impl Activity {
+    /// Feed item for a daemon event
+    pub fn event(event: &WebhookEvent) -> Self {
+        Self::Event {
+            timestamp: Utc::now(),
+            name: event.name().to_string(),
+            text: event.text(),
+        }
+    }
+
+    /// SSE event name: "request" or "event"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Request(_) => "request",
+            Self::Event { .. } => "event",
+        }
+    }
+}
+
+/// Fans activity out to `GET /v1/events` subscribers
+pub struct ActivityFeed {
+    tx: broadcast::Sender<Activity>,
+}
+
+impl ActivityFeed {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(FEED_CAPACITY);
+        Self { tx }
+    }
+
+    /// Whether anyone is subscribed
+    pub fn is_watched(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    /// Send `activity` to every current subscriber
+    pub fn publish(&self, activity: Activity) {
+        // Err only means nobody is watching
+        let _ = self.tx.send(activity);
+    }
+
+    /// Receive activity published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Activity> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for ActivityFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Middleware: publish every answered request to the activity feed.
+///
+/// Handler-provided `AccessDetails` supply the routing decision and tokens,
+/// as for the access log.
+pub async fn record_activity(
+    State(feed): State<Arc<ActivityFeed>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !feed.is_watched() {
+        return next.run(request).await;
+    }
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let worker = request.extensions().get::<WorkerTraffic>().is_some();
+
+    let response = next.run(request).await;
+    if route == EVENTS_ROUTE {
+        return response;
+    }
+
+    let details = response
+        .extensions()
+        .get::<AccessDetails>()
+        .cloned()
+        .unwrap_or_default();
+    feed.publish(Activity::Request(RequestActivity {
+        timestamp: Utc::now(),
+        method,
+        route,
+        status: response.status().as_u16(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        worker,
+        provider: details.provider,
+        decision: details.decision,
+        input_tokens: details.input_tokens,
+        output_tokens: details.output_tokens,
+    }));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_middleware_publishes_answered_requests() {
+        let feed = Arc::new(ActivityFeed::new());
+        let app = Router::new()
+            .route(
+                "/v1/chat/completions",
+                get(|| async {
+                    let mut response = StatusCode::OK.into_response();
+                    response.extensions_mut().insert(AccessDetails {
+                        provider: Some("local".to_string()),
+                        decision: Some("local".to_string()),
+                        output_tokens: Some(50),
+                        ..Default::default()
+                    });
+                    response
+                }),
+            )
+            .route(EVENTS_ROUTE, get(|| async { "" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::clone(&feed),
+                record_activity,
+            ));
+        let send = |uri: &str| {
+            let request = Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // Unwatched: nothing to publish to
+        send("/v1/chat/completions").await.unwrap();
+        let mut rx = feed.subscribe();
+        send(EVENTS_ROUTE).await.unwrap();
+        send("/v1/chat/completions").await.unwrap();
+
+        let Activity::Request(request) = rx.recv().await.unwrap() else {
+            panic!("expected a request");
+        };
+        assert_eq!(request.route, "/v1/chat/completions");
+        assert_eq!(request.status, 200);
+        assert_eq!(request.decision.as_deref(), Some("local"));
+        assert!(!request.worker);
+        assert!(rx.try_recv().is_err(), "the feed's own route is left out");
+    }
+
+    #[test]
+    fn test_activity_wire_format() {
+        let activity = Activity::event(&WebhookEvent::TrainingBatchComplete { examples: 8 });
+        let json = serde_json::to_value(&activity).unwrap();
+        assert_eq!(json["type"], "event");
+        assert_eq!(json["name"], "training.batch_complete");
+        assert_eq!(serde_json::from_value::<Activity>(json).unwrap(), activity);
+
+        let request = RequestActivity {
+            timestamp: Utc::now(),
+            method: "POST".to_string(),
+            route: "/v1/messages".to_string(),
+            status: 200,
+            latency_ms: 2000,
+            worker: true,
+            provider: None,
+            decision: None,
+            input_tokens: None,
+            output_tokens: Some(100),
+        };
+        assert_eq!(request.tokens_per_sec(), Some(50.0));
+        let json = serde_json::to_value(Activity::Request(request)).unwrap();
+        assert_eq!(json["type"], "request");
+        assert_eq!(json["worker"], true);
+        assert!(json.get("provider").is_none());
+    }
+}
@@ -1,14 +1,17 @@
 // Brain Registry — tracks daemon-side brain sessions
 //
 // Each brain session runs as a tokio task, intercepts AskUserQuestion and
-// PresentPlan tool calls, and communicates back to the REPL via polling.
+// PresentPlan tool calls, and communicates back to the REPL via polling or
+// by subscribing to the brain's progress events (GET /v1/brains/:id/events).
 
+use crate::scheduling::{BackgroundJob, ScheduleConfig};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{broadcast, oneshot, RwLock};
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
 /// Convert a task description into a URL-safe slug.
@@ -65,6 +68,23 @@ pub enum PlanResponse {
     Reject,
 }
 
+/// Progress event pushed to subscribers of a brain (SSE `/v1/brains/:id/events`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum BrainEvent {
+    /// Full detail at subscription time (first event of every stream)
+    Snapshot { detail: BrainDetail },
+    /// A line was appended to the event log
+    Log { line: String },
+    /// The brain changed state; `Dead` is the last event
+    State { state: BrainState },
+    /// Final summary, sent just before the brain goes `Dead`
+    Summary { text: String },
+}
+
+/// Buffered events per subscriber before it starts lagging
+const BRAIN_EVENT_CAPACITY: usize = 256;
+
 /// A single brain session entry in the registry.
 pub struct BrainEntry {
     pub id: Uuid,
@@ -79,6 +99,12 @@ pub struct BrainEntry {
     pub created_at: Instant,
     /// Final summary text produced by the brain (set when it finishes naturally).
     pub final_summary: Option<String>,
+    /// Progress events for subscribers
+    pub events: broadcast::Sender<BrainEvent>,
+    /// Handle to the brain's tokio task, used to stop it on cancel
+    pub handle: Option<AbortHandle>,
+    /// Workspace that spawned the brain (`None` on a single-tenant daemon)
+    pub workspace: Option<String>,
 }
 
 /// Serializable summary of a brain (for GET /v1/brains list).
@@ -92,7 +118,7 @@ pub struct BrainSummary {
 }
 
 /// Full detail of a brain (for GET /v1/brains/:id).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BrainDetail {
     pub id: Uuid,
     pub name: String,
@@ -106,19 +132,25 @@ pub struct BrainDetail {
 }
 
 /// Serializable view of a pending question.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PendingQuestionView {
     pub question: String,
     pub options: Vec<String>,
 }
 
 /// Serializable view of a pending plan.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PendingPlanView {
     pub plan: String,
 }
 
 impl BrainEntry {
+    /// Change state and notify subscribers.
+    fn set_state(&mut self, state: BrainState) {
+        self.state = state.clone();
+        let _ = self.events.send(BrainEvent::State { state });
+    }
+
     pub fn to_summary(&self) -> BrainSummary {
         BrainSummary {
             id: self.id,
@@ -238,6 +270,11 @@ impl BrainRegistry {
 
     /// Insert a new entry, returning the assigned name (slug, with collision suffix).
     pub async fn insert(&self, id: Uuid, task: String) -> String {
+        self.insert_for(id, task, None).await
+    }
+
+    /// Insert a new entry owned by `workspace`, returning the assigned name.
+    pub async fn insert_for(&self, id: Uuid, task: String, workspace: Option<String>) -> String {
         let base = slug_task(&task);
         let mut names = self.names.write().await;
         let mut brains = self.brains.write().await;
@@ -269,6 +306,9 @@ impl BrainRegistry {
                 pending_plan: None,
                 created_at: Instant::now(),
                 final_summary: None,
+                events: broadcast::channel(BRAIN_EVENT_CAPACITY).0,
+                handle: None,
+                workspace,
             },
         );
 
@@ -279,10 +319,32 @@ impl BrainRegistry {
     pub async fn append_log(&self, id: Uuid, line: String) {
         let mut brains = self.brains.write().await;
         if let Some(entry) = brains.get_mut(&id) {
+            let _ = entry.events.send(BrainEvent::Log { line: line.clone() });
             entry.event_log.push(line);
         }
     }
 
+    /// Hold brain `id` until `schedule` allows brains, noting the wait in its log.
+    pub async fn wait_for_schedule(&self, id: Uuid, schedule: &ScheduleConfig) {
+        if schedule.allows_now(BackgroundJob::Brain) {
+            return;
+        }
+        let wait = schedule.describe_wait(BackgroundJob::Brain);
+        self.append_log(id, format!("[schedule] quiet hours, waiting {}", wait))
+            .await;
+        schedule.wait_until_allowed(BackgroundJob::Brain).await;
+        self.append_log(id, "[schedule] quiet hours over, starting".to_string())
+            .await;
+    }
+
+    /// Remember the brain's tokio task so `cancel` can stop it.
+    pub async fn set_task_handle(&self, id: Uuid, handle: AbortHandle) {
+        let mut brains = self.brains.write().await;
+        if let Some(entry) = brains.get_mut(&id) {
+            entry.handle = Some(handle);
+        }
+    }
+
     /// Transition to WaitingForInput and store the pending question.
     pub async fn set_waiting_for_input(
         &self,
@@ -293,7 +355,7 @@ impl BrainRegistry {
     ) {
         let mut brains = self.brains.write().await;
         if let Some(entry) = brains.get_mut(&id) {
-            entry.state = BrainState::WaitingForInput;
+            entry.set_state(BrainState::WaitingForInput);
             entry.pending_question = Some(PendingQuestion {
                 question,
                 options,
@@ -311,7 +373,7 @@ impl BrainRegistry {
     ) {
         let mut brains = self.brains.write().await;
         if let Some(entry) = brains.get_mut(&id) {
-            entry.state = BrainState::PlanReady;
+            entry.set_state(BrainState::PlanReady);
             entry.pending_plan = Some(PendingPlan { plan, response_tx });
         }
     }
@@ -320,7 +382,7 @@ impl BrainRegistry {
     pub async fn set_dead(&self, id: Uuid) {
         let mut brains = self.brains.write().await;
         if let Some(entry) = brains.get_mut(&id) {
-            entry.state = BrainState::Dead;
+            entry.set_state(BrainState::Dead);
             entry.pending_question = None;
             entry.pending_plan = None;
         }
@@ -330,7 +392,10 @@ impl BrainRegistry {
     pub async fn set_completed_with_summary(&self, id: Uuid, summary: String) {
         let mut brains = self.brains.write().await;
         if let Some(entry) = brains.get_mut(&id) {
-            entry.state = BrainState::Dead;
+            let _ = entry.events.send(BrainEvent::Summary {
+                text: summary.clone(),
+            });
+            entry.set_state(BrainState::Dead);
             entry.pending_question = None;
             entry.pending_plan = None;
             entry.final_summary = Some(summary);
@@ -349,11 +414,21 @@ impl BrainRegistry {
             .take()
             .ok_or_else(|| anyhow::anyhow!("Brain {} has no pending question", id))?;
 
-        entry.state = BrainState::Running;
+        entry.set_state(BrainState::Running);
         let _ = question.response_tx.send(answer);
         Ok(())
     }
 
+    /// Drop an unanswered question and let the brain run on without it.
+    pub async fn expire_question(&self, id: Uuid) {
+        let mut brains = self.brains.write().await;
+        if let Some(entry) = brains.get_mut(&id) {
+            if entry.pending_question.take().is_some() {
+                entry.set_state(BrainState::Running);
+            }
+        }
+    }
+
     /// Respond to a pending plan. Returns Err if no plan is pending.
     pub async fn respond_to_plan(&self, id: Uuid, response: PlanResponse) -> Result<()> {
         let mut brains = self.brains.write().await;
@@ -367,19 +442,25 @@ impl BrainRegistry {
             .ok_or_else(|| anyhow::anyhow!("Brain {} has no pending plan", id))?;
 
         // If approved/rejected → go Dead after response; if changes requested → Running
-        entry.state = match &response {
+        entry.set_state(match &response {
             PlanResponse::ChangesRequested { .. } => BrainState::Running,
             _ => BrainState::Dead,
-        };
+        });
 
         let _ = plan.response_tx.send(response);
         Ok(())
     }
 
-    /// Cancel (remove) a brain by ID. Returns true if found.
+    /// Cancel (remove) a brain by ID, stopping its task. Returns true if found.
     pub async fn cancel(&self, id: Uuid) -> bool {
         let mut brains = self.brains.write().await;
-        if let Some(entry) = brains.remove(&id) {
+        if let Some(mut entry) = brains.remove(&id) {
+            if let Some(handle) = entry.handle.take() {
+                handle.abort();
+            }
+            if entry.state != BrainState::Dead {
+                entry.set_state(BrainState::Dead);
+            }
             // Also remove from name index
             let mut names = self.names.write().await;
             names.remove(&entry.name);
@@ -409,12 +490,47 @@ impl BrainRegistry {
         result
     }
 
+    /// Summaries of the brains `workspace` spawned (`all` includes dead ones).
+    pub async fn list_for(&self, workspace: &str, all: bool) -> Vec<BrainSummary> {
+        let brains = self.brains.read().await;
+        let mut result: Vec<BrainSummary> = brains
+            .values()
+            .filter(|e| e.workspace.as_deref() == Some(workspace))
+            .filter(|e| all || e.state != BrainState::Dead)
+            .map(|e| e.to_summary())
+            .collect();
+        result.sort_by_key(|s| s.age_secs);
+        result
+    }
+
+    /// Whether brain `id` exists and was spawned by `workspace`.
+    pub async fn belongs_to(&self, id: Uuid, workspace: &str) -> bool {
+        let brains = self.brains.read().await;
+        brains
+            .get(&id)
+            .is_some_and(|e| e.workspace.as_deref() == Some(workspace))
+    }
+
     /// Get full detail for a brain.
     pub async fn get_detail(&self, id: Uuid) -> Option<BrainDetail> {
         let brains = self.brains.read().await;
         brains.get(&id).map(|e| e.to_detail())
     }
 
+    /// Current detail plus a receiver for every event after it.
+    ///
+    /// Taken under one lock so no event falls between the snapshot and the
+    /// subscription.
+    pub async fn subscribe(
+        &self,
+        id: Uuid,
+    ) -> Option<(BrainDetail, broadcast::Receiver<BrainEvent>)> {
+        let brains = self.brains.read().await;
+        brains
+            .get(&id)
+            .map(|e| (e.to_detail(), e.events.subscribe()))
+    }
+
     /// Lookup brain ID by name.
     pub async fn id_by_name(&self, name: &str) -> Option<Uuid> {
         let names = self.names.read().await;
@@ -513,6 +629,25 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_expired_question_cannot_be_answered() {
+        let registry = BrainRegistry::new();
+        let id = Uuid::new_v4();
+        registry.insert(id, "test task".to_string()).await;
+
+        let (tx, _rx) = oneshot::channel();
+        registry
+            .set_waiting_for_input(id, "question?".to_string(), vec![], tx)
+            .await;
+        registry.expire_question(id).await;
+
+        assert_eq!(registry.brains.read().await[&id].state, BrainState::Running);
+        assert!(registry
+            .answer_question(id, "late".to_string())
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_respond_to_plan_approve() {
         let registry = BrainRegistry::new();
@@ -623,6 +758,93 @@ mod tests {
         assert!(detail.final_summary.is_none());
     }
 
+    #[tokio::test]
+    async fn test_wait_for_schedule_holds_brains_in_quiet_hours() {
+        let registry = BrainRegistry::new();
+        let id = Uuid::new_v4();
+        registry.insert(id, "a task".to_string()).await;
+
+        // No rules: starts at once, nothing logged
+        registry
+            .wait_for_schedule(id, &ScheduleConfig::default())
+            .await;
+        assert!(registry.get_detail(id).await.unwrap().event_log.is_empty());
+
+        let quiet: ScheduleConfig = toml::from_str(
+            r#"
+            [[rules]]
+            jobs = ["brain"]
+            quiet = "00:00-00:00"
+            "#,
+        )
+        .unwrap();
+        let waited = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            registry.wait_for_schedule(id, &quiet),
+        )
+        .await;
+        assert!(waited.is_err(), "brain should still be waiting");
+        let log = registry.get_detail(id).await.unwrap().event_log;
+        assert!(log[0].starts_with("[schedule] quiet hours, waiting"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_sees_snapshot_then_events() {
+        let registry = BrainRegistry::new();
+        let id = Uuid::new_v4();
+        registry.insert(id, "trace the bug".to_string()).await;
+        registry
+            .append_log(id, "[turn 1] looking".to_string())
+            .await;
+
+        let (detail, mut rx) = registry.subscribe(id).await.unwrap();
+        assert_eq!(detail.event_log, vec!["[turn 1] looking".to_string()]);
+
+        registry.append_log(id, "[tool] grep".to_string()).await;
+        registry
+            .set_completed_with_summary(id, "Found it".to_string())
+            .await;
+
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            BrainEvent::Log {
+                line: "[tool] grep".to_string()
+            }
+        );
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            BrainEvent::Summary {
+                text: "Found it".to_string()
+            }
+        );
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            BrainEvent::State {
+                state: BrainState::Dead
+            }
+        );
+        assert!(registry.subscribe(Uuid::new_v4()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_task_and_notifies() {
+        let registry = BrainRegistry::new();
+        let id = Uuid::new_v4();
+        registry.insert(id, "long task".to_string()).await;
+        let task = tokio::spawn(std::future::pending::<()>());
+        registry.set_task_handle(id, task.abort_handle()).await;
+        let (_, mut rx) = registry.subscribe(id).await.unwrap();
+
+        assert!(registry.cancel(id).await);
+        assert!(task.await.unwrap_err().is_cancelled());
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            BrainEvent::State {
+                state: BrainState::Dead
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_to_detail_propagates_final_summary() {
         let registry = BrainRegistry::new();
@@ -635,4 +857,28 @@ mod tests {
         let detail = registry.get_detail(id).await.unwrap();
         assert_eq!(detail.final_summary.as_deref(), Some("CPU bottleneck in tokenizer"));
     }
+
+    #[tokio::test]
+    async fn test_brains_are_listed_per_workspace() {
+        let registry = BrainRegistry::new();
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        registry
+            .insert_for(alice, "alice task".to_string(), Some("alice".to_string()))
+            .await;
+        registry
+            .insert_for(bob, "bob task".to_string(), Some("bob".to_string()))
+            .await;
+
+        let listed = registry.list_for("alice", false).await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, alice);
+        assert!(registry.belongs_to(alice, "alice").await);
+        assert!(!registry.belongs_to(alice, "bob").await);
+
+        registry.set_dead(alice).await;
+        assert!(registry.list_for("alice", false).await.is_empty());
+        assert_eq!(registry.list_for("alice", true).await.len(), 1);
+        assert_eq!(registry.list_all().await.len(), 2);
+    }
 }
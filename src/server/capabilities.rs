@@ -0,0 +1,242 @@
+// Capabilities — what a daemon offers, announced in `GET /health`
+//
+// Clients and daemons are upgraded separately: a REPL from a newer release
+// may talk to a daemon that has been running for weeks, and the other way
+// round.  The health response carries a capabilities document so a client
+// can tell at connect time what it may use:
+//
+//   * `protocol_version` changes only when an existing endpoint changes
+//     incompatibly; a daemon still serves clients back to
+//     `min_protocol_version`.  New endpoints don't bump it, they are listed.
+//   * `endpoints` are the routes this daemon serves.
+//   * `models`, `tool_passthrough`, `auth` and `streaming` describe the
+//     local models, client-side tool calls, accepted credentials and stream
+//     formats.
+//
+// `DaemonClient` refuses a daemon whose protocol it can't speak, and checks
+// `endpoints` before using a newer feature, so an old daemon gets "restart
+// the daemon" rather than a bare 404.  Fields are all defaulted, so either
+// side can add to the document without breaking the other.
+
+use serde::{Deserialize, Serialize};
+
+use super::AgentServer;
+
+/// Protocol this build speaks
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest client protocol this daemon still serves
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Version of the tool pass-through: the client sends OpenAI-style `tools`
+/// with a chat request, gets `tool_calls` back and runs them itself
+pub const TOOL_PASSTHROUGH_VERSION: u32 = 1;
+
+/// Routes every daemon of this build serves (`/ui` is added when enabled)
+pub const ENDPOINTS: &[&str] = &[
+    "/health",
+    "/metrics",
+    "/v1/messages",
+    "/v1/session/:id",
+    "/v1/sessions/:id/usage",
+    "/v1/sessions/:id/active-generation",
+    "/admin/sessions/:id/replay/:n",
+    "/v1/status",
+    "/v1/workspace",
+    "/v1/roles",
+    "/v1/roles/worker",
+    super::model_reload::RELOAD_ROUTE,
+    super::activity::EVENTS_ROUTE,
+    "/v1/chat/completions",
+    "/v1/models",
+    "/v1/node/info",
+    "/v1/node/stats",
+    "/v1/brains",
+    "/v1/brains/:id",
+    "/v1/brains/:id/answer",
+    "/v1/brains/:id/plan",
+    "/v1/brains/:id/events",
+    "/v1/brains/shared",
+    "/v1/brains/shared/:name",
+    "/v1/forth/eval",
+    "/v1/forth/define",
+    "/v1/forth/vocab",
+    "/v1/forth/push",
+    "/v1/exec",
+    "/v1/registry/join",
+    "/v1/registry/leave",
+    "/v1/registry/heartbeat",
+    "/v1/registry/peers",
+    "/v1/registry/ledger/:addr",
+    "/v1/registry/ledgers",
+    "/v1/registry/debit",
+    "/v1/settle",
+    "/v1/feedback",
+    "/v1/training/status",
+];
+
+/// Stream formats the daemon produces
+const STREAMING: &[&str] = &[
+    // `stream: true` on /v1/chat/completions: `data: <chunk>` lines, then
+    // `data: [DONE]`
+    "openai_chunks",
+    // Server-sent events with one JSON object per `data:` line (brain
+    // events, the activity feed, model reloads)
+    "sse_events",
+];
+
+/// The capabilities document in the health response.  The default is what
+/// a daemon that predates the document is assumed to offer: nothing known.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    /// finch release the daemon runs
+    pub version: String,
+    /// Routes the daemon serves, with `:name` for path parameters
+    pub endpoints: Vec<String>,
+    /// Local model ids (see `GET /v1/models`), loaded or still loading
+    pub models: Vec<String>,
+    /// Tool pass-through version; 0 when the daemon doesn't accept `tools`
+    pub tool_passthrough: u32,
+    /// Credentials accepted: "none", "workspace_key" and/or "owner_token"
+    pub auth: Vec<String>,
+    /// Stream formats, e.g. "openai_chunks" and "sse_events"
+    pub streaming: Vec<String>,
+}
+
+impl Capabilities {
+    /// The running daemon's capabilities
+    pub fn of(server: &AgentServer) -> Self {
+        let mut endpoints: Vec<String> = ENDPOINTS.iter().map(|e| e.to_string()).collect();
+        if server.config().web_ui {
+            endpoints.push("/ui".to_string());
+        }
+
+        let workspaces = server.workspaces();
+        let mut auth = Vec::new();
+        if !workspaces.requires_key() {
+            auth.push("none".to_string());
+        }
+        if workspaces.is_enabled() {
+            auth.push("workspace_key".to_string());
+        }
+        if workspaces.accepts_owner_token() {
+            auth.push("owner_token".to_string());
+        }
+
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            endpoints,
+            models: server
+                .local_models()
+                .slots()
+                .iter()
+                .map(|slot| slot.id.clone())
+                .collect(),
+            tool_passthrough: TOOL_PASSTHROUGH_VERSION,
+            auth,
+            streaming: STREAMING.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Whether the document came from a daemon that predates it (and so
+    /// says nothing about what the daemon supports)
+    pub fn is_legacy(&self) -> bool {
+        self.protocol_version == 0
+    }
+
+    /// Whether the daemon serves `endpoint` (as listed, `:name` for path
+    /// parameters).  Assumed for legacy daemons, which are left to answer.
+    pub fn supports(&self, endpoint: &str) -> bool {
+        self.is_legacy() || self.endpoints.iter().any(|e| e == endpoint)
+    }
+
+    /// Why a client speaking `PROTOCOL_VERSION` can't use this daemon, if
+    /// it can't
+    pub fn incompatibility(&self) -> Option<String> {
+        if self.is_legacy() {
+            return None;
+        }
+        if self.min_protocol_version > PROTOCOL_VERSION {
+            return Some(format!(
+                "the daemon runs finch {} (protocol {}), which no longer serves this client \
+                 (protocol {}); upgrade finch",
+                self.version, self.protocol_version, PROTOCOL_VERSION
+            ));
+        }
+        if self.protocol_version < MIN_PROTOCOL_VERSION {
+            return Some(format!(
+                "the daemon runs finch {} (protocol {}), older than this client supports \
+                 (protocol {}); restart it with `finch daemon-stop` and `finch daemon-start`",
+                self.version, self.protocol_version, MIN_PROTOCOL_VERSION
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current() -> Capabilities {
+        Capabilities {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            version: "9.9.9".to_string(),
+            endpoints: vec!["/health".to_string(), "/v1/events".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_documents_from_other_versions_parse() {
+        // A newer daemon may add fields; an older one may leave them out
+        let newer: Capabilities = serde_json::from_str(
+            r#"{"protocol_version": 1, "min_protocol_version": 1, "endpoints": ["/health"],
+                "batching": {"max": 8}}"#,
+        )
+        .unwrap();
+        assert!(newer.supports("/health"));
+        assert!(!newer.supports("/v1/models/reload"));
+
+        let legacy: Capabilities = serde_json::from_str("{}").unwrap();
+        assert!(legacy.is_legacy());
+        assert!(legacy.supports("/v1/models/reload"));
+        assert_eq!(legacy.incompatibility(), None);
+    }
+
+    #[test]
+    fn test_incompatible_protocols_are_explained() {
+        assert_eq!(current().incompatibility(), None);
+
+        let newer = Capabilities {
+            protocol_version: PROTOCOL_VERSION + 2,
+            min_protocol_version: PROTOCOL_VERSION + 1,
+            ..current()
+        };
+        assert!(newer.incompatibility().unwrap().contains("upgrade finch"));
+    }
+
+    #[test]
+    fn test_every_route_is_announced() {
+        // Routes are registered with string literals in handlers.rs
+        let source = include_str!("handlers.rs");
+        let routes = source
+            .split(".route(")
+            .skip(1)
+            .filter_map(|rest| rest.trim_start().strip_prefix('"')?.split('"').next())
+            .filter(|route| *route != "/ui");
+        for route in routes {
+            assert!(
+                ENDPOINTS.contains(&route),
+                "{} is missing from ENDPOINTS",
+                route
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Provider circuit breaker
+//
+// After `threshold` consecutive failures a provider's circuit opens and the
+// daemon fails fast for `cooldown` instead of making every caller wait on a
+// provider that is down.  Once the cooldown has passed, requests are let
+// through again: a success closes the circuit, the next failure re-opens it.
+//
+// A rate-limited provider isn't failing, so a 429 doesn't count towards the
+// threshold; it holds the circuit open until the provider's reset instead.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    rate_limited_until: Option<Instant>,
+}
+
+/// One provider's circuit as reported by `/health`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderHealth {
+    pub name: String,
+    /// "ok", "degraded" (recent failures), "rate_limited" (waiting for the
+    /// provider's reset) or "open" (failing fast)
+    pub status: &'static str,
+    pub consecutive_failures: u32,
+    /// Seconds until an open or rate-limited circuit lets requests through
+    /// again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Per-provider circuit state, keyed by provider name
+pub struct ProviderCircuits {
+    threshold: u32,
+    cooldown: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl ProviderCircuits {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// `Err(remaining)` while `provider`'s circuit is open or rate limited
+    pub fn check(&self, provider: &str) -> Result<(), Duration> {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(provider) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let blocked_until = [circuit.open_until, circuit.rate_limited_until]
+            .into_iter()
+            .flatten()
+            .filter(|until| *until > now)
+            .max();
+        if let Some(until) = blocked_until {
+            return Err(until - now);
+        }
+        // Cooldown or rate-limit window over: let requests through on trial
+        circuit.open_until = None;
+        circuit.rate_limited_until = None;
+        Ok(())
+    }
+
+    /// Current state of `provider`'s circuit (read-only, unlike `check`)
+    pub fn health(&self, provider: &str) -> ProviderHealth {
+        let circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.get(provider);
+        let consecutive_failures = circuit.map_or(0, |c| c.consecutive_failures);
+        let now = Instant::now();
+        let open = circuit
+            .and_then(|c| c.open_until)
+            .and_then(|until| until.checked_duration_since(now));
+        let rate_limited = circuit
+            .and_then(|c| c.rate_limited_until)
+            .and_then(|until| until.checked_duration_since(now));
+        let status = match (open, rate_limited, consecutive_failures) {
+            (Some(_), _, _) => "open",
+            (None, Some(_), _) => "rate_limited",
+            (None, None, 0) => "ok",
+            (None, None, _) => "degraded",
+        };
+        ProviderHealth {
+            name: provider.to_string(),
+            status,
+            consecutive_failures,
+            retry_after_secs: open.max(rate_limited).map(|d| d.as_secs().max(1)),
+        }
+    }
+
+    pub fn record_success(&self, provider: &str) {
+        self.circuits.lock().unwrap().remove(provider);
+    }
+
+    /// Hold `provider`'s circuit open until its rate-limit window resets
+    pub fn record_rate_limit(&self, provider: &str, retry_after: Duration) {
+        let until = Instant::now() + retry_after;
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(provider.to_string()).or_default();
+        circuit.rate_limited_until = circuit.rate_limited_until.max(Some(until));
+    }
+
+    /// Count a failure.  Returns the consecutive failure count when this
+    /// failure opened the circuit.
+    pub fn record_failure(&self, provider: &str) -> Option<u32> {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(provider.to_string()).or_default();
+        circuit.consecutive_failures += 1;
+        if circuit.consecutive_failures >= self.threshold && circuit.open_until.is_none() {
+            circuit.open_until = Some(Instant::now() + self.cooldown);
+            return Some(circuit.consecutive_failures);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_closes_on_success() {
+        let circuits = ProviderCircuits::new(3, Duration::from_secs(60));
+        assert_eq!(circuits.record_failure("openai"), None);
+        assert_eq!(circuits.record_failure("openai"), None);
+        assert_eq!(circuits.record_failure("openai"), Some(3));
+        assert!(circuits.check("openai").is_err());
+        assert!(circuits.check("claude").is_ok());
+
+        // Failures of in-flight requests don't re-announce an open circuit
+        assert_eq!(circuits.record_failure("openai"), None);
+
+        circuits.record_success("openai");
+        assert!(circuits.check("openai").is_ok());
+    }
+
+    #[test]
+    fn test_health_reports_failures_and_open_circuits() {
+        let circuits = ProviderCircuits::new(2, Duration::from_secs(60));
+        assert_eq!(circuits.health("openai").status, "ok");
+
+        circuits.record_failure("openai");
+        let health = circuits.health("openai");
+        assert_eq!(
+            (health.status, health.consecutive_failures),
+            ("degraded", 1)
+        );
+        assert_eq!(health.retry_after_secs, None);
+
+        circuits.record_failure("openai");
+        let health = circuits.health("openai");
+        assert_eq!(health.status, "open");
+        assert!(health.retry_after_secs.is_some_and(|s| s <= 60));
+    }
+
+    #[test]
+    fn test_rate_limit_holds_without_counting_as_failure() {
+        let circuits = ProviderCircuits::new(1, Duration::from_secs(60));
+        circuits.record_rate_limit("claude", Duration::from_secs(20));
+        assert!(circuits
+            .check("claude")
+            .is_err_and(|d| d <= Duration::from_secs(20)));
+
+        let health = circuits.health("claude");
+        assert_eq!(
+            (health.status, health.consecutive_failures),
+            ("rate_limited", 0)
+        );
+        assert!(health.retry_after_secs.is_some_and(|s| s <= 20));
+
+        circuits.record_rate_limit("grok", Duration::ZERO);
+        assert!(circuits.check("grok").is_ok());
+    }
+
+    #[test]
+    fn test_trial_failure_after_cooldown_reopens() {
+        let circuits = ProviderCircuits::new(1, Duration::ZERO);
+        assert_eq!(circuits.record_failure("grok"), Some(1));
+        assert!(circuits.check("grok").is_ok());
+        assert_eq!(circuits.record_failure("grok"), Some(2));
+    }
+}
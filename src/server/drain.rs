@@ -0,0 +1,218 @@
+// Graceful shutdown — stop admitting work, let in-flight work finish
+//
+// On SIGTERM the daemon flips `DrainController` into draining mode.  From then
+// on new sessions, brains, and fresh conversations are refused with `Draining`
+// (503 + Retry-After), while requests that continue an existing tool loop are
+// still admitted so an agent turn is not cut off mid-tool-call.  Every request
+// and background brain holds an `InFlightGuard`; `wait_idle()` returns once the
+// last guard is dropped or the drain deadline passes.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Retry-After sent while draining — roughly how long a restart takes.
+const DRAIN_RETRY_AFTER_SECS: u64 = 5;
+
+/// Returned when new work arrives after shutdown has begun.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("daemon is shutting down; retry in {retry_after_secs}s")]
+pub struct Draining {
+    /// Suggested client back-off, sent as the `Retry-After` header
+    pub retry_after_secs: u64,
+}
+
+impl Default for Draining {
+    fn default() -> Self {
+        Self {
+            retry_after_secs: DRAIN_RETRY_AFTER_SECS,
+        }
+    }
+}
+
+impl IntoResponse for Draining {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "type": "server_shutting_down",
+                "code": "draining"
+            }
+        });
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, self.retry_after_secs.to_string())],
+            Json(body),
+        )
+            .into_response()
+    }
+}
+
+/// Tracks in-flight work and whether the daemon is draining.
+#[derive(Default)]
+pub struct DrainController {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl DrainController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin draining.  Idempotent.
+    pub fn start(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Number of requests / background tasks currently holding a guard
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Decide whether a unit of work may start.
+    ///
+    /// `continues_turn` is true when the work finishes an agent turn that was
+    /// already running (e.g. a request carrying tool results); those are
+    /// admitted even while draining.
+    pub fn admit(&self, continues_turn: bool) -> Result<(), Draining> {
+        if self.is_draining() && !continues_turn {
+            return Err(Draining::default());
+        }
+        Ok(())
+    }
+
+    /// Count a unit of work as in flight until the guard is dropped.
+    pub fn track(self: &Arc<Self>) -> InFlightGuard {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard {
+            controller: Arc::clone(self),
+        }
+    }
+
+    /// Wait until nothing is in flight, or `deadline` elapses.
+    ///
+    /// Returns `true` if the daemon went idle, `false` on timeout.
+    pub async fn wait_idle(&self, deadline: Duration) -> bool {
+        tokio::time::timeout(deadline, async {
+            loop {
+                // Register for the wakeup before checking, so a guard dropped
+                // between the check and the await is not missed.
+                let notified = self.idle.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// Keeps its unit of work counted as in flight while alive.
+pub struct InFlightGuard {
+    controller: Arc<DrainController>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.controller.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.controller.idle.notify_waiters();
+        }
+    }
+}
+
+/// Middleware: count every HTTP request as in flight until its response body
+/// has been fully sent, so streamed (SSE) completions are drained too.
+pub async fn track_in_flight(
+    State(drain): State<Arc<DrainController>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let guard = drain.track();
+    let response = next.run(request).await;
+    let (parts, body) = response.into_parts();
+    let body = Body::from_stream(body.into_data_stream().map(move |chunk| {
+        let _held = &guard;
+        chunk
+    }));
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guards_count_in_flight_work() {
+        let drain = Arc::new(DrainController::new());
+        let a = drain.track();
+        let b = drain.track();
+        assert_eq!(drain.in_flight(), 2);
+        drop(a);
+        assert_eq!(drain.in_flight(), 1);
+        drop(b);
+        assert_eq!(drain.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_admit_refuses_new_work_while_draining() {
+        let drain = DrainController::new();
+        assert!(drain.admit(false).is_ok());
+
+        drain.start();
+        assert!(drain.is_draining());
+        assert!(drain.admit(false).is_err());
+        // Finishing an in-progress tool loop is still allowed
+        assert!(drain.admit(true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_returns_when_last_guard_drops() {
+        let drain = Arc::new(DrainController::new());
+        let guard = drain.track();
+        drain.start();
+
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert!(drain.wait_idle(Duration::from_secs(5)).await);
+        task.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_idle_gives_up_at_deadline() {
+        let drain = Arc::new(DrainController::new());
+        let _guard = drain.track();
+        assert!(!drain.wait_idle(Duration::from_secs(30)).await);
+        assert_eq!(drain.in_flight(), 1);
+    }
+
+    #[test]
+    fn test_draining_response_is_503_with_retry_after() {
+        let response = Draining::default().into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &DRAIN_RETRY_AFTER_SECS.to_string()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// Cancelling a session's in-flight generation
+//
+// Requests that name a session register their generation here for as long as
+// they run.  `DELETE /v1/sessions/:id/active-generation` fires its token,
+// which drops the provider call (so the upstream stream is closed and stops
+// billing) or ends a local model's SSE stream.  The session itself is left
+// alone: the cancelled turn is simply never added to its history.
+//
+// A newer request for the same session replaces the older entry, so only the
+// latest generation can be cancelled by id.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use dashmap::DashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+
+/// Non-standard "client closed request" status, as used by nginx
+const CANCELLED_STATUS: u16 = 499;
+
+/// Returned by a request whose generation was cancelled.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("generation cancelled for session {session_id}")]
+pub struct GenerationCancelled {
+    pub session_id: String,
+}
+
+impl IntoResponse for GenerationCancelled {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "type": "cancelled",
+                "code": "generation_cancelled"
+            }
+        });
+        let status = StatusCode::from_u16(CANCELLED_STATUS).expect("valid status code");
+        (status, Json(body)).into_response()
+    }
+}
+
+struct Entry {
+    /// Tells this generation apart from a later one for the same session
+    serial: u64,
+    /// Workspace of the request; only it (or the owner) may cancel
+    workspace: Option<String>,
+    token: CancellationToken,
+}
+
+/// Generations currently running, by session id
+#[derive(Default)]
+pub struct ActiveGenerations {
+    next_serial: AtomicU64,
+    active: Arc<DashMap<String, Entry>>,
+}
+
+impl ActiveGenerations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a generation for `session_id` until the returned guard is
+    /// dropped.
+    pub fn begin(&self, session_id: &str, workspace: Option<&str>) -> ActiveGeneration {
+        let serial = self.next_serial.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        self.active.insert(
+            session_id.to_string(),
+            Entry {
+                serial,
+                workspace: workspace.map(str::to_string),
+                token: token.clone(),
+            },
+        );
+        ActiveGeneration {
+            session_id: session_id.to_string(),
+            serial,
+            token,
+            active: Arc::clone(&self.active),
+        }
+    }
+
+    /// Cancel the generation running for `session_id` on behalf of
+    /// `workspace` (`None` = the daemon owner, who may cancel any).
+    ///
+    /// Returns `false` if nothing is running, it was already cancelled, or
+    /// it belongs to another workspace.
+    pub fn cancel(&self, session_id: &str, workspace: Option<&str>) -> bool {
+        let Some(entry) = self.active.get(session_id) else {
+            return false;
+        };
+        if workspace.is_some() && entry.workspace.as_deref() != workspace {
+            return false;
+        }
+        if entry.token.is_cancelled() {
+            return false;
+        }
+        entry.token.cancel();
+        tracing::info!(session_id, "Cancelled active generation");
+        true
+    }
+
+    /// Number of generations running
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+}
+
+/// A registered generation; deregisters itself when dropped
+pub struct ActiveGeneration {
+    session_id: String,
+    serial: u64,
+    token: CancellationToken,
+    active: Arc<DashMap<String, Entry>>,
+}
+
+impl ActiveGeneration {
+    /// Completes once the generation is cancelled
+    pub fn cancelled(&self) -> WaitForCancellationFuture<'_> {
+        self.token.cancelled()
+    }
+
+    /// Drive `work` to completion unless the generation is cancelled first,
+    /// in which case `work` is dropped.
+    pub async fn run<T>(&self, work: impl Future<Output = T>) -> Result<T, GenerationCancelled> {
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => Err(GenerationCancelled {
+                session_id: self.session_id.clone(),
+            }),
+            output = work => Ok(output),
+        }
+    }
+}
+
+impl Drop for ActiveGeneration {
+    fn drop(&mut self) {
+        // A later request for the session may have replaced this entry
+        self.active
+            .remove_if(&self.session_id, |_, entry| entry.serial == self.serial);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_stops_running_work() {
+        let generations = ActiveGenerations::new();
+        let generation = generations.begin("s1", None);
+
+        let run = generation.run(async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            "finished"
+        });
+        let cancel = async {
+            tokio::task::yield_now().await;
+            assert!(generations.cancel("s1", None));
+        };
+        let (result, ()) = tokio::join!(run, cancel);
+        assert_eq!(result.unwrap_err().session_id, "s1");
+
+        // Already cancelled, and gone once the guard is dropped
+        assert!(!generations.cancel("s1", None));
+        drop(generation);
+        assert!(generations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_finished_work_is_not_cancelled() {
+        let generations = ActiveGenerations::new();
+        let generation = generations.begin("s1", None);
+        assert_eq!(generation.run(async { 7 }).await.unwrap(), 7);
+        drop(generation);
+        assert!(!generations.cancel("s1", None));
+        assert!(!generations.cancel("unknown", None));
+    }
+
+    #[test]
+    fn test_workspaces_only_cancel_their_own() {
+        let generations = ActiveGenerations::new();
+        let _generation = generations.begin("s1", Some("team-a"));
+        assert!(!generations.cancel("s1", Some("team-b")));
+        assert!(generations.cancel("s1", Some("team-a")));
+
+        // The daemon owner may cancel any session's generation
+        let _other = generations.begin("s2", Some("team-b"));
+        assert!(generations.cancel("s2", None));
+    }
+
+    #[test]
+    fn test_newer_generation_replaces_older() {
+        let generations = ActiveGenerations::new();
+        let older = generations.begin("s1", None);
+        let newer = generations.begin("s1", None);
+
+        // Dropping the older guard leaves the newer one registered
+        drop(older);
+        assert_eq!(generations.len(), 1);
+        assert!(generations.cancel("s1", None));
+        assert!(newer.token.is_cancelled());
+        drop(newer);
+        assert!(generations.is_empty());
+    }
+}
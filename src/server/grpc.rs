@@ -0,0 +1,586 @@
+// gRPC API — query, stream and tool results over HTTP/2
+//
+// A typed front end to the same pipeline as POST /v1/chat/completions, for
+// clients generated from proto/finch/v1/finch.proto (editor integrations,
+// other languages).  Each call is translated into a `ChatCompletionRequest`
+// and handed to `handle_chat_completions`, so routing, the response cache,
+// the request queue and shutdown draining behave exactly as over HTTP.
+// tonic runs on its own listener because the axum server speaks HTTP/1 only.
+
+// Every conversion fails with a tonic `Status`, the same (large) error type
+// the generated service trait returns
+#![allow(clippy::result_large_err)]
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    Json,
+};
+use futures::{Stream, StreamExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tonic::metadata::MetadataMap;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Code, Request, Response, Status};
+
+use super::openai_types as openai;
+use super::request_queue::{RequestPriority, PRIORITY_HEADER};
+use super::{handle_chat_completions, AgentServer};
+
+/// Types and service traits generated from `proto/finch/v1/finch.proto`
+pub mod proto {
+    tonic::include_proto!("finch.v1");
+}
+
+use proto::finch_server::{Finch, FinchServer};
+
+/// Largest completion body read back from the HTTP pipeline
+const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Result<proto::QueryChunk, Status>> + Send>>;
+
+/// Serve the gRPC API on `listener` until the process exits
+pub async fn serve(server: Arc<AgentServer>, listener: TcpListener) -> anyhow::Result<()> {
+    let incoming = TcpIncoming::from_listener(listener, true, None)
+        .map_err(|e| anyhow::anyhow!("gRPC listener: {}", e))?;
+    tonic::transport::Server::builder()
+        .add_service(FinchServer::new(GrpcService::new(server)))
+        .serve_with_incoming(incoming)
+        .await?;
+    Ok(())
+}
+
+/// `finch.v1.Finch` implementation backed by the HTTP handlers
+pub struct GrpcService {
+    server: Arc<AgentServer>,
+}
+
+impl GrpcService {
+    pub fn new(server: Arc<AgentServer>) -> Self {
+        Self { server }
+    }
+
+    /// Run a request through the chat completions pipeline.
+    /// Non-2xx answers come back as the matching gRPC status.
+    async fn complete(
+        &self,
+        metadata: &MetadataMap,
+        query: proto::QueryRequest,
+        stream: bool,
+    ) -> Result<axum::response::Response, Status> {
+        let (mut headers, request) = to_chat_request(query, stream)?;
+        copy_api_key(metadata, &mut headers);
+        let response =
+            handle_chat_completions(State(Arc::clone(&self.server)), headers, Json(request)).await;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(error_status(response).await)
+        }
+    }
+
+    async fn query_response(
+        &self,
+        metadata: &MetadataMap,
+        query: proto::QueryRequest,
+    ) -> Result<proto::QueryResponse, Status> {
+        let response = self.complete(metadata, query, false).await?;
+        let body = read_body(response.into_body()).await?;
+        let completion: openai::ChatCompletionResponse = serde_json::from_slice(&body)
+            .map_err(|e| Status::internal(format!("Malformed completion: {}", e)))?;
+        Ok(from_completion(completion))
+    }
+}
+
+#[tonic::async_trait]
+impl Finch for GrpcService {
+    async fn query(
+        &self,
+        request: Request<proto::QueryRequest>,
+    ) -> Result<Response<proto::QueryResponse>, Status> {
+        let _in_flight = self.server.drain().track();
+        let metadata = request.metadata().clone();
+        self.query_response(&metadata, request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    type StreamQueryStream = ChunkStream;
+
+    async fn stream_query(
+        &self,
+        request: Request<proto::QueryRequest>,
+    ) -> Result<Response<Self::StreamQueryStream>, Status> {
+        let in_flight = self.server.drain().track();
+        let metadata = request.metadata().clone();
+        let query = request.into_inner();
+
+        // Only the local generator streams; routed queries are answered in
+        // one final chunk so clients handle both the same way
+        if !query.local_only {
+            let response = self.query_response(&metadata, query).await?;
+            let chunk = final_chunk(response);
+            return Ok(Response::new(Box::pin(futures::stream::iter([Ok(chunk)]))));
+        }
+
+        let response = self.complete(&metadata, query, true).await?;
+        let mut body = response.into_body().into_data_stream();
+        let (tx, rx) = mpsc::channel(32);
+        tokio::spawn(async move {
+            let _in_flight = in_flight;
+            let mut frames = SseFrames::default();
+            while let Some(data) = body.next().await {
+                let data = match data {
+                    Ok(data) => data,
+                    Err(e) => {
+                        let _ = tx.send(Err(Status::internal(e.to_string()))).await;
+                        return;
+                    }
+                };
+                for frame in frames.push(&data) {
+                    let Some(chunk) = parse_stream_chunk(&frame) else {
+                        continue;
+                    };
+                    // Receiver gone: the client hung up, stop reading
+                    if tx.send(Ok(chunk)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn submit_tool_results(
+        &self,
+        request: Request<proto::ToolResultsRequest>,
+    ) -> Result<Response<proto::QueryResponse>, Status> {
+        let _in_flight = self.server.drain().track();
+        let metadata = request.metadata().clone();
+        let query = with_tool_results(request.into_inner())?;
+        self.query_response(&metadata, query)
+            .await
+            .map(Response::new)
+    }
+}
+
+/// Translate a gRPC query into the HTTP request (and headers) it stands for
+fn to_chat_request(
+    query: proto::QueryRequest,
+    stream: bool,
+) -> Result<(HeaderMap, openai::ChatCompletionRequest), Status> {
+    let mut headers = HeaderMap::new();
+    if !query.provider.is_empty() {
+        let value = HeaderValue::from_str(&query.provider)
+            .map_err(|_| Status::invalid_argument("Invalid provider name"))?;
+        headers.insert("x-finch-provider", value);
+    }
+    let priority = match query.priority() {
+        proto::Priority::Unspecified => None,
+        proto::Priority::Interactive => Some(RequestPriority::Interactive),
+        proto::Priority::Agent => Some(RequestPriority::Agent),
+        proto::Priority::Training => Some(RequestPriority::Training),
+    };
+    if let Some(priority) = priority {
+        headers.insert(PRIORITY_HEADER, HeaderValue::from_static(priority.as_str()));
+    }
+
+    let tools = query
+        .tools
+        .into_iter()
+        .map(to_openai_tool)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let request = openai::ChatCompletionRequest {
+        model: query.model,
+        messages: query.messages.into_iter().map(to_openai_message).collect(),
+        max_tokens: query.max_tokens,
+        temperature: query.temperature,
+        top_p: None,
+        n: None,
+        stream,
+        stop: None,
+        tools: (!tools.is_empty()).then_some(tools),
+        local_only: Some(query.local_only),
+        cache: query.cache,
+        session_id: None,
+        incognito: None,
+        memory: None,
+    };
+    Ok((headers, request))
+}
+
+/// Pass the caller's workspace key (`authorization` or `x-api-key`
+/// metadata) on to the HTTP pipeline
+fn copy_api_key(metadata: &MetadataMap, headers: &mut HeaderMap) {
+    for name in ["authorization", "x-api-key"] {
+        let value = metadata
+            .get(name)
+            .and_then(|v| HeaderValue::from_bytes(v.as_bytes()).ok());
+        if let Some(value) = value {
+            headers.insert(name, value);
+        }
+    }
+}
+
+fn to_openai_tool(tool: proto::Tool) -> Result<openai::Tool, Status> {
+    let parameters = if tool.parameters_json.trim().is_empty() {
+        serde_json::json!({ "type": "object", "properties": {} })
+    } else {
+        serde_json::from_str(&tool.parameters_json).map_err(|e| {
+            Status::invalid_argument(format!(
+                "Tool '{}' has invalid parameters_json: {}",
+                tool.name, e
+            ))
+        })?
+    };
+    Ok(openai::Tool {
+        tool_type: "function".to_string(),
+        function: openai::FunctionDefinition {
+            name: tool.name,
+            description: (!tool.description.is_empty()).then_some(tool.description),
+            parameters,
+        },
+    })
+}
+
+fn to_openai_message(message: proto::ChatMessage) -> openai::ChatMessage {
+    let tool_calls: Vec<openai::ToolCall> = message
+        .tool_calls
+        .into_iter()
+        .map(|call| openai::ToolCall {
+            id: call.id,
+            tool_type: "function".to_string(),
+            function: openai::FunctionCall {
+                name: call.name,
+                arguments: call.arguments,
+            },
+        })
+        .collect();
+    openai::ChatMessage {
+        role: message.role,
+        content: message.content,
+        tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        tool_call_id: message.tool_call_id,
+        name: message.name,
+    }
+}
+
+fn from_openai_message(message: openai::ChatMessage) -> proto::ChatMessage {
+    proto::ChatMessage {
+        role: message.role,
+        content: message.content,
+        tool_calls: message
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| proto::ToolCall {
+                id: call.id,
+                name: call.function.name,
+                arguments: call.function.arguments,
+            })
+            .collect(),
+        tool_call_id: message.tool_call_id,
+        name: message.name,
+    }
+}
+
+fn from_completion(completion: openai::ChatCompletionResponse) -> proto::QueryResponse {
+    let choice = completion.choices.into_iter().next();
+    proto::QueryResponse {
+        id: completion.id,
+        model: completion.model,
+        finish_reason: choice
+            .as_ref()
+            .map(|c| c.finish_reason.clone())
+            .unwrap_or_default(),
+        message: choice.map(|c| from_openai_message(c.message)),
+        usage: Some(proto::Usage {
+            prompt_tokens: completion.usage.prompt_tokens,
+            completion_tokens: completion.usage.completion_tokens,
+            total_tokens: completion.usage.total_tokens,
+        }),
+    }
+}
+
+/// A whole answer delivered as the last (and only) chunk of a stream
+fn final_chunk(response: proto::QueryResponse) -> proto::QueryChunk {
+    let message = response.message.unwrap_or_default();
+    proto::QueryChunk {
+        model: response.model,
+        delta: message.content.unwrap_or_default(),
+        finish_reason: Some(response.finish_reason),
+        tool_calls: message.tool_calls,
+    }
+}
+
+/// Append the results of the assistant's tool calls to the conversation
+fn with_tool_results(request: proto::ToolResultsRequest) -> Result<proto::QueryRequest, Status> {
+    let mut query = request
+        .query
+        .ok_or_else(|| Status::invalid_argument("query is required"))?;
+    if request.results.is_empty() {
+        return Err(Status::invalid_argument("results cannot be empty"));
+    }
+    let call_ids: Vec<String> = query
+        .messages
+        .iter()
+        .rfind(|m| m.role == "assistant")
+        .map(|m| m.tool_calls.iter().map(|c| c.id.clone()).collect())
+        .unwrap_or_default();
+    for result in request.results {
+        if !call_ids.contains(&result.tool_call_id) {
+            return Err(Status::invalid_argument(format!(
+                "No tool call with id '{}' in the last assistant message",
+                result.tool_call_id
+            )));
+        }
+        query.messages.push(proto::ChatMessage {
+            role: "tool".to_string(),
+            content: Some(result.content),
+            tool_calls: vec![],
+            tool_call_id: Some(result.tool_call_id),
+            name: None,
+        });
+    }
+    Ok(query)
+}
+
+/// Parse one `chat.completion.chunk` SSE payload
+fn parse_stream_chunk(data: &str) -> Option<proto::QueryChunk> {
+    let chunk: serde_json::Value = serde_json::from_str(data).ok()?;
+    let choice = chunk.get("choices")?.get(0)?;
+    Some(proto::QueryChunk {
+        model: chunk["model"].as_str().unwrap_or_default().to_string(),
+        delta: choice["delta"]["content"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        finish_reason: choice["finish_reason"].as_str().map(str::to_string),
+        tool_calls: vec![],
+    })
+}
+
+/// Reassembles SSE `data:` payloads from body chunks split at arbitrary points
+#[derive(Default)]
+struct SseFrames {
+    buffer: String,
+}
+
+impl SseFrames {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.push_str(&String::from_utf8_lossy(bytes));
+        let mut frames = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let event: String = self.buffer.drain(..end + 2).collect();
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                .collect();
+            if !data.is_empty() {
+                frames.push(data.join("\n"));
+            }
+        }
+        frames
+    }
+}
+
+async fn read_body(body: Body) -> Result<axum::body::Bytes, Status> {
+    axum::body::to_bytes(body, MAX_RESPONSE_BYTES)
+        .await
+        .map_err(|e| Status::internal(format!("Failed to read completion: {}", e)))
+}
+
+/// gRPC status for an HTTP error response (`{"error": {"message", "type"}}`)
+async fn error_status(response: axum::response::Response) -> Status {
+    let status = response.status();
+    let body = read_body(response.into_body()).await.unwrap_or_default();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+    let message = error["error"]["message"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| status.to_string());
+    let code = status_code(status, error["error"]["type"].as_str().unwrap_or_default());
+    Status::new(code, message)
+}
+
+fn status_code(status: StatusCode, error_type: &str) -> Code {
+    match (status, error_type) {
+        // Upstream provider failures are reported as 400 api_error over HTTP
+        (_, "api_error") => Code::Unavailable,
+        (StatusCode::BAD_REQUEST, _) => Code::InvalidArgument,
+        (StatusCode::UNAUTHORIZED, _) => Code::Unauthenticated,
+        (StatusCode::FORBIDDEN, _) => Code::PermissionDenied,
+        (StatusCode::NOT_FOUND, _) => Code::NotFound,
+        (StatusCode::TOO_MANY_REQUESTS, _) => Code::ResourceExhausted,
+        (StatusCode::SERVICE_UNAVAILABLE, _) => Code::Unavailable,
+        (StatusCode::NOT_IMPLEMENTED, _) => Code::FailedPrecondition,
+        _ => Code::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(messages: Vec<proto::ChatMessage>) -> proto::QueryRequest {
+        proto::QueryRequest {
+            model: "qwen-local".to_string(),
+            messages,
+            ..Default::default()
+        }
+    }
+
+    fn message(role: &str, content: &str) -> proto::ChatMessage {
+        proto::ChatMessage {
+            role: role.to_string(),
+            content: Some(content.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_query_becomes_chat_request_and_headers() {
+        let mut q = query(vec![message("user", "hi")]);
+        q.provider = "grok".to_string();
+        q.priority = proto::Priority::Agent as i32;
+        q.cache = Some(false);
+        q.tools = vec![proto::Tool {
+            name: "read".to_string(),
+            description: String::new(),
+            parameters_json: r#"{"type":"object","properties":{"path":{"type":"string"}}}"#
+                .to_string(),
+        }];
+
+        let (headers, request) = to_chat_request(q, false).unwrap();
+        assert_eq!(headers["x-finch-provider"], "grok");
+        assert_eq!(headers[PRIORITY_HEADER], "agent");
+        assert_eq!(request.messages[0].content.as_deref(), Some("hi"));
+        assert_eq!(request.cache, Some(false));
+        assert_eq!(request.local_only, Some(false));
+        let tools = request.tools.unwrap();
+        assert!(tools[0].function.description.is_none());
+        assert_eq!(
+            tools[0].function.parameters["properties"]["path"]["type"],
+            "string"
+        );
+
+        let (headers, request) = to_chat_request(query(vec![]), true).unwrap();
+        assert!(headers.is_empty());
+        assert!(request.stream);
+        assert!(request.tools.is_none());
+    }
+
+    #[test]
+    fn test_invalid_tool_schema_is_rejected() {
+        let mut q = query(vec![message("user", "hi")]);
+        q.tools = vec![proto::Tool {
+            name: "read".to_string(),
+            description: String::new(),
+            parameters_json: "{not json".to_string(),
+        }];
+        let err = to_chat_request(q, false).unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_tool_results_are_appended() {
+        let mut assistant = message("assistant", "");
+        assistant.content = None;
+        assistant.tool_calls = vec![proto::ToolCall {
+            id: "call_1".to_string(),
+            name: "read".to_string(),
+            arguments: r#"{"path":"a.rs"}"#.to_string(),
+        }];
+        let base = query(vec![message("user", "read a.rs"), assistant]);
+
+        let result = |id: &str| proto::ToolResult {
+            tool_call_id: id.to_string(),
+            content: "fn main() {}".to_string(),
+        };
+        let q = with_tool_results(proto::ToolResultsRequest {
+            query: Some(base.clone()),
+            results: vec![result("call_1")],
+        })
+        .unwrap();
+        let last = q.messages.last().unwrap();
+        assert_eq!(last.role, "tool");
+        assert_eq!(last.tool_call_id.as_deref(), Some("call_1"));
+
+        let (_, request) = to_chat_request(q, false).unwrap();
+        let calls = request.messages[1].tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].function.name, "read");
+
+        let unknown = with_tool_results(proto::ToolResultsRequest {
+            query: Some(base),
+            results: vec![result("call_9")],
+        });
+        assert_eq!(unknown.unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_sse_frames_split_across_chunks() {
+        let mut frames = SseFrames::default();
+        assert!(frames
+            .push(b"data: {\"model\":\"q\",\"choices\":[{\"delta\":{\"con")
+            .is_empty());
+        let out = frames.push(
+            b"tent\":\"Hel\"},\"finish_reason\":null}]}\n\ndata: {\"model\":\"q\",\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+        );
+        assert_eq!(out.len(), 2);
+
+        let first = parse_stream_chunk(&out[0]).unwrap();
+        assert_eq!(first.delta, "Hel");
+        assert_eq!(first.finish_reason, None);
+        let last = parse_stream_chunk(&out[1]).unwrap();
+        assert_eq!(last.delta, "");
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_http_errors_map_to_grpc_codes() {
+        assert_eq!(
+            status_code(StatusCode::BAD_REQUEST, "invalid_request_error"),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            status_code(StatusCode::BAD_REQUEST, "api_error"),
+            Code::Unavailable
+        );
+        assert_eq!(
+            status_code(StatusCode::SERVICE_UNAVAILABLE, "server_shutting_down"),
+            Code::Unavailable
+        );
+        assert_eq!(
+            status_code(StatusCode::INTERNAL_SERVER_ERROR, "model_failed"),
+            Code::Internal
+        );
+        assert_eq!(
+            status_code(StatusCode::UNAUTHORIZED, "authentication_error"),
+            Code::Unauthenticated
+        );
+        assert_eq!(
+            status_code(StatusCode::TOO_MANY_REQUESTS, "rate_limit_error"),
+            Code::ResourceExhausted
+        );
+    }
+
+    #[test]
+    fn test_api_key_metadata_becomes_headers() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert("authorization", "Bearer key-a".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        copy_api_key(&metadata, &mut headers);
+        assert_eq!(headers["authorization"], "Bearer key-a");
+        assert!(headers.get("x-api-key").is_none());
+    }
+}
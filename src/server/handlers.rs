@@ -1,13 +1,18 @@
 // HTTP request handlers
 
 use axum::{
-    extract::{ConnectInfo, Path, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Json, Response},
-    routing::{get, post},
-    Router,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{delete, get, post},
+    Extension, Router,
 };
+use futures::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -35,8 +40,11 @@ fn check_peer_token(headers: &HeaderMap, peer_ip: &str, endpoint: &str) -> Resul
     }
 }
 
-use super::AgentServer;
+use super::{
+    AccessDetails, AgentServer, CacheKey, Exchange, SessionUsage, Workspace, WorkspaceError,
+};
 use crate::claude::{ContentBlock, Message};
+use crate::generators::Attribution;
 
 /// Create the main application router
 pub fn create_router(server: Arc<AgentServer>) -> Router {
@@ -53,11 +61,25 @@ pub fn create_router(server: Arc<AgentServer>) -> Router {
         .with_state(training_tx);
 
     // Create main router with server state
-    Router::new()
+    let router = Router::new()
         // Claude-compatible endpoints
         .route("/v1/messages", post(handle_message))
         .route("/v1/session/:id", get(get_session).delete(delete_session))
+        .route("/v1/sessions/:id/usage", get(get_session_usage))
+        .route(
+            "/v1/sessions/:id/active-generation",
+            delete(cancel_active_generation),
+        )
+        .route("/admin/sessions/:id/replay/:n", get(replay_session_request))
         .route("/v1/status", get(get_status))
+        .route("/v1/workspace", get(get_workspace))
+        .route("/v1/roles", get(get_roles))
+        .route(
+            "/v1/roles/worker",
+            post(take_worker_role).delete(drop_worker_role),
+        )
+        .route(super::model_reload::RELOAD_ROUTE, post(reload_model))
+        .route(super::activity::EVENTS_ROUTE, get(activity_events))
         // OpenAI-compatible endpoints
         .route("/v1/chat/completions", post(handle_chat_completions))
         .route("/v1/models", get(handle_list_models))
@@ -69,6 +91,7 @@ pub fn create_router(server: Arc<AgentServer>) -> Router {
         .route("/v1/brains/:id", get(get_brain).delete(cancel_brain))
         .route("/v1/brains/:id/answer", post(answer_brain_question))
         .route("/v1/brains/:id/plan", post(respond_to_brain_plan))
+        .route("/v1/brains/:id/events", get(brain_events))
         .route("/v1/brains/shared", get(list_shared_brains))
         .route("/v1/brains/shared/:name", get(get_shared_brain).post(contribute_shared_brain))
         // Note: node handlers load config independently (no AgentServer state needed)
@@ -89,7 +112,26 @@ pub fn create_router(server: Arc<AgentServer>) -> Router {
         .route("/v1/settle",             post(handle_settle))
         // Health and metrics
         .route("/health", get(health_check))
-        .route("/metrics", get(metrics_endpoint))
+        .route("/metrics", get(metrics_endpoint));
+
+    // Optional browser chat page
+    let router = if server.config().web_ui {
+        router.route("/ui", get(super::web::chat_page))
+    } else {
+        router
+    };
+
+    // Optional memory for API sessions
+    let router = if server.memory().is_enabled() {
+        router
+            .route("/v1/memory", post(remember))
+            .route("/v1/memory/query", post(query_memory))
+            .route("/v1/memory/stats", get(memory_stats))
+    } else {
+        router
+    };
+
+    router
         .with_state(server)
         // Merge feedback router
         .merge(feedback_router)
@@ -110,16 +152,41 @@ struct SpawnBrainRequest {
 
 async fn spawn_brain(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Json(req): Json<SpawnBrainRequest>,
 ) -> Result<Json<crate::server::brain_registry::BrainSummary>, AppError> {
     use crate::brain::daemon_brain::run_daemon_brain_loop;
+    use crate::config::ToolProfile;
+
+    // A brain is a fresh agent loop — never start one while draining
+    server.drain().admit(false)?;
+
+    // The workspace's tool profile decides whether (and with what) it runs
+    let workspace = server.workspaces().resolve(&headers)?;
+    let mut memory = None;
+    if let Some(workspace) = &workspace {
+        workspace.check_quota()?;
+        match workspace.tools() {
+            ToolProfile::None => {
+                return Err(WorkspaceError::Forbidden {
+                    workspace: workspace.name().to_string(),
+                    action: "start brains".to_string(),
+                }
+                .into())
+            }
+            ToolProfile::ReadOnly => {}
+            ToolProfile::Memory => memory = Some(workspace.memory().await?),
+        }
+    }
+    let in_flight = server.drain().track();
 
     let id = uuid::Uuid::new_v4();
     let registry = Arc::clone(server.brain_registry());
 
-    // Choose a provider (first available)
+    // Choose a provider (first available, or the workspace's default)
+    let provider_name = server.provider_for_workspace(workspace.as_deref(), None)?;
     let provider = server
-        .provider_for_name(None)
+        .provider_for_name(provider_name.as_deref())
         .cloned()
         .ok_or_else(|| anyhow::anyhow!("No provider configured for daemon brains"))?;
 
@@ -127,16 +194,33 @@ async fn spawn_brain(
         .map(|p| p.display().to_string())
         .unwrap_or_else(|_| "~".to_string());
 
-    let name = registry.insert(id, req.task.clone()).await;
+    let owner = workspace.as_ref().map(|w| w.name().to_string());
+    let name = registry.insert_for(id, req.task.clone(), owner).await;
     let _ = name; // name embedded in registry
 
     let registry_clone = Arc::clone(&registry);
     let task_clone = req.task.clone();
     let cwd_clone = cwd.clone();
-
-    tokio::spawn(async move {
-        run_daemon_brain_loop(id, task_clone, registry_clone, provider, cwd_clone).await;
+    let webhooks = Arc::clone(server.webhooks());
+    let schedule = Arc::clone(server.schedule());
+
+    let handle = tokio::spawn(async move {
+        // During quiet hours the brain is listed but waits to start
+        registry_clone.wait_for_schedule(id, &schedule).await;
+        run_daemon_brain_loop(
+            id,
+            task_clone,
+            registry_clone,
+            provider,
+            cwd_clone,
+            webhooks,
+            memory,
+        )
+        .await;
+        // Shutdown waits for the brain to finish its loop
+        drop(in_flight);
     });
+    registry.set_task_handle(id, handle.abort_handle()).await;
 
     let brains = registry.get_detail(id).await;
     let summary = brains
@@ -152,19 +236,57 @@ async fn spawn_brain(
     Ok(Json(summary))
 }
 
-/// GET /v1/brains — list active brains
+/// Query for GET /v1/brains
+#[derive(Debug, Deserialize)]
+struct ListBrainsQuery {
+    /// Include finished brains
+    #[serde(default)]
+    all: bool,
+}
+
+/// GET /v1/brains — list active brains (`?all=true` includes finished ones)
 async fn list_brains(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Query(query): Query<ListBrainsQuery>,
 ) -> Result<Json<Vec<crate::server::brain_registry::BrainSummary>>, AppError> {
-    let list = server.brain_registry().list_active().await;
+    let registry = server.brain_registry();
+    let list = if let Some(workspace) = server.workspaces().resolve(&headers)? {
+        registry.list_for(workspace.name(), query.all).await
+    } else if query.all {
+        registry.list_all().await
+    } else {
+        registry.list_active().await
+    };
     Ok(Json(list))
 }
 
+/// Check that brain `id` belongs to the caller's workspace, if it has one.
+/// Other workspaces' brains are reported as not found.
+async fn authorize_brain(
+    server: &AgentServer,
+    headers: &HeaderMap,
+    id: Uuid,
+) -> Result<(), AppError> {
+    if let Some(workspace) = server.workspaces().resolve(headers)? {
+        if !server
+            .brain_registry()
+            .belongs_to(id, workspace.name())
+            .await
+        {
+            return Err(WorkspaceError::NotFound(format!("Brain {}", id)).into());
+        }
+    }
+    Ok(())
+}
+
 /// GET /v1/brains/:id — full brain detail
 async fn get_brain(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
 ) -> Result<Json<crate::server::brain_registry::BrainDetail>, AppError> {
+    authorize_brain(&server, &headers, id).await?;
     let detail = server
         .brain_registry()
         .get_detail(id)
@@ -173,11 +295,77 @@ async fn get_brain(
     Ok(Json(detail))
 }
 
+/// GET /v1/brains/:id/events — stream a brain's progress as server-sent events
+///
+/// Every event is a JSON `BrainEvent`, named after its `type`.  The first is
+/// a `snapshot` with the full `BrainDetail`, followed by `log`, `state` and
+/// `summary` events.  The stream ends when the brain is dead or cancelled.
+async fn brain_events(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    use crate::server::brain_registry::{BrainEvent, BrainState};
+    use tokio::sync::broadcast::error::RecvError;
+
+    authorize_brain(&server, &headers, id).await?;
+
+    let (detail, rx) = server
+        .brain_registry()
+        .subscribe(id)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Brain {} not found", id))?;
+    let finished = detail.state == BrainState::Dead;
+    let snapshot = brain_sse_event(&BrainEvent::Snapshot { detail });
+
+    let updates = stream::unfold((rx, finished), move |(mut rx, finished)| async move {
+        if finished {
+            return None;
+        }
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let done = event
+                        == BrainEvent::State {
+                            state: BrainState::Dead,
+                        };
+                    return Some((Ok(brain_sse_event(&event)), (rx, done)));
+                }
+                // A slow client missed some lines; GET /v1/brains/:id has them all
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(brain = %id, skipped, "Brain event subscriber lagged");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::once(async move { Ok(snapshot) }).chain(updates);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn brain_sse_event(event: &crate::server::brain_registry::BrainEvent) -> Event {
+    use crate::server::brain_registry::BrainEvent;
+
+    let name = match event {
+        BrainEvent::Snapshot { .. } => "snapshot",
+        BrainEvent::Log { .. } => "log",
+        BrainEvent::State { .. } => "state",
+        BrainEvent::Summary { .. } => "summary",
+    };
+    Event::default()
+        .event(name)
+        .json_data(event)
+        .expect("brain events always serialize")
+}
+
 /// DELETE /v1/brains/:id — cancel a brain
 async fn cancel_brain(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
+    authorize_brain(&server, &headers, id).await?;
     if server.brain_registry().cancel(id).await {
         Ok(StatusCode::NO_CONTENT)
     } else {
@@ -193,9 +381,11 @@ struct AnswerRequest {
 
 async fn answer_brain_question(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(req): Json<AnswerRequest>,
 ) -> Result<StatusCode, AppError> {
+    authorize_brain(&server, &headers, id).await?;
     server
         .brain_registry()
         .answer_question(id, req.answer)
@@ -213,11 +403,14 @@ struct PlanResponseRequest {
 
 async fn respond_to_brain_plan(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(req): Json<PlanResponseRequest>,
 ) -> Result<StatusCode, AppError> {
     use crate::server::brain_registry::PlanResponse;
 
+    authorize_brain(&server, &headers, id).await?;
+
     let response = match req.action.as_str() {
         "approve" => PlanResponse::Approve,
         "reject" => PlanResponse::Reject,
@@ -237,21 +430,45 @@ async fn respond_to_brain_plan(
 /// GET /v1/brains/shared — list all shared brains (name, context, updated_at)
 async fn list_shared_brains(
     State(server): State<Arc<AgentServer>>,
-) -> Json<Vec<crate::server::brain_registry::SharedBrainEntry>> {
-    Json(server.brain_registry().list_shared().await)
+    headers: HeaderMap,
+) -> Result<Json<Vec<crate::server::brain_registry::SharedBrainEntry>>, AppError> {
+    let workspace = server.workspaces().resolve(&headers)?;
+    let prefix = shared_brain_key(workspace.as_deref(), "");
+    let entries = server
+        .brain_registry()
+        .list_shared()
+        .await
+        .into_iter()
+        .filter_map(|mut entry| {
+            entry.name = entry.name.strip_prefix(&prefix)?.to_string();
+            Some(entry)
+        })
+        .collect();
+    Ok(Json(entries))
+}
+
+/// Registry name of shared brain `name`; each workspace has its own set
+fn shared_brain_key(workspace: Option<&Workspace>, name: &str) -> String {
+    match workspace {
+        Some(workspace) => format!("{}/{}", workspace.name(), name),
+        None => name.to_string(),
+    }
 }
 
 /// GET /v1/brains/shared/:name — return one shared brain
 async fn get_shared_brain(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(name): Path<String>,
 ) -> Result<Json<crate::server::brain_registry::SharedBrainEntry>, AppError> {
-    server
+    let workspace = server.workspaces().resolve(&headers)?;
+    let mut entry = server
         .brain_registry()
-        .get_shared(&name)
+        .get_shared(&shared_brain_key(workspace.as_deref(), &name))
         .await
-        .map(Json)
-        .ok_or_else(|| AppError(anyhow::anyhow!("Shared brain '{}' not found", name)))
+        .ok_or_else(|| AppError(anyhow::anyhow!("Shared brain '{}' not found", name)))?;
+    entry.name = name;
+    Ok(Json(entry))
 }
 
 /// POST /v1/brains/shared/:name — contribute context to a shared brain
@@ -262,11 +479,17 @@ struct SharedBrainContribution {
 
 async fn contribute_shared_brain(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(name): Path<String>,
     Json(body): Json<SharedBrainContribution>,
-) -> StatusCode {
-    server.brain_registry().contribute_shared(&name, &body.context).await;
-    StatusCode::OK
+) -> Result<StatusCode, AppError> {
+    let workspace = server.workspaces().resolve(&headers)?;
+    let key = shared_brain_key(workspace.as_deref(), &name);
+    server
+        .brain_registry()
+        .contribute_shared(&key, &body.context)
+        .await;
+    Ok(StatusCode::OK)
 }
 
 /// Request body for /v1/messages endpoint (Claude-compatible)
@@ -286,6 +509,9 @@ pub struct MessageRequest {
     /// Session ID for conversation continuity
     #[serde(default)]
     pub session_id: Option<String>,
+    /// Set to `false` to bypass the response cache
+    #[serde(default)]
+    pub cache: Option<bool>,
 }
 
 /// Response body for /v1/messages endpoint (Claude-compatible)
@@ -304,8 +530,9 @@ pub struct MessageResponse {
 /// Handle POST /v1/messages - Main chat endpoint
 async fn handle_message(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Json(request): Json<MessageRequest>,
-) -> Result<Json<MessageResponse>, AppError> {
+) -> Result<(Extension<AccessDetails>, Json<MessageResponse>), AppError> {
     use crate::claude::MessageRequest as ClaudeRequest;
     use crate::metrics::{RequestMetric, ResponseComparison};
     use crate::router::RouteDecision;
@@ -313,10 +540,19 @@ async fn handle_message(
 
     let start_time = Instant::now();
 
+    // Tenants must be within quota and allowed the Claude client's provider
+    let workspace = server.workspaces().resolve(&headers)?;
+    if let Some(workspace) = &workspace {
+        workspace.check_quota()?;
+        workspace.check_provider(server.claude_client().provider().name())?;
+    }
+    let workspace_name = workspace.as_ref().map(|w| w.name());
+
     // Get or create session
     let mut session = server
         .session_manager()
-        .get_or_create(request.session_id.as_deref())?;
+        .get_or_create_in(request.session_id.as_deref(), workspace_name)
+        .await?;
 
     // Extract user message (last message should be user role)
     let user_message = request
@@ -324,143 +560,227 @@ async fn handle_message(
         .last()
         .ok_or_else(|| anyhow::anyhow!("No messages in request"))?;
 
+    // While shutting down, only tool results for a turn already under way
+    // are served; new turns are refused
+    server.drain().admit(user_message.has_tool_results())?;
+
     // Extract text content from the user message for routing
     let user_text = user_message.text();
 
+    // Repeated questions in the same conversation are answered from the
+    // response cache unless the client opted out
+    let cache = server
+        .response_cache()
+        .filter(|_| request.cache.unwrap_or(true));
+    let cache_key = cache.map(|_| {
+        let query = (!user_message.has_tool_results()).then_some(user_text.as_str());
+        CacheKey::new(
+            &(
+                workspace_name,
+                &request.model,
+                &request.system,
+                request.max_tokens,
+                session.conversation.get_messages(),
+            ),
+            user_message,
+            query,
+        )
+    });
+    let cached = cache
+        .zip(cache_key.as_ref())
+        .and_then(|(c, key)| c.get(key));
+
     // Add to conversation history
     session.conversation.add_message(user_message.clone());
 
-    // Process query through router
-    let router = server.router().read().await;
-    let decision = router.route(&user_text);
-
-    let (response_text, routing_decision) = match decision {
-        RouteDecision::Forward { reason } => {
-            let reason_str = format!("{:?}", reason);
-            tracing::info!(
-                session_id = %session.id,
-                reason = %reason_str,
-                "Forwarding to Claude API"
-            );
-
-            // Build Claude API request with full conversation context
-            let claude_request = ClaudeRequest::with_context(session.conversation.get_messages());
-
-            // Forward to Claude
-            let response = server.claude_client().send_message(&claude_request).await?;
-
-            // Extract text from response
-            let text = response.text();
-
-            (text, "forward".to_string())
-        }
-        RouteDecision::Local { .. } => {
-            tracing::info!(session_id = %session.id, "Handling locally");
-
-            // Check if local generator is ready
-            use crate::models::GeneratorState;
-            let state = server.generator_state().read().await;
-
-            match &*state {
-                GeneratorState::Ready { .. } => {
-                    drop(state); // Release lock before generating
-
-                    tracing::info!(session_id = %session.id, "Using local Qwen model");
-
-                    // Use local generator (need write lock for try_generate)
-                    let mut generator = server.local_generator().write().await;
-
-                    match generator.try_generate_from_pattern(&user_text) {
-                        Ok(Some(response_text)) => (response_text, "local".to_string()),
-                        Ok(None) => {
-                            // Confidence too low, fall back to Claude
-                            tracing::info!(
-                                session_id = %session.id,
-                                "Local confidence too low, falling back to Claude"
-                            );
-                            drop(generator); // Release lock
-
-                            let claude_request =
-                                ClaudeRequest::with_context(session.conversation.get_messages());
-                            let response =
-                                server.claude_client().send_message(&claude_request).await?;
-                            let text = response.text();
-
-                            (text, "confidence_fallback".to_string())
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                session_id = %session.id,
-                                error = %e,
-                                "Local generation failed, falling back to Claude"
-                            );
-                            drop(generator); // Release lock
-
-                            // Fall back to Claude on error
-                            let claude_request =
-                                ClaudeRequest::with_context(session.conversation.get_messages());
-                            let response =
-                                server.claude_client().send_message(&claude_request).await?;
-                            let text = response.text();
-
-                            (text, "local_error_fallback".to_string())
+    let (response_text, routing_decision) = if let Some((content, hit)) = cached {
+        tracing::info!(session_id = %session.id, ?hit, "Answered from response cache");
+        let text = content
+            .iter()
+            .filter_map(ContentBlock::as_text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        (text, "cache".to_string())
+    } else {
+        // DELETE /v1/sessions/:id/active-generation drops the provider call
+        // below; the turn is then left out of the session's history
+        let generation = server.generations().begin(&session.id, workspace_name);
+        generation
+            .run(async {
+                // Process query through router
+                let router = server.router().read().await;
+                let decision = router.route(&user_text);
+
+                Ok::<_, AppError>(match decision {
+                    RouteDecision::Forward { reason } => {
+                        let reason_str = format!("{:?}", reason);
+                        tracing::info!(
+                            session_id = %session.id,
+                            reason = %reason_str,
+                            "Forwarding to Claude API"
+                        );
+
+                        // Build Claude API request with full conversation context
+                        let claude_request =
+                            ClaudeRequest::with_context(session.conversation.get_messages());
+
+                        // Forward to Claude
+                        let response = server.forward_message(&session.id, &claude_request).await?;
+
+                        // Extract text from response
+                        let text = response.text();
+
+                        (text, "forward".to_string())
+                    }
+                    RouteDecision::Local { .. } => {
+                        tracing::info!(session_id = %session.id, "Handling locally");
+
+                        // Check if local generator is ready
+                        use crate::models::GeneratorState;
+                        let state = server.generator_state().read().await;
+
+                        match &*state {
+                            GeneratorState::Ready { .. } => {
+                                drop(state); // Release lock before generating
+
+                                // Wait our turn for the generator (503 if the queue is saturated)
+                                let priority = super::RequestPriority::from_headers(&headers);
+                                let permit = server.request_queue().acquire(priority).await?;
+
+                                tracing::info!(session_id = %session.id, "Using local Qwen model");
+
+                                // Use the permit's generator (need write lock for try_generate)
+                                let mut generator = server
+                                    .local_models()
+                                    .primary()
+                                    .generator(permit.lane())
+                                    .write()
+                                    .await;
+
+                                match generator.try_generate_from_pattern(&user_text) {
+                                    Ok(Some(response_text)) => (response_text, "local".to_string()),
+                                    Ok(None) => {
+                                        // Confidence too low, fall back to Claude
+                                        tracing::info!(
+                                            session_id = %session.id,
+                                            "Local confidence too low, falling back to Claude"
+                                        );
+                                        drop(generator); // Release lock
+                                        drop(permit);
+
+                                        let claude_request = ClaudeRequest::with_context(
+                                            session.conversation.get_messages(),
+                                        );
+                                        let response = server
+                                            .forward_message(&session.id, &claude_request)
+                                            .await?;
+                                        let text = response.text();
+
+                                        (text, "confidence_fallback".to_string())
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!(
+                                            session_id = %session.id,
+                                            error = %e,
+                                            "Local generation failed, falling back to Claude"
+                                        );
+                                        drop(generator); // Release lock
+                                        drop(permit);
+
+                                        // Fall back to Claude on error
+                                        let claude_request = ClaudeRequest::with_context(
+                                            session.conversation.get_messages(),
+                                        );
+                                        let response = server
+                                            .forward_message(&session.id, &claude_request)
+                                            .await?;
+                                        let text = response.text();
+
+                                        (text, "local_error_fallback".to_string())
+                                    }
+                                }
+                            }
+                            GeneratorState::Initializing
+                            | GeneratorState::Downloading { .. }
+                            | GeneratorState::Loading { .. } => {
+                                tracing::info!(
+                                    session_id = %session.id,
+                                    "Model still loading, forwarding to Claude"
+                                );
+                                drop(state); // Release lock
+
+                                // Model not ready yet, forward to Claude
+                                let claude_request = ClaudeRequest::with_context(
+                                    session.conversation.get_messages(),
+                                );
+                                let response =
+                                    server.forward_message(&session.id, &claude_request).await?;
+                                let text = response.text();
+
+                                (text, "loading_fallback".to_string())
+                            }
+                            GeneratorState::Failed { error } => {
+                                tracing::warn!(
+                                    session_id = %session.id,
+                                    error = %error,
+                                    "Model failed to load, forwarding to Claude"
+                                );
+                                drop(state); // Release lock
+
+                                // Model failed to load, forward to Claude
+                                let claude_request = ClaudeRequest::with_context(
+                                    session.conversation.get_messages(),
+                                );
+                                let response =
+                                    server.forward_message(&session.id, &claude_request).await?;
+                                let text = response.text();
+
+                                (text, "failed_fallback".to_string())
+                            }
+                            GeneratorState::NotAvailable => {
+                                tracing::info!(
+                                    session_id = %session.id,
+                                    "Model not available, forwarding to Claude"
+                                );
+                                drop(state); // Release lock
+
+                                // No model available, forward to Claude
+                                let claude_request = ClaudeRequest::with_context(
+                                    session.conversation.get_messages(),
+                                );
+                                let response =
+                                    server.forward_message(&session.id, &claude_request).await?;
+                                let text = response.text();
+
+                                (text, "unavailable_fallback".to_string())
+                            }
                         }
                     }
-                }
-                GeneratorState::Initializing
-                | GeneratorState::Downloading { .. }
-                | GeneratorState::Loading { .. } => {
-                    tracing::info!(
-                        session_id = %session.id,
-                        "Model still loading, forwarding to Claude"
-                    );
-                    drop(state); // Release lock
-
-                    // Model not ready yet, forward to Claude
-                    let claude_request =
-                        ClaudeRequest::with_context(session.conversation.get_messages());
-                    let response = server.claude_client().send_message(&claude_request).await?;
-                    let text = response.text();
-
-                    (text, "loading_fallback".to_string())
-                }
-                GeneratorState::Failed { error } => {
-                    tracing::warn!(
-                        session_id = %session.id,
-                        error = %error,
-                        "Model failed to load, forwarding to Claude"
-                    );
-                    drop(state); // Release lock
-
-                    // Model failed to load, forward to Claude
-                    let claude_request =
-                        ClaudeRequest::with_context(session.conversation.get_messages());
-                    let response = server.claude_client().send_message(&claude_request).await?;
-                    let text = response.text();
-
-                    (text, "failed_fallback".to_string())
-                }
-                GeneratorState::NotAvailable => {
-                    tracing::info!(
-                        session_id = %session.id,
-                        "Model not available, forwarding to Claude"
-                    );
-                    drop(state); // Release lock
-
-                    // No model available, forward to Claude
-                    let claude_request =
-                        ClaudeRequest::with_context(session.conversation.get_messages());
-                    let response = server.claude_client().send_message(&claude_request).await?;
-                    let text = response.text();
-
-                    (text, "unavailable_fallback".to_string())
-                }
-            }
-        }
+                })
+            })
+            .await??
     };
 
+    if routing_decision != "cache" {
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            cache.insert(key, vec![ContentBlock::text(&response_text)]);
+        }
+    }
+
     let elapsed_ms = start_time.elapsed().as_millis() as u64;
 
+    // Everything but "local" and "cache" was answered by the Claude client
+    let access = AccessDetails {
+        session_id: Some(session.id.clone()),
+        provider: Some(match routing_decision.as_str() {
+            "local" | "cache" => routing_decision.clone(),
+            _ => "claude".to_string(),
+        }),
+        decision: Some(routing_decision.clone()),
+        input_tokens: Some(session.conversation.estimated_tokens() as u32),
+        output_tokens: Some((response_text.len() / 4) as u32),
+    };
+
     // Log metrics
     let query_hash = crate::metrics::MetricsLogger::hash_query(&user_text);
     let metric = RequestMetric::new(
@@ -485,8 +805,24 @@ async fn handle_message(
     // Create assistant response message
     let assistant_message = Message::assistant(&response_text);
 
-    // Add response to conversation history
-    session.conversation.add_message(assistant_message);
+    // Add response to conversation history, attributed to whoever answered
+    // (the cache doesn't record who produced a cached answer)
+    match access.provider.as_deref() {
+        Some("cache") => session.conversation.add_message(assistant_message),
+        Some("local") => {
+            let attribution = server.local_models().primary().attribution().await;
+            session
+                .conversation
+                .add_attributed_message(assistant_message, attribution);
+        }
+        _ => {
+            let provider = server.claude_client().provider();
+            let attribution = Attribution::teacher(provider.name(), provider.default_model());
+            session
+                .conversation
+                .add_attributed_message(assistant_message, attribution);
+        }
+    }
 
     // Update session
     session.touch();
@@ -494,6 +830,25 @@ async fn handle_message(
         .session_manager()
         .update(&session.id, session.clone())?;
 
+    // Cache hits cost nothing; cloud calls go through the default Claude model
+    if access.decision.as_deref() != Some("cache") {
+        let model = match access.provider.as_deref() {
+            Some("local") => "local",
+            _ => crate::config::constants::DEFAULT_CLAUDE_MODEL,
+        };
+        let usage = SessionUsage::call(
+            model,
+            access.input_tokens.unwrap_or(0),
+            access.output_tokens.unwrap_or(0),
+        );
+        server
+            .session_manager()
+            .record_usage(Some(&session.id), &usage);
+        if let Some(workspace) = &workspace {
+            workspace.record_usage(&usage);
+        }
+    }
+
     // Build Claude-compatible response
     let response = MessageResponse {
         id: format!("msg_{}", uuid::Uuid::new_v4()),
@@ -505,15 +860,20 @@ async fn handle_message(
         session_id: session.id,
     };
 
-    Ok(Json(response))
+    Ok((Extension(access), Json(response)))
 }
 
 /// Handle GET /v1/session/:id - Retrieve session state
 async fn get_session(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(session_id): Path<String>,
 ) -> Result<Json<SessionInfo>, AppError> {
-    let session = server.session_manager().get_or_create(Some(&session_id))?;
+    let workspace = server.workspaces().resolve(&headers)?;
+    let session = server
+        .session_manager()
+        .get_or_create_in(Some(&session_id), workspace.as_ref().map(|w| w.name()))
+        .await?;
 
     let info = SessionInfo {
         id: session.id,
@@ -534,18 +894,507 @@ pub struct SessionInfo {
     pub message_count: usize,
 }
 
+/// Handle GET /v1/sessions/:id/usage - Tokens and estimated spend of a session
+async fn get_session_usage(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Response {
+    if let Err(e) = authorize_session(&server, &headers, &session_id).await {
+        return e.into_response();
+    }
+    match server.session_manager().usage(&session_id).await {
+        Some(usage) => Json(SessionUsageResponse { session_id, usage }).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": {
+                    "message": format!("Session not found: {}", session_id),
+                    "type": "not_found_error"
+                }
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Check that a session belongs to the caller's workspace, if it has one.
+/// Other workspaces' sessions are reported as not found.
+async fn authorize_session(
+    server: &AgentServer,
+    headers: &HeaderMap,
+    session_id: &str,
+) -> Result<(), WorkspaceError> {
+    if let Some(workspace) = server.workspaces().resolve(headers)? {
+        if !server
+            .session_manager()
+            .belongs_to(session_id, Some(workspace.name()))
+            .await
+        {
+            return Err(WorkspaceError::NotFound(format!("Session {}", session_id)));
+        }
+    }
+    Ok(())
+}
+
+/// Session usage response
+#[derive(Debug, Serialize)]
+pub struct SessionUsageResponse {
+    pub session_id: String,
+    #[serde(flatten)]
+    pub usage: SessionUsage,
+}
+
+/// Query for GET /admin/sessions/:id/replay/:n
+#[derive(Debug, Default, Deserialize)]
+struct ReplayQuery {
+    /// Re-send the captured request to this provider
+    provider: Option<String>,
+    /// Re-send the captured request with this model
+    model: Option<String>,
+}
+
+/// Response of GET /admin/sessions/:id/replay/:n
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    pub session_id: String,
+    /// Position of the capture, counting back from the latest (1)
+    pub n: usize,
+    /// The exchange as originally captured
+    pub original: Exchange,
+    /// The same request re-sent, when a provider or model was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay: Option<Exchange>,
+}
+
+/// Handle GET /admin/sessions/:id/replay/:n - Show the nth most recent
+/// provider exchange of a session, re-sending it when `provider` or `model`
+/// is given so the two responses can be compared
+async fn replay_session_request(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Path((session_id, n)): Path<(String, usize)>,
+    Query(query): Query<ReplayQuery>,
+) -> Response {
+    if let Err(e) = authorize_session(&server, &headers, &session_id).await {
+        return e.into_response();
+    }
+    let Some(original) = server.replay_log().get(&session_id, n) else {
+        let held = server.replay_log().len(&session_id);
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": {
+                    "message": format!(
+                        "No captured request {} for session {} ({} held)",
+                        n, session_id, held
+                    ),
+                    "type": "not_found_error"
+                }
+            })),
+        )
+            .into_response();
+    };
+
+    let replay = if query.provider.is_some() || query.model.is_some() {
+        match server
+            .replay(&original, query.provider.as_deref(), query.model.as_deref())
+            .await
+        {
+            Ok(exchange) => Some(exchange),
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": {
+                            "message": e.to_string(),
+                            "type": "invalid_request_error"
+                        }
+                    })),
+                )
+                    .into_response()
+            }
+        }
+    } else {
+        None
+    };
+
+    Json(ReplayResponse {
+        session_id,
+        n,
+        original,
+        replay,
+    })
+    .into_response()
+}
+
 /// Handle DELETE /v1/session/:id - Delete session
 async fn delete_session(
     State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
     Path(session_id): Path<String>,
 ) -> Result<StatusCode, AppError> {
-    if server.session_manager().delete(&session_id) {
+    authorize_session(&server, &headers, &session_id).await?;
+    server.replay_log().remove(&session_id);
+    if server.session_manager().delete(&session_id).await {
         Ok(StatusCode::NO_CONTENT)
     } else {
         Err(AppError(anyhow::anyhow!("Session not found")))
     }
 }
 
+/// Handle DELETE /v1/sessions/:id/active-generation - Stop the response
+/// being generated for a session, keeping the session itself
+async fn cancel_active_generation(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Response {
+    let workspace = match server.workspaces().resolve(&headers) {
+        Ok(workspace) => workspace,
+        Err(refused) => return refused.into_response(),
+    };
+    // Another workspace's generation is reported as not running
+    if server
+        .generations()
+        .cancel(&session_id, workspace.as_ref().map(|w| w.name()))
+    {
+        return StatusCode::NO_CONTENT.into_response();
+    }
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": {
+                "message": format!("No active generation for session {}", session_id),
+                "type": "not_found_error"
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Handle GET /v1/workspace - The caller's workspace and quota usage
+async fn get_workspace(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let workspace = server
+        .workspaces()
+        .resolve(&headers)?
+        .ok_or_else(|| WorkspaceError::NotFound("Workspace".to_string()))?;
+    Ok(Json(workspace.info()))
+}
+
+/// Request body for POST /v1/memory
+#[derive(Debug, Deserialize)]
+pub struct RememberRequest {
+    pub content: String,
+    /// Who said it: "user", "assistant" or "system" (the default)
+    #[serde(default = "default_memory_role")]
+    pub role: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+fn default_memory_role() -> String {
+    "system".to_string()
+}
+
+/// Request body for POST /v1/memory/query
+#[derive(Debug, Deserialize)]
+pub struct MemoryQueryRequest {
+    pub query: String,
+    /// Memories to return (default 5, at most 50)
+    #[serde(default)]
+    pub k: Option<usize>,
+    /// `session_id`, `role`, `project`, `after`, `before` and
+    /// `min_importance`, as in `/memory search`
+    #[serde(flatten)]
+    pub filter: crate::memory::MemoryFilter,
+}
+
+/// Handle POST /v1/memory — remember something for later sessions
+async fn remember(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Json(request): Json<RememberRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let workspace = server.workspaces().resolve(&headers)?;
+    let memory = server.memory().for_request(workspace.as_deref()).await?;
+    memory
+        .insert_conversation(
+            &request.role,
+            &request.content,
+            Some("api"),
+            request.session_id.as_deref(),
+        )
+        .await?;
+    Ok(Json(serde_json::json!({ "stored": true })))
+}
+
+/// Handle POST /v1/memory/query — recall memories relevant to a query
+async fn query_memory(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    Json(request): Json<MemoryQueryRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let workspace = server.workspaces().resolve(&headers)?;
+    let memory = server.memory().for_request(workspace.as_deref()).await?;
+    let memories = memory
+        .query_where(&request.query, Some(query_k(request.k)), &request.filter)
+        .await?;
+    Ok(Json(serde_json::json!({ "memories": memories })))
+}
+
+/// Memories to return for a requested `k`, capped so a client can't force
+/// an unbounded fetch
+fn query_k(requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or(super::memory::RECALL_K)
+        .min(super::memory::MAX_QUERY_K)
+}
+
+/// Handle GET /v1/memory/stats — size of the caller's memory
+async fn memory_stats(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let workspace = server.workspaces().resolve(&headers)?;
+    let memory = server.memory().for_request(workspace.as_deref()).await?;
+    let stats = memory.stats().await?;
+    Ok(Json(serde_json::json!({
+        "namespace": workspace.as_ref().map_or("daemon", |w| w.name()),
+        "conversations": stats.conversation_count,
+        "memories": stats.tree_node_count,
+        "facts": stats.fact_count,
+    })))
+}
+
+/// Request body for POST /v1/roles/worker
+#[derive(Debug, Deserialize)]
+pub struct WorkerRoleRequest {
+    /// Address to accept LAN traffic on, e.g. `0.0.0.0:8000`
+    pub bind: String,
+}
+
+/// Only the owner, on this machine, may change what the daemon listens on
+/// or which models it serves.  `action` completes "may not ...".
+fn authorize_owner(
+    server: &AgentServer,
+    headers: &HeaderMap,
+    worker: Option<Extension<super::WorkerTraffic>>,
+    action: &str,
+) -> Result<(), Response> {
+    let refusal = match server.workspaces().resolve(headers) {
+        Err(e) => return Err(e.into_response()),
+        Ok(Some(workspace)) => format!("workspace '{}' may not {}", workspace.name(), action),
+        Ok(None) if worker.is_some() => {
+            format!("only the daemon's own machine may {}", action)
+        }
+        Ok(None) => return Ok(()),
+    };
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(serde_json::json!({
+            "error": { "message": refusal, "type": "permission_error" }
+        })),
+    )
+        .into_response())
+}
+
+/// Handle GET /v1/roles — where the daemon serves local and worker traffic
+async fn get_roles(State(server): State<Arc<AgentServer>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "local": server.config().bind_address,
+        "worker": server.worker_role().address().await.map(|a| a.to_string()),
+    }))
+}
+
+/// Handle POST /v1/roles/worker — also serve LAN worker traffic, from the
+/// models already loaded
+async fn take_worker_role(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    worker: Option<Extension<super::WorkerTraffic>>,
+    Json(request): Json<WorkerRoleRequest>,
+) -> Response {
+    if let Err(refused) = authorize_owner(&server, &headers, worker, "change the daemon's roles") {
+        return refused;
+    }
+    if let Some(address) = server.worker_role().address().await {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": {
+                    "message": format!("Already serving worker traffic on {}", address),
+                    "type": "conflict_error"
+                },
+                "worker": address.to_string(),
+            })),
+        )
+            .into_response();
+    }
+    match server.worker_role().start(&request.bind).await {
+        Ok(address) => Json(serde_json::json!({ "worker": address.to_string() })).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": { "message": format!("{:#}", e), "type": "invalid_request_error" }
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Handle DELETE /v1/roles/worker — stop serving worker traffic
+async fn drop_worker_role(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    worker: Option<Extension<super::WorkerTraffic>>,
+) -> Response {
+    if let Err(refused) = authorize_owner(&server, &headers, worker, "change the daemon's roles") {
+        return refused;
+    }
+    if server.worker_role().stop().await {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Handle POST /v1/models/reload — unload a local model and load it again,
+/// possibly at another size or from another repository
+///
+/// Streams `ReloadEvent`s as server-sent events: progress twice a second,
+/// then "ready" or "failed".  The load carries on if the client goes away.
+async fn reload_model(
+    State(server): State<Arc<AgentServer>>,
+    headers: HeaderMap,
+    worker: Option<Extension<super::WorkerTraffic>>,
+    Json(request): Json<super::ModelReloadRequest>,
+) -> Response {
+    use super::ReloadEvent;
+
+    if let Err(refused) = authorize_owner(&server, &headers, worker, "reload local models") {
+        return refused;
+    }
+    let slot = match &request.model {
+        None => Arc::clone(server.local_models().primary()),
+        Some(id) => match server.local_models().find(id) {
+            Some(slot) => Arc::clone(slot),
+            None => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({
+                        "error": {
+                            "message": format!("No local model '{}'", id),
+                            "type": "not_found_error"
+                        }
+                    })),
+                )
+                    .into_response()
+            }
+        },
+    };
+    let load = match super::model_reload::reload(Arc::clone(&slot), &request).await {
+        Ok(load) => load,
+        Err(e) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": { "message": format!("{:#}", e), "type": "conflict_error" }
+                })),
+            )
+                .into_response()
+        }
+    };
+
+    let events = stream::unfold(Some(load), move |load| {
+        let slot = Arc::clone(&slot);
+        async move {
+            let mut load = load?;
+            let (event, load) = tokio::select! {
+                finished = &mut load => {
+                    let event = match finished {
+                        Ok(Ok(model_name)) => ReloadEvent::Ready { model_name },
+                        Ok(Err(e)) => ReloadEvent::Failed { error: format!("{:#}", e) },
+                        Err(e) => ReloadEvent::Failed { error: e.to_string() },
+                    };
+                    (event, None)
+                }
+                _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
+                    let health = ModelHealth::of(&slot).await;
+                    let event = ReloadEvent::Progress {
+                        stage: health.state.to_string(),
+                        model_name: health.model_name,
+                        progress: health.progress,
+                    };
+                    (event, Some(load))
+                }
+            };
+            let event = Event::default()
+                .json_data(&event)
+                .expect("reload events always serialize");
+            Some((Ok::<_, Infallible>(event), load))
+        }
+    });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// GET /v1/events — stream the daemon's activity as server-sent events
+///
+/// Every event is a JSON `Activity`, named after its `type` ("request" or
+/// "event").  Open to worker traffic, so a worker can be watched from the
+/// machine that hands it work; workspaces may not watch other tenants.
+async fn activity_events(State(server): State<Arc<AgentServer>>, headers: HeaderMap) -> Response {
+    use tokio::sync::broadcast::error::RecvError;
+
+    match server.workspaces().resolve(&headers) {
+        Err(e) => return e.into_response(),
+        Ok(Some(workspace)) => {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": {
+                        "message": format!(
+                            "workspace '{}' may not watch the daemon's activity",
+                            workspace.name()
+                        ),
+                        "type": "permission_error"
+                    }
+                })),
+            )
+                .into_response()
+        }
+        Ok(None) => {}
+    }
+
+    let rx = server.activity().subscribe();
+    let events = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(activity) => {
+                    let event = Event::default()
+                        .event(activity.kind())
+                        .json_data(&activity)
+                        .expect("activity always serializes");
+                    return Some((Ok::<_, Infallible>(event), rx));
+                }
+                // A slow watcher missed some; the feed is best-effort
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "Activity subscriber lagged");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
 /// Generator status information
 #[derive(Debug, Serialize)]
 #[serde(tag = "state", rename_all = "snake_case")]
@@ -621,19 +1470,120 @@ async fn get_status(
 #[derive(Debug, Serialize)]
 pub struct HealthStatus {
     pub status: String,
+    /// finch version the daemon is running (`finch update` waits on it)
+    pub version: String,
     pub uptime_seconds: u64,
     pub active_sessions: usize,
+    /// Tokens and estimated spend across all requests since startup
+    pub usage: SessionUsage,
+    /// Loading progress of each local model (primary first)
+    pub models: Vec<ModelHealth>,
+    /// Circuit state of each cloud provider
+    pub providers: Vec<super::ProviderHealth>,
+    pub memory: MemoryHealth,
+    /// Examples collected since the last training batch
+    pub training_queue_depth: usize,
+    /// Protocol, endpoints and formats this daemon offers (read by
+    /// `DaemonClient` at connect time)
+    pub capabilities: super::Capabilities,
+}
+
+/// One local model's readiness in the health response
+#[derive(Debug, Serialize)]
+pub struct ModelHealth {
+    pub id: String,
+    /// "initializing", "downloading", "loading", "ready", "failed" or "not_available"
+    pub state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub progress: crate::models::LoadProgressSnapshot,
+}
+
+/// System memory in the health response
+#[derive(Debug, Serialize)]
+pub struct MemoryHealth {
+    pub total_mb: u64,
+    pub available_mb: u64,
+    /// Resident memory of the daemon itself
+    pub process_mb: u64,
+    /// "normal", "low" (< 20% available) or "critical" (< 10% available)
+    pub pressure: &'static str,
+}
+
+impl ModelHealth {
+    async fn of(slot: &super::LocalModelSlot) -> Self {
+        use crate::models::GeneratorState;
+
+        let progress = slot.bootstrap_loader.progress().snapshot();
+        let fetching = progress.files_remaining > 0 || progress.current_file.is_some();
+        let (state, model_name, error) = match &*slot.generator_state.read().await {
+            GeneratorState::Initializing => ("initializing", None, None),
+            GeneratorState::Downloading { model_name, .. } => {
+                ("downloading", Some(model_name.clone()), None)
+            }
+            GeneratorState::Loading { model_name } if fetching => {
+                ("downloading", Some(model_name.clone()), None)
+            }
+            GeneratorState::Loading { model_name } => ("loading", Some(model_name.clone()), None),
+            GeneratorState::Ready { model_name, .. } => ("ready", Some(model_name.clone()), None),
+            GeneratorState::Failed { error } => ("failed", None, Some(error.clone())),
+            GeneratorState::NotAvailable => ("not_available", None, None),
+        };
+        Self {
+            id: slot.id.clone(),
+            state,
+            model_name,
+            error,
+            progress,
+        }
+    }
+}
+
+impl From<crate::monitoring::MemoryInfo> for MemoryHealth {
+    fn from(info: crate::monitoring::MemoryInfo) -> Self {
+        const MB: u64 = 1024 * 1024;
+        let pressure = if info.is_critical() {
+            "critical"
+        } else if info.is_low() {
+            "low"
+        } else {
+            "normal"
+        };
+        Self {
+            total_mb: info.total_memory / MB,
+            available_mb: info.available_memory / MB,
+            process_mb: info.process_memory / MB,
+            pressure,
+        }
+    }
 }
 
 /// Handle GET /health - Health check endpoint
 pub async fn health_check(
     State(server): State<Arc<AgentServer>>,
 ) -> Result<Json<HealthStatus>, AppError> {
-    // TODO: Track actual uptime
+    let mut models = Vec::new();
+    for slot in server.local_models().slots() {
+        models.push(ModelHealth::of(slot).await);
+    }
+    // sysinfo scans every process, which blocks
+    let memory = tokio::task::spawn_blocking(crate::monitoring::MemoryInfo::current).await?;
+    let training_queue_depth = server.training_coordinator().buffer()?.len();
+
     let status = HealthStatus {
         status: "healthy".to_string(),
-        uptime_seconds: 0, // Placeholder
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_seconds: server.uptime().as_secs(),
         active_sessions: server.session_manager().active_count(),
+        usage: server.session_manager().total_usage(),
+        models,
+        providers: server.provider_health(),
+        memory: memory.into(),
+        training_queue_depth,
+        capabilities: super::Capabilities::of(&server),
     };
 
     Ok(Json(status))
@@ -641,12 +1591,18 @@ pub async fn health_check(
 
 /// Handle GET /metrics - Prometheus metrics endpoint
 pub async fn metrics_endpoint(
-    State(_server): State<Arc<AgentServer>>,
+    State(server): State<Arc<AgentServer>>,
 ) -> Result<Response, AppError> {
-    // TODO: Implement Prometheus metrics
-    let metrics = "# HELP finch_queries_total Total number of queries\n\
-                   # TYPE finch_queries_total counter\n\
-                   finch_queries_total 0\n";
+    // TODO: Implement remaining Prometheus metrics
+    let mut metrics = String::from(
+        "# HELP finch_queries_total Total number of queries\n\
+         # TYPE finch_queries_total counter\n\
+         finch_queries_total 0\n",
+    );
+    metrics.push_str(&server.request_queue().snapshot().to_prometheus());
+    if let Some(cache) = server.response_cache() {
+        metrics.push_str(&cache.to_prometheus());
+    }
 
     Ok((StatusCode::OK, metrics).into_response())
 }
@@ -656,6 +1612,27 @@ pub struct AppError(anyhow::Error);
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        // Saturation is expected under load — answer 503 + Retry-After, not 500
+        if let Some(full) = self.0.downcast_ref::<super::QueueFull>() {
+            tracing::warn!(error = %self.0, "Request rejected");
+            return full.clone().into_response();
+        }
+        // Likewise while the daemon drains for shutdown
+        if let Some(draining) = self.0.downcast_ref::<super::Draining>() {
+            tracing::info!(error = %self.0, "Request rejected");
+            return draining.clone().into_response();
+        }
+        // The client cancelled the generation itself
+        if let Some(cancelled) = self.0.downcast_ref::<super::GenerationCancelled>() {
+            tracing::info!(error = %self.0, "Request cancelled");
+            return cancelled.clone().into_response();
+        }
+        // Unauthenticated, forbidden or over quota for the caller's workspace
+        if let Some(refused) = self.0.downcast_ref::<WorkspaceError>() {
+            tracing::info!(error = %self.0, "Request rejected");
+            return refused.clone().into_response();
+        }
+
         tracing::error!(error = %self.0, "Request failed");
 
         let error_message = self.0.to_string();
@@ -896,24 +1873,34 @@ async fn handle_forth_define(
 /// REPL sessions poll this to sync definitions made in other concurrent terminals.
 /// `version` is a monotonic counter — if it matches the caller's last-seen value,
 /// the vocabulary has not changed and there is nothing to compile.
-async fn handle_forth_vocab() -> Json<serde_json::Value> {
+async fn handle_forth_vocab(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, Response> {
+    let ip = addr.ip().to_string();
+    check_peer_token(&headers, &ip, "/v1/forth/vocab")?;
     let live = LIVE_VM.read().await;
     let source = live.dump_source();
     let version = VOCAB_VERSION.load(std::sync::atomic::Ordering::Relaxed);
-    Json(serde_json::json!({ "source": source, "version": version }))
+    let vocab = serde_json::json!({ "source": source, "version": version });
+    Ok(Json(vocab))
 }
 
 /// POST /v1/forth/push — receive a plain-text push message from a peer.
 /// Broadcasts it to the local TUI via PUSH_INBOX.
 async fn handle_forth_push(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(req): Json<ForthPushRequest>,
-) -> StatusCode {
+) -> Result<StatusCode, Response> {
+    let ip = addr.ip().to_string();
+    check_peer_token(&headers, &ip, "/v1/forth/push")?;
     let msg = match &req.from {
         Some(from) => format!("[{}] {}", from, req.text),
         None       => req.text.clone(),
     };
     let _ = PUSH_INBOX.send(msg);
-    StatusCode::OK
+    Ok(StatusCode::OK)
 }
 
 #[derive(Debug, Deserialize)]
@@ -1122,3 +2109,18 @@ async fn handle_exec(
         error: None,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_query_k_is_capped() {
+        let request: MemoryQueryRequest =
+            serde_json::from_str(r#"{"query": "deploys", "k": 18446744073709551615}"#).unwrap();
+        assert_eq!(request.k, Some(usize::MAX));
+        assert_eq!(query_k(request.k), crate::server::memory::MAX_QUERY_K);
+        assert_eq!(query_k(Some(3)), 3);
+        assert_eq!(query_k(None), crate::server::memory::RECALL_K);
+    }
+}
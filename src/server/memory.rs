@@ -0,0 +1,167 @@
+// Daemon memory — recall for API sessions
+//
+// Memory used to live only in the REPL, so OpenAI API clients and LAN
+// workers got no recall.  With `[server] memory = true` the daemon keeps a
+// memory database of its own in `~/.finch/daemon/memory.db` and serves it:
+//
+//   POST /v1/memory        {"content": "...", "role"?, "session_id"?}
+//   POST /v1/memory/query  {"query": "...", "k"?, "session_id"?, "role"?,
+//                           "project"?, "after"?, "before"?, "min_importance"?}
+//   GET  /v1/memory/stats
+//
+// `/v1/chat/completions` also recalls on the last user message into the
+// system prompt and remembers the exchange once it is answered.  A request
+// with `"memory": false` skips both; finch's own clients send that, since
+// they keep their own memory.  Incognito requests are recalled into but not
+// remembered, and neither are tool calls or streamed and `local_only`
+// answers.
+//
+// A request made with a workspace key uses that workspace's memory database
+// instead of the shared one, so tenants never recall each other's memories.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use super::{ChatMessage, Workspace};
+use crate::memory::facts::MAX_FACTS_INJECTED;
+use crate::memory::{MemoryConfig, MemorySystem};
+
+/// Memories recalled into a chat completion
+pub const RECALL_K: usize = 5;
+
+/// Most memories one POST /v1/memory/query returns
+pub const MAX_QUERY_K: usize = 50;
+
+/// The daemon's shared memory, opened on first use
+pub struct DaemonMemory {
+    /// Holds `memory.db`; `None` when daemon memory is disabled
+    dir: Option<PathBuf>,
+    shared: OnceCell<Arc<MemorySystem>>,
+}
+
+impl DaemonMemory {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            shared: OnceCell::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// The memory a request reads and writes: its workspace's own, or else
+    /// the daemon's shared memory
+    pub async fn for_request(&self, workspace: Option<&Workspace>) -> Result<Arc<MemorySystem>> {
+        if let Some(workspace) = workspace {
+            return workspace.memory().await;
+        }
+        let dir = self.dir.as_deref().context("Daemon memory is disabled")?;
+        self.shared
+            .get_or_try_init(|| async {
+                let memory = Arc::new(MemorySystem::new(MemoryConfig::workspace(dir))?);
+                memory.spawn_reembedding(|done, total| {
+                    tracing::info!("Re-embedding daemon memories: {}/{}", done, total)
+                });
+                Ok(memory)
+            })
+            .await
+            .cloned()
+    }
+}
+
+/// Remembered facts and memories relevant to `query`, as a system prompt
+/// section; `None` when nothing is relevant
+pub async fn recall(memory: &MemorySystem, query: &str) -> Result<Option<String>> {
+    let bullets = |lines: Vec<String>| {
+        lines
+            .iter()
+            .map(|line| format!("• {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut sections = Vec::new();
+    let facts = memory.matching_facts(query, MAX_FACTS_INJECTED).await?;
+    if !facts.is_empty() {
+        let statements = facts.into_iter().map(|f| f.statement).collect();
+        sections.push(format!(
+            "## Known facts and preferences\n{}",
+            bullets(statements)
+        ));
+    }
+    let memories = memory.query(query, Some(RECALL_K)).await?;
+    if !memories.is_empty() {
+        sections.push(format!(
+            "## Memories from previous sessions\n{}",
+            bullets(memories)
+        ));
+    }
+    Ok((!sections.is_empty()).then(|| sections.join("\n\n")))
+}
+
+/// Append `block` to the first system message, or put it in a new system
+/// message ahead of the conversation
+pub fn inject(messages: &mut Vec<ChatMessage>, block: &str) {
+    match messages.iter_mut().find(|m| m.role == "system") {
+        Some(system) => {
+            let content = system.content.get_or_insert_with(String::new);
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            content.push_str(block);
+        }
+        None => messages.insert(0, ChatMessage::system(block)),
+    }
+}
+
+/// Remember a chat completion's question and answer in the background
+pub fn remember_exchange(
+    memory: Arc<MemorySystem>,
+    query: String,
+    answer: String,
+    model: String,
+    session_id: Option<String>,
+) {
+    tokio::spawn(async move {
+        for (role, content) in [("user", query), ("assistant", answer)] {
+            if let Err(e) = memory
+                .insert_conversation(role, &content, Some(&model), session_id.as_deref())
+                .await
+            {
+                tracing::warn!("Failed to remember exchange in daemon memory: {}", e);
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_extends_or_adds_the_system_message() {
+        let block = "## Memories from previous sessions\n• deploys go through staging";
+
+        let mut messages = vec![
+            ChatMessage::system("You are terse."),
+            ChatMessage::user("how do I deploy?"),
+        ];
+        inject(&mut messages, block);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].content.as_deref(),
+            Some(format!("You are terse.\n\n{}", block).as_str())
+        );
+
+        let mut messages = vec![ChatMessage::user("how do I deploy?")];
+        inject(&mut messages, block);
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(messages[0].content.as_deref(), Some(block));
+        assert_eq!(messages[1].role, "user");
+    }
+}
@@ -2,20 +2,42 @@
 
 use axum::{
     body::Body,
+    extract::State,
     http::{Request, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-/// Authentication middleware (placeholder for Phase 4)
-pub async fn auth_middleware(request: Request<Body>, next: Next) -> Result<Response, StatusCode> {
-    // TODO: Implement API key authentication in Phase 4
-    // For now, allow all requests
-    Ok(next.run(request).await)
+use super::workspace::Workspaces;
+
+/// Served without a workspace key: health checks, the chat page (which asks
+/// for a key itself) and the peer network, which has its own tokens.  Every
+/// Forth endpoint checks the peer token (`X-Finch-Token`) itself.
+const OPEN_PATHS: &[&str] = &["/health", "/metrics", "/ui", "/v1/exec", "/v1/settle"];
+const OPEN_PREFIXES: &[&str] = &["/v1/node/", "/v1/registry/", "/v1/forth/"];
+
+/// Authentication middleware: once workspaces are configured or the daemon
+/// token is required, every other request needs a workspace key or the
+/// owner's token (401 otherwise).
+///
+/// Handlers resolve the key again to find the caller's workspace.
+pub async fn auth_middleware(
+    State(workspaces): State<Arc<Workspaces>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let open = OPEN_PATHS.contains(&path) || OPEN_PREFIXES.iter().any(|p| path.starts_with(p));
+    if !open {
+        if let Err(e) = workspaces.resolve(request.headers()) {
+            return e.into_response();
+        }
+    }
+    next.run(request).await
 }
 
 // ---------------------------------------------------------------------------
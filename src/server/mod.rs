@@ -1,37 +1,81 @@
 // Shammah - Agent Server Module
 // HTTP daemon mode for multi-tenant agent serving
 
+mod access_log;
+mod activity;
 pub mod brain_registry;
+mod capabilities;
+mod circuit;
+mod drain;
 mod feedback_handler;
+mod generations;
+pub mod grpc;
 pub mod handlers;
+mod memory;
 mod middleware;
+pub mod model_pool;
+mod model_reload;
 mod openai_handlers;
 pub mod openai_types; // Public for client access
+mod proxy;
+pub mod replay;
+pub mod request_queue;
+mod response_cache;
+pub mod self_eval;
 mod session;
+mod speculation;
 mod training_worker;
+#[cfg(unix)]
+mod unix_socket;
+mod web;
+mod webhooks;
+mod worker_role;
+mod workspace;
 
-pub use brain_registry::{BrainDetail, BrainRegistry, BrainState, BrainSummary, PendingPlanView, PendingQuestionView, PlanResponse};
+pub use access_log::{AccessDetails, AccessLog};
+pub use activity::{Activity, ActivityFeed, RequestActivity};
+pub use brain_registry::{BrainDetail, BrainEvent, BrainRegistry, BrainState, BrainSummary, PendingPlanView, PendingQuestionView, PlanResponse};
+pub use capabilities::{Capabilities, PROTOCOL_VERSION};
+pub use circuit::{ProviderCircuits, ProviderHealth};
+pub use drain::{DrainController, Draining, InFlightGuard};
 pub use feedback_handler::{handle_feedback, handle_training_status};
+pub use generations::{ActiveGeneration, ActiveGenerations, GenerationCancelled};
 pub use handlers::{
     create_router, handle_node_info, handle_node_stats, health_check, metrics_endpoint,
 };
+pub use memory::DaemonMemory;
 pub use middleware::{auth_middleware, RateLimiter};
+pub use model_pool::{LocalModelSlot, ModelPool};
+pub use model_reload::{ModelReloadRequest, ReloadEvent};
 pub use openai_handlers::{handle_chat_completions, handle_list_models};
 pub use openai_types::*;
-pub use session::{SessionManager, SessionState};
+pub use proxy::{
+    MessagesRequest, MessagesResponse, MessagesUsage, ProxyConfig, ProxyServer, ProxyUsage,
+};
+pub use replay::{Exchange, ReplayLog};
+pub use request_queue::{QueueFull, QueuePermit, RequestPriority, RequestQueue};
+pub use response_cache::{CacheHit, CacheKey, ResponseCache};
+pub use self_eval::{EvalRun, SelfEvalConfig, TeacherSample, TeacherSamples};
+pub use session::{SessionManager, SessionState, SessionUsage};
 pub use training_worker::TrainingWorker;
+pub use webhooks::{WebhookEvent, Webhooks};
+pub use worker_role::{WorkerRole, WorkerTraffic};
+pub use workspace::{Workspace, WorkspaceError, WorkspaceUsage, Workspaces};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{oneshot, RwLock};
+use tokio::task::JoinHandle;
 use tower_http::trace::TraceLayer;
 
 use crate::claude::ClaudeClient;
 use crate::config::Config;
+use crate::daemon::DaemonEndpoint;
 use crate::local::LocalGenerator;
 use crate::metrics::MetricsLogger;
-use crate::models::{BootstrapLoader, GeneratorState, TrainingCoordinator};
+use crate::models::{BootstrapLoader, GeneratorModel, GeneratorState, TrainingCoordinator};
 use crate::providers::LlmProvider;
 use crate::router::Router;
 
@@ -48,6 +92,41 @@ pub struct ServerConfig {
     pub auth_enabled: bool,
     /// Valid API keys for authentication
     pub api_keys: Vec<String>,
+    /// Maximum number of requests allowed to wait for the local generator
+    /// before new ones are rejected with 503 + Retry-After
+    pub max_queue_depth: usize,
+    /// Generations each local model runs at once, one loaded copy per session
+    pub parallel_sessions: usize,
+    /// Directory sessions are persisted to on shutdown (`None` = memory only)
+    pub sessions_dir: Option<std::path::PathBuf>,
+    /// How long shutdown waits for in-flight requests and agent turns
+    pub drain_timeout_secs: u64,
+    /// Access log written one JSON line per request (`None` = disabled)
+    pub access_log: Option<std::path::PathBuf>,
+    /// Seconds a cached response is reused (0 = response cache disabled)
+    pub response_cache_ttl_secs: u64,
+    /// Cosine similarity above which a similar question reuses a cached answer
+    pub response_cache_similarity: Option<f32>,
+    /// URLs that receive signed event notifications
+    pub webhooks: crate::config::WebhooksConfig,
+    /// Cross-origin access for browser clients
+    pub cors: crate::config::CorsConfig,
+    /// Serve the built-in chat page at `/ui`
+    pub web_ui: bool,
+    /// Also serve the gRPC API (proto/finch/v1/finch.proto) on this address
+    pub grpc_address: Option<String>,
+    /// Start local generation on an idle lane while routing is still deciding
+    pub speculative_local: bool,
+    /// Tenants and their API keys (empty = no authentication)
+    pub workspaces: Vec<crate::config::WorkspaceConfig>,
+    /// Where each workspace keeps its memory and usage (`<dir>/<name>/`)
+    pub workspaces_dir: Option<std::path::PathBuf>,
+    /// Directory of the daemon's shared memory (`None` = no daemon memory)
+    pub memory_dir: Option<std::path::PathBuf>,
+    /// Per-install token file clients must present (`None` = not required)
+    pub local_token: Option<std::path::PathBuf>,
+    /// Also serve LAN worker traffic on this address, below local requests
+    pub worker_address: Option<String>,
 }
 
 impl Default for ServerConfig {
@@ -58,6 +137,23 @@ impl Default for ServerConfig {
             session_timeout_minutes: 30,
             auth_enabled: false,
             api_keys: vec![],
+            max_queue_depth: 32,
+            parallel_sessions: 1,
+            sessions_dir: None,
+            drain_timeout_secs: crate::config::constants::DEFAULT_DRAIN_TIMEOUT_SECS,
+            access_log: None,
+            response_cache_ttl_secs: 0,
+            response_cache_similarity: None,
+            webhooks: crate::config::WebhooksConfig::default(),
+            cors: crate::config::CorsConfig::default(),
+            web_ui: false,
+            grpc_address: None,
+            speculative_local: true,
+            workspaces: Vec::new(),
+            workspaces_dir: None,
+            memory_dir: None,
+            local_token: None,
+            worker_address: None,
         }
     }
 }
@@ -91,6 +187,41 @@ pub struct AgentServer {
     training_rx: std::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<crate::models::WeightedExample>>>,
     /// Brain registry — tracks all daemon brain sessions
     brain_registry: Arc<BrainRegistry>,
+    /// Local models served by this daemon (primary first), selected by the
+    /// OpenAI `model` field. The primary slot shares the Arcs above.
+    local_models: ModelPool,
+    /// In-flight tracking and admission control for graceful shutdown
+    drain: Arc<DrainController>,
+    /// Shutdown signal and handle for the training worker spawned by `serve()`
+    training_worker: std::sync::Mutex<Option<(oneshot::Sender<()>, JoinHandle<()>)>>,
+    /// Cached responses for repeated requests (`None` when disabled)
+    response_cache: Option<ResponseCache>,
+    /// Event notifications (no-op when no URLs are configured)
+    webhooks: Arc<Webhooks>,
+    /// Fail-fast state for cloud providers that keep erroring
+    circuits: ProviderCircuits,
+    /// Recent redacted provider exchanges per session, for replay
+    replay_log: ReplayLog,
+    /// Generations that can be cancelled by session id
+    generations: ActiveGenerations,
+    /// Tenants by API key (none configured = open, single-tenant daemon)
+    workspaces: Arc<Workspaces>,
+    /// Memory for API sessions without a workspace (disabled by default)
+    memory: DaemonMemory,
+    /// Quiet hours for training and brains
+    schedule: Arc<crate::scheduling::ScheduleConfig>,
+    /// Idle-time replays of teacher answers through the local model
+    self_eval: SelfEvalConfig,
+    /// Teacher answers kept for those replays (`None` when disabled)
+    teacher_samples: Option<Arc<TeacherSamples>>,
+    /// How often metrics day files are uploaded (`None` = storage is local)
+    metrics_upload: Option<Duration>,
+    /// Second listener for LAN worker traffic, sharing the loaded models
+    worker_role: WorkerRole,
+    /// Live requests and daemon events for `GET /v1/events`
+    activity: Arc<ActivityFeed>,
+    /// When the server was created, for `/health` uptime
+    started_at: std::time::Instant,
 }
 
 impl AgentServer {
@@ -100,7 +231,7 @@ impl AgentServer {
     /// If empty, the server falls back to `claude_client` for all cloud forwarding.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        _config: Config,
+        config: Config,
         server_config: ServerConfig,
         claude_client: ClaudeClient,
         router: Router,
@@ -111,15 +242,108 @@ impl AgentServer {
         training_coordinator: Arc<TrainingCoordinator>,
         providers: Vec<Box<dyn LlmProvider>>,
     ) -> Result<Self> {
-        let session_manager = SessionManager::new(
+        let mut session_manager = SessionManager::new(
             server_config.max_sessions,
             server_config.session_timeout_minutes,
         );
+        if let Some(storage) = crate::storage::remote(&config.storage, "sessions")? {
+            tracing::info!("Persisting sessions to {}", storage.location());
+            session_manager = session_manager.with_storage(storage);
+        } else if let Some(dir) = &server_config.sessions_dir {
+            session_manager = session_manager.with_persist_dir(dir.clone())?;
+        }
+        let mut metrics_logger = metrics_logger;
+        let mut metrics_upload = None;
+        if let Some(storage) = crate::storage::remote(&config.storage, "metrics")? {
+            tracing::info!("Uploading metrics to {}", storage.location());
+            metrics_logger = metrics_logger.with_remote(storage, &hostname_or_default());
+            metrics_upload = Some(Duration::from_secs(
+                config.storage.metrics_upload_secs.max(1),
+            ));
+        }
 
         // Create training channel; receiver is taken by serve() to hand to the worker.
         let (training_tx, training_rx) = tokio::sync::mpsc::unbounded_channel();
         let providers: Vec<Arc<dyn LlmProvider>> = providers.into_iter().map(Arc::from).collect();
 
+        // The primary local model is the one passed in; extra models are added
+        // with add_local_model() before serving.
+        let primary_id = config
+            .local_providers()
+            .first()
+            .and_then(|p| p.local_model_id())
+            .unwrap_or_else(|| model_pool::DEFAULT_LOCAL_MODEL_ID.to_string());
+        let local_models = ModelPool::new(
+            LocalModelSlot {
+                id: primary_id,
+                local_generator: Arc::clone(&local_generator),
+                extra_lanes: Vec::new(),
+                generator_state: Arc::clone(&generator_state),
+                bootstrap_loader: Arc::clone(&bootstrap_loader),
+                request_queue: Arc::new(RequestQueue::new(server_config.max_queue_depth)),
+            }
+            .with_parallel_sessions(server_config.parallel_sessions),
+        );
+
+        let response_cache = (server_config.response_cache_ttl_secs > 0).then(|| {
+            ResponseCache::new(
+                Duration::from_secs(server_config.response_cache_ttl_secs),
+                crate::config::constants::RESPONSE_CACHE_MAX_ENTRIES,
+                server_config.response_cache_similarity,
+            )
+        });
+
+        // Daemon events go to the activity feed as well as to webhooks
+        let activity = Arc::new(ActivityFeed::new());
+        let webhooks = Arc::new(
+            Webhooks::new(server_config.webhooks.clone()).with_activity(Arc::clone(&activity)),
+        );
+
+        let owner_token = server_config
+            .local_token
+            .as_deref()
+            .map(crate::daemon::token::load_or_create)
+            .transpose()
+            .context("Failed to provision the daemon token")?;
+
+        // Captured requests never hold provider or daemon keys in the clear
+        let secrets = config
+            .providers
+            .iter()
+            .filter_map(|p| p.api_key())
+            .map(str::to_string)
+            .chain(server_config.api_keys.iter().cloned())
+            .chain(
+                server_config
+                    .workspaces
+                    .iter()
+                    .flat_map(|w| w.api_keys.iter().cloned()),
+            )
+            .chain(owner_token.clone())
+            .collect();
+        let replay_log = ReplayLog::new(
+            crate::config::constants::REPLAY_LOG_PER_SESSION,
+            server_config.max_sessions,
+            secrets,
+        );
+
+        let mut workspaces = Workspaces::new(
+            &server_config.workspaces,
+            server_config.workspaces_dir.as_deref(),
+        );
+        if let Some(token) = owner_token {
+            workspaces = workspaces.with_owner_token(token);
+        }
+        let workspaces = Arc::new(workspaces);
+        let memory = DaemonMemory::new(server_config.memory_dir.clone());
+
+        let teacher_samples = config
+            .self_eval
+            .enabled
+            .then(TeacherSamples::open_default)
+            .flatten()
+            .map(Arc::new);
+
         Ok(Self {
             claude_client: Arc::new(claude_client),
             providers,
@@ -134,15 +358,44 @@ impl AgentServer {
             training_tx: Arc::new(training_tx),
             training_rx: std::sync::Mutex::new(Some(training_rx)),
             brain_registry: Arc::new(BrainRegistry::new()),
+            local_models,
+            drain: Arc::new(DrainController::new()),
+            training_worker: std::sync::Mutex::new(None),
+            response_cache,
+            webhooks,
+            circuits: ProviderCircuits::new(
+                crate::config::constants::PROVIDER_CIRCUIT_FAILURE_THRESHOLD,
+                Duration::from_secs(crate::config::constants::PROVIDER_CIRCUIT_COOLDOWN_SECS),
+            ),
+            replay_log,
+            generations: ActiveGenerations::new(),
+            workspaces,
+            memory,
+            schedule: Arc::new(config.schedule.clone()),
+            self_eval: config.self_eval.clone(),
+            teacher_samples,
+            metrics_upload,
+            worker_role: WorkerRole::new(),
+            activity,
+            started_at: std::time::Instant::now(),
         })
     }
 
+    /// Serve an additional local model alongside the primary one.
+    ///
+    /// Must be called before `serve()`; the slot's loader should already be
+    /// running (or about to run) so its state eventually reaches `Ready`.
+    pub fn add_local_model(&mut self, slot: LocalModelSlot) -> Result<()> {
+        tracing::info!(model = %slot.id, "Registering additional local model");
+        self.local_models.add(slot)
+    }
+
     /// Start the HTTP server.
     ///
     /// Takes `Arc<Self>` so the same server instance can be shared with the
     /// Cap'n Proto IPC server that runs concurrently.
     pub async fn serve(self: Arc<Self>) -> Result<()> {
-        let addr: SocketAddr = self.config.bind_address.parse()?;
+        let endpoint = DaemonEndpoint::parse(&self.config.bind_address)?;
 
         // Take the training receiver that was created in new().  Panics if
         // serve() is called more than once on the same instance (shouldn't happen).
@@ -153,20 +406,39 @@ impl AgentServer {
             .take()
             .expect("AgentServer::serve() called twice");
 
-        // Spawn training worker in background
+        // Spawn training worker in background; drain_and_flush() stops it
+        let (worker_shutdown_tx, worker_shutdown_rx) = oneshot::channel();
         let worker = TrainingWorker::new(
             training_rx,
             Arc::clone(&self.training_coordinator),
             10, // batch_threshold: trigger after 10 examples
             5,  // batch_timeout_minutes: trigger after 5 minutes
-        );
+        )
+        .with_shutdown(worker_shutdown_rx)
+        .with_webhooks(Arc::clone(&self.webhooks))
+        .with_schedule(self.schedule.as_ref().clone());
 
-        tokio::spawn(async move {
+        let worker_handle = tokio::spawn(async move {
             worker.run().await;
         });
+        *self.training_worker.lock().unwrap() = Some((worker_shutdown_tx, worker_handle));
 
         tracing::info!("Training worker spawned");
 
+        // Replay teacher answers through the local model when `[schedule]`
+        // allows, calibrating the router
+        if let Some(samples) = &self.teacher_samples {
+            tokio::spawn(self_eval::run(
+                Arc::clone(&self),
+                Arc::clone(samples),
+                self.self_eval.clone(),
+            ));
+            tracing::info!(
+                every_minutes = self.self_eval.interval_minutes,
+                "Self-evaluation enabled"
+            );
+        }
+
         // Background task: expire stale registry entries every 30 seconds.
         let registry = std::sync::Arc::clone(&crate::server::handlers::REGISTRY);
         tokio::spawn(async move {
@@ -204,85 +476,342 @@ impl AgentServer {
             }
         });
 
-        // Monitor generator state and inject model when ready
-        let local_gen_clone = Arc::clone(&self.local_generator);
-        let state_monitor = Arc::clone(&self.generator_state);
+        // Drop expired sessions left in the store by earlier runs
+        let sessions = Arc::clone(&self.session_manager);
         tokio::spawn(async move {
-            tracing::info!("Model monitor task started");
-            loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
-                let state = state_monitor.read().await;
-                tracing::debug!(
-                    "Monitor checking state: {:?}",
-                    std::mem::discriminant(&*state)
-                );
+            match sessions.prune_persisted().await {
+                Ok(n) if n > 0 => tracing::info!(count = n, "Pruned expired persisted sessions"),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to prune persisted sessions: {}", e),
+            }
+        });
 
-                if let GeneratorState::Ready { model, model_name } = &*state {
-                    let model_clone = Arc::clone(model);
-                    let name = model_name.clone();
-                    drop(state); // Release read lock before acquiring write lock
-
-                    tracing::info!("Model is ready: {}, injecting into LocalGenerator", name);
-
-                    // Try to inject with timeout
-                    match tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
-                        tracing::info!("Acquiring write lock on LocalGenerator...");
-                        let mut gen = local_gen_clone.write().await;
-                        tracing::info!("Write lock acquired, creating new LocalGenerator...");
-                        *gen = LocalGenerator::with_models(Some(model_clone));
-                        tracing::info!("LocalGenerator updated");
-                    })
-                    .await
-                    {
-                        Ok(_) => {
-                            tracing::info!("✓ Model injected - local generation enabled");
-                            break; // Stop monitoring once injected
-                        }
-                        Err(_) => {
-                            tracing::error!(
-                                "❌ Timeout while injecting model (5s) - write lock may be held"
-                            );
-                        }
+        // Copy metrics to remote storage as they accumulate
+        if let Some(every) = self.metrics_upload {
+            let metrics = Arc::clone(&self.metrics_logger);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(every);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = metrics.upload().await {
+                        tracing::warn!("Failed to upload metrics: {}", e);
                     }
-                } else if matches!(
-                    *state,
-                    GeneratorState::Failed { .. } | GeneratorState::NotAvailable
-                ) {
-                    tracing::warn!("Model loading failed or not available, stopping monitor");
-                    break; // Stop monitoring on failure
                 }
-            }
-            tracing::info!("Model monitor task exiting");
-        });
+            });
+        }
+
+        // Monitor each local model's state and inject it when ready
+        for slot in self.local_models.slots() {
+            spawn_model_monitor(Arc::clone(slot));
+        }
 
         // Use the existing Arc as application state.
-        let app_state = self;
+        let drain_controller = Arc::clone(&self.drain);
+        let access_log = self.config.access_log.clone().map(|path| {
+            tracing::info!("Access log: {}", path.display());
+            Arc::new(AccessLog::new(
+                path,
+                crate::config::constants::ACCESS_LOG_MAX_BYTES,
+                crate::config::constants::ACCESS_LOG_KEEP,
+            ))
+        });
+        let webhooks = Arc::clone(&self.webhooks);
+        let cors = web::cors_layer(&self.config.cors);
+        let workspaces = Arc::clone(&self.workspaces);
+        if workspaces.is_enabled() {
+            tracing::info!(
+                count = self.config.workspaces.len(),
+                "Workspaces configured; API keys required"
+            );
+        }
+        if let Some(path) = &self.config.local_token {
+            tracing::info!(
+                "Clients must present the daemon token in {}",
+                path.display()
+            );
+        }
+
+        if let Some(grpc_address) = &self.config.grpc_address {
+            let addr: SocketAddr = grpc_address
+                .parse()
+                .with_context(|| format!("Invalid gRPC address: {}", grpc_address))?;
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Failed to bind gRPC API to {}", addr))?;
+            tracing::info!("Serving gRPC API on {}", addr);
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = grpc::serve(server, listener).await {
+                    tracing::error!("gRPC server failed: {:#}", e);
+                }
+            });
+        }
+
+        let app_state = Arc::clone(&self);
 
         // Build router with a body size limit to guard against oversized foreign payloads.
         // 4MB is generous for natural-language queries while blocking obvious DoS attempts.
-        let app = create_router(app_state)
+        let mut app = create_router(app_state).layer(axum::middleware::from_fn_with_state(
+            workspaces,
+            auth_middleware,
+        ));
+        if let Some(log) = access_log {
+            app = app.layer(axum::middleware::from_fn_with_state(log, access_log::record_access));
+        }
+        app = app.layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&self.activity),
+            activity::record_activity,
+        ));
+        let mut app = app
             .layer(axum::extract::DefaultBodyLimit::max(4 * 1024 * 1024)) // 4MB
-            .layer(TraceLayer::new_for_http());
+            .layer(axum::middleware::from_fn_with_state(drain_controller, drain::track_in_flight));
+        // Outside the other layers so preflight requests are answered directly
+        if let Some(cors) = cors {
+            app = app.layer(cors);
+        }
+        let app = app.layer(TraceLayer::new_for_http());
 
-        tracing::info!("Starting Shammah agent server on {}", addr);
+        // The worker listener serves the same routes, so LAN requests share
+        // the models (and request queues) local sessions use
+        self.worker_role.set_app(app.clone());
+        if let Some(address) = &self.config.worker_address {
+            self.worker_role.start(address).await?;
+        }
 
-        // Start server — ConnectInfo requires into_make_service_with_connect_info
-        // so handlers can read the peer's IP for auth logging.
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
-        ).await?;
+        tracing::info!("Starting Shammah agent server on {}", endpoint);
+        webhooks.emit(WebhookEvent::DaemonStarted {
+            bind_address: endpoint.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        });
+
+        match endpoint {
+            DaemonEndpoint::Tcp(addr) => {
+                let addr: SocketAddr = addr.parse()?;
+                // Start server — ConnectInfo requires into_make_service_with_connect_info
+                // so handlers can read the peer's IP for auth logging.
+                let listener = tokio::net::TcpListener::bind(addr).await?;
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                ).await?;
+            }
+            #[cfg(unix)]
+            DaemonEndpoint::Unix(path) => unix_socket::serve(app, &path).await?,
+            #[cfg(not(unix))]
+            DaemonEndpoint::Unix(path) => {
+                anyhow::bail!("Unix domain sockets are not supported: {}", path.display())
+            }
+        }
 
         Ok(())
     }
 
+    /// Shut down gracefully: refuse new sessions and conversations, wait up
+    /// to `deadline` for in-flight requests and agent turns, then flush the
+    /// training queue and persist sessions.
+    ///
+    /// Work still running at the deadline is abandoned (and logged).
+    pub async fn drain_and_flush(&self, deadline: Duration) {
+        self.drain.start();
+        self.session_manager.stop_accepting();
+
+        let in_flight = self.drain.in_flight();
+        if in_flight > 0 {
+            tracing::info!(
+                in_flight,
+                deadline_secs = deadline.as_secs(),
+                "Draining in-flight work before shutdown"
+            );
+        }
+        if self.drain.wait_idle(deadline).await {
+            tracing::info!("All in-flight work finished");
+        } else {
+            tracing::warn!(
+                abandoned = self.drain.in_flight(),
+                "Drain deadline reached; abandoning remaining work"
+            );
+        }
+
+        let worker = self.training_worker.lock().unwrap().take();
+        if let Some((shutdown_tx, handle)) = worker {
+            let _ = shutdown_tx.send(());
+            if tokio::time::timeout(Duration::from_secs(10), handle).await.is_err() {
+                tracing::warn!("Training worker did not stop in time; queue may be incomplete");
+            }
+        }
+
+        match self.session_manager.persist_all().await {
+            Ok(n) if n > 0 => tracing::info!(count = n, "Persisted sessions"),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to persist sessions: {}", e),
+        }
+        if let Err(e) = self.save_router().await {
+            tracing::warn!("Failed to save routing state: {}", e);
+        }
+        if let Err(e) = self.metrics_logger.upload().await {
+            tracing::warn!("Failed to upload metrics: {}", e);
+        }
+
+        if let Err(e) = self.workspaces.persist_usage() {
+            tracing::warn!("Failed to persist workspace usage: {}", e);
+        }
+    }
+
+    /// In-flight tracking and admission control used during shutdown
+    pub fn drain(&self) -> &Arc<DrainController> {
+        &self.drain
+    }
+
+    /// Response cache, if enabled
+    pub fn response_cache(&self) -> Option<&ResponseCache> {
+        self.response_cache.as_ref()
+    }
+
+    /// Event webhooks
+    pub fn webhooks(&self) -> &Arc<Webhooks> {
+        &self.webhooks
+    }
+
+    /// Circuit-breaker state for cloud providers
+    pub fn circuits(&self) -> &ProviderCircuits {
+        &self.circuits
+    }
+
+    /// Configured tenants, for resolving a request's workspace
+    pub fn workspaces(&self) -> &Arc<Workspaces> {
+        &self.workspaces
+    }
+
+    /// Memory served to API sessions
+    pub fn memory(&self) -> &DaemonMemory {
+        &self.memory
+    }
+
+    /// The LAN worker listener, when this daemon serves worker traffic
+    pub fn worker_role(&self) -> &WorkerRole {
+        &self.worker_role
+    }
+
+    /// Live feed of requests and daemon events
+    pub fn activity(&self) -> &Arc<ActivityFeed> {
+        &self.activity
+    }
+
+    /// Record the outcome of a cloud provider call, notifying webhooks when
+    /// repeated failures open the provider's circuit.
+    pub fn record_provider_outcome(&self, provider: &str, ok: bool) {
+        if ok {
+            self.circuits.record_success(provider);
+            return;
+        }
+        if let Some(failures) = self.circuits.record_failure(provider) {
+            tracing::warn!(
+                provider,
+                failures,
+                "Provider circuit opened; failing fast for {}s",
+                self.circuits.cooldown().as_secs()
+            );
+            self.webhooks.emit(WebhookEvent::ProviderCircuitOpened {
+                provider: provider.to_string(),
+                consecutive_failures: failures,
+                retry_after_secs: self.circuits.cooldown().as_secs(),
+            });
+        }
+    }
+
+    /// Circuit state of every cloud provider (the legacy Claude client when
+    /// no `[[providers]]` are configured)
+    pub fn provider_health(&self) -> Vec<ProviderHealth> {
+        if self.providers.is_empty() {
+            return vec![self.circuits.health("claude")];
+        }
+        self.providers
+            .iter()
+            .map(|p| self.circuits.health(p.name()))
+            .collect()
+    }
+
+    /// Time since the server was created
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
     /// Get reference to Claude client
     pub fn claude_client(&self) -> &Arc<ClaudeClient> {
         &self.claude_client
     }
 
+    /// Recent provider exchanges per session
+    pub fn replay_log(&self) -> &ReplayLog {
+        &self.replay_log
+    }
+
+    /// In-flight generations, for cancelling one by session id
+    pub fn generations(&self) -> &ActiveGenerations {
+        &self.generations
+    }
+
+    /// Send a request through the Claude client on behalf of a session,
+    /// capturing the exchange in the replay log.
+    pub async fn forward_message(
+        &self,
+        session_id: &str,
+        request: &crate::claude::MessageRequest,
+    ) -> Result<crate::claude::MessageResponse> {
+        let provider_request = self.claude_client.to_provider_request(request);
+        let provider = self.claude_client.provider();
+        let started = std::time::Instant::now();
+        let result = provider.send_message(&provider_request).await;
+        self.replay_log.record(
+            session_id,
+            provider.name(),
+            &provider_request,
+            &result,
+            started.elapsed(),
+        );
+        Ok(result?.into())
+    }
+
+    /// Re-send a captured exchange, optionally to another provider or model.
+    ///
+    /// The original model is kept when staying on the same provider; a
+    /// different provider uses its default model unless `model` is given.
+    /// Fails only when `provider` names no configured provider — a failed
+    /// call is reported in the returned exchange.
+    pub async fn replay(
+        &self,
+        exchange: &Exchange,
+        provider: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<Exchange> {
+        let target = match provider {
+            Some(name) => self
+                .providers
+                .iter()
+                .chain(std::iter::once(self.claude_client.provider()))
+                .find(|p| p.name().eq_ignore_ascii_case(name))
+                .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", name))?,
+            None => self
+                .providers
+                .iter()
+                .find(|p| p.name() == exchange.provider)
+                .unwrap_or_else(|| self.claude_client.provider()),
+        };
+
+        let mut request = exchange.request.clone();
+        request.model = match model {
+            Some(model) => model.to_string(),
+            None if target.name() == exchange.provider => request.model,
+            None => target.default_model().to_string(),
+        };
+
+        let started = std::time::Instant::now();
+        let result = target.send_message(&request).await;
+        self.replay_log
+            .capture(target.name(), &request, &result, started.elapsed())
+            .ok_or_else(|| anyhow::anyhow!("Failed to capture replayed exchange"))
+    }
+
     /// Resolve the cloud provider to use for a given request.
     ///
     /// If `name` matches a configured provider, returns that provider.
@@ -305,11 +834,47 @@ impl AgentServer {
         self.providers.first()
     }
 
+    /// Name of the cloud provider `name` resolves to ("claude" for the legacy
+    /// client when no `[[providers]]` are configured)
+    pub fn cloud_provider_name(&self, name: Option<&str>) -> String {
+        self.provider_for_name(name)
+            .map(|p| p.name().to_string())
+            .unwrap_or_else(|| "claude".to_string())
+    }
+
+    /// Provider a workspace's request should ask for: the one it names, or
+    /// else the workspace's default.  Refused when that resolves to a
+    /// provider outside the workspace's allowlist.
+    pub fn provider_for_workspace(
+        &self,
+        workspace: Option<&Workspace>,
+        name: Option<&str>,
+    ) -> std::result::Result<Option<String>, WorkspaceError> {
+        let Some(workspace) = workspace else {
+            return Ok(name.map(str::to_string));
+        };
+        let name = name.or(workspace.default_provider());
+        workspace.check_provider(&self.cloud_provider_name(name))?;
+        Ok(name.map(str::to_string))
+    }
+
     /// Get reference to router
     pub fn router(&self) -> &Arc<RwLock<Router>> {
         &self.router
     }
 
+    /// Save the routing policy's thresholds and statistics to
+    /// `~/.finch/models/threshold_router.json`, where the daemon loads them
+    /// from at startup
+    pub async fn save_router(&self) -> Result<()> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        let path = home
+            .join(".finch")
+            .join("models")
+            .join("threshold_router.json");
+        self.router.read().await.save(&path)
+    }
+
     /// Get reference to metrics logger
     pub fn metrics_logger(&self) -> &Arc<MetricsLogger> {
         &self.metrics_logger
@@ -357,6 +922,27 @@ impl AgentServer {
         &self.brain_registry
     }
 
+    /// Quiet hours for background work from `[schedule]`
+    pub fn schedule(&self) -> &Arc<crate::scheduling::ScheduleConfig> {
+        &self.schedule
+    }
+
+    /// Teacher answers kept for self-evaluation (`None` when `[self_eval]`
+    /// is off)
+    pub fn teacher_samples(&self) -> Option<&Arc<TeacherSamples>> {
+        self.teacher_samples.as_ref()
+    }
+
+    /// Get reference to the primary local generator's request queue
+    pub fn request_queue(&self) -> &Arc<RequestQueue> {
+        &self.local_models.primary().request_queue
+    }
+
+    /// Get all local models served by this daemon
+    pub fn local_models(&self) -> &ModelPool {
+        &self.local_models
+    }
+
     /// Return the primary cloud provider (first in the configured list, if any).
     ///
     /// Used by the IPC server to service CLI queries without going through the
@@ -466,6 +1052,93 @@ mod tests {
     }
 }
 
+/// Watch a local model's loading state and inject the model into its
+/// `LocalGenerator` once it becomes ready.
+fn spawn_model_monitor(slot: Arc<LocalModelSlot>) {
+    tokio::spawn(async move {
+        tracing::info!(model = %slot.id, "Model monitor task started");
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+            let state = slot.generator_state.read().await;
+            tracing::debug!(
+                "Monitor checking state: {:?}",
+                std::mem::discriminant(&*state)
+            );
+
+            if let GeneratorState::Ready { model, model_name } = &*state {
+                let model_clone = Arc::clone(model);
+                let name = model_name.clone();
+                drop(state); // Release read lock before acquiring write lock
+
+                tracing::info!("Model is ready: {}, injecting into LocalGenerator", name);
+
+                // Try to inject with timeout
+                match tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+                    tracing::info!("Acquiring write lock on LocalGenerator...");
+                    let mut gen = slot.local_generator.write().await;
+                    tracing::info!("Write lock acquired, creating new LocalGenerator...");
+                    *gen = LocalGenerator::with_models(Some(Arc::clone(&model_clone)));
+                    tracing::info!("LocalGenerator updated");
+                })
+                .await
+                {
+                    Ok(_) => {
+                        tracing::info!(model = %slot.id, "✓ Model injected - local generation enabled");
+                        load_extra_lanes(&slot, &model_clone).await;
+                        break; // Stop monitoring once injected
+                    }
+                    Err(_) => {
+                        tracing::error!(
+                            "❌ Timeout while injecting model (5s) - write lock may be held"
+                        );
+                    }
+                }
+            } else if matches!(
+                *state,
+                GeneratorState::Failed { .. } | GeneratorState::NotAvailable
+            ) {
+                tracing::warn!(model = %slot.id, "Model loading failed or not available, stopping monitor");
+                break; // Stop monitoring on failure
+            }
+        }
+        tracing::info!(model = %slot.id, "Model monitor task exiting");
+    });
+}
+
+/// Load a copy of `model` for each extra lane of `slot`, opening the lane in
+/// the request queue as soon as its copy is ready.  A reload closes the lanes
+/// before it starts, so they always reopen here one by one.
+async fn load_extra_lanes(slot: &LocalModelSlot, model: &Arc<RwLock<GeneratorModel>>) {
+    if slot.extra_lanes.is_empty() {
+        return;
+    }
+    let config = model.read().await.config().clone();
+    for generator in &slot.extra_lanes {
+        let config = config.clone();
+        let copy = tokio::task::spawn_blocking(move || GeneratorModel::new(config))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        match copy {
+            Ok(copy) => {
+                *generator.write().await =
+                    LocalGenerator::with_models(Some(Arc::new(RwLock::new(copy))));
+                let lane = slot.request_queue.add_lane();
+                tracing::info!(model = %slot.id, lane, "✓ Parallel session ready");
+            }
+            Err(e) => {
+                tracing::warn!(
+                    model = %slot.id,
+                    error = %e,
+                    "Failed to load a parallel session copy; serving with fewer lanes"
+                );
+                return;
+            }
+        }
+    }
+}
+
 /// Return the OS hostname, or "finch-node" if it can't be determined.
 fn hostname_or_default() -> String {
     std::process::Command::new("hostname")
@@ -0,0 +1,167 @@
+// Local model pool — several local models served side by side
+//
+// Each `[[providers]] type = "local"` entry gets its own slot: a
+// BootstrapLoader, generator state, LocalGenerator and request queue.  OpenAI
+// requests pick a slot by their `model` field; unknown ids (including the
+// legacy "qwen-local") go to the primary slot.
+//
+// With `--parallel-sessions N` a slot also holds N-1 extra LocalGenerators.
+// Each gets its own copy of the model (an ONNX session runs one generation at
+// a time), and the request queue hands out one lane per copy.
+
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::RequestQueue;
+use crate::generators::Attribution;
+use crate::local::LocalGenerator;
+use crate::models::{BootstrapLoader, GeneratorState};
+
+/// Model id advertised for the primary slot when no local provider is configured.
+pub const DEFAULT_LOCAL_MODEL_ID: &str = "qwen-local";
+
+/// One independently loaded local model.
+pub struct LocalModelSlot {
+    /// Model id clients put in the `model` request field
+    pub id: String,
+    /// Generator the model is injected into once loaded (lane 0)
+    pub local_generator: Arc<RwLock<LocalGenerator>>,
+    /// Generators for lanes 1.., each given its own copy of the model
+    pub extra_lanes: Vec<Arc<RwLock<LocalGenerator>>>,
+    /// Loading progress for this model
+    pub generator_state: Arc<RwLock<GeneratorState>>,
+    /// Loader driving `generator_state`
+    pub bootstrap_loader: Arc<BootstrapLoader>,
+    /// Assigns requests to this model's lanes (each slot generates independently)
+    pub request_queue: Arc<RequestQueue>,
+}
+
+impl LocalModelSlot {
+    /// Create a slot with fresh (not yet loaded) state.
+    pub fn new(id: impl Into<String>, max_queue_depth: usize) -> Self {
+        let generator_state = Arc::new(RwLock::new(GeneratorState::Initializing));
+        let bootstrap_loader = Arc::new(BootstrapLoader::new(Arc::clone(&generator_state), None));
+        Self {
+            id: id.into(),
+            local_generator: Arc::new(RwLock::new(LocalGenerator::new())),
+            extra_lanes: Vec::new(),
+            generator_state,
+            bootstrap_loader,
+            request_queue: Arc::new(RequestQueue::new(max_queue_depth)),
+        }
+    }
+
+    /// Serve up to `sessions` generations at once.  The extra lanes open as
+    /// their model copies finish loading.
+    pub fn with_parallel_sessions(mut self, sessions: usize) -> Self {
+        self.extra_lanes = (1..sessions.max(1))
+            .map(|_| Arc::new(RwLock::new(LocalGenerator::new())))
+            .collect();
+        self
+    }
+
+    /// Attribution for messages this model generates.  Reads the loading
+    /// state rather than a generator, which may be busy generating.
+    pub async fn attribution(&self) -> Attribution {
+        match &*self.generator_state.read().await {
+            GeneratorState::Ready { model_name, .. } => Attribution::local(model_name.clone()),
+            _ => Attribution::local(self.id.clone()),
+        }
+    }
+
+    /// Generator for the lane a `QueuePermit` was granted.
+    pub fn generator(&self, lane: usize) -> &Arc<RwLock<LocalGenerator>> {
+        match lane {
+            0 => &self.local_generator,
+            n => &self.extra_lanes[n - 1],
+        }
+    }
+}
+
+/// All local models served by this daemon.  The first slot is the primary.
+pub struct ModelPool {
+    slots: Vec<Arc<LocalModelSlot>>,
+}
+
+impl ModelPool {
+    /// Create a pool containing only the primary model.
+    pub fn new(primary: LocalModelSlot) -> Self {
+        Self {
+            slots: vec![Arc::new(primary)],
+        }
+    }
+
+    /// Add another model.  Ids must be unique (case-insensitive); a duplicate is
+    /// rejected so requests can't silently land on the wrong model.
+    pub fn add(&mut self, slot: LocalModelSlot) -> anyhow::Result<()> {
+        if self.find(&slot.id).is_some() {
+            anyhow::bail!("Duplicate local model id '{}'", slot.id);
+        }
+        self.slots.push(Arc::new(slot));
+        Ok(())
+    }
+
+    /// The primary (default) model.
+    pub fn primary(&self) -> &Arc<LocalModelSlot> {
+        &self.slots[0]
+    }
+
+    /// Exact (case-insensitive) id lookup.
+    pub fn find(&self, model: &str) -> Option<&Arc<LocalModelSlot>> {
+        self.slots.iter().find(|s| s.id.eq_ignore_ascii_case(model))
+    }
+
+    /// Slot for a request's `model` field, falling back to the primary.
+    pub fn resolve(&self, model: &str) -> &Arc<LocalModelSlot> {
+        self.find(model).unwrap_or_else(|| self.primary())
+    }
+
+    /// All slots, primary first.
+    pub fn slots(&self) -> &[Arc<LocalModelSlot>] {
+        &self.slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(ids: &[&str]) -> ModelPool {
+        let mut pool = ModelPool::new(LocalModelSlot::new(ids[0], 4));
+        for id in &ids[1..] {
+            pool.add(LocalModelSlot::new(*id, 4)).unwrap();
+        }
+        pool
+    }
+
+    #[test]
+    fn test_resolve_exact_and_case_insensitive() {
+        let pool = pool(&["qwen2.5-1.5b", "qwen2.5-7b"]);
+        assert_eq!(pool.resolve("qwen2.5-7b").id, "qwen2.5-7b");
+        assert_eq!(pool.resolve("QWEN2.5-7B").id, "qwen2.5-7b");
+    }
+
+    #[test]
+    fn test_resolve_unknown_falls_back_to_primary() {
+        let pool = pool(&["qwen2.5-1.5b", "qwen2.5-7b"]);
+        assert_eq!(pool.resolve("gpt-4").id, "qwen2.5-1.5b");
+        assert_eq!(pool.resolve(DEFAULT_LOCAL_MODEL_ID).id, "qwen2.5-1.5b");
+    }
+
+    #[test]
+    fn test_parallel_sessions_add_lane_generators() {
+        let slot = LocalModelSlot::new("qwen2.5-1.5b", 4).with_parallel_sessions(3);
+        assert_eq!(slot.extra_lanes.len(), 2);
+        assert!(Arc::ptr_eq(slot.generator(0), &slot.local_generator));
+        assert!(Arc::ptr_eq(slot.generator(2), &slot.extra_lanes[1]));
+        // Lanes only open as their copies load
+        assert_eq!(slot.request_queue.lanes(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_id_rejected() {
+        let mut pool = pool(&["qwen2.5-7b"]);
+        assert!(pool.add(LocalModelSlot::new("Qwen2.5-7B", 4)).is_err());
+        assert_eq!(pool.slots().len(), 1);
+    }
+}
@@ -0,0 +1,211 @@
+// Model reload — swap a local model without restarting the daemon
+//
+// `POST /v1/models/reload` unloads a local model and runs its BootstrapLoader
+// again, optionally for another size or repository.  While the model loads,
+// requests for it are forwarded to the cloud providers, as they are while the
+// daemon starts up.  The response streams the load's progress as server-sent
+// events until the model is ready or the load fails.  `/model reload` in the
+// REPL shows that progress in the status bar.
+//
+// The old model is dropped before the new one loads, so the two never have to
+// fit in memory together.  If the load fails, the model stays unloaded until
+// the next reload.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use super::LocalModelSlot;
+use crate::local::LocalGenerator;
+use crate::models::{GeneratorState, LoadProgressSnapshot, ModelLoadConfig, ModelSize};
+
+/// Route of the reload endpoint
+pub const RELOAD_ROUTE: &str = "/v1/models/reload";
+
+/// Body of `POST /v1/models/reload`.  Fields left out keep the model's
+/// current setting.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelReloadRequest {
+    /// Model id (see `GET /v1/models`); the primary model when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// A new size also goes back to the family's default repository,
+    /// unless `model_repo` is given too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_size: Option<ModelSize>,
+    /// HuggingFace repository to load from; "" for the family's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_repo: Option<String>,
+}
+
+impl ModelReloadRequest {
+    /// `current` with this request's changes
+    pub fn apply(&self, mut current: ModelLoadConfig) -> ModelLoadConfig {
+        if let Some(size) = self.model_size {
+            current.size = size;
+            current.repo_override = None;
+        }
+        if let Some(repo) = &self.model_repo {
+            current.repo_override = Some(repo.clone()).filter(|r| !r.is_empty());
+        }
+        current
+    }
+}
+
+/// One event of the `POST /v1/models/reload` stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReloadEvent {
+    /// Where the load is, sent twice a second
+    Progress {
+        /// "initializing", "downloading" or "loading"
+        stage: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model_name: Option<String>,
+        #[serde(flatten)]
+        progress: LoadProgressSnapshot,
+    },
+    /// The model serves requests again
+    Ready { model_name: String },
+    /// The load failed; the model stays unloaded
+    Failed { error: String },
+}
+
+/// Unload `slot`'s model and start loading it with `request`'s changes.
+///
+/// The returned task ends with the new model's name once it serves requests
+/// (parallel sessions follow as their copies load).  Fails straight away if
+/// the model is still loading or was never loaded at all.
+pub async fn reload(
+    slot: Arc<LocalModelSlot>,
+    request: &ModelReloadRequest,
+) -> Result<JoinHandle<Result<String>>> {
+    let Some(current) = slot.bootstrap_loader.load_config() else {
+        bail!(
+            "Model '{}' was never loaded (is the local backend disabled?)",
+            slot.id
+        );
+    };
+    let config = request.apply(current);
+    {
+        let mut state = slot.generator_state.write().await;
+        if matches!(
+            *state,
+            GeneratorState::Initializing
+                | GeneratorState::Downloading { .. }
+                | GeneratorState::Loading { .. }
+        ) {
+            bail!("Model '{}' is still loading", slot.id);
+        }
+        // From here on, requests for the model go to the cloud providers
+        *state = GeneratorState::Initializing;
+    }
+
+    // Drop the old model before loading the new one.  The extra lanes close
+    // first, so no request is handed a lane whose copy is gone; each one
+    // reopens once its new copy has loaded.
+    slot.request_queue.close_extra_lanes();
+    *slot.local_generator.write().await = LocalGenerator::new();
+    for lane in &slot.extra_lanes {
+        *lane.write().await = LocalGenerator::new();
+    }
+    tracing::info!(
+        model = %slot.id,
+        size = ?config.size,
+        repo = ?config.repo_override,
+        "Reloading local model"
+    );
+
+    Ok(tokio::spawn(async move {
+        let loader = &slot.bootstrap_loader;
+        if let Err(e) = loader
+            .load_generator_async(
+                config.provider,
+                config.family,
+                config.size,
+                config.target,
+                config.repo_override,
+            )
+            .await
+        {
+            let error = format!("{:#}", e);
+            loader.handle_error(e).await;
+            bail!(error);
+        }
+
+        let (model, model_name) = match &*slot.generator_state.read().await {
+            GeneratorState::Ready { model, model_name } => (Arc::clone(model), model_name.clone()),
+            _ => bail!("Model '{}' was unloaded while loading", slot.id),
+        };
+        *slot.local_generator.write().await = LocalGenerator::with_models(Some(Arc::clone(&model)));
+        tracing::info!(model = %slot.id, "✓ Reloaded as {}", model_name);
+        tokio::spawn(async move { super::load_extra_lanes(&slot, &model).await });
+        Ok(model_name)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExecutionTarget;
+    use crate::models::unified_loader::InferenceProvider;
+    use crate::models::ModelFamily;
+
+    fn current() -> ModelLoadConfig {
+        ModelLoadConfig {
+            provider: InferenceProvider::default(),
+            family: ModelFamily::Qwen2,
+            size: ModelSize::Medium,
+            target: ExecutionTarget::Cpu,
+            repo_override: Some("me/qwen-tuned".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_request_changes_only_what_it_names() {
+        let same = ModelReloadRequest::default().apply(current());
+        assert_eq!(same.size, ModelSize::Medium);
+        assert_eq!(same.repo_override.as_deref(), Some("me/qwen-tuned"));
+
+        let request: ModelReloadRequest =
+            serde_json::from_str(r#"{"model_size": "Large"}"#).unwrap();
+        let larger = request.apply(current());
+        assert_eq!(larger.size, ModelSize::Large);
+        assert_eq!(larger.repo_override, None, "back to the default repository");
+
+        let request = ModelReloadRequest {
+            model_repo: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(request.apply(current()).repo_override, None);
+    }
+
+    #[test]
+    fn test_reload_event_wire_format() {
+        let event = ReloadEvent::Progress {
+            stage: "downloading".to_string(),
+            model_name: Some("Qwen 2.5 7B".to_string()),
+            progress: LoadProgressSnapshot {
+                files_done: 2,
+                files_remaining: 3,
+                download_percent: Some(40.0),
+                current_file: Some("onnx/model.onnx".to_string()),
+                ..Default::default()
+            },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "progress");
+        assert_eq!(json["files_done"], 2);
+        assert_eq!(serde_json::from_value::<ReloadEvent>(json).unwrap(), event);
+
+        let json = serde_json::to_value(ReloadEvent::Failed {
+            error: "no such repo".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "failed", "error": "no such repo"})
+        );
+    }
+}
@@ -10,8 +10,9 @@ use axum::{
         sse::{Event, Sse},
         IntoResponse, Json, Response,
     },
+    Extension,
 };
-use futures::stream::{self};
+use futures::stream::{self, StreamExt};
 use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::Instant;
@@ -19,8 +20,14 @@ use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
 use super::openai_types::*;
-use super::AgentServer;
+use super::speculation::LocalDraft;
+use super::{
+    AccessDetails, ActiveGeneration, AgentServer, CacheKey, RequestPriority, SessionUsage,
+    TeacherSample,
+};
 use crate::claude::{ContentBlock, Message};
+use crate::generators::Attribution;
+use crate::providers::RateLimited;
 use crate::router::RouteDecision;
 use crate::tools::types::ToolDefinition as InternalToolDefinition;
 use crate::tools::types::ToolInputSchema;
@@ -194,6 +201,8 @@ async fn buffer_and_clean_tokens(
 async fn handle_chat_completions_streaming(
     server: Arc<AgentServer>,
     request: ChatCompletionRequest,
+    priority: RequestPriority,
+    generation: Option<ActiveGeneration>,
 ) -> Result<Response, Response> {
     // Validate request
     if request.messages.is_empty() {
@@ -215,9 +224,12 @@ async fn handle_chat_completions_streaming(
     let internal_messages = convert_messages_to_internal(&request.messages)
         .map_err(|e| error_response(&e.to_string(), "invalid_request_error"))?;
 
+    // Pick the local model named by the request (primary if unknown)
+    let slot = Arc::clone(server.local_models().resolve(&request.model));
+
     // Check generator state
     use crate::models::GeneratorState;
-    let state = server.generator_state().read().await;
+    let state = slot.generator_state.read().await;
 
     match &*state {
         GeneratorState::Ready { .. } => {
@@ -232,6 +244,13 @@ async fn handle_chat_completions_streaming(
     }
     drop(state);
 
+    // Wait our turn for the generator; the permit travels with the generation task
+    let permit = slot
+        .request_queue
+        .acquire(priority)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
     // Create bounded channel for streaming tokens with backpressure
     // Buffer size of 2 allows one token to be consumed while another is being generated
     let (tx, rx) = mpsc::channel::<String>(2);
@@ -240,10 +259,11 @@ async fn handle_chat_completions_streaming(
     let (cleaned_tx, cleaned_rx) = mpsc::channel::<String>(2);
 
     let model_name = request.model.clone();
+    let attribution = slot.attribution().await;
 
     // Get model adapter for cleaning
     let model_adapter = {
-        let gen = server.local_generator().read().await;
+        let gen = slot.local_generator.read().await;
         Some(gen.get_adapter())
     };
 
@@ -256,8 +276,10 @@ async fn handle_chat_completions_streaming(
     // ONNX generation is CPU-bound and synchronous, so we use spawn_blocking
     // to avoid blocking the async runtime. The bounded channel provides natural
     // backpressure - generation will pause if the HTTP stream can't keep up.
-    let server_clone = server.clone();
+    let slot_clone = Arc::clone(&slot);
+    let lane = permit.lane();
     tokio::spawn(async move {
+        let _permit = permit; // Released when generation finishes
         // Run CPU-bound generation on blocking thread pool
         let result = tokio::task::spawn_blocking(move || {
             // Create runtime handle for async operations inside blocking context
@@ -265,7 +287,7 @@ async fn handle_chat_completions_streaming(
 
             // Get generator (need to use block_on since we're in blocking context)
             let mut generator =
-                handle.block_on(async { server_clone.local_generator().write().await });
+                handle.block_on(async { slot_clone.generator(lane).write().await });
 
             // Accumulate response for logging
             let accumulated_response = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
@@ -326,10 +348,10 @@ async fn handle_chat_completions_streaming(
     });
 
     // Create SSE stream from cleaned token receiver
-    // State: (receiver, model_name, done_flag)
+    // State: (receiver, model_name, attribution, done_flag)
     let stream = stream::unfold(
-        (cleaned_rx, model_name, false),
-        |(mut rx, model_name, done)| async move {
+        (cleaned_rx, model_name, attribution, false),
+        |(mut rx, model_name, attribution, done)| async move {
             if done {
                 // Already sent final chunk, terminate stream
                 return None;
@@ -354,11 +376,11 @@ async fn handle_chat_completions_streaming(
 
                     Some((
                         Ok::<_, Infallible>(Event::default().json_data(chunk).unwrap()),
-                        (rx, model_name, false), // Continue streaming
+                        (rx, model_name, attribution, false), // Continue streaming
                     ))
                 }
                 None => {
-                    // Send final chunk with finish_reason
+                    // Send final chunk with finish_reason and attribution
                     let chunk = serde_json::json!({
                         "id": format!("chatcmpl-{}", uuid::Uuid::new_v4()),
                         "object": "chat.completion.chunk",
@@ -368,38 +390,82 @@ async fn handle_chat_completions_streaming(
                             "index": 0,
                             "delta": {},
                             "finish_reason": "stop"
-                        }]
+                        }],
+                        "metadata": &attribution
                     });
 
                     Some((
                         Ok::<_, Infallible>(Event::default().json_data(chunk).unwrap()),
-                        (rx, model_name, true), // Mark done, will terminate on next call
+                        (rx, model_name, attribution, true), // Mark done, will terminate on next call
                     ))
                 }
             }
         },
     );
 
-    Ok(Sse::new(stream).into_response())
+    // Cancelling the session's generation ends the stream early; dropping the
+    // receiver makes the generation task stop sending tokens
+    let cancelled = async move {
+        match &generation {
+            Some(generation) => generation.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+
+    Ok(Sse::new(stream.take_until(cancelled)).into_response())
 }
 
 /// Resolve the cloud provider to use, preferring the multi-provider pool.
 ///
 /// Falls back to `claude_client` (wrapped in a throw-away `ClaudeClient`) when
 /// `providers` is empty. Returns `None` only when the fallback also fails.
+/// On success yields the content plus the provider and model that produced it.
 async fn forward_to_cloud(
     server: &AgentServer,
     provider_name: Option<&str>,
     messages: Vec<crate::claude::Message>,
     tools: Option<Vec<InternalToolDefinition>>,
-) -> anyhow::Result<Vec<crate::claude::ContentBlock>> {
+) -> anyhow::Result<(Vec<crate::claude::ContentBlock>, String, String)> {
+    let circuit = server.cloud_provider_name(provider_name);
+    if let Err(remaining) = server.circuits().check(&circuit) {
+        let reason = match server.circuits().health(&circuit).status {
+            "rate_limited" => "rate limited",
+            _ => "failing",
+        };
+        anyhow::bail!(
+            "Provider {} is {}; not retrying for another {}s",
+            circuit,
+            reason,
+            remaining.as_secs().max(1)
+        );
+    }
+    let result = call_cloud(server, provider_name, messages, tools).await;
+    let limited = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<RateLimited>());
+    match limited {
+        Some(limited) => server
+            .circuits()
+            .record_rate_limit(&circuit, limited.retry_after),
+        None => server.record_provider_outcome(&circuit, result.is_ok()),
+    }
+    result
+}
+
+async fn call_cloud(
+    server: &AgentServer,
+    provider_name: Option<&str>,
+    messages: Vec<crate::claude::Message>,
+    tools: Option<Vec<InternalToolDefinition>>,
+) -> anyhow::Result<(Vec<crate::claude::ContentBlock>, String, String)> {
     if let Some(provider) = server.provider_for_name(provider_name) {
         let mut req = crate::providers::ProviderRequest::new(messages);
         if let Some(tools) = tools {
             req = req.with_tools(tools);
         }
         let resp = provider.send_message(&req).await?;
-        Ok(resp.content)
+        Ok((resp.content, resp.provider, resp.model))
     } else {
         // No providers configured — fall back to legacy ClaudeClient
         let mut claude_request = crate::claude::MessageRequest::with_context(messages);
@@ -407,7 +473,7 @@ async fn forward_to_cloud(
             claude_request = claude_request.with_tools(tools);
         }
         let resp = server.claude_client().send_message(&claude_request).await?;
-        Ok(resp.content)
+        Ok((resp.content, "claude".to_string(), resp.model))
     }
 }
 
@@ -416,6 +482,35 @@ pub async fn handle_chat_completions(
     State(server): State<Arc<AgentServer>>,
     headers: axum::http::HeaderMap,
     Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    // Requests naming a session can be stopped while they run with
+    // DELETE /v1/sessions/:id/active-generation
+    let generation = request.session_id.as_deref().map(|id| {
+        let workspace = server.workspaces().resolve(&headers).ok().flatten();
+        server
+            .generations()
+            .begin(id, workspace.as_ref().map(|w| w.name()))
+    });
+    match generation {
+        // A stream outlives this handler, so it watches for cancellation itself
+        Some(generation) if !request.stream => {
+            match generation
+                .run(chat_completions(server, headers, request, None))
+                .await
+            {
+                Ok(response) => response,
+                Err(cancelled) => cancelled.into_response(),
+            }
+        }
+        generation => chat_completions(server, headers, request, generation).await,
+    }
+}
+
+async fn chat_completions(
+    server: Arc<AgentServer>,
+    headers: axum::http::HeaderMap,
+    mut request: ChatCompletionRequest,
+    generation: Option<ActiveGeneration>,
 ) -> Response {
     let start_time = Instant::now();
 
@@ -424,24 +519,101 @@ pub async fn handle_chat_completions(
         .get("x-finch-provider")
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
+    let priority = RequestPriority::from_headers(&headers);
 
     // Validate request
     if request.messages.is_empty() {
         return error_response("messages array cannot be empty", "invalid_request_error");
     }
 
+    // While shutting down, only requests that carry tool results (finishing
+    // a tool loop already under way) are served
+    let continues_turn = request.messages.last().is_some_and(|m| m.role == "tool");
+    if let Err(draining) = server.drain().admit(continues_turn) {
+        return draining.into_response();
+    }
+
+    // Tenants must be within quota; the provider defaults to (and must be
+    // on) the workspace's allowlist
+    let workspace = match server.workspaces().resolve(&headers) {
+        Ok(workspace) => workspace,
+        Err(refused) => return refused.into_response(),
+    };
+    let provider_name = match workspace.as_deref().map(|w| {
+        w.check_quota()?;
+        server.provider_for_workspace(Some(w), provider_name.as_deref())
+    }) {
+        Some(Ok(name)) => name,
+        Some(Err(refused)) => return refused.into_response(),
+        None => provider_name,
+    };
+
+    // Daemon memory: recall into the system prompt now, remember the
+    // exchange once it is answered (see memory.rs)
+    let memory = if server.memory().is_enabled() && request.memory.unwrap_or(true) {
+        match server.memory().for_request(workspace.as_deref()).await {
+            Ok(memory) => Some(memory),
+            Err(e) => {
+                warn!("Daemon memory unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let Some(memory) = &memory {
+        let query = request
+            .messages
+            .iter()
+            .rfind(|m| m.role == "user")
+            .and_then(|m| m.content.clone());
+        if let Some(query) = query {
+            match super::memory::recall(memory, &query).await {
+                Ok(Some(block)) => super::memory::inject(&mut request.messages, &block),
+                Ok(None) => {}
+                Err(e) => warn!("Daemon memory recall failed: {}", e),
+            }
+        }
+    }
+
     // Handle streaming requests
     if request.stream {
-        match handle_chat_completions_streaming(server, request).await {
-            Ok(response) => return response,
+        match handle_chat_completions_streaming(server, request, priority, generation).await {
+            Ok(mut response) => {
+                // Streaming is local-only; token counts aren't known up front
+                response.extensions_mut().insert(AccessDetails {
+                    provider: Some("local".to_string()),
+                    decision: Some("local".to_string()),
+                    ..Default::default()
+                });
+                return response;
+            }
             Err(error_resp) => return error_resp,
         }
     }
 
     // Check if local-only mode requested
     if request.local_only.unwrap_or(false) {
-        match handle_local_only_query(server, request).await {
-            Ok(json_resp) => return json_resp.into_response(),
+        match handle_local_only_query(Arc::clone(&server), request, priority).await {
+            Ok(json_resp) => {
+                let usage = SessionUsage::call(
+                    "local",
+                    json_resp.usage.prompt_tokens,
+                    json_resp.usage.completion_tokens,
+                );
+                server.session_manager().record_usage(None, &usage);
+                if let Some(workspace) = &workspace {
+                    workspace.record_usage(&usage);
+                }
+                let access = AccessDetails {
+                    provider: Some("local".to_string()),
+                    decision: Some("local".to_string()),
+                    input_tokens: Some(json_resp.usage.prompt_tokens),
+                    output_tokens: Some(json_resp.usage.completion_tokens),
+                    ..Default::default()
+                };
+                return (Extension(access), json_resp).into_response();
+            }
             Err(error_resp) => return error_resp,
         }
     }
@@ -466,13 +638,62 @@ pub async fn handle_chat_completions(
         .and_then(|m| m.content.as_deref())
         .unwrap_or("");
 
+    // An incognito client session leaves nothing behind: no cache entry and
+    // no training example
+    let incognito = request.incognito.unwrap_or(false);
+
+    // Repeated requests are answered from the response cache unless the
+    // client opted out
+    let cache_key = server
+        .response_cache()
+        .filter(|_| request.cache.unwrap_or(true) && !incognito)
+        .and_then(|_| response_cache_key(&request, workspace.as_ref().map(|w| w.name())));
+    let cache = server.response_cache().filter(|_| cache_key.is_some());
+    let cached = cache
+        .zip(cache_key.as_ref())
+        .and_then(|(c, key)| c.get(key));
+    if let Some((content_blocks, hit)) = cached {
+        info!(?hit, "Answered from response cache");
+        let openai_response =
+            match convert_response_to_openai(content_blocks, &request.model, &request.messages) {
+                Ok(resp) => resp,
+                Err(error_resp) => return error_resp,
+            };
+        let access = AccessDetails {
+            provider: Some("cache".to_string()),
+            decision: Some("cache".to_string()),
+            input_tokens: Some(openai_response.usage.prompt_tokens),
+            output_tokens: Some(openai_response.usage.completion_tokens),
+            ..Default::default()
+        };
+        return (Extension(access), Json(openai_response)).into_response();
+    }
+
+    // Start drafting locally while the routing decision runs; the draft is
+    // kept only if the router picks the local model
+    let slot = Arc::clone(server.local_models().resolve(&request.model));
+    let draft = if server.config().speculative_local {
+        LocalDraft::start(
+            &slot,
+            priority,
+            internal_messages.clone(),
+            internal_tools.clone(),
+        )
+        .await
+    } else {
+        None
+    };
+
     // Route decision
     let router = server.router().read().await;
     let decision = router.route(user_query);
     drop(router);
 
-    let (content_blocks, routing_decision) = match decision {
+    let (content_blocks, routing_decision, provider, model) = match decision {
         RouteDecision::Forward { reason } => {
+            if let Some(draft) = draft {
+                draft.discard();
+            }
             info!(
                 "☁️  ROUTING TO TEACHER API (reason: {:?}, provider: {:?})",
                 reason, provider_name
@@ -486,65 +707,76 @@ pub async fn handle_chat_completions(
             )
             .await
             {
-                Ok(blocks) => (blocks, "forward"),
+                Ok((blocks, provider, model)) => (blocks, "forward", provider, model),
                 Err(e) => return error_response(&e.to_string(), "api_error"),
             }
         }
         RouteDecision::Local { .. } => {
             info!("🤖 ROUTING TO LOCAL MODEL");
 
-            // Check if model is ready
-            use crate::models::GeneratorState;
-            let state = server.generator_state().read().await;
-
-            match &*state {
-                GeneratorState::Ready { .. } => {
-                    drop(state);
+            // Pick up the speculative draft, or generate now if the requested
+            // local model is ready (`None` = not loaded yet)
+            let local = match draft {
+                Some(draft) => {
+                    debug!("Using speculative local draft");
+                    Some(draft.finish().await)
+                }
+                None if slot.generator_state.read().await.is_ready() => {
+                    // Wait our turn for the generator (503 if the queue is saturated)
+                    let permit = match slot.request_queue.acquire(priority).await {
+                        Ok(permit) => permit,
+                        Err(full) => return full.into_response(),
+                    };
 
                     // Try local generation with tools
-                    let mut generator = server.local_generator().write().await;
-                    match generator.try_generate_from_pattern_with_tools(
+                    let mut generator = slot.generator(permit.lane()).write().await;
+                    Some(generator.try_generate_from_pattern_with_tools(
                         &internal_messages,
                         internal_tools.clone(),
-                    ) {
-                        Ok(Some(response)) => {
-                            info!("✓ LOCAL MODEL RESPONDED");
-                            (response.content_blocks, "local")
-                        }
-                        Ok(None) => {
-                            drop(generator);
-                            warn!("❌ Local generation returned None, falling back to teacher");
-                            match forward_to_cloud(
-                                &server,
-                                provider_name.as_deref(),
-                                internal_messages.clone(),
-                                internal_tools.clone(),
-                            )
-                            .await
-                            {
-                                Ok(blocks) => (blocks, "fallback"),
-                                Err(e) => return error_response(&e.to_string(), "api_error"),
-                            }
-                        }
-                        Err(e) => {
-                            drop(generator);
-                            warn!("❌ Local generation error: {}, falling back to teacher", e);
-                            match forward_to_cloud(
-                                &server,
-                                provider_name.as_deref(),
-                                internal_messages.clone(),
-                                internal_tools,
-                            )
-                            .await
-                            {
-                                Ok(blocks) => (blocks, "fallback"),
-                                Err(e2) => return error_response(&e2.to_string(), "api_error"),
-                            }
-                        }
+                    ))
+                }
+                None => None,
+            };
+
+            match local {
+                Some(Ok(Some(response))) => {
+                    info!("✓ LOCAL MODEL RESPONDED");
+                    (
+                        response.content_blocks,
+                        "local",
+                        "local".to_string(),
+                        "local".to_string(),
+                    )
+                }
+                Some(Ok(None)) => {
+                    warn!("❌ Local generation returned None, falling back to teacher");
+                    match forward_to_cloud(
+                        &server,
+                        provider_name.as_deref(),
+                        internal_messages.clone(),
+                        internal_tools.clone(),
+                    )
+                    .await
+                    {
+                        Ok((blocks, provider, model)) => (blocks, "fallback", provider, model),
+                        Err(e) => return error_response(&e.to_string(), "api_error"),
                     }
                 }
-                _ => {
-                    drop(state);
+                Some(Err(e)) => {
+                    warn!("❌ Local generation error: {}, falling back to teacher", e);
+                    match forward_to_cloud(
+                        &server,
+                        provider_name.as_deref(),
+                        internal_messages.clone(),
+                        internal_tools,
+                    )
+                    .await
+                    {
+                        Ok((blocks, provider, model)) => (blocks, "fallback", provider, model),
+                        Err(e2) => return error_response(&e2.to_string(), "api_error"),
+                    }
+                }
+                None => {
                     info!("Model not ready, forwarding to cloud provider");
                     match forward_to_cloud(
                         &server,
@@ -554,7 +786,7 @@ pub async fn handle_chat_completions(
                     )
                     .await
                     {
-                        Ok(blocks) => (blocks, "forward"),
+                        Ok((blocks, provider, model)) => (blocks, "forward", provider, model),
                         Err(e) => return error_response(&e.to_string(), "api_error"),
                     }
                 }
@@ -569,10 +801,41 @@ pub async fn handle_chat_completions(
         "Chat completion handled"
     );
 
+    if let (Some(cache), Some(key)) = (cache, &cache_key) {
+        cache.insert(key, content_blocks.clone());
+    }
+
     // Automatically collect query/response for training (if not a tool call)
-    if !has_tool_calls(&content_blocks) {
+    if !incognito && !has_tool_calls(&content_blocks) {
         let response_text = extract_text_from_blocks(&content_blocks);
         if !user_query.is_empty() && !response_text.is_empty() {
+            if let Some(memory) = memory {
+                super::memory::remember_exchange(
+                    memory,
+                    user_query.to_string(),
+                    response_text.clone(),
+                    model.clone(),
+                    request.session_id.clone(),
+                );
+            }
+
+            // A teacher's answer is a reference for replaying the question
+            // through the local model later
+            let samples = server
+                .teacher_samples()
+                .filter(|_| routing_decision != "local");
+            if let Some(samples) = samples {
+                let sample = TeacherSample {
+                    query: user_query.to_string(),
+                    answer: response_text.clone(),
+                    provider: provider.clone(),
+                    recorded_at: chrono::Utc::now(),
+                };
+                if let Err(e) = samples.record(&sample) {
+                    warn!("Failed to keep teacher answer for self-evaluation: {}", e);
+                }
+            }
+
             // Send to training queue (non-blocking)
             let training_tx = server.training_tx();
             let example = crate::models::WeightedExample {
@@ -590,26 +853,56 @@ pub async fn handle_chat_completions(
         }
     }
 
+    let reasoning_tokens = crate::providers::reasoning::thinking_tokens(&content_blocks);
+
     // Convert internal response to OpenAI format (handles tool_calls)
-    let openai_response = match convert_response_to_openai(content_blocks, &request.model, &request.messages) {
+    let mut openai_response = match convert_response_to_openai(content_blocks, &request.model, &request.messages) {
         Ok(resp) => resp,
         Err(error_resp) => return error_resp,
     };
+    openai_response.metadata = Some(if routing_decision == "local" {
+        slot.attribution().await
+    } else {
+        Attribution::teacher(&provider, &model)
+    });
+
+    // No sessions on this endpoint; usage only counts toward daemon totals
+    let usage = SessionUsage::call(
+        &model,
+        openai_response.usage.prompt_tokens,
+        openai_response.usage.completion_tokens - reasoning_tokens,
+    )
+    .with_reasoning(&model, reasoning_tokens);
+    server.session_manager().record_usage(None, &usage);
+    if let Some(workspace) = &workspace {
+        workspace.record_usage(&usage);
+    }
 
-    Json(openai_response).into_response()
+    let access = AccessDetails {
+        provider: Some(provider),
+        decision: Some(routing_decision.to_string()),
+        input_tokens: Some(openai_response.usage.prompt_tokens),
+        output_tokens: Some(openai_response.usage.completion_tokens),
+        ..Default::default()
+    };
+    (Extension(access), Json(openai_response)).into_response()
 }
 
 /// Handle local-only query (bypass routing, direct local model access)
 async fn handle_local_only_query(
     server: Arc<AgentServer>,
     request: ChatCompletionRequest,
+    priority: RequestPriority,
 ) -> Result<Json<ChatCompletionResponse>, Response> {
     use crate::models::GeneratorState;
 
     info!("Local-only query (bypassing routing)");
 
+    // Pick the local model named by the request (primary if unknown)
+    let slot = Arc::clone(server.local_models().resolve(&request.model));
+
     // Check generator state
-    let state = server.generator_state().read().await;
+    let state = slot.generator_state.read().await;
 
     match &*state {
         GeneratorState::Ready { .. } => {
@@ -656,9 +949,16 @@ async fn handle_local_only_query(
     let internal_messages = convert_messages_to_internal(&request.messages)
         .map_err(|e| error_response(&e.to_string(), "invalid_request_error"))?;
 
+    // Wait our turn for the generator (503 if the queue is saturated)
+    let permit = slot
+        .request_queue
+        .acquire(priority)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
     // Generate response (no tools for now - direct generation only)
     info!("Acquiring write lock on generator...");
-    let mut generator = server.local_generator().write().await;
+    let mut generator = slot.generator(permit.lane()).write().await;
     info!("Write lock acquired, starting generation...");
 
     let content_blocks =
@@ -694,31 +994,38 @@ async fn handle_local_only_query(
             }
         };
     drop(generator);
+    drop(permit);
     info!("Write lock dropped");
 
     // Convert response to OpenAI format
     info!("Converting response to OpenAI format...");
-    let openai_response = convert_response_to_openai(content_blocks, &request.model, &request.messages)?;
+    let mut openai_response = convert_response_to_openai(content_blocks, &request.model, &request.messages)?;
+    openai_response.metadata = Some(slot.attribution().await);
     info!("Response converted, sending back to client");
 
     Ok(Json(openai_response))
 }
 
-/// Handle GET /v1/models - List available models
-pub async fn handle_list_models() -> Json<ModelsResponse> {
+/// Handle GET /v1/models - List available models (one entry per local model)
+pub async fn handle_list_models(State(server): State<Arc<AgentServer>>) -> Json<ModelsResponse> {
     Json(ModelsResponse {
         object: "list".to_string(),
-        data: vec![Model {
-            id: "qwen-local".to_string(),
-            object: "model".to_string(),
-            created: 1672531200, // Arbitrary timestamp
-            owned_by: "local".to_string(),
-        }],
+        data: server
+            .local_models()
+            .slots()
+            .iter()
+            .map(|slot| Model {
+                id: slot.id.clone(),
+                object: "model".to_string(),
+                created: 1672531200, // Arbitrary timestamp
+                owned_by: "local".to_string(),
+            })
+            .collect(),
     })
 }
 
 /// Convert OpenAI tools to internal format
-fn convert_tools_to_internal(tools: &[Tool]) -> Vec<InternalToolDefinition> {
+pub(super) fn convert_tools_to_internal(tools: &[Tool]) -> Vec<InternalToolDefinition> {
     tools
         .iter()
         .map(|t| {
@@ -765,13 +1072,13 @@ fn convert_tools_to_internal(tools: &[Tool]) -> Vec<InternalToolDefinition> {
 }
 
 /// Estimate token count from text using the ~4 chars/token heuristic.
-fn estimate_tokens(text: &str) -> u32 {
+pub(super) fn estimate_tokens(text: &str) -> u32 {
     ((text.len() + 3) / 4) as u32
 }
 
 /// Convert internal GeneratorResponse to OpenAI format
 #[allow(clippy::result_large_err)]
-fn convert_response_to_openai(
+pub(super) fn convert_response_to_openai(
     content_blocks: Vec<ContentBlock>,
     model: &str,
     input_messages: &[crate::server::openai_types::ChatMessage],
@@ -779,6 +1086,8 @@ fn convert_response_to_openai(
     let mut message_content: Option<String> = None;
     let mut tool_calls: Option<Vec<ToolCall>> = None;
     let mut finish_reason = "stop";
+    // Thinking is billed as output but never returned to the client
+    let mut reasoning_tokens = 0;
 
     // Process content blocks
     for block in content_blocks {
@@ -809,6 +1118,10 @@ fn convert_response_to_openai(
             ContentBlock::Image { .. } => {
                 // Image blocks: not applicable in this context
             }
+            ContentBlock::Thinking { thinking, .. } => {
+                reasoning_tokens += crate::providers::reasoning::estimate_tokens(&thinking);
+            }
+            ContentBlock::RedactedThinking { .. } => {}
         }
     }
 
@@ -826,7 +1139,8 @@ fn convert_response_to_openai(
             content_tokens + tool_tokens
         })
         .sum();
-    let completion_tokens = message_content.as_deref().map(estimate_tokens).unwrap_or(0);
+    let completion_tokens =
+        message_content.as_deref().map(estimate_tokens).unwrap_or(0) + reasoning_tokens;
 
     let response = ChatCompletionResponse {
         id: format!("chatcmpl-{}", uuid::Uuid::new_v4()),
@@ -849,6 +1163,7 @@ fn convert_response_to_openai(
             completion_tokens,
             total_tokens: prompt_tokens + completion_tokens,
         },
+        metadata: None,
     };
 
     Ok(response)
@@ -859,7 +1174,9 @@ fn convert_response_to_openai(
 /// Consecutive `role: "tool"` messages are batched into a single internal
 /// `role: "user"` message containing all `ToolResult` blocks, as required by
 /// the Claude API.
-fn convert_messages_to_internal(messages: &[ChatMessage]) -> anyhow::Result<Vec<Message>> {
+pub(super) fn convert_messages_to_internal(
+    messages: &[ChatMessage],
+) -> anyhow::Result<Vec<Message>> {
     let mut result: Vec<Message> = Vec::new();
 
     for msg in messages {
@@ -963,6 +1280,43 @@ fn error_response(message: &str, error_type: &str) -> Response {
     (StatusCode::BAD_REQUEST, Json(error)).into_response()
 }
 
+/// The response cache key for `request`, or `None` when it can't be cached.
+///
+/// Sampled requests (a temperature above zero, several choices) ask for a
+/// fresh answer each time, so they get no key.  Only a trailing user message
+/// may match by similarity.
+fn response_cache_key(
+    request: &ChatCompletionRequest,
+    workspace: Option<&str>,
+) -> Option<CacheKey> {
+    if request.temperature.is_some_and(|t| t > 0.0) || request.n.is_some_and(|n| n > 1) {
+        return None;
+    }
+    let (last, earlier) = request.messages.split_last()?;
+    let query = if last.role == "user" {
+        last.content.as_deref()
+    } else {
+        None
+    };
+    Some(CacheKey::new(
+        &(
+            workspace,
+            &request.model,
+            &request.tools,
+            (
+                request.max_tokens,
+                request.temperature,
+                request.top_p,
+                &request.stop,
+                request.n,
+            ),
+            earlier,
+        ),
+        last,
+        query,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -980,6 +1334,38 @@ mod tests {
         assert_eq!(internal[1].role, "user");
     }
 
+    #[test]
+    fn test_cache_key_covers_generation_settings() {
+        let request = |json: serde_json::Value| -> ChatCompletionRequest {
+            let mut body = serde_json::json!({
+                "model": "qwen-local",
+                "messages": [{"role": "user", "content": "What is 2+2?"}],
+            });
+            body.as_object_mut()
+                .unwrap()
+                .extend(json.as_object().unwrap().clone());
+            serde_json::from_value(body).unwrap()
+        };
+        let key = |json| response_cache_key(&request(json), None);
+
+        let plain = key(serde_json::json!({}));
+        assert!(plain.is_some());
+        assert_eq!(key(serde_json::json!({})), plain);
+        for settings in [
+            serde_json::json!({"max_tokens": 5}),
+            serde_json::json!({"top_p": 0.5}),
+            serde_json::json!({"stop": ["\n"]}),
+            serde_json::json!({"temperature": 0.0}),
+        ] {
+            let other = key(settings.clone());
+            assert!(other.is_some() && other != plain, "{}", settings);
+        }
+
+        // Sampled requests are never cached
+        assert_eq!(key(serde_json::json!({"temperature": 0.7})), None);
+        assert_eq!(key(serde_json::json!({"n": 2})), None);
+    }
+
     #[test]
     fn test_token_buffer_basic() {
         let mut buffer = TokenBuffer::new();
@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::generators::Attribution;
+
 /// Request body for /v1/chat/completions endpoint
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatCompletionRequest {
@@ -36,6 +38,21 @@ pub struct ChatCompletionRequest {
     /// Bypass routing and query local model directly (for testing)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_only: Option<bool>,
+    /// Set to `false` to bypass the daemon's response cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    /// Client session, so `DELETE /v1/sessions/:id/active-generation` can
+    /// cancel this request while it runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The client session is incognito: don't keep the exchange for
+    /// training or in the response cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incognito: Option<bool>,
+    /// Set to `false` to skip daemon memory: nothing is recalled into the
+    /// prompt and the exchange isn't remembered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<bool>,
 }
 
 /// Chat message in OpenAI format
@@ -115,6 +132,10 @@ pub struct ChatCompletionResponse {
     pub choices: Vec<Choice>,
     /// Usage statistics
     pub usage: Usage,
+    /// Which generator produced the completion (omitted for answers served
+    /// from the response cache)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Attribution>,
 }
 
 /// Completion choice
@@ -278,12 +299,23 @@ mod tests {
                 completion_tokens: 1,
                 total_tokens: 6,
             },
+            metadata: None,
         };
         let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("metadata"));
         let decoded: ChatCompletionResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded.id, "chatcmpl-123");
         assert_eq!(decoded.choices[0].finish_reason, "stop");
         assert_eq!(decoded.usage.total_tokens, 6);
+
+        let response = ChatCompletionResponse {
+            metadata: Some(Attribution::teacher("openai", "gpt-4o")),
+            ..decoded
+        };
+        let json: serde_json::Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["metadata"]["trust"], "teacher");
+        assert_eq!(json["metadata"]["provider"], "openai");
+        assert_eq!(json["metadata"]["model"], "gpt-4o");
     }
 
     #[test]
@@ -0,0 +1,963 @@
+// Proxy-only mode (`finch proxy`)
+//
+// Serves just the OpenAI- and Anthropic-compatible endpoints in front of the
+// `[[providers]]` fallback chain: no local model, sessions, tools, router or
+// training, so it starts at once and stays small.  Every request is
+// stateless and goes to the first provider in config order that answers;
+// providers whose rate-limit window hasn't reset are skipped.  A client can
+// pin a provider with `X-Finch-Provider: NAME` or a `model` of `NAME` or
+// `NAME/MODEL`.
+//
+// Tokens are estimated (~4 characters each, as in the daemon) and priced with
+// `metrics::pricing`; totals per provider are reported by `/health`.
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tower_http::trace::TraceLayer;
+use tracing::{info, warn};
+
+use super::middleware::auth_middleware;
+use super::openai_handlers::{
+    convert_messages_to_internal, convert_response_to_openai, convert_tools_to_internal,
+    estimate_tokens,
+};
+use super::openai_types::{ChatCompletionRequest, Model, ModelsResponse};
+use super::{SessionUsage, Workspaces};
+use crate::claude::{ContentBlock, Message};
+use crate::config::CorsConfig;
+use crate::daemon::DaemonEndpoint;
+use crate::providers::rate_limit::{self, RateLimited};
+use crate::providers::reasoning;
+use crate::providers::{
+    FallbackChain, LlmProvider, ProviderRequest, ProviderResponse, StreamChunk,
+};
+use crate::tools::types::ToolDefinition;
+
+/// Settings for `finch proxy`
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    /// `host:port`, or `unix:PATH` for a Unix socket
+    pub bind_address: String,
+    /// Per-install token file clients must present (`None` = not required)
+    pub local_token: Option<PathBuf>,
+    /// Cross-origin access for browser clients
+    pub cors: CorsConfig,
+}
+
+/// Tokens and estimated spend since the proxy started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProxyUsage {
+    pub total: SessionUsage,
+    /// By the provider that answered
+    pub providers: BTreeMap<String, SessionUsage>,
+}
+
+/// The proxy: a fallback chain behind the two chat APIs
+pub struct ProxyServer {
+    config: ProxyConfig,
+    chain: FallbackChain,
+    /// Only ever holds the owner token; the proxy has no workspaces
+    workspaces: Arc<Workspaces>,
+    usage: Mutex<ProxyUsage>,
+    started_at: Instant,
+}
+
+/// A provider the request named, and the model to ask it for
+struct Pinned<'a> {
+    provider: &'a dyn LlmProvider,
+    /// `None` = the provider's configured model
+    model: Option<String>,
+}
+
+impl ProxyServer {
+    /// Build the proxy over `providers`, in fallback order
+    pub fn new(config: ProxyConfig, providers: Vec<Box<dyn LlmProvider>>) -> Result<Self> {
+        if providers.is_empty() {
+            bail!("finch proxy needs at least one cloud provider in [[providers]]");
+        }
+        let mut workspaces = Workspaces::default();
+        if let Some(path) = &config.local_token {
+            let token = crate::daemon::token::load_or_create(path)
+                .context("Failed to provision the daemon token")?;
+            workspaces = workspaces.with_owner_token(token);
+        }
+        Ok(Self {
+            config,
+            chain: FallbackChain::new(providers),
+            workspaces: Arc::new(workspaces),
+            usage: Mutex::new(ProxyUsage::default()),
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Usage so far
+    pub fn usage(&self) -> ProxyUsage {
+        self.usage.lock().unwrap().clone()
+    }
+
+    /// Routes, behind the token check and body limit
+    pub fn router(self: Arc<Self>) -> Router {
+        let cors = super::web::cors_layer(&self.config.cors);
+        let workspaces = Arc::clone(&self.workspaces);
+        let router = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/messages", post(messages))
+            .route("/v1/models", get(list_models))
+            .route("/health", get(health))
+            .with_state(self)
+            .layer(axum::middleware::from_fn_with_state(
+                workspaces,
+                auth_middleware,
+            ))
+            .layer(DefaultBodyLimit::max(4 * 1024 * 1024));
+        match cors {
+            Some(cors) => router.layer(cors),
+            None => router,
+        }
+    }
+
+    /// Serve until the listener fails
+    pub async fn serve(self: Arc<Self>) -> Result<()> {
+        let endpoint = DaemonEndpoint::parse(&self.config.bind_address)?;
+        let app = self.router().layer(TraceLayer::new_for_http());
+        info!("Starting finch proxy on {}", endpoint);
+        match endpoint {
+            DaemonEndpoint::Tcp(addr) => {
+                let listener = tokio::net::TcpListener::bind(&addr)
+                    .await
+                    .with_context(|| format!("Failed to bind {}", addr))?;
+                axum::serve(listener, app).await?;
+            }
+            #[cfg(unix)]
+            DaemonEndpoint::Unix(path) => super::unix_socket::serve(app, &path).await?,
+            #[cfg(not(unix))]
+            DaemonEndpoint::Unix(path) => {
+                bail!("Unix domain sockets are not supported: {}", path.display())
+            }
+        }
+        Ok(())
+    }
+
+    /// `X-Finch-Provider: NAME`, or a `model` of `NAME` or `NAME/MODEL`,
+    /// pins a provider; anything else goes through the fallback chain
+    fn pinned(&self, headers: &HeaderMap, model: &str) -> Result<Option<Pinned<'_>>, ProxyError> {
+        let (name, model_id) = match model.split_once('/') {
+            Some((name, model_id)) => (name, Some(model_id)),
+            None => (model, None),
+        };
+        if let Some(provider) = self.chain.provider(name) {
+            return Ok(Some(Pinned {
+                provider,
+                model: model_id.filter(|m| !m.is_empty()).map(str::to_string),
+            }));
+        }
+        let Some(name) = headers
+            .get("x-finch-provider")
+            .and_then(|v| v.to_str().ok())
+        else {
+            return Ok(None);
+        };
+        match self.chain.provider(name) {
+            Some(provider) => Ok(Some(Pinned {
+                provider,
+                model: None,
+            })),
+            None => Err(ProxyError::InvalidRequest(format!(
+                "Unknown provider: {}",
+                name
+            ))),
+        }
+    }
+
+    /// Send `request` to the pinned provider, or down the chain
+    async fn send(
+        &self,
+        pinned: Option<Pinned<'_>>,
+        request: ProviderRequest,
+    ) -> Result<ProviderResponse, ProxyError> {
+        let Some(Pinned { provider, model }) = pinned else {
+            return Ok(self.chain.send_message_with_fallback(&request).await?);
+        };
+        check_throttle(provider)?;
+        let request = request.with_model(model.unwrap_or_else(|| provider.default_model().into()));
+        Ok(rate_limit::without_waiting(provider.send_message(&request)).await?)
+    }
+
+    /// Start streaming `request`; returns the provider and model answering
+    async fn stream(
+        &self,
+        pinned: Option<Pinned<'_>>,
+        request: ProviderRequest,
+    ) -> Result<(String, String, mpsc::Receiver<Result<StreamChunk>>), ProxyError> {
+        let request = request.with_stream(true);
+        let Some(Pinned { provider, model }) = pinned else {
+            let (provider, chunks) = self
+                .chain
+                .send_message_stream_with_provider(&request)
+                .await?;
+            return Ok((
+                provider.name().to_string(),
+                provider.default_model().to_string(),
+                chunks,
+            ));
+        };
+        check_throttle(provider)?;
+        let model = model.unwrap_or_else(|| provider.default_model().into());
+        let request = request.with_model(model.clone());
+        let chunks = rate_limit::without_waiting(provider.send_message_stream(&request)).await?;
+        Ok((provider.name().to_string(), model, chunks))
+    }
+
+    fn record(
+        &self,
+        provider: &str,
+        model: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+        reasoning_tokens: u32,
+    ) {
+        let usage = SessionUsage::call(model, input_tokens, output_tokens)
+            .with_reasoning(model, reasoning_tokens);
+        info!(
+            provider,
+            model,
+            input_tokens,
+            output_tokens,
+            reasoning_tokens,
+            cost_usd = usage.cost_usd,
+            "Proxied request"
+        );
+        let mut totals = self.usage.lock().unwrap();
+        totals.total.add(&usage);
+        totals
+            .providers
+            .entry(provider.to_string())
+            .or_default()
+            .add(&usage);
+    }
+
+    async fn chat_completion(
+        self: &Arc<Self>,
+        headers: &HeaderMap,
+        request: ChatCompletionRequest,
+    ) -> Result<Response, ProxyError> {
+        if request.messages.is_empty() {
+            return Err(ProxyError::InvalidRequest(
+                "messages array cannot be empty".to_string(),
+            ));
+        }
+
+        // Providers take the system prompt separately
+        let (system, conversation): (Vec<_>, Vec<_>) = request
+            .messages
+            .iter()
+            .cloned()
+            .partition(|m| m.role == "system");
+        let messages = convert_messages_to_internal(&conversation)
+            .map_err(|e| ProxyError::InvalidRequest(e.to_string()))?;
+        let system = system
+            .iter()
+            .filter_map(|m| m.content.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let mut provider_request = ProviderRequest::new(messages);
+        if !system.is_empty() {
+            provider_request = provider_request.with_system(system);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            provider_request = provider_request.with_max_tokens(max_tokens);
+        }
+        if let Some(temperature) = request.temperature {
+            provider_request = provider_request.with_temperature(temperature);
+        }
+        if let Some(tools) = &request.tools {
+            provider_request = provider_request.with_tools(convert_tools_to_internal(tools));
+        }
+
+        let pinned = self.pinned(headers, &request.model)?;
+        if request.stream {
+            return self.stream_chat_completion(pinned, provider_request).await;
+        }
+
+        let response = self.send(pinned, provider_request).await?;
+        let reasoning_tokens = reasoning::thinking_tokens(&response.content);
+        let completion = match convert_response_to_openai(
+            response.content,
+            &response.model,
+            &request.messages,
+        ) {
+            Ok(completion) => completion,
+            Err(error) => return Ok(error),
+        };
+        self.record(
+            &response.provider,
+            &response.model,
+            completion.usage.prompt_tokens,
+            completion.usage.completion_tokens - reasoning_tokens,
+            reasoning_tokens,
+        );
+        Ok(Json(completion).into_response())
+    }
+
+    /// Relay the provider's stream as `chat.completion.chunk` events
+    async fn stream_chat_completion(
+        self: &Arc<Self>,
+        pinned: Option<Pinned<'_>>,
+        request: ProviderRequest,
+    ) -> Result<Response, ProxyError> {
+        let estimated_input = estimate_input_tokens(&request);
+        let (provider, model, mut chunks) = self.stream(pinned, request).await?;
+
+        let (tx, rx) = mpsc::channel::<Event>(32);
+        let proxy = Arc::clone(self);
+        tokio::spawn(async move {
+            let id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
+            let mut input_tokens = estimated_input;
+            let mut output = String::new();
+            let mut thinking = String::new();
+            let mut reported_reasoning = None;
+            // Text of the current block streamed so far, so a provider that
+            // only sends complete blocks is relayed too
+            let mut block_text = String::new();
+            let mut tool_calls = 0;
+            let mut finished = true;
+
+            while let Some(chunk) = chunks.recv().await {
+                let delta = match chunk {
+                    Ok(StreamChunk::TextDelta(text)) => {
+                        block_text.push_str(&text);
+                        json!({ "content": text })
+                    }
+                    Ok(StreamChunk::ContentBlockComplete(ContentBlock::Text { text })) => {
+                        let streamed = std::mem::take(&mut block_text);
+                        output.push_str(&text);
+                        if !streamed.is_empty() {
+                            continue;
+                        }
+                        json!({ "content": text })
+                    }
+                    Ok(StreamChunk::ContentBlockComplete(ContentBlock::ToolUse {
+                        id,
+                        name,
+                        input,
+                    })) => {
+                        let arguments = input.to_string();
+                        output.push_str(&arguments);
+                        tool_calls += 1;
+                        json!({ "tool_calls": [{
+                            "index": tool_calls - 1,
+                            "id": id,
+                            "type": "function",
+                            "function": { "name": name, "arguments": arguments },
+                        }] })
+                    }
+                    Ok(StreamChunk::ThinkingDelta(text)) => {
+                        thinking.push_str(&text);
+                        json!({ "reasoning_content": text })
+                    }
+                    Ok(StreamChunk::ReasoningTokens(n)) => {
+                        reported_reasoning = Some(n);
+                        continue;
+                    }
+                    Ok(StreamChunk::ContentBlockComplete(_)) => continue,
+                    Ok(StreamChunk::Usage {
+                        input_tokens: reported,
+                    }) => {
+                        input_tokens = reported;
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(provider = %provider, "Proxied stream failed: {:#}", e);
+                        let error = json!({ "error": {
+                            "message": format!("{:#}", e),
+                            "type": "api_error",
+                        } });
+                        let _ = tx.send(Event::default().json_data(error).unwrap()).await;
+                        finished = false;
+                        break;
+                    }
+                };
+                // Receiver gone: the client hung up, stop reading
+                if tx
+                    .send(chunk_event(&id, &model, delta, None))
+                    .await
+                    .is_err()
+                {
+                    finished = false;
+                    break;
+                }
+            }
+
+            output.push_str(&block_text);
+            let reasoning_tokens =
+                reported_reasoning.unwrap_or_else(|| reasoning::estimate_tokens(&thinking));
+            proxy.record(
+                &provider,
+                &model,
+                input_tokens,
+                estimate_tokens(&output),
+                reasoning_tokens,
+            );
+            if finished {
+                let reason = if tool_calls > 0 { "tool_calls" } else { "stop" };
+                let _ = tx
+                    .send(chunk_event(&id, &model, json!({}), Some(reason)))
+                    .await;
+                let _ = tx.send(Event::default().data("[DONE]")).await;
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv()
+                .await
+                .map(|event| (Ok::<_, Infallible>(event), rx))
+        });
+        Ok(Sse::new(stream).into_response())
+    }
+
+    async fn message(
+        &self,
+        headers: &HeaderMap,
+        request: MessagesRequest,
+    ) -> Result<MessagesResponse, ProxyError> {
+        if request.stream {
+            return Err(ProxyError::InvalidRequest(
+                "Streaming is only supported on /v1/chat/completions".to_string(),
+            ));
+        }
+        if request.messages.is_empty() {
+            return Err(ProxyError::InvalidRequest(
+                "messages array cannot be empty".to_string(),
+            ));
+        }
+
+        let mut provider_request = ProviderRequest::new(request.messages);
+        if let Some(system) = request.system {
+            provider_request = provider_request.with_system(system);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            provider_request = provider_request.with_max_tokens(max_tokens);
+        }
+        if let Some(temperature) = request.temperature {
+            provider_request = provider_request.with_temperature(temperature);
+        }
+        if let Some(tools) = request.tools {
+            provider_request = provider_request.with_tools(tools);
+        }
+        let input_tokens = estimate_input_tokens(&provider_request);
+
+        let pinned = self.pinned(headers, &request.model)?;
+        let response = self.send(pinned, provider_request).await?;
+        let output_tokens = estimate_tokens(&blocks_text(&response.content));
+        let reasoning_tokens = reasoning::thinking_tokens(&response.content);
+        self.record(
+            &response.provider,
+            &response.model,
+            input_tokens,
+            output_tokens,
+            reasoning_tokens,
+        );
+        Ok(MessagesResponse {
+            id: response.id,
+            response_type: "message".to_string(),
+            role: "assistant".to_string(),
+            content: response.content,
+            model: response.model,
+            stop_reason: response.stop_reason,
+            // Anthropic counts thinking as output
+            usage: MessagesUsage {
+                input_tokens,
+                output_tokens: output_tokens + reasoning_tokens,
+            },
+        })
+    }
+}
+
+/// Request body for POST /v1/messages (Anthropic Messages API)
+#[derive(Debug, Deserialize)]
+pub struct MessagesRequest {
+    /// A provider name (or `provider/model`) pins that provider
+    pub model: String,
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub system: Option<String>,
+    #[serde(default)]
+    pub tools: Option<Vec<ToolDefinition>>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Not supported here; refused with 400
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Response body for POST /v1/messages
+#[derive(Debug, Serialize)]
+pub struct MessagesResponse {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub role: String,
+    pub content: Vec<ContentBlock>,
+    pub model: String,
+    pub stop_reason: Option<String>,
+    pub usage: MessagesUsage,
+}
+
+/// Estimated token counts for a /v1/messages response
+#[derive(Debug, Serialize)]
+pub struct MessagesUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+/// Which API's error format to answer with
+#[derive(Debug, Clone, Copy)]
+enum Api {
+    OpenAi,
+    Anthropic,
+}
+
+/// Why a proxied request failed
+#[derive(Debug, thiserror::Error)]
+enum ProxyError {
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("{message}")]
+    RateLimited {
+        message: String,
+        retry_after_secs: u64,
+    },
+    #[error("{0}")]
+    Provider(String),
+}
+
+impl From<anyhow::Error> for ProxyError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast_ref::<RateLimited>() {
+            Some(limited) => Self::RateLimited {
+                message: format!("{:#}", e),
+                retry_after_secs: rate_limit::ceil_secs(limited.retry_after),
+            },
+            None => Self::Provider(format!("{:#}", e)),
+        }
+    }
+}
+
+impl ProxyError {
+    fn into_response(self, api: Api) -> Response {
+        let (status, error_type) = match &self {
+            Self::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_request_error"),
+            Self::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_error"),
+            Self::Provider(_) => (StatusCode::BAD_GATEWAY, "api_error"),
+        };
+        let message = self.to_string();
+        let body = match api {
+            Api::OpenAi => json!({
+                "error": { "message": message, "type": error_type, "code": null }
+            }),
+            Api::Anthropic => json!({
+                "type": "error",
+                "error": { "type": error_type, "message": message }
+            }),
+        };
+        let mut response = (status, Json(body)).into_response();
+        if let Self::RateLimited {
+            retry_after_secs, ..
+        } = self
+        {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
+    }
+}
+
+/// Refuse a pinned provider whose rate-limit window hasn't reset
+fn check_throttle(provider: &dyn LlmProvider) -> Result<(), ProxyError> {
+    match rate_limit::remaining(provider.name()) {
+        Some(wait) => Err(ProxyError::RateLimited {
+            message: format!("{} is rate limited", provider.name()),
+            retry_after_secs: rate_limit::ceil_secs(wait),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Text, tool inputs and tool results in `blocks`, for token estimates
+fn blocks_text(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.clone()),
+            ContentBlock::ToolUse { input, .. } => Some(input.to_string()),
+            ContentBlock::ToolResult { content, .. } => Some(content.clone()),
+            ContentBlock::Image { .. }
+            | ContentBlock::Thinking { .. }
+            | ContentBlock::RedactedThinking { .. } => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn estimate_input_tokens(request: &ProviderRequest) -> u32 {
+    let system = request.system.as_deref().map(estimate_tokens).unwrap_or(0);
+    let messages: u32 = request
+        .messages
+        .iter()
+        .map(|m| estimate_tokens(&blocks_text(&m.content)))
+        .sum();
+    system + messages
+}
+
+/// One `chat.completion.chunk` event
+fn chunk_event(
+    id: &str,
+    model: &str,
+    delta: serde_json::Value,
+    finish_reason: Option<&str>,
+) -> Event {
+    let chunk = json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+    });
+    Event::default().json_data(chunk).unwrap()
+}
+
+/// POST /v1/chat/completions
+async fn chat_completions(
+    State(proxy): State<Arc<ProxyServer>>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    proxy
+        .chat_completion(&headers, request)
+        .await
+        .unwrap_or_else(|e| e.into_response(Api::OpenAi))
+}
+
+/// POST /v1/messages
+async fn messages(
+    State(proxy): State<Arc<ProxyServer>>,
+    headers: HeaderMap,
+    Json(request): Json<MessagesRequest>,
+) -> Response {
+    match proxy.message(&headers, request).await {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => e.into_response(Api::Anthropic),
+    }
+}
+
+/// GET /v1/models — each provider's configured model, as `provider/model`
+async fn list_models(State(proxy): State<Arc<ProxyServer>>) -> Json<ModelsResponse> {
+    Json(ModelsResponse {
+        object: "list".to_string(),
+        data: proxy
+            .chain
+            .providers()
+            .map(|p| Model {
+                id: format!("{}/{}", p.name(), p.default_model()),
+                object: "model".to_string(),
+                created: 1672531200, // Arbitrary timestamp
+                owned_by: p.name().to_string(),
+            })
+            .collect(),
+    })
+}
+
+/// GET /health — providers in fallback order and usage so far
+async fn health(State(proxy): State<Arc<ProxyServer>>) -> Json<serde_json::Value> {
+    let providers: Vec<_> = proxy
+        .chain
+        .providers()
+        .map(|p| {
+            json!({
+                "name": p.name(),
+                "model": p.default_model(),
+                "rate_limited_for_secs": rate_limit::remaining(p.name()).map(rate_limit::ceil_secs),
+            })
+        })
+        .collect();
+    Json(json!({
+        "status": "healthy",
+        "mode": "proxy",
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_seconds": proxy.started_at.elapsed().as_secs(),
+        "providers": providers,
+        "usage": proxy.usage(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    /// Answers with its name, or fails when `fail` is set
+    struct MockProvider {
+        name: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for MockProvider {
+        async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+            if self.fail {
+                bail!("{} is down", self.name);
+            }
+            Ok(ProviderResponse {
+                id: "msg-1".to_string(),
+                model: request.model.clone(),
+                content: vec![ContentBlock::Text {
+                    text: format!("hello from {}", self.name),
+                }],
+                stop_reason: Some("end_turn".to_string()),
+                role: "assistant".to_string(),
+                provider: self.name.to_string(),
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+            if self.fail {
+                bail!("{} is down", self.name);
+            }
+            let (tx, rx) = mpsc::channel(4);
+            tokio::spawn(async move {
+                for text in ["hel", "lo"] {
+                    let _ = tx.send(Ok(StreamChunk::TextDelta(text.to_string()))).await;
+                }
+            });
+            Ok(rx)
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            "mock-model"
+        }
+    }
+
+    fn proxy(config: ProxyConfig) -> Arc<ProxyServer> {
+        let providers: Vec<Box<dyn LlmProvider>> = vec![
+            Box::new(MockProvider {
+                name: "primary",
+                fail: true,
+            }),
+            Box::new(MockProvider {
+                name: "backup",
+                fail: false,
+            }),
+        ];
+        Arc::new(ProxyServer::new(config, providers).unwrap())
+    }
+
+    fn post(path: &str, body: serde_json::Value) -> Request<Body> {
+        Request::post(path)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_falls_back_and_counts_usage() {
+        let proxy = proxy(ProxyConfig::default());
+        let request = json!({
+            "model": "gpt-4o",
+            "messages": [
+                { "role": "system", "content": "Be brief" },
+                { "role": "user", "content": "Hi" },
+            ],
+        });
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/chat/completions", request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["model"], "mock-model");
+        assert_eq!(
+            body["choices"][0]["message"]["content"],
+            "hello from backup"
+        );
+
+        let usage = proxy.usage();
+        assert_eq!(usage.total.requests, 1);
+        assert_eq!(usage.providers["backup"].requests, 1);
+        assert!(!usage.providers.contains_key("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_model_or_header_pins_a_provider() {
+        let proxy = proxy(ProxyConfig::default());
+
+        // `provider/model` goes to that provider only, with that model
+        let request = json!({
+            "model": "primary/big-model",
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/chat/completions", request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert!(json_body(response).await["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("primary is down"));
+
+        let request = json!({
+            "model": "backup/big-model",
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/chat/completions", request))
+            .await
+            .unwrap();
+        assert_eq!(json_body(response).await["model"], "big-model");
+
+        let mut request = post(
+            "/v1/chat/completions",
+            json!({ "model": "x", "messages": [{ "role": "user", "content": "Hi" }] }),
+        );
+        request
+            .headers_mut()
+            .insert("x-finch-provider", HeaderValue::from_static("nope"));
+        let response = proxy.router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_chat_completion() {
+        let proxy = proxy(ProxyConfig::default());
+        let request = json!({
+            "model": "gpt-4o",
+            "stream": true,
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/chat/completions", request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(body.contains(r#""content":"hel""#));
+        assert!(body.contains(r#""finish_reason":"stop""#));
+        assert!(body.trim_end().ends_with("data: [DONE]"));
+        assert_eq!(proxy.usage().providers["backup"].requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_messages_endpoint_speaks_anthropic() {
+        let proxy = proxy(ProxyConfig::default());
+        let request = json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 100,
+            "system": "Be brief",
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/messages", request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["type"], "message");
+        assert_eq!(body["content"][0]["type"], "text");
+        assert_eq!(body["content"][0]["text"], "hello from backup");
+        assert!(body["usage"]["output_tokens"].as_u64().unwrap() > 0);
+
+        let request = json!({
+            "model": "claude-sonnet-4-5",
+            "stream": true,
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        let response = proxy
+            .router()
+            .oneshot(post("/v1/messages", request))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["type"], "error");
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+    }
+
+    #[tokio::test]
+    async fn test_token_required_except_health() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.token");
+        let proxy = proxy(ProxyConfig {
+            local_token: Some(path.clone()),
+            ..Default::default()
+        });
+        let token = crate::daemon::token::load_or_create(&path).unwrap();
+        let body = json!({ "model": "x", "messages": [{ "role": "user", "content": "Hi" }] });
+
+        let response = Arc::clone(&proxy)
+            .router()
+            .oneshot(post("/v1/chat/completions", body.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut request = post("/v1/chat/completions", body);
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        let response = Arc::clone(&proxy).router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let health = Request::get("/health").body(Body::empty()).unwrap();
+        let response = proxy.router().oneshot(health).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["mode"], "proxy");
+        assert_eq!(body["providers"][1]["name"], "backup");
+        assert_eq!(body["usage"]["total"]["requests"], 1);
+    }
+}
@@ -0,0 +1,716 @@
+// Priority request queue for the local generator
+//
+// Each loaded copy of a local model (a "lane") serves one generation at a
+// time.  Instead of letting every request pile up behind a generator's write
+// lock, requests acquire a `QueuePermit` naming a free lane first.  Waiters are
+// served highest-priority first (FIFO within a class), and once `max_depth`
+// requests are waiting new arrivals are rejected with `QueueFull` so the HTTP
+// layer can answer 503 + Retry-After.
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Header clients use to declare the priority class of a request.
+pub const PRIORITY_HEADER: &str = "x-finch-priority";
+
+/// Priority class of a request waiting for the local generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestPriority {
+    /// A human is waiting on the answer (REPL, IDE, chat UI)
+    Interactive,
+    /// Autonomous agent / brain work
+    Agent,
+    /// Background training-data generation
+    Training,
+}
+
+impl RequestPriority {
+    /// All classes, highest priority first.
+    pub const ALL: [RequestPriority; 3] = [
+        RequestPriority::Interactive,
+        RequestPriority::Agent,
+        RequestPriority::Training,
+    ];
+
+    /// Parse a priority name ("interactive", "agent", "training").
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "interactive" => Some(Self::Interactive),
+            "agent" => Some(Self::Agent),
+            "training" => Some(Self::Training),
+            _ => None,
+        }
+    }
+
+    /// Read the priority from the `X-Finch-Priority` header.
+    /// Missing or unrecognised values are treated as interactive.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(PRIORITY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Self::parse)
+            .unwrap_or(Self::Interactive)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Agent => "agent",
+            Self::Training => "training",
+        }
+    }
+
+    /// One class lower, for requests from other machines: they may not
+    /// jump ahead of someone waiting at this one.
+    pub fn demoted(self) -> Self {
+        match self {
+            Self::Interactive => Self::Agent,
+            other => other,
+        }
+    }
+
+    /// Larger rank = served first.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Interactive => 2,
+            Self::Agent => 1,
+            Self::Training => 0,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Self::Interactive => 0,
+            Self::Agent => 1,
+            Self::Training => 2,
+        }
+    }
+}
+
+/// Returned when the queue is saturated.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("local generator queue is full ({depth} requests waiting); retry in {retry_after_secs}s")]
+pub struct QueueFull {
+    /// Number of requests waiting when this one was rejected
+    pub depth: usize,
+    /// Suggested client back-off, sent as the `Retry-After` header
+    pub retry_after_secs: u64,
+}
+
+impl IntoResponse for QueueFull {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "type": "server_overloaded",
+                "code": "queue_full"
+            }
+        });
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, self.retry_after_secs.to_string())],
+            Json(body),
+        )
+            .into_response()
+    }
+}
+
+/// Per-priority counters (all atomics; read without locking).
+#[derive(Default)]
+struct ClassStats {
+    admitted: AtomicU64,
+    rejected: AtomicU64,
+    total_wait_ms: AtomicU64,
+    max_wait_ms: AtomicU64,
+}
+
+/// Point-in-time view of one priority class, for `/metrics`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ClassSnapshot {
+    pub priority: &'static str,
+    pub waiting: usize,
+    pub admitted: u64,
+    pub rejected: u64,
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64,
+}
+
+/// Extracts one per-class value for Prometheus rendering.
+type ClassMetric = fn(&ClassSnapshot) -> u64;
+
+/// Point-in-time view of the whole queue.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueueSnapshot {
+    pub busy: bool,
+    /// Lanes currently serving a request
+    pub active: usize,
+    /// Lanes available to serve requests
+    pub lanes: usize,
+    pub max_depth: usize,
+    pub avg_service_ms: u64,
+    pub classes: Vec<ClassSnapshot>,
+}
+
+impl QueueSnapshot {
+    /// Render as Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "# HELP finch_queue_busy Whether the local generator is currently serving a request\n",
+        );
+        out.push_str("# TYPE finch_queue_busy gauge\n");
+        out.push_str(&format!("finch_queue_busy {}\n", self.busy as u8));
+        out.push_str("# HELP finch_queue_active Local generator sessions currently in use\n");
+        out.push_str("# TYPE finch_queue_active gauge\n");
+        out.push_str(&format!("finch_queue_active {}\n", self.active));
+        out.push_str("# HELP finch_queue_lanes Local generator sessions loaded\n");
+        out.push_str("# TYPE finch_queue_lanes gauge\n");
+        out.push_str(&format!("finch_queue_lanes {}\n", self.lanes));
+        out.push_str(
+            "# HELP finch_queue_avg_service_ms Moving average time a request holds the generator\n",
+        );
+        out.push_str("# TYPE finch_queue_avg_service_ms gauge\n");
+        out.push_str(&format!(
+            "finch_queue_avg_service_ms {}\n",
+            self.avg_service_ms
+        ));
+
+        let series: [(&str, &str, &str, ClassMetric); 5] = [
+            (
+                "finch_queue_waiting",
+                "gauge",
+                "Requests currently waiting for the local generator",
+                |c| c.waiting as u64,
+            ),
+            (
+                "finch_queue_admitted_total",
+                "counter",
+                "Requests admitted to the local generator",
+                |c| c.admitted,
+            ),
+            (
+                "finch_queue_rejected_total",
+                "counter",
+                "Requests rejected with 503 because the queue was full",
+                |c| c.rejected,
+            ),
+            (
+                "finch_queue_wait_ms_total",
+                "counter",
+                "Total time admitted requests spent queued",
+                |c| c.total_wait_ms,
+            ),
+            (
+                "finch_queue_wait_ms_max",
+                "gauge",
+                "Longest time any admitted request spent queued",
+                |c| c.max_wait_ms,
+            ),
+        ];
+        for (name, kind, help, value) in series {
+            out.push_str(&format!(
+                "# HELP {} {}\n# TYPE {} {}\n",
+                name, help, name, kind
+            ));
+            for class in &self.classes {
+                out.push_str(&format!(
+                    "{}{{priority=\"{}\"}} {}\n",
+                    name,
+                    class.priority,
+                    value(class)
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// A request parked in the queue.
+struct Waiter {
+    priority: RequestPriority,
+    seq: u64,
+    tx: oneshot::Sender<QueuePermit>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    // BinaryHeap is a max-heap: higher priority wins, then the older (smaller) seq.
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .rank()
+            .cmp(&other.priority.rank())
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+struct QueueState {
+    /// Lanes with no outstanding permit
+    free_lanes: Vec<usize>,
+    /// Total lanes, free or in use
+    lanes: usize,
+    /// Bumped when the extra lanes close, so permits granted before that
+    /// do not hand their lane out again
+    epoch: u64,
+    waiters: BinaryHeap<Waiter>,
+    next_seq: u64,
+}
+
+/// Bounded priority queue guarding the local generator.
+pub struct RequestQueue {
+    state: Mutex<QueueState>,
+    max_depth: usize,
+    stats: [ClassStats; 3],
+    /// Exponential moving average of permit hold time (ms), used for Retry-After
+    avg_service_ms: AtomicU64,
+}
+
+impl RequestQueue {
+    /// Create a single-lane queue that allows at most `max_depth` requests to
+    /// wait.  More lanes are added with `add_lane()` as they become usable.
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                free_lanes: vec![0],
+                lanes: 1,
+                epoch: 0,
+                waiters: BinaryHeap::new(),
+                next_seq: 0,
+            }),
+            max_depth,
+            stats: Default::default(),
+            avg_service_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait for exclusive access to one lane of the local generator.
+    ///
+    /// Returns immediately when a lane is idle, otherwise parks behind
+    /// any higher-priority (or older same-priority) waiters.  Fails fast with
+    /// `QueueFull` when `max_depth` requests are already waiting.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        priority: RequestPriority,
+    ) -> Result<QueuePermit, QueueFull> {
+        let start = Instant::now();
+        let rx = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(lane) = state.free_lanes.pop() {
+                let epoch = state.epoch;
+                drop(state);
+                self.record_admitted(priority, Duration::ZERO);
+                return Ok(QueuePermit::new(Arc::clone(self), priority, lane, epoch));
+            }
+            if state.waiters.len() >= self.max_depth {
+                let depth = state.waiters.len();
+                drop(state);
+                self.stats[priority.index()]
+                    .rejected
+                    .fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    priority = priority.as_str(),
+                    depth,
+                    "Local generator queue full, rejecting request"
+                );
+                return Err(QueueFull {
+                    depth,
+                    retry_after_secs: self.retry_after_secs(depth),
+                });
+            }
+            let (tx, rx) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(Waiter { priority, seq, tx });
+            rx
+        };
+
+        // If this future is dropped while parked, `rx` is dropped and release()
+        // skips us.  If it is dropped after a permit was sent, the permit is
+        // dropped with the channel and passes the slot on.
+        let permit = rx.await.map_err(|_| QueueFull {
+            depth: 0,
+            retry_after_secs: 1,
+        })?;
+        self.record_admitted(priority, start.elapsed());
+        Ok(permit)
+    }
+
+    /// Take an idle lane without waiting, or `None` when every lane is busy.
+    ///
+    /// Used for speculative work that must never queue behind (or delay)
+    /// real requests; a failed attempt is not counted as a rejection.
+    pub fn try_acquire(self: &Arc<Self>, priority: RequestPriority) -> Option<QueuePermit> {
+        let (lane, epoch) = {
+            let mut state = self.state.lock().unwrap();
+            (state.free_lanes.pop()?, state.epoch)
+        };
+        self.record_admitted(priority, Duration::ZERO);
+        Some(QueuePermit::new(Arc::clone(self), priority, lane, epoch))
+    }
+
+    /// Snapshot counters for metrics.
+    pub fn snapshot(&self) -> QueueSnapshot {
+        let (active, lanes, mut waiting) = {
+            let state = self.state.lock().unwrap();
+            let mut waiting = [0usize; 3];
+            for w in state.waiters.iter() {
+                waiting[w.priority.index()] += 1;
+            }
+            (state.lanes - state.free_lanes.len(), state.lanes, waiting)
+        };
+        let classes = RequestPriority::ALL
+            .iter()
+            .map(|p| {
+                let s = &self.stats[p.index()];
+                ClassSnapshot {
+                    priority: p.as_str(),
+                    waiting: std::mem::take(&mut waiting[p.index()]),
+                    admitted: s.admitted.load(Ordering::Relaxed),
+                    rejected: s.rejected.load(Ordering::Relaxed),
+                    total_wait_ms: s.total_wait_ms.load(Ordering::Relaxed),
+                    max_wait_ms: s.max_wait_ms.load(Ordering::Relaxed),
+                }
+            })
+            .collect();
+        QueueSnapshot {
+            busy: active > 0,
+            active,
+            lanes,
+            max_depth: self.max_depth,
+            avg_service_ms: self.avg_service_ms.load(Ordering::Relaxed),
+            classes,
+        }
+    }
+
+    /// Number of requests currently waiting (excludes the ones being served).
+    pub fn depth(&self) -> usize {
+        self.state.lock().unwrap().waiters.len()
+    }
+
+    /// Number of lanes (free or in use).
+    pub fn lanes(&self) -> usize {
+        self.state.lock().unwrap().lanes
+    }
+
+    /// Open another lane once its generator is loaded, handing it straight to
+    /// the next waiter if there is one.  Returns the new lane's index.
+    pub fn add_lane(self: &Arc<Self>) -> usize {
+        let (lane, epoch) = {
+            let mut state = self.state.lock().unwrap();
+            state.lanes += 1;
+            (state.lanes - 1, state.epoch)
+        };
+        self.release(lane, epoch);
+        lane
+    }
+
+    /// Close every lane but the primary one, while their generators are
+    /// unloaded.  Requests already using one finish, but the lane is not
+    /// handed out again until `add_lane()` reopens it.
+    pub fn close_extra_lanes(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.lanes = 1;
+        state.epoch += 1;
+        state.free_lanes.retain(|&lane| lane == 0);
+    }
+
+    fn record_admitted(&self, priority: RequestPriority, waited: Duration) {
+        let stats = &self.stats[priority.index()];
+        let ms = waited.as_millis() as u64;
+        stats.admitted.fetch_add(1, Ordering::Relaxed);
+        stats.total_wait_ms.fetch_add(ms, Ordering::Relaxed);
+        stats.max_wait_ms.fetch_max(ms, Ordering::Relaxed);
+    }
+
+    fn record_service(&self, held: Duration) {
+        let ms = held.as_millis() as u64;
+        let prev = self.avg_service_ms.load(Ordering::Relaxed);
+        // EWMA with alpha = 1/4; first sample seeds the average.
+        let next = if prev == 0 { ms } else { (prev * 3 + ms) / 4 };
+        self.avg_service_ms.store(next, Ordering::Relaxed);
+    }
+
+    /// Estimate how long a rejected client should back off.
+    fn retry_after_secs(&self, depth: usize) -> u64 {
+        let avg_ms = self.avg_service_ms.load(Ordering::Relaxed).max(1000);
+        ((depth as u64 + 1) * avg_ms).div_ceil(1000).clamp(1, 300)
+    }
+
+    /// Hand `lane`, granted in `epoch`, to the next live waiter, or mark it
+    /// idle.  An extra lane closed since then is dropped instead.
+    fn release(self: &Arc<Self>, lane: usize, epoch: u64) {
+        loop {
+            let (waiter, epoch) = {
+                let mut state = self.state.lock().unwrap();
+                if lane > 0 && epoch != state.epoch {
+                    return;
+                }
+                match state.waiters.pop() {
+                    Some(w) => (w, state.epoch),
+                    None => {
+                        state.free_lanes.push(lane);
+                        return;
+                    }
+                }
+            };
+            let permit = QueuePermit::new(Arc::clone(self), waiter.priority, lane, epoch);
+            match waiter.tx.send(permit) {
+                Ok(()) => return,
+                Err(mut unclaimed) => {
+                    // Waiter gave up before being served; defuse and try the next one.
+                    unclaimed.queue = None;
+                }
+            }
+        }
+    }
+}
+
+/// Exclusive right to use one lane of the local generator.  Dropping it admits
+/// the next waiter.
+pub struct QueuePermit {
+    queue: Option<Arc<RequestQueue>>,
+    priority: RequestPriority,
+    lane: usize,
+    epoch: u64,
+    granted_at: Instant,
+}
+
+impl QueuePermit {
+    fn new(queue: Arc<RequestQueue>, priority: RequestPriority, lane: usize, epoch: u64) -> Self {
+        Self {
+            queue: Some(queue),
+            priority,
+            lane,
+            epoch,
+            granted_at: Instant::now(),
+        }
+    }
+
+    /// Priority class this permit was granted for.
+    pub fn priority(&self) -> RequestPriority {
+        self.priority
+    }
+
+    /// Lane (generator copy) this permit grants, 0 for the primary one.
+    pub fn lane(&self) -> usize {
+        self.lane
+    }
+}
+
+impl Drop for QueuePermit {
+    fn drop(&mut self) {
+        if let Some(queue) = self.queue.take() {
+            queue.record_service(self.granted_at.elapsed());
+            queue.release(self.lane, self.epoch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_idle_queue_admits_immediately() {
+        let queue = Arc::new(RequestQueue::new(4));
+        let permit = queue.acquire(RequestPriority::Interactive).await.unwrap();
+        assert!(queue.snapshot().busy);
+        drop(permit);
+        assert!(!queue.snapshot().busy);
+    }
+
+    #[tokio::test]
+    async fn test_try_acquire_only_takes_idle_lanes() {
+        let queue = Arc::new(RequestQueue::new(4));
+        let permit = queue.try_acquire(RequestPriority::Interactive).unwrap();
+        assert!(queue.try_acquire(RequestPriority::Interactive).is_none());
+        assert_eq!(queue.depth(), 0);
+        drop(permit);
+        assert!(queue.try_acquire(RequestPriority::Agent).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_when_full() {
+        let queue = Arc::new(RequestQueue::new(1));
+        let _held = queue.acquire(RequestPriority::Interactive).await.unwrap();
+
+        let q = Arc::clone(&queue);
+        let waiter = tokio::spawn(async move { q.acquire(RequestPriority::Agent).await });
+        while queue.depth() < 1 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = queue
+            .acquire(RequestPriority::Training)
+            .await
+            .err()
+            .expect("third request should be rejected");
+        assert_eq!(err.depth, 1);
+        assert!(err.retry_after_secs >= 1);
+        assert_eq!(queue.snapshot().classes[2].rejected, 1);
+
+        waiter.abort();
+    }
+
+    #[tokio::test]
+    async fn test_higher_priority_served_first() {
+        let queue = Arc::new(RequestQueue::new(8));
+        let held = queue.acquire(RequestPriority::Interactive).await.unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for p in [
+            RequestPriority::Training,
+            RequestPriority::Agent,
+            RequestPriority::Interactive,
+        ] {
+            let q = Arc::clone(&queue);
+            let o = Arc::clone(&order);
+            handles.push(tokio::spawn(async move {
+                let _permit = q.acquire(p).await.unwrap();
+                o.lock().unwrap().push(p);
+            }));
+            // Make sure each waiter is parked before the next one enqueues.
+            let expected = handles.len();
+            while queue.depth() < expected {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        drop(held);
+        for h in handles {
+            h.await.unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![
+                RequestPriority::Interactive,
+                RequestPriority::Agent,
+                RequestPriority::Training
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_wedge_queue() {
+        let queue = Arc::new(RequestQueue::new(4));
+        let held = queue.acquire(RequestPriority::Interactive).await.unwrap();
+
+        let q = Arc::clone(&queue);
+        let abandoned = tokio::spawn(async move { q.acquire(RequestPriority::Interactive).await });
+        while queue.depth() < 1 {
+            tokio::task::yield_now().await;
+        }
+        abandoned.abort();
+        let _ = abandoned.await;
+
+        drop(held);
+        let permit = tokio::time::timeout(
+            Duration::from_secs(1),
+            queue.acquire(RequestPriority::Agent),
+        )
+        .await
+        .expect("queue should not be wedged")
+        .unwrap();
+        assert_eq!(permit.priority(), RequestPriority::Agent);
+    }
+
+    #[tokio::test]
+    async fn test_added_lanes_serve_concurrently() {
+        let queue = Arc::new(RequestQueue::new(4));
+        let first = queue.acquire(RequestPriority::Interactive).await.unwrap();
+
+        // A waiter parked before the lane opens gets it immediately
+        let q = Arc::clone(&queue);
+        let waiter = tokio::spawn(async move { q.acquire(RequestPriority::Agent).await });
+        while queue.depth() < 1 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(queue.add_lane(), 1);
+        let second = waiter.await.unwrap().unwrap();
+        assert_eq!((first.lane(), second.lane()), (0, 1));
+
+        let snapshot = queue.snapshot();
+        assert_eq!((snapshot.active, snapshot.lanes), (2, 2));
+
+        // A freed lane is reused by the next request
+        drop(first);
+        let third = queue.acquire(RequestPriority::Training).await.unwrap();
+        assert_eq!(third.lane(), 0);
+        drop((second, third));
+        assert!(!queue.snapshot().busy);
+    }
+
+    #[tokio::test]
+    async fn test_closed_lanes_are_not_handed_out() {
+        let queue = Arc::new(RequestQueue::new(4));
+        let first = queue.acquire(RequestPriority::Interactive).await.unwrap();
+        queue.add_lane();
+        queue.add_lane();
+        let busy = queue.acquire(RequestPriority::Interactive).await.unwrap();
+        assert_ne!(busy.lane(), 0);
+
+        // One extra lane is idle, the other busy
+        queue.close_extra_lanes();
+        assert_eq!(queue.lanes(), 1);
+        assert!(queue.try_acquire(RequestPriority::Agent).is_none());
+        // A request finishing on a closed lane does not reopen it
+        drop(busy);
+        assert!(queue.try_acquire(RequestPriority::Agent).is_none());
+
+        // Reopened lanes are handed out once each
+        assert_eq!(queue.add_lane(), 1);
+        let second = queue.try_acquire(RequestPriority::Agent).unwrap();
+        assert_eq!(second.lane(), 1);
+        assert!(queue.try_acquire(RequestPriority::Agent).is_none());
+        drop((first, second));
+        assert_eq!(queue.snapshot().active, 0);
+    }
+
+    #[test]
+    fn test_priority_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            RequestPriority::from_headers(&headers),
+            RequestPriority::Interactive
+        );
+        headers.insert(PRIORITY_HEADER, "Training".parse().unwrap());
+        assert_eq!(
+            RequestPriority::from_headers(&headers),
+            RequestPriority::Training
+        );
+        headers.insert(PRIORITY_HEADER, "bogus".parse().unwrap());
+        assert_eq!(
+            RequestPriority::from_headers(&headers),
+            RequestPriority::Interactive
+        );
+    }
+
+    #[test]
+    fn test_prometheus_output_has_per_class_series() {
+        let queue = RequestQueue::new(4);
+        let text = queue.snapshot().to_prometheus();
+        assert!(text.contains("finch_queue_waiting{priority=\"interactive\"} 0"));
+        assert!(text.contains("finch_queue_rejected_total{priority=\"training\"} 0"));
+    }
+}
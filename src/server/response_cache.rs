@@ -0,0 +1,272 @@
+// Response cache — answer repeated questions without a model call
+//
+// Agent loops re-ask the same (or nearly the same) question constantly.  Each
+// response is stored under a hash of everything that shaped it — model, system
+// prompt, tools and the full message list — and replayed on an exact match.
+// With a similarity threshold configured, a request whose earlier context is
+// identical and whose final user message embeds close enough to a cached one
+// is served too; semantic hits only replay plain-text answers, never tool
+// calls, since arguments chosen for one question may be wrong for another.
+// Entries expire after a TTL and requests can opt out with `cache: false`.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::claude::ContentBlock;
+use crate::memory::{cosine_similarity, EmbeddingEngine, TfIdfEmbedding};
+
+/// Identifies a request for cache lookups
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheKey {
+    /// Hash of the whole request
+    exact: u64,
+    /// Hash of everything except the final message
+    context: u64,
+    /// Final user message text, when it is eligible for similarity matching
+    query: Option<String>,
+}
+
+impl CacheKey {
+    /// Build a key from the request's `context` (model, system prompt, tools,
+    /// earlier messages) and its final message.
+    ///
+    /// Pass `query` only when the final message is plain user text; tool
+    /// results and similar must match exactly.
+    pub fn new(context: &impl Serialize, last: &impl Serialize, query: Option<&str>) -> Self {
+        let context = hash_json(context);
+        let mut hasher = DefaultHasher::new();
+        context.hash(&mut hasher);
+        hash_json(last).hash(&mut hasher);
+        Self {
+            exact: hasher.finish(),
+            context,
+            query: query
+                .map(str::trim)
+                .filter(|q| !q.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
+
+fn hash_json(value: &impl Serialize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    // Serialization of in-memory request types cannot fail
+    serde_json::to_string(value)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// How a cached response was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheHit {
+    Exact,
+    Similar,
+}
+
+struct Entry {
+    exact: u64,
+    context: u64,
+    embedding: Option<Vec<f32>>,
+    content: Vec<ContentBlock>,
+    stored_at: Instant,
+}
+
+impl Entry {
+    fn is_text_only(&self) -> bool {
+        self.content
+            .iter()
+            .all(|block| matches!(block, ContentBlock::Text { .. }))
+    }
+}
+
+/// TTL-bounded response cache shared by the HTTP handlers
+pub struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    /// Minimum cosine similarity for a semantic hit (`None` = exact only)
+    similarity_threshold: Option<f32>,
+    embedder: TfIdfEmbedding,
+    entries: Mutex<VecDeque<Entry>>,
+    exact_hits: AtomicU64,
+    similar_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_entries: usize, similarity_threshold: Option<f32>) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            similarity_threshold,
+            embedder: TfIdfEmbedding::new(),
+            entries: Mutex::new(VecDeque::new()),
+            exact_hits: AtomicU64::new(0),
+            similar_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn embed(&self, key: &CacheKey) -> Option<Vec<f32>> {
+        self.similarity_threshold?;
+        self.embedder.embed(key.query.as_deref()?).ok()
+    }
+
+    /// Look up a response for `key`.
+    pub fn get(&self, key: &CacheKey) -> Option<(Vec<ContentBlock>, CacheHit)> {
+        let embedding = self.embed(key);
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = self.ttl;
+        entries.retain(|e| e.stored_at.elapsed() < ttl);
+
+        if let Some(entry) = entries.iter().find(|e| e.exact == key.exact) {
+            self.exact_hits.fetch_add(1, Ordering::Relaxed);
+            return Some((entry.content.clone(), CacheHit::Exact));
+        }
+
+        if let (Some(threshold), Some(embedding)) = (self.similarity_threshold, &embedding) {
+            let best = entries
+                .iter()
+                .filter(|e| e.context == key.context && e.is_text_only())
+                .filter_map(|e| {
+                    let score = cosine_similarity(embedding, e.embedding.as_deref()?);
+                    (score >= threshold).then_some((score, e))
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((score, entry)) = best {
+                tracing::debug!(similarity = score, "Response cache: similar hit");
+                self.similar_hits.fetch_add(1, Ordering::Relaxed);
+                return Some((entry.content.clone(), CacheHit::Similar));
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Store the response produced for `key`, evicting the oldest entry when full.
+    pub fn insert(&self, key: &CacheKey, content: Vec<ContentBlock>) {
+        if self.max_entries == 0 || content.is_empty() {
+            return;
+        }
+        let embedding = self.embed(key);
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|e| e.exact != key.exact);
+        while entries.len() >= self.max_entries {
+            entries.pop_front();
+        }
+        entries.push_back(Entry {
+            exact: key.exact,
+            context: key.context,
+            embedding,
+            content,
+            stored_at: Instant::now(),
+        });
+    }
+
+    /// Prometheus counters for `/metrics`
+    pub fn to_prometheus(&self) -> String {
+        format!(
+            "# HELP finch_response_cache_hits_total Requests answered from the response cache\n\
+             # TYPE finch_response_cache_hits_total counter\n\
+             finch_response_cache_hits_total{{match=\"exact\"}} {}\n\
+             finch_response_cache_hits_total{{match=\"similar\"}} {}\n\
+             # HELP finch_response_cache_misses_total Cacheable requests not found in the cache\n\
+             # TYPE finch_response_cache_misses_total counter\n\
+             finch_response_cache_misses_total {}\n",
+            self.exact_hits.load(Ordering::Relaxed),
+            self.similar_hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn key(system: &str, question: &str) -> CacheKey {
+        CacheKey::new(&("model", system), &question, Some(question))
+    }
+
+    fn text(s: &str) -> Vec<ContentBlock> {
+        vec![ContentBlock::Text {
+            text: s.to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_exact_hit_requires_identical_request() {
+        let cache = ResponseCache::new(TTL, 10, None);
+        cache.insert(&key("sys", "what is a monad"), text("a monoid"));
+
+        let (content, hit) = cache.get(&key("sys", "what is a monad")).unwrap();
+        assert_eq!(hit, CacheHit::Exact);
+        assert!(matches!(&content[..], [ContentBlock::Text { text }] if text == "a monoid"));
+
+        // Different system prompt, or a near-miss question without a threshold
+        assert!(cache.get(&key("other", "what is a monad")).is_none());
+        assert!(cache.get(&key("sys", "what is a monad?")).is_none());
+    }
+
+    #[test]
+    fn test_similar_hit_needs_same_context_and_text_answer() {
+        let cache = ResponseCache::new(TTL, 10, Some(0.9));
+        cache.insert(
+            &key("sys", "how do I read a file in rust"),
+            text("std::fs::read_to_string"),
+        );
+
+        let (_, hit) = cache
+            .get(&key("sys", "how do I read a file in rust?"))
+            .unwrap();
+        assert_eq!(hit, CacheHit::Similar);
+        assert!(cache
+            .get(&key("other", "how do I read a file in rust?"))
+            .is_none());
+        assert!(cache.get(&key("sys", "explain lifetimes")).is_none());
+
+        // Tool calls are only replayed for exact matches
+        cache.insert(
+            &key("sys", "list the files here"),
+            vec![ContentBlock::ToolUse {
+                id: "t1".to_string(),
+                name: "glob".to_string(),
+                input: serde_json::json!({"pattern": "*"}),
+            }],
+        );
+        assert!(cache.get(&key("sys", "list the files here")).is_some());
+        assert!(cache.get(&key("sys", "list the files here?")).is_none());
+    }
+
+    #[test]
+    fn test_tool_results_never_match_by_similarity() {
+        let cache = ResponseCache::new(TTL, 10, Some(0.5));
+        let result_key = |r: &str| CacheKey::new(&("model", "sys"), &r, None);
+        cache.insert(&result_key("exit 0"), text("done"));
+        assert!(cache.get(&result_key("exit 1")).is_none());
+    }
+
+    #[test]
+    fn test_entries_expire_and_capacity_is_bounded() {
+        let cache = ResponseCache::new(Duration::ZERO, 10, None);
+        cache.insert(&key("sys", "q"), text("a"));
+        assert!(cache.get(&key("sys", "q")).is_none());
+
+        let cache = ResponseCache::new(TTL, 2, None);
+        for q in ["a", "b", "c"] {
+            cache.insert(&key("sys", q), text(q));
+        }
+        assert!(cache.get(&key("sys", "a")).is_none());
+        assert!(cache.get(&key("sys", "c")).is_some());
+        assert!(cache
+            .to_prometheus()
+            .contains("finch_response_cache_misses_total 1"));
+    }
+}
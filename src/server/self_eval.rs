@@ -0,0 +1,494 @@
+// Idle-time self-evaluation of the local model
+//
+// The router only learns from queries it actually tried locally, so a
+// category it has learned to forward never gets another chance once the
+// adapter improves.  With `[self_eval]` enabled the daemon keeps the most
+// recent questions a teacher answered, and whenever `[schedule]` lets the
+// `eval` job run it replays a rotating sample of them through the current
+// local model and adapter.  A judge provider grades each local answer against
+// the teacher's, and the verdicts feed the routing policy's calibration:
+//
+//   [self_eval]
+//   enabled = true
+//   interval_minutes = 60
+//   sample_size = 10
+//   judge = "claude"     # default: the first provider
+//   pass_score = 7.0
+//
+//   [[schedule.rules]]
+//   jobs = ["eval"]
+//   only = "01:00-06:00"
+//
+// Replays only take an idle lane of the local model, so they never delay
+// real requests; a run that finds the model busy stops and the next one picks
+// up where it left off.  Each run is summarised in ~/.finch/eval/history.jsonl
+// (counts and scores only, no queries).
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{AgentServer, LocalModelSlot, RequestPriority};
+use crate::claude::Message;
+use crate::scheduling::BackgroundJob;
+
+/// Teacher answers kept for replay; the oldest are dropped first
+const MAX_SAMPLES: usize = 500;
+
+/// `[self_eval]` in config.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfEvalConfig {
+    /// Keep teacher answers and replay them through the local model
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes between runs (each run still waits for `[schedule]`)
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u64,
+    /// Teacher answers replayed per run
+    #[serde(default = "default_sample_size")]
+    pub sample_size: usize,
+    /// Provider that grades the local answers (default: the first provider)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge: Option<String>,
+    /// Judge score (0–10) at which a local answer counts as good enough
+    #[serde(default = "default_pass_score")]
+    pub pass_score: f64,
+}
+
+fn default_interval_minutes() -> u64 {
+    60
+}
+
+fn default_sample_size() -> usize {
+    10
+}
+
+fn default_pass_score() -> f64 {
+    7.0
+}
+
+impl Default for SelfEvalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_interval_minutes(),
+            sample_size: default_sample_size(),
+            judge: None,
+            pass_score: default_pass_score(),
+        }
+    }
+}
+
+impl SelfEvalConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_minutes == 0 {
+            bail!("[self_eval] interval_minutes must be at least 1");
+        }
+        if self.sample_size == 0 {
+            bail!("[self_eval] sample_size must be at least 1");
+        }
+        if !(0.0..=10.0).contains(&self.pass_score) {
+            bail!(
+                "[self_eval] pass_score must be between 0 and 10 (got {})",
+                self.pass_score
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A question a teacher answered, kept as a reference for replays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeacherSample {
+    pub query: String,
+    pub answer: String,
+    /// Provider that answered
+    pub provider: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// The most recent teacher answers, in a JSONL file
+/// (~/.finch/eval/teacher_samples.jsonl)
+pub struct TeacherSamples {
+    path: PathBuf,
+    capacity: usize,
+    /// Lines in the file, once counted
+    lines: Mutex<Option<usize>>,
+}
+
+impl TeacherSamples {
+    pub fn new(path: PathBuf, capacity: usize) -> Self {
+        Self {
+            path,
+            capacity: capacity.max(1),
+            lines: Mutex::new(None),
+        }
+    }
+
+    /// The store in ~/.finch/eval
+    pub fn open_default() -> Option<Self> {
+        let dir = dirs::home_dir()?.join(".finch").join("eval");
+        Some(Self::new(dir.join("teacher_samples.jsonl"), MAX_SAMPLES))
+    }
+
+    /// Append a sample (redacted like the training queue).  Once the file
+    /// holds twice `capacity` samples it is cut back to the newest
+    /// `capacity`.
+    pub fn record(&self, sample: &TeacherSample) -> Result<()> {
+        let mut lines = self.lines.lock().unwrap();
+        let count = match *lines {
+            Some(count) => count,
+            None => self.load()?.len(),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let line = crate::redact::active().to_json_line(sample)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{}", line)?;
+        drop(file);
+
+        let mut count = count + 1;
+        if count >= self.capacity * 2 {
+            let samples = self.load()?;
+            let keep = &samples[samples.len().saturating_sub(self.capacity)..];
+            let mut contents = String::new();
+            for sample in keep {
+                contents.push_str(&serde_json::to_string(sample)?);
+                contents.push('\n');
+            }
+            let tmp = self.path.with_extension("tmp");
+            std::fs::write(&tmp, contents)?;
+            std::fs::rename(&tmp, &self.path)?;
+            count = keep.len();
+        }
+        *lines = Some(count);
+        Ok(())
+    }
+
+    /// Every stored sample, oldest first (unreadable lines are skipped)
+    pub fn load(&self) -> Result<Vec<TeacherSample>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.path.display()))
+            }
+        };
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+/// `n` of `samples` starting at `offset`, wrapping around (never the same
+/// sample twice in one batch)
+fn rotate<T>(samples: &[T], offset: usize, n: usize) -> Vec<&T> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    (0..n.min(samples.len()))
+        .map(|i| &samples[(offset + i) % samples.len()])
+        .collect()
+}
+
+/// Summary of one self-evaluation run, appended to history.jsonl
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub run_at: DateTime<Utc>,
+    /// Samples replayed and scored (a question the local model declines
+    /// scores 0)
+    pub evaluated: usize,
+    /// Of those, how many scored at least `pass_score`
+    pub passed: usize,
+    /// Mean judge score (0–10), `None` when nothing was scored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_score: Option<f64>,
+    /// Where the next run starts in the sample rotation
+    pub next_offset: usize,
+}
+
+impl EvalRun {
+    fn new(scores: &[f64], pass_score: f64, next_offset: usize) -> Self {
+        Self {
+            run_at: Utc::now(),
+            evaluated: scores.len(),
+            passed: scores.iter().filter(|&&s| s >= pass_score).count(),
+            avg_score: (!scores.is_empty())
+                .then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+            next_offset,
+        }
+    }
+
+    /// Share of replayed questions the local model handled well enough
+    pub fn pass_rate(&self) -> Option<f64> {
+        (self.evaluated > 0).then(|| self.passed as f64 / self.evaluated as f64)
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join(".finch")
+            .join("eval")
+            .join("history.jsonl"),
+    )
+}
+
+fn append_history(path: &Path, run: &EvalRun) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(run)?)?;
+    Ok(())
+}
+
+/// Where the last recorded run left the rotation
+fn last_offset(path: &Path) -> usize {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| {
+            contents
+                .lines()
+                .rev()
+                .find_map(|line| serde_json::from_str::<EvalRun>(line).ok())
+        })
+        .map_or(0, |run| run.next_offset)
+}
+
+/// Replay teacher answers every `interval_minutes` while `[schedule]` allows
+/// the `eval` job.  Runs for the life of the daemon.
+pub async fn run(server: Arc<AgentServer>, samples: Arc<TeacherSamples>, config: SelfEvalConfig) {
+    let history = history_path();
+    let mut offset = history.as_deref().map_or(0, last_offset);
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_minutes * 60));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // The first tick is immediate; let the daemon settle first
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if !server.schedule().allows_now(BackgroundJob::Eval) {
+            tracing::debug!(
+                "Quiet hours: self-evaluation deferred {}",
+                server.schedule().describe_wait(BackgroundJob::Eval)
+            );
+            continue;
+        }
+        match run_once(&server, &samples, &config, offset).await {
+            Ok(Some(run)) => {
+                offset = run.next_offset;
+                tracing::info!(
+                    evaluated = run.evaluated,
+                    passed = run.passed,
+                    avg_score = ?run.avg_score,
+                    "Self-evaluation finished"
+                );
+                if let Some(path) = &history {
+                    if let Err(e) = append_history(path, &run) {
+                        tracing::warn!("Failed to record self-evaluation: {}", e);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Self-evaluation failed: {:#}", e),
+        }
+    }
+}
+
+/// One run: replay a batch from `offset`, grade it and calibrate the router.
+/// `None` when there was nothing to do (no samples, model not loaded, no
+/// judge, or the model busy from the start).
+async fn run_once(
+    server: &AgentServer,
+    samples: &TeacherSamples,
+    config: &SelfEvalConfig,
+    offset: usize,
+) -> Result<Option<EvalRun>> {
+    let all = samples.load()?;
+    if all.is_empty() {
+        tracing::debug!("Self-evaluation: no teacher answers to replay yet");
+        return Ok(None);
+    }
+    let slot = Arc::clone(server.local_models().primary());
+    if !slot.generator_state.read().await.is_ready() {
+        tracing::debug!("Self-evaluation: local model not loaded");
+        return Ok(None);
+    }
+    let Some(judge) = server.provider_for_name(config.judge.as_deref()).cloned() else {
+        tracing::warn!("Self-evaluation needs a configured provider to judge answers");
+        return Ok(None);
+    };
+
+    let mut scores = Vec::new();
+    let mut attempted = 0;
+    for sample in rotate(&all, offset, config.sample_size) {
+        let Some(local) = answer_locally(&slot, &sample.query).await else {
+            tracing::debug!("Self-evaluation: local model busy, stopping this run");
+            break;
+        };
+        attempted += 1;
+        let score = match local {
+            // The local model declined: it can't handle this one
+            Ok(None) => 0.0,
+            Ok(Some(answer)) => {
+                let rubric = format!(
+                    "About as correct, complete and useful as this reference answer:\n{}",
+                    sample.answer
+                );
+                match crate::metrics::judge_answer(judge.as_ref(), &sample.query, &rubric, &answer)
+                    .await
+                {
+                    Ok(score) => score,
+                    Err(e) => {
+                        tracing::warn!("Judge could not score a replayed answer: {}", e);
+                        continue;
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Local generation failed during self-evaluation: {}", e);
+                continue;
+            }
+        };
+        server
+            .router()
+            .write()
+            .await
+            .record_evaluation(&sample.query, score >= config.pass_score);
+        scores.push(score);
+    }
+    if attempted == 0 {
+        return Ok(None);
+    }
+
+    if !scores.is_empty() {
+        if let Err(e) = server.save_router().await {
+            tracing::warn!("Failed to save router calibration: {}", e);
+        }
+    }
+    let next_offset = (offset + attempted) % all.len();
+    Ok(Some(EvalRun::new(&scores, config.pass_score, next_offset)))
+}
+
+/// The local model's answer to `query` on an idle lane; `None` when every
+/// lane is busy, `Ok(None)` when the model declined
+async fn answer_locally(slot: &LocalModelSlot, query: &str) -> Option<Result<Option<String>>> {
+    let permit = slot.request_queue.try_acquire(RequestPriority::Training)?;
+    let generator = Arc::clone(slot.generator(permit.lane()));
+    let messages = vec![Message::user(query)];
+    let result = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        generator
+            .blocking_write()
+            .try_generate_from_pattern_with_tools(&messages, None)
+    })
+    .await
+    .context("Self-evaluation task failed")
+    .and_then(|result| result)
+    .map(|response| {
+        response
+            .map(|r| r.text)
+            .filter(|text| !text.trim().is_empty())
+    });
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(query: &str) -> TeacherSample {
+        TeacherSample {
+            query: query.to_string(),
+            answer: format!("answer to {}", query),
+            provider: "claude".to_string(),
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_store_keeps_the_newest_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TeacherSamples::new(dir.path().join("samples.jsonl"), 3);
+        assert!(store.load().unwrap().is_empty());
+
+        for i in 0..5 {
+            store.record(&sample(&format!("q{}", i))).unwrap();
+        }
+        // Six would trigger the cut; five are all still there
+        assert_eq!(store.load().unwrap().len(), 5);
+        store.record(&sample("q5")).unwrap();
+        let queries: Vec<_> = store.load().unwrap().into_iter().map(|s| s.query).collect();
+        assert_eq!(queries, ["q3", "q4", "q5"]);
+
+        // A store reopened on the same file counts what is already there
+        let reopened = TeacherSamples::new(dir.path().join("samples.jsonl"), 3);
+        reopened.record(&sample("q6")).unwrap();
+        assert_eq!(reopened.load().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_rotation_wraps_around() {
+        let samples = ["a", "b", "c", "d"];
+        assert_eq!(rotate(&samples, 0, 3), [&"a", &"b", &"c"]);
+        assert_eq!(rotate(&samples, 3, 3), [&"d", &"a", &"b"]);
+        assert_eq!(rotate(&samples, 1, 10).len(), 4);
+        assert!(rotate::<&str>(&[], 2, 3).is_empty());
+    }
+
+    #[test]
+    fn test_runs_summarise_scores() {
+        let run = EvalRun::new(&[9.0, 6.5, 7.0, 2.0], 7.0, 4);
+        assert_eq!(run.passed, 2);
+        assert_eq!(run.avg_score, Some(6.125));
+        assert_eq!(run.pass_rate(), Some(0.5));
+
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join("history.jsonl");
+        assert_eq!(last_offset(&history), 0);
+        append_history(&history, &run).unwrap();
+        append_history(&history, &EvalRun::new(&[], 7.0, 9)).unwrap();
+        assert_eq!(last_offset(&history), 9);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = SelfEvalConfig::default();
+        assert!(config.is_default());
+        assert!(!config.enabled);
+        config.validate().unwrap();
+
+        let parsed: SelfEvalConfig = toml::from_str("enabled = true\njudge = \"openai\"").unwrap();
+        assert_eq!(parsed.sample_size, 10);
+        assert_eq!(parsed.judge.as_deref(), Some("openai"));
+
+        for bad in [
+            "sample_size = 0",
+            "interval_minutes = 0",
+            "pass_score = 11.0",
+        ] {
+            let config: SelfEvalConfig = toml::from_str(bad).unwrap();
+            assert!(config.validate().is_err(), "{}", bad);
+        }
+    }
+}
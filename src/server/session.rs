@@ -1,9 +1,27 @@
 // Session management for concurrent HTTP clients
-
+//
+// Sessions live in memory.  When a store is configured (a local directory by
+// default, or a bucket with `[storage] backend = "s3"`) they are written to
+// `<session-id>.json` on shutdown and restored lazily the first time a client
+// presents a known id, so restarting the daemon (e.g. for an upgrade) doesn't
+// wipe every client's conversation.  With a shared bucket the client may even
+// come back to a different daemon.  Routing state is daemon-wide, not part of
+// a session, and is saved alongside by `AgentServer::drain_and_flush`.
+//
+// Each session also accumulates token and estimated-cost usage, and the
+// manager keeps daemon-wide totals (including sessionless OpenAI-style
+// requests) for `/health`.
+
+use crate::claude::Message;
 use crate::cli::ConversationHistory;
+use crate::generators::Attribution;
+use crate::storage::{LocalStorage, StorageBackend};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time;
 use uuid::Uuid;
@@ -19,6 +37,65 @@ pub struct SessionState {
     pub last_activity: DateTime<Utc>,
     /// Session creation time
     pub created_at: DateTime<Utc>,
+    /// Tokens and estimated spend so far
+    pub usage: SessionUsage,
+    /// Workspace that created the session (`None` on a single-tenant daemon)
+    pub workspace: Option<String>,
+}
+
+/// Token and cost accounting for one session (or the whole daemon)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    /// Model calls made (cache hits are free and not counted)
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Estimated spend in USD from `metrics::pricing`
+    pub cost_usd: f64,
+    /// Calls to models missing from the price table, excluded from `cost_usd`
+    pub unpriced_requests: u64,
+    /// Hidden reasoning tokens, not part of `output_tokens`
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+    /// Share of `cost_usd` spent on reasoning, billed at the output price
+    #[serde(default)]
+    pub reasoning_cost_usd: f64,
+}
+
+impl SessionUsage {
+    /// Usage of a single call to `model`
+    pub fn call(model: &str, input_tokens: u32, output_tokens: u32) -> Self {
+        let cost = crate::metrics::pricing::estimate_cost_usd(model, input_tokens, output_tokens);
+        Self {
+            requests: 1,
+            input_tokens: input_tokens as u64,
+            output_tokens: output_tokens as u64,
+            cost_usd: cost.unwrap_or(0.0),
+            unpriced_requests: cost.is_none() as u64,
+            reasoning_tokens: 0,
+            reasoning_cost_usd: 0.0,
+        }
+    }
+
+    /// Add the reasoning tokens `model` spent on this call
+    pub fn with_reasoning(mut self, model: &str, reasoning_tokens: u32) -> Self {
+        let cost =
+            crate::metrics::pricing::estimate_cost_usd(model, 0, reasoning_tokens).unwrap_or(0.0);
+        self.reasoning_tokens += reasoning_tokens as u64;
+        self.reasoning_cost_usd += cost;
+        self.cost_usd += cost;
+        self
+    }
+
+    pub fn add(&mut self, other: &SessionUsage) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost_usd += other.cost_usd;
+        self.unpriced_requests += other.unpriced_requests;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.reasoning_cost_usd += other.reasoning_cost_usd;
+    }
 }
 
 impl SessionState {
@@ -29,9 +106,17 @@ impl SessionState {
             conversation: ConversationHistory::new(),
             last_activity: Utc::now(),
             created_at: Utc::now(),
+            usage: SessionUsage::default(),
+            workspace: None,
         }
     }
 
+    /// Whether a caller in `workspace` may use this session.  Without a
+    /// workspace (single-tenant daemon) every session is visible.
+    pub fn belongs_to(&self, workspace: Option<&str>) -> bool {
+        workspace.is_none() || self.workspace.as_deref() == workspace
+    }
+
     /// Update last activity timestamp
     pub fn touch(&mut self) {
         self.last_activity = Utc::now();
@@ -51,6 +136,53 @@ impl Default for SessionState {
     }
 }
 
+/// On-disk form of a session.  Only messages are stored so that a restored
+/// `ConversationHistory` picks up the current default limits.
+#[derive(Serialize, Deserialize)]
+struct PersistedSession {
+    id: String,
+    messages: Vec<Message>,
+    /// Generator of each message; absent in files written before attribution
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attribution: Vec<Option<Attribution>>,
+    last_activity: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    /// Absent in files written before usage tracking
+    #[serde(default)]
+    usage: SessionUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workspace: Option<String>,
+}
+
+impl From<&SessionState> for PersistedSession {
+    fn from(session: &SessionState) -> Self {
+        Self {
+            id: session.id.clone(),
+            messages: session.conversation.snapshot(),
+            attribution: session.conversation.attributions().to_vec(),
+            last_activity: session.last_activity,
+            created_at: session.created_at,
+            usage: session.usage.clone(),
+            workspace: session.workspace.clone(),
+        }
+    }
+}
+
+impl From<PersistedSession> for SessionState {
+    fn from(persisted: PersistedSession) -> Self {
+        let mut conversation = ConversationHistory::new();
+        conversation.restore_attributed(persisted.messages, persisted.attribution);
+        Self {
+            id: persisted.id,
+            conversation,
+            last_activity: persisted.last_activity,
+            created_at: persisted.created_at,
+            usage: persisted.usage,
+            workspace: persisted.workspace,
+        }
+    }
+}
+
 /// Concurrent session manager using DashMap
 pub struct SessionManager {
     /// Active sessions (thread-safe concurrent HashMap)
@@ -59,6 +191,12 @@ pub struct SessionManager {
     max_sessions: usize,
     /// Session timeout in minutes
     timeout_minutes: u64,
+    /// Where sessions are persisted across restarts (`None` = memory only)
+    storage: Option<Arc<dyn StorageBackend>>,
+    /// Cleared when the daemon starts draining; only live sessions are served
+    accepting: AtomicBool,
+    /// Usage across every request since the daemon started
+    totals: Mutex<SessionUsage>,
 }
 
 impl SessionManager {
@@ -68,6 +206,9 @@ impl SessionManager {
             sessions: Arc::new(DashMap::new()),
             max_sessions,
             timeout_minutes,
+            storage: None,
+            accepting: AtomicBool::new(true),
+            totals: Mutex::new(SessionUsage::default()),
         };
 
         // Start background cleanup task
@@ -76,17 +217,66 @@ impl SessionManager {
         manager
     }
 
+    /// Persist sessions to `dir` on shutdown and restore them on demand
+    pub fn with_persist_dir(self, dir: PathBuf) -> anyhow::Result<Self> {
+        Ok(self.with_storage(Arc::new(LocalStorage::new(dir)?)))
+    }
+
+    /// Persist sessions to `storage` on shutdown and restore them on demand
+    pub fn with_storage(mut self, storage: Arc<dyn StorageBackend>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
     /// Get or create a session
-    pub fn get_or_create(&self, session_id: Option<&str>) -> anyhow::Result<SessionState> {
+    pub async fn get_or_create(&self, session_id: Option<&str>) -> anyhow::Result<SessionState> {
+        self.get_or_create_in(session_id, None).await
+    }
+
+    /// Get or create a session on behalf of `workspace`.  Another
+    /// workspace's session id is treated as unknown, so a new one is created.
+    pub async fn get_or_create_in(
+        &self,
+        session_id: Option<&str>,
+        workspace: Option<&str>,
+    ) -> anyhow::Result<SessionState> {
         // If session_id provided, try to retrieve existing session
         if let Some(id) = session_id {
-            if let Some(mut session) = self.sessions.get_mut(id) {
-                session.touch();
-                return Ok(session.clone());
+            // `Some(None)`: live, but another workspace's.  The map guard is
+            // dropped here, before restoring awaits the store.
+            let live = self.sessions.get_mut(id).map(|mut session| {
+                let owned = session.belongs_to(workspace);
+                if owned {
+                    session.touch();
+                }
+                owned.then(|| session.clone())
+            });
+            match live {
+                Some(Some(session)) => return Ok(session),
+                Some(None) => {}
+                None => {
+                    if let Some(mut session) = self.restore(id).await {
+                        // Not in memory — it was persisted by a previous daemon
+                        let owned = session.belongs_to(workspace);
+                        if owned {
+                            session.touch();
+                        }
+                        self.sessions.insert(session.id.clone(), session.clone());
+                        tracing::info!(session_id = %id, "Restored persisted session");
+                        if owned {
+                            return Ok(session);
+                        }
+                    }
+                }
             }
             // Session not found, will create new one below
         }
 
+        // Shutting down: in-memory sessions keep working, nothing new starts
+        if !self.accepting.load(Ordering::SeqCst) {
+            return Err(super::Draining::default().into());
+        }
+
         // Check session limit
         if self.sessions.len() >= self.max_sessions {
             anyhow::bail!(
@@ -97,7 +287,8 @@ impl SessionManager {
         }
 
         // Create new session
-        let session = SessionState::new();
+        let mut session = SessionState::new();
+        session.workspace = workspace.map(str::to_string);
         let id = session.id.clone();
         self.sessions.insert(id.clone(), session.clone());
 
@@ -105,6 +296,12 @@ impl SessionManager {
         Ok(session)
     }
 
+    /// Refuse new (and restored) sessions from now on.  Called when the
+    /// daemon starts draining for shutdown.
+    pub fn stop_accepting(&self) {
+        self.accepting.store(false, Ordering::SeqCst);
+    }
+
     /// Update session state
     pub fn update(&self, session_id: &str, session: SessionState) -> anyhow::Result<()> {
         if let Some(mut entry) = self.sessions.get_mut(session_id) {
@@ -115,9 +312,128 @@ impl SessionManager {
         }
     }
 
+    /// Whether a live or persisted session exists and `workspace` may use it
+    pub async fn belongs_to(&self, session_id: &str, workspace: Option<&str>) -> bool {
+        if let Some(session) = self.sessions.get(session_id) {
+            return session.belongs_to(workspace);
+        }
+        self.peek(session_id)
+            .await
+            .is_some_and(|p| workspace.is_none() || p.workspace.as_deref() == workspace)
+    }
+
     /// Delete a session
-    pub fn delete(&self, session_id: &str) -> bool {
-        self.sessions.remove(session_id).is_some()
+    pub async fn delete(&self, session_id: &str) -> bool {
+        let removed_object = match (&self.storage, session_key(session_id)) {
+            (Some(storage), Some(key)) => storage.delete(&key).await.unwrap_or_else(|e| {
+                tracing::warn!(session_id = %session_id, "Failed to delete persisted session: {}", e);
+                false
+            }),
+            _ => false,
+        };
+        self.sessions.remove(session_id).is_some() || removed_object
+    }
+
+    /// Add one call's usage to the daemon totals and, when given and still
+    /// live, to that session.
+    ///
+    /// Call after `update`, which replaces the whole session state.
+    pub fn record_usage(&self, session_id: Option<&str>, usage: &SessionUsage) {
+        self.totals.lock().unwrap().add(usage);
+        if let Some(mut session) = session_id.and_then(|id| self.sessions.get_mut(id)) {
+            session.usage.add(usage);
+        }
+    }
+
+    /// Usage of a live or persisted session, `None` if it is unknown.
+    pub async fn usage(&self, session_id: &str) -> Option<SessionUsage> {
+        if let Some(session) = self.sessions.get(session_id) {
+            return Some(session.usage.clone());
+        }
+        Some(self.peek(session_id).await?.usage)
+    }
+
+    /// Read a persisted session without consuming it; the client may resume
+    /// it later
+    async fn peek(&self, session_id: &str) -> Option<PersistedSession> {
+        let key = session_key(session_id)?;
+        let bytes = self.storage.as_ref()?.get(&key).await.ok()??;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Usage across every request since the daemon started
+    pub fn total_usage(&self) -> SessionUsage {
+        self.totals.lock().unwrap().clone()
+    }
+
+    /// Write every live session to the store.
+    ///
+    /// Called on daemon shutdown.  Returns the number of sessions written;
+    /// a no-op when persistence is disabled.
+    pub async fn persist_all(&self) -> anyhow::Result<usize> {
+        let Some(storage) = &self.storage else {
+            return Ok(0);
+        };
+        // Snapshot first: DashMap guards must not be held across an await
+        let snapshots: Vec<(String, Vec<u8>)> = self
+            .sessions
+            .iter()
+            .filter(|entry| !entry.value().is_expired(self.timeout_minutes))
+            .filter_map(|entry| {
+                let key = session_key(entry.key())?;
+                let bytes = serde_json::to_vec(&PersistedSession::from(entry.value()));
+                Some(bytes.map(|bytes| (key, bytes)))
+            })
+            .collect::<Result<_, _>>()?;
+        let written = snapshots.len();
+        for (key, bytes) in snapshots {
+            storage.put(&key, bytes).await?;
+        }
+        tracing::info!(count = written, location = %storage.location(), "Persisted sessions");
+        Ok(written)
+    }
+
+    /// Load a persisted session, consuming it.  Expired or unreadable
+    /// sessions are removed and yield `None`.
+    async fn restore(&self, session_id: &str) -> Option<SessionState> {
+        let key = session_key(session_id)?;
+        let storage = self.storage.as_ref()?;
+        let bytes = storage.get(&key).await.ok()??;
+        let _ = storage.delete(&key).await;
+        match serde_json::from_slice::<PersistedSession>(&bytes).map(SessionState::from) {
+            Ok(session) if !session.is_expired(self.timeout_minutes) => Some(session),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(session_id = %session_id, "Discarding unreadable session file: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Remove expired or corrupt sessions left in the store by earlier runs.
+    /// Called once when the daemon starts serving; returns how many went.
+    pub async fn prune_persisted(&self) -> anyhow::Result<usize> {
+        let Some(storage) = &self.storage else {
+            return Ok(0);
+        };
+        let mut removed = 0;
+        for key in storage.list().await? {
+            if !key.ends_with(".json") || key.contains('/') {
+                continue;
+            }
+            let keep = storage
+                .get(&key)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|b| serde_json::from_slice::<PersistedSession>(&b).ok())
+                .map(SessionState::from)
+                .is_some_and(|s| !s.is_expired(self.timeout_minutes));
+            if !keep && storage.delete(&key).await.unwrap_or(false) {
+                removed += 1;
+            }
+        }
+        Ok(removed)
     }
 
     /// Get active session count
@@ -190,6 +506,17 @@ impl SessionManager {
     }
 }
 
+/// Store key for a session id.  Ids come from clients, so anything that
+/// isn't a plain `[A-Za-z0-9_-]` token (path separators, `..`) is rejected.
+fn session_key(session_id: &str) -> Option<String> {
+    let valid = !session_id.is_empty()
+        && session_id.len() <= 128
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| format!("{}.json", session_id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,11 +527,11 @@ mod tests {
         let manager = SessionManager::new(10, 30);
 
         // Create first session
-        let session1 = manager.get_or_create(None).unwrap();
+        let session1 = manager.get_or_create(None).await.unwrap();
         assert_eq!(manager.active_count(), 1);
 
         // Create second session
-        let session2 = manager.get_or_create(None).unwrap();
+        let session2 = manager.get_or_create(None).await.unwrap();
         assert_eq!(manager.active_count(), 2);
 
         // Different session IDs
@@ -216,11 +543,11 @@ mod tests {
         let manager = SessionManager::new(10, 30);
 
         // Create session
-        let session1 = manager.get_or_create(None).unwrap();
+        let session1 = manager.get_or_create(None).await.unwrap();
         let session_id = session1.id.clone();
 
         // Retrieve same session
-        let session2 = manager.get_or_create(Some(&session_id)).unwrap();
+        let session2 = manager.get_or_create(Some(&session_id)).await.unwrap();
         assert_eq!(session1.id, session2.id);
         assert_eq!(manager.active_count(), 1); // Still only 1 session
     }
@@ -230,11 +557,11 @@ mod tests {
         let manager = SessionManager::new(2, 30);
 
         // Create 2 sessions (at limit)
-        manager.get_or_create(None).unwrap();
-        manager.get_or_create(None).unwrap();
+        manager.get_or_create(None).await.unwrap();
+        manager.get_or_create(None).await.unwrap();
 
         // Third session should fail
-        let result = manager.get_or_create(None);
+        let result = manager.get_or_create(None).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -242,20 +569,201 @@ mod tests {
             .contains("Maximum session limit"));
     }
 
+    #[tokio::test]
+    async fn test_draining_refuses_new_sessions_only() {
+        let manager = SessionManager::new(10, 30);
+        let existing = manager.get_or_create(None).await.unwrap();
+
+        manager.stop_accepting();
+
+        // A session already in flight keeps working
+        let again = manager.get_or_create(Some(&existing.id)).await.unwrap();
+        assert_eq!(again.id, existing.id);
+        // New ones are refused with a 503-mapped error
+        let err = manager.get_or_create(None).await.unwrap_err();
+        assert!(err.downcast_ref::<crate::server::Draining>().is_some());
+        assert_eq!(manager.active_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let session_id = {
+            let manager = SessionManager::new(10, 30)
+                .with_persist_dir(dir.path().to_path_buf())
+                .unwrap();
+            let mut session = manager.get_or_create(None).await.unwrap();
+            session
+                .conversation
+                .add_user_message("remember me".to_string());
+            session.conversation.add_attributed_message(
+                Message::assistant("I will"),
+                Attribution::local("Qwen2.5-3B"),
+            );
+            manager.update(&session.id, session.clone()).unwrap();
+            assert_eq!(manager.persist_all().await.unwrap(), 1);
+            session.id
+        };
+
+        // "Restarted" daemon: nothing in memory until the client comes back
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        assert_eq!(manager.active_count(), 0);
+
+        let restored = manager.get_or_create(Some(&session_id)).await.unwrap();
+        assert_eq!(restored.id, session_id);
+        assert_eq!(restored.conversation.message_count(), 2);
+        assert_eq!(
+            restored.conversation.attributions(),
+            [None, Some(Attribution::local("Qwen2.5-3B"))]
+        );
+        assert_eq!(manager.active_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_private_to_their_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        let alice = manager.get_or_create_in(None, Some("alice")).await.unwrap();
+        assert_eq!(alice.workspace.as_deref(), Some("alice"));
+
+        // Bob presenting Alice's id gets a fresh session of his own
+        let bob = manager
+            .get_or_create_in(Some(&alice.id), Some("bob"))
+            .await
+            .unwrap();
+        assert_ne!(bob.id, alice.id);
+        assert!(manager.belongs_to(&alice.id, Some("alice")).await);
+        assert!(!manager.belongs_to(&alice.id, Some("bob")).await);
+        assert!(manager.belongs_to(&alice.id, None).await);
+
+        // Ownership is kept across a restart
+        manager.persist_all().await.unwrap();
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        assert!(!manager.belongs_to(&alice.id, Some("bob")).await);
+        let other = manager
+            .get_or_create_in(Some(&alice.id), Some("bob"))
+            .await
+            .unwrap();
+        assert_ne!(other.id, alice.id);
+        let restored = manager
+            .get_or_create_in(Some(&alice.id), Some("alice"))
+            .await
+            .unwrap();
+        assert_eq!(restored.id, alice.id);
+    }
+
+    #[tokio::test]
+    async fn test_usage_accumulates_per_session_and_in_totals() {
+        let manager = SessionManager::new(10, 30);
+        let session = manager.get_or_create(None).await.unwrap();
+
+        let cloud = SessionUsage::call("claude-sonnet-4-6", 1_000_000, 0);
+        manager.record_usage(Some(&session.id), &cloud);
+        manager.record_usage(Some(&session.id), &SessionUsage::call("local", 50, 20));
+        // Sessionless and unpriced calls only show up in the totals
+        manager.record_usage(None, &SessionUsage::call("mystery-model", 10, 10));
+
+        let usage = manager.usage(&session.id).await.unwrap();
+        assert_eq!(usage.requests, 2);
+        assert_eq!(usage.input_tokens, 1_000_050);
+        assert!((usage.cost_usd - 3.0).abs() < 1e-9);
+
+        let totals = manager.total_usage();
+        assert_eq!(totals.requests, 3);
+        assert_eq!(totals.unpriced_requests, 1);
+        assert!((totals.cost_usd - 3.0).abs() < 1e-9);
+        assert!(manager.usage("no-such-session").await.is_none());
+    }
+
+    #[test]
+    fn test_reasoning_is_billed_and_counted_apart() {
+        let usage = SessionUsage::call("claude-sonnet-4-6", 0, 100)
+            .with_reasoning("claude-sonnet-4-6", 1_000_000);
+        assert_eq!(usage.output_tokens, 100);
+        assert_eq!(usage.reasoning_tokens, 1_000_000);
+        // Reasoning is billed at the output price ($15/M for Sonnet)
+        assert!((usage.reasoning_cost_usd - 15.0).abs() < 1e-9);
+        assert!(usage.cost_usd > usage.reasoning_cost_usd);
+
+        let mut totals = SessionUsage::default();
+        totals.add(&usage);
+        totals.add(&usage);
+        assert_eq!(totals.reasoning_tokens, 2_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_usage_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        let session = manager.get_or_create(None).await.unwrap();
+        manager.record_usage(Some(&session.id), &SessionUsage::call("gpt-4o", 100, 10));
+        manager.persist_all().await.unwrap();
+
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        // Readable before the client resumes, and still there afterwards
+        assert_eq!(manager.usage(&session.id).await.unwrap().input_tokens, 100);
+        let restored = manager.get_or_create(Some(&session.id)).await.unwrap();
+        assert_eq!(restored.usage.requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_session_rejects_path_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        assert!(session_key("../../etc/passwd").is_none());
+        assert!(session_key("a/b").is_none());
+
+        // Unknown or invalid ids just get a fresh session
+        let session = manager.get_or_create(Some("../secret")).await.unwrap();
+        assert_ne!(session.id, "../secret");
+    }
+
+    #[tokio::test]
+    async fn test_expired_persisted_session_is_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut stale = SessionState::new();
+        stale.last_activity = Utc::now() - chrono::Duration::hours(2);
+        let path = dir.path().join(format!("{}.json", stale.id));
+        std::fs::write(
+            &path,
+            serde_json::to_vec(&PersistedSession::from(&stale)).unwrap(),
+        )
+        .unwrap();
+
+        let manager = SessionManager::new(10, 30)
+            .with_persist_dir(dir.path().to_path_buf())
+            .unwrap();
+        assert_eq!(manager.prune_persisted().await.unwrap(), 1);
+        assert!(!path.exists());
+    }
+
     #[tokio::test]
     async fn test_session_deletion() {
         let manager = SessionManager::new(10, 30);
 
-        let session = manager.get_or_create(None).unwrap();
+        let session = manager.get_or_create(None).await.unwrap();
         let session_id = session.id.clone();
 
         assert_eq!(manager.active_count(), 1);
 
         // Delete session
-        assert!(manager.delete(&session_id));
+        assert!(manager.delete(&session_id).await);
         assert_eq!(manager.active_count(), 0);
 
         // Delete non-existent session
-        assert!(!manager.delete(&session_id));
+        assert!(!manager.delete(&session_id).await);
     }
 }
@@ -0,0 +1,121 @@
+// Speculative local drafts
+//
+// A cache miss still has to wait for the router lock and ask the routing
+// policy where the query goes.  When the local model is loaded and one of its
+// extra lanes is idle, the handler starts the local draft right after the
+// cache lookup and routes in parallel: a Local decision then picks up the
+// draft (often already finished), a forward discards it.
+//
+// Drafts only ever take an idle lane, so they never queue behind or delay
+// real requests.  A model with a single lane never drafts, so a draft can't
+// hold the only lane while other requests wait for it.  Discarding a draft
+// stops its generation before the next token.
+
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use super::{LocalModelSlot, RequestPriority};
+use crate::claude::Message;
+use crate::generators::GeneratorResponse;
+use crate::models::generation_stop;
+use crate::tools::types::ToolDefinition;
+
+/// A local generation started before the routing decision was made.
+/// Dropping it stops the generation.
+pub struct LocalDraft {
+    task: JoinHandle<Result<Option<GeneratorResponse>>>,
+    /// Checked by the generation before each token
+    stop: Arc<AtomicBool>,
+}
+
+impl LocalDraft {
+    /// Start drafting on an idle lane of `slot`.  Returns `None` when the
+    /// model has a single lane, isn't loaded yet or every lane is busy.
+    pub async fn start(
+        slot: &LocalModelSlot,
+        priority: RequestPriority,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+    ) -> Option<Self> {
+        if slot.request_queue.lanes() < 2 || !slot.generator_state.read().await.is_ready() {
+            return None;
+        }
+        let permit = slot.request_queue.try_acquire(priority)?;
+        let generator = Arc::clone(slot.generator(permit.lane()));
+        tracing::debug!(model = %slot.id, lane = permit.lane(), "Started local draft");
+
+        Some(Self::spawn(move || {
+            let _permit = permit;
+            generator
+                .blocking_write()
+                .try_generate_from_pattern_with_tools(&messages, tools)
+        }))
+    }
+
+    fn spawn(
+        generate: impl FnOnce() -> Result<Option<GeneratorResponse>> + Send + 'static,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let task = tokio::task::spawn_blocking(move || generation_stop::scope(flag, generate));
+        Self { task, stop }
+    }
+
+    /// Wait for the draft.  `Ok(None)` means the local model declined.
+    pub async fn finish(mut self) -> Result<Option<GeneratorResponse>> {
+        (&mut self.task).await.context("Local draft task failed")?
+    }
+
+    /// Throw the draft away, stopping its generation before the next token.
+    pub fn discard(self) {
+        if !self.task.is_finished() {
+            tracing::debug!("Discarding local draft still in progress");
+        }
+    }
+}
+
+impl Drop for LocalDraft {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_no_draft_until_model_ready() {
+        let slot = LocalModelSlot::new("qwen-local", 4);
+        slot.request_queue.add_lane();
+        let draft = LocalDraft::start(&slot, RequestPriority::Interactive, vec![], None).await;
+        assert!(draft.is_none());
+        // The lanes stay free for real requests
+        assert!(!slot.request_queue.snapshot().busy);
+    }
+
+    #[tokio::test]
+    async fn test_discard_stops_generation() {
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let draft = LocalDraft::spawn(move || {
+            // Stands in for a decode loop that would run much longer
+            while !generation_stop::requested() {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            done_tx.send(()).unwrap();
+            Ok(None)
+        });
+        draft.discard();
+        done_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("discarded draft should stop generating");
+    }
+
+    #[tokio::test]
+    async fn test_finished_draft_is_kept() {
+        let draft = LocalDraft::spawn(|| Ok(None));
+        assert!(draft.finish().await.unwrap().is_none());
+    }
+}
@@ -1,16 +1,21 @@
 // Background training worker for daemon
 //
 // Collects weighted examples via mpsc channel and triggers LoRA training
-// when batch threshold is reached or timeout occurs.
+// when batch threshold is reached or timeout occurs.  On daemon shutdown the
+// pending examples are written to the training queue instead of being lost.
+// During `[schedule]` quiet hours batches keep accumulating and train at the
+// first flush after the quiet window closes.
 
 use anyhow::Result;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, info};
 
+use super::webhooks::{WebhookEvent, Webhooks};
 use crate::models::{TrainingCoordinator, WeightedExample};
-use crate::training::lora_subprocess::LoRATrainingSubprocess;
+use crate::scheduling::{BackgroundJob, ScheduleConfig};
+use crate::training::lora_subprocess::{LoRATrainingConfig, LoRATrainingSubprocess};
 
 /// Training worker state
 pub struct TrainingWorker {
@@ -24,6 +29,12 @@ pub struct TrainingWorker {
     batch_threshold: usize,
     /// Timeout duration (trigger training after duration if batch not full)
     batch_timeout: Duration,
+    /// Fires when the daemon shuts down (`None` = run until the channel closes)
+    shutdown_rx: Option<oneshot::Receiver<()>>,
+    /// Notified when a batch is handed to training
+    webhooks: Option<Arc<Webhooks>>,
+    /// Quiet hours during which training waits
+    schedule: ScheduleConfig,
 }
 
 impl TrainingWorker {
@@ -42,12 +53,37 @@ impl TrainingWorker {
             subprocess,
             batch_threshold,
             batch_timeout: Duration::from_secs(batch_timeout_minutes * 60),
+            shutdown_rx: None,
+            webhooks: None,
+            schedule: ScheduleConfig::default(),
         }
     }
 
+    /// Stop (after flushing pending examples) when `shutdown_rx` fires
+    pub fn with_shutdown(mut self, shutdown_rx: oneshot::Receiver<()>) -> Self {
+        self.shutdown_rx = Some(shutdown_rx);
+        self
+    }
+
+    /// Send `training.batch_complete` events to `webhooks`
+    pub fn with_webhooks(mut self, webhooks: Arc<Webhooks>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Train only when `schedule` allows, at its CPU priority
+    pub fn with_schedule(mut self, schedule: ScheduleConfig) -> Self {
+        self.subprocess = LoRATrainingSubprocess::new(LoRATrainingConfig {
+            nice: schedule.nice,
+            ..LoRATrainingConfig::default()
+        });
+        self.schedule = schedule;
+        self
+    }
+
     /// Run the training worker loop
     ///
-    /// This runs indefinitely, accumulating examples and triggering training
+    /// This runs until shutdown, accumulating examples and triggering training
     /// when batch threshold is reached or timeout occurs.
     pub async fn run(mut self) {
         info!(
@@ -59,9 +95,16 @@ impl TrainingWorker {
         let mut batch = Vec::new();
         let mut flush_interval = tokio::time::interval(self.batch_timeout);
         flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut shutdown_rx = self.shutdown_rx.take();
 
         loop {
             tokio::select! {
+                // Daemon shutting down: persist what we have, skip training
+                _ = async { shutdown_rx.as_mut().unwrap().await }, if shutdown_rx.is_some() => {
+                    self.flush_on_shutdown();
+                    return;
+                }
+
                 // Receive examples from API
                 Some(example) = self.example_rx.recv() => {
                     debug!(weight = example.weight, "Received training example");
@@ -73,21 +116,17 @@ impl TrainingWorker {
                     }
 
                     // Check if batch threshold reached
-                    if batch.len() >= self.batch_threshold {
+                    if batch.len() >= self.batch_threshold && self.training_allowed(batch.len()) {
                         info!(count = batch.len(), "Batch threshold reached, triggering training");
-                        if let Err(e) = self.process_batch(&mut batch).await {
-                            error!(error = %e, "Failed to process training batch");
-                        }
+                        self.process_and_notify(&mut batch).await;
                     }
                 }
 
                 // Periodic flush (timeout)
                 _ = flush_interval.tick() => {
-                    if !batch.is_empty() {
+                    if !batch.is_empty() && self.training_allowed(batch.len()) {
                         info!(count = batch.len(), "Batch timeout reached, triggering training");
-                        if let Err(e) = self.process_batch(&mut batch).await {
-                            error!(error = %e, "Failed to process training batch");
-                        }
+                        self.process_and_notify(&mut batch).await;
                     } else {
                         debug!("Flush interval tick, but batch is empty");
                     }
@@ -96,6 +135,56 @@ impl TrainingWorker {
         }
     }
 
+    /// Move every example still in the channel into the coordinator and write
+    /// the buffer to the JSONL queue, so the next daemon run can train on it.
+    fn flush_on_shutdown(&mut self) {
+        while let Ok(example) = self.example_rx.try_recv() {
+            if let Err(e) = self.coordinator.add_example(example) {
+                error!(error = %e, "Failed to add example to coordinator");
+            }
+        }
+
+        match self.coordinator.write_training_queue() {
+            Ok(0) => debug!("No pending training examples at shutdown"),
+            Ok(count) => {
+                if let Err(e) = self.coordinator.clear_buffer() {
+                    error!(error = %e, "Failed to clear coordinator buffer");
+                }
+                info!(
+                    count,
+                    queue = %self.coordinator.queue_path().display(),
+                    "Flushed pending training examples at shutdown"
+                );
+            }
+            Err(e) => error!(error = %e, "Failed to flush training queue at shutdown"),
+        }
+    }
+
+    /// Whether a batch of `count` examples may train now
+    fn training_allowed(&self, count: usize) -> bool {
+        let allowed = self.schedule.allows_now(BackgroundJob::Training);
+        if !allowed {
+            debug!(
+                count,
+                "Quiet hours: training deferred {}",
+                self.schedule.describe_wait(BackgroundJob::Training)
+            );
+        }
+        allowed
+    }
+
+    async fn process_and_notify(&self, batch: &mut Vec<WeightedExample>) {
+        let examples = batch.len();
+        match self.process_batch(batch).await {
+            Ok(()) => {
+                if let Some(webhooks) = &self.webhooks {
+                    webhooks.emit(WebhookEvent::TrainingBatchComplete { examples });
+                }
+            }
+            Err(e) => error!(error = %e, "Failed to process training batch"),
+        }
+    }
+
     /// Process accumulated batch of examples
     async fn process_batch(&self, batch: &mut Vec<WeightedExample>) -> Result<()> {
         info!(count = batch.len(), "Processing training batch");
@@ -158,4 +247,42 @@ mod tests {
         assert_eq!(worker.batch_threshold, 10);
         assert_eq!(worker.batch_timeout, Duration::from_secs(5 * 60));
     }
+
+    #[test]
+    fn test_quiet_hours_defer_training() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let coordinator = Arc::new(TrainingCoordinator::new(100, 10, true));
+        let worker = TrainingWorker::new(rx, coordinator, 10, 5);
+        assert!(worker.training_allowed(10));
+
+        // A window whose end equals its start is quiet all day
+        let schedule: ScheduleConfig = toml::from_str(
+            r#"
+            nice = 15
+            [[rules]]
+            jobs = ["training"]
+            quiet = "00:00-00:00"
+            "#,
+        )
+        .unwrap();
+        let worker = worker.with_schedule(schedule);
+        assert!(!worker.training_allowed(10));
+        assert_eq!(worker.subprocess.config().nice, 15);
+    }
+
+    #[tokio::test]
+    async fn test_worker_exits_on_shutdown() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let coordinator = Arc::new(TrainingCoordinator::new(100, 10, true));
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let worker = TrainingWorker::new(rx, coordinator, 10, 5).with_shutdown(shutdown_rx);
+        let handle = tokio::spawn(worker.run());
+
+        shutdown_tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("worker should stop after shutdown")
+            .unwrap();
+    }
 }
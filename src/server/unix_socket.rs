@@ -0,0 +1,134 @@
+// HTTP over a Unix domain socket
+//
+// `axum::serve` only accepts TCP listeners, so for `unix:` binds we run the
+// accept loop ourselves and hand each connection to hyper.  The socket file has
+// mode 0600 from the moment it appears at its path: only the daemon's user can
+// connect.
+
+use anyhow::{bail, Context, Result};
+use axum::extract::ConnectInfo;
+use axum::{Extension, Router};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use std::net::SocketAddr;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use tokio::net::{UnixListener, UnixStream};
+
+/// Serve `app` on the socket at `path` until the listener fails.
+pub async fn serve(app: Router, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    // A socket left behind by a crashed daemon makes bind() fail; remove it
+    // unless another daemon is still answering on it.
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            bail!("Another daemon is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket: {}", path.display()))?;
+    }
+
+    let listener = bind_private(path)?;
+
+    // Handlers log the peer address via ConnectInfo; socket peers are local.
+    let app = app.layer(Extension(ConnectInfo(SocketAddr::from((
+        [127, 0, 0, 1],
+        0,
+    )))));
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                // e.g. EMFILE — back off instead of spinning
+                tracing::warn!("Unix socket accept failed: {}", e);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Unix socket connection closed with error: {}", e);
+            }
+        });
+    }
+}
+
+/// Bind the socket inside a 0700 directory beside `path`, restrict it to
+/// 0600, then link it into place.  Binding at `path` and chmod'ing after
+/// would leave it open to other users, with the umask's permissions, in
+/// between.
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    let staging = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create directory: {}", staging.display()))?;
+
+    let bound = staging.join("s");
+    let result = UnixListener::bind(&bound)
+        .with_context(|| format!("Failed to bind Unix socket: {}", path.display()))
+        .and_then(|listener| {
+            let private = std::fs::Permissions::from_mode(0o600);
+            std::fs::set_permissions(&bound, private).with_context(|| {
+                format!("Failed to restrict socket permissions: {}", path.display())
+            })?;
+            // Unlike rename, fails if another daemon has bound `path` since
+            std::fs::hard_link(&bound, path)
+                .with_context(|| format!("Failed to bind Unix socket: {}", path.display()))?;
+            Ok(listener)
+        });
+    // Drops the staging link; the listener keeps working through `path`
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpTransport;
+    use axum::routing::get;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_serves_http_on_private_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let app = Router::new().route(
+            "/peer",
+            get(|ConnectInfo(peer): ConnectInfo<SocketAddr>| async move { peer.to_string() }),
+        );
+        let path = socket.clone();
+        tokio::spawn(async move { serve(app, &path).await });
+        while !socket.exists() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Private from the moment it appears, with no staging directory left
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let addr = format!("unix:{}", socket.display());
+        let transport = HttpTransport::new(&addr, Duration::from_secs(5)).unwrap();
+        let peer = transport
+            .get("/peer", None)
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(peer, "127.0.0.1:0");
+
+        // A second daemon must not steal a live socket
+        assert!(serve(Router::new(), &socket).await.is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Browser access — CORS policy and the built-in `/ui` chat page
+//
+// Browser chat UIs on another origin (a dev server on :3000, a file:// page)
+// can only call the daemon if it answers with CORS headers for that origin.
+// The policy comes from `[server.cors]`; with no origins configured no CORS
+// headers are sent and browsers keep blocking cross-origin calls, as before.
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use axum::response::Html;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use super::request_queue::PRIORITY_HEADER;
+use crate::config::CorsConfig;
+
+/// Single-page chat client served at `/ui` when `web_ui` is enabled
+const CHAT_PAGE: &str = include_str!("ui.html");
+
+pub async fn chat_page() -> Html<&'static str> {
+    Html(CHAT_PAGE)
+}
+
+/// Build the CORS layer for `config` (`None` when no origins are allowed)
+pub fn cors_layer(config: &CorsConfig) -> Option<CorsLayer> {
+    if config.allowed_origins.is_empty() {
+        return None;
+    }
+
+    let any_origin = config.allowed_origins.iter().any(|o| o == "*");
+    let origin = if any_origin {
+        AllowOrigin::any()
+    } else {
+        let origins: Vec<HeaderValue> = config
+            .allowed_origins
+            .iter()
+            .filter_map(|o| match o.trim_end_matches('/').parse() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid CORS origin: {:?}", o);
+                    None
+                }
+            })
+            .collect();
+        AllowOrigin::list(origins)
+    };
+
+    let mut layer = CorsLayer::new()
+        .allow_origin(origin)
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            HeaderName::from_static("x-finch-provider"),
+            HeaderName::from_static(PRIORITY_HEADER),
+        ]);
+    if config.allow_credentials {
+        if any_origin {
+            tracing::warn!("CORS allow_credentials is ignored with allowed_origins = [\"*\"]");
+        } else {
+            layer = layer.allow_credentials(true);
+        }
+    }
+    Some(layer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    fn app(config: &CorsConfig) -> axum::Router {
+        let router = axum::Router::new().route("/v1/models", axum::routing::get(|| async { "ok" }));
+        match cors_layer(config) {
+            Some(layer) => router.layer(layer),
+            None => router,
+        }
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/v1/models")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_listed_origin_is_allowed_with_credentials() {
+        let config = CorsConfig {
+            allowed_origins: vec!["http://localhost:3000/".to_string()],
+            allow_credentials: true,
+        };
+
+        let resp = app(&config)
+            .oneshot(preflight("http://localhost:3000"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let headers = resp.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "http://localhost:3000"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+        let resp = app(&config)
+            .oneshot(preflight("http://evil.example"))
+            .await
+            .unwrap();
+        assert!(!resp
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_and_disabled() {
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            // Would make tower-http panic if passed through
+            allow_credentials: true,
+        };
+        let resp = app(&config)
+            .oneshot(preflight("http://any.example"))
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+        assert!(cors_layer(&CorsConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,409 @@
+// Event webhooks — `[server.webhooks]`
+//
+// The daemon POSTs a signed JSON envelope to every configured URL when
+// something worth a notification happens: the daemon started, a training
+// batch was queued, a background agent task finished or failed, or a
+// provider's circuit opened.  Delivery runs in the background with
+// exponential backoff; an event that still fails after `max_attempts` is
+// appended to a dead-letter JSONL file instead of disappearing silently.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use super::activity::{Activity, ActivityFeed};
+use crate::config::WebhooksConfig;
+use crate::crypto::hmac_sha256;
+
+/// Something the daemon reports to webhooks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", content = "data")]
+pub enum WebhookEvent {
+    #[serde(rename = "daemon.started")]
+    DaemonStarted {
+        bind_address: String,
+        version: String,
+    },
+    #[serde(rename = "training.batch_complete")]
+    TrainingBatchComplete { examples: usize },
+    #[serde(rename = "agent.task_done")]
+    AgentTaskDone {
+        id: String,
+        task: String,
+        summary: Option<String>,
+    },
+    #[serde(rename = "agent.task_failed")]
+    AgentTaskFailed {
+        id: String,
+        task: String,
+        error: String,
+    },
+    #[serde(rename = "provider.circuit_opened")]
+    ProviderCircuitOpened {
+        provider: String,
+        consecutive_failures: u32,
+        retry_after_secs: u64,
+    },
+}
+
+impl WebhookEvent {
+    /// Event name as sent in the payload and matched by `events` filters
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DaemonStarted { .. } => "daemon.started",
+            Self::TrainingBatchComplete { .. } => "training.batch_complete",
+            Self::AgentTaskDone { .. } => "agent.task_done",
+            Self::AgentTaskFailed { .. } => "agent.task_failed",
+            Self::ProviderCircuitOpened { .. } => "provider.circuit_opened",
+        }
+    }
+
+    /// One-line human-readable summary for chat integrations
+    pub fn text(&self) -> String {
+        match self {
+            Self::DaemonStarted {
+                bind_address,
+                version,
+            } => {
+                format!("finch daemon {} started on {}", version, bind_address)
+            }
+            Self::TrainingBatchComplete { examples } => {
+                format!(
+                    "finch queued a LoRA training batch of {} examples",
+                    examples
+                )
+            }
+            Self::AgentTaskDone { task, .. } => format!("finch agent task done: {}", task),
+            Self::AgentTaskFailed { task, error, .. } => {
+                format!("finch agent task failed: {} ({})", task, error)
+            }
+            Self::ProviderCircuitOpened {
+                provider,
+                consecutive_failures,
+                retry_after_secs,
+            } => format!(
+                "finch stopped calling {} after {} consecutive failures; retrying in {}s",
+                provider, consecutive_failures, retry_after_secs
+            ),
+        }
+    }
+}
+
+/// Sends events to the configured webhook URLs
+pub struct Webhooks {
+    config: WebhooksConfig,
+    client: reqwest::Client,
+    dead_letter: Option<PathBuf>,
+    /// Delay before the first retry; doubles on each further attempt
+    retry_base: Duration,
+    /// Also told about every event, whether or not webhooks want it
+    activity: Option<Arc<ActivityFeed>>,
+}
+
+impl Webhooks {
+    pub fn new(config: WebhooksConfig) -> Self {
+        let dead_letter = config
+            .dead_letter
+            .clone()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".finch").join("webhooks-dead.jsonl")));
+        Self {
+            config,
+            client: crate::http::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            dead_letter,
+            retry_base: Duration::from_secs(1),
+            activity: None,
+        }
+    }
+
+    /// Publish every event to `feed` too
+    pub fn with_activity(mut self, feed: Arc<ActivityFeed>) -> Self {
+        self.activity = Some(feed);
+        self
+    }
+
+    /// No URLs configured: every event is dropped
+    pub fn disabled() -> Self {
+        Self::new(WebhooksConfig::default())
+    }
+
+    /// Whether `event` should be sent at all
+    pub fn wants(&self, event: &WebhookEvent) -> bool {
+        self.config.is_enabled()
+            && (self.config.events.is_empty()
+                || self.config.events.iter().any(|e| e == event.name()))
+    }
+
+    /// Deliver `event` to every URL in the background.  Never blocks or fails
+    /// the caller; see the dead-letter log for events that could not be sent.
+    pub fn emit(&self, event: WebhookEvent) {
+        if let Some(feed) = &self.activity {
+            feed.publish(Activity::event(&event));
+        }
+        self.spawn_deliveries(event);
+    }
+
+    fn spawn_deliveries(&self, event: WebhookEvent) -> Vec<JoinHandle<()>> {
+        if !self.wants(&event) {
+            return Vec::new();
+        }
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let mut payload = serde_json::to_value(&event).unwrap_or_default();
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("id".to_string(), delivery_id.clone().into());
+            obj.insert("timestamp".to_string(), Utc::now().to_rfc3339().into());
+            obj.insert("text".to_string(), event.text().into());
+        }
+        let body = payload.to_string();
+        let signature = self
+            .config
+            .secret
+            .as_deref()
+            .map(|secret| format!("sha256={}", sign(secret.as_bytes(), body.as_bytes())));
+
+        self.config
+            .urls
+            .iter()
+            .map(|url| {
+                let delivery = Delivery {
+                    client: self.client.clone(),
+                    url: url.clone(),
+                    event: event.name(),
+                    id: delivery_id.clone(),
+                    body: body.clone(),
+                    signature: signature.clone(),
+                };
+                let max_attempts = self.config.max_attempts.max(1);
+                let retry_base = self.retry_base;
+                let dead_letter = self.dead_letter.clone();
+                tokio::spawn(async move {
+                    if let Err((attempts, error)) = delivery.send(max_attempts, retry_base).await {
+                        tracing::warn!(
+                            url = %delivery.url,
+                            event = delivery.event,
+                            attempts,
+                            "Webhook delivery failed: {}",
+                            error
+                        );
+                        if let Some(path) = dead_letter {
+                            if let Err(e) = delivery.dead_letter(&path, attempts, &error) {
+                                tracing::warn!("Failed to write webhook dead letter: {}", e);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// One event on its way to one URL
+struct Delivery {
+    client: reqwest::Client,
+    url: String,
+    event: &'static str,
+    id: String,
+    body: String,
+    signature: Option<String>,
+}
+
+impl Delivery {
+    /// POST with retries.  Timeouts, 5xx, 408 and 429 are retried; other
+    /// 4xx responses mean the endpoint rejected the event and are not.
+    /// Returns the attempts made and the last error on failure.
+    async fn send(&self, max_attempts: u32, retry_base: Duration) -> Result<(), (u32, String)> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut request = self
+                .client
+                .post(&self.url)
+                .header("content-type", "application/json")
+                .header("x-finch-event", self.event)
+                .header("x-finch-delivery", &self.id)
+                .body(self.body.clone());
+            if let Some(signature) = &self.signature {
+                request = request.header("x-finch-signature", signature);
+            }
+
+            let (error, retryable) = match request.send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) => {
+                    let status = resp.status();
+                    let retryable = status.is_server_error()
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                    (format!("HTTP {}", status), retryable)
+                }
+                Err(e) => (e.to_string(), true),
+            };
+            if !retryable || attempt >= max_attempts {
+                return Err((attempt, error));
+            }
+            tracing::debug!(url = %self.url, attempt, "Webhook delivery failed, retrying: {}", error);
+            tokio::time::sleep(retry_base * 2u32.saturating_pow(attempt - 1)).await;
+        }
+    }
+
+    fn dead_letter(
+        &self,
+        path: &std::path::Path,
+        attempts: u32,
+        error: &str,
+    ) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let record = serde_json::json!({
+            "failed_at": Utc::now().to_rfc3339(),
+            "url": self.url,
+            "attempts": attempts,
+            "error": error,
+            "payload": serde_json::from_str::<serde_json::Value>(&self.body).unwrap_or_default(),
+        });
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", record)
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret` (RFC 2104)
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    hmac_sha256(secret, body)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Local endpoint that fails the first `failures` requests, then accepts.
+    /// Returns its URL and every (headers, body) it received.
+    async fn endpoint(failures: usize) -> (String, Arc<Mutex<Vec<(HeaderMap, String)>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let log = Arc::clone(&received);
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |headers: HeaderMap, body: String| {
+                let log = Arc::clone(&log);
+                let calls = Arc::clone(&calls);
+                async move {
+                    log.lock().unwrap().push((headers, body));
+                    if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    } else {
+                        StatusCode::OK
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), received)
+    }
+
+    fn webhooks(url: String, max_attempts: u32, dead_letter: PathBuf) -> Webhooks {
+        let mut hooks = Webhooks::new(WebhooksConfig {
+            urls: vec![url],
+            secret: Some("s3cret".to_string()),
+            max_attempts,
+            dead_letter: Some(dead_letter),
+            ..Default::default()
+        });
+        hooks.retry_base = Duration::from_millis(1);
+        hooks
+    }
+
+    async fn deliver(hooks: &Webhooks, event: WebhookEvent) {
+        for handle in hooks.spawn_deliveries(event) {
+            handle.await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_sign_matches_rfc4231() {
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_and_is_signed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dead = dir.path().join("dead.jsonl");
+        let (url, received) = endpoint(2).await;
+        let hooks = webhooks(url, 5, dead.clone());
+
+        deliver(&hooks, WebhookEvent::TrainingBatchComplete { examples: 10 }).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let (headers, body) = &received[2];
+        assert_eq!(headers["x-finch-event"], "training.batch_complete");
+        assert_eq!(
+            headers["x-finch-signature"].to_str().unwrap(),
+            format!("sha256={}", sign(b"s3cret", body.as_bytes()))
+        );
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["event"], "training.batch_complete");
+        assert_eq!(payload["data"]["examples"], 10);
+        assert!(payload["text"].as_str().unwrap().contains("10 examples"));
+        assert!(!dead.exists());
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_event_is_dead_lettered() {
+        let dir = tempfile::tempdir().unwrap();
+        let dead = dir.path().join("dead.jsonl");
+        let (url, received) = endpoint(usize::MAX).await;
+        let hooks = webhooks(url.clone(), 3, dead.clone());
+
+        deliver(
+            &hooks,
+            WebhookEvent::AgentTaskFailed {
+                id: "b1".to_string(),
+                task: "audit deps".to_string(),
+                error: "provider timeout".to_string(),
+            },
+        )
+        .await;
+
+        assert_eq!(received.lock().unwrap().len(), 3);
+        let line = std::fs::read_to_string(&dead).unwrap();
+        let record: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(record["url"], url);
+        assert_eq!(record["attempts"], 3);
+        assert_eq!(record["payload"]["event"], "agent.task_failed");
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let hooks = Webhooks::new(WebhooksConfig {
+            urls: vec!["http://localhost/hook".to_string()],
+            events: vec!["agent.task_failed".to_string()],
+            ..Default::default()
+        });
+        assert!(!hooks.wants(&WebhookEvent::TrainingBatchComplete { examples: 1 }));
+        assert!(hooks.wants(&WebhookEvent::AgentTaskFailed {
+            id: String::new(),
+            task: String::new(),
+            error: String::new(),
+        }));
+        assert!(!Webhooks::disabled().wants(&WebhookEvent::TrainingBatchComplete { examples: 1 }));
+    }
+}
@@ -0,0 +1,186 @@
+// Worker role: LAN traffic served by the daemon's resident model
+//
+// `finch worker` used to start a daemon of its own, so a machine that also
+// ran the user's daemon held the model in memory twice.  Now one daemon
+// holds it and can take on the worker role: a second listener (usually
+// 0.0.0.0:8000) serving the same routes.  Requests arriving there are marked
+// `WorkerTraffic` and demoted one priority class, so the request queue
+// serves local sessions first and LAN work in between.
+//
+// The role is taken at startup (`ServerConfig::worker_address`, used when
+// `finch worker` finds no daemon running) or later through
+// `POST /v1/roles/worker`, which is what `finch worker` does when a daemon is
+// already up.  `DELETE /v1/roles/worker` closes the listener again.
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+use super::request_queue::{RequestPriority, PRIORITY_HEADER};
+
+/// Marks a request that came in over the worker listener
+#[derive(Debug, Clone, Copy)]
+pub struct WorkerTraffic;
+
+/// The worker listener, when the daemon has taken the role
+pub struct WorkerRole {
+    /// The daemon's routes, set once `serve()` has built them
+    app: OnceLock<axum::Router>,
+    listener: Mutex<Option<Listener>>,
+}
+
+struct Listener {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl WorkerRole {
+    pub fn new() -> Self {
+        Self {
+            app: OnceLock::new(),
+            listener: Mutex::new(None),
+        }
+    }
+
+    /// Routes the worker listener serves; called by `serve()`
+    pub(crate) fn set_app(&self, app: axum::Router) {
+        let _ = self.app.set(app);
+    }
+
+    /// Where worker traffic is accepted, if the role is taken
+    pub async fn address(&self) -> Option<SocketAddr> {
+        self.listener.lock().await.as_ref().map(|l| l.address)
+    }
+
+    /// Start accepting worker traffic on `address` (`host:port`).  Returns
+    /// the bound address; fails if the role is already taken.
+    pub async fn start(&self, address: &str) -> Result<SocketAddr> {
+        let Some(app) = self.app.get() else {
+            bail!("The daemon is still starting up");
+        };
+        let mut current = self.listener.lock().await;
+        if let Some(listener) = current.as_ref() {
+            bail!("Already serving worker traffic on {}", listener.address);
+        }
+        let addr: SocketAddr = address
+            .parse()
+            .with_context(|| format!("Invalid worker address: {}", address))?;
+        let tcp = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind worker address {}", addr))?;
+        let address = tcp.local_addr()?;
+
+        let app = app
+            .clone()
+            .layer(axum::middleware::from_fn(mark_worker_traffic));
+        let (shutdown, stop) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            let served = axum::serve(tcp, app.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(async {
+                    let _ = stop.await;
+                })
+                .await;
+            if let Err(e) = served {
+                tracing::error!("Worker listener failed: {}", e);
+            }
+        });
+        tracing::info!("Serving worker traffic on {}", address);
+        *current = Some(Listener {
+            address,
+            shutdown,
+            handle,
+        });
+        Ok(address)
+    }
+
+    /// Stop accepting worker traffic, letting requests already in progress
+    /// finish.  `false` if the role wasn't taken.
+    pub async fn stop(&self) -> bool {
+        let Some(listener) = self.listener.lock().await.take() else {
+            return false;
+        };
+        let _ = listener.shutdown.send(());
+        let _ = listener.handle.await;
+        tracing::info!("Stopped serving worker traffic on {}", listener.address);
+        true
+    }
+}
+
+impl Default for WorkerRole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tag a worker request and demote its priority, so LAN work waits behind
+/// local sessions in the request queue
+async fn mark_worker_traffic(mut request: Request<Body>, next: Next) -> Response {
+    let priority = RequestPriority::from_headers(request.headers()).demoted();
+    request
+        .headers_mut()
+        .insert(PRIORITY_HEADER, HeaderValue::from_static(priority.as_str()));
+    request.extensions_mut().insert(WorkerTraffic);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::get, Extension};
+
+    /// Reports the priority a handler sees and whether it was worker traffic
+    fn app() -> axum::Router {
+        axum::Router::new().route(
+            "/",
+            get(
+                |headers: HeaderMap, worker: Option<Extension<WorkerTraffic>>| async move {
+                    format!(
+                        "{} {}",
+                        RequestPriority::from_headers(&headers).as_str(),
+                        worker.is_some()
+                    )
+                },
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_worker_traffic_is_marked_and_demoted() {
+        let role = WorkerRole::new();
+        assert!(role.start("127.0.0.1:0").await.is_err(), "no routes yet");
+        role.set_app(app());
+
+        let address = role.start("127.0.0.1:0").await.unwrap();
+        assert_eq!(role.address().await, Some(address));
+        assert!(role.start("127.0.0.1:0").await.is_err(), "already taken");
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/", address);
+        let seen = client.get(&url).send().await.unwrap().text().await.unwrap();
+        assert_eq!(seen, "agent true");
+        let seen = client
+            .get(&url)
+            .header(PRIORITY_HEADER, "training")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(seen, "training true");
+
+        assert!(role.stop().await);
+        assert!(!role.stop().await);
+        assert_eq!(role.address().await, None);
+        assert!(client.get(&url).send().await.is_err());
+    }
+}
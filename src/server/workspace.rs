@@ -0,0 +1,549 @@
+// Workspaces: tenants of one shared daemon
+//
+// Each `[[server.workspaces]]` entry maps API keys to a workspace with its
+// own tool profile for daemon brains, memory database, cloud-provider
+// allowlist and usage quota.  Sessions and brains are only visible to the
+// workspace that created them.  With no workspaces configured the daemon is
+// single-tenant: open, or limited to the owner's per-install token
+// (`daemon::token`), which also works alongside workspaces without scoping.
+//
+// Quota counters are written to `<dir>/<name>/usage.json` on shutdown and
+// read back at startup, so restarting the daemon doesn't reset them.
+
+use anyhow::Context;
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+use super::SessionUsage;
+use crate::config::{ToolProfile, WorkspaceConfig};
+use crate::memory::{MemoryConfig, MemorySystem};
+
+/// Why a request was refused on behalf of its workspace
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum WorkspaceError {
+    /// No key, or a key no workspace owns
+    #[error("missing or unknown API key")]
+    Unauthorized,
+    /// The workspace's profile or allowlist doesn't permit this
+    #[error("workspace '{workspace}' may not {action}")]
+    Forbidden { workspace: String, action: String },
+    /// Belongs to another workspace (reported as if it didn't exist)
+    #[error("{0} not found")]
+    NotFound(String),
+    /// Daily tokens or monthly budget used up
+    #[error("workspace '{workspace}' has used its {quota}; resets in {retry_after_secs}s")]
+    QuotaExceeded {
+        workspace: String,
+        quota: &'static str,
+        retry_after_secs: u64,
+    },
+}
+
+impl IntoResponse for WorkspaceError {
+    fn into_response(self) -> Response {
+        let (status, error_type) = match &self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "authentication_error"),
+            Self::Forbidden { .. } => (StatusCode::FORBIDDEN, "permission_error"),
+            Self::NotFound(_) => (StatusCode::NOT_FOUND, "not_found_error"),
+            Self::QuotaExceeded { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_error"),
+        };
+        let body = serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "type": error_type,
+            }
+        });
+        let mut response = (status, Json(body)).into_response();
+        let headers = response.headers_mut();
+        match &self {
+            Self::Unauthorized => {
+                headers.insert(header::WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+            }
+            Self::QuotaExceeded {
+                retry_after_secs, ..
+            } => {
+                headers.insert(
+                    header::RETRY_AFTER,
+                    retry_after_secs.to_string().parse().unwrap(),
+                );
+            }
+            _ => {}
+        }
+        response
+    }
+}
+
+/// Quota counters for one workspace
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceUsage {
+    /// UTC day `day_tokens` counts
+    pub day: Option<NaiveDate>,
+    /// Input plus output tokens on `day`
+    pub day_tokens: u64,
+    /// First day of the month `month_cost_usd` covers
+    pub month: Option<NaiveDate>,
+    /// Estimated spend this month
+    pub month_cost_usd: f64,
+    /// Everything since the workspace was first used
+    pub total: SessionUsage,
+}
+
+impl WorkspaceUsage {
+    /// Start fresh day and month windows once `today` has moved past them
+    fn roll(&mut self, today: NaiveDate) {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.day_tokens = 0;
+        }
+        let month = today.with_day(1).unwrap_or(today);
+        if self.month != Some(month) {
+            self.month = Some(month);
+            self.month_cost_usd = 0.0;
+        }
+    }
+
+    fn add(&mut self, usage: &SessionUsage, today: NaiveDate) {
+        self.roll(today);
+        self.day_tokens += usage.input_tokens + usage.output_tokens;
+        self.month_cost_usd += usage.cost_usd;
+        self.total.add(usage);
+    }
+}
+
+/// One tenant: its settings, quota counters and lazily opened memory
+pub struct Workspace {
+    config: WorkspaceConfig,
+    /// `<workspaces_dir>/<name>`; `None` keeps usage in memory and
+    /// disables the memory tools
+    dir: Option<PathBuf>,
+    usage: Mutex<WorkspaceUsage>,
+    memory: OnceCell<Arc<MemorySystem>>,
+}
+
+impl Workspace {
+    /// Create a workspace, restoring its usage from `dir` when present
+    pub fn new(config: WorkspaceConfig, dir: Option<PathBuf>) -> Self {
+        let usage = dir
+            .as_deref()
+            .and_then(|dir| std::fs::read(dir.join("usage.json")).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            config,
+            dir,
+            usage: Mutex::new(usage),
+            memory: OnceCell::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Tools this workspace's daemon brains get
+    pub fn tools(&self) -> ToolProfile {
+        self.config.tools
+    }
+
+    /// Provider used when a request names none (the allowlist's first entry)
+    pub fn default_provider(&self) -> Option<&str> {
+        self.config.providers.first().map(String::as_str)
+    }
+
+    /// Refuse cloud providers outside the allowlist
+    pub fn check_provider(&self, provider: &str) -> Result<(), WorkspaceError> {
+        let allowed = self.config.providers.is_empty()
+            || self
+                .config
+                .providers
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(provider));
+        if allowed {
+            Ok(())
+        } else {
+            Err(self.forbidden(format!("use provider '{}'", provider)))
+        }
+    }
+
+    /// Refuse new model calls once the daily tokens or monthly budget are used up
+    pub fn check_quota(&self) -> Result<(), WorkspaceError> {
+        self.check_quota_at(Utc::now())
+    }
+
+    fn check_quota_at(&self, now: DateTime<Utc>) -> Result<(), WorkspaceError> {
+        let today = now.date_naive();
+        let mut usage = self.usage.lock().unwrap();
+        usage.roll(today);
+
+        if let Some(limit) = self.config.daily_tokens {
+            if usage.day_tokens >= limit {
+                let tomorrow = today + ChronoDuration::days(1);
+                return Err(self.over_quota("daily token quota", now, tomorrow));
+            }
+        }
+        if let Some(budget) = self.config.monthly_budget_usd {
+            if usage.month_cost_usd >= budget {
+                return Err(self.over_quota("monthly budget", now, next_month(today)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count one call towards the quota
+    pub fn record_usage(&self, usage: &SessionUsage) {
+        self.usage
+            .lock()
+            .unwrap()
+            .add(usage, Utc::now().date_naive());
+    }
+
+    /// Current quota counters
+    pub fn usage(&self) -> WorkspaceUsage {
+        let mut usage = self.usage.lock().unwrap();
+        usage.roll(Utc::now().date_naive());
+        usage.clone()
+    }
+
+    /// Settings, minus the API keys, and usage for `GET /v1/workspace`
+    pub fn info(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.config.name,
+            "tools": self.config.tools,
+            "providers": self.config.providers,
+            "daily_tokens": self.config.daily_tokens,
+            "monthly_budget_usd": self.config.monthly_budget_usd,
+            "usage": self.usage(),
+        })
+    }
+
+    /// The workspace's own memory database, opened on first use
+    pub async fn memory(&self) -> anyhow::Result<Arc<MemorySystem>> {
+        let dir = self
+            .dir
+            .as_deref()
+            .context("No workspaces directory configured for workspace memory")?;
+        self.memory
+            .get_or_try_init(|| async {
+                let memory = Arc::new(MemorySystem::new(MemoryConfig::workspace(dir))?);
+                memory.spawn_reembedding(|done, total| {
+                    tracing::info!("Re-embedding workspace memories: {}/{}", done, total)
+                });
+                Ok(memory)
+            })
+            .await
+            .cloned()
+    }
+
+    /// Write usage to `<dir>/usage.json`; `false` when there is no directory
+    fn persist_usage(&self) -> anyhow::Result<bool> {
+        let Some(dir) = &self.dir else {
+            return Ok(false);
+        };
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join("usage.json");
+        let bytes = serde_json::to_vec_pretty(&*self.usage.lock().unwrap())?;
+        std::fs::write(&path, bytes)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(true)
+    }
+
+    fn forbidden(&self, action: String) -> WorkspaceError {
+        WorkspaceError::Forbidden {
+            workspace: self.config.name.clone(),
+            action,
+        }
+    }
+
+    fn over_quota(
+        &self,
+        quota: &'static str,
+        now: DateTime<Utc>,
+        resets: NaiveDate,
+    ) -> WorkspaceError {
+        let resets = resets.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        WorkspaceError::QuotaExceeded {
+            workspace: self.config.name.clone(),
+            quota,
+            retry_after_secs: (resets - now).num_seconds().max(1) as u64,
+        }
+    }
+}
+
+/// First day of the month after `day`
+fn next_month(day: NaiveDate) -> NaiveDate {
+    let (year, month) = match day.month() {
+        12 => (day.year() + 1, 1),
+        m => (day.year(), m + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(day)
+}
+
+/// Every configured workspace, looked up by API key
+#[derive(Default)]
+pub struct Workspaces {
+    /// In config order
+    workspaces: Vec<Arc<Workspace>>,
+    by_key: HashMap<String, Arc<Workspace>>,
+    /// Per-install token of the user running the daemon
+    owner_token: Option<String>,
+}
+
+impl Workspaces {
+    /// Build from config; each workspace keeps its state in `dir/<name>`
+    pub fn new(configs: &[WorkspaceConfig], dir: Option<&Path>) -> Self {
+        let mut workspaces = Self::default();
+        for config in configs {
+            let workspace = Arc::new(Workspace::new(
+                config.clone(),
+                dir.map(|d| d.join(&config.name)),
+            ));
+            for key in &config.api_keys {
+                workspaces
+                    .by_key
+                    .insert(key.clone(), Arc::clone(&workspace));
+            }
+            workspaces.workspaces.push(workspace);
+        }
+        workspaces
+    }
+
+    /// Also accept the owner's per-install token, as an unscoped key
+    pub fn with_owner_token(mut self, token: String) -> Self {
+        self.owner_token = Some(token);
+        self
+    }
+
+    /// Whether any workspaces are configured
+    pub fn is_enabled(&self) -> bool {
+        !self.workspaces.is_empty()
+    }
+
+    /// Whether the owner's per-install token is accepted
+    pub fn accepts_owner_token(&self) -> bool {
+        self.owner_token.is_some()
+    }
+
+    /// Whether requests must carry a workspace key or the owner token
+    pub fn requires_key(&self) -> bool {
+        self.is_enabled() || self.owner_token.is_some()
+    }
+
+    /// The workspace a request belongs to, from `Authorization: Bearer <key>`
+    /// or `x-api-key`.  `Ok(None)` for the owner token, or when no key is
+    /// required.
+    pub fn resolve(&self, headers: &HeaderMap) -> Result<Option<Arc<Workspace>>, WorkspaceError> {
+        if !self.requires_key() {
+            return Ok(None);
+        }
+        let key = request_key(headers);
+        if let (Some(token), Some(key)) = (&self.owner_token, key) {
+            if crate::daemon::token::matches(token, key) {
+                return Ok(None);
+            }
+        }
+        key.and_then(|key| self.by_key.get(key))
+            .cloned()
+            .map(Some)
+            .ok_or(WorkspaceError::Unauthorized)
+    }
+
+    /// Write every workspace's usage to disk.  Returns how many were written.
+    pub fn persist_usage(&self) -> anyhow::Result<usize> {
+        let mut written = 0;
+        for workspace in &self.workspaces {
+            written += workspace.persist_usage()? as usize;
+        }
+        Ok(written)
+    }
+}
+
+/// API key sent with a request, if any
+fn request_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config(name: &str, key: &str) -> WorkspaceConfig {
+        WorkspaceConfig {
+            name: name.to_string(),
+            api_keys: vec![key.to_string()],
+            tools: ToolProfile::ReadOnly,
+            providers: Vec::new(),
+            daily_tokens: None,
+            monthly_budget_usd: None,
+        }
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_keys_resolve_to_their_workspace() {
+        let workspaces = Workspaces::new(&[config("alice", "key-a"), config("bob", "key-b")], None);
+        assert!(workspaces.is_enabled());
+
+        let alice = workspaces
+            .resolve(&headers("authorization", "Bearer key-a"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(alice.name(), "alice");
+        let bob = workspaces
+            .resolve(&headers("x-api-key", "key-b"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bob.name(), "bob");
+
+        for bad in [HeaderMap::new(), headers("authorization", "Bearer nope")] {
+            assert!(matches!(
+                workspaces.resolve(&bad),
+                Err(WorkspaceError::Unauthorized)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_no_workspaces_means_open_daemon() {
+        let workspaces = Workspaces::new(&[], None);
+        assert!(!workspaces.is_enabled());
+        assert!(workspaces.resolve(&HeaderMap::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_owner_token_is_unscoped() {
+        let workspaces = Workspaces::new(&[], None).with_owner_token("secret".to_string());
+        assert!(workspaces.requires_key());
+        let owner = headers("authorization", "Bearer secret");
+        assert!(workspaces.resolve(&owner).unwrap().is_none());
+        for bad in [HeaderMap::new(), headers("x-api-key", "guess")] {
+            assert!(matches!(
+                workspaces.resolve(&bad),
+                Err(WorkspaceError::Unauthorized)
+            ));
+        }
+
+        let workspaces = Workspaces::new(&[config("alice", "key-a")], None)
+            .with_owner_token("secret".to_string());
+        assert!(workspaces.resolve(&owner).unwrap().is_none());
+        let alice = headers("x-api-key", "key-a");
+        assert_eq!(workspaces.resolve(&alice).unwrap().unwrap().name(), "alice");
+    }
+
+    #[test]
+    fn test_provider_allowlist() {
+        let mut cfg = config("alice", "key-a");
+        assert!(Workspace::new(cfg.clone(), None)
+            .check_provider("openai")
+            .is_ok());
+
+        cfg.providers = vec!["Claude".to_string()];
+        let workspace = Workspace::new(cfg, None);
+        assert_eq!(workspace.default_provider(), Some("Claude"));
+        assert!(workspace.check_provider("claude").is_ok());
+        assert!(matches!(
+            workspace.check_provider("openai"),
+            Err(WorkspaceError::Forbidden { .. })
+        ));
+    }
+
+    #[test]
+    fn test_daily_quota_blocks_until_midnight() {
+        let mut cfg = config("alice", "key-a");
+        cfg.daily_tokens = Some(100);
+        let workspace = Workspace::new(cfg, None);
+        let now = Utc.with_ymd_and_hms(2026, 3, 14, 23, 0, 0).unwrap();
+
+        assert!(workspace.check_quota_at(now).is_ok());
+        workspace
+            .usage
+            .lock()
+            .unwrap()
+            .add(&SessionUsage::call("local", 60, 40), now.date_naive());
+        match workspace.check_quota_at(now) {
+            Err(WorkspaceError::QuotaExceeded {
+                quota,
+                retry_after_secs,
+                ..
+            }) => {
+                assert_eq!(quota, "daily token quota");
+                assert_eq!(retry_after_secs, 3600);
+            }
+            other => panic!("expected quota error, got {:?}", other),
+        }
+
+        // A new day starts a new window
+        let tomorrow = now + ChronoDuration::hours(2);
+        assert!(workspace.check_quota_at(tomorrow).is_ok());
+        assert_eq!(workspace.usage.lock().unwrap().day_tokens, 0);
+        assert_eq!(workspace.usage.lock().unwrap().total.requests, 1);
+    }
+
+    #[test]
+    fn test_monthly_budget_resets_next_month() {
+        let mut cfg = config("alice", "key-a");
+        cfg.monthly_budget_usd = Some(1.0);
+        let workspace = Workspace::new(cfg, None);
+        let now = Utc.with_ymd_and_hms(2026, 12, 31, 12, 0, 0).unwrap();
+        let spend = SessionUsage {
+            requests: 1,
+            cost_usd: 1.5,
+            ..Default::default()
+        };
+        workspace
+            .usage
+            .lock()
+            .unwrap()
+            .add(&spend, now.date_naive());
+
+        match workspace.check_quota_at(now) {
+            Err(WorkspaceError::QuotaExceeded {
+                quota,
+                retry_after_secs,
+                ..
+            }) => {
+                assert_eq!(quota, "monthly budget");
+                assert_eq!(retry_after_secs, 12 * 3600);
+            }
+            other => panic!("expected quota error, got {:?}", other),
+        }
+        let january = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 1).unwrap();
+        assert!(workspace.check_quota_at(january).is_ok());
+    }
+
+    #[test]
+    fn test_usage_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let configs = [config("alice", "key-a")];
+        let workspaces = Workspaces::new(&configs, Some(dir.path()));
+        workspaces.workspaces[0].record_usage(&SessionUsage::call("local", 10, 5));
+        assert_eq!(workspaces.persist_usage().unwrap(), 1);
+
+        let restarted = Workspaces::new(&configs, Some(dir.path()));
+        let usage = restarted.workspaces[0].usage();
+        assert_eq!(usage.day_tokens, 15);
+        assert_eq!(usage.total.requests, 1);
+    }
+}
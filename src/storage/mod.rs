@@ -0,0 +1,166 @@
+// Pluggable storage for daemon sessions and metrics
+//
+// The daemon keeps sessions as whole JSON objects and uploads its metrics
+// day files, so a store only needs get/put/delete/list of keyed objects.
+// The local filesystem is the default; `[storage] backend = "s3"` moves
+// both to an S3-compatible bucket so a fleet of worker daemons keeps its
+// transcripts and usage data in one place.
+
+pub mod s3;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::{StorageConfig, StorageKind};
+
+pub use s3::S3Storage;
+
+/// Keyed object store behind session persistence and metrics uploads
+///
+/// Keys are relative, `/`-separated paths such as `abc-123.json`.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Object at `key`, `None` if there is none
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Create or replace the object at `key`
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()>;
+
+    /// Remove the object at `key`, returning whether it existed
+    async fn delete(&self, key: &str) -> Result<bool>;
+
+    /// Keys of every stored object, in no particular order
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Where objects go, for logs (a directory or `s3://bucket/prefix`)
+    fn location(&self) -> String;
+}
+
+/// Objects as files under a directory
+pub struct LocalStorage {
+    dir: PathBuf,
+}
+
+impl LocalStorage {
+    /// Store under `dir`, creating it if needed
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create storage dir: {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// File for `key`; keys that could leave the directory are rejected
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        let plain = !key.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !plain {
+            bail!("Invalid storage key: {}", key);
+        }
+        Ok(self.dir.join(relative))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write then rename, so readers never see a half-written object
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        tokio::fs::write(&tmp, bytes)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let keys = walkdir::WalkDir::new(&self.dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) != Some("tmp"))
+            .filter_map(|entry| {
+                let relative = entry.path().strip_prefix(&self.dir).ok()?;
+                let parts: Vec<_> = relative.iter().map(|p| p.to_str()).collect::<Option<_>>()?;
+                Some(parts.join("/"))
+            })
+            .collect();
+        Ok(keys)
+    }
+
+    fn location(&self) -> String {
+        self.dir.display().to_string()
+    }
+}
+
+/// The configured remote store for `area` (`"sessions"` or `"metrics"`),
+/// `None` when storage is local
+pub fn remote(config: &StorageConfig, area: &str) -> Result<Option<Arc<dyn StorageBackend>>> {
+    match config.backend {
+        StorageKind::Local => Ok(None),
+        StorageKind::S3 => Ok(Some(Arc::new(S3Storage::from_config(config, area)?))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().join("store")).unwrap();
+
+        assert_eq!(storage.get("a.json").await.unwrap(), None);
+        storage.put("a.json", b"one".to_vec()).await.unwrap();
+        storage
+            .put("node/2026-10-18.jsonl", b"two".to_vec())
+            .await
+            .unwrap();
+        storage.put("a.json", b"three".to_vec()).await.unwrap();
+        assert_eq!(
+            storage.get("a.json").await.unwrap(),
+            Some(b"three".to_vec())
+        );
+
+        let mut keys = storage.list().await.unwrap();
+        keys.sort();
+        assert_eq!(keys, ["a.json", "node/2026-10-18.jsonl"]);
+
+        assert!(storage.delete("a.json").await.unwrap());
+        assert!(!storage.delete("a.json").await.unwrap());
+
+        // Keys never reach outside the directory
+        for key in ["../escape", "/etc/passwd", "a/../../b", ""] {
+            assert!(storage.get(key).await.is_err(), "{key}");
+        }
+    }
+}
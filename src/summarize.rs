@@ -0,0 +1,431 @@
+// Summaries of files and web pages (`finch summarize`)
+//
+// An input that fits in one request is summarized with a single routed query.
+// Larger inputs are map-reduced: every chunk is summarized on its own, and the
+// chunk summaries are combined (re-chunking until they fit, for at most
+// SUMMARIZE_MAX_ROUNDS rounds) for the final summary.  Chunk summaries go to
+// the local model when it is loaded; the final summary is an ordinary query
+// that the daemon's router places.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::config::constants::{SUMMARIZE_CHUNK_CHARS, SUMMARIZE_MAX_BYTES, SUMMARIZE_MAX_ROUNDS};
+use crate::memory::docs::chunk_text;
+
+/// How long the final summary should be
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryLength {
+    /// A few sentences
+    #[default]
+    Short,
+    /// An overview plus the main points under headings
+    Detailed,
+}
+
+impl SummaryLength {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Detailed => "detailed",
+        }
+    }
+
+    fn instructions(self) -> &'static str {
+        match self {
+            Self::Short => "Summarize it in 3 to 5 sentences of plain prose.",
+            Self::Detailed => {
+                "Write a detailed summary in markdown: a one-paragraph overview, \
+                 then the main points as bullet lists under short headings."
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for SummaryLength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "short" => Ok(Self::Short),
+            "detailed" => Ok(Self::Detailed),
+            other => Err(format!(
+                "unknown summary length '{other}' (expected short or detailed)"
+            )),
+        }
+    }
+}
+
+/// Text to summarize and where it came from
+#[derive(Debug, Clone)]
+pub struct Source {
+    /// The path or URL as given
+    pub label: String,
+    pub text: String,
+}
+
+impl Source {
+    /// Read a local file, or fetch an `http(s)://` URL (HTML is reduced to
+    /// the markdown of its main content)
+    pub async fn load(target: &str) -> Result<Self> {
+        let text = if target.starts_with("http://") || target.starts_with("https://") {
+            fetch_url(target).await?
+        } else {
+            read_file(Path::new(target))?
+        };
+        if text.trim().is_empty() {
+            bail!("{} has no text to summarize", target);
+        }
+        Ok(Self {
+            label: target.to_string(),
+            text,
+        })
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    let len = std::fs::metadata(path)
+        .with_context(|| format!("Cannot read {}", path.display()))?
+        .len();
+    if len > SUMMARIZE_MAX_BYTES as u64 {
+        bail!(
+            "{} is {} MB; finch summarize reads at most {} MB",
+            path.display(),
+            len / (1024 * 1024),
+            SUMMARIZE_MAX_BYTES / (1024 * 1024)
+        );
+    }
+    let bytes = std::fs::read(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    String::from_utf8(bytes).with_context(|| format!("{} is not a text file", path.display()))
+}
+
+async fn fetch_url(url: &str) -> Result<String> {
+    let client = crate::http::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(concat!("finch/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("html"));
+    let base = response.url().clone();
+    let too_large = || {
+        anyhow::anyhow!(
+            "{} is larger than {} MB",
+            url,
+            SUMMARIZE_MAX_BYTES / (1024 * 1024)
+        )
+    };
+    if response
+        .content_length()
+        .is_some_and(|len| len > SUMMARIZE_MAX_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+    // Stream the body so a server that lies about (or omits) its length
+    // can't make us buffer more than the limit
+    let mut body = Vec::new();
+    let mut response = response;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to read {}", url))?
+    {
+        if body.len() + chunk.len() > SUMMARIZE_MAX_BYTES {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = String::from_utf8_lossy(&body);
+    Ok(if is_html {
+        crate::web::html_to_markdown(&body, Some(&base))
+    } else {
+        body.into_owned()
+    })
+}
+
+/// Where summarization requests go
+#[async_trait]
+pub trait SummaryBackend: Send + Sync {
+    /// Answer with the local model only; errors when it isn't available
+    async fn local(&self, prompt: &str) -> Result<String>;
+
+    /// Answer however the router decides
+    async fn routed(&self, prompt: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl SummaryBackend for crate::client::DaemonClient {
+    async fn local(&self, prompt: &str) -> Result<String> {
+        self.query_local_only(prompt).await
+    }
+
+    async fn routed(&self, prompt: &str) -> Result<String> {
+        self.query_text(prompt).await
+    }
+}
+
+/// A finished summary (the `--json` output)
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub source: String,
+    pub length: SummaryLength,
+    /// Chunks the input was split into (1 = summarized in one request)
+    pub chunks: usize,
+    /// Chunk summaries the local model wrote
+    pub local_chunks: usize,
+    pub summary: String,
+}
+
+impl Summary {
+    /// Markdown for the terminal or a pipe
+    pub fn to_markdown(&self) -> String {
+        format!("## Summary of {}\n\n{}\n", self.source, self.summary.trim())
+    }
+}
+
+/// Summarize `source`, reporting progress through `progress`
+pub async fn summarize(
+    backend: &dyn SummaryBackend,
+    source: &Source,
+    length: SummaryLength,
+    mut progress: impl FnMut(&str),
+) -> Result<Summary> {
+    let chunks = chunk_text(&source.text, SUMMARIZE_CHUNK_CHARS);
+    let chunk_count = chunks.len();
+    let mut local_chunks = 0;
+
+    let final_prompt = if chunk_count <= 1 {
+        format!(
+            "Here is the content of {}.\n{}\n\n{}",
+            source.label,
+            length.instructions(),
+            source.text.trim()
+        )
+    } else {
+        // Map: summarize every chunk, then keep folding the partial
+        // summaries until they fit in one request.  Summaries that barely
+        // shrink would fold forever, so the last round keeps what fits.
+        let mut parts = chunks;
+        let mut round = 1;
+        loop {
+            let total = parts.len();
+            let mut partials = Vec::with_capacity(total);
+            for (i, part) in parts.iter().enumerate() {
+                progress(&format!(
+                    "Summarizing part {}/{}{}",
+                    i + 1,
+                    total,
+                    if round > 1 { " (combining)" } else { "" }
+                ));
+                let prompt = format!(
+                    "This is part {} of {} of {}. Summarize it in a short paragraph, \
+                     keeping every key fact, name and number.\n\n{}",
+                    i + 1,
+                    total,
+                    source.label,
+                    part
+                );
+                let (partial, local) = map_chunk(backend, &prompt).await?;
+                local_chunks += usize::from(local);
+                partials.push(partial.trim().to_string());
+            }
+            let mut combined = partials.join("\n\n");
+            if round == SUMMARIZE_MAX_ROUNDS && combined.len() > SUMMARIZE_CHUNK_CHARS {
+                tracing::warn!(
+                    "Chunk summaries still too long after {} rounds; truncating",
+                    round
+                );
+                let mut end = SUMMARIZE_CHUNK_CHARS;
+                while !combined.is_char_boundary(end) {
+                    end -= 1;
+                }
+                combined.truncate(end);
+            }
+            if combined.len() <= SUMMARIZE_CHUNK_CHARS || total == 1 {
+                break format!(
+                    "Below are summaries of consecutive parts of {}.\n{}\n\n{}",
+                    source.label,
+                    length.instructions(),
+                    combined
+                );
+            }
+            parts = chunk_text(&combined, SUMMARIZE_CHUNK_CHARS);
+            round += 1;
+        }
+    };
+
+    progress("Writing summary");
+    let summary = backend.routed(&final_prompt).await?;
+    Ok(Summary {
+        source: source.label.clone(),
+        length,
+        chunks: chunk_count,
+        local_chunks,
+        summary: summary.trim().to_string(),
+    })
+}
+
+/// Summarize one chunk locally when possible; `true` when the local model
+/// wrote it
+pub(crate) async fn map_chunk(backend: &dyn SummaryBackend, prompt: &str) -> Result<(String, bool)> {
+    match backend.local(prompt).await {
+        Ok(text) if !text.trim().is_empty() => Ok((text, true)),
+        Ok(_) => Ok((backend.routed(prompt).await?, false)),
+        Err(e) => {
+            tracing::debug!("Local chunk summary unavailable ({}), routing instead", e);
+            Ok((backend.routed(prompt).await?, false))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records prompts; the local model is "loaded" when `local_ready`
+    struct FakeBackend {
+        local_ready: bool,
+        prompts: Mutex<Vec<(&'static str, String)>>,
+    }
+
+    impl FakeBackend {
+        fn new(local_ready: bool) -> Self {
+            Self {
+                local_ready,
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SummaryBackend for FakeBackend {
+        async fn local(&self, prompt: &str) -> Result<String> {
+            if !self.local_ready {
+                bail!("Local model not ready");
+            }
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(("local", prompt.to_string()));
+            Ok("local summary".to_string())
+        }
+
+        async fn routed(&self, prompt: &str) -> Result<String> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push(("routed", prompt.to_string()));
+            Ok("routed summary".to_string())
+        }
+    }
+
+    fn source(text: String) -> Source {
+        Source {
+            label: "notes.txt".to_string(),
+            text,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_input_is_one_routed_query() {
+        let backend = FakeBackend::new(true);
+        let summary = summarize(
+            &backend,
+            &source("A short note.".to_string()),
+            SummaryLength::Short,
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.chunks, 1);
+        assert_eq!(summary.local_chunks, 0);
+        assert_eq!(summary.summary, "routed summary");
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].1.contains("A short note."));
+    }
+
+    #[tokio::test]
+    async fn test_large_input_maps_locally_and_reduces_routed() {
+        let line = "x".repeat(100);
+        let text = vec![line; SUMMARIZE_CHUNK_CHARS * 3 / 100].join("\n");
+        let backend = FakeBackend::new(true);
+        let summary = summarize(&backend, &source(text), SummaryLength::Detailed, |_| {})
+            .await
+            .unwrap();
+
+        assert!(summary.chunks >= 3);
+        assert_eq!(summary.local_chunks, summary.chunks);
+        let prompts = backend.prompts.lock().unwrap();
+        let (kind, last) = prompts.last().unwrap();
+        assert_eq!(*kind, "routed");
+        assert!(last.contains("local summary"));
+        assert!(last.contains("bullet lists"));
+    }
+
+    #[tokio::test]
+    async fn test_chunks_are_routed_when_local_model_is_unavailable() {
+        let text = vec!["y".repeat(100); SUMMARIZE_CHUNK_CHARS * 2 / 100].join("\n");
+        let backend = FakeBackend::new(false);
+        let summary = summarize(&backend, &source(text), SummaryLength::Short, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(summary.local_chunks, 0);
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), summary.chunks + 1);
+        assert!(prompts.iter().all(|(kind, _)| *kind == "routed"));
+    }
+
+    /// Echoes every prompt back, so chunk summaries never get shorter
+    struct EchoBackend {
+        calls: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl SummaryBackend for EchoBackend {
+        async fn local(&self, prompt: &str) -> Result<String> {
+            *self.calls.lock().unwrap() += 1;
+            Ok(prompt.to_string())
+        }
+
+        async fn routed(&self, prompt: &str) -> Result<String> {
+            self.local(prompt).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_combining_stops_after_max_rounds() {
+        let text = vec!["z".repeat(100); SUMMARIZE_CHUNK_CHARS * 4 / 100].join("\n");
+        let backend = EchoBackend {
+            calls: Mutex::new(0),
+        };
+        let summary = summarize(&backend, &source(text), SummaryLength::Short, |_| {})
+            .await
+            .unwrap();
+
+        // The final prompt holds at most one chunk of combined summaries
+        assert!(summary.summary.len() <= SUMMARIZE_CHUNK_CHARS + 200);
+        assert!(*backend.calls.lock().unwrap() < summary.chunks * SUMMARIZE_MAX_ROUNDS * 2);
+    }
+
+    #[test]
+    fn test_length_parses() {
+        assert_eq!("Detailed".parse(), Ok(SummaryLength::Detailed));
+        assert!("medium".parse::<SummaryLength>().is_err());
+    }
+}
@@ -0,0 +1,165 @@
+// Change preview — the diff a write / edit / patch call would make
+//
+// The approval dialog shows this diff so the user approves the change itself
+// rather than just "edit file X".  The agent's dry-run mode uses the same
+// rules to simulate these tools without touching the disk.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+
+use crate::tools::implementations::patch::{apply_patch, unified_diff};
+use crate::tools::types::ToolUse;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const GRAY: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Whether `tool` modifies a file (and so has a change to preview)
+pub fn modifies_file(tool: &str) -> bool {
+    matches!(tool.to_lowercase().as_str(), "write" | "edit" | "patch")
+}
+
+/// The content a write / edit / patch call leaves in a file that holds
+/// `before` (`None` if the file doesn't exist)
+pub fn changed_content(tool: &str, input: &Value, before: Option<&str>) -> Result<String> {
+    let file_path = input["file_path"]
+        .as_str()
+        .context("Missing file_path parameter")?;
+    if tool.eq_ignore_ascii_case("write") {
+        return Ok(input["content"]
+            .as_str()
+            .context("Missing content parameter")?
+            .to_string());
+    }
+
+    let original = before.with_context(|| format!("Failed to read file: {}", file_path))?;
+    if tool.eq_ignore_ascii_case("edit") {
+        apply_edit(original, input, file_path)
+    } else {
+        let patch = input["patch"].as_str().context("Missing patch parameter")?;
+        apply_patch(original, patch)
+            .with_context(|| format!("Failed to apply patch to {}", file_path))
+    }
+}
+
+/// Unified diff of the change `tool_use` would make on disk.  `None` for
+/// tools that don't modify files; an error if the call would fail.
+pub fn preview(tool_use: &ToolUse) -> Option<Result<String>> {
+    if !modifies_file(&tool_use.name) {
+        return None;
+    }
+    Some(diff_on_disk(&tool_use.name, &tool_use.input))
+}
+
+fn diff_on_disk(tool: &str, input: &Value) -> Result<String> {
+    let file_path = input["file_path"]
+        .as_str()
+        .context("Missing file_path parameter")?;
+    let before = fs::read_to_string(file_path).ok();
+    let after = changed_content(tool, input, before.as_deref())?;
+    Ok(unified_diff(
+        file_path,
+        before.as_deref().unwrap_or(""),
+        &after,
+    ))
+}
+
+/// `diff` with terminal colors, without its `---` / `+++` header
+pub fn colorize(diff: &str) -> String {
+    let mut colored = String::new();
+    for line in diff.lines() {
+        if line.starts_with("--- ") || line.starts_with("+++ ") {
+            continue;
+        }
+        let color = match line.chars().next() {
+            Some('+') => GREEN,
+            Some('-') => RED,
+            Some('@') => CYAN,
+            _ => GRAY,
+        };
+        colored.push_str(&format!("{}{}{}\n", color, line, RESET));
+    }
+    if colored.is_empty() {
+        colored = format!("{}(no changes){}\n", GRAY, RESET);
+    }
+    colored
+}
+
+/// Apply an edit tool call to `original`, with the edit tool's match rules
+fn apply_edit(original: &str, input: &Value, file_path: &str) -> Result<String> {
+    let old_string = input["old_string"]
+        .as_str()
+        .context("Missing old_string parameter")?;
+    let new_string = input["new_string"]
+        .as_str()
+        .context("Missing new_string parameter")?;
+    let replace_all = input["replace_all"].as_bool().unwrap_or(false);
+
+    match original.matches(old_string).count() {
+        0 => anyhow::bail!("old_string not found in {}", file_path),
+        1 => Ok(original.replacen(old_string, new_string, 1)),
+        _ if replace_all => Ok(original.replace(old_string, new_string)),
+        n => anyhow::bail!(
+            "old_string appears {} times in {}. Use replace_all: true or make it more specific.",
+            n,
+            file_path
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_use(name: &str, input: Value) -> ToolUse {
+        ToolUse {
+            id: "toolu_1".to_string(),
+            name: name.to_string(),
+            input,
+        }
+    }
+
+    #[test]
+    fn test_preview_shows_the_change_without_making_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "fn a() {}\nfn b() {}\n").unwrap();
+        let path = file.to_str().unwrap();
+
+        let edit = tool_use(
+            "Edit",
+            json!({"file_path": path, "old_string": "fn b() {}", "new_string": "fn c() {}"}),
+        );
+        let diff = preview(&edit).unwrap().unwrap();
+        assert!(diff.ends_with("@@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "fn a() {}\nfn b() {}\n");
+
+        let new_file = dir.path().join("new.txt");
+        let write = tool_use(
+            "write",
+            json!({"file_path": new_file.to_str().unwrap(), "content": "hi\n"}),
+        );
+        assert!(preview(&write).unwrap().unwrap().ends_with("+hi\n"));
+
+        let missing = tool_use(
+            "edit",
+            json!({"file_path": path, "old_string": "fn z()", "new_string": ""}),
+        );
+        assert!(preview(&missing).unwrap().is_err());
+        assert!(preview(&tool_use("bash", json!({"command": "ls"}))).is_none());
+    }
+
+    #[test]
+    fn test_colorize() {
+        let colored = colorize("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n+new\n");
+        assert_eq!(
+            colored,
+            format!("{CYAN}@@ -1 +1 @@{RESET}\n{RED}-old{RESET}\n{GREEN}+new{RESET}\n")
+        );
+        assert!(colorize("--- a/x\n+++ b/x\n").contains("(no changes)"));
+    }
+}
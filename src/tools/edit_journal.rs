@@ -0,0 +1,290 @@
+// Edit journal — undo for write / edit / patch
+//
+// Every file change a tool makes in this session is recorded: the path, a
+// hash of the file before and after, the diff, and what the file held before.
+// `/undo` reverts the last change and `/rollback N` the last N, newest first,
+// whatever the state of git (or in a directory that isn't a repository).
+//
+// A change is only reverted while the file still reads as the tool left it.
+// If it has been edited since, by the user or by `bash`, the rollback stops
+// there rather than throw that work away.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::tools::implementations::patch::unified_diff;
+use crate::tools::types::ToolUse;
+
+/// Tools whose changes are journaled
+const JOURNALED_TOOLS: &[&str] = &["write", "edit", "patch"];
+
+/// Changes kept; older ones can no longer be undone
+const MAX_ENTRIES: usize = 200;
+
+/// One file change made by a tool
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    /// Tool that made the change
+    pub tool: String,
+    /// As the tool was given it
+    pub path: PathBuf,
+    /// SHA-256 of the file before; `None` if the tool created it
+    pub before_hash: Option<String>,
+    /// SHA-256 of the file as the tool left it
+    pub after_hash: String,
+    /// Unified diff of the change
+    pub diff: String,
+    /// The file's contents before, restored on undo
+    before: Option<Vec<u8>>,
+}
+
+impl JournalEntry {
+    /// e.g. "edit src/main.rs (+2 -1)"
+    pub fn summary(&self) -> String {
+        let (added, removed) = self
+            .diff
+            .lines()
+            .filter(|line| !line.starts_with("+++") && !line.starts_with("---"))
+            .fold((0, 0), |(added, removed), line| {
+                match line.as_bytes().first() {
+                    Some(b'+') => (added + 1, removed),
+                    Some(b'-') => (added, removed + 1),
+                    _ => (added, removed),
+                }
+            });
+        let created = if self.before_hash.is_none() {
+            ", created"
+        } else {
+            ""
+        };
+        format!(
+            "{} {} (+{} -{}{})",
+            self.tool,
+            self.path.display(),
+            added,
+            removed,
+            created
+        )
+    }
+
+    /// Put the file back as it was before the change
+    fn revert(&self) -> Result<()> {
+        let current = fs::read(&self.path).ok();
+        if current.as_deref().map(hash).as_ref() != Some(&self.after_hash) {
+            anyhow::bail!(
+                "{} has changed since the {} — not reverting it",
+                self.path.display(),
+                self.tool
+            );
+        }
+        match &self.before {
+            Some(contents) => fs::write(&self.path, contents)
+                .with_context(|| format!("Failed to restore {}", self.path.display())),
+            None => fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove {}", self.path.display())),
+        }
+    }
+}
+
+/// A file as it was just before a journaled tool ran
+#[derive(Debug)]
+pub struct PendingEdit {
+    tool: String,
+    path: PathBuf,
+    before: Option<Vec<u8>>,
+}
+
+/// What a rollback did
+#[derive(Debug, Default)]
+pub struct Rollback {
+    /// Changes reverted, newest first
+    pub reverted: Vec<JournalEntry>,
+    /// Why it stopped short of the requested number of changes
+    pub stopped: Option<String>,
+}
+
+/// File changes made by tools this session, oldest first
+#[derive(Debug, Default)]
+pub struct EditJournal {
+    entries: Mutex<Vec<JournalEntry>>,
+}
+
+impl EditJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the file `tool_use` is about to change; `None` for tools that
+    /// aren't journaled.  Pass the result to [`record`](Self::record) once
+    /// the tool has succeeded.
+    pub fn before(&self, tool_use: &ToolUse) -> Option<PendingEdit> {
+        if !JOURNALED_TOOLS.contains(&tool_use.name.as_str()) {
+            return None;
+        }
+        let path = PathBuf::from(tool_use.input["file_path"].as_str()?);
+        let before = if path.exists() {
+            match fs::read(&path) {
+                Ok(contents) => Some(contents),
+                Err(e) => {
+                    tracing::warn!("Not journaling {}: {}", path.display(), e);
+                    return None;
+                }
+            }
+        } else {
+            None
+        };
+        Some(PendingEdit {
+            tool: tool_use.name.clone(),
+            path,
+            before,
+        })
+    }
+
+    /// Record the change the tool made since [`before`](Self::before)
+    pub fn record(&self, pending: PendingEdit) {
+        let Ok(after) = fs::read(&pending.path) else {
+            return;
+        };
+        if pending.before.as_deref() == Some(after.as_slice()) {
+            return;
+        }
+        let before_text = pending
+            .before
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        let entry = JournalEntry {
+            diff: unified_diff(
+                &pending.path.to_string_lossy(),
+                &before_text,
+                &String::from_utf8_lossy(&after),
+            ),
+            tool: pending.tool,
+            path: pending.path,
+            before_hash: pending.before.as_deref().map(hash),
+            after_hash: hash(&after),
+            before: pending.before,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == MAX_ENTRIES {
+            entries.remove(0);
+        }
+        entries.push(entry);
+    }
+
+    /// Changes that can be undone, oldest first
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Revert the last `n` changes, newest first.  Stops at the first one
+    /// that can't be reverted, which stays in the journal.
+    pub fn rollback(&self, n: usize) -> Rollback {
+        let mut entries = self.entries.lock().unwrap();
+        let mut rollback = Rollback::default();
+        while rollback.reverted.len() < n {
+            let Some(entry) = entries.last() else {
+                if rollback.reverted.is_empty() {
+                    rollback.stopped = Some("No file changes to undo".to_string());
+                } else {
+                    rollback.stopped = Some("No earlier file changes to undo".to_string());
+                }
+                break;
+            };
+            if let Err(e) = entry.revert() {
+                rollback.stopped = Some(format!("{:#}", e));
+                break;
+            }
+            rollback.reverted.extend(entries.pop());
+        }
+        rollback
+    }
+}
+
+/// Hex SHA-256 of `contents`
+fn hash(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn tool_use(name: &str, path: &Path) -> ToolUse {
+        ToolUse {
+            id: "t1".to_string(),
+            name: name.to_string(),
+            input: json!({ "file_path": path.to_str().unwrap() }),
+        }
+    }
+
+    /// Have `tool` write `contents` to `path`, journaled
+    fn change(journal: &EditJournal, tool: &str, path: &Path, contents: &str) {
+        let pending = journal.before(&tool_use(tool, path)).unwrap();
+        fs::write(path, contents).unwrap();
+        journal.record(pending);
+    }
+
+    #[test]
+    fn test_undo_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        let created = dir.path().join("new.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+
+        let journal = EditJournal::new();
+        assert!(journal.before(&tool_use("read", &file)).is_none());
+        change(&journal, "edit", &file, "fn main() { a(); }\n");
+        change(&journal, "write", &created, "fn a() {}\n");
+        change(&journal, "patch", &file, "fn main() { b(); }\n");
+        assert_eq!(journal.len(), 3);
+        let entries = journal.entries();
+        assert!(entries[0].diff.contains("+fn main() { a(); }"));
+        assert_eq!(entries[1].before_hash, None);
+        assert_eq!(hash(&fs::read(&file).unwrap()), entries[2].after_hash);
+
+        let undo = journal.rollback(1);
+        assert_eq!(undo.reverted.len(), 1);
+        assert!(undo.stopped.is_none());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "fn main() { a(); }\n");
+
+        let rollback = journal.rollback(5);
+        assert_eq!(rollback.reverted.len(), 2);
+        assert!(rollback.stopped.is_some(), "asked for more than there was");
+        assert!(!created.exists(), "a created file is removed again");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "fn main() {}\n");
+        assert!(journal.is_empty());
+    }
+
+    #[test]
+    fn test_rollback_stops_at_files_changed_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        fs::write(&file, "one\n").unwrap();
+
+        let journal = EditJournal::new();
+        change(&journal, "write", &file, "two\n");
+        // Edited outside the journal afterwards
+        fs::write(&file, "three\n").unwrap();
+
+        let rollback = journal.rollback(1);
+        assert!(rollback.reverted.is_empty());
+        assert!(rollback.stopped.unwrap().contains("has changed since"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "three\n");
+        assert_eq!(journal.len(), 1, "the entry is kept");
+    }
+}
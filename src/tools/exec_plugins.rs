@@ -0,0 +1,431 @@
+// Tools backed by executables, speaking JSON over stdin/stdout
+//
+// For tools not worth writing as WebAssembly, any program can be declared as
+// a tool in config.toml.  Each call starts the program in the project
+// directory (where finch was started), writes the tool call to its stdin and
+// reads the result from its stdout:
+//
+//   stdin:  {"id": "toolu_…", "name": "jira_lookup", "input": {"key": "OPS-12"}}
+//   stdout: {"content": "OPS-12: Rotate the staging certs (open)", "is_error": false}
+//
+// `~/` is expanded in the program and its arguments.  A non-zero exit status, output that isn't a result, or running past
+// `timeout_secs` fails the call.  The program runs with the user's rights, so
+// its calls are approved like any other tool's, and its `category` says what
+// it may do:
+//
+//   read   only looks: runs alongside other reads, allowed in plan mode and
+//          in agent dry runs
+//   write  changes something (the default): runs on its own, blocked in plan
+//          mode, not run in agent dry runs
+//
+//   [exec_plugins.jira_lookup]
+//   command = ["python3", "~/tools/jira.py"]
+//   description = "Look up a Jira issue by key"
+//   schema = { type = "object", properties = { key = { type = "string" } }, required = ["key"] }
+//   category = "read"
+//   timeout_secs = 30
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::expand_home;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema, ToolUse};
+
+static SETTINGS: OnceLock<ExecPluginsConfig> = OnceLock::new();
+
+/// Apply `[exec_plugins]`.  Called once when the config is loaded; later
+/// calls are ignored.
+pub fn init(config: &ExecPluginsConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// `[exec_plugins]` in config.toml: plugin tools by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExecPluginsConfig {
+    pub plugins: BTreeMap<String, ExecPluginConfig>,
+}
+
+/// One executable tool, from `[exec_plugins.<name>]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecPluginConfig {
+    /// Program and arguments; a leading `~/` in any of them is expanded
+    pub command: Vec<String>,
+    /// Description shown to the model
+    pub description: String,
+    /// JSON Schema of the input object
+    #[serde(default = "default_schema")]
+    pub schema: Value,
+    #[serde(default)]
+    pub category: PluginCategory,
+    /// Limit for each call
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// What a plugin tool may do, which decides where it is allowed to run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginCategory {
+    /// Only looks at things
+    Read,
+    /// Changes files or outside systems
+    #[default]
+    Write,
+}
+
+fn default_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+fn default_timeout_secs() -> u64 {
+    crate::config::constants::DEFAULT_EXEC_PLUGIN_TIMEOUT_SECS
+}
+
+impl ExecPluginsConfig {
+    pub fn is_default(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, plugin) in &self.plugins {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                bail!(
+                    "exec_plugins: '{}' is not a valid tool name (use a-z, 0-9 and _)",
+                    name
+                );
+            }
+            if plugin
+                .command
+                .first()
+                .is_none_or(|program| program.is_empty())
+            {
+                bail!("exec_plugins.{}: command is empty", name);
+            }
+            if !plugin.schema.is_object() {
+                bail!("exec_plugins.{}: schema must be a table", name);
+            }
+            if plugin.timeout_secs == 0 {
+                bail!("exec_plugins.{}: timeout_secs must be at least 1", name);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Category of the plugin tool `name`, or `None` if it isn't one
+pub fn category(name: &str) -> Option<PluginCategory> {
+    SETTINGS.get()?.plugins.get(name).map(|p| p.category)
+}
+
+/// Whether `name` is a plugin tool declared as `read`
+pub fn is_read_only(name: &str) -> bool {
+    category(name) == Some(PluginCategory::Read)
+}
+
+/// Tools for every plugin in `[exec_plugins]`
+pub fn load_plugins() -> Vec<Box<dyn Tool>> {
+    let Some(config) = SETTINGS.get() else {
+        return Vec::new();
+    };
+    config
+        .plugins
+        .iter()
+        .map(|(name, plugin)| {
+            tracing::info!("[plugins] declared {} as {:?}", name, plugin.command);
+            Box::new(ExecTool::new(name, plugin)) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+/// A tool that runs an executable per call
+pub struct ExecTool {
+    name: String,
+    program: PathBuf,
+    args: Vec<OsString>,
+    /// Directory the program runs in
+    dir: PathBuf,
+    description: String,
+    schema: ToolInputSchema,
+    timeout: Duration,
+}
+
+/// What a plugin writes to stdout
+#[derive(Deserialize)]
+struct PluginOutput {
+    content: String,
+    #[serde(default)]
+    is_error: bool,
+}
+
+impl ExecTool {
+    pub fn new(name: &str, config: &ExecPluginConfig) -> Self {
+        let program = config.command.first().map(String::as_str).unwrap_or("");
+        let program = expand_home(program);
+        let required = config
+            .schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| {
+                r.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            name: name.to_string(),
+            program,
+            args: config
+                .command
+                .iter()
+                .skip(1)
+                .map(|arg| expand_home(arg).into_os_string())
+                .collect(),
+            dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            description: config.description.clone(),
+            schema: ToolInputSchema {
+                schema_type: "object".to_string(),
+                properties: config
+                    .schema
+                    .get("properties")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({})),
+                required,
+            },
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+
+    /// Run in `dir` instead of the directory finch was started in
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    async fn run(&self, call: &ToolUse) -> Result<String> {
+        let mut child = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start {}", self.program.display()))?;
+
+        let request = serde_json::to_vec(call)?;
+        let mut stdin = child.stdin.take().context("Plugin stdin unavailable")?;
+        // Written alongside reading the output, so a plugin that answers
+        // before it has read everything can't block the call.  A plugin may
+        // also exit without reading; its status and output still decide.
+        let writer = tokio::spawn(async move {
+            let _ = stdin.write_all(&request).await;
+        });
+
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output()).await;
+        writer.abort();
+        let output = match output {
+            Ok(output) => output.context("Plugin did not finish")?,
+            Err(_) => bail!("{} timed out after {}s", self.name, self.timeout.as_secs()),
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "{} exited with {}: {}",
+                self.name,
+                output.status,
+                stderr.trim()
+            );
+        }
+        let result: PluginOutput = serde_json::from_slice(&output.stdout).with_context(|| {
+            format!(
+                "{} did not print a result ({{\"content\": \"…\"}}) on stdout",
+                self.name
+            )
+        })?;
+        if result.is_error {
+            bail!("{}", result.content);
+        }
+        Ok(result.content)
+    }
+}
+
+#[async_trait]
+impl Tool for ExecTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        self.schema.clone()
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let call = ToolUse {
+            id: ToolUse::generate_id(),
+            name: self.name.clone(),
+            input,
+        };
+        self.run(&call).await
+    }
+
+    async fn execute_call(&self, call: &ToolUse, _context: &ToolContext<'_>) -> Result<String> {
+        self.run(call).await
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn plugin(script: &str, timeout_secs: u64) -> ExecTool {
+        ExecTool::new(
+            "probe",
+            &ExecPluginConfig {
+                command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+                description: "test plugin".to_string(),
+                schema: serde_json::json!({
+                    "type": "object",
+                    "properties": { "key": { "type": "string" } },
+                    "required": ["key"],
+                }),
+                category: PluginCategory::Read,
+                timeout_secs,
+            },
+        )
+    }
+
+    fn call(input: Value) -> ToolUse {
+        ToolUse {
+            id: "toolu_1".to_string(),
+            name: "probe".to_string(),
+            input,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_over_stdio() {
+        // Answer with the requested key, taken from the call on stdin
+        let tool = plugin(
+            r#"sed 's/.*"name":"\([a-z]*\)".*"key":"\([^"]*\)".*/{"content": "\1 found \2"}/'"#,
+            5,
+        );
+        assert_eq!(tool.input_schema().required, vec!["key".to_string()]);
+        let out = tool
+            .run(&call(serde_json::json!({ "key": "OPS-12" })))
+            .await
+            .unwrap();
+        assert_eq!(out, "probe found OPS-12");
+    }
+
+    #[tokio::test]
+    async fn test_runs_in_project_dir_with_the_call_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = plugin(
+            r#"id=$(sed 's/.*"id":"\([^"]*\)".*/\1/'); printf '{"content": "%s in %s"}' "$id" "$(pwd -P)""#,
+            5,
+        )
+        .with_dir(dir.path());
+        let context = ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        };
+        let out = tool
+            .execute_call(&call(serde_json::json!({ "key": "OPS-12" })), &context)
+            .await
+            .unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+        assert_eq!(out, format!("toolu_1 in {}", dir.display()));
+    }
+
+    #[test]
+    fn test_home_is_expanded_in_arguments() {
+        let tool = ExecTool::new(
+            "jira_lookup",
+            &ExecPluginConfig {
+                command: vec!["python3".to_string(), "~/tools/jira.py".to_string()],
+                description: "Look up an issue".to_string(),
+                schema: default_schema(),
+                category: PluginCategory::Read,
+                timeout_secs: 5,
+            },
+        );
+        assert_eq!(tool.program, PathBuf::from("python3"));
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(tool.args, vec![home.join("tools/jira.py").into_os_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_failures() {
+        let tool = plugin(
+            r#"echo '{"content": "no such issue", "is_error": true}'"#,
+            5,
+        );
+        let err = tool.run(&call(Value::Null)).await.unwrap_err();
+        assert_eq!(err.to_string(), "no such issue");
+
+        let tool = plugin("echo broken >&2; exit 3", 5);
+        let err = tool.run(&call(Value::Null)).await.unwrap_err();
+        assert!(err.to_string().contains("broken"), "{}", err);
+
+        let tool = plugin("echo not json", 5);
+        assert!(tool.run(&call(Value::Null)).await.is_err());
+
+        let tool = plugin("sleep 10", 1);
+        let err = tool.run(&call(Value::Null)).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate() {
+        let config: ExecPluginsConfig = toml::from_str(
+            r#"
+            [jira_lookup]
+            command = ["jira-tool"]
+            description = "Look up an issue"
+            category = "read"
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let plugin = &config.plugins["jira_lookup"];
+        assert_eq!(plugin.category, PluginCategory::Read);
+        assert_eq!(plugin.timeout_secs, default_timeout_secs());
+
+        let mut bad = config.clone();
+        let plugin = bad.plugins.remove("jira_lookup").unwrap();
+        bad.plugins
+            .insert("Jira-Lookup".to_string(), plugin.clone());
+        assert!(bad.validate().is_err());
+
+        let mut bad = config;
+        bad.plugins.get_mut("jira_lookup").unwrap().command.clear();
+        assert!(bad.validate().is_err());
+    }
+}
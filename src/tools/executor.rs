@@ -3,14 +3,22 @@
 // Executes tools with permission checks and multi-turn support
 
 use crate::cli::ConversationHistory;
+use crate::tools::audit::{Approval, AuditCall};
+use crate::tools::edit_journal::EditJournal;
+use crate::tools::parallel::ToolGate;
+use crate::tools::path_sandbox::PathSandbox;
 use crate::tools::patterns::{ExactApproval, MatchType, PersistentPatternStore, ToolPattern};
 use crate::tools::permissions::{PermissionCheck, PermissionManager};
 use crate::tools::registry::ToolRegistry;
+use crate::tools::rename_check::RenameCheck;
+use crate::tools::result_budget::ToolResultBudget;
+use crate::tools::result_cache::{ResultCache, Snapshot};
 use crate::tools::types::{ToolResult, ToolUse};
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, error, info, instrument, warn};
 
 // ─── Co-Forth trace helpers ───────────────────────────────────────────────────
@@ -22,6 +30,7 @@ fn tool_label(name: &str, input: &serde_json::Value) -> String {
         "Read"     | "read"      => input["path"].as_str().unwrap_or("").to_string(),
         "Glob"     | "glob"      => input["pattern"].as_str().unwrap_or("").to_string(),
         "Grep"     | "grep"      => input["pattern"].as_str().unwrap_or("").to_string(),
+        "outline"                => input["file_path"].as_str().unwrap_or("").to_string(),
         "Bash"     | "bash"      => {
             let cmd = input["command"].as_str().unwrap_or("");
             cmd.chars().take(32).collect::<String>()
@@ -32,6 +41,17 @@ fn tool_label(name: &str, input: &serde_json::Value) -> String {
             let url = input["url"].as_str().unwrap_or("");
             url.chars().take(32).collect::<String>()
         }
+        "web_search"             => {
+            let query = input["query"].as_str().unwrap_or("");
+            query.chars().take(32).collect::<String>()
+        }
+        "http"                   => {
+            let method = input["method"].as_str().unwrap_or("GET").to_ascii_uppercase();
+            let url = input["url"].as_str().unwrap_or("");
+            format!("{} {}", method, url.chars().take(32).collect::<String>())
+        }
+        "run_code"               => input["language"].as_str().unwrap_or("").to_string(),
+        "spawn_task"             => input["subagent_type"].as_str().unwrap_or("general").to_string(),
         _ => String::new(),
     };
     let display_name = name.to_uppercase();
@@ -226,9 +246,23 @@ impl ToolConfirmationCache {
 /// Tool executor - manages tool execution lifecycle
 pub struct ToolExecutor {
     registry: ToolRegistry,
-    permissions: PermissionManager,
+    permissions: Arc<PermissionManager>,
     confirmation_cache: ToolConfirmationCache,
     mcp_client: Option<Arc<crate::tools::mcp::McpClient>>,
+    /// Token caps applied to every result before it reaches the model
+    result_budget: ToolResultBudget,
+    /// Holds `edit` renames that would leave references behind
+    rename_check: Arc<RenameCheck>,
+    /// File changes made by write / edit / patch, for `/undo`
+    edit_journal: Arc<EditJournal>,
+    /// Read / grep / glob results, reused while their files are unchanged
+    result_cache: Arc<ResultCache>,
+    /// Admits tool calls, letting read-only ones run side by side
+    gate: ToolGate,
+    /// Per-tool time limits from `features.tool_timeouts`
+    timeouts: Arc<HashMap<String, Duration>>,
+    /// Directories the file tools may read and change
+    path_sandbox: Arc<PathSandbox>,
     /// When set, every successful tool call auto-pushes a node into the poset.
     /// The execution trace becomes the Co-Forth vocabulary.
     pub poset: Option<Arc<tokio::sync::Mutex<crate::poset::Poset>>>,
@@ -243,9 +277,16 @@ impl ToolExecutor {
     ) -> Result<Self> {
         Ok(Self {
             registry,
-            permissions,
+            permissions: Arc::new(permissions),
             confirmation_cache: ToolConfirmationCache::new(patterns_path)?,
             mcp_client: None,
+            result_budget: ToolResultBudget::default(),
+            rename_check: Arc::default(),
+            edit_journal: Arc::default(),
+            result_cache: Arc::default(),
+            gate: ToolGate::default(),
+            timeouts: Arc::default(),
+            path_sandbox: Arc::new(crate::tools::path_sandbox::active().clone()),
             poset: None,
         })
     }
@@ -253,7 +294,7 @@ impl ToolExecutor {
     /// Add MCP client to enable MCP tools
     ///
     /// Always returns Self (never fails) - gracefully handles MCP connection errors
-    pub async fn with_mcp(mut self, config: &crate::config::Config) -> Self {
+    pub async fn with_mcp(self, config: &crate::config::Config) -> Self {
         if !config.mcp_servers.is_empty() {
             info!(
                 "Initializing MCP client with {} servers",
@@ -266,7 +307,7 @@ impl ToolExecutor {
                         "MCP client initialized with {} connected servers",
                         connected_servers.len()
                     );
-                    self.mcp_client = Some(Arc::new(mcp_client));
+                    return self.with_mcp_client(Arc::new(mcp_client)).await;
                 }
                 Err(e) => {
                     warn!("Failed to initialize MCP client: {}", e);
@@ -278,6 +319,108 @@ impl ToolExecutor {
         self
     }
 
+    /// Use an already connected MCP client, registering its tools
+    ///
+    /// MCP tools live in the registry like any other tool, so permission
+    /// rules, plan mode and the result budget apply to them too.
+    pub async fn with_mcp_client(mut self, client: Arc<crate::tools::mcp::McpClient>) -> Self {
+        for tool in crate::tools::mcp::registry_tools(&client).await {
+            self.registry.register(tool);
+        }
+        self.mcp_client = Some(client);
+        self
+    }
+
+    /// Re-discover MCP tools and resources, replacing the registered ones
+    ///
+    /// Returns the number of MCP tools now registered.
+    pub async fn refresh_mcp_tools(&mut self) -> Result<usize> {
+        let Some(client) = self.mcp_client.clone() else {
+            return Ok(0);
+        };
+        client.refresh_all_tools().await?;
+
+        for name in self.registry.tool_names() {
+            if name.starts_with("mcp_") {
+                self.registry.unregister(&name);
+            }
+        }
+        let tools = crate::tools::mcp::registry_tools(&client).await;
+        let count = tools.len();
+        for tool in tools {
+            self.registry.register(tool);
+        }
+        Ok(count)
+    }
+
+    /// Register the WebAssembly tool plugins installed for the current
+    /// project and the executables declared in `[exec_plugins]`.  Plugins
+    /// can't replace tools that are already registered.
+    pub fn with_plugins(mut self) -> Self {
+        let project = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let plugins = crate::tools::wasm_plugins::load_plugins(&project)
+            .into_iter()
+            .chain(crate::tools::exec_plugins::load_plugins());
+        for tool in plugins {
+            if self.registry.has_tool(tool.name()) {
+                warn!(
+                    "[plugins] not loading plugin {}: a tool of that name exists",
+                    tool.name()
+                );
+                continue;
+            }
+            self.registry.register(tool);
+        }
+        self
+    }
+
+    /// Replace the default tool result budget
+    pub fn with_result_budget(mut self, budget: ToolResultBudget) -> Self {
+        self.result_budget = budget;
+        self
+    }
+
+    /// Replace the rename check (disabled by default)
+    pub fn with_rename_check(mut self, check: RenameCheck) -> Self {
+        self.rename_check = Arc::new(check);
+        self
+    }
+
+    /// Limit how many read-only tool calls run at once (1 runs every call on
+    /// its own)
+    pub fn with_max_parallel_tools(mut self, limit: usize) -> Self {
+        self.gate = ToolGate::new(limit);
+        self
+    }
+
+    /// Give tools their own time limits in seconds, e.g. `{ bash = 600 }`,
+    /// overriding the caller's default
+    pub fn with_tool_timeouts(mut self, secs_by_tool: &HashMap<String, u64>) -> Self {
+        let timeouts = secs_by_tool
+            .iter()
+            .map(|(name, secs)| (name.clone(), Duration::from_secs(*secs)))
+            .collect();
+        self.timeouts = Arc::new(timeouts);
+        self
+    }
+
+    /// Replace the path sandbox (by default the project's, from its
+    /// permissions file)
+    pub fn with_path_sandbox(mut self, sandbox: PathSandbox) -> Self {
+        self.set_path_sandbox(sandbox);
+        self
+    }
+
+    /// Replace the path sandbox for the calls that follow
+    pub fn set_path_sandbox(&mut self, sandbox: PathSandbox) {
+        self.path_sandbox = Arc::new(sandbox);
+    }
+
+    /// File changes made by tools this session (`/undo`, `/rollback`)
+    pub fn edit_journal(&self) -> &Arc<EditJournal> {
+        &self.edit_journal
+    }
+
     /// Get reference to MCP client (for management commands)
     pub fn mcp_client(&self) -> Option<&Arc<crate::tools::mcp::McpClient>> {
         self.mcp_client.as_ref()
@@ -287,7 +430,7 @@ impl ToolExecutor {
     pub async fn list_all_tools(&self) -> Vec<crate::tools::types::ToolDefinition> {
         let mut tools = Vec::new();
 
-        // Add built-in tools from registry
+        // MCP tools are registered alongside the built-in ones
         for tool_name in self.registry.tool_names() {
             if let Some(tool) = self.registry.get(&tool_name) {
                 tools.push(crate::tools::types::ToolDefinition {
@@ -298,12 +441,6 @@ impl ToolExecutor {
             }
         }
 
-        // Add MCP tools if client is available
-        if let Some(mcp) = &self.mcp_client {
-            let mcp_tools = mcp.list_tools().await;
-            tools.extend(mcp_tools);
-        }
-
         tools
     }
 
@@ -358,8 +495,10 @@ impl ToolExecutor {
     }
 
     /// Execute a single tool use
+    ///
+    /// Runs while `self` is borrowed; to run several calls at once, take a
+    /// [`ToolRunner`] with [`runner`](Self::runner) and release the lock.
     #[allow(clippy::too_many_arguments)]
-    #[instrument(skip(self, tool_use, conversation, save_models_fn, batch_trainer, local_generator, tokenizer, repl_mode, plan_content, live_output, stack), fields(tool = %tool_use.name, id = %tool_use.id))]
     pub async fn execute_tool<F>(
         &self,
         tool_use: &ToolUse,
@@ -378,44 +517,259 @@ impl ToolExecutor {
     where
         F: Fn() -> Result<()> + Send + Sync,
     {
-        info!("Executing tool: {}", tool_use.name);
+        self.runner()
+            .execute_tool(
+                tool_use,
+                conversation,
+                save_models_fn,
+                batch_trainer,
+                local_generator,
+                tokenizer,
+                repl_mode,
+                plan_content,
+                live_output,
+                stack,
+            )
+            .await
+    }
 
-        // 1. Check if it's an MCP tool
-        if tool_use.name.starts_with("mcp_") {
-            if let Some(mcp) = &self.mcp_client {
-                info!("Routing to MCP client: {}", tool_use.name);
-                match mcp
-                    .execute_tool(&tool_use.name, tool_use.input.clone())
-                    .await
-                {
-                    Ok(output) => {
-                        info!("MCP tool executed successfully");
-                        return Ok(ToolResult::success(tool_use.id.clone(), output));
-                    }
-                    Err(e) => {
-                        error!("MCP tool execution failed: {}", e);
-                        return Ok(ToolResult::error(
-                            tool_use.id.clone(),
-                            format!("MCP execution error: {}", e),
-                        ));
-                    }
+    /// A detached copy of what tool calls need (tools, permission rules,
+    /// result budget, rename check, edit journal, result cache, gate, time
+    /// limits, path sandbox, poset),
+    /// cheap to take per call
+    pub fn runner(&self) -> ToolRunner {
+        ToolRunner {
+            registry: self.registry.clone(),
+            permissions: Arc::clone(&self.permissions),
+            result_budget: self.result_budget.clone(),
+            rename_check: Arc::clone(&self.rename_check),
+            edit_journal: Arc::clone(&self.edit_journal),
+            result_cache: Arc::clone(&self.result_cache),
+            gate: self.gate.clone(),
+            timeouts: Arc::clone(&self.timeouts),
+            path_sandbox: Arc::clone(&self.path_sandbox),
+            poset: self.poset.clone(),
+            approval: Approval::Automatic,
+        }
+    }
+
+    /// Execute multiple tool uses in sequence
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(
+        self,
+        tool_uses,
+        conversation,
+        save_models_fn,
+        batch_trainer,
+        local_generator,
+        tokenizer
+    ))]
+    pub async fn execute_tool_loop<F>(
+        &self,
+        tool_uses: Vec<ToolUse>,
+        conversation: Option<&ConversationHistory>,
+        save_models_fn: Option<F>,
+        batch_trainer: Option<
+            Arc<tokio::sync::RwLock<crate::training::batch_trainer::BatchTrainer>>,
+        >,
+        local_generator: Option<Arc<tokio::sync::RwLock<crate::local::LocalGenerator>>>,
+        tokenizer: Option<Arc<crate::models::tokenizer::TextTokenizer>>,
+        repl_mode: Option<Arc<tokio::sync::RwLock<crate::cli::ReplMode>>>,
+        plan_content: Option<Arc<tokio::sync::RwLock<Option<String>>>>,
+    ) -> Result<Vec<ToolResult>>
+    where
+        F: Fn() -> Result<()> + Send + Sync + Clone,
+    {
+        info!("Executing {} tool(s)", tool_uses.len());
+
+        let mut results = Vec::new();
+
+        for tool_use in tool_uses {
+            let result = self
+                .execute_tool(
+                    &tool_use,
+                    conversation,
+                    save_models_fn.clone(),
+                    batch_trainer.clone(),
+                    local_generator.clone(),
+                    tokenizer.clone(),
+                    repl_mode.clone(),
+                    plan_content.clone(),
+                    None, // live_output
+                    None, // stack
+                )
+                .await?;
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Get reference to registry
+    pub fn registry(&self) -> &ToolRegistry {
+        &self.registry
+    }
+
+    /// Get reference to permissions manager
+    pub fn permissions(&self) -> &PermissionManager {
+        &self.permissions
+    }
+}
+
+/// Runs tool calls without holding the [`ToolExecutor`] (and its lock)
+///
+/// Tools are shared, so calls taken from the same runner, or from runners
+/// taken at different times, can run concurrently.
+#[derive(Clone)]
+pub struct ToolRunner {
+    registry: ToolRegistry,
+    permissions: Arc<PermissionManager>,
+    result_budget: ToolResultBudget,
+    rename_check: Arc<RenameCheck>,
+    edit_journal: Arc<EditJournal>,
+    result_cache: Arc<ResultCache>,
+    gate: ToolGate,
+    timeouts: Arc<HashMap<String, Duration>>,
+    path_sandbox: Arc<PathSandbox>,
+    poset: Option<Arc<tokio::sync::Mutex<crate::poset::Poset>>>,
+    /// How the calls this runner makes were approved, for the audit log
+    approval: Approval,
+}
+
+impl ToolRunner {
+    /// Record calls made by this runner as approved by `approval`
+    pub fn with_approval(mut self, approval: Approval) -> Self {
+        self.approval = approval;
+        self
+    }
+
+    /// The gate shared by every runner of the same executor; hold a permit
+    /// from it while a call runs
+    pub fn gate(&self) -> &ToolGate {
+        &self.gate
+    }
+
+    /// How long a call to `tool_name` may run: its configured limit, or
+    /// `default`
+    pub fn timeout_for(&self, tool_name: &str, default: Duration) -> Duration {
+        self.timeouts.get(tool_name).copied().unwrap_or(default)
+    }
+
+    /// Execute the tool uses of one response, running independent ones
+    /// concurrently
+    ///
+    /// Results come back in the order of `tool_uses`. A call still running
+    /// after its limit (`timeout` unless the tool has its own) gets an error
+    /// result; the other calls are unaffected.
+    pub async fn execute_batch(
+        &self,
+        tool_uses: &[ToolUse],
+        timeout: Duration,
+    ) -> Vec<Result<ToolResult>> {
+        info!(
+            "Executing {} tool(s), up to {} at once",
+            tool_uses.len(),
+            self.gate.limit()
+        );
+        let calls = tool_uses.iter().map(|tool_use| async move {
+            let _permit = self.gate.enter(&tool_use.name).await;
+            let call = self.execute_tool::<fn() -> Result<()>>(
+                tool_use, None, // conversation
+                None, // save_models_fn
+                None, // batch_trainer
+                None, // local_generator
+                None, // tokenizer
+                None, // repl_mode
+                None, // plan_content
+                None, // live_output
+                None, // stack
+            );
+            let timeout = self.timeout_for(&tool_use.name, timeout);
+            match tokio::time::timeout(timeout, call).await {
+                Ok(result) => result,
+                Err(_) => {
+                    warn!("Tool {} timed out after {:?}", tool_use.name, timeout);
+                    Ok(ToolResult::error(
+                        tool_use.id.clone(),
+                        format!("Tool '{}' timed out after {:?}", tool_use.name, timeout),
+                    ))
                 }
-            } else {
-                error!("MCP tool requested but no MCP client available");
-                return Ok(ToolResult::error(
-                    tool_use.id.clone(),
-                    "MCP tools not available (no MCP servers configured)".to_string(),
-                ));
             }
-        }
+        });
+        futures::future::join_all(calls).await
+    }
 
-        // 2. Check if built-in tool exists
+    /// Execute a single tool use
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(self, tool_use, conversation, save_models_fn, batch_trainer, local_generator, tokenizer, repl_mode, plan_content, live_output, stack), fields(tool = %tool_use.name, id = %tool_use.id))]
+    pub async fn execute_tool<F>(
+        &self,
+        tool_use: &ToolUse,
+        conversation: Option<&ConversationHistory>,
+        save_models_fn: Option<F>,
+        batch_trainer: Option<
+            Arc<tokio::sync::RwLock<crate::training::batch_trainer::BatchTrainer>>,
+        >,
+        local_generator: Option<Arc<tokio::sync::RwLock<crate::local::LocalGenerator>>>,
+        tokenizer: Option<Arc<crate::models::tokenizer::TextTokenizer>>,
+        repl_mode: Option<Arc<tokio::sync::RwLock<crate::cli::ReplMode>>>,
+        plan_content: Option<Arc<tokio::sync::RwLock<Option<String>>>>,
+        live_output: Option<Arc<dyn Fn(String) + Send + Sync>>,
+        stack: Option<Arc<tokio::sync::Mutex<Vec<String>>>>,
+    ) -> Result<ToolResult>
+    where
+        F: Fn() -> Result<()> + Send + Sync,
+    {
+        // Recorded when the call ends, or as stopped if it is dropped first
+        let mut audit = AuditCall::start(tool_use, self.approval);
+        let result = self
+            .run_tool(
+                &mut audit,
+                tool_use,
+                conversation,
+                save_models_fn,
+                batch_trainer,
+                local_generator,
+                tokenizer,
+                repl_mode,
+                plan_content,
+                live_output,
+                stack,
+            )
+            .await;
+        audit.finish(&result);
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_tool<F>(
+        &self,
+        audit: &mut AuditCall,
+        tool_use: &ToolUse,
+        conversation: Option<&ConversationHistory>,
+        save_models_fn: Option<F>,
+        batch_trainer: Option<
+            Arc<tokio::sync::RwLock<crate::training::batch_trainer::BatchTrainer>>,
+        >,
+        local_generator: Option<Arc<tokio::sync::RwLock<crate::local::LocalGenerator>>>,
+        tokenizer: Option<Arc<crate::models::tokenizer::TextTokenizer>>,
+        repl_mode: Option<Arc<tokio::sync::RwLock<crate::cli::ReplMode>>>,
+        plan_content: Option<Arc<tokio::sync::RwLock<Option<String>>>>,
+        live_output: Option<Arc<dyn Fn(String) + Send + Sync>>,
+        stack: Option<Arc<tokio::sync::Mutex<Vec<String>>>>,
+    ) -> Result<ToolResult>
+    where
+        F: Fn() -> Result<()> + Send + Sync,
+    {
+        info!("Executing tool: {}", tool_use.name);
+
+        // 1. Check if tool exists (built-in or MCP)
         let tool = self
             .registry
             .get(&tool_use.name)
             .context(format!("Tool '{}' not found", tool_use.name))?;
 
-        // 3. Check permissions
+        // 2. Check permissions
         let permission_check = self
             .permissions
             .check_tool_use(&tool_use.name, &tool_use.input);
@@ -432,11 +786,22 @@ impl ToolExecutor {
             }
             PermissionCheck::Deny(reason) => {
                 error!("Tool execution denied: {}", reason);
+                audit.deny();
                 return Ok(ToolResult::error(tool_use.id.clone(), reason));
             }
         }
 
-        // 3. Check plan mode restrictions
+        // 3. Keep file tools inside the project and the allowed directories
+        if let Err(denial) = self.path_sandbox.check(tool_use) {
+            warn!(
+                "Tool {} refused by the path sandbox: {} ({})",
+                tool_use.name, denial.path, denial.reason
+            );
+            audit.deny();
+            return Ok(ToolResult::error(tool_use.id.clone(), denial.to_error()));
+        }
+
+        // 4. Check plan mode restrictions
         if let Some(ref mode) = repl_mode {
             let current_mode = mode.read().await;
             if let crate::cli::ReplMode::Planning { .. } = &*current_mode {
@@ -445,7 +810,9 @@ impl ToolExecutor {
                     "read",
                     "glob",
                     "grep",
+                    "outline",
                     "web_fetch",
+                    "web_search",
                     "enter_plan_mode",
                     "EnterPlanMode",
                     "present_plan",
@@ -460,7 +827,7 @@ impl ToolExecutor {
                         tool_use.id.clone(),
                         format!(
                             "Tool '{}' is not allowed in planning mode.\n\
-                             Available tools: read, glob, grep, web_fetch, present_plan, ask_user_question\n\
+                             Available tools: read, glob, grep, outline, web_fetch, present_plan, ask_user_question\n\
                              Use PresentPlan to show your plan for approval.",
                             tool_use.name
                         ),
@@ -470,7 +837,13 @@ impl ToolExecutor {
             drop(current_mode);
         }
 
-        // 4. Execute tool with context
+        // 5. Hold a rename that would leave references behind: the model gets
+        // the list instead, and applies the edit by sending it again
+        if let Some(report) = self.rename_check.check(tool_use).await {
+            return Ok(ToolResult::error(tool_use.id.clone(), report));
+        }
+
+        // 6. Execute tool with context
         let context = crate::tools::types::ToolContext {
             conversation,
             save_models: save_models_fn
@@ -486,12 +859,32 @@ impl ToolExecutor {
             poset: None,
         };
 
-        match tool.execute(tool_use.input.clone(), &context).await {
+        // A repeated read / grep / glob over files that haven't changed
+        if let Some(output) = self.result_cache.get(tool_use) {
+            self.poset_record_tool(&tool_use.name, &tool_use.input)
+                .await;
+            let output = self.result_budget.apply(&tool_use.name, output);
+            return Ok(ToolResult::success(tool_use.id.clone(), output));
+        }
+        let snapshot = Snapshot::take(tool_use);
+
+        // What a write / edit / patch is about to change, so it can be undone
+        let pending_edit = self.edit_journal.before(tool_use);
+        match tool.execute_call(tool_use, &context).await {
             Ok(output) => {
                 info!("Tool executed successfully");
+                if let Some(pending) = pending_edit {
+                    self.edit_journal.record(pending);
+                }
+                self.result_cache.invalidate(tool_use);
+                if let Some(snapshot) = snapshot {
+                    self.result_cache.insert(tool_use, snapshot, &output);
+                }
                 // Auto-push a node into the poset so the execution trace
                 // becomes the Co-Forth vocabulary.
                 self.poset_record_tool(&tool_use.name, &tool_use.input).await;
+                // Cap the result before it is appended to the conversation
+                let output = self.result_budget.apply(&tool_use.name, output);
                 Ok(ToolResult::success(tool_use.id.clone(), output))
             }
             Err(e) => {
@@ -529,68 +922,6 @@ impl ToolExecutor {
             poset.edges.push((new_id - 1, new_id));
         }
     }
-
-    /// Execute multiple tool uses in sequence
-    #[allow(clippy::too_many_arguments)]
-    #[instrument(skip(
-        self,
-        tool_uses,
-        conversation,
-        save_models_fn,
-        batch_trainer,
-        local_generator,
-        tokenizer
-    ))]
-    pub async fn execute_tool_loop<F>(
-        &self,
-        tool_uses: Vec<ToolUse>,
-        conversation: Option<&ConversationHistory>,
-        save_models_fn: Option<F>,
-        batch_trainer: Option<
-            Arc<tokio::sync::RwLock<crate::training::batch_trainer::BatchTrainer>>,
-        >,
-        local_generator: Option<Arc<tokio::sync::RwLock<crate::local::LocalGenerator>>>,
-        tokenizer: Option<Arc<crate::models::tokenizer::TextTokenizer>>,
-        repl_mode: Option<Arc<tokio::sync::RwLock<crate::cli::ReplMode>>>,
-        plan_content: Option<Arc<tokio::sync::RwLock<Option<String>>>>,
-    ) -> Result<Vec<ToolResult>>
-    where
-        F: Fn() -> Result<()> + Send + Sync + Clone,
-    {
-        info!("Executing {} tool(s)", tool_uses.len());
-
-        let mut results = Vec::new();
-
-        for tool_use in tool_uses {
-            let result = self
-                .execute_tool(
-                    &tool_use,
-                    conversation,
-                    save_models_fn.clone(),
-                    batch_trainer.clone(),
-                    local_generator.clone(),
-                    tokenizer.clone(),
-                    repl_mode.clone(),
-                    plan_content.clone(),
-                    None, // live_output
-                    None, // stack
-                )
-                .await?;
-            results.push(result);
-        }
-
-        Ok(results)
-    }
-
-    /// Get reference to registry
-    pub fn registry(&self) -> &ToolRegistry {
-        &self.registry
-    }
-
-    /// Get reference to permissions manager
-    pub fn permissions(&self) -> &PermissionManager {
-        &self.permissions
-    }
 }
 
 /// Generate a context-specific signature for a tool use
@@ -625,6 +956,16 @@ pub fn generate_tool_signature(tool_use: &ToolUse, working_dir: &std::path::Path
                 directory: Some(working_dir.display().to_string()),
             }
         }
+        "outline" => {
+            let file_path = tool_use.input["file_path"].as_str().unwrap_or("");
+            ToolSignature {
+                tool_name: "outline".to_string(),
+                context_key: format!("outlining {}", file_path),
+                command: None,
+                args: None,
+                directory: Some(working_dir.display().to_string()),
+            }
+        }
         "glob" => {
             let pattern = tool_use.input["pattern"].as_str().unwrap_or("");
             ToolSignature {
@@ -660,6 +1001,81 @@ pub fn generate_tool_signature(tool_use: &ToolUse, working_dir: &std::path::Path
                 directory: None,
             }
         }
+        "web_search" => {
+            // Searches all go to the one configured backend
+            ToolSignature {
+                tool_name: "web_search".to_string(),
+                context_key: "searching the web".to_string(),
+                command: None,
+                args: None,
+                directory: None,
+            }
+        }
+        "browser" => {
+            // Opening a site is approved per host; the other actions work on
+            // whatever page is already open
+            let action = tool_use.input["action"].as_str().unwrap_or("");
+            let host = tool_use.input["url"]
+                .as_str()
+                .and_then(|url| reqwest::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_string));
+            let context_key = match (action, host) {
+                ("navigate", Some(host)) => format!("browsing {}", host),
+                _ => format!("browser {} on the open page", action),
+            };
+            ToolSignature {
+                tool_name: "browser".to_string(),
+                context_key,
+                command: Some(action.to_string()),
+                args: None,
+                directory: None,
+            }
+        }
+        "http" => {
+            // API calls are approved per method and host: allowing GETs to a
+            // host doesn't allow DELETEs
+            let method = tool_use.input["method"]
+                .as_str()
+                .unwrap_or("GET")
+                .to_ascii_uppercase();
+            let host = tool_use.input["url"]
+                .as_str()
+                .and_then(|url| reqwest::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            ToolSignature {
+                tool_name: "http".to_string(),
+                context_key: format!("{} requests to {}", method, host),
+                command: Some(method),
+                args: None,
+                directory: None,
+            }
+        }
+        "spawn_task" => {
+            // The subagent runs its type's tools without asking, so approval
+            // is per subagent type
+            let subagent_type = tool_use.input["subagent_type"]
+                .as_str()
+                .unwrap_or("general");
+            ToolSignature {
+                tool_name: "spawn_task".to_string(),
+                context_key: format!("{} subagent", subagent_type),
+                command: Some(subagent_type.to_string()),
+                args: None,
+                directory: None,
+            }
+        }
+        "run_code" => {
+            // Snippets are sandboxed, so approval is per language
+            let language = tool_use.input["language"].as_str().unwrap_or("");
+            ToolSignature {
+                tool_name: "run_code".to_string(),
+                context_key: format!("{} snippet", language),
+                command: Some(language.to_string()),
+                args: None,
+                directory: None,
+            }
+        }
         "save_and_exec" => {
             let command = tool_use.input["command"].as_str().unwrap_or("");
 
@@ -840,6 +1256,38 @@ mod tests {
         assert!(result.content.contains("Mock result"));
     }
 
+    #[tokio::test]
+    async fn test_execute_tool_applies_result_budget() {
+        let executor = create_test_executor(true, false).with_result_budget(ToolResultBudget {
+            max_tokens: 10,
+            per_tool: Default::default(),
+        });
+        let long_param: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let tool_use = ToolUse::new(
+            "mock".to_string(),
+            serde_json::json!({ "param": long_param }),
+        );
+
+        let result = executor
+            .execute_tool(
+                &tool_use,
+                None,
+                None::<fn() -> Result<()>>,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None, // live_output
+                None, // stack
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("token budget"));
+    }
+
     #[tokio::test]
     async fn test_execute_tool_not_found() {
         let executor = create_test_executor(true, false);
@@ -945,6 +1393,155 @@ mod tests {
         assert!(!results[1].is_error);
     }
 
+    // Sleeps before answering; registered under a read-only tool name
+    struct SlowTool {
+        name: &'static str,
+        delay_ms: u64,
+    }
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "A slow tool"
+        }
+
+        fn input_schema(&self) -> ToolInputSchema {
+            ToolInputSchema::simple(vec![("param", "Test parameter")])
+        }
+
+        async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            Ok(format!("{} {}", self.name, input["param"]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_runs_read_only_calls_concurrently() {
+        let mut executor = create_test_executor(true, false);
+        executor.registry.register(Box::new(SlowTool {
+            name: "read",
+            delay_ms: 100,
+        }));
+        executor.registry.register(Box::new(SlowTool {
+            name: "grep",
+            delay_ms: 1_000,
+        }));
+        let tool_uses = vec![
+            ToolUse::new("read".to_string(), json!({"param": 1})),
+            ToolUse::new("read".to_string(), json!({"param": 2})),
+            ToolUse::new("mock".to_string(), json!({"param": 3})),
+            ToolUse::new("read".to_string(), json!({"param": 4})),
+            ToolUse::new("grep".to_string(), json!({"param": 5})),
+        ];
+
+        let started = std::time::Instant::now();
+        let results = executor
+            .runner()
+            .execute_batch(&tool_uses, Duration::from_millis(500))
+            .await;
+        let elapsed = started.elapsed();
+
+        // Answered in order, the slow grep cut off by the timeout
+        let results: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(results[0].content, "read 1");
+        assert_eq!(results[1].content, "read 2");
+        assert_eq!(results[2].content, r#"Mock result: {"param":3}"#);
+        assert_eq!(results[3].content, "read 4");
+        assert!(results[4].is_error);
+        assert_eq!(results[4].content, "Tool 'grep' timed out after 500ms");
+        for (tool_use, result) in tool_uses.iter().zip(&results) {
+            assert_eq!(result.tool_use_id, tool_use.id);
+        }
+
+        // The first two reads overlap; the mock call waits for them, and the
+        // last read runs alongside the grep
+        assert!(elapsed < Duration::from_millis(900), "took {:?}", elapsed);
+        assert!(elapsed >= Duration::from_millis(600), "took {:?}", elapsed);
+
+        // A limit of 1 runs the reads one after another
+        let mut executor = create_test_executor(true, false).with_max_parallel_tools(1);
+        executor.registry.register(Box::new(SlowTool {
+            name: "read",
+            delay_ms: 100,
+        }));
+        let started = std::time::Instant::now();
+        executor
+            .runner()
+            .execute_batch(&tool_uses[..2], Duration::from_millis(500))
+            .await;
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_tool_timeouts_override_the_default() {
+        let mut executor = create_test_executor(true, false)
+            .with_tool_timeouts(&HashMap::from([("grep".to_string(), 0)]));
+        for name in ["read", "grep"] {
+            executor.registry.register(Box::new(SlowTool {
+                name,
+                delay_ms: 100,
+            }));
+        }
+        let tool_uses = [
+            ToolUse::new("read".to_string(), json!({"param": 1})),
+            ToolUse::new("grep".to_string(), json!({"param": 2})),
+        ];
+
+        let runner = executor.runner();
+        assert_eq!(
+            runner.timeout_for("grep", Duration::from_secs(30)),
+            Duration::ZERO
+        );
+        let results = runner
+            .execute_batch(&tool_uses, Duration::from_secs(30))
+            .await;
+        assert_eq!(results[0].as_ref().unwrap().content, "read 1");
+        assert_eq!(
+            results[1].as_ref().unwrap().content,
+            "Tool 'grep' timed out after 0ns"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_path_sandbox_refuses_calls_outside_the_project() {
+        let project = tempfile::tempdir().unwrap();
+        let sandbox = PathSandbox::new(
+            &crate::tools::path_sandbox::FilePermissions::default(),
+            project.path(),
+        );
+        let mut executor = create_test_executor(true, false).with_path_sandbox(sandbox);
+        executor.registry.register(Box::new(SlowTool {
+            name: "write",
+            delay_ms: 0,
+        }));
+        let inside = project.path().join("notes.txt");
+        let tool_uses = [
+            ToolUse::new(
+                "write".to_string(),
+                json!({"param": 1, "file_path": inside.to_str().unwrap()}),
+            ),
+            ToolUse::new(
+                "write".to_string(),
+                json!({"param": 2, "file_path": "/etc/finch-sandbox-test"}),
+            ),
+        ];
+
+        let results = executor
+            .runner()
+            .execute_batch(&tool_uses, Duration::from_secs(30))
+            .await;
+        assert_eq!(results[0].as_ref().unwrap().content, "write 1");
+        let refused = results[1].as_ref().unwrap();
+        assert!(refused.is_error);
+        let error: Value = serde_json::from_str(&refused.content).unwrap();
+        assert_eq!(error["error"], "path_outside_sandbox");
+        assert_eq!(error["path"], "/etc/finch-sandbox-test");
+    }
+
     #[test]
     fn test_confirmation_cache() {
         let temp_path = std::env::temp_dir().join("test_cache_patterns.json");
@@ -1058,6 +1655,83 @@ mod tests {
         assert_eq!(sig.context_key, "pattern 'fn main' in src/");
     }
 
+    #[test]
+    fn test_generate_tool_signature_browser_is_per_host() {
+        let working_dir = Path::new("/test/dir");
+        let navigate = |url: &str| {
+            let tool_use = ToolUse::new(
+                "browser".to_string(),
+                json!({"action": "navigate", "url": url}),
+            );
+            generate_tool_signature(&tool_use, working_dir)
+        };
+
+        assert_eq!(
+            navigate("https://docs.example.com/a").context_key,
+            "browsing docs.example.com"
+        );
+        assert_eq!(
+            navigate("https://docs.example.com/a"),
+            navigate("https://docs.example.com/b?q=1")
+        );
+        assert_ne!(
+            navigate("https://docs.example.com/"),
+            navigate("https://shop.example.com/")
+        );
+
+        let click = ToolUse::new(
+            "browser".to_string(),
+            json!({"action": "click", "selector": "#buy"}),
+        );
+        assert_eq!(
+            generate_tool_signature(&click, working_dir).context_key,
+            "browser click on the open page"
+        );
+    }
+
+    #[test]
+    fn test_generate_tool_signature_http_is_per_method_and_host() {
+        let working_dir = Path::new("/test/dir");
+        let request = |input: serde_json::Value| {
+            generate_tool_signature(&ToolUse::new("http".to_string(), input), working_dir)
+        };
+
+        let get = request(json!({"url": "https://api.github.com/repos/a/b"}));
+        assert_eq!(get.context_key, "GET requests to api.github.com");
+        assert_eq!(
+            get,
+            request(json!({"url": "https://api.github.com/user", "method": "get"}))
+        );
+        assert_ne!(
+            get,
+            request(json!({"url": "https://api.github.com/repos/a/b", "method": "DELETE"}))
+        );
+        assert_ne!(get, request(json!({"url": "https://gitlab.com/api/v4"})));
+    }
+
+    #[test]
+    fn test_generate_tool_signature_spawn_task_is_per_subagent_type() {
+        let working_dir = Path::new("/test/dir");
+        let spawn = |input: serde_json::Value| {
+            generate_tool_signature(&ToolUse::new("spawn_task".to_string(), input), working_dir)
+        };
+
+        let explore = spawn(json!({"task": "find the parser", "subagent_type": "explore"}));
+        assert_eq!(explore.context_key, "explore subagent");
+        assert_eq!(
+            explore,
+            spawn(json!({"task": "list the tests", "subagent_type": "explore"}))
+        );
+        assert_ne!(
+            explore,
+            spawn(json!({"task": "find the parser", "subagent_type": "bash"}))
+        );
+        assert_eq!(
+            spawn(json!({"task": "anything"})).context_key,
+            "general subagent"
+        );
+    }
+
     #[test]
     fn test_tool_signature_uniqueness() {
         let working_dir = Path::new("/test/dir");
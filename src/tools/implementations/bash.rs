@@ -1,15 +1,69 @@
 // Bash tool - executes shell commands with live output streaming
+//
+// Commands run on the host, or in a container when the project's
+// permissions file asks for it (see `crate::tools::sandbox`).  On unix each
+// tool instance keeps one shell alive across calls (see `crate::tools::shell`),
+// so `cd`, exports and virtualenv activation persist between commands.  The
+// shell is closed after it sits idle, or on request with `restart`, and a
+// command that is cancelled partway takes its shell down with it.
 
 use crate::tools::registry::Tool;
+use crate::tools::sandbox;
 use crate::tools::types::{ToolContext, ToolInputSchema};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::Value;
-use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
 
-pub struct BashTool;
+#[cfg(unix)]
+use crate::tools::shell::{Ended, ShellSession};
+
+/// How long a shell may sit unused before it is closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How long one command may run before it is terminated
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Characters of output returned to the model
+const MAX_OUTPUT_CHARS: usize = 20_000;
+
+pub struct BashTool {
+    shell: Arc<Mutex<ShellSlot>>,
+    idle_timeout: Duration,
+    command_timeout: Duration,
+}
+
+#[derive(Default)]
+struct ShellSlot {
+    #[cfg(unix)]
+    session: Option<ShellSession>,
+    /// Bumped for every shell started, so an idle reaper only closes its own
+    serial: u64,
+    /// The last shell was closed for being idle; said once in the next output
+    reaped: bool,
+}
+
+impl BashTool {
+    pub fn new() -> Self {
+        Self::with_timeouts(IDLE_TIMEOUT, COMMAND_TIMEOUT)
+    }
+
+    fn with_timeouts(idle_timeout: Duration, command_timeout: Duration) -> Self {
+        Self {
+            shell: Arc::new(Mutex::new(ShellSlot::default())),
+            idle_timeout,
+            command_timeout,
+        }
+    }
+}
+
+impl Default for BashTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[async_trait]
 impl Tool for BashTool {
@@ -18,88 +72,231 @@ impl Tool for BashTool {
     }
 
     fn description(&self) -> &str {
-        "Execute bash commands. Use for terminal operations like git, npm, ls, etc."
+        "Execute bash commands. Use for terminal operations like git, npm, ls, etc. \
+         Commands run in one persistent shell, so the working directory, exported \
+         variables and activated virtualenvs carry over between calls. \
+         Set restart to true to start a fresh shell first."
     }
 
     fn input_schema(&self) -> ToolInputSchema {
-        ToolInputSchema::simple(vec![
-            ("command", "The bash command to execute"),
-            ("description", "Brief description of what this command does"),
-        ])
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "command": {
+                    "type": "string",
+                    "description": "The bash command to execute"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Brief description of what this command does"
+                },
+                "restart": {
+                    "type": "boolean",
+                    "description": "Close the current shell and run the command in a fresh one"
+                }
+            }),
+            required: vec!["command".to_string(), "description".to_string()],
+        }
     }
 
     async fn execute(&self, input: Value, context: &ToolContext<'_>) -> Result<String> {
         let command = input["command"]
             .as_str()
             .context("Missing command parameter")?;
+        let restart = input["restart"].as_bool().unwrap_or(false);
 
-        let mut child = Command::new("bash")
-            .arg("-c")
-            .arg(command)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .with_context(|| format!("Failed to spawn command: {}", command))?;
-
-        let stdout = child.stdout.take().expect("stdout was piped");
-        let stderr = child.stderr.take().expect("stderr was piped");
-
-        // Clone the live output callback for the stdout reader
-        let live_cb = context.live_output.clone();
-
-        // Drain stderr in a background task so it doesn't block stdout reading
-        let stderr_task = tokio::spawn(async move {
-            let mut buf = String::new();
-            let mut lines = BufReader::new(stderr).lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                buf.push_str(&line);
-                buf.push('\n');
-            }
-            buf
-        });
+        #[cfg(unix)]
+        let result = self.run_in_shell(command, restart, context).await?;
+        #[cfg(not(unix))]
+        let result = {
+            let _ = restart;
+            run_once(command, context).await?
+        };
 
-        // Drain stdout on this task, calling the live-output callback per line
-        let mut stdout_buf = String::new();
-        let mut stdout_lines = BufReader::new(stdout).lines();
-        while let Ok(Some(line)) = stdout_lines.next_line().await {
-            if let Some(ref cb) = live_cb {
-                cb(line.clone());
+        // Limit to 20,000 chars
+        if result.len() > MAX_OUTPUT_CHARS {
+            let mut end = MAX_OUTPUT_CHARS;
+            while !result.is_char_boundary(end) {
+                end -= 1;
             }
-            stdout_buf.push_str(&line);
-            stdout_buf.push('\n');
+            Ok(format!(
+                "{}\n\n[Output truncated - showing first 20,000 characters]",
+                &result[..end]
+            ))
+        } else {
+            Ok(result)
         }
+    }
+}
 
-        let stderr_buf = stderr_task.await.unwrap_or_default();
-        let exit_status = child.wait().await?;
-        let exit_code = exit_status.code().unwrap_or(-1);
+#[cfg(unix)]
+impl BashTool {
+    async fn run_in_shell(
+        &self,
+        command: &str,
+        restart: bool,
+        context: &ToolContext<'_>,
+    ) -> Result<String> {
+        let mut slot = self.shell.lock().await;
+        let mut notes = Vec::new();
+        if restart {
+            slot.session = None;
+            slot.reaped = false;
+        }
+        if slot.session.as_ref().is_some_and(ShellSession::interrupted) {
+            slot.session = None;
+            notes.push(
+                "[The previous command was stopped before it finished, which closed \
+                 its shell; this is a new one, with the directory and environment reset]"
+                    .to_string(),
+            );
+        }
+        if std::mem::take(&mut slot.reaped) {
+            notes.push(format!(
+                "[The previous shell was closed after {} minutes idle; this is a new one]",
+                self.idle_timeout.as_secs() / 60
+            ));
+        }
+        if slot.session.is_none() {
+            let session = ShellSession::start(sandbox::active()).await?;
+            slot.session = Some(session);
+            slot.serial += 1;
+            self.watch_idle(slot.serial);
+        }
+        let session = slot.session.as_mut().expect("shell was just started");
 
-        let mut result = stdout_buf;
+        let output = session
+            .run(command, self.command_timeout, context.live_output.as_ref())
+            .await?;
 
-        if !stderr_buf.is_empty() {
-            if !result.is_empty() {
+        let mut result = output.text;
+        if output.truncated_chars > 0 {
+            notes.push(format!(
+                "[Output truncated - {} more characters]",
+                output.truncated_chars
+            ));
+        }
+        match output.ended {
+            Ended::Exited(0) => {}
+            Ended::Exited(code) => notes.push(format!("Exit code: {}", code)),
+            Ended::TimedOut => notes.push(format!(
+                "[Command terminated after running for {} seconds; the shell is still open]",
+                self.command_timeout.as_secs()
+            )),
+            Ended::ShellGone => {
+                slot.session = None;
+                notes.push(
+                    "[The shell exited; the next command starts a new one, \
+                     with the directory and environment reset]"
+                        .to_string(),
+                );
+            }
+        }
+        for note in notes {
+            if !result.is_empty() && !result.ends_with('\n') {
                 result.push('\n');
             }
-            result.push_str("STDERR:\n");
-            result.push_str(&stderr_buf);
+            result.push_str(&note);
         }
+        Ok(result)
+    }
 
-        if exit_code != 0 {
-            if !result.is_empty() {
-                result.push('\n');
+    /// Close shell number `serial` once it has been idle for `idle_timeout`
+    fn watch_idle(&self, serial: u64) {
+        let shell = Arc::downgrade(&self.shell);
+        let idle_timeout = self.idle_timeout;
+        tokio::spawn(async move {
+            let mut wait = idle_timeout;
+            loop {
+                tokio::time::sleep(wait).await;
+                let Some(shell) = shell.upgrade() else {
+                    return;
+                };
+                let mut slot = shell.lock().await;
+                if slot.serial != serial {
+                    return;
+                }
+                let Some(session) = &slot.session else {
+                    return;
+                };
+                let idle = session.idle_for();
+                if idle < idle_timeout {
+                    wait = idle_timeout - idle;
+                    continue;
+                }
+                tracing::debug!("Closing bash shell after {:?} idle", idle);
+                slot.session = None;
+                slot.reaped = true;
+                return;
             }
-            result.push_str(&format!("Exit code: {}", exit_code));
+        });
+    }
+}
+
+/// Run `command` in a shell of its own
+#[cfg(not(unix))]
+async fn run_once(command: &str, context: &ToolContext<'_>) -> Result<String> {
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let mut child = sandbox::active()
+        .command(command)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to spawn command: {}", command))?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    // Clone the live output callback for the stdout reader
+    let live_cb = context.live_output.clone();
+
+    // Drain stderr in a background task so it doesn't block stdout reading
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            buf.push_str(&line);
+            buf.push('\n');
         }
+        buf
+    });
 
-        // Limit to 20,000 chars
-        if result.len() > 20_000 {
-            Ok(format!(
-                "{}\n\n[Output truncated - showing first 20,000 characters]",
-                &result[..20_000]
-            ))
-        } else {
-            Ok(result)
+    // Drain stdout on this task, calling the live-output callback per line
+    let mut stdout_buf = String::new();
+    let mut stdout_lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = stdout_lines.next_line().await {
+        if let Some(ref cb) = live_cb {
+            cb(line.clone());
+        }
+        stdout_buf.push_str(&line);
+        stdout_buf.push('\n');
+    }
+
+    let stderr_buf = stderr_task.await.unwrap_or_default();
+    let exit_status = child.wait().await?;
+    let exit_code = exit_status.code().unwrap_or(-1);
+
+    let mut result = stdout_buf;
+
+    if !stderr_buf.is_empty() {
+        if !result.is_empty() {
+            result.push('\n');
         }
+        result.push_str("STDERR:\n");
+        result.push_str(&stderr_buf);
     }
+
+    if exit_code != 0 {
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(&format!("Exit code: {}", exit_code));
+    }
+
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -123,7 +320,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_echo() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": "echo 'Hello, World!'",
             "description": "Test echo command"
@@ -135,7 +332,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_ls() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": "ls Cargo.toml",
             "description": "List Cargo.toml"
@@ -147,7 +344,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_nonzero_exit() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": "ls /nonexistent",
             "description": "Try to list nonexistent directory"
@@ -161,7 +358,7 @@ mod tests {
     #[tokio::test]
     async fn test_bash_live_output_callback_receives_lines() {
         use std::sync::{Arc, Mutex};
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": "printf 'line1\\nline2\\nline3\\n'",
             "description": "Test live output streaming"
@@ -212,7 +409,7 @@ mod tests {
     #[tokio::test]
     async fn test_bash_live_output_callback_receives_lines_in_order() {
         use std::sync::{Arc, Mutex};
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": "for i in 1 2 3 4 5; do echo \"item$i\"; done",
             "description": "Test ordering"
@@ -244,10 +441,68 @@ mod tests {
         assert_eq!(lines[4], "item5");
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_shell_persists_until_restart() {
+        let tool = BashTool::new();
+        let dir = tempfile::tempdir().unwrap();
+        let run = |command: String, restart: bool| {
+            let input = serde_json::json!({
+                "command": command,
+                "description": "Test persistence",
+                "restart": restart
+            });
+            let tool = &tool;
+            async move { tool.execute(input, &make_context()).await.unwrap() }
+        };
+
+        run(
+            format!("cd '{}' && export STEP=two", dir.path().display()),
+            false,
+        )
+        .await;
+        let output = run("echo \"$STEP in $(pwd)\"".to_string(), false).await;
+        assert_eq!(output, format!("two in {}\n", dir.path().display()));
+
+        let output = run("echo \"[$STEP]\"".to_string(), true).await;
+        assert_eq!(output, "[]\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_idle_shell_is_closed() {
+        let tool = BashTool::with_timeouts(Duration::from_millis(200), COMMAND_TIMEOUT);
+        let input = serde_json::json!({ "command": "KEPT=yes" });
+        tool.execute(input, &make_context()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        let input = serde_json::json!({ "command": "echo \"[$KEPT]\"" });
+        let output = tool.execute(input, &make_context()).await.unwrap();
+        assert!(output.starts_with("[]\n"), "{}", output);
+        assert!(output.contains("minutes idle"), "{}", output);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_cancelled_command_gets_a_new_shell() {
+        let tool = BashTool::new();
+        let input = serde_json::json!({ "command": "export GONE=1; sleep 30" });
+        let context = make_context();
+        let cancelled = tool.execute(input, &context);
+        assert!(tokio::time::timeout(Duration::from_millis(300), cancelled)
+            .await
+            .is_err());
+
+        let input = serde_json::json!({ "command": "echo \"[$GONE]\"" });
+        let output = tool.execute(input, &make_context()).await.unwrap();
+        assert!(output.starts_with("[]\n"), "{}", output);
+        assert!(output.contains("stopped before it finished"), "{}", output);
+    }
+
     #[tokio::test]
     async fn test_bash_no_callback_still_returns_output() {
         // When live_output is None, tool must still return complete output
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({ "command": "echo hello" });
         let result = tool.execute(input, &make_context()).await.unwrap();
         assert!(result.trim().contains("hello"));
@@ -0,0 +1,431 @@
+// Browser tool - drives a headless Chromium for pages web_fetch can't read
+//
+// One browser with one tab per tool instance, launched on first use with a
+// throwaway profile and closed when the tool is dropped.  Actions:
+// - navigate: load a URL (JavaScript runs, unlike web_fetch)
+// - extract_text: the rendered page as markdown, or one element's text
+// - click: click the element matching a CSS selector
+// - screenshot: save a PNG under ~/.finch/screenshots
+//
+// The tool is only registered when `[browser] enabled` is set, and its
+// approvals are per site (see `generate_tool_signature`).
+
+use crate::config::BrowserConfig;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::{Browser, Page};
+use futures::StreamExt;
+use reqwest::Url;
+use serde_json::Value;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Characters of page text returned to the model
+const MAX_OUTPUT_CHARS: usize = 10_000;
+
+/// Time given to a click's effects (navigation, rendering) before reporting
+const CLICK_SETTLE: Duration = Duration::from_millis(500);
+
+static SETTINGS: OnceLock<BrowserConfig> = OnceLock::new();
+
+/// Apply `[browser]`.  Called once when the config is loaded; later calls
+/// are ignored.
+pub fn init(config: &BrowserConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// Whether `[browser] enabled` is set, i.e. whether to register the tool
+pub fn enabled() -> bool {
+    SETTINGS.get().is_some_and(|config| config.enabled)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    Navigate { url: String },
+    ExtractText { selector: Option<String> },
+    Click { selector: String },
+    Screenshot { full_page: bool },
+}
+
+impl Action {
+    fn parse(input: &Value) -> Result<Self> {
+        let action = input["action"]
+            .as_str()
+            .context("Missing action parameter")?;
+        let selector = || {
+            input["selector"]
+                .as_str()
+                .filter(|s| !s.trim().is_empty())
+                .map(str::to_string)
+        };
+        Ok(match action {
+            "navigate" => {
+                let url = input["url"].as_str().context("navigate needs a url")?;
+                let parsed = Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    bail!("Only http and https URLs can be opened: {}", url);
+                }
+                Self::Navigate {
+                    url: parsed.to_string(),
+                }
+            }
+            "extract_text" => Self::ExtractText {
+                selector: selector(),
+            },
+            "click" => Self::Click {
+                selector: selector().context("click needs a selector")?,
+            },
+            "screenshot" => Self::Screenshot {
+                full_page: input["full_page"].as_bool().unwrap_or(false),
+            },
+            other => bail!(
+                "Unknown action '{}' (expected navigate, extract_text, click or screenshot)",
+                other
+            ),
+        })
+    }
+}
+
+/// A running browser and its one tab
+struct Session {
+    browser: Browser,
+    handler: JoinHandle<()>,
+    page: Page,
+    profile_dir: PathBuf,
+}
+
+pub struct BrowserTool {
+    config: BrowserConfig,
+    session: Mutex<Option<Session>>,
+}
+
+impl BrowserTool {
+    /// A tool using the `[browser]` settings
+    pub fn new() -> Self {
+        Self::with_config(SETTINGS.get().cloned().unwrap_or_default())
+    }
+
+    pub fn with_config(config: BrowserConfig) -> Self {
+        Self {
+            config,
+            session: Mutex::new(None),
+        }
+    }
+
+    async fn launch(&self) -> Result<Session> {
+        let profile_dir =
+            std::env::temp_dir().join(format!("finch-browser-{}", uuid::Uuid::new_v4()));
+        let mut builder = chromiumoxide::BrowserConfig::builder()
+            .user_data_dir(&profile_dir)
+            .request_timeout(self.timeout())
+            .window_size(1280, 800);
+        if let Some(path) = &self.config.chrome_path {
+            builder = builder.chrome_executable(path);
+        }
+        // Chromium refuses to sandbox itself when run as root (containers)
+        #[cfg(unix)]
+        if unsafe { libc::geteuid() } == 0 {
+            builder = builder.no_sandbox();
+        }
+        let config = builder.build().map_err(|e| {
+            anyhow!(
+                "{} (install Chrome or Chromium, or set chrome_path under [browser])",
+                e
+            )
+        })?;
+
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .context("Failed to launch headless Chromium")?;
+        let handler = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if let Err(e) = event {
+                    tracing::debug!("[browser] {}", e);
+                }
+            }
+        });
+        let page = browser
+            .new_page("about:blank")
+            .await
+            .context("Failed to open a browser tab")?;
+        tracing::info!("[browser] launched headless Chromium");
+        Ok(Session {
+            browser,
+            handler,
+            page,
+            profile_dir,
+        })
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs.max(1))
+    }
+
+    /// Run `work` under the per-action timeout
+    async fn timed<T>(&self, what: &str, work: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::time::timeout(self.timeout(), work)
+            .await
+            .map_err(|_| anyhow!("{} timed out after {}s", what, self.timeout().as_secs()))?
+    }
+
+    async fn run(&self, page: &Page, action: Action) -> Result<String> {
+        match action {
+            Action::Navigate { url } => {
+                self.timed("Loading the page", async {
+                    page.goto(url.as_str()).await?;
+                    Ok(())
+                })
+                .await?;
+                Ok(format!("Loaded {}", describe(page).await))
+            }
+            Action::ExtractText { selector: None } => {
+                let html = self
+                    .timed("Reading the page", async { Ok(page.content().await?) })
+                    .await?;
+                let base = page.url().await?.and_then(|url| Url::parse(&url).ok());
+                let markdown = crate::web::html_to_markdown(&html, base.as_ref());
+                Ok(truncate(markdown))
+            }
+            Action::ExtractText {
+                selector: Some(selector),
+            } => {
+                let text = self
+                    .timed("Reading the element", async {
+                        let element = page
+                            .find_element(selector.as_str())
+                            .await
+                            .with_context(|| format!("No element matches '{}'", selector))?;
+                        Ok(element.inner_text().await?)
+                    })
+                    .await?;
+                Ok(truncate(text.unwrap_or_default()))
+            }
+            Action::Click { selector } => {
+                self.timed("Clicking", async {
+                    let element = page
+                        .find_element(selector.as_str())
+                        .await
+                        .with_context(|| format!("No element matches '{}'", selector))?;
+                    element.click().await?;
+                    Ok(())
+                })
+                .await?;
+                tokio::time::sleep(CLICK_SETTLE).await;
+                Ok(format!(
+                    "Clicked '{}'; now at {}",
+                    selector,
+                    describe(page).await
+                ))
+            }
+            Action::Screenshot { full_page } => {
+                let png = self
+                    .timed("Taking the screenshot", async {
+                        let params = ScreenshotParams::builder()
+                            .format(CaptureScreenshotFormat::Png)
+                            .full_page(full_page)
+                            .build();
+                        Ok(page.screenshot(params).await?)
+                    })
+                    .await?;
+                let dir = dirs::home_dir()
+                    .context("No home directory for screenshots")?
+                    .join(".finch")
+                    .join("screenshots");
+                std::fs::create_dir_all(&dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+                let path = dir.join(format!(
+                    "browser-{}.png",
+                    chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+                ));
+                std::fs::write(&path, &png)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                Ok(format!(
+                    "Saved a screenshot of {} to {} ({} KB)",
+                    describe(page).await,
+                    path.display(),
+                    png.len() / 1024
+                ))
+            }
+        }
+    }
+}
+
+impl Default for BrowserTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for BrowserTool {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.get_mut().take() {
+            // Chromium is started with kill_on_drop; stop reading its events
+            // and remove the profile
+            session.handler.abort();
+            drop(session.browser);
+            let _ = std::fs::remove_dir_all(&session.profile_dir);
+        }
+    }
+}
+
+/// "<title> (<url>)" of the page, or whichever of them is known
+async fn describe(page: &Page) -> String {
+    let url = page.url().await.ok().flatten().unwrap_or_default();
+    match page.get_title().await.ok().flatten() {
+        Some(title) if !title.is_empty() => format!("{} ({})", title, url),
+        _ => url,
+    }
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n\n[Text truncated - showing first 10,000 characters of {}]",
+            &text[..end],
+            text.chars().count()
+        ),
+        None => text,
+    }
+}
+
+#[async_trait]
+impl Tool for BrowserTool {
+    fn name(&self) -> &str {
+        "browser"
+    }
+
+    fn description(&self) -> &str {
+        "Control a headless Chromium browser. Use when web_fetch can't read a page because \
+it is rendered by JavaScript, or to check how a deployed page actually looks and behaves. \
+Actions: navigate (load url), extract_text (rendered page as markdown, or the text of \
+the element matching selector), click (element matching selector), screenshot (saves a \
+PNG and returns its path). The tab stays open between calls."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["navigate", "extract_text", "click", "screenshot"],
+                    "description": "What to do"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "URL to load (navigate)"
+                },
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector of the element (click; optional for extract_text)"
+                },
+                "full_page": {
+                    "type": "boolean",
+                    "description": "Capture the whole page instead of the viewport (screenshot)"
+                }
+            }),
+            required: vec!["action".to_string()],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let action = Action::parse(&input)?;
+
+        let mut session = self.session.lock().await;
+        if session.is_none() {
+            *session = Some(self.launch().await?);
+        }
+        let page = &session.as_ref().expect("browser session").page;
+        self.run(page, action).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> ToolContext<'static> {
+        ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        assert_eq!(
+            Action::parse(&serde_json::json!({"action": "navigate", "url": "https://example.com"}))
+                .unwrap(),
+            Action::Navigate {
+                url: "https://example.com/".into()
+            }
+        );
+        assert_eq!(
+            Action::parse(&serde_json::json!({"action": "extract_text", "selector": " "})).unwrap(),
+            Action::ExtractText { selector: None }
+        );
+        assert_eq!(
+            Action::parse(&serde_json::json!({"action": "screenshot", "full_page": true})).unwrap(),
+            Action::Screenshot { full_page: true }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_input_fails_before_launching() {
+        // No Chromium is needed to reject these
+        let tool = BrowserTool::with_config(BrowserConfig {
+            chrome_path: Some("/nonexistent/chromium".into()),
+            ..BrowserConfig::default()
+        });
+        for (input, expected) in [
+            (serde_json::json!({}), "Missing action"),
+            (serde_json::json!({"action": "scroll"}), "Unknown action"),
+            (serde_json::json!({"action": "click"}), "selector"),
+            (
+                serde_json::json!({"action": "navigate", "url": "file:///etc/passwd"}),
+                "http and https",
+            ),
+        ] {
+            let err = tool.execute(input, &context()).await.unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+        }
+        assert!(tool.session.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore] // Needs Chrome or Chromium installed
+    async fn test_navigate_and_extract_rendered_text() {
+        let tool = BrowserTool::with_config(BrowserConfig::default());
+        let page = "data:text/html,<main><p id=out></p></main>\
+                    <script>document.getElementById('out').textContent='rendered by JS'</script>";
+        // data: URLs are refused, so load the page through the tab directly
+        let session = tool.launch().await.unwrap();
+        session.page.goto(page).await.unwrap();
+        *tool.session.lock().await = Some(session);
+
+        let text = tool
+            .execute(
+                serde_json::json!({"action": "extract_text", "selector": "#out"}),
+                &context(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(text, "rendered by JS");
+    }
+}
@@ -0,0 +1,143 @@
+// Clipboard tool - read or replace the text on the system clipboard
+//
+// Lets the model hand the user something to paste ("copied the command to
+// your clipboard") or look at what they copied.  Both directions go through
+// tool approval: the clipboard often holds things the user didn't mean to
+// share, and overwriting it loses what was there.  `/paste` uses the same
+// helpers to bring the clipboard into the conversation.
+//
+// On Linux the copied text is served by the process that set it, so one
+// clipboard handle is kept open for the whole session instead of one per
+// call; otherwise the text would vanish as soon as the call returned.
+
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// Opened on first use and kept, so text copied on Linux stays available
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+fn with_clipboard<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Result<T>) -> Result<T> {
+    let mut guard = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        let clipboard = arboard::Clipboard::new()
+            .context("No clipboard available (is there a display session?)")?;
+        *guard = Some(clipboard);
+    }
+    let clipboard = guard.as_mut().expect("clipboard was just opened");
+    f(clipboard)
+}
+
+/// The text on the clipboard
+pub fn read_text() -> Result<String> {
+    with_clipboard(|clipboard| match clipboard.get_text() {
+        Err(arboard::Error::ContentNotAvailable) => bail!("The clipboard holds no text"),
+        text => text.context("Failed to read the clipboard"),
+    })
+}
+
+/// Replace the clipboard's contents with `text`
+pub fn write_text(text: &str) -> Result<()> {
+    with_clipboard(|clipboard| {
+        clipboard
+            .set_text(text)
+            .context("Failed to write the clipboard")
+    })
+}
+
+/// What a call asks for
+#[derive(Debug, PartialEq)]
+enum Action {
+    Read,
+    Write(String),
+}
+
+fn parse_action(input: &Value) -> Result<Action> {
+    match input["action"].as_str() {
+        Some("read") => Ok(Action::Read),
+        Some("write") => {
+            let text = input["text"]
+                .as_str()
+                .context("Missing text parameter (required to write)")?;
+            Ok(Action::Write(text.to_string()))
+        }
+        Some(other) => bail!("Unknown action '{}': use read or write", other),
+        None => bail!("Missing action parameter"),
+    }
+}
+
+pub struct ClipboardTool;
+
+#[async_trait]
+impl Tool for ClipboardTool {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn description(&self) -> &str {
+        "Read or replace the text on the user's system clipboard. \
+         Write a command, snippet or message the user asked to have copied, \
+         then tell them it is on their clipboard. \
+         Read only when the user refers to something they copied."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["read", "write"],
+                    "description": "read returns the clipboard text; write replaces it"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Text to put on the clipboard (write only)"
+                }
+            }),
+            required: vec!["action".to_string()],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let action = parse_action(&input)?;
+        tokio::task::spawn_blocking(move || match action {
+            Action::Read => read_text(),
+            Action::Write(text) => {
+                write_text(&text)?;
+                Ok(format!(
+                    "Copied {} line{} ({} characters) to the clipboard",
+                    text.lines().count().max(1),
+                    if text.lines().count() > 1 { "s" } else { "" },
+                    text.chars().count()
+                ))
+            }
+        })
+        .await
+        .context("Clipboard task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(
+            parse_action(&json!({"action": "read"})).unwrap(),
+            Action::Read
+        );
+        assert_eq!(
+            parse_action(&json!({"action": "write", "text": "cargo test"})).unwrap(),
+            Action::Write("cargo test".to_string())
+        );
+        assert!(parse_action(&json!({"action": "write"})).is_err());
+        assert!(parse_action(&json!({"action": "clear"})).is_err());
+        assert!(parse_action(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+// doc_lookup tool — search indexed dependency documentation
+//
+// Docs are indexed ahead of time with `finch ask-docs`, which fetches
+// docs.rs / npm READMEs for the project's dependencies into ~/.finch/docs.db.
+
+use crate::memory::DocsIndex;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Search the docs of the libraries this project depends on
+pub struct DocLookupTool {
+    index: Arc<DocsIndex>,
+}
+
+impl DocLookupTool {
+    pub fn new(index: Arc<DocsIndex>) -> Self {
+        Self { index }
+    }
+}
+
+#[async_trait]
+impl Tool for DocLookupTool {
+    fn name(&self) -> &str {
+        "doc_lookup"
+    }
+
+    fn description(&self) -> &str {
+        "Search the indexed documentation of this project's dependencies (docs.rs for Rust crates, \
+         README for npm packages), pinned to the versions in Cargo.toml / package.json. Call this \
+         before using a library API you are not certain about — exact function names, signatures, \
+         feature flags — instead of guessing."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "query": {
+                    "type": "string",
+                    "description": "What to look up (e.g., 'spawn a blocking task', 'Router::route signature')"
+                },
+                "library": {
+                    "type": "string",
+                    "description": "Optional crate or package name to restrict the search to (e.g., 'tokio')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results to return (default: 3, max: 8)",
+                    "default": 3
+                }
+            }),
+            required: vec!["query".to_string()],
+        }
+    }
+
+    async fn execute(&self, params: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let query = params["query"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: query"))?;
+        let library = params["library"].as_str().filter(|s| !s.is_empty());
+        let limit = params["limit"].as_u64().unwrap_or(3).clamp(1, 8) as usize;
+
+        tracing::info!("Doc lookup: query='{}', library={:?}", query, library);
+
+        let results = self.index.lookup(query, library, limit).await?;
+
+        if results.is_empty() {
+            return Ok(match library {
+                Some(lib) => format!(
+                    "No indexed docs matched for '{}'. Run `finch ask-docs` in the project to index dependency docs.",
+                    lib
+                ),
+                None => "No indexed docs matched. Run `finch ask-docs` in the project to index dependency docs.".to_string(),
+            });
+        }
+
+        Ok(results
+            .iter()
+            .enumerate()
+            .map(|(i, text)| format!("{}. {}", i + 1, text))
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryConfig, MemorySystem};
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_doc_lookup_tool() -> Result<()> {
+        let temp = NamedTempFile::new()?;
+        let memory = Arc::new(MemorySystem::new(MemoryConfig {
+            db_path: temp.path().to_path_buf(),
+            use_neural_embeddings: false,
+            ..Default::default()
+        })?);
+        memory
+            .insert_documents(
+                "tokio@1.35",
+                &["[docs:tokio@1.35 spawn_blocking]\nRuns the provided closure on a thread where blocking is acceptable".into()],
+            )
+            .await?;
+
+        let tool = DocLookupTool::new(Arc::new(DocsIndex::with_memory(memory)));
+        let context = ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        };
+        let result = tool
+            .execute(
+                serde_json::json!({"query": "blocking closure", "library": "tokio"}),
+                &context,
+            )
+            .await?;
+        assert!(result.contains("spawn_blocking"));
+
+        let missing = tool
+            .execute(
+                serde_json::json!({"query": "router", "library": "axum"}),
+                &context,
+            )
+            .await?;
+        assert!(missing.contains("finch ask-docs"));
+
+        Ok(())
+    }
+}
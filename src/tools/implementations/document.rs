@@ -0,0 +1,441 @@
+// Document text for the read tool — PDF, Word and spreadsheets
+//
+// PDFs are extracted page by page, so `pages` ("3", "1-5", "2,7-9") only
+// parses the pages asked for.  Word documents (.docx) come out as paragraphs,
+// with headings marked `#` and table rows joined by ` | `.  Spreadsheets
+// (.xlsx, .xls, .ods) come out one sheet at a time, rows joined by ` | `,
+// and `sheet` picks one by name or 1-based index.
+//
+// Without `pages`, a long PDF stops before the page that would overflow the
+// read tool's output limit and says which pages are left.
+
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// Largest document the read tool will open
+pub const MAX_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Bytes of PDF text returned without `pages`, leaving room under the read
+/// tool's 50,000 for the note on where to go on
+const MAX_PDF_TEXT: usize = 49_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Spreadsheet,
+}
+
+impl DocumentKind {
+    /// The kind of document at `path`, by extension; `None` for text files
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
+            "xlsx" | "xlsm" | "xls" | "ods" => Some(Self::Spreadsheet),
+            _ => None,
+        }
+    }
+}
+
+/// Extract the text of the document at `path`, restricted to `pages`
+/// (PDF) or `sheet` (spreadsheets).  Blocking; parsing a large PDF can
+/// take a while.
+pub fn extract(
+    path: &Path,
+    kind: DocumentKind,
+    pages: Option<&str>,
+    sheet: Option<&str>,
+) -> Result<String> {
+    if pages.is_some() && kind != DocumentKind::Pdf {
+        bail!("`pages` only applies to PDF files");
+    }
+    if sheet.is_some() && kind != DocumentKind::Spreadsheet {
+        bail!("`sheet` only applies to spreadsheets (.xlsx, .xls, .ods)");
+    }
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?
+        .len();
+    if size > MAX_DOCUMENT_BYTES {
+        bail!(
+            "{} is {} MB; documents over {} MB aren't read",
+            path.display(),
+            size / (1024 * 1024),
+            MAX_DOCUMENT_BYTES / (1024 * 1024)
+        );
+    }
+
+    match kind {
+        DocumentKind::Pdf => {
+            let pages = pages.map(parse_pages).transpose()?;
+            extract_pdf(path, pages.as_deref())
+        }
+        DocumentKind::Docx => extract_docx(path),
+        DocumentKind::Spreadsheet => extract_spreadsheet(path, sheet),
+    }
+}
+
+/// Parse a page list like "3", "1-5" or "2,7-9" into 1-based page numbers
+pub fn parse_pages(spec: &str) -> Result<Vec<u32>> {
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (part, part),
+        };
+        let start: u32 = start
+            .parse()
+            .with_context(|| format!("Invalid page range `{}`", part))?;
+        let end: u32 = end
+            .parse()
+            .with_context(|| format!("Invalid page range `{}`", part))?;
+        if start == 0 || end < start {
+            bail!("Invalid page range `{}` (pages start at 1)", part);
+        }
+        pages.extend(start..=end);
+    }
+    if pages.is_empty() {
+        bail!("`pages` lists no pages");
+    }
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+fn extract_pdf(path: &Path, pages: Option<&[u32]>) -> Result<String> {
+    let mut doc = pdf_extract::Document::load(path)
+        .with_context(|| format!("Failed to open PDF: {}", path.display()))?;
+    if doc.is_encrypted() {
+        // Many PDFs are "encrypted" with an empty user password
+        doc.decrypt("")
+            .map_err(|_| anyhow::anyhow!("{} is password-protected", path.display()))?;
+    }
+    let total = doc.get_pages().len() as u32;
+    if total == 0 {
+        bail!("{} has no pages", path.display());
+    }
+
+    let wanted: Vec<u32> = match pages {
+        Some(pages) => {
+            if let Some(&past) = pages.iter().find(|&&p| p > total) {
+                bail!("PDF has {} pages; page {} is past the end", total, past);
+            }
+            pages.to_vec()
+        }
+        None => (1..=total).collect(),
+    };
+
+    let mut out = String::new();
+    for &page in &wanted {
+        let mut text = String::new();
+        let mut output = pdf_extract::PlainTextOutput::new(&mut text);
+        pdf_extract::output_doc_page(&doc, &mut output, page)
+            .map_err(|e| anyhow::anyhow!("Failed to extract page {}: {}", page, e))?;
+        let text = format!("[Page {} of {}]\n{}\n\n", page, total, text.trim());
+        if pages.is_none() && !out.is_empty() && out.len() + text.len() > MAX_PDF_TEXT {
+            out.push_str(&format!(
+                "[Stopped before page {} of {} — pass pages=\"{}-{}\" to read on]",
+                page, total, page, total
+            ));
+            break;
+        }
+        out.push_str(&text);
+    }
+    Ok(out.trim_end().to_string())
+}
+
+fn extract_docx(path: &Path) -> Result<String> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("{} is not a valid .docx file", path.display()))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .with_context(|| format!("{} has no word/document.xml", path.display()))?
+        .read_to_string(&mut xml)
+        .context("Failed to read word/document.xml")?;
+    docx_text(&xml)
+}
+
+/// Paragraph and table text of a WordprocessingML body
+fn docx_text(xml: &str) -> Result<String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut lines: Vec<String> = Vec::new();
+    let mut paragraph = String::new();
+    let mut heading = 0usize;
+    let mut in_run = false;
+    let mut in_text = false;
+    // Cells of the table row being read, and the cell being read
+    let mut row: Vec<String> = Vec::new();
+    let mut cell: Option<String> = None;
+
+    loop {
+        match reader.read_event().context("Malformed word/document.xml")? {
+            Event::Start(e) if e.local_name().as_ref() == b"t" => in_text = true,
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"r" => in_run = false,
+                b"p" => {
+                    let text = std::mem::take(&mut paragraph);
+                    let text = text.trim_end();
+                    match cell.as_mut() {
+                        Some(cell) => {
+                            if !cell.is_empty() && !text.is_empty() {
+                                cell.push(' ');
+                            }
+                            cell.push_str(text);
+                        }
+                        None if heading > 0 => {
+                            lines.push(format!("{} {}", "#".repeat(heading), text))
+                        }
+                        None => lines.push(text.to_string()),
+                    }
+                    heading = 0;
+                }
+                b"tc" => row.extend(cell.take()),
+                b"tr" => lines.push(std::mem::take(&mut row).join(" | ")),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"p" && cell.is_none() => {
+                lines.push(String::new())
+            }
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"r" => in_run = true,
+                // Tab stops in paragraph properties are `tab` too
+                b"tab" if in_run => paragraph.push('\t'),
+                b"br" | b"cr" if in_run => paragraph.push('\n'),
+                b"tc" => cell = Some(String::new()),
+                b"pStyle" => {
+                    // Heading1 … Heading9
+                    let style = e
+                        .try_get_attribute("w:val")
+                        .ok()
+                        .flatten()
+                        .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+                        .unwrap_or_default();
+                    heading = style
+                        .strip_prefix("Heading")
+                        .and_then(|n| n.parse().ok())
+                        .unwrap_or(0);
+                }
+                _ => {}
+            },
+            Event::Text(t) if in_text => paragraph.push_str(
+                &t.unescape()
+                    .context("Malformed text in word/document.xml")?,
+            ),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // Collapse runs of blank lines left by empty paragraphs
+    let mut out = String::new();
+    let mut blank = false;
+    for line in &lines {
+        if line.trim().is_empty() {
+            if !blank && !out.is_empty() {
+                out.push('\n');
+            }
+            blank = true;
+        } else {
+            out.push_str(line);
+            out.push('\n');
+            blank = false;
+        }
+    }
+    Ok(out.trim_end().to_string())
+}
+
+fn extract_spreadsheet(path: &Path, sheet: Option<&str>) -> Result<String> {
+    use calamine::{open_workbook_auto, Data, Reader};
+
+    let mut workbook = open_workbook_auto(path)
+        .with_context(|| format!("Failed to open spreadsheet: {}", path.display()))?;
+    let names = workbook.sheet_names().to_vec();
+    if names.is_empty() {
+        bail!("{} has no sheets", path.display());
+    }
+    let selected: Vec<usize> = match sheet {
+        Some(sheet) => {
+            let index = names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(sheet))
+                .or_else(|| {
+                    sheet
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| (1..=names.len()).contains(n))
+                        .map(|n| n - 1)
+                })
+                .with_context(|| {
+                    format!("No sheet `{}`; sheets are: {}", sheet, names.join(", "))
+                })?;
+            vec![index]
+        }
+        None => (0..names.len()).collect(),
+    };
+
+    let mut out = String::new();
+    for index in selected {
+        let name = &names[index];
+        let range = workbook
+            .worksheet_range(name)
+            .with_context(|| format!("Failed to read sheet `{}`", name))?;
+        let (rows, columns) = range.get_size();
+        out.push_str(&format!(
+            "[Sheet {} of {}: {} — {} rows × {} columns]\n",
+            index + 1,
+            names.len(),
+            name,
+            rows,
+            columns
+        ));
+        for row in range.rows() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| match cell {
+                    Data::Empty => String::new(),
+                    Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => {
+                        format!("{}", *f as i64)
+                    }
+                    Data::Error(e) => format!("#ERR:{:?}", e),
+                    other => other.to_string(),
+                })
+                .collect();
+            out.push_str(cells.join(" | ").trim_end_matches([' ', '|']));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    Ok(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_pages() {
+        assert_eq!(parse_pages("3").unwrap(), vec![3]);
+        assert_eq!(parse_pages("2, 7-9,3").unwrap(), vec![2, 3, 7, 8, 9]);
+        assert!(parse_pages("0").is_err());
+        assert!(parse_pages("5-2").is_err());
+        assert!(parse_pages("x").is_err());
+        assert!(parse_pages(" , ").is_err());
+    }
+
+    #[test]
+    fn test_kind_by_extension() {
+        assert_eq!(
+            DocumentKind::of(Path::new("spec.PDF")),
+            Some(DocumentKind::Pdf)
+        );
+        assert_eq!(
+            DocumentKind::of(Path::new("budget.xlsx")),
+            Some(DocumentKind::Spreadsheet)
+        );
+        assert_eq!(DocumentKind::of(Path::new("main.rs")), None);
+        assert_eq!(DocumentKind::of(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn test_docx_paragraphs_headings_and_tables() {
+        let xml = r#"<?xml version="1.0"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Scope</w:t></w:r></w:p>
+<w:p><w:r><w:t xml:space="preserve">Ships </w:t></w:r><w:r><w:t>in Q3 &amp; Q4.</w:t></w:r></w:p>
+<w:p/><w:p/>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Item</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Cost</w:t></w:r></w:p></w:tc></w:tr>
+<w:tr><w:tc><w:p><w:r><w:t>Disk</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>40</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+<w:p><w:r><w:instrText>PAGE</w:instrText><w:t>End</w:t></w:r></w:p>
+</w:body></w:document>"#;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec.docx");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file(
+            "word/document.xml",
+            zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored),
+        )
+        .unwrap();
+        zip.write_all(xml.as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let text = extract(&path, DocumentKind::Docx, None, None).unwrap();
+        assert_eq!(
+            text,
+            "# Scope\nShips in Q3 & Q4.\n\nItem | Cost\nDisk | 40\nEnd"
+        );
+        assert!(extract(&path, DocumentKind::Docx, Some("1"), None).is_err());
+    }
+
+    #[test]
+    fn test_pdf_pages() {
+        use pdf_extract::{dictionary, Object, Stream};
+
+        // Three pages, each saying "Page N"
+        let mut doc = pdf_extract::Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let kids: Vec<Object> = (1..=3)
+            .map(|n| {
+                let content = format!("BT /F1 24 Tf 72 700 Td (Page {}) Tj ET", n);
+                let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => 3,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec.pdf");
+        doc.save(&path).unwrap();
+
+        let all = extract(&path, DocumentKind::Pdf, None, None).unwrap();
+        assert!(
+            all.contains("[Page 1 of 3]") && all.contains("Page 3"),
+            "{}",
+            all
+        );
+
+        let some = extract(&path, DocumentKind::Pdf, Some("2"), None).unwrap();
+        assert!(some.starts_with("[Page 2 of 3]"), "{}", some);
+        assert!(!some.contains("Page 3"));
+
+        assert!(extract(&path, DocumentKind::Pdf, Some("4"), None).is_err());
+        assert!(extract(&path, DocumentKind::Pdf, None, Some("1")).is_err());
+    }
+}
@@ -64,25 +64,7 @@ impl Tool for GrepTool {
         let mut match_count = 0;
         const MAX_MATCHES: usize = 100;
 
-        'outer: for entry in WalkDir::new(path)
-            .max_depth(10)
-            .into_iter()
-            .filter_entry(|e| {
-                // Skip directories that are never useful source-code search targets.
-                // This prevents flooding results with compiled artifacts, VCS objects,
-                // or package manager caches (e.g. ./target/doc/*.html in Rust projects).
-                if e.file_type().is_dir() {
-                    let name = e.file_name().to_string_lossy();
-                    !matches!(
-                        name.as_ref(),
-                        "target" | ".git" | "node_modules" | ".cargo" | ".next" | "dist" | "build" | ".svn" | ".hg"
-                    )
-                } else {
-                    true
-                }
-            })
-            .filter_map(|e| e.ok())
-        {
+        'outer: for entry in search_walk(path) {
             if !entry.file_type().is_file() {
                 continue;
             }
@@ -169,6 +151,28 @@ impl Tool for GrepTool {
     }
 }
 
+/// The directories and files a search of `path` visits
+pub(crate) fn search_walk(path: &str) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(path)
+        .max_depth(10)
+        .into_iter()
+        .filter_entry(|e| {
+            // Skip directories that are never useful source-code search targets.
+            // This prevents flooding results with compiled artifacts, VCS objects,
+            // or package manager caches (e.g. ./target/doc/*.html in Rust projects).
+            if e.file_type().is_dir() {
+                let name = e.file_name().to_string_lossy();
+                !matches!(
+                    name.as_ref(),
+                    "target" | ".git" | "node_modules" | ".cargo" | ".next" | "dist" | "build" | ".svn" | ".hg"
+                )
+            } else {
+                true
+            }
+        })
+        .filter_map(|e| e.ok())
+}
+
 /// Simple glob matching for file extensions (e.g. "*.rs", "*.ts")
 fn glob_match(pattern: &str, name: &str) -> bool {
     if let Some(ext) = pattern.strip_prefix("*.") {
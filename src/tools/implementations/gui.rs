@@ -1,14 +1,15 @@
 // GUI automation tools (macOS only)
 //
-// Provides three tools for controlling macOS GUI applications:
+// Provides four tools for controlling macOS GUI applications:
 // - GuiClick: Click UI elements by coordinates
 // - GuiType: Type text into focused fields
 // - GuiInspect: Query UI hierarchy
+// - GuiScreenshot: Capture the screen, a window or a region, with OCR
 //
 // NOTE: Full implementation requires testing on macOS with proper accessibility permissions
 
 use crate::tools::registry::Tool;
-use crate::tools::types::{ToolContext, ToolInputSchema};
+use crate::tools::types::{attachment_dir, image_attachment_line, ToolContext, ToolInputSchema};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::{json, Value};
@@ -172,6 +173,117 @@ impl Tool for GuiInspectTool {
     }
 }
 
+/// Longest side of a screenshot sent to the model, in pixels; larger
+/// captures are scaled down
+const SCREENSHOT_MAX_SIDE: u32 = 1568;
+
+/// GuiScreenshot tool - Capture the screen, a window or a region, with OCR
+pub struct GuiScreenshotTool;
+
+#[async_trait]
+impl Tool for GuiScreenshotTool {
+    fn name(&self) -> &str {
+        "gui_screenshot"
+    }
+
+    fn description(&self) -> &str {
+        "Capture the screen, an app's front window, or a region of the screen on macOS. \
+         Returns the text on it (OCR) and the image itself. \
+         Use it to read what an error dialog or window says. \
+         Requires Screen Recording permission."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: json!({
+                "target": {
+                    "type": "string",
+                    "description": "What to capture: 'screen' (default), 'window' (front window of `app`, or of the frontmost app), or 'region' (x, y, width, height)",
+                    "enum": ["screen", "window", "region"]
+                },
+                "app": {
+                    "type": "string",
+                    "description": "Application whose front window to capture (target 'window')"
+                },
+                "x": { "type": "number", "description": "Left edge of the region (target 'region')" },
+                "y": { "type": "number", "description": "Top edge of the region (target 'region')" },
+                "width": { "type": "number", "description": "Width of the region (target 'region')" },
+                "height": { "type": "number", "description": "Height of the region (target 'region')" },
+                "ocr": {
+                    "type": "boolean",
+                    "description": "Recognize the text in the capture (default: true)"
+                },
+                "include_image": {
+                    "type": "boolean",
+                    "description": "Send the image itself as well as the text (default: true)"
+                }
+            }),
+            required: vec![],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let target = input["target"].as_str().unwrap_or("screen");
+        let ocr = input["ocr"].as_bool().unwrap_or(true);
+        let include_image = input["include_image"].as_bool().unwrap_or(true);
+        if !ocr && !include_image {
+            anyhow::bail!("Nothing to return: set 'ocr' or 'include_image' to true");
+        }
+
+        let region = match target {
+            "screen" => None,
+            "window" => Some(window_bounds(input["app"].as_str())?),
+            "region" => {
+                let coord = |key: &str| {
+                    input[key]
+                        .as_f64()
+                        .with_context(|| format!("Missing or invalid '{}' parameter", key))
+                };
+                Some([coord("x")?, coord("y")?, coord("width")?, coord("height")?])
+            }
+            _ => anyhow::bail!("Invalid target. Use 'screen', 'window', or 'region'"),
+        };
+
+        let dir = attachment_dir();
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("screenshot-{}.png", uuid::Uuid::new_v4()));
+        let (text, path) = tokio::task::spawn_blocking(move || -> Result<_> {
+            capture_screen(&path, region)?;
+            let text = if ocr {
+                Some(recognize_text(&path)?)
+            } else {
+                None
+            };
+            Ok((text, path))
+        })
+        .await
+        .context("Screenshot was cancelled")??;
+
+        let what = match (target, input["app"].as_str()) {
+            ("window", Some(app)) => format!("the front window of {}", app),
+            ("window", None) => "the front window".to_string(),
+            ("region", _) => "a screen region".to_string(),
+            _ => "the screen".to_string(),
+        };
+        let mut output = format!("Captured {}.\n", what);
+        if let Some(text) = text {
+            if text.trim().is_empty() {
+                output.push_str("\nNo text recognized.\n");
+            } else {
+                output.push_str(&format!("\nText (OCR):\n{}\n", text.trim_end()));
+            }
+        }
+        if include_image {
+            output.push('\n');
+            output.push_str(&image_attachment_line(&path));
+        } else {
+            let _ = std::fs::remove_file(&path);
+        }
+        Ok(output)
+    }
+}
+
 /// Inspect screen information (works!)
 fn inspect_screen() -> Result<String> {
     use core_graphics::display::CGDisplay;
@@ -390,3 +502,115 @@ fn inspect_focused() -> Result<String> {
         ))
     }
 }
+
+/// Bounds (x, y, width, height) of the front window of `app`, or of the
+/// frontmost application
+fn window_bounds(app: Option<&str>) -> Result<[f64; 4]> {
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(
+            r#"
+            on run argv
+                tell application "System Events"
+                    if (count of argv) > 0 then
+                        set proc to first application process whose name is (item 1 of argv)
+                    else
+                        set proc to first application process whose frontmost is true
+                    end if
+                    set {x, y} to position of window 1 of proc
+                    set {w, h} to size of window 1 of proc
+                    return (x as text) & "," & (y as text) & "," & (w as text) & "," & (h as text)
+                end tell
+            end run
+        "#,
+        )
+        .args(app)
+        .output()
+        .context("Failed to execute AppleScript - check permissions")?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Could not find the window: {}", error.trim());
+    }
+
+    let result = String::from_utf8_lossy(&output.stdout);
+    let bounds: Vec<f64> = result
+        .trim()
+        .split(',')
+        .filter_map(|n| n.trim().parse().ok())
+        .collect();
+    match bounds[..] {
+        [x, y, w, h] => Ok([x, y, w, h]),
+        _ => anyhow::bail!("Unexpected window bounds: {}", result.trim()),
+    }
+}
+
+/// Capture the screen, or `region` of it, to a PNG at `path`, scaled down
+/// to at most `SCREENSHOT_MAX_SIDE` pixels on its longest side
+fn capture_screen(path: &std::path::Path, region: Option<[f64; 4]>) -> Result<()> {
+    let mut command = std::process::Command::new("screencapture");
+    command.args(["-x", "-t", "png"]);
+    if let Some([x, y, w, h]) = region {
+        command.arg(format!(
+            "-R{},{},{},{}",
+            x as i64, y as i64, w as i64, h as i64
+        ));
+    }
+    let output = command
+        .arg(path)
+        .output()
+        .context("Failed to run screencapture")?;
+    if !output.status.success() || !path.exists() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "Screen capture failed - check Screen Recording permissions: {}",
+            error.trim()
+        );
+    }
+
+    // Retina captures are far larger than the model needs
+    let _ = std::process::Command::new("sips")
+        .arg("-Z")
+        .arg(SCREENSHOT_MAX_SIDE.to_string())
+        .arg(path)
+        .output();
+    Ok(())
+}
+
+/// Text in the image at `path`, one line per recognized line, using the
+/// Vision framework through AppleScriptObjC
+fn recognize_text(path: &std::path::Path) -> Result<String> {
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(
+            r#"
+            use framework "Foundation"
+            use framework "Vision"
+            use scripting additions
+
+            on run argv
+                set imageURL to current application's |NSURL|'s fileURLWithPath:(item 1 of argv)
+                set handler to current application's VNImageRequestHandler's alloc()'s initWithURL:imageURL options:(missing value)
+                set request to current application's VNRecognizeTextRequest's alloc()'s init()
+                request's setRecognitionLevel:(current application's VNRequestTextRecognitionLevelAccurate)
+                request's setUsesLanguageCorrection:true
+                handler's performRequests:(current application's NSArray's arrayWithObject:request) |error|:(missing value)
+                set recognized to {}
+                repeat with observation in (request's results() as list)
+                    set end of recognized to ((first item of (observation's topCandidates:1))'s |string|()) as text
+                end repeat
+                set AppleScript's text item delimiters to linefeed
+                return recognized as text
+            end run
+        "#,
+        )
+        .arg(path)
+        .output()
+        .context("Failed to execute AppleScript for OCR")?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("OCR failed: {}", error.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,556 @@
+// HTTP tool - API requests with any method, headers and body
+//
+// web_fetch only GETs pages and returns their text.  This tool is for API
+// work: any method, headers, a JSON or text body, and credentials from a
+// named profile under `[http_tool.profiles]`, so the model refers to a
+// secret without ever seeing it.
+//
+// Requests only go to hosts on `[http_tool] allowed_hosts` (redirects
+// included), and the tool isn't registered while that list is empty.  A
+// profile's credentials are only sent to the hosts it lists, so a request
+// using one stops at any redirect that leaves them.  Approvals are per method
+// and host (see `generate_tool_signature`).
+
+use crate::config::{HttpAuth, HttpToolConfig};
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Method, Url};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Characters of response body returned to the model
+const MAX_OUTPUT_CHARS: usize = 10_000;
+
+/// Redirects followed before the redirect itself is returned
+const MAX_REDIRECTS: usize = 5;
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Response headers worth showing the model
+const SHOWN_HEADERS: &[&str] = &["content-type", "location", "link", "retry-after"];
+
+const USER_AGENT: &str = concat!("finch/", env!("CARGO_PKG_VERSION"));
+
+static SETTINGS: OnceLock<HttpToolConfig> = OnceLock::new();
+
+/// Apply `[http_tool]`.  Called once when the config is loaded; later calls
+/// are ignored.
+pub fn init(config: &HttpToolConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// Whether any host is allowed, i.e. whether to register the tool
+pub fn enabled() -> bool {
+    SETTINGS
+        .get()
+        .is_some_and(|config| !config.allowed_hosts.is_empty())
+}
+
+/// Whether `host` matches one of `patterns` (`api.example.com`, or
+/// `*.example.com` for any subdomain)
+pub fn host_allowed(patterns: &[String], host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            None => host == pattern,
+        }
+    })
+}
+
+/// A client that only follows redirects to hosts matching every list in
+/// `host_lists`
+fn client(timeout_secs: u64, host_lists: Vec<Vec<String>>) -> reqwest::Client {
+    let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+        let allowed_host = attempt.url().host_str().is_some_and(|host| {
+            host_lists
+                .iter()
+                .all(|patterns| host_allowed(patterns, host))
+        });
+        if allowed_host && attempt.previous().len() <= MAX_REDIRECTS {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    });
+    crate::http::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .user_agent(USER_AGENT)
+        .redirect(redirects)
+        .build()
+        .expect("HTTP client")
+}
+
+pub struct HttpTool {
+    config: HttpToolConfig,
+    client: reqwest::Client,
+    /// Per auth profile: redirects also stay within the profile's hosts,
+    /// since reqwest only strips `Authorization` when the host changes
+    profile_clients: HashMap<String, reqwest::Client>,
+}
+
+impl HttpTool {
+    /// A tool using the `[http_tool]` settings
+    pub fn new() -> Self {
+        Self::with_config(SETTINGS.get().cloned().unwrap_or_default())
+    }
+
+    pub fn with_config(config: HttpToolConfig) -> Self {
+        let client = client(config.timeout_secs, vec![config.allowed_hosts.clone()]);
+        let profile_clients = config
+            .profiles
+            .iter()
+            .map(|(name, profile)| {
+                let hosts = vec![config.allowed_hosts.clone(), profile.hosts.clone()];
+                (name.clone(), self::client(config.timeout_secs, hosts))
+            })
+            .collect();
+        Self {
+            config,
+            client,
+            profile_clients,
+        }
+    }
+
+    /// The request `input` describes, checked against the allowlist and with
+    /// its auth profile applied
+    fn build(&self, input: &Value) -> Result<reqwest::RequestBuilder> {
+        let url = input["url"].as_str().context("Missing url parameter")?;
+        let parsed = Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            bail!("Only http and https URLs can be requested: {}", url);
+        }
+        let host = parsed.host_str().context("URL has no host")?.to_string();
+        if !host_allowed(&self.config.allowed_hosts, &host) {
+            bail!(
+                "{} is not in [http_tool] allowed_hosts; add it to config.toml to allow requests",
+                host
+            );
+        }
+
+        let method = input["method"]
+            .as_str()
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        if !METHODS.contains(&method.as_str()) {
+            bail!(
+                "Unsupported method '{}' (expected one of {})",
+                method,
+                METHODS.join(", ")
+            );
+        }
+        let auth = input["auth"].as_str();
+        let client = auth
+            .and_then(|name| self.profile_clients.get(name))
+            .unwrap_or(&self.client);
+        let mut request = client.request(Method::from_bytes(method.as_bytes())?, parsed);
+
+        if let Some(headers) = input["headers"].as_object() {
+            for (name, value) in headers {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                request = request.header(
+                    HeaderName::from_bytes(name.as_bytes())
+                        .with_context(|| format!("Invalid header name: {}", name))?,
+                    HeaderValue::from_str(&value)
+                        .with_context(|| format!("Invalid value for header {}", name))?,
+                );
+            }
+        }
+
+        request = match input.get("body") {
+            None | Some(Value::Null) => request,
+            Some(Value::String(text)) => request.body(text.clone()),
+            Some(json) => request.json(json),
+        };
+
+        if let Some(name) = auth {
+            request = self.authenticate(request, name, &host)?;
+        }
+        Ok(request)
+    }
+
+    /// Add the credentials of profile `name` for a request to `host`
+    fn authenticate(
+        &self,
+        request: reqwest::RequestBuilder,
+        name: &str,
+        host: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let Some(profile) = self.config.profiles.get(name) else {
+            let known: Vec<&str> = self.config.profiles.keys().map(String::as_str).collect();
+            bail!(
+                "No auth profile '{}' under [http_tool.profiles] (configured: {})",
+                name,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            );
+        };
+        if !host_allowed(&profile.hosts, host) {
+            bail!("Auth profile '{}' is not allowed for {}", name, host);
+        }
+        Ok(match &profile.auth {
+            HttpAuth::Bearer { token, token_env } => {
+                request.bearer_auth(secret(name, "token", token, token_env)?)
+            }
+            HttpAuth::Basic {
+                username,
+                password,
+                password_env,
+            } => request.basic_auth(
+                username,
+                Some(secret(name, "password", password, password_env)?),
+            ),
+            HttpAuth::Header {
+                name: header,
+                value,
+                value_env,
+            } => request.header(
+                HeaderName::from_bytes(header.as_bytes())
+                    .with_context(|| format!("Auth profile '{}': invalid header name", name))?,
+                secret(name, "value", value, value_env)?,
+            ),
+        })
+    }
+}
+
+impl Default for HttpTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A profile's secret, inline or from the environment
+fn secret(
+    profile: &str,
+    field: &str,
+    value: &Option<String>,
+    env: &Option<String>,
+) -> Result<String> {
+    if let Some(value) = value {
+        return Ok(value.clone());
+    }
+    match env {
+        Some(var) => std::env::var(var).with_context(|| {
+            format!(
+                "Auth profile '{}' reads its {} from ${}, which is not set",
+                profile, field, var
+            )
+        }),
+        None => bail!(
+            "Auth profile '{}' needs {} or {}_env",
+            profile,
+            field,
+            field
+        ),
+    }
+}
+
+/// Status line, the useful headers and the body (pretty-printed when JSON)
+async fn describe(response: reqwest::Response) -> Result<String> {
+    let status = response.status();
+    let mut out = format!("HTTP {}\n", status);
+    for name in SHOWN_HEADERS {
+        if let Some(value) = response.headers().get(*name) {
+            out.push_str(&format!("{}: {}\n", name, value.to_str().unwrap_or("")));
+        }
+    }
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+
+    let body = response
+        .text()
+        .await
+        .context("Failed to read the response")?;
+    let body = if is_json {
+        serde_json::from_str::<Value>(&body)
+            .ok()
+            .and_then(|json| serde_json::to_string_pretty(&json).ok())
+            .unwrap_or(body)
+    } else {
+        body
+    };
+    if !body.is_empty() {
+        out.push('\n');
+        match body.char_indices().nth(MAX_OUTPUT_CHARS) {
+            Some((end, _)) => out.push_str(&format!(
+                "{}\n\n[Response truncated - showing first 10,000 characters of {}]",
+                &body[..end],
+                body.chars().count()
+            )),
+            None => out.push_str(&body),
+        }
+    }
+    Ok(out)
+}
+
+#[async_trait]
+impl Tool for HttpTool {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn description(&self) -> &str {
+        "Make an HTTP request to an API: any method, headers and a JSON or text body. \
+Returns the status, key headers and the body. Only hosts the user has allowed can be \
+reached. For authenticated APIs, pass the name of a configured auth profile as auth \
+instead of putting credentials in headers. Use web_fetch to read web pages."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "url": {
+                    "type": "string",
+                    "description": "URL to request, including any query string"
+                },
+                "method": {
+                    "type": "string",
+                    "enum": METHODS,
+                    "description": "HTTP method (default GET)"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Request headers"
+                },
+                "body": {
+                    "description": "Request body: a JSON value is sent as JSON, a string as is"
+                },
+                "auth": {
+                    "type": "string",
+                    "description": "Name of an auth profile from the user's config"
+                }
+            }),
+            required: vec!["url".to_string()],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let request = self.build(&input)?;
+        let url = input["url"].as_str().unwrap_or_default();
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Request to {} failed", url))?;
+        describe(response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, Method as AxumMethod};
+    use axum::{routing::any, Json, Router};
+
+    fn context() -> ToolContext<'static> {
+        ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        }
+    }
+
+    fn config(toml: &str) -> HttpToolConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    /// Echoes the method, auth header and body of each request
+    async fn serve_echo() -> String {
+        async fn echo(method: AxumMethod, headers: HeaderMap, body: String) -> Json<Value> {
+            Json(serde_json::json!({
+                "method": method.as_str(),
+                "authorization": headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok()),
+                "body": body,
+            }))
+        }
+        let app = Router::new().route("/echo", any(echo));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_request_with_auth_profile_and_json_body() {
+        let site = serve_echo().await;
+        let tool = HttpTool::with_config(config(
+            r#"
+allowed_hosts = ["127.0.0.1"]
+
+[profiles.local]
+hosts = ["127.0.0.1"]
+type = "bearer"
+token = "s3cret"
+"#,
+        ));
+
+        let input = serde_json::json!({
+            "url": format!("{}/echo", site),
+            "method": "post",
+            "body": {"name": "finch"},
+            "auth": "local",
+        });
+        let result = tool.execute(input, &context()).await.unwrap();
+        assert!(result.starts_with("HTTP 200 OK\ncontent-type: application/json"));
+        assert!(result.contains(r#""method": "POST""#), "{}", result);
+        assert!(result.contains(r#""authorization": "Bearer s3cret""#));
+        assert!(result.contains(r#""body": "{\"name\":\"finch\"}""#));
+    }
+
+    #[tokio::test]
+    async fn test_refuses_other_hosts_and_profiles() {
+        let tool = HttpTool::with_config(config(
+            r#"
+allowed_hosts = ["api.example.com", "*.internal.example.com"]
+
+[profiles.github]
+hosts = ["api.github.com"]
+type = "header"
+name = "X-Api-Key"
+value_env = "FINCH_TEST_UNSET_KEY"
+"#,
+        ));
+        let run = |input: Value| {
+            let tool = &tool;
+            async move {
+                tool.execute(input, &context())
+                    .await
+                    .unwrap_err()
+                    .to_string()
+            }
+        };
+
+        let err = run(serde_json::json!({"url": "https://evil.example.org/"})).await;
+        assert!(err.contains("not in [http_tool] allowed_hosts"), "{}", err);
+
+        // A profile only goes to its own hosts, even when the host is allowed
+        let err = run(serde_json::json!({
+            "url": "https://api.example.com/",
+            "auth": "github",
+        }))
+        .await;
+        assert!(err.contains("not allowed for api.example.com"), "{}", err);
+
+        let err = run(serde_json::json!({
+            "url": "https://api.example.com/",
+            "auth": "gitlab",
+        }))
+        .await;
+        assert!(err.contains("configured: github"), "{}", err);
+
+        let err = run(serde_json::json!({
+            "url": "https://ci.internal.example.com/",
+            "method": "TRACE",
+        }))
+        .await;
+        assert!(err.contains("Unsupported method"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_profile_redirect_stops_at_other_hosts() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // The second server counts requests that carry the profile's key
+        let leaked = Arc::new(AtomicUsize::new(0));
+        let counter = leaked.clone();
+        let app = Router::new().route(
+            "/landing",
+            any(move |headers: HeaderMap| {
+                let counter = counter.clone();
+                async move {
+                    if headers.contains_key("x-api-key") {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    }
+                    "landed"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let landing = format!(
+            "http://localhost:{}/landing",
+            listener.local_addr().unwrap().port()
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // The first redirects there
+        let app = Router::new().route(
+            "/start",
+            any(move || std::future::ready(axum::response::Redirect::temporary(&landing))),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let start = format!("http://{}/start", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let tool = HttpTool::with_config(config(
+            r#"
+allowed_hosts = ["127.0.0.1", "localhost"]
+
+[profiles.local]
+hosts = ["127.0.0.1"]
+type = "header"
+name = "X-Api-Key"
+value = "s3cret"
+"#,
+        ));
+
+        let result = tool
+            .execute(
+                serde_json::json!({"url": start.clone(), "auth": "local"}),
+                &context(),
+            )
+            .await
+            .unwrap();
+        assert!(result.starts_with("HTTP 307"), "{}", result);
+        assert!(result.contains("location: http://localhost:"), "{}", result);
+        assert_eq!(leaked.load(Ordering::SeqCst), 0);
+
+        // Without the profile the redirect is followed
+        let result = tool
+            .execute(serde_json::json!({"url": start}), &context())
+            .await
+            .unwrap();
+        assert!(result.starts_with("HTTP 200 OK"), "{}", result);
+        assert!(result.ends_with("landed"), "{}", result);
+        assert_eq!(leaked.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_host_patterns() {
+        let patterns = vec!["api.github.com".to_string(), "*.example.com".to_string()];
+        assert!(host_allowed(&patterns, "api.github.com"));
+        assert!(host_allowed(&patterns, "API.GitHub.com"));
+        assert!(host_allowed(&patterns, "ci.example.com"));
+        assert!(host_allowed(&patterns, "a.b.example.com"));
+        assert!(!host_allowed(&patterns, "example.com"));
+        assert!(!host_allowed(&patterns, "badexample.com"));
+        assert!(!host_allowed(&patterns, "github.com"));
+    }
+}
@@ -49,6 +49,11 @@ impl Tool for SearchMemoryTool {
                     "type": "integer",
                     "description": "Maximum number of results to return (default: 3, max: 10)",
                     "default": 3
+                },
+                "include_archive": {
+                    "type": "boolean",
+                    "description": "Also search archived (older) memories. Slower; use only if recent memory has nothing relevant",
+                    "default": false
                 }
             }),
             required: vec!["query".to_string()],
@@ -61,10 +66,21 @@ impl Tool for SearchMemoryTool {
             .ok_or_else(|| anyhow::anyhow!("Missing required parameter: query"))?;
 
         let limit = params["limit"].as_u64().unwrap_or(3).min(10) as usize;
+        let include_archive = params["include_archive"].as_bool().unwrap_or(false);
 
         tracing::info!("Searching memory: query='{}', limit={}", query, limit);
 
-        let results = self.memory_system.query(query, Some(limit)).await?;
+        let mut results = self.memory_system.query(query, Some(limit)).await?;
+        if include_archive {
+            let hits = self.memory_system.search_archive(query, limit).await?;
+            results.extend(hits.into_iter().map(|hit| {
+                format!(
+                    "(archived, {}) {}",
+                    hit.record.timestamp().format("%Y-%m-%d"),
+                    hit.record.text()
+                )
+            }));
+        }
 
         if results.is_empty() {
             return Ok("No relevant memories found for this query.".to_string());
@@ -142,6 +158,13 @@ impl Tool for CreateMemoryTool {
 
         let context = params["context"].as_str().map(|s| s.to_string());
 
+        if crate::incognito::is_active() {
+            return Ok(
+                "Memory not created: this session is incognito, so nothing is remembered."
+                    .to_string(),
+            );
+        }
+
         // Store as a system note (not attributed to user or assistant)
         let role = "system";
         let full_content = if let Some(ctx) = context {
@@ -3,8 +3,10 @@
 // Concrete implementations of various tools
 
 // Read-only tools
+pub mod document; // PDF / docx / spreadsheet text for read
 pub mod glob;
 pub mod grep;
+pub mod outline;
 pub mod read;
 
 // File modification tools
@@ -13,10 +15,14 @@ pub mod patch;
 pub mod write;
 
 // Network tools
+pub mod browser;
+pub mod http;
 pub mod web_fetch;
+pub mod web_search;
 
 // Command execution
 pub mod bash;
+pub mod run_code;
 
 // Self-improvement tools
 pub mod restart;
@@ -28,6 +34,7 @@ pub mod present_plan;
 
 // User interaction tools
 pub mod ask_user_question;
+pub mod clipboard;
 
 // GUI automation tools (macOS only)
 #[cfg(target_os = "macos")]
@@ -36,9 +43,15 @@ pub mod gui;
 // LLM delegation tools (Phase 1)
 pub mod llm_tools;
 
+// Sub-agent delegation (spawn_task)
+pub mod spawn;
+
 // Memory tools (Phase 4)
 pub mod memory_tools;
 
+// Dependency documentation lookup
+pub mod doc_lookup;
+
 // Session task list tools
 pub mod todo_tools;
 
@@ -54,26 +67,35 @@ pub mod ansible;
 // Re-exports for convenience
 pub use ask_user_question::AskUserQuestionTool;
 pub use bash::BashTool;
+pub use browser::BrowserTool;
+pub use clipboard::ClipboardTool;
 pub use edit::EditTool;
 pub use enter_plan_mode::EnterPlanModeTool;
 pub use glob::GlobTool;
 pub use grep::GrepTool;
+pub use http::HttpTool;
+pub use outline::OutlineTool;
 pub use patch::PatchTool;
 pub use present_plan::PresentPlanTool;
 pub use read::ReadTool;
 pub use restart::RestartTool;
+pub use run_code::RunCodeTool;
 pub use save_and_exec::SaveAndExecTool;
+pub use spawn::TaskTool;
 pub use web_fetch::WebFetchTool;
+pub use web_search::WebSearchTool;
 pub use write::WriteTool;
 
 #[cfg(target_os = "macos")]
-pub use gui::{GuiClickTool, GuiInspectTool, GuiTypeTool};
+pub use gui::{GuiClickTool, GuiInspectTool, GuiScreenshotTool, GuiTypeTool};
 
 pub use llm_tools::LLMDelegationTool;
 
 pub use memory_tools::{CreateMemoryTool, ListRecentTool, SearchMemoryTool};
 
-pub use todo_tools::{TodoReadTool, TodoWriteTool};
+pub use doc_lookup::DocLookupTool;
+
+pub use todo_tools::{TodoPromoteTool, TodoReadTool, TodoWriteTool};
 
 pub use stack_tools::{StackClearTool, StackPopTool, StackPushTool, StackRunTool};
 
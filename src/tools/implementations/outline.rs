@@ -0,0 +1,110 @@
+// Outline tool - lists a source file's functions and types with line ranges
+//
+// The parsing lives in context::outline so other context features can reuse
+// it; this is the model-facing wrapper.
+
+use crate::context::outline::Outline;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::Path;
+
+/// Symbols listed before the outline is cut short
+const MAX_SYMBOLS: usize = 500;
+
+pub struct OutlineTool;
+
+#[async_trait]
+impl Tool for OutlineTool {
+    fn name(&self) -> &str {
+        "outline"
+    }
+
+    fn description(&self) -> &str {
+        "Show the structure of a source file: its functions, types, impls, classes and methods \
+         with their signatures and line ranges. Use it before reading a large file, then read \
+         only the ranges you need. Supports Rust, Python, JavaScript, TypeScript and Go."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema::simple(vec![("file_path", "Absolute path to the file to outline")])
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let file_path = input["file_path"]
+            .as_str()
+            .context("Missing file_path parameter")?;
+
+        let mut outline = Outline::of_file(Path::new(file_path))?;
+
+        if outline.symbols.is_empty() {
+            return Ok(format!(
+                "No functions or types found in {} ({} lines).",
+                file_path, outline.lines
+            ));
+        }
+
+        let total = outline.symbols.len();
+        outline.symbols.truncate(MAX_SYMBOLS);
+        let mut result = format!(
+            "{} ({} lines, {} symbols)\n\n{}",
+            file_path,
+            outline.lines,
+            total,
+            outline.render()
+        );
+        if total > MAX_SYMBOLS {
+            result.push_str(&format!("\n... (truncated to {} symbols)", MAX_SYMBOLS));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> ToolContext<'static> {
+        ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outline_rust_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(
+            &file,
+            "pub struct Cache;\n\nimpl Cache {\n    pub fn get(&self, key: &str) -> Option<String> {\n        None\n    }\n}\n",
+        )
+        .unwrap();
+
+        let input = serde_json::json!({"file_path": file.to_str().unwrap()});
+        let result = OutlineTool.execute(input, &context()).await.unwrap();
+        assert!(result.contains("(7 lines, 3 symbols)"));
+        assert!(result.contains("3-7  impl Cache"));
+        assert!(result.contains("4-6    pub fn get(&self, key: &str) -> Option<String>"));
+    }
+
+    #[tokio::test]
+    async fn test_outline_unsupported_language() {
+        let input = serde_json::json!({"file_path": "notes.txt"});
+        let result = OutlineTool.execute(input, &context()).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("supported languages"));
+    }
+}
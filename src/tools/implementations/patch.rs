@@ -25,6 +25,9 @@ const GRAY: &str = "\x1b[90m";
 const BOLD: &str = "\x1b[1m";
 const RESET: &str = "\x1b[0m";
 
+/// Context kept around the change by `unified_diff`
+const CONTEXT_LINES: usize = 3;
+
 pub struct PatchTool;
 
 #[async_trait]
@@ -90,6 +93,65 @@ impl Tool for PatchTool {
     }
 }
 
+/// Apply `patch` to `original` in memory (used for change previews and dry runs)
+pub(crate) fn apply_patch(original: &str, patch: &str) -> Result<String> {
+    let hunks =
+        parse_hunks(patch).context("Failed to parse unified diff — check @@ hunk header format")?;
+    if hunks.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No hunks found in patch. Ensure the diff contains @@ ... @@ headers."
+        ));
+    }
+    Ok(apply_hunks(original, &hunks)?.0)
+}
+
+/// Single-hunk unified diff of `before` → `after` (common prefix and suffix
+/// trimmed to `CONTEXT_LINES` of context)
+pub(crate) fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+    if prefix == old.len() && prefix == new.len() {
+        return diff;
+    }
+
+    let start = prefix.saturating_sub(CONTEXT_LINES);
+    let old_end = (old.len() - suffix + CONTEXT_LINES).min(old.len());
+    let new_end = (new.len() - suffix + CONTEXT_LINES).min(new.len());
+    // An empty range starts at the line before it, as in `diff -u`
+    let range = |len: usize| match len {
+        0 => format!("{},0", start),
+        _ => format!("{},{}", start + 1, len),
+    };
+    diff.push_str(&format!(
+        "@@ -{} +{} @@\n",
+        range(old_end - start),
+        range(new_end - start)
+    ));
+    for line in &old[start..prefix] {
+        diff.push_str(&format!(" {}\n", line));
+    }
+    for line in &old[prefix..old.len() - suffix] {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        diff.push_str(&format!("+{}\n", line));
+    }
+    for line in &old[old.len() - suffix..old_end] {
+        diff.push_str(&format!(" {}\n", line));
+    }
+    diff
+}
+
 // ── Unified diff parser ──────────────────────────────────────────────────────
 
 /// A single hunk from a unified diff.
@@ -1,14 +1,18 @@
 // Read tool - reads file contents from filesystem
 //
 // Supports optional offset (1-indexed start line) and limit (max lines)
-// so the AI can read large files in focused chunks.
+// so the AI can read large files in focused chunks.  PDFs, .docx files and
+// spreadsheets are read as their extracted text (see `document`), with
+// `pages` and `sheet` to address part of them.
 
+use super::document::{self, DocumentKind};
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolInputSchema};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub struct ReadTool;
 
@@ -21,7 +25,9 @@ impl Tool for ReadTool {
     fn description(&self) -> &str {
         "Read the contents of a file. Use offset and limit to read a specific range of lines \
          (e.g., offset=100 limit=50 reads lines 100-149). Without them, reads the whole file \
-         up to 50,000 characters."
+         up to 50,000 characters. PDF, .docx, .xlsx, .xls and .ods files are read as text; \
+         use pages (e.g. \"3\" or \"10-14\") for PDF pages and sheet (name or 1-based \
+         index) for a spreadsheet sheet."
     }
 
     fn input_schema(&self) -> ToolInputSchema {
@@ -39,6 +45,14 @@ impl Tool for ReadTool {
                 "limit": {
                     "type": "integer",
                     "description": "Maximum number of lines to read (optional)"
+                },
+                "pages": {
+                    "type": "string",
+                    "description": "PDF pages to read, e.g. \"3\", \"1-5\" or \"2,7-9\" (optional)"
+                },
+                "sheet": {
+                    "type": "string",
+                    "description": "Spreadsheet sheet to read, by name or 1-based index (optional, default: all)"
                 }
             }),
             required: vec!["file_path".to_string()],
@@ -50,8 +64,20 @@ impl Tool for ReadTool {
             .as_str()
             .context("Missing file_path parameter")?;
 
-        let contents = fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read file: {}", file_path))?;
+        let contents = match DocumentKind::of(Path::new(file_path)) {
+            Some(kind) => {
+                let path = PathBuf::from(file_path);
+                let pages = input["pages"].as_str().map(str::to_string);
+                let sheet = input["sheet"].as_str().map(str::to_string);
+                tokio::task::spawn_blocking(move || {
+                    document::extract(&path, kind, pages.as_deref(), sheet.as_deref())
+                })
+                .await
+                .map_err(|_| anyhow::anyhow!("Could not extract text from {}", file_path))??
+            }
+            None => fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read file: {}", file_path))?,
+        };
 
         let offset = input["offset"].as_u64().map(|n| n as usize);
         let limit = input["limit"].as_u64().map(|n| n as usize);
@@ -103,9 +129,13 @@ impl Tool for ReadTool {
 
         // No offset/limit — return full file up to char limit
         if contents.len() > 50_000 {
+            let mut end = 50_000;
+            while !contents.is_char_boundary(end) {
+                end -= 1;
+            }
             Ok(format!(
                 "{}\n\n[File truncated - showing first 50,000 of {} total characters]",
-                &contents[..50_000],
+                &contents[..end],
                 contents.len()
             ))
         } else {
@@ -0,0 +1,529 @@
+// RunCode tool - runs Python or JavaScript snippets in a sandboxed subprocess
+//
+// Each snippet runs in a fresh temporary directory with a minimal environment
+// (no API keys), no stdin, a wall-clock timeout, CPU / memory / file-size
+// limits and, unless `[run_code] allow_network` is set, no network: a new
+// network namespace on Linux, a sandbox-exec profile on macOS.  Platforms
+// without network isolation refuse to run snippets until it is allowed.
+//
+// The sandbox does not restrict file access outside the temporary directory.
+
+use crate::config::RunCodeConfig;
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+/// Characters of stdout and of stderr returned to the model
+const MAX_STREAM_CHARS: usize = 10_000;
+
+/// Largest file a snippet may write
+#[cfg(unix)]
+const MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Environment variables passed through to snippets: enough to find and run
+/// the interpreter (including pyenv / nvm / virtualenv setups), nothing else
+const PASSED_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "SYSTEMROOT",
+];
+const PASSED_ENV_PREFIXES: &[&str] = &["PYENV_", "NVM_", "VIRTUAL_ENV", "CONDA_"];
+
+static SETTINGS: OnceLock<RunCodeConfig> = OnceLock::new();
+
+/// Apply `[run_code]`.  Called once when the config is loaded; later calls
+/// are ignored.
+pub fn init(config: &RunCodeConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Python,
+    JavaScript,
+}
+
+impl Language {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "python" | "python3" | "py" => Some(Self::Python),
+            "javascript" | "js" | "node" => Some(Self::JavaScript),
+            _ => None,
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Python => "main.py",
+            Self::JavaScript => "main.js",
+        }
+    }
+}
+
+pub struct RunCodeTool {
+    config: RunCodeConfig,
+}
+
+impl RunCodeTool {
+    /// A tool using the `[run_code]` settings
+    pub fn new() -> Self {
+        Self::with_config(SETTINGS.get().cloned().unwrap_or_default())
+    }
+
+    pub fn with_config(config: RunCodeConfig) -> Self {
+        Self { config }
+    }
+
+    /// The interpreter invocation for `language` running `script`
+    fn command(&self, language: Language, script: &Path) -> Command {
+        let (program, mut args) = match language {
+            // -I: ignore PYTHON* variables and the user's site-packages
+            Language::Python => (self.config.python.clone(), vec!["-I".to_string()]),
+            // V8 reserves far more address space than it uses, so node's
+            // heap is capped by flag instead of RLIMIT_AS
+            Language::JavaScript => (
+                self.config.node.clone(),
+                vec![format!("--max-old-space-size={}", self.config.memory_mb)],
+            ),
+        };
+        args.push(script.display().to_string());
+
+        #[cfg(target_os = "macos")]
+        if !self.config.allow_network {
+            let mut cmd = Command::new("sandbox-exec");
+            cmd.args(["-p", "(version 1)(allow default)(deny network*)"])
+                .arg(program)
+                .args(args);
+            return cmd;
+        }
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        cmd
+    }
+
+    async fn run(&self, language: Language, code: &str) -> Result<String> {
+        if !self.config.allow_network && !cfg!(any(target_os = "linux", target_os = "macos")) {
+            bail!(
+                "run_code can only isolate the network on Linux and macOS; \
+                 set allow_network = true under [run_code] to run snippets without isolation"
+            );
+        }
+
+        let dir = SnippetDir::create()?;
+        let script = dir.path().join(language.file_name());
+        std::fs::write(&script, code).context("Failed to write snippet")?;
+
+        let mut cmd = self.command(language, &script);
+        cmd.current_dir(dir.path())
+            .env_clear()
+            .envs(passed_env())
+            .env("TMPDIR", dir.path())
+            .env("PYTHONDONTWRITEBYTECODE", "1")
+            .env("PYTHONIOENCODING", "utf-8")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        {
+            // Own process group, so a timeout kills whatever the snippet started
+            cmd.process_group(0);
+            limit_child(
+                &mut cmd,
+                ChildLimits {
+                    cpu_secs: self.config.timeout_secs.max(1),
+                    memory_bytes: (language == Language::Python)
+                        .then_some(self.config.memory_mb * 1024 * 1024),
+                    isolate_network: !self.config.allow_network && cfg!(target_os = "linux"),
+                },
+            );
+        }
+
+        let mut child = cmd.spawn().map_err(|e| {
+            let hint = if !self.config.allow_network && cfg!(target_os = "linux") {
+                " (if user namespaces are disabled on this system, set \
+                 allow_network = true under [run_code] to run without network isolation)"
+            } else {
+                ""
+            };
+            anyhow::anyhow!(
+                "Failed to start the {:?} interpreter: {}{}",
+                language,
+                e,
+                hint
+            )
+        })?;
+        let stdout = tokio::spawn(read_capped(child.stdout.take().expect("stdout was piped")));
+        let stderr = tokio::spawn(read_capped(child.stderr.take().expect("stderr was piped")));
+
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        let status = match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => Some(status?),
+            Err(_) => None,
+        };
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            // SAFETY: signals the snippet's own process group
+            unsafe {
+                libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+            }
+        }
+        if status.is_none() {
+            let _ = child.kill().await;
+        }
+        let stdout = stdout.await.unwrap_or_default();
+        let stderr = stderr.await.unwrap_or_default();
+
+        let mut result = match status {
+            Some(status) => match status.code() {
+                Some(code) => format!("Exit code: {}\n", code),
+                None => "Exit code: killed by a signal (CPU or memory limit?)\n".to_string(),
+            },
+            None => format!("Timed out after {}s and was killed\n", timeout.as_secs()),
+        };
+        result.push_str("\nSTDOUT:\n");
+        result.push_str(&stdout);
+        if !stderr.is_empty() {
+            result.push_str("\nSTDERR:\n");
+            result.push_str(&stderr);
+        }
+        Ok(result)
+    }
+}
+
+impl Default for RunCodeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for RunCodeTool {
+    fn name(&self) -> &str {
+        "run_code"
+    }
+
+    fn description(&self) -> &str {
+        "Run a short Python or JavaScript snippet in a sandboxed subprocess and return its \
+         exit code, stdout and stderr. Use it to check calculations, parse data or try out \
+         small pieces of logic. Each run starts in an empty temporary directory with a time \
+         and memory limit and no network access; print what you want to see."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "language": {
+                    "type": "string",
+                    "enum": ["python", "javascript"],
+                    "description": "Language of the snippet"
+                },
+                "code": {
+                    "type": "string",
+                    "description": "The program to run"
+                }
+            }),
+            required: vec!["language".to_string(), "code".to_string()],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let language = input["language"]
+            .as_str()
+            .context("Missing language parameter")?;
+        let language = Language::parse(language).with_context(|| {
+            format!(
+                "Unsupported language '{}' (use python or javascript)",
+                language
+            )
+        })?;
+        let code = input["code"].as_str().context("Missing code parameter")?;
+        self.run(language, code).await
+    }
+}
+
+/// A temporary directory removed on drop
+struct SnippetDir(PathBuf);
+
+impl SnippetDir {
+    fn create() -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("finch-run-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self(dir))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SnippetDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn passed_env() -> Vec<(String, String)> {
+    std::env::vars()
+        .filter(|(name, _)| {
+            PASSED_ENV.contains(&name.as_str())
+                || PASSED_ENV_PREFIXES.iter().any(|p| name.starts_with(p))
+        })
+        .collect()
+}
+
+/// Read all of `stream`, keeping the first `MAX_STREAM_CHARS` characters
+async fn read_capped<R: AsyncRead + Unpin>(mut stream: R) -> String {
+    let mut kept = Vec::new();
+    let mut total = 0usize;
+    let mut buf = [0u8; 8192];
+    while let Ok(n) = stream.read(&mut buf).await {
+        if n == 0 {
+            break;
+        }
+        total += n;
+        // Bytes, not chars, bound what is kept; trimmed to chars below
+        let room = (MAX_STREAM_CHARS * 4).saturating_sub(kept.len());
+        kept.extend_from_slice(&buf[..n.min(room)]);
+    }
+    let text = String::from_utf8_lossy(&kept);
+    match text.char_indices().nth(MAX_STREAM_CHARS) {
+        Some((end, _)) => format!("{}\n[truncated - {} bytes in total]\n", &text[..end], total),
+        None => text.into_owned(),
+    }
+}
+
+#[cfg(unix)]
+#[derive(Clone, Copy)]
+struct ChildLimits {
+    cpu_secs: u64,
+    memory_bytes: Option<u64>,
+    isolate_network: bool,
+}
+
+/// Apply `limits` to the child between fork and exec
+#[cfg(unix)]
+fn limit_child(cmd: &mut Command, limits: ChildLimits) {
+    // SAFETY: setrlimit and unshare are plain syscalls, and the closure
+    // touches nothing but its copied argument
+    unsafe {
+        cmd.pre_exec(move || {
+            set_limit(libc::RLIMIT_CPU, limits.cpu_secs)?;
+            set_limit(libc::RLIMIT_FSIZE, MAX_FILE_BYTES)?;
+            set_limit(libc::RLIMIT_CORE, 0)?;
+            if let Some(bytes) = limits.memory_bytes {
+                set_limit(libc::RLIMIT_AS, bytes)?;
+            }
+            #[cfg(target_os = "linux")]
+            if limits.isolate_network
+                && libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(unix)]
+fn set_limit(resource: RlimitResource, value: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    // SAFETY: plain syscall with a valid struct
+    if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(all(unix, target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(not(all(unix, target_os = "linux", target_env = "gnu")))]
+type RlimitResource = libc::c_int;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context() -> ToolContext<'static> {
+        ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        }
+    }
+
+    fn interpreter_available(program: &str) -> bool {
+        std::process::Command::new(program)
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+
+    /// Network isolation needs unprivileged user namespaces on Linux
+    fn isolation_available() -> bool {
+        !cfg!(target_os = "linux")
+            || std::process::Command::new("unshare")
+                .args(["-rn", "true"])
+                .status()
+                .is_ok_and(|s| s.success())
+    }
+
+    async fn run(tool: &RunCodeTool, language: &str, code: &str) -> Result<String> {
+        let input = serde_json::json!({ "language": language, "code": code });
+        tool.execute(input, &make_context()).await
+    }
+
+    fn open_tool() -> RunCodeTool {
+        RunCodeTool::with_config(RunCodeConfig {
+            allow_network: true,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_python_output_and_exit_code() {
+        if !interpreter_available("python3") {
+            return;
+        }
+        let out = run(
+            &open_tool(),
+            "python",
+            "import sys\nprint(sum(range(101)))\nprint('oops', file=sys.stderr)\nsys.exit(3)",
+        )
+        .await
+        .unwrap();
+        assert!(out.starts_with("Exit code: 3\n"), "{}", out);
+        assert!(out.contains("STDOUT:\n5050\n"), "{}", out);
+        assert!(out.contains("STDERR:\noops\n"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_javascript() {
+        if !interpreter_available("node") {
+            return;
+        }
+        let out = run(
+            &open_tool(),
+            "js",
+            "console.log([1, 2, 3].map(x => x * 2).join(','))",
+        )
+        .await
+        .unwrap();
+        assert!(out.contains("Exit code: 0\n"), "{}", out);
+        assert!(out.contains("2,4,6"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_runs_in_temp_dir_without_secrets() {
+        if !interpreter_available("python3") {
+            return;
+        }
+        std::env::set_var("FINCH_RUN_CODE_TEST_API_KEY", "secret");
+        let out = run(
+            &open_tool(),
+            "python",
+            "import os\nprint(os.getcwd())\nprint(os.environ.get('FINCH_RUN_CODE_TEST_API_KEY'))",
+        )
+        .await
+        .unwrap();
+        assert!(out.contains("finch-run-"), "{}", out);
+        assert!(out.contains("None"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_kills_snippet() {
+        if !interpreter_available("python3") {
+            return;
+        }
+        let tool = RunCodeTool::with_config(RunCodeConfig {
+            timeout_secs: 1,
+            allow_network: true,
+            ..Default::default()
+        });
+        let start = std::time::Instant::now();
+        let out = run(&tool, "python", "import time\ntime.sleep(30)")
+            .await
+            .unwrap();
+        assert!(out.starts_with("Timed out after 1s"), "{}", out);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_memory_limit() {
+        if !interpreter_available("python3") {
+            return;
+        }
+        let tool = RunCodeTool::with_config(RunCodeConfig {
+            memory_mb: 64,
+            allow_network: true,
+            ..Default::default()
+        });
+        let out = run(
+            &tool,
+            "python",
+            "x = bytearray(512 * 1024 * 1024)\nprint('allocated')",
+        )
+        .await
+        .unwrap();
+        assert!(!out.contains("allocated"), "{}", out);
+        assert!(out.contains("MemoryError"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_network_is_isolated_by_default() {
+        if !interpreter_available("python3") || !isolation_available() {
+            return;
+        }
+        let out = run(
+            &RunCodeTool::with_config(RunCodeConfig::default()),
+            "python",
+            "import socket\n\
+             try:\n    socket.create_connection(('1.1.1.1', 53), timeout=2)\n    print('connected')\n\
+             except OSError as e:\n    print('blocked', e)",
+        )
+        .await
+        .unwrap();
+        assert!(out.contains("blocked"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_language_and_missing_code() {
+        let tool = open_tool();
+        assert!(run(&tool, "cobol", "DISPLAY 'HI'").await.is_err());
+        let input = serde_json::json!({ "language": "python" });
+        assert!(tool.execute(input, &make_context()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_capped_truncates() {
+        let data = "x".repeat(MAX_STREAM_CHARS + 50);
+        let out = read_capped(data.as_bytes()).await;
+        assert!(out.starts_with(&"x".repeat(MAX_STREAM_CHARS)));
+        assert!(out.contains(&format!("{} bytes in total", MAX_STREAM_CHARS + 50)));
+    }
+}
@@ -3,18 +3,21 @@
 // Allows the orchestrating model to delegate subtasks to fresh, isolated
 // agentic loops with their own conversation history.  Each call to TaskTool
 // spawns one subagent that runs up to `max_turns` turns, then returns its
-// final text answer.
+// final text answer.  Only that answer reaches the parent conversation; the
+// subagent's own tool calls and results stay in its private history.
 //
 // Multiple TaskTool calls in a single model response can be executed in
-// parallel by the executor (see executor.rs).
+// parallel by the tool coordinator (see cli/repl_event/tool_execution.rs).
 
 use crate::claude::types::{ContentBlock, Message};
 use crate::providers::{LlmProvider, ProviderRequest};
 use crate::tools::implementations::bash::BashTool;
 use crate::tools::implementations::glob::GlobTool;
 use crate::tools::implementations::grep::GrepTool;
+use crate::tools::implementations::outline::OutlineTool;
 use crate::tools::implementations::read::ReadTool;
 use crate::tools::implementations::web_fetch::WebFetchTool;
+use crate::tools::implementations::web_search::WebSearchTool;
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolDefinition, ToolInputSchema, ToolUse};
 use anyhow::Result;
@@ -87,10 +90,18 @@ impl SubagentType {
 
     fn allowed_tools(self) -> &'static [&'static str] {
         match self {
-            Self::General => &["read", "glob", "grep", "bash", "web_fetch"],
-            Self::Explore => &["read", "glob", "grep"],
-            Self::Researcher => &["read", "glob", "grep", "web_fetch"],
-            Self::Coder => &["read", "glob", "grep", "bash"],
+            Self::General => &[
+                "read",
+                "glob",
+                "grep",
+                "outline",
+                "bash",
+                "web_fetch",
+                "web_search",
+            ],
+            Self::Explore => &["read", "glob", "grep", "outline"],
+            Self::Researcher => &["read", "glob", "grep", "outline", "web_fetch", "web_search"],
+            Self::Coder => &["read", "glob", "grep", "outline", "bash"],
             Self::Bash => &["bash"],
         }
     }
@@ -103,6 +114,14 @@ impl SubagentType {
 /// Default maximum number of turns a subagent may run.
 const DEFAULT_MAX_TURNS: usize = 10;
 
+/// Sent with the last turn's tool results so the subagent answers with what
+/// it has instead of running out of turns mid-task.
+const WRAP_UP_PROMPT: &str = "This is your last turn. Do not call any more tools: \
+     reply now with a summary of what you found or did, and anything left unfinished.";
+
+/// Live progress callback (the parent's `ToolContext::live_output`)
+type Progress = Arc<dyn Fn(String) + Send + Sync>;
+
 /// Tool that spawns a fresh, isolated subagent loop.
 ///
 /// The subagent has its own conversation history, a focused system prompt,
@@ -122,7 +141,8 @@ impl TaskTool {
         }
     }
 
-    /// Override the default maximum turns per subagent.
+    /// Override the default maximum turns per subagent (also the cap on a
+    /// call's `max_turns`).
     pub fn with_max_turns(mut self, max_turns: usize) -> Self {
         self.max_turns = max_turns;
         self
@@ -159,13 +179,17 @@ impl Tool for TaskTool {
                 "background": {
                     "type": "string",
                     "description": "Optional context from the parent conversation to share with the subagent."
+                },
+                "max_turns": {
+                    "type": "integer",
+                    "description": "Optional turn budget for the subagent (default and maximum: 10). Use fewer turns for small lookups."
                 }
             }),
             required: vec!["task".to_string()],
         }
     }
 
-    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+    async fn execute(&self, input: Value, context: &ToolContext<'_>) -> Result<String> {
         let task = input["task"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("spawn_task: missing required 'task' parameter"))?;
@@ -177,10 +201,15 @@ impl Tool for TaskTool {
 
         let background = input["background"].as_str();
 
+        let max_turns = input["max_turns"]
+            .as_u64()
+            .map_or(self.max_turns, |n| (n as usize).clamp(1, self.max_turns));
+
         info!(
-            "Spawning {:?} subagent for task: {}",
+            "Spawning {:?} subagent ({} turns) for task: {}",
             subagent_type,
-            &task[..task.len().min(80)]
+            max_turns,
+            task.chars().take(80).collect::<String>()
         );
 
         run_subagent(
@@ -188,7 +217,8 @@ impl Tool for TaskTool {
             task,
             subagent_type,
             background,
-            self.max_turns,
+            max_turns,
+            context.live_output.clone(),
         )
         .await
     }
@@ -201,13 +231,17 @@ impl Tool for TaskTool {
 /// Run a headless agentic loop and return the final text response.
 ///
 /// The subagent has no TUI, no approval prompts, and no recursion guard
-/// beyond `max_turns`.  Tools are executed directly without permission checks.
+/// beyond `max_turns`.  Tools are executed directly without permission checks
+/// (approving `spawn_task` approves its subagent type's tool set).  The last
+/// turn's results carry a wrap-up instruction, and one extra request collects
+/// the final answer.
 async fn run_subagent(
     provider: &dyn LlmProvider,
     task: &str,
     subagent_type: SubagentType,
     background: Option<&str>,
     max_turns: usize,
+    progress: Option<Progress>,
 ) -> Result<String> {
     // Build system prompt
     let mut system = subagent_type.system_prompt().to_string();
@@ -222,7 +256,7 @@ async fn run_subagent(
 
     let mut messages: Vec<Message> = vec![Message::user(task)];
 
-    for turn in 0..max_turns {
+    for turn in 0..=max_turns {
         debug!("Subagent turn {}/{}", turn + 1, max_turns);
 
         let mut request = ProviderRequest::new(messages.clone())
@@ -248,6 +282,15 @@ async fn run_subagent(
             );
             return Ok(text);
         }
+        if turn == max_turns {
+            // Ignored the wrap-up instruction: keep whatever text came with
+            // the tool calls
+            let text = response.text();
+            if !text.trim().is_empty() {
+                return Ok(text);
+            }
+            break;
+        }
 
         // Append assistant message (with tool_use blocks)
         messages.push(response.to_message());
@@ -258,6 +301,9 @@ async fn run_subagent(
 
         for tool_use in &tool_uses {
             debug!("Subagent calling tool: {}", tool_use.name);
+            if let Some(progress) = &progress {
+                progress(format!("↳ {}", describe_tool_use(tool_use)));
+            }
             let (content, is_error) = match execute_subagent_tool(&tools, tool_use).await {
                 Ok(output) => (output, false),
                 Err(e) => (format!("Error: {}", e), true),
@@ -269,6 +315,12 @@ async fn run_subagent(
             });
         }
 
+        if turn + 1 == max_turns {
+            result_blocks.push(ContentBlock::Text {
+                text: WRAP_UP_PROMPT.to_string(),
+            });
+        }
+
         // Append tool results as a user message
         messages.push(Message::with_content("user", result_blocks));
     }
@@ -279,6 +331,17 @@ async fn run_subagent(
     )
 }
 
+/// One-line description of a subagent tool call for live progress, e.g.
+/// `grep fn main`
+fn describe_tool_use(tool_use: &ToolUse) -> String {
+    let arg = ["command", "pattern", "file_path", "path", "query", "url"]
+        .iter()
+        .find_map(|key| tool_use.input[*key].as_str())
+        .unwrap_or("");
+    let arg: String = arg.chars().take(60).collect();
+    format!("{} {}", tool_use.name, arg).trim_end().to_string()
+}
+
 /// Execute a single tool inside the subagent (no permission checks).
 async fn execute_subagent_tool(tools: &[Box<dyn Tool>], tool_use: &ToolUse) -> Result<String> {
     let tool = tools
@@ -299,6 +362,9 @@ async fn execute_subagent_tool(tools: &[Box<dyn Tool>], tool_use: &ToolUse) -> R
         tokenizer: None,
         repl_mode: None,
         plan_content: None,
+        live_output: None,
+        stack: None,
+        poset: None,
     };
 
     tool.execute(tool_use.input.clone(), &context).await
@@ -312,8 +378,10 @@ fn build_subagent_tools(allowed: &[&str]) -> Vec<Box<dyn Tool>> {
             "read" => tools.push(Box::new(ReadTool)),
             "glob" => tools.push(Box::new(GlobTool)),
             "grep" => tools.push(Box::new(GrepTool)),
-            "bash" => tools.push(Box::new(BashTool)),
+            "outline" => tools.push(Box::new(OutlineTool)),
+            "bash" => tools.push(Box::new(BashTool::new())),
             "web_fetch" => tools.push(Box::new(WebFetchTool::new())),
+            "web_search" => tools.push(Box::new(WebSearchTool::new())),
             _ => {}
         }
     }
@@ -327,6 +395,70 @@ fn build_subagent_tools(allowed: &[&str]) -> Vec<Box<dyn Tool>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::{ProviderResponse, StreamChunk};
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Replays canned responses and records the messages of every request
+    struct ScriptedProvider {
+        replies: Mutex<VecDeque<ProviderResponse>>,
+        requests: Mutex<Vec<Vec<Message>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: Vec<Vec<ContentBlock>>) -> Self {
+            let replies = replies
+                .into_iter()
+                .map(|content| ProviderResponse {
+                    id: "test".to_string(),
+                    model: "test".to_string(),
+                    content,
+                    stop_reason: None,
+                    role: "assistant".to_string(),
+                    provider: "scripted".to_string(),
+                })
+                .collect();
+            Self {
+                replies: Mutex::new(replies),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        async fn send_message(&self, request: &ProviderRequest) -> Result<ProviderResponse> {
+            self.requests.lock().unwrap().push(request.messages.clone());
+            self.replies
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| anyhow::anyhow!("script exhausted"))
+        }
+
+        async fn send_message_stream(
+            &self,
+            _request: &ProviderRequest,
+        ) -> Result<tokio::sync::mpsc::Receiver<Result<StreamChunk>>> {
+            anyhow::bail!("not scripted")
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn default_model(&self) -> &str {
+            "scripted-model"
+        }
+    }
+
+    fn read_call(id: &str) -> ContentBlock {
+        ContentBlock::ToolUse {
+            id: id.to_string(),
+            name: "read".to_string(),
+            input: json!({"file_path": "/nonexistent/finch-subagent-test.txt"}),
+        }
+    }
 
     #[test]
     fn test_subagent_type_from_str() {
@@ -376,18 +508,90 @@ mod tests {
         assert!(names.contains(&"grep"));
         assert!(names.contains(&"bash"));
         assert!(names.contains(&"web_fetch"));
+        assert!(names.contains(&"web_search"));
     }
 
     #[test]
     fn test_task_tool_schema_requires_task() {
-        // Verify the schema marks "task" as required
-        struct MockProvider;
-
-        // We can't easily mock LlmProvider without a full impl, so just test
-        // the schema construction directly via SubagentType constants.
-        let allowed = SubagentType::General.allowed_tools();
-        assert!(allowed.contains(&"read"));
-        assert!(allowed.contains(&"bash"));
+        let tool = TaskTool::new(Arc::new(ScriptedProvider::new(vec![])));
+        let schema = tool.input_schema();
+        assert_eq!(schema.required, vec!["task".to_string()]);
+        assert!(schema.properties["max_turns"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_subagent_returns_only_final_answer() {
+        let provider = ScriptedProvider::new(vec![
+            vec![ContentBlock::text("Let me look."), read_call("t1")],
+            vec![ContentBlock::text("The file does not exist.")],
+        ]);
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&lines);
+        let progress: Progress = Arc::new(move |line| sink.lock().unwrap().push(line));
+
+        let answer = run_subagent(
+            &provider,
+            "Read the test file",
+            SubagentType::Explore,
+            Some("parent context"),
+            5,
+            Some(progress),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(answer, "The file does not exist.");
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec!["↳ read /nonexistent/finch-subagent-test.txt"]
+        );
+        // The second request carries the failed read back as an error result
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(matches!(
+            requests[1].last().unwrap().content[0],
+            ContentBlock::ToolResult {
+                is_error: Some(true),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_subagent_wraps_up_when_turn_budget_runs_out() {
+        let provider = ScriptedProvider::new(vec![
+            vec![read_call("t1")],
+            vec![read_call("t2")],
+            vec![ContentBlock::text("Partial findings.")],
+        ]);
+        let answer = run_subagent(&provider, "Dig", SubagentType::Explore, None, 2, None)
+            .await
+            .unwrap();
+
+        assert_eq!(answer, "Partial findings.");
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let last = requests[2].last().unwrap();
+        assert!(last
+            .content
+            .iter()
+            .any(|block| block.as_text() == Some(WRAP_UP_PROMPT)));
+        // Only the final turn carries the instruction
+        assert!(!requests[1]
+            .last()
+            .unwrap()
+            .content
+            .iter()
+            .any(|block| block.is_text()));
+    }
+
+    #[tokio::test]
+    async fn test_subagent_without_answer_after_wrap_up_fails() {
+        let provider = ScriptedProvider::new(vec![vec![read_call("t1")], vec![read_call("t2")]]);
+        let err = run_subagent(&provider, "Dig", SubagentType::Explore, None, 1, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("max_turns (1)"));
     }
 
     #[test]
@@ -1,15 +1,17 @@
-// TodoWrite / TodoRead tool implementations
+// TodoWrite / TodoRead / TodoPromote tool implementations
 //
 // The LLM uses these tools to manage a session-scoped task list that is
-// displayed in the TUI live area.  Both tools capture an
+// displayed in the TUI live area.  The tools capture an
 // Arc<RwLock<TodoList>> directly — no ToolContext fields needed.
 
+use crate::agent::backlog::TaskBacklog;
 use crate::tools::registry::Tool;
 use crate::tools::todo::{TodoItem, TodoList};
 use crate::tools::types::{ToolContext, ToolInputSchema};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -152,6 +154,88 @@ impl Tool for TodoReadTool {
     }
 }
 
+// ─── TodoPromoteTool ──────────────────────────────────────────────────────────
+
+/// Promote todos to the agent backlog (`/promote` and TodoPromote): the
+/// todos with `ids`, or every open one.  The tasks work in the current
+/// directory.  Returns a summary for the user or model.
+pub async fn promote_todos(
+    todo_list: &RwLock<TodoList>,
+    ids: &[String],
+    tasks_path: &Path,
+) -> Result<String> {
+    let mut backlog = TaskBacklog::load(tasks_path.to_path_buf())?;
+    let repo = std::env::current_dir()
+        .ok()
+        .map(|dir| dir.display().to_string());
+    let added = todo_list.write().await.promote(ids, &mut backlog, repo)?;
+    if added.is_empty() {
+        return Ok("No todos to promote: every open todo is already in the backlog.".to_string());
+    }
+    let mut summary = format!(
+        "Promoted {} todo{} to {}:",
+        added.len(),
+        if added.len() == 1 { "" } else { "s" },
+        tasks_path.display()
+    );
+    for (todo_id, task_id) in &added {
+        summary.push_str(&format!("\n  todo {} → task {}", todo_id, task_id));
+    }
+    summary.push_str("\nThey are checked off here when `finch agent` finishes them.");
+    Ok(summary)
+}
+
+/// Turn session todos into persisted agent backlog tasks.
+pub struct TodoPromoteTool {
+    todo_list: Arc<RwLock<TodoList>>,
+    tasks_path: PathBuf,
+}
+
+impl TodoPromoteTool {
+    pub fn new(todo_list: Arc<RwLock<TodoList>>, tasks_path: PathBuf) -> Self {
+        Self {
+            todo_list,
+            tasks_path,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for TodoPromoteTool {
+    fn name(&self) -> &str {
+        "TodoPromote"
+    }
+
+    fn description(&self) -> &str {
+        "Move session todos into the persistent agent backlog (tasks.toml) so `finch agent` \
+         works on them after this session. Use for follow-up work the user wants done later, \
+         not for steps you are about to do yourself. Omit ids to promote every open todo."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Todo IDs to promote (default: every open todo not promoted yet)"
+                }
+            }),
+            required: vec![],
+        }
+    }
+
+    async fn execute(&self, params: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let ids: Vec<String> = match params.get("ids") {
+            Some(ids) => serde_json::from_value(ids.clone())
+                .map_err(|e| anyhow::anyhow!("Invalid ids: {}", e))?,
+            None => Vec::new(),
+        };
+        promote_todos(&self.todo_list, &ids, &self.tasks_path).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,6 +445,43 @@ mod tests {
         assert_eq!(items.len(), 2);
     }
 
+    // ── TodoPromoteTool ───────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_todo_promote_writes_backlog() {
+        let list = make_list();
+        let dir = tempfile::tempdir().unwrap();
+        let tasks_path = dir.path().join("tasks.toml");
+        let ctx = dummy_context();
+        TodoWriteTool::new(Arc::clone(&list))
+            .execute(
+                serde_json::json!({
+                    "todos": [
+                        { "id": "1", "content": "Alpha", "status": "in_progress", "priority": "high" },
+                        { "id": "2", "content": "Beta",  "status": "completed",   "priority": "low"  }
+                    ]
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+
+        let tool = TodoPromoteTool::new(Arc::clone(&list), tasks_path.clone());
+        let result = tool.execute(serde_json::json!({}), &ctx).await.unwrap();
+        assert!(result.contains("todo 1 → task 001"), "{result}");
+
+        let backlog = TaskBacklog::load(tasks_path).unwrap();
+        assert_eq!(backlog.tasks().len(), 1);
+        assert_eq!(backlog.tasks()[0].description, "Alpha");
+
+        let again = tool.execute(serde_json::json!({}), &ctx).await.unwrap();
+        assert!(again.starts_with("No todos to promote"), "{again}");
+        let unknown = tool
+            .execute(serde_json::json!({ "ids": ["7"] }), &ctx)
+            .await;
+        assert!(unknown.is_err());
+    }
+
     // ── Schema ────────────────────────────────────────────────────────────────
 
     #[test]
@@ -1,25 +1,28 @@
-// WebFetch tool - fetches content from URLs
+// WebFetch tool - fetches pages as markdown through the shared web fetcher
+// (page cache, robots.txt and per-host pacing live in `crate::web`)
 
 use crate::tools::registry::Tool;
 use crate::tools::types::{ToolContext, ToolInputSchema};
+use crate::web::WebFetcher;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use reqwest;
 use serde_json::Value;
+use std::sync::Arc;
+
+/// Characters of page content returned to the model
+const MAX_OUTPUT_CHARS: usize = 10_000;
 
 pub struct WebFetchTool {
-    client: reqwest::Client,
+    fetcher: Arc<WebFetcher>,
 }
 
 impl WebFetchTool {
     pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .user_agent("Shammah/0.1.0")
-                .build()
-                .unwrap(),
-        }
+        Self::with_fetcher(crate::web::fetcher())
+    }
+
+    pub fn with_fetcher(fetcher: Arc<WebFetcher>) -> Self {
+        Self { fetcher }
     }
 }
 
@@ -36,7 +39,9 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch content from a URL. Use for retrieving web pages or API data."
+        "Fetch content from a URL. Use for retrieving web pages or API data. \
+HTML pages are returned as markdown of their main content. Recent fetches are \
+served from a cache, and pages disallowed by the site's robots.txt are refused."
     }
 
     fn input_schema(&self) -> ToolInputSchema {
@@ -49,29 +54,17 @@ impl Tool for WebFetchTool {
     async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
         let url = input["url"].as_str().context("Missing url parameter")?;
 
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .with_context(|| format!("Failed to fetch URL: {}", url))?;
-
-        let status = response.status();
-        if !status.is_success() {
-            anyhow::bail!("HTTP error {}: {}", status, url);
-        }
-
-        let body = response.text().await?;
+        let page = self.fetcher.fetch(url).await?;
+        let body = page.content;
 
         // Limit to 10,000 chars
-        if body.len() > 10_000 {
-            Ok(format!(
+        match body.char_indices().nth(MAX_OUTPUT_CHARS) {
+            Some((end, _)) => Ok(format!(
                 "{}\n\n[Response truncated - showing first 10,000 characters of {}]",
-                &body[..10_000],
-                body.len()
-            ))
-        } else {
-            Ok(body)
+                &body[..end],
+                body.chars().count()
+            )),
+            None => Ok(body),
         }
     }
 }
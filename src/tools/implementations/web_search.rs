@@ -0,0 +1,159 @@
+// WebSearch tool - ranked search results through the shared web searcher
+// (backend choice, result cache and request pacing live in `crate::web::search`)
+
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use crate::web::WebSearcher;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Most results a single call may ask for
+const MAX_LIMIT: u64 = 10;
+
+pub struct WebSearchTool {
+    searcher: Arc<WebSearcher>,
+}
+
+impl WebSearchTool {
+    pub fn new() -> Self {
+        Self::with_searcher(crate::web::search::searcher())
+    }
+
+    pub fn with_searcher(searcher: Arc<WebSearcher>) -> Self {
+        Self { searcher }
+    }
+}
+
+impl Default for WebSearchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web and get ranked results (title, URL, snippet). Use for current \
+information you may not know, such as the latest version of a library or recent \
+announcements. Follow up with web_fetch to read a result in full."
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: serde_json::json!({
+                "query": {
+                    "type": "string",
+                    "description": "The search query (e.g., 'latest tokio release')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results to return (default: 5, max: 10)"
+                }
+            }),
+            required: vec!["query".to_string()],
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let query = input["query"].as_str().context("Missing query parameter")?;
+        let limit = input["limit"]
+            .as_u64()
+            .map(|n| n.clamp(1, MAX_LIMIT) as usize);
+
+        tracing::info!(
+            "Web search ({}): {:?}",
+            self.searcher.backend().as_str(),
+            query
+        );
+        let results = self.searcher.search(query, limit).await?;
+
+        if results.is_empty() {
+            return Ok(format!("No results found for '{}'.", query));
+        }
+        Ok(results
+            .iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let mut entry = format!("{}. {}\n   {}", i + 1, result.title, result.url);
+                if !result.snippet.is_empty() {
+                    entry.push_str("\n   ");
+                    entry.push_str(&result.snippet);
+                }
+                entry
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SearchBackend, WebSearchConfig};
+    use axum::{routing::get, Json, Router};
+
+    #[tokio::test]
+    async fn test_web_search_formats_results() {
+        let app = Router::new().route(
+            "/search",
+            get(|| async {
+                Json(serde_json::json!({
+                    "results": [
+                        {
+                            "title": "tokio 1.47.1",
+                            "url": "https://crates.io/crates/tokio",
+                            "content": "An event-driven, non-blocking I/O platform"
+                        },
+                        { "title": "Tokio", "url": "https://tokio.rs/" }
+                    ]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let searcher = WebSearcher::new(
+            &WebSearchConfig {
+                backend: SearchBackend::Searxng,
+                searxng_url: Some(format!("http://{}", addr)),
+                min_interval_ms: 0,
+                ..WebSearchConfig::default()
+            },
+            None,
+        );
+        let tool = WebSearchTool::with_searcher(Arc::new(searcher));
+        let context = ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        };
+
+        let result = tool
+            .execute(serde_json::json!({"query": "latest tokio"}), &context)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            "1. tokio 1.47.1\n   https://crates.io/crates/tokio\n   \
+             An event-driven, non-blocking I/O platform\n\n\
+             2. Tokio\n   https://tokio.rs/"
+        );
+
+        assert!(tool.execute(serde_json::json!({}), &context).await.is_err());
+    }
+}
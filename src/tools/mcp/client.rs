@@ -3,7 +3,7 @@
 // Uses direct JSON-RPC 2.0 implementation over STDIO/SSE transports.
 
 use super::config::McpServerConfig;
-use super::connection::McpConnection;
+use super::connection::{McpConnection, McpResource, McpTool};
 use crate::tools::types::{ToolDefinition, ToolInputSchema};
 use anyhow::{Context, Result};
 use serde_json::Value;
@@ -11,7 +11,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-/// MCP client that manages multiple server connections (stub implementation)
+/// MCP client that manages multiple server connections
 pub struct McpClient {
     /// Active server connections (name -> connection)
     connections: Arc<RwLock<HashMap<String, Arc<RwLock<McpConnection>>>>>,
@@ -43,11 +43,7 @@ impl McpClient {
 
             match McpConnection::connect(name.clone(), config).await {
                 Ok(conn) => {
-                    client
-                        .connections
-                        .write()
-                        .await
-                        .insert(name.clone(), Arc::new(RwLock::new(conn)));
+                    client.add_connection(conn).await;
                     tracing::info!("Connected to MCP server: {}", name);
                 }
                 Err(e) => {
@@ -60,67 +56,102 @@ impl McpClient {
         Ok(client)
     }
 
+    /// Add an established connection, replacing any of the same name
+    pub(super) async fn add_connection(&self, conn: McpConnection) {
+        self.connections
+            .write()
+            .await
+            .insert(conn.name().to_string(), Arc::new(RwLock::new(conn)));
+    }
+
     /// List all available tools from all connected servers
     pub async fn list_tools(&self) -> Vec<ToolDefinition> {
+        self.server_tools()
+            .await
+            .into_iter()
+            .map(|(server_name, tool)| tool_definition(&server_name, &tool))
+            .collect()
+    }
+
+    /// Tools of every connected server, as (server, tool)
+    pub async fn server_tools(&self) -> Vec<(String, McpTool)> {
         let connections = self.connections.read().await;
         let mut tools = Vec::new();
-
         for (server_name, conn) in connections.iter() {
             let conn = conn.read().await;
-            let server_tools = conn.list_tools();
-
-            for tool in server_tools {
-                // Convert MCP tool to our ToolDefinition format
-                // Prefix tool name with "mcp_<server>_" to avoid conflicts
-                let prefixed_name = format!("mcp_{}_{}", server_name, tool.name);
-
-                // Convert MCP input schema to our format
-                let input_schema = convert_mcp_schema(&tool.input_schema);
-
-                tools.push(ToolDefinition {
-                    name: prefixed_name,
-                    description: tool
-                        .description
-                        .clone()
-                        .unwrap_or_else(|| format!("Tool from MCP server '{}'", server_name)),
-                    input_schema,
-                });
-            }
+            tools.extend(
+                conn.list_tools()
+                    .iter()
+                    .map(|tool| (server_name.clone(), tool.clone())),
+            );
         }
-
         tools
     }
 
-    /// Execute a tool on the appropriate server
-    pub async fn execute_tool(&self, tool_name: &str, params: Value) -> Result<String> {
-        // Parse prefixed name: "mcp_<server>_<tool>"
-        let parts: Vec<&str> = tool_name.split('_').collect();
-        if parts.len() < 3 || parts[0] != "mcp" {
-            anyhow::bail!("Invalid MCP tool name: {}", tool_name);
+    /// Resources of every connected server, as (server, resource)
+    pub async fn list_resources(&self) -> Vec<(String, McpResource)> {
+        let connections = self.connections.read().await;
+        let mut resources = Vec::new();
+        for (server_name, conn) in connections.iter() {
+            let conn = conn.read().await;
+            resources.extend(
+                conn.list_resources()
+                    .iter()
+                    .map(|resource| (server_name.clone(), resource.clone())),
+            );
         }
+        resources
+    }
 
-        let server_name = parts[1];
-        let actual_tool_name = parts[2..].join("_");
+    /// Execute a tool by its prefixed name (`mcp_<server>_<tool>`)
+    pub async fn execute_tool(&self, tool_name: &str, params: Value) -> Result<String> {
+        let (server_name, actual_tool_name) = {
+            let connections = self.connections.read().await;
+            split_tool_name(tool_name, connections.keys())
+                .with_context(|| format!("Invalid MCP tool name: {}", tool_name))?
+        };
+        self.call_tool(&server_name, &actual_tool_name, params)
+            .await
+    }
 
+    /// Call `tool_name` on `server_name`
+    pub async fn call_tool(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        params: Value,
+    ) -> Result<String> {
         tracing::debug!(
             "Executing MCP tool '{}' on server '{}'",
-            actual_tool_name,
+            tool_name,
             server_name
         );
 
-        // Find the connection
-        let connections = self.connections.read().await;
-        let conn = connections
-            .get(server_name)
-            .with_context(|| format!("MCP server '{}' not found", server_name))?;
-
-        // Call the tool
+        let conn = self.connection(server_name).await?;
         let conn = conn.read().await;
-        conn.call_tool(&actual_tool_name, params)
+        conn.call_tool(tool_name, params)
             .await
             .context("Failed to execute MCP tool")
     }
 
+    /// Read the resource at `uri` from `server_name`
+    pub async fn read_resource(&self, server_name: &str, uri: &str) -> Result<String> {
+        let conn = self.connection(server_name).await?;
+        let conn = conn.read().await;
+        conn.read_resource(uri)
+            .await
+            .with_context(|| format!("Failed to read MCP resource {}", uri))
+    }
+
+    async fn connection(&self, server_name: &str) -> Result<Arc<RwLock<McpConnection>>> {
+        self.connections
+            .read()
+            .await
+            .get(server_name)
+            .cloned()
+            .with_context(|| format!("MCP server '{}' not found", server_name))
+    }
+
     /// Refresh tools from all servers
     pub async fn refresh_all_tools(&self) -> Result<()> {
         let connections = self.connections.read().await;
@@ -130,6 +161,15 @@ impl McpClient {
             if let Err(e) = conn.refresh_tools().await {
                 tracing::warn!("Failed to refresh tools for MCP server '{}': {}", name, e);
             }
+            if !conn.list_resources().is_empty() {
+                if let Err(e) = conn.refresh_resources().await {
+                    tracing::warn!(
+                        "Failed to refresh resources for MCP server '{}': {}",
+                        name,
+                        e
+                    );
+                }
+            }
         }
 
         Ok(())
@@ -177,6 +217,38 @@ impl McpClient {
     }
 }
 
+/// Name of a server's tool as registered: `mcp_<server>_<tool>`
+pub fn tool_name(server_name: &str, tool_name: &str) -> String {
+    format!("mcp_{}_{}", server_name, tool_name)
+}
+
+/// A server's tool as a ToolDefinition under its prefixed name
+pub(super) fn tool_definition(server_name: &str, tool: &McpTool) -> ToolDefinition {
+    ToolDefinition {
+        name: tool_name(server_name, &tool.name),
+        description: tool
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Tool from MCP server '{}'", server_name)),
+        input_schema: convert_mcp_schema(&tool.input_schema),
+    }
+}
+
+/// Split `mcp_<server>_<tool>` into (server, tool); server names may contain
+/// underscores, so the longest matching server wins
+fn split_tool_name<'a>(
+    prefixed: &str,
+    servers: impl Iterator<Item = &'a String>,
+) -> Option<(String, String)> {
+    let rest = prefixed.strip_prefix("mcp_")?;
+    servers
+        .filter_map(|server| {
+            let tool = rest.strip_prefix(server.as_str())?.strip_prefix('_')?;
+            (!tool.is_empty()).then(|| (server.clone(), tool.to_string()))
+        })
+        .max_by_key(|(server, _)| server.len())
+}
+
 /// Convert MCP input schema to our ToolInputSchema format
 fn convert_mcp_schema(mcp_schema: &Value) -> ToolInputSchema {
     // MCP schemas are JSON Schema format
@@ -239,6 +311,7 @@ mod tests {
                 args: vec![],
                 env: HashMap::new(),
                 url: None,
+                headers: HashMap::new(),
                 enabled: true,
             },
         );
@@ -250,6 +323,7 @@ mod tests {
                 args: vec![],
                 env: HashMap::new(),
                 url: None,
+                headers: HashMap::new(),
                 enabled: false, // Disabled
             },
         );
@@ -263,6 +337,22 @@ mod tests {
         assert_eq!(servers.len(), 0);
     }
 
+    #[test]
+    fn test_split_tool_name() {
+        let servers = ["github".to_string(), "github_enterprise".to_string()];
+        assert_eq!(
+            split_tool_name("mcp_github_create_issue", servers.iter()),
+            Some(("github".to_string(), "create_issue".to_string()))
+        );
+        assert_eq!(
+            split_tool_name("mcp_github_enterprise_list_repos", servers.iter()),
+            Some(("github_enterprise".to_string(), "list_repos".to_string()))
+        );
+        assert_eq!(split_tool_name("mcp_gitlab_list", servers.iter()), None);
+        assert_eq!(split_tool_name("mcp_github_", servers.iter()), None);
+        assert_eq!(split_tool_name("bash", servers.iter()), None);
+    }
+
     #[tokio::test]
     async fn test_disconnect() {
         let mut config = HashMap::new();
@@ -274,6 +364,7 @@ mod tests {
                 args: vec![],
                 env: HashMap::new(),
                 url: None,
+                headers: HashMap::new(),
                 enabled: true,
             },
         );
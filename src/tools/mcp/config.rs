@@ -25,6 +25,11 @@ pub struct McpServerConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 
+    /// HTTP headers sent with every request (for SSE transport), e.g.
+    /// `Authorization`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
     /// Whether server is enabled
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -81,6 +86,7 @@ mod tests {
             ],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -95,6 +101,7 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -109,6 +116,7 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             url: Some("http://localhost:3000/mcp".to_string()),
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -123,6 +131,7 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -140,6 +149,7 @@ mod tests {
             ],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -165,6 +175,7 @@ mod tests {
             args: vec![],
             env,
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
@@ -184,6 +195,7 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: false,
         };
 
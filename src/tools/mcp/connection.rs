@@ -1,19 +1,32 @@
 // MCP connection wrapper for a single server
 //
-// Implements JSON-RPC 2.0 over STDIO to communicate with MCP servers
+// Implements JSON-RPC 2.0 over STDIO (one message per line) or HTTP+SSE
+// (requests POSTed to the endpoint the server announces, responses on the
+// event stream).  A reader task hands each response to the request waiting on
+// its id, so tools running in parallel never read each other's replies.
 
 use super::config::{McpServerConfig, TransportType};
 use anyhow::{Context, Result};
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{
-    Child as TokioChild, ChildStdin as TokioChildStdin, ChildStdout as TokioChildStdout,
-};
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::Child as TokioChild;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+/// How long to wait for a server to answer one request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long an SSE server has to announce its message endpoint
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// JSON-RPC 2.0 request
 #[derive(Debug, Serialize)]
@@ -30,6 +43,9 @@ struct JsonRpcRequest {
 struct JsonRpcResponse {
     jsonrpc: String,
     id: u64,
+    /// Set when this is a request from the server, not a response
+    #[serde(default)]
+    method: Option<String>,
     #[serde(default)]
     result: Option<Value>,
     #[serde(default)]
@@ -55,6 +71,17 @@ pub struct McpTool {
     pub input_schema: Value,
 }
 
+/// MCP resource (a file, record or document the server can return)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, rename = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
 /// MCP server implementation info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerInfo {
@@ -62,7 +89,48 @@ pub struct McpServerInfo {
     pub version: String,
 }
 
-/// A single MCP server connection over STDIO
+/// Requests waiting for a response, by id (`None` once the server is gone)
+type PendingMap = Option<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>;
+
+#[derive(Clone)]
+struct Pending(Arc<std::sync::Mutex<PendingMap>>);
+
+impl Pending {
+    fn new() -> Self {
+        Self(Arc::new(std::sync::Mutex::new(Some(HashMap::new()))))
+    }
+
+    /// Wait for a response to `id`; false once the connection has closed
+    fn insert(&self, id: u64, tx: oneshot::Sender<JsonRpcResponse>) -> bool {
+        match self.0.lock().unwrap().as_mut() {
+            Some(pending) => pending.insert(id, tx).is_none(),
+            None => false,
+        }
+    }
+
+    fn remove(&self, id: u64) -> Option<oneshot::Sender<JsonRpcResponse>> {
+        self.0.lock().unwrap().as_mut()?.remove(&id)
+    }
+
+    /// Fail every waiting request and refuse new ones
+    fn close(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+/// Where outgoing messages go
+enum Outbound {
+    /// The server process's stdin
+    Stdio(Mutex<Box<dyn AsyncWrite + Send + Unpin>>),
+    /// POSTed to the endpoint from the server's `endpoint` event
+    Sse {
+        http: reqwest::Client,
+        endpoint: reqwest::Url,
+        headers: HeaderMap,
+    },
+}
+
+/// A single MCP server connection
 #[allow(dead_code)]
 pub struct McpConnection {
     /// Server name
@@ -74,20 +142,29 @@ pub struct McpConnection {
     /// Available tools (cached from discovery)
     tools: Vec<McpTool>,
 
+    /// Available resources (cached from discovery)
+    resources: Vec<McpResource>,
+
     /// Server info (if connected)
     server_info: Option<McpServerInfo>,
 
+    /// Capabilities from the server's `initialize` response
+    capabilities: Value,
+
     /// Connection status
     is_connected: bool,
 
     /// Child process handle (for STDIO transport)
     child: Option<TokioChild>,
 
-    /// STDIO writer
-    stdin: Option<Arc<Mutex<TokioChildStdin>>>,
+    /// Outgoing messages
+    outbound: Outbound,
+
+    /// Requests waiting for a response
+    pending: Pending,
 
-    /// STDIO reader
-    stdout: Option<Arc<Mutex<BufReader<TokioChildStdout>>>>,
+    /// Task reading responses from the server
+    reader: JoinHandle<()>,
 
     /// Request ID counter
     next_id: Arc<AtomicU64>,
@@ -101,12 +178,38 @@ impl McpConnection {
             .validate(&name)
             .context("Invalid MCP server configuration")?;
 
-        match config.transport {
-            TransportType::Stdio => Self::connect_stdio(name, config).await,
-            TransportType::Sse => {
-                anyhow::bail!("SSE transport not yet implemented")
+        let conn = match config.transport {
+            TransportType::Stdio => Self::connect_stdio(name, config).await?,
+            TransportType::Sse => Self::connect_sse(name, config).await?,
+        };
+        conn.start().await
+    }
+
+    /// Initialize the connection and discover what the server offers
+    pub(super) async fn start(mut self) -> Result<Self> {
+        self.initialize().await?;
+
+        // Discover available tools and resources
+        self.refresh_tools().await?;
+        if self.capabilities.get("resources").is_some() {
+            if let Err(e) = self.refresh_resources().await {
+                tracing::warn!(
+                    "Failed to list resources of MCP server '{}': {}",
+                    self.name,
+                    e
+                );
             }
         }
+
+        self.is_connected = true;
+        tracing::info!(
+            "Connected to MCP server '{}' with {} tools and {} resources",
+            self.name,
+            self.tools.len(),
+            self.resources.len()
+        );
+
+        Ok(self)
     }
 
     /// Connect via STDIO transport
@@ -139,35 +242,104 @@ impl McpConnection {
             .take()
             .context("Failed to open stdout for MCP server")?;
 
-        let stdin = Arc::new(Mutex::new(stdin));
-        let stdout = Arc::new(Mutex::new(BufReader::new(stdout)));
+        let mut conn = Self::from_streams(name, config, BufReader::new(stdout), stdin);
+        conn.child = Some(child);
+        Ok(conn)
+    }
+
+    /// A connection exchanging newline-delimited messages over `reader`/`writer`
+    pub(super) fn from_streams(
+        name: String,
+        config: &McpServerConfig,
+        reader: impl AsyncBufRead + Send + Unpin + 'static,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+    ) -> Self {
+        let pending = Pending::new();
+        let reader = spawn_line_reader(name.clone(), reader, pending.clone());
+        Self::new(
+            name,
+            config,
+            Outbound::Stdio(Mutex::new(Box::new(writer))),
+            pending,
+            reader,
+        )
+    }
+
+    /// Connect via HTTP+SSE transport
+    async fn connect_sse(name: String, config: &McpServerConfig) -> Result<Self> {
+        let url = config.url.as_ref().context("SSE transport requires url")?;
+        let url = reqwest::Url::parse(url)
+            .with_context(|| format!("Invalid URL for MCP server '{}': {}", name, url))?;
+        let headers = header_map(&config.headers)
+            .with_context(|| format!("Invalid headers for MCP server '{}'", name))?;
+
+        tracing::info!("Connecting to MCP server '{}': {}", name, url);
+
+        let http = crate::http::builder()
+            .build()
+            .context("Failed to build HTTP client")?;
+        let events = http
+            .get(url.clone())
+            .headers(headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to MCP server '{}'", name))?
+            .error_for_status()
+            .with_context(|| format!("MCP server '{}' refused the event stream", name))?;
+
+        let pending = Pending::new();
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let reader = spawn_sse_reader(name.clone(), events, pending.clone(), endpoint_tx);
+
+        let endpoint = match tokio::time::timeout(ENDPOINT_TIMEOUT, endpoint_rx).await {
+            Ok(Ok(endpoint)) => endpoint,
+            Ok(Err(_)) => {
+                anyhow::bail!("MCP server '{}' closed the event stream", name)
+            }
+            Err(_) => {
+                reader.abort();
+                anyhow::bail!("MCP server '{}' sent no endpoint event", name)
+            }
+        };
+        let endpoint = url
+            .join(&endpoint)
+            .with_context(|| format!("Invalid endpoint from MCP server '{}'", name))?;
+
+        Ok(Self::new(
+            name,
+            config,
+            Outbound::Sse {
+                http,
+                endpoint,
+                headers,
+            },
+            pending,
+            reader,
+        ))
+    }
 
-        let mut conn = Self {
-            name: name.clone(),
+    fn new(
+        name: String,
+        config: &McpServerConfig,
+        outbound: Outbound,
+        pending: Pending,
+        reader: JoinHandle<()>,
+    ) -> Self {
+        Self {
+            name,
             config: config.clone(),
             tools: Vec::new(),
+            resources: Vec::new(),
             server_info: None,
+            capabilities: Value::Null,
             is_connected: false,
-            child: Some(child),
-            stdin: Some(stdin),
-            stdout: Some(stdout),
+            child: None,
+            outbound,
+            pending,
+            reader,
             next_id: Arc::new(AtomicU64::new(1)),
-        };
-
-        // Initialize the connection
-        conn.initialize().await?;
-
-        // Discover available tools
-        conn.refresh_tools().await?;
-
-        conn.is_connected = true;
-        tracing::info!(
-            "Connected to MCP server '{}' with {} tools",
-            name,
-            conn.tools.len()
-        );
-
-        Ok(conn)
+        }
     }
 
     /// Initialize the MCP connection
@@ -194,6 +366,7 @@ impl McpConnection {
         if let Some(server_info_val) = response.get("serverInfo") {
             self.server_info = serde_json::from_value(server_info_val.clone()).ok();
         }
+        self.capabilities = response.get("capabilities").cloned().unwrap_or_default();
 
         // Send initialized notification
         self.send_notification("notifications/initialized", None)
@@ -207,15 +380,17 @@ impl McpConnection {
         &self.tools
     }
 
+    /// Get the list of available resources
+    pub fn list_resources(&self) -> &[McpResource] {
+        &self.resources
+    }
+
     /// Refresh the list of available tools
     pub async fn refresh_tools(&mut self) -> Result<()> {
-        let response = self.send_request("tools/list", None).await?;
-
-        // Parse tools array
-        if let Some(tools_val) = response.get("tools") {
-            self.tools =
-                serde_json::from_value(tools_val.clone()).context("Failed to parse tools list")?;
-        }
+        self.tools = self
+            .list_paged("tools/list", "tools")
+            .await
+            .context("Failed to parse tools list")?;
 
         tracing::debug!(
             "Discovered {} tools from MCP server '{}'",
@@ -226,6 +401,39 @@ impl McpConnection {
         Ok(())
     }
 
+    /// Refresh the list of available resources
+    pub async fn refresh_resources(&mut self) -> Result<()> {
+        self.resources = self
+            .list_paged("resources/list", "resources")
+            .await
+            .context("Failed to parse resources list")?;
+
+        tracing::debug!(
+            "Discovered {} resources from MCP server '{}'",
+            self.resources.len(),
+            self.name
+        );
+
+        Ok(())
+    }
+
+    /// Every page of a `*/list` method
+    async fn list_paged<T: DeserializeOwned>(&self, method: &str, key: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = cursor.map(|c| serde_json::json!({ "cursor": c }));
+            let response = self.send_request(method, params).await?;
+            if let Some(page) = response.get(key) {
+                items.extend(serde_json::from_value::<Vec<T>>(page.clone())?);
+            }
+            cursor = match response.get("nextCursor").and_then(|c| c.as_str()) {
+                Some(next) => Some(next.to_string()),
+                None => return Ok(items),
+            };
+        }
+    }
+
     /// Call a tool on this server
     pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<String> {
         let response = self
@@ -239,34 +447,38 @@ impl McpConnection {
             .await?;
 
         // Extract content from response
-        if let Some(content) = response.get("content") {
-            if let Some(arr) = content.as_array() {
-                // Concatenate all text content
-                let mut result = String::new();
-                for item in arr {
-                    if let Some(text) = item.get("text") {
-                        if let Some(text_str) = text.as_str() {
-                            if !result.is_empty() {
-                                result.push('\n');
-                            }
-                            result.push_str(text_str);
-                        }
-                    }
-                }
-                return Ok(result);
+        if let Some(arr) = response.get("content").and_then(|c| c.as_array()) {
+            let result = content_text(arr);
+            // The tool ran but failed; report it as an error
+            if response.get("isError").and_then(|e| e.as_bool()) == Some(true) {
+                anyhow::bail!("{}", result);
             }
+            return Ok(result);
         }
 
         // Fallback: return entire response as JSON
         Ok(serde_json::to_string_pretty(&response)?)
     }
 
+    /// Read a resource from this server
+    pub async fn read_resource(&self, uri: &str) -> Result<String> {
+        let response = self
+            .send_request("resources/read", Some(serde_json::json!({ "uri": uri })))
+            .await?;
+        let contents = response
+            .get("contents")
+            .and_then(|c| c.as_array())
+            .context("No contents in resources/read response")?;
+        Ok(content_text(contents))
+    }
+
     /// Send a JSON-RPC request and get response
     async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value> {
-        let stdin = self.stdin.as_ref().context("No stdin available")?;
-        let stdout = self.stdout.as_ref().context("No stdout available")?;
-
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (response_tx, response_rx) = oneshot::channel();
+        if !self.pending.insert(id, response_tx) {
+            anyhow::bail!("MCP server '{}' closed the connection", self.name);
+        }
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -274,29 +486,24 @@ impl McpConnection {
             method: method.to_string(),
             params,
         };
-
-        let request_json = serde_json::to_string(&request)?;
-        tracing::debug!("MCP request: {}", request_json);
-
-        // Write request
-        {
-            let mut stdin_guard = stdin.lock().await;
-            stdin_guard.write_all(request_json.as_bytes()).await?;
-            stdin_guard.write_all(b"\n").await?;
-            stdin_guard.flush().await?;
-        }
-
-        // Read response
-        let mut line = String::new();
-        {
-            let mut stdout_guard = stdout.lock().await;
-            stdout_guard.read_line(&mut line).await?;
+        if let Err(e) = self.send(&serde_json::to_string(&request)?).await {
+            self.pending.remove(id);
+            return Err(e);
         }
 
-        tracing::debug!("MCP response: {}", line.trim());
-
-        let response: JsonRpcResponse =
-            serde_json::from_str(&line).context("Failed to parse JSON-RPC response")?;
+        let response = match tokio::time::timeout(REQUEST_TIMEOUT, response_rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => anyhow::bail!("MCP server '{}' closed the connection", self.name),
+            Err(_) => {
+                self.pending.remove(id);
+                anyhow::bail!(
+                    "MCP server '{}' did not answer {} within {}s",
+                    self.name,
+                    method,
+                    REQUEST_TIMEOUT.as_secs()
+                )
+            }
+        };
 
         // Check for errors
         if let Some(error) = response.error {
@@ -313,22 +520,41 @@ impl McpConnection {
 
     /// Send a JSON-RPC notification (no response expected)
     async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<()> {
-        let stdin = self.stdin.as_ref().context("No stdin available")?;
-
         let notification = serde_json::json!({
             "jsonrpc": "2.0",
             "method": method,
             "params": params
         });
+        self.send(&serde_json::to_string(&notification)?).await
+    }
 
-        let notification_json = serde_json::to_string(&notification)?;
-        tracing::debug!("MCP notification: {}", notification_json);
-
-        let mut stdin_guard = stdin.lock().await;
-        stdin_guard.write_all(notification_json.as_bytes()).await?;
-        stdin_guard.write_all(b"\n").await?;
-        stdin_guard.flush().await?;
+    /// Write one message to the server
+    async fn send(&self, message: &str) -> Result<()> {
+        tracing::debug!("MCP message to '{}': {}", self.name, message);
 
+        match &self.outbound {
+            Outbound::Stdio(stdin) => {
+                let mut stdin = stdin.lock().await;
+                stdin.write_all(message.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+                stdin.flush().await?;
+            }
+            Outbound::Sse {
+                http,
+                endpoint,
+                headers,
+            } => {
+                http.post(endpoint.clone())
+                    .headers(headers.clone())
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(message.to_string())
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to reach MCP server '{}'", self.name))?
+                    .error_for_status()
+                    .with_context(|| format!("MCP server '{}' rejected a message", self.name))?;
+            }
+        }
         Ok(())
     }
 
@@ -352,6 +578,7 @@ impl McpConnection {
         tracing::debug!("Shutting down MCP connection '{}'", self.name);
 
         self.is_connected = false;
+        self.reader.abort();
 
         // Kill the child process
         if let Some(mut child) = self.child.take() {
@@ -366,6 +593,7 @@ impl Drop for McpConnection {
     fn drop(&mut self) {
         tracing::debug!("Dropping MCP connection '{}'", self.name);
 
+        self.reader.abort();
         // Try to kill the child process if it's still running
         if let Some(mut child) = self.child.take() {
             let _ = child.start_kill();
@@ -373,6 +601,163 @@ impl Drop for McpConnection {
     }
 }
 
+/// Read newline-delimited messages until the server closes its output
+fn spawn_line_reader(
+    name: String,
+    reader: impl AsyncBufRead + Send + Unpin + 'static,
+    pending: Pending,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = reader.lines();
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => dispatch(&name, &pending, &line),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!("Failed to read from MCP server '{}': {}", name, e);
+                    break;
+                }
+            }
+        }
+        pending.close();
+    })
+}
+
+/// Read the event stream: the `endpoint` event, then `message` events
+fn spawn_sse_reader(
+    name: String,
+    events: reqwest::Response,
+    pending: Pending,
+    endpoint_tx: oneshot::Sender<String>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut stream = events.bytes_stream();
+        let mut parser = SseParser::default();
+        let mut endpoint_tx = Some(endpoint_tx);
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    tracing::warn!("MCP server '{}' event stream failed: {}", name, e);
+                    break;
+                }
+            };
+            for event in parser.feed(&chunk) {
+                match event.event.as_str() {
+                    "endpoint" => {
+                        if let Some(tx) = endpoint_tx.take() {
+                            let _ = tx.send(event.data);
+                        }
+                    }
+                    "message" => dispatch(&name, &pending, &event.data),
+                    other => {
+                        tracing::debug!("Ignoring '{}' event from MCP server '{}'", other, name)
+                    }
+                }
+            }
+        }
+        pending.close();
+    })
+}
+
+/// Hand a response to the request waiting for it; anything else (server
+/// notifications and requests) is logged and dropped
+fn dispatch(name: &str, pending: &Pending, message: &str) {
+    let message = message.trim();
+    if message.is_empty() {
+        return;
+    }
+    tracing::debug!("MCP message from '{}': {}", name, message);
+    let response = match serde_json::from_str::<JsonRpcResponse>(message) {
+        Ok(response) if response.method.is_none() => response,
+        _ => return,
+    };
+    if let Some(tx) = pending.remove(response.id) {
+        let _ = tx.send(response);
+    }
+}
+
+/// Text of MCP content items; binary items are described, not inlined
+fn content_text(items: &[Value]) -> String {
+    items
+        .iter()
+        .map(|item| match item.get("text").and_then(|t| t.as_str()) {
+            Some(text) => text.to_string(),
+            None => format!(
+                "[{} content omitted]",
+                item.get("mimeType")
+                    .or_else(|| item.get("type"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("binary")
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn header_map(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            Ok((
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            ))
+        })
+        .collect()
+}
+
+/// One server-sent event
+#[derive(Debug, PartialEq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental server-sent events parser (chunks may split lines)
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Add a chunk; returns the events it completed
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            // A blank line ends the event
+            if line.is_empty() {
+                let event = self.event.take();
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: event.unwrap_or_else(|| "message".to_string()),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue; // Comment / keep-alive
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,10 +810,96 @@ mod tests {
             args: vec![],
             env: HashMap::new(),
             url: None,
+            headers: HashMap::new(),
             enabled: true,
         };
 
         let result = McpConnection::connect("test".to_string(), &config).await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser
+            .feed(b": keep-alive\n\nevent: endpoint\ndata: /messages?session=1")
+            .is_empty());
+        assert_eq!(
+            parser.feed(b"\r\n\r\ndata: {\"id\":1}\n\n"),
+            vec![
+                SseEvent {
+                    event: "endpoint".to_string(),
+                    data: "/messages?session=1".to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "{\"id\":1}".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_responses_reach_their_requests() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (server_read, mut server_write) = tokio::io::split(server);
+        let config = McpServerConfig {
+            transport: TransportType::Stdio,
+            command: Some("fake".to_string()),
+            args: vec![],
+            env: HashMap::new(),
+            url: None,
+            headers: HashMap::new(),
+            enabled: true,
+        };
+        let conn = Arc::new(McpConnection::from_streams(
+            "fake".to_string(),
+            &config,
+            BufReader::new(client_read),
+            client_write,
+        ));
+
+        let first = tokio::spawn({
+            let conn = Arc::clone(&conn);
+            async move { conn.call_tool("echo", serde_json::json!({})).await }
+        });
+        let second = tokio::spawn({
+            let conn = Arc::clone(&conn);
+            async move { conn.read_resource("file:///notes.txt").await }
+        });
+
+        // Read both requests, then answer the second first with a
+        // notification in between
+        let mut requests = BufReader::new(server_read).lines();
+        let mut ids = HashMap::new();
+        for _ in 0..2 {
+            let line = requests.next_line().await.unwrap().unwrap();
+            let request: Value = serde_json::from_str(&line).unwrap();
+            ids.insert(
+                request["method"].as_str().unwrap().to_string(),
+                request["id"].as_u64().unwrap(),
+            );
+        }
+        let replies = format!(
+            "{}\n{}\n{}\n",
+            serde_json::json!({"jsonrpc": "2.0", "id": ids["resources/read"], "result": {
+                "contents": [{"uri": "file:///notes.txt", "text": "notes"}]
+            }}),
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/progress"}),
+            serde_json::json!({"jsonrpc": "2.0", "id": ids["tools/call"], "result": {
+                "content": [{"type": "text", "text": "no such file"}], "isError": true
+            }}),
+        );
+        server_write.write_all(replies.as_bytes()).await.unwrap();
+
+        assert_eq!(second.await.unwrap().unwrap(), "notes");
+        let error = first.await.unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "no such file");
+
+        // The server going away fails requests instead of hanging them
+        drop(server_write);
+        drop(requests);
+        assert!(conn.call_tool("echo", serde_json::json!({})).await.is_err());
+    }
 }
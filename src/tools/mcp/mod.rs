@@ -1,27 +1,37 @@
 // MCP (Model Context Protocol) integration
 //
-// Enables Shammah to connect to external MCP servers and use their tools.
+// Enables Shammah to connect to external MCP servers and use their tools
+// and resources.
 //
 // Architecture:
 // - McpClient: Manages multiple server connections
 // - McpConnection: Wraps a single MCP server connection
 // - McpServerConfig: Configuration for MCP servers
+// - McpServerTool / McpResourceTool: `Tool`s registered in the ToolRegistry,
+//   so MCP calls get the same permission checks and approvals as built-ins
+// - McpServer: the other direction, serving finch's own tools to MCP clients
+//   (`finch mcp serve`)
 //
 // Supported transports:
 // - STDIO: Launch local server processes (e.g., npx @modelcontextprotocol/server-*)
-// - SSE: Connect to remote HTTP+SSE servers (future)
+// - SSE: Connect to remote HTTP+SSE servers
 //
 // Usage:
 // ```rust
-// let mcp_client = McpClient::from_config(&config.mcp_servers).await?;
-// let tools = mcp_client.list_tools().await;
-// let result = mcp_client.execute_tool("mcp_filesystem_read_file", params).await?;
+// let mcp_client = Arc::new(McpClient::from_config(&config.mcp_servers).await?);
+// for tool in registry_tools(&mcp_client).await {
+//     registry.register(tool);
+// }
 // ```
 
 pub mod client;
 pub mod config;
 pub mod connection;
+pub mod server;
+pub mod tool;
 
 pub use client::McpClient;
 pub use config::{McpServerConfig, TransportType};
-pub use connection::McpConnection;
+pub use connection::{McpConnection, McpResource};
+pub use server::{default_registry, McpServer};
+pub use tool::{registry_tools, McpResourceTool, McpServerTool};
@@ -0,0 +1,399 @@
+// MCP server mode
+//
+// `finch mcp serve` speaks MCP (JSON-RPC 2.0, one message per line) on
+// stdin/stdout so Claude Desktop and other MCP clients can use finch's local
+// tools and memory.  Tools are ordinary registry `Tool`s and behave exactly as
+// they do in the REPL; approving each call is up to the client.  File tools
+// stay inside the project and its allowed directories, as the path sandbox
+// keeps them in the REPL.
+//
+// Requests are handled one at a time, in the order they arrive.
+
+use crate::memory::MemorySystem;
+use crate::tools::implementations::{
+    BashTool, GlobTool, GrepTool, OutlineTool, ReadTool, SearchMemoryTool,
+};
+use crate::tools::path_sandbox::PathSandbox;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::types::{ToolContext, ToolUse};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Protocol version offered when the client asks for one we don't know
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Protocol versions whose tool messages this server understands
+const SUPPORTED_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// The tools `finch mcp serve` exposes: read, grep, glob and outline, plus bash when
+/// allowed and search_memory when a memory system is available
+pub fn default_registry(memory: Option<Arc<MemorySystem>>, allow_bash: bool) -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+    registry.register(Box::new(ReadTool));
+    registry.register(Box::new(GrepTool));
+    registry.register(Box::new(GlobTool));
+    registry.register(Box::new(OutlineTool));
+    if allow_bash {
+        registry.register(Box::new(BashTool::new()));
+    }
+    if let Some(memory) = memory {
+        registry.register(Box::new(SearchMemoryTool::new(memory)));
+    }
+    registry
+}
+
+/// Serves the tools of a `ToolRegistry` to an MCP client
+pub struct McpServer {
+    registry: ToolRegistry,
+    path_sandbox: PathSandbox,
+}
+
+impl McpServer {
+    pub fn new(registry: ToolRegistry) -> Self {
+        Self {
+            registry,
+            path_sandbox: crate::tools::path_sandbox::active().clone(),
+        }
+    }
+
+    /// Use `sandbox` instead of the one loaded for the working directory
+    pub fn with_path_sandbox(mut self, sandbox: PathSandbox) -> Self {
+        self.path_sandbox = sandbox;
+        self
+    }
+
+    /// Names of the served tools, sorted
+    pub fn tool_names(&self) -> Vec<String> {
+        let mut names = self.registry.tool_names();
+        names.sort();
+        names
+    }
+
+    /// Serve on this process's stdin/stdout until the client closes stdin
+    pub async fn serve_stdio(&self) -> Result<()> {
+        self.serve(BufReader::new(tokio::io::stdin()), tokio::io::stdout())
+            .await
+    }
+
+    /// Serve newline-delimited JSON-RPC from `reader` until it reaches EOF
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(message).await,
+                Err(e) => Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Parse error: {}", e),
+                )),
+            };
+            if let Some(response) = response {
+                let mut out = serde_json::to_string(&response)?;
+                out.push('\n');
+                writer.write_all(out.as_bytes()).await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answer one message; notifications and stray responses get no reply
+    async fn handle_message(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid request: missing method".to_string(),
+            ));
+        };
+        let Some(id) = id else {
+            tracing::debug!("[mcp serve] notification: {}", method);
+            return None;
+        };
+
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn list_tools(&self) -> Value {
+        let mut definitions = self.registry.definitions();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        let tools: Vec<Value> = definitions
+            .into_iter()
+            .map(|d| {
+                json!({
+                    "name": d.name,
+                    "description": d.description,
+                    "inputSchema": d.input_schema,
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    /// Run a tool.  A failing tool is a successful call with `isError` set,
+    /// so the client's model can read the error; unknown tools are protocol
+    /// errors.
+    async fn call_tool(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let name = params["name"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let tool = self
+            .registry
+            .get(name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {}", name)))?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        let context = ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        };
+        tracing::info!("[mcp serve] tools/call {}", name);
+        let tool_use = ToolUse::new(name.to_string(), arguments);
+        let (text, is_error) = if let Err(denial) = self.path_sandbox.check(&tool_use) {
+            tracing::warn!(
+                "[mcp serve] {} refused by the path sandbox: {} ({})",
+                name,
+                denial.path,
+                denial.reason
+            );
+            (denial.to_error(), true)
+        } else {
+            match tool.execute_call(&tool_use, &context).await {
+                Ok(output) => (output, false),
+                Err(e) => (format!("{:#}", e), true),
+            }
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let version = params["protocolVersion"]
+        .as_str()
+        .filter(|v| SUPPORTED_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "finch", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::registry::Tool;
+    use crate::tools::types::ToolInputSchema;
+    use async_trait::async_trait;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text back"
+        }
+
+        fn input_schema(&self) -> ToolInputSchema {
+            ToolInputSchema::simple(vec![("text", "Text to echo")])
+        }
+
+        async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+            match input["text"].as_str() {
+                Some(text) => Ok(text.to_string()),
+                None => anyhow::bail!("Missing text parameter"),
+            }
+        }
+    }
+
+    /// Feed raw `input` to a server with the echo tool and collect its replies
+    async fn exchange_raw(input: &str) -> Vec<Value> {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+        let server = McpServer::new(registry);
+
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    async fn exchange(messages: &[Value]) -> Vec<Value> {
+        let input: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+        exchange_raw(&input).await
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let replies = exchange(&[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                   "params": {"protocolVersion": "2025-03-26", "capabilities": {}}}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": "two", "method": "tools/list"}),
+        ])
+        .await;
+
+        // The notification gets no reply
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(replies[0]["result"]["serverInfo"]["name"], "finch");
+        assert_eq!(replies[1]["id"], "two");
+        let tools = replies[1]["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert_eq!(tools[0]["inputSchema"]["type"], "object");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_protocol_version_falls_back() {
+        let replies = exchange(&[json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                   "params": {"protocolVersion": "1999-01-01"}})])
+        .await;
+        assert_eq!(replies[0]["result"]["protocolVersion"], PROTOCOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool() {
+        let replies = exchange(&[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                   "params": {"name": "echo", "arguments": {"text": "hello"}}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                   "params": {"name": "echo", "arguments": {}}}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                   "params": {"name": "nope"}}),
+        ])
+        .await;
+
+        assert_eq!(replies[0]["result"]["content"][0]["text"], "hello");
+        assert_eq!(replies[0]["result"]["isError"], false);
+        // A failing tool is reported to the model, not as a protocol error
+        assert_eq!(replies[1]["result"]["isError"], true);
+        assert!(replies[1]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Missing text"));
+        assert_eq!(replies[2]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors() {
+        let replies = exchange_raw(
+            "not json\n\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"resources/list\"}\n",
+        )
+        .await;
+
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["id"], Value::Null);
+        assert_eq!(replies[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(replies[1]["id"], 7);
+        assert_eq!(replies[1]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_read_outside_project_is_refused() {
+        let project = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let inside_file = project.path().join("notes.txt");
+        let outside_file = outside.path().join("secret.txt");
+        std::fs::write(&inside_file, "inside").unwrap();
+        std::fs::write(&outside_file, "outside").unwrap();
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(ReadTool));
+        let sandbox = PathSandbox::new(
+            &crate::tools::path_sandbox::FilePermissions::default(),
+            project.path(),
+        );
+        let server = McpServer::new(registry).with_path_sandbox(sandbox);
+        let call = |id: i64, path: &std::path::Path| {
+            format!(
+                "{}\n",
+                json!({"jsonrpc": "2.0", "id": id, "method": "tools/call",
+                       "params": {"name": "read", "arguments": {"file_path": path}}})
+            )
+        };
+        let input = call(1, &inside_file) + &call(2, &outside_file);
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+        let replies: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(replies[0]["result"]["isError"], false);
+        let text = replies[0]["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("inside"), "{}", text);
+        assert_eq!(replies[1]["result"]["isError"], true);
+        let text = replies[1]["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("path_outside_sandbox"), "{}", text);
+    }
+
+    #[test]
+    fn test_default_registry_bash_is_optional() {
+        let names = McpServer::new(default_registry(None, false)).tool_names();
+        assert_eq!(names, vec!["glob", "grep", "outline", "read"]);
+        let names = McpServer::new(default_registry(None, true)).tool_names();
+        assert!(names.contains(&"bash".to_string()));
+    }
+}
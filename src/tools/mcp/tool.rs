@@ -0,0 +1,269 @@
+// MCP tools in the ToolRegistry
+//
+// Every tool a connected server offers is registered as `mcp_<server>_<tool>`,
+// and every server with resources gets `mcp_<server>_read_resource`.  Being
+// ordinary `Tool`s, they go through the same permission rules, approval
+// prompts, plan-mode restrictions and result budget as the built-in tools.
+
+use super::client::{tool_definition, tool_name, McpClient};
+use crate::tools::registry::Tool;
+use crate::tools::types::{ToolContext, ToolInputSchema};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Resources listed by name in a read_resource tool's description
+const MAX_LISTED_RESOURCES: usize = 50;
+
+/// A tool served by an MCP server
+pub struct McpServerTool {
+    /// Registered name: `mcp_<server>_<tool>`
+    name: String,
+    server: String,
+    tool: String,
+    description: String,
+    input_schema: ToolInputSchema,
+    client: Arc<McpClient>,
+}
+
+#[async_trait]
+impl Tool for McpServerTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        self.input_schema.clone()
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        self.client.call_tool(&self.server, &self.tool, input).await
+    }
+}
+
+/// Reads the resources of one MCP server
+pub struct McpResourceTool {
+    /// Registered name: `mcp_<server>_read_resource`
+    name: String,
+    server: String,
+    description: String,
+    client: Arc<McpClient>,
+}
+
+#[async_trait]
+impl Tool for McpResourceTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> ToolInputSchema {
+        ToolInputSchema::simple(vec![("uri", "URI of the resource to read")])
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+        let uri = input["uri"].as_str().context("Missing uri parameter")?;
+        self.client.read_resource(&self.server, uri).await
+    }
+}
+
+/// Tools to register for everything `client`'s servers offer
+pub async fn registry_tools(client: &Arc<McpClient>) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for (server, tool) in client.server_tools().await {
+        let definition = tool_definition(&server, &tool);
+        tools.push(Box::new(McpServerTool {
+            name: definition.name,
+            server,
+            tool: tool.name,
+            description: definition.description,
+            input_schema: definition.input_schema,
+            client: Arc::clone(client),
+        }));
+    }
+
+    let mut resources: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (server, resource) in client.list_resources().await {
+        let mut line = format!("- {} ({})", resource.uri, resource.name);
+        if let Some(description) = &resource.description {
+            line.push_str(&format!(": {}", description));
+        }
+        resources.entry(server).or_default().push(line);
+    }
+    for (server, lines) in resources {
+        let name = tool_name(&server, "read_resource");
+        // A server tool of the same name takes precedence
+        if tools.iter().any(|t| t.name() == name) {
+            continue;
+        }
+        let mut description = format!(
+            "Read a resource (file, document or record) from the '{}' MCP server. Resources:\n{}",
+            server,
+            lines
+                .iter()
+                .take(MAX_LISTED_RESOURCES)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        if lines.len() > MAX_LISTED_RESOURCES {
+            description.push_str(&format!(
+                "\n...and {} more",
+                lines.len() - MAX_LISTED_RESOURCES
+            ));
+        }
+        tools.push(Box::new(McpResourceTool {
+            name,
+            server,
+            description,
+            client: Arc::clone(client),
+        }));
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::mcp::{McpConnection, McpServerConfig, TransportType};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    /// Answer requests like a server with one tool and two resources
+    fn reply(method: &str, params: &Value) -> Value {
+        match method {
+            "initialize" => json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {"tools": {}, "resources": {}},
+                "serverInfo": {"name": "fake", "version": "1.0"}
+            }),
+            "tools/list" => json!({"tools": [{
+                "name": "echo",
+                "description": "Echo the text back",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {"text": {"type": "string"}},
+                    "required": ["text"]
+                }
+            }]}),
+            "resources/list" => json!({"resources": [
+                {"uri": "file:///a.txt", "name": "a", "description": "First file"},
+                {"uri": "file:///b.txt", "name": "b"}
+            ]}),
+            "tools/call" => json!({"content": [
+                {"type": "text", "text": params["arguments"]["text"]}
+            ]}),
+            "resources/read" => json!({"contents": [
+                {"uri": params["uri"], "text": format!("contents of {}", params["uri"].as_str().unwrap())}
+            ]}),
+            other => panic!("unexpected method {}", other),
+        }
+    }
+
+    async fn fake_client() -> Arc<McpClient> {
+        let (client, server) = tokio::io::duplex(4096);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (server_read, mut server_write) = tokio::io::split(server);
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(server_read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                // Notifications get no reply
+                let Some(id) = request.get("id") else {
+                    continue;
+                };
+                let result = reply(request["method"].as_str().unwrap(), &request["params"]);
+                let response = json!({"jsonrpc": "2.0", "id": id, "result": result});
+                let message = format!("{}\n", response);
+                if server_write.write_all(message.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let config = McpServerConfig {
+            transport: TransportType::Stdio,
+            command: Some("fake".to_string()),
+            args: vec![],
+            env: HashMap::new(),
+            url: None,
+            headers: HashMap::new(),
+            enabled: true,
+        };
+        let conn = McpConnection::from_streams(
+            "fake".to_string(),
+            &config,
+            BufReader::new(client_read),
+            client_write,
+        )
+        .start()
+        .await
+        .unwrap();
+
+        let client = Arc::new(McpClient::new());
+        client.add_connection(conn).await;
+        client
+    }
+
+    #[tokio::test]
+    async fn test_registry_tools_cover_tools_and_resources() {
+        let client = fake_client().await;
+        let tools = registry_tools(&client).await;
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["mcp_fake_echo", "mcp_fake_read_resource"]);
+
+        let echo = &tools[0];
+        assert_eq!(echo.description(), "Echo the text back");
+        assert_eq!(echo.input_schema().required, vec!["text".to_string()]);
+
+        let read = &tools[1];
+        assert!(read
+            .description()
+            .contains("- file:///a.txt (a): First file"));
+        assert!(read.description().contains("- file:///b.txt (b)"));
+    }
+
+    #[tokio::test]
+    async fn test_registry_tools_execute_through_the_server() {
+        let client = fake_client().await;
+        let tools = registry_tools(&client).await;
+        let context = ToolContext {
+            conversation: None,
+            save_models: None,
+            batch_trainer: None,
+            local_generator: None,
+            tokenizer: None,
+            repl_mode: None,
+            plan_content: None,
+            live_output: None,
+            stack: None,
+            poset: None,
+        };
+
+        let output = tools[0]
+            .execute(json!({"text": "hello"}), &context)
+            .await
+            .unwrap();
+        assert_eq!(output, "hello");
+        let output = tools[1]
+            .execute(json!({"uri": "file:///a.txt"}), &context)
+            .await
+            .unwrap();
+        assert_eq!(output, "contents of file:///a.txt");
+        assert!(tools[1].execute(json!({}), &context).await.is_err());
+        assert!(client
+            .call_tool("missing", "echo", json!({}))
+            .await
+            .is_err());
+    }
+}
@@ -3,19 +3,40 @@
 // Enables Shammah to execute tools (WebFetch, Bash, Read, etc.) locally
 // instead of only generating text responses.
 
+pub mod audit;
+pub mod change_preview;
+pub mod edit_journal;
+pub mod exec_plugins;
 pub mod executor;
 pub mod implementations;
 pub mod mcp;
+pub mod parallel;
+pub mod path_sandbox;
 pub mod pattern_matcher;
 pub mod patterns;
 pub mod permissions;
 pub mod registry;
+pub mod rename_check;
+pub mod result_budget;
+pub mod result_cache;
+pub mod sandbox;
+#[cfg(unix)]
+pub mod shell;
 pub mod todo;
 pub mod types;
+pub mod wasm_plugins;
 
-pub use executor::{generate_tool_signature, ApprovalSource, ToolExecutor, ToolSignature};
+pub use edit_journal::{EditJournal, JournalEntry, Rollback};
+pub use executor::{
+    generate_tool_signature, ApprovalSource, ToolExecutor, ToolRunner, ToolSignature,
+};
+pub use parallel::ToolGate;
+pub use path_sandbox::PathSandbox;
 pub use pattern_matcher::ToolPatternMatcher;
 pub use patterns::{ExactApproval, MatchType, PersistentPatternStore, ToolPattern};
 pub use permissions::{PermissionCheck, PermissionManager, PermissionRule};
 pub use registry::{Tool, ToolRegistry};
+pub use rename_check::RenameCheck;
+pub use result_budget::ToolResultBudget;
+pub use result_cache::ResultCache;
 pub use types::{ContentBlock, ToolDefinition, ToolInputSchema, ToolResult, ToolUse};
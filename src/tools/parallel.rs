@@ -0,0 +1,139 @@
+// Concurrent execution of independent tool calls
+//
+// A response often asks for several lookups at once (three reads and a grep).
+// Tools that only look run side by side, up to a limit; a tool that changes
+// something waits for the calls queued before it and holds back the ones
+// queued after it, so a batch ends in the same state as a sequential run.
+
+use std::sync::Arc;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, OwnedSemaphorePermit};
+use tokio::sync::{RwLock, Semaphore};
+
+/// Whether a tool can run alongside other calls: it reads files, the web or
+/// memory, and never writes.  Executable plugins say so with `category = "read"`.
+pub fn is_parallel_safe(tool_name: &str) -> bool {
+    crate::tools::exec_plugins::is_read_only(tool_name)
+        || matches!(
+            tool_name,
+            "read"
+                | "Read"
+                | "glob"
+                | "Glob"
+                | "grep"
+                | "Grep"
+                | "outline"
+                | "web_fetch"
+                | "WebFetch"
+                | "web_search"
+                | "doc_lookup"
+                | "search_memory"
+                | "list_recent_memories"
+        )
+}
+
+/// Admits tool calls in the order they arrive
+///
+/// Clones share the same queue, so one gate covers every call of an executor.
+#[derive(Clone)]
+pub struct ToolGate {
+    // Fair lock: a queued writer blocks readers that arrive after it
+    order: Arc<RwLock<()>>,
+    slots: Arc<Semaphore>,
+    limit: usize,
+}
+
+/// Held while a tool runs; dropping it admits the next call
+pub struct ToolPermit {
+    _order: OrderGuard,
+    _slot: OwnedSemaphorePermit,
+}
+
+enum OrderGuard {
+    Shared(OwnedRwLockReadGuard<()>),
+    Exclusive(OwnedRwLockWriteGuard<()>),
+}
+
+impl ToolGate {
+    /// Gate running at most `limit` calls at once (0 is treated as 1, which
+    /// runs every call on its own)
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            order: Arc::new(RwLock::new(())),
+            slots: Arc::new(Semaphore::new(limit)),
+            limit,
+        }
+    }
+
+    /// Most calls admitted at once
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Wait until `tool_name` may run
+    pub async fn enter(&self, tool_name: &str) -> ToolPermit {
+        let order = if is_parallel_safe(tool_name) {
+            OrderGuard::Shared(Arc::clone(&self.order).read_owned().await)
+        } else {
+            OrderGuard::Exclusive(Arc::clone(&self.order).write_owned().await)
+        };
+        let slot = Arc::clone(&self.slots)
+            .acquire_owned()
+            .await
+            .expect("tool gate semaphore is never closed");
+        ToolPermit {
+            _order: order,
+            _slot: slot,
+        }
+    }
+}
+
+impl Default for ToolGate {
+    fn default() -> Self {
+        Self::new(crate::config::constants::DEFAULT_MAX_PARALLEL_TOOLS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    async fn run(gate: &ToolGate, name: &str, running: &AtomicUsize, peak: &AtomicUsize) {
+        let _permit = gate.enter(name).await;
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        running.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_calls_share_up_to_the_limit() {
+        let gate = ToolGate::new(2);
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        futures::future::join_all(
+            ["read", "grep", "glob", "read"].map(|name| run(&gate, name, &running, &peak)),
+        )
+        .await;
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_writing_calls_run_alone() {
+        let gate = ToolGate::new(4);
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        futures::future::join_all(
+            ["read", "bash", "read", "edit"].map(|name| run(&gate, name, &running, &peak)),
+        )
+        .await;
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+
+        // A limit of 1 runs even read-only calls one at a time
+        let gate = ToolGate::new(0);
+        peak.store(0, Ordering::SeqCst);
+        futures::future::join_all(["read", "read"].map(|name| run(&gate, name, &running, &peak)))
+            .await;
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+}
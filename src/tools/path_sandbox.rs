@@ -0,0 +1,532 @@
+// Path sandbox for the file tools
+//
+// read, glob, grep, outline and hash_compare may only look under the project
+// directory, and write, edit and patch may only change files there, unless
+// the permissions file (`<project>/.finch/permissions.toml`, else
+// `~/.finch/permissions.toml`, as for the bash sandbox) allows more:
+//
+//   [files]
+//   sandbox = true                          # default; false lets file tools go anywhere
+//   allow = ["~/notes"]                     # readable and writable, like the project
+//   allow_read = ["~/.cargo/registry/src"]  # readable only
+//
+// A path is checked where it really leads: relative paths are taken from the
+// project directory and symlinks are followed, so a link inside the project
+// pointing outside it is refused like the outside path itself.  A path that
+// can't be resolved safely (`..` below a directory that doesn't exist, a
+// dangling symlink) is refused too.
+//
+// A refused call never runs.  The model gets a JSON error naming the path,
+// where it leads and the directories it may use, so it can pick another path
+// or ask the user instead of retrying.  An invalid permissions file refuses
+// every file tool call rather than leaving the file tools unchecked.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::config::expand_home;
+use crate::tools::sandbox::permissions_file;
+use crate::tools::types::ToolUse;
+
+/// `[files]` in the permissions file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilePermissions {
+    /// Keep file tools inside the project and the allowed directories
+    #[serde(default = "default_sandbox")]
+    pub sandbox: bool,
+    /// Directories outside the project the file tools may read and change
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Directories outside the project the file tools may only read
+    #[serde(default)]
+    pub allow_read: Vec<String>,
+}
+
+impl Default for FilePermissions {
+    fn default() -> Self {
+        Self {
+            sandbox: default_sandbox(),
+            allow: Vec::new(),
+            allow_read: Vec::new(),
+        }
+    }
+}
+
+fn default_sandbox() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PermissionsFile {
+    #[serde(default)]
+    files: FilePermissions,
+}
+
+/// What a tool does with a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Where the file tools may go
+#[derive(Debug, Clone)]
+pub enum PathSandbox {
+    /// Anywhere (`sandbox = false`)
+    Off,
+    Scoped(Scope),
+    /// The permissions file can't be used; every file tool call is refused
+    Unusable(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    /// Relative paths are taken from here
+    base: PathBuf,
+    /// The project first, then `allow`
+    writable: Vec<Root>,
+    readable: Vec<Root>,
+}
+
+/// An allowed directory, as written and with its symlinks resolved
+#[derive(Debug, Clone)]
+struct Root {
+    given: PathBuf,
+    real: PathBuf,
+}
+
+impl Root {
+    fn new(path: &Path) -> Self {
+        Self {
+            given: path.to_path_buf(),
+            real: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+        }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.real)
+    }
+
+    /// Whether `path`, before resolving symlinks, looks like it is inside
+    fn seems_to_contain(&self, path: &Path) -> bool {
+        path.starts_with(&self.given) || path.starts_with(&self.real)
+    }
+}
+
+/// A file tool call the sandbox refused
+#[derive(Debug, Clone, PartialEq)]
+pub struct Denial {
+    pub tool: String,
+    pub path: String,
+    pub access: Access,
+    /// Where the path leads, when it could be resolved
+    pub resolved: Option<PathBuf>,
+    pub reason: String,
+    /// Directories the tool may use for this access
+    pub allowed: Vec<PathBuf>,
+}
+
+impl Denial {
+    /// The error returned to the model in place of the tool's result
+    pub fn to_error(&self) -> String {
+        let error = json!({
+            "error": "path_outside_sandbox",
+            "tool": self.tool,
+            "path": self.path,
+            "access": self.access.as_str(),
+            "resolved": self.resolved.as_ref().map(|p| p.display().to_string()),
+            "reason": self.reason,
+            "allowed_roots": self
+                .allowed
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>(),
+            "hint": "Use a path under allowed_roots, or ask the user to add the directory \
+                     to [files] allow / allow_read in .finch/permissions.toml",
+        });
+        serde_json::to_string_pretty(&error).unwrap_or_else(|_| self.reason.clone())
+    }
+}
+
+/// Read once from the directory finch was started in
+static ACTIVE: LazyLock<PathSandbox> = LazyLock::new(|| {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    match PathSandbox::load(&cwd) {
+        Ok(sandbox) => {
+            if matches!(sandbox, PathSandbox::Off) {
+                tracing::info!("file tools are not sandboxed");
+            }
+            sandbox
+        }
+        Err(e) => {
+            tracing::error!("File sandbox unusable, refusing file tools: {:#}", e);
+            PathSandbox::Unusable(format!("{:#}", e))
+        }
+    }
+});
+
+/// The path sandbox in force for this process
+pub fn active() -> &'static PathSandbox {
+    &ACTIVE
+}
+
+impl PathSandbox {
+    /// Sandbox settings for `project_root`, from its permissions file or the
+    /// home one
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let Some(file) = permissions_file(project_root) else {
+            return Ok(Self::new(&FilePermissions::default(), project_root));
+        };
+        let text = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let parsed: PermissionsFile = toml::from_str(&text)
+            .with_context(|| format!("Invalid permissions file {}", file.display()))?;
+        Ok(Self::new(&parsed.files, project_root))
+    }
+
+    pub fn new(settings: &FilePermissions, project_root: &Path) -> Self {
+        if !settings.sandbox {
+            return Self::Off;
+        }
+        let base = std::path::absolute(project_root).unwrap_or_else(|_| project_root.into());
+        let roots = |dirs: &[String]| -> Vec<Root> {
+            dirs.iter()
+                .map(|dir| Root::new(&base.join(expand_home(dir))))
+                .collect()
+        };
+        let mut writable = vec![Root::new(&base)];
+        writable.extend(roots(&settings.allow));
+        Self::Scoped(Scope {
+            readable: roots(&settings.allow_read),
+            writable,
+            base,
+        })
+    }
+
+    /// The same sandbox, also allowing `dir` to be read and changed (an agent
+    /// task's repository)
+    pub fn with_root(&self, dir: &Path) -> Self {
+        match self {
+            Self::Scoped(scope) => {
+                let mut scope = scope.clone();
+                scope.writable.push(Root::new(&scope.base.join(dir)));
+                Self::Scoped(scope)
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Refuse `tool_use` if a path it names is out of bounds; calls to other
+    /// tools always pass
+    pub fn check(&self, tool_use: &ToolUse) -> Result<(), Box<Denial>> {
+        for (path, access) in tool_paths(tool_use) {
+            let denial = |resolved: Option<PathBuf>, reason: String, allowed| {
+                Box::new(Denial {
+                    tool: tool_use.name.clone(),
+                    path: path.clone(),
+                    access,
+                    resolved,
+                    reason,
+                    allowed,
+                })
+            };
+            match self {
+                Self::Off => {}
+                Self::Unusable(reason) => {
+                    return Err(denial(
+                        None,
+                        format!(
+                            "file tools are sandboxed for this project but the \
+                             permissions file is unusable: {}",
+                            reason
+                        ),
+                        Vec::new(),
+                    ));
+                }
+                Self::Scoped(scope) => {
+                    if let Err((resolved, reason)) = scope.check(&path, access) {
+                        return Err(denial(resolved, reason, scope.allowed(access)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Scope {
+    fn roots(&self, access: Access) -> impl Iterator<Item = &Root> {
+        let readable = match access {
+            Access::Read => self.readable.as_slice(),
+            Access::Write => &[],
+        };
+        self.writable.iter().chain(readable)
+    }
+
+    fn allowed(&self, access: Access) -> Vec<PathBuf> {
+        self.roots(access).map(|root| root.real.clone()).collect()
+    }
+
+    fn check(&self, path: &str, access: Access) -> Result<(), (Option<PathBuf>, String)> {
+        let given = self.base.join(expand_home(path));
+        let resolved = resolve(&given).map_err(|reason| (None, reason))?;
+        if self.roots(access).any(|root| root.contains(&resolved)) {
+            return Ok(());
+        }
+        let reason =
+            if access == Access::Write && self.readable.iter().any(|r| r.contains(&resolved)) {
+                "the directory is allowed for reading only".to_string()
+            } else if self
+                .roots(access)
+                .any(|root| root.seems_to_contain(&normalize(&given)))
+            {
+                "the path goes through a symlink leading outside the sandbox".to_string()
+            } else {
+                "the path is outside the project and the allowed directories".to_string()
+            };
+        Err((Some(resolved), reason))
+    }
+}
+
+/// The paths a file tool call touches, and how
+fn tool_paths(tool_use: &ToolUse) -> Vec<(String, Access)> {
+    let input = &tool_use.input;
+    let field = |name: &str| input[name].as_str().map(str::to_string);
+    let (paths, access) = match tool_use.name.as_str() {
+        "read" | "outline" => (vec![field("file_path")], Access::Read),
+        "grep" => (
+            vec![Some(field("path").unwrap_or_else(|| ".".to_string()))],
+            Access::Read,
+        ),
+        "glob" => (vec![field("pattern").map(|p| glob_base(&p))], Access::Read),
+        "hash_compare" => (vec![field("file_a"), field("file_b")], Access::Read),
+        "write" | "edit" | "patch" => (vec![field("file_path")], Access::Write),
+        _ => return Vec::new(),
+    };
+    // A missing path is the tool's own error to report
+    paths.into_iter().flatten().map(|p| (p, access)).collect()
+}
+
+/// The directory a glob pattern searches: its literal prefix, or the
+/// pattern itself with `..` kept after a wildcard so the check refuses it
+fn glob_base(pattern: &str) -> String {
+    let is_wild = |part: &str| part.contains(['*', '?', '[', '{']);
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts.iter().take_while(|part| !is_wild(part)).count();
+    if parts[literal..].contains(&"..") {
+        return pattern.to_string();
+    }
+    let base = parts[..literal].join("/");
+    match (base.is_empty(), pattern.starts_with('/')) {
+        (true, true) => "/".to_string(),
+        (true, false) => ".".to_string(),
+        _ => base,
+    }
+}
+
+/// Where `path` (absolute) really leads: the deepest existing ancestor with
+/// its symlinks resolved, followed by the parts that don't exist yet
+fn resolve(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(real) => return Ok(missing.iter().rev().fold(real, |p, part| p.join(part))),
+            Err(_) if existing.symlink_metadata().is_ok() => {
+                return Err(format!(
+                    "{} is a symlink to a missing target",
+                    existing.display()
+                ));
+            }
+            Err(_) => {}
+        }
+        // `..` below a missing directory can't be resolved safely
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return Err(format!("{} cannot be resolved", path.display())),
+        }
+    }
+}
+
+/// `path` with `.` and `..` removed, without looking at the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, input: serde_json::Value) -> ToolUse {
+        ToolUse {
+            id: "toolu_1".to_string(),
+            name: name.to_string(),
+            input,
+        }
+    }
+
+    fn read(path: &Path) -> ToolUse {
+        call("read", json!({ "file_path": path.to_str().unwrap() }))
+    }
+
+    fn write(path: &Path) -> ToolUse {
+        call(
+            "write",
+            json!({ "file_path": path.to_str().unwrap(), "content": "x" }),
+        )
+    }
+
+    /// A project and a directory beside it
+    fn layout() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(project.join("src/lib.rs"), "").unwrap();
+        std::fs::write(outside.join("secret.txt"), "").unwrap();
+        (dir, project, outside)
+    }
+
+    #[test]
+    fn test_only_the_project_by_default() {
+        let (_dir, project, outside) = layout();
+        let sandbox = PathSandbox::new(&FilePermissions::default(), &project);
+
+        assert!(sandbox.check(&read(&project.join("src/lib.rs"))).is_ok());
+        assert!(sandbox
+            .check(&call("read", json!({ "file_path": "src/lib.rs" })))
+            .is_ok());
+        assert!(sandbox
+            .check(&write(&project.join("src/new/mod.rs")))
+            .is_ok());
+        assert!(sandbox
+            .check(&call("bash", json!({ "command": "cat /etc/passwd" })))
+            .is_ok());
+
+        let denial = sandbox
+            .check(&read(&outside.join("secret.txt")))
+            .unwrap_err();
+        assert_eq!(denial.access, Access::Read);
+        assert!(denial.reason.contains("outside the project"));
+        let relative = call("edit", json!({ "file_path": "../outside/secret.txt" }));
+        assert!(sandbox.check(&relative).is_err());
+        let grep = call(
+            "grep",
+            json!({ "pattern": "key", "path": outside.to_str().unwrap() }),
+        );
+        assert!(sandbox.check(&grep).is_err());
+        let glob = call("glob", json!({ "pattern": "../outside/**/*.txt" }));
+        assert!(sandbox.check(&glob).is_err());
+        let sneaky = call("glob", json!({ "pattern": "src/*/../../../outside/*" }));
+        assert!(sandbox.check(&sneaky).is_err());
+        assert!(sandbox
+            .check(&call("glob", json!({ "pattern": "**/*.rs" })))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_symlinks_are_followed() {
+        let (_dir, project, outside) = layout();
+        std::os::unix::fs::symlink(&outside, project.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.join("gone"), project.join("dangling")).unwrap();
+        let sandbox = PathSandbox::new(&FilePermissions::default(), &project);
+
+        let denial = sandbox
+            .check(&read(&project.join("escape/secret.txt")))
+            .unwrap_err();
+        assert!(denial.reason.contains("symlink"), "{}", denial.reason);
+        assert_eq!(
+            denial.resolved,
+            Some(outside.canonicalize().unwrap().join("secret.txt"))
+        );
+        // A write through the link to a file that doesn't exist yet
+        assert!(sandbox
+            .check(&write(&project.join("escape/new.txt")))
+            .is_err());
+        assert!(sandbox.check(&write(&project.join("dangling"))).is_err());
+        // `..` below a missing directory
+        assert!(sandbox
+            .check(&write(&project.join("nope/../../outside/x")))
+            .is_err());
+    }
+
+    #[test]
+    fn test_allowlists() {
+        let (dir, project, outside) = layout();
+        let notes = dir.path().join("notes");
+        std::fs::create_dir(&notes).unwrap();
+        let settings: PermissionsFile = toml::from_str(&format!(
+            "[files]\nallow = [{:?}]\nallow_read = [\"../outside\"]\n",
+            notes.to_str().unwrap()
+        ))
+        .unwrap();
+        let sandbox = PathSandbox::new(&settings.files, &project);
+
+        assert!(sandbox.check(&write(&notes.join("todo.md"))).is_ok());
+        assert!(sandbox.check(&read(&outside.join("secret.txt"))).is_ok());
+        let denial = sandbox
+            .check(&write(&outside.join("secret.txt")))
+            .unwrap_err();
+        assert!(denial.reason.contains("reading only"));
+        assert_eq!(denial.allowed.len(), 2);
+
+        let error: serde_json::Value = serde_json::from_str(&denial.to_error()).unwrap();
+        assert_eq!(error["error"], "path_outside_sandbox");
+        assert_eq!(error["access"], "write");
+
+        // An agent task's repository
+        let repo = sandbox.with_root(&outside);
+        assert!(repo.check(&write(&outside.join("secret.txt"))).is_ok());
+    }
+
+    #[test]
+    fn test_load() {
+        let (_dir, project, outside) = layout();
+        std::fs::create_dir(project.join(".finch")).unwrap();
+        let file = project
+            .join(".finch")
+            .join(crate::tools::sandbox::PERMISSIONS_FILE);
+
+        std::fs::write(
+            &file,
+            "[bash]\nsandbox = \"none\"\n\n[files]\nsandbox = false\n",
+        )
+        .unwrap();
+        let sandbox = PathSandbox::load(&project).unwrap();
+        assert!(sandbox.check(&read(&outside.join("secret.txt"))).is_ok());
+
+        std::fs::write(&file, "[files]\nallowed = [\"/\"]\n").unwrap();
+        let err = PathSandbox::load(&project).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid permissions file"));
+
+        let unusable = PathSandbox::Unusable("bad file".to_string());
+        assert!(unusable.check(&read(&project.join("src/lib.rs"))).is_err());
+    }
+}
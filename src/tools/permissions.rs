@@ -139,7 +139,8 @@ impl PermissionManager {
         match tool_name {
             "bash" => self.check_bash_safety(input),
             "read" => self.check_read_safety(input),
-            "web_fetch" => self.check_web_fetch_safety(input),
+            // The browser's URL checks are web_fetch's
+            "web_fetch" | "browser" => self.check_web_fetch_safety(input),
             _ => None,
         }
     }
@@ -380,6 +381,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_browser_navigation_checked_like_web_fetch() {
+        let manager = PermissionManager::new();
+
+        for url in ["file:///etc/passwd", "http://192.168.1.1/admin"] {
+            let input = serde_json::json!({"action": "navigate", "url": url});
+            assert!(
+                matches!(
+                    manager.check_tool_use("browser", &input),
+                    PermissionCheck::Deny(_)
+                ),
+                "Failed to block: {}",
+                url
+            );
+        }
+
+        let input = serde_json::json!({"action": "navigate", "url": "https://example.com/"});
+        assert!(matches!(
+            manager.check_tool_use("browser", &input),
+            PermissionCheck::AskUser(_)
+        ));
+    }
+
     #[test]
     fn test_safe_bash_command_requires_ask() {
         let manager = PermissionManager::new();
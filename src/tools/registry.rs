@@ -2,11 +2,12 @@
 //
 // Manages available tools and provides uniform execution interface
 
-use crate::tools::types::{ToolContext, ToolDefinition, ToolInputSchema};
+use crate::tools::types::{ToolContext, ToolDefinition, ToolInputSchema, ToolUse};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Tool trait - all tools must implement this
 #[async_trait]
@@ -23,6 +24,12 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given input and context
     async fn execute(&self, input: Value, context: &ToolContext<'_>) -> Result<String>;
 
+    /// Execute a whole tool call.  Tools that need the call's id override
+    /// this; the rest only see the input.
+    async fn execute_call(&self, call: &ToolUse, context: &ToolContext<'_>) -> Result<String> {
+        self.execute(call.input.clone(), context).await
+    }
+
     /// Get full tool definition (for Claude API)
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
@@ -34,8 +41,12 @@ pub trait Tool: Send + Sync {
 }
 
 /// Registry of available tools
+///
+/// Tools are shared, so a clone is cheap and calls through it can run while
+/// the original is locked or changed.
+#[derive(Clone)]
 pub struct ToolRegistry {
-    tools: HashMap<String, Box<dyn Tool>>,
+    tools: HashMap<String, Arc<dyn Tool>>,
 }
 
 impl ToolRegistry {
@@ -49,7 +60,12 @@ impl ToolRegistry {
     /// Register a tool
     pub fn register(&mut self, tool: Box<dyn Tool>) {
         let name = tool.name().to_string();
-        self.tools.insert(name, tool);
+        self.tools.insert(name, Arc::from(tool));
+    }
+
+    /// Remove a tool, returning whether it was registered
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.tools.remove(name).is_some()
     }
 
     /// Get tool by name
@@ -148,6 +164,18 @@ mod tests {
         assert_eq!(retrieved.unwrap().name(), "test");
     }
 
+    #[test]
+    fn test_registry_unregister() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(MockTool {
+            name: "test".to_string(),
+        }));
+
+        assert!(registry.unregister("test"));
+        assert!(!registry.unregister("test"));
+        assert!(registry.is_empty());
+    }
+
     #[test]
     fn test_registry_tool_names() {
         let mut registry = ToolRegistry::new();
@@ -0,0 +1,390 @@
+// Rename check — catch references an `edit` rename would leave behind
+//
+// The most common broken agent refactor renames a definition and updates only
+// the call sites the model happened to read.  Before an edit that renames a
+// definition (`fn old` → `fn new`, `struct Old` → `struct New`, ...) is
+// applied, the workspace is scanned for whole-word references to the old name
+// in files of the same language, and in the edited file as it would read after
+// the edit.  When any are found the edit is held once and the model gets the
+// list back as the tool result; sending the same edit again applies it.
+//
+// There is no language server behind this: references are lexical, so a
+// shadowed or unrelated symbol of the same name is reported too.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+use walkdir::WalkDir;
+
+use crate::tools::types::ToolUse;
+
+/// References listed in the tool result (the rest are counted)
+const MAX_LISTED: usize = 30;
+
+/// Files scanned before giving up on a huge tree
+const MAX_FILES: usize = 20_000;
+
+/// Larger files (generated code, bundles) are skipped
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Directories never scanned
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Extensions that reference each other's symbols
+const LANGUAGE_FAMILIES: &[&[&str]] = &[
+    &["c", "h", "cc", "cpp", "cxx", "hpp", "hh"],
+    &["js", "jsx", "mjs", "cjs", "ts", "tsx"],
+];
+
+/// A definition rename found in an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// A line that still mentions the old name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Relative to the scanned root when inside it
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+/// Holds `edit` renames that would leave references behind
+#[derive(Debug, Default)]
+pub struct RenameCheck {
+    enabled: bool,
+    /// Workspace to scan; the current directory when unset
+    root: Option<PathBuf>,
+    /// Edits already held once, by `path\0from\0to`
+    held: Mutex<HashSet<String>>,
+}
+
+impl RenameCheck {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    /// Scan `root` instead of the current directory
+    pub fn with_root(mut self, root: PathBuf) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    /// The report to return instead of running `tool_use`, or `None` to let
+    /// it run
+    pub async fn check(&self, tool_use: &ToolUse) -> Option<String> {
+        if !self.enabled || tool_use.name != "edit" {
+            return None;
+        }
+        let input = &tool_use.input;
+        let file_path = input["file_path"].as_str()?;
+        let old_string = input["old_string"].as_str()?;
+        let new_string = input["new_string"].as_str()?;
+        let replace_all = input["replace_all"].as_bool().unwrap_or(false);
+        let rename = detect_rename(old_string, new_string)?;
+
+        let key = format!("{}\0{}\0{}", file_path, rename.from, rename.to);
+        if self.held.lock().unwrap().remove(&key) {
+            // Sent again after the report: the model wants it as is
+            return None;
+        }
+
+        let root = match &self.root {
+            Some(root) => root.clone(),
+            None => std::env::current_dir().ok()?,
+        };
+        let file = root.join(file_path);
+        let original = std::fs::read_to_string(&file).ok()?;
+        if !original.contains(old_string) {
+            // The edit tool reports the mismatch itself
+            return None;
+        }
+        let edited = if replace_all {
+            original.replace(old_string, new_string)
+        } else {
+            original.replacen(old_string, new_string, 1)
+        };
+
+        let from = rename.from.clone();
+        let references =
+            tokio::task::spawn_blocking(move || find_references(&root, &file, &edited, &from))
+                .await
+                .ok()?;
+        if references.is_empty() {
+            return None;
+        }
+
+        tracing::info!(
+            from = %rename.from,
+            to = %rename.to,
+            references = references.len(),
+            "Holding rename edit that leaves references behind"
+        );
+        self.held.lock().unwrap().insert(key);
+        Some(report(&rename, &references))
+    }
+}
+
+/// The rename an edit makes to a definition, if it makes exactly one: a name
+/// defined in `old` (after `fn`, `struct`, `class`, `def`, ...) that no longer
+/// appears in `new`, where `new` defines a name `old` didn't have
+pub fn detect_rename(old: &str, new: &str) -> Option<Rename> {
+    let old_defs = definitions(old);
+    let new_defs = definitions(new);
+    let mut removed = old_defs.iter().filter(|name| !contains_word(new, name));
+    let from = removed.next()?;
+    if removed.next().is_some() {
+        return None;
+    }
+    let to = new_defs
+        .iter()
+        .find(|name| !old_defs.contains(name) && !contains_word(old, name))?;
+    Some(Rename {
+        from: from.clone(),
+        to: to.clone(),
+    })
+}
+
+/// Names defined in `text`, in order
+fn definitions(text: &str) -> Vec<String> {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    let re = DEFINITION.get_or_init(|| {
+        Regex::new(
+            r"(?:\b(?:fn|struct|enum|trait|type|const|static|mod|union|class|def|function|interface|func)|\bmacro_rules!)\s+([A-Za-z_][A-Za-z0-9_]*)",
+        )
+        .expect("static regex")
+    });
+    let mut names: Vec<String> = Vec::new();
+    for captures in re.captures_iter(text) {
+        let name = captures[1].to_string();
+        // Too short to search for usefully
+        if name.len() >= 3 && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+fn word_regex(word: &str) -> Regex {
+    Regex::new(&format!(r"\b{}\b", regex::escape(word))).expect("escaped word")
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    word_regex(word).is_match(text)
+}
+
+/// Whole-word mentions of `symbol` under `root` in files of `file`'s
+/// language, with `file` itself read as `edited`
+pub fn find_references(root: &Path, file: &Path, edited: &str, symbol: &str) -> Vec<Reference> {
+    let word = word_regex(symbol);
+    let display = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+
+    let mut references = matching_lines(&word, edited, &display(file));
+    let same_language = language_filter(file);
+    let walker = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(name.starts_with('.')
+                    || (entry.file_type().is_dir() && SKIP_DIRS.contains(&name.as_ref())))
+        });
+    for entry in walker.filter_map(|e| e.ok()).take(MAX_FILES) {
+        let path = entry.path();
+        if !entry.file_type().is_file() || path == file || !same_language(path) {
+            continue;
+        }
+        if entry.metadata().map_or(true, |m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        references.extend(matching_lines(&word, &text, &display(path)));
+    }
+    references
+}
+
+fn matching_lines(word: &Regex, text: &str, path: &str) -> Vec<Reference> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| word.is_match(line))
+        .map(|(i, line)| Reference {
+            path: path.to_string(),
+            line: i + 1,
+            text: line.trim().chars().take(120).collect(),
+        })
+        .collect()
+}
+
+/// Whether a path is in the same language as `file` (any file when it has
+/// no extension)
+fn language_filter(file: &Path) -> impl Fn(&Path) -> bool {
+    let extension = file
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    let family: Vec<String> = match &extension {
+        Some(ext) => LANGUAGE_FAMILIES
+            .iter()
+            .find(|family| family.contains(&ext.as_str()))
+            .map(|family| family.iter().map(|e| e.to_string()).collect())
+            .unwrap_or_else(|| vec![ext.clone()]),
+        None => Vec::new(),
+    };
+    move |path: &Path| {
+        family.is_empty()
+            || path
+                .extension()
+                .is_some_and(|e| family.contains(&e.to_string_lossy().to_ascii_lowercase()))
+    }
+}
+
+fn report(rename: &Rename, references: &[Reference]) -> String {
+    let mut out = format!(
+        "Edit not applied: it renames `{}` to `{}`, but {} reference{} to `{}` would be left behind:\n",
+        rename.from,
+        rename.to,
+        references.len(),
+        if references.len() == 1 { "" } else { "s" },
+        rename.from
+    );
+    for reference in references.iter().take(MAX_LISTED) {
+        out.push_str(&format!(
+            "  {}:{}: {}\n",
+            reference.path, reference.line, reference.text
+        ));
+    }
+    if references.len() > MAX_LISTED {
+        out.push_str(&format!(
+            "  ... and {} more\n",
+            references.len() - MAX_LISTED
+        ));
+    }
+    out.push_str(
+        "Update these as part of the refactor. If they are meant to stay (a different \
+         symbol with the same name, or you will change them next), send this edit again \
+         unchanged to apply it.",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_rename() {
+        assert_eq!(
+            detect_rename("pub fn parse_line(s: &str)", "pub fn parse_row(s: &str)"),
+            Some(Rename {
+                from: "parse_line".to_string(),
+                to: "parse_row".to_string(),
+            })
+        );
+        assert_eq!(
+            detect_rename("class Widget:\n    pass", "class Gadget:\n    pass"),
+            Some(Rename {
+                from: "Widget".to_string(),
+                to: "Gadget".to_string(),
+            })
+        );
+        // Body edits, signature changes and call-site edits are not renames
+        assert_eq!(
+            detect_rename("fn run() { a(); }", "fn run() { b(); }"),
+            None
+        );
+        assert_eq!(
+            detect_rename("fn run(x: u8)", "fn run(x: u16, y: u16)"),
+            None
+        );
+        assert_eq!(detect_rename("let total = a;", "let sum = a;"), None);
+        // Deleting a definition is not a rename
+        assert_eq!(detect_rename("fn helper() {}\n", ""), None);
+    }
+
+    #[test]
+    fn test_find_references_same_language_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn parse_line() {}\n").unwrap();
+        std::fs::write(
+            root.join("src/main.rs"),
+            "fn main() {\n    lib::parse_line();\n    parse_lines();\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("README.md"), "Call parse_line.\n").unwrap();
+        std::fs::write(root.join("target/gen.rs"), "parse_line();\n").unwrap();
+
+        let references = find_references(
+            root,
+            &root.join("src/lib.rs"),
+            "pub fn parse_row() {}\nfn x() { parse_line() }\n",
+            "parse_line",
+        );
+        assert_eq!(
+            references,
+            vec![
+                Reference {
+                    path: "src/lib.rs".to_string(),
+                    line: 2,
+                    text: "fn x() { parse_line() }".to_string(),
+                },
+                Reference {
+                    path: "src/main.rs".to_string(),
+                    line: 2,
+                    text: "lib::parse_line();".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_is_held_once_then_applied() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("lib.rs"), "pub fn parse_line() {}\n").unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() { parse_line(); }\n").unwrap();
+        let check = RenameCheck::new(true).with_root(root.to_path_buf());
+        let edit = ToolUse::new(
+            "edit".to_string(),
+            json!({
+                "file_path": "lib.rs",
+                "old_string": "pub fn parse_line()",
+                "new_string": "pub fn parse_row()"
+            }),
+        );
+
+        let report = check.check(&edit).await.unwrap();
+        assert!(report.starts_with(
+            "Edit not applied: it renames `parse_line` to `parse_row`, but 1 reference"
+        ));
+        assert!(report.contains("  main.rs:1: fn main() { parse_line(); }\n"));
+        // Sending it again goes through
+        assert_eq!(check.check(&edit).await, None);
+
+        // Nothing to report once the call site is updated
+        std::fs::write(root.join("main.rs"), "fn main() { parse_row(); }\n").unwrap();
+        assert_eq!(check.check(&edit).await, None);
+        // Disabled checks never hold anything
+        std::fs::write(root.join("main.rs"), "fn main() { parse_line(); }\n").unwrap();
+        let disabled = RenameCheck::new(false).with_root(root.to_path_buf());
+        assert_eq!(disabled.check(&edit).await, None);
+    }
+}
@@ -0,0 +1,188 @@
+// Tool result budget — keeps one huge tool output from filling the context
+//
+// A grep over a vendored tree or a verbose build log can be hundreds of
+// thousands of tokens.  Results over the tool's cap keep their first and last
+// lines; the middle is replaced by a note naming the omitted range so the
+// model can ask for exactly that part (read with offset/limit, sed -n, ...).
+
+use std::collections::HashMap;
+
+use crate::config::constants::DEFAULT_TOOL_RESULT_MAX_TOKENS;
+use crate::config::FeaturesConfig;
+
+/// Rough bytes per token, as in finch's other token estimates
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Per-tool token caps for tool results
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResultBudget {
+    /// Cap for tools without an override (0 = unlimited)
+    pub max_tokens: usize,
+    /// Caps by tool name
+    pub per_tool: HashMap<String, usize>,
+}
+
+impl Default for ToolResultBudget {
+    fn default() -> Self {
+        Self {
+            max_tokens: DEFAULT_TOOL_RESULT_MAX_TOKENS,
+            per_tool: HashMap::new(),
+        }
+    }
+}
+
+impl ToolResultBudget {
+    pub fn from_features(features: &FeaturesConfig) -> Self {
+        Self {
+            max_tokens: features.tool_result_max_tokens,
+            per_tool: features.tool_result_max_tokens_by_tool.clone(),
+        }
+    }
+
+    /// Token cap for `tool` (0 = unlimited)
+    pub fn limit_for(&self, tool: &str) -> usize {
+        self.per_tool.get(tool).copied().unwrap_or(self.max_tokens)
+    }
+
+    /// Cut `content` down to `tool`'s cap, leaving it untouched when it fits
+    pub fn apply(&self, tool: &str, content: String) -> String {
+        let max_tokens = self.limit_for(tool);
+        if max_tokens == 0 || content.len() <= max_tokens * BYTES_PER_TOKEN {
+            return content;
+        }
+        let truncated = truncate_middle(&content, tool, max_tokens);
+        tracing::info!(
+            tool,
+            original_tokens = content.len() / BYTES_PER_TOKEN,
+            max_tokens,
+            "Tool result truncated to fit its budget"
+        );
+        truncated
+    }
+}
+
+/// Keep the head and tail of `content` (half the budget each), replacing the
+/// middle with a note the model can act on
+fn truncate_middle(content: &str, tool: &str, max_tokens: usize) -> String {
+    let half = max_tokens * BYTES_PER_TOKEN / 2;
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut head = 0;
+    let mut head_bytes = 0;
+    while head < lines.len() && head_bytes + lines[head].len() < half {
+        head_bytes += lines[head].len() + 1;
+        head += 1;
+    }
+    let mut tail = 0;
+    let mut tail_bytes = 0;
+    while head + tail < lines.len() && tail_bytes + lines[lines.len() - 1 - tail].len() < half {
+        tail_bytes += lines[lines.len() - 1 - tail].len() + 1;
+        tail += 1;
+    }
+
+    let omitted_lines = lines.len() - head - tail;
+    if head > 0 && omitted_lines > 0 {
+        let first = head + 1;
+        let last = lines.len() - tail;
+        let omitted_tokens = (content.len() - head_bytes - tail_bytes) / BYTES_PER_TOKEN;
+        return format!(
+            "{}\n\n[… lines {}-{} of {} omitted (~{} tokens): this {} result is over its \
+             {}-token budget. If you need them, request that range specifically, e.g. read \
+             with offset/limit, a narrower grep pattern or path, or `sed -n '{},{}p'`. …]\n\n{}",
+            lines[..head].join("\n"),
+            first,
+            last,
+            lines.len(),
+            omitted_tokens,
+            tool,
+            max_tokens,
+            first,
+            last,
+            lines[lines.len() - tail..].join("\n"),
+        );
+    }
+
+    // A few enormous lines (minified JSON, base64): cut by bytes instead
+    let head_end = floor_char_boundary(content, half);
+    let tail_start = ceil_char_boundary(content, content.len() - half);
+    format!(
+        "{}\n\n[… bytes {}-{} of {} omitted (~{} tokens): this {} result is over its \
+         {}-token budget. Request a narrower range if you need the middle. …]\n\n{}",
+        &content[..head_end],
+        head_end,
+        tail_start,
+        content.len(),
+        (tail_start - head_end) / BYTES_PER_TOKEN,
+        tool,
+        max_tokens,
+        &content[tail_start..],
+    )
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(max_tokens: usize) -> ToolResultBudget {
+        ToolResultBudget {
+            max_tokens,
+            per_tool: HashMap::from([("read".to_string(), 0)]),
+        }
+    }
+
+    #[test]
+    fn test_small_results_and_unlimited_tools_are_untouched() {
+        let budget = budget(10);
+        assert_eq!(budget.apply("grep", "short".to_string()), "short");
+
+        let big = "x\n".repeat(1000);
+        assert_eq!(budget.apply("read", big.clone()), big);
+    }
+
+    #[test]
+    fn test_middle_lines_are_replaced_with_range_note() {
+        let content: String = (1..=1000).map(|i| format!("match {}\n", i)).collect();
+        let out = budget(100).apply("grep", content.clone());
+
+        assert!(out.len() < 100 * BYTES_PER_TOKEN + 400);
+        assert!(out.starts_with("match 1\nmatch 2\n"));
+        assert!(out.ends_with("match 999\nmatch 1000"));
+        assert!(out.contains("of 1000 omitted"));
+        assert!(!out.contains("match 500\n"));
+
+        // The advertised range is exactly what's missing
+        let note = out.split("[… lines ").nth(1).unwrap();
+        let (first, rest) = note.split_once('-').unwrap();
+        let last = rest.split_whitespace().next().unwrap();
+        assert!(out.contains(&format!(
+            "match {}\n\n[…",
+            first.parse::<usize>().unwrap() - 1
+        )));
+        assert!(out.contains(&format!(
+            "…]\n\nmatch {}",
+            last.parse::<usize>().unwrap() + 1
+        )));
+    }
+
+    #[test]
+    fn test_single_huge_line_is_cut_by_bytes() {
+        let content = "é".repeat(5000);
+        let out = budget(100).apply("web_fetch", content);
+        assert!(out.contains("[… bytes "));
+        assert!(out.len() < 100 * BYTES_PER_TOKEN + 400);
+    }
+}
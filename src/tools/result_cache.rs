@@ -0,0 +1,303 @@
+// Result cache for read, grep, glob and outline
+//
+// Agents re-read the same files every few turns.  Within a session a repeated
+// `read`, `grep`, `glob` or `outline` call with the same input is answered
+// from memory, as long as nothing it looked at has changed:
+//
+//   * before a call runs, the files and directories it will look at are
+//     stat'ed; a hit needs every one of them to have the same mtime and size
+//     (a file created or removed shows up as a changed directory)
+//   * a write, edit or patch through finch drops the entries that looked at
+//     the path straight away, so a rewrite within the mtime granularity is
+//     never missed
+//
+// Results are cached before the result budget cuts them.  Searches over very
+// large trees aren't cached: checking them would cost as much as running
+// them.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::tools::implementations::grep::search_walk;
+use crate::tools::types::ToolUse;
+
+/// Tools whose results are cached
+const CACHED_TOOLS: &[&str] = &["read", "grep", "glob", "outline"];
+
+/// Tools after which entries that looked at their `file_path` are dropped
+const WRITING_TOOLS: &[&str] = &["write", "edit", "patch"];
+
+/// Results kept; the oldest is dropped first
+const MAX_ENTRIES: usize = 200;
+
+/// Calls that look at more paths than this aren't cached
+const MAX_TRACKED_PATHS: usize = 20_000;
+
+/// What a path looked like: `None` when it didn't exist
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+/// The paths a call looks at, as they were just before it ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    paths: Vec<(PathBuf, Stamp)>,
+}
+
+impl Snapshot {
+    /// Stat the paths `tool_use` will look at; `None` for tools that aren't
+    /// cached, or calls that look at too much
+    pub fn take(tool_use: &ToolUse) -> Option<Self> {
+        let input = &tool_use.input;
+        let paths: Vec<PathBuf> = match tool_use.name.as_str() {
+            "read" | "outline" => vec![PathBuf::from(input["file_path"].as_str()?)],
+            "grep" => search_walk(input["path"].as_str().unwrap_or("."))
+                .take(MAX_TRACKED_PATHS + 1)
+                .map(|entry| entry.into_path())
+                .collect(),
+            "glob" => glob_dirs(input["pattern"].as_str()?)
+                .take(MAX_TRACKED_PATHS + 1)
+                .collect(),
+            _ => return None,
+        };
+        if paths.len() > MAX_TRACKED_PATHS {
+            return None;
+        }
+        Some(Self {
+            paths: paths
+                .into_iter()
+                .map(|path| {
+                    let stamp = stamp(&path);
+                    (absolute(&path), stamp)
+                })
+                .collect(),
+        })
+    }
+
+    /// Whether every path still looks as it did
+    fn is_current(&self) -> bool {
+        self.paths.iter().all(|(path, seen)| stamp(path) == *seen)
+    }
+
+    /// Whether the call looked at `path` or at the directory holding it
+    fn involves(&self, path: &Path) -> bool {
+        let parent = path.parent();
+        self.paths
+            .iter()
+            .any(|(seen, _)| seen == path || Some(seen.as_path()) == parent)
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok(), meta.len()))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The directories a glob pattern lists: everything below its literal
+/// prefix, as deep as the pattern reaches
+fn glob_dirs(pattern: &str) -> impl Iterator<Item = PathBuf> {
+    let is_wild = |part: &str| part.contains(['*', '?', '[', '{']);
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts.iter().take_while(|part| !is_wild(part)).count();
+    let (base, rest) = if literal == parts.len() {
+        // No wildcards: the path itself, and its directory for its existence
+        let path = PathBuf::from(pattern);
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        (dir, 0)
+    } else {
+        (parts[..literal].join("/").into(), parts.len() - literal - 1)
+    };
+    let base = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+    let depth = if parts[literal..].contains(&"**") {
+        usize::MAX
+    } else {
+        rest
+    };
+    walkdir::WalkDir::new(base)
+        .max_depth(depth)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_dir())
+        .map(|entry| entry.into_path())
+}
+
+struct Entry {
+    snapshot: Snapshot,
+    output: String,
+}
+
+/// Cached read / grep / glob / outline results for this session
+#[derive(Default)]
+pub struct ResultCache {
+    /// Keyed by tool name and input
+    entries: Mutex<HashMap<String, Entry>>,
+    /// Keys, oldest first
+    order: Mutex<VecDeque<String>>,
+}
+
+impl ResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(tool_use: &ToolUse) -> Option<String> {
+        CACHED_TOOLS
+            .contains(&tool_use.name.as_str())
+            .then(|| format!("{}:{}", tool_use.name, tool_use.input))
+    }
+
+    /// The result of an identical earlier call, if nothing it looked at has
+    /// changed since
+    pub fn get(&self, tool_use: &ToolUse) -> Option<String> {
+        let key = Self::key(tool_use)?;
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(&key)?;
+        if entry.snapshot.is_current() {
+            tracing::debug!(tool = %tool_use.name, "Answered from the result cache");
+            return Some(entry.output.clone());
+        }
+        entries.remove(&key);
+        None
+    }
+
+    /// Keep `output` of a call that ran after `snapshot` was taken
+    pub fn insert(&self, tool_use: &ToolUse, snapshot: Snapshot, output: &str) {
+        let Some(key) = Self::key(tool_use) else {
+            return;
+        };
+        let mut entries = self.entries.lock().unwrap();
+        let mut order = self.order.lock().unwrap();
+        order.retain(|k| *k != key);
+        while order.len() >= MAX_ENTRIES {
+            if let Some(oldest) = order.pop_front() {
+                entries.remove(&oldest);
+            }
+        }
+        order.push_back(key.clone());
+        entries.insert(
+            key,
+            Entry {
+                snapshot,
+                output: output.to_string(),
+            },
+        );
+    }
+
+    /// Drop the results a write, edit or patch by `tool_use` may have made
+    /// stale
+    pub fn invalidate(&self, tool_use: &ToolUse) {
+        if !WRITING_TOOLS.contains(&tool_use.name.as_str()) {
+            return;
+        }
+        let Some(path) = tool_use.input["file_path"].as_str() else {
+            return;
+        };
+        let path = absolute(Path::new(path));
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| !entry.snapshot.involves(&path));
+        self.order
+            .lock()
+            .unwrap()
+            .retain(|key| entries.contains_key(key));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(name: &str, input: serde_json::Value) -> ToolUse {
+        ToolUse::new(name.to_string(), input)
+    }
+
+    fn run(cache: &ResultCache, tool_use: &ToolUse, output: &str) -> String {
+        if let Some(hit) = cache.get(tool_use) {
+            return hit;
+        }
+        let snapshot = Snapshot::take(tool_use).unwrap();
+        cache.insert(tool_use, snapshot, output);
+        output.to_string()
+    }
+
+    #[test]
+    fn test_changed_files_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        let cache = ResultCache::new();
+
+        let read = call("read", json!({"file_path": file.to_str().unwrap()}));
+        assert_eq!(run(&cache, &read, "first"), "first");
+        assert_eq!(run(&cache, &read, "second"), "first");
+
+        // A different size misses even if the mtime didn't tick
+        std::fs::write(&file, "fn a() {}\nfn b() {}\n").unwrap();
+        assert_eq!(run(&cache, &read, "third"), "third");
+
+        // So does a file created where a grep looked
+        let grep = call(
+            "grep",
+            json!({"pattern": "fn", "path": dir.path().to_str().unwrap()}),
+        );
+        assert_eq!(run(&cache, &grep, "one match"), "one match");
+        assert_eq!(run(&cache, &grep, "unused"), "one match");
+        let snapshot = Snapshot::take(&grep).unwrap();
+        std::fs::write(dir.path().join("new.rs"), "fn c() {}\n").unwrap();
+        assert_ne!(Snapshot::take(&grep).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_writes_drop_entries_that_looked_at_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        let other = dir.path().join("other").join("main.rs");
+        std::fs::create_dir_all(other.parent().unwrap()).unwrap();
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        std::fs::write(&other, "fn main() {}\n").unwrap();
+        let cache = ResultCache::new();
+
+        let read = call("read", json!({"file_path": file.to_str().unwrap()}));
+        let read_other = call("read", json!({"file_path": other.to_str().unwrap()}));
+        run(&cache, &read, "a");
+        run(&cache, &read_other, "main");
+        assert!(cache.get(&call("bash", json!({"command": "ls"}))).is_none());
+
+        cache.invalidate(&call(
+            "edit",
+            json!({"file_path": file.to_str().unwrap(), "old_string": "a", "new_string": "b"}),
+        ));
+        assert!(cache.get(&read).is_none());
+        assert_eq!(cache.get(&read_other).as_deref(), Some("main"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_glob_dirs_follow_the_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src").join("cli")).unwrap();
+        let base = dir.path().to_str().unwrap();
+
+        let flat: Vec<PathBuf> = glob_dirs(&format!("{}/src/*.rs", base)).collect();
+        assert_eq!(flat, [dir.path().join("src")]);
+
+        let deep: Vec<PathBuf> = glob_dirs(&format!("{}/**/*.rs", base)).collect();
+        assert_eq!(deep.len(), 3);
+    }
+}
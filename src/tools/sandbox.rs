@@ -0,0 +1,367 @@
+// Container sandbox for the bash tool
+//
+// A project opts in with `<project>/.finch/permissions.toml`, or every
+// project with `~/.finch/permissions.toml` (a project file replaces the home
+// one):
+//
+//   [bash]
+//   sandbox = "container"
+//   image = "rust:1.80"   # must provide bash and whatever the commands use
+//   runtime = "podman"    # default: podman if installed, else docker
+//   network = false       # default: no network inside the container
+//
+// Each command then runs in a fresh container of `image` with the project
+// directory bind-mounted read-write at its own path.  Everything else (the
+// image's filesystem) is read-only apart from a scratch /tmp, and nothing
+// else of the host is visible, so even auto-approved commands in agent mode
+// can only change the project.
+//
+// An invalid permissions file, a sandbox without an image or a missing
+// container runtime make the bash tool refuse commands rather than run them
+// on the host.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::process::Command;
+
+/// Permissions file name, looked up in `<project>/.finch/` and `~/.finch/`
+pub const PERMISSIONS_FILE: &str = "permissions.toml";
+
+/// Runtimes tried, in order, when the file names none
+const RUNTIMES: &[&str] = &["podman", "docker"];
+
+/// Where bash commands run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxMode {
+    /// On the host
+    #[default]
+    None,
+    /// In a throwaway container
+    Container,
+}
+
+/// `[bash]` in the permissions file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BashPermissions {
+    #[serde(default)]
+    pub sandbox: SandboxMode,
+    /// Container image commands run in
+    pub image: Option<String>,
+    /// `podman` or `docker` (name on PATH or full path)
+    pub runtime: Option<String>,
+    /// Give containers network access
+    #[serde(default)]
+    pub network: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PermissionsFile {
+    #[serde(default)]
+    bash: BashPermissions,
+}
+
+/// How the bash tool runs commands
+#[derive(Debug, Clone)]
+pub enum Sandbox {
+    Host,
+    Container(ContainerSandbox),
+    /// A sandbox was asked for but can't be used; every command is refused
+    Unusable(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ContainerSandbox {
+    pub runtime: PathBuf,
+    pub image: String,
+    /// Mounted read-write; the only host directory the container sees
+    pub project: PathBuf,
+    pub network: bool,
+}
+
+/// Read once from the directory finch was started in
+static ACTIVE: LazyLock<Sandbox> = LazyLock::new(|| {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    match Sandbox::load(&cwd) {
+        Ok(sandbox) => {
+            if let Sandbox::Container(container) = &sandbox {
+                tracing::info!(
+                    image = %container.image,
+                    runtime = %container.runtime.display(),
+                    "bash commands run in a container"
+                );
+            }
+            sandbox
+        }
+        Err(e) => {
+            tracing::error!("Bash sandbox unusable, refusing bash commands: {:#}", e);
+            Sandbox::Unusable(format!("{:#}", e))
+        }
+    }
+});
+
+/// The sandbox in force for this process
+pub fn active() -> &'static Sandbox {
+    &ACTIVE
+}
+
+impl Sandbox {
+    /// Sandbox settings for `project_root`, from its permissions file or the
+    /// home one
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let Some(file) = permissions_file(project_root) else {
+            return Ok(Self::Host);
+        };
+        let text = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let parsed: PermissionsFile = toml::from_str(&text)
+            .with_context(|| format!("Invalid permissions file {}", file.display()))?;
+        Self::from_settings(&parsed.bash, project_root, find_in_path)
+            .with_context(|| format!("In {}", file.display()))
+    }
+
+    fn from_settings(
+        settings: &BashPermissions,
+        project_root: &Path,
+        find: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<Self> {
+        if settings.sandbox == SandboxMode::None {
+            return Ok(Self::Host);
+        }
+        let image = settings
+            .image
+            .clone()
+            .context("sandbox = \"container\" needs an image")?;
+        let runtime = match &settings.runtime {
+            Some(name) => find(name).with_context(|| format!("{} is not installed", name))?,
+            None => RUNTIMES
+                .iter()
+                .find_map(|name| find(name))
+                .context("neither podman nor docker is installed")?,
+        };
+        Ok(Self::Container(ContainerSandbox {
+            runtime,
+            image,
+            project: project_root.to_path_buf(),
+            network: settings.network,
+        }))
+    }
+
+    /// The command that runs `script` with bash under this sandbox
+    pub fn command(&self, script: &str) -> Result<Command> {
+        match self {
+            Self::Host => {
+                let mut cmd = Command::new("bash");
+                cmd.arg("-c").arg(script);
+                Ok(cmd)
+            }
+            Self::Container(container) => {
+                let cwd = std::env::current_dir().unwrap_or_else(|_| container.project.clone());
+                let mut cmd = Command::new(&container.runtime);
+                cmd.args(container.run_args(script, &cwd));
+                Ok(cmd)
+            }
+            Self::Unusable(reason) => bail!(
+                "bash is sandboxed for this project but the sandbox is unusable, \
+                 so commands are refused: {}",
+                reason
+            ),
+        }
+    }
+}
+
+impl ContainerSandbox {
+    /// `<runtime> run` arguments for running `script` from `cwd`
+    pub fn run_args(&self, script: &str, cwd: &Path) -> Vec<String> {
+        let project = self.project.display().to_string();
+        // Start where the command was issued if that is inside the project
+        let workdir = if cwd.starts_with(&self.project) {
+            cwd.display().to_string()
+        } else {
+            project.clone()
+        };
+        let mut args: Vec<String> = [
+            "run",
+            "--rm",
+            "--interactive",
+            "--init",
+            "--read-only",
+            "--tmpfs",
+            "/tmp",
+            "--cap-drop",
+            "ALL",
+            "--security-opt",
+            "no-new-privileges",
+            "--env",
+            "HOME=/tmp",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        if !self.network {
+            args.extend(["--network".to_string(), "none".to_string()]);
+        }
+        args.extend([
+            "--volume".to_string(),
+            format!("{}:{}:rw", project, project),
+            "--workdir".to_string(),
+            workdir,
+        ]);
+        // Files created in the project belong to the user, not root
+        if self.is_podman() {
+            args.extend(["--userns".to_string(), "keep-id".to_string()]);
+        } else {
+            #[cfg(unix)]
+            {
+                // SAFETY: getuid / getgid cannot fail
+                let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                args.extend(["--user".to_string(), format!("{}:{}", uid, gid)]);
+            }
+        }
+        args.extend([
+            self.image.clone(),
+            "bash".to_string(),
+            "-c".to_string(),
+            script.to_string(),
+        ]);
+        args
+    }
+
+    fn is_podman(&self) -> bool {
+        self.runtime
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("podman"))
+    }
+}
+
+/// The permissions file in force for `project_root`: its own, else the home
+/// one
+pub fn permissions_file(project_root: &Path) -> Option<PathBuf> {
+    let project_file = project_root.join(".finch").join(PERMISSIONS_FILE);
+    let home_file = dirs::home_dir().map(|h| h.join(".finch").join(PERMISSIONS_FILE));
+    std::iter::once(project_file)
+        .chain(home_file)
+        .find(|f| f.exists())
+}
+
+/// `name` if it is a path to a file, else the first match on PATH
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(text: &str) -> BashPermissions {
+        toml::from_str::<PermissionsFile>(text).unwrap().bash
+    }
+
+    fn only_docker(name: &str) -> Option<PathBuf> {
+        (name == "docker").then(|| PathBuf::from("/usr/bin/docker"))
+    }
+
+    #[test]
+    fn test_no_sandbox_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::from_settings(&settings(""), dir.path(), only_docker).unwrap();
+        assert!(matches!(sandbox, Sandbox::Host));
+    }
+
+    #[test]
+    fn test_container_settings() {
+        let project = Path::new("/work/app");
+        let sandbox = Sandbox::from_settings(
+            &settings("[bash]\nsandbox = \"container\"\nimage = \"rust:1.80\""),
+            project,
+            only_docker,
+        )
+        .unwrap();
+        let Sandbox::Container(container) = sandbox else {
+            panic!("expected a container sandbox");
+        };
+        assert_eq!(container.runtime, PathBuf::from("/usr/bin/docker"));
+        assert!(!container.network);
+
+        let args = container.run_args("cargo test", Path::new("/work/app/crates/core"));
+        let joined = args.join(" ");
+        assert!(joined.starts_with("run --rm --interactive --init --read-only"));
+        assert!(joined.contains("--network none"));
+        assert!(joined.contains("--volume /work/app:/work/app:rw"));
+        assert!(joined.contains("--workdir /work/app/crates/core"));
+        assert!(joined.ends_with("rust:1.80 bash -c cargo test"));
+        // The script stays one argument
+        assert_eq!(args.last().unwrap(), "cargo test");
+
+        // Outside the project the command starts at the project root
+        let args = container.run_args("ls", Path::new("/etc"));
+        assert!(args.join(" ").contains("--workdir /work/app "));
+    }
+
+    #[test]
+    fn test_podman_keeps_user_ids_and_network_can_be_allowed() {
+        let container = ContainerSandbox {
+            runtime: PathBuf::from("/usr/bin/podman"),
+            image: "alpine".to_string(),
+            project: PathBuf::from("/p"),
+            network: true,
+        };
+        let joined = container.run_args("true", Path::new("/p")).join(" ");
+        assert!(joined.contains("--userns keep-id"));
+        assert!(!joined.contains("--network"));
+        assert!(!joined.contains("--user "));
+    }
+
+    #[test]
+    fn test_incomplete_sandbox_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let no_image = settings("[bash]\nsandbox = \"container\"");
+        assert!(Sandbox::from_settings(&no_image, dir.path(), only_docker).is_err());
+
+        let podman =
+            settings("[bash]\nsandbox = \"container\"\nimage = \"x\"\nruntime = \"podman\"");
+        let err = Sandbox::from_settings(&podman, dir.path(), only_docker).unwrap_err();
+        assert!(err.to_string().contains("podman is not installed"));
+
+        let neither = settings("[bash]\nsandbox = \"container\"\nimage = \"x\"");
+        assert!(Sandbox::from_settings(&neither, dir.path(), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_load_from_project_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".finch")).unwrap();
+        let file = dir.path().join(".finch").join(PERMISSIONS_FILE);
+
+        std::fs::write(&file, "[bash]\nsandbox = \"none\"\n").unwrap();
+        assert!(matches!(Sandbox::load(dir.path()).unwrap(), Sandbox::Host));
+
+        std::fs::write(&file, "[bash]\nsandbox = \"jail\"\n").unwrap();
+        let err = Sandbox::load(dir.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid permissions file"));
+    }
+
+    #[tokio::test]
+    async fn test_unusable_sandbox_refuses_commands() {
+        let sandbox = Sandbox::Unusable("no runtime".to_string());
+        let err = sandbox.command("rm -rf build").unwrap_err();
+        assert!(err.to_string().contains("refused"));
+
+        let output = Sandbox::Host
+            .command("echo hi")
+            .unwrap()
+            .output()
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hi\n");
+    }
+}
@@ -0,0 +1,503 @@
+// Persistent shell behind the bash tool
+//
+// One bash process per conversation, attached to a pseudo-terminal, so `cd`,
+// exported variables and `source venv/bin/activate` carry over from one
+// command to the next the way they do in a real terminal.
+//
+// Commands are typed into the shell as `$'…'` string chunks (a tty drops
+// input lines longer than 4 KiB) and run with `eval` in the shell itself,
+// with stdin from /dev/null.  A `printf` after each one reports its exit
+// status behind a per-session nonce, which marks where its output ends.
+// Echo is turned off on the terminal so nothing typed shows up in the output.
+//
+// A command whose future is dropped before it finishes (the user cancelled,
+// or the tool timeout fired) hangs up the whole shell, so nothing it started
+// keeps running in the background; the session is then marked interrupted.
+
+use anyhow::{bail, Context, Result};
+use nix::sys::termios::{self, LocalFlags, OutputFlags, SetArg};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Child;
+use tokio::sync::mpsc;
+
+use super::sandbox::Sandbox;
+
+/// The shell started for a session; `--noediting` keeps readline from
+/// redrawing what is typed
+const SHELL: &str = "exec bash --noprofile --norc --noediting";
+
+/// Bytes of command text per typed line, well under the tty's line limit
+const CHUNK_BYTES: usize = 512;
+
+/// How long to wait for a new shell, or for a command to die after SIGTERM
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Characters of output kept per command; the rest is counted but dropped
+const MAX_OUTPUT_CHARS: usize = 20_000;
+
+/// How a command ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ended {
+    /// It finished with this exit code
+    Exited(i32),
+    /// It ran past the timeout and was terminated; the shell survived
+    TimedOut,
+    /// The shell itself is gone: the command ran `exit`, or it ran past the
+    /// timeout in the shell itself (a builtin loop) or ignored SIGTERM
+    ShellGone,
+}
+
+/// What a command printed and how it ended
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    /// stdout and stderr as the terminal showed them
+    pub text: String,
+    /// Characters dropped beyond `MAX_OUTPUT_CHARS`
+    pub truncated_chars: usize,
+    pub ended: Ended,
+}
+
+/// A bash process on a pseudo-terminal
+pub struct ShellSession {
+    child: Child,
+    /// Master side of the terminal, for typing into the shell
+    input: File,
+    /// Output read from the terminal by a reader thread
+    output: mpsc::UnboundedReceiver<Vec<u8>>,
+    /// Output received but not yet consumed
+    pending: Vec<u8>,
+    /// Printed after each command; unique to this session
+    marker: String,
+    last_used: Instant,
+    /// A command was abandoned partway, which closed the shell
+    interrupted: bool,
+}
+
+impl ShellSession {
+    /// Start a shell under `sandbox` and wait until it takes commands
+    pub async fn start(sandbox: &Sandbox) -> Result<Self> {
+        let pty = nix::pty::openpty(None, None).context("Failed to open a pseudo-terminal")?;
+        quiet_terminal(&pty.slave)?;
+
+        let mut cmd = sandbox.command(SHELL)?;
+        cmd.stdin(Stdio::from(pty.slave.try_clone()?))
+            .stdout(Stdio::from(pty.slave.try_clone()?))
+            .stderr(Stdio::from(pty.slave))
+            .env("TERM", "dumb");
+        // SAFETY: only async-signal-safe calls between fork and exec
+        unsafe {
+            cmd.pre_exec(|| {
+                // Own session with the terminal as its controlling tty, so
+                // job control works and each command gets a process group
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = cmd.spawn().context("Failed to start the shell")?;
+        // `cmd` holds our last copy of the slave side; without it the reader
+        // sees EIO once the shell and its jobs exit
+        drop(cmd);
+
+        let input = File::from(pty.master);
+        let mut reader = input.try_clone()?;
+        let (tx, output) = mpsc::unbounded_channel();
+        std::thread::Builder::new()
+            .name("finch-shell".to_string())
+            .spawn(move || {
+                let mut buf = [0u8; 8192];
+                while let Ok(n @ 1..) = reader.read(&mut buf) {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            })?;
+
+        let mut session = Self {
+            child,
+            input,
+            output,
+            pending: Vec::new(),
+            marker: format!("__FINCH_{}", uuid::Uuid::new_v4().simple()),
+            last_used: Instant::now(),
+            interrupted: false,
+        };
+        // Interactive bash sets its own prompts whatever the environment
+        // says; clear them, swallowing whatever was printed on startup
+        let ready = session
+            .run(
+                "PS1= PS2= PROMPT_COMMAND=; unset HISTFILE",
+                SETTLE_TIMEOUT,
+                None,
+            )
+            .await?;
+        if ready.ended != Ended::Exited(0) {
+            bail!("Shell did not start: {}", ready.text.trim());
+        }
+        Ok(session)
+    }
+
+    /// Time since the last command finished (or the shell started)
+    pub fn idle_for(&self) -> Duration {
+        self.last_used.elapsed()
+    }
+
+    /// Whether a command was dropped before it finished; the shell was
+    /// hung up then and takes no more commands
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// Run `command` in the shell.  `live` gets each output line as it
+    /// arrives.
+    pub async fn run(
+        &mut self,
+        command: &str,
+        timeout: Duration,
+        live: Option<&Arc<dyn Fn(String) + Send + Sync>>,
+    ) -> Result<CommandOutput> {
+        let script = self.script(command);
+        if let Err(e) = self.input.write_all(script.as_bytes()) {
+            return Ok(CommandOutput {
+                text: format!("Shell is gone: {}", e),
+                truncated_chars: 0,
+                ended: Ended::ShellGone,
+            });
+        }
+
+        // Cleared only if the loop below runs to the end
+        self.interrupted = true;
+        let mut hang_up = HangUpOnDrop(self.child.id());
+
+        let mut collected = Collected::default();
+        let mut deadline = tokio::time::Instant::now() + timeout;
+        let mut terminated = false;
+        let ended = loop {
+            if let Some(status) = self.take_lines(&mut collected, live) {
+                break if terminated {
+                    Ended::TimedOut
+                } else {
+                    Ended::Exited(status)
+                };
+            }
+            match tokio::time::timeout_at(deadline, self.output.recv()).await {
+                Ok(Some(bytes)) => self.pending.extend_from_slice(&bytes),
+                Ok(None) => {
+                    // Keep what was printed before the shell went away
+                    let rest = std::mem::take(&mut self.pending);
+                    collected.push(&clean_line(&rest), false);
+                    break Ended::ShellGone;
+                }
+                // SIGTERM rather than Ctrl+C: bash drops the rest of the
+                // line, status report included, when a job dies of SIGINT
+                Err(_) if !terminated && self.terminate_foreground_job() => {
+                    terminated = true;
+                    deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+                }
+                Err(_) => {
+                    self.kill();
+                    break Ended::ShellGone;
+                }
+            }
+        };
+        hang_up.0 = None;
+        self.interrupted = false;
+        self.last_used = Instant::now();
+        Ok(CommandOutput {
+            text: collected.text,
+            truncated_chars: collected.dropped,
+            ended,
+        })
+    }
+
+    /// Stop the shell and everything it started.  Like closing a terminal
+    /// window: bash passes the SIGHUP on to its jobs before exiting.
+    pub fn kill(&mut self) {
+        if let Some(pid) = self.child.id() {
+            hang_up(pid);
+        }
+    }
+
+    /// SIGTERM the job in the foreground of the terminal.  `false` if the
+    /// shell itself is in the foreground, so only killing it would help.
+    fn terminate_foreground_job(&self) -> bool {
+        // SAFETY: tcgetpgrp(3) on our own terminal fd, kill(2) of that group
+        unsafe {
+            let job = libc::tcgetpgrp(self.input.as_raw_fd());
+            let shell = self.child.id().map_or(-1, |pid| pid as libc::pid_t);
+            if job <= 0 || job == shell {
+                return false;
+            }
+            libc::kill(-job, libc::SIGTERM) == 0
+        }
+    }
+
+    /// What gets typed into the shell to run `command`
+    fn script(&self, command: &str) -> String {
+        let (head, tail) = self.marker.split_at(8);
+        let mut script = String::from("__finch_cmd=''\n");
+        let mut start = 0;
+        while start < command.len() {
+            let mut end = (start + CHUNK_BYTES).min(command.len());
+            while !command.is_char_boundary(end) {
+                end += 1;
+            }
+            script.push_str("__finch_cmd+=");
+            script.push_str(&ansi_c_quote(&command[start..end]));
+            script.push('\n');
+            start = end;
+        }
+        // The marker is split so it can't be matched in anything typed
+        script.push_str(&format!(
+            "{{ eval \"$__finch_cmd\"; }} </dev/null; \
+             printf '%s%s:%d\\n' '{}' '{}' \"$?\"\n",
+            head, tail
+        ));
+        script
+    }
+
+    /// Move complete lines from `pending` into `collected`.  Returns the exit
+    /// status once the marker line has been read.
+    fn take_lines(
+        &mut self,
+        collected: &mut Collected,
+        live: Option<&Arc<dyn Fn(String) + Send + Sync>>,
+    ) -> Option<i32> {
+        while let Some(newline) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            let line = clean_line(&line[..line.len() - 1]);
+            if let Some(at) = line.find(&self.marker) {
+                // Output that didn't end in a newline shares the marker's line
+                collected.push(&line[..at], false);
+                let status = line[at + self.marker.len()..].trim_start_matches(':');
+                return Some(status.trim().parse().unwrap_or(-1));
+            }
+            if let Some(live) = live {
+                live(line.clone());
+            }
+            collected.push(&line, true);
+        }
+        None
+    }
+}
+
+impl Drop for ShellSession {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// Hangs up the shell with this pid when dropped, unless emptied first
+struct HangUpOnDrop(Option<u32>);
+
+impl Drop for HangUpOnDrop {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0 {
+            hang_up(pid);
+        }
+    }
+}
+
+/// SIGHUP the process group of the shell `pid`
+fn hang_up(pid: u32) {
+    // SAFETY: plain kill(2) of the shell's process group
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGHUP);
+    }
+}
+
+#[derive(Default)]
+struct Collected {
+    text: String,
+    kept_chars: usize,
+    dropped: usize,
+}
+
+impl Collected {
+    fn push(&mut self, line: &str, newline: bool) {
+        let chars = line.chars().count() + newline as usize;
+        if self.kept_chars + chars > MAX_OUTPUT_CHARS {
+            self.dropped += chars;
+            return;
+        }
+        self.kept_chars += chars;
+        self.text.push_str(line);
+        if newline {
+            self.text.push('\n');
+        }
+    }
+}
+
+/// No echo of typed input and no `\r` added to output
+fn quiet_terminal(slave: &OwnedFd) -> Result<()> {
+    let mut attrs = termios::tcgetattr(slave).context("Failed to read terminal settings")?;
+    attrs.local_flags.remove(LocalFlags::ECHO);
+    attrs.output_flags.remove(OutputFlags::ONLCR);
+    termios::tcsetattr(slave, SetArg::TCSANOW, &attrs)
+        .context("Failed to change terminal settings")?;
+    Ok(())
+}
+
+/// `text` as a bash `$'…'` string
+fn ansi_c_quote(text: &str) -> String {
+    let mut quoted = String::from("$'");
+    for b in text.bytes() {
+        match b {
+            b'\'' | b'\\' => quoted.push_str(&format!("\\x{:02x}", b)),
+            0x20..=0x7e => quoted.push(b as char),
+            _ => quoted.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// A line of terminal output as text: escape sequences removed, and only
+/// what is left visible after carriage returns (progress bars)
+fn clean_line(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.strip_suffix('\r').unwrap_or(&text);
+    let text = text.rsplit('\r').next().unwrap_or_default();
+    let mut cleaned = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            cleaned.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn shell() -> ShellSession {
+        ShellSession::start(&Sandbox::Host).await.unwrap()
+    }
+
+    async fn run(shell: &mut ShellSession, command: &str) -> CommandOutput {
+        shell
+            .run(command, Duration::from_secs(20), None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_state_carries_over_between_commands() {
+        let mut shell = shell().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        run(&mut shell, &format!("cd '{}'", dir.path().display())).await;
+        run(&mut shell, "export GREETING=hello; touch marker.txt").await;
+        let out = run(&mut shell, "echo \"$GREETING from $(pwd)\"; ls").await;
+        assert_eq!(out.ended, Ended::Exited(0));
+        assert!(out
+            .text
+            .starts_with(&format!("hello from {}\n", dir.path().display())));
+        assert!(out.text.contains("marker.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_exit_codes_stderr_and_unterminated_output() {
+        let mut shell = shell().await;
+        let out = run(&mut shell, "echo oops >&2; false").await;
+        assert_eq!(out.ended, Ended::Exited(1));
+        assert_eq!(out.text, "oops\n");
+
+        let out = run(&mut shell, "printf 'no newline'").await;
+        assert_eq!(out.text, "no newline");
+        assert_eq!(out.ended, Ended::Exited(0));
+    }
+
+    #[tokio::test]
+    async fn test_long_and_tricky_commands() {
+        let mut shell = shell().await;
+        // Longer than a tty line, with quotes, backslashes and a heredoc
+        let long = "x".repeat(10_000);
+        let command = format!(
+            "cat <<'EOF' | wc -c\n{}\nEOF\necho 'it'\\''s' \"a\\\\b\" é",
+            long
+        );
+        let out = run(&mut shell, &command).await;
+        assert_eq!(out.text, "10001\nit's a\\b é\n");
+
+        // Commands reading stdin get EOF instead of the next command
+        let out = run(&mut shell, "cat; echo done").await;
+        assert_eq!(out.text, "done\n");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_terminates_command_and_shell_survives() {
+        let mut shell = shell().await;
+        run(&mut shell, "KEEP=1").await;
+        let out = shell
+            .run("echo started; sleep 30", Duration::from_millis(500), None)
+            .await
+            .unwrap();
+        assert_eq!(out.ended, Ended::TimedOut);
+        assert!(out.text.contains("started"));
+
+        let out = run(&mut shell, "echo $KEEP").await;
+        assert_eq!(out.text, "1\n");
+    }
+
+    #[tokio::test]
+    async fn test_dropped_command_hangs_up_the_shell() {
+        let mut shell = shell().await;
+        let dir = tempfile::tempdir().unwrap();
+        let late = dir.path().join("late.txt");
+        let command = format!("sleep 1 && touch '{}'", late.display());
+
+        let run = shell.run(&command, Duration::from_secs(20), None);
+        assert!(tokio::time::timeout(Duration::from_millis(200), run)
+            .await
+            .is_err());
+        assert!(shell.interrupted());
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!late.exists(), "the cancelled command kept running");
+    }
+
+    #[tokio::test]
+    async fn test_exit_ends_the_shell() {
+        let mut shell = shell().await;
+        let out = run(&mut shell, "echo bye; exit 3").await;
+        assert_eq!(out.ended, Ended::ShellGone);
+        assert!(out.text.contains("bye"));
+    }
+
+    #[test]
+    fn test_clean_line() {
+        assert_eq!(clean_line(b"\x1b[1;32mok\x1b[0m\r"), "ok");
+        assert_eq!(clean_line(b"10%\r50%\r100%"), "100%");
+        assert_eq!(clean_line(b"\x1b]0;title\x07text"), "text");
+        assert_eq!(ansi_c_quote("a'b\\c\n"), "$'a\\x27b\\x5cc\\x0a'");
+    }
+}
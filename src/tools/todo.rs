@@ -3,8 +3,18 @@
 // TodoList is never persisted to disk — it lives only for the duration of the
 // REPL session.  The LLM writes to it via TodoWrite and reads from it via
 // TodoRead.  The TUI renders the active (non-completed) items in the live area.
+//
+// Todos worth more than the session can be promoted (`/promote`, TodoPromote)
+// to pending tasks in the agent backlog (tasks.toml) for `finch agent` to pick
+// up.  The list remembers which task each promoted todo became, and once the
+// agent marks that task done the todo is checked off and stays in the live
+// area with a ✓.
 
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::agent::backlog::{TaskBacklog, TaskPriority, TaskStatus};
 
 /// Priority of a task item
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -45,11 +55,16 @@ pub struct TodoItem {
 #[derive(Debug, Default)]
 pub struct TodoList {
     items: Vec<TodoItem>,
+    /// Backlog task id of each promoted todo, by todo id
+    promoted: BTreeMap<String, String>,
 }
 
 impl TodoList {
     /// Replace the entire list atomically (the semantics of TodoWrite).
+    /// Todos keep their backlog task as long as their id stays in the list.
     pub fn replace_all(&mut self, items: Vec<TodoItem>) {
+        self.promoted
+            .retain(|id, _| items.iter().any(|item| &item.id == id));
         self.items = items;
     }
 
@@ -85,6 +100,85 @@ impl TodoList {
         in_progress
     }
 
+    /// Promoted todos that are done, shown checked off in the TUI
+    pub fn checked_off_items(&self) -> Vec<&TodoItem> {
+        self.items
+            .iter()
+            .filter(|i| i.status == TodoStatus::Completed && self.promoted.contains_key(&i.id))
+            .collect()
+    }
+
+    /// The backlog task `todo_id` was promoted to
+    pub fn backlog_task(&self, todo_id: &str) -> Option<&str> {
+        self.promoted.get(todo_id).map(String::as_str)
+    }
+
+    pub fn has_promoted(&self) -> bool {
+        !self.promoted.is_empty()
+    }
+
+    /// Add todos to `backlog` as pending tasks: those in `ids`, or when
+    /// `ids` is empty every open todo not promoted yet.  Todos promoted
+    /// before are skipped.  Returns (todo id, task id) for each new task.
+    pub fn promote(
+        &mut self,
+        ids: &[String],
+        backlog: &mut TaskBacklog,
+        repo: Option<String>,
+    ) -> Result<Vec<(String, String)>> {
+        if let Some(missing) = ids
+            .iter()
+            .find(|id| !self.items.iter().any(|item| &item.id == *id))
+        {
+            bail!("No todo with id {}", missing);
+        }
+        let chosen: Vec<TodoItem> = self
+            .items
+            .iter()
+            .filter(|item| {
+                if ids.is_empty() {
+                    item.status != TodoStatus::Completed
+                } else {
+                    ids.contains(&item.id)
+                }
+            })
+            .filter(|item| !self.promoted.contains_key(&item.id))
+            .cloned()
+            .collect();
+
+        let mut added = Vec::new();
+        for item in chosen {
+            let priority = match item.priority {
+                TodoPriority::High => TaskPriority::High,
+                TodoPriority::Medium => TaskPriority::Normal,
+                TodoPriority::Low => TaskPriority::Low,
+            };
+            let task_id = backlog.add(&item.content, priority, repo.clone())?;
+            self.promoted.insert(item.id.clone(), task_id.clone());
+            added.push((item.id, task_id));
+        }
+        Ok(added)
+    }
+
+    /// Check off the promoted todos whose backlog task is done, returning
+    /// what they said
+    pub fn sync_from_backlog(&mut self, backlog: &TaskBacklog) -> Vec<String> {
+        let mut finished = Vec::new();
+        for item in &mut self.items {
+            let Some(task_id) = self.promoted.get(&item.id) else {
+                continue;
+            };
+            let done = backlog
+                .get(task_id)
+                .is_some_and(|task| task.status == TaskStatus::Done);
+            if done && item.status != TodoStatus::Completed {
+                item.status = TodoStatus::Completed;
+                finished.push(item.content.clone());
+            }
+        }
+        finished
+    }
+
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
@@ -185,6 +279,53 @@ mod tests {
         assert!(list.active_items().is_empty());
     }
 
+    #[test]
+    fn test_promote_and_check_off_from_backlog() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.toml");
+        let mut backlog = TaskBacklog::load(path.clone()).unwrap();
+        let mut list = TodoList::default();
+        list.replace_all(vec![
+            item("1", TodoStatus::Pending, TodoPriority::High),
+            item("2", TodoStatus::InProgress, TodoPriority::Medium),
+            item("3", TodoStatus::Completed, TodoPriority::Low),
+        ]);
+
+        assert!(list
+            .promote(&["9".to_string()], &mut backlog, None)
+            .is_err());
+        let added = list
+            .promote(&["2".to_string()], &mut backlog, Some("/work/app".into()))
+            .unwrap();
+        assert_eq!(added, vec![("2".to_string(), "001".to_string())]);
+        // The rest of the open todos; "2" isn't added twice, "3" is done
+        let added = list.promote(&[], &mut backlog, None).unwrap();
+        assert_eq!(added, vec![("1".to_string(), "002".to_string())]);
+        assert_eq!(backlog.get("002").unwrap().priority, TaskPriority::High);
+        assert_eq!(
+            backlog.get("001").unwrap().repo.as_deref(),
+            Some("/work/app")
+        );
+
+        // The agent finishes task 001; a TodoWrite that keeps the id keeps the link
+        list.replace_all(vec![
+            item("1", TodoStatus::Pending, TodoPriority::High),
+            item("2", TodoStatus::InProgress, TodoPriority::Medium),
+        ]);
+        let mut agent_side = TaskBacklog::load(path.clone()).unwrap();
+        agent_side.mark_done("001").unwrap();
+        let finished = list.sync_from_backlog(&TaskBacklog::load(path).unwrap());
+        assert_eq!(finished, vec!["Task 2".to_string()]);
+        assert_eq!(list.active_items().len(), 1);
+        assert_eq!(list.checked_off_items()[0].id, "2");
+        assert_eq!(list.backlog_task("1"), Some("002"));
+
+        // Dropping a todo forgets its task
+        list.replace_all(vec![item("1", TodoStatus::Pending, TodoPriority::High)]);
+        assert!(list.backlog_task("2").is_none());
+        assert!(list.has_promoted());
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         let item = TodoItem {
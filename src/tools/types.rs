@@ -11,6 +11,7 @@ use crate::cli::ReplMode;
 use crate::local::LocalGenerator;
 use crate::models::tokenizer::TextTokenizer;
 use crate::training::batch_trainer::BatchTrainer;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -147,6 +148,28 @@ impl ToolResult {
     }
 }
 
+/// A tool call the user cancelled (Ctrl+C) before it finished
+///
+/// Sent back as the call's error result, so the model learns the call was
+/// stopped rather than that it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCancelled {
+    pub tool_name: String,
+}
+
+impl std::fmt::Display for ToolCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cancelled by the user: '{}' was stopped before it finished, \
+             and any process it started was killed",
+            self.tool_name
+        )
+    }
+}
+
+impl std::error::Error for ToolCancelled {}
+
 /// Extended ContentBlock enum to support tool use
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -207,10 +230,74 @@ impl ContentBlock {
     }
 }
 
+/// Start of an output line that hands an image to the model along with the
+/// tool's text, e.g. `[image: /tmp/finch-attachments/screenshot-….png]`
+pub const IMAGE_ATTACHMENT_PREFIX: &str = "[image: ";
+
+/// Where tools put images they attach to their output.  Only files in here
+/// are attached, so a tool echoing `[image: …]` can't send arbitrary files.
+pub fn attachment_dir() -> PathBuf {
+    std::env::temp_dir().join("finch-attachments")
+}
+
+/// Output line attaching the image at `path` (inside `attachment_dir()`)
+pub fn image_attachment_line(path: &Path) -> String {
+    format!("{}{}]", IMAGE_ATTACHMENT_PREFIX, path.display())
+}
+
+/// Images attached to a tool's output, as `(media_type, path)`.  Lines
+/// naming anything but a PNG or JPEG inside `attachment_dir()` are ignored.
+pub fn image_attachments(content: &str) -> Vec<(&'static str, PathBuf)> {
+    let Ok(dir) = attachment_dir().canonicalize() else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let path = line
+                .trim()
+                .strip_prefix(IMAGE_ATTACHMENT_PREFIX)?
+                .strip_suffix(']')?;
+            let path = Path::new(path).canonicalize().ok()?;
+            if !path.starts_with(&dir) {
+                return None;
+            }
+            let media_type = match path.extension()?.to_str()? {
+                "png" => "image/png",
+                "jpg" | "jpeg" => "image/jpeg",
+                _ => return None,
+            };
+            Some((media_type, path))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_image_attachments_only_from_attachment_dir() {
+        let dir = attachment_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = dir.join(format!("test-{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&image, b"png").unwrap();
+        let outside = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+
+        let content = format!(
+            "Captured the screen\n{}\n{}\n[image: {}/../etc/passwd.png]",
+            image_attachment_line(&image),
+            image_attachment_line(outside.path()),
+            dir.display()
+        );
+        let attached = image_attachments(&content);
+        assert_eq!(attached.len(), 1);
+        assert_eq!(attached[0].0, "image/png");
+        assert_eq!(attached[0].1, image.canonicalize().unwrap());
+
+        std::fs::remove_file(&image).unwrap();
+    }
+
     #[test]
     fn test_tool_use_id_generation() {
         let id = ToolUse::generate_id();
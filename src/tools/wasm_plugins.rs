@@ -0,0 +1,708 @@
+// Third-party tools as WebAssembly plugins
+//
+// Every `*.wasm` file in the project's `.finch/plugins/` and in
+// `~/.finch/plugins/` is loaded as a tool at startup (feature
+// `wasm-plugins`).  A project plugin wins over a global one of the same name;
+// neither can replace a built-in tool.  Plugin tools go through the same
+// permission rules and approval prompts as every other tool.
+//
+// ABI — a core WebAssembly module (any language that targets
+// wasm32-unknown-unknown) exporting:
+//
+//   memory                                   linear memory
+//   finch_alloc(len: i32) -> i32             room for `len` bytes the host writes
+//   finch_name() -> i64                      tool name, [a-z0-9_]+
+//   finch_description() -> i64               description shown to the model
+//   finch_schema() -> i64                    JSON Schema of the input object
+//   finch_execute(ptr: i32, len: i32) -> i64 input JSON in, output text out
+//
+// Strings are UTF-8 and returned as `ptr << 32 | len`.  Each call runs in a
+// fresh instance, with a fuel (instruction) budget and a memory cap.  The
+// host functions, imported from module "finch", are all the plugin can reach:
+//
+//   log(ptr, len)                   debug log line
+//   fail(ptr, len)                  make this call fail with the message
+//   read_file(ptr, len) -> i64      file inside the project (-1 if refused)
+//   list_dir(ptr, len) -> i64       JSON array of names in a project dir
+//   http_get(ptr, len) -> i64       response body; only hosts granted below
+//
+// No network by default; hosts are granted per plugin:
+//
+//   [wasm_plugins]
+//   fuel = 5000000000
+//   memory_mb = 64
+//
+//   [wasm_plugins.grants.jira_lookup]
+//   net = ["*.atlassian.net"]
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::tools::registry::Tool;
+
+/// Plugin directory, relative to the project and to the home directory
+pub const PLUGIN_DIR: &str = ".finch/plugins";
+
+static SETTINGS: OnceLock<WasmPluginsConfig> = OnceLock::new();
+
+/// Apply `[wasm_plugins]`.  Called once when the config is loaded; later
+/// calls are ignored.
+pub fn init(config: &WasmPluginsConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// `[wasm_plugins]` in config.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WasmPluginsConfig {
+    /// Load plugins at startup
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Instructions (roughly) a plugin may run per call
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+    /// Linear memory a plugin may grow to, in MB
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64,
+    /// Extra capabilities by plugin (tool) name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub grants: BTreeMap<String, PluginGrant>,
+}
+
+/// Capabilities granted to one plugin
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginGrant {
+    /// Hosts `http_get` may reach (`api.example.com` or `*.example.com`)
+    #[serde(default)]
+    pub net: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_fuel() -> u64 {
+    5_000_000_000
+}
+
+fn default_memory_mb() -> u64 {
+    64
+}
+
+impl Default for WasmPluginsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            fuel: default_fuel(),
+            memory_mb: default_memory_mb(),
+            grants: BTreeMap::new(),
+        }
+    }
+}
+
+impl WasmPluginsConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Where plugins are looked for, project first
+pub fn plugin_dirs(project: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![project.join(PLUGIN_DIR)];
+    if let Some(home) = dirs::home_dir() {
+        let global = home.join(PLUGIN_DIR);
+        if global != dirs[0] {
+            dirs.push(global);
+        }
+    }
+    dirs
+}
+
+/// `*.wasm` files in `dir`, sorted
+fn plugin_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// `requested` (relative to `project`, or absolute) if it resolves to a
+/// path inside the project.  Symlinks are followed before the check.
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+fn resolve_in_project(project: &Path, requested: &str) -> Result<PathBuf> {
+    let root = project.canonicalize()?;
+    let path = root.join(requested).canonicalize()?;
+    if !path.starts_with(&root) {
+        anyhow::bail!("{} is outside the project", requested);
+    }
+    Ok(path)
+}
+
+/// Tools for the plugins installed for `project`.  Plugins that fail to
+/// load are logged and skipped.
+#[cfg(feature = "wasm-plugins")]
+pub fn load_plugins(project: &Path) -> Vec<Box<dyn Tool>> {
+    let config = SETTINGS.get().cloned().unwrap_or_default();
+    if !config.enabled {
+        return Vec::new();
+    }
+    let engine = match runtime::engine() {
+        Ok(engine) => engine,
+        Err(e) => {
+            tracing::warn!("[plugins] WebAssembly runtime unavailable: {:#}", e);
+            return Vec::new();
+        }
+    };
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for path in plugin_dirs(project).iter().flat_map(|d| plugin_files(d)) {
+        match runtime::WasmTool::load(&engine, &path, &config, project) {
+            Ok(tool) if tools.iter().any(|t| t.name() == tool.name()) => tracing::info!(
+                "[plugins] {} is shadowed by another plugin named {}",
+                path.display(),
+                tool.name()
+            ),
+            Ok(tool) => {
+                tracing::info!("[plugins] loaded {} from {}", tool.name(), path.display());
+                tools.push(Box::new(tool));
+            }
+            Err(e) => tracing::warn!("[plugins] skipping {}: {:#}", path.display(), e),
+        }
+    }
+    tools
+}
+
+/// Without plugin support, installed plugins are only reported
+#[cfg(not(feature = "wasm-plugins"))]
+pub fn load_plugins(project: &Path) -> Vec<Box<dyn Tool>> {
+    let count: usize = plugin_dirs(project)
+        .iter()
+        .map(|dir| plugin_files(dir).len())
+        .sum();
+    if count > 0 {
+        tracing::warn!(
+            "[plugins] ignoring {} plugin(s): finch was built without the wasm-plugins feature",
+            count
+        );
+    }
+    Vec::new()
+}
+
+#[cfg(feature = "wasm-plugins")]
+mod runtime {
+    use super::{resolve_in_project, PluginGrant, WasmPluginsConfig};
+    use crate::tools::registry::Tool;
+    use crate::tools::types::{ToolContext, ToolInputSchema};
+    use anyhow::{bail, Context, Result};
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use wasmtime::{
+        AsContext, AsContextMut, Caller, Config, Engine, Extern, Instance, Linker, Memory, Module,
+        Store, StoreLimits, StoreLimitsBuilder, Trap, TypedFunc,
+    };
+
+    /// Largest file `read_file` hands to a plugin
+    const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+    /// Time allowed for an `http_get` request
+    const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub fn engine() -> Result<Engine> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config)
+    }
+
+    struct HostState {
+        plugin: String,
+        project: PathBuf,
+        grant: PluginGrant,
+        limits: StoreLimits,
+        /// Message passed to `fail` during this call
+        error: Option<String>,
+    }
+
+    fn pack(ptr: u32, len: u32) -> i64 {
+        (((ptr as u64) << 32) | len as u64) as i64
+    }
+
+    fn read_bytes(ctx: impl AsContext, memory: Memory, ptr: i32, len: i32) -> Result<Vec<u8>> {
+        let mut bytes = vec![0u8; len as u32 as usize];
+        memory
+            .read(ctx, ptr as u32 as usize, &mut bytes)
+            .context("Plugin passed a pointer outside its memory")?;
+        Ok(bytes)
+    }
+
+    fn read_string(ctx: impl AsContext, memory: Memory, ptr: i32, len: i32) -> Result<String> {
+        String::from_utf8(read_bytes(ctx, memory, ptr, len)?)
+            .context("Plugin passed a string that isn't UTF-8")
+    }
+
+    /// Copy `bytes` into memory the plugin allocates, returning the packed
+    /// pointer and length
+    fn write_bytes(
+        mut ctx: impl AsContextMut<Data = HostState>,
+        memory: Memory,
+        alloc: TypedFunc<i32, i32>,
+        bytes: &[u8],
+    ) -> Result<i64> {
+        let len = i32::try_from(bytes.len()).context("Value too large for the plugin")?;
+        let ptr = alloc.call(&mut ctx, len)?;
+        memory
+            .write(&mut ctx, ptr as u32 as usize, bytes)
+            .context("finch_alloc returned memory the plugin doesn't have")?;
+        Ok(pack(ptr as u32, len as u32))
+    }
+
+    fn caller_exports(caller: &mut Caller<'_, HostState>) -> Result<(Memory, TypedFunc<i32, i32>)> {
+        let memory = caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .context("Plugin exports no memory")?;
+        let alloc = caller
+            .get_export("finch_alloc")
+            .and_then(Extern::into_func)
+            .context("Plugin exports no finch_alloc")?
+            .typed::<i32, i32>(&caller)?;
+        Ok((memory, alloc))
+    }
+
+    /// Run `host` on the string argument and hand its result to the plugin,
+    /// or return -1 (after logging why) when it fails
+    fn call_host(
+        mut caller: Caller<'_, HostState>,
+        ptr: i32,
+        len: i32,
+        what: &str,
+        host: impl FnOnce(&HostState, &str) -> Result<Vec<u8>>,
+    ) -> Result<i64> {
+        let (memory, alloc) = caller_exports(&mut caller)?;
+        let argument = read_string(&caller, memory, ptr, len)?;
+        match host(caller.data(), &argument) {
+            Ok(bytes) => write_bytes(&mut caller, memory, alloc, &bytes),
+            Err(e) => {
+                tracing::debug!(
+                    "[plugins] {}: {}({}) refused: {:#}",
+                    caller.data().plugin,
+                    what,
+                    argument,
+                    e
+                );
+                Ok(-1)
+            }
+        }
+    }
+
+    fn linker(engine: &Engine) -> Result<Linker<HostState>> {
+        let mut linker = Linker::new(engine);
+        linker.func_wrap(
+            "finch",
+            "log",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<()> {
+                let (memory, _) = caller_exports(&mut caller)?;
+                let line = read_string(&caller, memory, ptr, len)?;
+                tracing::debug!("[plugins] {}: {}", caller.data().plugin, line);
+                Ok(())
+            },
+        )?;
+        linker.func_wrap(
+            "finch",
+            "fail",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<()> {
+                let (memory, _) = caller_exports(&mut caller)?;
+                let message = read_string(&caller, memory, ptr, len)?;
+                caller.data_mut().error = Some(message);
+                Ok(())
+            },
+        )?;
+        linker.func_wrap(
+            "finch",
+            "read_file",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                call_host(caller, ptr, len, "read_file", |state, requested| {
+                    let path = resolve_in_project(&state.project, requested)?;
+                    let size = std::fs::metadata(&path)?.len();
+                    if size > MAX_READ_BYTES {
+                        bail!("{} bytes is over the {} byte limit", size, MAX_READ_BYTES);
+                    }
+                    Ok(std::fs::read(path)?)
+                })
+            },
+        )?;
+        linker.func_wrap(
+            "finch",
+            "list_dir",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                call_host(caller, ptr, len, "list_dir", |state, requested| {
+                    let path = resolve_in_project(&state.project, requested)?;
+                    let mut names: Vec<String> = std::fs::read_dir(path)?
+                        .flatten()
+                        .map(|entry| {
+                            let name = entry.file_name().to_string_lossy().into_owned();
+                            match entry.file_type() {
+                                Ok(t) if t.is_dir() => format!("{}/", name),
+                                _ => name,
+                            }
+                        })
+                        .collect();
+                    names.sort();
+                    Ok(serde_json::to_vec(&names)?)
+                })
+            },
+        )?;
+        linker.func_wrap(
+            "finch",
+            "http_get",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                call_host(caller, ptr, len, "http_get", |state, url| {
+                    let parsed = reqwest::Url::parse(url)?;
+                    let host = parsed.host_str().unwrap_or_default();
+                    if !crate::tools::implementations::http::host_allowed(&state.grant.net, host) {
+                        bail!("{} is not granted to this plugin", host);
+                    }
+                    // Plugins run on a blocking thread, which can wait on the runtime
+                    let response = tokio::runtime::Handle::current().block_on(async {
+                        crate::http::builder()
+                            .timeout(HTTP_TIMEOUT)
+                            .redirect(reqwest::redirect::Policy::none())
+                            .build()?
+                            .get(parsed)
+                            .send()
+                            .await?
+                            .error_for_status()?
+                            .bytes()
+                            .await
+                    })?;
+                    Ok(response.to_vec())
+                })
+            },
+        )?;
+        Ok(linker)
+    }
+
+    struct Plugin {
+        name: String,
+        description: String,
+        schema: ToolInputSchema,
+        module: Module,
+        linker: Linker<HostState>,
+        project: PathBuf,
+        grant: PluginGrant,
+        fuel: u64,
+        memory_bytes: usize,
+    }
+
+    /// A tool backed by a WebAssembly plugin
+    pub struct WasmTool {
+        plugin: Arc<Plugin>,
+    }
+
+    impl Plugin {
+        fn store(
+            &self,
+            engine: &Engine,
+            name: &str,
+            grant: PluginGrant,
+        ) -> Result<Store<HostState>> {
+            let mut store = Store::new(
+                engine,
+                HostState {
+                    plugin: name.to_string(),
+                    project: self.project.clone(),
+                    grant,
+                    limits: StoreLimitsBuilder::new()
+                        .memory_size(self.memory_bytes)
+                        .instances(1)
+                        .build(),
+                    error: None,
+                },
+            );
+            store.limiter(|state| &mut state.limits);
+            store.set_fuel(self.fuel)?;
+            Ok(store)
+        }
+
+        fn instantiate(&self, store: &mut Store<HostState>) -> Result<Instance> {
+            self.linker
+                .instantiate(&mut *store, &self.module)
+                .context("Failed to instantiate plugin")
+        }
+
+        /// Call `export` (no arguments) and read back the string it returns
+        fn string_export(
+            store: &mut Store<HostState>,
+            instance: &Instance,
+            export: &str,
+        ) -> Result<String> {
+            let memory = instance
+                .get_memory(&mut *store, "memory")
+                .context("Plugin exports no memory")?;
+            let packed = instance
+                .get_typed_func::<(), i64>(&mut *store, export)
+                .with_context(|| format!("Plugin exports no {}", export))?
+                .call(&mut *store, ())
+                .map_err(|e| trap_error(e, export))?;
+            read_string(&*store, memory, (packed >> 32) as i32, packed as i32)
+        }
+
+        fn execute(&self, input: &str) -> Result<String> {
+            let mut store = self.store(self.module.engine(), &self.name, self.grant.clone())?;
+            let instance = self.instantiate(&mut store)?;
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .context("Plugin exports no memory")?;
+            let alloc = instance.get_typed_func::<i32, i32>(&mut store, "finch_alloc")?;
+            let packed = write_bytes(&mut store, memory, alloc, input.as_bytes())?;
+            let output = instance
+                .get_typed_func::<(i32, i32), i64>(&mut store, "finch_execute")?
+                .call(&mut store, ((packed >> 32) as i32, packed as i32))
+                .map_err(|e| trap_error(e, &self.name))?;
+            if let Some(error) = store.data_mut().error.take() {
+                bail!("{}", error);
+            }
+            read_string(&store, memory, (output >> 32) as i32, output as i32)
+        }
+    }
+
+    fn trap_error(error: anyhow::Error, what: &str) -> anyhow::Error {
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => {
+                anyhow::anyhow!("{} ran out of fuel ([wasm_plugins] fuel)", what)
+            }
+            _ => error.context(format!("{} trapped", what)),
+        }
+    }
+
+    impl WasmTool {
+        /// Compile the plugin at `path` and read its name, description and
+        /// schema
+        pub fn load(
+            engine: &Engine,
+            path: &Path,
+            config: &WasmPluginsConfig,
+            project: &Path,
+        ) -> Result<Self> {
+            let mut plugin = Plugin {
+                name: String::new(),
+                description: String::new(),
+                schema: ToolInputSchema::simple(vec![]),
+                module: Module::from_file(engine, path)?,
+                linker: linker(engine)?,
+                project: project.to_path_buf(),
+                grant: PluginGrant::default(),
+                fuel: config.fuel,
+                memory_bytes: (config.memory_mb as usize).saturating_mul(1024 * 1024),
+            };
+
+            // Metadata is read with no capabilities granted
+            let mut store =
+                plugin.store(engine, &path.display().to_string(), PluginGrant::default())?;
+            let instance = plugin.instantiate(&mut store)?;
+            let name = Plugin::string_export(&mut store, &instance, "finch_name")?;
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                bail!(
+                    "Tool name `{}` must be lowercase letters, digits and _",
+                    name
+                );
+            }
+            let description = Plugin::string_export(&mut store, &instance, "finch_description")?;
+            let schema: Value = serde_json::from_str(&Plugin::string_export(
+                &mut store,
+                &instance,
+                "finch_schema",
+            )?)
+            .context("finch_schema is not JSON")?;
+            for export in ["finch_alloc", "finch_execute"] {
+                if instance.get_func(&mut store, export).is_none() {
+                    bail!("Plugin exports no {}", export);
+                }
+            }
+
+            plugin.schema = ToolInputSchema {
+                schema_type: "object".to_string(),
+                properties: schema
+                    .get("properties")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({})),
+                required: schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(|r| {
+                        r.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            };
+            plugin.grant = config.grants.get(&name).cloned().unwrap_or_default();
+            plugin.name = name;
+            plugin.description = description;
+            Ok(Self {
+                plugin: Arc::new(plugin),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Tool for WasmTool {
+        fn name(&self) -> &str {
+            &self.plugin.name
+        }
+
+        fn description(&self) -> &str {
+            &self.plugin.description
+        }
+
+        fn input_schema(&self) -> ToolInputSchema {
+            self.plugin.schema.clone()
+        }
+
+        async fn execute(&self, input: Value, _context: &ToolContext<'_>) -> Result<String> {
+            let plugin = Arc::clone(&self.plugin);
+            let input = input.to_string();
+            tokio::task::spawn_blocking(move || plugin.execute(&input))
+                .await
+                .context("Plugin call was cancelled")?
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// A plugin whose execute calls `body` (WAT instructions leaving an
+        /// i64) with the input at $ptr/$len; strings at 0 ("echo"), 16 (the
+        /// description), 64 (the schema) and 256 (`data`)
+        fn plugin(dir: &Path, data: &str, body: &str) -> PathBuf {
+            let wat = format!(
+                r#"(module
+  (import "finch" "read_file" (func $read_file (param i32 i32) (result i64)))
+  (import "finch" "fail" (func $fail (param i32 i32)))
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (data (i32.const 0) "echo")
+  (data (i32.const 16) "Echo the input")
+  (data (i32.const 64) "{{\"properties\":{{\"text\":{{\"type\":\"string\"}}}},\"required\":[\"text\"]}}")
+  (data (i32.const 256) "{data}")
+  (func (export "finch_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+  (func (export "finch_name") (result i64) (i64.const 4))
+  (func (export "finch_description") (result i64)
+    (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 14)))
+  (func (export "finch_schema") (result i64)
+    (i64.or (i64.shl (i64.const 64) (i64.const 32)) (i64.const 61)))
+  (func (export "finch_execute") (param $ptr i32) (param $len i32) (result i64)
+    {body}))"#,
+                data = data,
+                body = body
+            );
+            let path = dir.join("echo.wasm");
+            std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+            path
+        }
+
+        const ECHO: &str = "(i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32)) (i64.extend_i32_u (local.get $len)))";
+
+        fn read_data(len: usize) -> String {
+            format!("(call $read_file (i32.const 256) (i32.const {}))", len)
+        }
+
+        fn load(path: &Path, project: &Path, fuel: u64) -> WasmTool {
+            let config = WasmPluginsConfig {
+                fuel,
+                ..Default::default()
+            };
+            WasmTool::load(&engine().unwrap(), path, &config, project).unwrap()
+        }
+
+        #[test]
+        fn test_plugin_metadata_and_echo() {
+            let dir = tempfile::tempdir().unwrap();
+            let tool = load(&plugin(dir.path(), "", ECHO), dir.path(), 1_000_000);
+            assert_eq!(tool.name(), "echo");
+            assert_eq!(tool.description(), "Echo the input");
+            assert_eq!(tool.input_schema().required, vec!["text"]);
+            assert_eq!(
+                tool.plugin.execute(r#"{"text":"hi"}"#).unwrap(),
+                r#"{"text":"hi"}"#
+            );
+        }
+
+        #[test]
+        fn test_read_file_is_confined_to_the_project() {
+            let dir = tempfile::tempdir().unwrap();
+            let project = dir.path().join("project");
+            std::fs::create_dir(&project).unwrap();
+            std::fs::write(project.join("notes.txt"), "inside").unwrap();
+            std::fs::write(dir.path().join("secret.txt"), "outside").unwrap();
+
+            let inside = plugin(dir.path(), "notes.txt", &read_data(9));
+            assert_eq!(
+                load(&inside, &project, 1_000_000)
+                    .plugin
+                    .execute("{}")
+                    .unwrap(),
+                "inside"
+            );
+
+            // -1 comes back for a refused read; the plugin reports it
+            let refused = format!(
+                "(if (result i64) (i64.eq {} (i64.const -1)) (then (call $fail (i32.const 256) (i32.const 13)) (i64.const 0)) (else (i64.const 0)))",
+                read_data(13)
+            );
+            let outside = plugin(dir.path(), "../secret.txt", &refused);
+            let err = load(&outside, &project, 1_000_000)
+                .plugin
+                .execute("{}")
+                .unwrap_err();
+            assert_eq!(err.to_string(), "../secret.txt");
+        }
+
+        #[test]
+        fn test_runaway_plugin_runs_out_of_fuel() {
+            let dir = tempfile::tempdir().unwrap();
+            let spin = "(loop $spin (br $spin)) (i64.const 0)";
+            let tool = load(&plugin(dir.path(), "", spin), dir.path(), 100_000);
+            let err = tool.plugin.execute("{}").unwrap_err();
+            assert!(err.to_string().contains("ran out of fuel"), "{}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_in_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/lib.rs"), "").unwrap();
+        std::fs::write(dir.path().join("secret"), "").unwrap();
+
+        assert!(resolve_in_project(&project, "src/lib.rs").is_ok());
+        assert!(resolve_in_project(&project, "../secret").is_err());
+        assert!(
+            resolve_in_project(&project, &dir.path().join("secret").display().to_string()).is_err()
+        );
+        assert!(resolve_in_project(&project, "missing.txt").is_err());
+    }
+}
@@ -33,6 +33,9 @@ pub struct LoRATrainingConfig {
 
     /// Learning rate (default: 1e-4)
     pub learning_rate: f64,
+
+    /// CPU niceness for the training process (default: 0, unchanged)
+    pub nice: i32,
 }
 
 impl Default for LoRATrainingConfig {
@@ -66,6 +69,7 @@ impl Default for LoRATrainingConfig {
             epochs: 3,
             batch_size: 4,
             learning_rate: 1e-4,
+            nice: 0,
         }
     }
 }
@@ -135,6 +139,7 @@ impl LoRATrainingSubprocess {
             .arg(self.config.batch_size.to_string())
             .arg("--learning-rate")
             .arg(self.config.learning_rate.to_string());
+        crate::scheduling::priority::lower_child(&mut cmd, self.config.nice);
 
         // Redirect output to log file
         let log_path = output_adapter.with_extension("training.log");
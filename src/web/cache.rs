@@ -0,0 +1,184 @@
+// On-disk cache of fetched pages
+//
+// `pages/<sha256 of URL>.json` records when a URL was last fetched and the
+// hash of what it returned; `bodies/<content hash>.md` holds the converted
+// content, so URLs serving the same page share one file.  Entries older than
+// the TTL are ignored, and removed by `prune()`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PageEntry {
+    url: String,
+    content_hash: String,
+    /// Unix seconds
+    fetched_at: i64,
+}
+
+pub struct PageCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl PageCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// Cached content for `url` if it was fetched within the TTL
+    pub fn get(&self, url: &str) -> Option<String> {
+        self.get_at(url, chrono::Utc::now().timestamp())
+    }
+
+    fn get_at(&self, url: &str, now: i64) -> Option<String> {
+        let entry = self.read_entry(&self.entry_path(url))?;
+        if entry.url != url || !self.is_fresh(&entry, now) {
+            return None;
+        }
+        std::fs::read_to_string(self.body_path(&entry.content_hash)).ok()
+    }
+
+    /// Store `content` as the current version of `url`
+    pub fn put(&self, url: &str, content: &str) -> Result<()> {
+        self.put_at(url, content, chrono::Utc::now().timestamp())
+    }
+
+    fn put_at(&self, url: &str, content: &str, now: i64) -> Result<()> {
+        let content_hash = hex_digest(content);
+        let body_path = self.body_path(&content_hash);
+        if !body_path.exists() {
+            write_file(&body_path, content.as_bytes())?;
+        }
+        let entry = PageEntry {
+            url: url.to_string(),
+            content_hash,
+            fetched_at: now,
+        };
+        write_file(&self.entry_path(url), &serde_json::to_vec(&entry)?)
+    }
+
+    /// Delete expired entries and bodies no entry refers to; returns the
+    /// number of files removed
+    pub fn prune(&self) -> usize {
+        self.prune_at(chrono::Utc::now().timestamp())
+    }
+
+    fn prune_at(&self, now: i64) -> usize {
+        let mut removed = 0;
+        let mut live = HashSet::new();
+        for path in list(&self.dir.join("pages")) {
+            match self.read_entry(&path) {
+                Some(entry) if self.is_fresh(&entry, now) => {
+                    live.insert(entry.content_hash);
+                }
+                _ => removed += usize::from(std::fs::remove_file(&path).is_ok()),
+            }
+        }
+        for path in list(&self.dir.join("bodies")) {
+            let hash = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            if !live.contains(hash) {
+                removed += usize::from(std::fs::remove_file(&path).is_ok());
+            }
+        }
+        removed
+    }
+
+    fn is_fresh(&self, entry: &PageEntry, now: i64) -> bool {
+        now.saturating_sub(entry.fetched_at) < self.ttl.as_secs() as i64
+    }
+
+    fn read_entry(&self, path: &Path) -> Option<PageEntry> {
+        serde_json::from_slice(&std::fs::read(path).ok()?).ok()
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join("pages")
+            .join(format!("{}.json", hex_digest(url)))
+    }
+
+    fn body_path(&self, content_hash: &str) -> PathBuf {
+        self.dir.join("bodies").join(format!("{}.md", content_hash))
+    }
+}
+
+fn hex_digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Write via a temporary file so a concurrent reader never sees half a file
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let dir = path.parent().context("cache path has no parent")?;
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&tmp, bytes).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn list(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path().to_path_buf(), Duration::from_secs(HOUR as u64));
+        cache.put_at("https://a.example/", "# A", 1_000).unwrap();
+
+        assert_eq!(
+            cache.get_at("https://a.example/", 1_000 + HOUR - 1),
+            Some("# A".to_string())
+        );
+        assert_eq!(cache.get_at("https://a.example/", 1_000 + HOUR), None);
+        assert_eq!(cache.get_at("https://b.example/", 1_000), None);
+    }
+
+    #[test]
+    fn test_identical_content_is_stored_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path().to_path_buf(), Duration::from_secs(HOUR as u64));
+        cache.put_at("https://a.example/", "same", 0).unwrap();
+        cache.put_at("https://a.example/?ref=x", "same", 0).unwrap();
+
+        assert_eq!(list(&dir.path().join("pages")).len(), 2);
+        assert_eq!(list(&dir.path().join("bodies")).len(), 1);
+        assert_eq!(
+            cache.get_at("https://a.example/?ref=x", 0),
+            Some("same".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prune_removes_expired_entries_and_orphaned_bodies() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path().to_path_buf(), Duration::from_secs(HOUR as u64));
+        cache.put_at("https://old.example/", "old", 0).unwrap();
+        cache
+            .put_at("https://new.example/", "new", 2 * HOUR)
+            .unwrap();
+        // A changed page leaves its previous body behind
+        cache
+            .put_at("https://new.example/", "newer", 2 * HOUR)
+            .unwrap();
+
+        assert_eq!(cache.prune_at(2 * HOUR + 1), 3);
+        assert_eq!(
+            cache.get_at("https://new.example/", 2 * HOUR + 1),
+            Some("newer".to_string())
+        );
+        assert_eq!(list(&dir.path().join("bodies")).len(), 1);
+    }
+}
@@ -0,0 +1,393 @@
+// HTML to markdown for the model
+//
+// Keeps the page's main content (`main`, `article` or `[role=main]`, else
+// `body`) and drops scripts, navigation, sidebars, forms and anything whose
+// class or id marks it as page chrome, such as cookie banners, share buttons
+// and ads.  Headers and footers are dropped too unless they sit inside the
+// main content, where they usually hold the article's title and byline.
+// Links are made absolute against the page URL; images are dropped.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements that never carry article content
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "aside", "form", "button", "select", "svg",
+    "iframe", "img", "picture", "video", "audio", "canvas",
+];
+
+/// Site-wide chrome when found outside the main content
+const PAGE_CHROME: &[&str] = &["header", "footer"];
+
+/// class / id words that mark boilerplate blocks
+const BOILERPLATE_WORDS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "advertisement",
+    "banner",
+    "breadcrumb",
+    "breadcrumbs",
+    "consent",
+    "cookie",
+    "cookies",
+    "menu",
+    "modal",
+    "navbar",
+    "newsletter",
+    "popup",
+    "promo",
+    "share",
+    "sidebar",
+    "social",
+    "subscribe",
+];
+
+/// Markdown for the main content of `html`; relative links resolve against
+/// `base`
+pub fn html_to_markdown(html: &str, base: Option<&Url>) -> String {
+    let document = Html::parse_document(html);
+    let main = Selector::parse("main, article, [role=main]").expect("static selector");
+    let body = Selector::parse("body").expect("static selector");
+    let Some(root) = document
+        .select(&main)
+        .next()
+        .or_else(|| document.select(&body).next())
+    else {
+        return String::new();
+    };
+
+    let mut writer = Writer {
+        out: String::new(),
+        base,
+        lists: Vec::new(),
+        skip_chrome: root.value().name() == "body",
+    };
+    writer.children(root);
+    let mut markdown = tidy(&writer.out);
+
+    // Pages whose content has no heading get their <title>
+    if !markdown.starts_with("# ") {
+        let title = Selector::parse("title").expect("static selector");
+        if let Some(title) = document.select(&title).next() {
+            let title = collapse(&title.text().collect::<String>());
+            if !title.is_empty() {
+                markdown = format!("# {}\n\n{}", title, markdown);
+            }
+        }
+    }
+    markdown
+}
+
+struct Writer<'a> {
+    out: String,
+    base: Option<&'a Url>,
+    /// Open lists, innermost last: `None` for `ul`, the next number for `ol`
+    lists: Vec<Option<usize>>,
+    /// Drop `PAGE_CHROME` elements (the content root is the whole body)
+    skip_chrome: bool,
+}
+
+impl Writer<'_> {
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        if SKIPPED_ELEMENTS.contains(&name)
+            || (self.skip_chrome && PAGE_CHROME.contains(&name))
+            || is_boilerplate(element)
+        {
+            return;
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.block();
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+                self.children(element);
+                self.block();
+            }
+            "br" => self.out.push('\n'),
+            "hr" => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            "pre" => {
+                let code = element.text().collect::<String>();
+                self.block();
+                self.out.push_str("```\n");
+                self.out.push_str(code.trim_end_matches('\n'));
+                self.out.push_str("\n```");
+                self.block();
+            }
+            "code" => {
+                let code = collapse(&element.text().collect::<String>());
+                if !code.is_empty() {
+                    self.out.push_str(&format!("`{}`", code));
+                }
+            }
+            "strong" | "b" => self.wrap(element, "**"),
+            "em" | "i" => self.wrap(element, "*"),
+            "a" => self.link(element),
+            "ul" | "ol" => {
+                self.block();
+                self.lists.push((name == "ol").then_some(1));
+                self.children(element);
+                self.lists.pop();
+                self.block();
+            }
+            "li" => {
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.line();
+                self.out.push_str(&"  ".repeat(depth - 1));
+                self.out.push_str(&marker);
+                self.children(element);
+            }
+            "blockquote" => {
+                self.block();
+                let start = self.out.len();
+                self.children(element);
+                let quoted: Vec<String> = tidy(&self.out[start..])
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_string())
+                    .collect();
+                self.out.truncate(start);
+                self.out.push_str(&quoted.join("\n"));
+                self.block();
+            }
+            "table" => self.table(element),
+            "p" | "div" | "section" | "article" | "main" | "figure" | "figcaption" | "dl"
+            | "dt" | "dd" | "details" | "summary" => {
+                self.block();
+                self.children(element);
+                self.block();
+            }
+            _ => self.children(element),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) {
+            self.space();
+        }
+        let words = collapse(text);
+        if words.is_empty() {
+            return;
+        }
+        self.out.push_str(&words);
+        if text.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    /// `marker` around the element's text, or nothing when it has none
+    fn wrap(&mut self, element: ElementRef, marker: &str) {
+        let start = self.out.len();
+        self.children(element);
+        let inner = self.out[start..].trim().to_string();
+        self.out.truncate(start);
+        if !inner.is_empty() {
+            self.out.push_str(&format!("{}{}{}", marker, inner, marker));
+        }
+    }
+
+    fn link(&mut self, element: ElementRef) {
+        let start = self.out.len();
+        self.children(element);
+        let text = self.out[start..].trim().to_string();
+        self.out.truncate(start);
+        if text.is_empty() {
+            return;
+        }
+        let href = element
+            .value()
+            .attr("href")
+            .filter(|href| !href.starts_with('#') && !href.starts_with("javascript:"))
+            .and_then(|href| match self.base {
+                Some(base) => base.join(href).ok().map(String::from),
+                None => Some(href.to_string()),
+            });
+        match href {
+            Some(href) => self.out.push_str(&format!("[{}]({})", text, href)),
+            None => self.out.push_str(&text),
+        }
+    }
+
+    fn table(&mut self, table: ElementRef) {
+        let rows = Selector::parse("tr").expect("static selector");
+        let cells = Selector::parse("th, td").expect("static selector");
+        let header = Selector::parse("th").expect("static selector");
+        self.block();
+        for (i, row) in table.select(&rows).enumerate() {
+            let texts: Vec<String> = row
+                .select(&cells)
+                .map(|cell| collapse(&cell.text().collect::<String>()).replace('|', "\\|"))
+                .collect();
+            if texts.is_empty() {
+                continue;
+            }
+            self.out.push_str(&format!("| {} |\n", texts.join(" | ")));
+            if i == 0 && row.select(&header).next().is_some() {
+                self.out
+                    .push_str(&format!("|{}\n", " --- |".repeat(texts.len())));
+            }
+        }
+        self.block();
+    }
+
+    /// Start a new paragraph (a new line inside lists)
+    fn block(&mut self) {
+        if !self.lists.is_empty() {
+            self.line();
+        } else if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push_str(if self.out.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+    }
+
+    fn line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn space(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+}
+
+/// Whether the element's class or id names a boilerplate block
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    let names = [value.attr("class"), value.attr("id")];
+    names.iter().flatten().any(|names| {
+        names
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| BOILERPLATE_WORDS.contains(&word.to_ascii_lowercase().as_str()))
+    })
+}
+
+/// Runs of whitespace collapsed to single spaces, trimmed
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Trailing spaces stripped and at most one blank line in a row, outside
+/// code blocks
+fn tidy(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let line = if in_code { line } else { line.trim_end() };
+        if line.starts_with("```") {
+            in_code = !in_code;
+        }
+        if line.is_empty() && !in_code {
+            if !blank && !out.is_empty() {
+                out.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        blank = false;
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_content_without_chrome() {
+        let html = r#"<html><head><title>Ignored</title></head><body>
+            <header><a href="/">Home</a></header>
+            <nav><ul><li>Docs</li></ul></nav>
+            <div class="cookie-banner">We use cookies</div>
+            <main>
+              <h1>Getting   started</h1>
+              <p>Install with <code>cargo add finch</code>, then read
+                 <a href="/docs/intro">the <b>intro</b></a>.</p>
+              <div id="share-buttons">Share on X</div>
+              <script>track()</script>
+            </main>
+            <footer>© 2026</footer>
+        </body></html>"#;
+        let base = Url::parse("https://example.com/guide/").unwrap();
+        assert_eq!(
+            html_to_markdown(html, Some(&base)),
+            "# Getting started\n\n\
+             Install with `cargo add finch`, then read \
+             [the **intro**](https://example.com/docs/intro)."
+        );
+    }
+
+    #[test]
+    fn test_lists_code_quotes_and_tables() {
+        let html = "<body>\
+            <ul><li>one</li><li>two<ol><li>a</li><li>b</li></ol></li></ul>\
+            <pre><code>fn main() {\n    println!(\"hi\");\n}\n</code></pre>\
+            <blockquote><p>Quoted</p><p>twice</p></blockquote>\
+            <table><tr><th>Name</th><th>Value</th></tr><tr><td>x</td><td>1</td></tr></table>\
+            </body>";
+        assert_eq!(
+            html_to_markdown(html, None),
+            "- one\n- two\n  1. a\n  2. b\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             > Quoted\n>\n> twice\n\n\
+             | Name | Value |\n| --- | --- |\n| x | 1 |"
+        );
+    }
+
+    #[test]
+    fn test_header_kept_inside_article() {
+        let html = "<body><header>Site name</header>\
+            <article><header><h1>Post</h1><p>by Ann</p></header><p>Body</p></article>\
+            <footer>Links</footer></body>";
+        assert_eq!(html_to_markdown(html, None), "# Post\n\nby Ann\n\nBody");
+        let html = "<body><header>Site name</header><p>Body</p><footer>Links</footer></body>";
+        assert_eq!(html_to_markdown(html, None), "Body");
+    }
+
+    #[test]
+    fn test_title_used_when_content_has_no_heading() {
+        let html = "<html><head><title>My Page</title></head><body><p>Text</p></body></html>";
+        assert_eq!(html_to_markdown(html, None), "# My Page\n\nText");
+    }
+
+    #[test]
+    fn test_boilerplate_words_match_whole_words() {
+        // "downloads" and "header-image" are not boilerplate words
+        let html = r#"<body><div class="downloads">Get it</div><p id="shadow">Kept</p></body>"#;
+        assert_eq!(html_to_markdown(html, None), "Get it\n\nKept");
+    }
+}
@@ -0,0 +1,359 @@
+// Polite web fetching for the web_fetch tool
+//
+// Every fetch goes through one process-wide `WebFetcher`, configured from
+// `[web_fetch]`, which
+// - answers from the on-disk page cache while an entry is younger than
+//   `cache_ttl_secs`,
+// - reads each host's robots.txt (once a day) and refuses disallowed pages,
+// - spaces requests to the same host by `min_interval_ms`, or the site's
+//   Crawl-delay when that is longer, and
+// - converts HTML to markdown with navigation and other page chrome removed.
+//
+// The web_search tool's `WebSearcher` lives in `search` and shares the cache.
+
+pub mod cache;
+pub mod markdown;
+pub mod robots;
+pub mod search;
+
+pub use cache::PageCache;
+pub use markdown::html_to_markdown;
+pub use robots::Robots;
+pub use search::{SearchResult, WebSearcher};
+
+use anyhow::{bail, Context, Result};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::WebFetchConfig;
+
+/// Sent with every request; robots.txt groups for `finch` apply to us
+const USER_AGENT: &str = concat!("finch/", env!("CARGO_PKG_VERSION"));
+
+/// Product token matched against robots.txt user-agent lines
+const ROBOTS_AGENT: &str = "finch";
+
+/// How long a host's robots.txt is trusted before it is fetched again
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest Crawl-delay honoured; sites asking for more still get this
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+/// Largest response read
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+static FETCHER: OnceLock<Arc<WebFetcher>> = OnceLock::new();
+
+/// Apply `[web_fetch]` to the shared fetcher.  Called once when the config
+/// is loaded; later calls are ignored.
+pub fn init(config: &WebFetchConfig) {
+    let _ = FETCHER.set(Arc::new(WebFetcher::new(config, default_cache_dir())));
+}
+
+/// The shared fetcher (default settings if `init()` hasn't been called)
+pub fn fetcher() -> Arc<WebFetcher> {
+    FETCHER
+        .get_or_init(|| {
+            Arc::new(WebFetcher::new(
+                &WebFetchConfig::default(),
+                default_cache_dir(),
+            ))
+        })
+        .clone()
+}
+
+fn default_cache_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".finch").join("web_cache"))
+}
+
+/// A fetched page, as markdown when it was HTML
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    pub content: String,
+    /// Served from the page cache without a request
+    pub from_cache: bool,
+}
+
+pub struct WebFetcher {
+    client: reqwest::Client,
+    cache: Option<PageCache>,
+    respect_robots: bool,
+    min_interval: Duration,
+    /// Parsed robots.txt per origin, with when it was fetched
+    robots: Mutex<HashMap<String, (Instant, Arc<Robots>)>>,
+    /// Earliest time of the next request to each origin
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl WebFetcher {
+    /// A fetcher caching pages under `cache_dir` (no cache when `None` or
+    /// when the TTL is 0)
+    pub fn new(config: &WebFetchConfig, cache_dir: Option<PathBuf>) -> Self {
+        let cache = cache_dir
+            .filter(|_| config.cache_ttl_secs > 0)
+            .map(|dir| PageCache::new(dir, Duration::from_secs(config.cache_ttl_secs)));
+        if let Some(cache) = &cache {
+            let removed = cache.prune();
+            if removed > 0 {
+                tracing::debug!("[web] pruned {} expired cache files", removed);
+            }
+        }
+        Self {
+            client: crate::http::builder()
+                .timeout(FETCH_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("HTTP client"),
+            cache,
+            respect_robots: config.respect_robots,
+            min_interval: Duration::from_millis(config.min_interval_ms),
+            robots: Mutex::new(HashMap::new()),
+            next_request: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetch `url`, from the cache when possible
+    pub async fn fetch(&self, url: &str) -> Result<FetchedPage> {
+        let parsed = Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            bail!("Only http and https URLs can be fetched: {}", url);
+        }
+        if let Some(content) = self.cache.as_ref().and_then(|c| c.get(parsed.as_str())) {
+            return Ok(FetchedPage {
+                content,
+                from_cache: true,
+            });
+        }
+
+        let origin = parsed.origin().ascii_serialization();
+        let crawl_delay = if self.respect_robots {
+            let robots = self.robots_for(&origin).await;
+            let path = match parsed.query() {
+                Some(query) => format!("{}?{}", parsed.path(), query),
+                None => parsed.path().to_string(),
+            };
+            if !robots.is_allowed(&path) {
+                bail!("{} is disallowed by {}/robots.txt", url, origin);
+            }
+            robots.crawl_delay
+        } else {
+            None
+        };
+        self.wait_turn(&origin, crawl_delay).await;
+
+        let response = self
+            .client
+            .get(parsed.clone())
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch URL: {}", url))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("HTTP error {}: {}", status, url);
+        }
+        let final_url = response.url().clone();
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("html"));
+        let body = response.bytes().await?;
+        if body.len() > MAX_PAGE_BYTES {
+            bail!(
+                "{} is larger than {} MB",
+                url,
+                MAX_PAGE_BYTES / (1024 * 1024)
+            );
+        }
+        let text = String::from_utf8_lossy(&body);
+        let content = if is_html {
+            html_to_markdown(&text, Some(&final_url))
+        } else {
+            text.into_owned()
+        };
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(parsed.as_str(), &content) {
+                tracing::warn!("[web] could not cache {}: {:#}", url, e);
+            }
+        }
+        Ok(FetchedPage {
+            content,
+            from_cache: false,
+        })
+    }
+
+    /// The robots.txt rules of `origin`, fetched if not known or stale
+    async fn robots_for(&self, origin: &str) -> Arc<Robots> {
+        if let Some((fetched, robots)) = self.robots.lock().unwrap().get(origin) {
+            if fetched.elapsed() < ROBOTS_TTL {
+                return Arc::clone(robots);
+            }
+        }
+
+        // robots.txt is a request to the host like any other
+        self.wait_turn(origin, None).await;
+        let robots = match self
+            .client
+            .get(format!("{}/robots.txt", origin))
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(text) => Robots::parse(&text, ROBOTS_AGENT),
+                Err(_) => Robots::allow_all(),
+            },
+            Ok(response) if response.status().is_server_error() => Robots::disallow_all(),
+            // No robots.txt (4xx), or the host is unreachable and the page
+            // fetch will report that
+            _ => Robots::allow_all(),
+        };
+        let robots = Arc::new(robots);
+        self.robots
+            .lock()
+            .unwrap()
+            .insert(origin.to_string(), (Instant::now(), Arc::clone(&robots)));
+        robots
+    }
+
+    /// Wait until a request to `origin` is due and book the next slot
+    async fn wait_turn(&self, origin: &str, crawl_delay: Option<Duration>) {
+        let interval = crawl_delay
+            .map(|delay| delay.min(MAX_CRAWL_DELAY))
+            .map_or(self.min_interval, |delay| delay.max(self.min_interval));
+        let start = {
+            let mut next = self.next_request.lock().unwrap();
+            let now = Instant::now();
+            let start = next
+                .get(origin)
+                .copied()
+                .filter(|t| *t > now)
+                .unwrap_or(now);
+            next.insert(origin.to_string(), start + interval);
+            start
+        };
+        tokio::time::sleep_until(start.into()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::header, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A site with a robots.txt and two pages, counting requests
+    async fn serve_site() -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counted = |body: &'static str, content_type: &'static str| {
+            let hits = Arc::clone(&hits);
+            move || {
+                hits.fetch_add(1, Ordering::SeqCst);
+                async move { ([(header::CONTENT_TYPE, content_type)], body) }
+            }
+        };
+        let app = Router::new()
+            .route(
+                "/robots.txt",
+                get(counted("User-agent: *\nDisallow: /private\n", "text/plain")),
+            )
+            .route(
+                "/page",
+                get(counted(
+                    "<html><body><nav>Menu</nav><main><h1>Hello</h1>\
+                     <p>See <a href=\"/other\">other</a>.</p></main></body></html>",
+                    "text/html; charset=utf-8",
+                )),
+            )
+            .route("/data.txt", get(counted("plain text", "text/plain")));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), hits)
+    }
+
+    fn config(cache_ttl_secs: u64, min_interval_ms: u64) -> WebFetchConfig {
+        WebFetchConfig {
+            cache_ttl_secs,
+            min_interval_ms,
+            respect_robots: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_converts_html_and_caches() {
+        let (site, hits) = serve_site().await;
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = WebFetcher::new(&config(3600, 0), Some(dir.path().to_path_buf()));
+
+        let page = fetcher.fetch(&format!("{}/page", site)).await.unwrap();
+        assert!(!page.from_cache);
+        assert_eq!(
+            page.content,
+            format!("# Hello\n\nSee [other]({}/other).", site)
+        );
+        // robots.txt + the page
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let again = fetcher.fetch(&format!("{}/page", site)).await.unwrap();
+        assert!(again.from_cache);
+        assert_eq!(again.content, page.content);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let text = fetcher.fetch(&format!("{}/data.txt", site)).await.unwrap();
+        assert_eq!(text.content, "plain text");
+        // robots.txt is only read once per host
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_robots_disallowed_page_is_not_fetched() {
+        let (site, hits) = serve_site().await;
+        let fetcher = WebFetcher::new(&config(0, 0), None);
+
+        let err = fetcher
+            .fetch(&format!("{}/private/keys", site))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("robots.txt"), "{}", err);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let ignoring = WebFetcher::new(
+            &WebFetchConfig {
+                respect_robots: false,
+                ..config(0, 0)
+            },
+            None,
+        );
+        // Ignoring robots.txt reaches the server (which has no such page)
+        let err = ignoring
+            .fetch(&format!("{}/private/keys", site))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_requests_to_a_host_are_spaced() {
+        let (site, _) = serve_site().await;
+        let fetcher = WebFetcher::new(&config(0, 200), None);
+
+        let start = Instant::now();
+        fetcher.fetch(&format!("{}/page", site)).await.unwrap();
+        fetcher.fetch(&format!("{}/data.txt", site)).await.unwrap();
+        // robots.txt, then two pages, each at least 200ms apart
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_non_http_urls() {
+        let fetcher = WebFetcher::new(&config(0, 0), None);
+        assert!(fetcher.fetch("file:///etc/passwd").await.is_err());
+        assert!(fetcher.fetch("not a url").await.is_err());
+    }
+}
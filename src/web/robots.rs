@@ -0,0 +1,195 @@
+// robots.txt rules (RFC 9309)
+//
+// Only the groups naming our product token apply, or the `*` groups when
+// none do.  The longest matching rule wins and Allow wins a tie; `*` in a
+// rule matches any run of characters and a trailing `$` anchors the end.
+
+use std::time::Duration;
+
+/// The rules of one robots.txt that apply to us
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Robots {
+    /// (allow, path pattern)
+    rules: Vec<(bool, String)>,
+    /// Delay the site asks for between requests
+    pub crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// No robots.txt: everything is allowed
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// robots.txt could not be read because of a server error: RFC 9309
+    /// treats the whole site as disallowed until it can be
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![(false, "/".to_string())],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parse `text` for the crawler whose product token is `agent`
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        // (user agents, rules) in file order
+        let mut groups: Vec<(Vec<String>, Robots)> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts the next group
+                    if in_rules || groups.is_empty() {
+                        groups.push((Vec::new(), Robots::default()));
+                        in_rules = false;
+                    }
+                    if let Some((agents, _)) = groups.last_mut() {
+                        agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    let Some((_, robots)) = groups.last_mut() else {
+                        continue;
+                    };
+                    // An empty Disallow allows everything: no rule needed
+                    if !value.is_empty() {
+                        robots.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let (Some((_, robots)), Ok(secs)) = (groups.last_mut(), value.parse()) {
+                        robots.crawl_delay = Duration::try_from_secs_f64(secs).ok();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let merge = |wanted: &str| {
+            let mut merged: Option<Robots> = None;
+            for (agents, robots) in &groups {
+                if agents.iter().any(|a| a == wanted) {
+                    let merged = merged.get_or_insert_with(Robots::default);
+                    merged.rules.extend(robots.rules.iter().cloned());
+                    merged.crawl_delay = merged.crawl_delay.or(robots.crawl_delay);
+                }
+            }
+            merged
+        };
+        merge(&agent).or_else(|| merge("*")).unwrap_or_default()
+    }
+
+    /// Whether `path` (path plus query string) may be fetched
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if !pattern_matches(pattern, path) {
+                continue;
+            }
+            let candidate = (pattern.len(), *allow);
+            // Longer patterns win; at equal length Allow wins
+            if best.is_none_or(|b| candidate > b) {
+                best = Some(candidate);
+            }
+        }
+        best.is_none_or(|(_, allow)| allow)
+    }
+}
+
+/// Match a robots.txt path pattern against the start of `path`
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = parts.next().and_then(|first| path.strip_prefix(first)) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example
+User-agent: *
+Disallow: /private
+Allow: /private/docs
+Disallow: /*.pdf$
+
+User-agent: finch
+User-agent: otherbot
+Disallow: /search
+Crawl-delay: 2
+
+User-agent: badbot
+Disallow: /
+";
+
+    #[test]
+    fn test_specific_group_replaces_wildcard() {
+        let robots = Robots::parse(ROBOTS, "Finch");
+        assert!(!robots.is_allowed("/search?q=x"));
+        // The `*` rules don't apply once a group names us
+        assert!(robots.is_allowed("/private"));
+        assert_eq!(robots.crawl_delay, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_wildcard_group_and_longest_match() {
+        let robots = Robots::parse(ROBOTS, "somebot");
+        assert!(robots.is_allowed("/"));
+        assert!(!robots.is_allowed("/private/keys"));
+        assert!(robots.is_allowed("/private/docs/index.html"));
+        assert!(!robots.is_allowed("/files/report.pdf"));
+        assert!(robots.is_allowed("/files/report.pdf?download=1"));
+        assert_eq!(robots.crawl_delay, None);
+    }
+
+    #[test]
+    fn test_empty_and_special_files() {
+        assert!(Robots::parse("", "finch").is_allowed("/anything"));
+        assert!(Robots::parse("User-agent: *\nDisallow:\n", "finch").is_allowed("/x"));
+        assert!(!Robots::disallow_all().is_allowed("/x"));
+        assert!(Robots::allow_all().is_allowed("/x"));
+    }
+
+    #[test]
+    fn test_allow_wins_a_tie() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /page\nAllow: /page\n", "finch");
+        assert!(robots.is_allowed("/page"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/a", "/abc"));
+        assert!(pattern_matches("/a*c", "/abbbc/d"));
+        assert!(pattern_matches("/a*c$", "/abbbc"));
+        assert!(!pattern_matches("/a*c$", "/abbbc/d"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exactly"));
+        assert!(!pattern_matches("/b", "/abc"));
+    }
+}
@@ -0,0 +1,437 @@
+// Web search for the web_search tool
+//
+// One process-wide `WebSearcher`, configured from `[web_search]`, queries a
+// single backend:
+// - DuckDuckGo's HTML results page (the default; no key needed),
+// - the Brave Search API (`api_key` or `BRAVE_API_KEY`), or
+// - a SearXNG instance with its JSON format enabled (`searxng_url`).
+//
+// Results are cached on disk by request URL like fetched pages are, and
+// requests to the backend are spaced by `min_interval_ms` so an agent loop
+// can't hammer it.
+
+use anyhow::{bail, Context, Result};
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::{PageCache, USER_AGENT};
+use crate::config::{SearchBackend, WebSearchConfig};
+
+const DUCKDUCKGO_URL: &str = "https://html.duckduckgo.com/html/";
+const BRAVE_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+static SEARCHER: OnceLock<Arc<WebSearcher>> = OnceLock::new();
+
+/// Apply `[web_search]` to the shared searcher.  Called once when the config
+/// is loaded; later calls are ignored.
+pub fn init(config: &WebSearchConfig) {
+    let _ = SEARCHER.set(Arc::new(WebSearcher::new(config, default_cache_dir())));
+}
+
+/// The shared searcher (default settings if `init()` hasn't been called)
+pub fn searcher() -> Arc<WebSearcher> {
+    SEARCHER
+        .get_or_init(|| {
+            Arc::new(WebSearcher::new(
+                &WebSearchConfig::default(),
+                default_cache_dir(),
+            ))
+        })
+        .clone()
+}
+
+fn default_cache_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".finch").join("web_cache").join("search"))
+}
+
+/// One search hit, in the backend's ranking order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+pub struct WebSearcher {
+    client: reqwest::Client,
+    backend: SearchBackend,
+    api_key: Option<String>,
+    searxng_url: Option<String>,
+    max_results: usize,
+    cache: Option<PageCache>,
+    min_interval: Duration,
+    /// Earliest time of the next request to the backend
+    next_request: Mutex<Option<Instant>>,
+}
+
+impl WebSearcher {
+    /// A searcher caching results under `cache_dir` (no cache when `None` or
+    /// when the TTL is 0)
+    pub fn new(config: &WebSearchConfig, cache_dir: Option<PathBuf>) -> Self {
+        let cache = cache_dir
+            .filter(|_| config.cache_ttl_secs > 0)
+            .map(|dir| PageCache::new(dir, Duration::from_secs(config.cache_ttl_secs)));
+        if let Some(cache) = &cache {
+            cache.prune();
+        }
+        Self {
+            client: crate::http::builder()
+                .timeout(SEARCH_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .expect("HTTP client"),
+            backend: config.backend,
+            api_key: config
+                .api_key
+                .clone()
+                .filter(|key| !key.is_empty())
+                .or_else(|| std::env::var("BRAVE_API_KEY").ok())
+                .filter(|key| !key.is_empty()),
+            searxng_url: config.searxng_url.clone(),
+            max_results: config.max_results.max(1),
+            cache,
+            min_interval: Duration::from_millis(config.min_interval_ms),
+            next_request: Mutex::new(None),
+        }
+    }
+
+    pub fn backend(&self) -> SearchBackend {
+        self.backend
+    }
+
+    /// Search for `query`, from the cache when possible.  Returns at most
+    /// `limit` results (the configured `max_results` when `None`).
+    pub async fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<SearchResult>> {
+        let query = query.trim();
+        if query.is_empty() {
+            bail!("Search query is empty");
+        }
+        let limit = limit.unwrap_or(self.max_results).max(1);
+        let url = self.request_url(query, limit)?;
+
+        let cache_key = format!("{}:{}", self.backend.as_str(), url);
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.get(&cache_key)) {
+            if let Ok(mut results) = serde_json::from_str::<Vec<SearchResult>>(&cached) {
+                results.truncate(limit);
+                return Ok(results);
+            }
+        }
+
+        let mut request = self.client.get(url);
+        if self.backend == SearchBackend::Brave {
+            let key = self
+                .api_key
+                .as_deref()
+                .context("Brave search needs [web_search] api_key or BRAVE_API_KEY")?;
+            request = request
+                .header("X-Subscription-Token", key)
+                .header(reqwest::header::ACCEPT, "application/json");
+        }
+        self.wait_turn().await;
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("{} search failed", self.backend.as_str()))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("{} search returned HTTP {}", self.backend.as_str(), status);
+        }
+        let body = response.text().await?;
+
+        let mut results = match self.backend {
+            SearchBackend::DuckDuckGo => parse_duckduckgo_html(&body),
+            SearchBackend::Brave => parse_brave_json(&serde_json::from_str(&body)?),
+            SearchBackend::Searxng => parse_searxng_json(&serde_json::from_str(&body)?),
+        };
+        results.truncate(limit);
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(&cache_key, &serde_json::to_string(&results)?) {
+                tracing::warn!("[web] could not cache search for {:?}: {:#}", query, e);
+            }
+        }
+        Ok(results)
+    }
+
+    fn request_url(&self, query: &str, limit: usize) -> Result<Url> {
+        let url = match self.backend {
+            SearchBackend::DuckDuckGo => Url::parse_with_params(DUCKDUCKGO_URL, [("q", query)])?,
+            SearchBackend::Brave => {
+                // The API returns at most 20 results a page
+                let count = limit.min(20).to_string();
+                Url::parse_with_params(BRAVE_URL, [("q", query), ("count", count.as_str())])?
+            }
+            SearchBackend::Searxng => {
+                let base = self
+                    .searxng_url
+                    .as_deref()
+                    .context("SearXNG search needs [web_search] searxng_url")?;
+                let endpoint = format!("{}/search", base.trim_end_matches('/'));
+                Url::parse_with_params(&endpoint, [("q", query), ("format", "json")])
+                    .with_context(|| format!("Invalid searxng_url: {}", base))?
+            }
+        };
+        Ok(url)
+    }
+
+    /// Wait until a request to the backend is due and book the next slot
+    async fn wait_turn(&self) {
+        let start = {
+            let mut next = self.next_request.lock().unwrap();
+            let now = Instant::now();
+            let start = next.filter(|t| *t > now).unwrap_or(now);
+            *next = Some(start + self.min_interval);
+            start
+        };
+        tokio::time::sleep_until(start.into()).await;
+    }
+}
+
+/// Results from DuckDuckGo's HTML page, skipping ads
+pub fn parse_duckduckgo_html(html: &str) -> Vec<SearchResult> {
+    let document = Html::parse_document(html);
+    let result = Selector::parse("div.result").unwrap();
+    let link = Selector::parse("a.result__a").unwrap();
+    let snippet = Selector::parse(".result__snippet").unwrap();
+
+    document
+        .select(&result)
+        .filter(|el| !el.value().classes().any(|class| class == "result--ad"))
+        .filter_map(|el| {
+            let anchor = el.select(&link).next()?;
+            let url = duckduckgo_target(anchor.value().attr("href")?)?;
+            Some(SearchResult {
+                title: collapse_whitespace(&anchor.text().collect::<String>()),
+                url,
+                snippet: el
+                    .select(&snippet)
+                    .next()
+                    .map(|s| collapse_whitespace(&s.text().collect::<String>()))
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// The destination of a DuckDuckGo result link, which is either direct or a
+/// `//duckduckgo.com/l/?uddg=<url>` redirect
+fn duckduckgo_target(href: &str) -> Option<String> {
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
+    } else {
+        href.to_string()
+    };
+    let url = Url::parse(&absolute).ok()?;
+    if url.path() == "/l/" {
+        return url
+            .query_pairs()
+            .find(|(key, _)| key == "uddg")
+            .map(|(_, target)| target.into_owned());
+    }
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Results from a Brave Search API response
+pub fn parse_brave_json(body: &Value) -> Vec<SearchResult> {
+    json_results(&body["web"]["results"], "description")
+}
+
+/// Results from a SearXNG `format=json` response
+pub fn parse_searxng_json(body: &Value) -> Vec<SearchResult> {
+    json_results(&body["results"], "content")
+}
+
+fn json_results(results: &Value, snippet_field: &str) -> Vec<SearchResult> {
+    results
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(SearchResult {
+                        title: strip_tags(item["title"].as_str()?),
+                        url: item["url"].as_str()?.to_string(),
+                        snippet: strip_tags(item[snippet_field].as_str().unwrap_or("")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Drop the `<strong>` highlighting some APIs put in titles and snippets
+fn strip_tags(text: &str) -> String {
+    let fragment = Html::parse_fragment(text);
+    collapse_whitespace(&fragment.root_element().text().collect::<String>())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Query, routing::get, Json, Router};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parse_duckduckgo_html() {
+        let html = r#"<html><body>
+            <div class="result results_links result--ad">
+              <a class="result__a" href="https://ads.example/">Sponsored</a>
+            </div>
+            <div class="result results_links">
+              <h2><a class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fcrates.io%2Fcrates%2Ftokio&amp;rut=abc">tokio - <b>crates.io</b></a></h2>
+              <a class="result__snippet" href="x">An event-driven,
+                non-blocking I/O platform</a>
+            </div>
+            <div class="result results_links">
+              <a class="result__a" href="https://docs.rs/tokio">tokio - Rust</a>
+            </div>
+        </body></html>"#;
+
+        assert_eq!(
+            parse_duckduckgo_html(html),
+            vec![
+                SearchResult {
+                    title: "tokio - crates.io".into(),
+                    url: "https://crates.io/crates/tokio".into(),
+                    snippet: "An event-driven, non-blocking I/O platform".into(),
+                },
+                SearchResult {
+                    title: "tokio - Rust".into(),
+                    url: "https://docs.rs/tokio".into(),
+                    snippet: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_brave_json() {
+        let body = serde_json::json!({
+            "web": { "results": [
+                {
+                    "title": "Rust 1.90",
+                    "url": "https://blog.rust-lang.org/",
+                    "description": "The <strong>latest</strong> stable release"
+                },
+                { "title": "no url" }
+            ]}
+        });
+        assert_eq!(
+            parse_brave_json(&body),
+            vec![SearchResult {
+                title: "Rust 1.90".into(),
+                url: "https://blog.rust-lang.org/".into(),
+                snippet: "The latest stable release".into(),
+            }]
+        );
+        assert!(parse_brave_json(&serde_json::json!({})).is_empty());
+    }
+
+    /// A SearXNG instance returning three results, counting requests
+    async fn serve_searxng() -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        let app = Router::new().route(
+            "/search",
+            get(move |Query(params): Query<HashMap<String, String>>| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    assert_eq!(params["format"], "json");
+                    let q = &params["q"];
+                    Json(serde_json::json!({
+                        "results": (1..=3).map(|i| serde_json::json!({
+                            "title": format!("{} {}", q, i),
+                            "url": format!("https://example.com/{}", i),
+                            "content": "snippet"
+                        })).collect::<Vec<_>>()
+                    }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), hits)
+    }
+
+    fn searxng_config(url: &str, min_interval_ms: u64) -> WebSearchConfig {
+        WebSearchConfig {
+            backend: SearchBackend::Searxng,
+            searxng_url: Some(url.to_string()),
+            max_results: 2,
+            min_interval_ms,
+            ..WebSearchConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_searxng_search_is_cached() {
+        let (url, hits) = serve_searxng().await;
+        let dir = tempfile::tempdir().unwrap();
+        let searcher = WebSearcher::new(&searxng_config(&url, 0), Some(dir.path().to_path_buf()));
+
+        let results = searcher.search("tokio", None).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "tokio 1");
+        assert_eq!(results[1].url, "https://example.com/2");
+
+        let again = searcher.search("tokio", None).await.unwrap();
+        assert_eq!(again, results);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        searcher.search("axum", Some(1)).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_searches_are_spaced() {
+        let (url, _) = serve_searxng().await;
+        let searcher = WebSearcher::new(&searxng_config(&url, 200), None);
+
+        let start = Instant::now();
+        searcher.search("one", None).await.unwrap();
+        searcher.search("two", None).await.unwrap();
+        searcher.search("three", None).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_missing_backend_settings_are_reported() {
+        let brave = WebSearcher::new(
+            &WebSearchConfig {
+                backend: SearchBackend::Brave,
+                api_key: Some(String::new()),
+                ..WebSearchConfig::default()
+            },
+            None,
+        );
+        // An empty key counts as unset unless BRAVE_API_KEY is exported
+        if std::env::var("BRAVE_API_KEY").is_err() {
+            let err = brave.search("rust", None).await.unwrap_err();
+            assert!(err.to_string().contains("BRAVE_API_KEY"), "{}", err);
+        }
+
+        let searxng = WebSearcher::new(
+            &WebSearchConfig {
+                backend: SearchBackend::Searxng,
+                ..WebSearchConfig::default()
+            },
+            None,
+        );
+        let err = searxng.search("rust", None).await.unwrap_err();
+        assert!(err.to_string().contains("searxng_url"), "{}", err);
+        assert!(searxng.search("   ", None).await.is_err());
+    }
+}
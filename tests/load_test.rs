@@ -60,9 +60,9 @@ async fn test_concurrent_session_creation_no_duplicates() {
     let mut handles = Vec::with_capacity(TASKS);
     for _ in 0..TASKS {
         let m = Arc::clone(&manager);
-        handles.push(tokio::spawn(
-            async move { m.get_or_create(None).map(|s| s.id) },
-        ));
+        handles.push(tokio::spawn(async move {
+            m.get_or_create(None).await.map(|s| s.id)
+        }));
     }
 
     let mut ids: HashSet<String> = HashSet::new();
@@ -95,6 +95,7 @@ async fn test_session_limit_under_concurrency() {
         let m = Arc::clone(&manager);
         handles.push(tokio::spawn(async move {
             m.get_or_create(None)
+                .await
                 .map(|s| s.id)
                 .map_err(|e| e.to_string())
         }));
@@ -148,7 +149,7 @@ async fn test_session_crud_under_load() {
         let m = Arc::clone(&manager);
         let ids = Arc::clone(&created_ids);
         handles.push(tokio::spawn(async move {
-            if let Ok(s) = m.get_or_create(None) {
+            if let Ok(s) = m.get_or_create(None).await {
                 ids.lock().unwrap().push(s.id);
             }
         }));
@@ -167,7 +168,7 @@ async fn test_session_crud_under_load() {
         let id = snapshot[i % snapshot.len()].clone();
         handles.push(tokio::spawn(async move {
             // get_or_create with known ID — should find it
-            let _ = m.get_or_create(Some(&id));
+            let _ = m.get_or_create(Some(&id)).await;
         }));
     }
     for h in handles {
@@ -180,7 +181,7 @@ async fn test_session_crud_under_load() {
     for id in to_delete {
         let m = Arc::clone(&manager);
         handles.push(tokio::spawn(async move {
-            m.delete(&id); // may return false if already deleted by a racing deleter — OK
+            m.delete(&id).await; // may return false if already deleted by a racing deleter — OK
         }));
     }
     for h in handles {
@@ -239,7 +240,7 @@ async fn test_session_update_under_concurrency() {
     let manager = Arc::new(SessionManager::new(TASKS + 10, 60));
 
     // Create one session to update
-    let session = manager.get_or_create(None).unwrap();
+    let session = manager.get_or_create(None).await.unwrap();
     let session_id = session.id.clone();
 
     // 100 tasks all try to touch and update the same session
@@ -248,7 +249,7 @@ async fn test_session_update_under_concurrency() {
         let m = Arc::clone(&manager);
         let id = session_id.clone();
         handles.push(tokio::spawn(async move {
-            if let Ok(mut s) = m.get_or_create(Some(&id)) {
+            if let Ok(mut s) = m.get_or_create(Some(&id)).await {
                 s.touch();
                 let _ = m.update(&id, s);
             }
@@ -265,7 +266,7 @@ async fn test_session_update_under_concurrency() {
         1,
         "still exactly 1 session after concurrent updates"
     );
-    let final_session = manager.get_or_create(Some(&session_id)).unwrap();
+    let final_session = manager.get_or_create(Some(&session_id)).await.unwrap();
     assert_eq!(final_session.id, session_id);
 }
 
@@ -489,6 +490,7 @@ async fn test_memory_stability_10k_session_lifecycle() {
     for _ in 0..ITERATIONS {
         let session = manager
             .get_or_create(None)
+            .await
             .expect("session creation must not fail");
         all_ids.push(session.id);
     }
@@ -501,7 +503,10 @@ async fn test_memory_stability_10k_session_lifecycle() {
 
     // Phase 2: Delete all
     for id in &all_ids {
-        assert!(manager.delete(id), "session {id} must exist when deleted");
+        assert!(
+            manager.delete(id).await,
+            "session {id} must exist when deleted"
+        );
     }
 
     assert_eq!(
@@ -522,7 +527,7 @@ async fn test_session_batch_lifecycle_consistency() {
         // Create BATCH sessions
         let mut ids = Vec::with_capacity(BATCH);
         for _ in 0..BATCH {
-            let s = manager.get_or_create(None).expect("create failed");
+            let s = manager.get_or_create(None).await.expect("create failed");
             ids.push(s.id);
         }
         assert_eq!(
@@ -533,7 +538,7 @@ async fn test_session_batch_lifecycle_consistency() {
 
         // Delete all
         for id in &ids {
-            manager.delete(id);
+            manager.delete(id).await;
         }
         assert_eq!(
             manager.active_count(),
@@ -0,0 +1,166 @@
+// Chaos-mode tests for the multi-provider stack
+//
+// These wrap mock providers in `FaultInjectingProvider` and verify that:
+// 1. FallbackChain moves past a provider that times out or rate-limits
+// 2. An all-faulty chain fails with a clear error instead of hanging
+// 3. Mid-stream faults reach the generator's receiver as `Err` chunks — the
+//    item the TUI event loop turns into `QueryFailed`
+// 4. Truncated non-streaming responses still come back as valid responses
+
+use anyhow::Result;
+use finch::claude::{ClaudeClient, ContentBlock, Message};
+use finch::generators::claude::ClaudeGenerator;
+use finch::generators::Generator;
+use finch::providers::{
+    ChaosConfig, FallbackChain, FaultInjectingProvider, LlmProvider, ProviderRequest,
+    ProviderResponse, StreamChunk,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Always-healthy provider returning a fixed reply
+struct StubProvider {
+    name: &'static str,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for StubProvider {
+    async fn send_message(&self, _request: &ProviderRequest) -> Result<ProviderResponse> {
+        Ok(ProviderResponse {
+            id: format!("{}-id", self.name),
+            model: "stub-model".to_string(),
+            content: vec![ContentBlock::Text {
+                text: "the quick brown fox".to_string(),
+            }],
+            stop_reason: Some("end_turn".to_string()),
+            role: "assistant".to_string(),
+            provider: self.name.to_string(),
+        })
+    }
+
+    async fn send_message_stream(
+        &self,
+        _request: &ProviderRequest,
+    ) -> Result<mpsc::Receiver<Result<StreamChunk>>> {
+        let (tx, rx) = mpsc::channel(8);
+        for word in ["the ", "quick ", "brown ", "fox"] {
+            tx.send(Ok(StreamChunk::TextDelta(word.to_string())))
+                .await?;
+        }
+        Ok(rx)
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn default_model(&self) -> &str {
+        "stub-model"
+    }
+}
+
+fn stub(name: &'static str) -> Box<dyn LlmProvider> {
+    Box::new(StubProvider { name })
+}
+
+fn chaotic(name: &'static str, spec: &str) -> Box<dyn LlmProvider> {
+    Box::new(FaultInjectingProvider::new(
+        stub(name),
+        ChaosConfig::parse(spec).unwrap(),
+    ))
+}
+
+fn request() -> ProviderRequest {
+    ProviderRequest::new(vec![Message::user("hello")])
+}
+
+#[tokio::test]
+async fn test_fallback_skips_rate_limited_primary() {
+    let chain = FallbackChain::new(vec![chaotic("primary", "rate_limit=1"), stub("backup")]);
+    let response = chain.send_message_with_fallback(&request()).await.unwrap();
+    assert_eq!(response.provider, "backup");
+}
+
+#[tokio::test]
+async fn test_fallback_skips_timed_out_primary() {
+    let chain = FallbackChain::new(vec![
+        chaotic("primary", "timeout=1,timeout_ms=20"),
+        stub("backup"),
+    ]);
+    let response = tokio::time::timeout(
+        Duration::from_secs(5),
+        chain.send_message_with_fallback(&request()),
+    )
+    .await
+    .expect("injected timeout must not hang the chain")
+    .unwrap();
+    assert_eq!(response.provider, "backup");
+}
+
+#[tokio::test]
+async fn test_fallback_all_faulty_reports_last_error() {
+    let chain = FallbackChain::new(vec![
+        chaotic("primary", "rate_limit=1"),
+        chaotic("backup", "malformed=1"),
+    ]);
+    let err = chain
+        .send_message_with_fallback(&request())
+        .await
+        .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("All fallback providers failed"));
+    assert!(message.contains("Failed to parse backup response"));
+}
+
+#[tokio::test]
+async fn test_streaming_fallback_skips_rate_limited_primary() {
+    let chain = FallbackChain::new(vec![chaotic("primary", "429=1"), stub("backup")]);
+    let mut rx = chain
+        .send_message_stream_with_fallback(&request())
+        .await
+        .unwrap();
+    let mut text = String::new();
+    while let Some(chunk) = rx.recv().await {
+        if let StreamChunk::TextDelta(delta) = chunk.unwrap() {
+            text.push_str(&delta);
+        }
+    }
+    assert_eq!(text, "the quick brown fox");
+}
+
+/// Stream faults happen after the chain has committed to a provider, so they
+/// surface to the caller (the TUI shows them via QueryFailed).
+#[tokio::test]
+async fn test_mid_stream_faults_reach_generator() {
+    for spec in ["truncate=1", "malformed=1"] {
+        let client = ClaudeClient::with_provider(chaotic("primary", spec));
+        let generator = ClaudeGenerator::new(Arc::new(client));
+        let mut rx = generator
+            .generate_stream(vec![Message::user("hello")], None)
+            .await
+            .unwrap()
+            .expect("Claude generator always streams");
+
+        let first = rx.recv().await.unwrap();
+        assert!(matches!(first, Ok(StreamChunk::TextDelta(_))));
+        let second = rx.recv().await.unwrap();
+        assert!(
+            second.is_err(),
+            "{} should end the stream with an error",
+            spec
+        );
+        assert!(rx.recv().await.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_truncated_response_is_still_usable() {
+    let provider = chaotic("primary", "truncate=1");
+    let response = provider.send_message(&request()).await.unwrap();
+    match &response.content[..] {
+        [ContentBlock::Text { text }] => assert!("the quick brown fox".starts_with(text.as_str())),
+        other => panic!("unexpected content: {:?}", other),
+    }
+    assert!(response.stop_reason.is_none());
+}
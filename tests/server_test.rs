@@ -7,20 +7,20 @@ async fn test_session_manager() {
     let manager = SessionManager::new(10, 30);
 
     // Create a session
-    let session1 = manager.get_or_create(None).unwrap();
+    let session1 = manager.get_or_create(None).await.unwrap();
     assert_eq!(manager.active_count(), 1);
 
     // Retrieve the same session
-    let session2 = manager.get_or_create(Some(&session1.id)).unwrap();
+    let session2 = manager.get_or_create(Some(&session1.id)).await.unwrap();
     assert_eq!(session1.id, session2.id);
     assert_eq!(manager.active_count(), 1); // Still only 1 session
 
     // Create a new session
-    let session3 = manager.get_or_create(None).unwrap();
+    let session3 = manager.get_or_create(None).await.unwrap();
     assert_ne!(session1.id, session3.id);
     assert_eq!(manager.active_count(), 2);
 
     // Delete a session
-    assert!(manager.delete(&session1.id));
+    assert!(manager.delete(&session1.id).await);
     assert_eq!(manager.active_count(), 1);
 }
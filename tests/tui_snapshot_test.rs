@@ -0,0 +1,202 @@
+// Snapshot tests for the TUI live area
+//
+// TuiRenderer::headless() draws into a CaptureTerminal instead of stdout, so
+// these tests run in CI without a TTY.  Each test renders, then compares the
+// captured screen (colours stripped) against the expected text.
+//
+// The separator row embeds the current directory, which differs between
+// machines; `snapshot()` below replaces it with a fixed placeholder.
+
+use finch::cli::tui::{CaptureTerminal, Dialog, DialogOption, TuiRenderer};
+use finch::cli::{OutputManager, StatusBar};
+use finch::config::ColorScheme;
+use std::sync::Arc;
+
+const SEPARATOR: &str = "── <cwd> ──";
+const IDLE_HINT: &str = "↑↓ history  ·  Tab complete  ·  /help for commands  ·  Ctrl+C cancel";
+
+struct Harness {
+    renderer: TuiRenderer,
+    term: CaptureTerminal,
+    output: Arc<OutputManager>,
+    status: Arc<StatusBar>,
+}
+
+fn harness(width: u16, height: u16) -> Harness {
+    let term = CaptureTerminal::new(width, height);
+    let output = Arc::new(OutputManager::new(ColorScheme::default()));
+    let status = Arc::new(StatusBar::new());
+    let renderer = TuiRenderer::headless(
+        Arc::clone(&output),
+        Arc::clone(&status),
+        ColorScheme::default(),
+        term.clone(),
+    );
+    Harness {
+        renderer,
+        term,
+        output,
+        status,
+    }
+}
+
+/// Screen rows with the cwd separator normalised
+fn snapshot(term: &CaptureTerminal) -> Vec<String> {
+    term.snapshot()
+        .lines()
+        .map(|line| {
+            if line.starts_with("── ") && line.ends_with(" ──") {
+                SEPARATOR.to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+fn idle_live_area(width: usize) -> Vec<String> {
+    vec![
+        SEPARATOR.to_string(),
+        "❯".to_string(),
+        "─".repeat(width),
+        IDLE_HINT.to_string(),
+    ]
+}
+
+#[test]
+fn test_idle_live_area() {
+    let mut h = harness(80, 10);
+    h.renderer.render().unwrap();
+
+    assert_eq!(snapshot(&h.term), idle_live_area(80));
+    // Cursor parked after the prompt
+    assert_eq!(h.term.cursor(), (2, 1));
+}
+
+#[test]
+fn test_repeated_redraws_do_not_accumulate() {
+    let mut h = harness(80, 10);
+    for _ in 0..5 {
+        h.renderer.render().unwrap();
+    }
+    assert_eq!(snapshot(&h.term), idle_live_area(80));
+    assert!(h.term.scrollback().is_empty());
+}
+
+/// A status line longer than the terminal wraps onto a second row; both rows
+/// must be erased when the status shrinks back to one line.
+#[test]
+fn test_wrapped_status_line_is_fully_erased() {
+    let mut h = harness(80, 10);
+    let long_status = "0123456789".repeat(12);
+    h.status.update_operation(long_status.clone());
+    h.renderer.render().unwrap();
+
+    let mut expected = idle_live_area(80);
+    expected.pop();
+    expected.push(long_status[..80].to_string());
+    expected.push(long_status[80..].to_string());
+    assert_eq!(snapshot(&h.term), expected);
+
+    h.renderer.render().unwrap();
+    assert_eq!(snapshot(&h.term), expected);
+
+    h.status.clear_operation();
+    h.renderer.render().unwrap();
+    assert_eq!(snapshot(&h.term), idle_live_area(80));
+}
+
+/// Completed messages are committed above the live area; once the screen is
+/// full they scroll into the terminal's scrollback.
+#[test]
+fn test_committed_messages_scroll_above_live_area() {
+    let mut h = harness(80, 6);
+    h.renderer.render().unwrap();
+
+    h.output.write_info("first");
+    h.output.write_info("second");
+    let output = Arc::clone(&h.output);
+    h.renderer.flush_output_safe(&output).unwrap();
+
+    let mut expected = vec!["second".to_string(), String::new()];
+    expected.extend(idle_live_area(80));
+    assert_eq!(snapshot(&h.term), expected);
+    assert_eq!(
+        h.term.scrollback(),
+        vec!["first".to_string(), String::new()]
+    );
+    assert_eq!(h.term.cursor(), (2, 3));
+
+    // Already-committed messages are not printed again
+    h.renderer.render().unwrap();
+    h.renderer.flush_output_safe(&output).unwrap();
+    assert_eq!(snapshot(&h.term), expected);
+}
+
+fn boxed(text: &str) -> String {
+    format!("│  {:<66}  │", text)
+}
+
+#[test]
+fn test_select_dialog_snapshot() {
+    let mut h = harness(80, 24);
+    h.renderer.active_dialog = Some(
+        Dialog::select(
+            "Allow bash to run `ls`?",
+            vec![DialogOption::new("Yes"), DialogOption::new("No")],
+        )
+        .with_help("Esc to cancel"),
+    );
+    h.renderer.render().unwrap();
+
+    let border = "─".repeat(70);
+    let expected = vec![
+        SEPARATOR.to_string(),
+        format!("┌{}┐", border),
+        boxed("Allow bash to run `ls`?"),
+        boxed("Esc to cancel"),
+        format!("├{}┤", border),
+        boxed("  ● Yes"),
+        boxed("  ○ No"),
+        format!("├{}┤", border),
+        boxed("  [ Cancel ]  ↑↓ nav · Enter select · Esc cancel"),
+        format!("└{}┘", border),
+    ];
+    assert_eq!(snapshot(&h.term), expected);
+    // Every box row has the same visible width, so the right border lines up
+    for row in &expected[1..] {
+        assert_eq!(row.chars().count(), 72, "misaligned row: {:?}", row);
+    }
+    // Cursor rests on the row below the box
+    assert_eq!(h.term.cursor(), (0, 10));
+
+    // Closing the dialog erases every box row
+    h.renderer.active_dialog = None;
+    h.renderer.render().unwrap();
+    assert_eq!(snapshot(&h.term), idle_live_area(80));
+    assert!(h.term.scrollback().is_empty());
+}
+
+#[test]
+fn test_confirm_dialog_snapshot() {
+    let mut h = harness(80, 24);
+    h.renderer.active_dialog = Some(Dialog::confirm("Proceed?", true));
+    h.renderer.render().unwrap();
+
+    let border = "─".repeat(70);
+    let expected = vec![
+        SEPARATOR.to_string(),
+        format!("┌{}┐", border),
+        boxed("Proceed?"),
+        format!("├{}┤", border),
+        boxed(""),
+        boxed("Yes   No"),
+        format!("├{}┤", border),
+        boxed("↑/↓ Navigate  Enter Select  Esc Cancel"),
+        format!("└{}┘", border),
+    ];
+    assert_eq!(snapshot(&h.term), expected);
+    for row in &expected[1..] {
+        assert_eq!(row.chars().count(), 72, "misaligned row: {:?}", row);
+    }
+}